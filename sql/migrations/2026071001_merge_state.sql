@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS `merge_state` (
+    `id`               INTEGER PRIMARY KEY AUTOINCREMENT,
+    `head_name`        TEXT NOT NULL,
+    `orig_head`        TEXT NOT NULL,
+    `merge_head`       TEXT NOT NULL,
+    `target_ref`       TEXT NOT NULL,
+    `merge_base`       TEXT NOT NULL,
+    `conflicted_paths` TEXT NOT NULL,
+    `message`          TEXT,
+    `updated_at`       TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -31,12 +31,12 @@ use crate::{
 const ROOT_AFTER_HELP: &str = "\
 Command Groups:
   Repository Setup        init, clone, config, completions
-  Working Tree            status, add, rm, mv, restore, clean, stash, dirty, lfs, ls-files, check-ignore, check-attr, check-mailmap, worktree
-  History Inspection      log, shortlog, show, show-ref, format-patch, ls-remote, ls-tree, diff, grep, blame, describe, notes, archive
+  Working Tree            status, add, rm, mv, restore, clean, stash, dirty, lfs, ls-files, check-ignore, check-attr, check-mailmap, worktree, sparse-checkout, submodule
+  History Inspection      log, shortlog, show, show-ref, show-branch, format-patch, ls-remote, ls-tree, diff, grep, blame, describe, notes, archive
   Commit And Branching    commit, branch, switch, checkout, tag, merge, rebase, reset, cherry-pick, revert, rerere, metadata
-  Remote And Cloud        remote, fetch, pull, push, open, cloud, cache, publish, credential, bundle
-  AI And Automation       code, code-control, automation, usage, graph, sandbox, agent, service
-  Maintenance And Plumbing fsck, maintenance, repack, logfile, cat-file, hash-object, write-tree, read-tree, update-index, update-ref, merge-file, merge-base, apply, diff-tree, diff-index, diff-files, fast-export, fast-import, replace, verify-pack, rev-parse, rev-list, symbolic-ref, reflog, bisect, for-each-ref
+  Remote And Cloud        remote, fetch, pull, push, open, cloud, cache, publish, credential, credential-helper, bundle
+  AI And Automation       code, code-control, automation, usage, graph, sandbox, export-snapshot, agent, service
+  Maintenance And Plumbing fsck, maintenance, repack, logfile, cat-file, hash-object, write-tree, commit-tree, read-tree, update-index, update-ref, merge-file, merge-base, apply, diff-tree, diff-index, diff-files, fast-export, fast-import, replace, verify-pack, verify-commit, verify-tag, rev-parse, rev-list, symbolic-ref, reflog, bisect, for-each-ref
 
 Help Topics:
   error-codes  Print the stable CLI error code table (`libra help error-codes`)
@@ -344,6 +344,16 @@ enum Commands {
         after_help = command::worktree::WORKTREE_EXAMPLES
     )]
     Worktree(command::worktree::WorktreeArgs),
+    #[command(
+        about = "Reduce the working tree to a subset of tracked files",
+        after_help = command::sparse_checkout::SPARSE_CHECKOUT_EXAMPLES
+    )]
+    SparseCheckout(command::sparse_checkout::SparseCheckoutArgs),
+    #[command(
+        about = "Manage repositories nested in this one as submodules",
+        after_help = command::submodule::SUBMODULE_EXAMPLES
+    )]
+    Submodule(command::submodule::SubmoduleArgs),
 
     #[command(about = "Show commit logs", alias = "hist", alias = "history")]
     Log(command::log::LogArgs),
@@ -378,6 +388,11 @@ enum Commands {
     Show(command::show::ShowArgs),
     #[command(about = "List references in a local repository")]
     ShowRef(command::show_ref::ShowRefArgs),
+    #[command(
+        about = "Show branches and the commits that set them apart",
+        after_help = command::show_branch::SHOW_BRANCH_EXAMPLES
+    )]
+    ShowBranch(command::show_branch::ShowBranchArgs),
     #[command(
         about = "Generate mbox-formatted patch files from commits",
         after_help = command::format_patch::FORMAT_PATCH_EXAMPLES
@@ -466,6 +481,11 @@ enum Commands {
         after_help = command::write_tree::WRITE_TREE_EXAMPLES
     )]
     WriteTree(command::write_tree::WriteTreeArgs),
+    #[command(
+        about = "Create a commit object from a tree",
+        after_help = command::commit_tree::COMMIT_TREE_EXAMPLES
+    )]
+    CommitTree(command::commit_tree::CommitTreeArgs),
     #[command(
         about = "Read a tree object into the index",
         after_help = command::read_tree::READ_TREE_EXAMPLES
@@ -483,6 +503,16 @@ enum Commands {
     UpdateRef(command::update_ref::UpdateRefArgs),
     #[command(about = "Validate pack index files against pack archives")]
     VerifyPack(command::verify_pack::VerifyPackArgs),
+    #[command(
+        about = "Check the PGP signature of commits",
+        after_help = command::verify_commit::VERIFY_COMMIT_EXAMPLES
+    )]
+    VerifyCommit(command::verify_commit::VerifyCommitArgs),
+    #[command(
+        about = "Check the PGP signature of tags",
+        after_help = command::verify_tag::VERIFY_TAG_EXAMPLES
+    )]
+    VerifyTag(command::verify_tag::VerifyTagArgs),
 
     #[command(about = "Record changes to the repository", alias = "ci")]
     Commit(command::commit::CommitArgs),
@@ -509,7 +539,7 @@ enum Commands {
     )]
     MergeBase(command::merge_base::MergeBaseArgs),
     #[command(
-        about = "Check whether a patch applies (git apply --check)",
+        about = "Apply a unified-diff patch to the working tree and index",
         after_help = command::apply::APPLY_EXAMPLES
     )]
     Apply(command::apply::ApplyArgs),
@@ -533,6 +563,11 @@ enum Commands {
         after_help = command::credential::CREDENTIAL_EXAMPLES
     )]
     Credential(command::credential::CredentialArgs),
+    #[command(
+        about = "Configure and test credential.helper; built-in store and keychain helpers",
+        after_help = command::credential_helper::CREDENTIAL_HELPER_EXAMPLES
+    )]
+    CredentialHelper(command::credential_helper::CredentialHelperArgs),
     #[command(
         about = "Reuse recorded conflict resolutions (git rerere)",
         after_help = command::rerere::RERERE_EXAMPLES
@@ -608,6 +643,11 @@ enum Commands {
     Graph(command::graph::GraphArgs),
     #[command(about = "Inspect AI sandbox diagnostics")]
     Sandbox(command::sandbox::SandboxArgs),
+    #[command(
+        about = "Export HEAD, status, recent commits, and the staged diff as one JSON document for AI agents (Libra extension)",
+        after_help = command::export_snapshot::EXPORT_SNAPSHOT_EXAMPLES
+    )]
+    ExportSnapshot(command::export_snapshot::ExportSnapshotArgs),
     #[command(about = "Manage external-agent capture (Claude Code, Gemini, …)")]
     Agent(command::agent::AgentArgs),
     #[command(
@@ -1159,13 +1199,18 @@ fn command_preflight(command: &Commands) -> CliResult<CommandPreflight> {
         // (exit 0, no output) even outside a repository, so it touches no objects
         // and skips the hash-kind preflight. It resolves the repo vault lazily.
         | Commands::Credential(_)
+        // `credential-helper` configures the global helper and runs helpers over
+        // stdin/stdout; like `credential`, it must work outside a repository.
+        | Commands::CredentialHelper(_)
         // `completions` renders a shell script from the clap command tree; it
         // reads no objects and works outside a repository.
         | Commands::Completions(_)
         // `logfile` only inspects env-derived tracing configuration.
         | Commands::Logfile(_)
         // `cache info` only inspects env/config-derived storage tunables.
-        | Commands::Cache(_)
+        | Commands::Cache(command::cache::CacheArgs {
+            command: command::cache::CacheCommand::Info,
+        })
         | Commands::Sandbox(_) => Ok(CommandPreflight::none()),
         Commands::HashObject(args) if !args.write => {
             match utils::util::try_get_storage_path(None) {
@@ -1184,6 +1229,14 @@ fn command_preflight(command: &Commands) -> CliResult<CommandPreflight> {
         // `grep --no-index` searches the filesystem directly and works outside a
         // repository, so it needs no storage/hash-kind preflight.
         Commands::Grep(args) if args.no_index => Ok(CommandPreflight::none()),
+        // `diff --no-index` compares files on disk; inside a repository it still
+        // hashes with the repository's object format for the `index` lines.
+        Commands::Diff(args) if args.no_index => match utils::util::try_get_storage_path(None) {
+            Ok(storage) => Ok(CommandPreflight::repo_hash_kind_without_schema_guard(
+                storage,
+            )),
+            Err(_) => Ok(CommandPreflight::sha1_without_repo()),
+        },
         Commands::Archive(args) if args.list => Ok(CommandPreflight::none()),
         #[cfg(unix)]
         Commands::Worktree(command::worktree::WorktreeArgs {
@@ -1450,6 +1503,9 @@ pub async fn parse_async(args: Option<&[&str]>) -> CliResult<()> {
         Commands::Usage(cmd_args) => command::usage::execute_safe(cmd_args, &output).await?,
         Commands::Graph(cmd_args) => command::graph::execute_safe(cmd_args, &output).await?,
         Commands::Sandbox(cmd_args) => command::sandbox::execute_safe(cmd_args, &output).await?,
+        Commands::ExportSnapshot(cmd_args) => {
+            command::export_snapshot::execute_safe(cmd_args, &output).await?
+        }
         Commands::Add(cmd_args) => command::add::execute_safe(cmd_args, &output).await?,
         Commands::Rm(cmd_args) => command::remove::execute_safe(cmd_args, &output).await?,
         Commands::Restore(cmd_args) => command::restore::execute_safe(cmd_args, &output).await?,
@@ -1467,6 +1523,9 @@ pub async fn parse_async(args: Option<&[&str]>) -> CliResult<()> {
         Commands::Shortlog(cmd_args) => command::shortlog::execute_safe(cmd_args, &output).await?,
         Commands::Show(cmd_args) => command::show::execute_safe(cmd_args, &output).await?,
         Commands::ShowRef(cmd_args) => command::show_ref::execute_safe(cmd_args, &output).await?,
+        Commands::ShowBranch(cmd_args) => {
+            command::show_branch::execute_safe(cmd_args, &output).await?
+        }
         Commands::FormatPatch(cmd_args) => {
             command::format_patch::execute_safe(cmd_args, &output).await?
         }
@@ -1503,6 +1562,9 @@ pub async fn parse_async(args: Option<&[&str]>) -> CliResult<()> {
         Commands::Credential(cmd_args) => {
             command::credential::execute_safe(cmd_args, &output).await?
         }
+        Commands::CredentialHelper(cmd_args) => {
+            command::credential_helper::execute_safe(cmd_args, &output).await?
+        }
         Commands::Rerere(cmd_args) => command::rerere::execute_safe(cmd_args, &output).await?,
         Commands::Reset(cmd_args) => command::reset::execute_safe(cmd_args, &output).await?,
         Commands::RevParse(cmd_args) => command::rev_parse::execute_safe(cmd_args, &output).await?,
@@ -1537,6 +1599,9 @@ pub async fn parse_async(args: Option<&[&str]>) -> CliResult<()> {
         Commands::WriteTree(cmd_args) => {
             command::write_tree::execute_safe(cmd_args, &output).await?
         }
+        Commands::CommitTree(cmd_args) => {
+            command::commit_tree::execute_safe(cmd_args, &output).await?
+        }
         Commands::ReadTree(cmd_args) => command::read_tree::execute_safe(cmd_args, &output).await?,
         Commands::UpdateIndex(cmd_args) => {
             command::update_index::execute_safe(cmd_args, &output).await?
@@ -1551,6 +1616,12 @@ pub async fn parse_async(args: Option<&[&str]>) -> CliResult<()> {
         Commands::VerifyPack(cmd_args) => {
             command::verify_pack::execute_safe(cmd_args, &output).await?
         }
+        Commands::VerifyCommit(cmd_args) => {
+            command::verify_commit::execute_safe(cmd_args, &output).await?
+        }
+        Commands::VerifyTag(cmd_args) => {
+            command::verify_tag::execute_safe(cmd_args, &output).await?
+        }
         Commands::IndexPack(cmd_args) => command::index_pack::execute_safe(cmd_args, &output)?,
         Commands::PackObjects(cmd_args) => {
             command::pack_objects::execute_safe(cmd_args, &output).await?
@@ -1574,6 +1645,12 @@ pub async fn parse_async(args: Option<&[&str]>) -> CliResult<()> {
         Commands::Reflog(cmd_args) => command::reflog::execute_safe(cmd_args, &output).await?,
         Commands::Op(cmd_args) => command::op::execute_safe(cmd_args, &output).await?,
         Commands::Worktree(cmd_args) => command::worktree::execute_safe(cmd_args, &output).await?,
+        Commands::SparseCheckout(cmd_args) => {
+            command::sparse_checkout::execute_safe(cmd_args, &output).await?
+        }
+        Commands::Submodule(cmd_args) => {
+            command::submodule::execute_safe(cmd_args, &output).await?
+        }
         Commands::Cloud(cmd_args) => command::cloud::execute_safe(cmd_args, &output).await?,
         Commands::Publish(cmd_args) => command::publish::execute_safe(cmd_args, &output).await?,
         Commands::Agent(cmd_args) => command::agent::execute_safe(cmd_args, &output).await?,
@@ -35,11 +35,15 @@ use git_internal::{
 use serde::Serialize;
 
 use crate::{
-    command::status::{self, Changes},
+    command::{
+        BlobFilter, add_patch,
+        status::{self, Changes},
+    },
     internal::ai::automation::{VCS_EVENT_POST_ADD, dispatch_current_repo_vcs_event_to_history},
     utils::{
         error::{CliError, CliResult, StableErrorCode},
-        lfs,
+        gitattributes::{self, Attributes},
+        index_marks::{self, IndexMark},
         object_ext::BlobExt,
         output::{self, OutputConfig},
         path, util,
@@ -54,6 +58,8 @@ EXAMPLES:
     libra add -A                       Stage all changes (adds, modifies, removes)
     libra add -u                       Update tracked files only (no new files)
     libra add --dry-run .              Preview what would be staged
+    libra add -p src/main.rs           Choose hunks to stage interactively
+    libra add -N new_file.rs           Record a new file without staging its content
     libra add -f ignored_file.log      Force-add an ignored file
     libra add --refresh                Refresh index metadata without staging";
 
@@ -129,6 +135,25 @@ pub struct AddArgs {
     /// failing. Mirrors Git's `add --ignore-missing`, which requires `--dry-run`.
     #[clap(long = "ignore-missing", requires = "dry_run")]
     pub ignore_missing: bool,
+
+    /// Interactively choose hunks of the tracked files' changes to stage,
+    /// answering y/n/q/s (split) per hunk. Untracked files are not offered.
+    #[clap(
+        short = 'p',
+        long,
+        conflicts_with_all = ["all", "refresh", "dry_run", "chmod", "renormalize"]
+    )]
+    pub patch: bool,
+
+    /// Record untracked paths as intent-to-add: an empty placeholder index
+    /// entry that makes the file show up in `diff` and `add -p` without staging
+    /// its content. Tracked paths are left alone.
+    #[clap(
+        short = 'N',
+        long = "intent-to-add",
+        conflicts_with_all = ["refresh", "patch", "renormalize"]
+    )]
+    pub intent_to_add: bool,
 }
 
 /// Domain error for `libra add`.
@@ -180,6 +205,10 @@ pub enum AddError {
     /// [`status::StatusError`] is preserved as a source.
     #[error("failed to inspect repository status: {source}")]
     Status { source: status::StatusError },
+    /// Reading an answer from stdin or writing a hunk to stdout failed during
+    /// `--patch`.
+    #[error("failed to run the interactive patch prompt: {source}")]
+    Prompt { source: io::Error },
 }
 
 impl From<AddError> for CliError {
@@ -222,6 +251,9 @@ impl From<AddError> for CliError {
             AddError::Status { .. } => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::RepoCorrupt)
                 .with_hint("failed to compute working tree status"),
+            AddError::Prompt { .. } => {
+                CliError::fatal(error.to_string()).with_stable_code(StableErrorCode::IoReadFailed)
+            }
         }
     }
 }
@@ -306,10 +338,11 @@ impl AddOutput {
 // Action tracking for add_a_file
 // ---------------------------------------------------------------------------
 
-/// The outcome of staging a single path. Returned by [`stage_a_file`] so the
-/// caller can sort each path into the correct [`AddOutput`] bucket.
+/// The outcome of staging a single path. Returned by [`stage_a_file`] (and
+/// `add_patch::stage_patch`) so the caller can sort each path into the correct
+/// [`AddOutput`] bucket.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum StagedAction {
+pub(crate) enum StagedAction {
     Added,
     Modified,
     Removed,
@@ -382,6 +415,12 @@ pub async fn execute(args: AddArgs) {
 pub async fn execute_safe(mut args: AddArgs, output: &OutputConfig) -> CliResult<()> {
     let verbose = args.verbose;
     let dry_run = args.dry_run;
+    if args.patch && output.is_json() {
+        return Err(CliError::command_usage(
+            "--patch is interactive and cannot be used with --json",
+        )
+        .with_stable_code(StableErrorCode::CliInvalidArguments));
+    }
 
     // If --pathspec-from-file is specified, read and merge pathspecs.
     if let Some(file) = args.pathspec_from_file.take() {
@@ -478,7 +517,7 @@ pub async fn run_add(args: &AddArgs) -> CliResult<AddOutput> {
     // Resolve pathspecs. `--renormalize` implies `-u` (tracked-only), so it also
     // permits an empty pathspec (operate on the whole tracked set).
     let requested_paths: Vec<PathBuf> = if args.pathspec.is_empty() {
-        if !args.all && !args.update && !args.refresh && !args.renormalize {
+        if !args.all && !args.update && !args.refresh && !args.renormalize && !args.patch {
             return Err(CliError::command_usage("nothing specified, nothing added")
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
                 .with_hint("maybe you wanted to say 'libra add .'?"));
@@ -567,7 +606,7 @@ pub async fn run_add(args: &AddArgs) -> CliResult<AddOutput> {
     } else {
         let mut f = visible_changes.modified;
         f.extend(visible_changes.deleted);
-        if !args.update {
+        if !args.update && !args.patch {
             f.extend(visible_changes.new);
         }
         filter_candidates(&f, &validated.files, &workdir, &current_dir)
@@ -594,6 +633,7 @@ pub async fn run_add(args: &AddArgs) -> CliResult<AddOutput> {
             let status = check_file_status(file, &index, &workdir)?;
             match status {
                 FileStatus::New => add_output.added.push(path_str),
+                _ if args.intent_to_add => {}
                 FileStatus::Modified => add_output.modified.push(path_str),
                 FileStatus::Deleted => add_output.removed.push(path_str),
                 FileStatus::Unchanged | FileStatus::NotFound => {}
@@ -613,12 +653,51 @@ pub async fn run_add(args: &AddArgs) -> CliResult<AddOutput> {
         return check_ignored_only_error(add_output);
     }
 
+    if args.intent_to_add {
+        let recorded = record_intent_to_add(&files, &mut index, &workdir)?;
+        index_marks::mark(&index, IndexMark::IntentToAdd, &recorded, true)
+            .map_err(|source| AddError::Workdir { source })?;
+        add_output.added = recorded;
+        index
+            .save(&index_path)
+            .map_err(|source| AddError::IndexSave {
+                path: index_path.clone(),
+                source,
+            })?;
+        return check_ignored_only_error(add_output);
+    }
+
     // Stage each file (`--renormalize` force-rewrites instead of diffing).
+    let attributes = Attributes::for_worktree().await;
+    if args.patch {
+        let staged = add_patch::stage_patch(
+            &files,
+            &mut index,
+            &workdir,
+            &attributes,
+            &mut io::stdin().lock(),
+            &mut io::stdout().lock(),
+        )?;
+        for (file, action) in staged {
+            let path_str = file.display().to_string();
+            match action {
+                StagedAction::Removed => add_output.removed.push(path_str),
+                _ => add_output.modified.push(path_str),
+            }
+        }
+        index
+            .save(&index_path)
+            .map_err(|source| AddError::IndexSave {
+                path: index_path.clone(),
+                source,
+            })?;
+        return check_ignored_only_error(add_output);
+    }
     for file in &files {
         let staged = if args.renormalize {
-            renormalize_entry(file, &mut index, &workdir)
+            renormalize_entry(file, &mut index, &workdir, &attributes)
         } else {
-            stage_a_file(file, &mut index, &workdir, &storage_path).await
+            stage_a_file(file, &mut index, &workdir, &storage_path, &attributes).await
         };
         match staged {
             Ok(action) => {
@@ -662,10 +741,47 @@ pub async fn run_add(args: &AddArgs) -> CliResult<AddOutput> {
             path: index_path.clone(),
             source,
         })?;
+    // Staged paths stop being intent-to-add, including empty files whose blob
+    // equals the placeholder.
+    let staged_paths: Vec<String> = files.iter().map(|f| f.display().to_string()).collect();
+    index_marks::retire(IndexMark::IntentToAdd, &staged_paths)
+        .map_err(|source| AddError::Workdir { source })?;
 
     check_ignored_only_error(add_output)
 }
 
+/// Record an intent-to-add placeholder (the empty blob, zeroed stat) for every
+/// untracked regular file in `files`; tracked paths are skipped, as in Git.
+/// Returns the index keys that were recorded.
+fn record_intent_to_add(
+    files: &[PathBuf],
+    index: &mut Index,
+    workdir: &Path,
+) -> Result<Vec<String>, AddError> {
+    let placeholder = index_marks::placeholder_blob();
+    let mut recorded = Vec::new();
+    for file in files {
+        if !matches!(check_file_status(file, index, workdir)?, FileStatus::New)
+            || !workdir.join(file).is_file()
+        {
+            continue;
+        }
+        let file_str = file.to_str().ok_or_else(|| AddError::InvalidPathEncoding {
+            path: file.to_path_buf(),
+        })?;
+        if recorded.is_empty() {
+            placeholder.save();
+        }
+        index.add(IndexEntry::new_from_blob(
+            file_str.to_string(),
+            placeholder.id,
+            0,
+        ));
+        recorded.push(file_str.to_string());
+    }
+    Ok(recorded)
+}
+
 /// Parse a `--chmod=(+|-)x` value into the index mode to record: `+x` ->
 /// `100755` (executable), `-x` -> `100644`.
 fn parse_chmod(value: &str) -> CliResult<u32> {
@@ -748,6 +864,7 @@ fn renormalize_entry(
     file: &Path,
     index: &mut Index,
     workdir: &Path,
+    attributes: &Attributes,
 ) -> Result<StagedAction, AddError> {
     let file_str = file.to_str().ok_or_else(|| AddError::InvalidPathEncoding {
         path: file.to_path_buf(),
@@ -772,7 +889,7 @@ fn renormalize_entry(
         // follows the link) would corrupt the entry. Leave it untouched.
         return Ok(StagedAction::Unchanged);
     }
-    let blob = gen_blob_from_file(&file_abs);
+    let blob = gen_blob_from_file(&file_abs, file, attributes);
     blob.save();
     index.update(
         IndexEntry::new_from_file(file, blob.id, workdir).map_err(|source| {
@@ -1225,6 +1342,7 @@ async fn stage_a_file(
     index: &mut Index,
     workdir: &Path,
     storage_path: &Path,
+    attributes: &Attributes,
 ) -> Result<StagedAction, AddError> {
     let file_abs = workdir.join(file);
     if !util::is_sub_path(&file_abs, workdir) {
@@ -1249,7 +1367,7 @@ async fn stage_a_file(
     let file_status = check_file_status(file, index, workdir)?;
     match file_status {
         FileStatus::New => {
-            let blob = gen_blob_from_file(&file_abs);
+            let blob = gen_blob_from_file(&file_abs, file, attributes);
             blob.save();
             index.add(
                 IndexEntry::new_from_file(file, blob.id, workdir).map_err(|source| {
@@ -1263,7 +1381,7 @@ async fn stage_a_file(
         }
         FileStatus::Modified => {
             if index.is_modified(file_str, 0, workdir) {
-                let blob = gen_blob_from_file(&file_abs);
+                let blob = gen_blob_from_file(&file_abs, file, attributes);
                 if !index.verify_hash(file_str, 0, &blob.id) {
                     blob.save();
                     index.update(IndexEntry::new_from_file(file, blob.id, workdir).map_err(
@@ -1336,14 +1454,20 @@ fn check_file_status(file: &Path, index: &Index, workdir: &Path) -> Result<FileS
 /// Generate a `Blob` from a file.
 ///
 /// Functional scope:
-/// - When the file matches a `.libra_attributes` LFS filter, returns a pointer
-///   blob via [`Blob::from_lfs_file`]; otherwise reads the file content
-///   verbatim into a regular blob.
-fn gen_blob_from_file(path: impl AsRef<Path>) -> Blob {
-    if lfs::is_lfs_tracked(&path) {
-        Blob::from_lfs_file(&path)
-    } else {
-        Blob::from_file(&path)
+/// - Applies the [`BlobFilter`] for `relative`: when the file matches an LFS
+///   filter (`filter=lfs` in `.libra_attributes` or any `.gitattributes`),
+///   returns a pointer blob via [`Blob::from_lfs_file`]; otherwise reads the
+///   file content into a regular blob, applying the `text`/`eol` line-ending
+///   normalization (see [`gitattributes::convert_to_index`]).
+fn gen_blob_from_file(path: impl AsRef<Path>, relative: &Path, attributes: &Attributes) -> Blob {
+    match BlobFilter::for_path(&path, relative, attributes) {
+        BlobFilter::Lfs => Blob::from_lfs_file(&path),
+        BlobFilter::Normalize(attrs) => {
+            let data = std::fs::read(path.as_ref())
+                .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.as_ref().display()));
+            Blob::from_content_bytes(gitattributes::convert_to_index(&attrs, data))
+        }
+        BlobFilter::None => Blob::from_file(&path),
     }
 }
 
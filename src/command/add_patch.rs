@@ -0,0 +1,409 @@
+//! Interactive hunk staging for `libra add -p` / `--patch`.
+//!
+//! Builds a line-level hunk model of the index-vs-worktree diff of each tracked
+//! file with the same Myers line diff `libra diff` uses, prompts
+//! `y`/`n`/`q`/`s` for each hunk, and stages a blob holding the index content
+//! with only the accepted hunks applied.
+//!
+//! Non-obvious responsibilities:
+//! - A hunk is a group of change *runs* (maximal blocks of deleted/inserted
+//!   lines) whose context windows touch; splitting a hunk yields one hunk per
+//!   run, which is exactly "subdivide at the context boundaries".
+//! - Applying is done per run against the full line diff, so the result never
+//!   depends on context matching and split hunks cannot conflict.
+//! - The staged entry is written with a zeroed stat so `status` re-hashes the
+//!   file and still reports the unstaged remainder as modified.
+
+use std::{
+    io::{BufRead, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use git_internal::internal::{
+    index::{Index, IndexEntry},
+    object::blob::Blob,
+};
+use similar::{Algorithm, ChangeTag, TextDiff};
+
+use crate::{
+    command::{
+        add::{AddError, StagedAction},
+        load_object,
+    },
+    utils::{
+        gitattributes::{self, Attributes},
+        lfs,
+        object_ext::BlobExt,
+    },
+};
+
+/// Context lines shown around each hunk, matching `libra diff`'s default.
+const PATCH_CONTEXT: usize = 3;
+
+/// Line diff of one file, with its change runs and line positions.
+pub(crate) struct FilePatch<'a> {
+    /// Every line of the diff in order, newline included.
+    changes: Vec<(ChangeTag, &'a str)>,
+    /// 1-based old/new line number of each entry in `changes`.
+    positions: Vec<(usize, usize)>,
+    /// Maximal ranges of non-equal entries in `changes`.
+    runs: Vec<Range<usize>>,
+}
+
+/// A displayable hunk: the change runs `runs` of its [`FilePatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Hunk {
+    runs: Range<usize>,
+}
+
+impl Hunk {
+    /// Whether [`Hunk::split`] would produce more than one hunk.
+    pub(crate) fn can_split(&self) -> bool {
+        self.runs.len() > 1
+    }
+
+    /// Subdivide the hunk at its context boundaries: one hunk per change run.
+    pub(crate) fn split(&self) -> Vec<Hunk> {
+        self.runs
+            .clone()
+            .map(|run| Hunk { runs: run..run + 1 })
+            .collect()
+    }
+}
+
+impl<'a> FilePatch<'a> {
+    /// Diff `old` (index) against `new` (worktree) line by line.
+    pub(crate) fn new(old: &'a str, new: &'a str) -> Self {
+        let diff = TextDiff::configure()
+            .algorithm(Algorithm::Myers)
+            .diff_lines(old, new);
+        let changes: Vec<(ChangeTag, &'a str)> = diff
+            .iter_all_changes()
+            .map(|change| (change.tag(), change.value()))
+            .collect();
+
+        let mut positions = Vec::with_capacity(changes.len());
+        let (mut old_line, mut new_line) = (1, 1);
+        for (tag, _) in &changes {
+            positions.push((old_line, new_line));
+            match tag {
+                ChangeTag::Equal => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                ChangeTag::Delete => old_line += 1,
+                ChangeTag::Insert => new_line += 1,
+            }
+        }
+
+        let mut runs: Vec<Range<usize>> = Vec::new();
+        for (i, (tag, _)) in changes.iter().enumerate() {
+            if *tag == ChangeTag::Equal {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(run) if run.end == i => run.end = i + 1,
+                _ => runs.push(i..i + 1),
+            }
+        }
+
+        Self {
+            changes,
+            positions,
+            runs,
+        }
+    }
+
+    /// Group the change runs into hunks: adjacent runs share a hunk when at
+    /// most `2 * context` equal lines separate them.
+    pub(crate) fn hunks(&self, context: usize) -> Vec<Hunk> {
+        let mut hunks: Vec<Hunk> = Vec::new();
+        for (i, run) in self.runs.iter().enumerate() {
+            match hunks.last_mut() {
+                Some(hunk) if run.start - self.runs[hunk.runs.end - 1].end <= 2 * context => {
+                    hunk.runs.end = i + 1;
+                }
+                _ => hunks.push(Hunk { runs: i..i + 1 }),
+            }
+        }
+        hunks
+    }
+
+    /// Render `hunk` as a unified-diff hunk with `context` lines around it.
+    pub(crate) fn render(&self, hunk: &Hunk, context: usize) -> String {
+        let first = self.runs[hunk.runs.start].start;
+        let last = self.runs[hunk.runs.end - 1].end;
+        let start = first.saturating_sub(context);
+        let end = (last + context).min(self.changes.len());
+        let lines = &self.changes[start..end];
+
+        let old_lines = lines
+            .iter()
+            .filter(|(tag, _)| *tag != ChangeTag::Insert)
+            .count();
+        let new_lines = lines
+            .iter()
+            .filter(|(tag, _)| *tag != ChangeTag::Delete)
+            .count();
+        let (old_start, new_start) = self.positions[start];
+        // An empty side is addressed by the line *before* it, like `diff -U`.
+        let old_start = if old_lines == 0 {
+            old_start - 1
+        } else {
+            old_start
+        };
+        let new_start = if new_lines == 0 {
+            new_start - 1
+        } else {
+            new_start
+        };
+
+        let mut out = format!("@@ -{old_start},{old_lines} +{new_start},{new_lines} @@\n");
+        for (tag, text) in lines {
+            let sign = match tag {
+                ChangeTag::Equal => ' ',
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+            };
+            out.push(sign);
+            out.push_str(text);
+            if !text.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        out
+    }
+
+    /// Number of change runs; the length of the `accepted` slice for [`apply`].
+    ///
+    /// [`apply`]: FilePatch::apply
+    pub(crate) fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Rebuild the old text with the runs flagged in `accepted` applied.
+    pub(crate) fn apply(&self, accepted: &[bool]) -> String {
+        let mut out = String::new();
+        let mut run = 0;
+        for (i, (tag, text)) in self.changes.iter().enumerate() {
+            while run < self.runs.len() && self.runs[run].end <= i {
+                run += 1;
+            }
+            let take = match tag {
+                ChangeTag::Equal => true,
+                ChangeTag::Delete => !accepted[run],
+                ChangeTag::Insert => accepted[run],
+            };
+            if take {
+                out.push_str(text);
+            }
+        }
+        out
+    }
+}
+
+/// User's answer to one prompt.
+enum Answer {
+    Yes,
+    No,
+    Quit,
+    Split,
+}
+
+/// Prompt with `question` until a recognized answer is read. End of input is
+/// treated as `q`, so a piped answer list that runs short stops cleanly.
+fn ask(
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    question: &str,
+    can_split: bool,
+) -> Result<Answer, AddError> {
+    let choices = if can_split { "y,n,q,s,?" } else { "y,n,q,?" };
+    loop {
+        write!(out, "{question} [{choices}]? ").map_err(|source| AddError::Prompt { source })?;
+        out.flush().map_err(|source| AddError::Prompt { source })?;
+        let mut line = String::new();
+        let read = input
+            .read_line(&mut line)
+            .map_err(|source| AddError::Prompt { source })?;
+        if read == 0 {
+            writeln!(out).map_err(|source| AddError::Prompt { source })?;
+            return Ok(Answer::Quit);
+        }
+        match line.trim() {
+            "y" => return Ok(Answer::Yes),
+            "n" => return Ok(Answer::No),
+            "q" => return Ok(Answer::Quit),
+            "s" if can_split => return Ok(Answer::Split),
+            _ => {
+                let mut help = String::from(
+                    "y - stage this hunk\nn - do not stage this hunk\n\
+                     q - quit; do not stage this hunk or any of the remaining ones\n",
+                );
+                if can_split {
+                    help.push_str("s - split the current hunk into smaller hunks\n");
+                }
+                help.push_str("? - print help\n");
+                write!(out, "{help}").map_err(|source| AddError::Prompt { source })?;
+            }
+        }
+    }
+}
+
+/// Interactively stage hunks of the tracked `files` (relative to `workdir`).
+///
+/// Modified text files are offered hunk by hunk; a deleted file is offered as
+/// a single deletion. New, binary, and LFS files are skipped, as `git add -p`
+/// skips untracked and unpatchable paths. Answering `q` (or reaching end of
+/// input) stops prompting but keeps the hunks accepted so far.
+pub(crate) fn stage_patch(
+    files: &[PathBuf],
+    index: &mut Index,
+    workdir: &Path,
+    attributes: &Attributes,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> Result<Vec<(PathBuf, StagedAction)>, AddError> {
+    let mut staged = Vec::new();
+    for file in files {
+        let file_str = file.to_str().ok_or_else(|| AddError::InvalidPathEncoding {
+            path: file.to_path_buf(),
+        })?;
+        let Some((mode, hash)) = index.get(file_str, 0).map(|e| (e.mode, e.hash)) else {
+            continue;
+        };
+        let file_abs = workdir.join(file);
+
+        if !file_abs.exists() {
+            writeln!(out, "deleted file {file_str}")
+                .map_err(|source| AddError::Prompt { source })?;
+            match ask(input, out, "Stage deletion", false)? {
+                Answer::Yes => {
+                    index.remove(file_str, 0);
+                    staged.push((file.clone(), StagedAction::Removed));
+                }
+                Answer::No | Answer::Split => {}
+                Answer::Quit => break,
+            }
+            continue;
+        }
+        if !file_abs.is_file() || lfs::is_lfs_tracked(&file_abs) {
+            continue;
+        }
+        let attrs = attributes.lookup(file);
+        if gitattributes::is_lfs_filter(&attrs) {
+            continue;
+        }
+
+        let worktree = std::fs::read(&file_abs).map_err(|source| AddError::CreateIndexEntry {
+            path: file.clone(),
+            source,
+        })?;
+        let worktree = gitattributes::convert_to_index(&attrs, worktree);
+        let Ok(indexed) = load_object::<Blob>(&hash) else {
+            continue;
+        };
+        let (Ok(old), Ok(new)) = (
+            std::str::from_utf8(&indexed.data),
+            std::str::from_utf8(&worktree),
+        ) else {
+            continue;
+        };
+        if old.contains('\0') || new.contains('\0') {
+            continue;
+        }
+
+        let patch = FilePatch::new(old, new);
+        if patch.run_count() == 0 {
+            continue;
+        }
+        writeln!(out, "diff --libra a/{file_str} b/{file_str}")
+            .map_err(|source| AddError::Prompt { source })?;
+
+        let mut accepted = vec![false; patch.run_count()];
+        let mut hunks = patch.hunks(PATCH_CONTEXT);
+        let mut quit = false;
+        let mut i = 0;
+        while i < hunks.len() {
+            write!(out, "{}", patch.render(&hunks[i], PATCH_CONTEXT))
+                .map_err(|source| AddError::Prompt { source })?;
+            let question = format!("({}/{}) Stage this hunk", i + 1, hunks.len());
+            match ask(input, out, &question, hunks[i].can_split())? {
+                Answer::Yes => {
+                    accepted[hunks[i].runs.clone()].fill(true);
+                    i += 1;
+                }
+                Answer::No => i += 1,
+                Answer::Split => {
+                    let parts = hunks[i].split();
+                    writeln!(out, "Split into {} hunks.", parts.len())
+                        .map_err(|source| AddError::Prompt { source })?;
+                    hunks.splice(i..=i, parts);
+                }
+                Answer::Quit => {
+                    quit = true;
+                    break;
+                }
+            }
+        }
+
+        if accepted.contains(&true) {
+            let blob = Blob::from_content_bytes(patch.apply(&accepted).into_bytes());
+            blob.save();
+            // Zeroed stat (new_from_blob) keeps the worktree file "dirty" for
+            // status, which must still see the hunks left unstaged.
+            let mut entry =
+                IndexEntry::new_from_blob(file_str.to_string(), blob.id, blob.data.len() as u32);
+            entry.mode = mode;
+            index.update(entry);
+            staged.push((file.clone(), StagedAction::Modified));
+        }
+        if quit {
+            break;
+        }
+    }
+    Ok(staged)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const OLD: &str = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+    const NEW: &str = "1\nTWO\n3\n4\n5\n6\n7\n8\nNINE\n10\n";
+
+    /// Two changes four lines apart share one hunk at context 3 and split into
+    /// two; applying only the first run stages only that change.
+    #[test]
+    fn split_and_apply_single_run() {
+        let patch = FilePatch::new(OLD, NEW);
+        let hunks = patch.hunks(3);
+        assert_eq!(hunks.len(), 1);
+        assert!(hunks[0].can_split());
+        assert!(
+            patch
+                .render(&hunks[0], 3)
+                .starts_with("@@ -1,10 +1,10 @@\n")
+        );
+
+        let parts = hunks[0].split();
+        assert_eq!(parts.len(), 2);
+        assert!(!parts[1].can_split());
+        assert!(patch.render(&parts[1], 3).starts_with("@@ -6,5 +6,5 @@\n"));
+
+        assert_eq!(
+            patch.apply(&[true, false]),
+            "1\nTWO\n3\n4\n5\n6\n7\n8\n9\n10\n"
+        );
+        assert_eq!(patch.apply(&[true, true]), NEW);
+        assert_eq!(patch.apply(&[false, false]), OLD);
+    }
+
+    /// Runs separated by more than twice the context form separate hunks.
+    #[test]
+    fn distant_runs_form_separate_hunks() {
+        let patch = FilePatch::new(OLD, NEW);
+        assert_eq!(patch.hunks(1).len(), 2);
+    }
+}
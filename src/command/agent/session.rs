@@ -820,7 +820,7 @@ async fn promote_as_intent_with_conn(
 
     use crate::{
         internal::ai::history::HistoryManager,
-        utils::{storage::local::LocalStorage, storage_ext::StorageExt},
+        utils::{client_storage::ClientStorage, storage_ext::StorageExt},
     };
 
     let snapshot = load_agent_session_snapshot(conn, &args.session_id).await?;
@@ -842,7 +842,7 @@ async fn promote_as_intent_with_conn(
     let objects_dir = repo_path.join("objects");
     std::fs::create_dir_all(&objects_dir)
         .map_err(|e| CliError::fatal(format!("create objects dir: {e}")))?;
-    let storage = Arc::new(LocalStorage::new(objects_dir));
+    let storage = Arc::new(ClientStorage::init(objects_dir));
     let history = HistoryManager::new(storage.clone(), repo_path, Arc::new(conn.clone()));
     let blob_hash = storage
         .put_tracked(&intent, &history)
@@ -1,11 +1,13 @@
-//! `libra apply --check` — validate that a unified-diff patch applies cleanly,
-//! without writing anything. A focused MVP of `git apply --check` built on the
-//! same `diffy` patch engine used elsewhere.
+//! `libra apply` — apply a unified-diff patch to the working tree (and, with
+//! `--index`, to the index), or with `--check` only validate that it applies.
+//! Built on the same `diffy` patch engine used elsewhere; this consumes
+//! standard unified diffs (`diff -u`, `libra diff`, `format-patch`), unlike
+//! the AI `apply_patch` tool's Codex format.
 //!
-//! Only `--check` is supported in this version: the patch is parsed, every
-//! target path is safety-checked, and each file hunk-set is test-applied
-//! against the current working tree. Actually writing the result (atomic
-//! temp-file + rename) is a documented future extension.
+//! Every file section is parsed, safety-checked and test-applied before
+//! anything is written, so a patch that does not apply as a whole leaves the
+//! working tree and index untouched. Existing files are replaced through
+//! [`atomic_write::write_atomic`].
 
 use std::{
     fs,
@@ -14,12 +16,18 @@ use std::{
 };
 
 use clap::Parser;
+use git_internal::internal::{
+    index::{Index, IndexEntry},
+    object::blob::Blob,
+};
 use serde::Serialize;
 
 use crate::utils::{
+    atomic_write,
     error::{CliError, CliResult, StableErrorCode},
+    object_ext::BlobExt,
     output::{OutputConfig, emit_json_data},
-    util,
+    path, util,
 };
 
 /// Hard cap on a patch input, matching the grit-gap plan's default.
@@ -28,20 +36,31 @@ const MAX_PATCH_BYTES: usize = 64 * 1024 * 1024;
 /// `--help` examples (cross-cutting EXAMPLES contract, `_general.md`).
 pub const APPLY_EXAMPLES: &str = "\
 EXAMPLES:
+    libra apply fix.patch                    Apply a patch to the working tree
+    libra apply --index fix.patch            Apply to the working tree and stage the result
+    libra apply -R fix.patch                 Undo a previously applied patch
     libra apply --check fix.patch            Check whether a patch applies cleanly
-    libra apply --check -p0 fix.patch        Do not strip a leading path component
-    cat fix.patch | libra apply --check      Read the patch from stdin
-    libra --json apply --check fix.patch     Structured { applies, files }";
+    libra apply -p0 fix.patch                Do not strip a leading path component
+    cat fix.patch | libra apply              Read the patch from stdin
+    libra --json apply --check fix.patch     Structured { applies, applied, files }";
 
-/// Validate that a unified-diff patch applies (`--check` only in this version).
+/// Apply a unified-diff patch to the working tree, or validate it.
 #[derive(Parser, Debug)]
 #[command(after_help = APPLY_EXAMPLES)]
 pub struct ApplyArgs {
-    /// Check whether the patch applies, without writing. Required in this
-    /// version (actually applying the patch is not yet supported).
+    /// Check whether the patch applies, without writing anything.
     #[clap(long)]
     pub check: bool,
 
+    /// Apply the patch to the index as well as the working tree. Every file
+    /// the patch touches must match its index entry.
+    #[clap(long)]
+    pub index: bool,
+
+    /// Apply the patch in reverse (undo it).
+    #[clap(short = 'R', long)]
+    pub reverse: bool,
+
     /// Strip `<n>` leading path components from each patched path (like
     /// `git apply -p<n>`; default 1).
     #[clap(short = 'p', value_name = "N", default_value_t = 1)]
@@ -56,8 +75,22 @@ pub struct ApplyArgs {
 struct ApplyOutput {
     /// Whether the whole patch applies cleanly.
     applies: bool,
+    /// Whether the patch was written (false under `--check` or on failure).
+    applied: bool,
+    /// Whether the index was updated too (`--index`).
+    index: bool,
     /// The target paths the patch touches.
     files: Vec<String>,
+    /// Why the patch does not apply, one message per failing file.
+    errors: Vec<String>,
+}
+
+/// The outcome of test-applying one file section.
+struct FilePlan {
+    target: String,
+    absolute: PathBuf,
+    /// New content, or `None` when the patch deletes the file.
+    content: Option<String>,
 }
 
 pub async fn execute(args: ApplyArgs) {
@@ -67,9 +100,10 @@ pub async fn execute(args: ApplyArgs) {
     }
 }
 
-/// Safe entry point. Exit 0 when the patch applies, 1 when it does not, 128 on
-/// errors (not a repo, unsupported mode, unreadable/oversized/malformed patch,
-/// or an unsafe target path).
+/// Safe entry point. Exit 0 when the patch applies (and, without `--check`,
+/// was written), 1 when it does not, 128 on errors (not a repo,
+/// unreadable/oversized/malformed patch, an unsafe target path, or a failed
+/// write).
 pub async fn execute_safe(args: ApplyArgs, output: &OutputConfig) -> CliResult<()> {
     util::require_repo().map_err(|_| CliError::repo_not_found())?;
 
@@ -79,61 +113,175 @@ pub async fn execute_safe(args: ApplyArgs, output: &OutputConfig) -> CliResult<(
             .with_stable_code(StableErrorCode::CliInvalidArguments)
     };
 
-    if !args.check {
-        return Err(error(
-            "this version of `apply` only supports --check; pass --check to validate a patch"
-                .to_string(),
-        ));
-    }
-
     let patch_text = read_patch(&args.patches).map_err(error)?;
     let workdir = util::working_dir();
+    let index_file = path::index();
+    let mut index = if args.index {
+        Some(Index::load(&index_file).map_err(|err| {
+            CliError::fatal(format!("failed to load index: {err}"))
+                .with_stable_code(StableErrorCode::IoReadFailed)
+        })?)
+    } else {
+        None
+    };
 
     let mut files = Vec::new();
-    let mut applies = true;
+    let mut plans = Vec::new();
+    let mut errors = Vec::new();
     for section in split_file_patches(&patch_text) {
-        let patch = diffy::Patch::from_str(&section)
+        let parsed = diffy::Patch::from_str(&section)
             .map_err(|err| error(format!("malformed patch: {err}")))?;
-        let target = patch_target(&patch, args.strip).map_err(error)?;
+        let reversed;
+        let patch = if args.reverse {
+            reversed = parsed.reverse();
+            &reversed
+        } else {
+            &parsed
+        };
+        let target = patch_target(patch, args.strip).map_err(error)?;
         let absolute = resolve_safe(&target, &workdir).map_err(error)?;
         files.push(target.clone());
+        match plan_file(patch, target, absolute, index.as_ref()) {
+            Ok(plan) => plans.push(plan),
+            Err(reason) => errors.push(reason),
+        }
+    }
 
-        let is_new_file = patch.original() == Some("/dev/null");
-        let is_deletion = patch.modified() == Some("/dev/null");
-        let base = if is_new_file {
-            String::new()
-        } else {
-            match fs::read_to_string(&absolute) {
-                Ok(content) => content,
-                // A missing or unreadable target means the patch does not apply.
-                Err(_) => {
-                    applies = false;
-                    continue;
-                }
-            }
-        };
-        match diffy::apply(&base, &patch) {
-            // A deletion patch must reduce the file to nothing; a non-empty
-            // result means the file did not match the patch's full extent.
-            Ok(result) if is_deletion && !result.is_empty() => applies = false,
-            Ok(_) => {}
-            Err(_) => applies = false,
+    let applies = errors.is_empty();
+    let applied = applies && !args.check;
+    if applied {
+        for plan in &plans {
+            write_plan(plan, &workdir, index.as_mut())?;
+        }
+        if let Some(index) = &index {
+            index.save(&index_file).map_err(|err| {
+                CliError::fatal(format!("failed to save index: {err}"))
+                    .with_stable_code(StableErrorCode::IoWriteFailed)
+            })?;
         }
     }
 
     if output.is_json() {
-        emit_json_data("apply", &ApplyOutput { applies, files }, output)?;
+        emit_json_data(
+            "apply",
+            &ApplyOutput {
+                applies,
+                applied,
+                index: applied && args.index,
+                files,
+                errors,
+            },
+            output,
+        )?;
+    } else {
+        for reason in &errors {
+            eprintln!("error: {reason}");
+        }
     }
 
     if applies {
         Ok(())
     } else {
-        // `--check` reports "does not apply" with exit 1 (Git-compatible); the
-        // working tree was never touched.
+        // "Does not apply" exits 1 (Git-compatible); nothing was written.
         Err(CliError::silent_exit(1))
     }
 }
 
+/// Test-apply one file section against the working tree (and, with an index,
+/// check that the file matches its index entry). `Err` carries the reason the
+/// patch does not apply to this file.
+fn plan_file(
+    patch: &diffy::Patch<'_, str>,
+    target: String,
+    absolute: PathBuf,
+    index: Option<&Index>,
+) -> Result<FilePlan, String> {
+    let is_new_file = patch.original() == Some("/dev/null");
+    let is_deletion = patch.modified() == Some("/dev/null");
+
+    let base = if is_new_file {
+        if absolute.exists() {
+            return Err(format!("{target}: already exists in working directory"));
+        }
+        if index.is_some_and(|index| index.tracked(&target, 0)) {
+            return Err(format!("{target}: already exists in index"));
+        }
+        String::new()
+    } else {
+        let Ok(content) = fs::read_to_string(&absolute) else {
+            return Err(format!("{target}: No such file or directory"));
+        };
+        if let Some(index) = index {
+            let Some(entry) = index.get(&target, 0) else {
+                return Err(format!("{target}: does not exist in index"));
+            };
+            if Blob::from_content_bytes(content.as_bytes().to_vec()).id != entry.hash {
+                return Err(format!("{target}: does not match index"));
+            }
+        }
+        content
+    };
+
+    let result = diffy::apply(&base, patch).map_err(|_| format!("patch failed: {target}"))?;
+    if is_deletion {
+        // A deletion patch must reduce the file to nothing; a non-empty
+        // result means the file did not match the patch's full extent.
+        if !result.is_empty() {
+            return Err(format!("{target}: patch does not apply"));
+        }
+        return Ok(FilePlan {
+            target,
+            absolute,
+            content: None,
+        });
+    }
+    Ok(FilePlan {
+        target,
+        absolute,
+        content: Some(result),
+    })
+}
+
+/// Write one planned file to the working tree and, with an index, stage it.
+fn write_plan(plan: &FilePlan, workdir: &Path, index: Option<&mut Index>) -> CliResult<()> {
+    let write_error = |err: io::Error| {
+        CliError::fatal(format!("failed to write '{}': {err}", plan.target))
+            .with_stable_code(StableErrorCode::IoWriteFailed)
+    };
+
+    let Some(content) = &plan.content else {
+        fs::remove_file(&plan.absolute).map_err(write_error)?;
+        util::clear_empty_dir(&plan.absolute);
+        if let Some(index) = index {
+            index.remove(&plan.target, 0);
+        }
+        return Ok(());
+    };
+
+    match fs::metadata(&plan.absolute) {
+        // Replace an existing file atomically, keeping its permissions.
+        Ok(metadata) => {
+            atomic_write::write_atomic(&plan.absolute, content.as_bytes(), false)
+                .map_err(write_error)?;
+            fs::set_permissions(&plan.absolute, metadata.permissions()).map_err(write_error)?;
+        }
+        Err(_) => util::write_file(content.as_bytes(), &plan.absolute).map_err(write_error)?,
+    }
+
+    if let Some(index) = index {
+        let blob = Blob::from_content_bytes(content.as_bytes().to_vec());
+        blob.save();
+        let entry = IndexEntry::new_from_file(Path::new(&plan.target), blob.id, workdir).map_err(
+            |err| {
+                CliError::fatal(format!("failed to stage '{}': {err}", plan.target))
+                    .with_stable_code(StableErrorCode::IoWriteFailed)
+            },
+        )?;
+        index.update(entry);
+    }
+    Ok(())
+}
+
 /// Read the patch from the given files (concatenated) or from stdin, enforcing
 /// the size cap.
 fn read_patch(patches: &[String]) -> Result<String, String> {
@@ -14,9 +14,13 @@
 //! - Tracks two parallel structures: the in-flight `LineBlame` vector
 //!   (mutated as the BFS progresses) and the queued
 //!   `(commit, parent_lines)` work items.
+//! - With `-C`, hands the lines each commit introduced to
+//!   [`blame_copy`](super::blame_copy), which looks for them in other files.
+
+use std::path::{Path, PathBuf};
 
 use chrono::DateTime;
-use clap::Parser;
+use clap::{ArgAction, Parser};
 use git_internal::{
     diff::compute_diff,
     hash::ObjectHash,
@@ -26,7 +30,7 @@ use regex::Regex;
 use serde::Serialize;
 
 use crate::{
-    command::{get_target_commit, load_object},
+    command::{blame_copy, get_target_commit, load_object},
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         object_ext::TreeExt,
@@ -46,6 +50,8 @@ EXAMPLES:
     libra blame -l src/main.rs             Show full commit hashes
     libra blame -s src/main.rs             Suppress the author and date columns
     libra blame -w src/main.rs             Ignore whitespace-only changes when attributing lines
+    libra blame -C src/main.rs             Follow lines copied from files changed in the same commit
+    libra blame -CCC src/main.rs           Follow lines copied from any file, modified or not
     libra --json blame src/main.rs         Structured JSON output for agents";
 
 #[derive(Parser, Debug)]
@@ -98,7 +104,8 @@ pub struct BlameArgs {
     pub root: bool,
 
     /// Show the filename in the original commit (after the hash column). Libra
-    /// does not follow renames/copies, so every line shows the blamed file.
+    /// does not follow renames, so every line shows the blamed file unless `-C`
+    /// traced it to a copy; copied lines always show the filename.
     #[clap(short = 'f', long = "show-name")]
     pub show_name: bool,
 
@@ -107,6 +114,26 @@ pub struct BlameArgs {
     /// Matches Git's `-w` (ignore-all-whitespace) semantics.
     #[clap(short = 'w', long = "ignore-whitespace")]
     pub ignore_whitespace: bool,
+
+    /// Detect lines copied from other files. `-C` searches the files changed in
+    /// the commit that introduced a line, `-CC` also every file of the parent
+    /// when that commit created the blamed file, and `-CCC` every file of the
+    /// parent in every commit. Copied lines are marked `C` with their origin.
+    #[clap(short = 'C', action = ArgAction::Count)]
+    pub copies: u8,
+
+    /// Minimum number of alphanumeric characters a block of lines must contain
+    /// to be attributed to a copy (Git's `-C<num>`).
+    #[clap(
+        long = "copy-threshold",
+        value_name = "N",
+        default_value_t = blame_copy::DEFAULT_COPY_SCORE
+    )]
+    pub copy_threshold: usize,
+
+    /// Skip copy detection even when `-C` is given.
+    #[clap(long = "skip-copy-detection")]
+    pub skip_copy_detection: bool,
 }
 
 /// Strip every whitespace character from a line for `-w` comparison, mirroring
@@ -117,7 +144,7 @@ pub struct BlameArgs {
 /// vertical-tab/form-feed/carriage-return only. We deliberately do NOT use
 /// `char::is_whitespace`, which also matches Unicode whitespace (e.g. NBSP), so
 /// a non-ASCII-whitespace edit is still treated as a real change as in Git.
-fn normalize_for_whitespace(line: &str) -> String {
+pub(crate) fn normalize_for_whitespace(line: &str) -> String {
     line.chars()
         .filter(|c| !matches!(c, ' ' | '\t' | '\n' | '\x0b' | '\x0c' | '\r'))
         .collect()
@@ -135,6 +162,10 @@ pub struct BlameLine {
     /// Raw author timestamp (epoch seconds); surfaced for `-t` and JSON callers.
     pub timestamp: i64,
     pub content: String,
+    /// File the line was copied from (`-C`); absent for lines of the blamed
+    /// file's own history.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copied_from: Option<String>,
 }
 
 /// Whole-file result of a `libra blame` invocation.
@@ -148,19 +179,23 @@ pub struct BlameOutput {
 /// Internal mutable state for one source line during the back-walk.
 /// `commit_id` is updated whenever an older ancestor still contains the same
 /// text — the final value is the line's introducing commit.
-struct LineBlame {
-    line_number: usize,
-    commit_id: ObjectHash,
-    author: String,
-    author_email: String,
-    timestamp: i64,
-    content: String,
+pub(crate) struct LineBlame {
+    pub(crate) line_number: usize,
+    pub(crate) commit_id: ObjectHash,
+    pub(crate) author: String,
+    pub(crate) author_email: String,
+    pub(crate) timestamp: i64,
+    pub(crate) content: String,
+    /// Workdir-relative path of the file the line is attributed in; differs
+    /// from the blamed file only for lines `-C` traced to a copy.
+    pub(crate) path: PathBuf,
+    pub(crate) copied: bool,
 }
 
 /// Domain error for `libra blame`. Mapped to stable [`CliError`] codes by
 /// the `From` impl below.
 #[derive(Debug, thiserror::Error)]
-enum BlameError {
+pub(crate) enum BlameError {
     /// CWD is not inside a `.libra` repository.
     #[error("not a libra repository")]
     NotInRepo,
@@ -258,6 +293,15 @@ pub async fn execute_safe(args: BlameArgs, out_config: &OutputConfig) -> CliResu
         return Ok(());
     }
 
+    // Copied lines force the filename column (like Git) with a `C` marker.
+    let any_copied = result.lines.iter().any(|line| line.copied_from.is_some());
+    let name_width = result
+        .lines
+        .iter()
+        .map(|line| line.copied_from.as_deref().unwrap_or(&result.file).len())
+        .max()
+        .unwrap_or(0);
+
     let mut output = String::new();
     for blame in &result.lines {
         // Hash column: `-l` shows the full hash; `--abbrev=<n>` shows n digits;
@@ -271,8 +315,17 @@ pub async fn execute_safe(args: BlameArgs, out_config: &OutputConfig) -> CliResu
         };
 
         // `-f`/`--show-name` inserts the filename right after the hash column.
-        // Libra does not follow renames, so it is the blamed file on every line.
-        let name_col = if args.show_name {
+        // Libra does not follow renames, so it is the blamed file on every line
+        // except those `-C` traced to a copy.
+        let name_col = if any_copied {
+            let marker = if blame.copied_from.is_some() {
+                'C'
+            } else {
+                ' '
+            };
+            let name = blame.copied_from.as_deref().unwrap_or(&result.file);
+            format!(" {marker} {name:name_width$}")
+        } else if args.show_name {
             format!(" {}", result.file)
         } else {
             String::new()
@@ -330,17 +383,14 @@ pub async fn execute_safe(args: BlameArgs, out_config: &OutputConfig) -> CliResu
 ///
 /// Functional scope:
 /// - Resolves the start commit and reads the file's lines at that revision.
-/// - Initialises one [`LineBlame`] per line, blaming everything to the start
-///   commit, then BFS-walks parents. For each `Equal` chunk in the diff to a
-///   parent, lines whose content still matches inherit the parent's commit
-///   id, author, and timestamp.
-/// - Applies the optional `-L` filter as a final pass.
+/// - Attributes every line through [`walk_history`].
+/// - Applies the optional `-L` filter, then (with `-C` and without
+///   `--skip-copy-detection`) traces the remaining lines that their commit
+///   introduced to copies in other files via [`blame_copy::detect_copies`].
 ///
 /// Boundary conditions:
 /// - Empty target file -> returns an empty [`BlameOutput`] without walking
 ///   history.
-/// - Failed parent loads (e.g. shallow clone boundary) are silently skipped
-///   so blame still produces a partial answer.
 /// - Bad `-L` ranges produce [`BlameError::InvalidLineRange`].
 async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
     util::require_repo().map_err(|_| BlameError::NotInRepo)?;
@@ -355,7 +405,12 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
         detail: e.to_string(),
     })?;
 
-    let target_lines = get_file_lines(&commit_obj, &args.file, &args.commit)?;
+    let target_path = util::to_workdir_path(&args.file);
+    let target_lines =
+        get_file_lines(&commit_obj, &target_path)?.ok_or_else(|| BlameError::FileNotFound {
+            path: args.file.clone(),
+            revision: args.commit.clone(),
+        })?;
 
     if target_lines.is_empty() {
         return Ok(BlameOutput {
@@ -365,7 +420,79 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
         });
     }
 
-    let mut blame_lines: Vec<LineBlame> = target_lines
+    let blame_lines = walk_history(
+        &target_path,
+        commit_id,
+        commit_obj,
+        target_lines,
+        args.ignore_whitespace,
+    );
+
+    let mut filtered_lines = if let Some(ref range) = args.line_range {
+        let (start, end) =
+            parse_line_range(range, &blame_lines).map_err(BlameError::InvalidLineRange)?;
+        blame_lines
+            .into_iter()
+            .filter(|b| b.line_number >= start && b.line_number <= end)
+            .collect::<Vec<_>>()
+    } else {
+        blame_lines
+    };
+
+    if args.copies > 0 && !args.skip_copy_detection {
+        let options = blame_copy::CopyOptions {
+            level: args.copies,
+            threshold: args.copy_threshold,
+            ignore_whitespace: args.ignore_whitespace,
+        };
+        blame_copy::detect_copies(&mut filtered_lines, &target_path, &options);
+    }
+
+    Ok(BlameOutput {
+        file: args.file.clone(),
+        revision: commit_id.to_string(),
+        lines: filtered_lines
+            .into_iter()
+            .map(|line| {
+                let hash = line.commit_id.to_string();
+                BlameLine {
+                    line_number: line.line_number,
+                    short_hash: hash.chars().take(8).collect(),
+                    hash,
+                    author: line.author,
+                    author_email: line.author_email,
+                    date: format_blame_timestamp(line.timestamp),
+                    timestamp: line.timestamp,
+                    content: line.content,
+                    copied_from: line
+                        .copied
+                        .then(|| line.path.to_string_lossy().replace('\\', "/")),
+                }
+            })
+            .collect(),
+    })
+}
+
+/// Attribute each of `lines` (the content of `path` at `commit_id`) to the
+/// oldest commit that still contains it.
+///
+/// Functional scope:
+/// - Initialises one [`LineBlame`] per line, blaming everything to the start
+///   commit, then BFS-walks parents. For each `Equal` chunk in the diff to a
+///   parent, lines whose content still matches inherit the parent's commit
+///   id, author, and timestamp.
+///
+/// Boundary conditions:
+/// - Failed parent loads (e.g. shallow clone boundary) and parents without
+///   the file are silently skipped so blame still produces a partial answer.
+pub(crate) fn walk_history(
+    path: &Path,
+    commit_id: ObjectHash,
+    commit_obj: Commit,
+    lines: Vec<String>,
+    ignore_whitespace: bool,
+) -> Vec<LineBlame> {
+    let mut blame_lines: Vec<LineBlame> = lines
         .iter()
         .enumerate()
         .map(|(idx, content)| LineBlame {
@@ -375,6 +502,8 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
             author_email: commit_obj.author.email.clone(),
             timestamp: commit_obj.author.timestamp as i64,
             content: content.clone(),
+            path: path.to_path_buf(),
+            copied: false,
         })
         .collect();
 
@@ -388,9 +517,9 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
     // are positions in the *current* commit, so they must be remapped through
     // this table to reach the right `blame_lines` slot — a direct `new_line - 1`
     // index is wrong once an intervening commit inserts or deletes lines above.
-    let init_map: Vec<Option<usize>> = (0..target_lines.len()).map(Some).collect();
+    let init_map: Vec<Option<usize>> = (0..lines.len()).map(Some).collect();
     let mut queue: VecDeque<WalkFrame> = VecDeque::new();
-    queue.push_back((commit_id, commit_obj, target_lines, init_map));
+    queue.push_back((commit_id, commit_obj, lines, init_map));
 
     while let Some((current_id, current_commit, current_lines, cur_to_final)) = queue.pop_front() {
         if !blame_lines.iter().any(|b| b.commit_id == current_id) {
@@ -403,9 +532,8 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
                 Err(_) => continue,
             };
 
-            let parent_revision = parent_id.to_string();
-            let parent_lines = match get_file_lines(&parent_commit, &args.file, &parent_revision) {
-                Ok(lines) if !lines.is_empty() => lines,
+            let parent_lines = match get_file_lines(&parent_commit, path) {
+                Ok(Some(lines)) if !lines.is_empty() => lines,
                 _ => continue,
             };
 
@@ -417,7 +545,7 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
             // differs only in whitespace is treated as unchanged and attributed
             // to the parent. The default path diffs the borrowed line vectors
             // directly (no copy); the original lines are always kept for display.
-            let operations = if args.ignore_whitespace {
+            let operations = if ignore_whitespace {
                 let diff_parent: Vec<String> = parent_lines
                     .iter()
                     .map(|l| normalize_for_whitespace(l))
@@ -451,7 +579,7 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
                             // whitespace-only differences. The default path compares
                             // borrowed strings directly (no allocation per line).
                             let parent_line = parent_lines.get(old_line - 1);
-                            let is_match = if args.ignore_whitespace {
+                            let is_match = if ignore_whitespace {
                                 let blame_key = normalize_for_whitespace(&blame.content);
                                 parent_line.map(|l| normalize_for_whitespace(l)) == Some(blame_key)
                             } else {
@@ -471,50 +599,20 @@ async fn run_blame(args: &BlameArgs) -> Result<BlameOutput, BlameError> {
         }
     }
 
-    let filtered_lines = if let Some(ref range) = args.line_range {
-        let (start, end) =
-            parse_line_range(range, &blame_lines).map_err(BlameError::InvalidLineRange)?;
-        blame_lines
-            .into_iter()
-            .filter(|b| b.line_number >= start && b.line_number <= end)
-            .collect::<Vec<_>>()
-    } else {
-        blame_lines
-    };
-
-    Ok(BlameOutput {
-        file: args.file.clone(),
-        revision: commit_id.to_string(),
-        lines: filtered_lines
-            .into_iter()
-            .map(|line| {
-                let hash = line.commit_id.to_string();
-                BlameLine {
-                    line_number: line.line_number,
-                    short_hash: hash.chars().take(8).collect(),
-                    hash,
-                    author: line.author,
-                    author_email: line.author_email,
-                    date: format_blame_timestamp(line.timestamp),
-                    timestamp: line.timestamp,
-                    content: line.content,
-                }
-            })
-            .collect(),
-    })
+    blame_lines
 }
-/// Read `file_path` at `commit` and return its lines (without trailing
-/// newlines).
+
+/// Read the workdir-relative `path` at `commit` and return its lines
+/// (without trailing newlines), or `None` when the commit's tree has no such
+/// file.
 ///
 /// Boundary conditions:
-/// - Returns [`BlameError::FileNotFound`] if the path is absent in the tree.
 /// - Non-UTF-8 blobs are decoded with `from_utf8_lossy`, replacing invalid
 ///   sequences with U+FFFD.
-fn get_file_lines(
+pub(crate) fn get_file_lines(
     commit: &Commit,
-    file_path: &str,
-    revision: &str,
-) -> Result<Vec<String>, BlameError> {
+    path: &Path,
+) -> Result<Option<Vec<String>>, BlameError> {
     let tree = load_object::<Tree>(&commit.tree_id).map_err(|e| BlameError::ObjectLoad {
         kind: "tree",
         object_id: commit.tree_id.to_string(),
@@ -522,17 +620,14 @@ fn get_file_lines(
     })?;
 
     let plain_items = tree.get_plain_items();
-    let target_path = util::to_workdir_path(file_path);
-
-    let blob_hash = plain_items
-        .iter()
-        .find(|(path, _)| path == &target_path)
-        .map(|(_, hash)| hash)
-        .ok_or_else(|| BlameError::FileNotFound {
-            path: file_path.to_string(),
-            revision: revision.to_string(),
-        })?;
+    let Some((_, blob_hash)) = plain_items.iter().find(|(item, _)| item == path) else {
+        return Ok(None);
+    };
+    read_blob_lines(blob_hash).map(Some)
+}
 
+/// Load a blob and split it into lines (lossy UTF-8, no trailing newlines).
+pub(crate) fn read_blob_lines(blob_hash: &ObjectHash) -> Result<Vec<String>, BlameError> {
     let blob = load_object::<Blob>(blob_hash).map_err(|e| BlameError::ObjectLoad {
         kind: "blob",
         object_id: blob_hash.to_string(),
@@ -605,7 +700,8 @@ fn render_blame_porcelain(result: &BlameOutput, file: &str, line_porcelain: bool
             buf.push_str(&format!("committer-time {}\n", commit.committer.timestamp));
             buf.push_str(&format!("committer-tz {}\n", commit.committer.timezone));
             buf.push_str(&format!("summary {summary}\n"));
-            buf.push_str(&format!("filename {file}\n"));
+            let filename = line.copied_from.as_deref().unwrap_or(file);
+            buf.push_str(&format!("filename {filename}\n"));
         }
         buf.push_str(&format!("\t{}\n", line.content));
     }
@@ -0,0 +1,240 @@
+//! Copy detection for `libra blame -C`.
+//!
+//! [`walk_history`] only follows a line while it stays in the blamed file, so
+//! a line pasted in from elsewhere is blamed on the commit that pasted it.
+//! [`detect_copies`] takes those lines — grouped by the commit they are
+//! blamed on — and looks for them in other files of that commit's first
+//! parent. Each run of consecutive lines whose longest match in a candidate
+//! file scores at least the threshold (alphanumeric characters, as Git's
+//! `-C<num>`) takes the attribution of the matching lines in that file, which
+//! is itself blamed (and, recursively, copy-detected) at the parent.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use git_internal::{
+    hash::ObjectHash,
+    internal::object::{commit::Commit, tree::Tree},
+};
+
+use super::blame::{LineBlame, normalize_for_whitespace, read_blob_lines, walk_history};
+use crate::{command::load_object, utils::object_ext::TreeExt};
+
+/// Default minimum score (alphanumeric characters) for a copied block.
+pub(crate) const DEFAULT_COPY_SCORE: usize = 40;
+
+/// How many files deep a copy of a copy is followed.
+const MAX_COPY_DEPTH: usize = 8;
+
+/// Settings derived from `-C`, `--copy-threshold`, and `-w`.
+pub(crate) struct CopyOptions {
+    /// Number of `-C` flags given (1 to 3; higher counts behave like 3).
+    pub(crate) level: u8,
+    pub(crate) threshold: usize,
+    pub(crate) ignore_whitespace: bool,
+}
+
+/// A candidate source: a file of the parent commit and its blame, computed
+/// only when one of its lines is first matched.
+struct Source {
+    path: PathBuf,
+    lines: Vec<String>,
+    blame: Option<Vec<LineBlame>>,
+}
+
+/// Re-attribute the lines of `path` (its blame, possibly `-L`-filtered) that
+/// were copied from other files. Matched lines take the commit, author, and
+/// path of their origin and are flagged `copied`.
+pub(crate) fn detect_copies(lines: &mut [LineBlame], path: &Path, options: &CopyOptions) {
+    detect_copies_at_depth(lines, path, options, 0);
+}
+
+fn detect_copies_at_depth(
+    lines: &mut [LineBlame],
+    path: &Path,
+    options: &CopyOptions,
+    depth: usize,
+) {
+    if depth >= MAX_COPY_DEPTH {
+        return;
+    }
+    let commits: HashSet<ObjectHash> = lines.iter().map(|line| line.commit_id).collect();
+    for commit_id in commits {
+        let Some((parent_id, parent, mut sources)) =
+            candidate_sources(&commit_id, path, options.level)
+        else {
+            continue;
+        };
+        if sources.is_empty() {
+            continue;
+        }
+
+        let key = |line: &str| {
+            if options.ignore_whitespace {
+                normalize_for_whitespace(line)
+            } else {
+                line.to_string()
+            }
+        };
+        let source_keys: Vec<Vec<String>> = sources
+            .iter()
+            .map(|source| source.lines.iter().map(|line| key(line)).collect())
+            .collect();
+
+        let mut pending = blocks_of(lines, &commit_id);
+        while let Some(block) = pending.pop() {
+            let target_keys: Vec<String> = block.iter().map(|&i| key(&lines[i].content)).collect();
+            let best = source_keys
+                .iter()
+                .enumerate()
+                .filter_map(|(source, keys)| {
+                    let (at, from, len) = longest_common_run(&target_keys, keys)?;
+                    let score = target_keys[at..at + len]
+                        .iter()
+                        .map(|line| alnum_score(line))
+                        .sum::<usize>();
+                    Some((score, source, at, from, len))
+                })
+                .max_by_key(|&(score, ..)| score);
+            let Some((score, source, at, from, len)) = best else {
+                continue;
+            };
+            if score < options.threshold {
+                continue;
+            }
+
+            let source = &mut sources[source];
+            let origin = source.blame.get_or_insert_with(|| {
+                let mut blame = walk_history(
+                    &source.path,
+                    parent_id,
+                    parent.clone(),
+                    source.lines.clone(),
+                    options.ignore_whitespace,
+                );
+                detect_copies_at_depth(&mut blame, &source.path, options, depth + 1);
+                blame
+            });
+            for offset in 0..len {
+                let line = &mut lines[block[at + offset]];
+                let copied = &origin[from + offset];
+                line.commit_id = copied.commit_id;
+                line.author = copied.author.clone();
+                line.author_email = copied.author_email.clone();
+                line.timestamp = copied.timestamp;
+                line.path = copied.path.clone();
+                line.copied = true;
+            }
+            pending.push(block[..at].to_vec());
+            pending.push(block[at + len..].to_vec());
+        }
+    }
+}
+
+/// Files of `commit_id`'s first parent that may have been copied into
+/// `path`, per the `-C` level. Returns `None` for root commits and commits
+/// that fail to load.
+fn candidate_sources(
+    commit_id: &ObjectHash,
+    path: &Path,
+    level: u8,
+) -> Option<(ObjectHash, Commit, Vec<Source>)> {
+    let commit = load_object::<Commit>(commit_id).ok()?;
+    let parent_id = *commit.parent_commit_ids.first()?;
+    let parent = load_object::<Commit>(&parent_id).ok()?;
+    let items = load_object::<Tree>(&commit.tree_id).ok()?.get_plain_items();
+    let parent_items = load_object::<Tree>(&parent.tree_id).ok()?.get_plain_items();
+
+    let current: HashMap<&PathBuf, &ObjectHash> =
+        items.iter().map(|(path, hash)| (path, hash)).collect();
+    let creates_target = !parent_items.iter().any(|(item, _)| item == path);
+    let search_all = level >= 3 || (level == 2 && creates_target);
+
+    let sources = parent_items
+        .iter()
+        .filter(|(item, _)| item != path)
+        .filter(|(item, hash)| search_all || current.get(item).is_some_and(|new| *new != hash))
+        .filter_map(|(item, hash)| {
+            Some(Source {
+                path: item.clone(),
+                lines: read_blob_lines(hash).ok()?,
+                blame: None,
+            })
+        })
+        .collect();
+    Some((parent_id, parent, sources))
+}
+
+/// Indices into `lines` of each run of consecutive line numbers blamed on
+/// `commit_id` and not already traced to a copy.
+fn blocks_of(lines: &[LineBlame], commit_id: &ObjectHash) -> Vec<Vec<usize>> {
+    let mut blocks: Vec<Vec<usize>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.commit_id != *commit_id || line.copied {
+            continue;
+        }
+        match blocks.last_mut() {
+            Some(block)
+                if block.last().is_some_and(|&prev| {
+                    prev + 1 == i && lines[prev].line_number + 1 == line.line_number
+                }) =>
+            {
+                block.push(i)
+            }
+            _ => blocks.push(vec![i]),
+        }
+    }
+    blocks
+}
+
+/// Longest run of equal lines shared by `target` and `source`, as
+/// `(target_start, source_start, len)`; the earliest in `target` wins ties.
+fn longest_common_run(target: &[String], source: &[String]) -> Option<(usize, usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None;
+    let mut previous = vec![0usize; source.len() + 1];
+    for (i, target_line) in target.iter().enumerate() {
+        let mut row = vec![0usize; source.len() + 1];
+        for (j, source_line) in source.iter().enumerate() {
+            if target_line == source_line {
+                row[j + 1] = previous[j] + 1;
+                let len = row[j + 1];
+                if best.is_none_or(|(.., best_len)| len > best_len) {
+                    best = Some((i + 1 - len, j + 1 - len, len));
+                }
+            }
+        }
+        previous = row;
+    }
+    best
+}
+
+/// Git scores blame copies by alphanumeric characters, so blank lines and
+/// punctuation (`}`, `});`) do not count towards the threshold.
+fn alnum_score(line: &str) -> usize {
+    line.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn longest_common_run_finds_earliest_longest_block() {
+        let target = lines(&["new", "a", "b", "c", "x", "a", "b"]);
+        let source = lines(&["z", "a", "b", "c", "y"]);
+        assert_eq!(longest_common_run(&target, &source), Some((1, 1, 3)));
+        assert_eq!(longest_common_run(&target, &lines(&["q"])), None);
+    }
+
+    #[test]
+    fn alnum_score_ignores_punctuation_and_whitespace() {
+        assert_eq!(alnum_score("    });"), 0);
+        assert_eq!(alnum_score("let x1 = y;"), 5);
+    }
+}
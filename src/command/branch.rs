@@ -10,7 +10,8 @@
 //!   `map_branch_store_error`.
 //! - For deletes, walks reachable commits from HEAD via
 //!   [`get_reachable_commits`] to detect "not fully merged" branches before
-//!   permitting deletion (skipped under `-D`).
+//!   permitting deletion (skipped under `-D`). `--merged`/`--no-merged`
+//!   share the same walk, so a branch listed by `--merged` is one `-d` accepts.
 //! - Suggests near-matches via Levenshtein distance when the user names a
 //!   missing branch.
 //! - For listing, supports `--contains` / `--no-contains` commit filters
@@ -24,7 +25,7 @@ use std::{
 use clap::{ArgGroup, Parser};
 use colored::Colorize;
 use git_internal::{hash::ObjectHash, internal::object::commit::Commit};
-use sea_orm::{ConnectionTrait, DbErr};
+use sea_orm::{ConnectionTrait, DbErr, TransactionError, TransactionTrait};
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -37,7 +38,9 @@ use crate::{
         config::ConfigKv,
         db::get_db_conn_instance,
         head::Head,
+        merge_base,
         operation_wrapper::{OperationMeta, OperationScope, with_operation_log},
+        reflog::Reflog,
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
@@ -230,10 +233,16 @@ pub struct BranchArgs {
     pub show_current: bool,
 
     /// Rename a branch. With one argument, renames the current branch. With two arguments, renames OLD_BRANCH to NEW_BRANCH.
+    /// The branch's reflog and upstream config move with it. Fails if the
+    /// destination already exists (use -M to overwrite).
     #[clap(short = 'm', long = "move", group = "action", value_names = ["OLD_BRANCH", "NEW_BRANCH"], num_args = 1..=2)]
     pub rename: Vec<String>,
 
-    /// Copy a branch (and its upstream config) to a new name, keeping the
+    /// Like -m, but overwrite the destination branch if it already exists.
+    #[clap(short = 'M', long = "move-force", group = "action", value_names = ["OLD_BRANCH", "NEW_BRANCH"], num_args = 1..=2)]
+    pub rename_force: Vec<String>,
+
+    /// Copy a branch (and its upstream config and reflog) to a new name, keeping the
     /// original. With one argument, copies the current branch. Fails if the
     /// destination already exists (use -C to overwrite).
     #[clap(short = 'c', long = "copy", group = "action", value_names = ["OLD_BRANCH", "NEW_BRANCH"], num_args = 1..=2)]
@@ -381,6 +390,9 @@ enum BranchError {
     #[error("cannot force-copy onto the currently checked-out branch '{0}'")]
     CopyOntoCurrentBranch(String),
 
+    #[error("cannot force-rename onto the currently checked-out branch '{0}'")]
+    MoveOntoCurrentBranch(String),
+
     #[error("not a valid object name: '{0}'")]
     InvalidCommit(String),
 
@@ -479,6 +491,11 @@ impl From<BranchError> for CliError {
             ))
             .with_stable_code(StableErrorCode::RepoStateInvalid)
             .with_hint("switch to a different branch first, or copy to a new name"),
+            BranchError::MoveOntoCurrentBranch(name) => CliError::fatal(format!(
+                "cannot force-rename onto the currently checked-out branch '{name}'"
+            ))
+            .with_stable_code(StableErrorCode::RepoStateInvalid)
+            .with_hint("switch to a different branch first, or rename to a new name"),
             BranchError::InvalidCommit(target) => {
                 CliError::fatal(format!("not a valid object name: '{target}'"))
                     .with_stable_code(StableErrorCode::CliInvalidTarget)
@@ -1021,9 +1038,9 @@ async fn create_branch_impl(
 ///
 /// Functional scope:
 /// - Refuses to delete a locked branch or the currently checked-out branch.
-/// - When `force == false`, walks `get_reachable_commits` from HEAD and
-///   ensures the branch tip is reachable; otherwise reports
-///   [`BranchError::NotFullyMerged`] (recoverable failure, exit code stays
+/// - When `force == false`, ensures the branch tip is in
+///   `reachable_commit_set(HEAD)` (the same check `--merged` uses); otherwise
+///   reports [`BranchError::NotFullyMerged`] (recoverable failure, exit code stays
 ///   non-fatal).
 ///
 /// Boundary conditions:
@@ -1055,12 +1072,10 @@ async fn delete_branch_impl(branch_name: String, force: bool) -> Result<BranchOu
             Head::Detached(commit_hash) => commit_hash,
         };
 
-        let head_reachable = get_reachable_commits(head_commit.to_string(), None)
-            .await
-            .map_err(BranchError::DelegatedCli)?;
-        let head_commit_ids: std::collections::HashSet<_> =
-            head_reachable.iter().map(|c| c.id).collect();
-        if !head_commit_ids.contains(&branch.commit) {
+        if !reachable_commit_set(head_commit)
+            .await?
+            .contains(&branch.commit)
+        {
             return Err(BranchError::NotFullyMerged(branch_name));
         }
     }
@@ -1076,19 +1091,22 @@ async fn delete_branch_impl(branch_name: String, force: bool) -> Result<BranchOu
     })
 }
 
-/// Body of `libra branch -m [old] new`.
+/// Body of `libra branch -m [old] new` / `libra branch -M [old] new`.
 ///
 /// Functional scope:
 /// - One argument: rename the current branch (errors on detached HEAD).
 /// - Two arguments: rename the named source branch.
-/// - When the rename touches the checked-out branch, HEAD is updated to
-///   point at the new name before deleting the old row.
+/// - The ref, HEAD (when it points at the renamed branch), the
+///   `branch.<name>.*` config keys, branch metadata and the
+///   `refs/heads/<name>` reflog all move inside a single transaction.
 ///
 /// Boundary conditions:
 /// - Returns [`BranchError::RenameTooManyArgs`] for argv with >2 names.
 /// - Returns [`BranchError::AlreadyExists`] if the destination already
-///   exists; the rename is non-destructive.
-async fn rename_branch_impl(args: &[String]) -> Result<BranchOutput, BranchError> {
+///   exists and `force` (`-M`) is not set.
+/// - Even with `-M`, refuses to overwrite the checked-out branch with a
+///   different branch ([`BranchError::MoveOntoCurrentBranch`]).
+async fn rename_branch_impl(args: &[String], force: bool) -> Result<BranchOutput, BranchError> {
     let (old_name, new_name) = match args.len() {
         1 => match Head::current().await {
             Head::Branch(name) => (name, args[0].clone()),
@@ -1109,57 +1127,110 @@ async fn rename_branch_impl(args: &[String]) -> Result<BranchOutput, BranchError
     }
 
     let old_branch = require_existing_local_branch(&old_name).await?;
-    if Branch::find_branch_result(&new_name, None)
+    let destination_exists = Branch::find_branch_result(&new_name, None)
         .await
         .map_err(map_branch_store_error)?
-        .is_some()
-    {
-        return Err(BranchError::AlreadyExists(new_name));
+        .is_some();
+    if destination_exists && old_name != new_name {
+        if !force {
+            return Err(BranchError::AlreadyExists(new_name));
+        }
+        if let Head::Branch(current) = Head::current().await
+            && current == new_name
+        {
+            return Err(BranchError::MoveOntoCurrentBranch(new_name));
+        }
+    }
+    if old_name == new_name {
+        // `git branch -m x x` is a successful no-op.
+        return Ok(BranchOutput::Rename { old_name, new_name });
     }
 
     let commit_hash = old_branch.commit.to_string();
-    Branch::update_branch(&new_name, &commit_hash, None)
-        .await
-        .map_err(|e| BranchError::CreateFailed {
-            branch: new_name.clone(),
-            detail: e.to_string(),
-        })?;
+    let db = get_db_conn_instance().await;
+    let (txn_old, txn_new) = (old_name.clone(), new_name.clone());
+    db.transaction::<_, (), BranchError>(move |txn| {
+        Box::pin(async move {
+            let (old_name, new_name) = (txn_old, txn_new);
+            Branch::update_branch_with_conn(txn, &new_name, &commit_hash, None)
+                .await
+                .map_err(|e| BranchError::CreateFailed {
+                    branch: new_name.clone(),
+                    detail: e.to_string(),
+                })?;
 
-    if let Head::Branch(name) = Head::current().await
-        && name == old_name
-    {
-        Head::update(Head::Branch(new_name.clone()), None).await;
-    }
+            if let Head::Branch(name) = Head::current_with_conn(txn).await
+                && name == old_name
+            {
+                Head::update_result_with_conn(txn, Head::Branch(new_name.clone()), None)
+                    .await
+                    .map_err(map_branch_store_error)?;
+            }
 
-    // Move branch metadata (lore.md §1.5) BEFORE deleting the old ref — the
-    // delete's metadata cascade would otherwise wipe the rows being moved.
-    // The destination has no rows (rename onto an existing branch is refused
-    // above), so the defensive dest-clear inside rename_target is a no-op.
-    {
-        let db = get_db_conn_instance().await;
-        crate::internal::metadata::MetadataKv::rename_target_with_conn(
-            &db,
-            crate::internal::metadata::MetadataScope::Branch,
-            &old_name,
-            &new_name,
-        )
-        .await
-        .map_err(|e| branch_config_write_error("branch metadata", e))?;
-    }
+            // Move upstream/description config. Destination keys left over from
+            // a branch overwritten by -M are cleared first.
+            for suffix in ["remote", "merge", "description"] {
+                let src_key = format!("branch.{old_name}.{suffix}");
+                let dst_key = format!("branch.{new_name}.{suffix}");
+                ConfigKv::unset_all_with_conn(txn, &dst_key)
+                    .await
+                    .map_err(|e| branch_config_write_error(&dst_key, e))?;
+                if let Some(entry) = ConfigKv::get_with_conn(txn, &src_key)
+                    .await
+                    .map_err(|e| branch_config_read_error(format!("config '{src_key}'"), e))?
+                {
+                    ConfigKv::set_with_conn(txn, &dst_key, &entry.value, false)
+                        .await
+                        .map_err(|e| branch_config_write_error(&dst_key, e))?;
+                    ConfigKv::unset_all_with_conn(txn, &src_key)
+                        .await
+                        .map_err(|e| branch_config_write_error(&src_key, e))?;
+                }
+            }
 
-    Branch::delete_branch_result(&old_name, None)
-        .await
-        .map_err(map_branch_store_error)?;
+            // Move branch metadata (lore.md §1.5) BEFORE deleting the old ref —
+            // the delete's metadata cascade would otherwise wipe the rows being
+            // moved. rename_target clears any destination rows first, which is
+            // what a forced rename wants.
+            crate::internal::metadata::MetadataKv::rename_target_with_conn(
+                txn,
+                crate::internal::metadata::MetadataScope::Branch,
+                &old_name,
+                &new_name,
+            )
+            .await
+            .map_err(|e| branch_config_write_error("branch metadata", e))?;
+
+            Reflog::rename_ref_with_conn(
+                txn,
+                &format!("refs/heads/{old_name}"),
+                &format!("refs/heads/{new_name}"),
+            )
+            .await
+            .map_err(|e| branch_config_write_error("branch reflog", e))?;
+
+            Branch::delete_branch_result_with_conn(txn, &old_name, None)
+                .await
+                .map_err(map_branch_store_error)?;
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| match e {
+        TransactionError::Connection(err) => BranchError::StorageQueryFailed(err.to_string()),
+        TransactionError::Transaction(err) => err,
+    })?;
 
     Ok(BranchOutput::Rename { old_name, new_name })
 }
 
 /// Copy a branch to a new name, keeping the original (`git branch -c`/`-C`).
 /// With one argument the current branch is the source. The new branch is
-/// created at the source's commit and the source's upstream config
-/// (`branch.<old>.remote`/`.merge`) is copied. `force` (`-C`) overwrites an
-/// existing destination; otherwise a clashing destination is an error. HEAD is
-/// never moved (the source remains intact).
+/// created at the source's commit and the source's `branch.<old>.*` config,
+/// branch metadata and reflog are copied inside a single transaction. `force`
+/// (`-C`) overwrites an existing destination, replacing its config; otherwise a
+/// clashing destination is an error. HEAD is never moved (the source remains
+/// intact).
 async fn copy_branch_impl(args: &[String], force: bool) -> Result<BranchOutput, BranchError> {
     let (old_name, new_name) = match args.len() {
         1 => match Head::current().await {
@@ -1197,41 +1268,68 @@ async fn copy_branch_impl(args: &[String], force: bool) -> Result<BranchOutput,
     }
 
     let commit_hash = old_branch.commit.to_string();
-    Branch::update_branch(&new_name, &commit_hash, None)
-        .await
-        .map_err(|e| BranchError::CreateFailed {
-            branch: new_name.clone(),
-            detail: e.to_string(),
-        })?;
-
-    // Copy the source branch's upstream configuration, if any, to the new
-    // branch (mirroring `git branch -c`). The raw stored values are copied
-    // verbatim so the `refs/heads/` prefix on `branch.<old>.merge` is preserved.
     let db = get_db_conn_instance().await;
-    for suffix in ["remote", "merge"] {
-        let src_key = format!("branch.{old_name}.{suffix}");
-        if let Some(entry) = ConfigKv::get_with_conn(&db, &src_key)
-            .await
-            .map_err(|e| branch_config_read_error(format!("config '{src_key}'"), e))?
-        {
-            let dst_key = format!("branch.{new_name}.{suffix}");
-            ConfigKv::set_with_conn(&db, &dst_key, &entry.value, false)
+    let (txn_old, txn_new) = (old_name.clone(), new_name.clone());
+    db.transaction::<_, (), BranchError>(move |txn| {
+        Box::pin(async move {
+            let (old_name, new_name) = (txn_old, txn_new);
+            Branch::update_branch_with_conn(txn, &new_name, &commit_hash, None)
                 .await
-                .map_err(|e| branch_config_write_error(&dst_key, e))?;
-        }
-    }
+                .map_err(|e| BranchError::CreateFailed {
+                    branch: new_name.clone(),
+                    detail: e.to_string(),
+                })?;
+
+            // Copy the source branch's upstream/description config (mirroring
+            // `git branch -c`). Destination keys left over from a branch
+            // overwritten by -C are cleared first, so the copy never inherits
+            // a stale upstream. The raw stored values are copied verbatim so
+            // the `refs/heads/` prefix on `branch.<old>.merge` is preserved.
+            for suffix in ["remote", "merge", "description"] {
+                let src_key = format!("branch.{old_name}.{suffix}");
+                let dst_key = format!("branch.{new_name}.{suffix}");
+                ConfigKv::unset_all_with_conn(txn, &dst_key)
+                    .await
+                    .map_err(|e| branch_config_write_error(&dst_key, e))?;
+                if let Some(entry) = ConfigKv::get_with_conn(txn, &src_key)
+                    .await
+                    .map_err(|e| branch_config_read_error(format!("config '{src_key}'"), e))?
+                {
+                    ConfigKv::set_with_conn(txn, &dst_key, &entry.value, false)
+                        .await
+                        .map_err(|e| branch_config_write_error(&dst_key, e))?;
+                }
+            }
 
-    // Copy branch metadata (lore.md §1.5). A forced copy (-C) replaces any
-    // metadata the overwritten destination carried — destructive by design,
-    // matching the ref overwrite itself.
-    crate::internal::metadata::MetadataKv::copy_target_with_conn(
-        &db,
-        crate::internal::metadata::MetadataScope::Branch,
-        &old_name,
-        &new_name,
-    )
+            // Copy branch metadata (lore.md §1.5). A forced copy (-C) replaces
+            // any metadata the overwritten destination carried — destructive by
+            // design, matching the ref overwrite itself.
+            crate::internal::metadata::MetadataKv::copy_target_with_conn(
+                txn,
+                crate::internal::metadata::MetadataScope::Branch,
+                &old_name,
+                &new_name,
+            )
+            .await
+            .map_err(|e| branch_config_write_error("branch metadata", e))?;
+
+            // Copy the reflog too, so `libra reflog <new>` shows the history
+            // the source branch accumulated (mirroring `git branch -c`).
+            Reflog::copy_ref_with_conn(
+                txn,
+                &format!("refs/heads/{old_name}"),
+                &format!("refs/heads/{new_name}"),
+            )
+            .await
+            .map_err(|e| branch_config_write_error("branch reflog", e))?;
+            Ok(())
+        })
+    })
     .await
-    .map_err(|e| branch_config_write_error("branch metadata", e))?;
+    .map_err(|e| match e {
+        TransactionError::Connection(err) => BranchError::StorageQueryFailed(err.to_string()),
+        TransactionError::Transaction(err) => err,
+    })?;
 
     Ok(BranchOutput::Copy { old_name, new_name })
 }
@@ -1425,7 +1523,9 @@ async fn run_branch(args: &BranchArgs) -> Result<BranchOutput, BranchError> {
         let set = edit_description_impl(&branch).await?;
         Ok(BranchOutput::EditDescription { branch, set })
     } else if !args.rename.is_empty() {
-        rename_branch_impl(&args.rename).await
+        rename_branch_impl(&args.rename, false).await
+    } else if !args.rename_force.is_empty() {
+        rename_branch_impl(&args.rename_force, true).await
     } else if !args.copy.is_empty() {
         copy_branch_impl(&args.copy, false).await
     } else if !args.copy_force.is_empty() {
@@ -1522,7 +1622,7 @@ async fn branch_upstream_segment(branch_name: &str, branch_commit: &str) -> Opti
         Ok(upstream_commit) => branch_commit
             .parse::<ObjectHash>()
             .ok()
-            .map(|local| super::status::compute_ahead_behind(&local, &upstream_commit)),
+            .and_then(|local| merge_base::ahead_behind(&local, &upstream_commit).ok()),
         Err(_) => None,
     };
     let segment = match counts {
@@ -1819,6 +1919,7 @@ pub async fn list_branches(
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: matches!(list_mode, BranchListMode::Remote),
@@ -2054,10 +2155,17 @@ async fn resolve_reachable_for_merge(
     let target = get_target_commit(spec)
         .await
         .map_err(|_| BranchError::InvalidCommit(spec.to_string()))?;
-    let reachable = get_reachable_commits(target.to_string(), None)
+    reachable_commit_set(target).await.map(Some)
+}
+
+/// The history of `commit` (itself included) as a set. Shared by
+/// `--merged`/`--no-merged` and the `-d` merged-check so both agree on what
+/// "fully merged" means.
+async fn reachable_commit_set(commit: ObjectHash) -> Result<HashSet<ObjectHash>, BranchError> {
+    let reachable = get_reachable_commits(commit.to_string(), None)
         .await
         .map_err(BranchError::DelegatedCli)?;
-    Ok(Some(reachable.iter().map(|c| c.id).collect()))
+    Ok(reachable.iter().map(|c| c.id).collect())
 }
 
 /// check if a branch contains at least one of the commits
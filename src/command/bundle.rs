@@ -10,9 +10,10 @@
 //! PACK……                    (a v2 pack of every object reachable from the tips)
 //! ```
 //!
-//! This version writes full (non-thin, no-prerequisite) bundles and can
-//! `verify` / `list-heads` any v2 bundle. Prerequisite (incremental) bundles,
-//! `unbundle`, and rev-range arguments are deferred.
+//! This version writes full (non-thin, no-prerequisite) bundles, can
+//! `verify` / `list-heads` any v2 bundle, and `unbundle` one into the object
+//! store. Prerequisite (incremental) bundles and rev-range arguments are
+//! deferred.
 
 use std::{
     collections::HashSet,
@@ -24,7 +25,7 @@ use std::{
 
 use clap::{Parser, Subcommand};
 use git_internal::{
-    hash::{ObjectHash, get_hash_kind, set_hash_kind},
+    hash::{HashKind, ObjectHash, get_hash_kind, set_hash_kind},
     internal::{
         metadata::{EntryMeta, MetaAttached},
         object::{
@@ -37,12 +38,16 @@ use git_internal::{
 use tokio::sync::mpsc;
 
 use crate::{
-    command::load_object,
-    internal::head::Head,
+    command::{index_pack, load_object},
+    internal::{
+        branch::Branch,
+        head::Head,
+        tag::{self, TagObject},
+    },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         output::OutputConfig,
-        util,
+        path, util,
     },
 };
 
@@ -52,8 +57,10 @@ pub const BUNDLE_EXAMPLES: &str = "\
 EXAMPLES:
     libra bundle create repo.bundle main      Bundle everything reachable from main
     libra bundle create all.bundle HEAD       Bundle the current branch
+    libra bundle create full.bundle --all     Bundle every local branch and tag
     libra bundle verify repo.bundle           Check a bundle's header and pack
-    libra bundle list-heads repo.bundle       List the refs a bundle carries";
+    libra bundle list-heads repo.bundle       List the refs a bundle carries
+    libra bundle unbundle repo.bundle         Store a bundle's objects and print its refs";
 
 /// Create and inspect Git v2 bundle files.
 #[derive(Parser, Debug)]
@@ -71,8 +78,11 @@ pub enum BundleSubcommand {
         #[clap(value_name = "FILE")]
         file: PathBuf,
         /// Revisions whose reachable history to include (each becomes a head).
-        #[clap(value_name = "REV", required = true)]
+        #[clap(value_name = "REV", required_unless_present = "all")]
         revs: Vec<String>,
+        /// Include every local branch and tag instead of naming revisions.
+        #[clap(long, conflicts_with = "revs")]
+        all: bool,
     },
     /// Check that a bundle's header is well-formed and its pack is present.
     Verify {
@@ -84,6 +94,12 @@ pub enum BundleSubcommand {
         #[clap(value_name = "FILE")]
         file: PathBuf,
     },
+    /// Store a bundle's objects in this repository and print its head lines.
+    /// Refs are not changed; point them at the printed ids with `update-ref`.
+    Unbundle {
+        #[clap(value_name = "FILE")]
+        file: PathBuf,
+    },
 }
 
 pub async fn execute(args: BundleArgs) {
@@ -96,9 +112,10 @@ pub async fn execute(args: BundleArgs) {
 pub async fn execute_safe(args: BundleArgs, _output: &OutputConfig) -> CliResult<()> {
     util::require_repo().map_err(|_| CliError::repo_not_found())?;
     match args.command {
-        BundleSubcommand::Create { file, revs } => create(&file, &revs).await,
+        BundleSubcommand::Create { file, revs, all } => create(&file, &revs, all).await,
         BundleSubcommand::Verify { file } => verify(&file),
         BundleSubcommand::ListHeads { file } => list_heads(&file),
+        BundleSubcommand::Unbundle { file } => unbundle(&file),
     }
 }
 
@@ -106,22 +123,41 @@ pub async fn execute_safe(args: BundleArgs, _output: &OutputConfig) -> CliResult
 // create
 // ----------------------------------------------------------------------------
 
-async fn create(file: &Path, revs: &[String]) -> CliResult<()> {
-    // Resolve each rev to (tip oid, ref name) — these become the bundle heads.
-    let mut heads: Vec<(ObjectHash, String)> = Vec::new();
-    for rev in revs {
-        let tip = util::get_commit_base(rev).await.map_err(|error| {
-            CliError::fatal(format!("not a valid revision '{rev}': {error}"))
-                .with_exit_code(128)
-                .with_stable_code(StableErrorCode::CliInvalidTarget)
-        })?;
-        heads.push((tip, resolve_ref_name(rev).await));
-    }
+/// One header line of a bundle being created.
+struct BundleHead {
+    /// The object the ref points at (an annotated tag's own id for tags).
+    oid: ObjectHash,
+    name: String,
+    /// The commit whose history the pack must carry.
+    tip: ObjectHash,
+}
 
-    // Collect every object reachable from the tips (deduplicated).
+async fn create(file: &Path, revs: &[String], all: bool) -> CliResult<()> {
+    // Annotated tag objects named by `--all` travel in the pack too.
     let mut seen: HashSet<ObjectHash> = HashSet::new();
     let mut entries: Vec<Entry> = Vec::new();
-    for (tip, _) in &heads {
+    let heads = if all {
+        all_local_heads(&mut seen, &mut entries).await?
+    } else {
+        // Resolve each rev to (tip oid, ref name) — these become the bundle heads.
+        let mut heads = Vec::new();
+        for rev in revs {
+            let tip = util::get_commit_base(rev).await.map_err(|error| {
+                CliError::fatal(format!("not a valid revision '{rev}': {error}"))
+                    .with_exit_code(128)
+                    .with_stable_code(StableErrorCode::CliInvalidTarget)
+            })?;
+            heads.push(BundleHead {
+                oid: tip,
+                name: resolve_ref_name(rev).await,
+                tip,
+            });
+        }
+        heads
+    };
+
+    // Collect every object reachable from the tips (deduplicated).
+    for BundleHead { tip, .. } in &heads {
         let commits = crate::command::log::get_reachable_commits(tip.to_string(), None)
             .await
             .map_err(|error| error.with_exit_code(128))?;
@@ -165,7 +201,7 @@ async fn create(file: &Path, revs: &[String]) -> CliResult<()> {
     let write_result = (|| -> std::io::Result<()> {
         let mut out = std::io::BufWriter::new(fs::File::create(&tmp)?);
         writeln!(out, "{BUNDLE_SIGNATURE_V2}")?;
-        for (oid, name) in &heads {
+        for BundleHead { oid, name, .. } in &heads {
             writeln!(out, "{oid} {name}")?;
         }
         out.write_all(b"\n")?;
@@ -189,6 +225,70 @@ async fn create(file: &Path, revs: &[String]) -> CliResult<()> {
     Ok(())
 }
 
+/// Every local branch and tag as a bundle head, sorted by ref name. Annotated
+/// tag objects are added to `entries`; tags that do not lead to a commit are
+/// skipped with a warning.
+async fn all_local_heads(
+    seen: &mut HashSet<ObjectHash>,
+    entries: &mut Vec<Entry>,
+) -> CliResult<Vec<BundleHead>> {
+    let mut heads = Vec::new();
+    let branches = Branch::list_branches_result(None).await.map_err(|error| {
+        CliError::fatal(format!("failed to list branches: {error}"))
+            .with_exit_code(128)
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    })?;
+    for branch in branches {
+        heads.push(BundleHead {
+            oid: branch.commit,
+            name: format!("refs/heads/{}", branch.name),
+            tip: branch.commit,
+        });
+    }
+
+    let tags = tag::list().await.map_err(|error| {
+        CliError::fatal(format!("failed to list tags: {error}"))
+            .with_exit_code(128)
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    })?;
+    for tag in tags {
+        let name = format!("refs/tags/{}", tag.name);
+        match tag.object {
+            TagObject::Commit(commit) => heads.push(BundleHead {
+                oid: commit.id,
+                name,
+                tip: commit.id,
+            }),
+            TagObject::Tag(tag_object) => {
+                let Ok(Some((_, commit))) = tag::find_tag_and_commit(&tag.name).await else {
+                    eprintln!("warning: skipping {name}: it does not point at a commit");
+                    continue;
+                };
+                let tip = commit.id;
+                heads.push(BundleHead {
+                    oid: tag_object.id,
+                    name,
+                    tip,
+                });
+                if seen.insert(tag_object.id) {
+                    entries.push(tag_object.into());
+                }
+            }
+            TagObject::Tree(_) | TagObject::Blob(_) => {
+                eprintln!("warning: skipping {name}: it does not point at a commit");
+            }
+        }
+    }
+
+    heads.sort_by(|a, b| a.name.cmp(&b.name));
+    if heads.is_empty() {
+        return Err(CliError::fatal("no branches or tags to bundle".to_string())
+            .with_exit_code(128)
+            .with_stable_code(StableErrorCode::CliInvalidTarget));
+    }
+    Ok(heads)
+}
+
 /// Recursively add a tree and everything beneath it to the object set.
 fn collect_tree(
     tree_id: &ObjectHash,
@@ -278,8 +378,18 @@ struct BundleHeader {
 fn verify(file: &Path) -> CliResult<()> {
     let bytes = fs::read(file).map_err(read_err)?;
     let header = parse_header(&bytes)?;
+    check_prerequisites(&header)?;
+    check_pack_magic(&bytes[header.pack_offset..])?;
+
+    println!("{} is okay", file.display());
+    for (oid, name) in &header.heads {
+        println!("{oid} {name}");
+    }
+    Ok(())
+}
 
-    // Any prerequisite object must already exist locally.
+/// Any prerequisite object must already exist locally.
+fn check_prerequisites(header: &BundleHeader) -> CliResult<()> {
     let storage = util::objects_storage();
     let mut missing = Vec::new();
     for (oid, _) in &header.prerequisites {
@@ -296,9 +406,11 @@ fn verify(file: &Path) -> CliResult<()> {
         .with_exit_code(1)
         .with_stable_code(StableErrorCode::CliInvalidTarget));
     }
+    Ok(())
+}
 
-    // The pack must start with the v2 PACK magic.
-    let pack = &bytes[header.pack_offset..];
+/// The pack must start with the v2 PACK magic.
+fn check_pack_magic(pack: &[u8]) -> CliResult<()> {
     if pack.len() < 8 || &pack[0..4] != b"PACK" || pack[4..8] != [0, 0, 0, 2] {
         return Err(
             CliError::fatal("bundle pack is missing or not a version-2 pack".to_string())
@@ -306,17 +418,69 @@ fn verify(file: &Path) -> CliResult<()> {
                 .with_stable_code(StableErrorCode::CliInvalidTarget),
         );
     }
+    Ok(())
+}
 
-    println!("{} is okay", file.display());
+fn list_heads(file: &Path) -> CliResult<()> {
+    let bytes = fs::read(file).map_err(read_err)?;
+    let header = parse_header(&bytes)?;
     for (oid, name) in &header.heads {
         println!("{oid} {name}");
     }
     Ok(())
 }
 
-fn list_heads(file: &Path) -> CliResult<()> {
+// ----------------------------------------------------------------------------
+// unbundle
+// ----------------------------------------------------------------------------
+
+/// Write the bundle's pack (checksum-verified) into `objects/pack` with an
+/// index, then print the heads like `git bundle unbundle`.
+fn unbundle(file: &Path) -> CliResult<()> {
     let bytes = fs::read(file).map_err(read_err)?;
     let header = parse_header(&bytes)?;
+    check_prerequisites(&header)?;
+    let pack = &bytes[header.pack_offset..];
+    check_pack_magic(pack)?;
+
+    let hash_len = get_hash_kind().size();
+    let payload_len = pack.len().saturating_sub(hash_len);
+    let checksum = ObjectHash::from_bytes(&pack[payload_len..]).ok();
+    if checksum != Some(ObjectHash::new(&pack[..payload_len])) {
+        return Err(CliError::fatal(
+            "bundle pack checksum does not match its contents".to_string(),
+        )
+        .with_exit_code(1)
+        .with_stable_code(StableErrorCode::CliInvalidTarget));
+    }
+    let checksum = ObjectHash::new(&pack[..payload_len]);
+
+    let pack_dir = path::try_objects()
+        .map_err(|_| CliError::repo_not_found())?
+        .join("pack");
+    fs::create_dir_all(&pack_dir).map_err(unbundle_write_err)?;
+    let pack_file = pack_dir.join(format!("pack-{checksum}.pack"));
+    let index_file = pack_file.with_extension("idx");
+    if !index_file.exists() {
+        fs::write(&pack_file, pack).map_err(unbundle_write_err)?;
+        let (pack_file, index_file) = (
+            pack_file.to_string_lossy().into_owned(),
+            index_file.to_string_lossy().into_owned(),
+        );
+        let indexed = match get_hash_kind() {
+            HashKind::Sha1 => index_pack::build_index_v1(&pack_file, &index_file),
+            HashKind::Sha256 => index_pack::build_index_v2(&pack_file, &index_file),
+        };
+        if let Err(error) = indexed {
+            let _ = fs::remove_file(&pack_file);
+            return Err(
+                CliError::fatal(format!("failed to index bundle pack: {error}"))
+                    .with_exit_code(128)
+                    .with_stable_code(StableErrorCode::RepoCorrupt),
+            );
+        }
+    }
+
     for (oid, name) in &header.heads {
         println!("{oid} {name}");
     }
@@ -420,6 +584,12 @@ fn read_err(error: std::io::Error) -> CliError {
         .with_stable_code(StableErrorCode::CliInvalidTarget)
 }
 
+fn unbundle_write_err(error: std::io::Error) -> CliError {
+    CliError::fatal(format!("failed to store bundle pack: {error}"))
+        .with_exit_code(128)
+        .with_stable_code(StableErrorCode::IoWriteFailed)
+}
+
 fn write_err(error: std::io::Error) -> CliError {
     CliError::fatal(format!("failed to write bundle: {error}"))
         .with_exit_code(128)
@@ -2,23 +2,32 @@
 //! (storage type, small/large threshold, LRU disk budget), exposing the existing
 //! `LIBRA_STORAGE_*` knobs for inspection (lore.md §0.10). Pure inspection of the
 //! resolved storage configuration; needs no repository.
+//!
+//! `libra cache dedup` / `libra cache verify-dedup` reclaim local disk by
+//! replacing large loose objects the durable tier already holds with redirect
+//! markers, and check that those redirects still resolve. Both need a
+//! repository and a tiered backend.
 
 use clap::{Parser, Subcommand};
 use serde::Serialize;
 
 use crate::utils::{
-    client_storage::{CacheConfig, resolve_cache_config},
-    error::{CliError, CliResult},
+    client_storage::{CacheConfig, ClientStorage, resolve_cache_config},
+    error::{CliError, CliResult, StableErrorCode},
     output::{OutputConfig, emit_json_data},
+    path, util,
 };
 
 pub const CACHE_EXAMPLES: &str = "\
 EXAMPLES:
     libra cache info                       Show the resolved storage/cache tunables
     LIBRA_STORAGE_TYPE=r2 LIBRA_STORAGE_CACHE_SIZE=536870912 libra cache info
-    libra --json cache info                Structured { storage_type, tiered, threshold_bytes, cache_size_bytes }";
+    libra --json cache info                Structured { storage_type, tiered, threshold_bytes, cache_size_bytes, object_cache_bytes }
+    libra cache dedup                      Redirect large objects the durable tier already holds
+    libra cache verify-dedup               Check that every redirect still resolves";
 
-/// Inspect the tiered-storage / LRU cache configuration.
+/// Inspect the tiered-storage / LRU cache configuration and deduplicate the
+/// local object store against the durable tier.
 #[derive(Parser, Debug)]
 #[command(after_help = CACHE_EXAMPLES)]
 pub struct CacheArgs {
@@ -30,6 +39,10 @@ pub struct CacheArgs {
 pub enum CacheCommand {
     /// Show the resolved storage/cache tunables (type, threshold, LRU budget).
     Info,
+    /// Replace large loose objects the durable tier already holds with redirects.
+    Dedup,
+    /// Check that every dedup redirect still resolves.
+    VerifyDedup,
 }
 
 #[derive(Debug, Serialize)]
@@ -44,11 +57,118 @@ struct CacheInfo {
     threshold_bytes: usize,
     /// Local LRU disk budget in bytes (`LIBRA_STORAGE_CACHE_SIZE`).
     cache_size_bytes: usize,
+    /// In-memory decompressed-object cache budget (`LIBRA_OBJECT_CACHE_SIZE`).
+    object_cache_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DedupOutput {
+    /// Loose objects examined.
+    scanned: usize,
+    /// Loose objects replaced by a redirect in this run.
+    deduplicated: usize,
+    /// Loose objects kept because they are below the threshold.
+    below_threshold: usize,
+    /// Large loose objects kept because the durable tier lacks them.
+    local_only: usize,
+    /// Size of the loose-object files removed.
+    bytes_reclaimed: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct VerifyDedupOutput {
+    /// Redirects examined.
+    checked: usize,
+    /// Redirected objects found neither in the durable tier nor locally.
+    dangling: Vec<String>,
 }
 
 pub async fn execute_safe(args: CacheArgs, output: &OutputConfig) -> CliResult<()> {
     match args.command {
         CacheCommand::Info => info(output),
+        CacheCommand::Dedup => dedup(output),
+        CacheCommand::VerifyDedup => verify_dedup(output),
+    }
+}
+
+/// Storage for the current repository, or an error when there is none.
+fn repository_storage() -> CliResult<ClientStorage> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+    Ok(ClientStorage::init(path::objects()))
+}
+
+fn not_tiered() -> CliError {
+    CliError::fatal("no durable tier is configured; there is nothing to deduplicate against")
+        .with_stable_code(StableErrorCode::Unsupported)
+        .with_hint("set LIBRA_STORAGE_TYPE to s3 or r2 (see `libra cache info`).")
+}
+
+fn dedup(output: &OutputConfig) -> CliResult<()> {
+    let storage = repository_storage()?;
+    let report = storage
+        .dedup()
+        .map_err(|err| {
+            CliError::fatal(format!("failed to deduplicate objects: {err}"))
+                .with_stable_code(StableErrorCode::IoWriteFailed)
+        })?
+        .ok_or_else(not_tiered)?;
+    let report = DedupOutput {
+        scanned: report.scanned,
+        deduplicated: report.deduplicated,
+        below_threshold: report.below_threshold,
+        local_only: report.local_only,
+        bytes_reclaimed: report.bytes_reclaimed,
+    };
+
+    if output.is_json() {
+        return emit_json_data("cache", &report, output);
+    }
+    if output.quiet {
+        return Ok(());
+    }
+
+    println!(
+        "deduplicated {} of {} loose objects, reclaimed {} bytes",
+        report.deduplicated, report.scanned, report.bytes_reclaimed
+    );
+    println!(
+        "kept {} below the threshold and {} not yet in the durable tier",
+        report.below_threshold, report.local_only
+    );
+    Ok(())
+}
+
+fn verify_dedup(output: &OutputConfig) -> CliResult<()> {
+    let storage = repository_storage()?;
+    let report = storage.verify_dedup().ok_or_else(not_tiered)?;
+    let report = VerifyDedupOutput {
+        checked: report.checked,
+        dangling: report.dangling.iter().map(ToString::to_string).collect(),
+    };
+
+    if output.is_json() {
+        emit_json_data("cache", &report, output)?;
+    } else if !output.quiet {
+        for hash in &report.dangling {
+            println!("dangling redirect: {hash}");
+        }
+        println!(
+            "checked {} redirects, {} dangling",
+            report.checked,
+            report.dangling.len()
+        );
+    }
+
+    if report.dangling.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::failure(format!(
+            "{} redirected objects are missing from both the durable tier and the local store",
+            report.dangling.len()
+        ))
+        .with_stable_code(StableErrorCode::RepoCorrupt)
+        .with_exit_code(1)
+        .with_hint("run `libra fsck` to see which objects are affected."))
     }
 }
 
@@ -58,6 +178,7 @@ fn info(output: &OutputConfig) -> CliResult<()> {
         tiered,
         threshold_bytes,
         cache_size_bytes,
+        object_cache_bytes,
     } = resolve_cache_config().map_err(|message| {
         CliError::fatal(format!(
             "failed to resolve storage/cache configuration: {message}"
@@ -68,6 +189,7 @@ fn info(output: &OutputConfig) -> CliResult<()> {
         tiered,
         threshold_bytes,
         cache_size_bytes,
+        object_cache_bytes,
     };
 
     if output.is_json() {
@@ -89,7 +211,11 @@ fn info(output: &OutputConfig) -> CliResult<()> {
         report.cache_size_bytes
     );
     println!(
-        "(configure via LIBRA_STORAGE_TYPE / LIBRA_STORAGE_THRESHOLD / LIBRA_STORAGE_CACHE_SIZE)"
+        "memory:    {} bytes (in-memory cache of decompressed objects; 0 disables it)",
+        report.object_cache_bytes
+    );
+    println!(
+        "(configure via LIBRA_STORAGE_TYPE / LIBRA_STORAGE_THRESHOLD / LIBRA_STORAGE_CACHE_SIZE / LIBRA_OBJECT_CACHE_SIZE)"
     );
     Ok(())
 }
@@ -35,9 +35,7 @@ use crate::{
         client_storage::ClientStorage,
         error::{CliError, CliResult, StableErrorCode},
         output::{OutputConfig, emit_json_data},
-        path,
-        storage::local::LocalStorage,
-        util,
+        path, util,
     },
 };
 
@@ -1348,7 +1346,7 @@ async fn build_history_manager() -> CliResult<HistoryManager> {
             ))
             .with_stable_code(StableErrorCode::RepoCorrupt)
         })?;
-    let storage = Arc::new(LocalStorage::new(objects_dir));
+    let storage = Arc::new(ClientStorage::init(objects_dir));
     Ok(HistoryManager::new(storage, repo_path, Arc::new(db_conn)))
 }
 
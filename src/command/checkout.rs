@@ -1,14 +1,17 @@
 //! Handles checkout-style flows to show the current branch, switch to existing branches, or create and switch to a new one using restore utilities.
 
-use std::str::FromStr;
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
 
 use clap::Parser;
-use git_internal::hash::ObjectHash;
+use git_internal::{
+    hash::ObjectHash,
+    internal::object::{commit::Commit, tree::Tree},
+};
 use serde::Serialize;
 
 use crate::{
     command::{
-        branch, pull,
+        branch, load_object, pull,
         restore::{self, RestoreArgs},
         switch,
     },
@@ -19,6 +22,7 @@ use crate::{
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
+        git_hooks, lfs,
         output::{OutputConfig, emit_json_data},
         util,
         util::get_commit_base,
@@ -288,7 +292,51 @@ pub async fn execute(args: CheckoutArgs) {
 /// writes fail.
 pub async fn execute_safe(args: CheckoutArgs, output: &OutputConfig) -> CliResult<()> {
     let result = run_checkout(args, output).await.map_err(CliError::from)?;
-    render_checkout_output(&result, output)
+    warn_locked_checkout_paths(&result);
+    render_checkout_output(&result, output)?;
+    if result.action != "show-current" {
+        // A path checkout leaves HEAD in place and reports flag 0.
+        git_hooks::run_post_checkout_hook(
+            result.previous_commit.as_deref(),
+            result.commit.as_deref(),
+            result.restore.is_none(),
+            output,
+        )
+        .await;
+    }
+    Ok(())
+}
+
+/// Warn when the checkout rewrote a path that the LFS lock cache records as
+/// locked by another user: edits to it could not be pushed.
+fn warn_locked_checkout_paths(result: &CheckoutOutput) {
+    if let Some(restore) = &result.restore {
+        lfs::warn_foreign_locks(restore.restored_files.iter().chain(&restore.deleted_files));
+        return;
+    }
+    if !result.switched || !lfs::LockCache::load().has_foreign_locks() {
+        return;
+    }
+    let (Some(from), Some(to)) = (&result.previous_commit, &result.commit) else {
+        return;
+    };
+    if from == to {
+        return;
+    }
+    let tree_items = |commit: &str| -> Option<HashMap<PathBuf, ObjectHash>> {
+        let commit: Commit = load_object(&ObjectHash::from_str(commit).ok()?).ok()?;
+        let tree: Tree = load_object(&commit.tree_id).ok()?;
+        Some(tree.get_plain_items().into_iter().collect())
+    };
+    let (Some(old), Some(new)) = (tree_items(from), tree_items(to)) else {
+        return;
+    };
+    let changed = new
+        .iter()
+        .filter(|(path, hash)| old.get(*path) != Some(hash))
+        .map(|(path, _)| path)
+        .chain(old.keys().filter(|path| !new.contains_key(*path)));
+    lfs::warn_foreign_locks(changed);
 }
 
 async fn run_checkout(
@@ -20,14 +20,10 @@ use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait};
 use serde::Serialize;
 
 use crate::{
-    command::{
-        commit::{CleanupMode, cleanup_commit_message, parse_cleanup_mode},
-        load_object, merge, save_object,
-    },
-    common_utils::format_commit_msg,
+    command::{load_object, merge, save_object},
+    common_utils::{CleanupMode, cleanup_commit_message, format_commit_msg, parse_cleanup_mode},
     internal::{
         branch::Branch,
-        config::ConfigKv,
         db::get_db_conn_instance,
         head::Head,
         reflog::{ReflogAction, ReflogContext, with_reflog},
@@ -104,6 +100,9 @@ enum CherryPickError {
     #[error("commit {0} has an empty commit message")]
     EmptyMessage(String),
 
+    #[error("failed to edit the cherry-pick message: {0}")]
+    Editor(String),
+
     #[error("failed to cherry-pick {commit}: {reason}")]
     Conflict { commit: String, reason: String },
 
@@ -150,6 +149,7 @@ impl CherryPickError {
             Self::EmptyCommit(_) => StableErrorCode::CliInvalidArguments,
             Self::RedundantCommit(_) => StableErrorCode::CliInvalidArguments,
             Self::EmptyMessage(_) => StableErrorCode::CliInvalidArguments,
+            Self::Editor(_) => StableErrorCode::IoReadFailed,
             Self::Conflict { .. } => StableErrorCode::ConflictUnresolved,
             Self::InProgress => StableErrorCode::ConflictOperationBlocked,
             Self::NoCherryPickInProgress => StableErrorCode::RepoStateInvalid,
@@ -198,6 +198,9 @@ impl From<CherryPickError> for CliError {
             CherryPickError::EmptyMessage(_) => CliError::fatal(message)
                 .with_stable_code(stable_code)
                 .with_hint("use --allow-empty-message to cherry-pick with an empty message"),
+            CherryPickError::Editor(_) => CliError::fatal(message)
+                .with_stable_code(stable_code)
+                .with_hint("set $GIT_EDITOR, core.editor, $VISUAL, or $EDITOR, or drop -e/--edit"),
             CherryPickError::Conflict { .. } => CliError::failure(message)
                 .with_stable_code(stable_code)
                 .with_hint(
@@ -238,6 +241,8 @@ enum CherryPickSingleError {
     EmptyCommit(String),
     RedundantCommit(String),
     EmptyMessage(String),
+    /// `-e`: the editor failed or exited abnormally.
+    Editor(String),
     /// A real three-way conflict: the listed paths were written to the index
     /// (stages 1/2/3) and worktree (conflict markers). The caller persists the
     /// sequencer state (commit-per-pick mode) before exiting.
@@ -613,6 +618,7 @@ fn map_single_error(err: CherryPickSingleError, commit_label: &str) -> CherryPic
         CherryPickSingleError::EmptyCommit(c) => CherryPickError::EmptyCommit(c),
         CherryPickSingleError::RedundantCommit(c) => CherryPickError::RedundantCommit(c),
         CherryPickSingleError::EmptyMessage(c) => CherryPickError::EmptyMessage(c),
+        CherryPickSingleError::Editor(e) => CherryPickError::Editor(e),
         CherryPickSingleError::Conflict(reason) => CherryPickError::Conflict {
             commit: commit_label.to_string(),
             reason,
@@ -715,6 +721,8 @@ async fn reset_hard(target: &str, output: &OutputConfig) -> Result<(), CherryPic
             soft: false,
             mixed: false,
             hard: true,
+            merge: false,
+            keep: false,
             pathspecs: Vec::new(),
             pathspec_from_file: None,
             pathspec_file_nul: false,
@@ -924,9 +932,12 @@ async fn run_cherry_pick_continue(
         .await
         .ok_or_else(|| CherryPickError::LoadObject("failed to resolve current HEAD".to_string()))?;
     let tree_id = create_tree_from_index(&index).map_err(|e| map_single_error(e, ""))?;
-    let new_commit = create_cherry_pick_commit(&original, &parent, tree_id, &opts_args, output)
-        .await
-        .map_err(|e| map_single_error(e, &state.current_oid.to_string()))?;
+    let new_commit = async {
+        let message = build_cherry_pick_message(&original, &opts_args, output).await?;
+        create_cherry_pick_commit(&original, &parent, tree_id, message, &opts_args).await
+    }
+    .await
+    .map_err(|e| map_single_error(e, &state.current_oid.to_string()))?;
 
     let mut acc = PickAccumulator {
         picked: vec![make_entry(&state.current_oid, Some(new_commit))],
@@ -1292,13 +1303,17 @@ async fn cherry_pick_single_commit(
         }
     }
 
+    // Settle the message (including any `-e` edit) before touching the index or
+    // worktree, so an aborted edit leaves nothing half-applied.
+    let message = build_cherry_pick_message(&commit_to_pick, args, output).await?;
+
     index
         .save(&index_file)
         .map_err(|e| CherryPickSingleError::SaveFailed(format!("failed to save index: {e}")))?;
     reset_workdir_tracked_only(&current_index, &index)?;
 
     let cherry_pick_commit_id =
-        create_cherry_pick_commit(&commit_to_pick, &current_head, tree_id, args, output).await?;
+        create_cherry_pick_commit(&commit_to_pick, &current_head, tree_id, message, args).await?;
     Ok(PickOutcome::Committed(cherry_pick_commit_id))
 }
 
@@ -1309,22 +1324,22 @@ async fn resolve_signoff_identity() -> (String, String) {
     (committer.name, committer.email)
 }
 
-/// Resolve the editor for `-e`, mirroring Git's precedence:
-/// `core.editor` config → `$VISUAL` → `$EDITOR`. Returns `None` when none is set.
-async fn resolve_editor() -> Option<String> {
-    if let Ok(Some(entry)) = ConfigKv::get("core.editor").await
-        && !entry.value.trim().is_empty()
-    {
-        return Some(entry.value);
+/// Pick the editor for `-e` the way `libra commit` does: an explicitly
+/// configured one (`$GIT_EDITOR` → `core.editor` → `$VISUAL` → `$EDITOR`) runs
+/// even without a TTY, the `vi` fallback only on an interactive terminal, and
+/// none at all in JSON/machine mode.
+async fn resolve_cherry_pick_editor(
+    args: &CherryPickArgs,
+    output: &OutputConfig,
+) -> Option<String> {
+    if !args.edit || output.is_json() {
+        return None;
     }
-    for var in ["VISUAL", "EDITOR"] {
-        if let Ok(value) = std::env::var(var)
-            && !value.trim().is_empty()
-        {
-            return Some(value);
-        }
+    match crate::command::editor::resolve_editor().await {
+        Some(cmd) => Some(cmd),
+        None if std::io::stdin().is_terminal() => Some("vi".to_string()),
+        None => None,
     }
-    None
 }
 
 /// Assemble the cherry-pick commit message, honoring `-x` (append source line),
@@ -1334,14 +1349,9 @@ async fn build_cherry_pick_message(
     args: &CherryPickArgs,
     output: &OutputConfig,
 ) -> Result<String, CherryPickSingleError> {
-    // Resolve the editor up front: `-e` only opens one on an interactive TTY,
-    // never in machine/JSON mode, and only if one is configured. Whether it
-    // actually opens governs the `default`/`scissors` cleanup fallback.
-    let editor = if args.edit && !output.is_json() && std::io::stdin().is_terminal() {
-        resolve_editor().await
-    } else {
-        None
-    };
+    // Resolve the editor up front. Whether one actually opens governs the
+    // `default`/`scissors` cleanup fallback.
+    let editor = resolve_cherry_pick_editor(args, output).await;
 
     // Resolve `--cleanup=<mode>` to its effective mode (validated up front, so an
     // unparseable value cannot reach here). `default`/`scissors` fall back to
@@ -1351,16 +1361,7 @@ async fn build_cherry_pick_message(
         .cleanup
         .as_deref()
         .and_then(parse_cleanup_mode)
-        .map(|mode| {
-            if editor.is_some() {
-                mode
-            } else {
-                match mode {
-                    CleanupMode::Default | CleanupMode::Scissors => CleanupMode::Whitespace,
-                    other => other,
-                }
-            }
-        });
+        .map(|mode| mode.effective(editor.is_some()));
 
     let body = original_commit.message.trim();
 
@@ -1376,11 +1377,13 @@ async fn build_cherry_pick_message(
         append_cherry_pick_trailers(&mut message, original_commit, args).await;
         Ok(message)
     } else {
-        // Default path (unchanged): trim → trailers → optional `-e` edit.
+        // Default path: trim → trailers → optional `-e` edit. An edited message
+        // gets `libra commit`'s default cleanup, so `#` lines are dropped.
         let mut message = body.to_string();
         append_cherry_pick_trailers(&mut message, original_commit, args).await;
         if let Some(editor) = editor {
-            message = edit_cherry_pick_message(&message, &editor).await?;
+            let edited = edit_cherry_pick_message(&message, &editor).await?;
+            message = cleanup_commit_message(&edited, CleanupMode::Default);
         }
         Ok(message)
     }
@@ -1415,29 +1418,25 @@ async fn append_cherry_pick_trailers(
 }
 
 /// Launch the resolved editor on a scratch message file via the shared editor
-/// helper. A missing or failing editor leaves the message intact
-/// (`abort_on_failure = false`). Cherry-pick keeps its own editor precedence
-/// (`core.editor` → `$VISUAL` → `$EDITOR`, no `$GIT_EDITOR`) via `resolve_editor`
-/// above and passes the resolved command to the shared launcher.
+/// helper. An editor that exits abnormally aborts the pick, as it aborts
+/// `libra commit`.
 async fn edit_cherry_pick_message(
     message: &str,
     editor: &str,
 ) -> Result<String, CherryPickSingleError> {
     let path = util::storage_path().join("CHERRY_PICK_MSG");
-    crate::command::editor::edit_message(&path, message, editor, false)
+    crate::command::editor::edit_message(&path, message, editor, true)
         .await
-        .map_err(|e| CherryPickSingleError::SaveFailed(e.to_string()))
+        .map_err(|e| CherryPickSingleError::Editor(e.to_string()))
 }
 
 async fn create_cherry_pick_commit(
     original_commit: &Commit,
     parent_id: &ObjectHash,
     tree_id: ObjectHash,
+    message: String,
     args: &CherryPickArgs,
-    output: &OutputConfig,
 ) -> Result<ObjectHash, CherryPickSingleError> {
-    let message = build_cherry_pick_message(original_commit, args, output).await?;
-
     if message.trim().is_empty() && !args.allow_empty_message {
         return Err(CherryPickSingleError::EmptyMessage(
             original_commit.id.to_string(),
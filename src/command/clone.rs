@@ -40,7 +40,7 @@ use crate::{
         },
         db::get_db_conn_instance,
         head::Head,
-        protocol::DiscoveryResult,
+        protocol::{DiscoveryResult, ObjectFilter},
         publish::{
             ai_export::publish_ai_graph_relative_key,
             contract::{
@@ -52,6 +52,7 @@ use crate::{
         reflog::{ReflogAction, ReflogContext, with_reflog},
     },
     utils::{
+        client_storage::ClientStorage,
         d1_client::{
             D1Client, ObjectIndexRow, PublishAiObjectRow, PublishAiVersionRow, PublishRefRow,
             PublishRevisionRow, PublishSiteRow, RepositoryRow,
@@ -60,10 +61,8 @@ use crate::{
         ignore as ignore_utils,
         output::{OutputConfig, emit_json_data},
         pager::LIBRA_TEST_ENV,
-        path,
-        storage::{
-            Storage, local::LocalStorage, publish_storage::PublishStorage, remote::RemoteStorage,
-        },
+        path, progress,
+        storage::{Storage, publish_storage::PublishStorage, remote::RemoteStorage},
         storage_ext::StorageExt,
         util,
     },
@@ -98,7 +97,8 @@ pub struct CloneArgs {
     /// The local path to clone the repository to
     pub local_path: Option<String>,
 
-    /// Checkout <BRANCH> instead of the remote's HEAD
+    /// Checkout <BRANCH> instead of the remote's HEAD. A tag name is also
+    /// accepted: HEAD is then detached at the tag's commit, as in Git.
     #[clap(short = 'b', long, required = false)]
     pub branch: Option<String>,
 
@@ -137,6 +137,11 @@ pub struct CloneArgs {
     #[clap(long = "no-progress")]
     pub no_progress: bool,
 
+    /// Fetch over N concurrent upload-pack requests (HTTPS remotes; `0` uses
+    /// one per CPU). Ignored with `--depth`.
+    #[clap(short = 'j', long, value_name = "N")]
+    pub jobs: Option<usize>,
+
     /// Do not check out HEAD into the working tree after cloning (objects, refs
     /// and HEAD are still set up), matching `git clone --no-checkout`.
     #[clap(long = "no-checkout")]
@@ -216,12 +221,12 @@ pub struct CloneArgs {
     #[clap(long = "mirror")]
     pub mirror: bool,
 
-    /// Partial-clone object filter (Git's `--filter <spec>`, e.g. `blob:none`).
-    /// Accepted for compatibility but a no-op with a warning: Libra has no
-    /// partial-clone/promisor support, so the filter is ignored (no objects are
-    /// excluded) and a complete clone is performed (subject only to `--depth` if
-    /// also given) — mirroring Git's own behavior when a server does not advertise
-    /// filtering. Not supported for `libra+cloud://` sources.
+    /// Partial-clone object filter (Git's `--filter <spec>`): `blob:none` omits
+    /// every blob, `blob:limit=<n>[kmg]` omits blobs of `n` bytes or more. The
+    /// remote is recorded as the promisor (`remote.<name>.promisor`,
+    /// `remote.<name>.partialclonefilter`, `extensions.partialclone`) and omitted
+    /// objects are fetched from it on demand, e.g. during checkout. Not supported
+    /// for `libra+cloud://` sources.
     #[clap(long = "filter", value_name = "spec")]
     pub filter: Option<String>,
 
@@ -240,6 +245,13 @@ pub struct CloneArgs {
     /// given multiple times. Not supported for `libra+cloud://` sources.
     #[clap(long = "shallow-exclude", value_name = "rev")]
     pub shallow_exclude: Vec<String>,
+
+    /// After cloning, register and check out every submodule recorded in the
+    /// cloned `.gitmodules`, as `libra submodule update --init` does. A
+    /// repository without submodules is unaffected; ignored for `--bare`,
+    /// `--mirror` and `--no-checkout` clones, which have no working tree.
+    #[clap(long = "recurse-submodules")]
+    pub recurse_submodules: bool,
 }
 
 /// `--reject-shallow`: refuse a clone that ended up shallow without the user
@@ -269,23 +281,14 @@ fn object_alternates_warning(args: &CloneArgs) -> Option<String> {
     )
 }
 
-/// Warnings for fetch-shaping flags Libra cannot honor (`--filter`,
-/// `--shallow-since`, `--shallow-exclude`). Libra has no partial-clone/promisor
-/// support and bounds shallow history only by `--depth`, so these flags are
-/// accepted but ignored — the optimization is simply not applied (the clone still
-/// fetches everything those flags would have trimmed, subject only to `--depth`
-/// if also given). Each given flag produces its own explanatory warning so the
-/// user knows it had no effect — mirroring Git, which warns and falls back to a
-/// full clone when a server cannot honor `--filter`.
+/// Warnings for fetch-shaping flags Libra cannot honor (`--shallow-since`,
+/// `--shallow-exclude`). Libra bounds shallow history only by `--depth`, so these
+/// flags are accepted but ignored — the optimization is simply not applied (the
+/// clone still fetches everything those flags would have trimmed, subject only to
+/// `--depth` if also given). Each given flag produces its own explanatory warning
+/// so the user knows it had no effect.
 fn unsupported_fetch_optimization_warnings(args: &CloneArgs) -> Vec<String> {
     let mut warnings = Vec::new();
-    if args.filter.is_some() {
-        warnings.push(
-            "--filter is ignored: Libra has no partial-clone support, so the object filter is not \
-             applied (no objects are excluded)"
-                .to_string(),
-        );
-    }
     if args.shallow_since.is_some() {
         warnings.push(
             "--shallow-since is ignored: Libra bounds shallow history only by --depth, so the date \
@@ -1032,7 +1035,7 @@ fn map_fetch_error(source: fetch::FetchError) -> CliError {
 /// Map a `RestoreError` from the checkout phase into a `CliError`.
 fn map_checkout_error(source: RestoreError) -> CliError {
     match source {
-        RestoreError::ResolveSource | RestoreError::ReferenceNotCommit => {
+        RestoreError::ResolveSource | RestoreError::ReferenceNotTree => {
             CliError::fatal("working tree checkout target could not be resolved")
                 .with_stable_code(StableErrorCode::RepoStateInvalid)
                 .with_hint("working tree checkout target could not be resolved")
@@ -1181,6 +1184,16 @@ fn validate_remote_name(name: &str) -> CliResult<()> {
     Ok(())
 }
 
+/// Validate a `--filter` spec; only `blob:none` and `blob:limit=<n>` are
+/// supported, anything else is a usage error (exit 129).
+fn validate_filter_spec(spec: &str) -> CliResult<()> {
+    ObjectFilter::parse(spec).map(|_| ()).map_err(|message| {
+        CliError::command_usage(message)
+            .with_stable_code(StableErrorCode::CliInvalidArguments)
+            .with_hint("use '--filter=blob:none' or '--filter=blob:limit=<n>'")
+    })
+}
+
 /// Apply Git's `check-ref-format` rules to the ref a remote name would create
 /// (`refs/remotes/<name>/HEAD`): reject empty components, leading-dot or
 /// `.lock`-suffixed components, `..`/`//`/`@{`, trailing `/` or `.`, and the
@@ -1279,6 +1292,11 @@ pub async fn execute_safe(mut args: CloneArgs, output: &OutputConfig) -> CliResu
     if let Some(name) = &args.origin {
         validate_remote_name(name)?;
     }
+    // `--filter` is sent to the server and recorded in config, so reject a spec
+    // Libra cannot apply before touching the filesystem.
+    if let Some(spec) = &args.filter {
+        validate_filter_spec(spec)?;
+    }
 
     // `--mirror` implies `--bare`: the mirror is a bare repository whose refs
     // mirror the source. Set it before dispatch so every bare-aware code path
@@ -1303,7 +1321,16 @@ pub async fn execute_safe(mut args: CloneArgs, output: &OutputConfig) -> CliResu
     }
 
     match result {
-        Ok(clone_output) => render_clone_result(&clone_output, output),
+        Ok(clone_output) => {
+            render_clone_result(&clone_output, output)?;
+            if args.recurse_submodules && !clone_output.bare && !args.no_checkout {
+                let report = command::submodule::update_in(Path::new(&clone_output.path))?;
+                if !report.is_empty() && !output.quiet && !output.is_json() {
+                    println!("{report}");
+                }
+            }
+            Ok(())
+        }
         Err(error) => {
             let mut cli_error = CliError::from(error);
             if let Some(warning) = cleanup_warning {
@@ -1500,6 +1527,7 @@ async fn execute_clone_inner(
     // --- Pre-check: specified branch exists on remote ---
     if let Some(branch) = &args.branch
         && !fetch::remote_has_branch(&discovery.refs, branch)
+        && !fetch::remote_has_tag(&discovery.refs, branch)
     {
         let cleanup_warning = cleanup_failed_clone(&local_path, created_by_clone);
         return Err((
@@ -1656,7 +1684,7 @@ async fn clone_cloud_publish_into_destination(
     if !output.quiet && !output.is_json() {
         eprintln!("Restoring objects from Cloudflare R2 ...");
     }
-    let local_storage = LocalStorage::new(path::objects());
+    let local_storage = ClientStorage::init(path::objects());
     let object_report = restore_indexed_objects_from_remote(
         &restore_plan.object_indexes,
         r2_storage,
@@ -1857,7 +1885,7 @@ async fn restore_cloud_publish_ai_objects(
     source: &CloudPublishSource,
     restore_plan: &CloudPublishRestorePlan,
     r2_storage: &RemoteStorage,
-    local_storage: &LocalStorage,
+    local_storage: &ClientStorage,
     db_conn: &DatabaseConnection,
 ) -> Result<(), CloneError> {
     if restore_plan.ai_objects.is_empty()
@@ -2033,7 +2061,7 @@ fn cloud_publish_relative_r2_key(
 async fn append_cloud_publish_ai_history<T>(
     source: &CloudPublishSource,
     restore_plan: &CloudPublishRestorePlan,
-    local_storage: &LocalStorage,
+    local_storage: &ClientStorage,
     history: &HistoryManager,
     history_type: &str,
     object_id: &str,
@@ -3125,6 +3153,19 @@ async fn clone_into_destination(
         git_internal::hash::HashKind::Sha256 => "sha256".to_string(),
     };
 
+    // `--branch <tag>`: a name that is not an advertised branch but is an
+    // advertised tag clones the branches as usual and detaches HEAD at the tag
+    // (the pre-check already rejected names that are neither).
+    let checkout_tag = args
+        .branch
+        .clone()
+        .filter(|name| !fetch::remote_has_branch(&discovery.refs, name));
+    let checkout_branch = if checkout_tag.is_some() {
+        None
+    } else {
+        args.branch.clone()
+    };
+
     // --- Step 4: Initialize repository ---
     if !output.quiet && !output.is_json() {
         eprintln!("Initializing repository ...");
@@ -3133,7 +3174,7 @@ async fn clone_into_destination(
     let init_output = command::init::run_init(command::init::InitArgs {
         bare: args.bare,
         template: None,
-        initial_branch: args.branch.clone(),
+        initial_branch: checkout_branch.clone(),
         repo_directory: local_path.to_string_lossy().into_owned(),
         quiet: true,
         shared: None,
@@ -3165,26 +3206,53 @@ async fn clone_into_destination(
     };
     // `git clone` fetches ALL tags by default; `--no-tags` skips them and records
     // `remote.<name>.tagOpt=--no-tags` so later fetches also skip tags.
-    let clone_tag_mode = if args.no_tags {
+    // A tag clone needs its tag ref even under `--no-tags`, so it fetches tags.
+    // `--single-branch` only auto-follows the tags that point into the one
+    // branch it fetched, as in Git.
+    let clone_tag_mode = if args.no_tags && checkout_tag.is_none() {
         let _ = ConfigKv::set(&format!("remote.{remote_name}.tagOpt"), "--no-tags", false).await;
         fetch::TagFetchMode::NoTags
+    } else if args.single_branch && checkout_tag.is_none() {
+        fetch::TagFetchMode::AutoFollow
     } else {
         fetch::TagFetchMode::All
     };
+    // `--filter`: record the remote as this partial clone's promisor before
+    // fetching, so the fetch requests a filtered pack and later reads fetch the
+    // omitted objects from it on demand.
+    if let Some(spec) = &args.filter {
+        for (key, value) in [
+            (format!("remote.{remote_name}.promisor"), "true"),
+            (
+                format!("remote.{remote_name}.partialclonefilter"),
+                spec.as_str(),
+            ),
+            ("extensions.partialclone".to_string(), remote_name.as_str()),
+        ] {
+            ConfigKv::set(&key, value, false)
+                .await
+                .map_err(|error| CloneError::SetupFailed {
+                    message: format!("failed to record partial-clone config '{key}': {error}"),
+                })?;
+        }
+    }
     // Capture the fetch result so the clone can report transfer counts
     // (`objects_fetched`/`bytes_received`) in its structured output. `dry_run`
     // and `force` are always false for a clone into a fresh repository.
     let fetch_result = fetch::fetch_repository_with_result(
         remote_config.clone(),
-        args.branch.clone(),
-        args.single_branch,
+        checkout_branch.clone(),
+        Vec::new(),
+        args.single_branch && checkout_tag.is_none(),
         args.depth,
+        fetch::resolve_fetch_jobs(args.jobs),
         false,
         Some(clone_tag_mode),
         false,
         // A fresh clone has no remote-tracking refs to prune.
         false,
         &child_output,
+        progress::for_output(&child_output),
     )
     .await
     .map_err(|source| CloneError::FetchFailed { source })?;
@@ -3198,12 +3266,20 @@ async fn clone_into_destination(
         eprintln!("Checking out working copy ...");
     }
 
-    let setup_result = setup_repository(
-        remote_config.clone(),
-        args.branch.clone(),
-        !args.bare && !args.no_checkout,
-    )
-    .await?;
+    let setup_result = match &checkout_tag {
+        Some(tag_name) => {
+            setup_repository_at_tag(&remote_config, tag_name, !args.bare && !args.no_checkout)
+                .await?
+        }
+        None => {
+            setup_repository(
+                remote_config.clone(),
+                checkout_branch.clone(),
+                !args.bare && !args.no_checkout,
+            )
+            .await?
+        }
+    };
 
     // `--mirror`: turn the standard tracking-ref layout into a mirror — every
     // fetched branch becomes a local `refs/heads/*` ref and the
@@ -3250,7 +3326,9 @@ async fn clone_into_destination(
     })?;
 
     // Build CloneOutput.
-    if setup_result.branch_name.is_none() {
+    if let Some(tag_name) = &checkout_tag {
+        warnings.push(format!("HEAD is detached at tag '{tag_name}'"));
+    } else if setup_result.branch_name.is_none() {
         warnings.push("You appear to have cloned an empty repository.".to_string());
     }
 
@@ -3501,6 +3579,81 @@ pub(crate) async fn setup_repository(
     }
 }
 
+/// Finishes a `clone --branch <tag>`: records the remote URL, detaches HEAD at
+/// the commit the tag points to (peeling an annotated tag), and checks out the
+/// worktree unless `checkout_worktree` is `false`. No local branch is created.
+async fn setup_repository_at_tag(
+    remote_config: &RemoteConfig,
+    tag_name: &str,
+    checkout_worktree: bool,
+) -> Result<SetupResult, CloneError> {
+    let (_, commit) = crate::internal::tag::find_tag_and_commit(tag_name)
+        .await
+        .map_err(|error| CloneError::SetupFailed {
+            message: format!("failed to resolve tag '{tag_name}': {error}"),
+        })?
+        .ok_or_else(|| CloneError::RemoteBranchNotFound {
+            branch: tag_name.to_string(),
+            remote: remote_config.name.clone(),
+        })?;
+    let commit_id = commit.id;
+
+    let context = ReflogContext {
+        old_oid: ObjectHash::zero_str(get_hash_kind()).to_string(),
+        new_oid: commit_id.to_string(),
+        action: ReflogAction::Clone {
+            from: remote_config.url.clone(),
+        },
+    };
+    let remote_name = remote_config.name.clone();
+    let remote_url = remote_config.url.clone();
+    with_reflog(
+        context,
+        move |txn: &DatabaseTransaction| {
+            Box::pin(async move {
+                Head::update_with_conn(txn, Head::Detached(commit_id), None).await;
+                let _ = ConfigKv::set_with_conn(
+                    txn,
+                    &format!("remote.{remote_name}.url"),
+                    &remote_url,
+                    false,
+                )
+                .await;
+                Ok(())
+            })
+        },
+        // Detached: only HEAD moves, there is no branch to log.
+        false,
+    )
+    .await
+    .map_err(|error| CloneError::SetupFailed {
+        message: error.to_string(),
+    })?;
+
+    if checkout_worktree {
+        command::restore::execute_checked_typed(RestoreArgs {
+            overlay: false,
+            no_overlay: false,
+            ours: false,
+            theirs: false,
+            ignore_unmerged: false,
+            merge: false,
+            conflict: None,
+            worktree: true,
+            staged: true,
+            source: None,
+            pathspec: vec![util::working_dir_string()],
+            pathspec_from_file: None,
+            pathspec_file_nul: false,
+            no_progress: false,
+        })
+        .await
+        .map_err(|source| CloneError::CheckoutFailed { source })?;
+    }
+
+    Ok(SetupResult { branch_name: None })
+}
+
 /// Unit tests for the clone module
 #[cfg(test)]
 mod tests {
@@ -3867,8 +4020,10 @@ mod tests {
             filter: None,
             shallow_since: None,
             shallow_exclude: vec![],
+            recurse_submodules: false,
             no_checkout: false,
             no_progress: false,
+            jobs: None,
             remote_repo: "libra+cloud://code.example.com/kepler-ledger".to_string(),
             local_path: None,
             branch: None,
@@ -4235,8 +4390,10 @@ mod tests {
             filter: None,
             shallow_since: None,
             shallow_exclude: vec![],
+            recurse_submodules: false,
             no_checkout: false,
             no_progress: false,
+            jobs: None,
             remote_repo: "libra+cloud://code.example.com/kepler-ledger".to_string(),
             local_path: None,
             branch: None,
@@ -4337,8 +4494,10 @@ mod tests {
             filter: None,
             shallow_since: None,
             shallow_exclude: vec![],
+            recurse_submodules: false,
             no_checkout: true,
             no_progress: false,
+            jobs: None,
             remote_repo: "libra+cloud://code.example.com/kepler-ledger".to_string(),
             local_path: None,
             branch: None,
@@ -4427,8 +4586,10 @@ mod tests {
             filter: None,
             shallow_since: None,
             shallow_exclude: vec![],
+            recurse_submodules: false,
             no_checkout: false,
             no_progress: false,
+            jobs: None,
             remote_repo: "libra+cloud://code.example.com/kepler-ledger?ref=refs/tags/v1.0.0"
                 .to_string(),
             local_path: None,
@@ -4507,8 +4668,10 @@ mod tests {
             filter: None,
             shallow_since: None,
             shallow_exclude: vec![],
+            recurse_submodules: false,
             no_checkout: false,
             no_progress: false,
+            jobs: None,
             remote_repo: "libra+cloud://code.example.com/kepler-ledger".to_string(),
             local_path: None,
             branch: None,
@@ -4584,8 +4747,10 @@ mod tests {
             filter: None,
             shallow_since: None,
             shallow_exclude: vec![],
+            recurse_submodules: false,
             no_checkout: false,
             no_progress: false,
+            jobs: None,
             remote_repo: "libra+cloud://code.example.com/kepler-ledger".to_string(),
             local_path: None,
             branch: None,
@@ -33,6 +33,7 @@ use crate::{
         model::{object_index, reference},
     },
     utils::{
+        client_storage::ClientStorage,
         d1_client::{AgentCheckpointRow, AgentSessionRow, D1Client, ObjectIndexRow},
         error::{CliError, CliResult, StableErrorCode, emit_warning},
         output::{OutputConfig, ProgressMode, emit_json_data},
@@ -1000,9 +1001,9 @@ pub(crate) async fn run_cloud_sync(
 
     progress.on_object_total(total_unsynced);
 
-    // Initialize local storage for reading objects.
-    let objects_path = path::objects();
-    let local_storage = LocalStorage::new(objects_path);
+    // Read objects through the client storage stack, so deduplicated and
+    // tiered objects resolve the same way they do for every other reader.
+    let local_storage = ClientStorage::init(path::objects());
 
     let mut synced_count = 0usize;
     let mut failed_count = 0usize;
@@ -1120,7 +1121,7 @@ fn parse_object_index_hash(obj: &object_index::Model) -> CloudResult<ObjectHash>
 /// existence is probed up front in one bounded-concurrency call.
 async fn sync_single_object(
     obj: &object_index::Model,
-    local_storage: &LocalStorage,
+    local_storage: &dyn Storage,
     r2_storage: &RemoteStorage,
     d1_client: &D1Client,
     hash: ObjectHash,
@@ -1164,7 +1165,7 @@ async fn sync_single_object(
 pub(crate) async fn restore_indexed_objects_from_remote(
     indexes: &[ObjectIndexRow],
     r2_storage: &RemoteStorage,
-    local_storage: &LocalStorage,
+    local_storage: &dyn Storage,
 ) -> CloudResult<ObjectRestoreReport> {
     let mut report = ObjectRestoreReport::default();
 
@@ -133,7 +133,8 @@ use crate::{
                     ApplyPatchHandler, GrepFilesHandler, ListDirHandler, McpBridgeHandler,
                     PlanHandler, ReadFileHandler, RequestUserInputHandler, SearchFilesHandler,
                     ShellHandler, SubmitIntentDraftHandler, SubmitPlanDraftHandler,
-                    SubmitTaskCompleteHandler, WebSearchHandler, register_semantic_handlers,
+                    SubmitTaskCompleteHandler, TreeSitterQueryHandler, WatchFileHandler,
+                    WebSearchHandler, register_semantic_handlers,
                 },
             },
             usage::{UsageContext, UsagePriceTable, UsageRecorder},
@@ -155,7 +156,9 @@ use crate::{
         },
         db::establish_connection,
         tui::{
-            App, AppConfig, ExitReason, Tui, TuiCodeUiAdapter, control::TuiControlCommand,
+            App, AppConfig, ExitReason, THEME_FILE_NAME, Theme, Tui, TuiCodeUiAdapter,
+            code_block::{HIGHLIGHT_THEME_CONFIG_KEY, set_highlight_theme},
+            control::TuiControlCommand,
             tui_init, tui_restore,
         },
     },
@@ -163,7 +166,6 @@ use crate::{
         client_storage::ClientStorage,
         error::{CliError, CliResult, StableErrorCode},
         output::OutputConfig,
-        storage::local::LocalStorage,
         util::{DATABASE, try_get_storage_path},
     },
 };
@@ -641,6 +643,13 @@ pub struct CodeArgs {
     /// the supervisor at startup.
     #[arg(long = "goal", value_name = "OBJECTIVE")]
     pub goal: Option<String>,
+
+    /// Enable Vim-style keybindings in the TUI input field. The composer
+    /// starts in Insert mode; `Esc` switches to Normal mode (`i`/`a` to
+    /// insert, `h`/`l`/`w`/`b`/`0`/`$` to move, `x` to delete a character,
+    /// `dd` to clear the line).
+    #[arg(long)]
+    pub vim: bool,
 }
 
 /// Resolves the effective `plan_mode` flag for the current invocation.
@@ -1422,12 +1431,16 @@ async fn execute_tui(args: CodeArgs) -> CliResult<()> {
         // [`default_context_budget_model`].
         None
     };
-    let preamble = system_preamble(
+    // The TUI follows edits to `.libra/rules/*.md` (and the user-global
+    // rules) so prompt iterations don't need a session restart.
+    let preamble_watch = system_preamble_builder(
         &working_dir,
         args.context,
         effective_provider,
         effective_model_for_preamble.as_deref(),
-    );
+    )
+    .build_with_watch();
+    let preamble = preamble_watch.borrow().clone();
     let temperature = args.temperature;
     let thinking = completion_thinking_for_provider(effective_provider, &args);
     let reasoning_effort = completion_reasoning_effort_for_provider(effective_provider, &args);
@@ -1466,6 +1479,8 @@ async fn execute_tui(args: CodeArgs) -> CliResult<()> {
         .register("list_dir", Arc::new(ListDirHandler))
         .register("grep_files", Arc::new(GrepFilesHandler))
         .register("search_files", Arc::new(SearchFilesHandler))
+        .register("watch_file", Arc::new(WatchFileHandler))
+        .register("tree_sitter_query", Arc::new(TreeSitterQueryHandler))
         .register("web_search", Arc::new(WebSearchHandler))
         .register("apply_patch", Arc::new(ApplyPatchHandler))
         .register("shell", Arc::new(ShellHandler))
@@ -1503,6 +1518,7 @@ async fn execute_tui(args: CodeArgs) -> CliResult<()> {
         mcp_port: args.mcp_port,
         registry,
         preamble,
+        preamble_watch,
         temperature,
         thinking,
         reasoning_effort,
@@ -1524,6 +1540,7 @@ async fn execute_tui(args: CodeArgs) -> CliResult<()> {
         control_runtime,
         browser_control,
         initial_goal: args.goal.clone(),
+        vim_mode: args.vim,
     };
 
     // Create agent based on provider. Every non-Codex provider funnels
@@ -2087,6 +2104,8 @@ fn build_headless_tool_registry(
         .register("list_dir", Arc::new(ListDirHandler))
         .register("grep_files", Arc::new(GrepFilesHandler))
         .register("search_files", Arc::new(SearchFilesHandler))
+        .register("watch_file", Arc::new(WatchFileHandler))
+        .register("tree_sitter_query", Arc::new(TreeSitterQueryHandler))
         .register("web_search", Arc::new(WebSearchHandler))
         .register("apply_patch", Arc::new(ApplyPatchHandler))
         .register("shell", Arc::new(ShellHandler))
@@ -2513,6 +2532,9 @@ struct TuiLaunchConfig {
     mcp_port: u16,
     registry: Arc<ToolRegistry>,
     preamble: String,
+    /// Rebuilt preamble published when rule files change on disk; forwarded
+    /// to [`AppConfig::preamble_watch`].
+    preamble_watch: tokio::sync::watch::Receiver<String>,
     temperature: Option<f64>,
     thinking: Option<CompletionThinking>,
     reasoning_effort: Option<CompletionReasoningEffort>,
@@ -2537,6 +2559,8 @@ struct TuiLaunchConfig {
     /// uses this to bootstrap a `GoalSpec` and seed
     /// [`AppConfig::initial_goal`] before the first turn.
     initial_goal: Option<String>,
+    /// `libra code --vim`; forwarded to [`AppConfig::vim_mode`].
+    vim_mode: bool,
 }
 
 #[derive(Clone)]
@@ -2686,7 +2710,7 @@ async fn load_code_ui_projection_bundle(
         .to_str()
         .ok_or_else(|| anyhow::anyhow!("database path is not valid UTF-8"))?;
     let db_conn = establish_connection(db_path).await?;
-    let storage = Arc::new(LocalStorage::new(storage_root.join("objects")));
+    let storage = Arc::new(ClientStorage::init(storage_root.join("objects")));
     let history = HistoryManager::new(storage.clone(), storage_root, Arc::new(db_conn.clone()));
     let rebuilder = ProjectionRebuilder::new(storage.as_ref(), &history);
     let resolver = ProjectionResolver::new(db_conn);
@@ -3180,6 +3204,30 @@ where
     let auto_classify_first_user_message =
         params.auto_classify_first_user_message && managed_code_ui_runtime.is_none();
 
+    // Fenced code blocks in the transcript are highlighted with the theme
+    // named by `code.highlightTheme` (syntect's `base16-ocean.dark` by default).
+    if let Ok(Some(entry)) =
+        crate::internal::config::ConfigKv::get(HIGHLIGHT_THEME_CONFIG_KEY).await
+        && !set_highlight_theme(entry.value.trim())
+    {
+        tracing::warn!(
+            theme = %entry.value,
+            "unknown {HIGHLIGHT_THEME_CONFIG_KEY}; using the default highlight theme"
+        );
+    }
+
+    // Response colours come from `.libra/theme.toml`; a missing file means the
+    // `default` theme, and a malformed one warns and falls back to it.
+    let theme_path = registry.working_dir().join(".libra").join(THEME_FILE_NAME);
+    let theme = Theme::load_or_default(&theme_path).unwrap_or_else(|err| {
+        tracing::warn!(
+            error = %err,
+            path = %theme_path.display(),
+            "failed to load theme.toml; falling back to the default theme",
+        );
+        Theme::default()
+    });
+
     // Create and run app
     let mut app = App::new(
         tui,
@@ -3207,6 +3255,9 @@ where
             auto_classify_first_user_message,
             initial_goal: params.initial_goal.clone(),
             source_pool,
+            vim_mode: params.vim_mode,
+            theme: Arc::new(theme),
+            preamble_watch: Some(params.preamble_watch),
         },
     );
 
@@ -3327,6 +3378,17 @@ fn system_preamble(
     provider: CodeProvider,
     model: Option<&str>,
 ) -> String {
+    system_preamble_builder(working_dir, context, provider, model).build()
+}
+
+/// The configured [`SystemPromptBuilder`] behind [`system_preamble`], for
+/// callers that want to watch the rule files instead of building once.
+fn system_preamble_builder(
+    working_dir: &std::path::Path,
+    context: Option<CodeContext>,
+    provider: CodeProvider,
+    model: Option<&str>,
+) -> SystemPromptBuilder {
     let intent = task_intent_for_context(context);
     let budget = ContextBudget::for_provider_model(
         context_budget_provider_name(provider),
@@ -3344,7 +3406,7 @@ fn system_preamble(
         };
         builder = builder.with_context(mode);
     }
-    builder.build()
+    builder
 }
 
 fn context_budget_provider_name(provider: CodeProvider) -> &'static str {
@@ -4238,6 +4300,7 @@ mod tests {
             codex_port: None,
             plan_mode: None,
             goal: None,
+            vim: false,
         }
     }
 
@@ -28,18 +28,22 @@ use serde::Serialize;
 
 use crate::{
     command::{diff, editor, load_object, save_object_to_storage, status},
-    common_utils::{check_conventional_commits_message, format_commit_msg, parse_commit_msg},
+    common_utils::{
+        CleanupMode, check_conventional_commits_message, cleanup_commit_message, format_commit_msg,
+        parse_cleanup_mode, parse_commit_msg, truncate_at_scissors,
+    },
     internal::{
         ai::automation::{VCS_EVENT_POST_COMMIT, dispatch_current_repo_vcs_event_to_history},
         branch::Branch,
         config::{LocalIdentityTarget, read_cascaded_config_value, resolve_user_identity_sources},
         head::Head,
         reflog::{ReflogAction, ReflogContext, with_reflog},
+        vault::PgpSigner,
     },
     utils::{
         client_storage::ClientStorage,
         error::{CliError, CliResult, StableErrorCode},
-        lfs,
+        git_hooks, index_marks, lfs,
         object_ext::BlobExt,
         output::{OutputConfig, emit_json_data},
         path, util,
@@ -99,6 +103,11 @@ pub struct CommitArgs {
     #[arg(long)]
     pub allow_empty: bool,
 
+    /// Allow a commit whose message is empty after cleanup (Git's
+    /// `--allow-empty-message`); without it an empty message aborts.
+    #[arg(long)]
+    pub allow_empty_message: bool,
+
     /// check if the commit message follows conventional commits
     #[arg(long)]
     pub conventional: bool,
@@ -135,8 +144,9 @@ pub struct CommitArgs {
     #[arg(long, value_name = "COMMIT", conflicts_with_all = ["message", "file", "squash", "reuse_message", "reedit_message"])]
     pub fixup: Option<String>,
 
-    /// Create a squash commit targeting the specified commit. The message becomes "squash! <subject>".
-    #[arg(long, value_name = "COMMIT", conflicts_with_all = ["message", "file", "fixup", "reuse_message", "reedit_message"])]
+    /// Create a squash commit targeting the specified commit. The message becomes "squash! <subject>",
+    /// followed by a blank line and the -m/-F text when one is given (as in Git).
+    #[arg(long, value_name = "COMMIT", conflicts_with_all = ["fixup", "reuse_message", "reedit_message"])]
     pub squash: Option<String>,
 
     /// Clean up the commit message according to the given mode: strip (default), whitespace, verbatim, scissors, or default.
@@ -147,7 +157,8 @@ pub struct CommitArgs {
     #[arg(long)]
     pub dry_run: bool,
 
-    /// Add a trailer line to the commit message. Can be given multiple times.
+    /// Add a `key: value` (or `key=value`) trailer to the commit message; a
+    /// trailer identical to one already present is not repeated. Repeatable.
     #[arg(long = "trailer", value_name = "TRAILER")]
     pub trailers: Vec<String>,
 
@@ -195,31 +206,21 @@ pub struct CommitArgs {
     #[arg(long = "no-status", overrides_with = "status")]
     pub no_status: bool,
 
+    /// Sign the commit with the vault PGP key (the `gpgsig` header) even when
+    /// `vault.signing` is off. `commit.gpgsign=true` makes this the default.
+    /// Fails when no vault unseal key is available.
+    #[arg(short = 'S', long = "gpg-sign", overrides_with = "no_gpg_sign")]
+    pub gpg_sign: bool,
+
     /// Force an unsigned commit: skip Libra's vault GPG signing
     /// (`vault_sign_commit`) for this commit, matching `git commit
     /// --no-gpg-sign`. Vault signing runs when `vault.signing=true` (the `libra
-    /// init` default) and a vault unseal key is available; `--no-gpg-sign`
-    /// suppresses it regardless, so it is a no-op only when signing would not
-    /// have happened anyway. (Git's positive `-S`/`--gpg-sign` is not exposed.)
-    #[arg(long = "no-gpg-sign")]
+    /// init` default), `commit.gpgsign=true`, or `-S` is given; `--no-gpg-sign`
+    /// suppresses it regardless (last of `-S`/`--no-gpg-sign` wins).
+    #[arg(long = "no-gpg-sign", overrides_with = "gpg_sign")]
     pub no_gpg_sign: bool,
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
-pub enum CleanupMode {
-    /// Strip leading/trailing empty lines and trailing whitespace from every line, then strip commentary lines.
-    #[default]
-    Strip,
-    /// Same as strip but leave consecutive empty lines.
-    Whitespace,
-    /// Do not change the message at all.
-    Verbatim,
-    /// Same as strip but truncate the message at the scissors line.
-    Scissors,
-    /// Same as strip if the message is to be edited, otherwise whitespace.
-    Default,
-}
-
 // ---------------------------------------------------------------------------
 // Structured error types
 // ---------------------------------------------------------------------------
@@ -277,6 +278,9 @@ pub enum CommitError {
     #[error("pre-commit hook failed: {0}")]
     PreCommitHook(String),
 
+    #[error("{hook} hook failed: {detail}")]
+    Hook { hook: &'static str, detail: String },
+
     #[error("conventional commit validation failed: {0}")]
     ConventionalCommit(String),
 
@@ -339,7 +343,9 @@ impl From<CommitError> for CliError {
                 .with_hint("edit the message in the editor, or pass -m to set it directly"),
             CommitError::EmptyMessage => CliError::failure(error.to_string())
                 .with_stable_code(StableErrorCode::RepoStateInvalid)
-                .with_hint("use -m to provide a commit message"),
+                .with_hint(
+                    "use -m to provide a commit message, or --allow-empty-message to commit without one",
+                ),
             CommitError::EditorFailed(..) => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::IoReadFailed)
                 .with_hint("set $EDITOR/core.editor, or pass -m to provide the message directly"),
@@ -358,6 +364,14 @@ impl From<CommitError> for CliError {
             CommitError::PreCommitHook(..) => CliError::failure(error.to_string())
                 .with_stable_code(StableErrorCode::RepoStateInvalid)
                 .with_hint("use --no-verify to bypass the hook"),
+            // Like Git, --no-verify bypasses commit-msg but not prepare-commit-msg.
+            CommitError::Hook { hook, .. } if *hook == "commit-msg" => {
+                CliError::failure(error.to_string())
+                    .with_stable_code(StableErrorCode::RepoStateInvalid)
+                    .with_hint("use --no-verify to bypass the hook")
+            }
+            CommitError::Hook { .. } => CliError::failure(error.to_string())
+                .with_stable_code(StableErrorCode::RepoStateInvalid),
             CommitError::ConventionalCommit(..) => CliError::command_usage(error.to_string())
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
                 .with_hint("see https://www.conventionalcommits.org for format rules"),
@@ -568,7 +582,10 @@ pub async fn run_commit(
         false
     };
 
-    let index = Index::load(path::index()).map_err(|e| CommitError::IndexLoad(e.to_string()))?;
+    let mut index =
+        Index::load(path::index()).map_err(|e| CommitError::IndexLoad(e.to_string()))?;
+    // `add -N` placeholders are not committed, as in Git.
+    index_marks::strip_intent_to_add(&mut index);
     let storage = ClientStorage::init(path::objects());
     let tracked_entries = index.tracked_entries(0);
 
@@ -585,6 +602,9 @@ pub async fn run_commit(
     if staged_changes.is_empty() && !args.allow_empty && !is_amend {
         return Err(CommitError::NothingToCommit);
     }
+    // Like Git LFS, another user's lock does not block the commit (the push
+    // is where the server enforces it), but the author should know now.
+    lfs::warn_foreign_locks(staged_changes.polymerization());
 
     // `--porcelain` snapshot of the would-be-committed state: taken AFTER `-a`
     // auto-staging and the staged recompute above so it reflects what would be
@@ -607,6 +627,16 @@ pub async fn run_commit(
     // no longer block the commit without explicit rollback logic.
     if !skip_hooks {
         run_pre_commit_hook(output)?;
+        git_hooks::run_hook("pre-commit", &[], None, output)
+            .await
+            .map_err(CommitError::PreCommitHook)?;
+        // A pre-commit hook may stage fixes (formatters, generated files), so
+        // commit the index as the hook left it, as Git does.
+        if !dry_run {
+            index =
+                Index::load(path::index()).map_err(|e| CommitError::IndexLoad(e.to_string()))?;
+            index_marks::strip_intent_to_add(&mut index);
+        }
     }
 
     // Resolve parent commits (needed to seed the editor with the amend parent's
@@ -684,6 +714,11 @@ pub async fn run_commit(
             Some(line) => append_trailers(&final_message, std::slice::from_ref(line)),
             None => final_message.clone(),
         };
+        let commit_message = if dry_run || args.no_verify {
+            commit_message
+        } else {
+            run_commit_msg_hook(commit_message, args.allow_empty_message, output).await?
+        };
 
         // Conventional commit validation
         if is_conventional
@@ -729,7 +764,7 @@ pub async fn run_commit(
                 &author,
                 &committer,
                 &commit_message,
-                false,
+                signing_requested(args.gpg_sign).await,
             )
             .await?
         };
@@ -745,7 +780,14 @@ pub async fn run_commit(
         // INVARIANT: persist the commit object before moving HEAD so a crash
         // after ref update never points the branch at a missing object.
         save_commit_object(&storage, &commit)?;
-        update_head_and_reflog(&commit.id.to_string(), &commit_message).await?;
+        update_head_and_reflog(&commit.id.to_string(), &commit_message, true).await?;
+        git_hooks::run_notify_hook("post-commit", &[], None, output).await;
+        git_hooks::run_post_rewrite_hook(
+            git_hooks::RewriteKind::Amend,
+            &[(parent_commit.id.to_string(), commit.id.to_string())],
+            output,
+        )
+        .await;
 
         let conventional_result = if is_conventional && !skip_conventional_check {
             Some(true)
@@ -771,6 +813,11 @@ pub async fn run_commit(
         Some(line) => append_trailers(&message, std::slice::from_ref(line)),
         None => message.clone(),
     };
+    let commit_message = if dry_run || args.no_verify {
+        commit_message
+    } else {
+        run_commit_msg_hook(commit_message, args.allow_empty_message, output).await?
+    };
 
     // Conventional commit validation
     if is_conventional
@@ -816,7 +863,7 @@ pub async fn run_commit(
             &author,
             &committer,
             &commit_message,
-            false,
+            signing_requested(args.gpg_sign).await,
         )
         .await?
     };
@@ -832,7 +879,8 @@ pub async fn run_commit(
     // INVARIANT: persist the commit object before moving HEAD so a crash after
     // ref update never points the branch at a missing object.
     save_commit_object(&storage, &commit)?;
-    update_head_and_reflog(&commit.id.to_string(), &commit_message).await?;
+    update_head_and_reflog(&commit.id.to_string(), &commit_message, false).await?;
+    git_hooks::run_notify_hook("post-commit", &[], None, output).await;
 
     let conventional_result = if is_conventional && !skip_conventional_check {
         Some(true)
@@ -852,6 +900,22 @@ pub async fn run_commit(
     .await)
 }
 
+/// The message given with `-m`, or read from the `-F` file, if any.
+async fn read_message_source(args: &CommitArgs) -> Result<Option<String>, CommitError> {
+    if let Some(msg) = &args.message {
+        Ok(Some(msg.clone()))
+    } else if let Some(file_path) = &args.file {
+        Ok(Some(tokio::fs::read_to_string(file_path).await.map_err(
+            |e| CommitError::MessageFileRead {
+                path: file_path.clone(),
+                detail: e.to_string(),
+            },
+        )?))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Resolve the final commit message from CLI arguments.
 /// Resolve the final commit message, opening the editor when needed and
 /// possible.
@@ -862,7 +926,8 @@ pub async fn run_commit(
 /// configured `$GIT_EDITOR`/`core.editor`/`$VISUAL`/`$EDITOR` runs even without
 /// a TTY; the implicit `vi` fallback requires an interactive terminal). With
 /// `-v` the staged diff is appended to the template and stripped at the scissors
-/// marker so it never enters the message. An empty final message aborts.
+/// marker so it never enters the message. An empty final message aborts unless
+/// `--allow-empty-message` is given.
 async fn resolve_final_message(
     args: &CommitArgs,
     output: &OutputConfig,
@@ -874,23 +939,20 @@ async fn resolve_final_message(
             commit_subject(&load_commit_message(spec).await?)
         ))
     } else if let Some(spec) = &args.squash {
-        Some(format!(
+        let subject = format!(
             "squash! {}",
             commit_subject(&load_commit_message(spec).await?)
-        ))
+        );
+        // Git keeps an explicit -m/-F message as the squash commit's body; the
+        // autosquash rebase later folds it into the combined message.
+        match read_message_source(args).await? {
+            Some(body) => Some(format!("{subject}\n\n{body}")),
+            None => Some(subject),
+        }
     } else if let Some(spec) = args.reuse_message.as_ref().or(args.reedit_message.as_ref()) {
         Some(load_commit_message(spec).await?)
-    } else if let Some(msg) = &args.message {
-        Some(msg.clone())
-    } else if let Some(file_path) = &args.file {
-        Some(tokio::fs::read_to_string(file_path).await.map_err(|e| {
-            CommitError::MessageFileRead {
-                path: file_path.clone(),
-                detail: e.to_string(),
-            }
-        })?)
     } else {
-        None
+        read_message_source(args).await?
     };
 
     // `-e`/`-c` always edit; otherwise an editor is needed only to author a
@@ -988,6 +1050,23 @@ async fn resolve_final_message(
         None
     };
 
+    // `prepare-commit-msg` may rewrite the seeded message before the editor
+    // opens. Its source argument names where the message came from, as in Git.
+    let (source, source_commit) =
+        if let Some(spec) = args.reuse_message.as_ref().or(args.reedit_message.as_ref()) {
+            (Some("commit"), Some(spec.clone()))
+        } else if base.is_some() {
+            (Some("message"), None)
+        } else if template_content.is_some() {
+            (Some("template"), None)
+        } else if args.amend && !parent_ids.is_empty() {
+            (Some("commit"), Some(parent_ids[0].to_string()))
+        } else {
+            (None, None)
+        };
+    let initial =
+        run_prepare_commit_msg_hook(initial, source, source_commit.as_deref(), output).await?;
+
     let editor_opened = editor_cmd.is_some();
     let resolved = if let Some(editor_cmd) = editor_cmd {
         let buffer = if verbose {
@@ -1022,16 +1101,8 @@ async fn resolve_final_message(
         {
             eprintln!("{diff}");
         }
-        // Git's `default` and `scissors` cleanup both carry an "if the message is
-        // edited" clause: `default` is strip-when-edited / whitespace-otherwise, and
-        // `scissors` truncates at the scissors marker only when edited. No editor
-        // opened here, so both resolve to whitespace (comment/scissors lines kept);
-        // every other mode is applied as-is.
-        let effective_mode = match mode {
-            CleanupMode::Default | CleanupMode::Scissors => CleanupMode::Whitespace,
-            other => other,
-        };
-        cleanup_commit_message(&initial, effective_mode)
+        // No editor opened, so `default` and `scissors` resolve to whitespace.
+        cleanup_commit_message(&initial, mode.effective(false))
     };
 
     // When a template seeded the message and the editor was meant to open
@@ -1051,7 +1122,7 @@ async fn resolve_final_message(
         }
     }
 
-    if resolved.trim().is_empty() {
+    if resolved.trim().is_empty() && !args.allow_empty_message {
         return Err(CommitError::EmptyMessage);
     }
 
@@ -1211,20 +1282,6 @@ fn commit_subject(message: &str) -> &str {
     message.lines().next().unwrap_or(message).trim()
 }
 
-/// Parse a `commit.cleanup` config value into a [`CleanupMode`], case-insensitively
-/// (`strip`/`whitespace`/`verbatim`/`scissors`/`default`). Returns `None` for an
-/// unrecognized value so the caller falls back to the built-in default.
-pub(crate) fn parse_cleanup_mode(value: &str) -> Option<CleanupMode> {
-    match value.trim().to_ascii_lowercase().as_str() {
-        "strip" => Some(CleanupMode::Strip),
-        "whitespace" => Some(CleanupMode::Whitespace),
-        "verbatim" => Some(CleanupMode::Verbatim),
-        "scissors" => Some(CleanupMode::Scissors),
-        "default" => Some(CleanupMode::Default),
-        _ => None,
-    }
-}
-
 /// Interpret a Git "bool-or-int" config value for `commit.verbose`: `true`/`yes`/
 /// `on` are `Some(true)` and `false`/`no`/`off` are `Some(false)`; an integer is
 /// truthy when non-zero (Git documents `commit.verbose` as boolean-or-int, where
@@ -1254,88 +1311,41 @@ fn parse_git_config_bool(value: &str) -> Option<bool> {
     }
 }
 
-/// Truncate a message at the scissors marker (everything from the marker line
-/// onward is dropped). Accepts both a bare marker and Git's comment-prefixed form
-/// (`# ------------------------ >8 ...`), so the `commit -v` template (Git-standard
-/// `#` form) and the staged diff below it are removed.
-fn truncate_at_scissors(message: &str) -> String {
-    message
-        .lines()
-        .take_while(|line| {
-            !line
-                .trim()
-                .trim_start_matches('#')
-                .trim_start()
-                .starts_with("------------------------ >8 ")
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
-
-/// Apply Git-style cleanup to a commit message.
-pub(crate) fn cleanup_commit_message(message: &str, mode: CleanupMode) -> String {
-    match mode {
-        CleanupMode::Verbatim => message.to_string(),
-        CleanupMode::Scissors => {
-            // Git's `scissors` is whitespace cleanup PLUS truncation at the marker:
-            // the message above the marker keeps its `#` comment lines (unlike
-            // strip). The verbose path, which needs a post-truncation strip, calls
-            // `truncate_at_scissors` + `Strip` directly instead.
-            cleanup_commit_message(&truncate_at_scissors(message), CleanupMode::Whitespace)
-        }
-        CleanupMode::Whitespace => {
-            let lines: Vec<String> = message
-                .lines()
-                .map(|line| line.trim_end().to_string())
-                .collect();
-            let trimmed = trim_empty_lines(&lines);
-            trimmed.join("\n")
-        }
-        CleanupMode::Strip | CleanupMode::Default => {
-            let lines: Vec<String> = message
-                .lines()
-                .map(|line| {
-                    let trimmed = line.trim_end();
-                    if trimmed.starts_with('#') {
-                        String::new()
-                    } else {
-                        trimmed.to_string()
-                    }
-                })
-                .collect();
-            let mut result = trim_empty_lines(&lines);
-            // Git's strip collapses CONSECUTIVE blank lines into one but keeps
-            // single blank separators — deleting every interior blank (the old
-            // behavior) flattened multi-paragraph messages and destroyed
-            // user-typed trailer blocks at write time.
-            result.dedup_by(|current, previous| current.is_empty() && previous.is_empty());
-            result.join("\n")
-        }
-    }
-}
-
-fn trim_empty_lines(lines: &[String]) -> Vec<String> {
-    let start = lines
-        .iter()
-        .position(|line| !line.trim().is_empty())
-        .unwrap_or(lines.len());
-    let end = lines
-        .iter()
-        .rposition(|line| !line.trim().is_empty())
-        .map(|i| i + 1)
-        .unwrap_or(lines.len());
-    lines[start..end].to_vec()
-}
-
 /// Append trailer lines to a commit message the way `git interpret-trailers`
 /// does: when the message already ends in a qualifying trailer block, append
 /// INTO that block (single newline — so `-s` + `--trailer` produce ONE
 /// Git-parseable block); otherwise open a new final paragraph (blank line).
 /// The old single-newline branch for newline-terminated messages glued
 /// trailers onto the last body line, invisible to a Git-strict parser.
+///
+/// Each trailer is normalized by [`normalize_trailer`] first, and one that is
+/// identical to a trailer already in the block (or earlier in `trailers`) is
+/// skipped, so `-s` never writes a second copy of the same `Signed-off-by`.
 fn append_trailers(message: &str, trailers: &[String]) -> String {
-    let trailers_block = trailers.join("\n");
     let trimmed = message.trim_end();
+    let mut existing = crate::internal::log::trailer::parse_trailers(trimmed);
+    let mut added = Vec::new();
+    for raw in trailers {
+        let line = normalize_trailer(raw);
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            if existing
+                .iter()
+                .any(|trailer| trailer.key_matches(key.trim()) && trailer.value == value)
+            {
+                continue;
+            }
+            existing.push(crate::internal::log::trailer::Trailer {
+                key: key.trim().to_string(),
+                value: value.to_string(),
+            });
+        }
+        added.push(line);
+    }
+    if added.is_empty() {
+        return message.to_string();
+    }
+    let trailers_block = added.join("\n");
     if trimmed.is_empty() {
         trailers_block
     } else if crate::internal::log::trailer::ends_with_trailer_block(trimmed) {
@@ -1345,6 +1355,18 @@ fn append_trailers(message: &str, trailers: &[String]) -> String {
     }
 }
 
+/// Normalize a `--trailer` argument to `Key: value`. Like Git, the command line
+/// accepts `=` as well as `:` between key and value (`--trailer key=value`).
+/// An argument without a separator, or with an empty key, is kept verbatim.
+fn normalize_trailer(raw: &str) -> String {
+    match raw.split_once([':', '=']) {
+        Some((key, value)) if !key.trim().is_empty() => {
+            format!("{}: {}", key.trim(), value.trim())
+        }
+        _ => raw.to_string(),
+    }
+}
+
 /// Run the pre-commit hook, respecting OutputConfig for I/O isolation.
 fn run_pre_commit_hook(output: &OutputConfig) -> Result<(), CommitError> {
     let hooks_dir = path::hooks();
@@ -1401,6 +1423,67 @@ fn run_pre_commit_hook(output: &OutputConfig) -> Result<(), CommitError> {
     Ok(())
 }
 
+/// Run `prepare-commit-msg <file> [<source> [<commit>]]` over the seeded
+/// message in `COMMIT_EDITMSG` and return the (possibly rewritten) message. A
+/// failing hook aborts the commit.
+async fn run_prepare_commit_msg_hook(
+    message: String,
+    source: Option<&str>,
+    source_commit: Option<&str>,
+    output: &OutputConfig,
+) -> Result<String, CommitError> {
+    const HOOK: &str = "prepare-commit-msg";
+    if git_hooks::find_hook(HOOK).await.is_none() {
+        return Ok(message);
+    }
+    let path = util::storage_path().join("COMMIT_EDITMSG");
+    let hook_error = |detail: String| CommitError::Hook { hook: HOOK, detail };
+    std::fs::write(&path, &message)
+        .map_err(|e| hook_error(format!("failed to write {}: {e}", path.display())))?;
+    let path_arg = path.to_string_lossy();
+    let hook_args: Vec<&str> = std::iter::once(path_arg.as_ref())
+        .chain(source)
+        .chain(source_commit)
+        .collect();
+    git_hooks::run_hook(HOOK, &hook_args, None, output)
+        .await
+        .map_err(hook_error)?;
+    std::fs::read_to_string(&path)
+        .map_err(|e| hook_error(format!("failed to read {}: {e}", path.display())))
+}
+
+/// Run `commit-msg <file>` over the final message. A failing hook aborts the
+/// commit; a hook that rewrites the file replaces the message (whitespace
+/// cleaned, as Git reads it back).
+async fn run_commit_msg_hook(
+    message: String,
+    allow_empty_message: bool,
+    output: &OutputConfig,
+) -> Result<String, CommitError> {
+    const HOOK: &str = "commit-msg";
+    if git_hooks::find_hook(HOOK).await.is_none() {
+        return Ok(message);
+    }
+    let path = util::storage_path().join("COMMIT_EDITMSG");
+    let hook_error = |detail: String| CommitError::Hook { hook: HOOK, detail };
+    let written = format!("{message}\n");
+    std::fs::write(&path, &written)
+        .map_err(|e| hook_error(format!("failed to write {}: {e}", path.display())))?;
+    git_hooks::run_hook(HOOK, &[path.to_string_lossy().as_ref()], None, output)
+        .await
+        .map_err(hook_error)?;
+    let rewritten = std::fs::read_to_string(&path)
+        .map_err(|e| hook_error(format!("failed to read {}: {e}", path.display())))?;
+    if rewritten == written {
+        return Ok(message);
+    }
+    let rewritten = cleanup_commit_message(&rewritten, CleanupMode::Whitespace);
+    if rewritten.is_empty() && !allow_empty_message {
+        return Err(CommitError::EmptyMessage);
+    }
+    Ok(rewritten)
+}
+
 /// Save a commit object to storage.
 fn save_commit_object(storage: &ClientStorage, commit: &Commit) -> Result<(), CommitError> {
     let data = commit
@@ -1602,6 +1685,16 @@ pub(crate) async fn vault_sign_commit(
             return Ok(None);
         }
     }
+    if !cfg!(feature = "signing") {
+        return if force {
+            Err(CommitError::VaultSign(
+                "this build of libra does not include signature support (the `signing` feature)"
+                    .to_string(),
+            ))
+        } else {
+            Ok(None)
+        };
+    }
 
     // Load unseal key
     let unseal_key = vault::load_unseal_key().await.ok_or_else(|| {
@@ -1636,7 +1729,8 @@ pub(crate) async fn vault_sign_commit(
 
     let root_dir = util::storage_path();
 
-    let sig_hex = vault::pgp_sign(&root_dir, &unseal_key, &content)
+    let key_name = vault::signing_key_name().await;
+    let sig_hex = vault::pgp_sign(&root_dir, &unseal_key, &key_name, &content)
         .await
         .map_err(|e| CommitError::VaultSign(format!("vault PGP signing failed: {e}")))?;
     let gpgsig = vault::signature_to_gpgsig(&sig_hex)
@@ -1645,34 +1739,55 @@ pub(crate) async fn vault_sign_commit(
     Ok(Some(gpgsig))
 }
 
+/// Whether this commit must be signed: an explicit `-S`, or the
+/// `commit.gpgsign` config default (Git's boolean spellings accepted).
+async fn signing_requested(gpg_sign_flag: bool) -> bool {
+    if gpg_sign_flag {
+        return true;
+    }
+    read_cascaded_config_value(LocalIdentityTarget::CurrentRepo, "commit.gpgsign")
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "true" | "yes" | "on" | "1"
+            )
+        })
+}
+
+/// Verdict line for a signature the vault key rejects. It names no signer:
+/// only a key that verified a signature is ever reported as one.
+pub(crate) const BAD_SIGNATURE_MESSAGE: &str =
+    "BAD signature (does not verify against the vault signing key)";
+
 /// Result of checking a commit's embedded PGP signature (for
-/// `merge --verify-signatures`).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// `merge --verify-signatures`, `log --show-signature` and `show
+/// --show-signature`).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum CommitSignatureStatus {
     /// The commit carries no `gpgsig` header.
     Unsigned,
-    /// The signature validates against the local vault PGP key.
-    Good,
+    /// The signature validates against the vault PGP key `user.signingkey`
+    /// names, whose identity is carried here.
+    Good(PgpSigner),
     /// A signature is present but does not validate.
     Bad,
 }
 
-/// Verify the PGP signature embedded in `commit`'s `gpgsig` header against the
-/// local vault key. Reconstructs the exact bytes that were signed (the commit
-/// content minus the signature — the same serialization [`vault_sign_commit`]
-/// produces) and checks them via the vault.
-///
-/// Like `tag -v`, this can only validate signatures made by THIS repository's
-/// vault key — Libra has no external GPG keyring, so a commit signed elsewhere
-/// (or with an SSH signature) cannot be verified and reports [`CommitSignatureStatus::Bad`].
-pub(crate) async fn verify_commit_signature(
+/// Split a signed commit into its armored signature (continuation-line
+/// prefixes removed) and the exact bytes that were signed: the raw commit
+/// with its `gpgsig` header dropped, which is both what [`vault_sign_commit`]
+/// signs and what Git hands to `gpg --verify`. `None` for an unsigned commit.
+pub(crate) fn commit_signed_payload(
     commit: &Commit,
-) -> Result<CommitSignatureStatus, CommitError> {
-    use crate::{common_utils::parse_commit_msg, internal::vault};
+) -> Result<Option<(String, Vec<u8>)>, CommitError> {
+    use crate::common_utils::parse_commit_msg;
 
     let (_, signature) = parse_commit_msg(&commit.message);
     let Some(sig_block) = signature else {
-        return Ok(CommitSignatureStatus::Unsigned);
+        return Ok(None);
     };
 
     // Recover the EXACT signed message body. `vault_sign_commit` signs the raw
@@ -1701,12 +1816,6 @@ pub(crate) async fn verify_commit_signature(
         .map(|line| line.strip_prefix(' ').unwrap_or(line))
         .collect::<Vec<_>>()
         .join("\n");
-    let sig_hex = match vault::armored_to_signature_hex(&armored) {
-        Ok(hex) => hex,
-        // A malformed / non-PGP (e.g. SSH) signature block cannot be validated
-        // against the vault PGP key — treat it as a bad signature, not an error.
-        Err(_) => return Ok(CommitSignatureStatus::Bad),
-    };
 
     // Reconstruct the signed content byte-for-byte, matching `vault_sign_commit`.
     let mut content: Vec<u8> = Vec::new();
@@ -1732,21 +1841,63 @@ pub(crate) async fn verify_commit_signature(
     content.extend(b"\n\n");
     content.extend(message.as_bytes());
 
+    Ok(Some((armored, content)))
+}
+
+/// Verify the PGP signature embedded in `commit`'s `gpgsig` header against the
+/// vault key `user.signingkey` names (the `libra init` key by default).
+/// Reconstructs the exact bytes that were signed (the commit
+/// content minus the signature — the same serialization [`vault_sign_commit`]
+/// produces) and checks them via the vault.
+///
+/// Like `tag -v`, this can only validate signatures made by THIS repository's
+/// vault key, so a commit signed elsewhere (or with an SSH signature) reports
+/// [`CommitSignatureStatus::Bad`]; `verify-commit` then falls back to the
+/// user's GnuPG keyring via [`commit_signed_payload`].
+pub(crate) async fn verify_commit_signature(
+    commit: &Commit,
+) -> Result<CommitSignatureStatus, CommitError> {
+    use crate::internal::vault;
+
+    let Some((armored, content)) = commit_signed_payload(commit)? else {
+        return Ok(CommitSignatureStatus::Unsigned);
+    };
+    let sig_hex = match vault::armored_to_signature_hex(&armored) {
+        Ok(hex) => hex,
+        // A malformed / non-PGP (e.g. SSH) signature block cannot be validated
+        // against the vault PGP key — treat it as a bad signature, not an error.
+        Err(_) => return Ok(CommitSignatureStatus::Bad),
+    };
+
     let unseal_key = vault::load_unseal_key().await.ok_or_else(|| {
         CommitError::VaultSign("signature verification requires a vault unseal key".to_string())
     })?;
     let root_dir = util::storage_path();
-    let valid = vault::pgp_verify(&root_dir, &unseal_key, &content, &sig_hex)
+    let key_name = vault::signing_key_name().await;
+    let signer = vault::pgp_verify_signer(&root_dir, &unseal_key, &key_name, &content, &sig_hex)
         .await
         .map_err(|e| CommitError::VaultSign(format!("vault PGP verification failed: {e}")))?;
 
-    Ok(if valid {
-        CommitSignatureStatus::Good
-    } else {
-        CommitSignatureStatus::Bad
+    Ok(match signer {
+        Some(signer) => CommitSignatureStatus::Good(signer),
+        None => CommitSignatureStatus::Bad,
     })
 }
 
+/// One-line signature verdict for `log --show-signature` / `show
+/// --show-signature`, naming the key that verified the signature (never the
+/// unverified committer header). `None` for an unsigned commit; a verification
+/// failure (e.g. no vault unseal key) is reported inline rather than aborting
+/// the listing.
+pub(crate) async fn describe_commit_signature(commit: &Commit) -> Option<String> {
+    match verify_commit_signature(commit).await {
+        Ok(CommitSignatureStatus::Unsigned) => None,
+        Ok(CommitSignatureStatus::Good(signer)) => Some(format!("Good signature from {signer}")),
+        Ok(CommitSignatureStatus::Bad) => Some(BAD_SIGNATURE_MESSAGE.to_string()),
+        Err(error) => Some(format!("Can't check signature: {error}")),
+    }
+}
+
 /// recursively create tree from index's tracked entries
 pub async fn create_tree(
     index: &Index,
@@ -1934,8 +2085,14 @@ async fn update_head<C: ConnectionTrait>(db: &C, commit_id: &str) -> Result<(),
     Ok(())
 }
 
-async fn update_head_and_reflog(commit_id: &str, commit_message: &str) -> Result<(), CommitError> {
-    let reflog_context = new_reflog_context(commit_id, commit_message).await;
+/// Move HEAD to `commit_id` and record a `commit` (or `commit (amend)`) reflog
+/// entry in the same transaction.
+async fn update_head_and_reflog(
+    commit_id: &str,
+    commit_message: &str,
+    amend: bool,
+) -> Result<(), CommitError> {
+    let reflog_context = new_reflog_context(commit_id, commit_message, amend).await;
     let commit_id = commit_id.to_string();
     with_reflog(
         reflog_context,
@@ -1952,7 +2109,7 @@ async fn update_head_and_reflog(commit_id: &str, commit_message: &str) -> Result
     .map_err(|e| CommitError::HeadUpdate(format!("failed to update reflog: {}", e)))
 }
 
-async fn new_reflog_context(commit_id: &str, message: &str) -> ReflogContext {
+async fn new_reflog_context(commit_id: &str, message: &str, amend: bool) -> ReflogContext {
     // INVARIANT: zero-filled bytes of the correct hash size always produce a valid ObjectHash
     let zero_hash =
         ObjectHash::from_bytes(&vec![0u8; get_hash_kind().size()]).expect("zero hash is valid");
@@ -1961,8 +2118,11 @@ async fn new_reflog_context(commit_id: &str, message: &str) -> ReflogContext {
         .unwrap_or(zero_hash)
         .to_string();
     let new_oid = commit_id.to_string();
-    let action = ReflogAction::Commit {
-        message: message.to_string(),
+    let message = message.to_string();
+    let action = if amend {
+        ReflogAction::CommitAmend { message }
+    } else {
+        ReflogAction::Commit { message }
     };
     ReflogContext {
         old_oid,
@@ -2005,17 +2165,39 @@ mod test {
     }
 
     #[test]
-    fn parse_cleanup_mode_is_case_insensitive_and_rejects_unknown() {
-        assert_eq!(parse_cleanup_mode("strip"), Some(CleanupMode::Strip));
+    fn append_trailers_normalizes_equals_and_skips_duplicates() {
+        let message = append_trailers(
+            "subject\n\nbody\n",
+            &[
+                "Co-authored-by=Alice <alice@example.com>".to_string(),
+                "Signed-off-by: A U Thor <a@example.com>".to_string(),
+            ],
+        );
         assert_eq!(
-            parse_cleanup_mode("WHITESPACE"),
-            Some(CleanupMode::Whitespace)
+            message,
+            "subject\n\nbody\n\nCo-authored-by: Alice <alice@example.com>\nSigned-off-by: A U Thor <a@example.com>"
         );
-        assert_eq!(parse_cleanup_mode("verbatim"), Some(CleanupMode::Verbatim));
-        assert_eq!(parse_cleanup_mode("scissors"), Some(CleanupMode::Scissors));
-        assert_eq!(parse_cleanup_mode("default"), Some(CleanupMode::Default));
-        assert_eq!(parse_cleanup_mode("bogus"), None);
+
+        // The same Signed-off-by again (e.g. `-s` after `--trailer`) is a no-op.
+        let again = append_trailers(
+            &message,
+            &["Signed-off-by: A U Thor <a@example.com>".to_string()],
+        );
+        assert_eq!(again, message);
+
+        // A different value for the same key still joins the block.
+        let other = append_trailers(&message, &["Signed-off-by: B <b@example.com>".to_string()]);
+        assert!(other.ends_with("<a@example.com>\nSigned-off-by: B <b@example.com>"));
     }
+
+    #[test]
+    fn normalize_trailer_accepts_colon_or_equals() {
+        assert_eq!(normalize_trailer("Key=value"), "Key: value");
+        assert_eq!(normalize_trailer("Key :  value "), "Key: value");
+        assert_eq!(normalize_trailer("no separator"), "no separator");
+        assert_eq!(normalize_trailer("=value"), "=value");
+    }
+
     use crate::utils::test::*;
 
     #[test]
@@ -0,0 +1,169 @@
+//! `libra commit-tree` — create a commit object from a tree and print its
+//! object id. Plumbing companion to `write-tree`: together with `update-ref`
+//! it lets scripts build history without a working tree. No ref, HEAD, or
+//! reflog is touched.
+
+use std::io::Read;
+
+use clap::Parser;
+use git_internal::{
+    hash::ObjectHash,
+    internal::object::{
+        ObjectTrait,
+        commit::Commit,
+        signature::{Signature, SignatureType},
+    },
+};
+use serde::Serialize;
+
+use crate::{
+    command::{commit::resolve_committer_identity, read_tree::resolve_tree_ish},
+    common_utils::format_commit_msg,
+    utils::{
+        error::{CliError, CliResult, StableErrorCode},
+        output::{OutputConfig, emit_json_data},
+        util,
+    },
+};
+
+/// `--help` examples (cross-cutting EXAMPLES contract, `_general.md`).
+pub const COMMIT_TREE_EXAMPLES: &str = "\
+EXAMPLES:
+    libra commit-tree $(libra write-tree) -m 'Initial import'     Create a root commit from the index
+    libra commit-tree <tree> -p HEAD -m 'Next'                    Create a child of HEAD
+    libra commit-tree <tree> -p main -p topic -m 'Merge topic'    Create a merge commit
+    echo 'From stdin' | libra commit-tree <tree>                  Read the message from stdin
+    libra --json commit-tree <tree> -m 'msg'                      Structured JSON output for agents";
+
+/// Create a commit object from a tree and print its object id.
+#[derive(Parser, Debug)]
+#[command(after_help = COMMIT_TREE_EXAMPLES)]
+pub struct CommitTreeArgs {
+    /// The tree object id, or a commit/branch/`HEAD` peeled to its tree
+    #[clap(value_name = "TREE")]
+    pub tree: String,
+
+    /// Parent commit (any revision). Repeat for a merge; omit for a root commit
+    #[clap(short = 'p', value_name = "PARENT")]
+    pub parents: Vec<String>,
+
+    /// Commit message. Repeat to add paragraphs. Read from stdin when neither
+    /// -m nor -F is given
+    #[clap(short = 'm', value_name = "MESSAGE", conflicts_with = "file")]
+    pub messages: Vec<String>,
+
+    /// Read the commit message from FILE (`-` for standard input)
+    #[clap(short = 'F', value_name = "FILE")]
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CommitTreeOutput {
+    commit: String,
+    tree: String,
+    parents: Vec<String>,
+}
+
+pub async fn execute(args: CommitTreeArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point. Resolves the tree and parents, writes a commit object
+/// authored and committed by the configured identity, and prints its id.
+///
+/// # Errors
+///
+/// Returns a fatal error when the tree or a parent does not resolve, the
+/// message source cannot be read, no identity is configured, or the object
+/// cannot be written. A parent given twice is ignored with a warning, like Git.
+pub async fn execute_safe(args: CommitTreeArgs, output: &OutputConfig) -> CliResult<()> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+
+    let tree_id = resolve_tree_ish(&args.tree).await?;
+
+    let mut parent_ids: Vec<ObjectHash> = Vec::with_capacity(args.parents.len());
+    for parent in &args.parents {
+        let id = util::get_commit_base(parent).await.map_err(|_| {
+            CliError::fatal(format!("{parent}: not a valid commit name"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget)
+        })?;
+        if parent_ids.contains(&id) {
+            if !output.quiet {
+                eprintln!("warning: duplicate parent {id} ignored");
+            }
+            continue;
+        }
+        parent_ids.push(id);
+    }
+
+    let message = read_message(&args)?;
+    let identity = resolve_committer_identity().await?;
+    let author = Signature::new(
+        SignatureType::Author,
+        identity.name.clone(),
+        identity.email.clone(),
+    );
+    let committer = Signature::new(SignatureType::Committer, identity.name, identity.email);
+
+    let commit = Commit::new(
+        author,
+        committer,
+        tree_id,
+        parent_ids.clone(),
+        &format_commit_msg(&message, None),
+    );
+    let data = commit.to_data().map_err(|error| {
+        CliError::fatal(format!("failed to serialize commit: {error}"))
+            .with_stable_code(StableErrorCode::InternalInvariant)
+    })?;
+    util::objects_storage()
+        .put(&commit.id, &data, commit.get_type())
+        .map_err(|error| {
+            CliError::fatal(format!("failed to write commit {}: {error}", commit.id))
+                .with_stable_code(StableErrorCode::IoWriteFailed)
+        })?;
+
+    if output.is_json() {
+        emit_json_data(
+            "commit-tree",
+            &CommitTreeOutput {
+                commit: commit.id.to_string(),
+                tree: tree_id.to_string(),
+                parents: parent_ids.iter().map(ToString::to_string).collect(),
+            },
+            output,
+        )
+    } else {
+        if !output.quiet {
+            println!("{}", commit.id);
+        }
+        Ok(())
+    }
+}
+
+/// The message from `-m` (paragraphs joined by a blank line), `-F`, or stdin.
+/// It is stored as given: commit-tree applies no cleanup, like Git.
+fn read_message(args: &CommitTreeArgs) -> CliResult<String> {
+    if !args.messages.is_empty() {
+        return Ok(args.messages.join("\n\n"));
+    }
+    match args.file.as_deref() {
+        Some("-") | None => {
+            let mut message = String::new();
+            std::io::stdin()
+                .read_to_string(&mut message)
+                .map_err(|error| {
+                    CliError::fatal(format!("failed to read commit message from stdin: {error}"))
+                        .with_stable_code(StableErrorCode::IoReadFailed)
+                })?;
+            Ok(message)
+        }
+        Some(path) => std::fs::read_to_string(path).map_err(|error| {
+            CliError::fatal(format!("could not read log file '{path}': {error}"))
+                .with_stable_code(StableErrorCode::IoReadFailed)
+        }),
+    }
+}
@@ -16,8 +16,9 @@ use crate::{
         config::{ConfigKv, ConfigKvEntry, is_sensitive_key, is_vault_internal_key},
         db::{create_database, establish_connection, get_db_conn_instance},
         vault::{
-            decrypt_token, encrypt_token, generate_pgp_key, generate_ssh_key_pair,
-            lazy_init_vault_for_scope, load_unseal_key_for_scope,
+            DEFAULT_PGP_KEY_NAME, decrypt_token, encrypt_token, generate_pgp_key,
+            generate_ssh_key_pair, lazy_init_vault_for_scope, load_unseal_key_for_scope,
+            pgp_public_key_config, signing_key_name,
         },
     },
     utils::{
@@ -2425,7 +2426,14 @@ async fn handle_generate_gpg_key(
         .map(String::from)
         .unwrap_or_else(|| "user@libra.local".to_string());
 
-    let public_key = generate_pgp_key(&storage, &unseal_key, &user_name, &user_email)
+    // A signing key goes under the name `user.signingkey` selects, so commit
+    // and tag signing pick it up.
+    let key_name = if is_signing {
+        signing_key_name().await
+    } else {
+        DEFAULT_PGP_KEY_NAME.to_string()
+    };
+    let public_key = generate_pgp_key(&storage, &unseal_key, &key_name, &user_name, &user_email)
         .await
         .map_err(|e| {
             CliError::from_legacy_string(format!("error: GPG key generation failed: {e}"))
@@ -2433,7 +2441,7 @@ async fn handle_generate_gpg_key(
 
     // Store pubkey under usage-specific dotted key
     let pubkey_config_key = if is_signing {
-        "vault.gpg.pubkey".to_string()
+        pgp_public_key_config(&key_name)
     } else {
         format!("vault.gpg.{usage}.pubkey")
     };
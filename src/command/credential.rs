@@ -14,7 +14,6 @@
 //!   on `fill`, and `store` refuses an already-expired `password_expiry_utc`.
 
 use std::{
-    collections::BTreeMap,
     io::{self, BufRead},
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -26,6 +25,7 @@ use sha2::{Digest, Sha256};
 use crate::{
     internal::{config::ConfigKv, vault},
     utils::{
+        credential_helper::{CredentialAttrs, read_attrs},
         error::{CliError, CliResult, StableErrorCode},
         output::OutputConfig,
         util,
@@ -62,19 +62,6 @@ pub enum CredentialCommand {
     Erase,
 }
 
-/// Parsed Git credential attributes (only the fields this helper uses).
-#[derive(Debug, Default)]
-struct CredentialAttrs {
-    protocol: String,
-    host: String,
-    path: String,
-    username: Option<String>,
-    password: Option<String>,
-    /// `password_expiry_utc` — a unix timestamp after which the password is
-    /// invalid (Git 2.30+).
-    password_expiry_utc: Option<u64>,
-}
-
 /// The encrypted-at-rest credential record.
 #[derive(Debug, Serialize, Deserialize)]
 struct StoredCredential {
@@ -265,7 +252,7 @@ fn routing_error(attrs: &CredentialAttrs, message: &str) -> CliError {
 /// Read the credential request line by line, stopping at the first blank line
 /// (the protocol terminator) or EOF. A `read_to_string` would deadlock, because
 /// Git keeps the helper's stdin open after the blank line.
-fn read_request() -> CliResult<String> {
+pub(crate) fn read_request() -> CliResult<String> {
     let stdin = io::stdin();
     let mut handle = stdin.lock();
     let mut request = String::new();
@@ -288,56 +275,6 @@ fn read_request() -> CliResult<String> {
     Ok(request)
 }
 
-/// Parse the Git credential key/value protocol. Reading stops at the first
-/// blank line (the protocol terminator). `url=` is expanded into
-/// protocol/host/path; explicit fields take precedence.
-fn read_attrs(input: &str) -> CliResult<CredentialAttrs> {
-    let mut fields: BTreeMap<String, String> = BTreeMap::new();
-    for line in input.lines() {
-        if line.is_empty() {
-            break;
-        }
-        let Some((key, value)) = line.split_once('=') else {
-            continue;
-        };
-        fields.insert(key.to_string(), value.to_string());
-    }
-
-    let mut attrs = CredentialAttrs::default();
-    if let Some(url) = fields.get("url") {
-        apply_url(&mut attrs, url);
-    }
-    if let Some(protocol) = fields.get("protocol") {
-        attrs.protocol = protocol.clone();
-    }
-    if let Some(host) = fields.get("host") {
-        attrs.host = host.clone();
-    }
-    if let Some(path) = fields.get("path") {
-        attrs.path = path.clone();
-    }
-    attrs.username = fields.get("username").filter(|v| !v.is_empty()).cloned();
-    attrs.password = fields.get("password").filter(|v| !v.is_empty()).cloned();
-    attrs.password_expiry_utc = fields
-        .get("password_expiry_utc")
-        .and_then(|value| value.parse::<u64>().ok());
-    Ok(attrs)
-}
-
-/// Best-effort split of a `url=` value into protocol/host/path.
-fn apply_url(attrs: &mut CredentialAttrs, url: &str) {
-    if let Some((protocol, rest)) = url.split_once("://") {
-        attrs.protocol = protocol.to_string();
-        let rest = rest.split_once('@').map(|(_, after)| after).unwrap_or(rest);
-        if let Some((host, path)) = rest.split_once('/') {
-            attrs.host = host.to_string();
-            attrs.path = path.to_string();
-        } else {
-            attrs.host = rest.to_string();
-        }
-    }
-}
-
 fn now_unix() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -0,0 +1,297 @@
+//! `libra credential-helper install | test | store | keychain` — configure the
+//! helper named by `credential.helper` and exercise it, plus the built-in
+//! helpers Libra ships.
+//!
+//! Helper resolution, the built-in `libra-store` / `libra-keychain` backends,
+//! and the HTTPS transport's use of them live in
+//! [`crate::utils::credential_helper`]; this module is the command surface.
+//! Like `libra credential`, passwords are never logged or echoed in errors.
+//! Only the protocol response of `get` carries them.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+use super::{
+    config::{ConfigScope, ScopedConfig},
+    credential::read_request,
+};
+use crate::{
+    internal::config::{LocalIdentityTarget, read_cascaded_config_value},
+    utils::{
+        credential_helper::{
+            Credential, CredentialAttrs, HELPER_KEY, Helper, HelperAction, KEYCHAIN_HELPER,
+            Keychain, STORE_HELPER, format_response, read_attrs,
+        },
+        error::{CliError, CliResult, StableErrorCode},
+        output::{OutputConfig, emit_json_data},
+    },
+};
+
+/// Host used by `test`; `.invalid` can never resolve to a real server.
+const TEST_HOST: &str = "libra-credential-helper-test.invalid";
+
+pub const CREDENTIAL_HELPER_EXAMPLES: &str = "\
+EXAMPLES:
+    libra credential-helper install                  Use the platform keychain (auto-detected)
+    libra credential-helper install libra-store      Use the plain-text store file
+    libra credential-helper install osxkeychain      Use Git's own git-credential-osxkeychain
+    libra credential-helper install '!pass-helper'   Use a shell command as the helper
+    libra credential-helper test                     Round-trip a dummy credential
+    printf 'protocol=https\\nhost=example.com\\n' | libra credential-helper store get";
+
+/// Configure and test `credential.helper`, and run the built-in helpers.
+#[derive(Parser, Debug)]
+#[command(after_help = CREDENTIAL_HELPER_EXAMPLES)]
+pub struct CredentialHelperArgs {
+    #[command(subcommand)]
+    pub command: CredentialHelperCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CredentialHelperCommand {
+    /// Set `credential.helper` in the global config. Without a helper, the
+    /// platform keychain is detected.
+    Install {
+        /// Helper to install: `libra-store`, `libra-keychain`, a Git helper
+        /// name, a program path, or `!<shell command>`.
+        helper: Option<String>,
+    },
+    /// Store, read back, and erase a dummy credential through a helper.
+    Test {
+        /// Helper to test instead of the configured `credential.helper`.
+        helper: Option<String>,
+    },
+    /// Built-in plain-text store helper (`libra-store`).
+    Store {
+        action: HelperAction,
+        /// Store file to use instead of `~/.libra-credentials`.
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+    },
+    /// Built-in platform keychain helper (`libra-keychain`: macOS `security`,
+    /// Linux libsecret).
+    Keychain { action: HelperAction },
+}
+
+#[derive(Debug, Serialize)]
+struct InstallOutput {
+    helper: String,
+    scope: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct TestOutput {
+    helper: String,
+    steps: Vec<&'static str>,
+}
+
+pub async fn execute(args: CredentialHelperArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point. `store` and `keychain` speak the Git credential protocol
+/// and ignore `--json`, like `libra credential`.
+pub async fn execute_safe(args: CredentialHelperArgs, output: &OutputConfig) -> CliResult<()> {
+    match args.command {
+        CredentialHelperCommand::Install { helper } => install(helper, output).await,
+        CredentialHelperCommand::Test { helper } => test(helper, output).await,
+        CredentialHelperCommand::Store { action, file } => {
+            serve(Helper::Store { file }, action, STORE_HELPER)
+        }
+        CredentialHelperCommand::Keychain { action } => {
+            let keychain = Keychain::detect().ok_or_else(no_keychain_error)?;
+            serve(Helper::Keychain(keychain), action, KEYCHAIN_HELPER)
+        }
+    }
+}
+
+/// `install`: validate the helper and write it to the global config.
+async fn install(helper: Option<String>, output: &OutputConfig) -> CliResult<()> {
+    let spec = match helper {
+        Some(spec) => spec,
+        None => {
+            Keychain::detect().ok_or_else(no_keychain_error)?;
+            KEYCHAIN_HELPER.to_string()
+        }
+    };
+    let parsed = Helper::parse(&spec).map_err(|message| {
+        CliError::command_usage(format!("invalid credential helper: {message}")).with_exit_code(128)
+    })?;
+    if let Helper::Program { program, .. } = &parsed
+        && program.contains('/')
+        && !Path::new(program).is_file()
+    {
+        return Err(
+            CliError::fatal(format!("credential helper '{program}' does not exist"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget),
+        );
+    }
+
+    // Replace rather than append: the helper chain is reset to this helper.
+    let write_error = |error: String| {
+        CliError::fatal(format!(
+            "failed to write {HELPER_KEY} to global config: {error}"
+        ))
+        .with_stable_code(StableErrorCode::IoWriteFailed)
+    };
+    ScopedConfig::unset_all(ConfigScope::Global, HELPER_KEY)
+        .await
+        .map_err(write_error)?;
+    ScopedConfig::set(ConfigScope::Global, HELPER_KEY, &spec, false)
+        .await
+        .map_err(write_error)?;
+
+    if output.is_json() {
+        return emit_json_data(
+            "credential-helper",
+            &InstallOutput {
+                helper: spec,
+                scope: "global",
+            },
+            output,
+        );
+    }
+    if !output.quiet {
+        println!("{HELPER_KEY} = {spec} (global)");
+    }
+    Ok(())
+}
+
+/// `test`: store, read back, and erase a dummy credential, then confirm it is
+/// gone.
+async fn test(helper: Option<String>, output: &OutputConfig) -> CliResult<()> {
+    let spec = match helper {
+        Some(spec) => spec,
+        None => read_cascaded_config_value(LocalIdentityTarget::CurrentRepo, HELPER_KEY)
+            .await
+            .map_err(|error| {
+                CliError::fatal(format!("failed to read {HELPER_KEY}: {error}"))
+                    .with_stable_code(StableErrorCode::IoReadFailed)
+            })?
+            .ok_or_else(|| {
+                CliError::fatal(format!("no {HELPER_KEY} is configured"))
+                    .with_stable_code(StableErrorCode::AuthMissingCredentials)
+                    .with_hint("run 'libra credential-helper install' first")
+            })?,
+    };
+    let helper = Helper::parse(&spec).map_err(|message| {
+        CliError::command_usage(format!("invalid credential helper: {message}")).with_exit_code(128)
+    })?;
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let expected = Credential {
+        username: "libra-test".to_string(),
+        password: format!("libra-test-{nanos}"),
+    };
+    let mut attrs = CredentialAttrs {
+        protocol: "https".to_string(),
+        host: TEST_HOST.to_string(),
+        ..Default::default()
+    };
+    let query = attrs.clone();
+    attrs.username = Some(expected.username.clone());
+    attrs.password = Some(expected.password.clone());
+
+    let step_error = |step: &str, detail: String| {
+        CliError::failure(format!(
+            "credential helper '{spec}' failed the round trip at {step}: {detail}"
+        ))
+        .with_exit_code(1)
+        .with_stable_code(StableErrorCode::AuthMissingCredentials)
+    };
+    let mut steps = Vec::new();
+
+    helper
+        .run_blocking(HelperAction::Store, attrs.clone())
+        .await
+        .map_err(|error| step_error("store", error.to_string()))?;
+    steps.push("store");
+
+    match helper.run_blocking(HelperAction::Get, query.clone()).await {
+        Ok(Some(found)) if found == expected => {}
+        Ok(_) => {
+            // Best effort: don't leave the dummy entry behind.
+            let _ = helper.run_blocking(HelperAction::Erase, attrs).await;
+            return Err(step_error(
+                "get",
+                "the stored credential was not returned".to_string(),
+            ));
+        }
+        Err(error) => return Err(step_error("get", error.to_string())),
+    }
+    steps.push("get");
+
+    helper
+        .run_blocking(HelperAction::Erase, attrs)
+        .await
+        .map_err(|error| step_error("erase", error.to_string()))?;
+    steps.push("erase");
+
+    match helper.run_blocking(HelperAction::Get, query).await {
+        Ok(Some(found)) if found == expected => {
+            return Err(step_error(
+                "verify",
+                "the credential is still returned after erase".to_string(),
+            ));
+        }
+        Ok(_) => {}
+        Err(error) => return Err(step_error("verify", error.to_string())),
+    }
+    steps.push("verify");
+
+    if output.is_json() {
+        return emit_json_data(
+            "credential-helper",
+            &TestOutput {
+                helper: spec,
+                steps,
+            },
+            output,
+        );
+    }
+    if !output.quiet {
+        println!("testing credential helper '{spec}' with https://{TEST_HOST}");
+        for step in steps {
+            println!("  {step:<7} ok");
+        }
+    }
+    Ok(())
+}
+
+/// Serve one protocol request from stdin with a built-in helper.
+fn serve(helper: Helper, action: HelperAction, name: &str) -> CliResult<()> {
+    let attrs = read_attrs(&read_request()?)?;
+    let found = helper.run(action, &attrs).map_err(|error| {
+        CliError::fatal(format!(
+            "{name} helper: {} failed: {error}",
+            action.as_str()
+        ))
+        .with_stable_code(StableErrorCode::IoWriteFailed)
+    })?;
+    if let Some(credential) = found {
+        print!("{}", format_response(&attrs, &credential));
+    }
+    Ok(())
+}
+
+fn no_keychain_error() -> CliError {
+    CliError::fatal("no supported platform keychain found")
+        .with_stable_code(StableErrorCode::Unsupported)
+        .with_hint(
+            "macOS needs 'security'; Linux needs a running Secret Service (GNOME Keyring, KWallet)",
+        )
+        .with_hint(
+            "or install the plain-text helper: 'libra credential-helper install libra-store'",
+        )
+}
@@ -31,7 +31,9 @@ use crate::{
     internal::{config::ConfigKv, head::Head},
     utils::{
         error::{CliError, CliResult, StableErrorCode},
+        gitattributes::{AttrValue, Attributes},
         ignore::{self, IgnorePolicy},
+        index_marks,
         object_ext::TreeExt,
         output::{ColorChoice, OutputConfig, ProgressMode, emit_json_data},
         pager::Pager,
@@ -58,6 +60,8 @@ EXAMPLES:
     libra diff --name-only -z               NUL-terminated changed-file list for scripts
     libra diff --cached --check             Warn about whitespace errors on added lines
     libra diff -R                           Reverse diff (swap additions and deletions)
+    libra diff --no-index old.txt new.txt   Compare two files on disk (works outside a repository)
+    libra diff --no-index --stat dir1 dir2  Compare two directories recursively
     libra --json diff --staged              Structured JSON output for agents";
 
 #[derive(Parser, Debug)]
@@ -330,6 +334,13 @@ pub struct DiffArgs {
     /// `--no-ext-diff` is given. Has no effect when `diff.external` is unset.
     #[clap(long = "ext-diff", overrides_with = "no_ext_diff")]
     pub ext_diff: bool,
+
+    /// Compare two paths on the filesystem instead of repository content, in
+    /// Git's patch format. Works outside a repository; two directories are
+    /// compared recursively, and a file against a directory is compared with
+    /// the same-named file inside it. Implies `--exit-code`.
+    #[clap(long = "no-index", conflicts_with_all = ["old", "new", "staged"])]
+    pub no_index: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -445,6 +456,10 @@ pub(crate) enum DiffError {
     #[error("--staged compares a single commit against the index; '{0}' is one revision too many")]
     StagedRevisionRange(String),
 
+    /// `--no-index` was given some number of paths other than two.
+    #[error("--no-index compares exactly two paths, got {0}")]
+    NoIndexPathCount(usize),
+
     /// `A...B` where both sides resolve but share no merge base.
     #[error("no merge base found for '{left}' and '{right}'")]
     NoMergeBase { left: String, right: String },
@@ -504,6 +519,9 @@ impl From<DiffError> for CliError {
             DiffError::TooManyRevisions(_) => CliError::fatal(message)
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
                 .with_hint("libra diff takes at most two revisions; put paths after '--'"),
+            DiffError::NoIndexPathCount(_) => CliError::command_usage(message)
+                .with_stable_code(StableErrorCode::CliInvalidArguments)
+                .with_hint("usage: libra diff --no-index [<options>] <path> <path>"),
             DiffError::StagedRevisionRange(_) => CliError::fatal(message)
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
                 .with_hint("drop --staged, or pass a single revision: libra diff --staged <commit>"),
@@ -521,6 +539,15 @@ pub async fn execute(args: DiffArgs) {
 }
 
 pub async fn execute_safe(args: DiffArgs, output: &OutputConfig) -> CliResult<()> {
+    if args.no_index {
+        // Like Git, `--no-index` always reports differences through the exit code.
+        let mut args = args;
+        args.exit_code = true;
+        validate_diff_algorithm(&args).map_err(CliError::from)?;
+        let mut result = run_diff_no_index(&args).map_err(CliError::from)?;
+        apply_word_diff(&args, &mut result, output, io::stdout().is_terminal())?;
+        return render_diff_output(&args, &result, output);
+    }
     if util::require_repo().is_err() {
         return Err(CliError::from(DiffError::NotInRepo));
     }
@@ -1304,17 +1331,7 @@ async fn run_diff(args: &DiffArgs, output: &OutputConfig) -> Result<DiffOutput,
     // `--ignore-blank-lines` COMPOSES with a whitespace flag: the diff and the
     // blank classification both run through the normalizer (matching Git).
     let regen_context = args.unified.unwrap_or(3);
-    let ws_normalize: Option<fn(&str) -> String> = if args.ignore_all_space {
-        Some(normalize_ignore_all_space)
-    } else if args.ignore_space_change {
-        Some(normalize_ignore_space_change)
-    } else if args.ignore_space_at_eol {
-        Some(normalize_ignore_space_at_eol)
-    } else if args.ignore_cr_at_eol {
-        Some(normalize_ignore_cr_at_eol)
-    } else {
-        None
-    };
+    let ws_normalize = whitespace_normalizer(args);
     let rediffs = ws_normalize.is_some() || args.ignore_blank_lines;
 
     // `--relative` restricts WHICH files are diffed; apply that restriction now —
@@ -1356,54 +1373,50 @@ async fn run_diff(args: &DiffArgs, output: &OutputConfig) -> Result<DiffOutput,
     // bytes. Skipped under `--check` (it scans raw added lines) and when an
     // external driver is active (that takes precedence). The post-pass below then
     // leaves textconv'd files alone.
+    let attributes = Attributes::for_worktree().await;
     let textconv_paths: std::collections::HashSet<String> =
         if !args.no_textconv && !args.check && external_command.is_none() {
-            let drivers = extract_diff_drivers(&path::attributes());
-            if drivers.is_empty() {
-                std::collections::HashSet::new()
-            } else {
-                let mut command_cache: HashMap<String, Option<String>> = HashMap::new();
-                // Per file: the (old-side, new-side) textconv command. A rename's
-                // old side is at `rename_from` and may resolve a different driver
-                // than the new side (Git resolves textconv per blob/path), so each
-                // side is looked up independently.
-                let mut path_commands: HashMap<String, (Option<String>, Option<String>)> =
-                    HashMap::new();
-                for file in &files {
-                    let new_path = PathBuf::from(&file.path);
-                    let old_path = file
-                        .rename_from
-                        .as_deref()
-                        .map(PathBuf::from)
-                        .unwrap_or_else(|| new_path.clone());
-                    let new_driver = diff_driver_for_path(&drivers, &new_path);
-                    let new_command =
-                        resolve_textconv_command(new_driver.as_deref(), &mut command_cache).await;
-                    let old_command = if old_path == new_path {
-                        new_command.clone()
-                    } else {
-                        let old_driver = diff_driver_for_path(&drivers, &old_path);
-                        resolve_textconv_command(old_driver.as_deref(), &mut command_cache).await
-                    };
-                    if old_command.is_some() || new_command.is_some() {
-                        path_commands.insert(file.path.clone(), (old_command, new_command));
-                    }
-                }
-                if path_commands.is_empty() {
-                    std::collections::HashSet::new()
+            let mut command_cache: HashMap<String, Option<String>> = HashMap::new();
+            // Per file: the (old-side, new-side) textconv command. A rename's
+            // old side is at `rename_from` and may resolve a different driver
+            // than the new side (Git resolves textconv per blob/path), so each
+            // side is looked up independently.
+            let mut path_commands: HashMap<String, (Option<String>, Option<String>)> =
+                HashMap::new();
+            for file in &files {
+                let new_path = PathBuf::from(&file.path);
+                let old_path = file
+                    .rename_from
+                    .as_deref()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| new_path.clone());
+                let new_driver = diff_driver_for_path(&attributes, &new_path);
+                let new_command =
+                    resolve_textconv_command(new_driver.as_deref(), &mut command_cache).await;
+                let old_command = if old_path == new_path {
+                    new_command.clone()
                 } else {
-                    apply_textconv(
-                        &mut files,
-                        &path_commands,
-                        &first_map,
-                        &second_map,
-                        &ext_worktree_entries,
-                        regen_context,
-                        ws_normalize,
-                        args.ignore_blank_lines,
-                    )?
+                    let old_driver = diff_driver_for_path(&attributes, &old_path);
+                    resolve_textconv_command(old_driver.as_deref(), &mut command_cache).await
+                };
+                if old_command.is_some() || new_command.is_some() {
+                    path_commands.insert(file.path.clone(), (old_command, new_command));
                 }
             }
+            if path_commands.is_empty() {
+                std::collections::HashSet::new()
+            } else {
+                apply_textconv(
+                    &mut files,
+                    &path_commands,
+                    &first_map,
+                    &second_map,
+                    &ext_worktree_entries,
+                    regen_context,
+                    ws_normalize,
+                    args.ignore_blank_lines,
+                )?
+            }
         } else {
             std::collections::HashSet::new()
         };
@@ -1412,15 +1425,28 @@ async fn run_diff(args: &DiffArgs, output: &OutputConfig) -> Result<DiffOutput,
     // `Binary files … differ` (or, with `--binary`, a `GIT binary patch`) instead
     // of a content diff. `--text` forces the content diff; `--check` and an active
     // external driver take over the body, and textconv'd files are already text.
-    // The context/whitespace post-pass below then skips binary files.
+    // The context/whitespace post-pass below then skips binary files. A path
+    // whose `diff` attribute is unset (`-diff`, or the `binary` macro) is binary
+    // regardless of its content.
     let mut binary_patch = false;
     if !args.text && !args.check && external_command.is_none() {
+        let attr_binary: std::collections::HashSet<String> = files
+            .iter()
+            .filter(|file| {
+                let old_path = file.rename_from.as_deref().unwrap_or(&file.path);
+                [file.path.as_str(), old_path]
+                    .iter()
+                    .any(|path| attributes.get(Path::new(path), "diff") == Some(AttrValue::Unset))
+            })
+            .map(|file| file.path.clone())
+            .collect();
         binary_patch = apply_binary_detection(
             &mut files,
             &first_map,
             &second_map,
             &ext_worktree_entries,
             &textconv_paths,
+            &attr_binary,
             args.binary,
         )?;
     } else if args.text && !args.check && external_command.is_none() {
@@ -1504,25 +1530,13 @@ async fn run_diff(args: &DiffArgs, output: &OutputConfig) -> Result<DiffOutput,
                 let path = PathBuf::from(&file.path);
                 let old_text = blob_text(&first_map, &path);
                 let new_text = blob_text(&second_map, &path);
-                // `--ignore-blank-lines` composes with a whitespace normalizer when
-                // both are given (matching `git diff -w --ignore-blank-lines`).
-                let body = if args.ignore_blank_lines {
-                    match ws_normalize {
-                        Some(normalize) => compute_unified_hunks_ignore_blank_normalized(
-                            &old_text,
-                            &new_text,
-                            regen_context,
-                            normalize,
-                        ),
-                        None => {
-                            compute_unified_hunks_ignore_blank(&old_text, &new_text, regen_context)
-                        }
-                    }
-                } else if let Some(normalize) = ws_normalize {
-                    compute_unified_hunks_normalized(&old_text, &new_text, regen_context, normalize)
-                } else {
-                    compute_unified_hunks(&old_text, &new_text, regen_context)
-                };
+                let body = compute_unified_hunks_with_rules(
+                    &old_text,
+                    &new_text,
+                    regen_context,
+                    ws_normalize,
+                    args.ignore_blank_lines,
+                );
                 // No change survives the rule. Git still reports an added/deleted
                 // filepair (header, zero counts, no hunk) even when its only content
                 // is blank lines — only a modification disappears entirely.
@@ -1610,6 +1624,251 @@ async fn run_diff(args: &DiffArgs, output: &OutputConfig) -> Result<DiffOutput,
     })
 }
 
+/// The line normalizer for the whitespace-ignoring flags, if any is set:
+/// `-w` > `-b` > `--ignore-space-at-eol` > `--ignore-cr-at-eol` (matching Git).
+fn whitespace_normalizer(args: &DiffArgs) -> Option<fn(&str) -> String> {
+    if args.ignore_all_space {
+        Some(normalize_ignore_all_space)
+    } else if args.ignore_space_change {
+        Some(normalize_ignore_space_change)
+    } else if args.ignore_space_at_eol {
+        Some(normalize_ignore_space_at_eol)
+    } else if args.ignore_cr_at_eol {
+        Some(normalize_ignore_cr_at_eol)
+    } else {
+        None
+    }
+}
+
+/// `diff --no-index`: compare two files, or two directories recursively, on
+/// the filesystem. No repository is needed; blob ids on the `index` lines are
+/// computed from the file content. A pair with identical content and mode is
+/// omitted; a file missing on one side is an add or a delete.
+fn run_diff_no_index(args: &DiffArgs) -> Result<DiffOutput, DiffError> {
+    let paths: Vec<&String> = args.pathspec.iter().chain(&args.after_dashdash).collect();
+    let [old, new] = paths[..] else {
+        return Err(DiffError::NoIndexPathCount(paths.len()));
+    };
+    let (old, new) = if args.reverse { (new, old) } else { (old, new) };
+    let (old, new) = (PathBuf::from(old), PathBuf::from(new));
+    for path in [&old, &new] {
+        std::fs::symlink_metadata(path).map_err(|e| DiffError::FileRead {
+            path: path.display().to_string(),
+            detail: e.to_string(),
+        })?;
+    }
+
+    let pairs: Vec<(Option<PathBuf>, Option<PathBuf>)> = match (old.is_dir(), new.is_dir()) {
+        (true, true) => {
+            let old_files = no_index_files(&old)?;
+            let new_files = no_index_files(&new)?;
+            let relative: std::collections::BTreeSet<&PathBuf> =
+                old_files.iter().chain(&new_files).collect();
+            relative
+                .into_iter()
+                .map(|rel| {
+                    (
+                        old_files.contains(rel).then(|| old.join(rel)),
+                        new_files.contains(rel).then(|| new.join(rel)),
+                    )
+                })
+                .collect()
+        }
+        // Like Git, a file is compared against the same-named file inside the
+        // directory given on the other side.
+        (true, false) => {
+            let name = new.file_name().map(PathBuf::from).unwrap_or_default();
+            vec![(Some(old.join(name)), Some(new.clone()))]
+        }
+        (false, true) => {
+            let name = old.file_name().map(PathBuf::from).unwrap_or_default();
+            vec![(Some(old.clone()), Some(new.join(name)))]
+        }
+        (false, false) => vec![(Some(old.clone()), Some(new.clone()))],
+    };
+
+    let mut files = Vec::new();
+    for (old_file, new_file) in pairs {
+        if let Some(file) = no_index_file_diff(args, old_file.as_deref(), new_file.as_deref())? {
+            files.push(file);
+        }
+    }
+
+    let total_insertions = files.iter().map(|file| file.insertions).sum();
+    let total_deletions = files.iter().map(|file| file.deletions).sum();
+    let files_changed = files.len();
+    Ok(DiffOutput {
+        old_ref: old.display().to_string(),
+        new_ref: new.display().to_string(),
+        files,
+        total_insertions,
+        total_deletions,
+        files_changed,
+        external_diff_applied: false,
+        binary_patch: false,
+    })
+}
+
+/// Every file and symlink under `root`, relative to it. Symlinks are not
+/// followed.
+fn no_index_files(root: &Path) -> Result<HashSet<PathBuf>, DiffError> {
+    let mut files = HashSet::new();
+    for entry in walkdir::WalkDir::new(root).min_depth(1) {
+        let entry = entry.map_err(|e| DiffError::FileRead {
+            path: root.display().to_string(),
+            detail: e.to_string(),
+        })?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        if let Ok(relative) = entry.path().strip_prefix(root) {
+            files.insert(relative.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+/// Content and Git mode of a file for `--no-index`: a symlink is its target
+/// (`120000`), a regular file its bytes (`100755` when executable).
+fn read_no_index_file(path: &Path) -> Result<(Vec<u8>, &'static str), DiffError> {
+    let read_error = |e: std::io::Error| DiffError::FileRead {
+        path: path.display().to_string(),
+        detail: e.to_string(),
+    };
+    let meta = std::fs::symlink_metadata(path).map_err(read_error)?;
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(path).map_err(read_error)?;
+        return Ok((target.to_string_lossy().into_owned().into_bytes(), "120000"));
+    }
+    let content = std::fs::read(path).map_err(read_error)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        if meta.permissions().mode() & 0o111 != 0 {
+            return Ok((content, "100755"));
+        }
+    }
+    Ok((content, "100644"))
+}
+
+/// The patch for one `--no-index` pair, or `None` when both sides match (or
+/// differ only in changes the whitespace/blank-line flags ignore).
+fn no_index_file_diff(
+    args: &DiffArgs,
+    old: Option<&Path>,
+    new: Option<&Path>,
+) -> Result<Option<DiffFileStat>, DiffError> {
+    let old_side = old.map(read_no_index_file).transpose()?;
+    let new_side = new.map(read_no_index_file).transpose()?;
+    if old_side == new_side {
+        return Ok(None);
+    }
+    // Git shows the given paths without a leading `/`.
+    let display = |path: &Path| {
+        path.to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches('/')
+            .to_string()
+    };
+    let (Some(old_name), Some(new_name)) = (old.or(new).map(display), new.or(old).map(display))
+    else {
+        return Ok(None);
+    };
+
+    let short_id = |side: &Option<(Vec<u8>, &'static str)>| match side {
+        Some((content, _)) => {
+            calculate_object_hash(ObjectType::Blob, content).to_string()[..7].to_string()
+        }
+        None => "0000000".to_string(),
+    };
+    let mut header = format!("diff --git a/{old_name} b/{new_name}\n");
+    let status = match (&old_side, &new_side) {
+        (None, Some((_, mode))) => {
+            header.push_str(&format!("new file mode {mode}\n"));
+            "added"
+        }
+        (Some((_, mode)), None) => {
+            header.push_str(&format!("deleted file mode {mode}\n"));
+            "deleted"
+        }
+        (Some((_, old_mode)), Some((_, new_mode))) if old_mode != new_mode => {
+            header.push_str(&format!("old mode {old_mode}\nnew mode {new_mode}\n"));
+            "modified"
+        }
+        _ => "modified",
+    };
+    let old_content = old_side.as_ref().map(|(content, _)| content.as_slice());
+    let new_content = new_side.as_ref().map(|(content, _)| content.as_slice());
+    let mode_only = old_content == new_content;
+
+    let mut file = DiffFileStat {
+        path: if status == "deleted" {
+            old_name.clone()
+        } else {
+            new_name.clone()
+        },
+        status: status.to_string(),
+        insertions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+        raw_diff: String::new(),
+        rename_from: None,
+        similarity: None,
+        binary: None,
+    };
+    if mode_only {
+        file.raw_diff = header;
+        return Ok(Some(file));
+    }
+
+    header.push_str(&format!(
+        "index {}..{}",
+        short_id(&old_side),
+        short_id(&new_side)
+    ));
+    match (&old_side, &new_side) {
+        (Some((_, old_mode)), Some((_, new_mode))) if old_mode == new_mode => {
+            header.push_str(&format!(" {old_mode}\n"));
+        }
+        _ => header.push('\n'),
+    }
+    let old_label = if old_side.is_some() {
+        format!("a/{old_name}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_label = if new_side.is_some() {
+        format!("b/{new_name}")
+    } else {
+        "/dev/null".to_string()
+    };
+
+    let old_bytes = old_content.unwrap_or_default();
+    let new_bytes = new_content.unwrap_or_default();
+    if !args.text && (old_bytes.contains(&0) || new_bytes.contains(&0)) {
+        file.raw_diff = format!("{header}Binary files {old_label} and {new_label} differ\n");
+        file.binary = Some((old_bytes.len() as u64, new_bytes.len() as u64));
+        return Ok(Some(file));
+    }
+
+    let body = compute_unified_hunks_with_rules(
+        &String::from_utf8_lossy(old_bytes),
+        &String::from_utf8_lossy(new_bytes),
+        args.unified.unwrap_or(3),
+        whitespace_normalizer(args),
+        args.ignore_blank_lines,
+    );
+    if body.trim().is_empty() && status == "modified" {
+        return Ok(None);
+    }
+    let (insertions, deletions) = count_body_changes(&body);
+    file.insertions = insertions;
+    file.deletions = deletions;
+    file.raw_diff = format!("{header}--- {old_label}\n+++ {new_label}\n{body}");
+    file.hunks = parse_diff_hunks(&file.raw_diff);
+    Ok(Some(file))
+}
+
 #[derive(Debug)]
 struct DiffSide {
     label: String,
@@ -1641,7 +1900,12 @@ fn get_files_blobs(
         .collect()
 }
 
-fn index_hash_if_worktree_stat_matches(path: &Path, index: &Index) -> Option<ObjectHash> {
+/// The staged hash of `path` when its worktree stat still matches the index
+/// entry, i.e. the file provably has not changed since it was staged.
+pub(crate) fn index_hash_if_worktree_stat_matches(
+    path: &Path,
+    index: &Index,
+) -> Option<ObjectHash> {
     let entry = index.get(path.to_str()?, 0)?;
     let absolute = util::workdir_to_absolute(path);
     let metadata = std::fs::symlink_metadata(&absolute).ok()?;
@@ -1808,10 +2072,22 @@ async fn resolve_diff_side(
             })
         } else {
             let files = get_worktree_diff_files(index)?;
-            let blobs = get_files_blobs(&files, index, IgnorePolicy::Respect)?;
+            let mut blobs = get_files_blobs(&files, index, IgnorePolicy::Respect)?;
+            // Skip-worktree entries (marked by hand or by the sparse checkout)
+            // and assume-unchanged entries compare as their index version.
+            let unchanged = index_marks::unchanged_paths(index);
+            blobs.retain(|(path, _)| !path.to_str().is_some_and(|p| unchanged.contains(p)));
+            let worktree_entries = blobs.iter().cloned().collect();
+            blobs.extend(
+                index
+                    .tracked_entries(0)
+                    .into_iter()
+                    .filter(|entry| unchanged.contains(&entry.name))
+                    .map(|entry| (PathBuf::from(&entry.name), entry.hash)),
+            );
             Ok(DiffSide {
                 label: "working tree".to_string(),
-                worktree_entries: blobs.iter().cloned().collect(),
+                worktree_entries,
                 blobs,
             })
         }
@@ -2115,7 +2391,7 @@ fn fnv1a(bytes: &[u8]) -> u64 {
 /// Git's similarity score (0..60000): common chunk bytes * 60000 / max file size.
 /// Two empty files are identical (full score). The displayed percent is
 /// `score / 600`.
-fn similarity_score(old: &[u8], new: &[u8]) -> u32 {
+pub(crate) fn similarity_score(old: &[u8], new: &[u8]) -> u32 {
     let max_size = old.len().max(new.len()) as u64;
     if max_size == 0 {
         return 60000;
@@ -2131,6 +2407,16 @@ fn similarity_score(old: &[u8], new: &[u8]) -> u32 {
     ((common * 60000) / max_size) as u32
 }
 
+/// Git's default `diff.renameLimit`: inexact rename detection is skipped when
+/// the deleted x added candidate matrix exceeds `RENAME_LIMIT` squared, since
+/// every pair loads and scores both blobs. Exact renames are still found.
+pub(crate) const RENAME_LIMIT: usize = 1000;
+
+/// Whether `deleted` x `added` inexact rename candidates fit in [`RENAME_LIMIT`].
+pub(crate) fn within_rename_limit(deleted: usize, added: usize) -> bool {
+    deleted.saturating_mul(added) <= RENAME_LIMIT * RENAME_LIMIT
+}
+
 /// Detect renames among the deleted + added files and fold each matched pair into
 /// a single rename entry (`-M`). Exact (same blob id) pairs are matched first,
 /// then the best inexact pairs whose similarity meets the threshold. Each side is
@@ -2195,10 +2481,13 @@ fn apply_rename_detection(
     // Like Git, a matching basename breaks ties so an ambiguous equal-score set
     // prefers same-name pairings. `-M100%` (threshold == MAX_SCORE) is exact-only:
     // Git skips inexact detection entirely, so a 100%-similar but non-identical
-    // pair (e.g. reordered lines) must NOT be folded.
+    // pair (e.g. reordered lines) must NOT be folded. Past [`RENAME_LIMIT`] the
+    // quadratic scoring is skipped, as Git does.
     const MAX_SCORE: u32 = 60000;
     let basename = |path: &str| path.rsplit('/').next().unwrap_or(path).to_string();
-    if threshold < MAX_SCORE {
+    let remaining_deleted = deleted.iter().filter(|&&di| !used_del[di]).count();
+    let remaining_added = added.iter().filter(|&&ai| !used_add[ai]).count();
+    if threshold < MAX_SCORE && within_rename_limit(remaining_deleted, remaining_added) {
         // (score, same_basename, di, ai)
         let mut candidates: Vec<(u32, bool, usize, usize)> = Vec::new();
         for &di in &deleted {
@@ -2346,57 +2635,14 @@ fn build_rename_entry(
     }
 }
 
-/// Parse `.libra_attributes` for `diff` attributes, returning `(pattern, value)`
-/// pairs in file order. `value` is `Some(driver)` for `diff=<driver>` and `None`
-/// for `-diff` / `!diff` / a bare `diff` (which unset or reset the driver, so a
-/// later such entry clears an earlier `diff=<driver>` under last-match-wins).
-fn extract_diff_drivers(attr_path: &Path) -> Vec<(String, Option<String>)> {
-    let Ok(content) = std::fs::read_to_string(attr_path) else {
-        return Vec::new();
-    };
-    let mut out = Vec::new();
-    for line in content.lines() {
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-        let mut tokens = line.split_whitespace();
-        let Some(pattern) = tokens.next() else {
-            continue;
-        };
-        for token in tokens {
-            if let Some(driver) = token.strip_prefix("diff=") {
-                if !driver.is_empty() {
-                    out.push((pattern.to_string(), Some(driver.to_string())));
-                }
-            } else if token == "diff" || token == "-diff" || token == "!diff" {
-                // Set/unset/unspecified: no named driver → clears any earlier one.
-                out.push((pattern.to_string(), None));
-            }
-        }
+/// The diff driver assigned to `path` by the `diff=<driver>` attribute (from
+/// `.gitattributes`/`.libra_attributes`, last match wins). `-diff`, `!diff`,
+/// and a bare `diff` name no driver.
+fn diff_driver_for_path(attributes: &Attributes, path: &Path) -> Option<String> {
+    match attributes.get(path, "diff") {
+        Some(AttrValue::Value(driver)) if !driver.is_empty() => Some(driver),
+        _ => None,
     }
-    out
-}
-
-/// The diff driver assigned to `path` by `.libra_attributes` — the LAST matching
-/// `diff` attribute wins (Git's attribute semantics); a matching unset/reset
-/// (`-diff`/`!diff`/bare `diff`) clears any earlier `diff=<driver>`, so this
-/// returns `None` for it.
-fn diff_driver_for_path(drivers: &[(String, Option<String>)], path: &Path) -> Option<String> {
-    let workdir = util::working_dir();
-    let mut result = None;
-    for (pattern, value) in drivers {
-        let mut builder = ::ignore::gitignore::GitignoreBuilder::new(&workdir);
-        if builder.add_line(None, pattern).is_err() {
-            continue;
-        }
-        if let Ok(gi) = builder.build()
-            && matches!(gi.matched(path, false), ::ignore::Match::Ignore(_))
-        {
-            result = value.clone();
-        }
-    }
-    result
 }
 
 /// Run a `diff.<driver>.textconv` command on `content`: Git writes the blob to a
@@ -2767,6 +3013,7 @@ fn apply_binary_detection(
     second_map: &HashMap<PathBuf, ObjectHash>,
     worktree_entries: &HashMap<PathBuf, ObjectHash>,
     textconv_paths: &std::collections::HashSet<String>,
+    attr_binary: &std::collections::HashSet<String>,
     want_patch: bool,
 ) -> Result<bool, DiffError> {
     let mut emitted_patch = false;
@@ -2795,7 +3042,8 @@ fn apply_binary_detection(
             .raw_diff
             .lines()
             .any(|line| line == "Binary files differ");
-        let raw_signal = file.raw_diff.contains('\0') || bare_binary;
+        let raw_signal =
+            file.raw_diff.contains('\0') || bare_binary || attr_binary.contains(&file.path);
         let old_path = file.rename_from.as_deref().unwrap_or(&file.path);
         // A rename's body was reconstructed via lossy UTF-8 (`build_rename_entry`),
         // so the raw-diff signal is unreliable for it — scan the actual blob bytes
@@ -3456,6 +3704,28 @@ fn compute_unified_hunks(old_text: &str, new_text: &str, context: usize) -> Stri
     assemble_unified_hunks(&changes, context, old_text.len() + new_text.len())
 }
 
+/// The hunk body under the whitespace (`ws_normalize`) and `--ignore-blank-lines`
+/// rules; `--ignore-blank-lines` composes with a whitespace normalizer when both
+/// are given (matching `git diff -w --ignore-blank-lines`).
+fn compute_unified_hunks_with_rules(
+    old_text: &str,
+    new_text: &str,
+    context: usize,
+    ws_normalize: Option<fn(&str) -> String>,
+    ignore_blank_lines: bool,
+) -> String {
+    match (ignore_blank_lines, ws_normalize) {
+        (true, Some(normalize)) => {
+            compute_unified_hunks_ignore_blank_normalized(old_text, new_text, context, normalize)
+        }
+        (true, None) => compute_unified_hunks_ignore_blank(old_text, new_text, context),
+        (false, Some(normalize)) => {
+            compute_unified_hunks_normalized(old_text, new_text, context, normalize)
+        }
+        (false, None) => compute_unified_hunks(old_text, new_text, context),
+    }
+}
+
 /// Normalizer for `-w` / `--ignore-all-space`: drop every whitespace character
 /// so two lines compare equal iff they match after all whitespace is removed.
 fn normalize_ignore_all_space(line: &str) -> String {
@@ -4093,6 +4363,7 @@ pub(crate) async fn staged_diff_text() -> Result<String, DiffError> {
         textconv: false,
         no_textconv: false,
         ext_diff: false,
+        no_index: false,
     };
     let result = run_diff(&args, &OutputConfig::default()).await?;
     Ok(format_unified_diff(&result))
@@ -4400,6 +4671,14 @@ mod test {
     use super::*;
     use crate::utils::test;
 
+    #[test]
+    fn rename_limit_bounds_the_inexact_candidate_matrix() {
+        assert!(within_rename_limit(RENAME_LIMIT, RENAME_LIMIT));
+        assert!(within_rename_limit(1, RENAME_LIMIT * RENAME_LIMIT));
+        assert!(!within_rename_limit(RENAME_LIMIT + 1, RENAME_LIMIT));
+        assert!(!within_rename_limit(usize::MAX, 2));
+    }
+
     #[test]
     fn parse_rename_score_matches_git_semantics() {
         // Bare integer = digits after an implied `0.` (Git's reading).
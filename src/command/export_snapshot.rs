@@ -0,0 +1,171 @@
+//! `libra export-snapshot` — one JSON document with the repository context an
+//! AI agent needs before acting (a Libra extension; Git has no equivalent):
+//! HEAD and branch, the `status --json` data, the most recent commits in
+//! `log --json` shape, the staged diff, and the active external-agent session.
+//! Each section is produced by the owning command's internals, so the
+//! snapshot never drifts from what `status`, `log`, and `diff --staged` print.
+
+use std::path::Path;
+
+use clap::Parser;
+use sea_orm::{ConnectionTrait, Statement};
+use serde::Serialize;
+
+use crate::{
+    command::{diff, log, status},
+    internal::{db::get_db_conn_instance, head::Head},
+    utils::{
+        error::{CliError, CliResult},
+        output::{OutputConfig, emit_json_data},
+        util,
+    },
+};
+
+pub const EXPORT_SNAPSHOT_EXAMPLES: &str = "\
+EXAMPLES:
+    libra --json export-snapshot           Full context document for an agent
+    libra --json export-snapshot -n 3      Only the three newest commits
+    libra --json export-snapshot --no-diff Leave the staged diff out
+    libra export-snapshot                  Human-readable summary of the same data";
+
+/// Export HEAD, status, recent commits, and the staged diff as one document.
+#[derive(Parser, Debug)]
+#[command(after_help = EXPORT_SNAPSHOT_EXAMPLES)]
+pub struct ExportSnapshotArgs {
+    /// Number of recent commits to include.
+    #[clap(
+        short = 'n',
+        long = "max-count",
+        value_name = "N",
+        default_value_t = 10
+    )]
+    pub max_count: usize,
+
+    /// Leave the staged diff out (`staged_diff` is `null`).
+    #[clap(long)]
+    pub no_diff: bool,
+}
+
+/// The `data` object of `libra --json export-snapshot`.
+#[derive(Debug, Serialize)]
+pub struct ExportSnapshot {
+    /// Commit id `HEAD` points at; `null` before the first commit.
+    pub head: Option<String>,
+    /// Current branch; `null` on a detached HEAD.
+    pub branch: Option<String>,
+    /// The newest `active` external-agent session working in this repository.
+    pub session_id: Option<String>,
+    /// Same object as `data` in `libra --json status`.
+    pub status: serde_json::Value,
+    /// Newest first, same entries as `libra --json log -n <N>`.
+    pub recent_commits: Vec<log::LogCommitEntry>,
+    /// Uncolored unified diff of index vs `HEAD`; `null` with `--no-diff`.
+    pub staged_diff: Option<String>,
+}
+
+pub async fn execute(args: ExportSnapshotArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+    }
+}
+
+pub async fn execute_safe(args: ExportSnapshotArgs, output: &OutputConfig) -> CliResult<()> {
+    if util::require_repo().is_err() {
+        return Err(CliError::repo_not_found());
+    }
+    let snapshot = collect_snapshot(args.max_count, !args.no_diff).await?;
+    if output.is_json() {
+        return emit_json_data("export-snapshot", &snapshot, output);
+    }
+    if !output.quiet {
+        print_summary(&snapshot);
+    }
+    Ok(())
+}
+
+/// Gather the snapshot for the repository in the current directory.
+pub async fn collect_snapshot(max_count: usize, include_diff: bool) -> CliResult<ExportSnapshot> {
+    let head = Head::current_commit().await;
+    let branch = match Head::current().await {
+        Head::Branch(name) => Some(name),
+        Head::Detached(_) => None,
+    };
+    let recent_commits = match head {
+        Some(_) if max_count > 0 => log::recent_log_entries(max_count).await?,
+        _ => Vec::new(),
+    };
+    let staged_diff = if include_diff {
+        Some(diff::staged_diff_text().await.map_err(CliError::from)?)
+    } else {
+        None
+    };
+    Ok(ExportSnapshot {
+        head: head.map(|oid| oid.to_string()),
+        branch,
+        session_id: active_session_id().await,
+        status: status::collect_status_json_data().await?,
+        recent_commits,
+        staged_diff,
+    })
+}
+
+/// Best effort: databases created before the agent-capture migration have
+/// no `agent_session` table, which simply means there is no session.
+async fn active_session_id() -> Option<String> {
+    let conn = get_db_conn_instance().await;
+    let rows = conn
+        .query_all(Statement::from_string(
+            conn.get_database_backend(),
+            "SELECT session_id, working_dir FROM agent_session \
+             WHERE state = 'active' ORDER BY last_event_at DESC",
+        ))
+        .await
+        .ok()?;
+    let workdir = util::working_dir();
+    let workdir = workdir.canonicalize().unwrap_or(workdir);
+    rows.into_iter().find_map(|row| {
+        let session_id = row.try_get_by::<String, _>("session_id").ok()?;
+        let working_dir = row.try_get_by::<String, _>("working_dir").ok()?;
+        let working_dir = Path::new(&working_dir);
+        let working_dir = working_dir
+            .canonicalize()
+            .unwrap_or_else(|_| working_dir.to_path_buf());
+        working_dir.starts_with(&workdir).then_some(session_id)
+    })
+}
+
+fn print_summary(snapshot: &ExportSnapshot) {
+    let head = snapshot.head.as_deref().unwrap_or("(no commits)");
+    match &snapshot.branch {
+        Some(branch) => println!("HEAD:     {head} ({branch})"),
+        None => println!("HEAD:     {head} (detached)"),
+    }
+    println!(
+        "Session:  {}",
+        snapshot.session_id.as_deref().unwrap_or("(none)")
+    );
+    let count = |section: &str, key: &str| {
+        snapshot.status[section][key]
+            .as_array()
+            .map_or(0, |paths| paths.len())
+    };
+    let staged = ["new", "modified", "deleted", "renamed"]
+        .iter()
+        .map(|key| count("staged", key))
+        .sum::<usize>();
+    let unstaged = ["modified", "deleted", "renamed"]
+        .iter()
+        .map(|key| count("unstaged", key))
+        .sum::<usize>();
+    let untracked = snapshot.status["untracked"]
+        .as_array()
+        .map_or(0, |paths| paths.len());
+    println!("Status:   {staged} staged, {unstaged} unstaged, {untracked} untracked");
+    println!("Recent commits:");
+    for commit in &snapshot.recent_commits {
+        println!("  {} {}", commit.short_hash, commit.subject);
+    }
+    if let Some(diff) = &snapshot.staged_diff {
+        println!("Staged diff: {} line(s)", diff.lines().count());
+    }
+}
@@ -9,14 +9,19 @@ use std::{
     io::{self, Error as IoError, Write},
     path::{Path, PathBuf},
     str::FromStr,
+    sync::{Arc, Mutex},
     time::{Duration, Instant, SystemTime},
 };
 
 use clap::Parser;
 use git_internal::{
     errors::GitError,
-    hash::{HashKind, ObjectHash, get_hash_kind},
-    internal::object::commit::Commit,
+    hash::{HashKind, ObjectHash, get_hash_kind, set_hash_kind},
+    internal::{
+        metadata::{EntryMeta, MetaAttached},
+        object::commit::Commit,
+        pack::{Pack, entry::Entry},
+    },
 };
 use indicatif::ProgressBar;
 use sea_orm::{
@@ -43,10 +48,10 @@ use crate::{
         head::Head,
         model::reference as ref_model,
         protocol::{
-            DiscRef, DiscoveryResult, FetchStream, ProtocolClient,
+            DiscRef, DiscoveryResult, FetchStream, ObjectFilter, ProtocolClient,
             git_client::GitClient,
             https_client::HttpsClient,
-            local_client::LocalClient,
+            local_client::{LocalClient, encode_pack_bytes},
             set_wire_hash_kind,
             ssh_client::{SshClient, is_ssh_spec},
         },
@@ -55,11 +60,13 @@ use crate::{
         vault::{decrypt_token, load_unseal_key},
     },
     utils::{
-        error::{CliError, CliResult, StableErrorCode},
+        error::{CliError, CliResult, StableErrorCode, emit_warning},
         output::{
             OutputConfig, ProgressMode, ProgressPreference, ProgressReporter, emit_json_data,
         },
-        path, util,
+        path,
+        progress::{self, Progress},
+        util,
         util::try_get_storage_path,
     },
 };
@@ -69,6 +76,8 @@ EXAMPLES:
     libra fetch                            Fetch the current branch's upstream
     libra fetch origin                     Fetch from a specific remote
     libra fetch origin main                Fetch only one branch from a remote
+    libra fetch origin 'refs/heads/*:refs/remotes/origin/*'
+                                           Fetch with an explicit refspec
     libra fetch --all                      Fetch every configured remote
     libra fetch origin --depth 1           Shallow fetch (latest commit only)
     libra fetch --all --depth 3            Shallow fetch across all remotes
@@ -155,6 +164,25 @@ impl RemoteClient {
         }
     }
 
+    /// Report transfer bytes to `progress`. Only the HTTPS transport reports;
+    /// the others render their own side-band progress or have none.
+    pub(crate) fn with_progress(self, progress: Arc<dyn Progress>) -> Self {
+        match self {
+            Self::Http(client) => Self::Http(client.with_progress(progress)),
+            other => other,
+        }
+    }
+
+    /// Request a partial (filtered) pack from the remote (`clone --filter`).
+    pub(crate) fn with_filter(self, filter: Option<ObjectFilter>) -> Self {
+        match self {
+            Self::Http(client) => Self::Http(client.with_filter(filter)),
+            Self::Local(client) => Self::Local(client.with_filter(filter)),
+            Self::Git(client) => Self::Git(client.with_filter(filter)),
+            Self::Ssh(client) => Self::Ssh(client.with_filter(filter)),
+        }
+    }
+
     /// Apply the connect/idle timeouts resolved from the environment, config, and
     /// built-in defaults for this remote. A no-op for local remotes.
     pub(crate) fn with_resolved_fetch_timeouts(self, remote: Option<&str>) -> Result<Self, String> {
@@ -602,7 +630,8 @@ pub struct FetchArgs {
     /// Repository to fetch from
     pub repository: Option<String>,
 
-    /// Refspec to fetch, usually a branch name
+    /// Branch to fetch, or a refspec `[+]<src>:<dst>` (one `*` allowed on each
+    /// side; `<dst>` under `refs/remotes/<repository>/`)
     #[clap(requires("repository"))]
     pub refspec: Option<String>,
 
@@ -614,6 +643,11 @@ pub struct FetchArgs {
     #[clap(long, value_name = "N")]
     pub depth: Option<usize>,
 
+    /// Split the wanted refs across N concurrent upload-pack requests (HTTPS
+    /// remotes; `0` uses one per CPU). Ignored with `--depth`.
+    #[clap(short = 'j', long, value_name = "N")]
+    pub jobs: Option<usize>,
+
     /// Show what would be fetched without downloading objects or writing any
     /// refs, reflog, FETCH_HEAD, or shallow metadata.
     #[clap(long = "dry-run")]
@@ -791,10 +825,16 @@ pub enum FetchError {
     PackWrite { path: PathBuf, source: io::Error },
     #[error("failed to build pack index for '{path}': {source}")]
     IndexPack { path: String, source: GitError },
+    #[error("failed to merge the packs of a parallel fetch: {source}")]
+    MergePacks { source: GitError },
     #[error("failed to update references after fetch: {message}")]
     UpdateRefs { message: String },
     #[error("failed to inspect local repository state: {message}")]
     LocalState { message: String },
+    #[error("invalid refspec '{spec}': {reason}")]
+    InvalidRefspec { spec: String, reason: String },
+    #[error("rejected non-fast-forward update of {}", refs.join(", "))]
+    NonFastForward { refs: Vec<String> },
 }
 
 impl From<FetchError> for CliError {
@@ -832,6 +872,13 @@ impl From<FetchError> for CliError {
             FetchError::RemoteBranchNotFound { .. } => CliError::command_usage(error.to_string())
                 .with_stable_code(StableErrorCode::CliInvalidTarget)
                 .with_hint("verify the remote branch name and try again"),
+            FetchError::InvalidRefspec { .. } => CliError::command_usage(error.to_string())
+                .with_stable_code(StableErrorCode::CliInvalidArguments)
+                .with_hint("use '<branch>' or '[+]<src>:refs/remotes/<remote>/<dst>'"),
+            FetchError::NonFastForward { .. } => CliError::fatal(error.to_string())
+                .with_stable_code(StableErrorCode::ConflictOperationBlocked)
+                .with_hint("prefix the refspec with '+' to allow the forced update")
+                .with_hint("or use --force to overwrite the remote-tracking ref"),
             FetchError::ObjectFormatMismatch { .. } => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::RepoStateInvalid),
             FetchError::IncompletePack { .. } => CliError::fatal(error.to_string())
@@ -840,7 +887,8 @@ impl From<FetchError> for CliError {
             FetchError::InvalidPktHeader { .. }
             | FetchError::RemoteSideband { .. }
             | FetchError::ChecksumMismatch
-            | FetchError::IndexPack { .. } => CliError::fatal(error.to_string())
+            | FetchError::IndexPack { .. }
+            | FetchError::MergePacks { .. } => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::NetworkProtocol),
             FetchError::ObjectsDirNotFound { .. } => {
                 CliError::fatal(error.to_string()).with_stable_code(StableErrorCode::IoReadFailed)
@@ -1016,6 +1064,7 @@ async fn run_fetch(args: FetchArgs, output: &OutputConfig) -> CliResult<FetchOut
         refspec,
         all,
         depth,
+        jobs,
         dry_run,
         append: _,
         verbose,
@@ -1034,6 +1083,7 @@ async fn run_fetch(args: FetchArgs, output: &OutputConfig) -> CliResult<FetchOut
     // --no-progress`. All other output settings are preserved.
     let suppressed_output = apply_no_progress(output, no_progress);
     let output = suppressed_output.as_ref().unwrap_or(output);
+    let jobs = resolve_fetch_jobs(jobs);
 
     // Resolve the CLI tag intent: `--tags` -> All, `--no-tags` -> NoTags, neither
     // -> None (let each remote fall back to `remote.<name>.tagOpt` then the Git
@@ -1064,7 +1114,18 @@ async fn run_fetch(args: FetchArgs, output: &OutputConfig) -> CliResult<FetchOut
             }
             results.push(
                 fetch_repository_with_result(
-                    remote, None, false, depth, dry_run, tag_cli, force, prune, output,
+                    remote,
+                    None,
+                    Vec::new(),
+                    false,
+                    depth,
+                    jobs,
+                    dry_run,
+                    tag_cli,
+                    force,
+                    prune,
+                    output,
+                    progress::for_output(output),
                 )
                 .await
                 .map_err(CliError::from)?,
@@ -1118,16 +1179,29 @@ async fn run_fetch(args: FetchArgs, output: &OutputConfig) -> CliResult<FetchOut
         );
     }
 
+    // A `<src>:<dst>` / pattern / `+` argument is a refspec; anything else is
+    // the branch-name shorthand.
+    let (branch, refspecs) = match refspec.as_deref() {
+        Some(spec) if FetchRefspec::is_refspec_syntax(spec) => (
+            None,
+            vec![FetchRefspec::parse(spec, &remote_config.name).map_err(CliError::from)?],
+        ),
+        _ => (refspec.clone(), Vec::new()),
+    };
+
     let result = fetch_repository_with_result(
         remote_config,
-        refspec.clone(),
+        branch,
+        refspecs,
         false,
         depth,
+        jobs,
         dry_run,
         tag_cli,
         force,
         prune,
         output,
+        progress::for_output(output),
     )
     .await
     .map_err(CliError::from)?;
@@ -1302,11 +1376,197 @@ pub(crate) fn normalize_branch_ref(branch: &str) -> String {
     }
 }
 
+/// A fetch refspec, `[+]<src>[:<dst>]`. `src` and `dst` either both contain a
+/// single `*` or neither does; `dst` must lie under `refs/remotes/<remote>/`,
+/// the only namespace fetch writes. A refspec without `dst` updates the default
+/// remote-tracking ref for each matched branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FetchRefspec {
+    pub force: bool,
+    pub src: String,
+    pub dst: Option<String>,
+}
+
+impl FetchRefspec {
+    /// Parse `spec` for `remote_name`. A bare `src` is normalized like a branch
+    /// name (`main` -> `refs/heads/main`).
+    pub(crate) fn parse(spec: &str, remote_name: &str) -> Result<Self, FetchError> {
+        let invalid = |reason: &str| FetchError::InvalidRefspec {
+            spec: spec.to_string(),
+            reason: reason.to_string(),
+        };
+        let (force, body) = match spec.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, spec),
+        };
+        let (src, dst) = match body.split_once(':') {
+            Some((src, dst)) => (src, Some(dst)),
+            None => (body, None),
+        };
+        if src.is_empty() {
+            return Err(invalid("empty source"));
+        }
+        if src.matches('*').count() > 1 {
+            return Err(invalid("more than one '*' in source"));
+        }
+        let src = normalize_branch_ref(src);
+        let dst = match dst {
+            None | Some("") => None,
+            Some(dst) => {
+                if src.contains('*') != dst.contains('*') || dst.matches('*').count() > 1 {
+                    return Err(invalid(
+                        "source and destination must both contain one '*' or neither",
+                    ));
+                }
+                if !dst.starts_with(&format!("refs/remotes/{remote_name}/")) {
+                    return Err(invalid(&format!(
+                        "destination must be under 'refs/remotes/{remote_name}/'"
+                    )));
+                }
+                Some(dst.to_string())
+            }
+        };
+        if dst.is_none() && src.contains('*') {
+            return Err(invalid("a pattern source needs a destination"));
+        }
+        Ok(Self { force, src, dst })
+    }
+
+    /// Whether `spec` (a `<repository>` argument's refspec) is a refspec rather
+    /// than a plain branch name.
+    pub(crate) fn is_refspec_syntax(spec: &str) -> bool {
+        spec.starts_with('+') || spec.contains(':') || spec.contains('*')
+    }
+
+    /// The local ref `remote_ref` is fetched into, when this refspec matches it.
+    fn map(&self, remote_name: &str, remote_ref: &str) -> Option<String> {
+        match &self.dst {
+            None => (remote_ref == self.src)
+                .then(|| default_tracking_ref(remote_name, remote_ref))
+                .flatten(),
+            Some(dst) => match_pattern(&self.src, remote_ref).map(|middle| match middle {
+                Some(middle) => dst.replacen('*', middle, 1),
+                None => dst.clone(),
+            }),
+        }
+    }
+
+    /// Whether the local tracking ref `local_ref` lies in this refspec's
+    /// destination, i.e. `--prune` may consider it.
+    fn covers_destination(&self, remote_name: &str, local_ref: &str) -> bool {
+        match &self.dst {
+            Some(dst) => match_pattern(dst, local_ref).is_some(),
+            None => default_tracking_ref(remote_name, &self.src).as_deref() == Some(local_ref),
+        }
+    }
+}
+
+/// Match `name` against a refspec side with at most one `*`. Returns
+/// `Some(Some(middle))` for a pattern match, `Some(None)` for an exact match.
+fn match_pattern<'a>(pattern: &str, name: &'a str) -> Option<Option<&'a str>> {
+    match pattern.split_once('*') {
+        None => (pattern == name).then_some(None),
+        Some((prefix, suffix)) => name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix))
+            .filter(|middle| !middle.is_empty())
+            .map(Some),
+    }
+}
+
+/// The remote-tracking ref a fetch without refspecs writes for `remote_ref`:
+/// `refs/heads/<b>` -> `refs/remotes/<remote>/<b>`, `refs/mr/<m>` ->
+/// `refs/remotes/<remote>/mr/<m>`; other refs are not tracked.
+fn default_tracking_ref(remote_name: &str, remote_ref: &str) -> Option<String> {
+    if let Some(branch_name) = remote_ref.strip_prefix("refs/heads/") {
+        Some(format!("refs/remotes/{remote_name}/{branch_name}"))
+    } else {
+        remote_ref
+            .strip_prefix("refs/mr/")
+            .map(|mr_name| format!("refs/remotes/{remote_name}/mr/{mr_name}"))
+    }
+}
+
+/// Where `remote_ref` is fetched into: the first matching refspec, or the
+/// default mapping when no refspecs are in effect.
+fn tracking_ref_for(
+    remote_name: &str,
+    refspecs: &[FetchRefspec],
+    remote_ref: &str,
+) -> Option<String> {
+    if refspecs.is_empty() {
+        return default_tracking_ref(remote_name, remote_ref);
+    }
+    refspecs
+        .iter()
+        .find_map(|spec| spec.map(remote_name, remote_ref))
+}
+
+/// The configured `remote.<name>.fetch` refspecs; empty when none are set.
+/// A refspec Libra cannot honour (for example one writing outside
+/// `refs/remotes/<remote>/`) is skipped with a warning rather than failing
+/// the whole fetch, since the config may have been written by Git.
+async fn configured_fetch_refspecs(remote_name: &str) -> Result<Vec<FetchRefspec>, FetchError> {
+    let entries = ConfigKv::get_all(&format!("remote.{remote_name}.fetch"))
+        .await
+        .map_err(|error| FetchError::LocalState {
+            message: format!("failed to read remote.{remote_name}.fetch: {error}"),
+        })?;
+    Ok(entries
+        .iter()
+        .filter_map(
+            |entry| match FetchRefspec::parse(entry.value.trim(), remote_name) {
+                Ok(spec) => Some(spec),
+                Err(error) => {
+                    emit_warning(format!("ignoring remote.{remote_name}.fetch: {error}"));
+                    None
+                }
+            },
+        )
+        .collect())
+}
+
+/// Names (relative to `refs/remotes/<remote>/`) that `--prune` must keep when
+/// refspecs are in effect: every ref the refspecs map an advertised ref to,
+/// plus every local tracking ref outside all refspec destinations — Git only
+/// prunes within the destinations of the refspecs it fetched.
+fn refspec_prune_keep_set(
+    remote_name: &str,
+    refspecs: &[FetchRefspec],
+    advertised: &[DiscRef],
+    local: &[Branch],
+) -> HashSet<String> {
+    let prefix = format!("refs/remotes/{remote_name}/");
+    let mut keep: HashSet<String> = advertised
+        .iter()
+        .filter_map(|reference| tracking_ref_for(remote_name, refspecs, &reference._ref))
+        .filter_map(|local_ref| local_ref.strip_prefix(&prefix).map(str::to_string))
+        .collect();
+    for branch in local {
+        let Some(name) = branch.name.strip_prefix(&prefix) else {
+            continue;
+        };
+        if !refspecs
+            .iter()
+            .any(|spec| spec.covers_destination(remote_name, &branch.name))
+        {
+            keep.insert(name.to_string());
+        }
+    }
+    keep
+}
+
 pub(crate) fn remote_has_branch(refs: &[DiscRef], branch: &str) -> bool {
     let normalized = normalize_branch_ref(branch);
     refs.iter().any(|reference| reference._ref == normalized)
 }
 
+/// Whether the remote advertises `refs/tags/<tag>` (`clone --branch <tag>`).
+pub(crate) fn remote_has_tag(refs: &[DiscRef], tag: &str) -> bool {
+    let normalized = format!("refs/tags/{tag}");
+    refs.iter().any(|reference| reference._ref == normalized)
+}
+
 pub(crate) fn normalize_remote_url(remote_input: &str, remote_client: &RemoteClient) -> String {
     match remote_client {
         RemoteClient::Http(_) | RemoteClient::Git(_) | RemoteClient::Ssh(_) => {
@@ -1351,32 +1611,53 @@ pub async fn fetch_repository_safe(
     fetch_repository_with_result(
         remote_config,
         branch,
+        Vec::new(),
         single_branch,
         depth,
+        1,
         false,
         tag_cli,
         false,
         false,
         output,
+        progress::for_output(output),
     )
     .await
     .map(|_| ())
 }
 
+/// Fetch from `remote_config` and report what changed. `progress` receives the
+/// pack bytes of a parallel (`--jobs`) HTTPS fetch; commands build it with
+/// [`progress::for_output`]. A single-request fetch leaves it untouched because
+/// [`read_fetch_stream`] already renders the side-band meter.
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn fetch_repository_with_result(
     remote_config: RemoteConfig,
     branch: Option<String>,
+    refspecs: Vec<FetchRefspec>,
     single_branch: bool,
     depth: Option<usize>,
+    jobs: usize,
     dry_run: bool,
     tag_cli: Option<TagFetchMode>,
     force: bool,
     prune: bool,
     output: &OutputConfig,
+    progress: Arc<dyn Progress>,
 ) -> Result<FetchRepositoryResult, FetchError> {
     let (remote_client, discovery) =
         discover_remote_with_name(&remote_config.url, Some(&remote_config.name)).await?;
+    // `--jobs N` splits the wants across N concurrent upload-pack requests.
+    // Only HTTPS supports it, and shallow fetches negotiate their boundary per
+    // request, so they stay single. A single request renders the side-band
+    // meter in `read_fetch_stream`; parallel side-band streams cannot share
+    // one spinner, so the transport's byte count is shown through `progress`
+    // instead.
+    let parallel = jobs > 1 && depth.is_none() && matches!(remote_client, RemoteClient::Http(_));
+    let progress = if parallel { progress } else { progress::noop() };
+    let remote_client = remote_client
+        .with_progress(Arc::clone(&progress))
+        .with_filter(configured_partial_clone_filter(&remote_config.name).await);
     // Redact credentials from the URL before storing it in the result to
     // prevent secret leakage in both human and JSON output.
     let normalized_url =
@@ -1390,6 +1671,14 @@ pub(crate) async fn fetch_repository_with_result(
     }
     set_wire_hash_kind(discovery.hash_kind);
 
+    // Explicit refspecs win; a branch shorthand fetches as before; otherwise
+    // the configured `remote.<name>.fetch` refspecs (if any) apply.
+    let refspecs = if refspecs.is_empty() && branch.is_none() {
+        configured_fetch_refspecs(&remote_config.name).await?
+    } else {
+        refspecs
+    };
+
     if let Some(branch_name) = &branch
         && !remote_has_branch(&discovery.refs, branch_name)
     {
@@ -1437,7 +1726,7 @@ pub(crate) async fn fetch_repository_with_result(
         .collect();
 
     // Only request refs we will actually persist. `update_references` saves
-    // `refs/heads/*` and `refs/mr/*`; with `--tags` (`All`) we also explicitly
+    // what the refspecs map (by default `refs/heads/*` and `refs/mr/*`); with `--tags` (`All`) we also explicitly
     // `want` `refs/tags/*`. Asking for anything else (HEAD symref, `refs/pull/*`)
     // makes the server include unreachable objects that the next fetch's `have`
     // cannot cover, forcing the same pack to be re-downloaded every time. Tags
@@ -1446,8 +1735,7 @@ pub(crate) async fn fetch_repository_with_result(
     // here — they arrive via the `include-tag` capability and are persisted
     // post-fetch only when their object/target is present.
     refs.retain(|reference| {
-        reference._ref.starts_with("refs/heads/")
-            || reference._ref.starts_with("refs/mr/")
+        tracking_ref_for(&remote_config.name, &refspecs, &reference._ref).is_some()
             || (tag_mode == TagFetchMode::All
                 && reference._ref.starts_with("refs/tags/")
                 && !reference._ref.ends_with("^{}"))
@@ -1456,8 +1744,13 @@ pub(crate) async fn fetch_repository_with_result(
     if let Some(branch_name) = &branch
         && single_branch
     {
+        // Tags wanted by `--tags` stay: `All` persists every advertised tag,
+        // so their objects must be fetched too.
         let normalized = normalize_branch_ref(branch_name);
-        refs.retain(|reference| reference._ref == normalized);
+        refs.retain(|reference| {
+            reference._ref == normalized
+                || (tag_mode == TagFetchMode::All && reference._ref.starts_with("refs/tags/"))
+        });
     }
 
     // `--dry-run`: compute the would-be remote-tracking ref updates from the
@@ -1465,16 +1758,11 @@ pub(crate) async fn fetch_repository_with_result(
     // anything (no `.pack`/`.idx`, no shallow update, no ref/reflog writes, no
     // FETCH_HEAD).
     if dry_run {
-        let refs_updated = compute_fetch_ref_preview(&remote_config, &refs).await?;
+        let refs_updated = compute_fetch_ref_preview(&remote_config, &refspecs, &refs).await?;
         // `--dry-run --prune`: report the stale refs that would be removed, but
         // write nothing.
         let pruned = if prune {
-            prune_stale_remote_refs(
-                &remote_config.name,
-                &remote_advertised_branch_names(&discovery.refs),
-                true,
-            )
-            .await?
+            prune_stale_remote_refs(&remote_config.name, &discovery.refs, &refspecs, true).await?
         } else {
             Vec::new()
         };
@@ -1497,46 +1785,60 @@ pub(crate) async fn fetch_repository_with_result(
     let have = current_have_safe().await?;
     let shallow_boundaries = read_shallow_boundaries()?;
     let shallow = shallow_boundaries.iter().cloned().collect::<Vec<_>>();
-    let mut result_stream = remote_client
-        .fetch_objects(&have, &want, &shallow, depth)
-        .await
-        .map_err(|source| FetchError::FetchObjects {
-            remote: remote_config.url.clone(),
-            source,
-        })?;
-
     let task = format!("fetch {}", remote_config.name);
-    let fetch_data = read_fetch_stream(&mut result_stream, output, &task).await?;
-    let objects_fetched = pack_object_count(&fetch_data.pack_data);
-    let bytes_received = fetch_data.pack_data.len();
-    let pack_file = write_pack_and_index(&fetch_data.pack_data)?;
-    if let Some(pack_file) = pack_file {
-        let index_version = match get_hash_kind() {
-            HashKind::Sha1 => None,
-            HashKind::Sha256 => Some(2),
-        };
-        match index_version {
-            Some(2) => index_pack::build_index_v2(&pack_file, &pack_file.replace(".pack", ".idx"))
-                .map_err(|source| FetchError::IndexPack {
-                    path: pack_file.clone(),
-                    source,
-                })?,
-            _ => index_pack::build_index_v1(&pack_file, &pack_file.replace(".pack", ".idx"))
-                .map_err(|source| FetchError::IndexPack {
-                    path: pack_file.clone(),
-                    source,
-                })?,
+    let fetched = if parallel {
+        read_parallel_fetch_streams(
+            &remote_client,
+            &remote_config,
+            &have,
+            &want,
+            jobs,
+            output,
+            &task,
+        )
+        .await
+    } else {
+        match remote_client
+            .fetch_objects(&have, &want, &shallow, depth)
+            .await
+        {
+            Ok(mut result_stream) => read_fetch_stream(&mut result_stream, output, &task)
+                .await
+                .map(|data| vec![data]),
+            Err(source) => Err(FetchError::FetchObjects {
+                remote: remote_config.url.clone(),
+                source,
+            }),
         }
+    };
+    progress.finish();
+    let fetched = fetched?;
+    let bytes_received = fetched.iter().map(|data| data.pack_data.len()).sum();
+    let fetched = if fetched.len() > 1 {
+        vec![merge_batch_packs(fetched).await?]
+    } else {
+        fetched
+    };
+    let objects_fetched = fetched
+        .iter()
+        .map(|data| pack_object_count(&data.pack_data))
+        .sum();
+    for fetch_data in &fetched {
+        if let Some(pack_file) = write_pack_and_index(&fetch_data.pack_data)? {
+            build_pack_index(&pack_file)?;
+        }
+        apply_shallow_updates(&fetch_data.shallow, &fetch_data.unshallow)?;
     }
-    apply_shallow_updates(&fetch_data.shallow, &fetch_data.unshallow)?;
 
-    let mut refs_updated = update_references(
+    let (mut refs_updated, rejected) = update_references(
         &remote_config,
+        &refspecs,
         &refs,
         &ref_heads,
         remote_head,
         branch,
         discovery.capabilities.clone(),
+        force,
     )
     .await?;
 
@@ -1566,16 +1868,17 @@ pub(crate) async fn fetch_repository_with_result(
     // with an audit reflog entry). Only stale tracking refs for *this* remote
     // are touched.
     let pruned = if prune {
-        prune_stale_remote_refs(
-            &remote_config.name,
-            &remote_advertised_branch_names(&discovery.refs),
-            false,
-        )
-        .await?
+        prune_stale_remote_refs(&remote_config.name, &discovery.refs, &refspecs, false).await?
     } else {
         Vec::new()
     };
 
+    // Like Git, the other refs are still updated; the fetch only fails once
+    // everything else has been persisted.
+    if !rejected.is_empty() {
+        return Err(FetchError::NonFastForward { refs: rejected });
+    }
+
     Ok(FetchRepositoryResult {
         remote: remote_config.name,
         url: normalized_url,
@@ -1586,6 +1889,190 @@ pub(crate) async fn fetch_repository_with_result(
     })
 }
 
+/// Build the `.idx` next to a freshly written `.pack` (v2 for SHA-256 repos).
+fn build_pack_index(pack_file: &str) -> Result<(), FetchError> {
+    let index_file = pack_file.replace(".pack", ".idx");
+    let built = match get_hash_kind() {
+        HashKind::Sha1 => index_pack::build_index_v1(pack_file, &index_file),
+        HashKind::Sha256 => index_pack::build_index_v2(pack_file, &index_file),
+    };
+    built.map_err(|source| FetchError::IndexPack {
+        path: pack_file.to_string(),
+        source,
+    })
+}
+
+/// The filter `clone --filter` recorded in `remote.<name>.partialclonefilter`,
+/// so later fetches from a promisor remote stay partial.
+async fn configured_partial_clone_filter(remote: &str) -> Option<ObjectFilter> {
+    let entry = ConfigKv::get(&format!("remote.{remote}.partialclonefilter"))
+        .await
+        .ok()
+        .flatten()?;
+    ObjectFilter::parse(entry.value.trim()).ok()
+}
+
+/// The promisor remote of a partial clone (`extensions.partialclone`),
+/// resolved once and reused for every lazy fetch from that repository.
+pub(crate) struct PromisorRemote {
+    remote: String,
+    url: String,
+    client: RemoteClient,
+}
+
+impl PromisorRemote {
+    /// Resolve the promisor remote and build its client. Returns `Ok(None)`
+    /// when the repository is not a partial clone.
+    pub(crate) async fn configured() -> Result<Option<Self>, FetchError> {
+        let Ok(Some(promisor)) = ConfigKv::get_best_effort("extensions.partialclone").await else {
+            return Ok(None);
+        };
+        let remote = promisor.value.trim().to_string();
+        let Ok(Some(url)) = ConfigKv::get_best_effort(&format!("remote.{remote}.url")).await else {
+            return Ok(None);
+        };
+        let url = url.value;
+        let client = RemoteClient::from_spec_with_remote(&url, Some(&remote))
+            .and_then(|client| client.with_resolved_fetch_timeouts(Some(&remote)))
+            .map_err(|message| {
+                let (kind, reason) = classify_remote_spec_error(&url, &message);
+                FetchError::InvalidRemoteSpec {
+                    spec: url.clone(),
+                    kind,
+                    reason,
+                }
+            })?;
+        Ok(Some(PromisorRemote {
+            remote,
+            url,
+            client,
+        }))
+    }
+
+    /// Fetch the objects a partial clone omitted in one upload-pack request
+    /// and store them as a new pack.
+    pub(crate) async fn fetch(&self, oids: &[ObjectHash]) -> Result<(), FetchError> {
+        let Some(first) = oids.first() else {
+            return Ok(());
+        };
+        // Lazy fetches run on their own thread, whose hash kind is not set yet.
+        set_hash_kind(first.kind());
+        set_wire_hash_kind(first.kind());
+        let want: Vec<String> = oids.iter().map(ToString::to_string).collect();
+        let mut stream = self
+            .client
+            .fetch_objects(&[], &want, &[], None)
+            .await
+            .map_err(|source| FetchError::FetchObjects {
+                remote: self.url.clone(),
+                source,
+            })?;
+        let quiet = apply_no_progress(&OutputConfig::default(), true).unwrap_or_default();
+        let data =
+            read_fetch_stream(&mut stream, &quiet, &format!("fetch {}", self.remote)).await?;
+        if let Some(pack_file) = write_pack_and_index(&data.pack_data)? {
+            build_pack_index(&pack_file)?;
+        }
+        Ok(())
+    }
+}
+
+/// Issue the batched upload-pack requests for `--jobs` and read every stream
+/// concurrently. Per-stream side-band rendering is silenced; the caller shows
+/// the transport's combined byte count. Any failed batch fails the fetch
+/// before a pack is written.
+async fn read_parallel_fetch_streams(
+    remote_client: &RemoteClient,
+    remote_config: &RemoteConfig,
+    have: &[String],
+    want: &[String],
+    jobs: usize,
+    output: &OutputConfig,
+    task: &str,
+) -> Result<Vec<FetchStreamData>, FetchError> {
+    let RemoteClient::Http(client) = remote_client else {
+        unreachable!("parallel fetch is only selected for HTTPS remotes");
+    };
+    let mut streams = client
+        .fetch_objects_batched(have, want, &[], None, jobs)
+        .await
+        .map_err(|source| FetchError::FetchObjects {
+            remote: remote_config.url.clone(),
+            source,
+        })?;
+    let quiet = apply_no_progress(output, true).unwrap_or_else(|| output.clone());
+    futures::future::try_join_all(
+        streams
+            .iter_mut()
+            .map(|stream| read_fetch_stream(stream, &quiet, task)),
+    )
+    .await
+}
+
+/// Merge the packs of a `--jobs` fetch into one pack holding each object once.
+/// Every batch negotiates with the same local haves, so packs of tips that
+/// share history repeat those objects; the first copy of each is kept.
+async fn merge_batch_packs(batches: Vec<FetchStreamData>) -> Result<FetchStreamData, FetchError> {
+    let mut merged = FetchStreamData::default();
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for batch in batches {
+        merged.shallow.extend(batch.shallow);
+        merged.unshallow.extend(batch.unshallow);
+        if pack_object_count(&batch.pack_data) == 0 {
+            continue;
+        }
+        for entry in decode_pack_entries(&batch.pack_data)? {
+            if seen.insert(entry.hash) {
+                entries.push(entry);
+            }
+        }
+    }
+    if !entries.is_empty() {
+        merged.pack_data = encode_pack_bytes(entries, get_hash_kind())
+            .await
+            .map_err(|source| FetchError::MergePacks {
+                source: GitError::IOError(source),
+            })?;
+    }
+    Ok(merged)
+}
+
+/// Decode every object of an in-memory pack, resolving its deltas.
+fn decode_pack_entries(pack_data: &[u8]) -> Result<Vec<Entry>, FetchError> {
+    let tmp_path = path::try_objects()
+        .map_err(|source| FetchError::ObjectsDirNotFound { source })?
+        .join("pack");
+    let entries = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&entries);
+    let mut pack = Pack::new(Some(8), Some(1024 * 1024 * 1024), Some(tmp_path), true);
+    pack.decode(
+        &mut io::Cursor::new(pack_data),
+        move |entry: MetaAttached<Entry, EntryMeta>| {
+            sink.lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .push(entry.inner);
+        },
+        None::<fn(ObjectHash)>,
+    )
+    .map_err(|source| FetchError::MergePacks { source })?;
+    let entries = std::mem::take(
+        &mut *entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    );
+    Ok(entries)
+}
+
+/// Resolve `--jobs`: absent means a single request, `0` one per CPU.
+pub(crate) fn resolve_fetch_jobs(jobs: Option<usize>) -> usize {
+    match jobs {
+        None => 1,
+        Some(0) => std::thread::available_parallelism().map_or(1, usize::from),
+        Some(n) => n,
+    }
+}
+
 #[derive(Default)]
 struct FetchStreamData {
     pack_data: Vec<u8>,
@@ -2204,6 +2691,7 @@ fn apply_shallow_updates(shallow: &[String], unshallow: &[String]) -> Result<(),
 /// database writes.
 async fn compute_fetch_ref_preview(
     remote_config: &RemoteConfig,
+    refspecs: &[FetchRefspec],
     refs: &[DiscRef],
 ) -> Result<Vec<FetchRefUpdate>, FetchError> {
     let mut updates = Vec::new();
@@ -2235,11 +2723,8 @@ async fn compute_fetch_ref_preview(
             continue;
         }
 
-        let full_ref_name = if let Some(branch_name) = reference._ref.strip_prefix("refs/heads/") {
-            format!("refs/remotes/{}/{}", remote_config.name, branch_name)
-        } else if let Some(mr_name) = reference._ref.strip_prefix("refs/mr/") {
-            format!("refs/remotes/{}/mr/{}", remote_config.name, mr_name)
-        } else {
+        let Some(full_ref_name) = tracking_ref_for(&remote_config.name, refspecs, &reference._ref)
+        else {
             continue;
         };
 
@@ -2391,7 +2876,8 @@ pub(crate) fn resolve_remote_default_branch(
 /// rule `remote prune` uses: a local tracking ref is stale when the remote no
 /// longer advertises a matching `refs/heads/*` / `refs/mr/*` ref.
 /// `refs/remotes/<name>/HEAD`, local branches, tags, and every other remote are
-/// never considered.
+/// never considered. With refspecs in effect only tracking refs inside their
+/// destinations are candidates ([`refspec_prune_keep_set`]).
 ///
 /// With `dry_run`, the stale refs are classified and returned but nothing is
 /// written. Otherwise each stale ref is removed and a non-lossy audit reflog
@@ -2400,7 +2886,8 @@ pub(crate) fn resolve_remote_default_branch(
 /// in a partially-pruned state.
 async fn prune_stale_remote_refs(
     remote_name: &str,
-    remote_branch_names: &HashSet<String>,
+    advertised: &[DiscRef],
+    refspecs: &[FetchRefspec],
     dry_run: bool,
 ) -> Result<Vec<FetchPruneEntry>, FetchError> {
     let local = Branch::list_branches_result(Some(remote_name))
@@ -2410,6 +2897,12 @@ async fn prune_stale_remote_refs(
                 "failed to list remote-tracking refs for prune of '{remote_name}': {error}"
             ),
         })?;
+    let remote_branch_names = if refspecs.is_empty() {
+        remote_advertised_branch_names(advertised)
+    } else {
+        refspec_prune_keep_set(remote_name, refspecs, advertised, &local)
+    };
+    let remote_branch_names = &remote_branch_names;
 
     let pruned: Vec<FetchPruneEntry> =
         classify_stale_tracking_branches(remote_name, remote_branch_names, &local)
@@ -2478,21 +2971,30 @@ async fn prune_stale_remote_refs(
     Ok(pruned)
 }
 
+/// Persist the fetched remote-tracking refs. A non-fast-forward update through
+/// a refspec without `+` is left alone (unless `force`) and its local ref is
+/// returned in the second list; the default mapping behaves like Git's
+/// `+refs/heads/*:refs/remotes/<remote>/*` and always updates.
+#[allow(clippy::too_many_arguments)]
 async fn update_references(
     remote_config: &RemoteConfig,
+    refspecs: &[FetchRefspec],
     refs: &[DiscRef],
     ref_heads: &[DiscRef],
     remote_head: Option<DiscRef>,
     branch: Option<String>,
     capabilities: Vec<String>,
-) -> Result<Vec<FetchRefUpdate>, FetchError> {
+    force: bool,
+) -> Result<(Vec<FetchRefUpdate>, Vec<String>), FetchError> {
     let db = get_db_conn_instance().await;
     let remote_config = remote_config.clone();
+    let refspecs = refspecs.to_vec();
     let refs = refs.to_vec();
     let ref_heads = ref_heads.to_vec();
     db.transaction(|txn| {
         Box::pin(async move {
             let mut updates = Vec::new();
+            let mut rejected = Vec::new();
             for reference in &refs {
                 // Tags are persisted separately by `persist_fetched_tags` (they
                 // live in the shared `refs/tags/*` namespace and have their own
@@ -2501,18 +3003,15 @@ async fn update_references(
                     continue;
                 }
 
-                let full_ref_name: String;
-                if let Some(branch_name) = reference._ref.strip_prefix("refs/heads/") {
-                    full_ref_name = format!("refs/remotes/{}/{}", remote_config.name, branch_name);
-                } else if let Some(mr_name) = reference._ref.strip_prefix("refs/mr/") {
-                    full_ref_name = format!("refs/remotes/{}/mr/{}", remote_config.name, mr_name);
-                } else {
+                let Some(full_ref_name) =
+                    tracking_ref_for(&remote_config.name, &refspecs, &reference._ref)
+                else {
                     tracing::debug!(
                         "Skipping unsupported ref type during fetch: {}",
                         reference._ref
                     );
                     continue;
-                }
+                };
 
                 let old_oid = Branch::find_branch_result_with_conn(
                     txn,
@@ -2531,6 +3030,12 @@ async fn update_references(
                     continue;
                 }
 
+                let forced = fetch_update_is_forced(old_oid.as_deref(), &reference._hash);
+                if forced && !force && !refspec_allows_forced_update(&refspecs, &remote_config.name, &reference._ref) {
+                    rejected.push(full_ref_name);
+                    continue;
+                }
+
                 Branch::update_branch_with_conn(
                     txn,
                     &full_ref_name,
@@ -2560,7 +3065,7 @@ async fn update_references(
                     })?;
                 updates.push(FetchRefUpdate {
                     remote_ref: full_ref_name,
-                    forced: fetch_update_is_forced(old_oid.as_deref(), &reference._hash),
+                    forced,
                     old_oid,
                     new_oid: reference._hash.clone(),
                 });
@@ -2578,7 +3083,7 @@ async fn update_references(
                 tracing::debug!("remote HEAD does not point to a branch ref");
             }
 
-            Ok::<_, FetchError>(updates)
+            Ok::<_, FetchError>((updates, rejected))
         })
     })
     .await
@@ -2590,6 +3095,20 @@ async fn update_references(
     })
 }
 
+/// Whether the refspec that maps `remote_ref` carries `+`. With no refspecs the
+/// default mapping applies, which Git configures as a forced refspec.
+fn refspec_allows_forced_update(
+    refspecs: &[FetchRefspec],
+    remote_name: &str,
+    remote_ref: &str,
+) -> bool {
+    refspecs.is_empty()
+        || refspecs
+            .iter()
+            .find(|spec| spec.map(remote_name, remote_ref).is_some())
+            .is_some_and(|spec| spec.force)
+}
+
 /// Whether `new_oid` updating `old_oid` is a forced (non-fast-forward) change.
 /// Best-effort: `false` for a new ref or when ancestry cannot be computed.
 fn fetch_update_is_forced(old_oid: Option<&str>, new_oid: &str) -> bool {
@@ -3317,6 +3836,53 @@ mod tests {
         pack
     }
 
+    /// Packs of `--jobs` batches repeat shared history; the merged pack holds
+    /// each object once.
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn merge_batch_packs_keeps_one_copy_of_shared_objects() {
+        use git_internal::{
+            hash::get_hash_kind,
+            internal::{object::blob::Blob, pack::entry::Entry},
+        };
+
+        use super::{
+            FetchStreamData, decode_pack_entries, encode_pack_bytes, merge_batch_packs,
+            pack_object_count,
+        };
+
+        let temp = tempdir().unwrap();
+        crate::utils::test::setup_with_new_libra_in(temp.path()).await;
+        let _guard = crate::utils::test::ChangeDirGuard::new(temp.path());
+        let blobs = ["shared", "left", "right"].map(Blob::from_content);
+        let pack = |picked: [usize; 2]| {
+            let entries = picked.map(|i| Entry::from(blobs[i].clone())).to_vec();
+            encode_pack_bytes(entries, get_hash_kind())
+        };
+        let batches = vec![
+            FetchStreamData {
+                pack_data: pack([0, 1]).await.unwrap(),
+                ..Default::default()
+            },
+            FetchStreamData {
+                pack_data: pack([0, 2]).await.unwrap(),
+                ..Default::default()
+            },
+        ];
+
+        let merged = merge_batch_packs(batches).await.unwrap();
+        assert_eq!(pack_object_count(&merged.pack_data), 3);
+        let mut ids: Vec<ObjectHash> = decode_pack_entries(&merged.pack_data)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.hash)
+            .collect();
+        ids.sort();
+        let mut expected: Vec<ObjectHash> = blobs.iter().map(|blob| blob.id).collect();
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
     #[tokio::test]
     async fn read_fetch_stream_accepts_eof_after_complete_pack_without_flush() {
         let pack = empty_pack_bytes();
@@ -3711,4 +4277,64 @@ mod tests {
         let entry = [0x50_u8]; // 0b0_101_0000 = type 5
         assert_eq!(parse_pack_entry_data_offset(&entry, 0, 20), None);
     }
+
+    /// Refspec parsing normalizes a bare source, keeps the `+` flag, and
+    /// rejects destinations outside `refs/remotes/<remote>/` and unbalanced
+    /// patterns.
+    #[test]
+    fn fetch_refspec_parse_validates_shape() {
+        let spec = FetchRefspec::parse("+refs/heads/*:refs/remotes/origin/*", "origin").unwrap();
+        assert!(spec.force);
+        assert_eq!(spec.src, "refs/heads/*");
+        assert_eq!(spec.dst.as_deref(), Some("refs/remotes/origin/*"));
+
+        let spec = FetchRefspec::parse("main:refs/remotes/origin/stable", "origin").unwrap();
+        assert!(!spec.force);
+        assert_eq!(spec.src, "refs/heads/main");
+
+        for bad in [
+            "main:refs/heads/main",
+            "main:refs/remotes/upstream/main",
+            "refs/heads/*:refs/remotes/origin/main",
+            "refs/heads/*",
+            ":refs/remotes/origin/x",
+        ] {
+            assert!(
+                matches!(
+                    FetchRefspec::parse(bad, "origin"),
+                    Err(FetchError::InvalidRefspec { .. })
+                ),
+                "{bad} should be rejected"
+            );
+        }
+        assert!(FetchRefspec::is_refspec_syntax(
+            "main:refs/remotes/origin/main"
+        ));
+        assert!(FetchRefspec::is_refspec_syntax("+main"));
+        assert!(!FetchRefspec::is_refspec_syntax("main"));
+    }
+
+    /// Pattern refspecs map the `*` segment; a source-only refspec uses the
+    /// default tracking ref; no refspecs keeps the historical heads/mr mapping.
+    #[test]
+    fn tracking_ref_for_applies_refspecs_in_order() {
+        let specs = vec![
+            FetchRefspec::parse("refs/heads/feature/*:refs/remotes/origin/f/*", "origin").unwrap(),
+            FetchRefspec::parse("main", "origin").unwrap(),
+        ];
+        assert_eq!(
+            tracking_ref_for("origin", &specs, "refs/heads/feature/login").as_deref(),
+            Some("refs/remotes/origin/f/login")
+        );
+        assert_eq!(
+            tracking_ref_for("origin", &specs, "refs/heads/main").as_deref(),
+            Some("refs/remotes/origin/main")
+        );
+        assert_eq!(tracking_ref_for("origin", &specs, "refs/heads/dev"), None);
+        assert_eq!(
+            tracking_ref_for("origin", &[], "refs/mr/7").as_deref(),
+            Some("refs/remotes/origin/mr/7")
+        );
+        assert_eq!(tracking_ref_for("origin", &[], "refs/pull/1/head"), None);
+    }
 }
@@ -34,9 +34,10 @@ use crate::{
         tui::{Tui, tui_init, tui_restore},
     },
     utils::{
+        client_storage::ClientStorage,
         error::{CliError, CliResult, StableErrorCode},
         output::{OutputConfig, emit_json_data},
-        storage::{Storage, local::LocalStorage},
+        storage::Storage,
         util::{DATABASE, try_get_storage_path},
     },
 };
@@ -187,7 +188,16 @@ async fn load_thread_graph(storage_root: &Path, requested_thread_id: Uuid) -> Re
     let db_conn = establish_connection(db_path_str)
         .await
         .with_context(|| format!("failed to open repository database '{}'", db_path.display()))?;
-    let storage = std::sync::Arc::new(LocalStorage::new(storage_root.join("objects")));
+    // `--repo` may name another repository; its objects are a foreign store, so
+    // only the current repository's objects go through the configured tier.
+    let objects = storage_root.join("objects");
+    let storage = std::sync::Arc::new(
+        if try_get_storage_path(None).is_ok_and(|current| current == storage_root) {
+            ClientStorage::init(objects)
+        } else {
+            ClientStorage::init_local(objects)
+        },
+    );
     let history = HistoryManager::new(
         storage.clone(),
         storage_root.to_path_buf(),
@@ -1,5 +1,10 @@
 //! Implements `hash-object` for computing Git-compatible object IDs for blob,
 //! commit, tree, and tag content (with optional `--literally` to skip validation).
+//!
+//! Blobs go through the same content filter as `add` ([`BlobFilter`]): inside a
+//! repository, an LFS-tracked path (`.libra_attributes` or `filter=lfs`) hashes
+//! to its LFS pointer (and `-w` stores the content in the LFS object store), and
+//! `text`/`eol` attributes normalize line endings, unless `--no-filters`.
 
 use std::{
     fs,
@@ -11,10 +16,15 @@ use clap::Parser;
 use git_internal::{hash::ObjectHash, internal::object::types::ObjectType};
 use serde::Serialize;
 
-use crate::utils::{
-    error::{CliError, CliResult, StableErrorCode},
-    output::{OutputConfig, emit_json_data},
-    util,
+use crate::{
+    command::BlobFilter,
+    utils::{
+        error::{CliError, CliResult, StableErrorCode},
+        gitattributes::{self, Attributes},
+        lfs,
+        output::{OutputConfig, emit_json_data},
+        util,
+    },
 };
 
 const HASH_OBJECT_EXAMPLES: &str = "\
@@ -66,11 +76,14 @@ pub struct HashObjectArgs {
     )]
     pub paths: Vec<PathBuf>,
 
-    /// Path context label for compatibility with Git hash-object
+    /// Apply the content filters (LFS, line endings) for PATH instead of the
+    /// input's own path;
+    /// also labels `--stdin` input
     #[arg(long = "path", value_name = "PATH", conflicts_with = "no_filters")]
     pub filter_path: Option<PathBuf>,
 
-    /// Hash raw bytes without path-based content filters
+    /// Hash raw bytes without path-based content filters (LFS pointers, line
+    /// endings)
     #[arg(long = "no-filters", conflicts_with = "filter_path")]
     pub no_filters: bool,
 }
@@ -109,13 +122,46 @@ pub async fn execute(args: HashObjectArgs) -> Result<(), String> {
 /// input, object-write failures, and stdout write failures.
 pub async fn execute_safe(args: HashObjectArgs, output: &OutputConfig) -> CliResult<()> {
     let object_type = parse_git_object_type(&args.object_type)?;
+    // Only blobs inside a repository are filtered; the attributes are those
+    // `add` reads, including `core.attributesFile`.
+    let attributes =
+        if object_type == ObjectType::Blob && !args.no_filters && util::require_repo().is_ok() {
+            Some(Attributes::for_worktree().await)
+        } else {
+            None
+        };
+    let filters = Filters {
+        args: &args,
+        attributes: attributes.as_ref(),
+    };
 
     if output.is_json() {
-        let result = hash_objects(&args, object_type)?;
+        let result = hash_objects(&filters, object_type)?;
         return render_hash_object_output(&result, output);
     }
 
-    hash_objects_streaming(&args, object_type, output)
+    hash_objects_streaming(&filters, object_type, output)
+}
+
+/// The arguments plus the attributes used to pick each blob's [`BlobFilter`].
+struct Filters<'a> {
+    args: &'a HashObjectArgs,
+    attributes: Option<&'a Attributes>,
+}
+
+impl Filters<'_> {
+    /// The filter for input labelled `path` (relative to the current directory):
+    /// none without a path or attributes, otherwise the same one `add` uses.
+    fn for_path(&self, path: Option<&Path>) -> BlobFilter {
+        match (self.attributes, path) {
+            (Some(attributes), Some(path)) => {
+                let absolute = util::cur_dir().join(path);
+                let relative = util::to_workdir_path(&absolute);
+                BlobFilter::for_path(&absolute, &relative, attributes)
+            }
+            _ => BlobFilter::None,
+        }
+    }
 }
 
 /// Parse the `-t`/`--type` value, restricted to the four Git object types. Other
@@ -135,7 +181,8 @@ fn parse_git_object_type(value: &str) -> CliResult<ObjectType> {
     }
 }
 
-fn hash_objects(args: &HashObjectArgs, object_type: ObjectType) -> CliResult<HashObjectOutput> {
+fn hash_objects(filters: &Filters<'_>, object_type: ObjectType) -> CliResult<HashObjectOutput> {
+    let args = filters.args;
     let objects = if args.stdin {
         vec![hash_one_source(
             stdin_source(args),
@@ -143,6 +190,7 @@ fn hash_objects(args: &HashObjectArgs, object_type: ObjectType) -> CliResult<Has
             args.write,
             object_type,
             args.literally,
+            filters.for_path(args.filter_path.as_deref()),
         )?]
     } else {
         let paths = effective_paths(args)?;
@@ -154,6 +202,7 @@ fn hash_objects(args: &HashObjectArgs, object_type: ObjectType) -> CliResult<Has
                 args.write,
                 object_type,
                 args.literally,
+                filters.for_path(Some(args.filter_path.as_deref().unwrap_or(path))),
             )?);
         }
         entries
@@ -167,13 +216,14 @@ fn hash_objects(args: &HashObjectArgs, object_type: ObjectType) -> CliResult<Has
 }
 
 fn hash_objects_streaming(
-    args: &HashObjectArgs,
+    filters: &Filters<'_>,
     object_type: ObjectType,
     output: &OutputConfig,
 ) -> CliResult<()> {
     if output.quiet {
-        return hash_objects(args, object_type).map(|_| ());
+        return hash_objects(filters, object_type).map(|_| ());
     }
+    let args = filters.args;
 
     let stdout = io::stdout();
     let mut writer = stdout.lock();
@@ -185,6 +235,7 @@ fn hash_objects_streaming(
             args.write,
             object_type,
             args.literally,
+            filters.for_path(args.filter_path.as_deref()),
         )?;
         write_hash_line(&mut writer, &entry.oid)?;
         return Ok(());
@@ -197,6 +248,7 @@ fn hash_objects_streaming(
             args.write,
             object_type,
             args.literally,
+            filters.for_path(Some(args.filter_path.as_deref().unwrap_or(path))),
         )?;
         write_hash_line(&mut writer, &entry.oid)?;
     }
@@ -241,7 +293,22 @@ fn hash_one_source(
     write: bool,
     object_type: ObjectType,
     literally: bool,
+    filter: BlobFilter,
 ) -> CliResult<HashObjectEntry> {
+    let data = match filter {
+        BlobFilter::Lfs => {
+            let (pointer, oid) = lfs::generate_pointer_data(&data);
+            if write {
+                lfs::backup_lfs_data(&data, &oid).map_err(|error| {
+                    CliError::fatal(format!("failed to store LFS object {oid}: {error}"))
+                        .with_stable_code(StableErrorCode::IoWriteFailed)
+                })?;
+            }
+            pointer.into_bytes()
+        }
+        BlobFilter::Normalize(attrs) => gitattributes::convert_to_index(&attrs, data),
+        BlobFilter::None => data,
+    };
     let size = data.len();
     // The object id is SHA over the loose-object header `<type> <size>\0<content>`,
     // computed identically for every type (this matches `Blob::id` for blobs).
@@ -637,8 +704,15 @@ mod tests {
 
     #[test]
     fn hash_one_source_matches_git_empty_blob_hash() {
-        let entry = hash_one_source("-", Vec::new(), false, ObjectType::Blob, false)
-            .expect("hash empty source");
+        let entry = hash_one_source(
+            "-",
+            Vec::new(),
+            false,
+            ObjectType::Blob,
+            false,
+            BlobFilter::None,
+        )
+        .expect("hash empty source");
         assert_eq!(entry.oid, "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
         assert_eq!(entry.size, 0);
         assert!(!entry.written);
@@ -12,7 +12,7 @@ use serde::Serialize;
 use crate::{
     command::{
         index_pack_support::{format_io_error, index_pack_error, keep_file_path, write_keep_file},
-        index_pack_v1, index_pack_v2,
+        index_pack_thin, index_pack_v1, index_pack_v2,
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
@@ -26,6 +26,7 @@ EXAMPLES:
     libra index-pack --keep pack-123.pack           Build an idx and empty pack-123.keep
     libra index-pack --keep=message pack-123.pack   Build an idx and write message to .keep
     libra index-pack --stdin -o pack-123.idx        Read pack bytes from stdin
+    libra index-pack --fix-thin pack-123.pack       Append missing delta bases from the repository
     libra index-pack --progress pack-123.pack       Accept Git-style progress request
     libra index-pack --no-progress pack-123.pack    Accept Git-style progress suppression
     libra index-pack pack-123.pack -o pack-123.idx  Write the index to a specific path
@@ -61,9 +62,8 @@ pub struct IndexPackArgs {
 
     #[arg(
         long = "fix-thin",
-        help = "Accept Git's --fix-thin (thin-pack completion) flag as a no-op: Libra requires \
-                self-contained packs and does not resolve external delta bases, and never \
-                produces thin packs, so on the packs it indexes there is nothing to complete"
+        help = "Complete a thin pack: write a copy with the REF_DELTA bases the pack does not \
+                contain appended from the repository's object store, and index that copy"
     )]
     pub fix_thin: bool,
 }
@@ -74,6 +74,9 @@ struct IndexPackOutput {
     index_file: String,
     index_version: u8,
     keep_file: Option<String>,
+    /// Delta bases appended from the object store by `--fix-thin`; when
+    /// non-zero, `pack_file` is the completed copy.
+    appended_objects: usize,
 }
 
 pub fn execute(args: IndexPackArgs) {
@@ -92,15 +95,9 @@ pub fn execute_safe(args: IndexPackArgs, output: &OutputConfig) -> CliResult<()>
         fix_thin,
     } = args;
 
-    // `--fix-thin` is accepted for Git CLI compatibility but is a no-op. A thin
-    // pack carries `REF_DELTA` objects whose base objects live outside the pack;
-    // completing it means resolving those bases from the repository and appending
-    // them. Libra's pack decoder (git-internal) has no external-base resolver — it
-    // requires self-contained packs — and Libra never produces thin packs, so any
-    // pack that indexes successfully already had no external delta bases to add.
-    // This matches Git, where `--fix-thin` on a complete pack does nothing.
-    let _ = fix_thin;
-
+    // Without `-o` the index follows the pack, including the completed copy
+    // `--fix-thin` may write.
+    let explicit_index = index_file.is_some();
     let index_file = match index_file {
         Some(index_file) => index_file,
         None if stdin => {
@@ -146,17 +143,6 @@ pub fn execute_safe(args: IndexPackArgs, output: &OutputConfig) -> CliResult<()>
         );
     }
 
-    let keep_path = keep.as_ref().map(|_| keep_file_path(&pack_file));
-    if keep_path.as_ref() == Some(&std::path::PathBuf::from(&index_file)) {
-        return Err(
-            CliError::fatal("keep-file and index-file are the same file")
-                .with_stable_code(StableErrorCode::CliInvalidArguments),
-        );
-    }
-    let keep_file = keep_path
-        .as_ref()
-        .map(|path| path.to_string_lossy().into_owned());
-
     if stdin {
         read_stdin_to_pack_file(&pack_file)?;
     }
@@ -170,6 +156,37 @@ pub fn execute_safe(args: IndexPackArgs, output: &OutputConfig) -> CliResult<()>
         .with_stable_code(StableErrorCode::IoReadFailed)
     })?;
 
+    // A thin pack must be completed (or rejected) before indexing: the
+    // decoder behind `build_index_v*` only resolves in-pack delta bases.
+    let completed = if fix_thin {
+        index_pack_thin::complete_thin_pack(&pack_file).map_err(index_pack_error)?
+    } else {
+        index_pack_thin::reject_thin_pack(&pack_file).map_err(index_pack_error)?;
+        None
+    };
+    let (pack_file, index_file, appended_objects) = match completed {
+        Some(completed) => {
+            let index_file = if explicit_index {
+                index_file
+            } else {
+                completed.path.replace(".pack", ".idx")
+            };
+            (completed.path, index_file, completed.appended)
+        }
+        None => (pack_file, index_file, 0),
+    };
+
+    let keep_path = keep.as_ref().map(|_| keep_file_path(&pack_file));
+    if keep_path.as_ref() == Some(&std::path::PathBuf::from(&index_file)) {
+        return Err(
+            CliError::fatal("keep-file and index-file are the same file")
+                .with_stable_code(StableErrorCode::CliInvalidArguments),
+        );
+    }
+    let keep_file = keep_path
+        .as_ref()
+        .map(|path| path.to_string_lossy().into_owned());
+
     let index_version = match index_version {
         Some(1) => {
             build_index_v1(&pack_file, &index_file).map_err(index_pack_error)?;
@@ -198,6 +215,7 @@ pub fn execute_safe(args: IndexPackArgs, output: &OutputConfig) -> CliResult<()>
         index_file,
         index_version,
         keep_file,
+        appended_objects,
     };
 
     if output.is_json() {
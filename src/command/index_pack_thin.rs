@@ -0,0 +1,367 @@
+//! Delta resolution and thin-pack completion for `index-pack --fix-thin`.
+//!
+//! A thin pack (what `git fetch` sends) carries `REF_DELTA` objects whose
+//! base lives in the receiving repository instead of the pack. git-internal's
+//! decoder resolves `OFS_DELTA` and in-pack `REF_DELTA` chains but has no
+//! notion of an external base, so before indexing, [`complete_thin_pack`]
+//! (or, without `--fix-thin`, [`reject_thin_pack`]) walks the entry headers
+//! looking for `REF_DELTA`s. Only when it finds one
+//! does it resolve every delta itself — iteratively, each object as soon as
+//! its base is known, so chains resolve in topological order whatever their
+//! order in the pack. With `--fix-thin` the `REF_DELTA` bases nothing in the
+//! pack provides are read from the local object store; those bases are appended as whole
+//! objects to a copy of the pack, named after its checksum like Git's
+//! `pack-<hash>.pack`; the input pack is never modified.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{Cursor, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use flate2::{Compression, Decompress, FlushDecompress, Status, write::ZlibEncoder};
+use git_internal::{
+    errors::GitError,
+    hash::{ObjectHash, get_hash_kind},
+    internal::{
+        object::types::ObjectType,
+        pack::{Pack, cache_object::CacheObject},
+    },
+};
+
+use crate::utils::util;
+
+/// Size of the `PACK` magic, version and object-count header.
+const PACK_HEADER_LEN: usize = 12;
+
+/// Pack offset recorded for bases loaded from the object store; no delta in
+/// the pack can name it as an `OFS_DELTA` base.
+const EXTERNAL_OFFSET: usize = usize::MAX;
+
+/// A thin pack made self-contained by [`complete_thin_pack`].
+#[derive(Debug)]
+pub(crate) struct CompletedPack {
+    /// The new pack file, beside the input.
+    pub path: String,
+    /// Delta bases appended from the object store.
+    pub appended: usize,
+}
+
+/// Complete `pack_file` for `--fix-thin`. A pack without external delta
+/// bases needs nothing and yields `None`. Otherwise the missing bases are
+/// read from the local object store and a completed copy is written next to
+/// the input as `pack-<checksum>.pack`.
+pub(crate) fn complete_thin_pack(pack_file: &str) -> Result<Option<CompletedPack>, GitError> {
+    let pack = std::fs::read(pack_file)?;
+    if !has_ref_delta(&pack)? {
+        return Ok(None);
+    }
+    let storage = util::try_objects_storage().map_err(|_| {
+        GitError::InvalidArgument(
+            "--fix-thin requires a repository to read delta bases from".to_string(),
+        )
+    })?;
+    let lookup = |hash: &ObjectHash| {
+        let object_type = storage.get_object_type(hash).ok()?;
+        let data = storage.get(hash).ok()?;
+        Some((object_type, data))
+    };
+
+    let Some(external) = resolve_external_bases(&pack, lookup)? else {
+        return Ok(None);
+    };
+    if external.is_empty() {
+        return Ok(None);
+    }
+
+    let completed = append_objects(&pack, &external)?;
+    let checksum = &completed[completed.len() - get_hash_kind().size()..];
+    let file_name = format!("pack-{}.pack", hex::encode(checksum));
+    let path = Path::new(pack_file)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(file_name);
+    let tmp_path = path.with_extension("pack.tmp");
+    std::fs::write(&tmp_path, &completed)?;
+    std::fs::rename(&tmp_path, &path)?;
+    Ok(Some(CompletedPack {
+        path: path.to_string_lossy().into_owned(),
+        appended: external.len(),
+    }))
+}
+
+/// Reject a thin pack when `--fix-thin` is not given: git-internal's decoder
+/// asserts that every delta base was found, so an external base must be
+/// caught before indexing. Only packs holding a `REF_DELTA` are resolved,
+/// and the object store is never consulted.
+pub(crate) fn reject_thin_pack(pack_file: &str) -> Result<(), GitError> {
+    let pack = std::fs::read(pack_file)?;
+    if !has_ref_delta(&pack)? {
+        return Ok(());
+    }
+    match resolve_external_bases(&pack, |_| None) {
+        Err(GitError::ObjectNotFound(detail)) => Err(GitError::InvalidPackFile(format!(
+            "{detail}; rerun with --fix-thin to complete it"
+        ))),
+        result => result.map(|_| ()),
+    }
+}
+
+/// Cheap pre-pass: whether `pack` holds any `REF_DELTA` entry. Only entry
+/// headers are parsed; each payload is run through zlib into a scratch buffer
+/// solely to find where the next entry starts, so no object is materialized,
+/// hashed or delta-applied. Packs written with `ofs-delta` (what Git sends by
+/// default) skip the resolve entirely, as do Libra's zstd offset-delta packs,
+/// which git-internal resolves itself.
+fn has_ref_delta(pack: &[u8]) -> Result<bool, GitError> {
+    let mut reader = Cursor::new(pack);
+    let (count, _) = Pack::check_header(&mut reader)?;
+    let truncated = || GitError::InvalidPackFile("pack is truncated".to_string());
+    let mut offset = PACK_HEADER_LEN;
+    for _ in 0..count {
+        let mut byte = *pack.get(offset).ok_or_else(truncated)?;
+        offset += 1;
+        let object_type = ObjectType::from_pack_type_u8((byte >> 4) & 0x07)?;
+        while byte & 0x80 != 0 {
+            byte = *pack.get(offset).ok_or_else(truncated)?;
+            offset += 1;
+        }
+        match object_type {
+            ObjectType::HashDelta => return Ok(true),
+            ObjectType::OffsetZstdelta => return Ok(false),
+            ObjectType::OffsetDelta => loop {
+                let byte = *pack.get(offset).ok_or_else(truncated)?;
+                offset += 1;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            },
+            _ => {}
+        }
+        offset = skip_zlib_stream(pack, offset)?;
+    }
+    Ok(false)
+}
+
+/// Offset just past the zlib stream starting at `offset`. The inflated bytes
+/// are discarded.
+fn skip_zlib_stream(pack: &[u8], offset: usize) -> Result<usize, GitError> {
+    let input = pack
+        .get(offset..)
+        .ok_or_else(|| GitError::InvalidPackFile("pack is truncated".to_string()))?;
+    let mut inflater = Decompress::new(true);
+    let mut scratch = [0u8; 8192];
+    loop {
+        let (read, written) = (inflater.total_in(), inflater.total_out());
+        let status = inflater
+            .decompress(&input[read as usize..], &mut scratch, FlushDecompress::None)
+            .map_err(|e| GitError::InvalidPackFile(format!("corrupt entry data: {e}")))?;
+        if status == Status::StreamEnd {
+            return Ok(offset + inflater.total_in() as usize);
+        }
+        if inflater.total_in() == read && inflater.total_out() == written {
+            return Err(GitError::InvalidPackFile("pack is truncated".to_string()));
+        }
+    }
+}
+
+/// Resolve every delta in `pack`, looking up `REF_DELTA` bases the pack does
+/// not provide with `lookup`. Returns those external bases (type and
+/// content, in the order they were needed), or `None` for packs using
+/// Libra's zstd offset deltas, which git-internal resolves itself and which
+/// never form thin packs. A base that neither the pack nor `lookup` provides
+/// is an error.
+fn resolve_external_bases(
+    pack: &[u8],
+    lookup: impl Fn(&ObjectHash) -> Option<(ObjectType, Vec<u8>)>,
+) -> Result<Option<Vec<(ObjectType, Vec<u8>)>>, GitError> {
+    let mut reader = Cursor::new(pack);
+    let (count, _) = Pack::check_header(&mut reader)?;
+    let mut offset = PACK_HEADER_LEN;
+
+    let mut resolved: VecDeque<Arc<CacheObject>> = VecDeque::new();
+    let mut by_offset: HashMap<usize, Vec<CacheObject>> = HashMap::new();
+    let mut by_hash: HashMap<ObjectHash, Vec<CacheObject>> = HashMap::new();
+    for _ in 0..count {
+        let Some(object) = Pack::decode_pack_object(&mut reader, &mut offset)? else {
+            continue;
+        };
+        if object.base_object_hash().is_some() {
+            resolved.push_back(Arc::new(object));
+        } else if let Some(base) = object.offset_delta() {
+            by_offset.entry(base).or_default().push(object);
+        } else if let Some(base) = object.hash_delta() {
+            by_hash.entry(base).or_default().push(object);
+        } else {
+            return Ok(None);
+        }
+    }
+
+    let mut known: HashSet<ObjectHash> = HashSet::new();
+    let mut external = Vec::new();
+    loop {
+        while let Some(base) = resolved.pop_front() {
+            let Some(hash) = base.base_object_hash() else {
+                continue;
+            };
+            known.insert(hash);
+            let mut waiting = by_hash.remove(&hash).unwrap_or_default();
+            if base.offset != EXTERNAL_OFFSET {
+                waiting.extend(by_offset.remove(&base.offset).unwrap_or_default());
+            }
+            for delta in waiting {
+                resolved.push_back(Arc::new(Pack::rebuild_delta(delta, base.clone())));
+            }
+        }
+
+        // Everything reachable from in-pack bases is resolved; the remaining
+        // `REF_DELTA` bases must come from outside the pack.
+        let missing: Vec<ObjectHash> = by_hash
+            .keys()
+            .filter(|hash| !known.contains(hash))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            break;
+        }
+        for hash in missing {
+            let (object_type, data) = lookup(&hash).ok_or_else(|| {
+                GitError::ObjectNotFound(format!("delta base {hash} is not in the pack"))
+            })?;
+            let base = CacheObject::new_for_undeltified(object_type, data, EXTERNAL_OFFSET, 0);
+            if base.base_object_hash() != Some(hash) {
+                return Err(GitError::InvalidObjectInfo(format!(
+                    "delta base {hash} in the object store does not match its id"
+                )));
+            }
+            external.push((object_type, base.data_decompressed.clone()));
+            resolved.push_back(Arc::new(base));
+        }
+    }
+
+    if let Some(base) = by_offset.keys().min() {
+        return Err(GitError::DeltaObjectError(format!(
+            "no object at offset {base} for an offset delta to apply to"
+        )));
+    }
+    Ok(Some(external))
+}
+
+/// `pack` with `objects` appended as undeltified entries: the header count
+/// is bumped and the trailing checksum recomputed.
+fn append_objects(pack: &[u8], objects: &[(ObjectType, Vec<u8>)]) -> Result<Vec<u8>, GitError> {
+    let hash_len = get_hash_kind().size();
+    let body_end = pack
+        .len()
+        .checked_sub(hash_len)
+        .filter(|end| *end >= PACK_HEADER_LEN)
+        .ok_or_else(|| GitError::InvalidPackFile("pack is truncated".to_string()))?;
+    let count = u32::from_be_bytes([pack[8], pack[9], pack[10], pack[11]]);
+    let count = u32::try_from(objects.len())
+        .ok()
+        .and_then(|added| count.checked_add(added))
+        .ok_or_else(|| GitError::InvalidPackFile("too many objects in pack".to_string()))?;
+
+    let mut out = Vec::with_capacity(pack.len());
+    out.extend_from_slice(&pack[..8]);
+    out.extend_from_slice(&count.to_be_bytes());
+    out.extend_from_slice(&pack[PACK_HEADER_LEN..body_end]);
+    for (object_type, data) in objects {
+        write_entry_header(&mut out, object_type.to_pack_type_u8()?, data.len());
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        out.extend_from_slice(&encoder.finish()?);
+    }
+    let trailer = ObjectHash::new(&out);
+    out.extend_from_slice(trailer.as_ref());
+    Ok(out)
+}
+
+/// Pack entry header: 3-bit type and the size as a little-endian varint,
+/// four bits in the first byte and seven in each following one.
+fn write_entry_header(out: &mut Vec<u8>, type_bits: u8, size: usize) {
+    let mut byte = (type_bits << 4) | (size & 0x0f) as u8;
+    let mut rest = size >> 4;
+    while rest != 0 {
+        out.push(byte | 0x80);
+        byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+    }
+    out.push(byte);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pack holding the blob `base` and one `REF_DELTA` against `delta_base`.
+    fn pack_with_ref_delta(base: &[u8], delta_base: ObjectHash) -> Vec<u8> {
+        let zlib = |data: &[u8]| {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        };
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        write_entry_header(&mut pack, 3, base.len());
+        pack.extend(zlib(base));
+        // Source size, target size, then "insert one byte".
+        let delta = [base.len() as u8, 1, 1, b'x'];
+        write_entry_header(&mut pack, 7, delta.len());
+        pack.extend_from_slice(delta_base.as_ref());
+        pack.extend(zlib(&delta));
+        let trailer = ObjectHash::new(&pack);
+        pack.extend_from_slice(trailer.as_ref());
+        pack
+    }
+
+    #[test]
+    fn scan_finds_ref_deltas_from_headers() {
+        let base = ObjectHash::from_type_and_data(ObjectType::Blob, b"base");
+        assert!(has_ref_delta(&pack_with_ref_delta(b"base", base)).unwrap());
+
+        let mut whole_only = b"PACK".to_vec();
+        whole_only.extend_from_slice(&2u32.to_be_bytes());
+        whole_only.extend_from_slice(&1u32.to_be_bytes());
+        write_entry_header(&mut whole_only, 3, 4);
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"base").unwrap();
+        whole_only.extend(encoder.finish().unwrap());
+        let trailer = ObjectHash::new(&whole_only);
+        whole_only.extend_from_slice(trailer.as_ref());
+        assert!(!has_ref_delta(&whole_only).unwrap());
+    }
+
+    #[test]
+    fn resolve_reports_only_bases_outside_the_pack() {
+        let in_pack = ObjectHash::from_type_and_data(ObjectType::Blob, b"base");
+        let elsewhere = ObjectHash::from_type_and_data(ObjectType::Blob, b"else");
+        let none = |_: &ObjectHash| None;
+        assert_eq!(
+            resolve_external_bases(&pack_with_ref_delta(b"base", in_pack), none)
+                .unwrap()
+                .map(|external| external.len()),
+            Some(0)
+        );
+        let store =
+            |hash: &ObjectHash| (*hash == elsewhere).then(|| (ObjectType::Blob, b"else".to_vec()));
+        let external = resolve_external_bases(&pack_with_ref_delta(b"base", elsewhere), store)
+            .unwrap()
+            .unwrap();
+        assert_eq!(external, vec![(ObjectType::Blob, b"else".to_vec())]);
+    }
+
+    #[test]
+    fn write_entry_header_encodes_type_and_varint_size() {
+        let mut out = Vec::new();
+        write_entry_header(&mut out, 3, 5);
+        assert_eq!(out, [0x35]);
+
+        let mut out = Vec::new();
+        write_entry_header(&mut out, 1, 300);
+        // 300 = 0b1_0010_1100: low nibble 0xc, then 0b1_0010 = 18.
+        assert_eq!(out, [0x80 | 0x10 | 0x0c, 18]);
+    }
+}
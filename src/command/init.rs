@@ -1264,8 +1264,14 @@ async fn init_vault_for_repo(root_dir: &Path, database_path: &Path) -> Result<()
         });
     }
 
-    if let Err(error) =
-        vault::generate_pgp_key(root_dir, &unseal_key, &user_name, &user_email).await
+    if let Err(error) = vault::generate_pgp_key(
+        root_dir,
+        &unseal_key,
+        vault::DEFAULT_PGP_KEY_NAME,
+        &user_name,
+        &user_email,
+    )
+    .await
     {
         rollback_failed_vault_init(root_dir).await;
         return Err(InitError::VaultInitializationFailed {
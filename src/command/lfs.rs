@@ -1,6 +1,7 @@
 //! LFS subcommands for authentication, batch negotiation, lock management, and integrating media storage with standard workflows.
 
 use std::{
+    collections::{BTreeMap, HashSet},
     fs::{File, OpenOptions},
     io,
     io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
@@ -8,22 +9,32 @@ use std::{
 };
 
 use clap::Subcommand;
-use git_internal::internal::index::Index;
+use git_internal::{
+    hash::ObjectHash,
+    internal::{
+        index::Index,
+        object::{commit::Commit, tree::Tree},
+    },
+};
 use reqwest::StatusCode;
 
 use crate::{
     command::{
-        lfs_schema::{LfsFileOutput, LfsOutput},
-        status,
+        diff, lfs_fetch,
+        lfs_migrate::{self, LfsMigrateCmds},
+        lfs_schema::{LfsFileOutput, LfsOutput, LfsStatusEntry, LfsStatusOutput, LfsStatusSide},
+        load_object, status,
     },
     internal::{
         head::Head,
         protocol::lfs_client::{LFSClient, LockListError},
     },
-    lfs_structs::LockListQuery,
+    lfs_structs::{LockListQuery, Ref, VerifiableLockRequest},
     utils::{
         error::{CliError, CliResult, StableErrorCode},
+        gitattributes::{self, Attributes},
         lfs,
+        object_ext::TreeExt,
         output::{OutputConfig, emit_json_data},
         path,
         path_ext::PathExt,
@@ -34,9 +45,10 @@ use crate::{
 /// `--help` examples shown in `libra lfs --help` output (attached in
 /// `src/cli.rs` via `after_help` on the `Lfs` subcommand).
 ///
-/// `lfs` exposes six sub-commands: `track` (read/add attributes patterns),
-/// `untrack`, `ls-files`, and the three lock-server flows (`locks`,
-/// `lock`, `unlock`). The banner pins the canonical invocation per
+/// `lfs` exposes nine sub-commands: `track` (read/add attributes patterns),
+/// `untrack`, `ls-files`, `status`, `fetch` (pre-populate the object cache),
+/// the three lock-server flows (`locks`, `lock`, `unlock`), and the
+/// `migrate import|export` history rewrite. The banner pins the canonical invocation per
 /// sub-command plus a JSON variant so users can map intent to invocation
 /// without reading the design doc. Cross-cutting `--help` EXAMPLES
 /// rollout per `docs/development/commands/_general.md` item B.
@@ -47,10 +59,17 @@ EXAMPLES:
     libra lfs untrack '*.bin'             Remove an LFS pattern
     libra lfs ls-files                    List LFS-tracked files in the working tree
     libra lfs ls-files --long --size      Show full OIDs and sizes
+    libra lfs status                      Show staged LFS objects and unstaged LFS-tracked changes
+    libra lfs fetch --recent              Cache LFS objects from branches updated in the last 7 days
+    libra lfs fetch --all                 Cache every LFS object reachable from any ref
     libra lfs locks                       List remote locks for the current branch
     libra lfs lock build/output.bin       Acquire a remote lock on a file
     libra lfs unlock build/output.bin     Release a lock you own
     libra lfs unlock --force --id <id>    Force-release a lock owned by someone else
+    libra lfs migrate import --include='*.psd'
+                                          Rewrite the current branch to store *.psd in LFS
+    libra lfs migrate export --include='*.psd' --everything
+                                          Turn *.psd pointers back into files on all branches
     libra lfs --json ls-files             Structured JSON output for agents";
 
 /// [Docs](https://github.com/git-lfs/git-lfs/tree/main/docs/man)
@@ -106,6 +125,30 @@ pub enum LfsCmds {
         #[clap(long, short)]
         name_only: bool,
     },
+    /// Show LFS objects staged for commit and LFS-tracked files changed in the working tree
+    Status,
+    /// Download LFS objects into the local cache without checking them out (default: HEAD)
+    Fetch {
+        /// Also fetch objects from commits of the last DAYS days (default 7, as Git LFS's
+        /// `lfs.fetchrecentrefsdays`) on recently updated branches
+        #[clap(
+            long,
+            value_name = "DAYS",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "7",
+            conflicts_with = "all"
+        )]
+        recent: Option<u64>,
+        /// Fetch every LFS object reachable from any ref
+        #[clap(long)]
+        all: bool,
+    },
+    /// Rewrite history to move matching files into or out of LFS
+    Migrate {
+        #[command(subcommand)]
+        command: LfsMigrateCmds,
+    },
 }
 
 pub async fn execute(cmd: LfsCmds) -> CliResult<()> {
@@ -160,6 +203,9 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
         LfsCmds::Locks { id, path, limit } => {
             let refspec = current_refspec_or_err().await?;
             tracing::debug!("refspec: {}", refspec);
+            // Only a complete listing may drop cached locks that the server
+            // no longer reports.
+            let unfiltered = id.is_none() && path.is_none() && limit.is_none();
             let query = LockListQuery {
                 id: id.unwrap_or_default(),
                 path: path.unwrap_or_default(),
@@ -167,16 +213,22 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
                 cursor: "".to_string(),
                 refspec: refspec.clone(),
             };
-            let locks = LFSClient::get()
-                .await
-                .map_err(|e| {
-                    CliError::fatal(e.to_string())
-                        .with_stable_code(StableErrorCode::NetworkUnavailable)
-                })?
+            let client = LFSClient::get().await.map_err(|e| {
+                CliError::fatal(e.to_string()).with_stable_code(StableErrorCode::NetworkUnavailable)
+            })?;
+            let locks = client
                 .get_locks(query)
                 .await
                 .map_err(map_lock_list_error)?
                 .locks;
+            let server_ours = verified_own_lock_ids(client, &refspec).await;
+            let mut cache = lfs::LockCache::load();
+            if unfiltered {
+                cache.refresh(&locks, server_ours.as_ref());
+            } else {
+                cache.merge(&locks, server_ours.as_ref());
+            }
+            save_lock_cache(&cache);
             Ok(LfsOutput {
                 action: "locks".to_string(),
                 locks,
@@ -194,7 +246,7 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
             }
 
             let refspec = current_refspec_or_err().await?;
-            let code = LFSClient::get()
+            let (code, lock) = LFSClient::get()
                 .await
                 .map_err(|e| {
                     CliError::fatal(e.to_string())
@@ -221,9 +273,18 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
                 ))
                 .with_detail("status", code.as_u16()));
             }
+            // Remember the id so `lfs unlock <path>` does not need to look it
+            // up again.
+            let id = lock.map(|lock| {
+                let mut cache = lfs::LockCache::load();
+                cache.record_ours(&lock);
+                save_lock_cache(&cache);
+                lock.id
+            });
             Ok(LfsOutput {
                 action: "lock".to_string(),
                 path: Some(path),
+                id,
                 refspec: Some(refspec),
                 ..LfsOutput::default()
             })
@@ -248,7 +309,9 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
                 }
             }
             let refspec = current_refspec_or_err().await?;
-            let id = match id {
+            let mut cache = lfs::LockCache::load();
+            let cached_id = cache.ours_for_path(&path).map(|lock| lock.id.clone());
+            let id = match id.or(cached_id) {
                 None => {
                     // get id by path
                     let locks = LFSClient::get()
@@ -287,6 +350,12 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
                     CliError::network(format!("LFS unlock request failed: {e}"))
                         .with_stable_code(StableErrorCode::NetworkUnavailable)
                 })?;
+            if code == StatusCode::NOT_FOUND {
+                // Released elsewhere (e.g. force-unlocked by an admin): the
+                // cached id is stale either way.
+                cache.remove(&id);
+                save_lock_cache(&cache);
+            }
             if code == StatusCode::FORBIDDEN {
                 return Err(CliError::fatal("You must have push access to unlock")
                     .with_stable_code(StableErrorCode::AuthPermissionDenied));
@@ -297,6 +366,8 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
                 ))
                 .with_detail("status", code.as_u16()));
             }
+            cache.remove(&id);
+            save_lock_cache(&cache);
             Ok(LfsOutput {
                 action: "unlock".to_string(),
                 path: Some(path),
@@ -358,6 +429,13 @@ async fn run_lfs(cmd: LfsCmds) -> CliResult<LfsOutput> {
                 ..LfsOutput::default()
             })
         }
+        LfsCmds::Status => Ok(LfsOutput {
+            action: "status".to_string(),
+            status: Some(lfs_status().await?),
+            ..LfsOutput::default()
+        }),
+        LfsCmds::Fetch { recent, all } => lfs_fetch::run_fetch(recent, all).await,
+        LfsCmds::Migrate { command } => lfs_migrate::run_migrate(command).await,
     }
 }
 
@@ -463,12 +541,216 @@ fn render_lfs_output(result: &LfsOutput, output: &OutputConfig) -> CliResult<()>
                 }
             }
         }
+        "status" => {
+            let Some(status) = &result.status else {
+                return Ok(());
+            };
+            match &status.branch {
+                Some(branch) => println!("On branch {branch}"),
+                None => println!("HEAD detached"),
+            }
+            println!();
+            println!("Objects to be committed:");
+            println!();
+            for entry in &status.staged {
+                println!("\t{} ({})", entry.path, describe_status_entry(entry));
+            }
+            println!();
+            println!("Objects not staged for commit:");
+            println!();
+            for entry in &status.unstaged {
+                println!("\t{} ({})", entry.path, describe_status_entry(entry));
+            }
+        }
+        "fetch" => {
+            let Some(fetch) = &result.fetch else {
+                return Ok(());
+            };
+            for oid in &fetch.fetched {
+                println!("fetch: {oid}");
+            }
+            println!(
+                "fetch: fetched {} object(s), {} already cached ({} commit(s) scanned)",
+                fetch.fetched.len(),
+                fetch.already_cached,
+                fetch.commits_scanned
+            );
+        }
+        "migrate-import" | "migrate-export" => {
+            let Some(migrate) = &result.migrate else {
+                return Ok(());
+            };
+            if migrate.refs.is_empty() {
+                println!("No commits needed rewriting");
+            }
+            for branch in &migrate.refs {
+                println!(
+                    "migrate: {}: {} -> {}",
+                    branch.name,
+                    &branch.old[..7.min(branch.old.len())],
+                    &branch.new[..7.min(branch.new.len())]
+                );
+            }
+            println!(
+                "migrate: rewrote {} commit(s), converted {} file version(s)",
+                migrate.commits_rewritten, migrate.blobs_converted
+            );
+        }
         _ => {}
     }
 
     Ok(())
 }
 
+/// `lfs status`: LFS-tracked paths (per `.libra_attributes` or a
+/// `filter=lfs` attribute) whose index entry differs from HEAD, and those whose
+/// working-tree content differs from the index. The working tree is compared
+/// by content, so a checked-out pointer file matches its staged pointer.
+async fn lfs_status() -> CliResult<LfsStatusOutput> {
+    let branch = match Head::current().await {
+        Head::Branch(name) => Some(name),
+        Head::Detached(_) => None,
+    };
+    let index = Index::load(path::index())
+        .map_err(|e| CliError::io(format!("failed to load index: {e}")))?;
+    let attributes = Attributes::for_worktree().await;
+    let is_tracked = |name: &str| {
+        lfs::is_lfs_tracked(util::workdir_to_absolute(name))
+            || gitattributes::is_lfs_filter(&attributes.lookup(Path::new(name)))
+    };
+
+    let mut head_blobs: BTreeMap<String, ObjectHash> = BTreeMap::new();
+    if let Some(head) = Head::current_commit().await {
+        let tree = load_object::<Commit>(&head)
+            .and_then(|commit| load_object::<Tree>(&commit.tree_id))
+            .map_err(|e| CliError::io(format!("failed to read HEAD tree: {e}")))?;
+        for (item, hash) in tree.get_plain_items() {
+            head_blobs.insert(item.to_string_lossy().into_owned(), hash);
+        }
+    }
+
+    let storage = util::objects_storage();
+    let read_blob = |hash: &ObjectHash| {
+        storage
+            .get(hash)
+            .map_err(|e| CliError::io(format!("failed to read blob {hash}: {e}")))
+    };
+    // Pointer blobs are a few hundred bytes, so a larger blob is plain Git
+    // content and is classified from its header size without being read.
+    let blob_side = |hash: &ObjectHash| -> CliResult<LfsStatusSide> {
+        let size = storage
+            .object_size(hash)
+            .map_err(|e| CliError::io(format!("failed to read blob {hash}: {e}")))?;
+        let pointer = if size > lfs::LFS_POINTER_MAX_SIZE {
+            None
+        } else {
+            lfs::parse_pointer_data(&read_blob(hash)?)
+        };
+        Ok(match pointer {
+            Some((oid, _)) => LfsStatusSide {
+                kind: "lfs".to_string(),
+                oid,
+            },
+            None => LfsStatusSide {
+                kind: "git".to_string(),
+                oid: hash.to_string(),
+            },
+        })
+    };
+
+    let mut status = LfsStatusOutput {
+        branch,
+        ..LfsStatusOutput::default()
+    };
+    let mut staged_paths = BTreeMap::new();
+    for entry in index.tracked_entries(0) {
+        if !is_tracked(&entry.name) {
+            continue;
+        }
+        staged_paths.insert(entry.name.clone(), entry.hash);
+
+        let side = blob_side(&entry.hash)?;
+        let head = head_blobs.get(&entry.name);
+        if head != Some(&entry.hash) {
+            status.staged.push(LfsStatusEntry {
+                path: entry.name.clone(),
+                from: head.map(&blob_side).transpose()?,
+                to: Some(side.clone()),
+            });
+        }
+
+        let worktree = util::workdir_to_absolute(&entry.name);
+        if !worktree.is_file() {
+            status.unstaged.push(LfsStatusEntry {
+                path: entry.name.clone(),
+                from: Some(side),
+                to: None,
+            });
+            continue;
+        }
+        // Unchanged since it was staged: nothing to hash.
+        if diff::index_hash_if_worktree_stat_matches(Path::new(&entry.name), &index)
+            == Some(entry.hash)
+        {
+            continue;
+        }
+        let content_oid = if side.kind == "lfs" {
+            side.oid.clone()
+        } else {
+            lfs::generate_pointer_data(&read_blob(&entry.hash)?).1
+        };
+        let (kind, oid) = match lfs::parse_pointer_file(&worktree) {
+            Ok((oid, _)) => ("lfs", oid),
+            Err(_) => (
+                "file",
+                lfs::calc_lfs_file_hash(&worktree)
+                    .map_err(|e| CliError::io(format!("failed to read '{}': {e}", entry.name)))?,
+            ),
+        };
+        if oid != content_oid {
+            status.unstaged.push(LfsStatusEntry {
+                path: entry.name.clone(),
+                from: Some(side),
+                to: Some(LfsStatusSide {
+                    kind: kind.to_string(),
+                    oid,
+                }),
+            });
+        }
+    }
+    for (name, hash) in &head_blobs {
+        if !staged_paths.contains_key(name) && is_tracked(name) {
+            status.staged.push(LfsStatusEntry {
+                path: name.clone(),
+                from: Some(blob_side(hash)?),
+                to: None,
+            });
+        }
+    }
+    status.staged.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(status)
+}
+
+/// `LFS: 1a2b3c4`, `Git: 5d6e7f8 -> LFS: 1a2b3c4`, `File: ... -> deleted`.
+fn describe_status_entry(entry: &LfsStatusEntry) -> String {
+    let side = |side: &LfsStatusSide| {
+        let label = match side.kind.as_str() {
+            "lfs" => "LFS",
+            "git" => "Git",
+            _ => "File",
+        };
+        format!("{label}: {}", &side.oid[..7.min(side.oid.len())])
+    };
+    let to = entry
+        .to
+        .as_ref()
+        .map_or_else(|| "deleted".to_string(), side);
+    match &entry.from {
+        Some(from) => format!("{} -> {to}", side(from)),
+        None => to,
+    }
+}
+
 pub(crate) async fn current_refspec() -> Option<String> {
     match Head::current().await {
         Head::Branch(name) => Some(format!("refs/heads/{name}")),
@@ -489,6 +771,44 @@ async fn current_refspec_or_err() -> CliResult<String> {
     })
 }
 
+/// The lock cache is an optimization for `unlock` and the commit/checkout
+/// warnings; failing to persist it must not fail the lock operation itself.
+/// Ids of the locks the server attributes to the current user, from the
+/// `locks/verify` endpoint (Git LFS's `ours`/`theirs` split). `None` when the
+/// server does not implement it or the request fails; the lock cache then
+/// falls back to the ids acquired through this client.
+async fn verified_own_lock_ids(client: &LFSClient, refspec: &str) -> Option<HashSet<String>> {
+    let mut ids = HashSet::new();
+    let mut cursor = None;
+    loop {
+        let (code, list) = client
+            .verify_locks(VerifiableLockRequest {
+                refs: Ref {
+                    name: refspec.to_string(),
+                },
+                cursor: cursor.take(),
+                limit: None,
+            })
+            .await
+            .inspect_err(|err| tracing::debug!(error = %err, "LFS verify locks request failed"))
+            .ok()?;
+        if !code.is_success() {
+            return None;
+        }
+        ids.extend(list.ours.into_iter().map(|lock| lock.id));
+        if list.next_cursor.is_empty() {
+            return Some(ids);
+        }
+        cursor = Some(list.next_cursor);
+    }
+}
+
+fn save_lock_cache(cache: &lfs::LockCache) {
+    if let Err(err) = cache.save() {
+        tracing::warn!(error = %err, "failed to update LFS lock cache");
+    }
+}
+
 fn map_lock_list_error(error: LockListError) -> CliError {
     match error {
         LockListError::Request(detail) => {
@@ -578,7 +898,13 @@ fn untrack_lfs_patterns(file_path: &str, patterns: Vec<String>) -> io::Result<Ve
         // form).
         for pattern in &patterns {
             let escaped = pattern.replace(" ", r"\ ");
-            if line.trim_start().starts_with(&escaped) && line.contains("filter=lfs") {
+            // Compare the whole pattern token so untracking `*.b` leaves a
+            // `*.bin` line alone.
+            let whole_token = line
+                .trim_start()
+                .strip_prefix(&escaped)
+                .is_some_and(|rest| rest.starts_with([' ', '\t']));
+            if whole_token && line.contains("filter=lfs") {
                 matched_pattern = Some(pattern.clone());
                 break;
             }
@@ -677,4 +1003,19 @@ mod tests {
         let on_disk = lfs::extract_lfs_patterns(&path).expect("extract");
         assert!(on_disk.is_empty(), "expected empty, got {on_disk:?}");
     }
+
+    #[test]
+    fn untrack_lfs_patterns_matches_whole_pattern_only() {
+        let tmp = tempfile::NamedTempFile::new().expect("tempfile");
+        let path = tmp.path().to_string_lossy().into_owned();
+        add_lfs_patterns(&path, vec!["*.bin".to_string(), "*.b".to_string()])
+            .expect("add_lfs_patterns");
+
+        let removed =
+            untrack_lfs_patterns(&path, vec!["*.b".to_string()]).expect("untrack_lfs_patterns");
+        assert_eq!(removed, vec!["*.b".to_string()]);
+
+        let on_disk = lfs::extract_lfs_patterns(&path).expect("extract");
+        assert_eq!(on_disk, vec!["*.bin".to_string()]);
+    }
 }
@@ -0,0 +1,271 @@
+//! `lfs fetch`: download the LFS objects referenced by commits into the local
+//! LFS cache (`.libra/lfs/objects`) without checking anything out, so a later
+//! checkout can smudge pointers while offline.
+//!
+//! Which commits are scanned depends on the flags: only `HEAD` by default,
+//! every commit from the last N days on recently updated branches with
+//! `--recent`, and every commit reachable from any ref with `--all`. Objects
+//! already in the cache are skipped; the rest are negotiated through the LFS
+//! batch API.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use git_internal::{
+    hash::ObjectHash,
+    internal::object::{
+        blob::Blob,
+        commit::Commit,
+        tree::{Tree, TreeItemMode},
+        types::ObjectType,
+    },
+};
+
+use crate::{
+    command::{
+        lfs_schema::{LfsFetchFailure, LfsFetchOutput, LfsOutput},
+        load_object,
+    },
+    internal::{
+        branch::Branch, config::ConfigKv, head::Head, protocol::lfs_client::LFSClient, tag,
+    },
+    utils::{
+        client_storage::ClientStorage,
+        error::{CliError, CliResult, StableErrorCode},
+        lfs, util,
+    },
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+pub(crate) async fn run_fetch(recent: Option<u64>, all: bool) -> CliResult<LfsOutput> {
+    let commits = if all {
+        let tips = all_ref_tips().await?;
+        reachable_commits(&tips, None)?
+    } else if let Some(days) = recent {
+        let cutoff = now_secs().saturating_sub(days.saturating_mul(SECONDS_PER_DAY));
+        let tips = recent_branch_tips(cutoff).await?;
+        reachable_commits(&tips, Some(cutoff))?
+    } else {
+        Head::current_commit().await.into_iter().collect()
+    };
+
+    let mut pointers = BTreeMap::new();
+    let mut seen_trees = HashSet::new();
+    let mut seen_blobs = HashSet::new();
+    let storage = util::objects_storage();
+    for id in &commits {
+        let commit: Commit = load(id, "commit")?;
+        collect_pointers(
+            &storage,
+            commit.tree_id,
+            &mut seen_trees,
+            &mut seen_blobs,
+            &mut pointers,
+        )?;
+    }
+
+    let objects_referenced = pointers.len();
+    let missing: Vec<(String, u64)> = pointers
+        .into_iter()
+        .filter(|(oid, _)| !lfs::lfs_object_path(oid).exists())
+        .collect();
+    let mut output = LfsFetchOutput {
+        commits_scanned: commits.len(),
+        objects_referenced,
+        already_cached: objects_referenced - missing.len(),
+        ..LfsFetchOutput::default()
+    };
+
+    if !missing.is_empty() {
+        let report = LFSClient::get()
+            .await
+            .map_err(|e| {
+                CliError::fatal(e.to_string()).with_stable_code(StableErrorCode::NetworkUnavailable)
+            })?
+            .fetch_objects(&missing)
+            .await
+            .map_err(|e| {
+                CliError::network(format!("LFS batch download failed: {e}"))
+                    .with_stable_code(StableErrorCode::NetworkUnavailable)
+            })?;
+        output.fetched = report.fetched;
+        output.failed = report
+            .failed
+            .into_iter()
+            .map(|(oid, reason)| LfsFetchFailure { oid, reason })
+            .collect();
+    }
+
+    if let Some(first) = output.failed.first() {
+        return Err(CliError::network(format!(
+            "failed to fetch {} LFS object(s); first: {}: {}",
+            output.failed.len(),
+            first.oid,
+            first.reason
+        ))
+        .with_stable_code(StableErrorCode::NetworkProtocol)
+        .with_detail("fetched", output.fetched.len())
+        .with_detail("failed", output.failed.len()));
+    }
+
+    Ok(LfsOutput {
+        action: "fetch".to_string(),
+        fetch: Some(output),
+        ..LfsOutput::default()
+    })
+}
+
+/// `HEAD` plus every local and remote-tracking branch whose tip was committed
+/// at or after `cutoff`.
+async fn recent_branch_tips(cutoff: u64) -> CliResult<Vec<ObjectHash>> {
+    let mut tips: Vec<ObjectHash> = Head::current_commit().await.into_iter().collect();
+    for branch in all_branches().await? {
+        let commit: Commit = load(&branch.commit, "commit")?;
+        if commit.committer.timestamp as u64 >= cutoff {
+            tips.push(branch.commit);
+        }
+    }
+    Ok(tips)
+}
+
+/// `HEAD`, every local and remote-tracking branch, and every tag that peels
+/// to a commit.
+async fn all_ref_tips() -> CliResult<Vec<ObjectHash>> {
+    let mut tips: Vec<ObjectHash> = Head::current_commit().await.into_iter().collect();
+    tips.extend(
+        all_branches()
+            .await?
+            .into_iter()
+            .map(|branch| branch.commit),
+    );
+    let tags = tag::list().await.map_err(|e| {
+        CliError::fatal(format!("failed to list tags: {e}"))
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    })?;
+    for t in tags {
+        match t.object {
+            tag::TagObject::Commit(commit) => tips.push(commit.id),
+            tag::TagObject::Tag(tag_obj) if tag_obj.object_type == ObjectType::Commit => {
+                tips.push(tag_obj.object_hash)
+            }
+            _ => {}
+        }
+    }
+    Ok(tips)
+}
+
+async fn all_branches() -> CliResult<Vec<Branch>> {
+    let list_err = |e: &dyn std::fmt::Display| {
+        CliError::fatal(format!("failed to list branches: {e}"))
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    };
+    let mut branches = Branch::list_branches_result(None)
+        .await
+        .map_err(|e| list_err(&e))?;
+    let remotes = ConfigKv::all_remote_configs().await.map_err(|e| {
+        CliError::fatal(format!("failed to list remotes: {e}"))
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    })?;
+    for remote in remotes {
+        branches.extend(
+            Branch::list_branches_result(Some(&remote.name))
+                .await
+                .map_err(|e| list_err(&e))?,
+        );
+    }
+    Ok(branches)
+}
+
+/// Commits reachable from `tips`. With a `cutoff`, a commit older than it is
+/// neither included nor walked past, except that the tips themselves always
+/// count.
+fn reachable_commits(tips: &[ObjectHash], cutoff: Option<u64>) -> CliResult<Vec<ObjectHash>> {
+    let mut seen = HashSet::new();
+    let mut commits = Vec::new();
+    let mut stack: Vec<(ObjectHash, bool)> = tips.iter().map(|tip| (*tip, true)).collect();
+    while let Some((id, is_tip)) = stack.pop() {
+        if seen.contains(&id) {
+            continue;
+        }
+        let commit: Commit = load(&id, "commit")?;
+        let in_window = cutoff.is_none_or(|cutoff| commit.committer.timestamp as u64 >= cutoff);
+        // Only mark a commit seen once it is taken, so an old tip that is
+        // first reached as someone's parent is still counted as a tip.
+        if !in_window && !is_tip {
+            continue;
+        }
+        seen.insert(id);
+        commits.push(id);
+        if in_window {
+            stack.extend(
+                commit
+                    .parent_commit_ids
+                    .iter()
+                    .map(|parent| (*parent, false)),
+            );
+        }
+    }
+    Ok(commits)
+}
+
+/// Record every LFS pointer in `tree_id` (recursively) as `oid -> size`.
+/// Trees and blobs shared between commits are visited once, and blobs too
+/// large to be a pointer are skipped by their header size without loading.
+fn collect_pointers(
+    storage: &ClientStorage,
+    tree_id: ObjectHash,
+    seen_trees: &mut HashSet<ObjectHash>,
+    seen_blobs: &mut HashSet<ObjectHash>,
+    pointers: &mut BTreeMap<String, u64>,
+) -> CliResult<()> {
+    if !seen_trees.insert(tree_id) {
+        return Ok(());
+    }
+    let tree: Tree = load(&tree_id, "tree")?;
+    for item in tree.tree_items {
+        match item.mode {
+            TreeItemMode::Tree => {
+                collect_pointers(storage, item.id, seen_trees, seen_blobs, pointers)?
+            }
+            // Submodule commits live in another repository.
+            TreeItemMode::Commit => {}
+            _ => {
+                if !seen_blobs.insert(item.id) {
+                    continue;
+                }
+                let size = storage.object_size(&item.id).map_err(|e| {
+                    CliError::fatal(format!("failed to read blob {}: {e}", item.id))
+                        .with_stable_code(StableErrorCode::RepoStateInvalid)
+                })?;
+                if size > lfs::LFS_POINTER_MAX_SIZE {
+                    continue;
+                }
+                let blob: Blob = load(&item.id, "blob")?;
+                if let Some((oid, size)) = lfs::parse_pointer_data(&blob.data) {
+                    pointers.insert(oid, size);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load<T>(id: &ObjectHash, kind: &str) -> CliResult<T>
+where
+    T: git_internal::internal::object::ObjectTrait,
+{
+    load_object(id).map_err(|e| {
+        CliError::fatal(format!("failed to read {kind} {id}: {e}"))
+            .with_stable_code(StableErrorCode::RepoStateInvalid)
+    })
+}
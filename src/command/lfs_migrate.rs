@@ -0,0 +1,595 @@
+//! `lfs migrate`: rewrite branch history so that matching files are stored as
+//! LFS pointers (`import`), or so that LFS pointers become ordinary blobs
+//! again (`export`).
+//!
+//! Commits are rewritten parents-first. A commit whose tree, and every
+//! parent, comes out unchanged keeps its original id, so history that never
+//! touched a matching path is shared with the old branch tips. Rewritten
+//! commits drop any signature, since it no longer covers the new content.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use clap::{Args, Subcommand};
+use git_internal::{
+    hash::ObjectHash,
+    internal::object::{
+        blob::Blob,
+        commit::Commit,
+        tree::{Tree, TreeItem, TreeItemMode},
+    },
+};
+use sea_orm::{TransactionError, TransactionTrait};
+
+use crate::{
+    command::{
+        lfs_schema::{LfsMigrateOutput, LfsMigrateRefOutput, LfsOutput},
+        load_object, reset, restore, save_object, status,
+    },
+    common_utils::{format_commit_msg, parse_commit_msg},
+    internal::{
+        branch::Branch,
+        db::get_db_conn_instance,
+        head::Head,
+        reflog::{HEAD, Reflog, ReflogAction, ReflogContext, ReflogError},
+        tree_plumbing,
+    },
+    utils::{
+        error::{CliError, CliResult, StableErrorCode},
+        lfs, util,
+    },
+};
+
+#[derive(Subcommand, Debug)]
+pub enum LfsMigrateCmds {
+    /// Rewrite history so that matching files are stored as LFS pointers
+    Import(LfsMigrateArgs),
+    /// Rewrite history so that matching LFS pointers become regular files again
+    Export(LfsMigrateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LfsMigrateArgs {
+    /// Comma-separated patterns of paths to convert (e.g. `*.psd,assets/**`)
+    #[clap(
+        long,
+        short = 'I',
+        value_delimiter = ',',
+        required = true,
+        value_name = "PATTERNS"
+    )]
+    pub include: Vec<String>,
+    /// Comma-separated patterns of paths to leave alone even if `--include` matches
+    #[clap(long, short = 'X', value_delimiter = ',', value_name = "PATTERNS")]
+    pub exclude: Vec<String>,
+    /// Rewrite every local branch instead of only the current one
+    #[clap(long, conflicts_with = "refs")]
+    pub everything: bool,
+    /// Leave commits reachable from REF untouched (repeatable)
+    #[clap(long = "exclude-ref", value_name = "REF")]
+    pub exclude_refs: Vec<String>,
+    /// Local branches to rewrite (default: the current branch)
+    pub refs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MigrateMode {
+    Import,
+    Export,
+}
+
+impl MigrateMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            MigrateMode::Import => "import",
+            MigrateMode::Export => "export",
+        }
+    }
+}
+
+pub(crate) async fn run_migrate(cmd: LfsMigrateCmds) -> CliResult<LfsOutput> {
+    let (mode, args) = match cmd {
+        LfsMigrateCmds::Import(args) => (MigrateMode::Import, args),
+        LfsMigrateCmds::Export(args) => (MigrateMode::Export, args),
+    };
+    let include = non_empty_patterns(args.include);
+    if include.is_empty() {
+        return Err(
+            CliError::command_usage("--include requires at least one pattern")
+                .with_stable_code(StableErrorCode::CliInvalidArguments),
+        );
+    }
+    if !status::is_clean().await {
+        return Err(CliError::conflict("working tree not clean")
+            .with_stable_code(StableErrorCode::ConflictOperationBlocked)
+            .with_hint("commit or stash your changes before running 'libra lfs migrate'"));
+    }
+
+    let branches = target_branches(args.everything, &args.refs).await?;
+    let mut excluded = HashSet::new();
+    for name in &args.exclude_refs {
+        let id = util::get_commit_base(name).await.map_err(|error| {
+            CliError::fatal(format!("not a valid commit '{name}': {error}"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget)
+        })?;
+        collect_ancestors(id, &mut excluded)?;
+    }
+
+    let mut migrator = Migrator {
+        mode,
+        include,
+        exclude: non_empty_patterns(args.exclude),
+        commits: HashMap::new(),
+        trees: HashMap::new(),
+        blobs: HashMap::new(),
+        commits_rewritten: 0,
+        blobs_converted: 0,
+    };
+    let tips = branches
+        .iter()
+        .map(|branch| branch.commit)
+        .collect::<Vec<_>>();
+    for id in commits_parents_first(&tips, &excluded)? {
+        migrator.rewrite_commit(id)?;
+    }
+
+    let current = match Head::current().await {
+        Head::Branch(name) => Some(name),
+        Head::Detached(_) => None,
+    };
+    let mut refs = Vec::new();
+    for branch in &branches {
+        let new = migrator.mapped(branch.commit);
+        if new == branch.commit {
+            continue;
+        }
+        let is_current = current.as_deref() == Some(branch.name.as_str());
+        update_branch_with_reflog(&branch.name, branch.commit, new, is_current).await?;
+        if is_current {
+            update_worktree(branch.commit, new).await?;
+        }
+        refs.push(LfsMigrateRefOutput {
+            name: branch.name.clone(),
+            old: branch.commit.to_string(),
+            new: new.to_string(),
+        });
+    }
+
+    Ok(LfsOutput {
+        action: format!("migrate-{}", mode.as_str()),
+        patterns: migrator.include,
+        migrate: Some(LfsMigrateOutput {
+            refs,
+            commits_rewritten: migrator.commits_rewritten,
+            blobs_converted: migrator.blobs_converted,
+        }),
+        ..LfsOutput::default()
+    })
+}
+
+/// Move `branch` from `old` to `new` and record an `lfs migrate` reflog entry
+/// for it (and for `HEAD` when it is the current branch) in one transaction.
+async fn update_branch_with_reflog(
+    branch: &str,
+    old: ObjectHash,
+    new: ObjectHash,
+    is_current: bool,
+) -> CliResult<()> {
+    let name = branch.to_string();
+    let context = ReflogContext {
+        old_oid: old.to_string(),
+        new_oid: new.to_string(),
+        action: ReflogAction::UpdateRef {
+            message: "lfs migrate".to_string(),
+        },
+    };
+    let db = get_db_conn_instance().await;
+    db.transaction(move |txn| {
+        Box::pin(async move {
+            Branch::update_branch_with_conn(txn, &name, &context.new_oid, None).await?;
+            Reflog::insert_single_entry(txn, &context, &format!("refs/heads/{name}")).await?;
+            if is_current {
+                Reflog::insert_single_entry(txn, &context, HEAD).await?;
+            }
+            Ok::<_, ReflogError>(())
+        })
+    })
+    .await
+    .map_err(|e| {
+        let detail = match e {
+            TransactionError::Connection(e) => e.to_string(),
+            TransactionError::Transaction(e) => e.to_string(),
+        };
+        CliError::fatal(format!("failed to update branch '{branch}': {detail}"))
+            .with_stable_code(StableErrorCode::IoWriteFailed)
+    })
+}
+
+/// Rewrite state shared by every commit of one migration. Whether a path
+/// matches depends on its full path, so trees are memoised only as root
+/// trees; blob conversions do not depend on the path and are memoised by id.
+struct Migrator {
+    mode: MigrateMode,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    commits: HashMap<ObjectHash, ObjectHash>,
+    trees: HashMap<ObjectHash, ObjectHash>,
+    blobs: HashMap<ObjectHash, Option<ObjectHash>>,
+    commits_rewritten: usize,
+    blobs_converted: usize,
+}
+
+impl Migrator {
+    fn mapped(&self, id: ObjectHash) -> ObjectHash {
+        self.commits.get(&id).copied().unwrap_or(id)
+    }
+
+    fn rewrite_commit(&mut self, id: ObjectHash) -> CliResult<()> {
+        let commit: Commit = load(&id, "commit")?;
+        let tree_id = self.rewrite_root_tree(commit.tree_id)?;
+        let parents = commit
+            .parent_commit_ids
+            .iter()
+            .map(|parent| self.mapped(*parent))
+            .collect::<Vec<_>>();
+        if tree_id == commit.tree_id && parents == commit.parent_commit_ids {
+            return Ok(());
+        }
+        let message = format_commit_msg(parse_commit_msg(&commit.message).0, None);
+        let rewritten = Commit::new(
+            commit.author.clone(),
+            commit.committer.clone(),
+            tree_id,
+            parents,
+            &message,
+        );
+        save(&rewritten, rewritten.id)?;
+        self.commits.insert(id, rewritten.id);
+        self.commits_rewritten += 1;
+        Ok(())
+    }
+
+    fn rewrite_root_tree(&mut self, tree_id: ObjectHash) -> CliResult<ObjectHash> {
+        if let Some(new) = self.trees.get(&tree_id) {
+            return Ok(*new);
+        }
+        let mut leaves = Vec::new();
+        flatten_tree(&tree_id, "", &mut leaves)?;
+
+        let mut changed = false;
+        for (path, mode, id) in leaves.iter_mut() {
+            if !matches!(*mode, TreeItemMode::Blob | TreeItemMode::BlobExecutable)
+                || !self.matches(path)
+            {
+                continue;
+            }
+            if let Some(new) = self.convert_blob(path, *id)? {
+                *id = new;
+                changed = true;
+            }
+        }
+        changed |= self.update_attributes(&mut leaves)?;
+
+        let new = if changed {
+            tree_plumbing::write_tree_from_leaves(
+                leaves
+                    .into_iter()
+                    .map(|(path, mode, id)| (PathBuf::from(path), mode, id)),
+            )
+            .map_err(|e| CliError::io(format!("failed to write tree: {e}")))?
+        } else {
+            tree_id
+        };
+        self.trees.insert(tree_id, new);
+        Ok(new)
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let path = util::workdir_to_absolute(path);
+        lfs::matches_lfs_patterns(&self.include, &path)
+            && !lfs::matches_lfs_patterns(&self.exclude, &path)
+    }
+
+    /// The replacement for blob `id` at `path`, or `None` when it is already
+    /// in the target form.
+    fn convert_blob(&mut self, path: &str, id: ObjectHash) -> CliResult<Option<ObjectHash>> {
+        if let Some(converted) = self.blobs.get(&id) {
+            return Ok(*converted);
+        }
+        let blob: Blob = load(&id, "blob")?;
+        let pointer = lfs::parse_pointer_data(&blob.data);
+        let data = match (self.mode, pointer) {
+            (MigrateMode::Import, Some(_)) | (MigrateMode::Export, None) => None,
+            (MigrateMode::Import, None) => {
+                let (pointer, oid) = lfs::generate_pointer_data(&blob.data);
+                lfs::backup_lfs_data(&blob.data, &oid).map_err(|e| {
+                    CliError::io(format!("failed to store LFS object for '{path}': {e}"))
+                })?;
+                Some(pointer.into_bytes())
+            }
+            (MigrateMode::Export, Some((oid, _))) => {
+                let object = lfs::lfs_object_path(&oid);
+                let data = std::fs::read(&object).map_err(|_| {
+                    CliError::fatal(format!(
+                        "LFS object {oid} for '{path}' is not available locally"
+                    ))
+                    .with_stable_code(StableErrorCode::RepoStateInvalid)
+                    .with_hint(format!(
+                        "download it first, e.g. 'libra restore --source <commit> {path}'"
+                    ))
+                })?;
+                Some(data)
+            }
+        };
+        let converted = match data {
+            Some(data) => {
+                let blob = Blob::from_content_bytes(data);
+                save(&blob, blob.id)?;
+                self.blobs_converted += 1;
+                Some(blob.id)
+            }
+            None => None,
+        };
+        self.blobs.insert(id, converted);
+        Ok(converted)
+    }
+
+    /// Keep the root `.libra_attributes` in step with the conversion: import
+    /// adds a `filter=lfs` line per include pattern, export drops them.
+    fn update_attributes(
+        &mut self,
+        leaves: &mut Vec<(String, TreeItemMode, ObjectHash)>,
+    ) -> CliResult<bool> {
+        let existing = leaves
+            .iter()
+            .position(|(path, mode, _)| path == util::ATTRIBUTES && *mode == TreeItemMode::Blob);
+        let content = match existing {
+            Some(index) => {
+                let blob: Blob = load(&leaves[index].2, "blob")?;
+                String::from_utf8_lossy(&blob.data).into_owned()
+            }
+            None if self.mode == MigrateMode::Export => return Ok(false),
+            None => String::new(),
+        };
+        let updated = match self.mode {
+            MigrateMode::Import => add_attribute_lines(&content, &self.include),
+            MigrateMode::Export => remove_attribute_lines(&content, &self.include),
+        };
+        if updated == content {
+            return Ok(false);
+        }
+        let blob = Blob::from_content_bytes(updated.into_bytes());
+        save(&blob, blob.id)?;
+        match existing {
+            Some(index) => leaves[index].2 = blob.id,
+            None => leaves.push((util::ATTRIBUTES.to_string(), TreeItemMode::Blob, blob.id)),
+        }
+        Ok(true)
+    }
+}
+
+fn non_empty_patterns(patterns: Vec<String>) -> Vec<String> {
+    patterns
+        .into_iter()
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect()
+}
+
+/// The first whitespace-delimited token of an attributes line, with `\ `
+/// unescaped, when the line sets `filter=lfs`.
+fn lfs_attribute_pattern(line: &str) -> Option<String> {
+    if !line.contains("filter=lfs") {
+        return None;
+    }
+    let line = line.trim_start();
+    let mut end = 0;
+    let bytes = line.as_bytes();
+    while end < bytes.len() && !bytes[end].is_ascii_whitespace() {
+        end += if bytes[end] == b'\\' && end + 1 < bytes.len() {
+            2
+        } else {
+            1
+        };
+    }
+    Some(line[..end].replace(r"\ ", " "))
+}
+
+fn add_attribute_lines(content: &str, patterns: &[String]) -> String {
+    let mut tracked = content
+        .lines()
+        .filter_map(lfs_attribute_pattern)
+        .collect::<HashSet<_>>();
+    let mut updated = content.to_string();
+    for pattern in patterns {
+        if !tracked.insert(pattern.clone()) {
+            continue;
+        }
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push_str(&format!(
+            "{} filter=lfs diff=lfs merge=lfs -text\n",
+            pattern.replace(" ", r"\ ")
+        ));
+    }
+    updated
+}
+
+fn remove_attribute_lines(content: &str, patterns: &[String]) -> String {
+    let mut updated = String::with_capacity(content.len());
+    for line in content.split_inclusive('\n') {
+        match lfs_attribute_pattern(line) {
+            Some(pattern) if patterns.contains(&pattern) => {}
+            _ => updated.push_str(line),
+        }
+    }
+    updated
+}
+
+async fn target_branches(everything: bool, refs: &[String]) -> CliResult<Vec<Branch>> {
+    if everything {
+        return Branch::list_branches_result(None).await.map_err(|e| {
+            CliError::fatal(format!("failed to list branches: {e}"))
+                .with_stable_code(StableErrorCode::RepoStateInvalid)
+        });
+    }
+    let names = if refs.is_empty() {
+        match Head::current().await {
+            Head::Branch(name) => vec![name],
+            Head::Detached(_) => {
+                return Err(CliError::fatal("HEAD is detached")
+                    .with_stable_code(StableErrorCode::RepoStateInvalid)
+                    .with_hint("name the branches to migrate, or pass --everything"));
+            }
+        }
+    } else {
+        refs.to_vec()
+    };
+    let mut branches = Vec::new();
+    for name in names {
+        let name = name
+            .strip_prefix("refs/heads/")
+            .unwrap_or(&name)
+            .to_string();
+        let branch = Branch::find_branch_result(&name, None)
+            .await
+            .map_err(|e| {
+                CliError::fatal(format!("failed to read branch '{name}': {e}"))
+                    .with_stable_code(StableErrorCode::RepoStateInvalid)
+            })?
+            .ok_or_else(|| {
+                CliError::fatal(format!("branch '{name}' not found"))
+                    .with_stable_code(StableErrorCode::CliInvalidTarget)
+            })?;
+        branches.push(branch);
+    }
+    Ok(branches)
+}
+
+fn collect_ancestors(start: ObjectHash, seen: &mut HashSet<ObjectHash>) -> CliResult<()> {
+    let mut stack = vec![start];
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let commit: Commit = load(&id, "commit")?;
+        stack.extend(commit.parent_commit_ids);
+    }
+    Ok(())
+}
+
+/// Every commit reachable from `tips` but not in `excluded`, ordered so that
+/// each commit comes after all of its parents.
+fn commits_parents_first(
+    tips: &[ObjectHash],
+    excluded: &HashSet<ObjectHash>,
+) -> CliResult<Vec<ObjectHash>> {
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    for tip in tips {
+        // (commit, parents already pushed)
+        let mut stack = vec![(*tip, false)];
+        while let Some((id, expanded)) = stack.pop() {
+            if expanded {
+                order.push(id);
+                continue;
+            }
+            if excluded.contains(&id) || !visited.insert(id) {
+                continue;
+            }
+            let commit: Commit = load(&id, "commit")?;
+            stack.push((id, true));
+            for parent in commit.parent_commit_ids.iter().rev() {
+                stack.push((*parent, false));
+            }
+        }
+    }
+    Ok(order)
+}
+
+fn flatten_tree(
+    tree_id: &ObjectHash,
+    prefix: &str,
+    out: &mut Vec<(String, TreeItemMode, ObjectHash)>,
+) -> CliResult<()> {
+    let tree: Tree = load(tree_id, "tree")?;
+    for TreeItem { mode, id, name } in tree.tree_items {
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{prefix}/{name}")
+        };
+        if mode == TreeItemMode::Tree {
+            flatten_tree(&id, &path, out)?;
+        } else {
+            out.push((path, mode, id));
+        }
+    }
+    Ok(())
+}
+
+/// Move the index and working tree from `old` to `new` after the current
+/// branch was rewritten. Only paths whose blob or mode changed are written.
+async fn update_worktree(old: ObjectHash, new: ObjectHash) -> CliResult<()> {
+    reset::reset_index_to_commit(&new).map_err(|e| {
+        CliError::fatal(format!("failed to update index: {e}"))
+            .with_stable_code(StableErrorCode::IoWriteFailed)
+    })?;
+    let old_commit: Commit = load(&old, "commit")?;
+    let new_commit: Commit = load(&new, "commit")?;
+    let mut old_leaves = Vec::new();
+    flatten_tree(&old_commit.tree_id, "", &mut old_leaves)?;
+    let old_leaves = old_leaves
+        .into_iter()
+        .map(|(path, mode, id)| (path, (mode, id)))
+        .collect::<HashMap<_, _>>();
+    let mut new_leaves = Vec::new();
+    flatten_tree(&new_commit.tree_id, "", &mut new_leaves)?;
+    for (path, mode, id) in new_leaves {
+        if old_leaves.get(&path) == Some(&(mode, id)) {
+            continue;
+        }
+        restore::restore_to_file_with_mode(&id, &PathBuf::from(&path), mode)
+            .await
+            .map_err(|e| CliError::io(format!("failed to write '{path}': {e}")))?;
+    }
+    Ok(())
+}
+
+fn load<T>(id: &ObjectHash, kind: &str) -> CliResult<T>
+where
+    T: git_internal::internal::object::ObjectTrait,
+{
+    load_object(id).map_err(|e| {
+        CliError::fatal(format!("failed to read {kind} {id}: {e}"))
+            .with_stable_code(StableErrorCode::RepoStateInvalid)
+    })
+}
+
+fn save<T>(object: &T, id: ObjectHash) -> CliResult<()>
+where
+    T: git_internal::internal::object::ObjectTrait,
+{
+    save_object(object, &id).map_err(|e| CliError::io(format!("failed to write object {id}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribute_lines_round_trip() {
+        let patterns = vec!["*.psd".to_string(), "my dir/*.bin".to_string()];
+        let added = add_attribute_lines("*.txt text", &patterns);
+        assert_eq!(
+            added,
+            "*.txt text\n*.psd filter=lfs diff=lfs merge=lfs -text\n\
+             my\\ dir/*.bin filter=lfs diff=lfs merge=lfs -text\n"
+        );
+        assert_eq!(add_attribute_lines(&added, &patterns), added);
+        assert_eq!(remove_attribute_lines(&added, &patterns), "*.txt text\n");
+    }
+}
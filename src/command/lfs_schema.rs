@@ -21,6 +21,82 @@ pub struct LfsOutput {
     pub name_only: bool,
     #[serde(skip_serializing_if = "is_false")]
     pub show_size: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrate: Option<LfsMigrateOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<LfsStatusOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch: Option<LfsFetchOutput>,
+}
+
+/// Result of `lfs status`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LfsStatusOutput {
+    /// Current branch; `None` on a detached HEAD.
+    pub branch: Option<String>,
+    /// LFS-tracked paths whose index entry differs from HEAD.
+    pub staged: Vec<LfsStatusEntry>,
+    /// LFS-tracked paths whose working-tree content differs from the index.
+    pub unstaged: Vec<LfsStatusEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LfsStatusEntry {
+    pub path: String,
+    /// Previous version; absent for a newly added path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<LfsStatusSide>,
+    /// New version; absent for a deleted path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<LfsStatusSide>,
+}
+
+/// One version of a path in `lfs status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LfsStatusSide {
+    /// `lfs` (a pointer; `oid` is the LFS sha256), `git` (the full content in
+    /// a Git blob; `oid` is the blob id) or `file` (working-tree content;
+    /// `oid` is its LFS sha256).
+    pub kind: String,
+    pub oid: String,
+}
+
+/// Result of `lfs fetch`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LfsFetchOutput {
+    /// Commits whose trees were scanned for LFS pointers.
+    pub commits_scanned: usize,
+    /// Distinct LFS objects referenced by those commits.
+    pub objects_referenced: usize,
+    /// Referenced objects that were already in the local LFS cache.
+    pub already_cached: usize,
+    /// OIDs downloaded by this run.
+    pub fetched: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<LfsFetchFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LfsFetchFailure {
+    pub oid: String,
+    pub reason: String,
+}
+
+/// Result of `lfs migrate import|export`; `patterns` on [`LfsOutput`] carries
+/// the `--include` patterns.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LfsMigrateOutput {
+    /// Branches whose tip moved, in the order they were rewritten.
+    pub refs: Vec<LfsMigrateRefOutput>,
+    pub commits_rewritten: usize,
+    pub blobs_converted: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LfsMigrateRefOutput {
+    pub name: String,
+    pub old: String,
+    pub new: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -4,6 +4,7 @@ use std::{
     cell::RefCell,
     cmp::min,
     collections::{HashMap, HashSet, VecDeque},
+    io::{self, Write},
     path::PathBuf,
     rc::Rc,
     str::FromStr,
@@ -19,7 +20,7 @@ use git_internal::{
 use serde::Serialize;
 
 use crate::{
-    command::load_object,
+    command::{diff, load_object},
     common_utils::parse_commit_msg,
     internal::{
         branch::{Branch, BranchStoreError},
@@ -34,7 +35,7 @@ use crate::{
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         object_ext::TreeExt,
-        output::{OutputConfig, emit_json_data},
+        output::{JsonFormat, OutputConfig, json_stream_envelope},
         pager::Pager,
         util,
     },
@@ -45,10 +46,11 @@ EXAMPLES:
     libra log -n 5                         Show the latest 5 commits
     libra log --oneline --graph            Show a compact commit graph
     libra log --pretty=fuller              Use a named format preset (short/full/fuller/reference/raw)
-    libra log --author alice                Filter commits by author (case-insensitive substring)
+    libra log --author '^alice'             Filter commits by author name or email (case-insensitive regex)
     libra log --since 24h --until 1h       Time-window filter (relative or RFC3339)
-    libra log --grep 'fix(' -n 20          Filter commits by message substring
+    libra log --grep '^fix' -n 20          Filter commits by message regex
     libra log --grep fix -i                Case-insensitive message grep
+    libra log --grep fix --grep ui --all-match  Require every --grep to match
     libra log --grep WIP --invert-grep     Hide commits whose message matches
     libra log --name-status src/           Show changed files under src/
     libra log --shortstat -n 5             Show just the diffstat summary line
@@ -150,10 +152,12 @@ pub struct LogArgs {
     /// Show only names of changed files
     #[clap(long)]
     pub name_only: bool,
-    /// Show names and status of changed files
+    /// Show names and status (`A`/`M`/`D`, or `R<score>` with the old path for
+    /// a detected rename) of changed files
     #[clap(long)]
     pub name_status: bool,
-    /// Filter commits by author name or email (case-insensitive substring match)
+    /// Filter commits by author: a regular expression matched case-insensitively
+    /// against the author name, the email, or `Name <email>`
     #[clap(long, value_name = "PATTERN")]
     pub author: Option<String>,
     /// Show commits more recent than DATE (RFC3339, `YYYY-MM-DD`, or relative like `24h` / `7d`)
@@ -209,9 +213,14 @@ pub struct LogArgs {
     #[clap(value_name = "REVISION_OR_PATH", num_args = 0..)]
     pathspec: Vec<String>,
 
-    /// Filter commits whose message contains PATTERN (case-sensitive substring match)
+    /// Filter commits whose message matches the regular expression PATTERN.
+    /// Repeatable; a commit matching any pattern is kept unless `--all-match`.
     #[clap(long, value_name = "PATTERN")]
-    pub grep: Option<String>,
+    pub grep: Vec<String>,
+
+    /// Require every `--grep` pattern to match instead of any one of them.
+    #[clap(long = "all-match")]
+    pub all_match: bool,
 
     /// Only list commits whose trailer block carries this trailer (Libra
     /// extension — Git has no such flag; nearest is a fragile
@@ -233,7 +242,8 @@ pub struct LogArgs {
     #[clap(short = 'i', long = "regexp-ignore-case")]
     pub ignore_case: bool,
 
-    /// Keep commits whose message does NOT match `--grep`.
+    /// Keep commits whose message does NOT match `--grep` (under `--all-match`,
+    /// those not matching every pattern).
     #[clap(long = "invert-grep")]
     pub invert_grep: bool,
 
@@ -312,11 +322,15 @@ pub struct LogArgs {
     #[clap(long = "no-mailmap")]
     pub no_mailmap: bool,
 
-    /// Do not display the GPG signature of signed commits. Accepted for Git
-    /// parity and is a no-op: Libra's log never displays commit signatures
-    /// inline, so it already matches the default. (Git's opposite
-    /// `--show-signature` is not implemented.)
-    #[clap(long = "no-show-signature")]
+    /// Verify each signed commit's vault PGP signature and print the result
+    /// (`Good signature from "<key user id>" (key <fingerprint>)` / `BAD
+    /// signature ...`) under the commit line. Unsigned commits print nothing extra.
+    #[clap(long = "show-signature", overrides_with = "no_show_signature")]
+    pub show_signature: bool,
+
+    /// Do not display the signature check of signed commits (the default);
+    /// countermands an earlier `--show-signature` (last one wins).
+    #[clap(long = "no-show-signature", overrides_with = "show_signature")]
     pub no_show_signature: bool,
 
     /// Pretend as if all the refs in refs/, along with HEAD, are listed on the command line.
@@ -350,12 +364,18 @@ pub enum ChangeType {
     Added,
     Modified,
     Deleted,
+    /// Renamed from [`FileChange::old_path`]; `similarity` is a whole percent.
+    Renamed {
+        similarity: u8,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub struct FileChange {
     pub path: PathBuf,
     pub status: ChangeType,
+    /// The first-parent path of a rename; `None` for every other change.
+    pub old_path: Option<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -368,6 +388,8 @@ struct SelectedLogCommit {
 pub struct LogFileChange {
     pub path: String,
     pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -397,10 +419,70 @@ pub struct LogTrailerEntry {
     pub value: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct LogOutput {
-    pub commits: Vec<LogCommitEntry>,
-    pub total: Option<usize>,
+/// Writes the `log` JSON envelope (`{"command", "data": {"commits", "total"},
+/// "ok"}`) incrementally: each commit is serialized as soon as it is built, so
+/// the entries' bodies and file lists are never held together. The commit
+/// walk itself is still collected up front, since ordering and `--reverse`
+/// need the whole set. `"ok"` is written last, after the fallible walk.
+struct LogJsonStream<W: Write> {
+    writer: W,
+    format: JsonFormat,
+    written: usize,
+}
+
+impl<W: Write> LogJsonStream<W> {
+    fn new(writer: W, format: JsonFormat) -> Self {
+        Self {
+            writer,
+            format,
+            written: 0,
+        }
+    }
+
+    fn write_prefix(&mut self) -> io::Result<()> {
+        if self.written > 0 {
+            return self.writer.write_all(b",");
+        }
+        let (head, _) = json_stream_envelope("log", true, self.format)?;
+        self.writer.write_all(head.as_bytes())?;
+        match self.format {
+            JsonFormat::Pretty => self.writer.write_all(b"{\n    \"commits\": ["),
+            _ => self.writer.write_all(br#"{"commits":["#),
+        }
+    }
+
+    fn push(&mut self, entry: &LogCommitEntry) -> io::Result<()> {
+        self.write_prefix()?;
+        if self.format == JsonFormat::Pretty {
+            // Nest the entry under `commits`; JSON strings never contain a
+            // raw newline, so re-indenting line by line is safe.
+            let pretty = serde_json::to_string_pretty(entry)?;
+            for line in pretty.lines() {
+                write!(self.writer, "\n      {line}")?;
+            }
+        } else {
+            serde_json::to_writer(&mut self.writer, entry)?;
+        }
+        self.written += 1;
+        self.writer.flush()
+    }
+
+    /// Close the envelope. `ok` is false when the walk failed part-way; the
+    /// error itself is reported on stderr like any other command error.
+    fn finish(mut self, total: Option<usize>, ok: bool) -> io::Result<()> {
+        if self.written == 0 {
+            self.write_prefix()?;
+        }
+        let total = serde_json::to_string(&total)?;
+        if self.format == JsonFormat::Pretty {
+            write!(self.writer, "\n    ],\n    \"total\": {total}\n  }}")?;
+        } else {
+            write!(self.writer, r#"],"total":{total}}}"#)?;
+        }
+        let (_, tail) = json_stream_envelope("log", ok, self.format)?;
+        self.writer.write_all(tail.as_bytes())?;
+        self.writer.flush()
+    }
 }
 
 /// Resolve `--merges`/`--no-merges`/`--min-parents`/`--max-parents` into
@@ -418,14 +500,16 @@ fn resolve_parent_bounds(
 }
 
 struct CommitFilter {
-    author: Option<String>,
+    /// `--author` regex (case-insensitive), see [`build_author_filter`].
+    author: Option<regex::Regex>,
     committer: Option<String>,
     since: Option<i64>,
     until: Option<i64>,
     paths: Vec<PathBuf>,
-    grep: Option<String>,
-    /// `-i`/`--regexp-ignore-case`: case-insensitive `--grep` message match.
-    grep_ignore_case: bool,
+    /// `--grep` regexes (`-i` already compiled in), see [`build_grep_filters`].
+    grep: Vec<regex::Regex>,
+    /// `--all-match`: every `--grep` must match instead of any one.
+    all_match: bool,
     /// `--invert-grep`: keep commits whose message does NOT match `--grep`.
     invert_grep: bool,
     min_parents: Option<usize>,
@@ -479,24 +563,24 @@ enum PickaxeKind {
 impl CommitFilter {
     #[allow(clippy::too_many_arguments)]
     fn new(
-        author: Option<String>,
+        author: Option<regex::Regex>,
         committer: Option<String>,
         since: Option<i64>,
         until: Option<i64>,
         paths: Vec<PathBuf>,
-        grep: Option<String>,
+        grep: Vec<regex::Regex>,
         min_parents: Option<usize>,
         max_parents: Option<usize>,
         pickaxe: Option<PickaxeKind>,
     ) -> Self {
         Self {
-            author: author.map(|s| s.to_lowercase()),
+            author,
             committer: committer.map(|s| s.to_lowercase()),
             since,
             until,
             paths,
             grep,
-            grep_ignore_case: false,
+            all_match: false,
             invert_grep: false,
             min_parents,
             max_parents,
@@ -511,23 +595,20 @@ impl CommitFilter {
         self
     }
 
-    /// Apply `-i`/`--regexp-ignore-case` and `--invert-grep` to the `--grep`
-    /// message filter. Author/committer matching is already case-insensitive
-    /// (both sides are lower-cased), so `-i` only affects `--grep` here.
-    fn with_grep_options(mut self, ignore_case: bool, invert_grep: bool) -> Self {
-        self.grep_ignore_case = ignore_case;
+    /// Apply `--all-match` and `--invert-grep` to the `--grep` message filter.
+    fn with_grep_options(mut self, all_match: bool, invert_grep: bool) -> Self {
+        self.all_match = all_match;
         self.invert_grep = invert_grep;
         self
     }
 
     fn passes_non_path_filters(&self, commit: &Commit) -> bool {
         if let Some(author_filter) = &self.author {
-            let author = format!(
-                "{} <{}>",
-                commit.author.name.to_lowercase(),
-                commit.author.email.to_lowercase()
-            );
-            if !author.contains(author_filter) {
+            let (name, email) = (commit.author.name.trim(), commit.author.email.trim());
+            if !author_filter.is_match(name)
+                && !author_filter.is_match(email)
+                && !author_filter.is_match(&format!("{name} <{email}>"))
+            {
                 return false;
             }
         }
@@ -583,16 +664,12 @@ impl CommitFilter {
             return false;
         }
 
-        if let Some(pattern) = &self.grep
-            && !pattern.is_empty()
-        {
-            let matches = if self.grep_ignore_case {
-                commit
-                    .message
-                    .to_lowercase()
-                    .contains(&pattern.to_lowercase())
+        if !self.grep.is_empty() {
+            let message = parse_commit_msg(&commit.message).0;
+            let matches = if self.all_match {
+                self.grep.iter().all(|pattern| pattern.is_match(message))
             } else {
-                commit.message.contains(pattern.as_str())
+                self.grep.iter().any(|pattern| pattern.is_match(message))
             };
             // `--invert-grep` keeps the non-matching commits: exclude exactly
             // when `matches == invert_grep` (matches & !invert, or !matches & invert).
@@ -1169,56 +1246,81 @@ fn parse_line_range(spec: &str) -> CliResult<LineRange> {
     Ok(LineRange { start, end, file })
 }
 
-/// Detect whether a commit changed the given file path, following renames.
-async fn commit_touches_path_follow(
-    commit: &Commit,
-    target: &PathBuf,
-) -> Result<Option<PathBuf>, CliError> {
-    let changes = get_changed_files_for_commit(commit, &[]).await?;
-    for change in changes {
-        if &change.path == target {
-            return Ok(Some(target.clone()));
-        }
-    }
+/// Minimum similarity (on `diff::similarity_score`'s 0..60000 scale) for a
+/// deleted parent file to count as the rename source of an added path — 50%,
+/// Git's default rename threshold.
+const RENAME_THRESHOLD: u32 = 30000;
+
+/// How a commit relates to the path being followed.
+enum FollowStep {
+    /// The commit does not change the path.
+    Untouched,
+    /// The commit changes the path (add, modify, or delete) under its current name.
+    Touched,
+    /// The commit created the path by renaming this parent path.
+    RenamedFrom(PathBuf),
+}
 
-    // Rename detection: if the target file does not exist in this commit's tree
-    // but a file with the same blob hash as the target existed in the parent,
-    // treat that parent path as the target for this commit.
+/// Classify `commit` against its first parent for `target`. When the commit adds
+/// `target`, a parent file that disappears in the same commit is its rename
+/// source if it has the same blob, or else the most similar content scoring at
+/// least [`RENAME_THRESHOLD`].
+async fn follow_step(commit: &Commit, target: &PathBuf) -> Result<FollowStep, CliError> {
     let tree = load_object::<Tree>(&commit.tree_id)
         .map_err(|e| log_repo_corrupt_error(format!("failed to load tree object: {e}")))?;
     let current_items: HashMap<PathBuf, ObjectHash> = tree.get_plain_items().into_iter().collect();
+    let parent_items: HashMap<PathBuf, ObjectHash> = match commit.parent_commit_ids.first() {
+        Some(parent_id) => {
+            let parent_commit = load_object::<Commit>(parent_id).map_err(|e| {
+                log_repo_corrupt_error(format!("failed to load parent commit: {e}"))
+            })?;
+            let parent_tree = load_object::<Tree>(&parent_commit.tree_id)
+                .map_err(|e| log_repo_corrupt_error(format!("failed to load parent tree: {e}")))?;
+            parent_tree.get_plain_items().into_iter().collect()
+        }
+        None => HashMap::new(),
+    };
 
-    if current_items.contains_key(target) {
-        return Ok(Some(target.clone()));
+    let current = current_items.get(target);
+    let parent = parent_items.get(target);
+    if current == parent {
+        return Ok(FollowStep::Untouched);
     }
+    let Some(target_blob) = current.filter(|_| parent.is_none()) else {
+        return Ok(FollowStep::Touched);
+    };
 
-    if commit.parent_commit_ids.is_empty() {
-        return Ok(None);
+    // Only parent files gone from this commit can be rename sources.
+    let mut deleted: Vec<(&PathBuf, &ObjectHash)> = parent_items
+        .iter()
+        .filter(|(path, _)| !current_items.contains_key(*path))
+        .collect();
+    if deleted.is_empty() {
+        return Ok(FollowStep::Touched);
     }
-
-    let target_blob = current_items.get(target).copied();
-    let parent_commit = load_object::<Commit>(&commit.parent_commit_ids[0])
-        .map_err(|e| log_repo_corrupt_error(format!("failed to load parent commit: {e}")))?;
-    let parent_tree = load_object::<Tree>(&parent_commit.tree_id)
-        .map_err(|e| log_repo_corrupt_error(format!("failed to load parent tree: {e}")))?;
-    let parent_items: HashMap<PathBuf, ObjectHash> =
-        parent_tree.get_plain_items().into_iter().collect();
-
-    // If target was added here (not in parent), no rename predecessor.
-    if target_blob.is_none() && parent_items.contains_key(target) {
-        return Ok(Some(target.clone()));
+    deleted.sort_by(|a, b| a.0.cmp(b.0));
+    if let Some((path, _)) = deleted.iter().find(|(_, hash)| *hash == target_blob) {
+        return Ok(FollowStep::RenamedFrom((*path).clone()));
     }
 
-    // Look for a deleted parent file whose blob matches the target's current blob.
-    if let Some(target_blob) = target_blob {
-        for (path, hash) in parent_items {
-            if hash == target_blob {
-                return Ok(Some(path));
-            }
+    let target_data = load_blob_bytes(target_blob)?;
+    let mut best: Option<(u32, &PathBuf)> = None;
+    for (path, hash) in deleted {
+        let score = diff::similarity_score(&load_blob_bytes(hash)?, &target_data);
+        if score >= RENAME_THRESHOLD && best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, path));
         }
     }
+    Ok(match best {
+        Some((_, path)) => FollowStep::RenamedFrom(path.clone()),
+        None => FollowStep::Touched,
+    })
+}
 
-    Ok(None)
+fn load_blob_bytes(hash: &ObjectHash) -> Result<Vec<u8>, CliError> {
+    load_object::<Blob>(hash)
+        .map(|blob| blob.data)
+        .map_err(|e| log_repo_corrupt_error(format!("failed to load blob {hash}: {e}")))
 }
 
 /// Filter reachable commits for `--follow` and `-L` paths.
@@ -1235,14 +1337,15 @@ async fn apply_follow_and_line_filters(
         .collect::<Result<Vec<_>, _>>()?;
 
     for commit in commits {
-        let path_to_check = if let Some(path) = &current_path {
-            let touched = commit_touches_path_follow(&commit, path).await?;
-            if let Some(new_path) = touched {
-                current_path = Some(new_path.clone());
-                Some(new_path)
-            } else {
-                continue;
+        // Commits arrive newest-first, so a rename hands the older name on to
+        // the commits that follow.
+        let path_to_check = if let Some(path) = current_path.clone() {
+            match follow_step(&commit, &path).await? {
+                FollowStep::Untouched => continue,
+                FollowStep::Touched => {}
+                FollowStep::RenamedFrom(old_path) => current_path = Some(old_path),
             }
+            Some(path)
         } else {
             None
         };
@@ -1305,9 +1408,8 @@ async fn commit_affects_line_range(
 pub async fn execute_safe(args: LogArgs, output: &OutputConfig) -> CliResult<()> {
     let decorate_option = resolve_decorate_option(&args).await?;
 
-    if output.is_json() {
-        let result = run_log(&args).await?;
-        return emit_json_data("log", &result, output);
+    if let Some(format) = output.json_format {
+        return stream_log_json(&args, format).await;
     }
 
     let name_status = args.name_status;
@@ -1332,17 +1434,17 @@ pub async fn execute_safe(args: LogArgs, output: &OutputConfig) -> CliResult<()>
     );
     let pickaxe = build_pickaxe(&args)?;
     let filter = CommitFilter::new(
-        args.author.clone(),
+        build_author_filter(&args)?,
         args.committer.clone(),
         since,
         until,
         path_filters.clone(),
-        args.grep.clone(),
+        build_grep_filters(&args)?,
         min_parents,
         max_parents,
         pickaxe,
     )
-    .with_grep_options(args.ignore_case, args.invert_grep)
+    .with_grep_options(args.all_match, args.invert_grep)
     .with_trailer_filters(parse_trailer_filters(&args.trailers)?);
 
     let (branch_name, current_head_commit) = resolve_log_head_commit().await?;
@@ -1355,13 +1457,13 @@ pub async fn execute_safe(args: LogArgs, output: &OutputConfig) -> CliResult<()>
         get_reachable_commits_excluding(start_commits, excludes, None, args.first_parent).await?;
     // newest first
     sort_commits_newest_first(&mut reachable_commits, args.author_date_order);
+    // `--follow` walks newest-first, so it runs before `--reverse`.
+    reachable_commits =
+        apply_follow_and_line_filters(reachable_commits, &args.follow, &args.line_range).await?;
     if args.reverse {
         reachable_commits.reverse();
     }
 
-    reachable_commits =
-        apply_follow_and_line_filters(reachable_commits, &args.follow, &args.line_range).await?;
-
     let default_abbrev = util::get_min_unique_hash_length(&reachable_commits).max(7);
 
     let max_output_number = min(args.number.unwrap_or(usize::MAX), reachable_commits.len());
@@ -1577,6 +1679,11 @@ pub async fn execute_safe(args: LogArgs, output: &OutputConfig) -> CliResult<()>
             extra_hashes: &extra_hashes,
         };
         let mut message = formatter.format(&commit, &ctx);
+        if args.show_signature
+            && let Some(report) = crate::command::commit::describe_commit_signature(&commit).await
+        {
+            insert_after_first_line(&mut message, &report);
+        }
 
         if name_only || name_status {
             if let Some(changes) = cached_changes.take()
@@ -1585,6 +1692,7 @@ pub async fn execute_safe(args: LogArgs, output: &OutputConfig) -> CliResult<()>
                 if !message.ends_with('\n') {
                     message.push('\n');
                 }
+                let changes = detect_commit_renames(&commit, changes)?;
                 message.push_str(&format_changes(&changes, name_status));
             }
         } else if patch {
@@ -1635,7 +1743,45 @@ pub async fn execute_safe(args: LogArgs, output: &OutputConfig) -> CliResult<()>
     Ok(())
 }
 
-async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
+/// Walk the selected history and stream each matching commit into the JSON
+/// envelope as it is built. The envelope is only started once the walk has
+/// produced its first commit, so setup errors still leave stdout empty; an
+/// error after that closes the document with `"ok": false`.
+async fn stream_log_json(args: &LogArgs, format: JsonFormat) -> CliResult<()> {
+    let write_err = |e: io::Error| CliError::io(format!("failed to write JSON output: {e}"));
+    let mut stream = LogJsonStream::new(io::stdout(), format);
+    let walked = walk_log_entries(args, |entry| stream.push(&entry).map_err(write_err)).await;
+    match walked {
+        Ok(total) => stream.finish(total, true).map_err(write_err),
+        Err(error) => {
+            if stream.written > 0 {
+                stream.finish(None, false).map_err(write_err)?;
+            }
+            Err(error)
+        }
+    }
+}
+
+/// The newest `limit` commits reachable from `HEAD`, in the same shape as a
+/// `libra --json log -n <limit>` entry.
+pub(crate) async fn recent_log_entries(limit: usize) -> CliResult<Vec<LogCommitEntry>> {
+    let limit = limit.to_string();
+    let args = LogArgs::parse_from(["log", "-n", limit.as_str()]);
+    let mut entries = Vec::new();
+    walk_log_entries(&args, |entry| {
+        entries.push(entry);
+        Ok(())
+    })
+    .await?;
+    Ok(entries)
+}
+
+/// Build a [`LogCommitEntry`] for every commit `args` selects and hand it to
+/// `sink` in output order. Returns the filtered total when `-n` is absent.
+async fn walk_log_entries(
+    args: &LogArgs,
+    mut sink: impl FnMut(LogCommitEntry) -> CliResult<()>,
+) -> CliResult<Option<usize>> {
     let since = args.since.as_deref().map(parse_date_arg).transpose()?;
     let until = args.until.as_deref().map(parse_date_arg).transpose()?;
     let (ranges, paths) = resolve_log_inputs(args).await?;
@@ -1648,17 +1794,17 @@ async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
     );
     let pickaxe = build_pickaxe(args)?;
     let filter = CommitFilter::new(
-        args.author.clone(),
+        build_author_filter(args)?,
         args.committer.clone(),
         since,
         until,
         path_filters.clone(),
-        args.grep.clone(),
+        build_grep_filters(args)?,
         min_parents,
         max_parents,
         pickaxe,
     )
-    .with_grep_options(args.ignore_case, args.invert_grep)
+    .with_grep_options(args.all_match, args.invert_grep)
     .with_trailer_filters(parse_trailer_filters(&args.trailers)?);
 
     let (branch_name, current_head_commit) = resolve_log_head_commit().await?;
@@ -1671,6 +1817,8 @@ async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
         get_reachable_commits_excluding(start_commits, excludes, None, args.first_parent).await?;
     // newest first
     sort_commits_newest_first(&mut reachable_commits, args.author_date_order);
+    reachable_commits =
+        apply_follow_and_line_filters(reachable_commits, &args.follow, &args.line_range).await?;
     if args.reverse {
         reachable_commits.reverse();
     }
@@ -1678,12 +1826,12 @@ async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
     let max_output_number = min(args.number.unwrap_or(usize::MAX), reachable_commits.len());
     let include_total = args.number.is_none();
     let ref_commits = create_reference_commit_map().await;
-    let mut commits = Vec::new();
+    let mut emitted = 0usize;
     let mut total = 0usize;
     let skip = args.skip.unwrap_or(0);
 
     for commit in reachable_commits {
-        if !include_total && commits.len() >= max_output_number {
+        if !include_total && emitted >= max_output_number {
             break;
         }
         if !filter.passes_non_path_filters(&commit) {
@@ -1700,9 +1848,11 @@ async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
         if total <= skip {
             continue;
         }
-        if commits.len() >= max_output_number {
+        if emitted >= max_output_number {
             continue;
         }
+        // Rename pairing loads blobs, so only commits that are emitted pay for it.
+        let files = detect_commit_renames(&commit, files)?;
 
         let (parsed_message, _) = parse_commit_msg(&commit.message);
         let mut message_lines = parsed_message.lines();
@@ -1718,7 +1868,7 @@ async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
         let hash = commit.id.to_string();
         let short_hash = hash.get(..7).unwrap_or(&hash).to_string();
 
-        commits.push(LogCommitEntry {
+        let entry = LogCommitEntry {
             hash,
             short_hash,
             author_name: commit.author.name.trim().to_string(),
@@ -1749,17 +1899,18 @@ async fn run_log(args: &LogArgs) -> CliResult<LogOutput> {
                         ChangeType::Added => "added",
                         ChangeType::Modified => "modified",
                         ChangeType::Deleted => "deleted",
+                        ChangeType::Renamed { .. } => "renamed",
                     }
                     .to_string(),
+                    old_path: file.old_path.map(|path| path.display().to_string()),
                 })
                 .collect(),
-        });
+        };
+        sink(entry)?;
+        emitted += 1;
     }
 
-    Ok(LogOutput {
-        commits,
-        total: include_total.then_some(total),
-    })
+    Ok(include_total.then_some(total))
 }
 
 async fn select_log_commits(
@@ -1886,6 +2037,34 @@ fn count_string_occurrences(hash: &ObjectHash, needle: &str) -> Result<usize, Cl
     Ok(String::from_utf8_lossy(&blob.data).matches(needle).count())
 }
 
+/// Compile `--author` as a case-insensitive regex (Libra has always matched
+/// identities case-insensitively). An invalid pattern is a usage error.
+fn build_author_filter(args: &LogArgs) -> CliResult<Option<regex::Regex>> {
+    args.author
+        .as_deref()
+        .map(|pattern| compile_log_regex("--author", pattern, true))
+        .transpose()
+}
+
+/// Compile each `--grep`, case-insensitively under `-i`/`--regexp-ignore-case`.
+fn build_grep_filters(args: &LogArgs) -> CliResult<Vec<regex::Regex>> {
+    args.grep
+        .iter()
+        .map(|pattern| compile_log_regex("--grep", pattern, args.ignore_case))
+        .collect()
+}
+
+fn compile_log_regex(flag: &str, pattern: &str, ignore_case: bool) -> CliResult<regex::Regex> {
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| {
+            CliError::command_usage(format!("invalid {flag} regex '{pattern}': {e}"))
+                .with_stable_code(StableErrorCode::CliInvalidArguments)
+                .with_hint("use a valid regular expression or escape metacharacters")
+        })
+}
+
 /// Build the pickaxe filter from `-S`/`-G` (mutually exclusive at the clap
 /// layer). `-G`'s regex is compiled here so an invalid pattern is a usage error.
 fn build_pickaxe(args: &LogArgs) -> CliResult<Option<PickaxeKind>> {
@@ -1998,6 +2177,18 @@ pub(crate) fn parse_pretty_format(pretty: String) -> FormatType {
     }
 }
 
+/// Insert `line` right after the first line of `text` (the `commit <hash>`
+/// header), where Git places `--show-signature` output.
+pub(crate) fn insert_after_first_line(text: &mut String, line: &str) {
+    let at = text.find('\n').map_or(text.len(), |pos| pos + 1);
+    let insertion = if at == text.len() && !text.ends_with('\n') {
+        format!("\n{line}")
+    } else {
+        format!("{line}\n")
+    };
+    text.insert_str(at, &insertion);
+}
+
 fn format_log_timestamp(timestamp: i64) -> String {
     chrono::DateTime::from_timestamp(timestamp, 0)
         .map(|date| date.to_rfc3339())
@@ -2081,16 +2272,16 @@ async fn commit_touches_paths(commit: &Commit, filters: &[PathBuf]) -> Result<bo
     Ok(!changes.is_empty())
 }
 
-/// Get list of changed files for a commit
-pub(crate) async fn get_changed_files_for_commit(
-    commit: &Commit,
-    paths: &[PathBuf],
-) -> Result<Vec<FileChange>, CliError> {
+/// The blobs of `commit`'s tree and of its first parent's tree (empty for a
+/// root commit).
+type CommitBlobs = (Vec<(PathBuf, ObjectHash)>, Vec<(PathBuf, ObjectHash)>);
+
+fn commit_and_parent_blobs(commit: &Commit) -> Result<CommitBlobs, CliError> {
     let tree = load_object::<Tree>(&commit.tree_id)
         .map_err(|e| log_repo_corrupt_error(format!("failed to load tree object: {e}")))?;
-    let new_blobs: Vec<(PathBuf, ObjectHash)> = tree.get_plain_items();
+    let new_blobs = tree.get_plain_items();
 
-    let old_blobs: Vec<(PathBuf, ObjectHash)> = if !commit.parent_commit_ids.is_empty() {
+    let old_blobs = if !commit.parent_commit_ids.is_empty() {
         let parent = &commit.parent_commit_ids[0];
         let parent_commit = load_object::<Commit>(parent)
             .map_err(|e| log_repo_corrupt_error(format!("failed to load parent commit: {e}")))?;
@@ -2100,6 +2291,15 @@ pub(crate) async fn get_changed_files_for_commit(
     } else {
         Vec::new()
     };
+    Ok((new_blobs, old_blobs))
+}
+
+/// Get list of changed files for a commit
+pub(crate) async fn get_changed_files_for_commit(
+    commit: &Commit,
+    paths: &[PathBuf],
+) -> Result<Vec<FileChange>, CliError> {
+    let (new_blobs, old_blobs) = commit_and_parent_blobs(commit)?;
 
     let matches_filter = |path: &PathBuf, filters: &[PathBuf]| -> bool {
         if filters.is_empty() {
@@ -2118,6 +2318,7 @@ pub(crate) async fn get_changed_files_for_commit(
             changed_files.push(FileChange {
                 path: file.clone(),
                 status: ChangeType::Added,
+                old_path: None,
             });
         }
     }
@@ -2130,6 +2331,7 @@ pub(crate) async fn get_changed_files_for_commit(
             changed_files.push(FileChange {
                 path: file.clone(),
                 status: ChangeType::Modified,
+                old_path: None,
             });
         }
     }
@@ -2139,6 +2341,7 @@ pub(crate) async fn get_changed_files_for_commit(
             changed_files.push(FileChange {
                 path: file.clone(),
                 status: ChangeType::Deleted,
+                old_path: None,
             });
         }
     }
@@ -2147,16 +2350,115 @@ pub(crate) async fn get_changed_files_for_commit(
     Ok(changed_files)
 }
 
+/// Fold each deleted + added pair in `changes` whose content matches into one
+/// rename entry, as `git log --name-status` does by default. Exact (same blob)
+/// pairs are matched first, then the most similar pairs scoring at least
+/// [`RENAME_THRESHOLD`]; each path takes part in at most one rename. Like
+/// `diff`, the inexact pass is skipped past [`diff::RENAME_LIMIT`].
+pub(crate) fn detect_commit_renames(
+    commit: &Commit,
+    mut changes: Vec<FileChange>,
+) -> Result<Vec<FileChange>, CliError> {
+    let indices_of = |status: ChangeType, changes: &[FileChange]| -> Vec<usize> {
+        (0..changes.len())
+            .filter(|&i| changes[i].status == status)
+            .collect()
+    };
+    let deleted = indices_of(ChangeType::Deleted, &changes);
+    let added = indices_of(ChangeType::Added, &changes);
+    if deleted.is_empty() || added.is_empty() {
+        return Ok(changes);
+    }
+
+    let (new_blobs, old_blobs) = commit_and_parent_blobs(commit)?;
+    let new_blobs: HashMap<PathBuf, ObjectHash> = new_blobs.into_iter().collect();
+    let old_blobs: HashMap<PathBuf, ObjectHash> = old_blobs.into_iter().collect();
+    let blob_of = |blobs: &HashMap<PathBuf, ObjectHash>, change: &FileChange| {
+        blobs.get(&change.path).copied()
+    };
+
+    let mut used = vec![false; changes.len()];
+    let mut pairs: Vec<(usize, usize, u32)> = Vec::new();
+    for &di in &deleted {
+        let old_hash = blob_of(&old_blobs, &changes[di]);
+        if let Some(&ai) = added.iter().find(|&&ai| {
+            !used[ai] && old_hash.is_some() && blob_of(&new_blobs, &changes[ai]) == old_hash
+        }) {
+            used[di] = true;
+            used[ai] = true;
+            pairs.push((di, ai, 60000));
+        }
+    }
+
+    let mut added_data: HashMap<usize, Vec<u8>> = HashMap::new();
+    let mut candidates: Vec<(u32, usize, usize)> = Vec::new();
+    let remaining_deleted = deleted.iter().filter(|&&di| !used[di]).count();
+    let remaining_added = added.iter().filter(|&&ai| !used[ai]).count();
+    let inexact = diff::within_rename_limit(remaining_deleted, remaining_added);
+    for &di in deleted.iter().filter(|&&di| inexact && !used[di]) {
+        let Some(old_hash) = blob_of(&old_blobs, &changes[di]) else {
+            continue;
+        };
+        let old = load_blob_bytes(&old_hash)?;
+        for &ai in added.iter().filter(|&&ai| !used[ai]) {
+            let Some(new_hash) = blob_of(&new_blobs, &changes[ai]) else {
+                continue;
+            };
+            if let std::collections::hash_map::Entry::Vacant(entry) = added_data.entry(ai) {
+                entry.insert(load_blob_bytes(&new_hash)?);
+            }
+            let score = diff::similarity_score(&old, &added_data[&ai]);
+            if score >= RENAME_THRESHOLD {
+                candidates.push((score, di, ai));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+    for (score, di, ai) in candidates {
+        if !used[di] && !used[ai] {
+            used[di] = true;
+            used[ai] = true;
+            pairs.push((di, ai, score));
+        }
+    }
+    if pairs.is_empty() {
+        return Ok(changes);
+    }
+
+    for &(di, ai, score) in &pairs {
+        changes[ai].status = ChangeType::Renamed {
+            similarity: (score / 600) as u8,
+        };
+        changes[ai].old_path = Some(changes[di].path.clone());
+    }
+    let renamed_from: HashSet<usize> = pairs.iter().map(|&(di, _, _)| di).collect();
+    Ok(changes
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !renamed_from.contains(i))
+        .map(|(_, change)| change)
+        .collect())
+}
+
 fn format_changes(changes: &[FileChange], include_status: bool) -> String {
     let mut out = String::new();
     for change in changes {
         if include_status {
             let status = match change.status {
-                ChangeType::Added => "A",
-                ChangeType::Modified => "M",
-                ChangeType::Deleted => "D",
+                ChangeType::Added => "A".to_string(),
+                ChangeType::Modified => "M".to_string(),
+                ChangeType::Deleted => "D".to_string(),
+                ChangeType::Renamed { similarity } => format!("R{similarity:03}"),
             };
-            out.push_str(&format!("{}\t{}\n", status, change.path.display()));
+            match &change.old_path {
+                Some(old_path) => out.push_str(&format!(
+                    "{}\t{}\t{}\n",
+                    status,
+                    old_path.display(),
+                    change.path.display()
+                )),
+                None => out.push_str(&format!("{}\t{}\n", status, change.path.display())),
+            }
         } else {
             out.push_str(&format!("{}\n", change.path.display()));
         }
@@ -2485,6 +2787,52 @@ mod tests {
 
     use super::*;
 
+    fn sample_entry(hash: &str) -> LogCommitEntry {
+        LogCommitEntry {
+            hash: hash.to_string(),
+            short_hash: hash.to_string(),
+            author_name: "A".to_string(),
+            author_email: "a@example.com".to_string(),
+            author_date: String::new(),
+            committer_name: "A".to_string(),
+            committer_email: "a@example.com".to_string(),
+            committer_date: String::new(),
+            subject: "subject".to_string(),
+            body: "line one\nline two".to_string(),
+            parents: Vec::new(),
+            refs: Vec::new(),
+            files: Vec::new(),
+            trailers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn log_json_stream_writes_the_shared_envelope_with_a_trailing_status() {
+        for format in [JsonFormat::Pretty, JsonFormat::Compact] {
+            let mut out = Vec::new();
+            let mut stream = LogJsonStream::new(&mut out, format);
+            stream.push(&sample_entry("a1")).unwrap();
+            stream.push(&sample_entry("b2")).unwrap();
+            stream.finish(None, true).unwrap();
+            let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(value["ok"], true);
+            assert_eq!(value["command"], "log");
+            assert_eq!(value["data"]["commits"][1]["hash"], "b2");
+            assert_eq!(value["data"]["commits"][0]["body"], "line one\nline two");
+            assert!(value["data"]["total"].is_null());
+
+            let mut out = Vec::new();
+            let mut stream = LogJsonStream::new(&mut out, format);
+            stream.push(&sample_entry("a1")).unwrap();
+            stream.finish(None, false).unwrap();
+            let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+            assert_eq!(
+                value["ok"], false,
+                "a failed walk still closes the document"
+            );
+        }
+    }
+
     // Test parameter parsing
     #[test]
     fn test_log_args_name_only() {
@@ -2707,16 +3055,27 @@ mod tests {
 
     #[test]
     fn test_format_changes_output() {
-        let changes = vec![FileChange {
-            path: PathBuf::from("src/main.rs"),
-            status: ChangeType::Added,
-        }];
+        let changes = vec![
+            FileChange {
+                path: PathBuf::from("src/main.rs"),
+                status: ChangeType::Added,
+                old_path: None,
+            },
+            FileChange {
+                path: PathBuf::from("src/new.rs"),
+                status: ChangeType::Renamed { similarity: 87 },
+                old_path: Some(PathBuf::from("src/old.rs")),
+            },
+        ];
         let with_status = format_changes(&changes, true);
         assert!(with_status.contains("A\tsrc/main.rs"));
+        assert!(with_status.contains("R087\tsrc/old.rs\tsrc/new.rs"));
 
         let names_only = format_changes(&changes, false);
         assert!(names_only.contains("src/main.rs"));
         assert!(!names_only.contains("A\t"));
+        assert!(names_only.contains("src/new.rs\n"));
+        assert!(!names_only.contains("src/old.rs"));
     }
 
     #[tokio::test]
@@ -2727,12 +3086,12 @@ mod tests {
         commit.committer.timestamp = 1_766_102_400; // 2025-12-19 00:00:00 UTC
 
         let filter = CommitFilter::new(
-            Some("lvy".to_string()),
+            Some(regex::Regex::new("lvy").unwrap()),
             None,
             Some(1_766_000_000),
             Some(1_766_200_000),
             Vec::new(),
-            None,
+            Vec::new(),
             None,
             None,
             None,
@@ -2759,7 +3118,7 @@ mod tests {
             None,
             None,
             Vec::new(),
-            None,
+            Vec::new(),
             Some(2),
             None,
             None,
@@ -2771,7 +3130,7 @@ mod tests {
             None,
             None,
             Vec::new(),
-            None,
+            Vec::new(),
             None,
             Some(1),
             None,
@@ -2785,7 +3144,7 @@ mod tests {
             None,
             None,
             Vec::new(),
-            None,
+            Vec::new(),
             None,
             None,
             None,
@@ -2797,7 +3156,7 @@ mod tests {
             None,
             None,
             Vec::new(),
-            None,
+            Vec::new(),
             None,
             None,
             None,
@@ -10,7 +10,10 @@ use url::Url;
 use crate::{
     command::fetch::RemoteClient,
     git_protocol::ServiceType::UploadPack,
-    internal::{config::ConfigKv, protocol::ssh_client::is_ssh_spec},
+    internal::{
+        config::ConfigKv,
+        protocol::{DiscoveryResult, ssh_client::is_ssh_spec},
+    },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         output::{OutputConfig, emit_json_data},
@@ -242,19 +245,7 @@ async fn run_ls_remote(args: LsRemoteArgs) -> Result<LsRemoteOutput, LsRemoteErr
             remote: visible_remote.clone(),
             source: sanitize_discovery_error(source, &remote_url),
         })?;
-    let patterns = compile_patterns(&args.patterns)?;
-    let mut entries: Vec<LsRemoteEntry> = discovery
-        .refs
-        .iter()
-        .filter(|reference| include_reference(reference, &args, &patterns))
-        .map(|reference| LsRemoteEntry {
-            hash: reference._hash.clone(),
-            refname: reference._ref.clone(),
-        })
-        .collect();
-    sort_entries(&mut entries, args.sort.as_deref())?;
-
-    let symrefs = resolve_output_symrefs(&discovery.capabilities, &entries, args.symref);
+    let (entries, symrefs) = select_advertised_refs(&discovery, &args)?;
 
     Ok(LsRemoteOutput {
         remote: visible_remote,
@@ -271,6 +262,29 @@ async fn run_ls_remote(args: LsRemoteArgs) -> Result<LsRemoteOutput, LsRemoteErr
     })
 }
 
+/// Apply the `--heads`/`--tags`/`--refs`/pattern filters and `--sort` to a
+/// parsed reference advertisement, and resolve the `--symref` targets of the
+/// refs that survive.
+fn select_advertised_refs(
+    discovery: &DiscoveryResult,
+    args: &LsRemoteArgs,
+) -> Result<(Vec<LsRemoteEntry>, Vec<LsRemoteSymref>), LsRemoteError> {
+    let patterns = compile_patterns(&args.patterns)?;
+    let mut entries: Vec<LsRemoteEntry> = discovery
+        .refs
+        .iter()
+        .filter(|reference| include_reference(reference, args, &patterns))
+        .map(|reference| LsRemoteEntry {
+            hash: reference._hash.clone(),
+            refname: reference._ref.clone(),
+        })
+        .collect();
+    sort_entries(&mut entries, args.sort.as_deref())?;
+
+    let symrefs = resolve_output_symrefs(&discovery.capabilities, &entries, args.symref);
+    Ok((entries, symrefs))
+}
+
 async fn resolve_remote(
     repository: &str,
 ) -> Result<(String, String, Option<String>), LsRemoteError> {
@@ -1,5 +1,6 @@
 use std::fs;
 
+use bytes::{Bytes, BytesMut};
 use git_internal::errors::GitError;
 use serial_test::serial;
 use tempfile::tempdir;
@@ -11,10 +12,11 @@ use super::{
         sanitize_discovery_error, sanitize_remote_error_reason, visible_remote_display,
         visible_remote_url,
     },
-    parse_symrefs, resolve_output_symrefs, resolve_remote, write_ref_lines,
+    parse_symrefs, resolve_output_symrefs, resolve_remote, select_advertised_refs, write_ref_lines,
 };
 use crate::{
-    internal::protocol::DiscRef,
+    git_protocol::{ServiceType, add_pkt_line_string},
+    internal::protocol::{DiscRef, parse_discovered_references},
     utils::{test::ChangeDirGuard, util},
 };
 
@@ -363,3 +365,59 @@ fn ls_remote_output_json_omits_empty_symrefs_and_includes_populated() {
         serde_json::json!([{"name": "HEAD", "target": "refs/heads/main"}]),
     );
 }
+
+/// A smart-HTTP `info/refs?service=git-upload-pack` body as a server sends it.
+fn mock_upload_pack_advertisement(refs: &[(&str, &str)]) -> Bytes {
+    let mut buf = BytesMut::new();
+    add_pkt_line_string(&mut buf, "# service=git-upload-pack\n".to_string());
+    buf.extend_from_slice(b"0000");
+    for (index, (hash, refname)) in refs.iter().enumerate() {
+        let line = if index == 0 {
+            format!("{hash} {refname}\0multi_ack side-band-64k symref=HEAD:refs/heads/main\n")
+        } else {
+            format!("{hash} {refname}\n")
+        };
+        add_pkt_line_string(&mut buf, line);
+    }
+    buf.extend_from_slice(b"0000");
+    buf.freeze()
+}
+
+#[test]
+fn mock_advertisement_prints_parsed_refs() {
+    let main = "a".repeat(40);
+    let topic = "b".repeat(40);
+    let tag = "c".repeat(40);
+    let peeled = "d".repeat(40);
+    let body = mock_upload_pack_advertisement(&[
+        (&main, "HEAD"),
+        (&main, "refs/heads/main"),
+        (&topic, "refs/heads/topic"),
+        (&tag, "refs/tags/v1"),
+        (&peeled, "refs/tags/v1^{}"),
+    ]);
+    let discovery = parse_discovered_references(body, ServiceType::UploadPack).unwrap();
+
+    let mut args = args_with_filters(false, false, false);
+    args.symref = true;
+    let (entries, symrefs) = select_advertised_refs(&discovery, &args).unwrap();
+    let mut buf: Vec<u8> = Vec::new();
+    write_ref_lines(&mut buf, &output_with(entries, symrefs)).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        format!(
+            "ref: refs/heads/main\tHEAD\n{main}\tHEAD\n{main}\trefs/heads/main\n\
+             {topic}\trefs/heads/topic\n{tag}\trefs/tags/v1\n{peeled}\trefs/tags/v1^{{}}\n"
+        ),
+    );
+
+    let mut heads = args_with_filters(true, false, false);
+    heads.patterns = vec!["topic".to_string()];
+    let (entries, _) = select_advertised_refs(&discovery, &heads).unwrap();
+    let mut buf: Vec<u8> = Vec::new();
+    write_ref_lines(&mut buf, &output_with(entries, vec![])).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        format!("{topic}\trefs/heads/topic\n")
+    );
+}
@@ -20,26 +20,31 @@ use git_internal::{
         },
     },
 };
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    get_target_commit, load_object, log, reset,
+    get_target_commit, load_object, reset,
     restore::{self, RestoreArgs},
     save_object, status, switch,
 };
 use crate::{
-    common_utils::format_commit_msg,
+    common_utils::{CleanupMode, cleanup_commit_message, format_commit_msg, parse_commit_msg},
     info_println,
     internal::{
         branch::{Branch, BranchStoreError},
         config::ConfigKv,
         db::get_db_conn_instance,
         head::Head,
+        merge_base,
         reflog::{ReflogAction, ReflogContext, with_reflog},
         tree_plumbing,
     },
     utils::{
-        error::{CliError, CliResult, StableErrorCode},
+        error::{CliError, CliResult, StableErrorCode, emit_warning},
+        git_hooks,
+        gitattributes::{self, Attributes},
+        index_marks::{self, IndexMark},
         object_ext::TreeExt,
         output::{OutputConfig, emit_json_data},
         path, util, worktree,
@@ -53,6 +58,8 @@ EXAMPLES:
     libra merge feature-x          Fast-forward current branch onto feature-x if possible
     libra merge origin/main        Fast-forward onto a remote-tracking branch
     libra merge feature-x --no-edit  Accept the default merge message (no editor)
+    libra merge --no-commit -m \"Integrate x\" feature-x  Stage the merge; commit it with --continue
+    libra merge --log feature-x    Append the merged commits' subjects to the message
     libra merge --verify-signatures feature-x  Require a valid PGP signature on the merged tip
     libra merge --continue         Finish an in-progress merge after resolving conflicts
     libra merge --abort            Restore the pre-merge HEAD, index, and worktree
@@ -70,8 +77,11 @@ NOTES:
 #[derive(Parser, Debug)]
 #[command(after_help = MERGE_EXAMPLES)]
 pub struct MergeArgs {
-    /// The branch to merge into the current branch, could be remote branch
-    pub branch: Option<String>,
+    /// The branch to merge into the current branch, could be remote branch.
+    /// Naming several branches makes an octopus merge: one commit with a
+    /// parent per branch, refused if any of them conflicts.
+    #[arg(value_name = "BRANCH")]
+    pub branches: Vec<String>,
 
     /// Continue an in-progress merge after resolving conflicts
     #[arg(long = "continue", conflicts_with = "abort")]
@@ -96,8 +106,8 @@ pub struct MergeArgs {
     /// DISCARDS any conflict resolution done so far — then immediately re-run
     /// the same merge against the recorded target commit, regenerating fresh
     /// conflict markers. The re-run uses default merge options (an original
-    /// `-m`/`--no-ff`/`--squash`/`--no-commit` is not replayed).
-    #[arg(long, conflicts_with_all = ["branch", "continue_merge", "abort", "ff_only", "no_ff", "message", "squash", "no_commit", "verify_signatures"])]
+    /// `-m`/`--log`/`--no-ff`/`--squash`/`--no-commit` is not replayed).
+    #[arg(long, conflicts_with_all = ["branches", "continue_merge", "abort", "ff_only", "no_ff", "message", "log", "squash", "no_commit", "verify_signatures"])]
     pub restart: bool,
 
     /// Refuse to merge unless the current branch can fast-forward to the target.
@@ -108,10 +118,16 @@ pub struct MergeArgs {
     #[arg(long = "no-ff", conflicts_with_all = ["ff_only", "continue_merge", "abort"])]
     pub no_ff: bool,
 
-    /// Use the given message for the merge commit instead of the default.
+    /// Use the given message for the merge commit instead of the default
+    /// `Merge branch '<branch>' into <current>`.
     #[arg(short = 'm', long = "message", value_name = "MSG", conflicts_with_all = ["continue_merge", "abort"])]
     pub message: Option<String>,
 
+    /// Append the one-line subjects of (at most N, default 20) commits being
+    /// merged to the merge message, newest first.
+    #[arg(long, value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "20", conflicts_with_all = ["continue_merge", "abort"])]
+    pub log: Option<usize>,
+
     /// Merge changes but stage the result without committing or moving HEAD
     /// (no merge info recorded); finalize with a normal `commit`.
     #[arg(long, conflicts_with_all = ["ff_only", "continue_merge", "abort"])]
@@ -213,8 +229,11 @@ pub(crate) struct PullMergeOptions {
     /// skipped and a two-parent merge commit is recorded instead.
     pub no_ff: bool,
     /// Override the merge-commit message (`libra merge -m <msg>`). `None` uses
-    /// the default `Merge <upstream> into <head>` message.
+    /// the default `Merge branch '<upstream>' into <head>` message.
     pub message: Option<String>,
+    /// `libra merge --log[=<n>]`: append the subjects of up to `n` merged
+    /// commits to the message. Always `None` for `pull`.
+    pub log: Option<usize>,
     /// `libra merge --squash`: produce the merged index/worktree but do NOT
     /// create a commit or move HEAD (and never fast-forward), leaving the result
     /// staged for a subsequent normal `commit`.
@@ -235,7 +254,14 @@ pub(crate) struct PullMergeOptions {
     pub dry_run: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// In-progress merge, persisted in the repo database's `merge_state` table the
+/// same way [`RebaseState`](crate::command::rebase::RebaseState) persists a
+/// rebase, so `merge --continue`/`--abort`/`--restart` and `status` can find
+/// it. `target` is Git's `MERGE_HEAD` and `orig_head` its `ORIG_HEAD`. Older
+/// repositories may still hold the pre-database `.libra/merge-state.json`; it
+/// is read once and moved into the table. The table is created by the
+/// `2026071001_merge_state` schema migration.
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct MergeState {
     pub head_name: String,
     pub orig_head: String,
@@ -243,49 +269,142 @@ pub(crate) struct MergeState {
     pub target_ref: String,
     pub base: String,
     pub conflicted_paths: Vec<String>,
+    /// Message `--continue` commits with (`-m`/`--log` already applied).
+    /// `None` for states recorded before it was stored; the default message
+    /// is regenerated then.
+    #[serde(default)]
+    pub message: Option<String>,
 }
 
 impl MergeState {
-    fn path() -> PathBuf {
+    fn legacy_path() -> PathBuf {
         util::storage_path().join("merge-state.json")
     }
 
-    pub(crate) fn load_optional_sync() -> Result<Option<Self>, String> {
-        let path = Self::path();
+    pub(crate) async fn load_optional() -> Result<Option<Self>, String> {
+        let db = get_db_conn_instance().await;
+        let stmt = Statement::from_string(
+            DbBackend::Sqlite,
+            r#"
+                SELECT head_name, orig_head, merge_head, target_ref, merge_base, conflicted_paths,
+                       message
+                FROM merge_state
+                LIMIT 1
+            "#
+            .to_string(),
+        );
+        let row = db
+            .query_one(stmt)
+            .await
+            .map_err(|error| format!("failed to load merge_state: {error}"))?;
+        let Some(row) = row else {
+            return Self::migrate_legacy_state().await;
+        };
+
+        let column = |index: usize, name: &str| -> Result<String, String> {
+            row.try_get_by_index(index)
+                .map_err(|error| format!("invalid {name}: {error}"))
+        };
+        Ok(Some(MergeState {
+            head_name: column(0, "head_name")?,
+            orig_head: column(1, "orig_head")?,
+            target: column(2, "merge_head")?,
+            target_ref: column(3, "target_ref")?,
+            base: column(4, "merge_base")?,
+            conflicted_paths: column(5, "conflicted_paths")?
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+            message: row
+                .try_get_by_index(6)
+                .map_err(|error| format!("invalid message: {error}"))?,
+        }))
+    }
+
+    /// Move a pre-database `.libra/merge-state.json` into the table.
+    async fn migrate_legacy_state() -> Result<Option<Self>, String> {
+        let path = Self::legacy_path();
         if !path.exists() {
             return Ok(None);
         }
         let data = fs::read_to_string(&path)
             .map_err(|error| format!("failed to read {}: {error}", path.display()))?;
-        serde_json::from_str(&data)
-            .map(Some)
-            .map_err(|error| format!("failed to parse {}: {error}", path.display()))
+        let state: MergeState = serde_json::from_str(&data)
+            .map_err(|error| format!("failed to parse {}: {error}", path.display()))?;
+        state.save_row().await?;
+        fs::remove_file(&path)
+            .map_err(|error| format!("failed to remove {}: {error}", path.display()))?;
+        Ok(Some(state))
     }
 
-    fn load_required() -> Result<Self, PullMergeError> {
-        Self::load_optional_sync()
+    async fn load_required() -> Result<Self, PullMergeError> {
+        Self::load_optional()
+            .await
             .map_err(PullMergeError::StateLoad)?
             .ok_or(PullMergeError::NoMergeInProgress)
     }
 
-    fn save(&self) -> Result<(), PullMergeError> {
-        let path = Self::path();
-        let data = serde_json::to_vec_pretty(self)
-            .map_err(|error| PullMergeError::StateSave(error.to_string()))?;
-        // Atomic + fsynced write (lore.md §7.7): sequencer state is
-        // recovery-critical, so a crash must leave it either fully written or
-        // absent — never truncated — and it must survive a power loss.
-        crate::utils::atomic_write::write_atomic(&path, &data, true)
-            .map_err(|error| PullMergeError::StateSave(format!("{}: {error}", path.display())))
+    async fn save(&self) -> Result<(), PullMergeError> {
+        self.save_row().await.map_err(PullMergeError::StateSave)
     }
 
-    fn cleanup() -> Result<(), PullMergeError> {
-        let path = Self::path();
-        if !path.exists() {
-            return Ok(());
+    /// Replace the stored merge with `self`; the `DELETE`+`INSERT` runs in one
+    /// transaction so a crash leaves the state either fully written or absent.
+    async fn save_row(&self) -> Result<(), String> {
+        let db = get_db_conn_instance().await;
+        let txn = db
+            .begin()
+            .await
+            .map_err(|error| format!("failed to begin merge_state transaction: {error}"))?;
+        txn.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "DELETE FROM merge_state".to_string(),
+        ))
+        .await
+        .map_err(|error| format!("failed to clear merge_state: {error}"))?;
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+                INSERT INTO merge_state
+                (head_name, orig_head, merge_head, target_ref, merge_base, conflicted_paths,
+                 message)
+                VALUES (?, ?, ?, ?, ?, ?, ?);
+            "#,
+            [
+                self.head_name.clone().into(),
+                self.orig_head.clone().into(),
+                self.target.clone().into(),
+                self.target_ref.clone().into(),
+                self.base.clone().into(),
+                self.conflicted_paths.join("\n").into(),
+                self.message.clone().into(),
+            ],
+        ))
+        .await
+        .map_err(|error| format!("failed to save merge_state: {error}"))?;
+        txn.commit()
+            .await
+            .map_err(|error| format!("failed to commit merge_state transaction: {error}"))
+    }
+
+    async fn cleanup() -> Result<(), PullMergeError> {
+        let db = get_db_conn_instance().await;
+        db.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "DELETE FROM merge_state".to_string(),
+        ))
+        .await
+        .map_err(|error| {
+            PullMergeError::StateCleanup(format!("failed to clear merge_state: {error}"))
+        })?;
+        let legacy = Self::legacy_path();
+        if legacy.exists() {
+            fs::remove_file(&legacy).map_err(|error| {
+                PullMergeError::StateCleanup(format!("{}: {error}", legacy.display()))
+            })?;
         }
-        fs::remove_file(&path)
-            .map_err(|error| PullMergeError::StateCleanup(format!("{}: {error}", path.display())))
+        Ok(())
     }
 }
 
@@ -368,6 +487,15 @@ pub(crate) enum PullMergeError {
     BadMergeSignature { commit: String },
     #[error("failed to verify the signature of the merged commit: {0}")]
     SignatureCheck(String),
+    /// An octopus merge hit a conflict merging `branch`. Octopus merges are
+    /// all-or-nothing, so nothing has been written.
+    #[error("merging '{branch}' conflicts in {paths}; an octopus merge must be conflict-free")]
+    OctopusConflict { branch: String, paths: String },
+    /// A flag that needs a single merge target was given several branches.
+    #[error("{0} cannot be used when merging more than one branch")]
+    OctopusOption(&'static str),
+    #[error("can merge only exactly one commit into an empty head")]
+    OctopusEmptyHead,
 }
 
 pub(crate) type MergeError = PullMergeError;
@@ -375,7 +503,9 @@ pub(crate) type MergeError = PullMergeError;
 impl From<PullMergeError> for CliError {
     fn from(error: PullMergeError) -> Self {
         match &error {
-            PullMergeError::MissingAction | PullMergeError::ConflictingAction => {
+            PullMergeError::MissingAction
+            | PullMergeError::ConflictingAction
+            | PullMergeError::OctopusOption(..) => {
                 CliError::command_usage(error.to_string())
                     .with_stable_code(StableErrorCode::CliInvalidArguments)
             }
@@ -413,8 +543,13 @@ impl From<PullMergeError> for CliError {
                 .with_stable_code(StableErrorCode::ConflictOperationBlocked)
                 .with_hint("resolve conflicts, then run 'libra merge --continue'")
                 .with_hint("or run 'libra merge --abort' to restore the pre-merge state"),
-            PullMergeError::NoMergeInProgress => CliError::failure(error.to_string())
-                .with_stable_code(StableErrorCode::RepoStateInvalid),
+            PullMergeError::OctopusConflict { .. } => CliError::failure(error.to_string())
+                .with_stable_code(StableErrorCode::ConflictOperationBlocked)
+                .with_hint("merge the branches one at a time to resolve the conflict"),
+            PullMergeError::NoMergeInProgress | PullMergeError::OctopusEmptyHead => {
+                CliError::failure(error.to_string())
+                    .with_stable_code(StableErrorCode::RepoStateInvalid)
+            }
             PullMergeError::RestartWithoutConflicts => CliError::failure(error.to_string())
                 .with_stable_code(StableErrorCode::RepoStateInvalid)
                 .with_hint("finish the staged merge with 'libra merge --continue'")
@@ -468,11 +603,14 @@ pub async fn execute(args: MergeArgs) {
 pub async fn execute_safe(args: MergeArgs, output: &OutputConfig) -> CliResult<()> {
     // Refuse to start a merge while a cherry-pick sequence is in progress.
     crate::command::cherry_pick::ensure_no_cherry_pick_in_progress().await?;
-    // `args` is moved into `run_merge`; capture the diffstat opt-in first.
+    // `args` is moved into `run_merge`; capture the diffstat opt-in and the
+    // flags the `post-merge` hook depends on first.
     let show_stat = args.stat;
+    let (squash, no_commit) = (args.squash, args.no_commit);
     let result = run_merge(args, output).await.map_err(merge_error_to_cli)?;
     render_merge_output(&result, output)?;
     maybe_print_merge_stat(show_stat, &result, output).await;
+    run_post_merge_hook(&result, squash, no_commit, output).await;
     // `--dry-run` that would conflict: the summary (human or JSON) has been
     // rendered; exit 1 to signal the outcome — mirroring `merge-file`'s
     // conflict-with-output exit and `diff --exit-code`. Deliberately not the
@@ -484,6 +622,27 @@ pub async fn execute_safe(args: MergeArgs, output: &OutputConfig) -> CliResult<(
     Ok(())
 }
 
+/// Run `post-merge` after a merge that finished: a fast-forward, a merge
+/// commit, or a squash (flag `1`). Like Git, an up-to-date, aborted,
+/// conflicted or `--no-commit` merge does not run it, nor does a preview.
+pub(crate) async fn run_post_merge_hook(
+    result: &MergeOutput,
+    squash: bool,
+    no_commit: bool,
+    output: &OutputConfig,
+) {
+    if result.dry_run
+        || result.up_to_date
+        || result.aborted
+        || result.continued
+        || no_commit
+        || !result.conflicted_paths.is_empty()
+    {
+        return;
+    }
+    git_hooks::run_post_merge_hook(squash, output).await;
+}
+
 /// `--stat`: print a Git-style diffstat of what the merge changed (pre-merge
 /// HEAD vs the new commit). Human output only — `--json` already exposes
 /// `files_changed`. Skipped when there is no completed new commit (up-to-date,
@@ -513,24 +672,26 @@ async fn run_merge(args: MergeArgs, output: &OutputConfig) -> Result<MergeOutput
     if args.restart {
         return run_merge_restart(output).await;
     }
-    match (args.branch.as_deref(), args.continue_merge, args.abort) {
-        (Some(branch), false, false) => {
-            let options = PullMergeOptions {
-                ff_only: args.ff_only,
-                no_ff: args.no_ff,
-                message: args.message.clone(),
-                squash: args.squash,
-                no_commit: args.no_commit,
-                // `--verify-signatures` is enforced inside the merge on the loaded
-                // tip commit, so the verified object is exactly the merged object.
-                verify_signatures: args.verify_signatures,
-                dry_run: args.dry_run,
-            };
+    let options = PullMergeOptions {
+        ff_only: args.ff_only,
+        no_ff: args.no_ff,
+        message: args.message.clone(),
+        log: args.log,
+        squash: args.squash,
+        no_commit: args.no_commit,
+        // `--verify-signatures` is enforced inside the merge on the loaded
+        // tip commit, so the verified object is exactly the merged object.
+        verify_signatures: args.verify_signatures,
+        dry_run: args.dry_run,
+    };
+    match (args.branches.as_slice(), args.continue_merge, args.abort) {
+        ([branch], false, false) => {
             run_merge_for_pull_with_options(branch, branch, output, options).await
         }
-        (None, true, false) => run_merge_continue(output).await,
-        (None, false, true) => run_merge_abort(output).await,
-        (None, false, false) => Err(MergeError::MissingAction),
+        ([_, _, ..], false, false) => run_octopus_merge(&args.branches, output, options).await,
+        ([], true, false) => run_merge_continue(output).await,
+        ([], false, true) => run_merge_abort(output).await,
+        ([], false, false) => Err(MergeError::MissingAction),
         _ => Err(MergeError::ConflictingAction),
     }
 }
@@ -543,7 +704,7 @@ async fn verify_merge_commit_signature(commit: &Commit) -> Result<(), MergeError
     use crate::command::commit::{CommitSignatureStatus, verify_commit_signature};
 
     match verify_commit_signature(commit).await {
-        Ok(CommitSignatureStatus::Good) => Ok(()),
+        Ok(CommitSignatureStatus::Good(_)) => Ok(()),
         Ok(CommitSignatureStatus::Unsigned) => Err(MergeError::UnsignedMergeCommit {
             commit: commit.id.to_string(),
         }),
@@ -579,7 +740,8 @@ fn render_merge_output(result: &MergeOutput, output: &OutputConfig) -> CliResult
         } else {
             info_println!(
                 output,
-                "Would merge cleanly by the 'three-way' strategy.\n(dry run: nothing was written)"
+                "Would merge cleanly by the '{}' strategy.\n(dry run: nothing was written)",
+                result.strategy
             );
         }
         return Ok(());
@@ -599,6 +761,7 @@ fn render_merge_output(result: &MergeOutput, output: &OutputConfig) -> CliResult
     } else {
         match result.strategy.as_str() {
             "three-way" => info_println!(output, "Merge made by the 'three-way' strategy."),
+            "octopus" => info_println!(output, "Merge made by the 'octopus' strategy."),
             "squash" => info_println!(output, "Squash commit -- not updating HEAD"),
             "no-commit" => info_println!(
                 output,
@@ -626,7 +789,8 @@ pub(crate) async fn run_merge_for_pull_with_options(
     output: &OutputConfig,
     options: PullMergeOptions,
 ) -> Result<PullMergeSummary, PullMergeError> {
-    if MergeState::load_optional_sync()
+    if MergeState::load_optional()
+        .await
         .map_err(PullMergeError::StateLoad)?
         .is_some()
     {
@@ -676,11 +840,8 @@ pub(crate) async fn run_merge_for_pull_with_options(
             detail: error.to_string(),
         })?;
 
-    let lca = lca_commit(&current_commit, &target_commit)
-        .await
-        .map_err(|error| PullMergeError::History(error.to_string()))?;
-
-    let lca = lca.ok_or(PullMergeError::UnrelatedHistories)?;
+    let lca = merge_base_commit(&current_commit.id, &target_commit.id, !options.dry_run)?
+        .ok_or(PullMergeError::UnrelatedHistories)?;
 
     if lca.id == target_commit.id {
         return Ok(PullMergeSummary {
@@ -702,6 +863,7 @@ pub(crate) async fn run_merge_for_pull_with_options(
         let files_changed = count_changed_files(Some(&current_commit), &target_commit)?;
         // `--dry-run`: report the fast-forward preview without applying it.
         if !options.dry_run {
+            record_orig_head(&current_commit_id)?;
             apply_fast_forward_merge(target_commit.clone(), upstream, output).await?;
         }
         return Ok(PullMergeSummary {
@@ -729,6 +891,9 @@ pub(crate) async fn run_merge_for_pull_with_options(
         });
     }
 
+    if !options.dry_run {
+        record_orig_head(&current_commit_id)?;
+    }
     perform_three_way_merge(
         current_commit,
         target_commit,
@@ -736,6 +901,7 @@ pub(crate) async fn run_merge_for_pull_with_options(
         upstream,
         ThreeWayMergeOptions {
             message_override: options.message.clone(),
+            log: options.log,
             squash: options.squash,
             no_commit: options.no_commit,
             dry_run: options.dry_run,
@@ -745,6 +911,297 @@ pub(crate) async fn run_merge_for_pull_with_options(
     .await
 }
 
+/// `libra merge a b c`: merge several branches into one commit with a parent
+/// per head (Git's `octopus` strategy). Each head is three-way merged in turn
+/// into the accumulated tree, over its merge base with HEAD. Heads already
+/// contained in HEAD are dropped; if only one remains this is an ordinary
+/// merge. Any conflict aborts the whole merge before anything is written
+/// (the auto-merged blobs of earlier heads stay as unreferenced objects).
+/// `--squash` and `--dry-run` apply; `--no-commit` and `--ff-only` are
+/// refused because they need a single target.
+async fn run_octopus_merge(
+    branches: &[String],
+    output: &OutputConfig,
+    options: PullMergeOptions,
+) -> Result<PullMergeSummary, PullMergeError> {
+    if options.no_commit {
+        return Err(PullMergeError::OctopusOption("--no-commit"));
+    }
+    if options.ff_only {
+        return Err(PullMergeError::OctopusOption("--ff-only"));
+    }
+    if MergeState::load_optional()
+        .await
+        .map_err(PullMergeError::StateLoad)?
+        .is_some()
+    {
+        return Err(PullMergeError::MergeInProgress);
+    }
+
+    let mut targets: Vec<(&str, Commit)> = Vec::new();
+    for branch in branches {
+        let commit_hash = resolve_merge_target(branch)
+            .await
+            .map_err(|_| PullMergeError::InvalidTarget(branch.clone()))?;
+        let commit: Commit =
+            load_object(&commit_hash).map_err(|error| PullMergeError::TargetLoad {
+                commit_id: commit_hash.to_string(),
+                detail: error.to_string(),
+            })?;
+        if options.verify_signatures {
+            verify_merge_commit_signature(&commit).await?;
+        }
+        targets.push((branch.as_str(), commit));
+    }
+
+    let current_commit_id = Head::current_commit()
+        .await
+        .ok_or(PullMergeError::OctopusEmptyHead)?;
+    let current_commit: Commit =
+        load_object(&current_commit_id).map_err(|error| PullMergeError::CurrentLoad {
+            commit_id: current_commit_id.to_string(),
+            detail: error.to_string(),
+        })?;
+
+    let mut heads: Vec<(&str, Commit)> = Vec::new();
+    for (name, commit) in targets {
+        let merged = merge_base::is_ancestor(&commit.id, &current_commit.id)
+            .map_err(|error| PullMergeError::History(error.to_string()))?;
+        if !merged && !heads.iter().any(|(_, head)| head.id == commit.id) {
+            heads.push((name, commit));
+        }
+    }
+    match heads.as_slice() {
+        [] => {
+            return Ok(PullMergeSummary {
+                strategy: "already-up-to-date".to_string(),
+                old_commit: Some(current_commit_id.to_string()),
+                commit: None,
+                files_changed: 0,
+                up_to_date: true,
+                parents: Vec::new(),
+                conflicted_paths: Vec::new(),
+                aborted: false,
+                continued: false,
+                dry_run: options.dry_run,
+                would_conflict: false,
+            });
+        }
+        [(name, _)] => {
+            let name = name.to_string();
+            let options = PullMergeOptions {
+                verify_signatures: false,
+                ..options
+            };
+            return run_merge_for_pull_with_options(&name, &name, output, options).await;
+        }
+        _ => {}
+    }
+
+    if !options.dry_run {
+        switch::ensure_clean_status(output)
+            .await
+            .map_err(|_| PullMergeError::DirtyWorktree)?;
+    }
+
+    let our_items = commit_tree_items(&current_commit)?;
+    let mut merged_items = our_items.clone();
+    for (name, commit) in &heads {
+        let base = merge_base_commit(&current_commit.id, &commit.id, !options.dry_run)?
+            .ok_or(PullMergeError::UnrelatedHistories)?;
+        let base_items = commit_tree_items(&base)?;
+        let their_items = commit_tree_items(commit)?;
+        let result = merge_tree_items(&base_items, &merged_items, &their_items, !options.dry_run)?;
+        if !result.conflicts.is_empty() {
+            let conflicted_paths: Vec<String> = result
+                .conflicts
+                .iter()
+                .map(|(path, _)| path.display().to_string())
+                .collect();
+            if options.dry_run {
+                return Ok(PullMergeSummary {
+                    strategy: "octopus".to_string(),
+                    old_commit: Some(current_commit.id.to_string()),
+                    commit: None,
+                    files_changed: 0,
+                    up_to_date: false,
+                    parents: Vec::new(),
+                    conflicted_paths,
+                    aborted: false,
+                    continued: false,
+                    dry_run: true,
+                    would_conflict: true,
+                });
+            }
+            return Err(PullMergeError::OctopusConflict {
+                branch: name.to_string(),
+                paths: conflicted_paths.join(", "),
+            });
+        }
+        merged_items = result.merged_items;
+    }
+    let files_changed = count_item_map_changes(&our_items, &merged_items);
+    let mut parents = vec![current_commit.id];
+    parents.extend(heads.iter().map(|(_, commit)| commit.id));
+
+    if options.dry_run {
+        return Ok(PullMergeSummary {
+            strategy: "octopus".to_string(),
+            old_commit: Some(current_commit.id.to_string()),
+            commit: None,
+            files_changed,
+            up_to_date: false,
+            parents: Vec::new(),
+            conflicted_paths: Vec::new(),
+            aborted: false,
+            continued: false,
+            dry_run: true,
+            would_conflict: false,
+        });
+    }
+
+    let current_index =
+        Index::load(path::index()).map_err(|error| PullMergeError::IndexLoad(error.to_string()))?;
+    let paths_to_write: Vec<PathBuf> = merged_items.keys().cloned().collect();
+    ensure_no_untracked_conflicts(&current_index, &paths_to_write)?;
+    let tree_id = create_tree_from_items_map(&merged_items).map_err(PullMergeError::TreeCreate)?;
+    record_orig_head(&current_commit_id)?;
+
+    if options.squash {
+        reset_index_and_workdir_to_tree(&tree_id)?;
+        return Ok(PullMergeSummary {
+            strategy: "squash".to_string(),
+            old_commit: Some(current_commit.id.to_string()),
+            commit: None,
+            files_changed,
+            up_to_date: false,
+            parents: Vec::new(),
+            conflicted_paths: Vec::new(),
+            aborted: false,
+            continued: false,
+            dry_run: false,
+            would_conflict: false,
+        });
+    }
+
+    let head_name = current_head_name().await?;
+    let named_heads: Vec<(&str, ObjectHash)> = heads
+        .iter()
+        .map(|(name, commit)| (*name, commit.id))
+        .collect();
+    let message = merge_commit_message(
+        options.message,
+        options.log,
+        &named_heads,
+        &head_name,
+        &current_commit.id,
+    )?;
+    let merge_commit =
+        Commit::from_tree_id(tree_id, parents.clone(), &format_commit_msg(&message, None));
+    save_object(&merge_commit, &merge_commit.id)
+        .map_err(|error| PullMergeError::CommitSave(error.to_string()))?;
+    let names: Vec<&str> = heads.iter().map(|(name, _)| *name).collect();
+    update_head_with_reflog(&head_name, merge_commit.id, &names.join(" "), "octopus").await?;
+    reset_index_and_workdir_to_tree(&tree_id)?;
+
+    Ok(PullMergeSummary {
+        strategy: "octopus".to_string(),
+        old_commit: Some(current_commit.id.to_string()),
+        commit: Some(merge_commit.id.to_string()),
+        files_changed,
+        up_to_date: false,
+        parents: parents.iter().map(ToString::to_string).collect(),
+        conflicted_paths: Vec::new(),
+        aborted: false,
+        continued: false,
+        dry_run: false,
+        would_conflict: false,
+    })
+}
+
+/// Record the pre-merge tip as `ORIG_HEAD` before a merge moves HEAD or the
+/// worktree, so `reset --hard ORIG_HEAD` undoes it (also after `pull`).
+fn record_orig_head(current: &ObjectHash) -> Result<(), PullMergeError> {
+    Head::record_orig_head(current)
+        .map_err(|error| PullMergeError::StateSave(format!("failed to write ORIG_HEAD: {error}")))
+}
+
+/// Git's default merge-commit message: `Merge branch 'a' into main`, or for
+/// an octopus `Merge branches 'a', 'b' and 'c' into main`.
+fn default_merge_message(upstreams: &[&str], head_name: &str) -> String {
+    let quoted: Vec<String> = upstreams.iter().map(|name| format!("'{name}'")).collect();
+    match quoted.as_slice() {
+        [] => format!("Merge into {head_name}"),
+        [one] => format!("Merge branch {one} into {head_name}"),
+        [rest @ .., last] => format!(
+            "Merge branches {} and {last} into {head_name}",
+            rest.join(", ")
+        ),
+    }
+}
+
+/// The merge-commit message: `-m` or the default, followed for `--log[=<n>]`
+/// by one Git-style `* <branch>:` block per merged head listing the subjects
+/// of the commits it brings in (reachable from the head but not `ours`),
+/// newest first, with `...` when more than `n` were cut off. The message is
+/// never edited, so it gets Git's default cleanup for that case (whitespace).
+fn merge_commit_message(
+    message_override: Option<String>,
+    log: Option<usize>,
+    heads: &[(&str, ObjectHash)],
+    head_name: &str,
+    ours: &ObjectHash,
+) -> Result<String, PullMergeError> {
+    let mut message = message_override.unwrap_or_else(|| {
+        let names: Vec<&str> = heads.iter().map(|(name, _)| *name).collect();
+        default_merge_message(&names, head_name)
+    });
+    let Some(limit) = log.filter(|limit| *limit > 0) else {
+        return Ok(cleanup_commit_message(
+            &message,
+            CleanupMode::Default.effective(false),
+        ));
+    };
+
+    message = message.trim_end().to_string();
+    for (upstream, theirs) in heads {
+        let ids = merge_base::commits_between(ours, theirs)
+            .map_err(|error| PullMergeError::History(error.to_string()))?;
+        let mut commits = ids
+            .iter()
+            .map(|id| {
+                load_object::<Commit>(id).map_err(|error| PullMergeError::TargetLoad {
+                    commit_id: id.to_string(),
+                    detail: error.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        commits.sort_by(|a, b| {
+            b.committer
+                .timestamp
+                .cmp(&a.committer.timestamp)
+                .then_with(|| a.id.to_string().cmp(&b.id.to_string()))
+        });
+
+        message.push_str(&format!("\n\n* {upstream}:"));
+        for commit in commits.iter().take(limit) {
+            let subject = parse_commit_msg(&commit.message)
+                .0
+                .lines()
+                .next()
+                .unwrap_or("");
+            message.push_str(&format!("\n  {subject}"));
+        }
+        if commits.len() > limit {
+            message.push_str("\n  ...");
+        }
+    }
+    Ok(cleanup_commit_message(
+        &message,
+        CleanupMode::Default.effective(false),
+    ))
+}
+
 struct ThreeWayMergeResult {
     merged_items: HashMap<PathBuf, MergeTreeEntry>,
     conflicts: Vec<(PathBuf, ConflictKind)>,
@@ -764,6 +1221,7 @@ impl MergeTreeEntry {
 
 struct ThreeWayMergeOptions<'a> {
     message_override: Option<String>,
+    log: Option<usize>,
     squash: bool,
     no_commit: bool,
     /// Preview only: compute the outcome, write nothing (lore.md §1.3).
@@ -822,6 +1280,16 @@ async fn perform_three_way_merge(
         });
     }
 
+    // Resolved up front so a conflicted or `--no-commit` merge records it in
+    // MergeState and `--continue` commits the same message.
+    let message = merge_commit_message(
+        options.message_override,
+        options.log,
+        &[(upstream, target_commit.id)],
+        &head_name,
+        &current_commit.id,
+    )?;
+
     if !merge_result.conflicts.is_empty() {
         // Resolved only on the conflict path: a clean merge never renders
         // markers, so an invalid style config cannot block it.
@@ -841,7 +1309,10 @@ async fn perform_three_way_merge(
             our_items,
             their_items,
             conflict_style,
-        })?;
+            attributes: Attributes::for_worktree().await,
+            message,
+        })
+        .await?;
         // rerere: record the preimage of each merge conflict just written and
         // replay a recorded resolution if one matches. A no-op unless
         // `rerere.enabled`; staging of a replayed file follows `rerere.autoUpdate`
@@ -849,7 +1320,10 @@ async fn perform_three_way_merge(
         if let Err(error) = crate::command::rerere::auto_update(false).await {
             tracing::warn!("rerere auto-update after merge conflict failed: {error}");
         }
-        let paths = MergeState::load_required()?.conflicted_paths.join(", ");
+        let paths = MergeState::load_required()
+            .await?
+            .conflicted_paths
+            .join(", ");
         return Err(PullMergeError::Conflicts { paths });
     }
 
@@ -895,8 +1369,10 @@ async fn perform_three_way_merge(
             target_ref: upstream.to_string(),
             base: base_commit.id.to_string(),
             conflicted_paths: Vec::new(),
+            message: Some(message),
         }
-        .save()?;
+        .save()
+        .await?;
         return Ok(PullMergeSummary {
             strategy: "no-commit".to_string(),
             old_commit: Some(current_commit.id.to_string()),
@@ -912,9 +1388,6 @@ async fn perform_three_way_merge(
         });
     }
 
-    let message = options
-        .message_override
-        .unwrap_or_else(|| format!("Merge {upstream} into {head_name}"));
     let merge_commit = Commit::from_tree_id(
         tree_id,
         vec![current_commit.id, target_commit.id],
@@ -989,9 +1462,13 @@ struct MergeConflictInput {
     their_items: HashMap<PathBuf, MergeTreeEntry>,
     /// Marker style for conflicted paths, resolved from `merge.conflictStyle`.
     conflict_style: diffy::ConflictStyle,
+    /// Worktree attributes; `-merge`/`binary` paths get no conflict markers.
+    attributes: Attributes,
+    /// Message `merge --continue` commits with.
+    message: String,
 }
 
-fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMergeError> {
+async fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMergeError> {
     let current_index =
         Index::load(path::index()).map_err(|error| PullMergeError::IndexLoad(error.to_string()))?;
 
@@ -1010,7 +1487,6 @@ fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMer
 
     let conflict_set: HashSet<PathBuf> = conflict_paths.iter().cloned().collect();
     let workdir = util::working_dir();
-    let marker_eol = conflict_marker_eol();
     let theirs_abbrev = short_object_id(&input.theirs);
 
     let mut index = Index::new();
@@ -1039,15 +1515,20 @@ fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMer
             .iter()
             .map(|path| path.display().to_string())
             .collect(),
+        message: Some(input.message),
     };
-    state.save()?;
+    state.save().await?;
 
     if let Err(error) = index.save(path::index()) {
-        let _ = MergeState::cleanup();
+        let _ = MergeState::cleanup().await;
         return Err(PullMergeError::IndexSave(error.to_string()));
     }
 
     for (path, entry) in &input.merged_items {
+        if entry.mode == TreeItemMode::Commit {
+            // `libra submodule update` checks gitlinks out.
+            continue;
+        }
         let blob: Blob = load_object(&entry.hash).map_err(|error| {
             PullMergeError::WorkdirReset(format!(
                 "failed to load merged blob {} for '{}': {error}",
@@ -1055,7 +1536,8 @@ fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMer
                 path.display()
             ))
         })?;
-        write_workdir_file(&workdir, path, &blob.data).map_err(PullMergeError::WorkdirReset)?;
+        write_workdir_file(&workdir, path, entry.mode, &blob.data)
+            .map_err(PullMergeError::WorkdirReset)?;
     }
 
     let mut tracked_paths: HashSet<PathBuf> = current_index.tracked_files().into_iter().collect();
@@ -1067,7 +1549,8 @@ fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMer
             continue;
         }
         let full_path = workdir.join(&path);
-        if full_path.exists() {
+        // A submodule checkout is left in place, as Git does.
+        if full_path.exists() && !full_path.is_dir() {
             fs::remove_file(&full_path).map_err(|error| {
                 PullMergeError::WorkdirReset(format!(
                     "failed to remove {}: {error}",
@@ -1078,13 +1561,34 @@ fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMer
     }
 
     for (path, kind) in &input.conflicts {
+        // A gitlink conflict has no file content to mark up; the index
+        // stages alone record it.
+        let is_gitlink = |items: &HashMap<PathBuf, MergeTreeEntry>| {
+            items
+                .get(path)
+                .is_some_and(|entry| entry.mode == TreeItemMode::Commit)
+        };
+        if is_gitlink(&input.our_items) || is_gitlink(&input.their_items) {
+            continue;
+        }
+        // The marked-up file keeps an executable bit either side records.
+        let mode = if [&input.our_items, &input.their_items].iter().any(|items| {
+            items
+                .get(path)
+                .is_some_and(|entry| entry.mode == TreeItemMode::BlobExecutable)
+        }) {
+            TreeItemMode::BlobExecutable
+        } else {
+            TreeItemMode::Blob
+        };
         write_conflict_markers(
             &workdir,
             path,
-            marker_eol,
+            mode,
             &theirs_abbrev,
             *kind,
             input.conflict_style,
+            &input.attributes,
         )
         .map_err(PullMergeError::WorkdirReset)?;
     }
@@ -1093,7 +1597,7 @@ fn write_conflicted_merge_state(input: MergeConflictInput) -> Result<(), PullMer
 }
 
 async fn run_merge_continue(_output: &OutputConfig) -> Result<MergeOutput, MergeError> {
-    let state = MergeState::load_required()?;
+    let state = MergeState::load_required().await?;
     ensure_no_unstaged_changes_for_continue()?;
     let index =
         Index::load(path::index()).map_err(|error| MergeError::IndexLoad(error.to_string()))?;
@@ -1120,7 +1624,11 @@ async fn run_merge_continue(_output: &OutputConfig) -> Result<MergeOutput, Merge
     let index_items = index_tree_items(&index)?;
     let files_changed = count_item_map_changes(&original_items, &index_items);
     let tree_id = create_tree_from_items_map(&index_items).map_err(MergeError::TreeCreate)?;
-    let message = format!("Merge {} into {}", state.target_ref, state.head_name);
+    let message = state
+        .message
+        .as_deref()
+        .map(|message| cleanup_commit_message(message, CleanupMode::Default.effective(false)))
+        .unwrap_or_else(|| default_merge_message(&[&state.target_ref], &state.head_name));
     let merge_commit = Commit::from_tree_id(
         tree_id,
         vec![orig_head, target],
@@ -1136,7 +1644,7 @@ async fn run_merge_continue(_output: &OutputConfig) -> Result<MergeOutput, Merge
     )
     .await?;
     reset_index_and_workdir_to_tree(&tree_id)?;
-    MergeState::cleanup()?;
+    MergeState::cleanup().await?;
 
     Ok(PullMergeSummary {
         strategy: "three-way".to_string(),
@@ -1179,7 +1687,7 @@ async fn restore_pre_merge_state(
             detail: error.to_string(),
         })?;
     reset_index_and_workdir_to_tree(&original_commit.tree_id)?;
-    MergeState::cleanup()?;
+    MergeState::cleanup().await?;
     Ok(orig_head)
 }
 
@@ -1190,10 +1698,10 @@ async fn restore_pre_merge_state(
 /// RECORDED target commit (`state.target`, not the ref name, which may have
 /// moved since the original merge), regenerating fresh conflict markers and
 /// merge state. The re-run uses default merge options: the original
-/// `-m`/`--no-ff`/`--squash`/`--no-commit` are not persisted in [`MergeState`]
-/// and are not replayed (documented limitation).
+/// `-m`/`--log`/`--no-ff`/`--squash`/`--no-commit` are not replayed (the stored
+/// message is dropped with the aborted state; documented limitation).
 async fn run_merge_restart(output: &OutputConfig) -> Result<MergeOutput, MergeError> {
-    let state = MergeState::load_required()?;
+    let state = MergeState::load_required().await?;
     // A `--no-commit` merge also persists MergeState — with no conflicts.
     // Restarting it would silently discard the staged result and re-run with
     // default options (possibly fast-forwarding); refuse instead.
@@ -1209,7 +1717,7 @@ async fn run_merge_restart(output: &OutputConfig) -> Result<MergeOutput, MergeEr
 }
 
 async fn run_merge_abort(_output: &OutputConfig) -> Result<MergeOutput, MergeError> {
-    let state = MergeState::load_required()?;
+    let state = MergeState::load_required().await?;
     let orig_head = restore_pre_merge_state(&state, "abort").await?;
 
     Ok(PullMergeSummary {
@@ -1240,32 +1748,105 @@ async fn resolve_merge_target(target_ref: &str) -> Result<ObjectHash, Box<dyn st
     get_target_commit(target_ref).await
 }
 
-async fn lca_commit(lhs: &Commit, rhs: &Commit) -> Result<Option<Commit>, CliError> {
-    let lhs_reachable = log::get_reachable_commits(lhs.id.to_string(), None).await?;
-    let rhs_reachable = log::get_reachable_commits(rhs.id.to_string(), None).await?;
-
-    // Commit `eq` is based on tree_id, so we shouldn't use it here
-
-    for commit in lhs_reachable.iter() {
-        if commit.id == rhs.id {
-            return Ok(Some(commit.to_owned()));
-        }
+/// The base for merging `lhs` and `rhs`, from the shared LCA implementation in
+/// [`merge_base`]. A criss-cross history has several LCAs; like Git's
+/// `recursive` strategy they are merged pairwise into a virtual base commit,
+/// each pair over its own (recursively computed) base. The virtual commit and
+/// its tree are written to the object store so the recursion can walk them,
+/// but nothing references them. `--dry-run` must not write, so a preview uses
+/// the first LCA instead.
+fn merge_base_commit(
+    lhs: &ObjectHash,
+    rhs: &ObjectHash,
+    persist_virtual_base: bool,
+) -> Result<Option<Commit>, PullMergeError> {
+    let bases =
+        merge_base::merge_bases(lhs, rhs).map_err(|e| PullMergeError::History(e.to_string()))?;
+    let mut bases = bases.iter();
+    let Some(first) = bases.next() else {
+        return Ok(None);
+    };
+    let mut base = load_history_commit(first)?;
+    if !persist_virtual_base {
+        return Ok(Some(base));
     }
-
-    for commit in rhs_reachable.iter() {
-        if commit.id == lhs.id {
-            return Ok(Some(commit.to_owned()));
-        }
+    for next in bases {
+        base = virtual_merge_base(base, load_history_commit(next)?)?;
     }
+    Ok(Some(base))
+}
 
-    for lhs_parent in lhs_reachable.iter() {
-        for rhs_parent in rhs_reachable.iter() {
-            if lhs_parent.id == rhs_parent.id {
-                return Ok(Some(lhs_parent.to_owned()));
+/// Merge two merge-base candidates into a virtual commit. Conflicts do not
+/// stop the merge: both-changed content keeps its conflict markers and a
+/// modify/delete keeps the modified side, so the outer merge sees them as
+/// changes against the base rather than silently picking a side.
+fn virtual_merge_base(ours: Commit, theirs: Commit) -> Result<Commit, PullMergeError> {
+    let base_items = match merge_base_commit(&ours.id, &theirs.id, true)? {
+        Some(base) => commit_tree_items(&base)?,
+        None => HashMap::new(),
+    };
+    let our_items = commit_tree_items(&ours)?;
+    let their_items = commit_tree_items(&theirs)?;
+    let result = merge_tree_items(&base_items, &our_items, &their_items, true)?;
+
+    let mut merged_items = result.merged_items;
+    for (path, kind) in result.conflicts {
+        let entry = match kind {
+            ConflictKind::BothChanged {
+                base,
+                ours: ours_hash,
+                theirs: theirs_hash,
+            } => {
+                let content = both_changed_conflict_content(
+                    base,
+                    &load_merge_blob(ours_hash)?.data,
+                    &load_merge_blob(theirs_hash)?.data,
+                    "\n",
+                    &short_object_id(&theirs.id),
+                    diffy::ConflictStyle::Merge,
+                )
+                .map_err(PullMergeError::TreeCreate)?;
+                let blob = Blob::from_content_bytes(content);
+                save_object(&blob, &blob.id).map_err(|error| {
+                    PullMergeError::TreeCreate(format!(
+                        "failed to save virtual merge base blob {}: {error}",
+                        blob.id
+                    ))
+                })?;
+                let mode = our_items
+                    .get(&path)
+                    .map_or(TreeItemMode::Blob, |entry| entry.mode);
+                MergeTreeEntry {
+                    hash: blob.id,
+                    mode,
+                }
             }
-        }
+            ConflictKind::OursModifiedTheirsDeleted { .. } => our_items[&path],
+            ConflictKind::TheirsModifiedOursDeleted { .. } => their_items[&path],
+        };
+        merged_items.insert(path, entry);
     }
-    Ok(None)
+
+    let tree_id = create_tree_from_items_map(&merged_items).map_err(PullMergeError::TreeCreate)?;
+    let commit = Commit::new(
+        ours.author.clone(),
+        ours.committer.clone(),
+        tree_id,
+        vec![ours.id, theirs.id],
+        &format_commit_msg("virtual merge base", None),
+    );
+    save_object(&commit, &commit.id).map_err(|error| {
+        PullMergeError::TreeCreate(format!(
+            "failed to save virtual merge base {}: {error}",
+            commit.id
+        ))
+    })?;
+    Ok(commit)
+}
+
+fn load_history_commit(id: &ObjectHash) -> Result<Commit, PullMergeError> {
+    load_object(id)
+        .map_err(|error| PullMergeError::History(format!("failed to load {id}: {error}")))
 }
 
 async fn apply_fast_forward_merge(
@@ -1384,16 +1965,12 @@ fn commit_tree_items(commit: &Commit) -> Result<HashMap<PathBuf, MergeTreeEntry>
         tree_id: commit.tree_id.to_string(),
         detail: error.to_string(),
     })?;
+    // Gitlinks merge like any other entry (by commit id); they are never
+    // content-merged, so two different submodule commits conflict.
     Ok(tree
         .get_plain_items_with_mode()
         .into_iter()
-        .filter_map(|(path, hash, mode)| {
-            if mode == TreeItemMode::Commit {
-                None
-            } else {
-                Some((path, MergeTreeEntry { hash, mode }))
-            }
-        })
+        .map(|(path, hash, mode)| (path, MergeTreeEntry { hash, mode }))
         .collect())
 }
 
@@ -1664,18 +2241,21 @@ fn add_blob_index_entry(
     item: MergeTreeEntry,
     stage: u8,
 ) -> Result<(), PullMergeError> {
-    let blob: Blob = load_object(&item.hash).map_err(|error| {
-        PullMergeError::IndexSave(format!(
-            "failed to load blob {} for index entry '{}': {error}",
-            item.hash,
-            path.display()
-        ))
-    })?;
-    let mut entry = IndexEntry::new_from_blob(
-        path_to_index_key(path)?.to_string(),
-        item.hash,
-        blob.data.len() as u32,
-    );
+    // A gitlink's commit lives in the submodule's repository: no blob to size.
+    let size = if item.mode == TreeItemMode::Commit {
+        0
+    } else {
+        let blob: Blob = load_object(&item.hash).map_err(|error| {
+            PullMergeError::IndexSave(format!(
+                "failed to load blob {} for index entry '{}': {error}",
+                item.hash,
+                path.display()
+            ))
+        })?;
+        blob.data.len() as u32
+    };
+    let mut entry =
+        IndexEntry::new_from_blob(path_to_index_key(path)?.to_string(), item.hash, size);
     entry.mode = tree_item_mode_to_index_mode(item.mode)?;
     entry.flags.stage = stage;
     index.add(entry);
@@ -1700,13 +2280,16 @@ fn ensure_no_untracked_conflicts(
     Ok(())
 }
 
-fn write_workdir_file(workdir: &Path, relative: &Path, content: &[u8]) -> Result<(), String> {
+/// Write `content` to `relative` as the file type `mode` records (executable
+/// bit and symlinks included).
+fn write_workdir_file(
+    workdir: &Path,
+    relative: &Path,
+    mode: TreeItemMode,
+    content: &[u8],
+) -> Result<(), String> {
     let file_path = workdir.join(relative);
-    if let Some(parent) = file_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|error| format!("failed to create {}: {error}", parent.display()))?;
-    }
-    fs::write(&file_path, content)
+    worktree::write_blob_with_mode(&file_path, mode, content)
         .map_err(|error| format!("failed to write {}: {error}", file_path.display()))
 }
 
@@ -1721,47 +2304,71 @@ fn conflict_payload(content: &[u8]) -> Cow<'_, str> {
     }
 }
 
+/// Write a conflicted path into the working tree. Text conflicts get conflict
+/// markers; a binary path (the `-merge`/`binary` attribute, or a NUL byte on
+/// either side) keeps a side's content verbatim instead — ours when it exists,
+/// else theirs — since markers would corrupt it. The index still records the
+/// conflict stages either way.
 fn write_conflict_markers(
     workdir: &Path,
     path: &Path,
-    marker_eol: &str,
+    mode: TreeItemMode,
     commit_abbrev: &str,
     kind: ConflictKind,
     conflict_style: diffy::ConflictStyle,
+    attributes: &Attributes,
 ) -> Result<(), String> {
+    let attrs = attributes.lookup(path);
+    let marker_eol = conflict_marker_eol();
     let content: Vec<u8> = match kind {
         ConflictKind::BothChanged { base, ours, theirs } => {
             let ours_blob: Blob = load_object(&ours).map_err(|error| error.to_string())?;
             let theirs_blob: Blob = load_object(&theirs).map_err(|error| error.to_string())?;
-            both_changed_conflict_content(
-                base,
-                &ours_blob.data,
-                &theirs_blob.data,
-                marker_eol,
-                commit_abbrev,
-                conflict_style,
-            )?
+            if gitattributes::is_binary_merge(&attrs, &[&ours_blob.data, &theirs_blob.data]) {
+                emit_warning(format!(
+                    "Cannot merge binary files: {} (HEAD vs. {commit_abbrev})",
+                    path.display()
+                ));
+                ours_blob.data
+            } else {
+                both_changed_conflict_content(
+                    base,
+                    &ours_blob.data,
+                    &theirs_blob.data,
+                    marker_eol,
+                    commit_abbrev,
+                    conflict_style,
+                )?
+            }
         }
         ConflictKind::OursModifiedTheirsDeleted { ours } => {
             let ours_blob: Blob = load_object(&ours).map_err(|error| error.to_string())?;
-            format!(
-                "<<<<<<< HEAD{marker_eol}{}{marker_eol}======={marker_eol}>>>>>>> {} (deleted){marker_eol}",
-                conflict_payload(&ours_blob.data),
-                commit_abbrev
-            )
-            .into_bytes()
+            if gitattributes::is_binary_merge(&attrs, &[&ours_blob.data]) {
+                ours_blob.data
+            } else {
+                format!(
+                    "<<<<<<< HEAD{marker_eol}{}{marker_eol}======={marker_eol}>>>>>>> {} (deleted){marker_eol}",
+                    conflict_payload(&ours_blob.data),
+                    commit_abbrev
+                )
+                .into_bytes()
+            }
         }
         ConflictKind::TheirsModifiedOursDeleted { theirs } => {
             let theirs_blob: Blob = load_object(&theirs).map_err(|error| error.to_string())?;
-            format!(
-                "<<<<<<< HEAD (deleted){marker_eol}======={marker_eol}{}{marker_eol}>>>>>>> {}{marker_eol}",
-                conflict_payload(&theirs_blob.data),
-                commit_abbrev
-            )
-            .into_bytes()
+            if gitattributes::is_binary_merge(&attrs, &[&theirs_blob.data]) {
+                theirs_blob.data
+            } else {
+                format!(
+                    "<<<<<<< HEAD (deleted){marker_eol}======={marker_eol}{}{marker_eol}>>>>>>> {}{marker_eol}",
+                    conflict_payload(&theirs_blob.data),
+                    commit_abbrev
+                )
+                .into_bytes()
+            }
         }
     };
-    write_workdir_file(workdir, path, &content)
+    write_workdir_file(workdir, path, mode, &content)
 }
 
 /// Build the worktree content for a both-modified conflict.
@@ -1926,10 +2533,16 @@ fn relabel_conflict_markers(conflicted: Vec<u8>, marker_len: usize, commit_label
     relabelled.into_bytes()
 }
 
+/// The stage-0 index as tree entries, without intent-to-add placeholders.
 fn index_tree_items(index: &Index) -> Result<HashMap<PathBuf, MergeTreeEntry>, PullMergeError> {
+    let intent_paths = index_marks::active(index, IndexMark::IntentToAdd);
     let mut items = HashMap::new();
     for path in index.tracked_files() {
-        if let Some(entry) = index.get(path_to_index_key(&path)?, 0) {
+        let key = path_to_index_key(&path)?;
+        if intent_paths.contains(key) {
+            continue;
+        }
+        if let Some(entry) = index.get(key, 0) {
             items.insert(
                 path,
                 MergeTreeEntry {
@@ -1988,7 +2601,8 @@ fn reset_workdir_tracked_only(
     for path_buf in current_index.tracked_files() {
         if !new_tracked_paths.contains(&path_buf) {
             let full_path = workdir.join(path_buf);
-            if full_path.exists() {
+            // A submodule checkout is left in place, as Git does.
+            if full_path.exists() && !full_path.is_dir() {
                 fs::remove_file(&full_path).map_err(|error| {
                     PullMergeError::WorkdirReset(format!("failed to remove file: {error}"))
                 })?;
@@ -1998,6 +2612,10 @@ fn reset_workdir_tracked_only(
 
     for path_buf in new_index.tracked_files() {
         if let Some(entry) = new_index.get(path_to_index_key(&path_buf)?, 0) {
+            if entry.mode == 0o160000 {
+                // `libra submodule update` checks gitlinks out.
+                continue;
+            }
             let blob: Blob = load_object(&entry.hash).map_err(|error| {
                 PullMergeError::WorkdirReset(format!(
                     "failed to load blob {} for '{}': {error}",
@@ -2005,7 +2623,8 @@ fn reset_workdir_tracked_only(
                     path_buf.display()
                 ))
             })?;
-            write_workdir_file(&workdir, &path_buf, &blob.data)
+            let mode = worktree::tree_mode_from_index_mode(entry.mode);
+            write_workdir_file(&workdir, &path_buf, mode, &blob.data)
                 .map_err(PullMergeError::WorkdirReset)?;
         }
     }
@@ -2061,9 +2680,7 @@ fn tree_item_mode_to_index_mode(mode: TreeItemMode) -> Result<u32, PullMergeErro
         TreeItemMode::Tree => Err(PullMergeError::IndexSave(
             "tree entry cannot be represented as a file index entry".to_string(),
         )),
-        TreeItemMode::Commit => Err(PullMergeError::IndexSave(
-            "gitlink entries are not supported by merge".to_string(),
-        )),
+        TreeItemMode::Commit => Ok(0o160000),
     }
 }
 
@@ -2072,6 +2689,7 @@ fn index_mode_to_tree_item_mode(mode: u32) -> Result<TreeItemMode, PullMergeErro
         0o100644 => Ok(TreeItemMode::Blob),
         0o100755 => Ok(TreeItemMode::BlobExecutable),
         0o120000 => Ok(TreeItemMode::Link),
+        0o160000 => Ok(TreeItemMode::Commit),
         other => Err(PullMergeError::TreeCreate(format!(
             "unsupported index mode {other:o} while creating merge tree"
         ))),
@@ -2258,7 +2876,17 @@ mod tests {
         let no_ff = MergeArgs::try_parse_from(["merge", "--no-ff", "feature"]).unwrap();
         assert!(no_ff.no_ff);
         assert!(!no_ff.ff_only);
-        assert_eq!(no_ff.branch.as_deref(), Some("feature"));
+        assert_eq!(no_ff.branches, ["feature"]);
+
+        let octopus = MergeArgs::try_parse_from(["merge", "a", "b", "c"]).unwrap();
+        assert_eq!(octopus.branches, ["a", "b", "c"]);
+
+        // `--log` only takes a value via `=`, so it never swallows the branch.
+        let log = MergeArgs::try_parse_from(["merge", "--log", "feature"]).unwrap();
+        assert_eq!(log.log, Some(20));
+        assert_eq!(log.branches, ["feature"]);
+        let log = MergeArgs::try_parse_from(["merge", "--log=3", "feature"]).unwrap();
+        assert_eq!(log.log, Some(3));
 
         let ff_only = MergeArgs::try_parse_from(["merge", "--ff-only", "feature"]).unwrap();
         assert!(ff_only.ff_only);
@@ -1,5 +1,5 @@
-//! `libra merge-base` — print the best common ancestor(s) of two commits, a
-//! focused subset of `git merge-base`. Backed by the single LCA implementation
+//! `libra merge-base` — print the best common ancestor(s) of two commits (or
+//! of any number with `--octopus`), a focused subset of `git merge-base`. Backed by the single LCA implementation
 //! in [`crate::internal::merge_base`], which `diff A...B` also uses.
 
 use clap::Parser;
@@ -20,6 +20,7 @@ EXAMPLES:
     libra merge-base main feature          Print the best common ancestor
     libra merge-base --all main feature    Print every lowest common ancestor
     libra merge-base --is-ancestor A B     Exit 0 if A is an ancestor of B, else 1
+    libra merge-base --octopus A B C       Common ancestor of all of A, B, and C
     libra --json merge-base main feature   Structured { bases: [...] }";
 
 /// Find the best common ancestor(s) of two commits.
@@ -35,7 +36,13 @@ pub struct MergeBaseArgs {
     #[clap(long = "is-ancestor")]
     pub is_ancestor: bool,
 
-    /// The two commits (branch, tag, `HEAD`, or object id).
+    /// Compute the common ancestor of all the given commits, as needed for an
+    /// n-way (octopus) merge, instead of exactly two.
+    #[clap(long)]
+    pub octopus: bool,
+
+    /// The commits (branch, tag, `HEAD`, or object id): exactly two, or one
+    /// or more with `--octopus`.
     #[clap(value_name = "COMMIT")]
     pub commits: Vec<String>,
 }
@@ -72,15 +79,28 @@ pub async fn execute_safe(args: MergeBaseArgs, output: &OutputConfig) -> CliResu
             "--is-ancestor and --all cannot be combined".to_string(),
         ));
     }
-    if args.commits.len() != 2 {
+    if args.is_ancestor && args.octopus {
+        return Err(usage(
+            "--is-ancestor and --octopus cannot be combined".to_string(),
+        ));
+    }
+    if args.octopus {
+        if args.commits.is_empty() {
+            return Err(usage(
+                "merge-base --octopus requires at least one commit".to_string(),
+            ));
+        }
+    } else if args.commits.len() != 2 {
         return Err(usage(format!(
             "merge-base requires exactly two commits, got {}",
             args.commits.len()
         )));
     }
 
-    let a = resolve_commit(&args.commits[0]).await?;
-    let b = resolve_commit(&args.commits[1]).await?;
+    let mut commits = Vec::with_capacity(args.commits.len());
+    for name in &args.commits {
+        commits.push(resolve_commit(name).await?);
+    }
 
     let internal_err = |error: merge_base::MergeBaseError| {
         CliError::fatal(error.to_string())
@@ -88,8 +108,11 @@ pub async fn execute_safe(args: MergeBaseArgs, output: &OutputConfig) -> CliResu
             .with_stable_code(StableErrorCode::RepoStateInvalid)
     };
 
-    if args.is_ancestor {
-        let yes = merge_base::is_ancestor(&a, &b).map_err(internal_err)?;
+    let bases = if args.octopus {
+        merge_base::octopus_merge_bases(&commits).map_err(internal_err)?
+    } else if args.is_ancestor {
+        let (a, b) = (&commits[0], &commits[1]);
+        let yes = merge_base::is_ancestor(a, b).map_err(internal_err)?;
         if output.is_json() {
             emit_json_data(
                 "merge-base",
@@ -105,9 +128,9 @@ pub async fn execute_safe(args: MergeBaseArgs, output: &OutputConfig) -> CliResu
         } else {
             Err(CliError::silent_exit(1))
         };
-    }
-
-    let bases = merge_base::merge_bases(&a, &b).map_err(internal_err)?;
+    } else {
+        merge_base::merge_bases(&commits[0], &commits[1]).map_err(internal_err)?
+    };
     let printed: Vec<String> = if args.all {
         bases.iter().map(|id| id.to_string()).collect()
     } else {
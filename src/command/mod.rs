@@ -9,12 +9,14 @@
 //! rendering consequences.
 
 pub mod add;
+pub mod add_patch;
 pub mod agent;
 pub mod apply;
 pub mod archive;
 pub mod automation;
 pub mod bisect;
 pub mod blame;
+mod blame_copy;
 pub mod branch;
 pub mod bundle;
 pub mod cache;
@@ -31,14 +33,17 @@ pub mod code;
 pub mod code_control;
 pub mod code_control_files;
 pub mod commit;
+pub mod commit_tree;
 pub mod completions;
 pub mod config;
 pub mod credential;
+pub mod credential_helper;
 pub mod describe;
 pub mod diff;
 pub mod diff_plumbing;
 pub mod dirty;
 pub mod editor;
+pub mod export_snapshot;
 pub mod fast_export;
 pub mod fast_import;
 pub mod fetch;
@@ -51,10 +56,13 @@ pub mod hash_object;
 pub mod hooks;
 pub mod index_pack;
 mod index_pack_support;
+mod index_pack_thin;
 mod index_pack_v1;
 mod index_pack_v2;
 pub mod init;
 pub mod lfs;
+pub mod lfs_fetch;
+pub mod lfs_migrate;
 pub mod lfs_schema;
 pub mod log;
 pub mod logfile;
@@ -92,24 +100,29 @@ pub mod sandbox;
 pub mod service;
 pub mod shortlog;
 pub mod show;
+pub mod show_branch;
 pub mod show_ref;
 mod show_ref_check;
 mod show_ref_deref;
 mod show_ref_exclude_existing;
 mod show_ref_render;
+pub mod sparse_checkout;
+pub mod submodule;
 pub mod symbolic_ref;
 pub mod tag;
 pub mod update_index;
 pub mod update_ref;
 pub mod usage;
+pub mod verify_commit;
 pub mod verify_pack;
 mod verify_pack_decode;
-mod verify_pack_index;
+pub(crate) mod verify_pack_index;
 mod verify_pack_index_common;
 mod verify_pack_index_v2;
 mod verify_pack_render;
 mod verify_pack_support;
 mod verify_pack_types;
+pub mod verify_tag;
 #[cfg(all(unix, feature = "worktree-fuse"))]
 #[path = "worktree-fuse.rs"]
 pub mod worktree;
@@ -122,7 +135,7 @@ pub mod switch;
 pub mod web_assets;
 pub mod write_tree;
 
-use std::{io, io::Write, path::Path};
+use std::{collections::HashMap, io, io::Write, path::Path};
 
 use git_internal::{
     errors::GitError,
@@ -235,6 +248,60 @@ pub fn calc_file_blob_hash(path: impl AsRef<Path>) -> io::Result<ObjectHash> {
     Ok(blob.id)
 }
 
+/// The conversion `add` applies to a worktree file before storing it as a
+/// blob, decided from the attributes on its workdir-relative path.
+pub(crate) enum BlobFilter {
+    /// `.libra_attributes` LFS tracking or `filter=lfs`: store the LFS pointer.
+    Lfs,
+    /// `text`/`eol` attributes: normalize line endings with these attributes.
+    Normalize(HashMap<String, utils::gitattributes::AttrValue>),
+    /// Store the bytes verbatim.
+    None,
+}
+
+impl BlobFilter {
+    /// The filter for the file at `path`, whose workdir-relative path is
+    /// `relative`.
+    pub(crate) fn for_path(
+        path: impl AsRef<Path>,
+        relative: &Path,
+        attributes: &utils::gitattributes::Attributes,
+    ) -> Self {
+        use utils::gitattributes;
+
+        let attrs = attributes.lookup(relative);
+        if utils::lfs::is_lfs_tracked(&path) || gitattributes::is_lfs_filter(&attrs) {
+            BlobFilter::Lfs
+        } else if attrs.contains_key("text") || attrs.contains_key("eol") {
+            BlobFilter::Normalize(attrs)
+        } else {
+            BlobFilter::None
+        }
+    }
+}
+
+/// [`calc_file_blob_hash`] after the conversions `add` applies from the
+/// attributes on `relative` (see [`BlobFilter`]): `filter=lfs` hashes the
+/// pointer, and `text`/`eol` normalize line endings, so a file checked out
+/// with `eol=crlf` still hashes to its LF blob.
+pub fn calc_file_blob_hash_with_attributes(
+    path: impl AsRef<Path>,
+    relative: &Path,
+    attributes: &utils::gitattributes::Attributes,
+) -> io::Result<ObjectHash> {
+    match BlobFilter::for_path(&path, relative, attributes) {
+        BlobFilter::Lfs => {
+            let (pointer, _) = utils::lfs::generate_pointer_file(&path);
+            Ok(Blob::from_content(&pointer).id)
+        }
+        BlobFilter::Normalize(attrs) => {
+            let data = std::fs::read(path.as_ref())?;
+            Ok(Blob::from_content_bytes(utils::gitattributes::convert_to_index(&attrs, data)).id)
+        }
+        BlobFilter::None => Ok(Blob::from_file(&path).id),
+    }
+}
+
 /// Get the commit hash from branch name or commit hash, support remote branch
 pub async fn get_target_commit(
     branch_or_commit: &str,
@@ -60,7 +60,9 @@ pub struct MvArgs {
     #[clap(short = 'k', long = "skip-errors")]
     pub skip_errors: bool,
 
-    /// Accept Git's sparse-checkout flag. Libra has no sparse-checkout state, so this is a no-op.
+    /// Accept Git's sparse-checkout flag as a no-op. Entries outside the sparse
+    /// checkout are absent from the working tree, and `mv` only moves files that
+    /// exist on disk, so they are rejected as a bad source either way.
     #[clap(long)]
     pub sparse: bool,
 }
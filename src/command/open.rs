@@ -1,12 +1,16 @@
 //! `libra open` command implementation for opening repository remotes in a browser.
 //!
+//! Besides the repository home page, the `commit`, `pr`, and `file` subcommands
+//! build forge-specific page URLs. The forge (GitHub, GitLab, Gitea, Bitbucket)
+//! is detected from the remote hostname; unknown hosts use GitHub's URL layout.
+//!
 //! Boundary: this command parses common Git remote URL forms and delegates launching to
-//! the host OS; it does not validate network reachability. Command tests cover HTTPS,
-//! SSH/SCP-like URLs, missing remotes, and malformed input.
+//! the host OS (or `LIBRA_BROWSER`); it does not validate network reachability. Command
+//! tests cover HTTPS, SSH/SCP-like URLs, missing remotes, and malformed input.
 
-use std::process::Command;
+use std::{path::PathBuf, process::Command};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::Serialize;
@@ -17,27 +21,113 @@ use crate::{
         error::{CliError, CliResult, StableErrorCode},
         output::{OutputConfig, emit_json_data},
         pager::LIBRA_TEST_ENV,
-        util::require_repo,
+        util::{self, require_repo},
     },
 };
 
+/// Browser command used instead of the OS launcher, e.g. `firefox` or
+/// `firefox --new-window`. The URL is appended as the last argument.
+pub const LIBRA_BROWSER_ENV: &str = "LIBRA_BROWSER";
+
 const OPEN_EXAMPLES: &str = "\
 EXAMPLES:
     libra open                                            Open the auto-detected upstream in the browser
     libra open origin                                     Open a specific remote
     libra open https://github.com/web3infra-foundation/libra    Open a direct URL
+    libra open commit HEAD~1                              Open the page of a commit
+    libra open pr                                         Open or create a pull request for the current branch
+    libra open file src/main.rs --line 42                 Open a file at a line on the current branch
     libra open --json                                     Structured JSON output for agents (no browser)";
 
 #[derive(Parser, Debug)]
-#[command(after_help = OPEN_EXAMPLES)]
+#[command(after_help = OPEN_EXAMPLES, args_conflicts_with_subcommands = true)]
 pub struct OpenArgs {
+    #[command(subcommand)]
+    pub target: Option<OpenTarget>,
+
     /// Remote name (e.g. `origin`) or a direct URL. Omit to auto-detect from the current branch's upstream
     #[arg(value_name = "REMOTE_OR_URL")]
     pub remote: Option<String>,
 }
 
+/// Page to open on the forge.
+#[derive(Subcommand, Debug, Clone)]
+pub enum OpenTarget {
+    /// Open the repository home page (same as `libra open [REMOTE_OR_URL]`)
+    Repo {
+        /// Remote name or a direct URL. Omit to auto-detect
+        #[arg(value_name = "REMOTE_OR_URL")]
+        remote: Option<String>,
+    },
+    /// Open the page of a commit
+    Commit {
+        /// Any revision that resolves to a commit
+        #[arg(value_name = "COMMIT")]
+        commit: String,
+        /// Remote name or a direct URL. Omit to auto-detect
+        #[arg(long, value_name = "REMOTE_OR_URL")]
+        remote: Option<String>,
+    },
+    /// Open a pull request (merge request on GitLab) for the current branch;
+    /// the forge shows the existing one or offers to create it
+    Pr {
+        /// Remote name or a direct URL. Omit to auto-detect
+        #[arg(long, value_name = "REMOTE_OR_URL")]
+        remote: Option<String>,
+    },
+    /// Open a file on the current branch (the HEAD commit when detached)
+    File {
+        /// File path, relative to the current directory
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+        /// Highlight this line
+        #[arg(long, value_name = "N")]
+        line: Option<u32>,
+        /// Remote name or a direct URL. Omit to auto-detect
+        #[arg(long, value_name = "REMOTE_OR_URL")]
+        remote: Option<String>,
+    },
+}
+
+/// Forge family, detected from the remote hostname. Each one lays out commit,
+/// file, and pull-request pages differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Forge {
+    GitHub,
+    GitLab,
+    Gitea,
+    Bitbucket,
+    /// Self-hosted or unrecognised; GitHub's URL layout is used.
+    Unknown,
+}
+
+/// Resolved page within the repository.
+#[derive(Debug, Clone)]
+enum OpenPage {
+    Repo,
+    Commit(String),
+    PullRequest {
+        branch: String,
+    },
+    File {
+        reference: FileRef,
+        path: String,
+        line: Option<u32>,
+    },
+}
+
+/// Revision a file page is shown at.
+#[derive(Debug, Clone)]
+enum FileRef {
+    Branch(String),
+    Commit(String),
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct OpenOutput {
+    target: &'static str,
+    forge: Forge,
     remote: Option<String>,
     remote_url: String,
     web_url: String,
@@ -64,6 +154,12 @@ enum OpenError {
     UnsafeUrl(String),
     #[error("failed to open browser: {0}")]
     BrowserLaunch(String),
+    #[error("{0}: not a valid commit name")]
+    InvalidCommit(String),
+    #[error("HEAD is detached; a pull request needs a branch")]
+    DetachedHead,
+    #[error("path '{0}' is outside the repository")]
+    PathOutsideRepo(String),
 }
 
 lazy_static! {
@@ -89,14 +185,19 @@ pub async fn execute(args: OpenArgs) {
 /// browser.
 pub async fn execute_safe(args: OpenArgs, output: &OutputConfig) -> CliResult<()> {
     let in_repo = require_repo().is_ok();
-    let resolution = resolve_open_target(args, in_repo)
+    let (remote, page) = resolve_open_page(args, in_repo)
+        .await
+        .map_err(open_cli_error)?;
+    let resolution = resolve_open_target(remote, in_repo)
         .await
         .map_err(open_cli_error)?;
-    let web_url = transform_url(&resolution.remote_url);
+    let repo_url = transform_url(&resolution.remote_url);
 
-    if !is_safe_url(&web_url) {
-        return Err(open_cli_error(OpenError::UnsafeUrl(web_url)));
+    if !is_safe_url(&repo_url) {
+        return Err(open_cli_error(OpenError::UnsafeUrl(repo_url)));
     }
+    let forge = detect_forge(&repo_url);
+    let web_url = forge.page_url(&repo_url, &page);
 
     let launched = if output.is_json() {
         false
@@ -106,6 +207,8 @@ pub async fn execute_safe(args: OpenArgs, output: &OutputConfig) -> CliResult<()
     };
 
     let open_output = OpenOutput {
+        target: page.kind(),
+        forge,
         remote: resolution.remote,
         remote_url: resolution.remote_url,
         web_url: web_url.clone(),
@@ -121,8 +224,63 @@ pub async fn execute_safe(args: OpenArgs, output: &OutputConfig) -> CliResult<()
     Ok(())
 }
 
-async fn resolve_open_target(args: OpenArgs, in_repo: bool) -> Result<OpenResolution, OpenError> {
-    if let Some(input) = args.remote {
+/// Split the arguments into the remote to open and the page on it, resolving
+/// revisions and paths against the repository.
+async fn resolve_open_page(
+    args: OpenArgs,
+    in_repo: bool,
+) -> Result<(Option<String>, OpenPage), OpenError> {
+    match args.target {
+        None => Ok((args.remote, OpenPage::Repo)),
+        Some(OpenTarget::Repo { remote }) => Ok((remote, OpenPage::Repo)),
+        Some(_) if !in_repo => Err(OpenError::NotInRepo),
+        Some(OpenTarget::Commit { commit, remote }) => {
+            let id = util::get_commit_base(&commit)
+                .await
+                .map_err(|_| OpenError::InvalidCommit(commit))?;
+            Ok((remote, OpenPage::Commit(id.to_string())))
+        }
+        Some(OpenTarget::Pr { remote }) => match current_head().await? {
+            Head::Branch(branch) => Ok((remote, OpenPage::PullRequest { branch })),
+            Head::Detached(_) => Err(OpenError::DetachedHead),
+        },
+        Some(OpenTarget::File { path, line, remote }) => {
+            let relative = util::to_workdir_path(&path);
+            if relative.starts_with("..") {
+                return Err(OpenError::PathOutsideRepo(path.display().to_string()));
+            }
+            let path = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let reference = match current_head().await? {
+                Head::Branch(branch) => FileRef::Branch(branch),
+                Head::Detached(id) => FileRef::Commit(id.to_string()),
+            };
+            Ok((
+                remote,
+                OpenPage::File {
+                    reference,
+                    path,
+                    line,
+                },
+            ))
+        }
+    }
+}
+
+async fn current_head() -> Result<Head, OpenError> {
+    Head::current_result()
+        .await
+        .map_err(|error| OpenError::ConfigRead(error.to_string()))
+}
+
+async fn resolve_open_target(
+    remote: Option<String>,
+    in_repo: bool,
+) -> Result<OpenResolution, OpenError> {
+    if let Some(input) = remote {
         if in_repo {
             let remotes = ConfigKv::all_remote_configs()
                 .await
@@ -211,6 +369,13 @@ async fn load_remote_url(remote: &str) -> Result<String, OpenError> {
 }
 
 fn open_browser(url: &str) -> std::io::Result<bool> {
+    if let Ok(browser) = std::env::var(LIBRA_BROWSER_ENV) {
+        let mut parts = browser.split_whitespace();
+        if let Some(program) = parts.next() {
+            Command::new(program).args(parts).arg(url).spawn()?;
+            return Ok(true);
+        }
+    }
     if std::env::var_os(LIBRA_TEST_ENV).is_some() {
         // Keep integration tests side-effect free across all platforms.
         return Ok(false);
@@ -278,6 +443,86 @@ fn transform_url(remote: &str) -> String {
     remote.to_string()
 }
 
+fn detect_forge(web_url: &str) -> Forge {
+    let host = url::Url::parse(web_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+    if host.contains("github") {
+        Forge::GitHub
+    } else if host.contains("gitlab") {
+        Forge::GitLab
+    } else if host.contains("bitbucket") {
+        Forge::Bitbucket
+    } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+        Forge::Gitea
+    } else {
+        Forge::Unknown
+    }
+}
+
+impl Forge {
+    /// Build the URL of `page` under the repository home page `repo_url`.
+    fn page_url(self, repo_url: &str, page: &OpenPage) -> String {
+        let base = repo_url.trim_end_matches('/');
+        let suffix = match page {
+            OpenPage::Repo => return base.to_string(),
+            OpenPage::Commit(id) => match self {
+                Forge::GitLab => format!("-/commit/{id}"),
+                Forge::Bitbucket => format!("commits/{id}"),
+                Forge::GitHub | Forge::Gitea | Forge::Unknown => format!("commit/{id}"),
+            },
+            OpenPage::PullRequest { branch } => match self {
+                Forge::GitLab => format!(
+                    "-/merge_requests/new?merge_request%5Bsource_branch%5D={}",
+                    query_escape(branch)
+                ),
+                Forge::Bitbucket => format!("pull-requests/new?source={}", query_escape(branch)),
+                // Gitea compares against the default branch when no base is given.
+                Forge::Gitea => format!("compare/{branch}"),
+                Forge::GitHub | Forge::Unknown => format!("pull/new/{branch}"),
+            },
+            OpenPage::File {
+                reference,
+                path,
+                line,
+            } => {
+                let name = match reference {
+                    FileRef::Branch(name) | FileRef::Commit(name) => name,
+                };
+                let page = match (self, reference) {
+                    (Forge::GitLab, _) => format!("-/blob/{name}/{path}"),
+                    (Forge::Bitbucket, _) => format!("src/{name}/{path}"),
+                    (Forge::Gitea, FileRef::Branch(_)) => format!("src/branch/{name}/{path}"),
+                    (Forge::Gitea, FileRef::Commit(_)) => format!("src/commit/{name}/{path}"),
+                    (Forge::GitHub | Forge::Unknown, _) => format!("blob/{name}/{path}"),
+                };
+                match (self, line) {
+                    (_, None) => page,
+                    (Forge::Bitbucket, Some(line)) => format!("{page}#lines-{line}"),
+                    (_, Some(line)) => format!("{page}#L{line}"),
+                }
+            }
+        };
+        format!("{base}/{suffix}")
+    }
+}
+
+impl OpenPage {
+    fn kind(&self) -> &'static str {
+        match self {
+            OpenPage::Repo => "repo",
+            OpenPage::Commit(_) => "commit",
+            OpenPage::PullRequest { .. } => "pr",
+            OpenPage::File { .. } => "file",
+        }
+    }
+}
+
+fn query_escape(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
 fn open_cli_error(error: OpenError) -> CliError {
     match error {
         OpenError::NotInRepo => CliError::repo_not_found(),
@@ -303,6 +548,22 @@ fn open_cli_error(error: OpenError) -> CliError {
         OpenError::BrowserLaunch(message) => {
             CliError::fatal(format!("failed to open browser: {message}"))
                 .with_stable_code(StableErrorCode::IoWriteFailed)
+                .with_hint(format!(
+                    "check that a default browser is configured, or set {LIBRA_BROWSER_ENV}."
+                ))
+        }
+        OpenError::InvalidCommit(name) => {
+            CliError::fatal(format!("{name}: not a valid commit name"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget)
+        }
+        OpenError::DetachedHead => {
+            CliError::fatal("HEAD is detached; a pull request needs a branch")
+                .with_stable_code(StableErrorCode::RepoStateInvalid)
+                .with_hint("switch to the branch you want to propose, then retry.")
+        }
+        OpenError::PathOutsideRepo(path) => {
+            CliError::fatal(format!("path '{path}' is outside the repository"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget)
         }
     }
 }
@@ -336,6 +597,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_forge_from_hostname() {
+        assert_eq!(detect_forge("https://github.com/o/r"), Forge::GitHub);
+        assert_eq!(
+            detect_forge("https://gitlab.example.com/g/p"),
+            Forge::GitLab
+        );
+        assert_eq!(detect_forge("https://codeberg.org/o/r"), Forge::Gitea);
+        assert_eq!(detect_forge("https://gitea.corp.local/o/r"), Forge::Gitea);
+        assert_eq!(detect_forge("https://bitbucket.org/o/r"), Forge::Bitbucket);
+        assert_eq!(detect_forge("https://git.corp.local/o/r"), Forge::Unknown);
+    }
+
+    #[test]
+    fn test_page_url_per_forge() {
+        let commit = OpenPage::Commit("abc123".to_string());
+        let pr = OpenPage::PullRequest {
+            branch: "feature/x".to_string(),
+        };
+        let file = OpenPage::File {
+            reference: FileRef::Branch("main".to_string()),
+            path: "src/lib.rs".to_string(),
+            line: Some(7),
+        };
+        let cases = [
+            (
+                Forge::GitHub,
+                "https://github.com/o/r/commit/abc123",
+                "https://github.com/o/r/pull/new/feature/x",
+                "https://github.com/o/r/blob/main/src/lib.rs#L7",
+            ),
+            (
+                Forge::GitLab,
+                "https://github.com/o/r/-/commit/abc123",
+                "https://github.com/o/r/-/merge_requests/new?merge_request%5Bsource_branch%5D=feature%2Fx",
+                "https://github.com/o/r/-/blob/main/src/lib.rs#L7",
+            ),
+            (
+                Forge::Gitea,
+                "https://github.com/o/r/commit/abc123",
+                "https://github.com/o/r/compare/feature/x",
+                "https://github.com/o/r/src/branch/main/src/lib.rs#L7",
+            ),
+            (
+                Forge::Bitbucket,
+                "https://github.com/o/r/commits/abc123",
+                "https://github.com/o/r/pull-requests/new?source=feature%2Fx",
+                "https://github.com/o/r/src/main/src/lib.rs#lines-7",
+            ),
+        ];
+        for (forge, commit_url, pr_url, file_url) in cases {
+            let base = "https://github.com/o/r";
+            assert_eq!(forge.page_url(base, &OpenPage::Repo), base);
+            assert_eq!(forge.page_url(base, &commit), commit_url, "{forge:?}");
+            assert_eq!(forge.page_url(base, &pr), pr_url, "{forge:?}");
+            assert_eq!(forge.page_url(base, &file), file_url, "{forge:?}");
+        }
+
+        let detached = OpenPage::File {
+            reference: FileRef::Commit("abc123".to_string()),
+            path: "README.md".to_string(),
+            line: None,
+        };
+        assert_eq!(
+            Forge::Gitea.page_url("https://codeberg.org/o/r", &detached),
+            "https://codeberg.org/o/r/src/commit/abc123/README.md"
+        );
+    }
+
     #[test]
     fn test_is_safe_url() {
         assert!(is_safe_url("https://github.com/rust-lang/rust"));
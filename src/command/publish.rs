@@ -71,6 +71,7 @@ use crate::{
         tag::{self, TagObject},
     },
     utils::{
+        client_storage::ClientStorage,
         d1_client::{
             D1Client, D1Error, PublishAiObjectRow, PublishAiVersionRow, PublishFileRow,
             PublishRefRow, PublishRevisionRow, PublishSiteLatestUpdate,
@@ -79,7 +80,7 @@ use crate::{
         error::{CliError, CliResult, StableErrorCode},
         object_ext::TreeExt,
         output::{self, CommandOutput, OutputConfig},
-        storage::{local::LocalStorage, publish_storage::PublishStorage},
+        storage::publish_storage::PublishStorage,
         util,
     },
 };
@@ -1322,13 +1323,13 @@ trait PublishAiExportPlanner {
 
 struct HistoryBackedPublishAiExportPlanner {
     history: HistoryManager,
-    storage: Arc<LocalStorage>,
+    storage: Arc<ClientStorage>,
 }
 
 impl HistoryBackedPublishAiExportPlanner {
     async fn new() -> CliResult<Self> {
         let repo_path = util::try_get_storage_path(None).map_err(|_| CliError::repo_not_found())?;
-        let storage = Arc::new(LocalStorage::new(repo_path.join("objects")));
+        let storage = Arc::new(ClientStorage::init(repo_path.join("objects")));
         let db_path = repo_path.join(util::DATABASE);
         let db_conn = db::get_db_conn_instance_for_path(&db_path)
             .await
@@ -3140,7 +3141,7 @@ mod tests {
     use crate::{
         command::save_object,
         internal::publish::contract::{AiObjectLayer, AiObjectRedaction, PublishAiObject},
-        utils::{storage_ext::StorageExt, test},
+        utils::{storage::local::LocalStorage, storage_ext::StorageExt, test},
     };
 
     fn default_init_args() -> InitArgs {
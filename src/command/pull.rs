@@ -10,11 +10,13 @@ use super::{fetch, merge, rebase, stash};
 use crate::{
     internal::{
         config::{ConfigKv, RemoteConfig},
+        db::get_db_conn_instance,
         head::Head,
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         output::{OutputConfig, ProgressMode, emit_json_data},
+        progress,
     },
 };
 
@@ -37,7 +39,8 @@ NOTES:
     divergent histories instead of creating a merge commit, or --no-ff to force
     a merge commit even when a fast-forward is possible. Use --rebase to replay
     local-only commits onto the upstream tip instead, and --depth to limit the
-    fetch to a shallow history before integrating.";
+    fetch to a shallow history before integrating. Without these flags the
+    pull.rebase and pull.ff (true/false/only) config keys pick the mode.";
 
 /// Fetch from a remote and integrate changes into the current branch.
 // EXAMPLES are wired via `#[command(after_help = PULL_EXAMPLES)]` and render
@@ -187,6 +190,9 @@ pub(crate) enum PullError {
 
     #[error("pull --autostash failed: {0}")]
     Autostash(String),
+
+    #[error("invalid value '{value}' for '{key}'")]
+    InvalidConfig { key: &'static str, value: String },
 }
 
 impl From<PullError> for CliError {
@@ -220,6 +226,18 @@ impl From<PullError> for CliError {
                         "resolve the working tree, then recover the stash with 'libra stash pop'",
                     )
             }
+            PullError::InvalidConfig { key, value } => {
+                let expected = if key == PULL_FF_CONFIG {
+                    "true, false, or only"
+                } else {
+                    "true or false"
+                };
+                CliError::command_usage(format!("invalid value '{value}' for '{key}'"))
+                    .with_stable_code(StableErrorCode::CliInvalidArguments)
+                    .with_hint(format!(
+                        "set it to {expected}: 'libra config {key} <value>'"
+                    ))
+            }
         }
     }
 }
@@ -276,10 +294,71 @@ pub async fn execute_safe(args: PullArgs, output: &OutputConfig) -> CliResult<()
     render_pull_output(&result, output)
 }
 
+/// Config key choosing rebase instead of merge when neither `--rebase` nor
+/// `--no-rebase` is given.
+const PULL_REBASE_CONFIG: &str = "pull.rebase";
+/// Config key holding the fast-forward policy (`true`/`false`/`only`) when no
+/// `--ff`/`--no-ff`/`--ff-only` flag is given.
+const PULL_FF_CONFIG: &str = "pull.ff";
+
+/// Fold `pull.rebase` and `pull.ff` into `args`, with command-line flags
+/// taking precedence as in Git:
+/// - `pull.rebase` applies only when no rebase flag and no merge-only flag
+///   (`--ff`, `--no-ff`, `--ff-only`, `--squash`, `--[no-]commit`, `--depth`)
+///   was given, since those imply a merge.
+/// - `pull.ff` applies only when merging and no fast-forward flag was given;
+///   `only` behaves like `--ff-only`, `false` like `--no-ff`.
+async fn apply_pull_config(mut args: PullArgs) -> Result<PullArgs, PullError> {
+    let db = get_db_conn_instance().await;
+    let merge_only_flag = args.ff
+        || args.no_ff
+        || args.ff_only
+        || args.squash
+        || args.no_commit
+        || args.commit
+        || args.depth.is_some();
+
+    if !args.rebase && !args.no_rebase && !merge_only_flag {
+        let configured = ConfigKv::get_with_conn(&db, PULL_REBASE_CONFIG)
+            .await
+            .ok()
+            .flatten();
+        if let Some(entry) = configured {
+            args.rebase =
+                ConfigKv::parse_bool(entry.value.trim()).ok_or(PullError::InvalidConfig {
+                    key: PULL_REBASE_CONFIG,
+                    value: entry.value,
+                })?;
+        }
+    }
+
+    if !args.rebase && !args.ff && !args.no_ff && !args.ff_only {
+        let configured = ConfigKv::get_with_conn(&db, PULL_FF_CONFIG)
+            .await
+            .ok()
+            .flatten();
+        if let Some(entry) = configured {
+            if entry.value.trim().eq_ignore_ascii_case("only") {
+                args.ff_only = true;
+            } else {
+                let ff =
+                    ConfigKv::parse_bool(entry.value.trim()).ok_or(PullError::InvalidConfig {
+                        key: PULL_FF_CONFIG,
+                        value: entry.value,
+                    })?;
+                args.no_ff = !ff;
+            }
+        }
+    }
+
+    Ok(args)
+}
+
 pub(crate) async fn run_pull(
     args: PullArgs,
     output: &OutputConfig,
 ) -> Result<PullOutput, PullError> {
+    let args = apply_pull_config(args).await?;
     let target = resolve_pull_target(&args).await?;
     // `--no-progress` forwards to the fetch: suppress its "Receiving objects"
     // meter just like `git pull --no-progress`.
@@ -290,8 +369,10 @@ pub(crate) async fn run_pull(
     let fetch_result = fetch::fetch_repository_with_result(
         target.remote_config.clone(),
         Some(target.remote_branch.clone()),
+        Vec::new(),
         false,
         args.depth,
+        1,
         false,
         // `git pull` auto-follows tags (and honours remote.<name>.tagOpt).
         None,
@@ -299,6 +380,7 @@ pub(crate) async fn run_pull(
         // `pull` does not prune; use `fetch --prune` or `remote prune`.
         false,
         &child_output,
+        progress::for_output(&child_output),
     )
     .await
     .map_err(PullError::Fetch)?;
@@ -365,6 +447,8 @@ pub(crate) async fn run_pull(
                 ff_only: args.ff_only,
                 no_ff: args.no_ff,
                 message: None,
+                // `pull` does not expose `--log`.
+                log: None,
                 squash: args.squash,
                 no_commit: args.no_commit,
                 // `pull` does not expose `--verify-signatures`.
@@ -375,23 +459,32 @@ pub(crate) async fn run_pull(
         )
         .await
         {
-            Ok(merge_result) => Ok(PullOutput {
-                branch: target.branch,
-                upstream: target.upstream,
-                fetch: fetch_summary,
-                merge: Some(PullMergeResult {
-                    strategy: merge_result.strategy,
-                    old_commit: merge_result.old_commit,
-                    commit: merge_result.commit,
-                    files_changed: merge_result.files_changed,
-                    up_to_date: merge_result.up_to_date,
-                    parents: merge_result.parents,
-                    conflicted_paths: merge_result.conflicted_paths,
-                    aborted: merge_result.aborted,
-                    continued: merge_result.continued,
-                }),
-                rebase: None,
-            }),
+            Ok(merge_result) => {
+                merge::run_post_merge_hook(
+                    &merge_result,
+                    args.squash,
+                    args.no_commit,
+                    &child_output,
+                )
+                .await;
+                Ok(PullOutput {
+                    branch: target.branch,
+                    upstream: target.upstream,
+                    fetch: fetch_summary,
+                    merge: Some(PullMergeResult {
+                        strategy: merge_result.strategy,
+                        old_commit: merge_result.old_commit,
+                        commit: merge_result.commit,
+                        files_changed: merge_result.files_changed,
+                        up_to_date: merge_result.up_to_date,
+                        parents: merge_result.parents,
+                        conflicted_paths: merge_result.conflicted_paths,
+                        aborted: merge_result.aborted,
+                        continued: merge_result.continued,
+                    }),
+                    rebase: None,
+                })
+            }
             Err(error) => Err(PullError::Merge(error)),
         }
     };
@@ -655,7 +748,8 @@ fn map_fetch_error_to_cli(error: &fetch::FetchError) -> CliError {
         fetch::FetchError::InvalidPktHeader { .. }
         | fetch::FetchError::RemoteSideband { .. }
         | fetch::FetchError::ChecksumMismatch
-        | fetch::FetchError::IndexPack { .. } => {
+        | fetch::FetchError::IndexPack { .. }
+        | fetch::FetchError::MergePacks { .. } => {
             CliError::fatal(error.to_string()).with_stable_code(StableErrorCode::NetworkProtocol)
         }
         fetch::FetchError::ObjectsDirNotFound { .. } => {
@@ -669,6 +763,11 @@ fn map_fetch_error_to_cli(error: &fetch::FetchError) -> CliError {
         fetch::FetchError::LocalState { .. } => {
             CliError::fatal(error.to_string()).with_stable_code(StableErrorCode::RepoCorrupt)
         }
+        fetch::FetchError::InvalidRefspec { .. } => CliError::command_usage(error.to_string())
+            .with_stable_code(StableErrorCode::CliInvalidArguments)
+            .with_hint("check the remote's configured fetch refspecs"),
+        fetch::FetchError::NonFastForward { .. } => CliError::fatal(error.to_string())
+            .with_stable_code(StableErrorCode::ConflictOperationBlocked),
     }
 }
 
@@ -48,8 +48,10 @@ use crate::{
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode, emit_warning},
+        git_hooks,
         object_ext::{BlobExt, CommitExt, TreeExt},
         output::{OutputConfig, ProgressMode, ProgressReporter, emit_json_data},
+        progress,
         text::levenshtein,
     },
 };
@@ -167,9 +169,7 @@ pub struct PushArgs {
     #[clap(long, requires("repository"))]
     pub mirror: bool,
 
-    /// Bypass the `pre-push` hook. Accepted for Git parity and is a no-op:
-    /// Libra's push does not run a client-side `pre-push` hook, so there is
-    /// nothing to bypass.
+    /// Bypass the `pre-push` hook.
     #[clap(long = "no-verify")]
     pub no_verify: bool,
 
@@ -234,7 +234,9 @@ pub enum PushError {
     #[error("source ref '{0}' not found")]
     SourceRefNotFound(String),
 
-    #[error("pushing to local file repositories is not supported")]
+    #[error(
+        "pushing to local file repositories is not supported unless the target is a Libra repository"
+    )]
     UnsupportedLocalFileRemote,
 
     #[error("invalid remote URL '{url}': {detail}")]
@@ -300,6 +302,9 @@ pub enum PushError {
 
     #[error("failed to create push certificate signature: {0}")]
     PushSignFailed(String),
+
+    #[error("pre-push hook failed: {0}")]
+    PrePushHook(String),
 }
 
 impl From<PushError> for CliError {
@@ -333,7 +338,7 @@ impl From<PushError> for CliError {
             PushError::UnsupportedLocalFileRemote => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::CliInvalidTarget)
                 .with_hint(
-                    "use fetch/clone for local-path repositories; push currently supports network remotes only",
+                    "create the target with 'libra init --bare <path>'; use fetch/clone for local Git repositories",
                 ),
             PushError::InvalidRemoteUrl { .. } => CliError::command_usage(error.to_string())
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
@@ -363,6 +368,14 @@ impl From<PushError> for CliError {
             PushError::RemoteUnpackFailed => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::NetworkProtocol)
                 .with_hint("the remote server failed to process the pack; retry or check server logs"),
+            PushError::RemoteRefUpdateFailed { reason, .. }
+                if is_non_fast_forward_reason(reason) =>
+            {
+                CliError::fatal(error.to_string())
+                    .with_stable_code(StableErrorCode::ConflictOperationBlocked)
+                    .with_hint("pull and integrate remote changes first: 'libra pull'")
+                    .with_hint("or use --force to overwrite (data loss risk)")
+            }
             PushError::RemoteRefUpdateFailed { .. } => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::NetworkProtocol)
                 .with_hint("the remote rejected the update; check branch protection rules"),
@@ -401,6 +414,9 @@ impl From<PushError> for CliError {
                 .with_hint("configure a signing key (see 'libra config user.signingkey' / vault setup)"),
             PushError::PushSignFailed(_) => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::InternalInvariant),
+            PushError::PrePushHook(_) => CliError::fatal(error.to_string())
+                .with_stable_code(StableErrorCode::RepoStateInvalid)
+                .with_hint("use --no-verify to bypass the hook"),
         }
     }
 }
@@ -810,8 +826,9 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
         }
     };
 
-    // Local file path remotes are not supported for push
-    if is_local_file_remote(&repo_url) {
+    // Local paths can only be pushed to when they hold a Libra repository,
+    // which is then updated in-process as a server would.
+    if is_local_file_remote(&repo_url) && !is_local_libra_remote(&repo_url) {
         return Err(PushError::UnsupportedLocalFileRemote);
     }
 
@@ -830,6 +847,10 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
     let remote_client = remote_client
         .with_network_timeouts(PUSH_CONNECT_TIMEOUT, PUSH_IDLE_TIMEOUT)
         .map_err(|e| PushError::Network(format!("failed to configure remote transport: {e}")))?;
+    // Only HTTPS uploads report through this sink; the pack itself is built
+    // below with the "Compressing/Writing objects" reporters.
+    let upload_progress = progress::for_output(&progress_output_config(output, args.no_progress));
+    let remote_client = remote_client.with_progress(upload_progress);
 
     let discovery = tokio::time::timeout(
         PUSH_CONNECT_TIMEOUT,
@@ -919,7 +940,7 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
                     )?;
                 }
                 ParsedRefspec::Delete { dst } => {
-                    let remote_ref = normalize_delete_ref(&dst)?;
+                    let remote_ref = resolve_delete_ref(&dst, &remote_refs)?;
                     add_delete_ref_plan(
                         remote_ref,
                         &remote_refs,
@@ -1005,6 +1026,12 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
         validate_force_with_lease(&lease, &plans, &tracking)?;
     }
 
+    // Like Git, the hook vets the final ref updates (dry-run included) before
+    // anything is sent.
+    if !args.no_verify {
+        run_pre_push_hook(&repository, &repo_url, &plans, output).await?;
+    }
+
     let obj_result = collect_push_objects(&plans).await?;
     let objs = obj_result.objs;
     warnings.extend(obj_result.warnings);
@@ -1048,6 +1075,17 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
     if push_cert_nonce.is_some() {
         capabilities.push("push-cert");
     }
+    // Ask for a side-band report so the remote's own messages reach the user;
+    // the in-process local target always answers with a plain report.
+    let advertised = |cap: &str| discovery.capabilities.iter().any(|c| c == cap);
+    if !matches!(remote_client, RemoteClient::Local(_)) {
+        if advertised("side-band-64k") {
+            capabilities.push("side-band-64k");
+        }
+        if output.quiet && advertised("quiet") {
+            capabilities.push("quiet");
+        }
+    }
     let capability = capabilities.join(" ");
     let zero_oid = ObjectHash::zero_str(get_hash_kind());
 
@@ -1087,6 +1125,7 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
         let sig_hex = crate::internal::vault::pgp_sign(
             &crate::utils::util::storage_path(),
             &unseal_key,
+            &crate::internal::vault::signing_key_name().await,
             certificate.as_bytes(),
         )
         .await
@@ -1115,8 +1154,9 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
     tracing::debug!("{:?}", data);
 
     // Upload LFS files (only for HTTP remotes)
+    let is_local = matches!(remote_client, RemoteClient::Local(_));
     let mut lfs_files_uploaded = 0;
-    if !is_ssh && !objs.is_empty() {
+    if !is_ssh && !is_local && !objs.is_empty() {
         let url = Url::parse(&repo_url).map_err(|e| PushError::InvalidRemoteUrl {
             url: repo_url.clone(),
             detail: e.to_string(),
@@ -1133,8 +1173,9 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
                 })?;
     }
 
+    // A local target receives the objects directly, without a pack.
     let mut pack_data = Vec::new();
-    if !objs.is_empty() {
+    if !objs.is_empty() && !is_local {
         let (entry_tx, entry_rx) = mpsc::channel::<MetaAttached<Entry, EntryMeta>>(1_000_000);
         let (stream_tx, mut stream_rx) = mpsc::channel(1_000_000);
 
@@ -1185,7 +1226,11 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
                 .send_pack(data.freeze())
                 .await
                 .map_err(|e| classify_transport_error("send-pack", e))?;
-            validate_receive_pack_response(response_bytes, &plans)?;
+            validate_receive_pack_response(
+                response_bytes,
+                &plans,
+                &mut push_status_writer(output),
+            )?;
         }
         RemoteClient::Http(http_client) => {
             let res = http_client.send_pack(data.freeze()).await.map_err(|e| {
@@ -1212,7 +1257,14 @@ pub async fn run_push(args: PushArgs, output: &OutputConfig) -> Result<PushOutpu
                     )),
                 )
             })?;
-            validate_receive_pack_response(data, &plans)?;
+            validate_receive_pack_response(data, &plans, &mut push_status_writer(output))?;
+        }
+        RemoteClient::Local(local_client) => {
+            let response = local_client
+                .receive_pack(&objs, &commands)
+                .await
+                .map_err(|e| PushError::Network(e.to_string()))?;
+            validate_receive_pack_response(response, &plans, &mut push_status_writer(output))?;
         }
         _ => {
             return Err(PushError::UnsupportedLocalFileRemote);
@@ -1371,6 +1423,29 @@ fn normalize_delete_ref(input: &str) -> Result<String, PushError> {
     }
 }
 
+/// Resolve a deletion target against the refs the remote advertises: a short
+/// name deletes the remote tag when only `refs/tags/<name>` exists there (so
+/// `push --delete origin v1` removes a tag), and a branch otherwise. A name
+/// that is both a branch and a tag on the remote must be spelled out.
+fn resolve_delete_ref(
+    input: &str,
+    remote_refs: &HashMap<String, String>,
+) -> Result<String, PushError> {
+    if input.starts_with("refs/") {
+        return normalize_delete_ref(input);
+    }
+    let branch = remote_refs.contains_key(&format!("refs/heads/{input}"));
+    let tag = remote_refs.contains_key(&format!("refs/tags/{input}"));
+    match (branch, tag) {
+        (true, true) => Err(PushError::InvalidRefspec(format!(
+            "'{input}' matches both a branch and a tag on the remote; \
+             use refs/heads/{input} or refs/tags/{input}"
+        ))),
+        (false, true) => normalize_tag_ref(input),
+        _ => normalize_delete_ref(input),
+    }
+}
+
 async fn resolve_local_ref(input: &str) -> Result<ResolvedLocalRef, PushError> {
     if input.starts_with("refs/heads/") {
         let short_name = input
@@ -1904,20 +1979,108 @@ async fn collect_tag_object_chain(
     }
 }
 
-fn validate_receive_pack_response(
-    mut response_data: Bytes,
+/// Run the `pre-push <remote> <url>` hook, feeding it one
+/// `<local ref> <local oid> <remote ref> <remote oid>` line per update on stdin
+/// (a deletion's local side is `(delete)` and the zero id). A non-zero exit
+/// aborts the push.
+async fn run_pre_push_hook(
+    remote: &str,
+    url: &str,
     plans: &[RefUpdatePlan],
+    output: &OutputConfig,
 ) -> Result<(), PushError> {
+    let zero_oid = ObjectHash::zero_str(get_hash_kind());
+    let stdin: String = plans
+        .iter()
+        .map(|plan| {
+            let update = &plan.update;
+            let (local_ref, local_oid) = match update.kind {
+                PushRefUpdateKind::Update => (update.local_ref.as_str(), update.new_oid.as_str()),
+                PushRefUpdateKind::Delete => ("(delete)", zero_oid.as_str()),
+            };
+            let remote_oid = update.old_oid.as_deref().unwrap_or(&zero_oid);
+            format!(
+                "{local_ref} {local_oid} {} {remote_oid}\n",
+                update.remote_ref
+            )
+        })
+        .collect();
+    git_hooks::run_hook("pre-push", &[remote, url], Some(stdin.as_bytes()), output)
+        .await
+        .map_err(PushError::PrePushHook)
+}
+
+/// Per-ref outcome from the remote's report-status: `ok <ref>` or
+/// `ng <ref> <reason>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RefStatus {
+    refname: String,
+    /// `None` when the remote accepted the update.
+    rejection: Option<String>,
+}
+
+/// The decoded receive-pack response: per-ref statuses plus any progress text
+/// the remote sent on side-band 2.
+#[derive(Debug, Default)]
+struct ReceivePackReport {
+    refs: Vec<RefStatus>,
+    remote_messages: Vec<String>,
+}
+
+/// Strip side-band framing from a receive-pack response when present.
+///
+/// With `side-band-64k` negotiated every pkt-line starts with a band byte:
+/// band 1 carries the report-status pkt-lines (possibly split across frames),
+/// band 2 remote progress, band 3 a fatal remote error. A plain report starts
+/// with `unpack`, so the first payload byte tells the two forms apart.
+fn demux_receive_pack_response(mut response: Bytes) -> Result<(Bytes, Vec<String>), PushError> {
+    let framed = response.len() > 4 && matches!(response.get(4), Some(1..=3));
+    if !framed {
+        return Ok((response, Vec::new()));
+    }
+
+    let mut report = BytesMut::new();
+    let mut progress = String::new();
+    loop {
+        let (len, payload) = read_pkt_line(&mut response);
+        if len == 0 {
+            break;
+        }
+        let Some((&band, data)) = payload.split_first() else {
+            continue;
+        };
+        match band {
+            1 => report.extend_from_slice(data),
+            2 => progress.push_str(&String::from_utf8_lossy(data)),
+            3 => {
+                return Err(PushError::Network(format!(
+                    "remote error: {}",
+                    String::from_utf8_lossy(data).trim()
+                )));
+            }
+            _ => tracing::debug!("ignoring unknown side-band code {band}"),
+        }
+    }
+    let messages = progress
+        .split(['\n', '\r'])
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    Ok((report.freeze(), messages))
+}
+
+/// Decode the report-status returned after the pack is sent. A failed unpack or
+/// a line that is neither `ok` nor `ng` is an error; per-ref rejections are
+/// returned in the report for the caller to render.
+fn parse_receive_pack_report(response: Bytes) -> Result<ReceivePackReport, PushError> {
+    let (mut response_data, remote_messages) = demux_receive_pack_response(response)?;
     let (_, pkt_line) = read_pkt_line(&mut response_data);
     if pkt_line != "unpack ok\n" {
         return Err(PushError::RemoteUnpackFailed);
     }
 
-    let expected_refs: HashSet<_> = plans
-        .iter()
-        .map(|plan| plan.update.remote_ref.as_str())
-        .collect();
-    let mut seen_refs = HashSet::new();
+    let mut refs = Vec::new();
     loop {
         let (len, pkt_line) = read_pkt_line(&mut response_data);
         if len == 0 {
@@ -1925,32 +2088,127 @@ fn validate_receive_pack_response(
         }
         let line = String::from_utf8_lossy(&pkt_line).trim().to_string();
         if let Some(refname) = line.strip_prefix("ok ") {
-            seen_refs.insert(refname.to_string());
+            refs.push(RefStatus {
+                refname: refname.to_string(),
+                rejection: None,
+            });
             continue;
         }
         if let Some(rest) = line.strip_prefix("ng ") {
             let (refname, reason) = rest
                 .split_once(' ')
                 .unwrap_or((rest, "remote rejected update"));
-            return Err(PushError::RemoteRefUpdateFailed {
+            refs.push(RefStatus {
                 refname: refname.to_string(),
-                reason: reason.to_string(),
+                rejection: Some(reason.to_string()),
             });
+            continue;
         }
         return Err(PushError::Network(format!(
             "unexpected receive-pack status line: {line}"
         )));
     }
+    Ok(ReceivePackReport {
+        refs,
+        remote_messages,
+    })
+}
 
-    for expected in expected_refs {
-        if !seen_refs.contains(expected) {
-            return Err(PushError::RemoteRefUpdateFailed {
-                refname: expected.to_string(),
-                reason: "missing status from remote".to_string(),
-            });
-        }
+/// Pair every planned update with its reported rejection, in plan order. A ref
+/// the remote did not report on counts as rejected.
+fn rejected_ref_updates<'a>(
+    report: &ReceivePackReport,
+    plans: &'a [RefUpdatePlan],
+) -> Vec<(&'a PushRefUpdate, String)> {
+    plans
+        .iter()
+        .filter_map(|plan| {
+            let status = report
+                .refs
+                .iter()
+                .find(|status| status.refname == plan.update.remote_ref);
+            match status {
+                Some(RefStatus {
+                    rejection: None, ..
+                }) => None,
+                Some(RefStatus {
+                    rejection: Some(reason),
+                    ..
+                }) => Some((&plan.update, reason.clone())),
+                None => Some((&plan.update, "missing status from remote".to_string())),
+            }
+        })
+        .collect()
+}
+
+/// Format one rejected ref the way `git push` does:
+/// ` ! [rejected]        main -> main (non-fast-forward)`. Ref-update policy
+/// refusals (`non-fast-forward`, `fetch first`, `stale info`) read
+/// `[rejected]`; anything else the remote declined reads `[remote rejected]`.
+fn format_rejected_ref(update: &PushRefUpdate, reason: &str) -> String {
+    let short = |name: &str| {
+        name.strip_prefix("refs/heads/")
+            .or_else(|| name.strip_prefix("refs/tags/"))
+            .unwrap_or(name)
+            .to_string()
+    };
+    let label = if is_non_fast_forward_reason(reason) || reason == "stale info" {
+        "[rejected]"
+    } else {
+        "[remote rejected]"
+    };
+    let refs = if update.kind == PushRefUpdateKind::Delete {
+        short(&update.remote_ref)
+    } else {
+        format!(
+            "{} -> {}",
+            short(&update.local_ref),
+            short(&update.remote_ref)
+        )
+    };
+    format!(" ! {label:<17} {refs} ({reason})")
+}
+
+/// Reasons a remote gives for refusing an update that is not a descendant of
+/// the current tip.
+fn is_non_fast_forward_reason(reason: &str) -> bool {
+    matches!(reason, "non-fast-forward" | "fetch first")
+}
+
+/// Check the remote's report-status against the plan. Remote progress lines
+/// are echoed as `remote: …` and every rejected ref gets its own line on
+/// `status_out`; the push fails with the first rejection.
+fn validate_receive_pack_response(
+    response_data: Bytes,
+    plans: &[RefUpdatePlan],
+    status_out: &mut dyn Write,
+) -> Result<(), PushError> {
+    let report = parse_receive_pack_report(response_data)?;
+    for message in &report.remote_messages {
+        let _ = writeln!(status_out, "remote: {message}");
+    }
+
+    let rejected = rejected_ref_updates(&report, plans);
+    for (update, reason) in &rejected {
+        let _ = writeln!(status_out, "{}", format_rejected_ref(update, reason));
+    }
+    match rejected.into_iter().next() {
+        Some((update, reason)) => Err(PushError::RemoteRefUpdateFailed {
+            refname: update.remote_ref.clone(),
+            reason,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Where per-ref rejection lines go: stderr for human output, nowhere in JSON
+/// mode (the error envelope already carries the failure).
+fn push_status_writer(output: &OutputConfig) -> Box<dyn Write> {
+    if output.is_json() {
+        Box::new(std::io::sink())
+    } else {
+        Box::new(std::io::stderr())
     }
-    Ok(())
 }
 
 async fn update_remote_tracking_refs(
@@ -2385,6 +2643,13 @@ fn is_local_file_remote(spec: &str) -> bool {
     Path::new(spec).exists()
 }
 
+fn is_local_libra_remote(spec: &str) -> bool {
+    matches!(
+        RemoteClient::from_spec_with_remote(spec, None),
+        Ok(RemoteClient::Local(client)) if client.is_libra_repo()
+    )
+}
+
 /// collect all commits from `commit_id` to root commit
 fn collect_history_commits(commit_id: &ObjectHash) -> HashSet<ObjectHash> {
     let zero_oid = zero_object_hash();
@@ -2996,7 +3261,7 @@ mod test {
         );
         assert_eq!(
             PushError::UnsupportedLocalFileRemote.to_string(),
-            "pushing to local file repositories is not supported",
+            "pushing to local file repositories is not supported unless the target is a Libra repository",
         );
         assert_eq!(
             PushError::InvalidRemoteUrl {
@@ -3080,7 +3345,8 @@ mod test {
             "ok refs/heads/release\n",
         ]);
 
-        validate_receive_pack_response(response, &plans).expect("all ref statuses should pass");
+        validate_receive_pack_response(response, &plans, &mut std::io::sink())
+            .expect("all ref statuses should pass");
     }
 
     #[test]
@@ -3092,12 +3358,78 @@ mod test {
         ]);
 
         assert!(matches!(
-            validate_receive_pack_response(response, &plans),
+            validate_receive_pack_response(response, &plans, &mut std::io::sink()),
             Err(PushError::RemoteRefUpdateFailed { refname, reason })
                 if refname == "refs/heads/main" && reason == "protected branch hook declined"
         ));
     }
 
+    #[test]
+    fn validate_receive_pack_response_prints_rejected_refs_and_fails() {
+        let plans = vec![
+            test_ref_update_plan("refs/heads/release"),
+            test_ref_update_plan("refs/heads/main"),
+        ];
+        let response = receive_pack_response(&[
+            "unpack ok\n",
+            "ok refs/heads/release\n",
+            "ng refs/heads/main non-fast-forward\n",
+        ]);
+
+        let mut status_out = Vec::new();
+        let result = validate_receive_pack_response(response, &plans, &mut status_out);
+
+        assert!(matches!(
+            &result,
+            Err(PushError::RemoteRefUpdateFailed { refname, reason })
+                if refname == "refs/heads/main" && reason == "non-fast-forward"
+        ));
+        assert_eq!(
+            String::from_utf8(status_out).unwrap(),
+            " ! [rejected]        main -> main (non-fast-forward)\n"
+        );
+        let cli_error = CliError::from(result.unwrap_err());
+        assert_eq!(
+            cli_error.stable_code(),
+            StableErrorCode::ConflictOperationBlocked
+        );
+    }
+
+    #[test]
+    fn validate_receive_pack_response_demuxes_side_band_report() {
+        let plans = vec![test_ref_update_plan("refs/heads/main")];
+        let report = receive_pack_response(&[
+            "unpack ok\n",
+            "ng refs/heads/main pre-receive hook declined\n",
+        ]);
+        let mut response = BytesMut::new();
+        let mut band = |code: u8, payload: &[u8]| {
+            let mut frame = vec![code];
+            frame.extend_from_slice(payload);
+            response.extend_from_slice(format!("{:04x}", frame.len() + 4).as_bytes());
+            response.extend_from_slice(&frame);
+        };
+        band(2, b"policy: main is frozen\n");
+        let (head, tail) = report.split_at(10);
+        band(1, head);
+        band(1, tail);
+        response.extend_from_slice(b"0000");
+
+        let mut status_out = Vec::new();
+        let result = validate_receive_pack_response(response.freeze(), &plans, &mut status_out);
+
+        assert!(matches!(
+            result,
+            Err(PushError::RemoteRefUpdateFailed { reason, .. })
+                if reason == "pre-receive hook declined"
+        ));
+        assert_eq!(
+            String::from_utf8(status_out).unwrap(),
+            "remote: policy: main is frozen\n \
+             ! [remote rejected] main -> main (pre-receive hook declined)\n"
+        );
+    }
+
     #[test]
     fn validate_receive_pack_response_rejects_missing_expected_ref_status() {
         let plans = vec![
@@ -3107,7 +3439,7 @@ mod test {
         let response = receive_pack_response(&["unpack ok\n", "ok refs/heads/main\n"]);
 
         assert!(matches!(
-            validate_receive_pack_response(response, &plans),
+            validate_receive_pack_response(response, &plans, &mut std::io::sink()),
             Err(PushError::RemoteRefUpdateFailed { refname, reason })
                 if refname == "refs/heads/release" && reason == "missing status from remote"
         ));
@@ -3119,7 +3451,7 @@ mod test {
         let response = receive_pack_response(&["unpack ok\n", "ready refs/heads/main\n"]);
 
         assert!(matches!(
-            validate_receive_pack_response(response, &plans),
+            validate_receive_pack_response(response, &plans, &mut std::io::sink()),
             Err(PushError::Network(message))
                 if message == "unexpected receive-pack status line: ready refs/heads/main"
         ));
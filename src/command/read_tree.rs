@@ -90,7 +90,7 @@ pub async fn execute_safe(args: ReadTreeArgs, output: &OutputConfig) -> CliResul
 /// Resolve a tree-ish to a concrete tree object id. Accepts a raw tree id, a
 /// commit id (peeled to its tree), or any revision name `util::get_commit_base`
 /// understands (branch, tag, `HEAD`, …, peeled to its tree).
-async fn resolve_tree_ish(tree_ish: &str) -> CliResult<ObjectHash> {
+pub(crate) async fn resolve_tree_ish(tree_ish: &str) -> CliResult<ObjectHash> {
     if let Ok(hash) = ObjectHash::from_str(tree_ish) {
         if let Ok(tree) = load_object::<Tree>(&hash) {
             return Ok(tree.id);
@@ -3,7 +3,7 @@
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet, VecDeque},
-    fs,
+    fs, io,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -23,17 +23,20 @@ use serde::Serialize;
 
 use crate::{
     cli_error,
-    command::{load_object, save_object, status, switch},
-    common_utils::{format_commit_msg, parse_commit_msg},
+    command::{editor, load_object, save_object, status, switch},
+    common_utils::{CleanupMode, cleanup_commit_message, format_commit_msg, parse_commit_msg},
     internal::{
         branch::Branch,
         db::get_db_conn_instance,
         head::Head,
         reflog,
         reflog::{ReflogAction, ReflogContext, ReflogError, with_reflog},
+        tree_plumbing,
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode, emit_warning},
+        git_hooks,
+        gitattributes::{self, Attributes},
         ignore::IgnorePolicy,
         object_ext::{BlobExt, TreeExt},
         output::{OutputConfig, emit_json_data},
@@ -710,6 +713,8 @@ pub const REBASE_EXAMPLES: &str = "\
 EXAMPLES:
     libra rebase main             Replay current branch on top of main
     libra rebase --autosquash main Fold fixup!/squash! commits while replaying
+    libra rebase --autosquash --no-autosquash main  Last one wins: replay fixups as picks
+    libra rebase -i --autosquash main  Edit the todo list, fixups already placed
     libra rebase --reapply-cherry-picks main
     libra rebase --onto main dev  Replay dev..HEAD onto main, keeping the upstream range
     libra rebase --keep-empty main Keep empty commits while replaying (Libra's default)
@@ -751,9 +756,20 @@ pub struct RebaseArgs {
     pub skip: bool,
 
     /// Move fixup!/squash! commits next to their targets and fold them while replaying
-    #[clap(long, conflicts_with_all = ["continue_rebase", "abort", "skip"])]
+    #[clap(long, overrides_with = "no_autosquash", conflicts_with_all = ["continue_rebase", "abort", "skip"])]
     pub autosquash: bool,
 
+    /// Replay fixup!/squash! commits as ordinary picks. Toggle pair with
+    /// `--autosquash`; the last one wins.
+    #[clap(long = "no-autosquash", overrides_with = "autosquash")]
+    pub no_autosquash: bool,
+
+    /// Edit the todo list in the configured editor before replaying. With
+    /// `--autosquash` the list starts with fixup!/squash! commits already moved
+    /// under their targets and marked `fixup`/`squash`.
+    #[clap(short = 'i', long, conflicts_with_all = ["continue_rebase", "abort", "skip"])]
+    pub interactive: bool,
+
     /// Explicitly replay clean cherry-pick commits instead of dropping them
     #[clap(long = "reapply-cherry-picks", conflicts_with_all = ["continue_rebase", "abort", "skip"])]
     pub reapply_cherry_picks: bool,
@@ -797,6 +813,10 @@ pub struct RebaseArgs {
     /// has no halt-on-empty resume flow.
     #[clap(long = "empty", value_name = "mode")]
     pub empty: Option<String>,
+
+    /// Bypass the `pre-rebase` hook.
+    #[clap(long = "no-verify")]
+    pub no_verify: bool,
 }
 
 #[derive(Debug, Clone, Default, Serialize)]
@@ -917,6 +937,16 @@ pub(crate) enum RebaseError {
     StateSave(String),
     #[error("failed to finalize rebase: {0}")]
     Finalize(String),
+    #[error("the pre-rebase hook refused to rebase: {0}")]
+    PreRebaseHook(String),
+    #[error("no editor configured to edit the rebase todo list")]
+    NoEditor,
+    #[error("failed to edit the rebase todo list: {0}")]
+    TodoEdit(String),
+    #[error("invalid rebase todo list: {0}")]
+    TodoInvalid(String),
+    #[error("nothing to do")]
+    TodoEmpty,
 }
 
 impl From<RebaseError> for CliError {
@@ -1017,6 +1047,21 @@ impl From<RebaseError> for CliError {
             RebaseError::IndexLoad(..) => {
                 CliError::fatal(error.to_string()).with_stable_code(StableErrorCode::IoReadFailed)
             }
+            RebaseError::PreRebaseHook(..) => CliError::fatal(error.to_string())
+                .with_stable_code(StableErrorCode::RepoStateInvalid)
+                .with_hint("use --no-verify to bypass the hook"),
+            RebaseError::NoEditor => CliError::fatal(error.to_string())
+                .with_stable_code(StableErrorCode::RepoStateInvalid)
+                .with_hint("set GIT_EDITOR, core.editor, VISUAL, or EDITOR"),
+            RebaseError::TodoEdit(..) => {
+                CliError::io(error.to_string()).with_stable_code(StableErrorCode::IoReadFailed)
+            }
+            RebaseError::TodoInvalid(..) => CliError::fatal(error.to_string())
+                .with_stable_code(StableErrorCode::CliInvalidArguments)
+                .with_hint("each line must be `<command> <commit>`; run 'libra rebase -i' again."),
+            RebaseError::TodoEmpty => CliError::fatal(error.to_string())
+                .with_stable_code(StableErrorCode::CliInvalidArguments)
+                .with_hint("the todo list was emptied, so the rebase was not started."),
         }
     }
 }
@@ -1089,13 +1134,18 @@ pub async fn execute_safe(args: RebaseArgs, output: &OutputConfig) -> CliResult<
     }
     if args.continue_rebase {
         let result = run_rebase_continue().await.map_err(CliError::from)?;
-        return render_rebase_output(&result, output);
+        return render_rebase_result(&result, output).await;
     }
     if args.skip {
         let result = run_rebase_skip().await.map_err(CliError::from)?;
-        return render_rebase_output(&result, output);
+        return render_rebase_result(&result, output).await;
     }
     if let Some(upstream) = args.upstream.as_deref() {
+        // Like Git, the hook sees `<upstream> [<branch>]` before the branch is
+        // checked out or anything is rewritten.
+        if !args.no_verify {
+            run_pre_rebase_hook(upstream, args.branch.as_deref(), output).await?;
+        }
         // `git rebase --onto <newbase> <upstream> <branch>` form: check out the
         // named branch first (no-op when it is already current), so the rest of
         // the start path rebases it as "the current branch".
@@ -1106,17 +1156,49 @@ pub async fn execute_safe(args: RebaseArgs, output: &OutputConfig) -> CliResult<
             upstream,
             args.onto.as_deref(),
             args.autosquash,
+            args.interactive,
             args.reapply_cherry_picks,
             args.no_keep_empty,
             empty_mode,
         )
         .await
         .map_err(CliError::from)?;
-        return render_rebase_output(&result, output);
+        return render_rebase_result(&result, output).await;
     }
     Ok(())
 }
 
+/// Render a rebase that ran (start, `--continue`, `--skip`) and, once it has
+/// completed, run `post-rewrite rebase` with the commits it rewrote. The
+/// old-to-new mapping is not persisted across a conflict stop, so a rebase
+/// finished by `--continue`/`--skip` lists only the commits that invocation
+/// replayed.
+async fn render_rebase_result(result: &RebaseOutput, output: &OutputConfig) -> CliResult<()> {
+    render_rebase_output(result, output)?;
+    if result.status == "completed" {
+        let rewritten: Vec<(String, String)> = result
+            .applied_commits
+            .iter()
+            .map(|applied| (applied.original_commit.clone(), applied.commit.clone()))
+            .collect();
+        git_hooks::run_post_rewrite_hook(git_hooks::RewriteKind::Rebase, &rewritten, output).await;
+    }
+    Ok(())
+}
+
+/// Run the `pre-rebase <upstream> [<branch>]` hook; a non-zero exit aborts the
+/// rebase.
+async fn run_pre_rebase_hook(
+    upstream: &str,
+    branch: Option<&str>,
+    output: &OutputConfig,
+) -> Result<(), RebaseError> {
+    let args: Vec<&str> = std::iter::once(upstream).chain(branch).collect();
+    git_hooks::run_hook("pre-rebase", &args, None, output)
+        .await
+        .map_err(RebaseError::PreRebaseHook)
+}
+
 /// Check out `<branch>` before a `rebase ... <branch>` start, unless it is
 /// already the current branch. Uses `switch::execute_safe` (not `execute`) so a
 /// switch failure (dirty worktree, missing branch) propagates as a non-zero
@@ -1502,6 +1584,149 @@ fn autosquash_target_match_kind(
         .unwrap_or(None)
 }
 
+/// Open the planned todo list for `rebase -i` in the configured editor and
+/// return the list the user saved. The buffer lives under the storage
+/// directory only while the editor runs.
+async fn edit_rebase_todo(
+    planned: &[RebaseTodoItem],
+    onto: &ObjectHash,
+) -> Result<Vec<RebaseTodoItem>, RebaseError> {
+    use std::io::IsTerminal;
+
+    // An explicitly configured editor runs even without a TTY (so scripted
+    // editors work in tests/automation); `vi` is only assumed on a terminal.
+    let editor_cmd = match editor::resolve_editor().await {
+        Some(cmd) => cmd,
+        None if std::io::stdin().is_terminal() => "vi".to_string(),
+        None => return Err(RebaseError::NoEditor),
+    };
+
+    let initial = render_rebase_todo(planned, onto)?;
+    let path = util::storage_path().join("rebase-todo");
+    let edited = editor::edit_message(&path, &initial, &editor_cmd, true)
+        .await
+        .map_err(|error| RebaseError::TodoEdit(error.to_string()));
+    let _ = fs::remove_file(&path);
+
+    let planned_commits = planned.iter().map(|item| item.commit).collect::<Vec<_>>();
+    parse_rebase_todo(&edited?, &planned_commits)
+}
+
+/// Render the todo buffer Git-style: one `<command> <short-hash> <subject>`
+/// line per commit, followed by a commented command reference.
+fn render_rebase_todo(
+    planned: &[RebaseTodoItem],
+    onto: &ObjectHash,
+) -> Result<String, RebaseError> {
+    let mut out = String::new();
+    for item in planned {
+        let commit: Commit =
+            load_object(&item.commit).map_err(|error| RebaseError::CommitLoad {
+                commit: item.commit.to_string(),
+                detail: error.to_string(),
+            })?;
+        let id = item.commit.to_string();
+        out.push_str(&format!(
+            "{} {} {}\n",
+            item.action.as_str(),
+            &id[..7],
+            commit_subject_from_message(&commit.message)
+        ));
+    }
+    let onto = onto.to_string();
+    out.push_str(&format!(
+        "\n# Rebase onto {} ({} command(s))\n",
+        &onto[..7],
+        planned.len()
+    ));
+    out.push_str(
+        "#
+# Commands:
+# p, pick <commit> = use commit
+# s, squash <commit> = use commit, but meld into previous commit
+# f, fixup <commit> = like \"squash\" but discard this commit's log message
+# amend <commit> = like \"fixup\" but use this commit's log message
+# d, drop <commit> = remove commit
+#
+# These lines can be re-ordered; they are executed from top to bottom.
+# If you remove a line here THAT COMMIT WILL BE LOST.
+# However, if you remove everything, the rebase will be aborted.
+",
+    );
+    Ok(out)
+}
+
+/// Parse an edited todo buffer. Every commit must be one of `planned` and may
+/// appear at most once; commits whose line was removed or marked `drop` are
+/// not replayed. An empty list aborts the rebase.
+fn parse_rebase_todo(
+    content: &str,
+    planned: &[ObjectHash],
+) -> Result<Vec<RebaseTodoItem>, RebaseError> {
+    let mut items: Vec<RebaseTodoItem> = Vec::new();
+    let mut seen = HashSet::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let command = fields.next().unwrap_or_default();
+        let action = match command {
+            "p" | "pick" => Some(RebaseTodoAction::Pick),
+            "f" | "fixup" => Some(RebaseTodoAction::Fixup),
+            "s" | "squash" => Some(RebaseTodoAction::Squash),
+            "amend" => Some(RebaseTodoAction::Amend),
+            "d" | "drop" => None,
+            other => {
+                return Err(RebaseError::TodoInvalid(format!(
+                    "unknown command '{other}' in line '{line}'"
+                )));
+            }
+        };
+        let Some(prefix) = fields.next() else {
+            return Err(RebaseError::TodoInvalid(format!(
+                "missing commit in line '{line}'"
+            )));
+        };
+        let prefix = prefix.to_ascii_lowercase();
+        let mut matches = planned
+            .iter()
+            .filter(|commit| commit.to_string().starts_with(&prefix));
+        let commit = match (matches.next(), matches.next()) {
+            (Some(commit), None) => *commit,
+            (Some(_), Some(_)) => {
+                return Err(RebaseError::TodoInvalid(format!(
+                    "commit '{prefix}' is ambiguous"
+                )));
+            }
+            (None, _) => {
+                return Err(RebaseError::TodoInvalid(format!(
+                    "'{prefix}' is not one of the commits being rebased"
+                )));
+            }
+        };
+        if !seen.insert(commit) {
+            return Err(RebaseError::TodoInvalid(format!(
+                "commit '{prefix}' is listed more than once"
+            )));
+        }
+        let Some(action) = action else {
+            continue;
+        };
+        if action.folds_into_previous() && items.is_empty() {
+            return Err(RebaseError::TodoInvalid(format!(
+                "cannot '{}' without a previous commit",
+                action.as_str()
+            )));
+        }
+        items.push(RebaseTodoItem { commit, action });
+    }
+    if items.is_empty() {
+        return Err(RebaseError::TodoEmpty);
+    }
+    Ok(items)
+}
+
 async fn preflight_rebase(args: &RebaseArgs) -> CliResult<()> {
     if args.continue_rebase || args.abort || args.skip {
         return Ok(());
@@ -1551,6 +1776,7 @@ async fn run_rebase_start(
     upstream: &str,
     onto: Option<&str>,
     autosquash: bool,
+    interactive: bool,
     _reapply_cherry_picks: bool,
     no_keep_empty: bool,
     empty_mode: RebaseEmptyMode,
@@ -1624,6 +1850,8 @@ async fn run_rebase_start(
         rebuild_index_from_tree(&upstream_tree, &mut index, "")
             .map_err(RebaseError::IndexRebuild)?;
         rebase_worktree_guard_structured(&index, "fast-forward rebase").await?;
+        Head::record_orig_head(&head_to_rebase_id)
+            .map_err(|e| RebaseError::StateSave(format!("failed to write ORIG_HEAD: {e}")))?;
 
         let fast_forward_action = ReflogAction::Rebase {
             state: "fast-forward".to_string(),
@@ -1734,6 +1962,21 @@ async fn run_rebase_start(
         commits_to_replay = planned_todo.iter().map(|item| item.commit).collect();
         todo_actions = planned_todo.iter().map(|item| item.action).collect();
     }
+    // `-i`: hand the planned list (already autosquashed when requested) to the
+    // editor before anything is mutated, so an aborted edit leaves no state.
+    if interactive && !commits_to_replay.is_empty() {
+        let planned_todo = commits_to_replay
+            .iter()
+            .zip(&todo_actions)
+            .map(|(commit, action)| RebaseTodoItem {
+                commit: *commit,
+                action: *action,
+            })
+            .collect::<Vec<_>>();
+        let edited = edit_rebase_todo(&planned_todo, &newbase_id).await?;
+        commits_to_replay = edited.iter().map(|item| item.commit).collect();
+        todo_actions = edited.iter().map(|item| item.action).collect();
+    }
     // Only genuinely-nothing-to-rebase (collect returned empty) returns early and
     // leaves the branch put. If `--no-keep-empty` emptied a non-empty range, fall
     // through to the normal setup so the branch is still rebased onto newbase
@@ -1774,6 +2017,10 @@ async fn run_rebase_start(
     rebuild_index_from_tree(&newbase_tree, &mut guard_index, "")
         .map_err(RebaseError::IndexRebuild)?;
     rebase_worktree_guard_structured(&guard_index, "rebase").await?;
+    // Like Git, remember the pre-rebase tip so `reset --hard ORIG_HEAD`
+    // recovers it after the rebase finishes.
+    Head::record_orig_head(&head_to_rebase_id)
+        .map_err(|e| RebaseError::StateSave(format!("failed to write ORIG_HEAD: {e}")))?;
 
     // The replay lands on `newbase_id` (== upstream_id for a plain rebase): the
     // initial detach, the rebase state's onto/current_head, and the start reflog
@@ -1868,8 +2115,16 @@ pub(crate) struct PullRebaseSummary {
 /// variant so the `phase=rebase` detail can be attached.
 pub(crate) async fn run_rebase_for_pull(upstream: &str) -> Result<PullRebaseSummary, RebaseError> {
     // `pull --rebase` keeps Libra's default (keep become-empty commits).
-    let output =
-        run_rebase_start(upstream, None, false, false, false, RebaseEmptyMode::Keep).await?;
+    let output = run_rebase_start(
+        upstream,
+        None,
+        false,
+        false,
+        false,
+        false,
+        RebaseEmptyMode::Keep,
+    )
+    .await?;
     let old_commit = output
         .previous_commit
         .clone()
@@ -2446,24 +2701,12 @@ fn has_unmerged_entries(index: &git_internal::internal::index::Index) -> bool {
     false
 }
 
-/// Create a tree from the current index
+/// Create a tree from the current index through the shared index→tree
+/// writer, which keeps gitlinks and leaves out intent-to-add entries.
 fn create_tree_from_index(
     index: &git_internal::internal::index::Index,
 ) -> Result<ObjectHash, String> {
-    let mut items: HashMap<PathBuf, RebaseTreeEntry> = HashMap::new();
-    for path in index.tracked_files() {
-        let path_str = path_to_index_key(&path)?;
-        if let Some(entry) = index.get(path_str, 0) {
-            items.insert(
-                path.clone(),
-                RebaseTreeEntry {
-                    hash: entry.hash,
-                    mode: index_mode_to_tree_item_mode(entry.mode)?,
-                },
-            );
-        }
-    }
-    create_tree_from_items_map(&items)
+    tree_plumbing::write_tree_from_index(index).map_err(|error| error.to_string())
 }
 
 fn write_workdir_file(workdir: &Path, path: &Path, content: &[u8]) -> Result<(), String> {
@@ -2487,6 +2730,13 @@ fn write_rebase_workdir_entry(
     path: &Path,
     entry: RebaseTreeEntry,
 ) -> Result<(), String> {
+    if entry.mode == TreeItemMode::Commit {
+        // A gitlink's commit lives in the submodule's repository: keep the
+        // submodule directory (creating it empty if absent), as Git does.
+        let dir = workdir.join(path);
+        return fs::create_dir_all(&dir)
+            .map_err(|error| format!("failed to create {}: {error}", dir.display()));
+    }
     let blob: Blob = load_object(&entry.hash).map_err(|error| {
         format!(
             "failed to load blob {} for worktree path '{}': {error}",
@@ -2594,13 +2844,7 @@ fn collect_tree_items_and_paths<'a>(
         let map: HashMap<PathBuf, RebaseTreeEntry> = tree
             .get_plain_items_with_mode()
             .into_iter()
-            .filter_map(|(path, hash, mode)| {
-                if mode == TreeItemMode::Commit {
-                    None
-                } else {
-                    Some((path, RebaseTreeEntry { hash, mode }))
-                }
-            })
+            .map(|(path, hash, mode)| (path, RebaseTreeEntry { hash, mode }))
             .collect();
         all_paths.extend(map.keys().cloned());
         items.push(map);
@@ -2624,9 +2868,10 @@ mod tests {
     #[cfg(unix)]
     use super::path_to_index_key;
     use super::{
-        RebaseError, RebaseTreeEntry, ReplayErrorKind, classify_relative_to_base,
-        collect_tree_items_and_paths, create_tree_from_items_map, index_mode_to_tree_item_mode,
-        resolve_three_way, tree_item_mode_to_index_mode, tree_item_name, write_workdir_blob,
+        RebaseError, RebaseTodoAction, RebaseTodoItem, RebaseTreeEntry, ReplayErrorKind,
+        classify_relative_to_base, collect_tree_items_and_paths, create_tree_from_items_map,
+        index_mode_to_tree_item_mode, parse_rebase_todo, resolve_three_way,
+        tree_item_mode_to_index_mode, tree_item_name, write_workdir_blob,
     };
     use crate::{
         command::load_object,
@@ -2643,6 +2888,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_rebase_todo_follows_the_edited_order() {
+        let planned = [
+            ObjectHash::new(b"first"),
+            ObjectHash::new(b"second"),
+            ObjectHash::new(b"third"),
+        ];
+        let short = |id: &ObjectHash| id.to_string()[..7].to_string();
+        let todo = format!(
+            "# comment\npick {} first\n\nf {} fixup! first\ndrop {} third\n",
+            short(&planned[0]),
+            short(&planned[1]),
+            short(&planned[2])
+        );
+        assert_eq!(
+            parse_rebase_todo(&todo, &planned).unwrap(),
+            vec![
+                RebaseTodoItem {
+                    commit: planned[0],
+                    action: RebaseTodoAction::Pick,
+                },
+                RebaseTodoItem {
+                    commit: planned[1],
+                    action: RebaseTodoAction::Fixup,
+                },
+            ]
+        );
+
+        let rejected = [
+            format!("edit {}", short(&planned[0])),
+            format!("squash {}", short(&planned[0])),
+            format!("pick {0}\npick {0}", short(&planned[0])),
+            "pick 0000000".to_string(),
+            "pick".to_string(),
+        ];
+        for todo in rejected {
+            assert!(
+                matches!(
+                    parse_rebase_todo(&todo, &planned),
+                    Err(RebaseError::TodoInvalid(_))
+                ),
+                "{todo:?} should be rejected"
+            );
+        }
+        assert!(matches!(
+            parse_rebase_todo("# everything removed\n", &planned),
+            Err(RebaseError::TodoEmpty)
+        ));
+    }
+
     #[test]
     fn replay_error_kind_stable_codes_route_distinct_failures() {
         // Object load failures point at repository corruption.
@@ -3212,8 +3507,16 @@ mod tests {
             index_mode_to_tree_item_mode(0o120000).expect("symlink"),
             TreeItemMode::Link
         );
-        assert!(tree_item_mode_to_index_mode(TreeItemMode::Commit).is_err());
-        assert!(index_mode_to_tree_item_mode(0o160000).is_err());
+        assert_eq!(
+            tree_item_mode_to_index_mode(TreeItemMode::Commit).expect("gitlink"),
+            0o160000
+        );
+        assert_eq!(
+            index_mode_to_tree_item_mode(0o160000).expect("gitlink"),
+            TreeItemMode::Commit
+        );
+        assert!(tree_item_mode_to_index_mode(TreeItemMode::Tree).is_err());
+        assert!(index_mode_to_tree_item_mode(0o040000).is_err());
     }
 
     #[tokio::test]
@@ -3443,17 +3746,30 @@ fn resolve_three_way(
     }
 }
 
+/// Write a conflicted path into the working tree: conflict markers for text,
+/// or a side's content verbatim (ours when it exists, else theirs) for a
+/// binary path (`-merge`/`binary` attribute, or a NUL byte on either side).
 fn write_conflict_markers(
     workdir: &Path,
     path: &Path,
     marker_eol: &str,
     commit_abbrev: &str,
     kind: ConflictKind,
+    attributes: &Attributes,
 ) -> Result<(), String> {
+    let attrs = attributes.lookup(path);
     match kind {
         ConflictKind::BothChanged { ours, theirs } => {
             let our_content = Blob::load(&ours).data;
             let their_content = Blob::load(&theirs).data;
+            if gitattributes::is_binary_merge(&attrs, &[&our_content, &their_content]) {
+                emit_warning(format!(
+                    "Cannot merge binary files: {} (HEAD vs. {commit_abbrev})",
+                    path.display()
+                ));
+                return write_workdir_file(workdir, path, &our_content)
+                    .map_err(|e| format!("conflict file: {}", e));
+            }
             let our_text = conflict_payload(&our_content);
             let their_text = conflict_payload(&their_content);
             let conflict_content = format!(
@@ -3464,6 +3780,10 @@ fn write_conflict_markers(
         }
         ConflictKind::OursModifiedTheirsDeleted { ours } => {
             let our_content = Blob::load(&ours).data;
+            if gitattributes::is_binary_merge(&attrs, &[&our_content]) {
+                return write_workdir_file(workdir, path, &our_content)
+                    .map_err(|e| format!("conflict file: {}", e));
+            }
             let our_text = conflict_payload(&our_content);
             let conflict_content = format!(
                 "<<<<<<< HEAD{marker_eol}{}{marker_eol}======={marker_eol}>>>>>>> {} (deleted){marker_eol}",
@@ -3473,6 +3793,10 @@ fn write_conflict_markers(
         }
         ConflictKind::TheirsModifiedOursDeleted { theirs } => {
             let their_content = Blob::load(&theirs).data;
+            if gitattributes::is_binary_merge(&attrs, &[&their_content]) {
+                return write_workdir_file(workdir, path, &their_content)
+                    .map_err(|e| format!("conflict file: {}", e));
+            }
             let their_text = conflict_payload(&their_content);
             let conflict_content = format!(
                 "<<<<<<< HEAD (deleted){marker_eol}======={marker_eol}{}{marker_eol}>>>>>>> {}{marker_eol}",
@@ -3604,8 +3928,20 @@ async fn replay_commit_with_conflict_detection(
             );
         }
 
+        let attributes = Attributes::for_worktree().await;
         for (path, kind) in &conflict_items {
-            if let Err(e) = write_conflict_markers(&workdir, path, marker_eol, commit_short, *kind)
+            // A submodule conflict has no content to mark up: the directory
+            // stays and the index stages carry the competing commits.
+            let is_gitlink = [base_items, their_items, our_items].iter().any(|items| {
+                items
+                    .get(path)
+                    .is_some_and(|e| e.mode == TreeItemMode::Commit)
+            });
+            if is_gitlink {
+                continue;
+            }
+            if let Err(e) =
+                write_conflict_markers(&workdir, path, marker_eol, commit_short, *kind, &attributes)
             {
                 return ReplayResult::internal(ReplayErrorKind::ConflictMarker, e);
             }
@@ -3679,7 +4015,7 @@ async fn replay_commit_with_conflict_detection(
             if !full_path.exists() {
                 continue;
             }
-            if let Err(e) = fs::remove_file(&full_path) {
+            if let Err(e) = remove_workdir_entry(&full_path) {
                 return ReplayResult::Conflict {
                     paths: conflicts,
                     message: Some(format!("failed to remove {}: {}", full_path.display(), e)),
@@ -3769,9 +4105,7 @@ fn create_replayed_commit(
         }
         RebaseTodoAction::Squash => {
             let target: Commit = load_object(&new_parent_id).map_err(|error| error.to_string())?;
-            let mut message = target.message.clone();
-            message.push_str("\n\n");
-            message.push_str(original_commit.message.trim());
+            let message = squash_message(&target.message, &original_commit.message);
             Ok(Commit::from_tree_id(
                 tree_id,
                 target.parent_commit_ids.clone(),
@@ -3790,16 +4124,35 @@ fn create_replayed_commit(
     }
 }
 
+/// The stored message of a `squash`: the target's message, a blank line, then
+/// the squashed commit's message, with the whitespace cleanup `commit` applies
+/// to a message that was not edited.
+fn squash_message(target: &str, squashed: &str) -> String {
+    let (target_message, gpg_sig) = parse_commit_msg(target);
+    let combined = cleanup_commit_message(
+        &format!("{target_message}\n\n{}", parse_commit_msg(squashed).0),
+        CleanupMode::Default.effective(false),
+    );
+    match gpg_sig {
+        Some(signature) => format_commit_msg(&combined, Some(&format!("gpgsig {signature}"))),
+        None => format_commit_msg(&combined, None),
+    }
+}
+
 fn amend_replacement_message(message: &str) -> String {
     let (clean_message, gpg_sig) = parse_commit_msg(message);
     let subject = clean_message.lines().next().unwrap_or("");
     if !subject.starts_with("amend! ") {
         return message.to_string();
     }
-    let replacement = clean_message
-        .split_once('\n')
-        .map(|(_, replacement)| replacement.trim_start_matches('\n'))
-        .unwrap_or_default();
+    let replacement = cleanup_commit_message(
+        clean_message
+            .split_once('\n')
+            .map(|(_, replacement)| replacement)
+            .unwrap_or_default(),
+        CleanupMode::Default.effective(false),
+    );
+    let replacement = replacement.as_str();
     match gpg_sig {
         Some(signature) => format_commit_msg(replacement, Some(&format!("gpgsig {signature}"))),
         None => format_commit_msg(replacement, None),
@@ -3962,7 +4315,7 @@ fn reset_workdir_tracked_only(
         if !new_tracked_paths.contains(&path_buf) {
             let full_path = workdir.join(path_buf);
             if full_path.exists() {
-                fs::remove_file(&full_path).map_err(|e| e.to_string())?;
+                remove_workdir_entry(&full_path).map_err(|e| e.to_string())?;
             }
         }
     }
@@ -3985,6 +4338,19 @@ fn reset_workdir_tracked_only(
     Ok(())
 }
 
+/// Remove a tracked path from the working tree. A directory is a dropped
+/// submodule: it is removed only when empty, and a checked-out one is kept
+/// with a warning, like Git's "unable to rmdir".
+fn remove_workdir_entry(full_path: &Path) -> io::Result<()> {
+    if !full_path.is_dir() || fs::symlink_metadata(full_path)?.file_type().is_symlink() {
+        return fs::remove_file(full_path);
+    }
+    if fs::remove_dir(full_path).is_err() {
+        emit_warning(format!("unable to rmdir '{}'", full_path.display()));
+    }
+    Ok(())
+}
+
 fn tree_item_name(path: &Path) -> Result<String, String> {
     let name = path
         .file_name()
@@ -4005,17 +4371,23 @@ fn add_rebase_index_entry(
     item: RebaseTreeEntry,
     stage: u8,
 ) -> Result<(), String> {
-    let blob: Blob = load_object(&item.hash).map_err(|error| {
-        format!(
-            "failed to load blob {} for index entry '{}': {error}",
-            item.hash,
-            path.display()
-        )
-    })?;
+    // A gitlink has no blob in this repository; its entry records size 0.
+    let size = if item.mode == TreeItemMode::Commit {
+        0
+    } else {
+        let blob: Blob = load_object(&item.hash).map_err(|error| {
+            format!(
+                "failed to load blob {} for index entry '{}': {error}",
+                item.hash,
+                path.display()
+            )
+        })?;
+        blob.data.len() as u32
+    };
     let mut entry = git_internal::internal::index::IndexEntry::new_from_blob(
         path_to_index_key(path)?.to_string(),
         item.hash,
-        blob.data.len() as u32,
+        size,
     );
     entry.mode = tree_item_mode_to_index_mode(item.mode)?;
     entry.flags.stage = stage;
@@ -4031,7 +4403,7 @@ fn tree_item_mode_to_index_mode(mode: TreeItemMode) -> Result<u32, String> {
         TreeItemMode::Tree => {
             Err("tree entry cannot be represented as a file index entry".to_string())
         }
-        TreeItemMode::Commit => Err("gitlink entries are not supported by rebase".to_string()),
+        TreeItemMode::Commit => Ok(0o160000),
     }
 }
 
@@ -4040,6 +4412,7 @@ fn index_mode_to_tree_item_mode(mode: u32) -> Result<TreeItemMode, String> {
         0o100644 => Ok(TreeItemMode::Blob),
         0o100755 => Ok(TreeItemMode::BlobExecutable),
         0o120000 => Ok(TreeItemMode::Link),
+        0o160000 => Ok(TreeItemMode::Commit),
         other => Err(format!(
             "unsupported index mode {other:o} while creating rebase tree"
         )),
@@ -4081,10 +4454,13 @@ fn rebuild_index_from_tree(
             git_internal::internal::object::tree::TreeItemMode::BlobExecutable => 0o100755,
             git_internal::internal::object::tree::TreeItemMode::Link => 0o120000,
             git_internal::internal::object::tree::TreeItemMode::Commit => {
-                return Err(format!(
-                    "unsupported gitlink tree entry '{}' while rebuilding rebase index",
-                    full_path
-                ));
+                // A gitlink's commit lives in the submodule's repository, so
+                // there is no blob to load; keep the entry as-is.
+                let mut entry =
+                    git_internal::internal::index::IndexEntry::new_from_blob(full_path, item.id, 0);
+                entry.mode = 0o160000;
+                index.add(entry);
+                continue;
             }
         };
 
@@ -4183,11 +4559,12 @@ mod rebuild_index_tests {
     }
 
     #[tokio::test]
-    async fn rebuild_index_from_tree_rejects_gitlink_entries() {
+    async fn rebuild_index_from_tree_keeps_gitlink_entries() {
         let repo = tempdir().unwrap();
         setup_with_new_libra_in(repo.path()).await;
         let _guard = ChangeDirGuard::new(repo.path());
 
+        // The submodule commit is not in this repository's object database.
         let gitlink = ObjectHash::from_str("0123456789abcdef0123456789abcdef01234567").unwrap();
         let tree = Tree::from_tree_items(vec![TreeItem::new(
             TreeItemMode::Commit,
@@ -4197,9 +4574,10 @@ mod rebuild_index_tests {
         .unwrap();
         let mut index = Index::new();
 
-        let err = rebuild_index_from_tree(&tree, &mut index, "").unwrap_err();
+        rebuild_index_from_tree(&tree, &mut index, "").unwrap();
 
-        assert!(err.contains("unsupported gitlink tree entry"));
-        assert!(err.contains("vendor"));
+        let entry = index.get("vendor", 0).unwrap();
+        assert_eq!(entry.mode, 0o160000);
+        assert_eq!(entry.hash, gitlink);
     }
 }
@@ -45,6 +45,7 @@ pub const REFLOG_EXAMPLES: &str = "\
 EXAMPLES:
     libra reflog show                          Show HEAD reflog entries
     libra reflog show main --number 20         Show the last 20 entries for refs/heads/main
+    libra reflog show --all                    Show the reflog of every ref
     libra reflog show --grep 'commit (amend)'  Filter HEAD reflog by message pattern
     libra reflog show --no-abbrev              Show entries with full object names
     libra reflog exists refs/heads/feature-x   Probe whether a ref has reflog entries
@@ -64,7 +65,8 @@ pub struct ReflogArgs {
 /// matching Git's default.
 fn default_reflog_command() -> Subcommands {
     Subcommands::Show {
-        ref_name: "HEAD".to_string(),
+        ref_name: None,
+        all: false,
         pretty: FormatterKind::default(),
         since: None,
         until: None,
@@ -81,8 +83,12 @@ fn default_reflog_command() -> Subcommands {
 enum Subcommands {
     /// show reflog records.
     Show {
-        #[clap(default_value = "HEAD")]
-        ref_name: String,
+        /// Ref whose reflog to show (default: HEAD)
+        #[arg(conflicts_with = "all")]
+        ref_name: Option<String>,
+        /// Show the reflog of every ref that has one, HEAD first
+        #[arg(long)]
+        all: bool,
         #[arg(long = "pretty")]
         #[clap(default_value_t = FormatterKind::default())]
         pretty: FormatterKind,
@@ -165,6 +171,7 @@ pub async fn execute_safe(args: ReflogArgs, output: &OutputConfig) -> CliResult<
     match args.command.unwrap_or_else(default_reflog_command) {
         Subcommands::Show {
             ref_name,
+            all,
             pretty,
             since,
             until,
@@ -186,7 +193,7 @@ pub async fn execute_safe(args: ReflogArgs, output: &OutputConfig) -> CliResult<
                 stat,
                 no_abbrev,
             };
-            handle_show(&ref_name, options, output).await
+            handle_show(ref_name.as_deref(), all, options, output).await
         }
         Subcommands::Delete { selectors } => handle_delete(&selectors, output).await,
         Subcommands::Exists { ref_name } => handle_exists(&ref_name, output).await,
@@ -243,9 +250,20 @@ struct ReflogShowOptions {
     no_abbrev: bool,
 }
 
+/// A reflog entry selected by `show`: its index within its own ref's reflog
+/// and the name printed in its `<label>@{<index>}` selector.
+struct ShowEntry {
+    label: String,
+    ref_name: String,
+    index: usize,
+    log: Model,
+}
+
 #[derive(Debug, Serialize)]
 struct ReflogShowOutput {
-    ref_name: String,
+    /// The requested ref, or `null` for `--all`.
+    ref_name: Option<String>,
+    all: bool,
     pretty: String,
     count: usize,
     total_count: usize,
@@ -308,7 +326,8 @@ struct ReflogExistsOutput {
 }
 
 async fn handle_show(
-    ref_name: &str,
+    ref_name: Option<&str>,
+    all: bool,
     options: ReflogShowOptions,
     output: &OutputConfig,
 ) -> CliResult<()> {
@@ -335,15 +354,35 @@ async fn handle_show(
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
         })?;
 
-    let ref_name = parse_ref_name(ref_name).await;
-    let logs = Reflog::find_all(&db, &ref_name).await.map_err(|e| {
-        CliError::fatal(format!("failed to get reflog entries: {e}"))
-            .with_stable_code(StableErrorCode::IoReadFailed)
-    })?;
-    let total_count = logs.len();
+    // (selector label, full ref name) for each reflog to list. A single ref
+    // keeps the name as typed (`main@{0}`), like Git; `--all` uses full names.
+    let targets = if all {
+        let mut refs = list_reflog_refs(&db).await?;
+        if let Some(pos) = refs.iter().position(|name| name == HEAD) {
+            let head = refs.remove(pos);
+            refs.insert(0, head);
+        }
+        refs.into_iter().map(|name| (name.clone(), name)).collect()
+    } else {
+        let label = ref_name.unwrap_or(HEAD);
+        vec![(label.to_string(), parse_ref_name(label).await)]
+    };
 
-    // Preserve original indices before filtering
-    let logs_with_index: Vec<_> = logs.into_iter().enumerate().collect();
+    let mut entries = Vec::new();
+    for (label, full_name) in &targets {
+        let logs = Reflog::find_all(&db, full_name).await.map_err(|e| {
+            CliError::fatal(format!("failed to get reflog entries: {e}"))
+                .with_stable_code(StableErrorCode::IoReadFailed)
+        })?;
+        // Preserve original indices before filtering
+        entries.extend(logs.into_iter().enumerate().map(|(index, log)| ShowEntry {
+            label: label.clone(),
+            ref_name: full_name.clone(),
+            index,
+            log,
+        }));
+    }
+    let total_count = entries.len();
 
     // Apply filters
     let filter = ReflogFilter::new(
@@ -352,9 +391,9 @@ async fn handle_show(
         options.grep.clone(),
         options.author.clone(),
     );
-    let filtered_logs: Vec<_> = logs_with_index
+    let filtered_logs: Vec<_> = entries
         .into_iter()
-        .filter(|(_, log)| filter.passes(log))
+        .filter(|entry| filter.passes(&entry.log))
         .collect();
 
     // Apply number limit
@@ -362,7 +401,8 @@ async fn handle_show(
     let limited_logs = &filtered_logs[..filtered_logs.len().min(max_output)];
 
     if output.is_json() {
-        let structured = build_reflog_show_output(&ref_name, &options, total_count, limited_logs)?;
+        let requested = (!all).then(|| targets[0].1.clone());
+        let structured = build_reflog_show_output(requested, &options, total_count, limited_logs)?;
         return emit_json_data("reflog.show", &structured, output);
     }
 
@@ -382,18 +422,19 @@ async fn handle_show(
 }
 
 fn build_reflog_show_output(
-    ref_name: &str,
+    ref_name: Option<String>,
     options: &ReflogShowOptions,
     total_count: usize,
-    logs: &[(usize, Model)],
+    logs: &[ShowEntry],
 ) -> CliResult<ReflogShowOutput> {
     let entries = logs
         .iter()
-        .map(|(idx, log)| build_reflog_entry(ref_name, *idx, log, options))
+        .map(|entry| build_reflog_entry(&entry.ref_name, entry.index, &entry.log, options))
         .collect::<CliResult<Vec<_>>>()?;
 
     Ok(ReflogShowOutput {
-        ref_name: ref_name.to_string(),
+        all: ref_name.is_none(),
+        ref_name,
         pretty: options.pretty.to_string(),
         count: entries.len(),
         total_count,
@@ -576,6 +617,27 @@ fn map_reflog_error(error: ReflogError) -> CliError {
     }
 }
 
+/// Every ref that has at least one reflog entry, sorted by name.
+async fn list_reflog_refs<C: ConnectionTrait>(conn: &C) -> CliResult<Vec<String>> {
+    let rows = conn
+        .query_all(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT DISTINCT ref_name FROM reflog;".to_string(),
+        ))
+        .await
+        .map_err(|e| {
+            CliError::fatal(format!("failed to enumerate reflog refs: {e}"))
+                .with_stable_code(StableErrorCode::IoReadFailed)
+        })?;
+    let mut refs: Vec<String> = rows
+        .iter()
+        .filter_map(|row| row.try_get::<String>("", "ref_name").ok())
+        .collect();
+    refs.sort();
+    refs.dedup();
+    Ok(refs)
+}
+
 /// Phase A of `reflog expire`: resolve and validate the ref list with **no**
 /// writes, so an invalid/unknown ref aborts before any other ref is touched.
 async fn resolve_expire_refs<C: ConnectionTrait>(
@@ -583,23 +645,7 @@ async fn resolve_expire_refs<C: ConnectionTrait>(
     options: &ExpireCliOptions,
 ) -> CliResult<Vec<String>> {
     if options.all {
-        let rows = conn
-            .query_all(Statement::from_string(
-                DbBackend::Sqlite,
-                "SELECT DISTINCT ref_name FROM reflog;".to_string(),
-            ))
-            .await
-            .map_err(|e| {
-                CliError::fatal(format!("failed to enumerate reflog refs: {e}"))
-                    .with_stable_code(StableErrorCode::IoReadFailed)
-            })?;
-        let mut refs: Vec<String> = rows
-            .iter()
-            .filter_map(|row| row.try_get::<String>("", "ref_name").ok())
-            .collect();
-        refs.sort();
-        refs.dedup();
-        return Ok(refs);
+        return list_reflog_refs(conn).await;
     }
 
     if options.refs.is_empty() {
@@ -910,7 +956,7 @@ impl From<String> for FormatterKind {
 }
 
 struct ReflogFormatter<'a> {
-    logs: &'a [(usize, Model)],
+    logs: &'a [ShowEntry],
     kind: FormatterKind,
     patch: bool,
     stat: bool,
@@ -922,8 +968,9 @@ impl Display for ReflogFormatter<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let all = self.logs
             .iter()
-            .map(|(idx, log)| {
-                let head = format!("HEAD@{{{idx}}}");
+            .map(|entry| {
+                let log = &entry.log;
+                let head = format!("{}@{{{}}}", entry.label, entry.index);
                 let new_oid = if self.no_abbrev {
                     log.new_oid.as_str()
                 } else {
@@ -1170,6 +1217,7 @@ mod tests {
 
         if let Some(Subcommands::Show {
             ref_name,
+            all,
             pretty: _,
             since,
             until,
@@ -1181,7 +1229,8 @@ mod tests {
             no_abbrev: _,
         }) = args.command
         {
-            assert_eq!(ref_name, "HEAD");
+            assert_eq!(ref_name, None);
+            assert!(!all);
             assert_eq!(since.as_deref(), Some("2024-01-01"));
             assert_eq!(until.as_deref(), Some("2024-12-31"));
             assert_eq!(grep.as_deref(), Some("commit"));
@@ -8,7 +8,10 @@ use std::{
 
 use clap::Subcommand;
 use git_internal::hash::get_hash_kind;
-use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, TransactionTrait};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, TransactionTrait,
+};
 use serde::Serialize;
 
 use crate::{
@@ -713,13 +716,31 @@ async fn run_add_remote(
     Ok(RemoteOutput::Add { name, url })
 }
 
+/// `remote remove <name>`: drop the remote's config (and the upstream of every
+/// branch tracking it) together with its remote-tracking refs and cached
+/// remote HEAD, in one transaction.
 async fn run_remove_remote(name: String) -> Result<RemoteOutput, RemoteError> {
     ensure_remote_exists(&name).await?;
-    ConfigKv::remove_remote(&name)
-        .await
-        .map_err(|error| RemoteError::ConfigWrite {
-            detail: error.to_string(),
-        })?;
+    let db = get_db_conn_instance().await;
+    let txn_name = name.clone();
+    db.transaction::<_, (), DbErr>(move |txn| {
+        Box::pin(async move {
+            ConfigKv::remove_remote_with_conn(txn, &txn_name)
+                .await
+                .map_err(|e| DbErr::Custom(e.to_string()))?;
+            // Every row owned by the remote: `refs/remotes/<name>/*` branches
+            // and the `refs/remotes/<name>/HEAD` row.
+            reference::Entity::delete_many()
+                .filter(reference::Column::Remote.eq(txn_name))
+                .exec(txn)
+                .await?;
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| RemoteError::ConfigWrite {
+        detail: e.to_string(),
+    })?;
     Ok(RemoteOutput::Remove { name })
 }
 
@@ -732,8 +753,19 @@ async fn run_rename_remote(old: String, new: String) -> Result<RemoteOutput, Rem
         return Err(RemoteError::SshKeyNamespaceExists { name: new });
     }
 
+    let db = get_db_conn_instance().await;
+    let (txn_old, txn_new) = (old.clone(), new.clone());
     let new_for_error = new.clone();
-    ConfigKv::rename_remote(&old, &new).await.map_err(|error| {
+    db.transaction::<_, (), DbErr>(move |txn| {
+        Box::pin(async move {
+            ConfigKv::rename_remote_with_conn(txn, &txn_old, &txn_new)
+                .await
+                .map_err(|e| DbErr::Custom(e.to_string()))?;
+            rename_remote_refs(txn, &txn_old, &txn_new).await
+        })
+    })
+    .await
+    .map_err(|error| {
         let detail = error.to_string();
         if detail.contains("SSH key namespace for remote") {
             RemoteError::SshKeyNamespaceExists {
@@ -749,6 +781,31 @@ async fn run_rename_remote(old: String, new: String) -> Result<RemoteOutput, Rem
     })
 }
 
+/// Move the remote's ref rows to the new name: `refs/remotes/<old>/<b>` becomes
+/// `refs/remotes/<new>/<b>`, and the cached remote HEAD (whose name is the
+/// branch it points at) is re-homed as-is.
+async fn rename_remote_refs<C: ConnectionTrait>(db: &C, old: &str, new: &str) -> Result<(), DbErr> {
+    let old_prefix = format!("refs/remotes/{old}/");
+    let rows = reference::Entity::find()
+        .filter(reference::Column::Remote.eq(old))
+        .all(db)
+        .await?;
+    for row in rows {
+        let renamed = row
+            .name
+            .as_deref()
+            .and_then(|name| name.strip_prefix(&old_prefix))
+            .map(|branch| format!("refs/remotes/{new}/{branch}"));
+        let mut active: reference::ActiveModel = row.into();
+        active.remote = Set(Some(new.to_owned()));
+        if let Some(name) = renamed {
+            active.name = Set(Some(name));
+        }
+        active.update(db).await?;
+    }
+    Ok(())
+}
+
 async fn run_list_remotes(verbose: bool) -> Result<RemoteOutput, RemoteError> {
     let remote_names = list_remote_names().await?;
 
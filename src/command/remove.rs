@@ -1,6 +1,6 @@
 //! Removes paths from the index and working tree according to pathspecs, supporting recursive deletion and cache-only modes.
 
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
 
 use clap::Parser;
 use colored::Colorize;
@@ -11,7 +11,8 @@ use tokio::fs;
 use crate::{
     command::status::{changes_to_be_committed_safe, changes_to_be_staged},
     utils::{
-        error::{CliError, CliResult},
+        error::{CliError, CliResult, StableErrorCode},
+        index_marks::{self, IndexMark},
         output::{OutputConfig, emit_json_data},
         path,
         path_ext::PathExt,
@@ -36,7 +37,7 @@ EXAMPLES:
     libra rm -f conflicted.txt              Force removal even if the file has unstaged changes
     libra rm --dry-run --cached '*.tmp'     Preview what would be untracked without applying
     libra rm --pathspec-from-file=todo.txt  Read NUL- or newline-separated pathspecs from a file
-    libra rm --sparse stale.txt             Accept Git's sparse-checkout flag as a no-op
+    libra rm --sparse hidden/old.txt        Remove an entry outside the sparse checkout
     libra rm --json stale.txt               Structured JSON output for agents";
 
 #[derive(Parser, Debug, Clone)]
@@ -66,9 +67,8 @@ pub struct RemoveArgs {
     #[clap(long = "pathspec-file-nul")]
     pub pathspec_file_nul: bool,
 
-    /// Accept Git's `--sparse` flag. Git uses it to allow removing index
-    /// entries outside the sparse-checkout cone; Libra has no sparse-checkout
-    /// state (every path is always "in cone"), so this is a no-op.
+    /// Allow removing index entries outside the sparse checkout. Without it,
+    /// a pathspec that only matches such entries is refused, as in Git.
     #[clap(long)]
     pub sparse: bool,
 }
@@ -208,6 +208,13 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
         return Err(CliError::fatal(error_msg));
     }
 
+    // Skip-worktree entries (left out by the sparse checkout) are only removed
+    // with `--sparse`; they have no file to delete.
+    let skip_worktree = index_marks::active(&index, IndexMark::SkipWorktree);
+    let outside_cone = |relative: &str| skip_worktree.contains(relative);
+    let mut outside_sparse = Vec::new();
+    let mut index_only = HashSet::new();
+
     // Build the remove list from input paths, handling tracked files and optionally ignoring untracked paths based on the `ignore_unmatch` flag.
     for path_str in pathspecs.iter() {
         let path = PathBuf::from(path_str);
@@ -223,11 +230,24 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
             } else {
                 format!("{}{}", relative_path, std::path::MAIN_SEPARATOR)
             };
+            let mut matched_in_cone = false;
+            let mut matched_outside = false;
             for entry in entries.iter() {
                 if entry.name.starts_with(&dir_prefix) {
+                    if outside_cone(&entry.name) {
+                        matched_outside = true;
+                        if !args.sparse {
+                            continue;
+                        }
+                        index_only.insert(entry.name.clone());
+                    }
+                    matched_in_cone = true;
                     remove_list.push(entry.name.clone());
                 }
             }
+            if matched_outside && !matched_in_cone {
+                outside_sparse.push(path_str.clone());
+            }
             // For recursive removal, add the directory itself to be removed from filesystem
             if args.recursive && !args.cached {
                 remove_dir_list.push(path_str.clone());
@@ -236,6 +256,13 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
             // file
             // - If tracked, would be removed from index
             if index.tracked(&relative_path, 0) {
+                if outside_cone(&relative_path) {
+                    if !args.sparse {
+                        outside_sparse.push(path_str.clone());
+                        continue;
+                    }
+                    index_only.insert(path_str.clone());
+                }
                 remove_list.push(path_str.clone());
             } else if !args.ignore_unmatch {
                 // If ignore_unmatch is false, error if the pathspec does not match any tracked files (consistent with Git behavior).
@@ -247,6 +274,20 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
         }
     }
 
+    if !outside_sparse.is_empty() {
+        let list = outside_sparse
+            .iter()
+            .map(|path| format!("\t{path}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(CliError::failure(format!(
+            "the following paths and/or pathspecs matched paths that exist outside of your sparse-checkout definition, so will not be updated in the index:\n{list}"
+        ))
+        .with_stable_code(StableErrorCode::RepoStateInvalid)
+        .with_hint("use --sparse to remove them from the index anyway.")
+        .with_hint("or widen the sparse checkout with 'libra sparse-checkout add'."));
+    }
+
     // Check all input paths for any uncommitted changes.
     let mut diff_status = DiffStatus::default();
     if !args.force {
@@ -355,7 +396,7 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
         .map(|path| RemovePathOutput {
             path: path.clone(),
             removed_from_index: !args.dry_run,
-            removed_from_disk: !args.cached && !args.dry_run,
+            removed_from_disk: !args.cached && !args.dry_run && !index_only.contains(path),
         })
         .collect();
     let directories = remove_dir_list
@@ -374,6 +415,9 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
     }
     if !args.cached && !args.dry_run {
         for path_str in remove_list {
+            if index_only.contains(&path_str) {
+                continue;
+            }
             let path = PathBuf::from(&path_str);
             if let Err(e) = fs::remove_file(&path).await {
                 return Err(CliError::failure(format!(
@@ -385,6 +429,10 @@ async fn run_remove(args: RemoveArgs) -> CliResult<RemoveOutput> {
         }
         for path_str in remove_dir_list {
             let path = PathBuf::from(&path_str);
+            // A directory wholly outside the sparse checkout is not on disk.
+            if sparse.is_some() && !path.exists() {
+                continue;
+            }
             if args.recursive {
                 if let Err(e) = fs::remove_dir_all(&path).await {
                     return Err(CliError::failure(format!(
@@ -1,15 +1,18 @@
 //! `libra rerere` — REuse REcorded REsolution. Records how a merge conflict was
-//! resolved and replays that resolution when the identical conflict reappears.
+//! resolved and replays that resolution when the same conflict reappears.
 //!
-//! Storage lives under `.libra/rerere/`:
-//! - `<id>/preimage`  — the conflicted file content (with markers) as first seen
+//! Storage lives under `.libra/rr-cache/`:
+//! - `<id>/preimage`  — the conflicted file content, normalised, as first seen
 //! - `<id>/postimage` — the resolved content once the user fixes it
 //! - `MERGE_RR`       — `id<TAB>path` lines for conflicts currently being tracked
 //!
-//! `<id>` is the SHA-256 of the conflicted file's bytes. This version matches a
-//! conflict only when the whole conflicted file is byte-identical to a recorded
-//! preimage (Git's per-hunk normalisation / ours-theirs-swap independence remain
-//! a documented follow-up).
+//! Conflicts are normalised the way Git's rerere does it: marker labels and any
+//! diff3 base section are dropped, and the two sides of each hunk are put in
+//! byte order. `<id>` is the SHA-256 of the normalised hunks alone, so a
+//! conflict matches across different commit labels, swapped sides, and changed
+//! surrounding context. A recorded resolution is replayed through a three-way
+//! merge of preimage → postimage onto the current file, which carries it over
+//! when only the context moved.
 //!
 //! When `rerere.enabled` is set, [`auto_update`] is invoked automatically by the
 //! merge / rebase / cherry-pick sequencers (at both conflict and resolution
@@ -18,6 +21,7 @@
 //! (the default) those hooks are complete no-ops.
 
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -35,9 +39,8 @@ use crate::{
     },
 };
 
-const CONFLICT_START: &str = "<<<<<<<";
-const CONFLICT_SEP: &str = "=======";
-const CONFLICT_END: &str = ">>>>>>>";
+/// Shortest run of marker characters Git recognises (`<<<<<<<`).
+const MIN_MARKER_SIZE: usize = 7;
 
 pub const RERERE_EXAMPLES: &str = "\
 EXAMPLES:
@@ -187,27 +190,33 @@ async fn apply(rr_dir: &Path, stage_replayed: bool) -> CliResult<()> {
         let Ok(content) = fs::read(&absolute) else {
             continue;
         };
-        if !is_conflicted(&content) {
+        let Some(conflict) = normalize_conflict(&content) else {
             continue;
-        }
-        let id = conflict_id(&content);
+        };
+        let id = conflict.id;
+        let preimage = entry_path(rr_dir, &id, "preimage");
         let postimage = entry_path(rr_dir, &id, "postimage");
         // Replay only when BOTH the recorded preimage and postimage exist — a
         // defensive guard so a stray postimage can never overwrite a file.
-        if postimage.exists() && entry_path(rr_dir, &id, "preimage").exists() {
-            let resolution = fs::read(&postimage).map_err(read_err)?;
-            fs::write(&absolute, &resolution).map_err(write_err)?;
-            println!("Resolved '{path}' using a previously recorded resolution.");
-            if stage_replayed {
-                stage_path(path).await?;
-            }
-        } else {
-            write_entry(rr_dir, &id, "preimage", &content)?;
-            if !merge_rr.iter().any(|(p, _)| p == path) {
-                merge_rr.push((path.to_string(), id));
+        if postimage.exists() && preimage.exists() {
+            let preimage = fs::read(&preimage).map_err(read_err)?;
+            let postimage = fs::read(&postimage).map_err(read_err)?;
+            if let Some(resolution) = replay(&preimage, &postimage, &conflict.image) {
+                fs::write(&absolute, &resolution).map_err(write_err)?;
+                println!("Resolved '{path}' using a previously recorded resolution.");
+                if stage_replayed {
+                    stage_path(path).await?;
+                }
+                continue;
             }
-            println!("Recorded preimage for '{path}'.");
+            // The context moved too far for the recording to apply: track the
+            // conflict afresh so the new resolution replaces the old one.
         }
+        write_entry(rr_dir, &id, "preimage", &conflict.image)?;
+        if !merge_rr.iter().any(|(p, _)| p == path) {
+            merge_rr.push((path.to_string(), id));
+        }
+        println!("Recorded preimage for '{path}'.");
     }
 
     // Only persist MERGE_RR when there is something to track, or a file already
@@ -240,6 +249,8 @@ async fn stage_path(path: &str) -> CliResult<()> {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     };
     crate::command::add::run_add(&args).await?;
 
@@ -280,7 +291,11 @@ fn diff(rr_dir: &Path) -> CliResult<()> {
         let Ok(preimage) = fs::read_to_string(entry_path(rr_dir, &id, "preimage")) else {
             continue;
         };
+        // Compare like with like: a still-conflicted file is normalised the
+        // same way its preimage was, so relabelled markers are not reported.
         let current_bytes = read_or_empty(&workdir.join(&path))?;
+        let current_bytes =
+            normalize_conflict(&current_bytes).map_or(current_bytes, |conflict| conflict.image);
         let current = String::from_utf8_lossy(&current_bytes);
         let patch = diffy::create_patch(&preimage, &current);
         println!("* {path}");
@@ -369,25 +384,121 @@ fn gc(rr_dir: &Path) -> CliResult<()> {
 
 // ── helpers ──
 
-/// Whether `content` contains a conflict marker.
+/// Whether `content` contains at least one complete conflict hunk.
 fn is_conflicted(content: &[u8]) -> bool {
-    content
-        .split(|&b| b == b'\n')
-        .any(|line| starts_with(line, CONFLICT_START))
-        && content
-            .split(|&b| b == b'\n')
-            .any(|line| starts_with(line, CONFLICT_SEP) || starts_with(line, CONFLICT_END))
+    normalize_conflict(content).is_some()
+}
+
+/// A conflicted file in rerere's canonical form.
+struct NormalizedConflict {
+    /// SHA-256 over the normalised hunks only (not the surrounding context).
+    id: String,
+    /// The whole file with every hunk rewritten as label-less
+    /// `<<<<<<<` / `=======` / `>>>>>>>` markers around the sorted sides.
+    image: Vec<u8>,
 }
 
-fn starts_with(line: &[u8], prefix: &str) -> bool {
-    line.starts_with(prefix.as_bytes())
+#[derive(PartialEq)]
+enum HunkSection {
+    Ours,
+    Base,
+    Theirs,
 }
 
-/// The cache id for a conflicted file: the SHA-256 of its bytes.
-fn conflict_id(content: &[u8]) -> String {
+/// Normalise `content` Git-style, or `None` when it holds no complete
+/// conflict hunk. Marker labels and diff3 base sections are dropped and the
+/// two sides of each hunk are sorted, so the same conflict produced by a merge,
+/// a rebase (which swaps ours and theirs), or a differently-labelled commit
+/// maps to one id. An unterminated hunk is kept verbatim.
+fn normalize_conflict(content: &[u8]) -> Option<NormalizedConflict> {
     let mut hasher = Sha256::new();
-    hasher.update(content);
-    hex::encode(hasher.finalize())
+    let mut image = Vec::with_capacity(content.len());
+    let mut hunks = 0usize;
+    let mut lines = content.split_inclusive(|&b| b == b'\n');
+    while let Some(start) = lines.next() {
+        let Some(size) = marker_size(start, b'<') else {
+            image.extend_from_slice(start);
+            continue;
+        };
+        let mut raw = start.to_vec();
+        let (mut ours, mut theirs) = (Vec::new(), Vec::new());
+        let mut section = HunkSection::Ours;
+        let mut closed = false;
+        for line in lines.by_ref() {
+            raw.extend_from_slice(line);
+            if section == HunkSection::Ours && marker_size(line, b'|') == Some(size) {
+                section = HunkSection::Base;
+            } else if section != HunkSection::Theirs && is_separator(line, size) {
+                section = HunkSection::Theirs;
+            } else if section == HunkSection::Theirs && marker_size(line, b'>') == Some(size) {
+                closed = true;
+                break;
+            } else {
+                match section {
+                    HunkSection::Ours => ours.extend_from_slice(line),
+                    HunkSection::Base => {}
+                    HunkSection::Theirs => theirs.extend_from_slice(line),
+                }
+            }
+        }
+        if !closed {
+            image.extend_from_slice(&raw);
+            break;
+        }
+        if theirs < ours {
+            std::mem::swap(&mut ours, &mut theirs);
+        }
+        hasher.update(&ours);
+        hasher.update([0]);
+        hasher.update(&theirs);
+        hasher.update([0]);
+
+        let eol: &[u8] = if start.ends_with(b"\r\n") {
+            b"\r\n"
+        } else {
+            b"\n"
+        };
+        for (marker, side) in [(b'<', &ours), (b'=', &theirs)] {
+            image.extend(std::iter::repeat_n(marker, size));
+            image.extend_from_slice(eol);
+            image.extend_from_slice(side);
+        }
+        image.extend(std::iter::repeat_n(b'>', size));
+        image.extend_from_slice(eol);
+        hunks += 1;
+    }
+    (hunks > 0).then(|| NormalizedConflict {
+        id: hex::encode(hasher.finalize()),
+        image,
+    })
+}
+
+/// Length of the marker run that opens `line` (`<<<<<<< label`), when it is
+/// at least [`MIN_MARKER_SIZE`] long and followed by a label or line end.
+fn marker_size(line: &[u8], marker: u8) -> Option<usize> {
+    let size = line.iter().take_while(|&&b| b == marker).count();
+    let rest = &line[size..];
+    (size >= MIN_MARKER_SIZE
+        && (rest.is_empty() || matches!(rest[0], b' ' | b'\t' | b'\r' | b'\n')))
+    .then_some(size)
+}
+
+/// Whether `line` is the bare `=======` separator of a hunk opened with
+/// `size` marker characters.
+fn is_separator(line: &[u8], size: usize) -> bool {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    line.len() == size && line.iter().all(|&b| b == b'=')
+}
+
+/// Carry a recorded resolution over to `thisimage`: verbatim when the
+/// normalised file is unchanged, otherwise by a three-way merge of the
+/// preimage → postimage edit onto it. `None` when the two clash.
+fn replay(preimage: &[u8], postimage: &[u8], thisimage: &[u8]) -> Option<Vec<u8>> {
+    if thisimage == preimage {
+        return Some(postimage.to_vec());
+    }
+    diffy::merge_bytes(preimage, postimage, thisimage).ok()
 }
 
 fn entry_path(rr_dir: &Path, id: &str, name: &str) -> PathBuf {
@@ -460,7 +571,60 @@ fn write_merge_rr(rr_dir: &Path, entries: &[(String, String)]) -> CliResult<()>
 
 fn rerere_dir() -> CliResult<PathBuf> {
     let storage = util::try_get_storage_path(None).map_err(|_| CliError::repo_not_found())?;
-    Ok(storage.join("rerere"))
+    let rr_dir = storage.join("rr-cache");
+    let legacy = storage.join("rerere");
+    if legacy.is_dir() {
+        migrate_legacy_dir(&legacy, &rr_dir)?;
+    }
+    Ok(rr_dir)
+}
+
+/// Move recordings from the old `.libra/rerere/` layout, keyed by the SHA-256
+/// of the raw conflicted file, into `rr-cache`: each preimage is normalised to
+/// find its new id, its postimage moves with it, and `MERGE_RR` is rewritten
+/// against the new ids. Entries whose preimage no longer reads as a conflict
+/// are dropped along with the old directory.
+fn migrate_legacy_dir(legacy: &Path, rr_dir: &Path) -> CliResult<()> {
+    let mut new_ids = HashMap::new();
+    for entry in fs::read_dir(legacy).map_err(read_err)? {
+        let dir = entry.map_err(read_err)?.path();
+        let Some(old_id) = dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !dir.is_dir() || !is_valid_id(old_id) {
+            continue;
+        }
+        let Ok(preimage) = fs::read(dir.join("preimage")) else {
+            continue;
+        };
+        let Some(conflict) = normalize_conflict(&preimage) else {
+            continue;
+        };
+        // An existing rr-cache recording of the same conflict wins.
+        if !entry_path(rr_dir, &conflict.id, "preimage").exists() {
+            write_entry(rr_dir, &conflict.id, "preimage", &conflict.image)?;
+            let postimage = dir.join("postimage");
+            if postimage.exists() {
+                fs::rename(&postimage, entry_path(rr_dir, &conflict.id, "postimage"))
+                    .map_err(write_err)?;
+            }
+        }
+        new_ids.insert(old_id.to_string(), conflict.id);
+    }
+
+    let mut merge_rr = read_merge_rr(rr_dir)?;
+    let before = merge_rr.len();
+    for (path, old_id) in read_merge_rr(legacy)? {
+        if let Some(id) = new_ids.get(&old_id)
+            && !merge_rr.iter().any(|(tracked, _)| *tracked == path)
+        {
+            merge_rr.push((path, id.clone()));
+        }
+    }
+    if merge_rr.len() != before {
+        write_merge_rr(rr_dir, &merge_rr)?;
+    }
+    fs::remove_dir_all(legacy).map_err(write_err)
 }
 
 fn load_index() -> CliResult<Index> {
@@ -498,11 +662,79 @@ mod tests {
 
     #[test]
     fn conflict_id_is_stable_and_content_addressed() {
-        let a = conflict_id(b"<<<<<<<\nx\n=======\ny\n>>>>>>>\n");
-        let b = conflict_id(b"<<<<<<<\nx\n=======\ny\n>>>>>>>\n");
-        let c = conflict_id(b"<<<<<<<\nx\n=======\nz\n>>>>>>>\n");
+        let id = |content: &[u8]| normalize_conflict(content).unwrap().id;
+        let a = id(b"<<<<<<<\nx\n=======\ny\n>>>>>>>\n");
+        let b = id(b"<<<<<<<\nx\n=======\ny\n>>>>>>>\n");
+        let c = id(b"<<<<<<<\nx\n=======\nz\n>>>>>>>\n");
         assert_eq!(a, b);
         assert_ne!(a, c);
         assert_eq!(a.len(), 64);
     }
+
+    #[test]
+    fn conflict_id_ignores_labels_side_order_base_and_context() {
+        let plain = normalize_conflict(b"<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> abc1234\n").unwrap();
+        let swapped = normalize_conflict(
+            b"top\n<<<<<<< HEAD\ny\n||||||| base\nw\n=======\nx\n>>>>>>> def5678 (msg)\n",
+        )
+        .unwrap();
+        assert_eq!(plain.id, swapped.id);
+        assert_eq!(plain.image, b"<<<<<<<\nx\n=======\ny\n>>>>>>>\n");
+        assert_eq!(swapped.image, b"top\n<<<<<<<\nx\n=======\ny\n>>>>>>>\n");
+    }
+
+    #[test]
+    fn normalize_keeps_longer_markers_and_unterminated_hunks() {
+        let long = normalize_conflict(b"<<<<<<<< HEAD\n<<<<<<< ours\n========\nb\n>>>>>>>> abc\n")
+            .unwrap();
+        assert_eq!(
+            long.image,
+            b"<<<<<<<<\n<<<<<<< ours\n========\nb\n>>>>>>>>\n"
+        );
+        assert!(normalize_conflict(b"<<<<<<< HEAD\na\n=======\n").is_none());
+    }
+
+    #[test]
+    fn legacy_rerere_dir_moves_into_rr_cache_under_normalised_ids() {
+        let temp = tempfile::tempdir().unwrap();
+        let legacy = temp.path().join("rerere");
+        let rr_dir = temp.path().join("rr-cache");
+        let conflicted = b"a\n<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> topic\n";
+        let old_id = hex::encode(Sha256::digest(conflicted));
+        write_entry(&legacy, &old_id, "preimage", conflicted).unwrap();
+        write_entry(&legacy, &old_id, "postimage", b"a\nxy\n").unwrap();
+        let stray = "0".repeat(64);
+        write_entry(&legacy, &stray, "preimage", b"no markers\n").unwrap();
+        write_merge_rr(&legacy, &[("f.txt".to_string(), old_id)]).unwrap();
+
+        migrate_legacy_dir(&legacy, &rr_dir).unwrap();
+
+        let conflict = normalize_conflict(conflicted).unwrap();
+        assert!(!legacy.exists());
+        assert_eq!(
+            fs::read(entry_path(&rr_dir, &conflict.id, "preimage")).unwrap(),
+            conflict.image
+        );
+        assert_eq!(
+            fs::read(entry_path(&rr_dir, &conflict.id, "postimage")).unwrap(),
+            b"a\nxy\n"
+        );
+        assert!(!rr_dir.join(&stray).exists());
+        assert_eq!(
+            read_merge_rr(&rr_dir).unwrap(),
+            vec![("f.txt".to_string(), conflict.id)]
+        );
+    }
+
+    #[test]
+    fn replay_carries_resolution_across_changed_context() {
+        let preimage = b"a\nb\nc\nd\n<<<<<<<\nx\n=======\ny\n>>>>>>>\n";
+        let postimage = b"a\nb\nc\nd\nxy\n";
+        let thisimage = b"A\nb\nc\nd\n<<<<<<<\nx\n=======\ny\n>>>>>>>\n";
+        assert_eq!(
+            replay(preimage, postimage, thisimage).unwrap(),
+            b"A\nb\nc\nd\nxy\n"
+        );
+        assert_eq!(replay(preimage, postimage, preimage).unwrap(), postimage);
+    }
 }
@@ -1,7 +1,7 @@
-//! Reset command covering soft/mixed/hard behaviors to move HEAD and align the index or working tree to a chosen commit.
+//! Reset command covering soft/mixed/hard/merge/keep behaviors to move HEAD and align the index or working tree to a chosen commit.
 
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashMap, HashSet},
     fs, io,
     io::{BufRead, Read},
     path::{Path, PathBuf},
@@ -12,13 +12,16 @@ use git_internal::{
     hash::ObjectHash,
     internal::{
         index::{Index, IndexEntry},
-        object::{commit::Commit, tree::Tree},
+        object::{
+            commit::Commit,
+            tree::{Tree, TreeItemMode},
+        },
     },
 };
 use serde::Serialize;
 
 use crate::{
-    command::load_object,
+    command::{calc_file_blob_hash_with_attributes, load_object, restore, sparse_checkout},
     common_utils::parse_commit_msg,
     internal::{
         branch::{self, Branch},
@@ -28,11 +31,13 @@ use crate::{
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode, emit_warning},
+        gitattributes::{self, Attributes},
         object_ext::{BlobExt, TreeExt},
         output::{OutputConfig, emit_json_data},
         path,
+        sparse_checkout::{SparseCheckout, refresh_skip_worktree},
         text::short_display_hash,
-        util,
+        util, worktree,
     },
 };
 
@@ -41,6 +46,8 @@ EXAMPLES:
     libra reset HEAD~1                    Move HEAD and reset index to the previous commit
     libra reset --soft HEAD~2             Move HEAD only, keep index and worktree
     libra reset --hard main               Reset HEAD, index, and worktree to branch 'main'
+    libra reset --merge ORIG_HEAD         Like --hard, but keep unstaged edits the reset does not touch
+    libra reset --keep HEAD~1             Like --hard, but refuse if a local edit would be lost
     libra reset HEAD -- src/lib.rs        Unstage a path back to HEAD
     libra reset --pathspec-from-file=paths.txt   Unstage paths read from a file ('-' for stdin)
     libra reset --json --hard HEAD~1      Structured JSON output for agents";
@@ -64,6 +71,16 @@ pub struct ResetArgs {
     #[clap(long, group = "mode")]
     pub hard: bool,
 
+    /// Merge reset: like --hard, but keep unstaged changes in files the reset
+    /// does not touch
+    #[clap(long, group = "mode")]
+    pub merge: bool,
+
+    /// Keep reset: like --hard, but abort if a file with local changes differs
+    /// between HEAD and the target
+    #[clap(long, group = "mode")]
+    pub keep: bool,
+
     /// Pathspecs to reset specific files
     #[clap(value_name = "PATH")]
     pub pathspecs: Vec<String>,
@@ -90,6 +107,8 @@ enum ResetMode {
     Soft,
     Mixed,
     Hard,
+    Merge,
+    Keep,
 }
 
 impl ResetMode {
@@ -98,6 +117,8 @@ impl ResetMode {
             Self::Soft => "soft",
             Self::Mixed => "mixed",
             Self::Hard => "hard",
+            Self::Merge => "merge",
+            Self::Keep => "keep",
         }
     }
 }
@@ -131,6 +152,8 @@ pub struct ResetOutput {
 /// - Soft: Only moves HEAD pointer
 /// - Mixed: Moves HEAD and resets index (default)
 /// - Hard: Moves HEAD, resets index and working directory
+/// - Merge/Keep: Like hard, but preserve local changes (refusing when they
+///   would be overwritten)
 pub async fn execute(args: ResetArgs) {
     if let Err(e) = execute_safe(args, &OutputConfig::default()).await {
         e.print_stderr();
@@ -144,6 +167,8 @@ pub async fn execute(args: ResetArgs) {
 /// - Moves HEAD/current branch to the resolved target commit.
 /// - In mixed mode, rewrites the index from the target tree or pathspecs.
 /// - In hard mode, rewrites both the index and working tree.
+/// - In merge/keep mode, rewrites the index and only the working-tree files
+///   that differ between HEAD and the target.
 /// - Emits warnings for recoverable filesystem cleanup issues.
 ///
 /// # Errors
@@ -210,8 +235,8 @@ enum ResetError {
     #[error("pathspec '{0}' is not compatible with --soft reset")]
     PathspecWithSoft(String),
 
-    #[error("Cannot do hard reset with paths.")]
-    PathspecWithHard,
+    #[error("Cannot do {0} reset with paths.")]
+    PathspecWithWorktreeReset(&'static str),
 
     #[error("pathspec '{0}' did not match any file(s) known to libra")]
     PathspecNotMatched(String),
@@ -232,6 +257,16 @@ enum ResetError {
     /// Refused to reset onto a Libra-managed locked branch (`intent`,
     /// `traces`, …). These refs hold AI-agent state that the user
     /// should not be able to overwrite by `reset`.
+    /// `--merge` / `--keep` found local changes the reset would overwrite.
+    #[error(
+        "local changes to the following files would be overwritten by reset --{mode}: {}",
+        .paths.join(", ")
+    )]
+    LocalChangesWouldBeOverwritten {
+        mode: &'static str,
+        paths: Vec<String>,
+    },
+
     #[error("refusing to reset to locked branch '{0}'")]
     LockedTarget(String),
 
@@ -265,11 +300,14 @@ impl ResetError {
             Self::RevisionCorrupt(_) => StableErrorCode::RepoCorrupt,
             Self::InvalidPathspecEncoding(_) => StableErrorCode::CliInvalidArguments,
             Self::PathspecWithSoft(_) => StableErrorCode::CliInvalidArguments,
-            Self::PathspecWithHard => StableErrorCode::CliInvalidArguments,
+            Self::PathspecWithWorktreeReset(_) => StableErrorCode::CliInvalidArguments,
             Self::PathspecNotMatched(_) => StableErrorCode::CliInvalidTarget,
             Self::PathspecSourceConflict => StableErrorCode::CliInvalidArguments,
             Self::PathspecOutsideWorkdir(_) => StableErrorCode::CliInvalidArguments,
             Self::PathspecFileRead { .. } => StableErrorCode::IoReadFailed,
+            Self::LocalChangesWouldBeOverwritten { .. } => {
+                StableErrorCode::ConflictOperationBlocked
+            }
             Self::LockedTarget(_) => StableErrorCode::CliInvalidTarget,
             Self::LockedCurrentBranch(_) => StableErrorCode::ConflictOperationBlocked,
             Self::Rollback { primary, .. } => primary.stable_code(),
@@ -293,8 +331,8 @@ impl ResetError {
             Self::PathspecWithSoft(_) => {
                 Some("--soft only moves HEAD; use --mixed to reset index for specific paths.")
             }
-            Self::PathspecWithHard => Some(
-                "--hard updates the working tree; omit pathspecs or use --mixed for specific paths.",
+            Self::PathspecWithWorktreeReset(_) => Some(
+                "--hard, --merge and --keep update the working tree; omit pathspecs or use --mixed for specific paths.",
             ),
             Self::PathspecNotMatched(_) => Some("check the path and try again."),
             Self::PathspecSourceConflict => Some(
@@ -306,6 +344,9 @@ impl ResetError {
             Self::PathspecFileRead { .. } => {
                 Some("check that the pathspec file exists and is readable.")
             }
+            Self::LocalChangesWouldBeOverwritten { .. } => {
+                Some("commit or stash your changes, or use --hard to discard them.")
+            }
             Self::LockedTarget(_) => Some(
                 "Libra-managed branches like 'intent' and 'traces' cannot be used as reset targets",
             ),
@@ -327,7 +368,7 @@ impl ResetError {
     fn is_command_usage(&self) -> bool {
         match self {
             Self::PathspecWithSoft(_)
-            | Self::PathspecWithHard
+            | Self::PathspecWithWorktreeReset(_)
             | Self::PathspecSourceConflict
             | Self::PathspecOutsideWorkdir(_) => true,
             Self::Rollback { primary, .. } => primary.is_command_usage(),
@@ -405,6 +446,10 @@ async fn run_reset(args: ResetArgs) -> Result<ResetExecution, ResetError> {
         ResetMode::Soft
     } else if args.hard {
         ResetMode::Hard
+    } else if args.merge {
+        ResetMode::Merge
+    } else if args.keep {
+        ResetMode::Keep
     } else {
         ResetMode::Mixed
     };
@@ -420,8 +465,8 @@ async fn run_reset(args: ResetArgs) -> Result<ResetExecution, ResetError> {
         if matches!(mode, ResetMode::Soft) {
             return Err(ResetError::PathspecWithSoft(effective_pathspecs.join(" ")));
         }
-        if matches!(mode, ResetMode::Hard) {
-            return Err(ResetError::PathspecWithHard);
+        if matches!(mode, ResetMode::Hard | ResetMode::Merge | ResetMode::Keep) {
+            return Err(ResetError::PathspecWithWorktreeReset(mode.as_str()));
         }
 
         let target_commit_id = resolve_commit(&args.target).await?;
@@ -667,17 +712,36 @@ async fn perform_reset(
     } else {
         HashSet::new()
     };
+    // `--merge` / `--keep` decide which files to rewrite (or refuse) before
+    // anything is touched, so a refusal leaves the checkout as it was.
+    let selective_paths = if matches!(mode, ResetMode::Merge | ResetMode::Keep) {
+        plan_selective_reset(mode, &old_oid, &target_commit_id)?
+    } else {
+        Vec::new()
+    };
+    // Like Git, every commit-level reset records the pre-reset tip so
+    // `reset --hard ORIG_HEAD` can undo it.
+    Head::record_orig_head(&old_oid)
+        .map_err(|error| ResetError::HeadUpdate(format!("failed to write ORIG_HEAD: {error}")))?;
     // INVARIANT: apply index/worktree changes before moving HEAD. If a
     // filesystem write fails, rollback can still restore the old index/worktree
     // while refs continue to point at the previous commit.
-    let stats =
-        match apply_reset_side_effects(mode, &target_commit_id, &previously_tracked_paths).await {
-            Ok(stats) => stats,
-            Err(error) => {
-                let rollback = rollback_reset_side_effects(mode, &old_oid, &target_commit_id).await;
-                return Err(merge_reset_failure(error, rollback));
-            }
-        };
+    let stats = match apply_reset_side_effects(
+        mode,
+        &target_commit_id,
+        &previously_tracked_paths,
+        &selective_paths,
+    )
+    .await
+    {
+        Ok(stats) => stats,
+        Err(error) => {
+            let rollback =
+                rollback_reset_side_effects(mode, &old_oid, &target_commit_id, &selective_paths)
+                    .await;
+            return Err(merge_reset_failure(error, rollback));
+        }
+    };
 
     if let Some(current_head_state) = current_head_state
         && let Err(error) = update_reset_reference(
@@ -691,7 +755,8 @@ async fn perform_reset(
         // INVARIANT: if the final ref move fails after side effects, restore the
         // index/worktree to match the old commit so the visible checkout does
         // not diverge from HEAD.
-        let rollback = rollback_reset_side_effects(mode, &old_oid, &target_commit_id).await;
+        let rollback =
+            rollback_reset_side_effects(mode, &old_oid, &target_commit_id, &selective_paths).await;
         return Err(merge_reset_failure(error, rollback));
     }
 
@@ -702,6 +767,7 @@ async fn apply_reset_side_effects(
     mode: ResetMode,
     target_commit_id: &ObjectHash,
     previously_tracked_paths: &HashSet<PathBuf>,
+    selective_paths: &[PathBuf],
 ) -> Result<ResetStats, ResetError> {
     let mut stats = ResetStats::default();
     match mode {
@@ -717,6 +783,15 @@ async fn apply_reset_side_effects(
             stats.files_restored = worktree_stats.files_restored;
             stats.warnings = worktree_stats.warnings;
         }
+        ResetMode::Merge | ResetMode::Keep => {
+            reset_index_to_commit_typed(target_commit_id)?;
+            stats.files_restored =
+                reset_working_paths_to_commit(target_commit_id, selective_paths).await?;
+        }
+    }
+    // The rewritten index needs the sparse checkout's skip-worktree marks.
+    if !matches!(mode, ResetMode::Soft) {
+        refresh_skip_worktree().map_err(|e| ResetError::WorktreeRestore(e.to_string()))?;
     }
     Ok(stats)
 }
@@ -725,6 +800,7 @@ async fn rollback_reset_side_effects(
     mode: ResetMode,
     old_oid: &ObjectHash,
     target_commit_id: &ObjectHash,
+    selective_paths: &[PathBuf],
 ) -> Result<(), ResetError> {
     match mode {
         ResetMode::Soft => Ok(()),
@@ -742,7 +818,183 @@ async fn rollback_reset_side_effects(
             }
             Ok(())
         }
+        ResetMode::Merge | ResetMode::Keep => {
+            reset_index_to_commit_typed(old_oid)?;
+            reset_working_paths_to_commit(old_oid, selective_paths).await?;
+            Ok(())
+        }
+    }
+}
+
+/// Work out which working-tree files `reset --merge` / `--keep` rewrite,
+/// comparing HEAD, the target, the index and the working tree per path (the
+/// tables in git-reset(1)). Returns the paths to rewrite from the target, or
+/// [`ResetError::LocalChangesWouldBeOverwritten`] listing every path whose
+/// local changes the reset would lose.
+///
+/// - `--merge` keeps unstaged changes when the index matches HEAD and the
+///   target does not touch the path; staged changes and unmerged entries are
+///   replaced by the target.
+/// - `--keep` keeps every local change to a path the target does not touch,
+///   and refuses when a path it does touch has staged or unstaged changes.
+///
+/// Index entries outside an active sparse checkout are taken to match the
+/// index and are never materialized.
+fn plan_selective_reset(
+    mode: ResetMode,
+    head_commit_id: &ObjectHash,
+    target_commit_id: &ObjectHash,
+) -> Result<Vec<PathBuf>, ResetError> {
+    let head = tree_blobs_from_commit(head_commit_id)?;
+    let target = tree_blobs_from_commit(target_commit_id)?;
+    let index = Index::load(path::index()).map_err(|e| ResetError::IndexLoad(e.to_string()))?;
+    let staged: HashMap<PathBuf, ObjectHash> = index
+        .tracked_entries(0)
+        .into_iter()
+        .map(|entry| (PathBuf::from(&entry.name), entry.hash))
+        .collect();
+    let unmerged: HashSet<PathBuf> = (1..=3)
+        .flat_map(|stage| index.tracked_entries(stage))
+        .map(|entry| PathBuf::from(&entry.name))
+        .filter(|path| !staged.contains_key(path))
+        .collect();
+
+    let workdir = util::working_dir();
+    let attributes = Attributes::load_default(&workdir);
+    let sparse = SparseCheckout::load();
+
+    let mut paths: BTreeSet<&PathBuf> = head.keys().collect();
+    paths.extend(target.keys());
+    paths.extend(staged.keys());
+    paths.extend(unmerged.iter());
+
+    let mut overwrite = Vec::new();
+    let mut conflicts = Vec::new();
+    for path in paths {
+        let in_head = head.get(path).copied();
+        let in_target = target.get(path).copied();
+        let in_index = staged.get(path).copied();
+        let in_worktree = if sparse
+            .as_ref()
+            .is_some_and(|sparse| sparse.skips(path, &workdir))
+        {
+            in_index
+        } else {
+            worktree_blob(&workdir, path, &attributes)?
+        };
+
+        let rewrite = match mode {
+            ResetMode::Merge if unmerged.contains(path) => Some(true),
+            ResetMode::Merge if in_worktree != in_index => {
+                (in_index == in_head && in_target == in_head).then_some(false)
+            }
+            ResetMode::Merge => Some(in_target != in_worktree),
+            ResetMode::Keep if unmerged.contains(path) => None,
+            ResetMode::Keep if in_target != in_head => {
+                (in_index == in_head && in_worktree == in_index).then_some(true)
+            }
+            ResetMode::Keep => Some(false),
+            ResetMode::Soft | ResetMode::Mixed | ResetMode::Hard => Some(false),
+        };
+        match rewrite {
+            Some(true) => overwrite.push(path.clone()),
+            Some(false) => {}
+            None => conflicts.push(path.display().to_string()),
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Err(ResetError::LocalChangesWouldBeOverwritten {
+            mode: mode.as_str(),
+            paths: conflicts,
+        });
+    }
+    Ok(overwrite)
+}
+
+/// Blob hash of the working-tree file at `path`, or `None` when it is absent.
+fn worktree_blob(
+    workdir: &Path,
+    path: &Path,
+    attributes: &Attributes,
+) -> Result<Option<ObjectHash>, ResetError> {
+    let absolute = workdir.join(path);
+    if fs::symlink_metadata(&absolute).is_err() {
+        return Ok(None);
     }
+    calc_file_blob_hash_with_attributes(&absolute, path, attributes)
+        .map(Some)
+        .map_err(|e| {
+            ResetError::WorktreeRead(format!("failed to read file {}: {}", absolute.display(), e))
+        })
+}
+
+fn tree_blobs_from_commit(
+    commit_id: &ObjectHash,
+) -> Result<HashMap<PathBuf, ObjectHash>, ResetError> {
+    Ok(tree_items_from_commit(commit_id)?
+        .into_iter()
+        .map(|(path, (hash, _))| (path, hash))
+        .collect())
+}
+
+fn tree_items_from_commit(
+    commit_id: &ObjectHash,
+) -> Result<HashMap<PathBuf, (ObjectHash, TreeItemMode)>, ResetError> {
+    let commit: Commit = load_object(commit_id)
+        .map_err(|e| object_load_error("commit", commit_id.to_string(), e.to_string()))?;
+    let tree: Tree = load_object(&commit.tree_id)
+        .map_err(|e| object_load_error("tree", commit.tree_id.to_string(), e.to_string()))?;
+    Ok(tree
+        .get_plain_items_with_mode()
+        .into_iter()
+        .map(|(path, hash, mode)| (path, (hash, mode)))
+        .collect())
+}
+
+/// Rewrite only `paths` in the working tree from `commit_id`, removing those
+/// the commit does not contain. Returns how many files were written or removed.
+async fn reset_working_paths_to_commit(
+    commit_id: &ObjectHash,
+    paths: &[PathBuf],
+) -> Result<usize, ResetError> {
+    let items = tree_items_from_commit(commit_id)?;
+    let workdir = util::working_dir();
+    let attributes = Attributes::load_default(&workdir);
+    let sparse = SparseCheckout::load();
+    let mut files_restored = 0;
+
+    for path in paths {
+        let absolute = workdir.join(path);
+        match items.get(path) {
+            Some(_) if sparse.as_ref().is_some_and(|s| s.skips(path, &workdir)) => {}
+            Some((hash, mode)) => {
+                restore::restore_to_file_with_attributes(hash, path, *mode, &attributes)
+                    .await
+                    .map_err(|e| {
+                        ResetError::WorktreeRestore(format!(
+                            "failed to write file {}: {}",
+                            absolute.display(),
+                            e
+                        ))
+                    })?;
+                files_restored += 1;
+            }
+            None if fs::symlink_metadata(&absolute).is_ok() => {
+                fs::remove_file(&absolute).map_err(|e| {
+                    ResetError::WorktreeRestore(format!(
+                        "failed to remove file {}: {}",
+                        absolute.display(),
+                        e
+                    ))
+                })?;
+                util::clear_empty_dir(&absolute);
+                files_restored += 1;
+            }
+            None => {}
+        }
+    }
+    Ok(files_restored)
 }
 
 fn load_commit_summary_or_warn(commit_id: &ObjectHash) -> String {
@@ -825,15 +1077,21 @@ async fn reset_working_directory_to_commit(
         .map_err(|e| object_load_error("tree", commit.tree_id.to_string(), e.to_string()))?;
 
     let workdir = util::working_dir();
-    let target_files = tree.get_plain_items();
-    let target_files_set: HashSet<_> = target_files.iter().map(|(path, _)| path.clone()).collect();
+    // Gitlinks count as targets too, so a submodule kept by the target tree
+    // is not mistaken for a removed file.
+    let target_files_set: HashSet<_> = tree
+        .get_plain_items_with_mode()
+        .into_iter()
+        .map(|(path, _, _)| path)
+        .collect();
     let mut files_restored = 0;
 
-    // Remove tracked files that should not exist in the target tree.
+    // Remove tracked files that should not exist in the target tree. A
+    // submodule checkout the target drops is left in place, as Git does.
     for file_path in previously_tracked_paths {
         if !target_files_set.contains(file_path) {
             let full_path = workdir.join(file_path);
-            if full_path.exists() {
+            if full_path.exists() && !full_path.is_dir() {
                 fs::remove_file(&full_path).map_err(|e| {
                     ResetError::WorktreeRestore(format!(
                         "failed to remove file {}: {}",
@@ -850,7 +1108,13 @@ async fn reset_working_directory_to_commit(
     let warnings = remove_empty_directories_with_warnings(&workdir)?;
 
     // Restore files from target tree
-    files_restored += restore_working_directory_from_tree_counted_typed(&tree, &workdir, "")?;
+    let attributes = Attributes::load_default(&workdir);
+    files_restored +=
+        restore_working_directory_from_tree_counted_typed(&tree, &workdir, "", &attributes)?;
+    // The tree was written in full; drop what the sparse checkout excludes.
+    sparse_checkout::reapply()
+        .await
+        .map_err(|e| ResetError::WorktreeRestore(e.to_string()))?;
 
     Ok(ResetStats {
         files_restored,
@@ -900,19 +1164,28 @@ fn rebuild_index_from_tree_typed(
         };
 
         match item.mode {
-            git_internal::internal::object::tree::TreeItemMode::Tree => {
+            TreeItemMode::Tree => {
                 let subtree: Tree = load_object(&item.id)
                     .map_err(|e| object_load_error("tree", item.id.to_string(), e.to_string()))?;
                 rebuild_index_from_tree_typed(&subtree, index, &full_path)?;
             }
+            TreeItemMode::Commit => {
+                // A gitlink names a commit in the submodule's repository, which
+                // is not in this object database; record it without a blob.
+                let mut entry = IndexEntry::new_from_blob(full_path, item.id, 0);
+                entry.mode = worktree::index_mode_from_tree_mode(item.mode);
+                index.add(entry);
+            }
             _ => {
                 // Add file to index - but don't modify working directory files
                 // Use the blob hash from the tree, not from working directory
                 // Get blob size for IndexEntry
                 let blob = git_internal::internal::object::blob::Blob::load(&item.id);
 
-                // Create IndexEntry with the tree's blob hash
-                let entry = IndexEntry::new_from_blob(full_path, item.id, blob.data.len() as u32);
+                // Create IndexEntry with the tree's blob hash and mode
+                let mut entry =
+                    IndexEntry::new_from_blob(full_path, item.id, blob.data.len() as u32);
+                entry.mode = worktree::index_mode_from_tree_mode(item.mode);
                 index.add(entry);
             }
         }
@@ -927,7 +1200,8 @@ pub(crate) fn restore_working_directory_from_tree(
     workdir: &Path,
     prefix: &str,
 ) -> Result<(), String> {
-    restore_working_directory_from_tree_counted_typed(tree, workdir, prefix)
+    let attributes = Attributes::load_default(workdir);
+    restore_working_directory_from_tree_counted_typed(tree, workdir, prefix, &attributes)
         .map(|_| ())
         .map_err(|e| e.to_string())
 }
@@ -936,6 +1210,7 @@ fn restore_working_directory_from_tree_counted_typed(
     tree: &Tree,
     workdir: &Path,
     prefix: &str,
+    attributes: &Attributes,
 ) -> Result<usize, ResetError> {
     let mut files_restored = 0;
     for item in &tree.tree_items {
@@ -948,7 +1223,7 @@ fn restore_working_directory_from_tree_counted_typed(
         let file_path = workdir.join(&full_path);
 
         match item.mode {
-            git_internal::internal::object::tree::TreeItemMode::Tree => {
+            TreeItemMode::Tree => {
                 // Create directory
                 fs::create_dir_all(&file_path).map_err(|e| {
                     ResetError::WorktreeRestore(format!(
@@ -961,28 +1236,44 @@ fn restore_working_directory_from_tree_counted_typed(
                 let subtree: Tree = load_object(&item.id)
                     .map_err(|e| object_load_error("tree", item.id.to_string(), e.to_string()))?;
                 files_restored += restore_working_directory_from_tree_counted_typed(
-                    &subtree, workdir, &full_path,
+                    &subtree, workdir, &full_path, attributes,
                 )?;
             }
+            // `libra submodule update` checks gitlinks out.
+            TreeItemMode::Commit => {}
             _ => {
-                // Restore file
+                // Restore file with the mode the tree records
                 let blob = load_object::<git_internal::internal::object::blob::Blob>(&item.id)
                     .map_err(|e| object_load_error("blob", item.id.to_string(), e.to_string()))?;
+                // Symlink targets are stored verbatim; file content gets the
+                // `eol=crlf` worktree conversion from `.gitattributes`.
+                let data = if item.mode == TreeItemMode::Link {
+                    blob.data
+                } else {
+                    gitattributes::convert_to_worktree(
+                        &attributes.lookup(Path::new(&full_path)),
+                        blob.data,
+                    )
+                };
 
-                // Create parent directory if needed
-                if let Some(parent) = file_path.parent() {
-                    fs::create_dir_all(parent).map_err(|e| {
-                        ResetError::WorktreeRestore(format!(
-                            "failed to create directory {}: {}",
-                            parent.display(),
-                            e
-                        ))
-                    })?;
-                }
-
-                let needs_write = match fs::read(&file_path) {
-                    Ok(existing) => existing != blob.data,
-                    Err(err) if err.kind() == io::ErrorKind::NotFound => true,
+                let existing = if item.mode == TreeItemMode::Link {
+                    fs::read_link(&file_path)
+                        .map(|target| target.into_os_string().into_encoded_bytes())
+                } else {
+                    fs::read(&file_path)
+                };
+                let needs_write = match existing {
+                    Ok(existing) => {
+                        existing != data || !worktree::workdir_mode_matches(&file_path, item.mode)
+                    }
+                    Err(err)
+                        if matches!(
+                            err.kind(),
+                            io::ErrorKind::NotFound | io::ErrorKind::InvalidInput
+                        ) =>
+                    {
+                        true
+                    }
                     Err(err) => {
                         return Err(ResetError::WorktreeRead(format!(
                             "failed to read file {}: {}",
@@ -993,7 +1284,7 @@ fn restore_working_directory_from_tree_counted_typed(
                 };
 
                 if needs_write {
-                    fs::write(&file_path, blob.data).map_err(|e| {
+                    worktree::write_blob_with_mode(&file_path, item.mode, &data).map_err(|e| {
                         ResetError::WorktreeRestore(format!(
                             "failed to write file {}: {}",
                             file_path.display(),
@@ -1210,7 +1501,7 @@ fn find_tree_item_recursive(
             if index == parts.len() - 1 {
                 // Found the target
                 return Ok(Some(item.clone()));
-            } else if item.mode == git_internal::internal::object::tree::TreeItemMode::Tree {
+            } else if item.mode == TreeItemMode::Tree {
                 // Continue searching in subtree
                 let subtree = load_object::<Tree>(&item.id)
                     .map_err(|e| object_load_error("tree", item.id.to_string(), e.to_string()))?;
@@ -1253,7 +1544,7 @@ mod tests {
             "Cannot reset: HEAD is unborn and points to no commit.",
         );
         assert_eq!(
-            ResetError::PathspecWithHard.to_string(),
+            ResetError::PathspecWithWorktreeReset("hard").to_string(),
             "Cannot do hard reset with paths.",
         );
         // {0}-prefixed variants where the inner string IS the message.
@@ -1385,9 +1676,17 @@ mod tests {
             StableErrorCode::CliInvalidArguments,
         );
         assert_eq!(
-            ResetError::PathspecWithHard.stable_code(),
+            ResetError::PathspecWithWorktreeReset("keep").stable_code(),
             StableErrorCode::CliInvalidArguments,
         );
+        assert_eq!(
+            ResetError::LocalChangesWouldBeOverwritten {
+                mode: "merge",
+                paths: vec!["a.txt".to_string()],
+            }
+            .stable_code(),
+            StableErrorCode::ConflictOperationBlocked,
+        );
         assert_eq!(
             ResetError::PathspecNotMatched("ignored".to_string()).stable_code(),
             StableErrorCode::CliInvalidTarget,
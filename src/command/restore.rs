@@ -11,27 +11,36 @@ use git_internal::{
     hash::ObjectHash,
     internal::{
         index::{Index, IndexEntry},
-        object::{blob::Blob, commit::Commit, tree::Tree, types::ObjectType},
+        object::{
+            blob::Blob,
+            commit::Commit,
+            tag::Tag,
+            tree::{Tree, TreeItemMode},
+            types::ObjectType,
+        },
     },
 };
 use serde::Serialize;
 
 use crate::{
-    command::{calc_file_blob_hash, load_object},
+    command::{calc_file_blob_hash_with_attributes, load_object},
     internal::{
         branch::{self, Branch, BranchStoreError},
         head::Head,
         protocol::lfs_client::LFSClient,
+        tag,
     },
     utils::{
         client_storage::ClientStorage,
         error::{CliError, CliResult, StableErrorCode},
+        gitattributes::{self, Attributes},
         lfs,
         object_ext::{BlobExt, CommitExt, TreeExt},
         output::{OutputConfig, emit_json_data},
         path,
         path_ext::PathExt,
-        util,
+        sparse_checkout::{self, SparseCheckout},
+        util, worktree,
     },
 };
 
@@ -55,8 +64,8 @@ EXAMPLES:
 pub enum RestoreError {
     #[error("failed to resolve checkout source")]
     ResolveSource,
-    #[error("reference is not a commit")]
-    ReferenceNotCommit,
+    #[error("reference is not a tree")]
+    ReferenceNotTree,
     #[error("pathspec '{0}' did not match any files")]
     PathspecNotMatched(String),
     #[error("failed to read index")]
@@ -114,7 +123,7 @@ impl RestoreError {
     fn stable_code(&self) -> StableErrorCode {
         match self {
             Self::ResolveSource => StableErrorCode::CliInvalidTarget,
-            Self::ReferenceNotCommit => StableErrorCode::CliInvalidTarget,
+            Self::ReferenceNotTree => StableErrorCode::CliInvalidTarget,
             Self::PathspecNotMatched(_) => StableErrorCode::CliInvalidTarget,
             Self::ReadIndex => StableErrorCode::IoReadFailed,
             Self::ReadObject => StableErrorCode::IoReadFailed,
@@ -143,10 +152,10 @@ impl From<RestoreError> for CliError {
                 .with_stable_code(stable_code)
                 .with_exit_code(128)
                 .with_hint("check that the source ref exists with 'libra log'"),
-            RestoreError::ReferenceNotCommit => CliError::fatal(message)
+            RestoreError::ReferenceNotTree => CliError::fatal(message)
                 .with_stable_code(stable_code)
                 .with_exit_code(128)
-                .with_hint("only commit references can be used as restore source"),
+                .with_hint("the restore source must name a commit, tag or tree"),
             RestoreError::PathspecNotMatched(_) => CliError::fatal(message)
                 .with_stable_code(stable_code)
                 .with_hint("check the path and try again"),
@@ -410,7 +419,8 @@ async fn run_restore(mut args: RestoreArgs) -> Result<RestoreOutput, RestoreErro
     }
 
     let storage = util::objects_storage();
-    let mut target_blobs = resolve_target_blobs(source.as_deref(), staged, &storage).await?;
+    let (mut target_blobs, modes) =
+        resolve_target_blobs(source.as_deref(), staged, &storage).await?;
 
     let mut paths = args
         .pathspec
@@ -461,12 +471,13 @@ async fn run_restore(mut args: RestoreArgs) -> Result<RestoreOutput, RestoreErro
     let overlay = args.overlay;
 
     if worktree {
-        let (restored, deleted) = restore_worktree_tracked(&paths, &target_blobs, overlay).await?;
+        let (restored, deleted) =
+            restore_worktree_tracked(&paths, &target_blobs, &modes, overlay).await?;
         restored_files.extend(restored);
         deleted_files.extend(deleted);
     }
     if staged {
-        let (restored, deleted) = restore_index_tracked(&paths, &target_blobs, overlay)?;
+        let (restored, deleted) = restore_index_tracked(&paths, &target_blobs, &modes, overlay)?;
         let mut restored_seen: HashSet<String> = restored_files.iter().cloned().collect();
         let mut deleted_seen: HashSet<String> = deleted_files.iter().cloned().collect();
 
@@ -481,6 +492,8 @@ async fn run_restore(mut args: RestoreArgs) -> Result<RestoreOutput, RestoreErro
             }
         }
     }
+    // Entries the sparse checkout left unmaterialized become skip-worktree.
+    sparse_checkout::refresh_skip_worktree().map_err(|_| RestoreError::WriteWorktree)?;
 
     Ok(RestoreOutput {
         source: source.clone(),
@@ -516,41 +529,20 @@ async fn resolve_target_blobs(
     source: Option<&str>,
     staged: bool,
     storage: &ClientStorage,
-) -> Result<Vec<(PathBuf, ObjectHash)>, RestoreError> {
-    const HEAD: &str = "HEAD";
-
+) -> Result<(Vec<(PathBuf, ObjectHash)>, SourceModes), RestoreError> {
     match source {
         None => {
             if staged {
                 return Err(RestoreError::ResolveSource);
             }
             let index = Index::load(path::index()).map_err(|_| RestoreError::ReadIndex)?;
-            Ok(index
-                .tracked_entries(0)
-                .into_iter()
-                .map(|entry| (PathBuf::from(&entry.name), entry.hash))
-                .collect())
+            Ok(index_source(&index))
         }
         Some(src) => {
-            let commit = if src == HEAD {
-                Head::current_commit_result()
-                    .await
-                    .map_err(map_restore_branch_store_error)?
-                    .ok_or(RestoreError::ResolveSource)?
-            } else if src.contains('~') || src.contains('^') {
-                util::get_commit_base_typed(src)
-                    .await
-                    .map_err(|_| RestoreError::ResolveSource)?
-            } else {
-                resolve_source_commit(src, storage).await?
-            };
-
-            let tree_id = load_object::<Commit>(&commit)
-                .map_err(|_| RestoreError::ReadObject)?
-                .tree_id;
-            Ok(load_object::<Tree>(&tree_id)
-                .map_err(|_| RestoreError::ReadObject)?
-                .get_plain_items())
+            let tree_id = resolve_source_tree(src, storage).await?;
+            Ok(tree_source(
+                &load_object::<Tree>(&tree_id).map_err(|_| RestoreError::ReadObject)?,
+            ))
         }
     }
 }
@@ -560,6 +552,7 @@ async fn resolve_target_blobs(
 async fn restore_worktree_tracked(
     filter: &[PathBuf],
     target_blobs: &[(PathBuf, ObjectHash)],
+    modes: &SourceModes,
     overlay: bool,
 ) -> Result<(Vec<String>, Vec<String>), RestoreError> {
     let target_map = preprocess_blobs(target_blobs);
@@ -589,8 +582,15 @@ async fn restore_worktree_tracked(
     // name a real source blob or an existing worktree file; the directory's
     // actual files arrive via the `deleted_files` discovery set above.
     file_paths.retain(|p| target_map.contains_key(p) || util::workdir_to_absolute(p).is_file());
+    // Paths outside an active sparse checkout stay unmaterialized: they are
+    // only touched while a (locally modified) copy is still on disk.
+    if let Some(sparse) = SparseCheckout::load() {
+        let workdir = util::working_dir();
+        file_paths.retain(|p| !sparse.skips(p, &workdir));
+    }
 
     let index = Index::load(path::index()).map_err(|_| RestoreError::ReadIndex)?;
+    let attributes = Attributes::for_worktree().await;
     let mut restored = Vec::new();
     let mut deleted = Vec::new();
 
@@ -598,17 +598,25 @@ async fn restore_worktree_tracked(
         let path_abs = util::workdir_to_absolute(path_wd);
         if !path_abs.exists() {
             if target_map.contains_key(path_wd) {
-                restore_to_file_typed(&target_map[path_wd], path_wd).await?;
+                restore_to_file_typed(
+                    &target_map[path_wd],
+                    path_wd,
+                    source_mode(modes, path_wd),
+                    &attributes,
+                )
+                .await?;
                 restored.push(path_wd.display().to_string());
             } else {
                 return Err(pathspec_not_matched(path_wd));
             }
         } else {
             let path_wd_str = path_to_utf8_typed(path_wd)?;
-            let hash = calc_file_blob_hash(&path_abs).map_err(|_| RestoreError::ReadObject)?;
+            let hash = calc_file_blob_hash_with_attributes(&path_abs, path_wd, &attributes)
+                .map_err(|_| RestoreError::ReadObject)?;
             if target_map.contains_key(path_wd) {
-                if hash != target_map[path_wd] {
-                    restore_to_file_typed(&target_map[path_wd], path_wd).await?;
+                let mode = source_mode(modes, path_wd);
+                if hash != target_map[path_wd] || !worktree::workdir_mode_matches(&path_abs, mode) {
+                    restore_to_file_typed(&target_map[path_wd], path_wd, mode, &attributes).await?;
                     restored.push(path_wd.display().to_string());
                 }
             } else if !overlay && index.tracked(path_wd_str, 0) {
@@ -627,6 +635,7 @@ async fn restore_worktree_tracked(
 fn restore_index_tracked(
     filter: &[PathBuf],
     target_blobs: &[(PathBuf, ObjectHash)],
+    modes: &SourceModes,
     overlay: bool,
 ) -> Result<(Vec<String>, Vec<String>), RestoreError> {
     let target_map = preprocess_blobs(target_blobs);
@@ -651,24 +660,18 @@ fn restore_index_tracked(
             if target_map.contains_key(path) {
                 let hash = target_map[path];
                 let blob = load_object::<Blob>(&hash).map_err(|_| RestoreError::ReadObject)?;
-                index.add(IndexEntry::new_from_blob(
-                    path_str.to_string(),
-                    hash,
-                    blob.data.len() as u32,
-                ));
+                index.add(source_index_entry(path_str, hash, &blob, modes));
                 restored.push(path.display().to_string());
             } else {
                 return Err(pathspec_not_matched(path));
             }
         } else if target_map.contains_key(path) {
             let hash = target_map[path];
-            if !index.verify_hash(path_str, 0, &hash) {
+            if !index.verify_hash(path_str, 0, &hash)
+                || !index_mode_matches(&index, path_str, modes)
+            {
                 let blob = load_object::<Blob>(&hash).map_err(|_| RestoreError::ReadObject)?;
-                index.update(IndexEntry::new_from_blob(
-                    path_str.to_string(),
-                    hash,
-                    blob.data.len() as u32,
-                ));
+                index.update(source_index_entry(path_str, hash, &blob, modes));
                 restored.push(path.display().to_string());
             }
         } else if !overlay {
@@ -736,22 +739,18 @@ pub async fn execute_checked(args: RestoreArgs) -> io::Result<()> {
         }
     };
 
-    let target_blobs: Vec<(PathBuf, ObjectHash)> = {
+    let (target_blobs, modes) = {
         match (source.as_ref(), target_commit) {
             (None, _) => {
                 assert!(!staged);
                 let index =
                     Index::load(path::index()).map_err(|e| io::Error::other(e.to_string()))?;
-                index
-                    .tracked_entries(0)
-                    .into_iter()
-                    .map(|entry| (PathBuf::from(&entry.name), entry.hash))
-                    .collect()
+                index_source(&index)
             }
             (Some(_), Some(commit)) => {
                 let tree_id = Commit::load(&commit).tree_id;
                 let tree = Tree::load(&tree_id);
-                tree.get_plain_items()
+                tree_source(&tree)
             }
             (Some(src), None) => {
                 if storage
@@ -778,10 +777,10 @@ pub async fn execute_checked(args: RestoreArgs) -> io::Result<()> {
         .collect::<Vec<PathBuf>>();
 
     if worktree {
-        restore_worktree(&paths, &target_blobs).await?;
+        restore_worktree(&paths, &target_blobs, &modes).await?;
     }
     if staged {
-        restore_index(&paths, &target_blobs)?;
+        restore_index(&paths, &target_blobs, &modes)?;
     }
     Ok(())
 }
@@ -803,78 +802,111 @@ pub async fn execute_checked_typed(args: RestoreArgs) -> Result<(), RestoreError
     }
 
     let storage = util::objects_storage();
-    let target_blobs: Vec<(PathBuf, ObjectHash)> = match source.as_ref() {
+    let (target_blobs, modes) = match source.as_ref() {
         None => {
             if staged {
                 return Err(RestoreError::ResolveSource);
             }
             let index = Index::load(path::index()).map_err(|_| RestoreError::ReadIndex)?;
-            index
-                .tracked_entries(0)
-                .into_iter()
-                .map(|entry| (PathBuf::from(&entry.name), entry.hash))
-                .collect()
+            index_source(&index)
         }
         Some(src) => {
-            let commit = if src == HEAD {
-                Head::current_commit_result()
-                    .await
-                    .map_err(map_restore_branch_store_error)?
-                    .ok_or(RestoreError::ResolveSource)?
-            } else {
-                resolve_source_commit(src, &storage).await?
-            };
-
-            let tree_id = load_object::<Commit>(&commit)
-                .map_err(|_| RestoreError::ReadObject)?
-                .tree_id;
-            load_object::<Tree>(&tree_id)
-                .map_err(|_| RestoreError::ReadObject)?
-                .get_plain_items()
+            let tree_id = resolve_source_tree(src, &storage).await?;
+            tree_source(&load_object::<Tree>(&tree_id).map_err(|_| RestoreError::ReadObject)?)
         }
     };
 
     let paths = args.pathspec.iter().map(PathBuf::from).collect::<Vec<_>>();
     if worktree {
-        restore_worktree_legacy_typed(&paths, &target_blobs).await?;
+        restore_worktree_legacy_typed(&paths, &target_blobs, &modes).await?;
     }
     if staged {
-        restore_index_legacy_typed(&paths, &target_blobs)?;
+        restore_index_legacy_typed(&paths, &target_blobs, &modes)?;
     }
     Ok(())
 }
 
 // ── Shared helpers ───────────────────────────────────────────────────
 
-async fn resolve_source_commit(
+/// Resolve `--source` to the tree to restore from. Like Git, any tree-ish is
+/// accepted: `HEAD` and `HEAD~n`-style navigation, a branch, a tag, or an
+/// object id naming a commit, an annotated tag or a tree.
+async fn resolve_source_tree(
     src: &str,
     storage: &ClientStorage,
 ) -> Result<ObjectHash, RestoreError> {
-    if let Some(branch) = Branch::find_branch_result(src, None)
+    const HEAD: &str = "HEAD";
+
+    let commit = if src == HEAD {
+        Head::current_commit_result()
+            .await
+            .map_err(map_restore_branch_store_error)?
+            .ok_or(RestoreError::ResolveSource)?
+    } else if src.contains('~') || src.contains('^') {
+        util::get_commit_base_typed(src)
+            .await
+            .map_err(|_| RestoreError::ResolveSource)?
+    } else if let Some(branch) = Branch::find_branch_result(src, None)
         .await
         .map_err(map_restore_branch_store_error)?
     {
-        return Ok(branch.commit);
-    }
-
-    if Branch::exists_result(src, None)
+        branch.commit
+    } else if Branch::exists_result(src, None)
         .await
         .map_err(map_restore_branch_store_error)?
     {
         return Err(RestoreError::ResolveSource);
-    }
-
-    let objs = storage
-        .search_result(src)
+    } else if let Some(tag_ref) = tag::find_tag_ref(src)
         .await
-        .map_err(|_| RestoreError::ReadObject)?;
-    if objs.len() != 1 {
-        return Err(RestoreError::ResolveSource);
-    }
-    if !storage.is_object_type(&objs[0], ObjectType::Commit) {
-        return Err(RestoreError::ReferenceNotCommit);
+        .map_err(|_| RestoreError::ReadObject)?
+    {
+        let target = tag_ref
+            .target
+            .as_deref()
+            .and_then(|target| target.parse::<ObjectHash>().ok())
+            .ok_or(RestoreError::ReadObject)?;
+        return peel_to_tree(target, storage);
+    } else {
+        let objs = storage
+            .search_result(src)
+            .await
+            .map_err(|_| RestoreError::ReadObject)?;
+        if objs.len() != 1 {
+            return Err(RestoreError::ResolveSource);
+        }
+        return peel_to_tree(objs[0], storage);
+    };
+
+    load_object::<Commit>(&commit)
+        .map(|commit| commit.tree_id)
+        .map_err(|_| RestoreError::ReadObject)
+}
+
+/// Peel a commit or (chain of) annotated tags down to a tree id.
+fn peel_to_tree(object: ObjectHash, storage: &ClientStorage) -> Result<ObjectHash, RestoreError> {
+    let mut current = object;
+    let mut seen = HashSet::new();
+    while seen.insert(current) {
+        let object_type = storage
+            .get_object_type(&current)
+            .map_err(|_| RestoreError::ReadObject)?;
+        match object_type {
+            ObjectType::Tree => return Ok(current),
+            ObjectType::Commit => {
+                return load_object::<Commit>(&current)
+                    .map(|commit| commit.tree_id)
+                    .map_err(|_| RestoreError::ReadObject);
+            }
+            ObjectType::Tag => {
+                current = load_object::<Tag>(&current)
+                    .map_err(|_| RestoreError::ReadObject)?
+                    .object_hash;
+            }
+            _ => return Err(RestoreError::ReferenceNotTree),
+        }
     }
-    Ok(objs[0])
+    // A tag chain that loops back on itself.
+    Err(RestoreError::ReadObject)
 }
 
 async fn resolve_source_commit_io(
@@ -929,6 +961,61 @@ async fn reject_restore_on_ai_managed_current_branch() -> Result<(), RestoreErro
     }
 }
 
+/// Tree mode of every source path, so the worktree keeps the executable bit and
+/// symlinks and the index keeps the recorded mode. A path missing from the map
+/// is a regular file.
+type SourceModes = HashMap<PathBuf, TreeItemMode>;
+
+/// The blobs and modes of a tree source. Gitlinks (submodules) are skipped;
+/// `libra submodule update` checks them out.
+fn tree_source(tree: &Tree) -> (Vec<(PathBuf, ObjectHash)>, SourceModes) {
+    let mut blobs = Vec::new();
+    let mut modes = SourceModes::new();
+    for (path, hash, mode) in tree.get_plain_items_with_mode() {
+        if mode == TreeItemMode::Commit {
+            continue;
+        }
+        modes.insert(path.clone(), mode);
+        blobs.push((path, hash));
+    }
+    (blobs, modes)
+}
+
+/// The stage-0 blobs and modes of an index source.
+fn index_source(index: &Index) -> (Vec<(PathBuf, ObjectHash)>, SourceModes) {
+    let mut blobs = Vec::new();
+    let mut modes = SourceModes::new();
+    for entry in index.tracked_entries(0) {
+        let path = PathBuf::from(&entry.name);
+        modes.insert(
+            path.clone(),
+            worktree::tree_mode_from_index_mode(entry.mode),
+        );
+        blobs.push((path, entry.hash));
+    }
+    (blobs, modes)
+}
+
+fn source_mode(modes: &SourceModes, path: &Path) -> TreeItemMode {
+    modes.get(path).copied().unwrap_or(TreeItemMode::Blob)
+}
+
+fn source_index_entry(
+    path: &str,
+    hash: ObjectHash,
+    blob: &Blob,
+    modes: &SourceModes,
+) -> IndexEntry {
+    let mut entry = IndexEntry::new_from_blob(path.to_string(), hash, blob.data.len() as u32);
+    entry.mode = worktree::index_mode_from_tree_mode(source_mode(modes, Path::new(path)));
+    entry
+}
+
+fn index_mode_matches(index: &Index, path: &str, modes: &SourceModes) -> bool {
+    let mode = worktree::index_mode_from_tree_mode(source_mode(modes, Path::new(path)));
+    index.get(path, 0).is_some_and(|entry| entry.mode == mode)
+}
+
 fn preprocess_blobs(blobs: &[(PathBuf, ObjectHash)]) -> HashMap<PathBuf, ObjectHash> {
     blobs
         .iter()
@@ -1006,6 +1093,7 @@ async fn restore_conflict_stage(
     overlay: bool,
 ) -> Result<(Vec<String>, Vec<String>), RestoreError> {
     let index = Index::load(path::index()).map_err(|_| RestoreError::ReadIndex)?;
+    let attributes = Attributes::for_worktree().await;
     let unmerged = collect_unmerged_paths(&index);
     let filter: Vec<PathBuf> = pathspec.iter().map(PathBuf::from).collect();
     let matched = util::filter_to_fit_paths(&unmerged, &filter);
@@ -1020,7 +1108,12 @@ async fn restore_conflict_stage(
         let path_str = path_to_utf8_typed(path)?;
         match stage_blob(&index, path_str, stage) {
             Some(hash) => {
-                restore_to_file_typed(&hash, path).await?;
+                let mode = index
+                    .get(path_str, stage)
+                    .map_or(TreeItemMode::Blob, |entry| {
+                        worktree::tree_mode_from_index_mode(entry.mode)
+                    });
+                restore_to_file_typed(&hash, path, mode, &attributes).await?;
                 restored.push(path.display().to_string());
             }
             None if overlay => {
@@ -1141,7 +1234,12 @@ fn build_conflict_markers(
     out
 }
 
-async fn restore_to_file_typed(hash: &ObjectHash, path: &PathBuf) -> Result<(), RestoreError> {
+async fn restore_to_file_typed(
+    hash: &ObjectHash,
+    path: &PathBuf,
+    mode: TreeItemMode,
+    attributes: &Attributes,
+) -> Result<(), RestoreError> {
     let blob = load_object::<Blob>(hash).map_err(|_| RestoreError::ReadObject)?;
     let path_abs = util::workdir_to_absolute(path);
     if let Some(parent) = path_abs.parent() {
@@ -1163,7 +1261,9 @@ async fn restore_to_file_typed(hash: &ObjectHash, path: &PathBuf) -> Result<(),
             }
         }
         None => {
-            util::write_file(&blob.data, &path_abs).map_err(|_| RestoreError::WriteWorktree)?;
+            let data = smudge_blob(blob.data, path, mode, attributes);
+            worktree::write_blob_with_mode(&path_abs, mode, &data)
+                .map_err(|_| RestoreError::WriteWorktree)?;
         }
     }
 
@@ -1174,6 +1274,28 @@ async fn restore_to_file_typed(hash: &ObjectHash, path: &PathBuf) -> Result<(),
 /// If blob is an LFS pointer, download the actual file from LFS server.
 /// - `path` : to workdir
 pub async fn restore_to_file(hash: &ObjectHash, path: &PathBuf) -> io::Result<()> {
+    restore_to_file_with_mode(hash, path, TreeItemMode::Blob).await
+}
+
+/// [`restore_to_file`] for a blob recorded with `mode`: executables get the
+/// executable bit and `120000` blobs become symlinks.
+pub async fn restore_to_file_with_mode(
+    hash: &ObjectHash,
+    path: &PathBuf,
+    mode: TreeItemMode,
+) -> io::Result<()> {
+    let attributes = Attributes::for_worktree().await;
+    restore_to_file_with_attributes(hash, path, mode, &attributes).await
+}
+
+/// [`restore_to_file_with_mode`] with the worktree attributes already loaded,
+/// so restoring many files reads `.gitattributes` once.
+pub async fn restore_to_file_with_attributes(
+    hash: &ObjectHash,
+    path: &PathBuf,
+    mode: TreeItemMode,
+    attributes: &Attributes,
+) -> io::Result<()> {
     let blob = Blob::load(hash);
     let path_abs = util::workdir_to_absolute(path);
     if let Some(parent) = path_abs.parent() {
@@ -1194,12 +1316,23 @@ pub async fn restore_to_file(hash: &ObjectHash, path: &PathBuf) -> io::Result<()
             }
         }
         None => {
-            util::write_file(&blob.data, &path_abs)?;
+            let data = smudge_blob(blob.data, path, mode, attributes);
+            worktree::write_blob_with_mode(&path_abs, mode, &data)?;
         }
     }
     Ok(())
 }
 
+/// Apply the `eol=crlf` worktree conversion from the attributes on `path`
+/// (see [`gitattributes::convert_to_worktree`]). Symlink targets are never
+/// converted.
+fn smudge_blob(data: Vec<u8>, path: &Path, mode: TreeItemMode, attributes: &Attributes) -> Vec<u8> {
+    if mode == TreeItemMode::Link {
+        return data;
+    }
+    gitattributes::convert_to_worktree(&attributes.lookup(path), data)
+}
+
 fn get_worktree_deleted_files_in_filters(
     filters: &[PathBuf],
     target_blobs: &HashMap<PathBuf, ObjectHash>,
@@ -1219,6 +1352,7 @@ fn get_worktree_deleted_files_in_filters(
 pub async fn restore_worktree(
     filter: &[PathBuf],
     target_blobs: &[(PathBuf, ObjectHash)],
+    modes: &HashMap<PathBuf, TreeItemMode>,
 ) -> io::Result<()> {
     let target_blobs = preprocess_blobs(target_blobs);
     let deleted_files = get_worktree_deleted_files_in_filters(filter, &target_blobs);
@@ -1245,11 +1379,18 @@ pub async fn restore_worktree(
     file_paths.retain(|p| target_blobs.contains_key(p) || util::workdir_to_absolute(p).is_file());
 
     let index = Index::load(path::index()).map_err(|e| io::Error::other(e.to_string()))?;
+    let attributes = Attributes::for_worktree().await;
     for path_wd in &file_paths {
         let path_abs = util::workdir_to_absolute(path_wd);
         if !path_abs.exists() {
             if target_blobs.contains_key(path_wd) {
-                restore_to_file(&target_blobs[path_wd], path_wd).await?;
+                restore_to_file_with_attributes(
+                    &target_blobs[path_wd],
+                    path_wd,
+                    source_mode(modes, path_wd),
+                    &attributes,
+                )
+                .await?;
             } else {
                 return Err(io::Error::other(format!(
                     "pathspec '{}' did not match any files",
@@ -1258,11 +1399,19 @@ pub async fn restore_worktree(
             }
         } else {
             let path_wd_str = path_to_utf8(path_wd)?;
-            let hash =
-                calc_file_blob_hash(&path_abs).map_err(|e| io::Error::other(e.to_string()))?;
+            let hash = calc_file_blob_hash_with_attributes(&path_abs, path_wd, &attributes)
+                .map_err(|e| io::Error::other(e.to_string()))?;
             if target_blobs.contains_key(path_wd) {
-                if hash != target_blobs[path_wd] {
-                    restore_to_file(&target_blobs[path_wd], path_wd).await?;
+                let mode = source_mode(modes, path_wd);
+                if hash != target_blobs[path_wd] || !worktree::workdir_mode_matches(&path_abs, mode)
+                {
+                    restore_to_file_with_attributes(
+                        &target_blobs[path_wd],
+                        path_wd,
+                        mode,
+                        &attributes,
+                    )
+                    .await?;
                 }
             } else if index.tracked(path_wd_str, 0) {
                 fs::remove_file(&path_abs)?;
@@ -1276,6 +1425,7 @@ pub async fn restore_worktree(
 async fn restore_worktree_legacy_typed(
     filter: &[PathBuf],
     target_blobs: &[(PathBuf, ObjectHash)],
+    modes: &SourceModes,
 ) -> Result<(), RestoreError> {
     let target_blobs = preprocess_blobs(target_blobs);
     let deleted_files = get_worktree_deleted_files_in_filters(filter, &target_blobs);
@@ -1298,20 +1448,31 @@ async fn restore_worktree_legacy_typed(
     file_paths.retain(|p| target_blobs.contains_key(p) || util::workdir_to_absolute(p).is_file());
 
     let index = Index::load(path::index()).map_err(|_| RestoreError::ReadIndex)?;
+    let attributes = Attributes::for_worktree().await;
     for path_wd in &file_paths {
         let path_abs = util::workdir_to_absolute(path_wd);
         if !path_abs.exists() {
             if target_blobs.contains_key(path_wd) {
-                restore_to_file_typed(&target_blobs[path_wd], path_wd).await?;
+                restore_to_file_typed(
+                    &target_blobs[path_wd],
+                    path_wd,
+                    source_mode(modes, path_wd),
+                    &attributes,
+                )
+                .await?;
             } else {
                 return Err(pathspec_not_matched(path_wd));
             }
         } else {
             let path_wd_str = path_to_utf8_typed(path_wd)?;
-            let hash = calc_file_blob_hash(&path_abs).map_err(|_| RestoreError::ReadObject)?;
+            let hash = calc_file_blob_hash_with_attributes(&path_abs, path_wd, &attributes)
+                .map_err(|_| RestoreError::ReadObject)?;
             if target_blobs.contains_key(path_wd) {
-                if hash != target_blobs[path_wd] {
-                    restore_to_file_typed(&target_blobs[path_wd], path_wd).await?;
+                let mode = source_mode(modes, path_wd);
+                if hash != target_blobs[path_wd] || !worktree::workdir_mode_matches(&path_abs, mode)
+                {
+                    restore_to_file_typed(&target_blobs[path_wd], path_wd, mode, &attributes)
+                        .await?;
                 }
             } else if index.tracked(path_wd_str, 0) {
                 fs::remove_file(&path_abs).map_err(|_| RestoreError::WriteWorktree)?;
@@ -1323,7 +1484,11 @@ async fn restore_worktree_legacy_typed(
     Ok(())
 }
 
-pub fn restore_index(filter: &[PathBuf], target_blobs: &[(PathBuf, ObjectHash)]) -> io::Result<()> {
+pub fn restore_index(
+    filter: &[PathBuf],
+    target_blobs: &[(PathBuf, ObjectHash)],
+    modes: &HashMap<PathBuf, TreeItemMode>,
+) -> io::Result<()> {
     let target_blobs = preprocess_blobs(target_blobs);
 
     let idx_file = path::index();
@@ -1340,11 +1505,7 @@ pub fn restore_index(filter: &[PathBuf], target_blobs: &[(PathBuf, ObjectHash)])
             if target_blobs.contains_key(path) {
                 let hash = target_blobs[path];
                 let blob = Blob::load(&hash);
-                index.add(IndexEntry::new_from_blob(
-                    path_str.to_string(),
-                    hash,
-                    blob.data.len() as u32,
-                ));
+                index.add(source_index_entry(path_str, hash, &blob, modes));
             } else {
                 return Err(io::Error::other(format!(
                     "pathspec '{}' did not match any files",
@@ -1353,13 +1514,11 @@ pub fn restore_index(filter: &[PathBuf], target_blobs: &[(PathBuf, ObjectHash)])
             }
         } else if target_blobs.contains_key(path) {
             let hash = target_blobs[path];
-            if !index.verify_hash(path_str, 0, &hash) {
+            if !index.verify_hash(path_str, 0, &hash)
+                || !index_mode_matches(&index, path_str, modes)
+            {
                 let blob = Blob::load(&hash);
-                index.update(IndexEntry::new_from_blob(
-                    path_str.to_string(),
-                    hash,
-                    blob.data.len() as u32,
-                ));
+                index.update(source_index_entry(path_str, hash, &blob, modes));
             }
         } else {
             index.remove(path_str, 0);
@@ -1374,6 +1533,7 @@ pub fn restore_index(filter: &[PathBuf], target_blobs: &[(PathBuf, ObjectHash)])
 fn restore_index_legacy_typed(
     filter: &[PathBuf],
     target_blobs: &[(PathBuf, ObjectHash)],
+    modes: &SourceModes,
 ) -> Result<(), RestoreError> {
     let target_blobs = preprocess_blobs(target_blobs);
 
@@ -1392,23 +1552,17 @@ fn restore_index_legacy_typed(
             if target_blobs.contains_key(path) {
                 let hash = target_blobs[path];
                 let blob = load_object::<Blob>(&hash).map_err(|_| RestoreError::ReadObject)?;
-                index.add(IndexEntry::new_from_blob(
-                    path_str.to_string(),
-                    hash,
-                    blob.data.len() as u32,
-                ));
+                index.add(source_index_entry(path_str, hash, &blob, modes));
             } else {
                 return Err(pathspec_not_matched(path));
             }
         } else if target_blobs.contains_key(path) {
             let hash = target_blobs[path];
-            if !index.verify_hash(path_str, 0, &hash) {
+            if !index.verify_hash(path_str, 0, &hash)
+                || !index_mode_matches(&index, path_str, modes)
+            {
                 let blob = load_object::<Blob>(&hash).map_err(|_| RestoreError::ReadObject)?;
-                index.update(IndexEntry::new_from_blob(
-                    path_str.to_string(),
-                    hash,
-                    blob.data.len() as u32,
-                ));
+                index.update(source_index_entry(path_str, hash, &blob, modes));
             }
         } else {
             index.remove(path_str, 0);
@@ -1473,8 +1627,8 @@ mod tests {
             "failed to resolve checkout source",
         );
         assert_eq!(
-            RestoreError::ReferenceNotCommit.to_string(),
-            "reference is not a commit",
+            RestoreError::ReferenceNotTree.to_string(),
+            "reference is not a tree",
         );
         assert_eq!(
             RestoreError::PathspecNotMatched("src/missing.rs".to_string()).to_string(),
@@ -1553,7 +1707,7 @@ mod tests {
             StableErrorCode::CliInvalidTarget,
         );
         assert_eq!(
-            RestoreError::ReferenceNotCommit.stable_code(),
+            RestoreError::ReferenceNotTree.stable_code(),
             StableErrorCode::CliInvalidTarget,
         );
         assert_eq!(
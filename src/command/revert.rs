@@ -1,7 +1,7 @@
 //! Implements the revert command by parsing targets, reversing commit changes into the index/worktree, and optionally creating a new commit.
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
     str::FromStr,
@@ -21,19 +21,20 @@ use git_internal::{
         },
     },
 };
+use sea_orm::{ConnectionTrait, DbBackend, Statement, TransactionTrait};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    command::{editor, load_object, save_object},
+    command::{editor, load_object, log::get_reachable_commits, save_object},
     common_utils::format_commit_msg,
-    internal::{branch::Branch, head::Head},
+    internal::{branch::Branch, db::get_db_conn_instance, head::Head},
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         object_ext::{BlobExt, TreeExt},
         output::{OutputConfig, emit_json_data},
         path,
         text::short_display_hash,
-        util,
+        util, worktree,
     },
 };
 
@@ -42,6 +43,8 @@ EXAMPLES:
     libra revert HEAD                     Revert the most recent commit
     libra revert abc1234                  Revert a specific commit
     libra revert -n HEAD                  Revert without auto-committing
+    libra revert HEAD~3..HEAD             Revert the last three commits, newest first
+    libra revert -n HEAD~3..HEAD          Stage the reverts of the last three commits for one commit
     libra revert -m 1 <merge>             Revert a merge commit relative to parent 1
     libra revert HEAD --edit              Edit the revert message in $EDITOR before committing
     libra revert HEAD --no-edit           Accept the default revert message (no editor)
@@ -111,6 +114,9 @@ enum RevertError {
     #[error("{0}")]
     MultiCommitUnsupported(String),
 
+    #[error("empty commit set passed: '{0}' selects no commits")]
+    EmptyRange(String),
+
     #[error("Aborting revert due to empty commit message")]
     EmptyMessage,
 
@@ -165,7 +171,7 @@ impl RevertError {
         match self {
             Self::NotInRepo => StableErrorCode::RepoNotFound,
             Self::DetachedHead => StableErrorCode::RepoStateInvalid,
-            Self::InvalidCommit(_) => StableErrorCode::CliInvalidTarget,
+            Self::InvalidCommit(_) | Self::EmptyRange(_) => StableErrorCode::CliInvalidTarget,
             Self::MainlineRequired(_)
             | Self::MainlineForNonMerge(_)
             | Self::InvalidMainline { .. } => StableErrorCode::CliInvalidArguments,
@@ -243,7 +249,8 @@ pub struct RevertOutput {
 #[command(after_help = REVERT_EXAMPLES)]
 pub struct RevertArgs {
     /// Commits to revert, in order (commit hash, branch name, or HEAD). Multiple
-    /// commits are reverted sequentially, each as its own revert commit.
+    /// commits are reverted sequentially, each as its own revert commit. An
+    /// `A..B` range reverts the commits in B but not A, newest first.
     #[clap(required_unless_present_any = ["continue_revert", "abort", "skip"], num_args = 0..)]
     pub commit: Vec<String>,
 
@@ -260,7 +267,8 @@ pub struct RevertArgs {
     #[clap(long, conflicts_with_all = ["continue_revert", "abort", "no_commit", "mainline"])]
     pub skip: bool,
 
-    /// Don't automatically commit the revert, just stage the changes
+    /// Don't automatically commit the revert, just stage the changes. With
+    /// several commits the reverts accumulate in the index for one commit.
     #[clap(short = 'n', long)]
     pub no_commit: bool,
 
@@ -327,7 +335,7 @@ async fn run_revert(args: RevertArgs) -> Result<RevertOutput, RevertError> {
 
     // A conflicted revert must be resolved (`--continue`) or unwound (`--abort`)
     // before a new revert can start.
-    if RevertState::load_optional()?.is_some() {
+    if RevertState::load_optional().await?.is_some() {
         return Err(RevertError::RevertInProgress);
     }
 
@@ -335,37 +343,35 @@ async fn run_revert(args: RevertArgs) -> Result<RevertOutput, RevertError> {
         return Err(RevertError::DetachedHead);
     }
 
-    // `--no-commit` and `-m` operate on a single revert; combining them with
-    // multiple commits would need a full multi-commit sequencer, so reject the
-    // combination rather than silently misbehave.
-    if args.commit.len() > 1 {
-        if args.no_commit {
-            return Err(RevertError::MultiCommitUnsupported(
-                "--no-commit cannot be combined with multiple commits".to_string(),
-            ));
-        }
-        if args.mainline.is_some() {
-            return Err(RevertError::MultiCommitUnsupported(
-                "-m/--mainline cannot be combined with multiple commits".to_string(),
-            ));
-        }
-    }
-
     // Resolve every commit spec to a stable commit ID up front (failing fast on
     // a bad ref before any revert is applied). The pending queue persisted on a
     // conflict then holds commit IDs, not refs — so a branch/`HEAD` that moves
     // while the revert is paused cannot make `--continue`/`--skip` revert a
     // different commit than the one originally named (matching Git's sequencer).
+    // An `A..B` range expands to its commits, newest first.
     let mut resolved: Vec<ObjectHash> = Vec::with_capacity(args.commit.len());
     for spec in &args.commit {
-        let id = resolve_commit(spec)
-            .await
-            .map_err(|_| RevertError::InvalidCommit(spec.clone()))?;
-        resolved.push(id);
+        match spec.split_once("..") {
+            Some((from, to)) => resolved.extend(resolve_range(spec, from, to).await?),
+            None => resolved.push(
+                resolve_commit(spec)
+                    .await
+                    .map_err(|_| RevertError::InvalidCommit(spec.clone()))?,
+            ),
+        }
+    }
+
+    // `-m` names a parent of one specific merge commit; it cannot apply to a
+    // whole sequence.
+    if resolved.len() > 1 && args.mainline.is_some() {
+        return Err(RevertError::MultiCommitUnsupported(
+            "-m/--mainline cannot be combined with multiple commits".to_string(),
+        ));
     }
 
     // Revert each commit in order; each revert is applied relative to (and
-    // committed onto) the HEAD produced by the previous one. A conflict stops the
+    // committed onto) the HEAD produced by the previous one — or, with
+    // `--no-commit`, on top of the index the previous one staged. A conflict stops the
     // sequence and records state (with the still-pending commit IDs) so
     // `--continue`/`--skip` can finish the rest.
     let params = RevertParams {
@@ -376,7 +382,7 @@ async fn run_revert(args: RevertArgs) -> Result<RevertOutput, RevertError> {
     };
     let outcome = revert_sequence(&resolved, &params, None, 0).await?;
     // A clean run leaves no in-progress state to clear (cleanup is a no-op then).
-    RevertState::cleanup()?;
+    RevertState::cleanup().await?;
     // INVARIANT: control reached here only past the abort/continue/skip
     // short-circuits, so clap's `required_unless_present_any` guarantees
     // `args.commit` (and thus `resolved`) is non-empty; `revert_sequence` over a
@@ -396,6 +402,44 @@ async fn run_revert(args: RevertArgs) -> Result<RevertOutput, RevertError> {
     })
 }
 
+/// Expand the range `from..to` (either side defaulting to `HEAD`) into the
+/// commits reachable from `to` but not from `from`, newest first — the order
+/// in which reverting them undoes history back to front.
+async fn resolve_range(spec: &str, from: &str, to: &str) -> Result<Vec<ObjectHash>, RevertError> {
+    // `A...B` (symmetric difference) leaves a leading '.' on the right side.
+    if to.starts_with('.') {
+        return Err(RevertError::InvalidCommit(spec.to_string()));
+    }
+    let mut ends = Vec::with_capacity(2);
+    for side in [from, to] {
+        let side = if side.is_empty() { "HEAD" } else { side };
+        ends.push(
+            resolve_commit(side)
+                .await
+                .map_err(|_| RevertError::InvalidCommit(spec.to_string()))?,
+        );
+    }
+    let (from_id, to_id) = (ends[0], ends[1]);
+
+    let excluded: HashSet<ObjectHash> = get_reachable_commits(from_id.to_string(), None)
+        .await
+        .map_err(|e| RevertError::LoadObject(e.to_string()))?
+        .into_iter()
+        .map(|commit| commit.id)
+        .collect();
+    let mut commits: Vec<Commit> = get_reachable_commits(to_id.to_string(), None)
+        .await
+        .map_err(|e| RevertError::LoadObject(e.to_string()))?
+        .into_iter()
+        .filter(|commit| !excluded.contains(&commit.id))
+        .collect();
+    commits.sort_by(|a, b| b.committer.timestamp.cmp(&a.committer.timestamp));
+    if commits.is_empty() {
+        return Err(RevertError::EmptyRange(spec.to_string()));
+    }
+    Ok(commits.into_iter().map(|commit| commit.id).collect())
+}
+
 /// Revert the already-resolved commit `ids` in order against the current HEAD
 /// using `params`. `seed` is the already-completed
 /// `(reverted_commit, revert_commit_id)` from a resumed sequence (so an empty
@@ -435,6 +479,13 @@ async fn revert_sequence(
     let mut total_files_changed = seed_files_changed;
     for (i, commit_id) in ids.iter().enumerate() {
         let orig_head = Head::current_commit().await.map(|h| h.to_string());
+        // `--no-commit` stacks reverts in the index; remember where this one
+        // started so `--skip` can drop just its changes.
+        let base_tree = if params.no_commit {
+            Some(index_tree().await?.to_string())
+        } else {
+            None
+        };
         match revert_single_commit(commit_id, params).await? {
             SingleRevertOutcome::Committed {
                 revert_commit_id,
@@ -445,15 +496,23 @@ async fn revert_sequence(
             }
             SingleRevertOutcome::Conflicted { conflicted_paths } => {
                 if let Some(orig_head) = orig_head {
+                    let head_name = match Head::current().await {
+                        Head::Branch(name) => name,
+                        Head::Detached(_) => String::new(),
+                    };
                     RevertState {
+                        head_name,
                         orig_head,
                         reverted_commit: commit_id.to_string(),
                         signoff: params.signoff,
                         edit: params.edit,
+                        no_commit: params.no_commit,
+                        base_tree,
                         remaining: ids[(i + 1)..].iter().map(|h| h.to_string()).collect(),
                         conflicted_paths: conflicted_paths.clone(),
                     }
-                    .save()?;
+                    .save()
+                    .await?;
                 }
                 return Err(RevertError::Conflicts {
                     paths: conflicted_paths.join(", "),
@@ -465,9 +524,12 @@ async fn revert_sequence(
 }
 
 /// `revert --continue`: require every conflict resolved (no markers staged),
-/// then create the revert commit from the resolved index and clear the state.
+/// then create the revert commit from the resolved index (just keep it staged
+/// under `--no-commit`) and clear the state.
 async fn run_revert_continue() -> Result<RevertOutput, RevertError> {
-    let state = RevertState::load_optional()?.ok_or(RevertError::NoRevertInProgress)?;
+    let state = RevertState::load_optional()
+        .await?
+        .ok_or(RevertError::NoRevertInProgress)?;
 
     // Refuse to finish while conflict markers remain in any *staged* file (the
     // index is what gets committed, so the user must resolve and re-`add`).
@@ -488,18 +550,16 @@ async fn run_revert_continue() -> Result<RevertOutput, RevertError> {
         .map_err(|e| RevertError::LoadObject(e.to_string()))?;
 
     // Build the revert commit from the (resolved) index tree.
-    let tree_items: std::collections::HashMap<PathBuf, ObjectHash> = index
-        .tracked_files()
-        .into_iter()
-        .filter_map(|p| {
-            let key = p.to_str()?;
-            index.get_hash(key, 0).map(|h| (p.clone(), h))
-        })
-        .collect();
+    let (tree_items, modes) = index_entries(&index);
     let files_changed = tree_items.len();
-    let tree_id = build_tree_from_map(tree_items).await?;
-    let message = resolve_revert_message(&reverted_commit_id, state.signoff, state.edit).await?;
-    let revert_commit_id = create_revert_commit(&orig_head, &tree_id, &message).await?;
+    let revert_commit_id = if state.no_commit {
+        None
+    } else {
+        let tree_id = build_tree_from_map(tree_items, &modes).await?;
+        let message =
+            resolve_revert_message(&reverted_commit_id, state.signoff, state.edit).await?;
+        Some(create_revert_commit(&orig_head, &tree_id, &message).await?)
+    };
 
     // The conflicted commit is now finished. Clear its state BEFORE draining the
     // rest of the sequence: if a remaining commit fails with a non-conflict error
@@ -507,10 +567,10 @@ async fn run_revert_continue() -> Result<RevertOutput, RevertError> {
     // state pointing at the already-committed conflict (which a retry would
     // re-process). A remaining *conflict* re-saves fresh state inside
     // `revert_sequence`; a clean drain leaves no state behind.
-    RevertState::cleanup()?;
+    RevertState::cleanup().await?;
     let remaining = parse_remaining_ids(&state.remaining)?;
-    let params = RevertParams::for_sequence(state.signoff, state.edit);
-    let seed = Some((state.reverted_commit.clone(), Some(revert_commit_id)));
+    let params = RevertParams::for_sequence(state.signoff, state.edit, state.no_commit);
+    let seed = Some((state.reverted_commit.clone(), revert_commit_id));
     let outcome = revert_sequence(&remaining, &params, seed, files_changed).await?;
 
     // INVARIANT: `seed` is `Some` (the just-finished conflict commit), so
@@ -524,7 +584,7 @@ async fn run_revert_continue() -> Result<RevertOutput, RevertError> {
         short_new: last_revert_commit
             .as_ref()
             .map(|id| short_display_hash(&id.to_string()).to_string()),
-        no_commit: false,
+        no_commit: state.no_commit,
         files_changed: total_files_changed,
     })
 }
@@ -533,16 +593,27 @@ async fn run_revert_continue() -> Result<RevertOutput, RevertError> {
 /// restoring the working tree/index to the commit's start point, then continue
 /// the sequence with the remaining commits.
 async fn run_revert_skip() -> Result<RevertOutput, RevertError> {
-    let state = RevertState::load_optional()?.ok_or(RevertError::NoRevertInProgress)?;
+    let state = RevertState::load_optional()
+        .await?
+        .ok_or(RevertError::NoRevertInProgress)?;
 
     // HEAD is already at `orig_head` (the conflict stopped before committing), so
-    // restoring the index/worktree to its tree drops the conflict markers.
-    restore_to_orig_head(&state.orig_head).await?;
+    // restoring the index/worktree to its tree drops the conflict markers. Under
+    // `--no-commit` the earlier reverts are only staged, so go back to the index
+    // tree the conflicted revert started from instead.
+    match &state.base_tree {
+        Some(base_tree) => {
+            let tree_id = ObjectHash::from_str(base_tree)
+                .map_err(|e| RevertError::LoadObject(e.to_string()))?;
+            restore_index_to_tree(&tree_id)?;
+        }
+        None => restore_to_orig_head(&state.orig_head).await?,
+    }
 
     // Clear the skipped commit's state before draining the rest, so a non-conflict
     // error among the remaining commits cannot leave stale state (see
     // `run_revert_continue`). A remaining conflict re-saves fresh state.
-    RevertState::cleanup()?;
+    RevertState::cleanup().await?;
 
     if state.remaining.is_empty() {
         // Nothing left after the skipped commit: the sequence is complete.
@@ -552,13 +623,13 @@ async fn run_revert_skip() -> Result<RevertOutput, RevertError> {
             short_reverted: short_display_hash(&commit_str).to_string(),
             new_commit: None,
             short_new: None,
-            no_commit: false,
+            no_commit: state.no_commit,
             files_changed: 0,
         });
     }
 
     let remaining = parse_remaining_ids(&state.remaining)?;
-    let params = RevertParams::for_sequence(state.signoff, state.edit);
+    let params = RevertParams::for_sequence(state.signoff, state.edit, state.no_commit);
     let outcome = revert_sequence(&remaining, &params, None, 0).await?;
 
     // INVARIANT: the empty-`remaining` case returned above, so `remaining`
@@ -572,7 +643,7 @@ async fn run_revert_skip() -> Result<RevertOutput, RevertError> {
         short_new: last_revert_commit
             .as_ref()
             .map(|id| short_display_hash(&id.to_string()).to_string()),
-        no_commit: false,
+        no_commit: state.no_commit,
         files_changed: total_files_changed,
     })
 }
@@ -587,24 +658,71 @@ async fn restore_to_orig_head(orig_head_str: &str) -> Result<(), RevertError> {
         ObjectHash::from_str(orig_head_str).map_err(|e| RevertError::LoadObject(e.to_string()))?;
     let commit: Commit =
         load_object(&orig_head).map_err(|e| RevertError::LoadObject(e.to_string()))?;
-    let tree: Tree =
-        load_object(&commit.tree_id).map_err(|e| RevertError::LoadObject(e.to_string()))?;
+    restore_index_to_tree(&commit.tree_id)?;
+    update_head(orig_head_str).await
+}
+
+/// Replace the index and tracked working-tree files with `tree_id`'s contents.
+fn restore_index_to_tree(tree_id: &ObjectHash) -> Result<(), RevertError> {
+    let tree: Tree = load_object(tree_id).map_err(|e| RevertError::LoadObject(e.to_string()))?;
     let mut new_index = Index::new();
     rebuild_index_from_tree(&tree, &mut new_index, "")?;
     let current_index = Index::load(path::index()).unwrap_or_else(|_| Index::new());
     reset_workdir_safely(&current_index, &new_index)?;
     new_index
         .save(path::index())
-        .map_err(|e| RevertError::IndexSave(e.to_string()))?;
-    update_head(orig_head_str).await
+        .map_err(|e| RevertError::IndexSave(e.to_string()))
+}
+
+/// Tree modes of the entries in a path → object map; paths missing from it
+/// are regular files.
+type FileModes = HashMap<PathBuf, TreeItemMode>;
+
+/// The stage-0 index entries as a path → blob map.
+fn index_files() -> (HashMap<PathBuf, ObjectHash>, FileModes) {
+    let index = Index::load(path::index()).unwrap_or_else(|_| Index::new());
+    index_entries(&index)
+}
+
+fn index_entries(index: &Index) -> (HashMap<PathBuf, ObjectHash>, FileModes) {
+    let mut files = HashMap::new();
+    let mut modes = FileModes::new();
+    for entry in index.tracked_entries(0) {
+        let path = PathBuf::from(&entry.name);
+        modes.insert(
+            path.clone(),
+            worktree::tree_mode_from_index_mode(entry.mode),
+        );
+        files.insert(path, entry.hash);
+    }
+    (files, modes)
+}
+
+/// A tree's leaves (gitlinks included) as a path → object map.
+fn tree_files(tree: &Tree) -> (HashMap<PathBuf, ObjectHash>, FileModes) {
+    let mut files = HashMap::new();
+    let mut modes = FileModes::new();
+    for (path, hash, mode) in tree.get_plain_items_with_mode() {
+        modes.insert(path.clone(), mode);
+        files.insert(path, hash);
+    }
+    (files, modes)
+}
+
+/// Write the stage-0 index entries as a tree.
+async fn index_tree() -> Result<ObjectHash, RevertError> {
+    let (files, modes) = index_files();
+    build_tree_from_map(files, &modes).await
 }
 
 /// `revert --abort`: reset HEAD/index/worktree to the pre-revert commit and clear
 /// the state.
 async fn run_revert_abort() -> Result<RevertOutput, RevertError> {
-    let state = RevertState::load_optional()?.ok_or(RevertError::NoRevertInProgress)?;
+    let state = RevertState::load_optional()
+        .await?
+        .ok_or(RevertError::NoRevertInProgress)?;
     restore_to_orig_head(&state.orig_head).await?;
-    RevertState::cleanup()?;
+    RevertState::cleanup().await?;
 
     let commit_str = state.reverted_commit.clone();
     Ok(RevertOutput {
@@ -646,11 +764,17 @@ fn render_revert_output(result: &RevertOutput, output: &OutputConfig) -> CliResu
 /// Conflict marker that introduces the "ours" side of a 3-way conflict.
 const CONFLICT_MARKER: &str = "<<<<<<<";
 
-/// Persisted state for an in-progress conflicted revert (`.libra/revert-state.json`),
-/// mirroring merge's file-based state so `revert --continue`/`--abort` can finish
-/// or unwind the revert.
-#[derive(Debug, Serialize, Deserialize)]
+/// In-progress revert sequence, persisted in the repo database's
+/// `revert_sequence` table the same way
+/// [`CherryPickState`](crate::command::cherry_pick::CherryPickState) persists
+/// cherry-pick's, so `revert --continue`/`--skip`/`--abort` can finish or unwind
+/// it. Older repositories may still hold the pre-database
+/// `.libra/revert-state.json`; it is read once and moved into the table.
+#[derive(Debug, Deserialize)]
 struct RevertState {
+    /// Branch HEAD pointed at when the sequence began.
+    #[serde(default)]
+    head_name: String,
     /// HEAD at the time the revert started — the `--abort` reset target and the
     /// parent of the eventual revert commit.
     orig_head: String,
@@ -661,45 +785,184 @@ struct RevertState {
     /// Whether `--edit` was requested, so `--continue` opens the editor too.
     #[serde(default)]
     edit: bool,
-    /// Commit specs still to revert after the current (conflicted) one — drained
+    /// Whether `--no-commit` was requested: `--continue` then stages the
+    /// remaining reverts instead of committing.
+    #[serde(default)]
+    no_commit: bool,
+    /// `--no-commit` only: the index tree before the conflicted revert, which
+    /// `--skip` restores so earlier staged reverts survive.
+    #[serde(default)]
+    base_tree: Option<String>,
+    /// Commit IDs still to revert after the current (conflicted) one — drained
     /// by `--continue`/`--skip`. Empty when the conflict was the last in the
-    /// sequence. `#[serde(default)]` keeps older state files loadable.
+    /// sequence.
     #[serde(default)]
     remaining: Vec<String>,
     /// Paths left with conflict markers for the user to resolve.
     conflicted_paths: Vec<String>,
 }
 
+/// The `opts_json` column of `revert_sequence`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RevertStateOpts {
+    signoff: bool,
+    #[serde(default)]
+    edit: bool,
+    #[serde(default)]
+    no_commit: bool,
+    #[serde(default)]
+    base_tree: Option<String>,
+    #[serde(default)]
+    conflicted_paths: Vec<String>,
+}
+
 impl RevertState {
-    fn path() -> PathBuf {
+    fn legacy_path() -> PathBuf {
         util::storage_path().join("revert-state.json")
     }
 
-    fn load_optional() -> Result<Option<Self>, RevertError> {
-        let path = Self::path();
+    async fn ensure_table_exists<C: ConnectionTrait>(db: &C) -> Result<(), RevertError> {
+        let create = Statement::from_string(
+            DbBackend::Sqlite,
+            r#"
+                CREATE TABLE IF NOT EXISTS `revert_sequence` (
+                    `id`          INTEGER PRIMARY KEY AUTOINCREMENT,
+                    `head_name`   TEXT NOT NULL,
+                    `head_orig`   TEXT NOT NULL,
+                    `current_oid` TEXT NOT NULL,
+                    `todo`        TEXT NOT NULL,
+                    `opts_json`   TEXT NOT NULL,
+                    `updated_at`  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+                );
+            "#
+            .to_string(),
+        );
+        db.execute(create).await.map_err(|e| {
+            RevertError::StateIo(format!("failed to create revert_sequence table: {e}"))
+        })?;
+        Ok(())
+    }
+
+    async fn load_optional() -> Result<Option<Self>, RevertError> {
+        let db = get_db_conn_instance().await;
+        Self::ensure_table_exists(&db).await?;
+        let stmt = Statement::from_string(
+            DbBackend::Sqlite,
+            r#"
+                SELECT head_name, head_orig, current_oid, todo, opts_json
+                FROM revert_sequence
+                LIMIT 1
+            "#
+            .to_string(),
+        );
+        let row = db
+            .query_one(stmt)
+            .await
+            .map_err(|e| RevertError::StateIo(format!("failed to load revert_sequence: {e}")))?;
+        let Some(row) = row else {
+            return Self::migrate_legacy_state().await;
+        };
+
+        let column = |index: usize, name: &str| -> Result<String, RevertError> {
+            row.try_get_by_index(index)
+                .map_err(|e| RevertError::StateIo(format!("invalid {name}: {e}")))
+        };
+        let head_name = column(0, "head_name")?;
+        let orig_head = column(1, "head_orig")?;
+        let reverted_commit = column(2, "current_oid")?;
+        let todo = column(3, "todo")?;
+        let opts: RevertStateOpts = serde_json::from_str(&column(4, "opts_json")?)
+            .map_err(|e| RevertError::StateIo(format!("invalid opts_json: {e}")))?;
+
+        Ok(Some(RevertState {
+            head_name,
+            orig_head,
+            reverted_commit,
+            signoff: opts.signoff,
+            edit: opts.edit,
+            no_commit: opts.no_commit,
+            base_tree: opts.base_tree,
+            remaining: todo
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+            conflicted_paths: opts.conflicted_paths,
+        }))
+    }
+
+    /// Move a pre-database `.libra/revert-state.json` into the table.
+    async fn migrate_legacy_state() -> Result<Option<Self>, RevertError> {
+        let path = Self::legacy_path();
         if !path.exists() {
             return Ok(None);
         }
         let data = fs::read_to_string(&path).map_err(|e| RevertError::StateIo(e.to_string()))?;
-        serde_json::from_str(&data)
-            .map(Some)
-            .map_err(|e| RevertError::StateIo(e.to_string()))
+        let state: RevertState =
+            serde_json::from_str(&data).map_err(|e| RevertError::StateIo(e.to_string()))?;
+        state.save().await?;
+        fs::remove_file(&path).map_err(|e| RevertError::StateIo(e.to_string()))?;
+        Ok(Some(state))
     }
 
-    fn save(&self) -> Result<(), RevertError> {
-        let path = Self::path();
-        let data =
-            serde_json::to_vec_pretty(self).map_err(|e| RevertError::StateIo(e.to_string()))?;
-        // Atomic + fsynced write (lore.md §7.7): recovery-critical sequencer
-        // state must never be left truncated by a crash.
-        crate::utils::atomic_write::write_atomic(&path, &data, true)
-            .map_err(|e| RevertError::StateIo(e.to_string()))
+    /// Replace the stored sequence with `self`; the `DELETE`+`INSERT` runs in
+    /// one transaction so a crash never leaves a half-written row.
+    async fn save(&self) -> Result<(), RevertError> {
+        let opts = serde_json::to_string(&RevertStateOpts {
+            signoff: self.signoff,
+            edit: self.edit,
+            no_commit: self.no_commit,
+            base_tree: self.base_tree.clone(),
+            conflicted_paths: self.conflicted_paths.clone(),
+        })
+        .map_err(|e| RevertError::StateIo(e.to_string()))?;
+
+        let db = get_db_conn_instance().await;
+        Self::ensure_table_exists(&db).await?;
+        let txn = db.begin().await.map_err(|e| {
+            RevertError::StateIo(format!("failed to begin revert_sequence transaction: {e}"))
+        })?;
+        txn.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "DELETE FROM revert_sequence".to_string(),
+        ))
+        .await
+        .map_err(|e| RevertError::StateIo(format!("failed to clear revert_sequence: {e}")))?;
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+                INSERT INTO revert_sequence
+                (head_name, head_orig, current_oid, todo, opts_json)
+                VALUES (?, ?, ?, ?, ?);
+            "#,
+            [
+                self.head_name.clone().into(),
+                self.orig_head.clone().into(),
+                self.reverted_commit.clone().into(),
+                self.remaining.join("\n").into(),
+                opts.into(),
+            ],
+        ))
+        .await
+        .map_err(|e| RevertError::StateIo(format!("failed to save revert_sequence: {e}")))?;
+        txn.commit().await.map_err(|e| {
+            RevertError::StateIo(format!("failed to commit revert_sequence transaction: {e}"))
+        })
     }
 
-    fn cleanup() -> Result<(), RevertError> {
-        let path = Self::path();
-        if path.exists() {
-            fs::remove_file(&path).map_err(|e| RevertError::StateIo(e.to_string()))?;
+    async fn cleanup() -> Result<(), RevertError> {
+        let db = get_db_conn_instance().await;
+        Self::ensure_table_exists(&db).await?;
+        db.execute(Statement::from_string(
+            DbBackend::Sqlite,
+            "DELETE FROM revert_sequence".to_string(),
+        ))
+        .await
+        .map_err(|e| RevertError::StateIo(format!("failed to clear revert_sequence: {e}")))?;
+        let legacy = Self::legacy_path();
+        if legacy.exists() {
+            fs::remove_file(&legacy).map_err(|e| RevertError::StateIo(e.to_string()))?;
         }
         Ok(())
     }
@@ -751,8 +1014,8 @@ fn three_way_revert_blob(
 
 /// The per-commit knobs that drive a single revert, decoupled from `RevertArgs`
 /// so the same logic serves the initial run and the `--continue`/`--skip`
-/// sequence drain (where `mainline`/`no_commit` never apply and `signoff`/`edit`
-/// come from the persisted [`RevertState`]).
+/// sequence drain (where `mainline` never applies and `signoff`/`edit`/
+/// `no_commit` come from the persisted [`RevertState`]).
 #[derive(Clone, Copy)]
 struct RevertParams {
     mainline: Option<usize>,
@@ -762,12 +1025,12 @@ struct RevertParams {
 }
 
 impl RevertParams {
-    /// Knobs for draining a `--continue`/`--skip` sequence: always commit, never
-    /// a mainline, carrying the original `--signoff`/`--edit` choices.
-    fn for_sequence(signoff: bool, edit: bool) -> Self {
+    /// Knobs for draining a `--continue`/`--skip` sequence: never a mainline,
+    /// carrying the original `--signoff`/`--edit`/`--no-commit` choices.
+    fn for_sequence(signoff: bool, edit: bool, no_commit: bool) -> Self {
         Self {
             mainline: None,
-            no_commit: false,
+            no_commit,
             signoff,
             edit,
         }
@@ -813,19 +1076,25 @@ async fn revert_single_commit(
     let current_commit: Commit =
         load_object(&current_head_commit_id).map_err(|e| RevertError::LoadObject(e.to_string()))?;
 
-    let current_tree: Tree =
-        load_object(&current_commit.tree_id).map_err(|e| RevertError::LoadObject(e.to_string()))?;
     let reverted_tree: Tree = load_object(&reverted_commit.tree_id)
         .map_err(|e| RevertError::LoadObject(e.to_string()))?;
     let parent_tree: Tree =
         load_object(&parent_commit.tree_id).map_err(|e| RevertError::LoadObject(e.to_string()))?;
 
-    let mut current_files: std::collections::HashMap<_, _> =
-        current_tree.get_plain_items().into_iter().collect();
-    let reverted_files: std::collections::HashMap<_, _> =
-        reverted_tree.get_plain_items().into_iter().collect();
-    let parent_files: std::collections::HashMap<_, _> =
-        parent_tree.get_plain_items().into_iter().collect();
+    // `--no-commit` reverts on top of the index rather than HEAD ("the revert
+    // is done against the beginning state of your index"), so several reverts
+    // can be staged before one commit.
+    let (mut current_files, mut modes) = if params.no_commit {
+        index_files()
+    } else {
+        let current_tree: Tree = load_object(&current_commit.tree_id)
+            .map_err(|e| RevertError::LoadObject(e.to_string()))?;
+        tree_files(&current_tree)
+    };
+    let (reverted_files, _) = tree_files(&reverted_tree);
+    let (parent_files, parent_modes) = tree_files(&parent_tree);
+    let is_gitlink =
+        |modes: &FileModes, path: &PathBuf| modes.get(path) == Some(&TreeItemMode::Commit);
 
     let mut files_changed: usize = 0;
     let mut conflicted_paths: Vec<String> = Vec::new();
@@ -842,6 +1111,11 @@ async fn revert_single_commit(
         // and record a conflict (with markers in the worktree) when both sides
         // touched overlapping regions.
         if current_files.get(path) != Some(&reverted_hash) && current_files.contains_key(path) {
+            // A submodule moved on since the reverted commit keeps its current
+            // commit; gitlinks have no content to merge.
+            if is_gitlink(&modes, path) || is_gitlink(&parent_modes, path) {
+                continue;
+            }
             let current_hash = current_files[path];
             let (merged_hash, conflicted) =
                 three_way_revert_blob(reverted_hash, current_hash, parent_hash.copied())?;
@@ -854,6 +1128,7 @@ async fn revert_single_commit(
         }
 
         if let Some(parent_hash) = parent_hash {
+            modes.insert(path.clone(), parent_modes[path]);
             if current_files.insert(path.clone(), *parent_hash) != Some(*parent_hash) {
                 files_changed += 1;
             }
@@ -863,14 +1138,16 @@ async fn revert_single_commit(
     }
 
     for (path, &parent_hash) in &parent_files {
-        if !reverted_files.contains_key(path)
-            && current_files.insert(path.clone(), parent_hash) != Some(parent_hash)
-        {
+        if reverted_files.contains_key(path) {
+            continue;
+        }
+        modes.insert(path.clone(), parent_modes[path]);
+        if current_files.insert(path.clone(), parent_hash) != Some(parent_hash) {
             files_changed += 1;
         }
     }
 
-    let final_tree_id = build_tree_from_map(current_files).await?;
+    let final_tree_id = build_tree_from_map(current_files, &modes).await?;
     let final_tree: Tree =
         load_object(&final_tree_id).map_err(|e| RevertError::LoadObject(e.to_string()))?;
 
@@ -912,10 +1189,12 @@ async fn revert_single_commit(
 
 async fn build_tree_from_map(
     files: std::collections::HashMap<PathBuf, ObjectHash>,
+    modes: &FileModes,
 ) -> Result<ObjectHash, RevertError> {
     fn build_subtree(
         paths: &std::collections::HashMap<PathBuf, ObjectHash>,
         current_dir: &PathBuf,
+        modes: &FileModes,
     ) -> Result<Tree, RevertError> {
         let mut tree_items = Vec::new();
         let mut subdirs = std::collections::HashMap::new();
@@ -923,7 +1202,7 @@ async fn build_tree_from_map(
             if let Ok(relative_path) = path.strip_prefix(current_dir) {
                 if relative_path.components().count() == 1 {
                     tree_items.push(git_internal::internal::object::tree::TreeItem {
-                        mode: git_internal::internal::object::tree::TreeItemMode::Blob,
+                        mode: modes.get(path).copied().unwrap_or(TreeItemMode::Blob),
                         name: path_to_utf8(relative_path)?.to_string(),
                         id: *hash,
                     });
@@ -943,7 +1222,7 @@ async fn build_tree_from_map(
             }
         }
         for (subdir, subdir_files) in subdirs {
-            let subdir_tree = build_subtree(&subdir_files.into_iter().collect(), &subdir)?;
+            let subdir_tree = build_subtree(&subdir_files.into_iter().collect(), &subdir, modes)?;
             tree_items.push(git_internal::internal::object::tree::TreeItem {
                 mode: git_internal::internal::object::tree::TreeItemMode::Tree,
                 name: file_name_to_utf8(&subdir)?,
@@ -955,7 +1234,7 @@ async fn build_tree_from_map(
     }
 
     let root_dir = PathBuf::new();
-    let root_tree = build_subtree(&files, &root_dir)?;
+    let root_tree = build_subtree(&files, &root_dir, modes)?;
     save_object(&root_tree, &root_tree.id).map_err(|e| RevertError::SaveObject(e.to_string()))?;
     Ok(root_tree.id)
 }
@@ -1016,8 +1295,16 @@ fn rebuild_index_from_tree(
             })?;
             rebuild_index_from_tree(&subtree, index, full_path_str)?;
         } else {
-            let blob = git_internal::internal::object::blob::Blob::load(&item.id);
-            let entry = IndexEntry::new_from_blob(
+            // A gitlink's commit lives in the submodule's repository, so it
+            // has no blob here to size.
+            let size = if item.mode == TreeItemMode::Commit {
+                0
+            } else {
+                git_internal::internal::object::blob::Blob::load(&item.id)
+                    .data
+                    .len() as u32
+            };
+            let mut entry = IndexEntry::new_from_blob(
                 full_path
                     .to_str()
                     .ok_or_else(|| {
@@ -1027,8 +1314,9 @@ fn rebuild_index_from_tree(
                     })?
                     .to_string(),
                 item.id,
-                blob.data.len() as u32,
+                size,
             );
+            entry.mode = worktree::index_mode_from_tree_mode(item.mode);
             index.add(entry);
         }
     }
@@ -1042,7 +1330,8 @@ fn reset_workdir_safely(current_index: &Index, new_index: &Index) -> Result<(),
     for path_buf in current_index.tracked_files() {
         if !new_tracked_paths.contains(&path_buf) {
             let full_path = workdir.join(path_buf);
-            if full_path.exists() {
+            // A submodule checkout is left in place, as Git does.
+            if full_path.exists() && !full_path.is_dir() {
                 fs::remove_file(&full_path).map_err(|e| {
                     RevertError::WriteWorktree(format!(
                         "failed to remove '{}': {e}",
@@ -1056,6 +1345,10 @@ fn reset_workdir_safely(current_index: &Index, new_index: &Index) -> Result<(),
     for path_buf in new_index.tracked_files() {
         let path_str = path_to_utf8(&path_buf)?;
         if let Some(entry) = new_index.get(path_str, 0) {
+            if entry.mode == 0o160000 {
+                // `libra submodule update` checks gitlinks out.
+                continue;
+            }
             let blob = git_internal::internal::object::blob::Blob::load(&entry.hash);
             let target_path = workdir.join(path_str);
             if let Some(parent) = target_path.parent() {
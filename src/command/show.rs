@@ -136,10 +136,14 @@ pub struct ShowArgs {
     #[clap(long = "no-mailmap")]
     pub no_mailmap: bool,
 
-    /// Do not display the GPG signature of signed commits. Accepted for Git
-    /// parity and is a no-op: Libra's show never displays commit signatures
-    /// inline. (Git's opposite `--show-signature` is not implemented.)
-    #[clap(long = "no-show-signature")]
+    /// Verify a signed commit's vault PGP signature and print the result under
+    /// the commit line.
+    #[clap(long = "show-signature", overrides_with = "no_show_signature")]
+    pub show_signature: bool,
+
+    /// Do not display the signature check (the default); countermands an
+    /// earlier `--show-signature` (last one wins).
+    #[clap(long = "no-show-signature", overrides_with = "show_signature")]
     pub no_show_signature: bool,
 
     /// Limit output to matching paths.
@@ -410,6 +414,11 @@ async fn show_commit(commit_hash: &ObjectHash, args: &ShowArgs) -> CliResult<Str
 
     let mut output = String::new();
     display_commit_info(&mut output, &commit, args);
+    if args.show_signature
+        && let Some(report) = crate::command::commit::describe_commit_signature(&commit).await
+    {
+        crate::command::log::insert_after_first_line(&mut output, &report);
+    }
 
     // Render patch-style details when requested.
     if !args.no_patch {
@@ -454,6 +463,7 @@ async fn show_commit(commit_hash: &ObjectHash, args: &ShowArgs) -> CliResult<Str
                         ChangeType::Added => "A",
                         ChangeType::Modified => "M",
                         ChangeType::Deleted => "D",
+                        ChangeType::Renamed { .. } => "R",
                     };
                     output.push_str(&format!("{}\t{}\n", status, file.path.display()));
                 }
@@ -726,49 +736,53 @@ fn validate_blob(hash: &ObjectHash) -> CliResult<()> {
     Ok(())
 }
 
-/// Shows a file from a specific revision.
-async fn show_commit_file(rev: &str, file_path: &str) -> CliResult<String> {
-    // Resolve the revision before looking up the path.
+/// Resolve `<rev>:<path>` to the object at `path` in the revision's tree,
+/// walking one component at a time so directories resolve too. An empty path
+/// (`HEAD:`) names the root tree.
+async fn resolve_revision_path(
+    rev: &str,
+    file_path: &str,
+) -> CliResult<(ObjectHash, TreeItemMode)> {
     let commit_hash = util::get_commit_base(rev)
         .await
         .map_err(|_| show_bad_revision_error(rev))?;
-
     let commit =
         load_object::<Commit>(&commit_hash).map_err(|e| show_object_load_error(commit_hash, e))?;
 
-    // Load the tree for the resolved commit.
-    let tree = load_object::<Tree>(&commit.tree_id)
-        .map_err(|e| show_object_load_error(commit.tree_id, e))?;
-
-    // Find the target path inside the tree.
-    let items = tree.get_plain_items();
-    let target_path = PathBuf::from(file_path);
+    let mut current = (commit.tree_id, TreeItemMode::Tree);
+    for component in file_path
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+    {
+        if current.1 != TreeItemMode::Tree {
+            return Err(show_path_not_found_error(file_path, rev));
+        }
+        let tree =
+            load_object::<Tree>(&current.0).map_err(|e| show_object_load_error(current.0, e))?;
+        let item = tree
+            .tree_items
+            .iter()
+            .find(|item| item.name == component)
+            .ok_or_else(|| show_path_not_found_error(file_path, rev))?;
+        current = (item.id, item.mode);
+    }
+    Ok(current)
+}
 
-    if let Some((_, blob_hash)) = items.iter().find(|(path, _)| path == &target_path) {
-        show_blob(blob_hash).await
-    } else {
-        Err(show_path_not_found_error(file_path, rev))
+/// Shows a file, or lists a directory, from a specific revision.
+async fn show_commit_file(rev: &str, file_path: &str) -> CliResult<String> {
+    match resolve_revision_path(rev, file_path).await? {
+        (hash, TreeItemMode::Tree) => show_tree(&hash).await,
+        (_, TreeItemMode::Commit) => Err(show_unsupported_object_type_error("commit (submodule)")),
+        (hash, _) => show_blob(&hash).await,
     }
 }
 
 async fn validate_commit_file(rev: &str, file_path: &str) -> CliResult<()> {
-    let commit_hash = util::get_commit_base(rev)
-        .await
-        .map_err(|_| show_bad_revision_error(rev))?;
-
-    let commit =
-        load_object::<Commit>(&commit_hash).map_err(|e| show_object_load_error(commit_hash, e))?;
-
-    let tree = load_object::<Tree>(&commit.tree_id)
-        .map_err(|e| show_object_load_error(commit.tree_id, e))?;
-
-    let items = tree.get_plain_items();
-    let target_path = PathBuf::from(file_path);
-
-    if let Some((_, blob_hash)) = items.iter().find(|(path, _)| path == &target_path) {
-        validate_blob(blob_hash)
-    } else {
-        Err(show_path_not_found_error(file_path, rev))
+    match resolve_revision_path(rev, file_path).await? {
+        (hash, TreeItemMode::Tree) => validate_tree(&hash),
+        (_, TreeItemMode::Commit) => Err(show_unsupported_object_type_error("commit (submodule)")),
+        (hash, _) => validate_blob(&hash),
     }
 }
 
@@ -842,7 +856,7 @@ async fn show_diffstat(commit: &Commit, paths: Vec<PathBuf>) -> CliResult<String
         match change.status {
             ChangeType::Added => additions += 1,
             ChangeType::Deleted => deletions += 1,
-            ChangeType::Modified => {
+            ChangeType::Modified | ChangeType::Renamed { .. } => {
                 additions += 1;
                 deletions += 1;
             }
@@ -851,6 +865,7 @@ async fn show_diffstat(commit: &Commit, paths: Vec<PathBuf>) -> CliResult<String
             ChangeType::Added => "A",
             ChangeType::Modified => "M",
             ChangeType::Deleted => "D",
+            ChangeType::Renamed { .. } => "R",
         };
         output.push_str(&format!("{}  {}\n", status, change.path.display()));
     }
@@ -1046,20 +1061,10 @@ async fn collect_blob_output(hash: &ObjectHash) -> CliResult<ShowOutput> {
 }
 
 async fn collect_commit_file_output(rev: &str, file_path: &str) -> CliResult<ShowOutput> {
-    let commit_hash = util::get_commit_base(rev)
-        .await
-        .map_err(|_| show_bad_revision_error(rev))?;
-    let commit =
-        load_object::<Commit>(&commit_hash).map_err(|e| show_object_load_error(commit_hash, e))?;
-    let tree = load_object::<Tree>(&commit.tree_id)
-        .map_err(|e| show_object_load_error(commit.tree_id, e))?;
-    let items = tree.get_plain_items();
-    let target_path = PathBuf::from(file_path);
-
-    if let Some((_, blob_hash)) = items.iter().find(|(path, _)| path == &target_path) {
-        collect_blob_output(blob_hash).await
-    } else {
-        Err(show_path_not_found_error(file_path, rev))
+    match resolve_revision_path(rev, file_path).await? {
+        (hash, TreeItemMode::Tree) => collect_tree_output(&hash).await,
+        (_, TreeItemMode::Commit) => Err(show_unsupported_object_type_error("commit (submodule)")),
+        (hash, _) => collect_blob_output(&hash).await,
     }
 }
 
@@ -1085,6 +1090,7 @@ fn change_type_name(change: ChangeType) -> &'static str {
         ChangeType::Added => "added",
         ChangeType::Modified => "modified",
         ChangeType::Deleted => "deleted",
+        ChangeType::Renamed { .. } => "renamed",
     }
 }
 
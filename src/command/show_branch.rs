@@ -0,0 +1,445 @@
+//! `libra show-branch` — show which commits are on which branches as a compact
+//! matrix, a focused subset of `git show-branch`.
+//!
+//! Each requested branch gets one marker column. Commits are listed newest
+//! first from the branch tips down to (and including) their common merge base,
+//! so the output shows exactly where the branches diverge. Commits reachable
+//! from every branch below the merge base are omitted. The merge base comes
+//! from the shared LCA implementation in [`crate::internal::merge_base`].
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+};
+
+use clap::Parser;
+use git_internal::{hash::ObjectHash, internal::object::commit::Commit};
+use serde::Serialize;
+
+use crate::{
+    common_utils::parse_commit_msg,
+    internal::{branch::Branch, config::ConfigKv, head::Head, merge_base},
+    utils::{
+        error::{CliError, CliResult, StableErrorCode, emit_warning},
+        object_ext::CommitExt,
+        output::{OutputConfig, emit_json_data},
+        util,
+    },
+};
+
+/// `--help` examples (cross-cutting EXAMPLES contract, `_general.md`).
+pub const SHOW_BRANCH_EXAMPLES: &str = "\
+EXAMPLES:
+    libra show-branch                      Compare every local branch
+    libra show-branch main feature         Compare two branches
+    libra show-branch -a                   Include remote-tracking branches
+    libra --json show-branch main feature  Structured { branches, commits }";
+
+/// Git's `show-branch` tracks each branch in one bit of a commit flag word,
+/// which caps it at 29 refs; keep the same limit so wide listings stay legible.
+const MAX_BRANCHES: usize = 29;
+
+/// Show branches and the commits that set them apart.
+#[derive(Parser, Debug)]
+#[command(after_help = SHOW_BRANCH_EXAMPLES)]
+pub struct ShowBranchArgs {
+    /// Show remote-tracking branches as well as local branches.
+    #[clap(short = 'a', long, conflicts_with = "remotes")]
+    pub all: bool,
+
+    /// Show only remote-tracking branches.
+    #[clap(short = 'r', long)]
+    pub remotes: bool,
+
+    /// Branches (or any commit-ish) to compare. Defaults to every local branch.
+    #[clap(value_name = "REV", conflicts_with_all = ["all", "remotes"])]
+    pub revs: Vec<String>,
+}
+
+/// One marker column: a branch tip and everything reachable from it.
+struct Column {
+    name: String,
+    tip: ObjectHash,
+    current: bool,
+    reach: HashSet<ObjectHash>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShowBranchOutput {
+    branches: Vec<ShowBranchHead>,
+    commits: Vec<ShowBranchCommit>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShowBranchHead {
+    name: String,
+    commit: String,
+    current: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ShowBranchCommit {
+    commit: String,
+    /// Name relative to the first branch that reaches it, e.g. `main~2`.
+    name: String,
+    subject: String,
+    /// One character per branch: `*` (current branch), `+` (other branch),
+    /// `-` (merge commit on that branch), or a space (not on that branch).
+    markers: String,
+}
+
+pub async fn execute(args: ShowBranchArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point. Exit 0 on success; exit 128 for an unresolvable revision
+/// or an unreadable commit.
+pub async fn execute_safe(args: ShowBranchArgs, output: &OutputConfig) -> CliResult<()> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+
+    let current = match Head::current().await {
+        Head::Branch(name) => Some(name),
+        Head::Detached(_) => None,
+    };
+    let mut heads = collect_heads(&args).await?;
+    if heads.len() > MAX_BRANCHES {
+        for (name, _) in heads.drain(MAX_BRANCHES..) {
+            emit_warning(format!(
+                "ignoring {name}; cannot handle more than {MAX_BRANCHES} refs"
+            ));
+        }
+    }
+
+    let mut commits = CommitCache::default();
+    let mut columns = Vec::with_capacity(heads.len());
+    for (name, tip) in heads {
+        let reach = commits.ancestors(&tip)?;
+        columns.push(Column {
+            current: current.as_deref() == Some(name.as_str()),
+            name,
+            tip,
+            reach,
+        });
+    }
+
+    let rows = matrix_rows(&columns, &mut commits)?;
+    let names = name_rows(&columns, &rows, &commits);
+    let report = ShowBranchOutput {
+        branches: columns
+            .iter()
+            .map(|column| ShowBranchHead {
+                name: column.name.clone(),
+                commit: column.tip.to_string(),
+                current: column.current,
+            })
+            .collect(),
+        commits: rows
+            .iter()
+            .map(|id| ShowBranchCommit {
+                commit: id.to_string(),
+                name: names.get(id).cloned().unwrap_or_else(|| short_id(id)),
+                subject: commits.subject(id),
+                markers: markers(&columns, id, commits.is_merge(id)),
+            })
+            .collect(),
+    };
+
+    if output.is_json() {
+        return emit_json_data("show-branch", &report, output);
+    }
+    if output.quiet {
+        return Ok(());
+    }
+    for line in render_lines(&report, &commits, &columns) {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// Resolve the `(display name, tip)` pairs to compare, in column order.
+async fn collect_heads(args: &ShowBranchArgs) -> CliResult<Vec<(String, ObjectHash)>> {
+    if !args.revs.is_empty() {
+        let mut heads = Vec::with_capacity(args.revs.len());
+        for rev in &args.revs {
+            let tip = util::get_commit_base(rev).await.map_err(|error| {
+                CliError::fatal(format!("not a valid commit '{rev}': {error}"))
+                    .with_exit_code(128)
+                    .with_stable_code(StableErrorCode::CliInvalidTarget)
+            })?;
+            heads.push((rev.clone(), tip));
+        }
+        return Ok(heads);
+    }
+
+    let store_error = |error: crate::internal::branch::BranchStoreError| {
+        CliError::fatal(format!("failed to list branches: {error}"))
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    };
+    let mut heads = Vec::new();
+    if !args.remotes {
+        let mut local = Branch::list_branches_result(None)
+            .await
+            .map_err(store_error)?;
+        local.sort_by(|a, b| a.name.cmp(&b.name));
+        heads.extend(local.into_iter().map(|branch| (branch.name, branch.commit)));
+    }
+    if args.all || args.remotes {
+        let remotes = ConfigKv::all_remote_configs().await.map_err(|error| {
+            CliError::fatal(format!("failed to read remote configuration: {error}"))
+                .with_stable_code(StableErrorCode::IoReadFailed)
+        })?;
+        let mut tracking = Vec::new();
+        for remote in remotes {
+            for branch in Branch::list_branches_result(Some(&remote.name))
+                .await
+                .map_err(store_error)?
+            {
+                let name = match branch.name.strip_prefix("refs/remotes/") {
+                    Some(stripped) => stripped.to_string(),
+                    None => format!("{}/{}", remote.name, branch.name),
+                };
+                tracking.push((name, branch.commit));
+            }
+        }
+        tracking.sort_by(|a, b| a.0.cmp(&b.0));
+        heads.extend(tracking);
+    }
+    Ok(heads)
+}
+
+/// Commits to list, newest first: everything reachable from some branch but
+/// not all of them, plus the merge base(s) where they meet. Parents always
+/// follow their children; among ready commits the newer one goes first, and
+/// ties prefer the commit on the leftmost column.
+fn matrix_rows(columns: &[Column], commits: &mut CommitCache) -> CliResult<Vec<ObjectHash>> {
+    if columns.is_empty() {
+        return Ok(Vec::new());
+    }
+    let tips: Vec<ObjectHash> = columns.iter().map(|column| column.tip).collect();
+    let bases = merge_base::octopus_merge_bases(&tips).map_err(|error| {
+        CliError::fatal(error.to_string())
+            .with_exit_code(128)
+            .with_stable_code(StableErrorCode::RepoCorrupt)
+    })?;
+
+    let mut shown: HashSet<ObjectHash> = bases.into_iter().collect();
+    for column in columns {
+        for id in &column.reach {
+            if !columns.iter().all(|other| other.reach.contains(id)) {
+                shown.insert(*id);
+            }
+        }
+    }
+
+    let mut pending: HashMap<ObjectHash, usize> = shown.iter().map(|id| (*id, 0)).collect();
+    for id in &shown {
+        for parent in commits.parents(id)? {
+            if let Some(count) = pending.get_mut(&parent) {
+                *count += 1;
+            }
+        }
+    }
+
+    let rank = |id: &ObjectHash, commits: &mut CommitCache| -> CliResult<RowRank> {
+        Ok(RowRank {
+            timestamp: commits.timestamp(id)?,
+            column: Reverse(
+                columns
+                    .iter()
+                    .position(|column| column.reach.contains(id))
+                    .unwrap_or(usize::MAX),
+            ),
+            id: Reverse(*id),
+        })
+    };
+    let mut ready = BinaryHeap::new();
+    for (id, count) in &pending {
+        if *count == 0 {
+            ready.push(rank(id, commits)?);
+        }
+    }
+
+    let mut rows = Vec::with_capacity(shown.len());
+    while let Some(RowRank {
+        id: Reverse(id), ..
+    }) = ready.pop()
+    {
+        rows.push(id);
+        for parent in commits.parents(&id)? {
+            if let Some(count) = pending.get_mut(&parent) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(rank(&parent, commits)?);
+                }
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Priority of a ready commit: newest first, then leftmost column, then id.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct RowRank {
+    timestamp: i64,
+    column: Reverse<usize>,
+    id: Reverse<ObjectHash>,
+}
+
+/// Name each listed commit relative to a branch tip (`main`, `main^`,
+/// `main~3`, `main^2~1`). Tips are named first in column order; every other
+/// commit takes its name from the first listed child that reaches it.
+fn name_rows(
+    columns: &[Column],
+    rows: &[ObjectHash],
+    commits: &CommitCache,
+) -> HashMap<ObjectHash, String> {
+    // (prefix, first-parent generations below the prefix)
+    let mut names: HashMap<ObjectHash, (String, usize)> = HashMap::new();
+    for column in columns {
+        names
+            .entry(column.tip)
+            .or_insert_with(|| (column.name.clone(), 0));
+    }
+    let listed: HashSet<&ObjectHash> = rows.iter().collect();
+    for id in rows {
+        let Some(name) = names.get(id).cloned() else {
+            continue;
+        };
+        for (index, parent) in commits.cached_parents(id).iter().enumerate() {
+            if !listed.contains(parent) || names.contains_key(parent) {
+                continue;
+            }
+            let parent_name = if index == 0 {
+                (name.0.clone(), name.1 + 1)
+            } else {
+                (format!("{}^{}", format_name(&name), index + 1), 0)
+            };
+            names.insert(*parent, parent_name);
+        }
+    }
+    names
+        .into_iter()
+        .map(|(id, name)| (id, format_name(&name)))
+        .collect()
+}
+
+fn format_name((prefix, generations): &(String, usize)) -> String {
+    match generations {
+        0 => prefix.clone(),
+        1 => format!("{prefix}^"),
+        n => format!("{prefix}~{n}"),
+    }
+}
+
+/// The marker column for `id`: `*`/`+` when the branch reaches it (`-` for a
+/// merge commit), a space otherwise.
+fn markers(columns: &[Column], id: &ObjectHash, is_merge: bool) -> String {
+    columns
+        .iter()
+        .map(
+            |column| match (column.reach.contains(id), is_merge, column.current) {
+                (false, _, _) => ' ',
+                (true, true, _) => '-',
+                (true, false, true) => '*',
+                (true, false, false) => '+',
+            },
+        )
+        .collect()
+}
+
+/// Header (one indented line per branch), a `-` separator, then one row per
+/// commit, as `git show-branch` prints them.
+fn render_lines(
+    report: &ShowBranchOutput,
+    commits: &CommitCache,
+    columns: &[Column],
+) -> Vec<String> {
+    let mut lines = Vec::with_capacity(columns.len() + report.commits.len() + 1);
+    for (index, column) in columns.iter().enumerate() {
+        let mark = if column.current { '*' } else { '!' };
+        lines.push(format!(
+            "{}{mark} [{}] {}",
+            " ".repeat(index),
+            column.name,
+            commits.subject(&column.tip)
+        ));
+    }
+    lines.push("-".repeat(columns.len()));
+    for row in &report.commits {
+        lines.push(format!("{} [{}] {}", row.markers, row.name, row.subject));
+    }
+    lines
+}
+
+fn short_id(id: &ObjectHash) -> String {
+    id.to_string().chars().take(7).collect()
+}
+
+/// Commits loaded so far, so each object is read at most once.
+#[derive(Default)]
+struct CommitCache {
+    commits: HashMap<ObjectHash, Commit>,
+}
+
+impl CommitCache {
+    fn get(&mut self, id: &ObjectHash) -> CliResult<&Commit> {
+        if !self.commits.contains_key(id) {
+            let commit = Commit::try_load(id).ok_or_else(|| {
+                CliError::fatal(format!("failed to load commit {id}"))
+                    .with_exit_code(128)
+                    .with_stable_code(StableErrorCode::RepoCorrupt)
+            })?;
+            self.commits.insert(*id, commit);
+        }
+        Ok(&self.commits[id])
+    }
+
+    fn parents(&mut self, id: &ObjectHash) -> CliResult<Vec<ObjectHash>> {
+        Ok(self.get(id)?.parent_commit_ids.clone())
+    }
+
+    fn timestamp(&mut self, id: &ObjectHash) -> CliResult<i64> {
+        Ok(self.get(id)?.committer.timestamp as i64)
+    }
+
+    /// Parents of a commit already loaded by [`Self::ancestors`].
+    fn cached_parents(&self, id: &ObjectHash) -> &[ObjectHash] {
+        self.commits
+            .get(id)
+            .map(|commit| commit.parent_commit_ids.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn is_merge(&self, id: &ObjectHash) -> bool {
+        self.cached_parents(id).len() > 1
+    }
+
+    fn subject(&self, id: &ObjectHash) -> String {
+        self.commits
+            .get(id)
+            .map(|commit| {
+                parse_commit_msg(&commit.message)
+                    .0
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Every commit reachable from `start`, inclusive.
+    fn ancestors(&mut self, start: &ObjectHash) -> CliResult<HashSet<ObjectHash>> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([*start]);
+        while let Some(id) = queue.pop_front() {
+            if seen.insert(id) {
+                queue.extend(self.parents(&id)?);
+            }
+        }
+        Ok(seen)
+    }
+}
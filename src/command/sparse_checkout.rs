@@ -0,0 +1,377 @@
+//! `libra sparse-checkout` — limit the working tree to a subset of the tracked
+//! paths while the index keeps every entry.
+//!
+//! `set` replaces the rules, `add` extends them, `list` prints them, `reparse`
+//! re-applies them to the working tree and `disable` restores every file. Cone
+//! mode (the default, as in Git) takes directories; `--no-cone` takes
+//! `.gitignore`-style patterns. The rules themselves live in
+//! [`crate::utils::sparse_checkout`], which `restore` (and so `switch` /
+//! `checkout`), `reset --hard`, `status` and `diff` consult.
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
+use git_internal::internal::index::Index;
+use serde::Serialize;
+
+use crate::{
+    command::{calc_file_blob_hash_with_attributes, restore},
+    internal::config::ConfigKv,
+    utils::{
+        error::{CliError, CliResult, StableErrorCode, emit_warning},
+        gitattributes::Attributes,
+        index_marks::{self, IndexMark},
+        output::{OutputConfig, emit_json_data},
+        path,
+        sparse_checkout::{self, SparseCheckout},
+        util, worktree,
+    },
+};
+
+pub const SPARSE_CHECKOUT_EXAMPLES: &str = "\
+EXAMPLES:
+    libra sparse-checkout set src docs        Check out only src/, docs/ and top-level files
+    libra sparse-checkout add tests           Also check out tests/
+    libra sparse-checkout set --no-cone '*.md'  Check out paths matching gitignore-style patterns
+    libra sparse-checkout list                Show the sparse directories or patterns
+    libra sparse-checkout reparse             Re-apply the rules to the working tree
+    libra sparse-checkout disable             Check out every tracked file again";
+
+/// Config key recording whether the rules were written in cone mode.
+const CONE_CONFIG: &str = "core.sparseCheckoutCone";
+
+/// Reduce the working tree to a subset of the tracked files.
+#[derive(Parser, Debug)]
+#[command(after_help = SPARSE_CHECKOUT_EXAMPLES)]
+pub struct SparseCheckoutArgs {
+    #[command(subcommand)]
+    pub command: SparseCheckoutSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SparseCheckoutSubcommand {
+    /// Enable sparse checkout with the given directories (or patterns) and
+    /// update the working tree.
+    Set {
+        /// Interpret arguments as directories (the default).
+        #[clap(long, conflicts_with = "no_cone")]
+        cone: bool,
+        /// Interpret arguments as gitignore-style patterns.
+        #[clap(long)]
+        no_cone: bool,
+        #[clap(value_name = "DIRECTORY|PATTERN")]
+        patterns: Vec<String>,
+    },
+    /// Add directories (or patterns) to the existing rules.
+    Add {
+        #[clap(value_name = "DIRECTORY|PATTERN", required = true)]
+        patterns: Vec<String>,
+    },
+    /// List the sparse directories (cone mode) or patterns.
+    List,
+    /// Re-apply the rules to the working tree.
+    Reparse,
+    /// Disable sparse checkout and restore every tracked file.
+    Disable,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SparseCheckoutError {
+    #[error("this worktree is not sparse")]
+    NotEnabled,
+    #[error("'{0}' is not a directory; cone mode only accepts directories")]
+    InvalidDirectory(String),
+    #[error("failed to read sparse-checkout rules: {0}")]
+    ReadRules(std::io::Error),
+    #[error("failed to write sparse-checkout rules: {0}")]
+    WriteRules(std::io::Error),
+    #[error("failed to load index: {0}")]
+    LoadIndex(String),
+    #[error("failed to update '{path}': {detail}")]
+    UpdateWorktree { path: String, detail: String },
+}
+
+impl From<SparseCheckoutError> for CliError {
+    fn from(error: SparseCheckoutError) -> Self {
+        let message = error.to_string();
+        match error {
+            SparseCheckoutError::NotEnabled => CliError::fatal(message)
+                .with_exit_code(128)
+                .with_stable_code(StableErrorCode::RepoStateInvalid)
+                .with_hint("use 'libra sparse-checkout set <directory>...' to enable it."),
+            SparseCheckoutError::InvalidDirectory(_) => CliError::command_usage(message)
+                .with_hint("use '--no-cone' to pass gitignore-style patterns."),
+            SparseCheckoutError::ReadRules(_) | SparseCheckoutError::LoadIndex(_) => {
+                CliError::fatal(message)
+                    .with_exit_code(128)
+                    .with_stable_code(StableErrorCode::IoReadFailed)
+            }
+            SparseCheckoutError::WriteRules(_) | SparseCheckoutError::UpdateWorktree { .. } => {
+                CliError::fatal(message)
+                    .with_exit_code(128)
+                    .with_stable_code(StableErrorCode::IoWriteFailed)
+            }
+        }
+    }
+}
+
+/// What a sparse-checkout pass changed in the working tree.
+#[derive(Debug, Default, Serialize)]
+pub struct WorktreeUpdate {
+    /// Included files that were missing and have been written.
+    pub materialized: Vec<String>,
+    /// Excluded files that matched the index and have been removed.
+    pub removed: Vec<String>,
+    /// Excluded files left in place because they have local modifications.
+    pub kept: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SparseCheckoutOutput {
+    action: &'static str,
+    enabled: bool,
+    cone: bool,
+    patterns: Vec<String>,
+    directories: Vec<String>,
+    #[serde(flatten)]
+    update: WorktreeUpdate,
+}
+
+pub async fn execute(args: SparseCheckoutArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point that returns structured [`CliResult`] instead of printing
+/// errors and exiting.
+///
+/// # Side Effects
+/// - Writes or removes `.libra/info/sparse-checkout` and the
+///   `core.sparseCheckout` / `core.sparseCheckoutCone` config.
+/// - Writes included files that are missing and removes unmodified excluded
+///   files.
+///
+/// # Errors
+/// Returns [`CliError`] outside a repository, when `add`/`list`/`reparse` run
+/// without sparse checkout enabled, when a cone-mode argument is not a
+/// directory, or when the rules or working tree cannot be updated.
+pub async fn execute_safe(args: SparseCheckoutArgs, output: &OutputConfig) -> CliResult<()> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+
+    let result = match args.command {
+        SparseCheckoutSubcommand::Set {
+            cone,
+            no_cone,
+            patterns,
+        } => {
+            let cone = if cone || no_cone {
+                cone
+            } else {
+                enabled_patterns()?.is_none() || is_cone_mode().await
+            };
+            let rules = if cone {
+                sparse_checkout::cone_patterns(&cone_arguments(&patterns)?)
+            } else {
+                patterns
+            };
+            apply_rules("set", cone, rules).await?
+        }
+        SparseCheckoutSubcommand::Add { patterns } => {
+            let mut rules = enabled_patterns()?.ok_or(SparseCheckoutError::NotEnabled)?;
+            let cone = is_cone_mode().await;
+            if cone {
+                let mut directories = sparse_checkout::cone_directories(&rules);
+                directories.extend(cone_arguments(&patterns)?);
+                rules = sparse_checkout::cone_patterns(&directories);
+            } else {
+                rules.extend(patterns);
+            }
+            apply_rules("add", cone, rules).await?
+        }
+        SparseCheckoutSubcommand::List => {
+            let rules = enabled_patterns()?.ok_or(SparseCheckoutError::NotEnabled)?;
+            let cone = is_cone_mode().await;
+            summary("list", true, cone, rules, WorktreeUpdate::default())
+        }
+        SparseCheckoutSubcommand::Reparse => {
+            let rules = enabled_patterns()?.ok_or(SparseCheckoutError::NotEnabled)?;
+            let cone = is_cone_mode().await;
+            let update = update_working_tree(Some(&SparseCheckout::from_patterns(&rules))).await?;
+            summary("reparse", true, cone, rules, update)
+        }
+        SparseCheckoutSubcommand::Disable => {
+            let update = update_working_tree(None).await?;
+            sparse_checkout::remove_patterns().map_err(SparseCheckoutError::WriteRules)?;
+            let _ = ConfigKv::set("core.sparseCheckout", "false", false).await;
+            summary("disable", false, false, Vec::new(), update)
+        }
+    };
+
+    render(&result, output)
+}
+
+/// Whether the stored rules are in cone mode (`core.sparseCheckoutCone`,
+/// default on as in Git).
+async fn is_cone_mode() -> bool {
+    !matches!(
+        ConfigKv::get(CONE_CONFIG)
+            .await
+            .ok()
+            .flatten()
+            .map(|entry| entry.value.trim().to_ascii_lowercase())
+            .as_deref(),
+        Some("false" | "0" | "no" | "off")
+    )
+}
+
+fn enabled_patterns() -> Result<Option<Vec<String>>, SparseCheckoutError> {
+    sparse_checkout::read_patterns().map_err(SparseCheckoutError::ReadRules)
+}
+
+fn cone_arguments(arguments: &[String]) -> Result<Vec<String>, SparseCheckoutError> {
+    arguments
+        .iter()
+        .map(|arg| {
+            sparse_checkout::normalize_cone_directory(arg)
+                .ok_or_else(|| SparseCheckoutError::InvalidDirectory(arg.clone()))
+        })
+        .collect()
+}
+
+async fn apply_rules(
+    action: &'static str,
+    cone: bool,
+    rules: Vec<String>,
+) -> Result<SparseCheckoutOutput, SparseCheckoutError> {
+    sparse_checkout::write_patterns(&rules).map_err(SparseCheckoutError::WriteRules)?;
+    let _ = ConfigKv::set("core.sparseCheckout", "true", false).await;
+    let _ = ConfigKv::set(CONE_CONFIG, if cone { "true" } else { "false" }, false).await;
+    let update = update_working_tree(Some(&SparseCheckout::from_patterns(&rules))).await?;
+    Ok(summary(action, true, cone, rules, update))
+}
+
+fn summary(
+    action: &'static str,
+    enabled: bool,
+    cone: bool,
+    patterns: Vec<String>,
+    update: WorktreeUpdate,
+) -> SparseCheckoutOutput {
+    let directories = if cone {
+        sparse_checkout::cone_directories(&patterns)
+    } else {
+        Vec::new()
+    };
+    SparseCheckoutOutput {
+        action,
+        enabled,
+        cone,
+        patterns,
+        directories,
+        update,
+    }
+}
+
+fn render(result: &SparseCheckoutOutput, output: &OutputConfig) -> CliResult<()> {
+    if output.is_json() {
+        return emit_json_data("sparse-checkout", result, output);
+    }
+    for path in &result.update.kept {
+        emit_warning(format!(
+            "not removing '{path}': it has local modifications outside the sparse-checkout"
+        ));
+    }
+    if result.action == "list" {
+        let lines = if result.cone {
+            &result.directories
+        } else {
+            &result.patterns
+        };
+        for line in lines {
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+/// Re-apply the active rules after a command rewrote the working tree from a
+/// full tree (`reset --hard`). A no-op while sparse checkout is disabled.
+pub(crate) async fn reapply() -> Result<(), SparseCheckoutError> {
+    if let Some(sparse) = SparseCheckout::load() {
+        let update = update_working_tree(Some(&sparse)).await?;
+        for path in update.kept {
+            emit_warning(format!(
+                "not removing '{path}': it has local modifications outside the sparse-checkout"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Bring the working tree in line with `sparse` (every tracked file when
+/// `None`): write included index entries that are missing, and remove
+/// excluded files whose content still matches the index. An excluded file
+/// with local modifications is kept and reported. The excluded entries left
+/// out of the working tree are then marked skip-worktree; disabling clears
+/// every skip-worktree mark, as Git does.
+pub(crate) async fn update_working_tree(
+    sparse: Option<&SparseCheckout>,
+) -> Result<WorktreeUpdate, SparseCheckoutError> {
+    let index = Index::load(path::index())
+        .map_err(|error| SparseCheckoutError::LoadIndex(error.to_string()))?;
+    let workdir = util::working_dir();
+    let attributes = Attributes::for_worktree().await;
+    let mut update = WorktreeUpdate::default();
+
+    for entry in index.tracked_entries(0) {
+        let relative = PathBuf::from(&entry.name);
+        let absolute = workdir.join(&relative);
+        let present = std::fs::symlink_metadata(&absolute).is_ok();
+        let included = sparse.is_none_or(|sparse| sparse.includes(&relative));
+
+        if included {
+            if !present {
+                let mode = worktree::tree_mode_from_index_mode(entry.mode);
+                restore::restore_to_file_with_attributes(&entry.hash, &relative, mode, &attributes)
+                    .await
+                    .map_err(|error| update_error(&entry.name, error))?;
+                update.materialized.push(entry.name.clone());
+            }
+        } else if present {
+            if is_unmodified(&absolute, &relative, &entry.hash, &attributes) {
+                std::fs::remove_file(&absolute)
+                    .map_err(|error| update_error(&entry.name, error))?;
+                util::clear_empty_dir(&absolute);
+                update.removed.push(entry.name.clone());
+            } else {
+                update.kept.push(entry.name.clone());
+            }
+        }
+    }
+    let skipped = sparse
+        .map(|sparse| sparse.skipped_entries(&index, &workdir))
+        .unwrap_or_default();
+    index_marks::replace(IndexMark::SkipWorktree, &skipped)
+        .map_err(SparseCheckoutError::WriteRules)?;
+    Ok(update)
+}
+
+fn is_unmodified(
+    absolute: &Path,
+    relative: &Path,
+    indexed: &git_internal::hash::ObjectHash,
+    attributes: &Attributes,
+) -> bool {
+    absolute.is_file()
+        && calc_file_blob_hash_with_attributes(absolute, relative, attributes)
+            .is_ok_and(|hash| &hash == indexed)
+}
+
+fn update_error(path: &str, error: std::io::Error) -> SparseCheckoutError {
+    SparseCheckoutError::UpdateWorktree {
+        path: path.to_string(),
+        detail: error.to_string(),
+    }
+}
@@ -42,6 +42,7 @@ use crate::{
     internal::{
         branch::{Branch as InternalBranch, BranchStoreError},
         head::Head,
+        tree_plumbing,
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
@@ -375,9 +376,10 @@ async fn run_push(options: StashPushOptions) -> Result<StashOutput, StashError>
         .ok_or(StashError::NoInitialCommit)?;
     let head_commit_hash_str = head_commit_hash.to_string();
 
-    let index_tree =
-        tree::create_tree_from_index(&index).map_err(|e| StashError::WriteObject(e.to_string()))?;
-    let index_tree_hash = index_tree.id;
+    // The index commit records the index as a commit would, so intent-to-add
+    // placeholders are left out.
+    let index_tree_hash = tree_plumbing::write_tree_from_index(&index)
+        .map_err(|e| StashError::WriteObject(e.to_string()))?;
 
     let (author, committer) = util::create_signatures().await;
     let (current_branch_name, head_commit_summary) = match Head::current().await {
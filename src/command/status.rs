@@ -1,7 +1,7 @@
 //! Implements status reporting with ignore policy support, computing staged/unstaged/untracked sets and printing concise summaries.
 
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashMap, HashSet},
     io,
     io::{IsTerminal, Write},
     path::PathBuf,
@@ -24,15 +24,18 @@ use serde::Serialize;
 
 use super::{merge, stash};
 use crate::{
-    command::calc_file_blob_hash,
+    command::{calc_file_blob_hash, calc_file_blob_hash_with_attributes},
     internal::{
         branch::{Branch, BranchStoreError},
         config::ConfigKv,
         head::Head,
+        merge_base,
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
+        gitattributes::Attributes,
         ignore::IgnorePolicy,
+        index_marks::{self, IndexMark},
         object_ext::{CommitExt, TreeExt},
         output::{ColorChoice, OutputConfig, emit_json_data},
         path, util,
@@ -219,6 +222,9 @@ pub struct Changes {
     pub deleted: Vec<PathBuf>,
     /// Detected renames: (source_path, target_path) pairs.
     pub renamed: Vec<(PathBuf, PathBuf)>,
+    /// Intent-to-add paths (`add -N`) among the unstaged `modified` entries:
+    /// rendered as new files with nothing staged (` A`).
+    pub intent_to_add: Vec<PathBuf>,
 }
 
 impl Changes {
@@ -232,11 +238,16 @@ impl Changes {
     /// to relative path(to cur_dir)
     pub fn to_relative(&self) -> Changes {
         let mut change = self.clone();
-        [&mut change.new, &mut change.modified, &mut change.deleted]
-            .into_iter()
-            .for_each(|paths| {
-                *paths = paths.iter().map(util::workdir_to_current).collect();
-            });
+        [
+            &mut change.new,
+            &mut change.modified,
+            &mut change.deleted,
+            &mut change.intent_to_add,
+        ]
+        .into_iter()
+        .for_each(|paths| {
+            *paths = paths.iter().map(util::workdir_to_current).collect();
+        });
         change.renamed = change
             .renamed
             .into_iter()
@@ -435,7 +446,7 @@ async fn collect_status_data(args: &StatusArgs) -> CliResult<StatusData> {
 
     // Resolve upstream tracking info
     let upstream = resolve_upstream_info(&head, head_oid.as_ref()).await?;
-    let merge_state = match merge::MergeState::load_optional_sync().map_err(|detail| {
+    let merge_state = match merge::MergeState::load_optional().await.map_err(|detail| {
         CliError::fatal(format!("failed to inspect merge state: {detail}"))
             .with_stable_code(StableErrorCode::IoReadFailed)
     })? {
@@ -617,8 +628,8 @@ pub(crate) fn load_status_index() -> CliResult<Index> {
 /// data}` envelope that `libra status --json` prints, so `/api/repo/status`
 /// stays byte-compatible with the CLI output.
 ///
-/// Internally re-uses [`collect_status_data`] + [`build_status_json`] with a
-/// default [`StatusArgs`] (untracked files in normal mode, no porcelain v2,
+/// Internally re-uses [`collect_status_json_data`], i.e. a default
+/// [`StatusArgs`] (untracked files in normal mode, no porcelain v2,
 /// no ignored files, no stash count).
 ///
 /// Status collection currently resolves storage from the process working
@@ -630,7 +641,6 @@ pub async fn collect_status_json_envelope_for_api(
 ) -> CliResult<serde_json::Value> {
     use std::path::PathBuf;
 
-    let args = StatusArgs::default();
     let canon_working =
         std::fs::canonicalize(working_dir).unwrap_or_else(|_| PathBuf::from(working_dir));
     let canon_cwd = std::env::current_dir()
@@ -647,15 +657,21 @@ pub async fn collect_status_json_envelope_for_api(
         )));
     }
 
-    let data = collect_status_data(&args).await?;
-    let inner = build_status_json(&data, &args);
     Ok(serde_json::json!({
         "ok": true,
         "command": "status",
-        "data": inner,
+        "data": collect_status_json_data().await?,
     }))
 }
 
+/// The `data` object of `libra status --json` for the current repository,
+/// collected with a default [`StatusArgs`].
+pub(crate) async fn collect_status_json_data() -> CliResult<serde_json::Value> {
+    let args = StatusArgs::default();
+    let data = collect_status_data(&args).await?;
+    Ok(build_status_json(&data, &args))
+}
+
 pub async fn execute(args: StatusArgs) {
     if let Err(err) = execute_to(args, &mut std::io::stdout()).await {
         err.print_stderr();
@@ -1075,7 +1091,7 @@ async fn run_status_cache_mode(args: &StatusArgs, output: &OutputConfig) -> CliR
     let staged = staged.to_relative();
     let unstaged = unstaged.to_relative();
     let upstream = resolve_upstream_info(&head, head_oid_hash.as_ref()).await?;
-    let merge_state = match merge::MergeState::load_optional_sync().map_err(|detail| {
+    let merge_state = match merge::MergeState::load_optional().await.map_err(|detail| {
         CliError::fatal(format!("failed to inspect merge state: {detail}"))
             .with_stable_code(StableErrorCode::IoReadFailed)
     })? {
@@ -1345,13 +1361,20 @@ fn render_human_status(
             "  use \"libra restore <file>...\" to discard changes in working directory"
         )
         .map_err(write_error)?;
+        let modified: Vec<PathBuf> = data
+            .unstaged
+            .modified
+            .iter()
+            .filter(|path| !data.unstaged.intent_to_add.contains(path))
+            .cloned()
+            .collect();
         let entries = build_human_entries(
             &data.unstaged.deleted,
             "deleted:",
-            &data.unstaged.modified,
+            &modified,
             "modified:",
-            &[],
-            "",
+            &data.unstaged.intent_to_add,
+            "new file:",
             &data.unstaged.renamed,
             "renamed:",
         );
@@ -1522,12 +1545,14 @@ fn render_merge_state_human(merge_state: &MergeStatusInfo, buffer: &mut Vec<u8>)
     )
     .map_err(write_error)?;
     if merge_state.conflicted_paths.is_empty() {
+        writeln!(buffer, "All conflicts fixed but you are still merging.").map_err(write_error)?;
         writeln!(
             buffer,
             "  (all conflicts fixed: run \"libra merge --continue\")"
         )
         .map_err(write_error)?;
     } else {
+        writeln!(buffer, "You have unmerged paths.").map_err(write_error)?;
         writeln!(
             buffer,
             "  (fix conflicts and run \"libra merge --continue\")"
@@ -1673,6 +1698,7 @@ fn build_status_json(data: &StatusData, _args: &StatusArgs) -> serde_json::Value
         },
         "untracked": paths_to_json(&data.unstaged.new),
         "ignored": paths_to_json(&data.ignored_files),
+        "files": build_status_entries_json(&data.staged, &data.unstaged, &data.ignored_files),
         "is_clean": !data.is_dirty(),
     });
 
@@ -1697,6 +1723,51 @@ fn build_status_json(data: &StatusData, _args: &StatusArgs) -> serde_json::Value
     json_data
 }
 
+/// Per-path records for `--json`: the same `XY` letters as the short format,
+/// with `.` for an unchanged side as in porcelain v2. A rename is reported
+/// once, under its destination, with the source in `orig_path`.
+fn build_status_entries_json(
+    staged: &Changes,
+    unstaged: &Changes,
+    ignored: &[PathBuf],
+) -> Vec<serde_json::Value> {
+    let renamed_from: HashMap<&PathBuf, &PathBuf> = staged
+        .renamed
+        .iter()
+        .chain(&unstaged.renamed)
+        .map(|(old, new)| (new, old))
+        .collect();
+    let rename_sources: HashSet<&PathBuf> = renamed_from.values().copied().collect();
+    let letter = |status: char| match status {
+        ' ' => ".".to_string(),
+        other => other.to_string(),
+    };
+
+    let mut entries: Vec<serde_json::Value> = generate_short_format_status(staged, unstaged)
+        .into_iter()
+        .filter(|(path, _, _)| !rename_sources.contains(path) || renamed_from.contains_key(path))
+        .map(|(path, index, worktree)| {
+            let mut entry = serde_json::json!({
+                "path": path.display().to_string(),
+                "index": letter(index),
+                "worktree": letter(worktree),
+            });
+            if let Some(orig) = renamed_from.get(&path) {
+                entry["orig_path"] = serde_json::json!(orig.display().to_string());
+            }
+            entry
+        })
+        .collect();
+    entries.extend(ignored.iter().map(|path| {
+        serde_json::json!({
+            "path": path.display().to_string(),
+            "index": "!",
+            "worktree": "!",
+        })
+    }));
+    entries
+}
+
 // ---------------------------------------------------------------------------
 // Porcelain v1
 // ---------------------------------------------------------------------------
@@ -1801,6 +1872,13 @@ fn is_submodule_mode(mode: u32) -> bool {
     mode == 0o160000
 }
 
+/// Whether the stage-0 index entry for `path` is a gitlink (submodule).
+fn is_gitlink_entry(index: &Index, path: &str) -> bool {
+    index
+        .get(path, 0)
+        .is_some_and(|entry| is_submodule_mode(entry.mode))
+}
+
 fn get_submodule_status(_file_path: &std::path::Path) -> String {
     "S...".to_string()
 }
@@ -1963,6 +2041,7 @@ pub fn generate_short_format_status(
     }
 
     process_unstaged_changes(&unstaged.modified, &mut file_status, 'M');
+    process_unstaged_changes(&unstaged.intent_to_add, &mut file_status, 'A');
     process_unstaged_changes(&unstaged.deleted, &mut file_status, 'D');
     for (old, new) in &unstaged.renamed {
         process_unstaged_changes(std::slice::from_ref(old), &mut file_status, 'R');
@@ -2285,84 +2364,21 @@ async fn resolve_upstream_info(
         }
     };
 
-    let (ahead, behind) = compute_ahead_behind(local_commit, &tracking_commit);
+    // A missing or corrupt commit (e.g. a shallow clone) leaves the counts
+    // unknown rather than reporting a misleading number.
+    let (ahead, behind) = match merge_base::ahead_behind(local_commit, &tracking_commit) {
+        Ok((ahead, behind)) => (Some(ahead), Some(behind)),
+        Err(_) => (None, None),
+    };
 
     Ok(Some(UpstreamInfo {
         remote_ref: remote_ref_display,
-        ahead: Some(ahead),
-        behind: Some(behind),
+        ahead,
+        behind,
         gone: false,
     }))
 }
 
-/// Compute the number of commits ahead/behind between two refs.
-///
-/// Performs a bidirectional BFS from both tips, classifying each commit as
-/// local-only, remote-only, or common (reachable from both sides).  Once a
-/// commit is found from the opposite side it is reclassified as common and
-/// its ancestors are not enqueued again, which reduces redundant work when
-/// the histories share a recent merge-base.
-///
-/// **Complexity**: proportional to the number of commits reachable from
-/// both tips until the queues are drained.  For disjoint histories (no
-/// common ancestor) this visits all reachable commits from both sides.
-/// Falls back gracefully when a commit object is missing or corrupt
-/// (e.g. shallow clone) by stopping traversal on that branch.
-pub(crate) fn compute_ahead_behind(local: &ObjectHash, remote: &ObjectHash) -> (usize, usize) {
-    if local == remote {
-        return (0, 0);
-    }
-
-    let mut local_only: HashSet<ObjectHash> = HashSet::new();
-    let mut remote_only: HashSet<ObjectHash> = HashSet::new();
-    let mut common: HashSet<ObjectHash> = HashSet::new();
-    let mut local_queue: VecDeque<ObjectHash> = VecDeque::new();
-    let mut remote_queue: VecDeque<ObjectHash> = VecDeque::new();
-
-    local_queue.push_back(*local);
-    remote_queue.push_back(*remote);
-
-    while !local_queue.is_empty() || !remote_queue.is_empty() {
-        // Expand one commit from the local side.
-        if let Some(hash) = local_queue.pop_front() {
-            if common.contains(&hash) {
-                // Already common — skip without expanding parents.
-                continue;
-            } else if remote_only.remove(&hash) {
-                // Discovered from the remote side too → merge-base.
-                common.insert(hash);
-            } else if local_only.insert(hash)
-                && let Some(commit) = Commit::try_load(&hash)
-            {
-                for parent in &commit.parent_commit_ids {
-                    if !common.contains(parent) {
-                        local_queue.push_back(*parent);
-                    }
-                }
-            }
-        }
-
-        // Expand one commit from the remote side.
-        if let Some(hash) = remote_queue.pop_front() {
-            if common.contains(&hash) {
-                continue;
-            } else if local_only.remove(&hash) {
-                common.insert(hash);
-            } else if remote_only.insert(hash)
-                && let Some(commit) = Commit::try_load(&hash)
-            {
-                for parent in &commit.parent_commit_ids {
-                    if !common.contains(parent) {
-                        remote_queue.push_back(*parent);
-                    }
-                }
-            }
-        }
-    }
-
-    (local_only.len(), remote_only.len())
-}
-
 // ---------------------------------------------------------------------------
 // Bare repository detection
 // ---------------------------------------------------------------------------
@@ -2480,7 +2496,16 @@ pub async fn changes_to_be_committed_safe() -> Result<Changes, StatusError> {
         source,
     })?;
     let head_commit = Head::current_commit().await;
-    let tracked_files = index.tracked_files();
+    // Intent-to-add placeholders carry no staged content.
+    let intent_paths: HashSet<PathBuf> = index_marks::active(&index, IndexMark::IntentToAdd)
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    let tracked_files: Vec<PathBuf> = index
+        .tracked_files()
+        .into_iter()
+        .filter(|path| !intent_paths.contains(path))
+        .collect();
 
     if head_commit.is_none() {
         changes.new = tracked_files;
@@ -2572,29 +2597,66 @@ pub fn changes_to_be_staged_split_force() -> Result<(Changes, Changes), StatusEr
     changes_to_be_staged_split_force_with_index(&workdir, &index)
 }
 
+/// Whether a stat-dirty tracked file's content differs from its index entry.
+/// A raw-hash mismatch is re-checked with the conversions `add` applies from
+/// the attributes (loaded once, on first need), so a file that differs only in
+/// line endings under `text`/`eol` is not reported as modified.
+fn tracked_content_differs(
+    index: &Index,
+    file: &std::path::Path,
+    file_abs: &std::path::Path,
+    workdir: &std::path::Path,
+    attributes: &mut Option<Attributes>,
+) -> Result<bool, StatusError> {
+    let file_str = file
+        .to_str()
+        .ok_or_else(|| StatusError::InvalidPathEncoding {
+            path: file.to_path_buf(),
+        })?;
+    let hash_error = |source| StatusError::FileHash {
+        path: file_abs.to_path_buf(),
+        source,
+    };
+    let file_hash = calc_file_blob_hash(file_abs).map_err(hash_error)?;
+    if index.verify_hash(file_str, 0, &file_hash) {
+        return Ok(false);
+    }
+    let attributes = attributes.get_or_insert_with(|| Attributes::load_default(workdir));
+    let normalized =
+        calc_file_blob_hash_with_attributes(file_abs, file, attributes).map_err(hash_error)?;
+    Ok(!index.verify_hash(file_str, 0, &normalized))
+}
+
 fn changes_to_be_staged_split_force_with_index(
     workdir: &PathBuf,
     index: &Index,
 ) -> Result<(Changes, Changes), StatusError> {
     let mut visible = Changes::default();
     let mut ignored = Changes::default();
+    let mut attributes = None;
     let tracked_files = index.tracked_files();
+    let intent_paths = index_marks::active(index, IndexMark::IntentToAdd);
+    let unchanged = index_marks::unchanged_paths(index);
     for file in tracked_files.iter() {
         let file_str = file
             .to_str()
             .ok_or_else(|| StatusError::InvalidPathEncoding { path: file.clone() })?;
+        // Skip-worktree / assume-unchanged entries always match the worktree;
+        // a gitlink names a commit of a nested repository, not a file.
+        if unchanged.contains(file_str) || is_gitlink_entry(index, file_str) {
+            continue;
+        }
         let file_abs = workdir.join(file);
         if !file_abs.exists() {
             visible.deleted.push(file.clone());
-        } else if index.is_modified(file_str, 0, workdir) {
-            let file_hash =
-                calc_file_blob_hash(&file_abs).map_err(|source| StatusError::FileHash {
-                    path: file_abs.clone(),
-                    source,
-                })?;
-            if !index.verify_hash(file_str, 0, &file_hash) {
-                visible.modified.push(file.clone());
-            }
+        } else if intent_paths.contains(file_str) {
+            // The whole file (even an empty one) is still waiting to be added.
+            visible.modified.push(file.clone());
+            visible.intent_to_add.push(file.clone());
+        } else if index.is_modified(file_str, 0, workdir)
+            && tracked_content_differs(index, file, &file_abs, workdir, &mut attributes)?
+        {
+            visible.modified.push(file.clone());
         }
     }
     let (files, ignored_files) = list_workdir_files_split_force(workdir).map_err(|source| {
@@ -2628,23 +2690,30 @@ fn changes_to_be_staged_split_with_index(
 ) -> Result<(Changes, Changes), StatusError> {
     let mut visible = Changes::default();
     let mut ignored = Changes::default();
+    let mut attributes = None;
     let tracked_files = index.tracked_files();
+    let intent_paths = index_marks::active(index, IndexMark::IntentToAdd);
+    let unchanged = index_marks::unchanged_paths(index);
     for file in tracked_files.iter() {
         let file_str = file
             .to_str()
             .ok_or_else(|| StatusError::InvalidPathEncoding { path: file.clone() })?;
+        // Skip-worktree / assume-unchanged entries always match the worktree;
+        // a gitlink names a commit of a nested repository, not a file.
+        if unchanged.contains(file_str) || is_gitlink_entry(index, file_str) {
+            continue;
+        }
         let file_abs = workdir.join(file);
         if !file_abs.exists() {
             visible.deleted.push(file.clone());
-        } else if index.is_modified(file_str, 0, workdir) {
-            let file_hash =
-                calc_file_blob_hash(&file_abs).map_err(|source| StatusError::FileHash {
-                    path: file_abs.clone(),
-                    source,
-                })?;
-            if !index.verify_hash(file_str, 0, &file_hash) {
-                visible.modified.push(file.clone());
-            }
+        } else if intent_paths.contains(file_str) {
+            // The whole file (even an empty one) is still waiting to be added.
+            visible.modified.push(file.clone());
+            visible.intent_to_add.push(file.clone());
+        } else if index.is_modified(file_str, 0, workdir)
+            && tracked_content_differs(index, file, &file_abs, workdir, &mut attributes)?
+        {
+            visible.modified.push(file.clone());
         }
     }
     let (files, ignored_files) =
@@ -2694,6 +2763,10 @@ fn list_workdir_files_split_safe(workdir: &PathBuf) -> io::Result<(Vec<PathBuf>,
                 .map_err(|err| io::Error::other(err.to_string()))?
                 .to_path_buf();
             if file_type.is_dir() {
+                if util::is_nested_repository(&path) {
+                    // A submodule (or other nested repository) is never walked.
+                    continue;
+                }
                 if util::check_gitignore(workdir, &path) {
                     ignored.push(relative);
                 } else {
@@ -2737,6 +2810,9 @@ fn list_workdir_files_split_force(workdir: &PathBuf) -> io::Result<(Vec<PathBuf>
                 .map_err(|err| io::Error::other(err.to_string()))?
                 .to_path_buf();
             if file_type.is_dir() {
+                if util::is_nested_repository(&path) {
+                    continue;
+                }
                 // Always recurse into directories, even ignored ones.
                 // We never push the directory entry itself — only its files
                 // — so `add --force` sees concrete blobs, not a path that
@@ -0,0 +1,776 @@
+//! `libra submodule` — record other repositories inside this one as gitlinks.
+//!
+//! `add` clones a repository into a path, records its URL in `.gitmodules` and
+//! stages a gitlink (a mode-`160000` index entry naming the commit checked out
+//! in the nested repository). `init` copies the URLs from `.gitmodules` into
+//! `submodule.<name>.url`, `update` clones missing submodules and checks out
+//! the pinned commit in each, and `status` compares the pinned commit with the
+//! one checked out. Nested repositories are driven through the `libra`
+//! executable itself, so their storage never mixes with this repository's.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+};
+
+use clap::{Parser, Subcommand};
+use git_internal::{
+    hash::ObjectHash,
+    internal::{
+        index::{Index, IndexEntry},
+        object::blob::Blob,
+    },
+};
+use serde::Serialize;
+
+use crate::{
+    internal::config::ConfigKv,
+    utils::{
+        error::{CliError, CliResult, StableErrorCode},
+        object_ext::BlobExt,
+        output::{OutputConfig, emit_json_data},
+        path, util,
+    },
+};
+
+pub const SUBMODULE_EXAMPLES: &str = "\
+EXAMPLES:
+    libra submodule add ../lib.git vendor/lib    Clone a repository into vendor/lib and pin it
+    libra submodule status                       Show the pinned and checked-out commit of each submodule
+    libra submodule init                         Register the URLs from .gitmodules in config
+    libra submodule update --init                Clone missing submodules and check out the pinned commits
+    libra --json submodule status                Structured JSON output for agents";
+
+/// File recording each submodule's name, path and URL, as in Git.
+pub const GITMODULES: &str = ".gitmodules";
+
+/// Index mode of a gitlink entry.
+const GITLINK_MODE: u32 = 0o160000;
+
+/// Manage repositories nested in this one.
+#[derive(Parser, Debug)]
+#[command(after_help = SUBMODULE_EXAMPLES)]
+pub struct SubmoduleArgs {
+    #[command(subcommand)]
+    pub command: SubmoduleSubcommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SubmoduleSubcommand {
+    /// Clone a repository into <path>, record it in .gitmodules and stage the
+    /// commit it has checked out.
+    Add {
+        /// Name of the submodule (defaults to its path).
+        #[clap(long)]
+        name: Option<String>,
+        /// Repository to clone. `./` and `../` URLs are relative to the
+        /// `origin` remote, or to this worktree when there is none.
+        url: String,
+        /// Where to place the submodule, relative to the current directory.
+        path: String,
+    },
+    /// Register the URLs from .gitmodules in config.
+    Init {
+        #[clap(value_name = "PATH")]
+        paths: Vec<String>,
+    },
+    /// Clone missing submodules and check out the commit each one is pinned to.
+    Update {
+        /// Register uninitialized submodules first (as `init` does).
+        #[clap(long)]
+        init: bool,
+        #[clap(value_name = "PATH")]
+        paths: Vec<String>,
+    },
+    /// Show the pinned and checked-out commit of each submodule.
+    Status {
+        #[clap(value_name = "PATH")]
+        paths: Vec<String>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SubmoduleError {
+    #[error("'{0}' already exists in the index")]
+    AlreadyExists(String),
+    #[error("'{0}' already exists and is not an empty directory")]
+    PathNotEmpty(String),
+    #[error("path '{0}' is outside the repository")]
+    OutsideRepository(String),
+    #[error("no submodule mapping found in .gitmodules for path '{0}'")]
+    NoMapping(String),
+    #[error("clone of '{url}' into submodule path '{path}' failed: {detail}")]
+    CloneFailed {
+        url: String,
+        path: String,
+        detail: String,
+    },
+    #[error("'libra {command}' failed in submodule path '{path}': {detail}")]
+    Nested {
+        command: String,
+        path: String,
+        detail: String,
+    },
+    #[error("failed to read .gitmodules: {0}")]
+    ReadGitmodules(std::io::Error),
+    #[error("failed to write .gitmodules: {0}")]
+    WriteGitmodules(std::io::Error),
+    #[error("failed to update the index: {0}")]
+    Index(String),
+    #[error("failed to update config: {0}")]
+    Config(String),
+}
+
+impl From<SubmoduleError> for CliError {
+    fn from(error: SubmoduleError) -> Self {
+        let message = error.to_string();
+        match error {
+            SubmoduleError::AlreadyExists(_)
+            | SubmoduleError::PathNotEmpty(_)
+            | SubmoduleError::OutsideRepository(_)
+            | SubmoduleError::NoMapping(_)
+            | SubmoduleError::CloneFailed { .. } => CliError::fatal(message)
+                .with_exit_code(128)
+                .with_stable_code(StableErrorCode::CliInvalidTarget),
+            SubmoduleError::Nested { .. } => CliError::fatal(message)
+                .with_exit_code(128)
+                .with_stable_code(StableErrorCode::RepoStateInvalid),
+            SubmoduleError::ReadGitmodules(_) => CliError::fatal(message)
+                .with_exit_code(128)
+                .with_stable_code(StableErrorCode::IoReadFailed),
+            SubmoduleError::WriteGitmodules(_)
+            | SubmoduleError::Index(_)
+            | SubmoduleError::Config(_) => CliError::fatal(message)
+                .with_exit_code(128)
+                .with_stable_code(StableErrorCode::IoWriteFailed),
+        }
+    }
+}
+
+/// One `[submodule "<name>"]` section of `.gitmodules`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GitmodulesEntry {
+    name: String,
+    path: String,
+    url: String,
+}
+
+/// A submodule as reported by every subcommand.
+#[derive(Debug, Serialize)]
+struct SubmoduleInfo {
+    name: String,
+    path: String,
+    /// URL registered in config, falling back to the `.gitmodules` one.
+    url: Option<String>,
+    /// Commit recorded by the gitlink in the index.
+    pinned: Option<String>,
+    /// Commit checked out in the nested repository, if it exists.
+    checked_out: Option<String>,
+    /// `added`, `registered`, `already_registered`, `checked_out`,
+    /// `uninitialized`, `current` or `modified`.
+    state: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct SubmoduleOutput {
+    action: &'static str,
+    submodules: Vec<SubmoduleInfo>,
+}
+
+pub async fn execute(args: SubmoduleArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point that returns structured [`CliResult`] instead of printing
+/// errors and exiting.
+///
+/// # Side Effects
+/// - `add` clones into the submodule path, appends to and stages
+///   `.gitmodules`, stages the gitlink and sets `submodule.<name>.url`.
+/// - `init` (and `update --init`) set `submodule.<name>.url`.
+/// - `update` clones into submodule paths and switches nested repositories to
+///   their pinned commits.
+///
+/// # Errors
+/// Returns [`CliError`] outside a repository, for a path that is already
+/// tracked or not an empty directory, for a path without a `.gitmodules`
+/// mapping, and when cloning or updating a nested repository fails.
+pub async fn execute_safe(args: SubmoduleArgs, output: &OutputConfig) -> CliResult<()> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+
+    let result = match args.command {
+        SubmoduleSubcommand::Add { name, url, path } => SubmoduleOutput {
+            action: "add",
+            submodules: vec![add(name, &url, &path).await?],
+        },
+        SubmoduleSubcommand::Init { paths } => SubmoduleOutput {
+            action: "init",
+            submodules: init(&paths).await?,
+        },
+        SubmoduleSubcommand::Update { init, paths } => SubmoduleOutput {
+            action: "update",
+            submodules: update(init, &paths).await?,
+        },
+        SubmoduleSubcommand::Status { paths } => SubmoduleOutput {
+            action: "status",
+            submodules: status(&paths).await?,
+        },
+    };
+
+    render(&result, output)
+}
+
+async fn add(
+    name: Option<String>,
+    url: &str,
+    path_arg: &str,
+) -> Result<SubmoduleInfo, SubmoduleError> {
+    let workdir = util::working_dir();
+    let path = workdir_path(&workdir, path_arg)?;
+    let name = name.unwrap_or_else(|| path.clone());
+
+    let index_file = path::index();
+    let mut index = Index::load(&index_file).map_err(|e| SubmoduleError::Index(e.to_string()))?;
+    let modules = read_gitmodules(&workdir)?;
+    if index.tracked(&path, 0)
+        || index.contains_dir_file(&path)
+        || modules
+            .iter()
+            .any(|module| module.name == name || module.path == path)
+    {
+        return Err(SubmoduleError::AlreadyExists(path));
+    }
+
+    let resolved = resolve_url(url, &url_base(&workdir).await);
+    let absolute = workdir.join(&path);
+    if !util::is_nested_repository(&absolute) {
+        if is_occupied(&absolute) {
+            return Err(SubmoduleError::PathNotEmpty(path));
+        }
+        clone_into(&workdir, &resolved, &absolute, &path)?;
+    }
+    let head = checked_out_commit(&absolute).ok_or_else(|| SubmoduleError::Nested {
+        command: "rev-parse HEAD".to_string(),
+        path: path.clone(),
+        detail: "the repository has no commit checked out".to_string(),
+    })?;
+
+    append_gitmodules(
+        &workdir,
+        &GitmodulesEntry {
+            name: name.clone(),
+            path: path.clone(),
+            url: url.to_string(),
+        },
+    )?;
+    let gitmodules = Blob::from_content_bytes(
+        std::fs::read(workdir.join(GITMODULES)).map_err(SubmoduleError::ReadGitmodules)?,
+    );
+    gitmodules.save();
+    index.update(
+        IndexEntry::new_from_file(Path::new(GITMODULES), gitmodules.id, &workdir)
+            .map_err(|e| SubmoduleError::Index(e.to_string()))?,
+    );
+    let mut gitlink = IndexEntry::new_from_blob(path.clone(), head, 0);
+    gitlink.mode = GITLINK_MODE;
+    index.update(gitlink);
+    index
+        .save(&index_file)
+        .map_err(|e| SubmoduleError::Index(e.to_string()))?;
+    set_url(&name, &resolved).await?;
+
+    Ok(SubmoduleInfo {
+        name,
+        path,
+        url: Some(resolved),
+        pinned: Some(head.to_string()),
+        checked_out: Some(head.to_string()),
+        state: "added",
+    })
+}
+
+async fn init(paths: &[String]) -> Result<Vec<SubmoduleInfo>, SubmoduleError> {
+    let workdir = util::working_dir();
+    let index = load_index()?;
+    let base = url_base(&workdir).await;
+    let mut infos = Vec::new();
+    for module in select_modules(&workdir, paths)? {
+        let (url, state) = match configured_url(&module.name).await {
+            Some(url) => (url, "already_registered"),
+            None => {
+                let url = resolve_url(&module.url, &base);
+                set_url(&module.name, &url).await?;
+                (url, "registered")
+            }
+        };
+        infos.push(SubmoduleInfo {
+            pinned: pinned_commit(&index, &module.path).map(|id| id.to_string()),
+            checked_out: checked_out_commit(&workdir.join(&module.path)).map(|id| id.to_string()),
+            name: module.name,
+            path: module.path,
+            url: Some(url),
+            state,
+        });
+    }
+    Ok(infos)
+}
+
+async fn update(init: bool, paths: &[String]) -> Result<Vec<SubmoduleInfo>, SubmoduleError> {
+    let workdir = util::working_dir();
+    let index = load_index()?;
+    let base = url_base(&workdir).await;
+    let modules = read_gitmodules(&workdir)?;
+    let mut infos = Vec::new();
+    for (path, pinned) in select_gitlinks(&workdir, &index, paths)? {
+        let module = modules
+            .iter()
+            .find(|module| module.path == path)
+            .ok_or_else(|| SubmoduleError::NoMapping(path.clone()))?;
+        let url = match configured_url(&module.name).await {
+            Some(url) => Some(url),
+            None if init => {
+                let url = resolve_url(&module.url, &base);
+                set_url(&module.name, &url).await?;
+                Some(url)
+            }
+            None => None,
+        };
+        let absolute = workdir.join(&path);
+        let Some(url) = url else {
+            // Like Git, an uninitialized submodule is left alone.
+            infos.push(SubmoduleInfo {
+                name: module.name.clone(),
+                path,
+                url: None,
+                pinned: Some(pinned.to_string()),
+                checked_out: checked_out_commit(&absolute).map(|id| id.to_string()),
+                state: "uninitialized",
+            });
+            continue;
+        };
+
+        if !util::is_nested_repository(&absolute) {
+            if is_occupied(&absolute) {
+                return Err(SubmoduleError::PathNotEmpty(path));
+            }
+            clone_into(&workdir, &url, &absolute, &path)?;
+        }
+        let state = if checked_out_commit(&absolute) == Some(pinned) {
+            "current"
+        } else {
+            let commit = pinned.to_string();
+            if run_nested(&absolute, &path, &["cat-file", "-e", &commit]).is_err() {
+                run_nested(&absolute, &path, &["fetch"])?;
+            }
+            run_nested(&absolute, &path, &["switch", "--detach", &commit])?;
+            "checked_out"
+        };
+        infos.push(SubmoduleInfo {
+            name: module.name.clone(),
+            path,
+            url: Some(url),
+            pinned: Some(pinned.to_string()),
+            checked_out: checked_out_commit(&absolute).map(|id| id.to_string()),
+            state,
+        });
+    }
+    Ok(infos)
+}
+
+async fn status(paths: &[String]) -> Result<Vec<SubmoduleInfo>, SubmoduleError> {
+    let workdir = util::working_dir();
+    let index = load_index()?;
+    let modules = read_gitmodules(&workdir)?;
+    let mut infos = Vec::new();
+    for (path, pinned) in select_gitlinks(&workdir, &index, paths)? {
+        let module = modules.iter().find(|module| module.path == path);
+        let name = module.map_or_else(|| path.clone(), |module| module.name.clone());
+        let url = match configured_url(&name).await {
+            Some(url) => Some(url),
+            None => module.map(|module| module.url.clone()),
+        };
+        let checked_out = checked_out_commit(&workdir.join(&path));
+        let state = match checked_out {
+            None => "uninitialized",
+            Some(commit) if commit == pinned => "current",
+            Some(_) => "modified",
+        };
+        infos.push(SubmoduleInfo {
+            name,
+            path,
+            url,
+            pinned: Some(pinned.to_string()),
+            checked_out: checked_out.map(|id| id.to_string()),
+            state,
+        });
+    }
+    Ok(infos)
+}
+
+/// Run `libra submodule update --init` in the freshly cloned worktree at
+/// `worktree` (`clone --recurse-submodules`) and return what it printed.
+pub(crate) fn update_in(worktree: &Path) -> Result<String, SubmoduleError> {
+    run_libra(worktree, &["submodule", "update", "--init"]).map_err(|detail| {
+        SubmoduleError::Nested {
+            command: "submodule update --init".to_string(),
+            path: worktree.display().to_string(),
+            detail,
+        }
+    })
+}
+
+fn render(result: &SubmoduleOutput, output: &OutputConfig) -> CliResult<()> {
+    if output.is_json() {
+        return emit_json_data("submodule", result, output);
+    }
+    if output.quiet {
+        return Ok(());
+    }
+    for info in &result.submodules {
+        match (result.action, info.state) {
+            ("add", _) => println!(
+                "Added submodule '{}' at {}",
+                info.path,
+                info.pinned.as_deref().unwrap_or_default()
+            ),
+            ("init", "registered") => println!(
+                "Submodule '{}' ({}) registered for path '{}'",
+                info.name,
+                info.url.as_deref().unwrap_or_default(),
+                info.path
+            ),
+            ("update", "checked_out") => println!(
+                "Submodule path '{}': checked out '{}'",
+                info.path,
+                info.pinned.as_deref().unwrap_or_default()
+            ),
+            ("status", state) => {
+                let (marker, commit) = match state {
+                    "uninitialized" => ('-', &info.pinned),
+                    "modified" => ('+', &info.checked_out),
+                    _ => (' ', &info.pinned),
+                };
+                println!(
+                    "{marker}{} {}",
+                    commit.as_deref().unwrap_or_default(),
+                    info.path
+                );
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn load_index() -> Result<Index, SubmoduleError> {
+    Index::load(path::index()).map_err(|e| SubmoduleError::Index(e.to_string()))
+}
+
+/// `path_arg` (relative to the current directory) as a `/`-separated path
+/// relative to the worktree root.
+fn workdir_path(workdir: &Path, path_arg: &str) -> Result<String, SubmoduleError> {
+    let absolute = util::cur_dir().join(path_arg);
+    if !util::is_sub_path(&absolute, workdir) {
+        return Err(SubmoduleError::OutsideRepository(path_arg.to_string()));
+    }
+    let relative = util::to_workdir_path(&absolute)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .trim_end_matches('/')
+        .to_string();
+    if relative.is_empty() || relative == "." {
+        return Err(SubmoduleError::OutsideRepository(path_arg.to_string()));
+    }
+    Ok(relative)
+}
+
+/// The gitlinks in the index as `(path, pinned commit)`, narrowed to `paths`
+/// when any are given.
+fn select_gitlinks(
+    workdir: &Path,
+    index: &Index,
+    paths: &[String],
+) -> Result<Vec<(String, ObjectHash)>, SubmoduleError> {
+    let gitlinks: BTreeMap<String, ObjectHash> = index
+        .tracked_entries(0)
+        .into_iter()
+        .filter(|entry| entry.mode == GITLINK_MODE)
+        .map(|entry| (entry.name.clone(), entry.hash))
+        .collect();
+    if paths.is_empty() {
+        return Ok(gitlinks.into_iter().collect());
+    }
+    paths
+        .iter()
+        .map(|arg| {
+            let path = workdir_path(workdir, arg)?;
+            let pinned = gitlinks
+                .get(&path)
+                .copied()
+                .ok_or_else(|| SubmoduleError::NoMapping(path.clone()))?;
+            Ok((path, pinned))
+        })
+        .collect()
+}
+
+/// The `.gitmodules` entries, narrowed to `paths` when any are given.
+fn select_modules(
+    workdir: &Path,
+    paths: &[String],
+) -> Result<Vec<GitmodulesEntry>, SubmoduleError> {
+    let modules = read_gitmodules(workdir)?;
+    if paths.is_empty() {
+        return Ok(modules);
+    }
+    paths
+        .iter()
+        .map(|arg| {
+            let path = workdir_path(workdir, arg)?;
+            modules
+                .iter()
+                .find(|module| module.path == path)
+                .cloned()
+                .ok_or(SubmoduleError::NoMapping(path))
+        })
+        .collect()
+}
+
+/// Whether something other than an empty directory is at `absolute`, so a
+/// submodule cannot be cloned there.
+fn is_occupied(absolute: &Path) -> bool {
+    absolute.exists()
+        && std::fs::read_dir(absolute).map_or(true, |mut entries| entries.next().is_some())
+}
+
+fn pinned_commit(index: &Index, path: &str) -> Option<ObjectHash> {
+    index
+        .get(path, 0)
+        .filter(|entry| entry.mode == GITLINK_MODE)
+        .map(|entry| entry.hash)
+}
+
+/// The commit checked out in the nested repository at `absolute`, if there is
+/// one.
+fn checked_out_commit(absolute: &Path) -> Option<ObjectHash> {
+    if !util::is_nested_repository(absolute) {
+        return None;
+    }
+    let head = run_libra(absolute, &["rev-parse", "HEAD"]).ok()?;
+    ObjectHash::from_str(&head).ok()
+}
+
+fn read_gitmodules(workdir: &Path) -> Result<Vec<GitmodulesEntry>, SubmoduleError> {
+    match std::fs::read_to_string(workdir.join(GITMODULES)) {
+        Ok(text) => Ok(parse_gitmodules(&text)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(SubmoduleError::ReadGitmodules(error)),
+    }
+}
+
+/// Parse the `[submodule "<name>"]` sections of a `.gitmodules` file.
+/// Sections without both a `path` and a `url` are ignored.
+fn parse_gitmodules(text: &str) -> Vec<GitmodulesEntry> {
+    let mut sections: Vec<(String, Option<String>, Option<String>)> = Vec::new();
+    let mut in_submodule = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_submodule = false;
+            if let Some(name) = header
+                .trim()
+                .strip_prefix("submodule")
+                .map(str::trim)
+                .and_then(|rest| rest.strip_prefix('"'))
+                .and_then(|rest| rest.strip_suffix('"'))
+            {
+                sections.push((name.to_string(), None, None));
+                in_submodule = true;
+            }
+            continue;
+        }
+        let (Some(section), true) = (sections.last_mut(), in_submodule) else {
+            continue;
+        };
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "path" => section.1 = Some(value),
+                "url" => section.2 = Some(value),
+                _ => {}
+            }
+        }
+    }
+    sections
+        .into_iter()
+        .filter_map(|(name, path, url)| {
+            Some(GitmodulesEntry {
+                name,
+                path: path?,
+                url: url?,
+            })
+        })
+        .collect()
+}
+
+/// Append a section for `module`, keeping the rest of the file as written.
+fn append_gitmodules(workdir: &Path, module: &GitmodulesEntry) -> Result<(), SubmoduleError> {
+    let file = workdir.join(GITMODULES);
+    let mut text = match std::fs::read_to_string(&file) {
+        Ok(text) => text,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(SubmoduleError::ReadGitmodules(error)),
+    };
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "[submodule \"{}\"]\n\tpath = {}\n\turl = {}\n",
+        module.name, module.path, module.url
+    ));
+    std::fs::write(&file, text).map_err(SubmoduleError::WriteGitmodules)
+}
+
+/// What `./` and `../` URLs are relative to: the `origin` remote's URL, or
+/// this worktree when there is no such remote.
+async fn url_base(workdir: &Path) -> String {
+    ConfigKv::get("remote.origin.url")
+        .await
+        .ok()
+        .flatten()
+        .map(|entry| entry.value)
+        .unwrap_or_else(|| workdir.to_string_lossy().replace('\\', "/"))
+}
+
+/// Resolve a `./` or `../` submodule URL against `base`, as Git does: each
+/// `../` drops one trailing component of `base`. Other URLs are returned as
+/// given.
+fn resolve_url(url: &str, base: &str) -> String {
+    if !(url.starts_with("./") || url.starts_with("../")) {
+        return url.to_string();
+    }
+    let mut base = base.trim_end_matches('/').to_string();
+    let mut rest = url;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("./") {
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("../") {
+            rest = stripped;
+            if let Some(slash) = base.rfind('/') {
+                base.truncate(slash);
+            }
+        } else {
+            break;
+        }
+    }
+    format!("{base}/{rest}")
+}
+
+async fn configured_url(name: &str) -> Option<String> {
+    ConfigKv::get(&format!("submodule.{name}.url"))
+        .await
+        .ok()
+        .flatten()
+        .map(|entry| entry.value)
+}
+
+async fn set_url(name: &str, url: &str) -> Result<(), SubmoduleError> {
+    ConfigKv::set(&format!("submodule.{name}.url"), url, false)
+        .await
+        .map_err(|e| SubmoduleError::Config(e.to_string()))
+}
+
+fn clone_into(
+    workdir: &Path,
+    url: &str,
+    absolute: &Path,
+    path: &str,
+) -> Result<(), SubmoduleError> {
+    let target = absolute.to_string_lossy();
+    run_libra(workdir, &["clone", "--no-progress", url, &target]).map_err(|detail| {
+        SubmoduleError::CloneFailed {
+            url: url.to_string(),
+            path: path.to_string(),
+            detail,
+        }
+    })?;
+    Ok(())
+}
+
+fn run_nested(absolute: &Path, path: &str, args: &[&str]) -> Result<String, SubmoduleError> {
+    run_libra(absolute, args).map_err(|detail| SubmoduleError::Nested {
+        command: args.join(" "),
+        path: path.to_string(),
+        detail,
+    })
+}
+
+/// Run `libra <args>` in `dir` and return its trimmed stdout, or the last
+/// line of its stderr when it fails.
+fn run_libra(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let exe: PathBuf = std::env::current_exe().map_err(|e| e.to_string())?;
+    let output = Command::new(exe)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("exited with an error")
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_gitmodules_reads_sections_with_path_and_url() {
+        let text = "\
+# comment
+[submodule \"lib\"]
+\tpath = vendor/lib
+\turl = ../lib.git
+[core]
+\tpath = ignored
+[submodule \"broken\"]
+\tpath = nowhere
+";
+        assert_eq!(
+            parse_gitmodules(text),
+            [GitmodulesEntry {
+                name: "lib".to_string(),
+                path: "vendor/lib".to_string(),
+                url: "../lib.git".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn resolve_url_walks_up_the_base_for_each_parent_step() {
+        assert_eq!(
+            resolve_url("../lib.git", "https://example.com/org/app.git"),
+            "https://example.com/org/lib.git"
+        );
+        assert_eq!(resolve_url("./lib", "/work/app/"), "/work/app/lib");
+        assert_eq!(resolve_url("../../lib", "/work/app"), "/lib");
+        assert_eq!(
+            resolve_url("https://example.com/lib.git", "/work/app"),
+            "https://example.com/lib.git"
+        );
+    }
+}
@@ -13,8 +13,7 @@ use super::{
     status,
 };
 use crate::{
-    command::{load_object, save_object, status::StatusArgs},
-    common_utils::format_commit_msg,
+    command::{load_object, status::StatusArgs},
     internal::{
         ai::automation::{VCS_EVENT_POST_SWITCH, dispatch_current_repo_vcs_event_to_history},
         branch::{self as repo_branch, Branch},
@@ -25,6 +24,7 @@ use crate::{
     },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
+        git_hooks,
         output::{OutputConfig, emit_json_data},
         path,
         text::levenshtein,
@@ -45,6 +45,8 @@ EXAMPLES:
     libra switch -c fix-123 abc1234        Create branch from specific commit
     libra switch --detach v1.0             Detach HEAD at a tag
     libra switch --track origin/main       Track and switch to remote branch
+    libra switch -c topic --track origin/main  Create topic from origin/main and track it
+    libra switch -                         Switch back to the previously checked-out branch
     libra switch feature                   Auto-create a tracking branch from a unique remote (guess)
     libra switch --no-guess feature        Disable remote-tracking guessing
     libra switch --json main               Structured JSON output for agents";
@@ -63,7 +65,8 @@ pub struct SwitchArgs {
     #[clap(long, short = 'C', group = "sub")]
     pub force_create: Option<String>,
 
-    /// Create a new orphan branch with no parents and an empty tree, and switch to it
+    /// Switch to a new unborn branch with an empty index and no tracked files;
+    /// its first commit has no parents
     #[clap(long, group = "sub")]
     pub orphan: Option<String>,
 
@@ -71,12 +74,10 @@ pub struct SwitchArgs {
     #[clap(long, short, action, default_value = "false", group = "sub")]
     pub detach: bool,
 
-    #[clap(
-        short = 't',
-        long,
-        conflicts_with_all = ["create", "detach"],
-        help = "Set upstream tracking when switching to remote branch"
-    )]
+    /// Set upstream tracking when switching to a remote branch. With
+    /// `-c`/`-C`, the start point must be a remote-tracking branch and the new
+    /// branch tracks it.
+    #[clap(short = 't', long, conflicts_with_all = ["detach", "orphan"])]
     pub track: bool,
 
     /// Proceed even with local changes, discarding them when switching to a
@@ -161,9 +162,6 @@ pub enum SwitchError {
     #[error("failed to delete existing branch '{branch}': {detail}")]
     BranchDelete { branch: String, detail: String },
 
-    #[error("failed to create orphan root commit for branch '{0}'")]
-    OrphanCommitCreate(String),
-
     #[error("'{0}' is a reserved branch name")]
     InternalBranchBlocked(String),
 
@@ -251,9 +249,6 @@ impl From<SwitchError> for CliError {
             SwitchError::BranchDelete { ref branch, ref detail } => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::IoWriteFailed)
                 .with_hint(format!("branch '{branch}' could not be deleted before force-create: {detail}")),
-            SwitchError::OrphanCommitCreate(ref name) => CliError::fatal(error.to_string())
-                .with_stable_code(StableErrorCode::IoWriteFailed)
-                .with_hint(format!("failed to create orphan root commit for branch '{name}'")),
             SwitchError::InternalBranchBlocked(..) => CliError::fatal(error.to_string())
                 .with_stable_code(StableErrorCode::CliInvalidTarget),
             SwitchError::DirtyUnstaged => CliError::fatal(error.to_string())
@@ -560,6 +555,23 @@ fn parse_remote_switch_target(target: &str) -> Result<(String, String), SwitchEr
 
 async fn resolve_tracked_remote_target(
     target: &str,
+) -> Result<ResolvedTrackedRemoteTarget, SwitchError> {
+    let resolved = resolve_remote_start_point(target).await?;
+    if Branch::find_branch_result(&resolved.remote_branch, None)
+        .await
+        .map_err(map_branch_store_error)?
+        .is_some()
+    {
+        return Err(SwitchError::BranchAlreadyExists(resolved.remote_branch));
+    }
+    Ok(resolved)
+}
+
+/// Resolve a `<remote>/<branch>` remote-tracking ref to its commit, without
+/// requiring that no local branch of the same name exists (the `-c --track`
+/// case names the new branch explicitly).
+async fn resolve_remote_start_point(
+    target: &str,
 ) -> Result<ResolvedTrackedRemoteTarget, SwitchError> {
     let (remote_name, remote_branch_name) = parse_remote_switch_target(target)?;
 
@@ -573,13 +585,6 @@ async fn resolve_tracked_remote_target(
             remote: remote_name.clone(),
             branch: remote_branch_name.clone(),
         })?;
-    if Branch::find_branch_result(&remote_branch_name, None)
-        .await
-        .map_err(map_branch_store_error)?
-        .is_some()
-    {
-        return Err(SwitchError::BranchAlreadyExists(remote_branch_name));
-    }
     Ok(ResolvedTrackedRemoteTarget {
         remote: remote_name,
         remote_branch: remote_branch_name,
@@ -725,6 +730,15 @@ pub async fn execute(args: SwitchArgs) {
 pub async fn execute_safe(args: SwitchArgs, output: &OutputConfig) -> CliResult<()> {
     let result = run_switch(args, output).await.map_err(CliError::from)?;
     render_switch_output(&result, output)?;
+    // Like Git, `post-checkout` runs even when already on the branch; an
+    // orphan branch has no commit yet and passes the zero id.
+    git_hooks::run_post_checkout_hook(
+        result.previous_commit.as_deref(),
+        Some(result.commit.as_str()).filter(|commit| !commit.is_empty()),
+        true,
+        output,
+    )
+    .await;
     if !result.already_on {
         dispatch_current_repo_vcs_event_to_history(VCS_EVENT_POST_SWITCH).await;
     }
@@ -746,7 +760,7 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
     } = args;
     let (previous_branch, previous_commit) = current_switch_state().await;
 
-    if track {
+    if track && create.is_none() && force_create.is_none() {
         let target = branch.ok_or(SwitchError::MissingTrackTarget)?;
         let tracked_target = resolve_tracked_remote_target(&target).await?;
         ensure_switch_clean_or_force(force, tracked_target.commit, output).await?;
@@ -767,6 +781,21 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
         });
     }
 
+    // `-c`/`-C` with `--track`: the start point is a remote-tracking branch,
+    // resolved once so the new branch is created at its commit and tracks it.
+    let start_tracking = match (&create, &force_create) {
+        (None, None) => None,
+        _ if track => {
+            let start = branch.as_deref().ok_or(SwitchError::MissingTrackTarget)?;
+            Some(resolve_remote_start_point(start).await?)
+        }
+        _ => None,
+    };
+    let branch = match &start_tracking {
+        Some(start) => Some(start.commit.to_string()),
+        None => branch,
+    };
+
     if let Some(new_branch_name) = create {
         validate_new_branch_request(&new_branch_name, branch.as_deref(), false).await?;
         match resolve_create_switch_target(branch.as_deref()).await? {
@@ -777,6 +806,10 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
         }
 
         branch::create_branch_safe(new_branch_name.clone(), branch).await?;
+        let tracking = match start_tracking {
+            Some(start) => Some(track_start_point(&new_branch_name, start, output).await?),
+            None => None,
+        };
         let created_branch = resolve_created_branch(&new_branch_name).await?;
         let commit = switch_to_resolved_branch(created_branch, output).await?;
         return Ok(SwitchOutput {
@@ -787,7 +820,7 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
             created: true,
             detached: false,
             already_on: false,
-            tracking: None,
+            tracking,
         });
     }
 
@@ -820,6 +853,10 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
             None => ensure_clean_status(output).await?,
         }
         branch::create_branch_safe(new_branch_name.clone(), branch).await?;
+        let tracking = match start_tracking {
+            Some(start) => Some(track_start_point(&new_branch_name, start, output).await?),
+            None => None,
+        };
         let created_branch = resolve_created_branch(&new_branch_name).await?;
         let commit = switch_to_resolved_branch(created_branch, output).await?;
         return Ok(SwitchOutput {
@@ -830,54 +867,22 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
             created: true,
             detached: false,
             already_on: false,
-            tracking: None,
+            tracking,
         });
     }
 
     if let Some(new_branch_name) = orphan {
-        validate_new_branch_request(&new_branch_name, None, true).await?;
-        if let Some(existing) = Branch::find_branch_result(&new_branch_name, None)
-            .await
-            .map_err(map_branch_store_error)?
-        {
-            if Some(existing.name.as_str()) == previous_branch.as_deref() {
-                return Err(SwitchError::DelegatedCli(
-                    CliError::fatal(format!(
-                        "cannot create orphan from the currently checked-out branch '{}'",
-                        new_branch_name
-                    ))
-                    .with_stable_code(StableErrorCode::ConflictOperationBlocked),
-                ));
-            }
-            Branch::delete_branch_result(&new_branch_name, None)
-                .await
-                .map_err(|e| SwitchError::BranchDelete {
-                    branch: new_branch_name.clone(),
-                    detail: e.to_string(),
-                })?;
-        }
+        validate_new_branch_request(&new_branch_name, None, false).await?;
         ensure_clean_status(output).await?;
-        let orphan_commit = create_orphan_root_commit().await.map_err(|e| {
-            SwitchError::DelegatedCli(
-                CliError::fatal(format!(
-                    "failed to create orphan root commit for branch '{new_branch_name}': {e}"
-                ))
-                .with_stable_code(StableErrorCode::IoWriteFailed),
-            )
-        })?;
-        Branch::update_branch(&new_branch_name, &orphan_commit.to_string(), None)
-            .await
-            .map_err(|e| SwitchError::BranchCreate {
-                branch: new_branch_name.clone(),
-                detail: e.to_string(),
-            })?;
-        let created_branch = resolve_created_branch(&new_branch_name).await?;
-        let commit = switch_to_resolved_branch(created_branch, output).await?;
+        clear_tracked_worktree()?;
+        // HEAD now names a branch that has no ref yet (unborn, like a freshly
+        // initialised repository); the first commit creates it without parents.
+        Head::update(Head::Branch(new_branch_name.clone()), None).await;
         return Ok(SwitchOutput {
             previous_branch,
             previous_commit,
             branch: Some(new_branch_name),
-            commit: commit.to_string(),
+            commit: String::new(),
             created: true,
             detached: false,
             already_on: false,
@@ -906,6 +911,7 @@ async fn run_switch(args: SwitchArgs, output: &OutputConfig) -> Result<SwitchOut
     }
 
     let branch = branch.ok_or(SwitchError::MissingBranchName)?;
+    let branch = resolve_previous_branch_shorthand(branch).await?;
     match resolve_switch_branch_target(&branch, guess, no_guess).await? {
         SwitchTarget::Local(target_branch) => {
             if previous_branch.as_deref() == Some(&branch) {
@@ -1055,6 +1061,50 @@ async fn switch_to_tracked_remote_branch(
     })
 }
 
+/// Record `start` as the upstream of the freshly created `local_branch`
+/// (`switch -c <name> --track <remote>/<branch>`).
+async fn track_start_point(
+    local_branch: &str,
+    start: ResolvedTrackedRemoteTarget,
+    output: &OutputConfig,
+) -> Result<SwitchTrackingInfo, SwitchError> {
+    let mut upstream_output = output.clone();
+    if output.is_json() {
+        upstream_output.quiet = true;
+    }
+    branch::set_upstream_safe_with_output(
+        local_branch,
+        &format!("{}/{}", start.remote, start.remote_branch),
+        &upstream_output,
+    )
+    .await?;
+    Ok(SwitchTrackingInfo {
+        remote: start.remote,
+        remote_branch: start.remote_branch,
+    })
+}
+
+/// Expand `-` (short for `@{-1}`) and `@{-N}` to the N-th branch checked out
+/// before the current one; any other name is returned unchanged.
+async fn resolve_previous_branch_shorthand(name: String) -> Result<String, SwitchError> {
+    let n = if name == "-" {
+        1
+    } else if let Some(n) = name
+        .strip_prefix("@{-")
+        .and_then(|rest| rest.strip_suffix('}'))
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+    {
+        n
+    } else {
+        return Ok(name);
+    };
+    util::previous_branch_name(n)
+        .await
+        .map_err(|error| SwitchError::CommitResolve(error.to_string()))?
+        .ok_or_else(|| SwitchError::CommitResolve(format!("{name}: no previous branch")))
+}
+
 /// change the working directory to the version of commit_hash
 async fn switch_to_commit(
     commit_hash: ObjectHash,
@@ -1214,69 +1264,31 @@ fn render_switch_output(result: &SwitchOutput, output: &OutputConfig) -> CliResu
     Ok(())
 }
 
-async fn create_orphan_root_commit() -> Result<ObjectHash, CliError> {
-    use git_internal::internal::object::{
-        blob::Blob,
-        commit::Commit,
-        signature::{Signature, SignatureType},
-        tree::{Tree, TreeItem, TreeItemMode},
-    };
-
-    let name = ConfigKv::get("user.name")
-        .await
-        .ok()
-        .flatten()
-        .map(|v| v.value)
-        .unwrap_or_else(|| "Libra Orphan".to_string());
-    let email = ConfigKv::get("user.email")
-        .await
-        .ok()
-        .flatten()
-        .map(|v| v.value)
-        .unwrap_or_else(|| "orphan@libra".to_string());
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::SystemTime::UNIX_EPOCH)
-        .map(|d| d.as_secs() as usize)
-        .unwrap_or(0);
-    let author = Signature {
-        signature_type: SignatureType::Author,
-        name: name.clone(),
-        email: email.clone(),
-        timestamp,
-        timezone: "+0000".to_string(),
-    };
-    let committer = Signature {
-        signature_type: SignatureType::Committer,
-        name,
-        email,
-        timestamp,
-        timezone: "+0000".to_string(),
-    };
-    let blob = Blob::from_content("");
-    save_object(&blob, &blob.id).map_err(|e| {
-        CliError::fatal(format!("failed to save orphan blob object: {e}"))
-            .with_stable_code(StableErrorCode::IoWriteFailed)
-    })?;
-    let tree_item = TreeItem {
-        mode: TreeItemMode::Blob,
-        name: ".librakeep".to_string(),
-        id: blob.id,
+/// Empty the index and remove every tracked file from the working tree for
+/// `--orphan` (Git's `switch --orphan`, i.e. `git rm -rf .` on the new branch).
+/// Untracked and ignored files are left in place.
+fn clear_tracked_worktree() -> Result<(), SwitchError> {
+    let write_failed = |message: String| {
+        SwitchError::DelegatedCli(
+            CliError::fatal(message).with_stable_code(StableErrorCode::IoWriteFailed),
+        )
     };
-    let tree = Tree::from_tree_items(vec![tree_item]).map_err(|e| {
-        CliError::fatal(format!("failed to create orphan tree object: {e}"))
-            .with_stable_code(StableErrorCode::IoWriteFailed)
-    })?;
-    save_object(&tree, &tree.id).map_err(|e| {
-        CliError::fatal(format!("failed to save orphan tree object: {e}"))
-            .with_stable_code(StableErrorCode::IoWriteFailed)
-    })?;
-    let message = format_commit_msg("orphan branch root commit", None);
-    let commit = Commit::new(author, committer, tree.id, Vec::new(), &message);
-    save_object(&commit, &commit.id).map_err(|e| {
-        CliError::fatal(format!("failed to save orphan commit object: {e}"))
-            .with_stable_code(StableErrorCode::IoWriteFailed)
-    })?;
-    Ok(commit.id)
+    let index_path = path::index();
+    let index =
+        Index::load(&index_path).map_err(|err| SwitchError::StatusCheck(err.to_string()))?;
+    let workdir = util::working_dir();
+    for file in index.tracked_files() {
+        let path_abs = workdir.join(&file);
+        if path_abs.symlink_metadata().is_err() {
+            continue;
+        }
+        std::fs::remove_file(&path_abs)
+            .map_err(|e| write_failed(format!("failed to remove '{}': {e}", file.display())))?;
+        util::clear_empty_dir(&path_abs);
+    }
+    Index::new()
+        .save(&index_path)
+        .map_err(|e| write_failed(format!("failed to clear the index: {e}")))
 }
 
 async fn current_switch_state() -> (Option<String>, Option<String>) {
@@ -1,13 +1,23 @@
 //! Implements `symbolic-ref` for reading and updating Libra's symbolic HEAD.
+//!
+//! The set form repoints HEAD and appends a HEAD reflog entry inside one
+//! SQLite transaction, so a failed reflog write leaves HEAD untouched.
 
 use std::io::Write;
 
 use clap::Parser;
+use git_internal::hash::{ObjectHash, get_hash_kind};
+use sea_orm::{TransactionError, TransactionTrait};
 use serde::Serialize;
 
 use crate::{
     command::branch::is_valid_git_branch_name,
-    internal::{branch::BranchStoreError, head::Head},
+    internal::{
+        branch::{Branch, BranchStoreError},
+        db::get_db_conn_instance,
+        head::Head,
+        reflog::{Reflog, ReflogAction, ReflogContext},
+    },
     utils::{
         error::{CliError, CliResult, StableErrorCode},
         output::{OutputConfig, emit_json_data},
@@ -30,6 +40,7 @@ EXAMPLES:
     libra symbolic-ref HEAD                       Print HEAD's symbolic target (refs/heads/<branch>)
     libra symbolic-ref --short HEAD               Print only the short branch name
     libra symbolic-ref HEAD refs/heads/main       Update HEAD to point at refs/heads/main
+    libra symbolic-ref -m 'reason' HEAD refs/heads/main   Record a reflog reason with the update
     libra symbolic-ref -q HEAD                    Suppress error output when HEAD is detached
     libra symbolic-ref --json HEAD                Structured JSON output for agents";
 
@@ -44,6 +55,10 @@ pub struct SymbolicRefArgs {
     #[clap(long)]
     pub short: bool,
 
+    /// Reflog reason recorded for HEAD when updating it.
+    #[clap(short = 'm', value_name = "REASON", requires = "target")]
+    pub message: Option<String>,
+
     /// Symbolic ref to read or update. Libra currently supports HEAD.
     #[clap(value_name = "NAME")]
     pub name: Option<String>,
@@ -93,7 +108,7 @@ async fn run_symbolic_ref(
     validate_name(name)?;
 
     if let Some(target) = args.target.as_deref() {
-        set_head_target(target).await?;
+        set_head_target(target, args.message.as_deref()).await?;
         return Ok(SymbolicRefOutput {
             name: name.to_string(),
             target: target.to_string(),
@@ -139,12 +154,49 @@ fn validate_name(name: &str) -> CliResult<()> {
     .with_hint("use 'libra symbolic-ref HEAD' to inspect the current branch."))
 }
 
-async fn set_head_target(target: &str) -> CliResult<()> {
-    let branch_name = branch_name_from_full_ref(target)?;
-    Head::update_result(Head::Branch(branch_name.to_string()), None)
-        .await
-        .map_err(map_head_write_error)?;
-    Ok(())
+async fn set_head_target(target: &str, reason: Option<&str>) -> CliResult<()> {
+    let branch_name = branch_name_from_full_ref(target)?.to_string();
+    let reason = reason.map(str::to_string);
+
+    let db = get_db_conn_instance().await;
+    db.transaction(move |txn| {
+        Box::pin(async move {
+            let old_head = Head::current_result_with_conn(txn).await?;
+            let zero = ObjectHash::zero_str(get_hash_kind());
+            let old_oid = Head::current_commit_result_with_conn(txn)
+                .await?
+                .map(|oid| oid.to_string())
+                .unwrap_or_else(|| zero.clone());
+            let new_oid = Branch::find_branch_result_with_conn(txn, &branch_name, None)
+                .await?
+                .map(|branch| branch.commit.to_string())
+                .unwrap_or(zero);
+
+            Head::update_result_with_conn(txn, Head::Branch(branch_name.clone()), None).await?;
+
+            let from = match old_head {
+                Head::Branch(name) => name,
+                Head::Detached(oid) => oid.to_string(),
+            };
+            let context = ReflogContext {
+                old_oid,
+                new_oid,
+                action: ReflogAction::SymbolicRef {
+                    message: reason
+                        .unwrap_or_else(|| format!("moving from {from} to {branch_name}")),
+                },
+            };
+            Reflog::insert_single_entry(txn, &context, HEAD_REF)
+                .await
+                .map_err(|error| BranchStoreError::Query(error.to_string()))
+        })
+    })
+    .await
+    .map_err(|error| match error {
+        TransactionError::Connection(error) => BranchStoreError::Query(error.to_string()),
+        TransactionError::Transaction(error) => error,
+    })
+    .map_err(map_head_write_error)
 }
 
 fn branch_name_from_full_ref(target: &str) -> CliResult<&str> {
@@ -224,6 +276,7 @@ mod tests {
         assert!(!args.short);
         assert!(args.name.is_none());
         assert!(args.target.is_none());
+        assert!(args.message.is_none());
     }
 
     #[test]
@@ -240,4 +293,18 @@ mod tests {
         assert_eq!(args.name.as_deref(), Some("HEAD"));
         assert_eq!(args.target.as_deref(), Some("refs/heads/feature"));
     }
+
+    #[test]
+    fn reflog_reason_requires_a_target() {
+        assert!(SymbolicRefArgs::try_parse_from(["symbolic-ref", "-m", "why", "HEAD"]).is_err());
+        let args = SymbolicRefArgs::try_parse_from([
+            "symbolic-ref",
+            "-m",
+            "why",
+            "HEAD",
+            "refs/heads/feature",
+        ])
+        .unwrap();
+        assert_eq!(args.message.as_deref(), Some("why"));
+    }
 }
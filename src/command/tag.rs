@@ -1,6 +1,6 @@
 //! Manages tags by resolving target commits, creating lightweight or annotated tag objects, storing refs, and listing existing tags.
 
-use std::io;
+use std::{collections::HashSet, io};
 
 use clap::Parser;
 use git_internal::{errors::GitError, hash::ObjectHash};
@@ -32,6 +32,9 @@ EXAMPLES:
     libra tag -l -n 2                     List tags with up to 2 annotation lines
     libra tag -d v1.0                     Delete a tag
     libra tag --points-at HEAD            List tags pointing at HEAD's commit
+    libra tag --contains v1.0             List tags whose history includes v1.0
+    libra tag --no-contains v1.0          List tags older than (or beside) v1.0
+    libra tag --merged main               List tags reachable from main
     libra tag -l --column                 List tags laid out in columns
     libra tag --json v1.0                 Structured JSON output for agents";
 
@@ -76,29 +79,38 @@ pub struct TagArgs {
     #[clap(short, long)]
     pub n_lines: Option<usize>,
 
-    /// Only list tags pointing at the given object (peeled to its commit). Implies list mode.
-    #[clap(long = "points-at", value_name = "object")]
-    pub points_at: Option<String>,
-
-    /// Only list tags whose commit contains COMMIT (COMMIT is an ancestor). Implies list mode.
-    #[clap(long, value_name = "commit")]
-    pub contains: Option<String>,
-
-    /// Only list tags whose commit does NOT contain COMMIT. Implies list mode.
-    #[clap(long = "no-contains", value_name = "commit")]
-    pub no_contains: Option<String>,
-
-    /// Only list tags whose target is reachable from COMMIT. Implies list mode.
-    #[clap(long, value_name = "commit")]
+    /// Only list tags pointing at the given object (peeled to its commit; HEAD if
+    /// not specified). Repeatable: a tag matching any object is listed. Implies
+    /// list mode.
+    #[clap(long = "points-at", value_name = "object", num_args = 0..=1, default_missing_value = "HEAD", action = clap::ArgAction::Append)]
+    pub points_at: Vec<String>,
+
+    /// Only list tags whose commit contains COMMIT (COMMIT is an ancestor; HEAD
+    /// if not specified). Repeatable: a tag containing any of them is listed.
+    /// Implies list mode.
+    #[clap(long, value_name = "commit", num_args = 0..=1, default_missing_value = "HEAD", action = clap::ArgAction::Append)]
+    pub contains: Vec<String>,
+
+    /// Only list tags whose commit contains none of the given commits (HEAD if
+    /// not specified). Repeatable. Implies list mode.
+    #[clap(long = "no-contains", value_name = "commit", num_args = 0..=1, default_missing_value = "HEAD", action = clap::ArgAction::Append)]
+    pub no_contains: Vec<String>,
+
+    /// Only list tags whose target is reachable from COMMIT (HEAD if not
+    /// specified). Implies list mode.
+    #[clap(long, value_name = "commit", num_args = 0..=1, default_missing_value = "HEAD")]
     pub merged: Option<String>,
 
-    /// Only list tags whose target is NOT reachable from COMMIT. Implies list mode.
-    #[clap(long = "no-merged", value_name = "commit")]
+    /// Only list tags whose target is NOT reachable from COMMIT (HEAD if not
+    /// specified). Implies list mode.
+    #[clap(long = "no-merged", value_name = "commit", num_args = 0..=1, default_missing_value = "HEAD")]
     pub no_merged: Option<String>,
 
-    /// Sort tags by key. Supported: refname, -refname, creatordate, -creatordate.
-    #[clap(long, value_name = "key")]
-    pub sort: Option<String>,
+    /// Sort tags by key: `refname`, `version:refname` (alias `v:refname`), or
+    /// `creatordate`; a leading `-` reverses. Repeatable: the last key is the
+    /// primary one and earlier keys break its ties.
+    #[clap(long, value_name = "key", action = clap::ArgAction::Append)]
+    pub sort: Vec<String>,
 
     /// Display the tag list in columns. Accepts a comma/space-separated list of
     /// options: enablement `always` / `auto` (only when stdout is a terminal) /
@@ -164,6 +176,10 @@ pub struct TagListEntry {
     pub message: Option<String>,
     #[serde(skip_serializing)]
     pub display_message: Option<String>,
+    /// Tagger date of an annotated tag, committer date of a lightweight tag's
+    /// commit (`--sort=creatordate`); 0 for tags on trees and blobs.
+    #[serde(skip_serializing)]
+    pub creator_date: i64,
 }
 
 pub async fn execute(args: TagArgs) {
@@ -206,12 +222,12 @@ fn validate_message_source_create_only(args: &TagArgs) -> Result<(), TagError> {
         || args.delete
         || args.verify
         || args.n_lines.is_some()
-        || args.points_at.is_some()
-        || args.contains.is_some()
-        || args.no_contains.is_some()
+        || !args.points_at.is_empty()
+        || !args.contains.is_empty()
+        || !args.no_contains.is_empty()
         || args.merged.is_some()
         || args.no_merged.is_some()
-        || args.sort.is_some()
+        || !args.sort.is_empty()
         || args.column.is_some();
     if non_create {
         return Err(TagError::MessageOptionRequiresCreate(
@@ -411,7 +427,9 @@ impl From<TagError> for CliError {
             }
             TagError::InvalidSortKey(_) => CliError::command_usage(message)
                 .with_stable_code(StableErrorCode::CliInvalidArguments)
-                .with_hint("supported sort keys: refname, -refname, creatordate, -creatordate"),
+                .with_hint(
+                    "supported sort keys: refname, version:refname, creatordate, each reversible with a leading '-'",
+                ),
             TagError::VaultSign(_) => CliError::fatal(message)
                 .with_stable_code(StableErrorCode::RepoStateInvalid)
                 .with_hint("ensure the vault is initialized and signing is configured"),
@@ -608,53 +626,21 @@ async fn run_tag(args: &TagArgs) -> Result<TagOutput, TagError> {
 
     if args.list
         || args.n_lines.is_some()
-        || args.points_at.is_some()
-        || args.contains.is_some()
-        || args.no_contains.is_some()
+        || !args.points_at.is_empty()
+        || !args.contains.is_empty()
+        || !args.no_contains.is_empty()
         || args.merged.is_some()
         || args.no_merged.is_some()
-        || args.sort.is_some()
+        || !args.sort.is_empty()
         || args.column.is_some()
         || args.name.is_none()
     {
-        // `--points-at` peels each tag to its commit and keeps only those that
-        // resolve to the requested object, mirroring `git tag --points-at`.
-        // Like `-n`, it forces list mode even when a name is also supplied.
-        let points_at = match args.points_at.as_deref() {
-            Some(object) => Some(resolve_points_at_object(object).await?),
-            None => None,
-        };
-        // `--contains`/`--no-contains` resolve to a commit and keep (or drop)
-        // tags whose peeled commit has that commit as an ancestor.
-        let contains = match args.contains.as_deref() {
-            Some(object) => Some(resolve_points_at_object(object).await?),
-            None => None,
-        };
-        let no_contains = match args.no_contains.as_deref() {
-            Some(object) => Some(resolve_points_at_object(object).await?),
-            None => None,
-        };
-        let merged = match args.merged.as_deref() {
-            Some(object) => Some(resolve_points_at_object(object).await?),
-            None => None,
-        };
-        let no_merged = match args.no_merged.as_deref() {
-            Some(object) => Some(resolve_points_at_object(object).await?),
-            None => None,
-        };
+        // The filters force list mode even when a name is also supplied, like `-n`.
+        let filters = TagListFilters::resolve(args).await?;
         // In list mode a positional name acts as a glob pattern (`tag -l 'v1.*'`).
         let pattern = args.name.as_deref().map(compile_tag_glob);
-        let mut tags = collect_tags(
-            args.n_lines.unwrap_or(0),
-            points_at.as_ref(),
-            pattern.as_ref(),
-            contains.as_ref(),
-            no_contains.as_ref(),
-            merged.as_ref(),
-            no_merged.as_ref(),
-        )
-        .await?;
-        sort_tags(&mut tags, args.sort.as_deref())?;
+        let mut tags = collect_tags(args.n_lines.unwrap_or(0), pattern.as_ref(), &filters).await?;
+        sort_tags(&mut tags, &args.sort)?;
         return Ok(TagOutput::List { tags });
     }
 
@@ -785,14 +771,91 @@ async fn run_delete_tag(tag_name: &str) -> Result<TagOutput, TagError> {
     })
 }
 
+/// The `tag -l` commit filters, resolved to object IDs before any tag is
+/// inspected.
+#[derive(Default)]
+struct TagListFilters {
+    /// `--points-at`: keep tags whose peeled commit is one of these.
+    points_at: HashSet<ObjectHash>,
+    /// `--contains`: keep tags whose history reaches any of these.
+    contains: HashSet<ObjectHash>,
+    /// `--no-contains`: drop tags whose history reaches any of these.
+    no_contains: HashSet<ObjectHash>,
+    /// `--merged`: the commits reachable from the target, walked once.
+    merged: Option<HashSet<ObjectHash>>,
+    /// `--no-merged`: the commits reachable from the target, walked once.
+    no_merged: Option<HashSet<ObjectHash>>,
+}
+
+impl TagListFilters {
+    async fn resolve(args: &TagArgs) -> Result<Self, TagError> {
+        Ok(Self {
+            points_at: resolve_tag_filter_objects(&args.points_at).await?,
+            contains: resolve_tag_filter_objects(&args.contains).await?,
+            no_contains: resolve_tag_filter_objects(&args.no_contains).await?,
+            merged: resolve_reachable_from(args.merged.as_deref()).await?,
+            no_merged: resolve_reachable_from(args.no_merged.as_deref()).await?,
+        })
+    }
+
+    /// Whether the tag peeling to `peeled` passes every filter. The tag's own
+    /// history is walked only when `--contains`/`--no-contains` need it.
+    async fn keeps(&self, peeled: ObjectHash) -> Result<bool, TagError> {
+        if !self.points_at.is_empty() && !self.points_at.contains(&peeled) {
+            return Ok(false);
+        }
+        if let Some(reachable) = &self.merged
+            && !reachable.contains(&peeled)
+        {
+            return Ok(false);
+        }
+        if let Some(reachable) = &self.no_merged
+            && reachable.contains(&peeled)
+        {
+            return Ok(false);
+        }
+        if self.contains.is_empty() && self.no_contains.is_empty() {
+            return Ok(true);
+        }
+        let history = reachable_commit_ids(peeled).await?;
+        if !self.contains.is_empty() && history.is_disjoint(&self.contains) {
+            return Ok(false);
+        }
+        Ok(history.is_disjoint(&self.no_contains))
+    }
+}
+
+async fn resolve_tag_filter_objects(specs: &[String]) -> Result<HashSet<ObjectHash>, TagError> {
+    let mut objects = HashSet::new();
+    for spec in specs {
+        objects.insert(resolve_points_at_object(spec).await?);
+    }
+    Ok(objects)
+}
+
+async fn resolve_reachable_from(
+    spec: Option<&str>,
+) -> Result<Option<HashSet<ObjectHash>>, TagError> {
+    match spec {
+        Some(spec) => {
+            let target = resolve_points_at_object(spec).await?;
+            Ok(Some(reachable_commit_ids(target).await?))
+        }
+        None => Ok(None),
+    }
+}
+
+async fn reachable_commit_ids(tip: ObjectHash) -> Result<HashSet<ObjectHash>, TagError> {
+    let commits = crate::command::log::get_reachable_commits(tip.to_string(), None)
+        .await
+        .map_err(|error| TagError::Reachability(error.to_string()))?;
+    Ok(commits.into_iter().map(|commit| commit.id).collect())
+}
+
 async fn collect_tags(
     show_lines: usize,
-    points_at: Option<&ObjectHash>,
     pattern: Option<&regex::Regex>,
-    contains: Option<&ObjectHash>,
-    no_contains: Option<&ObjectHash>,
-    merged: Option<&ObjectHash>,
-    no_merged: Option<&ObjectHash>,
+    filters: &TagListFilters,
 ) -> Result<Vec<TagListEntry>, TagError> {
     let tags = tag::list().await.map_err(TagError::ListFailed)?;
     let mut entries = Vec::with_capacity(tags.len());
@@ -803,89 +866,52 @@ async fn collect_tags(
         {
             continue;
         }
-        if let Some(target) = points_at
-            && &tag_peeled_commit(&tag.object) != target
-        {
+        if !filters.keeps(tag_peeled_commit(&tag.object)).await? {
             continue;
         }
-        // `--contains`/`--no-contains`: walk the tag's peeled commit's history
-        // and keep (or drop) tags that reach the requested commit.
-        if contains.is_some() || no_contains.is_some() {
-            let peeled = tag_peeled_commit(&tag.object);
-            let reachable = crate::command::log::get_reachable_commits(peeled.to_string(), None)
-                .await
-                .map_err(|error| TagError::Reachability(error.to_string()))?;
-            let reaches = |target: &ObjectHash| reachable.iter().any(|commit| &commit.id == target);
-            if let Some(target) = contains
-                && !reaches(target)
-            {
-                continue;
-            }
-            if let Some(target) = no_contains
-                && reaches(target)
-            {
-                continue;
-            }
-        }
-        // `--merged <commit>`: walk from the given commit and keep tags whose
-        // peeled commit is an ancestor (reachable from it).
-        // `--no-merged <commit>`: drop tags whose peeled commit is reachable.
-        if merged.is_some() || no_merged.is_some() {
-            let peeled = tag_peeled_commit(&tag.object);
-            if let Some(target) = merged {
-                let target_reachable =
-                    crate::command::log::get_reachable_commits(target.to_string(), None)
-                        .await
-                        .map_err(|error| TagError::Reachability(error.to_string()))?;
-                if !target_reachable.iter().any(|c| c.id == peeled) {
-                    continue;
-                }
-            }
-            if let Some(target) = no_merged {
-                let target_reachable =
-                    crate::command::log::get_reachable_commits(target.to_string(), None)
-                        .await
-                        .map_err(|error| TagError::Reachability(error.to_string()))?;
-                if target_reachable.iter().any(|c| c.id == peeled) {
-                    continue;
-                }
-            }
-        }
         entries.push(tag_to_list_entry(tag, show_lines));
     }
     Ok(entries)
 }
 
-/// Sort tag entries by the given key. Supported keys: `refname`, `-refname`,
-/// `creatordate`, `-creatordate`. The `-` prefix reverses the order.
-fn sort_tags(tags: &mut [TagListEntry], key: Option<&str>) -> Result<(), TagError> {
-    let Some(key) = key else {
-        return Ok(());
-    };
-    let (field, reverse) = if let Some(stripped) = key.strip_prefix('-') {
-        (stripped, true)
-    } else {
-        (key, false)
-    };
-    match field {
-        "refname" => {
-            if reverse {
-                tags.sort_by(|a, b| b.name.cmp(&a.name));
-            } else {
-                tags.sort_by(|a, b| a.name.cmp(&b.name));
-            }
-        }
-        "creatordate" => {
-            // Tag entries don't carry commit timestamps in the list entry;
-            // fall back to hash ordering as a stable approximation.
-            if reverse {
-                tags.sort_by(|a, b| b.hash.cmp(&a.hash));
-            } else {
-                tags.sort_by(|a, b| a.hash.cmp(&b.hash));
-            }
+/// Sort tag entries by the `--sort` keys: `refname`, `version:refname` /
+/// `v:refname` (numeric-aware), and `creatordate`, each reversed by a leading
+/// `-`. With several keys the last is primary and earlier ones break ties, as
+/// in Git; remaining ties fall back to ascending refname.
+fn sort_tags(tags: &mut [TagListEntry], keys: &[String]) -> Result<(), TagError> {
+    let mut parsed = Vec::with_capacity(keys.len());
+    for key in keys {
+        let (field, reverse) = match key.strip_prefix('-') {
+            Some(stripped) => (stripped, true),
+            None => (key.as_str(), false),
+        };
+        if !matches!(
+            field,
+            "refname" | "version:refname" | "v:refname" | "creatordate"
+        ) {
+            return Err(TagError::InvalidSortKey(field.to_string()));
         }
-        other => return Err(TagError::InvalidSortKey(other.to_string())),
+        parsed.push((field, reverse));
+    }
+    if parsed.is_empty() {
+        return Ok(());
     }
+
+    tags.sort_by(|a, b| {
+        parsed
+            .iter()
+            .rev()
+            .map(|&(field, reverse)| {
+                let ord = match field {
+                    "refname" => a.name.cmp(&b.name),
+                    "creatordate" => a.creator_date.cmp(&b.creator_date),
+                    _ => util::version_refname_cmp(&a.name, &b.name),
+                };
+                if reverse { ord.reverse() } else { ord }
+            })
+            .find(|ord| ord.is_ne())
+            .unwrap_or_else(|| a.name.cmp(&b.name))
+    });
     Ok(())
 }
 
@@ -952,6 +978,11 @@ fn tag_object_to_list_entry(
         TagObject::Tree(tree) => tree.id.to_string(),
         TagObject::Blob(blob) => blob.id.to_string(),
     };
+    let creator_date = match &object {
+        TagObject::Tag(tag_object) => tag_object.tagger.timestamp as i64,
+        TagObject::Commit(commit) => commit.committer.timestamp as i64,
+        _ => 0,
+    };
     let (tag_type, message, display_message) = match &object {
         TagObject::Tag(tag_object) => {
             let message = trim_tag_message(&tag_object.message, show_lines);
@@ -971,6 +1002,7 @@ fn tag_object_to_list_entry(
         tag_type,
         message,
         display_message,
+        creator_date,
     }
 }
 
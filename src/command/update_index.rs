@@ -21,6 +21,7 @@ use serde::Serialize;
 
 use crate::utils::{
     error::{CliError, CliResult, StableErrorCode},
+    index_marks::{self, IndexMark},
     lfs,
     object_ext::BlobExt,
     output::{OutputConfig, emit_json_data},
@@ -34,6 +35,11 @@ EXAMPLES:
     libra update-index --remove old.txt         Drop a path from the index
     libra update-index --cacheinfo 100644,<oid>,dir/f.txt
                                                  Register an entry directly from an object id
+    libra update-index --chmod=+x run.sh        Restage a file with the executable bit set
+    libra update-index --skip-worktree local.cfg
+                                                 Ignore local edits to a tracked file
+    libra update-index --no-assume-unchanged a.txt
+                                                 Report changes to a file again
     libra --json update-index --add a.txt       Structured JSON output for agents";
 
 /// Modify the index directly: stage working-tree files (`--add`), drop paths
@@ -57,6 +63,29 @@ pub struct UpdateIndexArgs {
     #[clap(long, value_name = "<mode>,<object>,<path>")]
     pub cacheinfo: Vec<String>,
 
+    /// Set the executable bit (`+x`) or clear it (`-x`) on the index entries
+    /// of the (re)staged paths.
+    #[clap(long, value_name = "(+|-)x")]
+    pub chmod: Option<String>,
+
+    /// Mark the paths skip-worktree: status and diff ignore their working-tree
+    /// state. Only marks the entries; their content is not restaged.
+    #[clap(long, conflicts_with_all = ["no_skip_worktree", "remove"])]
+    pub skip_worktree: bool,
+
+    /// Clear the skip-worktree mark on the paths.
+    #[clap(long, conflicts_with = "remove")]
+    pub no_skip_worktree: bool,
+
+    /// Mark the paths assume-unchanged: status and diff do not look at their
+    /// working-tree files. Only marks the entries.
+    #[clap(long, conflicts_with_all = ["no_assume_unchanged", "remove"])]
+    pub assume_unchanged: bool,
+
+    /// Clear the assume-unchanged mark on the paths.
+    #[clap(long, conflicts_with = "remove")]
+    pub no_assume_unchanged: bool,
+
     /// Paths to (re)stage from the working tree, or to remove with `--remove`.
     #[clap(value_name = "PATH")]
     pub paths: Vec<String>,
@@ -68,6 +97,9 @@ struct UpdateIndexOutput {
     updated: usize,
     /// Number of index entries removed.
     removed: usize,
+    /// Number of index entries whose skip-worktree/assume-unchanged marks
+    /// changed.
+    marked: usize,
 }
 
 pub async fn execute(args: UpdateIndexArgs) {
@@ -93,8 +125,17 @@ pub async fn execute_safe(args: UpdateIndexArgs, output: &OutputConfig) -> CliRe
             .with_stable_code(StableErrorCode::RepoStateInvalid)
     })?;
 
+    let chmod_mode = args
+        .chmod
+        .as_deref()
+        .map(parse_chmod)
+        .transpose()
+        .map_err(usage)?;
+    let marks = requested_marks(&args);
+
     let mut updated = 0usize;
     let mut removed = 0usize;
+    let mut marked = 0usize;
 
     // `--cacheinfo <mode>,<object>,<path>`: register entries directly.
     for spec in &args.cacheinfo {
@@ -106,6 +147,14 @@ pub async fn execute_safe(args: UpdateIndexArgs, output: &OutputConfig) -> CliRe
     // Positional paths: remove, or (re)stage from the working tree.
     let workdir = util::working_dir();
     for path_str in &args.paths {
+        // Mark-only options leave the entry's content alone, as in Git.
+        if !marks.is_empty() {
+            if !index.tracked(path_str, 0) {
+                return Err(usage(format!("Unable to mark file {path_str}")));
+            }
+            marked += 1;
+            continue;
+        }
         if args.remove {
             if index.remove(path_str, 0).is_some() {
                 removed += 1;
@@ -121,11 +170,27 @@ pub async fn execute_safe(args: UpdateIndexArgs, output: &OutputConfig) -> CliRe
         }
 
         let absolute = resolve_within_worktree(path_str, &workdir).map_err(usage)?;
-        let entry = stage_working_tree_path(path_str, &absolute, &workdir)?;
+        let mut entry = stage_working_tree_path(path_str, &absolute, &workdir)?;
+        if let Some(mode) = chmod_mode {
+            if !matches!(entry.mode, 0o100644 | 0o100755) {
+                return Err(usage(format!(
+                    "cannot chmod {} '{path_str}'",
+                    args.chmod.as_deref().unwrap_or_default()
+                )));
+            }
+            entry.mode = mode;
+        }
         index.update(entry);
         updated += 1;
     }
 
+    for (mark, on) in marks {
+        index_marks::mark(&index, mark, &args.paths, on).map_err(|error| {
+            CliError::fatal(format!("failed to record index marks: {error}"))
+                .with_stable_code(StableErrorCode::IoWriteFailed)
+        })?;
+    }
+
     index.save(path::index()).map_err(|error| {
         CliError::fatal(format!("failed to save index: {error}"))
             .with_stable_code(StableErrorCode::RepoStateInvalid)
@@ -134,7 +199,11 @@ pub async fn execute_safe(args: UpdateIndexArgs, output: &OutputConfig) -> CliRe
     if output.is_json() {
         emit_json_data(
             "update-index",
-            &UpdateIndexOutput { updated, removed },
+            &UpdateIndexOutput {
+                updated,
+                removed,
+                marked,
+            },
             output,
         )
     } else {
@@ -142,6 +211,29 @@ pub async fn execute_safe(args: UpdateIndexArgs, output: &OutputConfig) -> CliRe
     }
 }
 
+/// The skip-worktree/assume-unchanged marks to set (`true`) or clear.
+fn requested_marks(args: &UpdateIndexArgs) -> Vec<(IndexMark, bool)> {
+    let mut marks = Vec::new();
+    if args.skip_worktree || args.no_skip_worktree {
+        marks.push((IndexMark::SkipWorktree, args.skip_worktree));
+    }
+    if args.assume_unchanged || args.no_assume_unchanged {
+        marks.push((IndexMark::AssumeUnchanged, args.assume_unchanged));
+    }
+    marks
+}
+
+/// Parse a `--chmod=(+|-)x` value into the index mode to record.
+fn parse_chmod(value: &str) -> Result<u32, String> {
+    match value {
+        "+x" => Ok(0o100755),
+        "-x" => Ok(0o100644),
+        other => Err(format!(
+            "invalid --chmod value '{other}' (expected +x or -x)"
+        )),
+    }
+}
+
 /// Parse a `--cacheinfo` spec `<mode>,<object>,<path>` into an [`IndexEntry`].
 /// Validates the mode, the object id (length must match the repository hash
 /// kind), and rejects worktree-escaping paths.
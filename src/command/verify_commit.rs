@@ -0,0 +1,244 @@
+//! `libra verify-commit` — check the PGP signature of one or more commits.
+//! Plumbing companion to `commit -S`: verification goes through the same
+//! vault-key backend as `merge --verify-signatures` and `log --show-signature`,
+//! falling back to the user's GnuPG keyring (`gpg --verify`) for signatures the
+//! vault cannot vouch for, such as commits signed in Git.
+
+use clap::Parser;
+use git_internal::internal::object::commit::Commit;
+use serde::Serialize;
+
+use crate::{
+    command::{
+        commit::{
+            BAD_SIGNATURE_MESSAGE, CommitSignatureStatus, commit_signed_payload,
+            verify_commit_signature,
+        },
+        load_object,
+    },
+    internal::vault::PgpSigner,
+    utils::{
+        error::{CliError, CliResult, StableErrorCode},
+        gpg::{self, GpgVerdict},
+        output::{OutputConfig, emit_json_data},
+        util,
+    },
+};
+
+/// `--help` examples (cross-cutting EXAMPLES contract, `_general.md`).
+pub const VERIFY_COMMIT_EXAMPLES: &str = "\
+EXAMPLES:
+    libra verify-commit HEAD                Check the signature of the current commit
+    libra verify-commit main v1.0 abc1234   Check several commits; exit 1 if any fails
+    libra verify-commit -v HEAD             Also print GnuPG's own output
+    libra --json verify-commit HEAD         Structured JSON output for agents";
+
+/// Check the PGP signature of commits.
+#[derive(Parser, Debug)]
+#[command(after_help = VERIFY_COMMIT_EXAMPLES)]
+pub struct VerifyCommitArgs {
+    /// Commits to verify (any revision that resolves to a commit)
+    #[clap(required = true, value_name = "COMMIT")]
+    pub commits: Vec<String>,
+
+    /// Print GnuPG's full output for signatures checked against the keyring
+    #[clap(short, long)]
+    pub verbose: bool,
+}
+
+/// Verdict for one verified object, shared by `verify-commit` and `verify-tag`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SignatureVerdict {
+    Good,
+    Bad,
+    Unsigned,
+    /// The signature could not be checked (e.g. no vault unseal key).
+    Error,
+}
+
+/// One row of `verify-commit` / `verify-tag` output.
+#[derive(Debug, Serialize)]
+pub(crate) struct SignatureCheck {
+    /// The argument as given on the command line.
+    pub object: String,
+    /// The resolved object id.
+    pub id: String,
+    pub status: SignatureVerdict,
+    /// The key that verified a good signature. Never taken from the object's
+    /// author, committer or tagger header, which nothing vouches for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<PgpSigner>,
+    /// Human-readable status line (also printed to stderr).
+    pub message: String,
+    /// GnuPG's own output, kept only under `--verbose` when the keyring was
+    /// consulted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpg_output: Option<String>,
+}
+
+impl SignatureCheck {
+    /// Row for a signature the vault key `signer` verified.
+    pub(crate) fn good(object: String, id: String, signer: PgpSigner) -> Self {
+        Self {
+            object,
+            id,
+            status: SignatureVerdict::Good,
+            message: format!("Good signature from {signer}"),
+            signer: Some(signer),
+            gpg_output: None,
+        }
+    }
+
+    /// Row for any verdict other than good.
+    pub(crate) fn failed(
+        object: String,
+        id: String,
+        status: SignatureVerdict,
+        message: String,
+    ) -> Self {
+        Self {
+            object,
+            id,
+            status,
+            signer: None,
+            message,
+            gpg_output: None,
+        }
+    }
+
+    /// Re-check a signature the vault could not vouch for (`Bad` or `Error`)
+    /// against the user's GnuPG keyring. A definite GnuPG verdict replaces the
+    /// vault one; a missing public key or `gpg` binary leaves it unchanged.
+    pub(crate) async fn fall_back_to_gpg(
+        &mut self,
+        signature: &str,
+        payload: &[u8],
+        verbose: bool,
+    ) {
+        if !matches!(self.status, SignatureVerdict::Bad | SignatureVerdict::Error) {
+            return;
+        }
+        let Ok(verification) = gpg::verify_detached(signature, payload).await else {
+            return;
+        };
+        match verification.verdict {
+            GpgVerdict::Good { signer } => {
+                self.status = SignatureVerdict::Good;
+                self.message = format!("Good signature from {signer}");
+                self.signer = Some(signer);
+            }
+            GpgVerdict::Bad { signer } => {
+                self.status = SignatureVerdict::Bad;
+                self.message = format!("BAD signature from \"{signer}\"");
+                self.signer = None;
+            }
+            GpgVerdict::Unchecked => {}
+        }
+        if verbose {
+            self.gpg_output = Some(verification.output);
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct VerifyOutput<'a> {
+    results: &'a [SignatureCheck],
+}
+
+pub async fn execute(args: VerifyCommitArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point. Resolves every argument to a commit and checks its
+/// `gpgsig` header against the vault PGP key (then the GnuPG keyring, see
+/// [`SignatureCheck::fall_back_to_gpg`]), printing one status line per commit
+/// to stderr.
+///
+/// # Errors
+///
+/// An unresolvable revision is a fatal error (exit 128) reported before any
+/// verification runs. When every commit resolves but at least one is unsigned,
+/// carries a bad signature, or cannot be checked, all results are still
+/// reported and the command exits 1.
+pub async fn execute_safe(args: VerifyCommitArgs, output: &OutputConfig) -> CliResult<()> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+
+    let mut commits = Vec::with_capacity(args.commits.len());
+    for name in &args.commits {
+        let id = util::get_commit_base(name).await.map_err(|_| {
+            CliError::fatal(format!("{name}: not a valid commit name"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget)
+        })?;
+        let commit = load_object::<Commit>(&id).map_err(|error| {
+            CliError::fatal(format!("failed to load commit {id}: {error}"))
+                .with_stable_code(StableErrorCode::RepoCorrupt)
+        })?;
+        commits.push((name.clone(), commit));
+    }
+
+    let mut results = Vec::with_capacity(commits.len());
+    for (name, commit) in commits {
+        let id = commit.id.to_string();
+        let mut check = match verify_commit_signature(&commit).await {
+            Ok(CommitSignatureStatus::Good(signer)) => SignatureCheck::good(name, id, signer),
+            Ok(CommitSignatureStatus::Bad) => SignatureCheck::failed(
+                name,
+                id,
+                SignatureVerdict::Bad,
+                BAD_SIGNATURE_MESSAGE.to_string(),
+            ),
+            Ok(CommitSignatureStatus::Unsigned) => SignatureCheck::failed(
+                name,
+                id,
+                SignatureVerdict::Unsigned,
+                "no signature found".to_string(),
+            ),
+            Err(error) => SignatureCheck::failed(
+                name,
+                id,
+                SignatureVerdict::Error,
+                format!("can't check signature: {error}"),
+            ),
+        };
+        if let Ok(Some((signature, payload))) = commit_signed_payload(&commit) {
+            check
+                .fall_back_to_gpg(&signature, &payload, args.verbose)
+                .await;
+        }
+        results.push(check);
+    }
+
+    report_signature_checks("verify-commit", &results, output)
+}
+
+/// Render `results` (stderr lines, or a JSON envelope) and turn any non-good
+/// verdict into a silent exit 1, matching Git's verify-* plumbing.
+pub(crate) fn report_signature_checks(
+    command: &str,
+    results: &[SignatureCheck],
+    output: &OutputConfig,
+) -> CliResult<()> {
+    if output.is_json() {
+        emit_json_data(command, &VerifyOutput { results }, output)?;
+    } else if !output.quiet {
+        for check in results {
+            if let Some(gpg_output) = &check.gpg_output {
+                eprint!("{gpg_output}");
+            }
+            eprintln!("{}: {}", check.object, check.message);
+        }
+    }
+
+    if results
+        .iter()
+        .all(|check| check.status == SignatureVerdict::Good)
+    {
+        Ok(())
+    } else {
+        Err(CliError::silent_exit(1))
+    }
+}
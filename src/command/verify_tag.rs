@@ -0,0 +1,131 @@
+//! `libra verify-tag` — check the PGP signature of one or more annotated tags.
+//! Plumbing companion to `tag -s`; shares the vault-key verification backend
+//! with `tag -v` and the GnuPG keyring fallback with `verify-commit`.
+
+use std::str::FromStr;
+
+use clap::Parser;
+use git_internal::{hash::ObjectHash, internal::object::tag::Tag};
+
+use crate::{
+    command::{
+        commit::BAD_SIGNATURE_MESSAGE,
+        load_object,
+        verify_commit::{SignatureCheck, SignatureVerdict, report_signature_checks},
+    },
+    internal::tag::{self, VerifyTagError},
+    utils::{
+        error::{CliError, CliResult, StableErrorCode},
+        output::OutputConfig,
+        util,
+    },
+};
+
+/// `--help` examples (cross-cutting EXAMPLES contract, `_general.md`).
+pub const VERIFY_TAG_EXAMPLES: &str = "\
+EXAMPLES:
+    libra verify-tag v1.0               Check the signature of an annotated tag
+    libra verify-tag v1.0 v1.1          Check several tags; exit 1 if any fails
+    libra verify-tag -v v1.0            Also print GnuPG's own output
+    libra --json verify-tag v1.0        Structured JSON output for agents";
+
+/// Check the PGP signature of tags.
+#[derive(Parser, Debug)]
+#[command(after_help = VERIFY_TAG_EXAMPLES)]
+pub struct VerifyTagArgs {
+    /// Tags to verify (a tag name, `refs/tags/<name>`, or a tag object id)
+    #[clap(required = true, value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Print GnuPG's full output for signatures checked against the keyring
+    #[clap(short, long)]
+    pub verbose: bool,
+}
+
+pub async fn execute(args: VerifyTagArgs) {
+    if let Err(err) = execute_safe(args, &OutputConfig::default()).await {
+        err.print_stderr();
+        std::process::exit(err.exit_code());
+    }
+}
+
+/// Safe entry point. Resolves every argument to an annotated tag object and
+/// checks its embedded signature against the vault PGP key (then the GnuPG
+/// keyring), printing one status line per tag to stderr.
+///
+/// # Errors
+///
+/// An argument that names no tag, or resolves to a non-tag object (such as a
+/// lightweight tag's commit), is a fatal error (exit 128) reported before any
+/// verification runs. When every tag resolves but at least one is unsigned,
+/// carries a bad signature, or cannot be checked, all results are still
+/// reported and the command exits 1.
+pub async fn execute_safe(args: VerifyTagArgs, output: &OutputConfig) -> CliResult<()> {
+    util::require_repo().map_err(|_| CliError::repo_not_found())?;
+
+    let mut tags = Vec::with_capacity(args.tags.len());
+    for name in &args.tags {
+        let id = resolve_tag_object(name).await?;
+        let tag_object = load_object::<Tag>(&id).map_err(|_| {
+            CliError::fatal(format!("{name}: cannot verify a non-tag object"))
+                .with_stable_code(StableErrorCode::CliInvalidTarget)
+        })?;
+        tags.push((name.clone(), id, tag_object));
+    }
+
+    let mut results = Vec::with_capacity(tags.len());
+    for (name, id, tag_object) in tags {
+        let id = id.to_string();
+        // The tagger header is unverified, so only the key that checked the
+        // signature is ever named as the signer.
+        let mut check = match tag::verify_tag_object(&tag_object).await {
+            Ok(Some(signer)) => SignatureCheck::good(name, id, signer),
+            Ok(None) => SignatureCheck::failed(
+                name,
+                id,
+                SignatureVerdict::Bad,
+                BAD_SIGNATURE_MESSAGE.to_string(),
+            ),
+            Err(VerifyTagError::NotSigned(_)) => SignatureCheck::failed(
+                name,
+                id,
+                SignatureVerdict::Unsigned,
+                "no signature found".to_string(),
+            ),
+            Err(error) => SignatureCheck::failed(
+                name,
+                id,
+                SignatureVerdict::Error,
+                format!("can't check signature: {error}"),
+            ),
+        };
+        if let Some((signature, payload)) = tag::tag_signed_payload(&tag_object) {
+            check
+                .fall_back_to_gpg(&signature, &payload, args.verbose)
+                .await;
+        }
+        results.push(check);
+    }
+
+    report_signature_checks("verify-tag", &results, output)
+}
+
+/// Resolve a tag name (bare or `refs/tags/`-qualified) to the object its ref
+/// points at, falling back to a full object id.
+async fn resolve_tag_object(name: &str) -> CliResult<ObjectHash> {
+    let short = name.strip_prefix("refs/tags/").unwrap_or(name);
+    let tag_ref = tag::find_tag_ref(short).await.map_err(|error| {
+        CliError::fatal(format!("failed to read tag '{short}': {error}"))
+            .with_stable_code(StableErrorCode::IoReadFailed)
+    })?;
+    if let Some(target) = tag_ref.and_then(|tag_ref| tag_ref.target) {
+        return ObjectHash::from_str(&target).map_err(|error| {
+            CliError::fatal(format!("tag '{short}' has invalid object hash: {error}"))
+                .with_stable_code(StableErrorCode::RepoCorrupt)
+        });
+    }
+    ObjectHash::from_str(name).map_err(|_| {
+        CliError::fatal(format!("tag '{name}' not found"))
+            .with_stable_code(StableErrorCode::CliInvalidTarget)
+    })
+}
@@ -1,5 +1,5 @@
-//! Common helpers for formatting commit messages, parsing embedded GPG signatures, and
-//! validating Conventional Commit styles.
+//! Common helpers for formatting and cleaning up commit messages, parsing embedded GPG
+//! signatures, and validating Conventional Commit styles.
 //!
 //! This module is intentionally dependency-light so that it can be shared by both the CLI
 //! command layer and lower-level repository code without introducing dependency cycles.
@@ -78,6 +78,123 @@ pub fn parse_commit_msg(msg_gpg: &str) -> (&str, Option<&str>) {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CleanupMode {
+    /// Strip leading/trailing empty lines and trailing whitespace from every line, then strip commentary lines.
+    #[default]
+    Strip,
+    /// Same as strip but keep commentary lines.
+    Whitespace,
+    /// Do not change the message at all.
+    Verbatim,
+    /// Same as strip but truncate the message at the scissors line.
+    Scissors,
+    /// Same as strip if the message is to be edited, otherwise whitespace.
+    Default,
+}
+
+impl CleanupMode {
+    /// The mode actually applied. Git's `default` and `scissors` only act "if
+    /// the message is to be edited": without an editor both fall back to
+    /// `whitespace`, keeping comment and scissors lines.
+    pub fn effective(self, edited: bool) -> CleanupMode {
+        match self {
+            CleanupMode::Default | CleanupMode::Scissors if !edited => CleanupMode::Whitespace,
+            other => other,
+        }
+    }
+}
+
+/// Parse a `commit.cleanup` config value into a [`CleanupMode`], case-insensitively
+/// (`strip`/`whitespace`/`verbatim`/`scissors`/`default`). Returns `None` for an
+/// unrecognized value so the caller falls back to the built-in default.
+pub fn parse_cleanup_mode(value: &str) -> Option<CleanupMode> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "strip" => Some(CleanupMode::Strip),
+        "whitespace" => Some(CleanupMode::Whitespace),
+        "verbatim" => Some(CleanupMode::Verbatim),
+        "scissors" => Some(CleanupMode::Scissors),
+        "default" => Some(CleanupMode::Default),
+        _ => None,
+    }
+}
+
+/// Truncate a message at the scissors marker (everything from the marker line
+/// onward is dropped). Accepts both a bare marker and Git's comment-prefixed form
+/// (`# ------------------------ >8 ...`), so the `commit -v` template (Git-standard
+/// `#` form) and the staged diff below it are removed.
+pub fn truncate_at_scissors(message: &str) -> String {
+    message
+        .lines()
+        .take_while(|line| {
+            !line
+                .trim()
+                .trim_start_matches('#')
+                .trim_start()
+                .starts_with("------------------------ >8 ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Apply Git-style cleanup to a commit message. `commit`, `merge`, `rebase` and
+/// `cherry-pick` all clean the message they record through this function
+/// before handing it to [`format_commit_msg`].
+pub fn cleanup_commit_message(message: &str, mode: CleanupMode) -> String {
+    match mode {
+        CleanupMode::Verbatim => message.to_string(),
+        CleanupMode::Scissors => {
+            // Git's `scissors` is whitespace cleanup PLUS truncation at the marker:
+            // the message above the marker keeps its `#` comment lines (unlike
+            // strip). The verbose path, which needs a post-truncation strip, calls
+            // `truncate_at_scissors` + `Strip` directly instead.
+            cleanup_commit_message(&truncate_at_scissors(message), CleanupMode::Whitespace)
+        }
+        CleanupMode::Whitespace => {
+            let lines: Vec<String> = message
+                .lines()
+                .map(|line| line.trim_end().to_string())
+                .collect();
+            let mut result = trim_empty_lines(&lines);
+            result.dedup_by(|current, previous| current.is_empty() && previous.is_empty());
+            result.join("\n")
+        }
+        CleanupMode::Strip | CleanupMode::Default => {
+            let lines: Vec<String> = message
+                .lines()
+                .map(|line| {
+                    let trimmed = line.trim_end();
+                    if trimmed.starts_with('#') {
+                        String::new()
+                    } else {
+                        trimmed.to_string()
+                    }
+                })
+                .collect();
+            let mut result = trim_empty_lines(&lines);
+            // Git's strip collapses CONSECUTIVE blank lines into one but keeps
+            // single blank separators — deleting every interior blank (the old
+            // behavior) flattened multi-paragraph messages and destroyed
+            // user-typed trailer blocks at write time.
+            result.dedup_by(|current, previous| current.is_empty() && previous.is_empty());
+            result.join("\n")
+        }
+    }
+}
+
+fn trim_empty_lines(lines: &[String]) -> Vec<String> {
+    let start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(lines.len());
+    lines[start..end].to_vec()
+}
+
 /// Check whether the first line of `msg` matches the Conventional Commits 1.0 grammar.
 ///
 /// Functional scope:
@@ -255,4 +372,84 @@ mod tests {
             );
         }
     }
+
+    /// A message exercising every cleanup rule: surrounding blank lines,
+    /// trailing whitespace, a run of blank lines, a comment and a scissors
+    /// line followed by text.
+    const RAW_MESSAGE: &str = "\n  \nsubject  \n\n\n# comment\nbody\t\n# ------------------------ >8 ------------------------\ndiff\n\n";
+
+    /// `strip` trims, collapses blank runs and drops `#` lines, including
+    /// the scissors line (but not the text after it).
+    #[test]
+    fn cleanup_strip_drops_comments() {
+        assert_eq!(
+            cleanup_commit_message(RAW_MESSAGE, CleanupMode::Strip),
+            "subject\n\nbody\n\ndiff"
+        );
+        // Only comments: strip leaves nothing, which callers treat as empty.
+        assert_eq!(
+            cleanup_commit_message("# a\n\n# b\n", CleanupMode::Strip),
+            ""
+        );
+    }
+
+    /// `whitespace` trims and collapses blank runs but keeps `#` lines.
+    #[test]
+    fn cleanup_whitespace_keeps_comments() {
+        assert_eq!(
+            cleanup_commit_message(RAW_MESSAGE, CleanupMode::Whitespace),
+            "subject\n\n# comment\nbody\n# ------------------------ >8 ------------------------\ndiff"
+        );
+    }
+
+    /// `verbatim` leaves the message untouched.
+    #[test]
+    fn cleanup_verbatim_keeps_message() {
+        assert_eq!(
+            cleanup_commit_message(RAW_MESSAGE, CleanupMode::Verbatim),
+            RAW_MESSAGE
+        );
+    }
+
+    /// `scissors` is whitespace cleanup of everything above the marker.
+    #[test]
+    fn cleanup_scissors_truncates_at_marker() {
+        assert_eq!(
+            cleanup_commit_message(RAW_MESSAGE, CleanupMode::Scissors),
+            "subject\n\n# comment\nbody"
+        );
+    }
+
+    /// `default` strips an edited message; unedited, it (like `scissors`)
+    /// falls back to `whitespace`.
+    #[test]
+    fn cleanup_default_depends_on_editing() {
+        assert_eq!(
+            cleanup_commit_message(RAW_MESSAGE, CleanupMode::Default),
+            cleanup_commit_message(RAW_MESSAGE, CleanupMode::Strip)
+        );
+        assert_eq!(CleanupMode::Default.effective(true), CleanupMode::Default);
+        assert_eq!(
+            CleanupMode::Default.effective(false),
+            CleanupMode::Whitespace
+        );
+        assert_eq!(
+            CleanupMode::Scissors.effective(false),
+            CleanupMode::Whitespace
+        );
+        assert_eq!(CleanupMode::Strip.effective(false), CleanupMode::Strip);
+    }
+
+    #[test]
+    fn parse_cleanup_mode_is_case_insensitive_and_rejects_unknown() {
+        assert_eq!(parse_cleanup_mode("strip"), Some(CleanupMode::Strip));
+        assert_eq!(
+            parse_cleanup_mode("WHITESPACE"),
+            Some(CleanupMode::Whitespace)
+        );
+        assert_eq!(parse_cleanup_mode("verbatim"), Some(CleanupMode::Verbatim));
+        assert_eq!(parse_cleanup_mode("scissors"), Some(CleanupMode::Scissors));
+        assert_eq!(parse_cleanup_mode("default"), Some(CleanupMode::Default));
+        assert_eq!(parse_cleanup_mode("bogus"), None);
+    }
 }
@@ -2,7 +2,8 @@
 //!
 //! Agents are defined in markdown files with YAML frontmatter. Each agent has a
 //! name, description, tool list, model preference, and system prompt. The agent
-//! router auto-selects the appropriate agent based on user input.
+//! router auto-selects the appropriate agent based on user input; a profile
+//! marked `default: true` is the fallback when nothing is selected.
 //!
 //! Agent definitions are loaded from a three-tier hierarchy:
 //! 1. `{working_dir}/.libra/agents/*.md` (project-local)
@@ -74,6 +74,10 @@ pub struct AgentProfile {
     pub top_p: Option<f32>,
     /// Per-agent maximum tool-loop steps override (parsed from `steps:`).
     pub max_steps: Option<u32>,
+    /// Whether this profile is the fallback agent used when no agent is
+    /// selected explicitly (`default: true`). See
+    /// [`super::router::AgentProfileRouter::default_agent`].
+    pub default: bool,
 }
 
 impl AgentProfile {
@@ -133,8 +137,9 @@ impl AgentProfile {
 ///   fence becomes the system prompt body.
 /// - Accepts a small fixed set of keys: the legacy four (`name`, `description`,
 ///   `tools`, `model`) plus the OC-Phase 2 additions (`mode`, `variant`,
-///   `temperature`, `top_p`, `steps`, `permission`). Unknown keys are ignored
-///   so future schema additions stay forward-compatible.
+///   `temperature`, `top_p`, `steps`, `permission`) and the `default` fallback
+///   flag. Unknown keys are ignored so future schema additions stay
+///   forward-compatible.
 /// - When `model:` carries a `provider/model[@variant]` value, the parser lifts
 ///   it into [`AgentProfile::model_binding`] alongside the literal string in
 ///   `model_preference`. Legacy aliases like `default` / `fast` / `powerful`
@@ -201,6 +206,7 @@ pub fn parse_agent_profile(content: &str) -> Option<AgentProfile> {
     let mut top_p: Option<f32> = None;
     let mut max_steps: Option<u32> = None;
     let mut permission = AgentPermissionSpec::default();
+    let mut default = false;
 
     let frontmatter_lines: Vec<&str> = frontmatter.lines().collect();
     let mut index = 0usize;
@@ -258,6 +264,10 @@ pub fn parse_agent_profile(content: &str) -> Option<AgentProfile> {
             && let Ok(v) = unquote(val.trim()).parse::<u32>()
         {
             max_steps = Some(v);
+        } else if let Some(val) = line.strip_prefix("default:")
+            && let Ok(v) = unquote(val.trim()).parse::<bool>()
+        {
+            default = v;
         } else if is_permission_header(line) {
             let (parsed_permission, consumed) =
                 parse_permission_block(&frontmatter_lines[index + 1..], leading_indent(raw_line));
@@ -285,6 +295,7 @@ pub fn parse_agent_profile(content: &str) -> Option<AgentProfile> {
         temperature,
         top_p,
         max_steps,
+        default,
     })
 }
 
@@ -478,6 +489,22 @@ You are an implementation planner.
         assert!(def.temperature.is_none());
         assert!(def.top_p.is_none());
         assert!(def.max_steps.is_none());
+        assert!(!def.default);
+    }
+
+    /// Scenario: `default:` accepts `true`/`false` (optionally quoted); any other
+    /// value is ignored and the profile stays a non-default agent.
+    #[test]
+    fn test_parse_default_flag() {
+        let parse = |value: &str| {
+            parse_agent_profile(&format!("---\nname: a\ndefault: {value}\n---\nbody"))
+                .unwrap()
+                .default
+        };
+        assert!(parse("true"));
+        assert!(parse("\"true\""));
+        assert!(!parse("false"));
+        assert!(!parse("yes"));
     }
 
     /// Scenario: a markdown blob with no `---` fence is rejected with `None` rather
@@ -69,6 +69,18 @@ impl AgentProfileRouter {
         best.map(|(profile, _)| profile)
     }
 
+    /// The fallback profile to use when no agent was selected explicitly.
+    ///
+    /// Returns the first profile marked `default: true`. Because
+    /// [`load_profiles`] appends tiers in priority order (project-local,
+    /// user-global, embedded), a project default wins over a user-global one,
+    /// which wins over an embedded one. `None` when no profile is marked.
+    ///
+    /// See `tests::test_default_agent_prefers_highest_tier`.
+    pub fn default_agent(&self) -> Option<&AgentProfile> {
+        self.profiles.iter().find(|profile| profile.default)
+    }
+
     /// Get all registered profiles.
     ///
     /// Used by the TUI to render an "/agents" menu and by introspection helpers.
@@ -219,9 +231,9 @@ pub fn load_profiles(working_dir: &std::path::Path) -> Vec<AgentProfile> {
 
 /// Helper for [`load_profiles`]: scan one directory and append fresh profiles.
 ///
-/// Functional scope: lists the directory, filters to `.md` files, applies the size
-/// limit, parses each, and appends every profile whose `name` is not already in
-/// `loaded_names`.
+/// Functional scope: lists the directory in file-name order, filters to `.md`
+/// files, applies the size limit, parses each, and appends every profile whose
+/// `name` is not already in `loaded_names`.
 ///
 /// Boundary conditions:
 /// - Missing directory: the function silently returns. Callers can call it for both
@@ -235,8 +247,11 @@ fn load_profiles_from_dir(
     loaded_names: &mut std::collections::HashSet<String>,
 ) {
     if let Ok(entries) = std::fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
+        // Sort so name collisions and `default: true` ties inside one tier
+        // resolve the same way on every platform.
+        let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+        for path in paths {
             if path.extension().is_none_or(|ext| ext != "md") {
                 continue;
             }
@@ -402,6 +417,7 @@ mod tests {
                 temperature: None,
                 top_p: None,
                 max_steps: None,
+                default: false,
             },
             AgentProfile {
                 name: "agent_b".to_string(),
@@ -416,6 +432,7 @@ mod tests {
                 temperature: None,
                 top_p: None,
                 max_steps: None,
+                default: false,
             },
         ];
         let router = AgentProfileRouter::new(profiles);
@@ -450,6 +467,7 @@ mod tests {
             temperature: Some(0.4),
             top_p: None,
             max_steps: Some(20),
+            default: false,
         };
         let router = AgentProfileRouter::new(vec![profile.clone()]);
 
@@ -478,6 +496,65 @@ mod tests {
         assert_eq!(spec, spec_again);
     }
 
+    fn profile(name: &str, default: bool) -> AgentProfile {
+        super::super::parser::parse_agent_profile(&format!(
+            "---\nname: {name}\ndescription: {name} agent\ndefault: {default}\n---\nbody"
+        ))
+        .unwrap()
+    }
+
+    /// Scenario: with no profile marked `default: true` there is no fallback; with
+    /// one marked, it is returned even though `select` matches nothing.
+    #[test]
+    fn test_default_agent_fallback() {
+        let router = AgentProfileRouter::new(load_embedded_profiles());
+        assert!(router.default_agent().is_none());
+
+        let router = AgentProfileRouter::new(vec![profile("alpha", false), profile("beta", true)]);
+        assert!(router.select("hello world").is_none());
+        assert_eq!(router.default_agent().unwrap().name, "beta");
+    }
+
+    /// Scenario: defaults in several tiers resolve to the project-local one,
+    /// then the user-global one, then the embedded one — the order in which
+    /// [`load_profiles`] appends them.
+    #[test]
+    fn test_default_agent_prefers_highest_tier() {
+        let router = AgentProfileRouter::new(vec![
+            profile("project", true),
+            profile("user", true),
+            profile("embedded", true),
+        ]);
+        assert_eq!(router.default_agent().unwrap().name, "project");
+
+        let router = AgentProfileRouter::new(vec![
+            profile("project", false),
+            profile("user", true),
+            profile("embedded", true),
+        ]);
+        assert_eq!(router.default_agent().unwrap().name, "user");
+    }
+
+    /// Scenario: a project-local default agent is picked up by `load_profiles`
+    /// ahead of the embedded profiles, and two defaults in the same tier
+    /// resolve by file name.
+    #[test]
+    fn test_load_profiles_project_default_agent() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let agents_dir = tmp.path().join(".libra").join("agents");
+        std::fs::create_dir_all(&agents_dir).unwrap();
+        for name in ["b_helper", "a_helper"] {
+            std::fs::write(
+                agents_dir.join(format!("{name}.md")),
+                format!("---\nname: {name}\ndescription: Helper\ndefault: true\n---\nbody"),
+            )
+            .unwrap();
+        }
+
+        let router = AgentProfileRouter::new(load_profiles(tmp.path()));
+        assert_eq!(router.default_agent().unwrap().name, "a_helper");
+    }
+
     /// Scenario: a project-local `.libra/agents/planner.md` shadows the embedded
     /// `planner` default — verifies the three-tier override logic.
     #[test]
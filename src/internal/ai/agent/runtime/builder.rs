@@ -1,16 +1,17 @@
 //! Fluent builder for [`super::Agent`].
 //!
 //! Construction goes through [`AgentBuilder`] so that knobs that need validation
-//! (currently only the sampling temperature) reject bad input before the agent is
+//! (the sampling temperature and the cost cap) reject bad input before the agent is
 //! observable. The builder is intentionally consuming (`mut self` returns) to mirror
 //! the rest of the codebase's builder style.
 
 use std::sync::Arc;
 
-use super::Agent;
+use super::{Agent, AgentPricing};
 use crate::internal::ai::{
     completion::CompletionModel,
     tools::{Tool, ToolRegistry, ToolSet},
+    usage::UsagePriceTable,
 };
 
 /// A builder for configuring and creating AI Agent instances.
@@ -23,6 +24,9 @@ pub struct AgentBuilder<M: CompletionModel> {
     preamble: Option<String>,
     temperature: Option<f64>,
     tools: ToolSet,
+    max_tokens: Option<u32>,
+    max_cost_usd: Option<f64>,
+    pricing: Option<AgentPricing>,
 }
 
 impl<M: CompletionModel> AgentBuilder<M> {
@@ -36,6 +40,9 @@ impl<M: CompletionModel> AgentBuilder<M> {
             preamble: None,
             temperature: None,
             tools: ToolSet::default(),
+            max_tokens: None,
+            max_cost_usd: None,
+            pricing: None,
         }
     }
 
@@ -99,6 +106,52 @@ impl<M: CompletionModel> AgentBuilder<M> {
         Ok(self)
     }
 
+    /// Caps the total tokens consumed across all API calls of one run.
+    ///
+    /// Functional scope: every response's usage is added up while the agent loops
+    /// through tool calls; once the total reaches `max_tokens`, the agent returns
+    /// `Err(CompletionError::ResponseError("Token budget exceeded"))` instead of
+    /// making another request. Responses that report no usage count as zero.
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Caps the spend (USD) across all API calls of one run.
+    ///
+    /// Functional scope: works like [`Self::max_tokens`] but on cost, returning
+    /// `Err(CompletionError::ResponseError("Cost budget exceeded"))`. A response's
+    /// cost is the provider-reported `cost_usd` when present, otherwise the estimate
+    /// from the table given to [`Self::pricing`].
+    ///
+    /// Boundary conditions:
+    /// - Returns `Err(String)` for a negative or non-finite value.
+    pub fn max_cost_usd(mut self, max_cost_usd: f64) -> Result<Self, String> {
+        if !max_cost_usd.is_finite() || max_cost_usd < 0.0 {
+            return Err(format!(
+                "Cost limit must be a non-negative number of USD, got {max_cost_usd}"
+            ));
+        }
+        self.max_cost_usd = Some(max_cost_usd);
+        Ok(self)
+    }
+
+    /// Sets the price table used to estimate the cost of responses whose provider
+    /// does not report one, looked up under `provider` / `model`.
+    pub fn pricing(
+        mut self,
+        table: UsagePriceTable,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+    ) -> Self {
+        self.pricing = Some(AgentPricing {
+            table,
+            provider: provider.into(),
+            model: model.into(),
+        });
+        self
+    }
+
     /// Builds and returns the configured Agent instance.
     ///
     /// Wraps the model in an `Arc` so the resulting [`Agent`] is cheap to clone for
@@ -109,6 +162,9 @@ impl<M: CompletionModel> AgentBuilder<M> {
             preamble: self.preamble,
             temperature: self.temperature,
             tools: self.tools,
+            max_tokens: self.max_tokens,
+            max_cost_usd: self.max_cost_usd,
+            pricing: self.pricing,
         }
     }
 }
@@ -274,6 +330,27 @@ mod tests {
         assert!(neg.contains("Temperature"));
     }
 
+    /// `max_cost_usd()` accepts zero and positive values and rejects negative,
+    /// NaN, and infinite ones with a message naming the value.
+    #[test]
+    fn agent_builder_max_cost_usd_validation() {
+        let agent = AgentBuilder::new(MockModel)
+            .max_tokens(10_000)
+            .max_cost_usd(0.0)
+            .expect("zero is a valid cap")
+            .build();
+        assert_eq!(agent.max_tokens, Some(10_000));
+        assert_eq!(agent.max_cost_usd, Some(0.0));
+
+        for bad in [-0.01, f64::NAN, f64::INFINITY] {
+            let err = AgentBuilder::new(MockModel)
+                .max_cost_usd(bad)
+                .err()
+                .expect("invalid cost cap must be rejected");
+            assert!(err.contains("Cost limit"), "got: {err}");
+        }
+    }
+
     /// `tool()` appends to the existing tool set preserving order. Pin
     /// so a future refactor using a HashSet or sorted Vec gets caught.
     #[test]
@@ -7,7 +7,7 @@
 //! the running conversation.
 
 use super::Agent;
-use crate::internal::ai::completion::{CompletionError, CompletionModel, Message};
+use crate::internal::ai::completion::{CompletionError, CompletionModel, CompletionUsage, Message};
 
 /// A stateful agent that maintains conversation history.
 ///
@@ -19,8 +19,11 @@ use crate::internal::ai::completion::{CompletionError, CompletionModel, Message}
 ///
 /// ```rust,no_run
 /// # use libra::internal::ai::agent::{Agent, ChatAgent};
-/// # use libra::internal::ai::completion::CompletionModel;
-/// # async fn example<M: CompletionModel>(model: M) {
+/// # use libra::internal::ai::completion::{CompletionModel, CompletionUsage};
+/// # async fn example<M: CompletionModel>(model: M)
+/// # where
+/// #     M::Response: CompletionUsage,
+/// # {
 /// let agent = Agent::new(model);
 /// let mut chat_agent = ChatAgent::new(agent);
 ///
@@ -69,7 +72,10 @@ impl<M: CompletionModel> ChatAgent<M> {
     pub async fn chat(
         &mut self,
         prompt: impl Into<String> + Send,
-    ) -> Result<String, CompletionError> {
+    ) -> Result<String, CompletionError>
+    where
+        M::Response: CompletionUsage,
+    {
         let user_msg = Message::user(prompt.into());
 
         // Update history with user message first
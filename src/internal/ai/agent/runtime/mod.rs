@@ -19,10 +19,12 @@ use std::sync::Arc;
 
 use crate::internal::ai::{
     completion::{
-        Chat, CompletionError, CompletionModel, CompletionRequest, Message, Prompt,
+        Chat, CompletionError, CompletionModel, CompletionRequest, CompletionUsage,
+        CompletionUsageSummary, Message, Prompt,
         message::{AssistantContent, OneOrMany, ToolResult, UserContent},
     },
     tools::{ToolDefinition, ToolSet},
+    usage::UsagePriceTable,
 };
 
 pub mod builder;
@@ -82,6 +84,30 @@ pub struct Agent<M: CompletionModel> {
     temperature: Option<f64>,
     /// Set of tools available to the agent.
     tools: ToolSet,
+    /// Cap on the tokens consumed across all requests of one
+    /// [`Self::run_with_history`] call.
+    max_tokens: Option<u32>,
+    /// Cap on the spend (USD) across all requests of one
+    /// [`Self::run_with_history`] call.
+    max_cost_usd: Option<f64>,
+    /// Prices used to estimate spend for responses that do not report `cost_usd`.
+    pricing: Option<AgentPricing>,
+}
+
+/// The price table entry an [`Agent`] uses to estimate the cost of a response
+/// whose provider does not report one.
+#[derive(Clone, Debug)]
+pub(crate) struct AgentPricing {
+    pub(crate) table: UsagePriceTable,
+    pub(crate) provider: String,
+    pub(crate) model: String,
+}
+
+/// Tokens and spend accumulated by one [`Agent::run_with_history`] call.
+#[derive(Debug, Default)]
+struct RunUsage {
+    tokens: u64,
+    cost_usd: f64,
 }
 
 impl<M: CompletionModel> Agent<M> {
@@ -100,9 +126,63 @@ impl<M: CompletionModel> Agent<M> {
             preamble: None,
             temperature: None,
             tools: ToolSet::default(),
+            max_tokens: None,
+            max_cost_usd: None,
+            pricing: None,
         }
     }
 
+    /// Add one response's usage to the running totals. Tokens fall back to
+    /// `input + output + reasoning` when the provider omits `total_tokens`, and
+    /// cost falls back to the configured price table when it omits `cost_usd`.
+    fn record_usage(&self, run: &mut RunUsage, usage: &CompletionUsageSummary) {
+        let tokens = usage.total_tokens.unwrap_or_else(|| {
+            usage
+                .input_tokens
+                .saturating_add(usage.output_tokens)
+                .saturating_add(usage.reasoning_tokens.unwrap_or(0))
+        });
+        run.tokens = run.tokens.saturating_add(tokens);
+        let cost = usage.cost_usd.or_else(|| {
+            let pricing = self.pricing.as_ref()?;
+            let micro_dollars =
+                pricing
+                    .table
+                    .estimate_micro_dollars(&pricing.provider, &pricing.model, usage)?;
+            Some(micro_dollars as f64 / 1_000_000.0)
+        });
+        run.cost_usd += cost.unwrap_or(0.0);
+    }
+
+    /// Refuse to issue another request once a configured cap has been reached.
+    fn check_budget(&self, run: &RunUsage) -> Result<(), CompletionError> {
+        if let Some(limit) = self.max_tokens
+            && run.tokens >= u64::from(limit)
+        {
+            tracing::info!(
+                spent = run.tokens,
+                limit,
+                "agent stopped before the next request: token budget exceeded"
+            );
+            return Err(CompletionError::ResponseError(
+                "Token budget exceeded".to_string(),
+            ));
+        }
+        if let Some(limit) = self.max_cost_usd
+            && run.cost_usd >= limit
+        {
+            tracing::info!(
+                spent_usd = run.cost_usd,
+                limit_usd = limit,
+                "agent stopped before the next request: cost budget exceeded"
+            );
+            return Err(CompletionError::ResponseError(
+                "Cost budget exceeded".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Drive the model/tool ping-pong starting from a pre-populated chat history.
     ///
     /// Functional scope:
@@ -121,15 +201,25 @@ impl<M: CompletionModel> Agent<M> {
     ///   tried to call a tool that is not registered on the agent.
     /// - Empty content in either the assistant turn or the tool-result turn is treated
     ///   as a malformed response and surfaces as `ResponseError` rather than panicking.
-    /// - This loop has no iteration limit by design; callers that need a budget should
-    ///   use the [`tool_loop`] entry points instead.
+    /// - With [`AgentBuilder::max_tokens`] or [`AgentBuilder::max_cost_usd`] set, the
+    ///   usage of every response is accumulated and checked before the next request;
+    ///   once a cap is reached the loop returns `ResponseError("Token budget
+    ///   exceeded")` / `ResponseError("Cost budget exceeded")` instead of calling the
+    ///   model again. A final answer is still returned even if it crossed a cap.
+    /// - Without caps this loop has no iteration limit by design; callers that need a
+    ///   turn budget should use the [`tool_loop`] entry points instead.
     pub(crate) async fn run_with_history(
         &self,
         mut chat_history: Vec<Message>,
-    ) -> Result<String, CompletionError> {
+    ) -> Result<String, CompletionError>
+    where
+        M::Response: CompletionUsage,
+    {
         let tools: Vec<ToolDefinition> = self.tools.tools.iter().map(|t| t.definition()).collect();
+        let mut run_usage = RunUsage::default();
 
         loop {
+            self.check_budget(&run_usage)?;
             let request = CompletionRequest {
                 preamble: self.preamble.clone(),
                 chat_history: chat_history.clone(),
@@ -139,6 +229,9 @@ impl<M: CompletionModel> Agent<M> {
             };
 
             let response = self.model.completion(request).await?;
+            if let Some(usage) = response.raw_response.usage_summary() {
+                self.record_usage(&mut run_usage, &usage);
+            }
 
             let mut tool_calls = Vec::new();
             for item in &response.content {
@@ -227,7 +320,10 @@ impl<M: CompletionModel> Agent<M> {
     }
 }
 
-impl<M: CompletionModel> Prompt for Agent<M> {
+impl<M: CompletionModel> Prompt for Agent<M>
+where
+    M::Response: CompletionUsage,
+{
     /// Single-shot prompt: starts a fresh conversation containing only `prompt` and
     /// drives the tool loop until the model stops calling tools.
     async fn prompt(&self, prompt: impl Into<Message> + Send) -> Result<String, CompletionError> {
@@ -236,7 +332,10 @@ impl<M: CompletionModel> Prompt for Agent<M> {
     }
 }
 
-impl<M: CompletionModel> Chat for Agent<M> {
+impl<M: CompletionModel> Chat for Agent<M>
+where
+    M::Response: CompletionUsage,
+{
     /// Multi-turn prompt: appends `prompt` to `chat_history` (without persisting the
     /// updated history anywhere) and drives the tool loop. The agent itself stays
     /// stateless — the caller owns the history.
@@ -253,16 +352,22 @@ impl<M: CompletionModel> Chat for Agent<M> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
     use serde_json::json;
 
     use super::AgentBuilder;
     use crate::internal::ai::{
         completion::{
-            CompletionError, CompletionModel, CompletionRequest, CompletionResponse, Message,
-            Prompt,
+            CompletionError, CompletionModel, CompletionRequest, CompletionResponse,
+            CompletionUsage, CompletionUsageSummary, Message, Prompt,
             message::{AssistantContent, Function, Text, ToolCall, UserContent},
         },
         tools::{Tool, ToolDefinition, ToolSet},
+        usage::{UsagePrice, UsagePriceTable},
     };
 
     #[derive(Clone)]
@@ -352,4 +457,85 @@ mod tests {
 
         assert_eq!(response, "done");
     }
+
+    /// Reports the wrapped usage for every response.
+    struct UsageResponse(CompletionUsageSummary);
+
+    impl CompletionUsage for UsageResponse {
+        fn usage_summary(&self) -> Option<CompletionUsageSummary> {
+            Some(self.0.clone())
+        }
+    }
+
+    /// A model stuck in a tool loop: every response calls `mock_tool` again and
+    /// reports 100 input tokens.
+    #[derive(Clone, Default)]
+    struct LoopingModel {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl CompletionModel for LoopingModel {
+        type Response = UsageResponse;
+
+        async fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(CompletionResponse {
+                content: vec![AssistantContent::ToolCall(ToolCall {
+                    id: "call_1".to_string(),
+                    name: "mock_tool".to_string(),
+                    function: Function {
+                        name: "mock_tool".to_string(),
+                        arguments: json!({"value": 1}),
+                    },
+                })],
+                reasoning_content: None,
+                raw_response: UsageResponse(CompletionUsageSummary {
+                    input_tokens: 100,
+                    ..Default::default()
+                }),
+            })
+        }
+    }
+
+    /// Scenario: with a 150-token cap the second response pushes the total to 200,
+    /// so the agent stops before a third request.
+    #[tokio::test]
+    async fn token_budget_stops_runaway_tool_loop() {
+        let model = LoopingModel::default();
+        let agent = AgentBuilder::new(model.clone())
+            .tool(MockTool)
+            .max_tokens(150)
+            .build();
+
+        let err = Prompt::prompt(&agent, "hi").await.unwrap_err();
+
+        assert!(err.to_string().contains("Token budget exceeded"), "{err}");
+        assert_eq!(model.calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// Scenario: the provider reports no cost, so spend is estimated from the price
+    /// table ($1 per 100 input tokens); a $1.50 cap allows exactly two requests.
+    #[tokio::test]
+    async fn cost_budget_uses_price_table_estimate() {
+        let model = LoopingModel::default();
+        let table = UsagePriceTable::new().with_override(
+            "mock",
+            "looping",
+            UsagePrice::new(10_000_000_000, 0),
+        );
+        let agent = AgentBuilder::new(model.clone())
+            .tool(MockTool)
+            .pricing(table, "mock", "looping")
+            .max_cost_usd(1.5)
+            .unwrap()
+            .build();
+
+        let err = Prompt::prompt(&agent, "hi").await.unwrap_err();
+
+        assert!(err.to_string().contains("Cost budget exceeded"), "{err}");
+        assert_eq!(model.calls.load(Ordering::SeqCst), 2);
+    }
 }
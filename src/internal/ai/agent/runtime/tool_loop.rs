@@ -2493,6 +2493,7 @@ mod tests {
                     description: "test blocker".to_string(),
                     timeout_ms: 5000,
                     enabled: true,
+                    priority: 0,
                 }],
             },
             temp_dir.path().to_path_buf(),
@@ -1,6 +1,6 @@
 //! Slash command dispatcher: routes `/command args` to the right handler.
 
-use super::parser::CommandDefinition;
+use super::parser::{CommandDefinition, CommandExpandError};
 
 /// Dispatches slash commands to their definitions.
 pub struct CommandDispatcher {
@@ -24,9 +24,10 @@ impl CommandDispatcher {
 
     /// Try to dispatch user input as a slash command.
     ///
-    /// Returns `Some(DispatchResult)` if the input starts with a known `/command`,
-    /// or `None` if it's not a slash command.
-    pub fn dispatch(&self, input: &str) -> Option<DispatchResult> {
+    /// Returns `Some(Ok(DispatchResult))` if the input starts with a known
+    /// `/command`, `Some(Err(_))` if the command's template cannot be expanded
+    /// (e.g. too few arguments), or `None` if it's not a slash command.
+    pub fn dispatch(&self, input: &str) -> Option<Result<DispatchResult, CommandExpandError>> {
         let input = input.trim();
         if !input.starts_with('/') {
             return None;
@@ -40,10 +41,10 @@ impl CommandDispatcher {
 
         let command = self.commands.iter().find(|c| c.name == cmd_name)?;
 
-        Some(DispatchResult {
-            prompt: command.expand(arguments),
+        Some(command.expand(arguments).map(|prompt| DispatchResult {
+            prompt,
             agent: command.agent.clone(),
-        })
+        }))
     }
 
     /// Get all registered command definitions.
@@ -143,7 +144,7 @@ mod tests {
 
         let result = dispatcher.dispatch("/architect add user authentication");
         assert!(result.is_some());
-        let result = result.unwrap();
+        let result = result.unwrap().unwrap();
         assert!(result.prompt.contains("add user authentication"));
         assert_eq!(result.agent.as_deref(), Some("architect"));
     }
@@ -155,7 +156,7 @@ mod tests {
 
         let result = dispatcher.dispatch("/code-review src/main.rs");
         assert!(result.is_some());
-        let result = result.unwrap();
+        let result = result.unwrap().unwrap();
         assert!(result.prompt.contains("src/main.rs"));
         assert_eq!(result.agent.as_deref(), Some("code_reviewer"));
     }
@@ -167,7 +168,7 @@ mod tests {
 
         let result = dispatcher.dispatch("/verify");
         assert!(result.is_some());
-        let result = result.unwrap();
+        let result = result.unwrap().unwrap();
         assert!(result.agent.is_none());
     }
 
@@ -211,6 +212,7 @@ mod tests {
         // Dispatch /architect command
         let result = dispatcher
             .dispatch("/architect implement user auth")
+            .unwrap()
             .unwrap();
         assert_eq!(result.agent.as_deref(), Some("architect"));
 
@@ -221,7 +223,10 @@ mod tests {
         assert!(!agent.tools.is_empty());
 
         // Dispatch /code-review command
-        let result = dispatcher.dispatch("/code-review src/lib.rs").unwrap();
+        let result = dispatcher
+            .dispatch("/code-review src/lib.rs")
+            .unwrap()
+            .unwrap();
         assert_eq!(result.agent.as_deref(), Some("code_reviewer"));
         let agent = router.get("code_reviewer").unwrap();
         assert_eq!(agent.name, "code_reviewer");
@@ -233,12 +238,32 @@ mod tests {
         let commands = load_embedded_commands();
         let dispatcher = CommandDispatcher::new(commands);
 
-        let result = dispatcher.dispatch("/verify").unwrap();
+        let result = dispatcher.dispatch("/verify").unwrap().unwrap();
         assert!(result.agent.is_none());
         // The prompt should still be expanded
         assert!(!result.prompt.is_empty());
     }
 
+    #[test]
+    fn test_dispatch_reports_missing_required_args() {
+        let commands = vec![
+            crate::internal::ai::commands::parser::parse_command_definition(
+                "---\nname: rename\nrequired_args: 2\n---\nRename $1 to $2 everywhere.",
+            )
+            .unwrap(),
+        ];
+        let dispatcher = CommandDispatcher::new(commands);
+
+        let err = dispatcher.dispatch("/rename old").unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "/rename needs at least 2 arguments, but 1 given."
+        );
+
+        let result = dispatcher.dispatch("/rename old new").unwrap().unwrap();
+        assert_eq!(result.prompt, "Rename old to new everywhere.");
+    }
+
     #[test]
     fn test_load_commands_with_project_override() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -2,8 +2,8 @@
 //!
 //! Commands are `/name arguments` patterns intercepted from user input.
 //! Each command is defined in a markdown file with YAML frontmatter specifying
-//! its name, description, optional agent, and a template body with `$ARGUMENTS`
-//! placeholder.
+//! its name, description, optional agent, optional `required_args` count, and
+//! a template body with `$ARGUMENTS` and positional `$1`, `$2`, … placeholders.
 //!
 //! Command definitions are loaded from a three-tier hierarchy:
 //! 1. `{working_dir}/.libra/commands/*.md` (project-local)
@@ -14,4 +14,4 @@ pub mod dispatcher;
 pub mod parser;
 
 pub use dispatcher::{CommandDispatcher, DispatchResult, load_commands, load_embedded_commands};
-pub use parser::CommandDefinition;
+pub use parser::{CommandDefinition, CommandExpandError};
@@ -1,6 +1,12 @@
 //! Slash command parser: markdown + YAML frontmatter → CommandDefinition.
+//!
+//! Templates may reference the raw argument string as `$ARGUMENTS` and
+//! individual shell-split arguments as `$1`, `$2`, … Every occurrence is
+//! expanded. Inside a fenced `sh`/`bash`/`shell`/`zsh`/`console` block the
+//! substituted values are single-quoted so user input cannot inject extra
+//! shell syntax into a command the model is asked to run.
 
-use std::path::Path;
+use std::{fmt, path::Path};
 
 /// A parsed slash command definition.
 #[derive(Debug, Clone)]
@@ -11,17 +17,159 @@ pub struct CommandDefinition {
     pub description: String,
     /// Optional agent to use when executing this command.
     pub agent: Option<String>,
-    /// The command template body (markdown). Contains `$ARGUMENTS` placeholder.
+    /// Minimum number of positional arguments the command needs
+    /// (`required_args:` in the frontmatter, default 0).
+    pub required_args: usize,
+    /// The command template body (markdown). Contains `$ARGUMENTS` and/or
+    /// `$1`, `$2`, … placeholders.
     pub template: String,
 }
 
+/// User-readable template expansion errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandExpandError {
+    MissingArguments {
+        command: String,
+        required: usize,
+        given: usize,
+    },
+}
+
+impl fmt::Display for CommandExpandError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingArguments {
+                command,
+                required,
+                given,
+            } => {
+                let noun = if *required == 1 {
+                    "argument"
+                } else {
+                    "arguments"
+                };
+                write!(
+                    formatter,
+                    "/{command} needs at least {required} {noun}, but {given} given."
+                )
+            }
+        }
+    }
+}
+
 impl CommandDefinition {
     /// Expand the command template with the given arguments.
-    pub fn expand(&self, arguments: &str) -> String {
-        self.template.replace("$ARGUMENTS", arguments)
+    ///
+    /// Fails when fewer than `required_args` positional arguments are given;
+    /// otherwise missing `$N` placeholders expand to an empty string.
+    pub fn expand(&self, arguments: &str) -> Result<String, CommandExpandError> {
+        let arguments = arguments.trim();
+        let positional = split_arguments(arguments);
+        if positional.len() < self.required_args {
+            return Err(CommandExpandError::MissingArguments {
+                command: self.name.clone(),
+                required: self.required_args,
+                given: positional.len(),
+            });
+        }
+
+        let mut output = String::with_capacity(self.template.len() + arguments.len());
+        let mut shell_fence: Option<(char, usize, bool)> = None;
+        for line in self.template.split_inclusive('\n') {
+            if let Some((ch, len, info)) = parse_fence(line) {
+                match shell_fence {
+                    None => {
+                        shell_fence = Some((ch, len, is_shell_info(info)));
+                        output.push_str(line);
+                        continue;
+                    }
+                    Some((open_ch, open_len, _))
+                        if ch == open_ch && len >= open_len && info.is_empty() =>
+                    {
+                        shell_fence = None;
+                        output.push_str(line);
+                        continue;
+                    }
+                    Some(_) => {}
+                }
+            }
+            let in_shell = shell_fence.is_some_and(|(_, _, shell)| shell);
+            expand_line(line, arguments, &positional, in_shell, &mut output);
+        }
+        Ok(output)
     }
 }
 
+/// Split the raw argument string like a POSIX shell, falling back to plain
+/// whitespace splitting when the quoting is unbalanced.
+fn split_arguments(arguments: &str) -> Vec<String> {
+    shlex::split(arguments)
+        .unwrap_or_else(|| arguments.split_whitespace().map(str::to_string).collect())
+}
+
+/// Recognize a markdown code fence line, returning the fence character, its
+/// length, and the trimmed info string.
+fn parse_fence(line: &str) -> Option<(char, usize, &str)> {
+    let trimmed = line.trim_start();
+    let ch = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == ch).count();
+    (len >= 3).then(|| (ch, len, trimmed[len..].trim()))
+}
+
+fn is_shell_info(info: &str) -> bool {
+    let lang = info.split_whitespace().next().unwrap_or("");
+    matches!(
+        lang.to_ascii_lowercase().as_str(),
+        "sh" | "bash" | "shell" | "zsh" | "console"
+    )
+}
+
+/// POSIX single-quote `value` so the shell treats it as one literal word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\"'\"'"))
+}
+
+fn expand_line(
+    line: &str,
+    arguments: &str,
+    positional: &[String],
+    in_shell: bool,
+    output: &mut String,
+) {
+    let mut rest = line;
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(tail) = after.strip_prefix("ARGUMENTS") {
+            if in_shell {
+                let quoted: Vec<String> = positional.iter().map(|arg| shell_quote(arg)).collect();
+                output.push_str(&quoted.join(" "));
+            } else {
+                output.push_str(arguments);
+            }
+            rest = tail;
+            continue;
+        }
+        let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+        match after[..digits].parse::<usize>() {
+            Ok(index) if index > 0 => {
+                let value = positional.get(index - 1).map(String::as_str).unwrap_or("");
+                if in_shell {
+                    output.push_str(&shell_quote(value));
+                } else {
+                    output.push_str(value);
+                }
+                rest = &after[digits..];
+            }
+            _ => {
+                output.push('$');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+}
+
 /// Parse a markdown string with YAML frontmatter into a CommandDefinition.
 pub fn parse_command_definition(content: &str) -> Option<CommandDefinition> {
     let content = content.trim();
@@ -37,6 +185,7 @@ pub fn parse_command_definition(content: &str) -> Option<CommandDefinition> {
     let mut name = None;
     let mut description = None;
     let mut agent = None;
+    let mut required_args = 0;
 
     for line in frontmatter.lines() {
         let line = line.trim();
@@ -49,6 +198,8 @@ pub fn parse_command_definition(content: &str) -> Option<CommandDefinition> {
             if !val.is_empty() {
                 agent = Some(val.to_string());
             }
+        } else if let Some(val) = line.strip_prefix("required_args:") {
+            required_args = val.trim().parse().ok()?;
         }
     }
 
@@ -56,6 +207,7 @@ pub fn parse_command_definition(content: &str) -> Option<CommandDefinition> {
         name: name?,
         description: description.unwrap_or_default(),
         agent,
+        required_args,
         template: body.to_string(),
     })
 }
@@ -116,13 +268,102 @@ Create a plan for: $ARGUMENTS
             template: "Plan for: $ARGUMENTS\n\nDetails about $ARGUMENTS".to_string(),
         };
 
-        let expanded = cmd.expand("add user auth");
+        let expanded = cmd.expand("add user auth").unwrap();
         assert_eq!(
             expanded,
             "Plan for: add user auth\n\nDetails about add user auth"
         );
     }
 
+    fn command(template: &str, required_args: usize) -> CommandDefinition {
+        CommandDefinition {
+            name: "run".to_string(),
+            description: String::new(),
+            agent: None,
+            required_args,
+            template: template.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_expand_without_arguments() {
+        let cmd = command("Mode: $ARGUMENTS.", 0);
+        assert_eq!(cmd.expand("").unwrap(), "Mode: .");
+        assert_eq!(cmd.expand("   ").unwrap(), "Mode: .");
+    }
+
+    #[test]
+    fn test_expand_positional_placeholders() {
+        let cmd = command("from $1 to $2, missing [$3], all: $ARGUMENTS, cost $0 $", 0);
+        assert_eq!(
+            cmd.expand(r#"main "feature x""#).unwrap(),
+            r#"from main to feature x, missing [], all: main "feature x", cost $0 $"#
+        );
+    }
+
+    #[test]
+    fn test_expand_multi_digit_placeholder() {
+        let args = (1..=10)
+            .map(|n| format!("a{n}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(command("$10|$1", 0).expand(&args).unwrap(), "a10|a1");
+    }
+
+    #[test]
+    fn test_expand_quotes_arguments_inside_shell_fence() {
+        let cmd = command(
+            "Target: $ARGUMENTS\n```bash\ngrep -rn $1 $ARGUMENTS\n```\nAfter: $1\n",
+            0,
+        );
+        let expanded = cmd.expand("x'; rm -rf / #").unwrap();
+        assert_eq!(
+            expanded,
+            "Target: x'; rm -rf / #\n```bash\ngrep -rn 'x'\"'\"'';' 'x'\"'\"'';' 'rm' '-rf' '/' '#'\n```\nAfter: x';\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_leaves_non_shell_fence_unquoted() {
+        let cmd = command("```text\n$ARGUMENTS\n```\n~~~sh\necho $1\n~~~\n", 0);
+        assert_eq!(
+            cmd.expand("a b").unwrap(),
+            "```text\na b\n```\n~~~sh\necho 'a'\n~~~\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_enforces_required_args() {
+        let cmd = command("Rename $1 to $2", 2);
+        let err = cmd.expand("old").unwrap_err();
+        assert_eq!(
+            err,
+            CommandExpandError::MissingArguments {
+                command: "run".to_string(),
+                required: 2,
+                given: 1,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "/run needs at least 2 arguments, but 1 given."
+        );
+        assert_eq!(cmd.expand("old new").unwrap(), "Rename old to new");
+    }
+
+    #[test]
+    fn test_parse_required_args() {
+        let content = "---\nname: rename\nrequired_args: 2\n---\nRename $1 to $2";
+        let cmd = parse_command_definition(content).unwrap();
+        assert_eq!(cmd.required_args, 2);
+
+        let content = "---\nname: rename\n---\nBody";
+        assert_eq!(parse_command_definition(content).unwrap().required_args, 0);
+
+        let content = "---\nname: rename\nrequired_args: two\n---\nBody";
+        assert!(parse_command_definition(content).is_none());
+    }
+
     #[test]
     fn test_parse_no_frontmatter() {
         assert!(parse_command_definition("No frontmatter").is_none());
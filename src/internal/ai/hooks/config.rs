@@ -6,9 +6,10 @@
 //!
 //! Two tiers are merged (not overridden) so a project may layer additional hooks on
 //! top of a user-global default set. Both tiers are optional; missing files are
-//! silently ignored.
+//! silently ignored. A hook repeated verbatim in both tiers runs once, and
+//! `priority` orders hooks across tiers.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -38,6 +39,10 @@ pub struct HookDefinition {
     /// Whether this hook is enabled. Defaults to true.
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// Ordering across tiers: higher priorities run first. Hooks with equal
+    /// priority keep file order, project tier before user tier. Defaults to 0.
+    #[serde(default)]
+    pub priority: i32,
 }
 
 /// Default hook timeout in milliseconds. Chosen to be long enough for typical lint /
@@ -73,6 +78,14 @@ impl HookDefinition {
             .split('|')
             .any(|pattern| pattern.trim() == tool_name)
     }
+
+    /// Whether `other` registers the same command for the same event and tools,
+    /// so running both would only repeat the work.
+    fn is_duplicate_of(&self, other: &HookDefinition) -> bool {
+        self.event == other.event
+            && self.matcher.trim() == other.matcher.trim()
+            && self.command.trim() == other.command.trim()
+    }
 }
 
 /// Root document persisted in `hooks.json`.
@@ -94,6 +107,9 @@ pub struct HookConfig {
 ///   Linux/macOS).
 /// - Hooks from both files are concatenated; later tiers do not override earlier
 ///   ones — every matching hook fires.
+/// - A hook whose event, matcher, and command repeat an earlier one is skipped,
+///   so the same script listed in both tiers runs once (the first copy wins).
+/// - The merged list is stably sorted by descending `priority`.
 ///
 /// Boundary conditions:
 /// - Missing files are silently skipped — running without hooks is a valid state.
@@ -102,23 +118,30 @@ pub struct HookConfig {
 /// - When `dirs::config_dir()` returns `None` (unusual sandboxed environments) only
 ///   the project-local tier is loaded.
 pub fn load_hook_config(working_dir: &Path) -> HookConfig {
-    let mut all_hooks = Vec::new();
-
-    // 1. Project-local
-    let project_config = working_dir.join(".libra").join("hooks.json");
-    if let Some(config) = load_config_file(&project_config) {
-        all_hooks.extend(config.hooks);
+    // 1. Project-local, 2. User-global
+    let mut tiers = vec![working_dir.join(".libra").join("hooks.json")];
+    if let Some(config_dir) = dirs::config_dir() {
+        tiers.push(config_dir.join("libra").join("hooks.json"));
     }
+    load_hook_tiers(&tiers)
+}
 
-    // 2. User-global
-    if let Some(config_dir) = dirs::config_dir() {
-        let user_config = config_dir.join("libra").join("hooks.json");
-        if let Some(config) = load_config_file(&user_config) {
-            all_hooks.extend(config.hooks);
+/// Load each tier file in order and merge the hooks they define.
+fn load_hook_tiers(paths: &[PathBuf]) -> HookConfig {
+    let mut hooks: Vec<HookDefinition> = Vec::new();
+    for hook in paths
+        .iter()
+        .filter_map(|path| load_config_file(path))
+        .flat_map(|config| config.hooks)
+    {
+        if hooks.iter().any(|existing| existing.is_duplicate_of(&hook)) {
+            tracing::debug!("Skipping duplicate hook command: {}", hook.command);
+            continue;
         }
+        hooks.push(hook);
     }
-
-    HookConfig { hooks: all_hooks }
+    hooks.sort_by_key(|hook| std::cmp::Reverse(hook.priority));
+    HookConfig { hooks }
 }
 
 /// Try to read and parse a single `hooks.json` from the given path.
@@ -150,6 +173,7 @@ mod tests {
             description: String::new(),
             timeout_ms: 10_000,
             enabled: true,
+            priority: 0,
         };
 
         assert!(hook.matches_tool("read_file"));
@@ -167,6 +191,7 @@ mod tests {
             description: String::new(),
             timeout_ms: 10_000,
             enabled: true,
+            priority: 0,
         };
 
         assert!(hook.matches_tool("read_file"));
@@ -183,6 +208,7 @@ mod tests {
             description: String::new(),
             timeout_ms: 10_000,
             enabled: true,
+            priority: 0,
         };
 
         assert!(hook.matches_tool("anything"));
@@ -213,6 +239,63 @@ mod tests {
         assert_eq!(config.hooks[0].matcher, "shell");
     }
 
+    fn write_tier(dir: &Path, hooks: &str) -> PathBuf {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join("hooks.json");
+        std::fs::write(&path, format!(r#"{{"hooks": {hooks}}}"#)).unwrap();
+        path
+    }
+
+    // Scenario: project and user tiers both define a shell hook plus one shared
+    // command. The shared command is kept once, the distinct hooks from both
+    // tiers run, and `priority` orders them across tiers.
+    #[tokio::test]
+    async fn test_load_hook_tiers_merges_dedups_and_orders() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let project = write_tier(
+            &tmp.path().join("project"),
+            r#"[
+                {"event": "pre_tool_use", "matcher": "shell", "command": "echo project >> project.log"},
+                {"event": "pre_tool_use", "matcher": "shell", "command": "echo shared >> shared.log"}
+            ]"#,
+        );
+        let user = write_tier(
+            &tmp.path().join("user"),
+            r#"[
+                {"event": "pre_tool_use", "matcher": "shell", "command": "echo user >> user.log", "priority": 10},
+                {"event": "pre_tool_use", "matcher": "shell", "command": "echo shared >> shared.log "}
+            ]"#,
+        );
+
+        let config = load_hook_tiers(&[project, user]);
+        let commands: Vec<&str> = config.hooks.iter().map(|h| h.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            [
+                "echo user >> user.log",
+                "echo project >> project.log",
+                "echo shared >> shared.log",
+            ]
+        );
+
+        let runner = super::super::HookRunner::new(config, tmp.path().to_path_buf());
+        let action = runner
+            .run_pre_tool_use("shell", serde_json::json!({"command": "ls"}))
+            .await;
+        assert_eq!(action, super::super::HookAction::Allow);
+        for (log, expected) in [
+            ("project.log", "project\n"),
+            ("user.log", "user\n"),
+            ("shared.log", "shared\n"),
+        ] {
+            assert_eq!(
+                std::fs::read_to_string(tmp.path().join(log)).unwrap(),
+                expected,
+                "{log}"
+            );
+        }
+    }
+
     // Scenario: full JSON round-trip with both explicit and default-filled fields.
     #[test]
     fn test_deserialize_hook_config() {
@@ -242,5 +325,6 @@ mod tests {
         assert_eq!(config.hooks[1].event, HookEvent::PostToolUse);
         assert_eq!(config.hooks[1].timeout_ms, 10_000); // default
         assert!(config.hooks[1].enabled); // default
+        assert_eq!(config.hooks[1].priority, 0); // default
     }
 }
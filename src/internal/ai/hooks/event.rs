@@ -131,9 +131,9 @@ impl HookInput {
 
 /// Optional structured response a hook may print to stdout as JSON.
 ///
-/// Hooks usually communicate their decision via the exit code (0 = allow, 129 =
-/// block); the optional `message` field is rendered verbatim when present so a
-/// blocking hook can explain itself.
+/// Hooks usually communicate their decision via the exit code (0 = allow, 2 or
+/// 129 = block); the optional `message` field is rendered verbatim when present
+/// so a blocking hook can explain itself.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HookOutput {
     /// Optional message from the hook.
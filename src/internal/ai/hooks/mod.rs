@@ -2,7 +2,7 @@
 //!
 //! Hooks are external shell commands triggered by lifecycle events (tool use,
 //! session start/end). They receive a JSON payload on stdin and can optionally
-//! block operations (`PreToolUse` only, signalled via exit code 129).
+//! block operations (`PreToolUse` only, signalled via exit code 2 or 129).
 //!
 //! Hook configuration is loaded from:
 //! 1. `{working_dir}/.libra/hooks.json` (project-local)
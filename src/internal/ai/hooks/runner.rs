@@ -5,9 +5,12 @@
 //! [`HookInput`] JSON payload to stdin, and translates the resulting exit code into
 //! a [`HookAction`]:
 //! - `0` - allow.
-//! - `129` - block (PreToolUse only); the rejection reason is read from stdout,
-//!   falling back to `"Blocked by hook"`.
-//! - Any other exit code - error, logged at `warn` level but never blocks.
+//! - `2` or `129` - block (PreToolUse only); the rejection reason is read from
+//!   stdout (a JSON `message` or plain text), then stderr, falling back to
+//!   `"Blocked by hook"`. Exit `2` matches the convention of other agent hook
+//!   systems, so their blocking scripts work unchanged.
+//! - Any other exit code (including `1`) - error, logged at `warn` level but
+//!   never blocks.
 //!
 //! Hooks are isolated per invocation: the runner uses `kill_on_drop(true)` so a
 //! hook that overruns its `timeout_ms` is terminated together with the pending
@@ -227,20 +230,8 @@ impl HookRunner {
 
                 match exit_code {
                     0 => HookResult::Allow,
-                    129 => {
-                        // Exit code 129 = block (PreToolUse only)
-                        let reason = if let Ok(output) = serde_json::from_str::<HookOutput>(&stdout)
-                        {
-                            output
-                                .message
-                                .unwrap_or_else(|| "Blocked by hook".to_string())
-                        } else if !stdout.trim().is_empty() {
-                            stdout.trim().to_string()
-                        } else {
-                            "Blocked by hook".to_string()
-                        };
-                        HookResult::Block(reason)
-                    }
+                    // Exit code 2 or 129 = block (PreToolUse only)
+                    2 | 129 => HookResult::Block(block_reason(&stdout, &stderr)),
                     code => HookResult::Error(format!(
                         "Hook exited with code {code}: {}",
                         stderr.trim()
@@ -253,6 +244,23 @@ impl HookRunner {
     }
 }
 
+/// Pick the rejection reason a blocking hook reported: the `message` of a JSON
+/// [`HookOutput`] on stdout, otherwise plain stdout text, otherwise stderr.
+fn block_reason(stdout: &str, stderr: &str) -> String {
+    let stdout_text = match serde_json::from_str::<HookOutput>(stdout) {
+        Ok(HookOutput {
+            message: Some(message),
+        }) => return message,
+        Ok(_) => "",
+        Err(_) => stdout.trim(),
+    };
+    [stdout_text, stderr.trim()]
+        .into_iter()
+        .find(|text| !text.is_empty())
+        .unwrap_or("Blocked by hook")
+        .to_string()
+}
+
 /// Internal result of executing a hook.
 ///
 /// Distinct from [`HookAction`] in that it carries a third `Error` variant —
@@ -282,6 +290,7 @@ mod tests {
             description: "test hook".to_string(),
             timeout_ms: 5000,
             enabled: true,
+            priority: 0,
         }
     }
 
@@ -318,6 +327,72 @@ mod tests {
         }
     }
 
+    /// Write a guard script that blocks `shell` calls touching protected paths
+    /// with exit 2 and a stderr reason, and allows everything else.
+    fn write_guard_script(dir: &Path) -> String {
+        let script = dir.join("guard.sh");
+        std::fs::write(
+            &script,
+            r#"input=$(cat)
+case "$input" in
+  *'rm -rf /'*|*'/etc/'*)
+    echo "protected path: refusing to run" >&2
+    exit 2
+    ;;
+esac
+exit 0
+"#,
+        )
+        .unwrap();
+        format!("sh '{}'", script.display())
+    }
+
+    // Scenario: a guard script exiting 2 blocks dangerous commands with its
+    // stderr as the reason, and lets benign commands through.
+    #[tokio::test]
+    async fn test_pre_tool_use_exit_2_blocks_dangerous_paths() {
+        let script_dir = tempfile::TempDir::new().unwrap();
+        let command = write_guard_script(script_dir.path());
+        let (runner, _tmp) = make_runner(vec![make_hook(HookEvent::PreToolUse, "shell", &command)]);
+
+        let action = runner
+            .run_pre_tool_use("shell", serde_json::json!({"command": "cat /etc/passwd"}))
+            .await;
+        assert_eq!(
+            action,
+            HookAction::Block("protected path: refusing to run".to_string())
+        );
+
+        let action = runner
+            .run_pre_tool_use("shell", serde_json::json!({"command": "cargo test"}))
+            .await;
+        assert_eq!(action, HookAction::Allow);
+    }
+
+    // Scenario: exit 1 is a hook failure, logged as a warning; the tool runs.
+    #[tokio::test]
+    async fn test_pre_tool_use_exit_1_does_not_block() {
+        let (runner, _tmp) = make_runner(vec![make_hook(
+            HookEvent::PreToolUse,
+            "shell",
+            "echo 'lint failed' >&2; exit 1",
+        )]);
+
+        let action = runner
+            .run_pre_tool_use("shell", serde_json::json!({"command": "rm -rf /"}))
+            .await;
+        assert_eq!(action, HookAction::Allow);
+    }
+
+    #[test]
+    fn test_block_reason_precedence() {
+        assert_eq!(block_reason(r#"{"message":"json"}"#, "err"), "json");
+        assert_eq!(block_reason("plain\n", "err"), "plain");
+        assert_eq!(block_reason("  ", "err\n"), "err");
+        assert_eq!(block_reason("{}", ""), "Blocked by hook");
+        assert_eq!(block_reason("", ""), "Blocked by hook");
+    }
+
     // Scenario: when no hook matches the tool name, the runner short-circuits.
     #[tokio::test]
     async fn test_pre_tool_use_no_matching_hooks() {
@@ -341,6 +416,7 @@ mod tests {
             description: "slow hook".to_string(),
             timeout_ms: 100, // 100ms timeout
             enabled: true,
+            priority: 0,
         }]);
 
         let action = runner
@@ -360,6 +436,7 @@ mod tests {
             description: "disabled hook".to_string(),
             timeout_ms: 5000,
             enabled: false,
+            priority: 0,
         }]);
 
         let action = runner
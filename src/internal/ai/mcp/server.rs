@@ -10,7 +10,8 @@
 //! - `libra://objects/{object_type}`: list objects by type (one line: `{object_id} {object_hash}`).
 //!   All AI object types (intent, task, run, plan, etc.) are stored on a single branch (`refs/libra/intent`).
 //! - `libra://history/latest`: returns the current AI orphan-branch HEAD commit hash.
-//! - `libra://context/active`: returns the latest active Run/Task/ContextSnapshot as JSON.
+//! - `libra://context/active`: returns the latest active Run/Task/ContextSnapshot as JSON,
+//!   plus the `export-snapshot` document of the repository (`repository`).
 //!
 //! If `HistoryManager` or `Storage` is missing, related calls return `ErrorData`.
 use std::{
@@ -24,15 +25,20 @@ use rmcp::{
 };
 
 use crate::{
+    command::export_snapshot,
     internal::ai::{
         history::HistoryManager,
         mcp::authz::{AuthzDecision, McpAuthorizer, McpOperation},
         runtime::hardening::PrincipalContext,
         web::code_ui::CodeUiSession,
     },
-    utils::{storage::Storage, storage_ext::StorageExt},
+    utils::{storage::Storage, storage_ext::StorageExt, util},
 };
 
+/// Recent commits included in the `repository` section of
+/// `libra://context/active`, matching `export-snapshot`'s default.
+const ACTIVE_CONTEXT_COMMITS: usize = 10;
+
 #[derive(Clone)]
 pub struct LibraMcpServer {
     pub intent_history_manager: Option<Arc<HistoryManager>>,
@@ -276,7 +282,9 @@ impl LibraMcpServer {
     ///
     /// Returns a JSON object with `task`, `run`, and optionally `context_snapshot` fields.
     /// If no active run is found, falls back to the latest non-terminal Task.
-    /// If nothing is active, returns `{"active": false}`.
+    /// If nothing is active, returns `{"active": false}`. Inside a repository the
+    /// object also carries `repository`, the `libra export-snapshot` document
+    /// (HEAD, status, recent commits, staged diff), whether or not anything is active.
     async fn read_active_context(&self) -> Result<Vec<ResourceContents>, ErrorData> {
         use git_internal::internal::object::{
             context::ContextSnapshot, run::Run, run_event::RunEventKind, task::Task,
@@ -431,12 +439,30 @@ impl LibraMcpServer {
             }
         }
 
+        if let Some(repository) = repository_snapshot().await {
+            result.insert("repository".to_string(), repository);
+        }
+
         let json = serde_json::to_string(&result)
             .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
         Ok(vec![ResourceContents::text(json, uri)])
     }
 }
 
+/// The `export-snapshot` document for the repository the server runs in.
+/// Best effort: outside a repository, or when a section cannot be read, it is
+/// left out so the AI-object context is still served.
+async fn repository_snapshot() -> Option<serde_json::Value> {
+    util::require_repo().ok()?;
+    match export_snapshot::collect_snapshot(ACTIVE_CONTEXT_COMMITS, true).await {
+        Ok(snapshot) => serde_json::to_value(snapshot).ok(),
+        Err(err) => {
+            tracing::warn!(error = %err, "failed to collect the repository snapshot for libra://context/active");
+            None
+        }
+    }
+}
+
 #[tool_handler(router = self.tool_router)]
 impl ServerHandler for LibraMcpServer {
     fn get_info(&self) -> ServerInfo {
@@ -124,7 +124,10 @@ impl<M: CompletionModel> AgentAction<M> {
 }
 
 #[async_trait]
-impl<M: CompletionModel> Action for AgentAction<M> {
+impl<M: CompletionModel> Action for AgentAction<M>
+where
+    M::Response: CompletionUsage,
+{
     /// Executes the agent within the DAG node lifecycle.
     ///
     /// This method performs the following steps:
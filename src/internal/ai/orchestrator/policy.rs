@@ -405,7 +405,8 @@ fn normalize_toml_key_name(key: &str) -> String {
 
 fn acl_tool_alias(tool_name: &str) -> &str {
     match tool_name {
-        "read_file" | "list_dir" | "grep_files" | "search_files" | "apply_patch" => "workspace.fs",
+        "read_file" | "list_dir" | "grep_files" | "search_files" | "watch_file"
+        | "tree_sitter_query" | "apply_patch" => "workspace.fs",
         "web_search" => "web.search",
         "request_user_input" => "interaction",
         "submit_intent_draft" | "submit_plan_draft" => "planning",
@@ -557,6 +558,15 @@ fn derive_tool_footprint(
                 Vec::new(),
             ))
         }
+        "watch_file" => {
+            let path = required_string(arguments, "path")?;
+            Ok((
+                "workspace.fs".into(),
+                "read".into(),
+                vec![normalize_path(path, working_dir)],
+                Vec::new(),
+            ))
+        }
         "list_dir" => {
             let path = required_string(arguments, "dir_path")?;
             Ok((
@@ -566,7 +576,7 @@ fn derive_tool_footprint(
                 Vec::new(),
             ))
         }
-        "grep_files" | "search_files" => {
+        "grep_files" | "search_files" | "tree_sitter_query" => {
             let path = arguments
                 .get("path")
                 .and_then(Value::as_str)
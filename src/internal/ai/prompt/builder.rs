@@ -1,6 +1,12 @@
 //! System prompt builder that composes rules into a final prompt string.
 
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use tokio::sync::{mpsc, watch};
 
 use super::{
     context::ContextMode,
@@ -18,9 +24,11 @@ use crate::internal::ai::{
 /// Rules are loaded from a three-tier hierarchy (project-local > user-global > embedded)
 /// and composed in a fixed order. The `{working_dir}` placeholder in rule content is
 /// substituted with the actual working directory path.
+#[derive(Clone)]
 pub struct SystemPromptBuilder {
     working_dir: PathBuf,
     rules: Vec<RuleFile>,
+    overrides: Vec<(RuleCategory, String)>,
     context: Option<ContextMode>,
     intent: Option<TaskIntent>,
     dynamic_context: bool,
@@ -36,6 +44,7 @@ impl SystemPromptBuilder {
         Self {
             working_dir: working_dir.to_path_buf(),
             rules,
+            overrides: Vec::new(),
             context: None,
             intent: None,
             dynamic_context: false,
@@ -46,10 +55,11 @@ impl SystemPromptBuilder {
     }
 
     /// Replace the content of a specific rule category.
+    ///
+    /// Overrides survive rule reloads in [`Self::build_with_watch`].
     pub fn override_rule(mut self, category: RuleCategory, content: impl Into<String>) -> Self {
-        if let Some(rule) = self.rules.iter_mut().find(|r| r.category == category) {
-            rule.content = content.into();
-        }
+        self.overrides.push((category, content.into()));
+        self.apply_overrides();
         self
     }
 
@@ -92,6 +102,52 @@ impl SystemPromptBuilder {
         self
     }
 
+    /// Build the prompt and keep it up to date with the rule files on disk.
+    ///
+    /// The returned receiver starts with the prompt as built now. A background
+    /// task watches the project-local and user-global `rules/` and `contexts/`
+    /// directories that exist at call time, reloads the rules whenever a
+    /// Markdown file in them changes, and publishes the rebuilt prompt if it
+    /// differs. The task stops once every receiver is dropped. If the watcher
+    /// cannot be set up, the receiver simply never changes.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn build_with_watch(self) -> watch::Receiver<String> {
+        let (tx, rx) = watch::channel(self.clone().build());
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let mut watcher =
+            match notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let _ = event_tx.send(event);
+            }) {
+                Ok(watcher) => watcher,
+                Err(error) => {
+                    tracing::warn!(%error, "failed to start rule file watcher");
+                    return rx;
+                }
+            };
+        for dir in watched_rule_dirs(&self.working_dir) {
+            if let Err(error) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                tracing::warn!(%error, path = %dir.display(), "failed to watch rule directory");
+            }
+        }
+        tokio::spawn(watch_rule_files(self, watcher, event_rx, tx));
+        rx
+    }
+
+    /// Re-read every rule from disk, keeping explicit overrides.
+    fn reload_rules(&mut self) {
+        self.rules = load_all_rules(&self.working_dir);
+        self.apply_overrides();
+    }
+
+    fn apply_overrides(&mut self) {
+        for (category, content) in &self.overrides {
+            if let Some(rule) = self.rules.iter_mut().find(|r| r.category == *category) {
+                rule.content = content.clone();
+            }
+        }
+    }
+
     /// Build the final system prompt string.
     pub fn build(self) -> String {
         let working_dir_str = self.working_dir.display().to_string();
@@ -137,6 +193,70 @@ impl SystemPromptBuilder {
     }
 }
 
+/// How long to wait after a change for further events from the same save.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Directories holding rule and context overrides that currently exist.
+fn watched_rule_dirs(working_dir: &Path) -> Vec<PathBuf> {
+    let mut roots = vec![working_dir.join(".libra")];
+    if let Some(config_dir) = dirs::config_dir() {
+        roots.push(config_dir.join("libra"));
+    }
+    roots
+        .iter()
+        .flat_map(|root| [root.join("rules"), root.join("contexts")])
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Whether a watcher event may have changed a rule file's content.
+fn touches_rule_file(event: &notify::Event) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event
+            .paths
+            .iter()
+            .any(|path| path.extension().is_some_and(|ext| ext == "md"))
+}
+
+async fn watch_rule_files(
+    mut builder: SystemPromptBuilder,
+    // Held so the watcher keeps delivering events for the task's lifetime.
+    _watcher: notify::RecommendedWatcher,
+    mut events: mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+    tx: watch::Sender<String>,
+) {
+    loop {
+        let event = tokio::select! {
+            event = events.recv() => event,
+            _ = tx.closed() => return,
+        };
+        match event {
+            Some(Ok(event)) if touches_rule_file(&event) => {}
+            Some(Ok(_)) => continue,
+            Some(Err(error)) => {
+                tracing::warn!(%error, "rule file watcher error");
+                continue;
+            }
+            None => return,
+        }
+
+        // Editors often save in several steps; coalesce them into one reload.
+        tokio::time::sleep(RELOAD_DEBOUNCE).await;
+        while events.try_recv().is_ok() {}
+
+        builder.reload_rules();
+        let prompt = builder.clone().build();
+        tx.send_if_modified(|current| {
+            if *current == prompt {
+                return false;
+            }
+            *current = prompt;
+            true
+        });
+        tracing::debug!("reloaded system prompt rule files");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
@@ -329,4 +449,42 @@ mod tests {
         assert!(!prompt.contains("Code Review Mode"));
         assert!(!prompt.contains("Research Mode"));
     }
+
+    #[test]
+    fn test_reload_rules_keeps_overrides() {
+        let tmp = TempDir::new().unwrap();
+        let rules_dir = tmp.path().join(".libra").join("rules");
+        std::fs::create_dir_all(&rules_dir).unwrap();
+        std::fs::write(rules_dir.join("base.md"), "Base v1").unwrap();
+
+        let mut builder = SystemPromptBuilder::new(tmp.path())
+            .override_rule(RuleCategory::ToolUse, "Pinned tool rule");
+        std::fs::write(rules_dir.join("base.md"), "Base v2").unwrap();
+        builder.reload_rules();
+        let prompt = builder.build();
+
+        assert!(prompt.contains("Base v2"));
+        assert!(!prompt.contains("Base v1"));
+        assert!(prompt.contains("Pinned tool rule"));
+    }
+
+    #[tokio::test]
+    async fn test_build_with_watch_publishes_rule_edits() {
+        let tmp = TempDir::new().unwrap();
+        let rules_dir = tmp.path().join(".libra").join("rules");
+        std::fs::create_dir_all(&rules_dir).unwrap();
+        std::fs::write(rules_dir.join("base.md"), "Base before edit").unwrap();
+
+        let mut rx = SystemPromptBuilder::new(tmp.path()).build_with_watch();
+        assert!(rx.borrow().contains("Base before edit"));
+
+        std::fs::write(rules_dir.join("base.md"), "Base after edit").unwrap();
+        tokio::time::timeout(Duration::from_secs(10), rx.changed())
+            .await
+            .expect("rule edit should be published")
+            .unwrap();
+        let prompt = rx.borrow_and_update().clone();
+        assert!(prompt.contains("Base after edit"));
+        assert!(!prompt.contains("Base before edit"));
+    }
 }
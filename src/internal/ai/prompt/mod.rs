@@ -7,6 +7,9 @@
 //! 2. **User-global**: `~/.config/libra/rules/{category}.md`
 //! 3. **Embedded**: Default rules compiled into the binary
 //!
+//! [`SystemPromptBuilder::build_with_watch`] keeps the prompt in sync with the
+//! override files, which `libra code` uses to reload rules mid-session.
+//!
 //! # Example
 //! ```no_run
 //! use libra::internal::ai::prompt::{SystemPromptBuilder, ContextMode};
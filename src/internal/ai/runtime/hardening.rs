@@ -294,6 +294,8 @@ impl ToolBoundaryPolicy {
                 "list_dir",
                 "grep_files",
                 "search_files",
+                "watch_file",
+                "tree_sitter_query",
                 "web_search",
                 "request_user_input",
                 "mcp_read",
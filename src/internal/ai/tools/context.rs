@@ -358,6 +358,56 @@ fn default_web_search_limit() -> usize {
     5
 }
 
+/// File system change the watch_file tool waits for.
+#[derive(Clone, Copy, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchFileEvent {
+    /// The path comes into existence.
+    Create,
+    /// The path's size or modification time changes.
+    Modify,
+    /// The path stops existing.
+    Delete,
+}
+
+/// Arguments for the watch_file tool.
+#[derive(Clone, Deserialize, Debug)]
+pub struct WatchFileArgs {
+    /// Path to watch, absolute or relative to the working directory.
+    #[serde(alias = "file_path")]
+    pub path: String,
+    /// Event to wait for.
+    pub event: WatchFileEvent,
+    /// Seconds to wait before giving up (default: 30, capped by the handler).
+    #[serde(default = "default_watch_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_watch_timeout_secs() -> u64 {
+    30
+}
+
+/// Arguments for the tree_sitter_query tool.
+#[derive(Clone, Deserialize, Debug)]
+pub struct TreeSitterQueryArgs {
+    /// Grammar to parse with (rust, python, javascript, typescript, tsx, go, c).
+    #[serde(alias = "lang")]
+    pub language: String,
+    /// Tree-sitter query S-expression; every capture is reported as a match.
+    #[serde(alias = "pattern")]
+    pub query: String,
+    /// Directory or file path to search. Defaults to the working directory.
+    #[serde(default, alias = "dir_path", alias = "file_path")]
+    pub path: Option<String>,
+    /// Maximum number of captures to return (default: 50, max enforced by handler).
+    #[serde(default = "default_tree_sitter_max_results", alias = "limit")]
+    pub max_results: usize,
+}
+
+fn default_tree_sitter_max_results() -> usize {
+    50
+}
+
 // ── update_plan types ──────────────────────────────────────────────────
 
 /// Status of a single plan step.
@@ -366,7 +416,10 @@ fn default_web_search_limit() -> usize {
 pub enum StepStatus {
     Pending,
     InProgress,
+    #[serde(alias = "done")]
     Completed,
+    /// Dropped without being done (no longer needed or superseded).
+    Skipped,
 }
 
 /// A single step in a plan.
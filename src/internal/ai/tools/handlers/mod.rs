@@ -19,7 +19,9 @@ pub mod submit_goal_complete;
 pub mod submit_intent_draft;
 pub mod submit_plan_draft;
 pub mod submit_task_complete;
+pub mod tree_sitter_query;
 pub mod update_goal_progress;
+pub mod watch_file;
 pub mod web_search;
 
 pub use apply_patch::ApplyPatchHandler;
@@ -39,7 +41,9 @@ pub use submit_goal_complete::SubmitGoalCompleteHandler;
 pub use submit_intent_draft::SubmitIntentDraftHandler;
 pub use submit_plan_draft::SubmitPlanDraftHandler;
 pub use submit_task_complete::SubmitTaskCompleteHandler;
+pub use tree_sitter_query::TreeSitterQueryHandler;
 pub use update_goal_progress::UpdateGoalProgressHandler;
+pub use watch_file::WatchFileHandler;
 pub use web_search::WebSearchHandler;
 
 use crate::internal::ai::tools::{ToolResult, error::ToolError};
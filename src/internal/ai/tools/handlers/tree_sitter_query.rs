@@ -0,0 +1,475 @@
+//! Handler for the tree_sitter_query tool.
+//!
+//! Runs a tree-sitter query over every source file of one language under a
+//! path and reports each capture with its file, position, and node text.
+//! Files are visited in name order so results are stable between calls.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use tokio::time::timeout;
+use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator};
+use walkdir::WalkDir;
+
+use super::parse_arguments;
+use crate::internal::ai::tools::{
+    context::{ToolInvocation, ToolKind, ToolOutput, ToolPayload, TreeSitterQueryArgs},
+    error::ToolError,
+    registry::ToolHandler,
+    spec::{FunctionParameters, ToolSpec},
+    utils::{
+        generated_build_artifact_hidden_message, is_ai_file_tool_hidden_path,
+        is_generated_build_artifact_path, resolve_path,
+    },
+};
+
+/// Handler for structured code search with tree-sitter queries.
+///
+/// AI user story: let the agent find definitions, imports, and other syntactic
+/// constructs precisely instead of approximating them with regular expressions.
+/// The tool is read-only; results are capped and the walk is timed like
+/// `grep_files`.
+pub struct TreeSitterQueryHandler;
+
+const MAX_RESULTS: usize = 500;
+const MAX_TEXT_CHARS: usize = 400;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Grammars the tool can parse with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueryLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    C,
+}
+
+impl QueryLanguage {
+    const SUPPORTED: &'static str = "rust, python, javascript, typescript, tsx, go, c";
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(Self::Rust),
+            "python" | "py" => Some(Self::Python),
+            "javascript" | "js" | "jsx" => Some(Self::JavaScript),
+            "typescript" | "ts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" | "golang" => Some(Self::Go),
+            "c" => Some(Self::C),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Go => "go",
+            Self::C => "c",
+        }
+    }
+
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &["rs"],
+            Self::Python => &["py", "pyi"],
+            Self::JavaScript => &["js", "mjs", "cjs", "jsx"],
+            Self::TypeScript => &["ts", "mts", "cts"],
+            Self::Tsx => &["tsx"],
+            Self::Go => &["go"],
+            Self::C => &["c", "h"],
+        }
+    }
+
+    fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::C => tree_sitter_c::LANGUAGE.into(),
+        }
+    }
+
+    fn matches_path(self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.extensions().contains(&ext))
+    }
+}
+
+#[derive(Debug)]
+struct QueryResults {
+    matches: Vec<Value>,
+    truncated: bool,
+}
+
+#[async_trait]
+impl ToolHandler for TreeSitterQueryHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, ToolError> {
+        let ToolInvocation {
+            payload,
+            working_dir,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(ToolError::IncompatiblePayload(
+                    "tree_sitter_query handler only accepts Function payloads".to_string(),
+                ));
+            }
+        };
+
+        let args: TreeSitterQueryArgs = parse_arguments(&arguments)?;
+
+        let language = QueryLanguage::from_name(&args.language).ok_or_else(|| {
+            ToolError::InvalidArguments(format!(
+                "unsupported language '{}'; expected one of: {}",
+                args.language.trim(),
+                QueryLanguage::SUPPORTED
+            ))
+        })?;
+        let query_source = args.query.trim();
+        if query_source.is_empty() {
+            return Err(ToolError::InvalidArguments(
+                "query must not be empty".to_string(),
+            ));
+        }
+        if args.max_results == 0 {
+            return Err(ToolError::InvalidArguments(
+                "max_results must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.max_results.min(MAX_RESULTS);
+
+        let query = Query::new(&language.grammar(), query_source)
+            .map_err(|e| ToolError::InvalidArguments(format!("invalid tree-sitter query: {e}")))?;
+        if query.capture_names().is_empty() {
+            return Err(ToolError::InvalidArguments(
+                "query must capture at least one node, e.g. (function_item name: (identifier) @name)"
+                    .to_string(),
+            ));
+        }
+
+        let search_path = match &args.path {
+            Some(p) => resolve_path(Path::new(p), &working_dir)?,
+            None => working_dir.clone(),
+        };
+        if is_generated_build_artifact_path(&search_path, &working_dir) {
+            return Ok(ToolOutput::success(
+                generated_build_artifact_hidden_message(&search_path),
+            ));
+        }
+
+        let search = search_path.clone();
+        let root = working_dir.clone();
+        let results = timeout(
+            QUERY_TIMEOUT,
+            tokio::task::spawn_blocking(move || {
+                query_files_blocking(language, &query, &search, &root, limit)
+            }),
+        )
+        .await
+        .map_err(|_| {
+            ToolError::ExecutionFailed("tree-sitter query timed out after 30 seconds".to_string())
+        })?
+        .map_err(|e| ToolError::ExecutionFailed(format!("tree-sitter query task failed: {e}")))??;
+
+        let output = json!({
+            "language": language.name(),
+            "path": search_path.display().to_string(),
+            "matches": results.matches,
+            "truncated": results.truncated,
+        });
+        let content = serde_json::to_string_pretty(&output).map_err(|e| {
+            ToolError::ExecutionFailed(format!("failed to serialize query results: {e}"))
+        })?;
+        Ok(ToolOutput::success(content))
+    }
+
+    fn schema(&self) -> ToolSpec {
+        ToolSpec::new(
+            "tree_sitter_query",
+            "Run a tree-sitter query over source files of one language and return every capture with its file path, 1-indexed line and column, capture name, and node text. Use it to find definitions, imports, and other syntactic constructs precisely. Generated build output directories are skipped.",
+        )
+        .with_parameters(FunctionParameters::object(
+            [
+                ("language", "string", "Grammar to use: rust, python, javascript, typescript, tsx, go, or c"),
+                ("query", "string", "Tree-sitter query S-expression with at least one capture, e.g. (function_item name: (identifier) @name)"),
+                ("path", "string", "Directory or file path to search, absolute or relative to the working directory (defaults to the working directory)"),
+                ("max_results", "integer", "Maximum number of captures to return (default: 50, max: 500)"),
+            ],
+            [("language", true), ("query", true)],
+        ))
+    }
+}
+
+/// Parse every file of `language` under `search_path` and collect captures,
+/// stopping once more than `limit` have been seen.
+fn query_files_blocking(
+    language: QueryLanguage,
+    query: &Query,
+    search_path: &Path,
+    working_dir: &Path,
+    limit: usize,
+) -> Result<QueryResults, ToolError> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language.grammar())
+        .map_err(|e| ToolError::ExecutionFailed(format!("failed to load grammar: {e}")))?;
+    let capture_names = query.capture_names();
+    let mut matches = Vec::new();
+
+    for entry in WalkDir::new(search_path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.path() == search_path || !is_ai_file_tool_hidden_path(entry.path(), working_dir)
+        })
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_file() || !language.matches_path(entry.path()) {
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(entry.path()) else {
+            continue; // skip binary / unreadable files
+        };
+        let Some(tree) = parser.parse(&source, None) else {
+            continue;
+        };
+
+        let file_path = display_path(entry.path(), working_dir);
+        let mut cursor = QueryCursor::new();
+        let mut captures = cursor.captures(query, tree.root_node(), source.as_bytes());
+        while let Some((query_match, index)) = captures.next() {
+            if matches.len() == limit {
+                return Ok(QueryResults {
+                    matches,
+                    truncated: true,
+                });
+            }
+            let capture = query_match.captures[*index];
+            let position = capture.node.start_position();
+            let text = capture
+                .node
+                .utf8_text(source.as_bytes())
+                .unwrap_or_default();
+            matches.push(json!({
+                "file_path": file_path,
+                "line": position.row + 1,
+                "column": position.column + 1,
+                "capture": capture_names[capture.index as usize],
+                "text": truncate_text(text),
+            }));
+        }
+    }
+
+    Ok(QueryResults {
+        matches,
+        truncated: false,
+    })
+}
+
+fn display_path(path: &Path, working_dir: &Path) -> String {
+    path.strip_prefix(working_dir)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn truncate_text(text: &str) -> String {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn make_invocation(args: serde_json::Value, working_dir: PathBuf) -> ToolInvocation {
+        ToolInvocation::new(
+            "call-1",
+            "tree_sitter_query",
+            ToolPayload::Function {
+                arguments: args.to_string(),
+            },
+            working_dir,
+        )
+    }
+
+    async fn run_query(temp: &TempDir, args: serde_json::Value) -> Value {
+        let output = TreeSitterQueryHandler
+            .handle(make_invocation(args, temp.path().to_path_buf()))
+            .await
+            .unwrap();
+        serde_json::from_str(output.as_text().unwrap()).unwrap()
+    }
+
+    fn write(temp: &TempDir, path: &str, content: &str) {
+        let path = temp.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rust_function_names_are_captured_with_positions() {
+        let temp = TempDir::new().unwrap();
+        write(&temp, "src/lib.rs", "fn alpha() {}\n\npub fn beta() {}\n");
+        write(&temp, "notes.py", "def alpha():\n    pass\n");
+
+        let result = run_query(
+            &temp,
+            serde_json::json!({
+                "language": "rust",
+                "query": "(function_item name: (identifier) @name)",
+            }),
+        )
+        .await;
+
+        let matches = result["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2, "{result}");
+        assert_eq!(matches[0]["file_path"], "src/lib.rs");
+        assert_eq!(matches[0]["line"], 1);
+        assert_eq!(matches[0]["column"], 4);
+        assert_eq!(matches[0]["capture"], "name");
+        assert_eq!(matches[0]["text"], "alpha");
+        assert_eq!(matches[1]["line"], 3);
+        assert_eq!(matches[1]["text"], "beta");
+        assert_eq!(result["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn test_python_imports_in_a_single_file() {
+        let temp = TempDir::new().unwrap();
+        write(&temp, "app.py", "import os\nfrom sys import argv\n");
+
+        let result = run_query(
+            &temp,
+            serde_json::json!({
+                "language": "py",
+                "query": "(import_statement) @import",
+                "path": "app.py",
+            }),
+        )
+        .await;
+
+        let matches = result["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1, "{result}");
+        assert_eq!(matches[0]["text"], "import os");
+    }
+
+    #[tokio::test]
+    async fn test_max_results_truncates() {
+        let temp = TempDir::new().unwrap();
+        write(
+            &temp,
+            "main.go",
+            "package main\n\nfunc a() {}\nfunc b() {}\n",
+        );
+
+        let result = run_query(
+            &temp,
+            serde_json::json!({
+                "language": "go",
+                "query": "(function_declaration name: (identifier) @name)",
+                "max_results": 1,
+            }),
+        )
+        .await;
+
+        assert_eq!(result["matches"].as_array().unwrap().len(), 1);
+        assert_eq!(result["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_language_rejected() {
+        let temp = TempDir::new().unwrap();
+        let result = TreeSitterQueryHandler
+            .handle(make_invocation(
+                serde_json::json!({ "language": "cobol", "query": "(x) @x" }),
+                temp.path().to_path_buf(),
+            ))
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_query_rejected() {
+        let temp = TempDir::new().unwrap();
+        for query in ["(function_item", "(function_item)"] {
+            let result = TreeSitterQueryHandler
+                .handle(make_invocation(
+                    serde_json::json!({ "language": "rust", "query": query }),
+                    temp.path().to_path_buf(),
+                ))
+                .await;
+            assert!(
+                matches!(result, Err(ToolError::InvalidArguments(_))),
+                "{query}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_path_outside_working_dir_fails() {
+        let sandbox = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let result = TreeSitterQueryHandler
+            .handle(make_invocation(
+                serde_json::json!({
+                    "language": "c",
+                    "query": "(function_definition) @fn",
+                    "path": outside.path(),
+                }),
+                sandbox.path().to_path_buf(),
+            ))
+            .await;
+        assert!(matches!(result, Err(ToolError::PathOutsideWorkingDir(_))));
+    }
+
+    #[test]
+    fn test_every_language_compiles_a_trivial_query() {
+        for name in QueryLanguage::SUPPORTED.split(", ") {
+            let language = QueryLanguage::from_name(name).unwrap();
+            assert_eq!(language.name(), name);
+            Query::new(&language.grammar(), "(_) @node").unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kind_and_schema() {
+        assert_eq!(TreeSitterQueryHandler.kind(), ToolKind::Function);
+        let json = TreeSitterQueryHandler.schema().to_json();
+        assert_eq!(json["function"]["name"], "tree_sitter_query");
+        let required = json["function"]["parameters"]["required"]
+            .as_array()
+            .unwrap();
+        assert!(required.iter().any(|v| v == "language"));
+        assert!(required.iter().any(|v| v == "query"));
+        assert!(!required.iter().any(|v| v == "path"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Handler for the watch_file tool.
+//!
+//! Waits for a single path to be created, modified, or deleted, up to a
+//! bounded timeout. The handler polls file metadata rather than subscribing to
+//! OS notifications, so it behaves the same on every platform and inside the
+//! sandbox. A modification is any change in size or modification time.
+
+use std::{
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
+
+use async_trait::async_trait;
+use tokio::fs;
+
+use super::parse_arguments;
+use crate::internal::ai::tools::{
+    context::{ToolInvocation, ToolKind, ToolOutput, ToolPayload, WatchFileArgs, WatchFileEvent},
+    error::ToolError,
+    registry::ToolHandler,
+    spec::{FunctionParameters, ToolSpec},
+    utils::resolve_path,
+};
+
+/// Handler for waiting on a file system change.
+///
+/// AI user story: let the agent wait for a test report, build output, or lock
+/// file to appear or disappear without burning turns on repeated shell polls.
+/// The tool is read-only and always returns; a timeout is reported as
+/// `occurred: false` rather than an error so the agent can decide what to do.
+pub struct WatchFileHandler;
+
+/// Upper bound on a single wait, so one call cannot stall a turn indefinitely.
+const MAX_TIMEOUT_SECS: u64 = 300;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Observable state of the watched path: `None` when it does not exist.
+type Snapshot = Option<(u64, Option<SystemTime>)>;
+
+#[async_trait]
+impl ToolHandler for WatchFileHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, ToolError> {
+        let ToolInvocation {
+            payload,
+            working_dir,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(ToolError::IncompatiblePayload(
+                    "watch_file handler only accepts Function payloads".to_string(),
+                ));
+            }
+        };
+
+        let args: WatchFileArgs = parse_arguments(&arguments)?;
+        if args.timeout_secs > MAX_TIMEOUT_SECS {
+            return Err(ToolError::InvalidArguments(format!(
+                "timeout_secs must be at most {MAX_TIMEOUT_SECS}"
+            )));
+        }
+
+        let path = resolve_path(Path::new(&args.path), &working_dir)?;
+        let started = Instant::now();
+        let occurred =
+            wait_for_event(&path, args.event, Duration::from_secs(args.timeout_secs)).await;
+        let elapsed_secs = (started.elapsed().as_millis() as f64) / 1000.0;
+
+        Ok(ToolOutput::success(
+            serde_json::json!({
+                "occurred": occurred,
+                "elapsed_secs": elapsed_secs,
+            })
+            .to_string(),
+        ))
+    }
+
+    fn schema(&self) -> ToolSpec {
+        ToolSpec::new(
+            "watch_file",
+            "Wait until a file is created, modified, or deleted, or until the timeout expires. Returns JSON {\"occurred\": bool, \"elapsed_secs\": number}; occurred is false on timeout. A create/delete that has already happened returns immediately.",
+        )
+        .with_parameters(FunctionParameters::object(
+            [
+                ("path", "string", "Path to watch, absolute or relative to the working directory"),
+                ("event", "string", "Event to wait for: \"create\", \"modify\", or \"delete\""),
+                ("timeout_secs", "integer", "Seconds to wait before giving up (default: 30, max: 300)"),
+            ],
+            [("path", true), ("event", true)],
+        ))
+    }
+}
+
+/// Poll `path` until `event` is observed or `timeout` elapses. The state is
+/// checked at least once, so a zero timeout reports the current state.
+async fn wait_for_event(path: &Path, event: WatchFileEvent, timeout: Duration) -> bool {
+    let baseline = snapshot(path).await;
+    if has_occurred(event, &baseline, &baseline) {
+        return true;
+    }
+
+    tokio::time::timeout(timeout, async {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if has_occurred(event, &baseline, &snapshot(path).await) {
+                return;
+            }
+        }
+    })
+    .await
+    .is_ok()
+}
+
+async fn snapshot(path: &Path) -> Snapshot {
+    let metadata = fs::metadata(path).await.ok()?;
+    Some((metadata.len(), metadata.modified().ok()))
+}
+
+fn has_occurred(event: WatchFileEvent, baseline: &Snapshot, current: &Snapshot) -> bool {
+    match event {
+        WatchFileEvent::Create => current.is_some(),
+        WatchFileEvent::Delete => current.is_none(),
+        WatchFileEvent::Modify => current.is_some() && current != baseline,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs as std_fs, path::PathBuf};
+
+    use serde_json::Value;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn make_invocation(args: serde_json::Value, working_dir: PathBuf) -> ToolInvocation {
+        ToolInvocation::new(
+            "call-1",
+            "watch_file",
+            ToolPayload::Function {
+                arguments: args.to_string(),
+            },
+            working_dir,
+        )
+    }
+
+    async fn watch(temp: &TempDir, args: serde_json::Value) -> Value {
+        let output = WatchFileHandler
+            .handle(make_invocation(args, temp.path().to_path_buf()))
+            .await
+            .unwrap();
+        serde_json::from_str(output.as_text().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_returns_immediately_when_file_exists() {
+        let temp = TempDir::new().unwrap();
+        std_fs::write(temp.path().join("report.txt"), "done").unwrap();
+
+        let result = watch(
+            &temp,
+            serde_json::json!({ "path": "report.txt", "event": "create", "timeout_secs": 5 }),
+        )
+        .await;
+
+        assert_eq!(result["occurred"], true);
+        assert!(result["elapsed_secs"].as_f64().unwrap() < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_create_observes_file_written_later() {
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join("report.txt");
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            fs::write(target, "done").await.unwrap();
+        });
+
+        let result = watch(
+            &temp,
+            serde_json::json!({ "path": "report.txt", "event": "create", "timeout_secs": 5 }),
+        )
+        .await;
+        writer.await.unwrap();
+
+        assert_eq!(result["occurred"], true);
+    }
+
+    #[tokio::test]
+    async fn test_modify_observes_content_change() {
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join("log.txt");
+        std_fs::write(&target, "a").unwrap();
+        let writer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            fs::write(target, "a longer line").await.unwrap();
+        });
+
+        let result = watch(
+            &temp,
+            serde_json::json!({ "path": "log.txt", "event": "modify", "timeout_secs": 5 }),
+        )
+        .await;
+        writer.await.unwrap();
+
+        assert_eq!(result["occurred"], true);
+    }
+
+    #[tokio::test]
+    async fn test_delete_times_out_when_file_remains() {
+        let temp = TempDir::new().unwrap();
+        std_fs::write(temp.path().join("lock"), "").unwrap();
+
+        let result = watch(
+            &temp,
+            serde_json::json!({ "path": "lock", "event": "delete", "timeout_secs": 0 }),
+        )
+        .await;
+
+        assert_eq!(result["occurred"], false);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_above_limit_rejected() {
+        let temp = TempDir::new().unwrap();
+        let result = WatchFileHandler
+            .handle(make_invocation(
+                serde_json::json!({ "path": "x", "event": "create", "timeout_secs": 301 }),
+                temp.path().to_path_buf(),
+            ))
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidArguments(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_event_rejected() {
+        let temp = TempDir::new().unwrap();
+        let result = WatchFileHandler
+            .handle(make_invocation(
+                serde_json::json!({ "path": "x", "event": "rename" }),
+                temp.path().to_path_buf(),
+            ))
+            .await;
+        assert!(matches!(result, Err(ToolError::ParseError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_path_outside_working_dir_fails() {
+        let sandbox = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let result = WatchFileHandler
+            .handle(make_invocation(
+                serde_json::json!({ "path": outside.path(), "event": "delete" }),
+                sandbox.path().to_path_buf(),
+            ))
+            .await;
+        assert!(matches!(result, Err(ToolError::PathOutsideWorkingDir(_))));
+    }
+
+    #[tokio::test]
+    async fn test_kind_and_schema() {
+        assert_eq!(WatchFileHandler.kind(), ToolKind::Function);
+        let json = WatchFileHandler.schema().to_json();
+        assert_eq!(json["function"]["name"], "watch_file");
+        let required = json["function"]["parameters"]["required"]
+            .as_array()
+            .unwrap();
+        assert!(required.iter().any(|v| v == "path"));
+        assert!(required.iter().any(|v| v == "event"));
+        assert!(!required.iter().any(|v| v == "timeout_secs"));
+    }
+}
@@ -396,6 +396,8 @@ fn is_read_only_or_semantic_tool(tool_name: &str) -> bool {
             | "list_dir"
             | "grep_files"
             | "search_files"
+            | "watch_file"
+            | "tree_sitter_query"
             | "web_search"
             | "list_symbols"
             | "read_symbol"
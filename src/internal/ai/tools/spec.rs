@@ -220,7 +220,7 @@ impl ToolSpec {
                                         },
                                         "status": {
                                             "type": "string",
-                                            "enum": ["pending", "in_progress", "completed"],
+                                            "enum": ["pending", "in_progress", "completed", "skipped"],
                                             "description": "Current status of the step"
                                         }
                                     },
@@ -1153,6 +1153,7 @@ fn step_status_label(status: &StepStatus) -> &'static str {
         StepStatus::Pending => "pending",
         StepStatus::InProgress => "in_progress",
         StepStatus::Completed => "completed",
+        StepStatus::Skipped => "skipped",
     }
 }
 
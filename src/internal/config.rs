@@ -485,8 +485,19 @@ impl ConfigKv {
 
     // ── Type helpers ─────────────────────────────────────────────────────
 
-    /// Get a boolean config value. Normalises `true/yes/on/1` -> `true`,
-    /// `false/no/off/0` -> `false`.
+    /// Parse a Git boolean config spelling, case-insensitively: `true/yes/on/1`
+    /// -> `true`, `false/no/off/0` -> `false`, anything else -> `None`. Shared by
+    /// [`Self::get_bool_with_conn`] and callers that accept extra non-boolean
+    /// values (such as `pull.ff = only`) for the same key.
+    pub fn parse_bool(value: &str) -> Option<bool> {
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(true),
+            "false" | "no" | "off" | "0" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Get a boolean config value, parsed with [`Self::parse_bool`].
     ///
     /// Boundary conditions:
     /// - Returns `Ok(None)` when the key is absent.
@@ -498,18 +509,14 @@ impl ConfigKv {
         let entry = Self::get_with_conn(db, key).await?;
         match entry {
             None => Ok(None),
-            Some(e) => {
-                let v = e.value.to_ascii_lowercase();
-                match v.as_str() {
-                    "true" | "yes" | "on" | "1" => Ok(Some(true)),
-                    "false" | "no" | "off" | "0" => Ok(Some(false)),
-                    _ => Err(anyhow!(
-                        "invalid value '{}' for key '{}': expected bool (true/false)",
-                        if e.encrypted { "<REDACTED>" } else { &e.value },
-                        key
-                    )),
-                }
-            }
+            Some(e) => match Self::parse_bool(&e.value) {
+                Some(value) => Ok(Some(value)),
+                None => Err(anyhow!(
+                    "invalid value '{}' for key '{}': expected bool (true/false)",
+                    if e.encrypted { "<REDACTED>" } else { &e.value },
+                    key
+                )),
+            },
         }
     }
 
@@ -730,7 +737,9 @@ impl ConfigKv {
     ///
     /// Cascading deletes:
     /// 1. Every `remote.<name>.*` row.
-    /// 2. Every `vault.ssh.<name>.*` row (private keys, host fingerprints).
+    /// 2. `branch.<b>.remote` / `branch.<b>.merge` for every branch whose
+    ///    upstream is this remote, so no branch keeps a dangling upstream.
+    /// 3. Every `vault.ssh.<name>.*` row (private keys, host fingerprints).
     ///
     /// Boundary condition: returns `Err("fatal: No such remote ...")` when the
     /// `remote.<name>.*` namespace is empty. The SSH cleanup never errors on
@@ -754,6 +763,23 @@ impl ConfigKv {
                 .context("failed to delete remote entry")?;
         }
 
+        // Drop the upstream of every branch that tracks this remote
+        let branch_entries = Self::get_by_prefix_with_conn(db, "branch.").await?;
+        for be in branch_entries {
+            if be.value != name {
+                continue;
+            }
+            let Some(branch) = be
+                .key
+                .strip_prefix("branch.")
+                .and_then(|rest| rest.strip_suffix(".remote"))
+            else {
+                continue;
+            };
+            Self::unset_all_with_conn(db, &be.key).await?;
+            Self::unset_all_with_conn(db, &format!("branch.{branch}.merge")).await?;
+        }
+
         // Also clean up SSH keys for this remote
         let ssh_prefix = format!("vault.ssh.{name}.");
         let ssh_entries = config_kv::Entity::find()
@@ -780,7 +806,9 @@ impl ConfigKv {
     /// Rename a remote, updating all related config entries atomically.
     ///
     /// Performs three cascading rewrites:
-    /// 1. `remote.<old>.*` keys are renamed to `remote.<new>.*`.
+    /// 1. `remote.<old>.*` keys are renamed to `remote.<new>.*`; a
+    ///    `remote.<old>.fetch` refspec whose destination lies under
+    ///    `refs/remotes/<old>/` is rewritten to `refs/remotes/<new>/`.
     /// 2. Any `branch.*.remote = <old>` value is updated to `<new>`.
     /// 3. `vault.ssh.<old>.*` SSH key namespace is renamed to
     ///    `vault.ssh.<new>.*` so credentials follow the rename.
@@ -824,10 +852,17 @@ impl ConfigKv {
             .all(db)
             .await
             .context("failed to query remote entries for rename")?;
+        let old_tracking = format!(":refs/remotes/{old}/");
+        let new_tracking = format!(":refs/remotes/{new}/");
         for entry in entries {
             let new_key = entry.key.replacen(&old_prefix, &new_prefix, 1);
+            let new_value = (entry.key == format!("{old_prefix}fetch"))
+                .then(|| entry.value.replacen(&old_tracking, &new_tracking, 1));
             let mut active: config_kv::ActiveModel = entry.into();
             active.key = Set(new_key);
+            if let Some(value) = new_value {
+                active.value = Set(value);
+            }
             active
                 .update(db)
                 .await
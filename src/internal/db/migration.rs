@@ -677,6 +677,15 @@ pub fn builtin_migrations() -> Vec<Migration> {
             include_str!("../../../sql/migrations/2026070202_working_dirty.sql"),
             include_str!("../../../sql/migrations/2026070202_working_dirty_down.sql"),
         ),
+        // In-progress `merge` state (`MERGE_HEAD`/`ORIG_HEAD`, conflicted paths
+        // and the `-m`/`--log` message `--continue` commits with). Owner API:
+        // `command::merge::MergeState`.
+        sql_migration(
+            2026071001,
+            "merge_state",
+            include_str!("../../../sql/migrations/2026071001_merge_state.sql"),
+            include_str!("../../../sql/migrations/2026071001_merge_state_down.sql"),
+        ),
     ]
 }
 
@@ -805,9 +814,9 @@ mod tests {
         // `builtin_migrations()` so silent registry regressions surface
         // here in addition to `tests/db_migration_test.rs`.
         let runner = builtin_runner().expect("CEX-12.5 builtin registry must build clean");
-        assert_eq!(runner.len(), 15);
+        assert_eq!(runner.len(), 16);
         assert!(!runner.is_empty());
-        assert_eq!(runner.max_registered_version(), Some(2026070202));
+        assert_eq!(runner.max_registered_version(), Some(2026071001));
     }
 
     #[test]
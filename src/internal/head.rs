@@ -1,6 +1,6 @@
 //! HEAD management backed by the database, supporting local and remote heads, detached states, and transaction-safe query/update helpers.
 
-use std::{str::FromStr, time::Duration};
+use std::{fs, io, str::FromStr, time::Duration};
 
 use git_internal::hash::ObjectHash;
 use sea_orm::{
@@ -9,10 +9,13 @@ use sea_orm::{
 };
 use tokio::time::sleep;
 
-use crate::internal::{
-    branch::{Branch, BranchStoreError},
-    db::get_db_conn_instance,
-    model::reference,
+use crate::{
+    internal::{
+        branch::{Branch, BranchStoreError},
+        db::get_db_conn_instance,
+        model::reference,
+    },
+    utils::{atomic_write::write_atomic, util},
 };
 
 #[derive(Debug, Clone)]
@@ -369,6 +372,42 @@ impl Head {
     }
 }
 
+/// `ORIG_HEAD`: the commit HEAD pointed at before the last reset, merge,
+/// rebase, or pull moved it, so `reset --hard ORIG_HEAD` can undo that move.
+///
+/// Stored as `.libra/ORIG_HEAD` (one hex object id), like `.libra/FETCH_HEAD`,
+/// rather than as a `reference` row: the table's `kind` CHECK only admits
+/// `Branch`/`Tag`/`Head`, and every `Head` row is read as the local or a
+/// remote HEAD.
+impl Head {
+    const ORIG_HEAD_FILE: &'static str = "ORIG_HEAD";
+
+    /// Record `commit` as `ORIG_HEAD`, replacing any previous value.
+    pub fn record_orig_head(commit: &ObjectHash) -> io::Result<()> {
+        let path = util::try_get_storage_path(None)?.join(Self::ORIG_HEAD_FILE);
+        write_atomic(&path, format!("{commit}\n").as_bytes(), true)
+    }
+
+    /// The recorded `ORIG_HEAD`, or `Ok(None)` when nothing has moved HEAD yet.
+    pub fn orig_head() -> Result<Option<ObjectHash>, BranchStoreError> {
+        let corrupt = |detail: String| BranchStoreError::Corrupt {
+            name: Self::ORIG_HEAD_FILE.to_string(),
+            detail,
+        };
+        let path = util::try_get_storage_path(None)
+            .map_err(|error| BranchStoreError::Query(error.to_string()))?
+            .join(Self::ORIG_HEAD_FILE);
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(corrupt(error.to_string())),
+        };
+        ObjectHash::from_str(data.trim())
+            .map(Some)
+            .map_err(|error| corrupt(format!("invalid object id: {error}")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
@@ -1,18 +1,16 @@
 //! Merge-base computation over the commit graph: the lowest common ancestors
-//! (LCAs) of two commits.
+//! (LCAs) of two commits, and the `--octopus` bases of more than two.
 //!
 //! This is the single, correct implementation behind `libra merge-base` and the
-//! `diff A...B` three-dot range. Unlike the older first-found walks in
-//! `log.rs` / `rebase.rs`, it returns true LCAs: a common ancestor is a merge
+//! `diff A...B` three-dot range. Unlike the older first-found walks it
+//! replaced, it returns true LCAs: a common ancestor is a merge
 //! base only when it is not a *strict* ancestor of another common ancestor, so
 //! criss-cross histories yield every maximal common ancestor (with `--all`) and
 //! a deterministic single base otherwise.
 //!
-//! Migrating `log.rs` / `rebase.rs` onto this module (with golden-output
-//! regression and a legacy toggle) is tracked as a follow-up; this module is
-//! self-contained and does not change those call sites yet.
+//! `rebase` now computes its base through this module as well.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
 use git_internal::{hash::ObjectHash, internal::object::commit::Commit};
 
@@ -26,28 +24,48 @@ pub enum MergeBaseError {
     Load(String),
 }
 
+/// The parts of a commit the graph walks need.
+struct CommitNode {
+    parents: Vec<ObjectHash>,
+    timestamp: usize,
+}
+
 /// Lazily-loaded parent adjacency, so each commit is read at most once.
 struct CommitGraph {
-    parents: HashMap<ObjectHash, Vec<ObjectHash>>,
+    nodes: HashMap<ObjectHash, CommitNode>,
 }
 
 impl CommitGraph {
     fn new() -> Self {
         Self {
-            parents: HashMap::new(),
+            nodes: HashMap::new(),
         }
     }
 
-    /// Parent ids of `id`, loading and caching the commit on first access.
-    fn parents_of(&mut self, id: &ObjectHash) -> Result<Vec<ObjectHash>, MergeBaseError> {
-        if let Some(parents) = self.parents.get(id) {
-            return Ok(parents.clone());
+    /// The node for `id`, loading and caching the commit on first access.
+    fn node(&mut self, id: &ObjectHash) -> Result<&CommitNode, MergeBaseError> {
+        if !self.nodes.contains_key(id) {
+            let commit: Commit =
+                Commit::try_load(id).ok_or_else(|| MergeBaseError::Load(id.to_string()))?;
+            self.nodes.insert(
+                *id,
+                CommitNode {
+                    parents: commit.parent_commit_ids,
+                    timestamp: commit.committer.timestamp,
+                },
+            );
         }
-        let commit: Commit =
-            Commit::try_load(id).ok_or_else(|| MergeBaseError::Load(id.to_string()))?;
-        let parents = commit.parent_commit_ids.clone();
-        self.parents.insert(*id, parents.clone());
-        Ok(parents)
+        Ok(&self.nodes[id])
+    }
+
+    /// Parent ids of `id`.
+    fn parents_of(&mut self, id: &ObjectHash) -> Result<Vec<ObjectHash>, MergeBaseError> {
+        Ok(self.node(id)?.parents.clone())
+    }
+
+    /// Committer timestamp of `id`.
+    fn timestamp_of(&mut self, id: &ObjectHash) -> Result<usize, MergeBaseError> {
+        Ok(self.node(id)?.timestamp)
     }
 
     /// All ancestors of `start`, inclusive of `start` itself.
@@ -70,7 +88,64 @@ impl CommitGraph {
 /// Every lowest common ancestor of `a` and `b`, sorted deterministically by hex
 /// id. Empty when the two commits share no history.
 pub fn merge_bases(a: &ObjectHash, b: &ObjectHash) -> Result<Vec<ObjectHash>, MergeBaseError> {
+    lowest_common_ancestors(&mut CommitGraph::new(), a, b)
+}
+
+/// Merge bases of more than two commits, as `git merge-base --octopus`: the
+/// running set starts as the first commit and is replaced by the LCAs of each
+/// member with the next commit. The union is then reduced so no result is an
+/// ancestor of another, and sorted by hex id. Empty when the commits share no
+/// history; a single commit is its own base.
+pub fn octopus_merge_bases(commits: &[ObjectHash]) -> Result<Vec<ObjectHash>, MergeBaseError> {
+    let Some((first, rest)) = commits.split_first() else {
+        return Ok(Vec::new());
+    };
     let mut graph = CommitGraph::new();
+    let mut result = vec![*first];
+    for next in rest {
+        let mut step: Vec<ObjectHash> = Vec::new();
+        for current in &result {
+            for base in lowest_common_ancestors(&mut graph, current, next)? {
+                if !step.contains(&base) {
+                    step.push(base);
+                }
+            }
+        }
+        result = reduce_to_heads(&mut graph, step)?;
+        if result.is_empty() {
+            break;
+        }
+    }
+    result.sort_by_key(|id| id.to_string());
+    Ok(result)
+}
+
+/// Drop every candidate that is a strict ancestor of another candidate.
+fn reduce_to_heads(
+    graph: &mut CommitGraph,
+    candidates: Vec<ObjectHash>,
+) -> Result<Vec<ObjectHash>, MergeBaseError> {
+    let mut heads = Vec::with_capacity(candidates.len());
+    for (i, candidate) in candidates.iter().enumerate() {
+        let mut dominated = false;
+        for (j, other) in candidates.iter().enumerate() {
+            if i != j && graph.ancestors(other)?.contains(candidate) {
+                dominated = true;
+                break;
+            }
+        }
+        if !dominated {
+            heads.push(*candidate);
+        }
+    }
+    Ok(heads)
+}
+
+fn lowest_common_ancestors(
+    graph: &mut CommitGraph,
+    a: &ObjectHash,
+    b: &ObjectHash,
+) -> Result<Vec<ObjectHash>, MergeBaseError> {
     let ancestors_a = graph.ancestors(a)?;
     let ancestors_b = graph.ancestors(b)?;
     let common: HashSet<ObjectHash> = ancestors_a.intersection(&ancestors_b).copied().collect();
@@ -117,3 +192,88 @@ pub fn is_ancestor(ancestor: &ObjectHash, descendant: &ObjectHash) -> Result<boo
     let mut graph = CommitGraph::new();
     Ok(graph.ancestors(descendant)?.contains(ancestor))
 }
+
+/// Paint bits for [`ahead_behind`]: which tip reaches a commit.
+const LOCAL: u8 = 1;
+const UPSTREAM: u8 = 2;
+const BOTH: u8 = LOCAL | UPSTREAM;
+
+/// Commit counts `(ahead, behind)` of `local` relative to `upstream`, with the
+/// same range semantics as `rev-list --count upstream..local` and
+/// `local..upstream`. Shared by `status` and `branch -vv`.
+///
+/// Like Git, this paints commits down from both tips, newest committer date
+/// first, and stops once every queued commit is reachable from both: the walk
+/// ends at the merge base instead of reading the whole history.
+pub fn ahead_behind(
+    local: &ObjectHash,
+    upstream: &ObjectHash,
+) -> Result<(usize, usize), MergeBaseError> {
+    if local == upstream {
+        return Ok((0, 0));
+    }
+    let mut graph = CommitGraph::new();
+    let mut paint: HashMap<ObjectHash, u8> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for (tip, side) in [(*local, LOCAL), (*upstream, UPSTREAM)] {
+        *paint.entry(tip).or_default() |= side;
+        queue.push((graph.timestamp_of(&tip)?, tip));
+    }
+
+    while queue.iter().any(|(_, id)| paint[id] != BOTH) {
+        let Some((_, id)) = queue.pop() else {
+            break;
+        };
+        let flags = paint[&id];
+        for parent in graph.parents_of(&id)? {
+            let entry = paint.entry(parent).or_default();
+            if *entry | flags != *entry {
+                *entry |= flags;
+                queue.push((graph.timestamp_of(&parent)?, parent));
+            }
+        }
+    }
+
+    // Commits sharing a timestamp can be walked before the other side reaches
+    // them. Finish painting below the shared commits still queued, down to the
+    // oldest commit painted by one side only.
+    let mut floor = None;
+    for (id, flags) in &paint {
+        if *flags != BOTH {
+            let timestamp = graph.timestamp_of(id)?;
+            floor = Some(floor.map_or(timestamp, |floor: usize| floor.min(timestamp)));
+        }
+    }
+    if let Some(floor) = floor {
+        let mut stack: Vec<ObjectHash> = queue.into_iter().map(|(_, id)| id).collect();
+        while let Some(id) = stack.pop() {
+            for parent in graph.parents_of(&id)? {
+                if graph.timestamp_of(&parent)? < floor {
+                    continue;
+                }
+                let entry = paint.entry(parent).or_default();
+                if *entry != BOTH {
+                    *entry = BOTH;
+                    stack.push(parent);
+                }
+            }
+        }
+    }
+
+    let ahead = paint.values().filter(|flags| **flags == LOCAL).count();
+    let behind = paint.values().filter(|flags| **flags == UPSTREAM).count();
+    Ok((ahead, behind))
+}
+
+/// Commits reachable from `include` but not from `exclude` — the
+/// `rev-list exclude..include` range — in no particular order. Used by
+/// `merge --log` to list the commits a merge brings in.
+pub fn commits_between(
+    exclude: &ObjectHash,
+    include: &ObjectHash,
+) -> Result<Vec<ObjectHash>, MergeBaseError> {
+    let mut graph = CommitGraph::new();
+    let excluded = graph.ancestors(exclude)?;
+    let included = graph.ancestors(include)?;
+    Ok(included.difference(&excluded).copied().collect())
+}
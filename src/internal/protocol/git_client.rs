@@ -12,8 +12,8 @@ use tokio::{
 use url::Url;
 
 use super::{
-    DiscoveryResult, FetchStream, ProtocolClient, generate_upload_pack_content,
-    parse_discovered_references,
+    DiscoveryResult, FetchStream, ObjectFilter, ProtocolClient,
+    generate_filtered_upload_pack_content, parse_discovered_references,
 };
 use crate::git_protocol::{ServiceType, add_pkt_line_string};
 
@@ -34,6 +34,8 @@ pub struct GitClient {
     /// accepts the negotiation but never starts streaming is caught sooner than
     /// the (longer) idle timeout would.
     first_byte_timeout: Duration,
+    /// Partial-clone filter sent with every upload-pack request.
+    filter: Option<ObjectFilter>,
 }
 
 /// Default `git://` connect timeout when nothing overrides it.
@@ -58,6 +60,7 @@ impl ProtocolClient for GitClient {
             connect_timeout: DEFAULT_CONNECT_TIMEOUT,
             idle_timeout: DEFAULT_IDLE_TIMEOUT,
             first_byte_timeout: DEFAULT_FIRST_BYTE_TIMEOUT,
+            filter: None,
         }
     }
 }
@@ -89,6 +92,12 @@ impl GitClient {
         self
     }
 
+    /// Ask the server to omit objects per `filter` (partial clone).
+    pub fn with_filter(mut self, filter: Option<ObjectFilter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
     async fn open_stream(&self) -> Result<TcpStream, IoError> {
         match tokio::time::timeout(
             self.connect_timeout,
@@ -186,7 +195,8 @@ impl GitClient {
         self.write_all_idle(&mut stream, &request).await?;
         self.read_advertisement(&mut stream).await?;
 
-        let body = generate_upload_pack_content(have, want, shallow, depth);
+        let body =
+            generate_filtered_upload_pack_content(have, want, shallow, depth, self.filter.as_ref());
         self.write_all_idle(&mut stream, &body).await?;
 
         // Read the pack with a per-read IDLE bound (the timer resets whenever
@@ -1,6 +1,10 @@
 //! HTTPS smart protocol client that discovers refs, negotiates upload-pack/receive-pack, streams pack data, and supports basic authentication.
 
-use std::{io::Error as IoError, ops::Deref, sync::Mutex, time::Duration};
+use std::{
+    io::Error as IoError,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use futures_util::{StreamExt, TryStreamExt};
 use git_internal::errors::GitError;
@@ -8,15 +12,17 @@ use reqwest::{Body, RequestBuilder, Response, StatusCode, header::CONTENT_TYPE};
 use url::Url;
 
 use super::{
-    DiscoveryResult, FetchStream, ProtocolClient, generate_upload_pack_content,
-    parse_discovered_references,
+    DiscoveryResult, FetchStream, ObjectFilter, ProtocolClient, batched_want_requests,
+    generate_filtered_upload_pack_content, parse_discovered_references,
 };
 use crate::{
     command::ask_basic_auth,
     git_protocol::ServiceType,
     utils::{
         backoff::{RetryOutcome, RetryPolicy, parse_retry_after, retry_idempotent},
+        credential_helper::TransportHelper,
         error::emit_warning,
+        progress::{self, Progress},
         redact::redact_url_credentials,
     },
 };
@@ -26,6 +32,10 @@ use crate::{
 pub struct HttpsClient {
     pub(crate) url: Url,
     pub(crate) client: reqwest::Client,
+    /// Receives the bytes of fetched packs and uploaded push bodies.
+    progress: Arc<dyn Progress>,
+    /// Partial-clone filter sent with every upload-pack request.
+    filter: Option<ObjectFilter>,
 }
 
 /// Default connection timeout for initial TCP+TLS handshake.
@@ -60,16 +70,38 @@ impl BasicAuth {
     }
 
     /// send request with basic auth, retry 3 times
+    ///
+    /// On a `401` the configured `credential.helper` is asked first, then the
+    /// user is prompted. Credentials the server accepts are stored through
+    /// the helper; ones it rejects are erased from it.
     pub async fn send<Fut>(request_builder: impl Fn() -> Fut) -> Result<Response, reqwest::Error>
+    where
+        Fut: std::future::Future<Output = RequestBuilder>,
+    {
+        Self::send_with_helper(None, request_builder).await
+    }
+
+    /// [`BasicAuth::send`] with `helper` in place of the configured
+    /// `credential.helper`, which is otherwise read on the first `401`.
+    pub(crate) async fn send_with_helper<Fut>(
+        helper: Option<TransportHelper>,
+        request_builder: impl Fn() -> Fut,
+    ) -> Result<Response, reqwest::Error>
     where
         Fut: std::future::Future<Output = RequestBuilder>,
     {
         const MAX_TRY: usize = 3;
+        // `None` until resolved; `Some(None)` when no helper is configured.
+        let mut helper = helper.map(Some);
+        let mut helper_asked = false;
+        // Credentials obtained by this call, approved once a request succeeds.
+        let mut pending: Option<BasicAuth> = None;
         let mut res;
         let mut try_cnt = 0;
         loop {
             let mut request = request_builder().await; // RequestBuilder can't be cloned
-            if let Some(auth) = AUTH.lock().expect("AUTH mutex poisoned").deref() {
+            let auth = AUTH.lock().expect("AUTH mutex poisoned").clone();
+            if let Some(auth) = &auth {
                 request = request.basic_auth(auth.username.clone(), Some(auth.password.clone()));
             } // if no auth exists, try without auth (e.g. clone public)
             res = request.send().await?;
@@ -81,17 +113,43 @@ impl BasicAuth {
                 tracing::warn!("HTTP 403 Forbidden from server; caller will decide how to handle");
                 break;
             } else if res.status() != StatusCode::UNAUTHORIZED {
+                if res.status().is_success()
+                    && let Some(accepted) = pending.take()
+                    && let Some(Some(helper)) = &helper
+                {
+                    helper.approve(res.url(), &accepted).await;
+                }
                 break;
             }
             // 401 (Unauthorized): username or password is incorrect
+            if helper.is_none() {
+                helper = Some(TransportHelper::configured().await);
+            }
+            let helper = helper.as_ref().and_then(Option::as_ref);
+            if let (Some(helper), Some(rejected)) = (helper, &auth) {
+                helper.reject(res.url(), rejected).await;
+            }
             if try_cnt >= MAX_TRY {
                 eprintln!("fatal: failed to authenticate after {MAX_TRY} attempts");
                 break;
             }
-            emit_warning("authentication required, retrying...");
+            // The helper is asked once; a credential it supplied that the
+            // server rejected has just been erased, so then prompt.
+            let from_helper = match helper {
+                Some(helper) if !helper_asked => {
+                    helper_asked = true;
+                    helper.fill(res.url()).await
+                }
+                _ => None,
+            };
+            let next = from_helper.unwrap_or_else(|| {
+                emit_warning("authentication required, retrying...");
+                ask_basic_auth()
+            });
             AUTH.lock()
                 .expect("AUTH mutex poisoned")
-                .replace(ask_basic_auth());
+                .replace(next.clone());
+            pending = Some(next);
             try_cnt += 1;
         }
         Ok(res)
@@ -109,7 +167,12 @@ impl HttpsClient {
     ) -> Result<Self, String> {
         let url = normalize_url(url);
         let client = build_client(connect_timeout, read_timeout)?;
-        Ok(Self { url, client })
+        Ok(Self {
+            url,
+            client,
+            progress: progress::noop(),
+            filter: None,
+        })
     }
 
     pub fn with_timeouts(
@@ -121,6 +184,19 @@ impl HttpsClient {
         Ok(self)
     }
 
+    /// Report transfer bytes to `progress`: each upload-pack response chunk as
+    /// it arrives, and each receive-pack body against its length.
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Ask the server to omit objects per `filter` (partial clone).
+    pub fn with_filter(mut self, filter: Option<ObjectFilter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
     /// GET $GIT_URL/info/refs?service=git-upload-pack HTTP/1.0<br>
     /// Discover the references of the remote repository before fetching the objects.
     /// the first ref named HEAD as default ref.
@@ -255,7 +331,8 @@ impl HttpsClient {
             .url
             .join("git-upload-pack")
             .expect("'git-upload-pack' is a valid relative URL");
-        let body = generate_upload_pack_content(have, want, shallow, depth);
+        let body =
+            generate_filtered_upload_pack_content(have, want, shallow, depth, self.filter.as_ref());
         tracing::debug!("fetch_objects with body: {:?}", body);
 
         let res = BasicAuth::send(|| async {
@@ -290,12 +367,38 @@ impl HttpsClient {
                 res.status()
             )));
         }
-        let result = res.bytes_stream().map_err(std::io::Error::other).boxed();
+        self.progress.set_message("Receiving objects");
+        let progress = Arc::clone(&self.progress);
+        let result = res
+            .bytes_stream()
+            .inspect_ok(move |chunk| progress.inc(chunk.len() as u64))
+            .map_err(std::io::Error::other)
+            .boxed();
 
         Ok(result)
     }
 
-    pub async fn send_pack<T: Into<Body> + Clone>(
+    /// Fetch `want` as up to `jobs` packs over concurrent upload-pack
+    /// requests, one stream per batch (see [`batched_want_requests`]). Fails
+    /// as a whole if any request fails.
+    pub async fn fetch_objects_batched(
+        &self,
+        have: &[String],
+        want: &[String],
+        shallow: &[String],
+        depth: Option<usize>,
+        jobs: usize,
+    ) -> Result<Vec<FetchStream>, IoError> {
+        let requests = batched_want_requests(have, want, jobs);
+        futures_util::future::try_join_all(
+            requests
+                .iter()
+                .map(|(have, want)| self.fetch_objects(have, want, shallow, depth)),
+        )
+        .await
+    }
+
+    pub async fn send_pack<T: Into<Body> + Clone + AsRef<[u8]>>(
         &self,
         data: T,
     ) -> Result<Response, reqwest::Error> {
@@ -304,13 +407,23 @@ impl HttpsClient {
             .url
             .join("git-receive-pack")
             .expect("'git-receive-pack' is a valid relative URL");
-        BasicAuth::send(|| async {
+        let len = data.as_ref().len() as u64;
+        self.progress.set_message("Writing objects");
+        self.progress.set_total(len);
+        let res = BasicAuth::send(|| async {
             self.client
                 .post(receive_pack_url.clone())
                 .header(CONTENT_TYPE, "application/x-git-receive-pack-request")
                 .body(data.clone())
         })
-        .await
+        .await;
+        // The body is handed to reqwest whole, so it is only known to be sent
+        // once the response arrives.
+        if res.is_ok() {
+            self.progress.inc(len);
+        }
+        self.progress.finish();
+        res
     }
 }
 
@@ -335,3 +448,59 @@ fn build_client(
         .build()
         .map_err(|e| format!("failed to build HTTPS client: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, http::HeaderMap, routing::get};
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    use super::*;
+
+    /// A `401` is answered from the configured helper, and the credential the
+    /// server then accepts is handed back to the helper's `store`.
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn send_round_trips_credentials_through_helper() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/info/refs",
+            get(|headers: HeaderMap| async move {
+                let expected = format!("Basic {}", STANDARD.encode("alice:s3cret"));
+                match headers.get("authorization") {
+                    Some(value) if value.as_bytes() == expected.as_bytes() => StatusCode::OK,
+                    _ => StatusCode::UNAUTHORIZED,
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let helper = TransportHelper::parse(&format!(
+            "!f() {{ case \"$1\" in \
+             get) printf 'username=alice\\npassword=s3cret\\n' ;; \
+             store) cat > '{0}/stored' ;; \
+             erase) cat > '{0}/erased' ;; \
+             esac; }}; f",
+            dir.path().display()
+        ))
+        .unwrap();
+
+        AUTH.lock().unwrap().take();
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let url = format!("http://{addr}/info/refs");
+        let res = BasicAuth::send_with_helper(Some(helper), || async { client.get(&url) })
+            .await
+            .unwrap();
+        AUTH.lock().unwrap().take();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        let stored = std::fs::read_to_string(dir.path().join("stored")).unwrap();
+        assert!(stored.contains(&format!("host={addr}")), "{stored}");
+        assert!(stored.contains("username=alice"), "{stored}");
+        assert!(stored.contains("password=s3cret"), "{stored}");
+        assert!(!dir.path().join("erased").exists());
+    }
+}
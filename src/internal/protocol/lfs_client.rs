@@ -21,9 +21,9 @@ use crate::{
         protocol::{ProtocolClient, https_client::BasicAuth},
     },
     lfs_structs::{
-        Action, BatchRequest, ChunkDownloadObject, FetchchunkResponse, LockList, LockListQuery,
-        LockRequest, ObjectError, Operation, Ref, RequestObject, ResponseObject, UnlockRequest,
-        VerifiableLockList, VerifiableLockRequest,
+        Action, BatchRequest, ChunkDownloadObject, FetchchunkResponse, Link, Lock, LockList,
+        LockListQuery, LockRequest, LockResponse, ObjectError, Operation, Ref, RequestObject,
+        ResponseObject, UnlockRequest, VerifiableLockList, VerifiableLockRequest,
     },
     utils::{lfs, util},
 };
@@ -73,6 +73,18 @@ pub enum LockListError {
     Decode(String),
 }
 
+/// Outcome of [`LFSClient::fetch_objects`].
+#[derive(Debug, Default)]
+pub struct LfsFetchReport {
+    /// OIDs written to the local LFS cache.
+    pub fetched: Vec<String>,
+    /// OIDs the server could not serve, with the reason.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Objects negotiated per batch request by [`LFSClient::fetch_objects`].
+const FETCH_BATCH_SIZE: usize = 100;
+
 static LFS_CLIENT: OnceCell<LFSClient> = OnceCell::const_new();
 impl LFSClient {
     /// Get LFSClient instance
@@ -491,6 +503,104 @@ impl LFSClient {
         Ok(())
     }
 
+    /// Download `objects` (oid, size) into the local LFS cache
+    /// (`.libra/lfs/objects`) without touching the working tree. Objects are
+    /// negotiated [`FETCH_BATCH_SIZE`] at a time through the batch API; an
+    /// object the server cannot serve is reported in `failed` instead of
+    /// aborting the rest.
+    pub async fn fetch_objects(&self, objects: &[(String, u64)]) -> anyhow::Result<LfsFetchReport> {
+        let mut report = LfsFetchReport::default();
+        for batch in objects.chunks(FETCH_BATCH_SIZE) {
+            let batch_request = BatchRequest {
+                operation: Operation::Download,
+                transfers: vec![lfs::LFS_TRANSFER_API.to_string()],
+                objects: batch
+                    .iter()
+                    .map(|(oid, size)| RequestObject {
+                        oid: oid.clone(),
+                        size: *size as i64,
+                        ..Default::default()
+                    })
+                    .collect(),
+                hash_algo: lfs::LFS_HASH_ALGO.to_string(),
+            };
+            let response = BasicAuth::send(|| async {
+                self.client
+                    .post(self.batch_url.clone())
+                    .json(&batch_request)
+                    .headers(lfs::LFS_HEADERS.clone())
+            })
+            .await?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "LFS batch download failed with status {}",
+                    response.status()
+                ));
+            }
+            let resp = response.json::<LfsBatchResponse>().await?;
+            for obj in resp.objects {
+                let link = obj
+                    .actions
+                    .as_ref()
+                    .and_then(|actions| actions.get(&Action::Download));
+                match (&obj.error, link) {
+                    (Some(err), _) => report
+                        .failed
+                        .push((obj.oid, format!("{} (code {})", err.message, err.code))),
+                    (None, None) => report
+                        .failed
+                        .push((obj.oid, "no download action returned".to_string())),
+                    (None, Some(link)) => match self.download_to_cache(&obj.oid, link).await {
+                        Ok(()) => report.fetched.push(obj.oid),
+                        Err(e) => report.failed.push((obj.oid, e.to_string())),
+                    },
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// GET one object from `link` into the LFS cache. The content is written
+    /// to a `.part` file and only moved into place once its SHA-256 matches
+    /// `oid`, so an interrupted download never looks cached.
+    async fn download_to_cache(&self, oid: &str, link: &Link) -> anyhow::Result<()> {
+        let path = lfs::lfs_object_path(oid);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let response = BasicAuth::send(|| async {
+            let mut request = self.client.get(&link.href);
+            for (k, v) in &link.header {
+                request = request.header(k, v);
+            }
+            request
+        })
+        .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("download failed with status {}", response.status()));
+        }
+
+        let part = path.with_extension("part");
+        let mut file = tokio::fs::File::create(&part).await?;
+        let mut checksum = Context::new(&SHA256);
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            file.write_all(&chunk).await?;
+            checksum.update(&chunk);
+        }
+        file.flush().await?;
+        drop(file);
+
+        let actual = hex::encode(checksum.finish().as_ref());
+        if actual != oid {
+            let _ = tokio::fs::remove_file(&part).await;
+            return Err(anyhow!("checksum mismatch: got {actual}"));
+        }
+        tokio::fs::rename(&part, &path).await?;
+        Ok(())
+    }
+
     #[allow(clippy::type_complexity)]
     /// download (GET) one LFS file from remote server
     pub async fn download_object(
@@ -803,7 +913,13 @@ impl LFSClient {
 
     /// lock an LFS file
     /// - `refspec` is must in Mega Server, but optional in Git Doc
-    pub async fn lock(&self, path: String, refspec: String) -> Result<StatusCode, reqwest::Error> {
+    /// - returns the granted lock when the success body parses, so the caller
+    ///   can remember its id for `unlock`
+    pub async fn lock(
+        &self,
+        path: String,
+        refspec: String,
+    ) -> Result<(StatusCode, Option<Lock>), reqwest::Error> {
         // INVARIANT: `self.lfs_url` was parsed by `Url::parse` during client
         // construction; joining a static relative URL onto a valid base URL
         // cannot fail.
@@ -821,11 +937,21 @@ impl LFSClient {
         })
         .await?;
         let code = resp.status();
-        if !resp.status().is_success() && code != StatusCode::FORBIDDEN {
+        if code.is_success() {
+            let lock = match resp.json::<LockResponse>().await {
+                Ok(body) => Some(body.lock),
+                Err(err) => {
+                    tracing::warn!(error = %err, "LFS lock response has no usable lock body");
+                    None
+                }
+            };
+            return Ok((code, lock));
+        }
+        if code != StatusCode::FORBIDDEN {
             let body = resp.text().await.unwrap_or_default();
             tracing::warn!(status = %code, body = %body, "LFS lock failed");
         }
-        Ok(code)
+        Ok((code, None))
     }
 
     pub async fn unlock(
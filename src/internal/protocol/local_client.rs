@@ -10,7 +10,7 @@ use std::{
     sync::OnceLock,
 };
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures_util::stream;
 use git_internal::{
     errors::GitError,
@@ -28,13 +28,14 @@ use git_internal::{
         pack::{encode::PackEncoder, entry::Entry},
     },
 };
+use sea_orm::{ConnectionTrait, TransactionError, TransactionTrait};
 use tokio::sync::Mutex;
 use url::Url;
 
-use super::{DiscoveryResult, FetchStream, ProtocolClient};
+use super::{DiscoveryResult, FetchStream, ObjectFilter, ProtocolClient};
 use crate::{
     command::{load_object, log::get_reachable_commits},
-    git_protocol::ServiceType,
+    git_protocol::{ServiceType, add_pkt_line_string},
     internal::{
         branch::Branch, config::ConfigKv, db::get_db_conn_instance_for_path, head::Head,
         protocol::DiscRef, reflog, tag,
@@ -42,7 +43,7 @@ use crate::{
     utils::{
         client_storage::ClientStorage,
         object_ext::TreeExt,
-        util::{DATABASE, cur_dir},
+        util::{DATABASE, cur_dir, objects_storage},
     },
 };
 
@@ -56,6 +57,12 @@ enum RepoType {
 pub struct LocalClient {
     repo_path: PathBuf,
     source_type: RepoType,
+    /// Working tree of the repository; `None` for a bare repository, whose
+    /// storage (`objects/`, `libra.db` or `HEAD`) sits directly at `repo_path`.
+    work_dir: Option<PathBuf>,
+    /// Partial-clone filter applied while assembling fetched packs, standing
+    /// in for the server-side `filter` a remote upload-pack would honor.
+    filter: Option<ObjectFilter>,
 }
 
 static LOCAL_PROTOCOL_CWD_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -147,6 +154,8 @@ impl ProtocolClient for LocalClient {
         let path = url
             .to_file_path()
             .unwrap_or_else(|_| PathBuf::from(url.path()));
+        let has_work_dir = path.join(".libra/libra.db").try_exists().unwrap_or(false)
+            || path.join(".git/HEAD").try_exists().unwrap_or(false);
         Self {
             repo_path: path.clone(),
             source_type: {
@@ -158,6 +167,8 @@ impl ProtocolClient for LocalClient {
                     RepoType::GitRepo
                 }
             },
+            work_dir: has_work_dir.then_some(path),
+            filter: None,
         }
     }
 }
@@ -193,6 +204,12 @@ impl LocalClient {
         }
     }
 
+    /// Omit objects per `filter` from fetched packs (partial clone).
+    pub fn with_filter(mut self, filter: Option<ObjectFilter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self, IoError> {
         let path = path.as_ref();
         let absolute = if path.is_absolute() {
@@ -213,10 +230,14 @@ impl LocalClient {
                 (true, false) => Ok(Self {
                     repo_path: absolute,
                     source_type: RepoType::LibraRepo,
+                    work_dir: None,
+                    filter: None,
                 }),
                 (false, true) => Ok(Self {
                     repo_path: absolute,
                     source_type: RepoType::GitRepo,
+                    work_dir: None,
+                    filter: None,
                 }),
                 _ => Err(IoError::other(format!(
                     "No valid Git directory structure found at: {}",
@@ -227,6 +248,8 @@ impl LocalClient {
             Ok(Self {
                 repo_path: absolute.join(".git"),
                 source_type: RepoType::GitRepo,
+                work_dir: Some(absolute),
+                filter: None,
             })
         } else if absolute
             .join(".libra/libra.db")
@@ -236,6 +259,8 @@ impl LocalClient {
             Ok(Self {
                 repo_path: absolute.join(".libra"),
                 source_type: RepoType::LibraRepo,
+                work_dir: Some(absolute),
+                filter: None,
             })
         } else {
             Err(IoError::other(format!(
@@ -249,6 +274,17 @@ impl LocalClient {
         &self.repo_path
     }
 
+    /// Whether the repository has no working tree.
+    pub fn is_bare(&self) -> bool {
+        self.work_dir.is_none()
+    }
+
+    /// Whether the repository is a Libra repository (the only kind that can
+    /// receive a push).
+    pub fn is_libra_repo(&self) -> bool {
+        matches!(self.source_type, RepoType::LibraRepo)
+    }
+
     async fn repo_hash_kind(&self) -> Result<HashKind, String> {
         let db_path = self.repo_path.join(DATABASE);
         let db_conn = get_db_conn_instance_for_path(&db_path)
@@ -284,11 +320,15 @@ impl LocalClient {
         &self,
         service: ServiceType,
     ) -> Result<DiscoveryResult, GitError> {
-        if service != ServiceType::UploadPack {
-            return Err(GitError::NetworkError(
-                "Unsupported service type for local protocol".to_string(),
-            ));
-        }
+        let receive_pack = match service {
+            ServiceType::UploadPack => false,
+            ServiceType::ReceivePack if self.is_libra_repo() => true,
+            _ => {
+                return Err(GitError::NetworkError(
+                    "Unsupported service type for local protocol".to_string(),
+                ));
+            }
+        };
         match self.source_type {
             RepoType::GitRepo => {
                 // In-process discovery: read the foreign Git repository's refs
@@ -354,7 +394,13 @@ impl LocalClient {
                                 _ref: reflog::HEAD.to_string(),
                             }))
                             .collect::<Vec<_>>(),
-                        capabilities: vec![],
+                        // `receive_pack` validates every command before
+                        // applying any, so pushes here are always atomic.
+                        capabilities: if receive_pack {
+                            vec!["report-status".to_string(), "atomic".to_string()]
+                        } else {
+                            vec![]
+                        },
                         hash_kind: repo_hash_kind,
                     })
                 })
@@ -385,14 +431,20 @@ impl LocalClient {
                 // never held across an `.await`.
                 let (entries, shallow) = {
                     let _hash_guard = HashKindRestoreGuard::switch_to(hash_kind);
-                    collect_git_repo_entries(&storage, &self.repo_path, want, have, depth).map_err(
-                        |error| {
-                            IoError::other(format!(
-                                "failed to assemble pack for '{}': {error}",
-                                self.repo_path.display()
-                            ))
-                        },
-                    )?
+                    collect_git_repo_entries(
+                        &storage,
+                        &self.repo_path,
+                        want,
+                        have,
+                        depth,
+                        self.filter.as_ref(),
+                    )
+                    .map_err(|error| {
+                        IoError::other(format!(
+                            "failed to assemble pack for '{}': {error}",
+                            self.repo_path.display()
+                        ))
+                    })?
                 };
                 encode_entries_to_fetch_response(entries, shallow, hash_kind).await
             }
@@ -421,6 +473,18 @@ impl LocalClient {
                             commit_targets.push(want_hash.clone());
                             continue;
                         };
+                        // A blob want is part of a partial clone's lazy fetch
+                        // of missing objects: send it as is, never filtered.
+                        if matches!(
+                            objects_storage().get_object_type(&oid),
+                            Ok(ObjectType::Blob)
+                        ) {
+                            if seen.insert(want_hash.clone()) {
+                                let blob = load_object::<Blob>(&oid).map_err(IoError::other)?;
+                                tag_entries.push(Entry::from(blob));
+                            }
+                            continue;
+                        }
                         match tag::load_object_trait(&oid).await {
                             Ok(tag::TagObject::Tag(tag_obj)) => {
                                 let mut current = tag_obj;
@@ -489,6 +553,13 @@ impl LocalClient {
                             _ => IoError::other(format!("{}", giterror)),
                         })?;
 
+                    // `blob:none` skips every blob without reading it;
+                    // `blob:limit` has to load a blob to learn its size.
+                    let blob_hash = if self.filter == Some(ObjectFilter::BlobNone) {
+                        Vec::new()
+                    } else {
+                        blob_hash
+                    };
                     let blobs = blob_hash
                         .into_iter()
                         .map(|(hash, _)| load_object::<Blob>(&hash))
@@ -496,7 +567,13 @@ impl LocalClient {
                         .map_err(|giterror| match giterror {
                             GitError::IOError(io_error) => io_error,
                             _ => IoError::other(format!("{}", giterror)),
-                        })?;
+                        })?
+                        .into_iter()
+                        .filter(|blob| {
+                            self.filter
+                                .is_none_or(|filter| filter.keeps_blob(blob.data.len()))
+                        })
+                        .collect::<Vec<_>>();
 
                     let commit_entries: Vec<Entry> = commits.into_iter().map(Entry::from).collect();
 
@@ -518,6 +595,87 @@ impl LocalClient {
             }
         }
     }
+
+    /// Act as `receive-pack` for a Libra repository on disk: store the pushed
+    /// objects, apply the `(old, new, ref)` commands, and return the
+    /// report-status response a server would send (`unpack ok`, then one
+    /// `ok <ref>` / `ng <ref> <reason>` line per command).
+    ///
+    /// Every command is checked before any is applied — the ref must still hold
+    /// `old`, the new object must be present, and a non-bare repository refuses
+    /// to move its checked-out branch — so one rejection fails the whole push,
+    /// and the accepted commands are then applied in a single transaction.
+    pub async fn receive_pack(
+        &self,
+        objects: impl IntoIterator<Item = &Entry>,
+        commands: &[(String, String, String)],
+    ) -> Result<Bytes, IoError> {
+        if !self.is_libra_repo() {
+            return Err(IoError::other(format!(
+                "pushing into a Git repository on disk is not supported: {}",
+                self.repo_path.display()
+            )));
+        }
+        self.with_repo_current_dir(|| async move {
+            let repo_hash_kind = self.repo_hash_kind().await.map_err(IoError::other)?;
+            let _hash_guard = HashKindRestoreGuard::switch_to(repo_hash_kind);
+
+            let storage = objects_storage();
+            for entry in objects {
+                storage.put(&entry.hash, &entry.data, entry.obj_type)?;
+            }
+
+            let checked_out = match (&self.work_dir, Head::current().await) {
+                (Some(_), Head::Branch(name)) => Some(format!("refs/heads/{name}")),
+                _ => None,
+            };
+            let mut rejections = Vec::new();
+            for (old, new, refname) in commands {
+                if let Some(reason) =
+                    check_ref_command(&storage, checked_out.as_deref(), old, new, refname).await?
+                {
+                    rejections.push((refname.clone(), reason));
+                }
+            }
+
+            let mut report = BytesMut::new();
+            add_pkt_line_string(&mut report, "unpack ok\n".to_string());
+            if rejections.is_empty() {
+                // All refs move together: a failed write rolls back the ones
+                // already applied instead of leaving the push half-done.
+                let db_conn = get_db_conn_instance_for_path(&self.repo_path.join(DATABASE)).await?;
+                let updates = commands.to_vec();
+                db_conn
+                    .transaction::<_, (), IoError>(move |txn| {
+                        Box::pin(async move {
+                            for (_, new, refname) in &updates {
+                                apply_ref_command(txn, new, refname).await?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .await
+                    .map_err(|error| match error {
+                        TransactionError::Connection(err) => IoError::other(err.to_string()),
+                        TransactionError::Transaction(err) => err,
+                    })?;
+                for (_, _, refname) in commands {
+                    add_pkt_line_string(&mut report, format!("ok {refname}\n"));
+                }
+            } else {
+                for (_, _, refname) in commands {
+                    let reason = rejections
+                        .iter()
+                        .find(|(rejected, _)| rejected == refname)
+                        .map_or("atomic push failed", |(_, reason)| reason.as_str());
+                    add_pkt_line_string(&mut report, format!("ng {refname} {reason}\n"));
+                }
+            }
+            report.extend_from_slice(b"0000");
+            Ok(report.freeze())
+        })
+        .await
+    }
 }
 
 /// Read `objectformat` from a foreign Git repository's `config`, defaulting to
@@ -681,6 +839,7 @@ fn collect_git_repo_entries(
     want: &[String],
     have: &[String],
     depth: Option<usize>,
+    filter: Option<&ObjectFilter>,
 ) -> Result<(Vec<Entry>, Vec<String>), GitError> {
     let have_set: HashSet<String> = have.iter().cloned().collect();
     let mut seen: HashSet<String> = have_set.clone();
@@ -696,7 +855,16 @@ fn collect_git_repo_entries(
         let Ok(oid) = ObjectHash::from_str(spec) else {
             continue;
         };
-        if matches!(storage.get_object_type(&oid), Ok(ObjectType::Tag)) {
+        let object_type = storage.get_object_type(&oid);
+        // A blob want is part of a partial clone's lazy fetch of missing
+        // objects: send it as is, never filtered.
+        if matches!(object_type, Ok(ObjectType::Blob)) {
+            if seen.insert(oid.to_string()) {
+                entries.push(Entry::from(Blob::from_bytes(&storage.get(&oid)?, oid)?));
+            }
+            continue;
+        }
+        if matches!(object_type, Ok(ObjectType::Tag)) {
             let mut current = oid;
             for _ in 0..32 {
                 if !seen.insert(current.to_string()) {
@@ -753,10 +921,13 @@ fn collect_git_repo_entries(
                 TreeItemMode::Tree => tree_queue.push_back(item.id),
                 // A gitlink points at a commit in another repository.
                 TreeItemMode::Commit => {}
+                _ if filter == Some(&ObjectFilter::BlobNone) => {}
                 _ => {
                     if seen.insert(item.id.to_string()) {
                         let blob = Blob::from_bytes(&storage.get(&item.id)?, item.id)?;
-                        entries.push(Entry::from(blob));
+                        if filter.is_none_or(|filter| filter.keeps_blob(blob.data.len())) {
+                            entries.push(Entry::from(blob));
+                        }
                     }
                 }
             }
@@ -845,7 +1016,10 @@ fn empty_pack_bytes(hash_kind: HashKind) -> Vec<u8> {
 
 /// Encode `entries` (non-empty) into a v2 pack, propagating the repository hash
 /// kind into the encoder's spawned task.
-async fn encode_pack_bytes(entries: Vec<Entry>, hash_kind: HashKind) -> Result<Vec<u8>, IoError> {
+pub(crate) async fn encode_pack_bytes(
+    entries: Vec<Entry>,
+    hash_kind: HashKind,
+) -> Result<Vec<u8>, IoError> {
     let (entry_tx, entry_rx) = tokio::sync::mpsc::channel::<MetaAttached<Entry, EntryMeta>>(1_000);
     let (stream_tx, mut stream_rx) = tokio::sync::mpsc::channel(1_000);
 
@@ -945,6 +1119,87 @@ async fn encode_entries_to_fetch_response(
     Ok(Box::pin(response_stream) as FetchStream)
 }
 
+fn is_zero_oid(oid: &str) -> bool {
+    oid.bytes().all(|byte| byte == b'0')
+}
+
+/// Current target of a pushable ref (`refs/heads/*` or `refs/tags/*`) in the
+/// repository at the current directory. `Err` carries an `ng` reason.
+async fn current_ref_target(refname: &str) -> Result<Option<String>, String> {
+    if let Some(branch) = refname.strip_prefix("refs/heads/") {
+        Branch::find_branch_result(branch, None)
+            .await
+            .map(|found| found.map(|branch| branch.commit.to_string()))
+            .map_err(|error| format!("failed to read ref: {error}"))
+    } else if let Some(name) = refname.strip_prefix("refs/tags/") {
+        tag::find_tag_ref(name)
+            .await
+            .map(|found| found.and_then(|tag_ref| tag_ref.target))
+            .map_err(|error| format!("failed to read ref: {error}"))
+    } else {
+        Err("unsupported ref namespace".to_string())
+    }
+}
+
+/// Validate one receive-pack command; `Some(reason)` rejects it.
+async fn check_ref_command(
+    storage: &ClientStorage,
+    checked_out: Option<&str>,
+    old: &str,
+    new: &str,
+    refname: &str,
+) -> Result<Option<String>, IoError> {
+    let current = match current_ref_target(refname).await {
+        Ok(current) => current,
+        Err(reason) => return Ok(Some(reason)),
+    };
+    let expected = (!is_zero_oid(old)).then_some(old);
+    if current.as_deref() != expected {
+        return Ok(Some("stale info".to_string()));
+    }
+    if checked_out == Some(refname) {
+        return Ok(Some("branch is currently checked out".to_string()));
+    }
+    if !is_zero_oid(new) {
+        let present = ObjectHash::from_str(new)
+            .map(|oid| storage.exist(&oid))
+            .unwrap_or(false);
+        if !present {
+            return Ok(Some("missing necessary objects".to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Point `refname` at `new`, or delete it when `new` is the zero id.
+async fn apply_ref_command<C>(db: &C, new: &str, refname: &str) -> Result<(), IoError>
+where
+    C: ConnectionTrait,
+{
+    if let Some(branch) = refname.strip_prefix("refs/heads/") {
+        if is_zero_oid(new) {
+            Branch::delete_branch_result_with_conn(db, branch, None)
+                .await
+                .map_err(|error| IoError::other(error.to_string()))
+        } else {
+            Branch::update_branch_with_conn(db, branch, new, None)
+                .await
+                .map_err(|error| IoError::other(error.to_string()))
+        }
+    } else {
+        let name = refname.strip_prefix("refs/tags/").unwrap_or(refname);
+        if is_zero_oid(new) {
+            tag::delete_with_conn(db, name)
+                .await
+                .map_err(|error| IoError::other(error.to_string()))
+        } else {
+            tag::update_ref_with_conn(db, name, new)
+                .await
+                .map_err(|error| IoError::other(error.to_string()))
+        }
+    }
+}
+
 fn tag_object_hash(object: &tag::TagObject) -> String {
     match object {
         tag::TagObject::Commit(commit) => commit.id.to_string(),
@@ -193,11 +193,80 @@ pub fn parse_discovered_references(
     })
 }
 
+/// A partial-clone object filter (`clone --filter=<spec>`). Sent to the server
+/// as a `filter <spec>` upload-pack line; the in-process local transport
+/// applies it itself while assembling the pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectFilter {
+    /// `blob:none` — omit every blob.
+    BlobNone,
+    /// `blob:limit=<n>` — omit blobs of `n` bytes or more.
+    BlobLimit(u64),
+}
+
+impl ObjectFilter {
+    /// Parse a filter spec: `blob:none`, or `blob:limit=<n>` where `<n>` may
+    /// carry a `k`/`m`/`g` (1024-based) suffix, as Git accepts.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if spec == "blob:none" {
+            return Ok(Self::BlobNone);
+        }
+        let Some(limit) = spec.strip_prefix("blob:limit=") else {
+            return Err(format!(
+                "unsupported filter '{spec}' (expected 'blob:none' or 'blob:limit=<n>')"
+            ));
+        };
+        let lower = limit.to_ascii_lowercase();
+        let (digits, multiplier) = match lower.chars().last() {
+            Some('k') => (&lower[..lower.len() - 1], 1024),
+            Some('m') => (&lower[..lower.len() - 1], 1024 * 1024),
+            Some('g') => (&lower[..lower.len() - 1], 1024 * 1024 * 1024),
+            _ => (lower.as_str(), 1),
+        };
+        digits
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(multiplier))
+            .map(Self::BlobLimit)
+            .ok_or_else(|| format!("invalid blob size limit in filter '{spec}'"))
+    }
+
+    /// Whether a blob of `size` bytes passes the filter.
+    pub fn keeps_blob(&self, size: usize) -> bool {
+        match self {
+            Self::BlobNone => false,
+            Self::BlobLimit(limit) => (size as u64) < *limit,
+        }
+    }
+}
+
+impl std::fmt::Display for ObjectFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlobNone => write!(f, "blob:none"),
+            Self::BlobLimit(limit) => write!(f, "blob:limit={limit}"),
+        }
+    }
+}
+
 pub fn generate_upload_pack_content(
     have: &[String],
     want: &[String],
     shallow: &[String],
     depth: Option<usize>,
+) -> Bytes {
+    generate_filtered_upload_pack_content(have, want, shallow, depth, None)
+}
+
+/// [`generate_upload_pack_content`] with an optional partial-clone `filter`:
+/// the `filter` capability joins the first want line and a `filter <spec>`
+/// line follows the shallow/deepen lines.
+pub fn generate_filtered_upload_pack_content(
+    have: &[String],
+    want: &[String],
+    shallow: &[String],
+    depth: Option<usize>,
+    filter: Option<&ObjectFilter>,
 ) -> Bytes {
     let mut buf = BytesMut::new();
     let mut write_first_line = false;
@@ -220,6 +289,9 @@ pub fn generate_upload_pack_content(
     if get_wire_hash_kind() == HashKind::Sha256 {
         capability.push("object-format=sha256");
     }
+    if filter.is_some() {
+        capability.push("filter");
+    }
     let capability = capability.join(" ");
     for w in want {
         if !write_first_line {
@@ -246,6 +318,10 @@ pub fn generate_upload_pack_content(
         add_pkt_line_string(&mut buf, format!("deepen {d}\n").to_string());
     }
 
+    if let Some(filter) = filter {
+        add_pkt_line_string(&mut buf, format!("filter {filter}\n"));
+    }
+
     buf.extend(b"0000");
     for h in have {
         add_pkt_line_string(&mut buf, format!("have {h}\n").to_string());
@@ -256,6 +332,26 @@ pub fn generate_upload_pack_content(
     buf.freeze()
 }
 
+/// Split `want` into at most `jobs` upload-pack requests as `(have, want)`
+/// pairs. Every batch advertises only `have`, the objects the client really
+/// holds: a want of another batch is not local yet, and the server would leave
+/// out everything reachable from it. Packs of batches whose tips share history
+/// therefore overlap, and the caller deduplicates objects when merging them.
+/// `thin-pack` stays unadvertised, so each pack is self-contained.
+pub fn batched_want_requests(
+    have: &[String],
+    want: &[String],
+    jobs: usize,
+) -> Vec<(Vec<String>, Vec<String>)> {
+    if want.is_empty() {
+        return Vec::new();
+    }
+    let batch_size = want.len().div_ceil(jobs.max(1));
+    want.chunks(batch_size)
+        .map(|batch| (have.to_vec(), batch.to_vec()))
+        .collect()
+}
+
 impl From<Branch> for DiscoveredReference {
     fn from(branch: Branch) -> Self {
         let _ref = if branch.name.starts_with("refs/") {
@@ -275,7 +371,65 @@ impl From<Branch> for DiscoveredReference {
 
 #[cfg(test)]
 mod test {
-    use super::generate_upload_pack_content;
+    use super::{
+        ObjectFilter, batched_want_requests, generate_filtered_upload_pack_content,
+        generate_upload_pack_content,
+    };
+
+    #[test]
+    fn object_filter_parses_blob_none_and_limits() {
+        assert_eq!(ObjectFilter::parse("blob:none"), Ok(ObjectFilter::BlobNone));
+        assert_eq!(
+            ObjectFilter::parse("blob:limit=512"),
+            Ok(ObjectFilter::BlobLimit(512))
+        );
+        assert_eq!(
+            ObjectFilter::parse("blob:limit=1k"),
+            Ok(ObjectFilter::BlobLimit(1024))
+        );
+        assert!(ObjectFilter::parse("tree:0").is_err());
+        assert!(ObjectFilter::parse("blob:limit=x").is_err());
+
+        assert!(!ObjectFilter::BlobNone.keeps_blob(0));
+        assert!(ObjectFilter::BlobLimit(4).keeps_blob(3));
+        assert!(!ObjectFilter::BlobLimit(4).keeps_blob(4));
+        assert_eq!(ObjectFilter::BlobLimit(1024).to_string(), "blob:limit=1024");
+    }
+
+    #[test]
+    fn filtered_upload_pack_request_sends_filter_line() {
+        let want = vec!["1".repeat(40)];
+        let body = generate_filtered_upload_pack_content(
+            &[],
+            &want,
+            &[],
+            None,
+            Some(&ObjectFilter::BlobNone),
+        );
+        let text = String::from_utf8_lossy(&body);
+        let first_line = text.lines().next().unwrap_or_default();
+        assert!(first_line.contains(" filter"), "{text}");
+        assert!(text.contains("filter blob:none\n"), "{text}");
+
+        let unfiltered = generate_upload_pack_content(&[], &want, &[], None);
+        assert!(!String::from_utf8_lossy(&unfiltered).contains("filter"));
+    }
+
+    #[test]
+    fn batched_want_requests_send_only_local_haves() {
+        let have = vec!["h".to_string()];
+        let want: Vec<String> = ["a", "b", "c", "d", "e"].map(String::from).to_vec();
+
+        let batches = batched_want_requests(&have, &want, 2);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0], (have.clone(), want[..3].to_vec()));
+        assert_eq!(batches[1], (have.clone(), want[3..].to_vec()));
+
+        // More jobs than wants: one request per want; zero jobs: one request.
+        assert_eq!(batched_want_requests(&have, &want[..2], 8).len(), 2);
+        assert_eq!(batched_want_requests(&have, &want, 0).len(), 1);
+        assert!(batched_want_requests(&have, &[], 4).is_empty());
+    }
 
     #[test]
     fn upload_pack_want_line_advertises_expected_capabilities() {
@@ -16,7 +16,8 @@ use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio_stream::wrappers::ReceiverStream;
 
 use super::{
-    DiscoveryResult, FetchStream, generate_upload_pack_content, parse_discovered_references,
+    DiscoveryResult, FetchStream, ObjectFilter, generate_filtered_upload_pack_content,
+    parse_discovered_references,
 };
 use crate::git_protocol::ServiceType;
 
@@ -49,6 +50,8 @@ pub struct SshClient {
     temp_key_file: Option<NamedTempFile>,
     strict_host_key_checking: String,
     idle_timeout: Duration,
+    /// Partial-clone filter sent with every upload-pack request.
+    filter: Option<ObjectFilter>,
 }
 
 impl SshClient {
@@ -81,6 +84,12 @@ impl SshClient {
         self
     }
 
+    /// Ask the server to omit objects per `filter` (partial clone).
+    pub fn with_filter(mut self, filter: Option<ObjectFilter>) -> Self {
+        self.filter = filter;
+        self
+    }
+
     /// Configure StrictHostKeyChecking mode.
     ///
     /// Supported values: `yes` (default), `accept-new` (explicit opt-in).
@@ -119,6 +128,7 @@ impl SshClient {
             temp_key_file: None,
             strict_host_key_checking: "yes".to_string(),
             idle_timeout: default_ssh_idle_timeout(),
+            filter: None,
         })
     }
 
@@ -142,6 +152,7 @@ impl SshClient {
             temp_key_file: None,
             strict_host_key_checking: "yes".to_string(),
             idle_timeout: default_ssh_idle_timeout(),
+            filter: None,
         })
     }
 
@@ -303,7 +314,8 @@ impl SshClient {
         }
 
         // Send the upload-pack request
-        let body = generate_upload_pack_content(have, want, shallow, depth);
+        let body =
+            generate_filtered_upload_pack_content(have, want, shallow, depth, self.filter.as_ref());
         let mut stdin = child
             .stdin
             .take()
@@ -11,6 +11,7 @@ use std::{
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseTransaction, DbBackend, DbErr,
     EntityTrait, QueryFilter, QueryOrder, Set, Statement, TransactionError, TransactionTrait,
+    sea_query::Expr,
 };
 use serde::Serialize;
 use tokio::time::sleep;
@@ -75,7 +76,7 @@ impl std::error::Error for ReflogError {}
 impl Display for ReflogContext {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self.action {
-            ReflogAction::Commit { message } => write!(
+            ReflogAction::Commit { message } | ReflogAction::CommitAmend { message } => write!(
                 f,
                 "{}",
                 message.lines().next().unwrap_or("(no commit message)")
@@ -99,6 +100,7 @@ impl Display for ReflogContext {
             ReflogAction::Rebase { state, details } => write!(f, "({state}) {details}"),
             ReflogAction::Clone { from } => write!(f, "from {from}"),
             ReflogAction::UpdateRef { message } => write!(f, "{message}"),
+            ReflogAction::SymbolicRef { message } => write!(f, "{message}"),
         }
     }
 }
@@ -108,6 +110,10 @@ pub enum ReflogAction {
     Commit {
         message: String,
     },
+    /// `commit --amend`, logged as `commit (amend)` like Git.
+    CommitAmend {
+        message: String,
+    },
     Reset {
         target: String,
     },
@@ -140,11 +146,16 @@ pub enum ReflogAction {
     UpdateRef {
         message: String,
     },
+    /// `symbolic-ref HEAD <ref>` repointing HEAD at another branch.
+    SymbolicRef {
+        message: String,
+    },
 }
 
 #[derive(Copy, Clone)]
 pub enum ReflogActionKind {
     Commit,
+    CommitAmend,
     Reset,
     // we don't need `checkout` because we have `switch`,
     Checkout,
@@ -158,12 +169,14 @@ pub enum ReflogActionKind {
     Push,
     Clone,
     UpdateRef,
+    SymbolicRef,
 }
 
 impl Display for ReflogActionKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Commit => write!(f, "commit"),
+            Self::CommitAmend => write!(f, "commit (amend)"),
             Self::Reset => write!(f, "reset"),
             Self::Checkout => write!(f, "checkout"),
             Self::Switch => write!(f, "switch"),
@@ -175,6 +188,7 @@ impl Display for ReflogActionKind {
             Self::Push => write!(f, "push"),
             Self::Clone => write!(f, "clone"),
             Self::UpdateRef => write!(f, "update-ref"),
+            Self::SymbolicRef => write!(f, "symbolic-ref"),
         }
     }
 }
@@ -183,6 +197,7 @@ impl ReflogAction {
     fn kind(&self) -> ReflogActionKind {
         match self {
             Self::Commit { .. } => ReflogActionKind::Commit,
+            Self::CommitAmend { .. } => ReflogActionKind::CommitAmend,
             Self::Reset { .. } => ReflogActionKind::Reset,
             Self::Switch { .. } => ReflogActionKind::Switch,
             Self::Merge { .. } => ReflogActionKind::Merge,
@@ -194,6 +209,7 @@ impl ReflogAction {
             Self::Fetch => ReflogActionKind::Fetch,
             Self::Push => ReflogActionKind::Push,
             Self::UpdateRef { .. } => ReflogActionKind::UpdateRef,
+            Self::SymbolicRef { .. } => ReflogActionKind::SymbolicRef,
         }
     }
 }
@@ -277,10 +293,76 @@ impl Reflog {
         Ok(reflog::Entity::find()
             .filter(reflog::Column::RefName.eq(ref_name))
             .order_by_desc(reflog::Column::Timestamp)
+            .order_by_desc(reflog::Column::Id)
             .all(db)
             .await?)
     }
 
+    /// Move every reflog entry recorded for `old_ref` onto `new_ref`, keeping
+    /// ids, timestamps and messages intact. Any entries already recorded for
+    /// `new_ref` are dropped first so a forced rename does not interleave two
+    /// unrelated histories. Returns the number of entries moved.
+    pub async fn rename_ref_with_conn<C: ConnectionTrait>(
+        db: &C,
+        old_ref: &str,
+        new_ref: &str,
+    ) -> Result<u64, ReflogError> {
+        // A self-move/self-copy must not clear the destination, which is
+        // also the source.
+        if old_ref == new_ref || !reflog_table_exists(db).await? {
+            return Ok(0);
+        }
+        reflog::Entity::delete_many()
+            .filter(reflog::Column::RefName.eq(new_ref))
+            .exec(db)
+            .await?;
+        let moved = reflog::Entity::update_many()
+            .col_expr(reflog::Column::RefName, Expr::value(new_ref))
+            .filter(reflog::Column::RefName.eq(old_ref))
+            .exec(db)
+            .await?;
+        Ok(moved.rows_affected)
+    }
+
+    /// Duplicate every reflog entry recorded for `src_ref` under `dst_ref`,
+    /// preserving the original timestamps and committer identity. Existing
+    /// `dst_ref` entries are replaced. Returns the number of entries copied.
+    pub async fn copy_ref_with_conn<C: ConnectionTrait>(
+        db: &C,
+        src_ref: &str,
+        dst_ref: &str,
+    ) -> Result<u64, ReflogError> {
+        if src_ref == dst_ref || !reflog_table_exists(db).await? {
+            return Ok(0);
+        }
+        reflog::Entity::delete_many()
+            .filter(reflog::Column::RefName.eq(dst_ref))
+            .exec(db)
+            .await?;
+        let entries = reflog::Entity::find()
+            .filter(reflog::Column::RefName.eq(src_ref))
+            .order_by_asc(reflog::Column::Id)
+            .all(db)
+            .await?;
+        let copied = entries.len() as u64;
+        for entry in entries {
+            ActiveModel {
+                ref_name: Set(dst_ref.to_string()),
+                old_oid: Set(entry.old_oid),
+                new_oid: Set(entry.new_oid),
+                timestamp: Set(entry.timestamp),
+                committer_name: Set(entry.committer_name),
+                committer_email: Set(entry.committer_email),
+                action: Set(entry.action),
+                message: Set(entry.message),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+        Ok(copied)
+    }
+
     pub async fn find_one<C: ConnectionTrait>(
         db: &C,
         ref_name: &str,
@@ -288,6 +370,7 @@ impl Reflog {
         Ok(reflog::Entity::find()
             .filter(reflog::Column::RefName.eq(ref_name))
             .order_by_desc(reflog::Column::Timestamp)
+            .order_by_desc(reflog::Column::Id)
             .one(db)
             .await?)
     }
@@ -15,7 +15,9 @@ use git_internal::{
         types::ObjectType,
     },
 };
-use sea_orm::{ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set,
+};
 
 use crate::{
     command::load_object,
@@ -176,6 +178,7 @@ pub async fn create(
             let sig_hex = crate::internal::vault::pgp_sign(
                 &root_dir,
                 &unseal_key,
+                &crate::internal::vault::signing_key_name().await,
                 unsigned_content.as_bytes(),
             )
             .await
@@ -267,8 +270,8 @@ pub enum VerifyTagError {
 
 /// Verify the PGP signature embedded in an annotated tag (`tag -v`). Reconstructs
 /// the canonical unsigned tag content (everything before the appended signature)
-/// and checks it against the armored signature via the vault PGP key. Returns
-/// `Ok(true)` for a good signature and `Ok(false)` for a bad one.
+/// and checks it against the armored signature via the vault PGP key (see
+/// [`verify_tag_object`]). Returns `Ok(true)` for a good signature and `Ok(false)` for a bad one.
 pub async fn verify(name: &str) -> Result<bool, VerifyTagError> {
     let tag_ref = find_tag_ref(name)
         .await
@@ -281,9 +284,19 @@ pub async fn verify(name: &str) -> Result<bool, VerifyTagError> {
         ObjectHash::from_str(&target).map_err(|e| VerifyTagError::LoadObject(e.to_string()))?;
     let tag = load_object::<git_internalTag>(&object_id)
         .map_err(|_| VerifyTagError::NotAnnotated(name.to_string()))?;
+    verify_tag_object(&tag).await.map(|signer| signer.is_some())
+}
 
+/// Verify an already-loaded annotated tag object's embedded PGP signature
+/// against the vault key `user.signingkey` names. Returns the identity of
+/// that key for a good signature and `None` for a bad one. Shared by `tag -v`
+/// (which resolves a tag name first) and `verify-tag` (which also accepts raw
+/// tag object ids).
+pub async fn verify_tag_object(
+    tag: &git_internalTag,
+) -> Result<Option<crate::internal::vault::PgpSigner>, VerifyTagError> {
     let Some(pos) = tag.message.find(TAG_SIGNATURE_MARKER) else {
-        return Err(VerifyTagError::NotSigned(name.to_string()));
+        return Err(VerifyTagError::NotSigned(tag.tag_name.clone()));
     };
     let armored = &tag.message[pos..];
     let original_message = tag.message[..pos]
@@ -302,9 +315,10 @@ pub async fn verify(name: &str) -> Result<bool, VerifyTagError> {
         .await
         .ok_or(VerifyTagError::NoVaultKey)?;
     let root_dir = util::storage_path();
-    crate::internal::vault::pgp_verify(
+    crate::internal::vault::pgp_verify_signer(
         &root_dir,
         &unseal_key,
+        &crate::internal::vault::signing_key_name().await,
         unsigned_content.as_bytes(),
         &sig_hex,
     )
@@ -312,6 +326,23 @@ pub async fn verify(name: &str) -> Result<bool, VerifyTagError> {
     .map_err(|e| VerifyTagError::Vault(e.to_string()))
 }
 
+/// Split a signed tag into its armored signature and the payload Git hands to
+/// `gpg --verify`: the raw tag object up to (not including) the signature
+/// block. Unlike the vault check, the message keeps its trailing newline,
+/// which is part of what `git tag -s` signs. `None` for an unsigned tag.
+pub fn tag_signed_payload(tag: &git_internalTag) -> Option<(String, Vec<u8>)> {
+    let pos = tag.message.find(TAG_SIGNATURE_MARKER)?;
+    let payload = format!(
+        "object {}\ntype {}\ntag {}\ntagger {}\n\n{}",
+        tag.object_hash,
+        tag.object_type,
+        tag.tag_name,
+        tag.tagger,
+        &tag.message[..pos]
+    );
+    Some((tag.message[pos..].to_string(), payload.into_bytes()))
+}
+
 /// Typed failures from [`list`]. Lets callers distinguish a transient SQLite
 /// read failure (mapped to `IoReadFailed`) from a genuinely corrupt tag row
 /// (`RepoCorrupt`) without parsing the underlying error string.
@@ -377,12 +408,20 @@ pub async fn list() -> Result<Vec<Tag>, ListTagError> {
 /// Deletes a tag reference from the repository.
 pub async fn delete(name: &str) -> Result<(), anyhow::Error> {
     let db_conn = get_db_conn_instance().await;
+    delete_with_conn(&db_conn, name).await
+}
+
+/// Connection-taking counterpart of [`delete`], for use inside a transaction.
+pub async fn delete_with_conn<C>(db: &C, name: &str) -> Result<(), anyhow::Error>
+where
+    C: ConnectionTrait,
+{
     let full_ref_name = format!("{}{}", TAG_REF_PREFIX, name);
 
     let result = reference::Entity::delete_many()
         .filter(reference::Column::Name.eq(full_ref_name))
         .filter(reference::Column::Kind.eq(reference::ConfigKind::Tag))
-        .exec(&db_conn)
+        .exec(db)
         .await?;
 
     if result.rows_affected == 0 {
@@ -392,6 +431,41 @@ pub async fn delete(name: &str) -> Result<(), anyhow::Error> {
     }
 }
 
+/// Points `refs/tags/<name>` at `target`, creating the ref if needed. Unlike
+/// [`create`] no object is written and no existing-tag check is made; used when
+/// a tag arrives from elsewhere (a push into this repository, whose ref updates
+/// share one transaction).
+pub async fn update_ref_with_conn<C>(db: &C, name: &str, target: &str) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let full_ref_name = format!("{}{}", TAG_REF_PREFIX, name);
+
+    let existing = reference::Entity::find()
+        .filter(reference::Column::Name.eq(full_ref_name.clone()))
+        .filter(reference::Column::Kind.eq(reference::ConfigKind::Tag))
+        .one(db)
+        .await?;
+    match existing {
+        Some(existing) => {
+            let mut existing: reference::ActiveModel = existing.into();
+            existing.commit = Set(Some(target.to_string()));
+            existing.update(db).await?;
+        }
+        None => {
+            reference::ActiveModel {
+                name: Set(Some(full_ref_name)),
+                kind: Set(reference::ConfigKind::Tag),
+                commit: Set(Some(target.to_string())),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
 /// Finds the raw tag reference row without dereferencing the target object.
 pub async fn find_tag_ref(name: &str) -> Result<Option<TagReference>, DbErr> {
     let db_conn = get_db_conn_instance().await;
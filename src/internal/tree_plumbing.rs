@@ -26,7 +26,11 @@ use git_internal::{
     },
 };
 
-use crate::utils::{tree::sort_tree_items_for_git, util};
+use crate::utils::{
+    index_marks::{self, IndexMark},
+    tree::sort_tree_items_for_git,
+    util,
+};
 
 /// Errors from the index ↔ tree plumbing. Domain-specific so callers can map to
 /// their own error type with `.to_string()` without parsing strings.
@@ -57,13 +61,18 @@ impl From<GitError> for TreePlumbingError {
 /// object id. An empty index yields the canonical empty tree. File modes are
 /// preserved (regular / executable / symlink / gitlink) and the object format
 /// (SHA-1 / SHA-256) follows the process hash kind, since the tree id is derived
-/// from the serialized tree bytes.
+/// from the serialized tree bytes. Intent-to-add entries (`add -N`) are left
+/// out, as Git never writes their placeholder into a tree.
 pub fn write_tree_from_index(index: &Index) -> Result<ObjectHash, TreePlumbingError> {
+    let intent_paths = index_marks::active(index, IndexMark::IntentToAdd);
     let mut leaves = Vec::new();
     for path in index.tracked_files() {
         let key = path
             .to_str()
             .ok_or_else(|| TreePlumbingError::NonUtf8Path(path.display().to_string()))?;
+        if intent_paths.contains(key) {
+            continue;
+        }
         let Some(entry) = index.get(key, 0) else {
             continue;
         };
@@ -20,10 +20,14 @@ use async_trait::async_trait;
 use chrono::Utc;
 use crossterm::event::{KeyCode, KeyModifiers};
 use git_internal::internal::object::context::SelectionStrategy;
+use ratatui::layout::{Constraint, Layout};
 use ring::digest;
 use serde::Deserialize;
 use tokio::{
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    sync::{
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
     task::JoinHandle,
     time::{interval, sleep, timeout},
 };
@@ -35,14 +39,18 @@ use super::{
         TurnInputSource,
     },
     chatwidget::ChatWidget,
+    clipboard,
     control::{CancelSource, TuiControlCommand, TuiControlError},
     diff::FileChange,
+    file_tree::{self, FileTree},
     history_cell::{
         AssistantHistoryCell, DiffHistoryCell, HistoryCell, OrchestratorResultHistoryCell,
         PlanSummaryHistoryCell, PlanUpdateHistoryCell, ThinkingHistoryCell, ToolCallHistoryCell,
         UserHistoryCell,
     },
+    plan_sidebar::PlanSidebar,
     terminal::{TARGET_FRAME_INTERVAL, Tui, TuiEvent},
+    theme::Theme,
     welcome_shader::{self, WelcomeView},
 };
 #[cfg(unix)]
@@ -449,6 +457,13 @@ pub struct AppConfig {
     pub initial_goal: Option<String>,
     /// Source Pool control surface backing `/source` commands.
     pub source_pool: SourcePool,
+    /// Enable Vim-style Normal/Insert keybindings in the input field.
+    pub vim_mode: bool,
+    /// Response rendering theme loaded from `.libra/theme.toml`.
+    pub theme: Arc<Theme>,
+    /// Preamble rebuilt whenever the rule files change on disk
+    /// (see [`SystemPromptBuilder::build_with_watch`]).
+    pub preamble_watch: Option<watch::Receiver<String>>,
 }
 
 /// The main application struct.
@@ -577,6 +592,12 @@ pub struct App<M: CompletionModel> {
     goal_session: Option<super::goal_session::GoalSession>,
     /// Source Pool control state for this TUI session.
     source_pool: SourcePool,
+    /// Left-side working-tree explorer toggled with `Ctrl+B`.
+    file_tree: FileTree,
+    /// Right-side plan checklist toggled with a bare `/plan`.
+    plan_sidebar: PlanSidebar,
+    /// Live preamble updates from the rule file watcher.
+    preamble_watch: Option<watch::Receiver<String>>,
 }
 
 impl<M: CompletionModel + Clone + 'static> App<M>
@@ -624,6 +645,9 @@ where
         widget
             .bottom_pane
             .set_git_branch(current_git_branch_label(registry.working_dir()));
+        widget.bottom_pane.set_vim_mode(app_config.vim_mode);
+        widget.set_theme(app_config.theme);
+        let file_tree = FileTree::new(registry.working_dir().to_path_buf());
         let usage_snapshot = UsageDisplaySnapshot {
             provider: app_config.provider_name.clone(),
             model: app_config.model_name.clone(),
@@ -755,6 +779,9 @@ where
             next_code_ui_item_id: 1,
             goal_session: initial_goal_session,
             source_pool: app_config.source_pool,
+            file_tree,
+            plan_sidebar: PlanSidebar::new(),
+            preamble_watch: app_config.preamble_watch,
         }
     }
 
@@ -863,6 +890,7 @@ where
         let (managed_event_tx, mut managed_event_rx) =
             mpsc::unbounded_channel::<CodeUiEventEnvelope>();
         let mut code_control_rx = self.code_control_rx.take();
+        let mut preamble_watch = self.preamble_watch.take();
         let managed_event_task = self.managed_code_ui_runtime.as_ref().map(|runtime| {
             let mut events = runtime.subscribe();
             tokio::spawn(async move {
@@ -911,6 +939,25 @@ where
                     self.handle_tui_control_command(command).await;
                 }
 
+                // Swap in the rebuilt preamble when rule files change; the
+                // next turn picks it up without restarting the agent loop.
+                changed = async {
+                    match preamble_watch.as_mut() {
+                        Some(rx) => rx.changed().await,
+                        None => std::future::pending().await,
+                    }
+                }, if preamble_watch.is_some() => {
+                    match changed {
+                        Ok(()) => {
+                            if let Some(rx) = preamble_watch.as_mut() {
+                                self.reload_preamble(rx.borrow_and_update().clone());
+                            }
+                        }
+                        // The watcher is gone; keep the current preamble.
+                        Err(_) => preamble_watch = None,
+                    }
+                }
+
                 // Handle user-input requests from the tool handler
                 Some(request) = self.user_input_rx.recv() => {
                     self.drain_pending_app_events().await?;
@@ -925,10 +972,13 @@ where
 
                 // Drive subtle status/tool animations while the agent is active.
                 _ = animation_tick.tick() => {
+                    let status_flash_active = self.widget.bottom_pane.tick_status_flash();
                     if matches!(
                         self.widget.bottom_pane.status,
                         AgentStatus::Thinking | AgentStatus::Retrying | AgentStatus::ExecutingTool
-                    ) || self.welcome_active {
+                    ) || self.welcome_active
+                        || status_flash_active
+                    {
                         self.schedule_draw();
                     }
                 }
@@ -952,6 +1002,17 @@ where
         Ok(exit_info)
     }
 
+    /// Install a preamble rebuilt from edited rule files. A turn already in
+    /// flight keeps the prompt it started with.
+    fn reload_preamble(&mut self, preamble: String) {
+        self.config.preamble = Some(preamble);
+        self.widget
+            .bottom_pane
+            .show_status_flash("[Rules reloaded]");
+        self.schedule_draw();
+        tracing::info!("system prompt rule files changed; preamble reloaded");
+    }
+
     fn begin_turn(&mut self) -> TurnId {
         let turn_id = self.next_turn_id;
         self.next_turn_id = self.next_turn_id.saturating_add(1);
@@ -1621,6 +1682,14 @@ where
             return Ok(());
         }
 
+        if self.widget.bottom_pane.status == AgentStatus::Idle
+            && self.widget.is_searching()
+            && self.handle_search_key(key)
+        {
+            self.schedule_draw();
+            return Ok(());
+        }
+
         // Handle input based on agent status
         match self.widget.bottom_pane.status {
             AgentStatus::Idle => match key.code {
@@ -1647,6 +1716,63 @@ where
                     self.widget.bottom_pane.dismiss_command_popup();
                     self.schedule_draw();
                 }
+                // ── History search (`/` outside the composer) ────────
+                KeyCode::Char('/')
+                    if key.modifiers.difference(KeyModifiers::SHIFT).is_empty()
+                        && (self.widget.bottom_pane.is_vim_normal()
+                            || !self.widget.bottom_pane.focused) =>
+                {
+                    self.widget.start_search();
+                    self.schedule_draw();
+                }
+                // ── File tree panel (Ctrl+B) ─────────────────────────
+                KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.toggle_file_tree().await;
+                    self.schedule_draw();
+                }
+                KeyCode::Up if self.file_tree.is_focused() => {
+                    self.file_tree.select_prev();
+                    self.schedule_draw();
+                }
+                KeyCode::Down if self.file_tree.is_focused() => {
+                    self.file_tree.select_next();
+                    self.schedule_draw();
+                }
+                KeyCode::Left if self.file_tree.is_focused() => {
+                    self.file_tree.collapse_selected();
+                    self.schedule_draw();
+                }
+                KeyCode::Right if self.file_tree.is_focused() => {
+                    self.file_tree.expand_selected();
+                    self.schedule_draw();
+                }
+                KeyCode::Enter if self.file_tree.is_focused() => {
+                    if let Some(path) = self.file_tree.activate_selected() {
+                        self.open_file_tree_entry(&path);
+                    }
+                    self.schedule_draw();
+                }
+                KeyCode::Esc if self.file_tree.is_focused() => {
+                    self.file_tree.set_focused(false);
+                    self.schedule_draw();
+                }
+                // ── Vim keybindings (`libra code --vim`) ─────────────
+                KeyCode::Esc
+                    if self.widget.bottom_pane.vim_mode()
+                        && !self.widget.bottom_pane.is_vim_normal() =>
+                {
+                    self.widget.bottom_pane.enter_normal_mode();
+                    self.schedule_draw();
+                }
+                KeyCode::Char(c)
+                    if self.widget.bottom_pane.is_vim_normal()
+                        && key.modifiers.difference(KeyModifiers::SHIFT).is_empty() =>
+                {
+                    if self.widget.bottom_pane.handle_vim_normal_key(c) {
+                        self.widget.bottom_pane.sync_command_popup();
+                        self.schedule_draw();
+                    }
+                }
                 // ── Normal idle handlers ─────────────────────────────
                 KeyCode::Enter if !self.widget.bottom_pane.is_empty() => {
                     let text = self.widget.bottom_pane.take_input();
@@ -1692,6 +1818,27 @@ where
                     self.widget.scroll_down_lines(10);
                     self.schedule_draw();
                 }
+                // ── History cell focus (Up/Down on an empty composer, `y` to copy) ──
+                KeyCode::Up if self.widget.bottom_pane.is_empty() => {
+                    self.widget.focus_prev_cell();
+                    self.schedule_draw();
+                }
+                KeyCode::Down if self.widget.focused_cell.is_some() => {
+                    self.widget.focus_next_cell();
+                    self.schedule_draw();
+                }
+                KeyCode::Char('y')
+                    if self.widget.focused_cell.is_some()
+                        && self.widget.bottom_pane.is_empty()
+                        && key.modifiers.difference(KeyModifiers::SHIFT).is_empty() =>
+                {
+                    self.copy_focused_cell();
+                    self.schedule_draw();
+                }
+                KeyCode::Esc if self.widget.focused_cell.is_some() => {
+                    self.widget.clear_cell_focus();
+                    self.schedule_draw();
+                }
                 KeyCode::Up => {
                     self.widget.scroll_up_lines(1);
                     self.schedule_draw();
@@ -1711,6 +1858,7 @@ where
                     self.schedule_draw();
                 }
                 KeyCode::Char(c) => {
+                    self.widget.clear_cell_focus();
                     self.widget.bottom_pane.insert_char(c);
                     self.widget.bottom_pane.sync_command_popup();
                     self.schedule_draw();
@@ -2763,10 +2911,116 @@ where
         }
     }
 
+    /// Route a key to the active history search. Returns `false` when the key
+    /// should fall through to normal handling; any such key while browsing
+    /// results ends the search first.
+    fn handle_search_key(&mut self, key: crossterm::event::KeyEvent) -> bool {
+        if key.code == KeyCode::Esc {
+            self.widget.cancel_search();
+            return true;
+        }
+        if self.widget.is_search_editing() {
+            match key.code {
+                KeyCode::Enter => self.widget.finish_search_editing(),
+                KeyCode::Backspace => self.widget.search_backspace(),
+                KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+                    self.widget.search_push_char(c)
+                }
+                _ => return false,
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Char('n') if key.modifiers.is_empty() => self.widget.search_next(),
+            KeyCode::Char('N') if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+                self.widget.search_prev()
+            }
+            KeyCode::Char('/') if key.modifiers.is_empty() => self.widget.start_search(),
+            _ => {
+                self.widget.cancel_search();
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Copy the focused history cell's text to the system clipboard and flash
+    /// `[Copied]` in the status bar.
+    fn copy_focused_cell(&mut self) {
+        let Some(text) = self.widget.focused_cell_text() else {
+            return;
+        };
+        match clipboard::copy_to_clipboard(&text) {
+            Ok(()) => self.widget.bottom_pane.show_status_flash("[Copied]"),
+            Err(error) => self
+                .widget
+                .bottom_pane
+                .show_status_flash(format!("[Copy failed: {error}]")),
+        }
+    }
+
+    /// Render an `update_plan` call that has no cell yet. A follow-up to the
+    /// latest plan (sharing at least one step) updates that checklist in
+    /// place; anything else starts a new plan cell.
+    fn show_plan_update(&mut self, call_id: String, arguments: &serde_json::Value) {
+        let (explanation, steps) = parse_update_plan_arguments(arguments);
+        let latest_plan = self
+            .widget
+            .cells
+            .iter_mut()
+            .rev()
+            .find_map(|cell| cell.as_any_mut().downcast_mut::<PlanUpdateHistoryCell>());
+        if let Some(plan_cell) = latest_plan
+            && !plan_cell.is_running
+            && plan_cell.is_same_plan(&steps)
+        {
+            plan_cell.continue_plan(call_id, explanation, steps);
+            return;
+        }
+        self.insert_before_streaming_assistant(Box::new(PlanUpdateHistoryCell::new(
+            call_id,
+            explanation,
+            steps,
+        )));
+    }
+
+    /// Show (refreshing working-tree status) or hide the file tree panel.
+    async fn toggle_file_tree(&mut self) {
+        self.file_tree.toggle();
+        if !self.file_tree.is_visible() {
+            return;
+        }
+        // Outside a repository (or on a status failure) the tree still lists
+        // files, just without status colours.
+        let staged = crate::command::status::changes_to_be_committed_safe()
+            .await
+            .unwrap_or_default();
+        let unstaged = crate::command::status::changes_to_be_staged().unwrap_or_default();
+        self.file_tree
+            .refresh(file_tree::statuses_from_changes(&staged, &unstaged));
+    }
+
+    /// Hand a file chosen in the tree to the composer as a `read_file` prompt.
+    fn open_file_tree_entry(&mut self, path: &std::path::Path) {
+        self.widget.bottom_pane.clear();
+        self.widget
+            .bottom_pane
+            .insert_text(&format!("read_file {}", path.display()));
+        self.widget.bottom_pane.sync_command_popup();
+        self.widget.bottom_pane.focused = true;
+        self.file_tree.set_focused(false);
+    }
+
     fn handle_mouse_event(&mut self, mouse: crossterm::event::MouseEvent) {
         use crossterm::event::{MouseButton, MouseEventKind};
 
         match mouse.kind {
+            MouseEventKind::ScrollUp if self.plan_sidebar.contains(mouse.column, mouse.row) => {
+                self.plan_sidebar.scroll_up(3);
+            }
+            MouseEventKind::ScrollDown if self.plan_sidebar.contains(mouse.column, mouse.row) => {
+                self.plan_sidebar.scroll_down(3);
+            }
             MouseEventKind::ScrollUp => {
                 self.widget.scroll_up_lines(3);
             }
@@ -2776,6 +3030,13 @@ where
             MouseEventKind::Down(MouseButton::Left) => {
                 let x = mouse.column;
                 let y = mouse.row;
+                if self.file_tree.contains(x, y) {
+                    if let Some(path) = self.file_tree.click(x, y) {
+                        self.open_file_tree_entry(&path);
+                    }
+                    return;
+                }
+                self.file_tree.set_focused(false);
                 self.widget.bottom_pane.focused = self.widget.is_in_input_area(x, y);
             }
             _ => {}
@@ -4138,16 +4399,13 @@ where
                     &tool_name,
                     &arguments,
                 );
+                if tool_name == "update_plan" {
+                    let (explanation, steps) = parse_update_plan_arguments(&arguments);
+                    self.plan_sidebar.set_plan(explanation, steps);
+                }
                 if !already_visible {
                     if tool_name == "update_plan" {
-                        // Parse the plan arguments and render a specialised cell.
-                        let (explanation, steps) = parse_update_plan_arguments(&arguments);
-                        let cell = Box::new(PlanUpdateHistoryCell::new(
-                            call_id.clone(),
-                            explanation,
-                            steps,
-                        ));
-                        self.insert_before_streaming_assistant(cell);
+                        self.show_plan_update(call_id.clone(), &arguments);
                     } else if !append_to_last_tool_group_cell(
                         &mut self.widget.cells,
                         call_id.clone(),
@@ -4208,13 +4466,7 @@ where
                     &arguments,
                 ) {
                     if tool_name == "update_plan" {
-                        let (explanation, steps) = parse_update_plan_arguments(&arguments);
-                        let cell = Box::new(PlanUpdateHistoryCell::new(
-                            call_id.clone(),
-                            explanation,
-                            steps,
-                        ));
-                        self.insert_before_streaming_assistant(cell);
+                        self.show_plan_update(call_id.clone(), &arguments);
                     } else if !append_to_last_tool_group_preview_cell(
                         &mut self.widget.cells,
                         call_id.clone(),
@@ -4841,17 +5093,23 @@ where
         }
 
         // 2. Try YAML-defined slash commands (sent to model).
-        let (effective_text, agent_name) =
-            if let Some(result) = self.command_dispatcher.dispatch(&text) {
-                (result.prompt, result.agent)
-            } else {
-                (text.clone(), None)
-            };
+        let (effective_text, agent_name) = match self.command_dispatcher.dispatch(&text) {
+            Some(Ok(result)) => (result.prompt, result.agent),
+            Some(Err(error)) => {
+                self.widget
+                    .add_cell(Box::new(AssistantHistoryCell::new(error.to_string())));
+                self.schedule_draw();
+                return;
+            }
+            None => (text.clone(), None),
+        };
 
-        // Agent is only selected via slash command, not auto-detected
-        let agent = agent_name
-            .as_deref()
-            .and_then(|name| self.agent_router.get(name));
+        // Agent is only selected via slash command, not auto-detected; without
+        // one, fall back to the profile marked `default: true` (if any).
+        let agent = match agent_name.as_deref() {
+            Some(name) => self.agent_router.get(name),
+            None => self.agent_router.default_agent(),
+        };
 
         let agent_prompt = agent.map(|a| a.system_prompt.clone());
         let allowed_tools = agent.map(|a| a.tools.clone()).filter(|t| !t.is_empty());
@@ -5050,6 +5308,9 @@ where
                             self.schedule_draw();
                         }
                     }
+                } else if args.trim().is_empty() {
+                    self.plan_sidebar.toggle();
+                    self.schedule_draw();
                 } else {
                     self.start_plan_workflow(args).await;
                 }
@@ -7916,7 +8177,21 @@ where
     /// Draw the current frame.
     fn draw(&mut self) -> anyhow::Result<()> {
         self.tui.draw(|frame| {
-            let area = frame.area();
+            let mut area = frame.area();
+            if self.file_tree.is_visible() {
+                let width = FileTree::panel_width(area.width);
+                let [tree_area, rest] =
+                    Layout::horizontal([Constraint::Length(width), Constraint::Min(0)]).areas(area);
+                self.file_tree.render(tree_area, frame.buffer_mut());
+                area = rest;
+            }
+            if self.plan_sidebar.is_visible() {
+                let width = PlanSidebar::panel_width(area.width);
+                let [rest, plan_area] =
+                    Layout::horizontal([Constraint::Min(0), Constraint::Length(width)]).areas(area);
+                self.plan_sidebar.render(plan_area, frame.buffer_mut());
+                area = rest;
+            }
             let cursor_pos = if self.welcome_active {
                 let chat_area = self.widget.chat_area_rect(area);
                 let welcome_view = WelcomeView {
@@ -8994,7 +9269,10 @@ mod tests {
                 CodeUiTranscriptEntryKind, initial_snapshot,
             },
         },
-        tui::history_cell::{AssistantHistoryCell, HistoryCell, ToolCallHistoryCell},
+        tui::{
+            history_cell::{AssistantHistoryCell, HistoryCell, ToolCallHistoryCell},
+            theme::Theme,
+        },
     };
 
     fn make_task(title: &str, kind: TaskKind) -> TaskSpec {
@@ -10141,7 +10419,7 @@ mod tests {
         ));
 
         let rendered = cells[0]
-            .display_lines(100)
+            .display_lines(100, &Theme::default())
             .into_iter()
             .map(|line| line.to_string())
             .collect::<Vec<_>>()
@@ -10234,7 +10512,7 @@ mod tests {
 
         cell.complete_call("1", Err("file not found".to_string()));
 
-        let rendered = cell.display_lines(100);
+        let rendered = cell.display_lines(100, &Theme::default());
         let joined = rendered
             .into_iter()
             .map(|line| line.to_string())
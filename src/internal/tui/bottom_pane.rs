@@ -4,7 +4,8 @@
 
 use std::{
     path::{Path, PathBuf},
-    time::{SystemTime, UNIX_EPOCH},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 // SAFETY: The unwrap() calls in this module are generally safe because:
@@ -17,7 +18,10 @@ use ratatui::{
 };
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use super::{app_event::AgentStatus, theme};
+use super::{
+    app_event::AgentStatus,
+    theme::{self, Theme},
+};
 use crate::internal::ai::{
     sandbox::{ExecApprovalRequest, NetworkAccess},
     tools::context::UserInputQuestion,
@@ -65,6 +69,22 @@ struct CommandPopupState {
 
 const COMMAND_POPUP_MAX_VISIBLE: usize = 8;
 
+/// How long a status-bar flash such as `[Copied]` stays visible.
+const STATUS_FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// Vim-style editing mode of the input field.
+///
+/// Only meaningful when the pane's `vim_mode` is enabled; otherwise the
+/// input is always in [`InputMode::Insert`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputMode {
+    /// Keys are commands (`h`/`l`/`w`/`b`/`0`/`$`/`x`/`dd`/`i`/`a`).
+    Normal,
+    /// Keys insert text; `Esc` returns to [`InputMode::Normal`].
+    #[default]
+    Insert,
+}
+
 /// The bottom pane containing input area and status.
 #[derive(Debug)]
 pub struct BottomPane {
@@ -76,6 +96,12 @@ pub struct BottomPane {
     pub status: AgentStatus,
     /// Whether the input is focused.
     pub focused: bool,
+    /// Whether Vim-style keybindings (`libra code --vim`) are enabled.
+    vim_mode: bool,
+    /// Current Vim editing mode; always `Insert` when `vim_mode` is off.
+    input_mode: InputMode,
+    /// First key of a pending two-key Normal-mode command (e.g. `d` of `dd`).
+    pending_vim_operator: Option<char>,
     /// Snapshot of the current user-input questions (while awaiting input).
     user_input_questions: Option<Vec<UserInputQuestionSnapshot>>,
     /// Index of the question currently being answered (driven by App).
@@ -102,6 +128,8 @@ pub struct BottomPane {
     git_branch: Option<String>,
     /// Current retry notice shown in the status line.
     retry_notice: Option<String>,
+    /// Short-lived status-bar flash (e.g. `[Copied]`) and when it was shown.
+    status_flash: Option<(String, Instant)>,
     /// Optional context label shown on the input box title.
     input_context_label: Option<String>,
     /// Optional placeholder override for local TUI controls.
@@ -120,6 +148,8 @@ pub struct BottomPane {
     /// every Goal mutation without touching the renderer's state
     /// machine.
     goal_status_line: Option<String>,
+    /// Response theme used for the status bar.
+    theme: Arc<Theme>,
 }
 
 impl BottomPane {
@@ -130,6 +160,9 @@ impl BottomPane {
             cursor_pos: 0,
             status: AgentStatus::Idle,
             focused: true,
+            vim_mode: false,
+            input_mode: InputMode::Insert,
+            pending_vim_operator: None,
             user_input_questions: None,
             user_input_current_question: 0,
             user_input_selected_option: 0,
@@ -148,11 +181,13 @@ impl BottomPane {
             cwd: None,
             git_branch: None,
             retry_notice: None,
+            status_flash: None,
             input_context_label: None,
             input_hint: None,
             usage_line: None,
             usage_detail_panel: None,
             goal_status_line: None,
+            theme: Arc::new(Theme::default()),
         }
     }
 
@@ -341,6 +376,116 @@ impl BottomPane {
         self.cursor_pos = self.input.len();
     }
 
+    /// Move cursor to the start of the next word.
+    pub fn cursor_word_forward(&mut self) {
+        let rest = &self.input[self.cursor_pos..];
+        let mut chars = rest.char_indices().peekable();
+        // Skip the remainder of the current word, then the whitespace after it.
+        while let Some((_, c)) = chars.peek().copied() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+        }
+        while let Some((_, c)) = chars.peek().copied() {
+            if !c.is_whitespace() {
+                break;
+            }
+            chars.next();
+        }
+        self.cursor_pos = chars
+            .peek()
+            .map(|(i, _)| self.cursor_pos + i)
+            .unwrap_or(self.input.len());
+    }
+
+    /// Move cursor to the start of the current or previous word.
+    pub fn cursor_word_backward(&mut self) {
+        let before = &self.input[..self.cursor_pos];
+        let mut chars = before.char_indices().rev().peekable();
+        while let Some((_, c)) = chars.peek().copied() {
+            if !c.is_whitespace() {
+                break;
+            }
+            chars.next();
+        }
+        let mut start = 0;
+        for (i, c) in chars {
+            if c.is_whitespace() {
+                break;
+            }
+            start = i;
+        }
+        self.cursor_pos = start;
+    }
+
+    /// Enable or disable Vim-style keybindings. Enabling starts in
+    /// [`InputMode::Insert`] so typing works immediately.
+    pub fn set_vim_mode(&mut self, enabled: bool) {
+        self.vim_mode = enabled;
+        self.input_mode = InputMode::Insert;
+        self.pending_vim_operator = None;
+    }
+
+    /// Whether Vim-style keybindings are enabled.
+    pub fn vim_mode(&self) -> bool {
+        self.vim_mode
+    }
+
+    /// Current Vim editing mode.
+    pub fn input_mode(&self) -> InputMode {
+        self.input_mode
+    }
+
+    /// Whether keys should be interpreted as Vim Normal-mode commands.
+    pub fn is_vim_normal(&self) -> bool {
+        self.vim_mode && self.input_mode == InputMode::Normal
+    }
+
+    /// Leave Insert mode (`Esc`). Like Vim, the cursor steps back onto the
+    /// last inserted character. No-op when Vim mode is disabled.
+    pub fn enter_normal_mode(&mut self) {
+        if !self.vim_mode {
+            return;
+        }
+        if self.input_mode == InputMode::Insert {
+            self.cursor_left();
+        }
+        self.input_mode = InputMode::Normal;
+        self.pending_vim_operator = None;
+    }
+
+    /// Apply a Normal-mode command key. Returns `false` for keys that have
+    /// no Normal-mode meaning so the caller can ignore them.
+    pub fn handle_vim_normal_key(&mut self, c: char) -> bool {
+        if let Some(operator) = self.pending_vim_operator.take() {
+            return match (operator, c) {
+                ('d', 'd') => {
+                    self.clear();
+                    true
+                }
+                _ => false,
+            };
+        }
+        match c {
+            'i' => self.input_mode = InputMode::Insert,
+            'a' => {
+                self.cursor_right();
+                self.input_mode = InputMode::Insert;
+            }
+            'h' => self.cursor_left(),
+            'l' => self.cursor_right(),
+            'w' => self.cursor_word_forward(),
+            'b' => self.cursor_word_backward(),
+            '0' => self.cursor_home(),
+            '$' => self.cursor_end(),
+            'x' => self.delete(),
+            'd' => self.pending_vim_operator = Some('d'),
+            _ => return false,
+        }
+        true
+    }
+
     /// Clear the input.
     pub fn clear(&mut self) {
         self.input.clear();
@@ -362,6 +507,11 @@ impl BottomPane {
         }
     }
 
+    /// Set the response theme used for the status bar.
+    pub fn set_theme(&mut self, theme: Arc<Theme>) {
+        self.theme = theme;
+    }
+
     /// Set the current working directory badge shown on the input border.
     pub fn set_cwd(&mut self, cwd: PathBuf) {
         self.cwd = Some(cwd);
@@ -378,6 +528,24 @@ impl BottomPane {
         self.status = AgentStatus::Retrying;
     }
 
+    /// Flash `text` at the end of the status line for [`STATUS_FLASH_DURATION`].
+    pub fn show_status_flash(&mut self, text: impl Into<String>) {
+        self.status_flash = Some((text.into(), Instant::now()));
+    }
+
+    /// Expire the status flash once its duration has elapsed. Returns `true`
+    /// while a redraw is needed (the flash is visible or has just expired).
+    pub fn tick_status_flash(&mut self) -> bool {
+        match &self.status_flash {
+            Some((_, shown_at)) if shown_at.elapsed() >= STATUS_FLASH_DURATION => {
+                self.status_flash = None;
+                true
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
     /// Set or clear a contextual title for the shared input box.
     pub fn set_input_context_label(&mut self, label: Option<String>) {
         self.input_context_label = label;
@@ -1240,7 +1408,24 @@ impl BottomPane {
                 theme::status::pending_choice(),
             ),
         };
-        Paragraph::new(status_line).render(area, buf);
+        let mut status_line = status_line;
+        if self.vim_mode {
+            let label = match self.input_mode {
+                InputMode::Normal => "-- NORMAL -- ",
+                InputMode::Insert => "-- INSERT -- ",
+            };
+            status_line
+                .spans
+                .insert(0, Span::styled(label, theme::status::pending_choice()));
+        }
+        if let Some((flash, _)) = &self.status_flash {
+            status_line
+                .spans
+                .push(Span::styled(format!("  {flash}"), theme::status::success()));
+        }
+        Paragraph::new(status_line)
+            .style(theme::status::bar(&self.theme))
+            .render(area, buf);
     }
 
     fn render_input_area(&self, area: Rect, buf: &mut Buffer) -> Option<Position> {
@@ -1574,7 +1759,7 @@ mod tests {
 
     use ratatui::{buffer::Buffer, layout::Rect};
 
-    use super::BottomPane;
+    use super::{BottomPane, InputMode};
     use crate::internal::{
         ai::sandbox::NetworkAccess,
         tui::{app_event::AgentStatus, theme},
@@ -1592,6 +1777,58 @@ mod tests {
         (0..width).find(|&x| buf[(x, y)].symbol() == symbol)
     }
 
+    #[test]
+    fn vim_normal_mode_motions_and_edits() {
+        let mut pane = BottomPane::new();
+        pane.set_vim_mode(true);
+        pane.insert_text("hello big world");
+        pane.enter_normal_mode();
+        assert_eq!(pane.input_mode(), InputMode::Normal);
+        assert_eq!(pane.cursor_pos, "hello big worl".len());
+
+        assert!(pane.handle_vim_normal_key('0'));
+        assert_eq!(pane.cursor_pos, 0);
+        pane.handle_vim_normal_key('w');
+        assert_eq!(pane.cursor_pos, "hello ".len());
+        pane.handle_vim_normal_key('w');
+        assert_eq!(pane.cursor_pos, "hello big ".len());
+        pane.handle_vim_normal_key('b');
+        assert_eq!(pane.cursor_pos, "hello ".len());
+        pane.handle_vim_normal_key('l');
+        pane.handle_vim_normal_key('h');
+        pane.handle_vim_normal_key('x');
+        assert_eq!(pane.input, "hello ig world");
+        pane.handle_vim_normal_key('$');
+        assert_eq!(pane.cursor_pos, pane.input.len());
+
+        pane.handle_vim_normal_key('0');
+        pane.handle_vim_normal_key('a');
+        assert_eq!(pane.input_mode(), InputMode::Insert);
+        assert_eq!(pane.cursor_pos, 1);
+
+        pane.enter_normal_mode();
+        assert!(pane.handle_vim_normal_key('d'));
+        assert_eq!(pane.input, "hello ig world");
+        assert!(pane.handle_vim_normal_key('d'));
+        assert!(pane.input.is_empty());
+        assert!(!pane.handle_vim_normal_key('z'));
+    }
+
+    #[test]
+    fn vim_mode_indicator_renders_in_status_bar() {
+        let mut pane = BottomPane::new();
+        pane.set_vim_mode(true);
+        pane.enter_normal_mode();
+        let area = Rect::new(0, 0, 80, pane.desired_height());
+        let mut buf = Buffer::empty(area);
+        let _ = pane.render(area, &mut buf);
+        let rendered = (0..area.height)
+            .map(|y| row_text(&buf, y, area.width))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(rendered.contains("-- NORMAL --"), "{rendered}");
+    }
+
     #[test]
     fn normal_mode_height_is_six_lines() {
         let pane = BottomPane::new();
@@ -4,6 +4,7 @@
 
 use std::{
     path::PathBuf,
+    sync::Arc,
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
@@ -16,7 +17,7 @@ use uuid::Uuid;
 use super::{
     bottom_pane::BottomPane,
     history_cell::{AssistantHistoryCell, HistoryCell, ThinkingHistoryCell, ToolCallHistoryCell},
-    theme,
+    theme::{self, Theme},
 };
 use crate::internal::ai::orchestrator::types::{
     ExecutionPlanSpec, TaskKind, TaskNodeStatus, TaskRuntimeEvent, TaskRuntimeNoteLevel,
@@ -572,6 +573,18 @@ pub struct ChatWidget {
     last_input_area: Option<Rect>,
     /// Last rendered chat area width used to estimate added line count.
     last_chat_area_width: u16,
+    /// History search query; `Some` while search mode (`/`) is active.
+    pub search_query: Option<String>,
+    /// Indices into `cells` whose text contains `search_query`.
+    pub match_indices: Vec<usize>,
+    /// Position within `match_indices` of the current match (`n`/`N`).
+    search_cursor: usize,
+    /// Whether keystrokes still edit the query (before `Enter`).
+    search_editing: bool,
+    /// Index into `cells` of the cell selected with `Up`/`Down` (for `y`).
+    pub focused_cell: Option<usize>,
+    /// Response theme handed to the history cells when they render.
+    theme: Arc<Theme>,
 }
 
 impl ChatWidget {
@@ -586,15 +599,27 @@ impl ChatWidget {
             bottom_pane: BottomPane::new(),
             last_input_area: None,
             last_chat_area_width: 80,
+            search_query: None,
+            match_indices: Vec::new(),
+            search_cursor: 0,
+            search_editing: false,
+            focused_cell: None,
+            theme: Arc::new(Theme::default()),
         }
     }
 
+    /// Set the response theme used by the history cells and the status bar.
+    pub fn set_theme(&mut self, theme: Arc<Theme>) {
+        self.bottom_pane.set_theme(Arc::clone(&theme));
+        self.theme = theme;
+    }
+
     /// Add a cell to the history.
     pub fn add_cell(&mut self, cell: Box<dyn HistoryCell>) {
         if self.scroll_from_bottom_lines > 0 {
-            self.scroll_from_bottom_lines = self
-                .scroll_from_bottom_lines
-                .saturating_add(cell.desired_height(self.last_chat_area_width) as usize);
+            self.scroll_from_bottom_lines = self.scroll_from_bottom_lines.saturating_add(
+                cell.desired_height(self.last_chat_area_width, &self.theme) as usize,
+            );
         }
         self.cells.push(cell);
     }
@@ -602,9 +627,14 @@ impl ChatWidget {
     /// Insert a cell at a specific index.
     pub fn insert_cell(&mut self, index: usize, cell: Box<dyn HistoryCell>) {
         if self.scroll_from_bottom_lines > 0 {
-            self.scroll_from_bottom_lines = self
-                .scroll_from_bottom_lines
-                .saturating_add(cell.desired_height(self.last_chat_area_width) as usize);
+            self.scroll_from_bottom_lines = self.scroll_from_bottom_lines.saturating_add(
+                cell.desired_height(self.last_chat_area_width, &self.theme) as usize,
+            );
+        }
+        if let Some(focused) = self.focused_cell.as_mut()
+            && *focused >= index
+        {
+            *focused += 1;
         }
         self.cells.insert(index, cell);
     }
@@ -635,6 +665,126 @@ impl ChatWidget {
         self.dag_panel = None;
         self.task_mux = None;
         self.scroll_from_bottom_lines = 0;
+        self.search_query = None;
+        self.search_editing = false;
+        self.match_indices.clear();
+        self.search_cursor = 0;
+        self.focused_cell = None;
+    }
+
+    /// Move cell focus one cell up (older), starting from the newest cell.
+    pub fn focus_prev_cell(&mut self) {
+        if self.cells.is_empty() {
+            return;
+        }
+        self.focused_cell = Some(match self.focused_cell {
+            Some(index) => index.saturating_sub(1),
+            None => self.cells.len() - 1,
+        });
+    }
+
+    /// Move cell focus one cell down (newer); past the newest cell, focus is
+    /// released and the view returns to the bottom.
+    pub fn focus_next_cell(&mut self) {
+        match self.focused_cell {
+            Some(index) if index + 1 < self.cells.len() => self.focused_cell = Some(index + 1),
+            Some(_) => {
+                self.focused_cell = None;
+                self.scroll_to_bottom();
+            }
+            None => {}
+        }
+    }
+
+    pub fn clear_cell_focus(&mut self) {
+        self.focused_cell = None;
+    }
+
+    /// Plain-text content of the focused cell, if any.
+    pub fn focused_cell_text(&self) -> Option<String> {
+        self.focused_cell
+            .and_then(|index| self.cells.get(index))
+            .map(|cell| cell.text_content())
+    }
+
+    /// Enter history search mode with an empty query.
+    pub fn start_search(&mut self) {
+        self.search_query = Some(String::new());
+        self.search_editing = true;
+        self.update_search_matches();
+    }
+
+    /// Leave search mode and restore the full history.
+    pub fn cancel_search(&mut self) {
+        self.search_query = None;
+        self.search_editing = false;
+        self.match_indices.clear();
+        self.search_cursor = 0;
+        self.scroll_to_bottom();
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search_query.is_some()
+    }
+
+    /// Whether typed characters currently extend the search query.
+    pub fn is_search_editing(&self) -> bool {
+        self.search_query.is_some() && self.search_editing
+    }
+
+    /// Stop editing the query and switch to `n`/`N` navigation.
+    pub fn finish_search_editing(&mut self) {
+        self.search_editing = false;
+    }
+
+    pub fn search_push_char(&mut self, c: char) {
+        if let Some(query) = self.search_query.as_mut() {
+            query.push(c);
+            self.update_search_matches();
+        }
+    }
+
+    pub fn search_backspace(&mut self) {
+        if let Some(query) = self.search_query.as_mut() {
+            query.pop();
+            self.update_search_matches();
+        }
+    }
+
+    /// Jump to the next (older → newer) match, wrapping around.
+    pub fn search_next(&mut self) {
+        if !self.match_indices.is_empty() {
+            self.search_cursor = (self.search_cursor + 1) % self.match_indices.len();
+        }
+    }
+
+    /// Jump to the previous match, wrapping around.
+    pub fn search_prev(&mut self) {
+        if !self.match_indices.is_empty() {
+            let len = self.match_indices.len();
+            self.search_cursor = (self.search_cursor + len - 1) % len;
+        }
+    }
+
+    /// Recompute `match_indices` for the current query, selecting the most
+    /// recent match.
+    fn update_search_matches(&mut self) {
+        self.match_indices.clear();
+        if let Some(query) = self.search_query.as_deref().filter(|q| !q.is_empty()) {
+            let width = self.last_chat_area_width;
+            self.match_indices = self
+                .cells
+                .iter()
+                .enumerate()
+                .filter(|(_, cell)| {
+                    cell.display_lines(width, &self.theme)
+                        .iter()
+                        .any(|line| !find_match_ranges(&line_text(line), query).is_empty())
+                })
+                .map(|(index, _)| index)
+                .collect();
+        }
+        self.search_cursor = self.match_indices.len().saturating_sub(1);
     }
 
     pub fn set_usage_header(&mut self, usage_header: Option<String>) {
@@ -939,11 +1089,25 @@ impl ChatWidget {
     }
 
     fn render_history_cells(&mut self, area: Rect, buf: &mut Buffer) {
+        if self.search_query.is_some() {
+            self.render_search_results(area, buf);
+            return;
+        }
+
         // Calculate visible lines.
         let mut lines: Vec<Line<'static>> = Vec::new();
-
-        for cell in &self.cells {
-            lines.extend(cell.display_lines(area.width));
+        let mut focused_range = None;
+
+        for (index, cell) in self.cells.iter().enumerate() {
+            let cell_lines = cell.display_lines(area.width, &self.theme);
+            if self.focused_cell == Some(index) {
+                let start = lines.len();
+                let style = theme::history::focused_cell();
+                lines.extend(cell_lines.into_iter().map(|line| line.patch_style(style)));
+                focused_range = Some(start..lines.len());
+            } else {
+                lines.extend(cell_lines);
+            }
         }
 
         let visible_lines = area.height as usize;
@@ -952,6 +1116,21 @@ impl ChatWidget {
         let max_scroll_from_bottom = total_lines.saturating_sub(visible_lines);
         self.scroll_from_bottom_lines = self.scroll_from_bottom_lines.min(max_scroll_from_bottom);
 
+        // Keep the focused cell in view, preferring its first line when it is
+        // taller than the viewport.
+        if let Some(range) = focused_range {
+            let start = max_scroll_from_bottom - self.scroll_from_bottom_lines;
+            let target = if range.start < start {
+                range.start
+            } else if range.end > start + visible_lines {
+                range.end.saturating_sub(visible_lines).min(range.start)
+            } else {
+                start
+            };
+            self.scroll_from_bottom_lines =
+                max_scroll_from_bottom - target.min(max_scroll_from_bottom);
+        }
+
         let start_line = total_lines
             .saturating_sub(visible_lines)
             .saturating_sub(self.scroll_from_bottom_lines);
@@ -973,6 +1152,66 @@ impl ChatWidget {
         }
     }
 
+    /// Render the search bar plus only the matching cells, with every
+    /// occurrence highlighted and the current match scrolled to the top.
+    fn render_search_results(&mut self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let query = self.search_query.clone().unwrap_or_default();
+        let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(area);
+
+        let position = if self.match_indices.is_empty() {
+            "no matches".to_string()
+        } else {
+            format!("{}/{}", self.search_cursor + 1, self.match_indices.len())
+        };
+        let help = if self.search_editing {
+            "Enter: done · Esc: cancel"
+        } else {
+            "n/N: next/prev · Esc: cancel"
+        };
+        Paragraph::new(Line::from(vec![
+            Span::styled(format!("/{query}"), theme::interactive::accent()),
+            Span::styled(format!("  [{position}]  "), theme::text::muted()),
+            Span::styled(help, theme::text::help()),
+        ]))
+        .render(chunks[0], buf);
+
+        if query.is_empty() {
+            let mut lines: Vec<Line<'static>> = Vec::new();
+            for cell in &self.cells {
+                lines.extend(cell.display_lines(chunks[1].width, &self.theme));
+            }
+            let start = lines.len().saturating_sub(chunks[1].height as usize);
+            Paragraph::new(Text::from(lines))
+                .scroll((start.min(u16::MAX as usize) as u16, 0))
+                .render(chunks[1], buf);
+            return;
+        }
+
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        let mut current_start = 0;
+        for (position, &index) in self.match_indices.iter().enumerate() {
+            let Some(cell) = self.cells.get(index) else {
+                continue;
+            };
+            if position == self.search_cursor {
+                current_start = lines.len();
+            }
+            lines.extend(
+                cell.display_lines(chunks[1].width, &self.theme)
+                    .into_iter()
+                    .map(|line| highlight_matches(line, &query)),
+            );
+        }
+        let max_start = lines.len().saturating_sub(chunks[1].height as usize);
+        let start = current_start.min(max_start);
+        Paragraph::new(Text::from(lines))
+            .scroll((start.min(u16::MAX as usize) as u16, 0))
+            .render(chunks[1], buf);
+    }
+
     fn render_task_mux(&self, area: Rect, buf: &mut Buffer) {
         let Some(task_mux) = self.task_mux.as_ref() else {
             return;
@@ -1295,8 +1534,12 @@ impl ChatWidget {
 
         let available_log_lines = inner.height.saturating_sub(lines.len() as u16) as usize;
         if available_log_lines > 0 {
-            let rendered_logs =
-                render_task_transcript_lines(&task.transcript, inner.width, available_log_lines);
+            let rendered_logs = render_task_transcript_lines(
+                &task.transcript,
+                inner.width,
+                available_log_lines,
+                &self.theme,
+            );
             lines.extend(rendered_logs);
         }
 
@@ -2029,6 +2272,7 @@ fn render_task_transcript_lines(
     transcript: &[TaskMuxTranscriptEntry],
     width: u16,
     max_lines: usize,
+    theme: &Theme,
 ) -> Vec<Line<'static>> {
     if transcript.is_empty() || width == 0 || max_lines == 0 {
         return vec![Line::styled("waiting for activity", theme::text::subtle())];
@@ -2045,13 +2289,13 @@ fn render_task_transcript_lines(
                 all_lines.extend(wrap_mux_text(&entry.text, prefix, width, style));
             }
             TaskMuxTranscriptEntry::Thinking(cell) => {
-                all_lines.extend(cell.display_lines(width));
+                all_lines.extend(cell.display_lines(width, theme));
             }
             TaskMuxTranscriptEntry::Assistant(cell) => {
-                all_lines.extend(cell.display_lines(width));
+                all_lines.extend(cell.display_lines(width, theme));
             }
             TaskMuxTranscriptEntry::Tool(cell) => {
-                all_lines.extend(cell.display_lines(width));
+                all_lines.extend(cell.display_lines(width, theme));
             }
         }
     }
@@ -2188,6 +2432,77 @@ fn truncate_label(text: &str, max_chars: usize) -> String {
     truncated
 }
 
+fn line_text(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Byte ranges of `query` in `text`. Case-insensitive when lowercasing keeps
+/// byte offsets stable (always true for ASCII), case-sensitive otherwise.
+fn find_match_ranges(text: &str, query: &str) -> Vec<std::ops::Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let lowered_text = text.to_lowercase();
+    let lowered_query = query.to_lowercase();
+    let (haystack, needle) =
+        if lowered_text.len() == text.len() && lowered_query.len() == query.len() {
+            (lowered_text.as_str(), lowered_query.as_str())
+        } else {
+            (text, query)
+        };
+    haystack
+        .match_indices(needle)
+        .map(|(start, m)| start..start + m.len())
+        .collect()
+}
+
+/// Re-split `line`'s spans so every occurrence of `query` carries the search
+/// highlight on top of the span's own style.
+fn highlight_matches(line: Line<'static>, query: &str) -> Line<'static> {
+    let ranges = find_match_ranges(&line_text(&line), query);
+    if ranges.is_empty() {
+        return line;
+    }
+    let style = line.style;
+    let alignment = line.alignment;
+    let mut out: Vec<Span<'static>> = Vec::new();
+    let mut offset = 0;
+    for span in line.spans {
+        let content = span.content.as_ref();
+        let start = offset;
+        let end = offset + content.len();
+        offset = end;
+        let mut cursor = start;
+        for range in ranges.iter().filter(|r| r.start < end && r.end > start) {
+            let from = range.start.max(start);
+            let to = range.end.min(end);
+            if from > cursor {
+                out.push(Span::styled(
+                    content[cursor - start..from - start].to_string(),
+                    span.style,
+                ));
+            }
+            out.push(Span::styled(
+                content[from - start..to - start].to_string(),
+                span.style.patch(theme::search::highlight()),
+            ));
+            cursor = to;
+        }
+        if cursor < end {
+            out.push(Span::styled(
+                content[cursor - start..].to_string(),
+                span.style,
+            ));
+        }
+    }
+    let mut highlighted = Line::from(out).style(style);
+    highlighted.alignment = alignment;
+    highlighted
+}
+
 impl Default for ChatWidget {
     fn default() -> Self {
         Self::new()
@@ -2211,6 +2526,7 @@ mod tests {
         },
         tui::{
             history_cell::{AssistantHistoryCell, ThinkingHistoryCell},
+            theme::{self, Theme},
             welcome_shader,
         },
     };
@@ -2289,6 +2605,63 @@ mod tests {
         assert!(rendered.contains("Ready"));
     }
 
+    #[test]
+    fn history_search_filters_cells_and_cycles_matches() {
+        let mut widget = ChatWidget::new();
+        for text in ["alpha build", "beta test", "gamma BUILD"] {
+            widget.add_cell(Box::new(AssistantHistoryCell::new(text.to_string())));
+        }
+
+        widget.start_search();
+        for c in "build".chars() {
+            widget.search_push_char(c);
+        }
+        assert_eq!(
+            widget.match_indices,
+            vec![0, 2],
+            "matching is case-insensitive"
+        );
+        assert_eq!(widget.search_cursor, 1, "starts on the most recent match");
+        widget.search_next();
+        assert_eq!(widget.search_cursor, 0, "n wraps around");
+        widget.search_prev();
+        assert_eq!(widget.search_cursor, 1);
+
+        let area = Rect::new(0, 0, 60, 8);
+        let mut buf = Buffer::empty(area);
+        widget.render_chat_area(area, &mut buf);
+        let rendered = (0..area.height)
+            .map(|y| row_text(&buf, y, area.width))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(rendered.contains("/build"));
+        assert!(rendered.contains("gamma BUILD"));
+        assert!(
+            !rendered.contains("beta test"),
+            "non-matching cells are hidden"
+        );
+        let highlighted = (0..area.height).any(|y| {
+            (0..area.width).any(|x| {
+                buf[(x, y)].symbol() == "B"
+                    && buf[(x, y)].bg == theme::search::highlight().bg.unwrap()
+            })
+        });
+        assert!(highlighted, "matches carry the search highlight");
+
+        widget.cancel_search();
+        assert!(widget.search_query.is_none());
+        let mut buf = Buffer::empty(area);
+        widget.render_chat_area(area, &mut buf);
+        let rendered = (0..area.height)
+            .map(|y| row_text(&buf, y, area.width))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(
+            rendered.contains("beta test"),
+            "Esc restores the full history"
+        );
+    }
+
     #[test]
     fn chat_history_scroll_changes_visible_window() {
         let mut widget = ChatWidget::new();
@@ -2532,7 +2905,7 @@ mod tests {
         cell.append("checking the failed plan step");
         let transcript = vec![TaskMuxTranscriptEntry::Thinking(cell)];
 
-        let rendered = render_task_transcript_lines(&transcript, 80, 10)
+        let rendered = render_task_transcript_lines(&transcript, 80, 10, &Theme::default())
             .into_iter()
             .map(|line| line.to_string())
             .collect::<Vec<_>>()
@@ -2990,4 +3363,23 @@ mod tests {
             "assistant cell text must appear in the rendered buffer;\nrendered:\n{rendered}",
         );
     }
+
+    #[test]
+    fn cell_focus_walks_history_and_exposes_focused_text() {
+        let mut widget = ChatWidget::new();
+        widget.add_cell(Box::new(AssistantHistoryCell::new("first".to_string())));
+        widget.add_cell(Box::new(AssistantHistoryCell::new("second".to_string())));
+        assert_eq!(widget.focused_cell_text(), None);
+
+        widget.focus_prev_cell();
+        assert_eq!(widget.focused_cell_text().as_deref(), Some("second"));
+        widget.focus_prev_cell();
+        widget.focus_prev_cell();
+        assert_eq!(widget.focused_cell_text().as_deref(), Some("first"));
+
+        widget.focus_next_cell();
+        assert_eq!(widget.focused_cell_text().as_deref(), Some("second"));
+        widget.focus_next_cell();
+        assert_eq!(widget.focused_cell, None);
+    }
 }
@@ -0,0 +1,48 @@
+//! Copy text to the system clipboard from inside the TUI.
+//!
+//! Uses the OSC 52 terminal escape (`ESC ] 52 ; c ; <base64> BEL`), which the
+//! terminal emulator turns into a clipboard write. Unlike a native clipboard
+//! crate this needs no extra dependency or display-server connection and also
+//! works over SSH; terminals that do not support OSC 52 silently ignore it.
+//! Inside tmux the sequence is wrapped in a DCS passthrough so it reaches the
+//! outer terminal.
+
+use std::io::{self, Write};
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+/// Write `text` to the system clipboard via OSC 52 on stdout.
+pub(crate) fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let sequence = osc52_sequence(text, std::env::var_os("TMUX").is_some());
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn osc52_sequence(text: &str, in_tmux: bool) -> String {
+    let osc = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if in_tmux {
+        // tmux passthrough: escape every ESC inside the payload by doubling it.
+        format!("\x1bPtmux;{}\x1b\\", osc.replace('\x1b', "\x1b\x1b"))
+    } else {
+        osc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc52_sequence_encodes_text_as_base64() {
+        assert_eq!(osc52_sequence("hi", false), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn osc52_sequence_wraps_for_tmux_passthrough() {
+        assert_eq!(
+            osc52_sequence("hi", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Syntax-highlighted rendering of fenced code blocks in the TUI transcript.
+//!
+//! The language is taken from the opening fence (```` ```rust ````) and
+//! resolved against syntect's bundled syntax definitions. Unknown languages
+//! return `None` from [`CodeBlockWidget::highlighted_lines`] so the Markdown
+//! renderer falls back to its plain code-block style.
+//!
+//! The colour theme is process-global: `libra code` reads
+//! [`HIGHLIGHT_THEME_CONFIG_KEY`] from the repository config at startup and
+//! installs it with [`set_highlight_theme`]; [`DEFAULT_HIGHLIGHT_THEME`] is
+//! used otherwise.
+
+use std::sync::{OnceLock, RwLock};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Theme, ThemeSet},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+use super::theme;
+
+/// Theme used when `code.highlightTheme` is unset or names an unknown theme.
+pub const DEFAULT_HIGHLIGHT_THEME: &str = "base16-ocean.dark";
+
+/// Config key (`libra config code.highlightTheme <name>`) selecting the theme.
+pub const HIGHLIGHT_THEME_CONFIG_KEY: &str = "code.highlightTheme";
+
+static ACTIVE_THEME: RwLock<Option<String>> = RwLock::new(None);
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Select the highlighting theme by name. Returns `false` (leaving the
+/// current theme in place) when no bundled theme has that name.
+pub fn set_highlight_theme(name: &str) -> bool {
+    if !theme_set().themes.contains_key(name) {
+        return false;
+    }
+    if let Ok(mut active) = ACTIVE_THEME.write() {
+        *active = Some(name.to_string());
+    }
+    true
+}
+
+fn active_theme() -> Option<&'static Theme> {
+    let themes = &theme_set().themes;
+    let selected = ACTIVE_THEME
+        .read()
+        .ok()
+        .and_then(|active| active.clone())
+        .unwrap_or_else(|| DEFAULT_HIGHLIGHT_THEME.to_string());
+    themes
+        .get(&selected)
+        .or_else(|| themes.get(DEFAULT_HIGHLIGHT_THEME))
+}
+
+/// Resolve a fence info string (`rust`, `rs`, `rust,ignore`, `python title="x"`)
+/// to a syntect syntax definition.
+fn find_syntax(lang: &str) -> Option<&'static SyntaxReference> {
+    let token = lang
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .trim();
+    if token.is_empty() {
+        return None;
+    }
+    syntax_set().find_syntax_by_token(token)
+}
+
+fn convert_style(style: syntect::highlighting::Style, response_theme: &theme::Theme) -> Style {
+    let fg = style.foreground;
+    let mut converted =
+        theme::markdown::code_block_background(response_theme).fg(Color::Rgb(fg.r, fg.g, fg.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        converted = converted.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        converted = converted.add_modifier(Modifier::UNDERLINED);
+    }
+    converted
+}
+
+/// A fenced code block rendered with syntax highlighting. The response theme
+/// supplies the block background and the plain fallback style.
+pub struct CodeBlockWidget<'a> {
+    lang: &'a str,
+    code: &'a str,
+    response_theme: &'a theme::Theme,
+}
+
+impl<'a> CodeBlockWidget<'a> {
+    pub fn new(lang: &'a str, code: &'a str, response_theme: &'a theme::Theme) -> Self {
+        Self {
+            lang,
+            code,
+            response_theme,
+        }
+    }
+
+    /// Whether the fence language maps to a known syntax.
+    pub fn is_supported(&self) -> bool {
+        find_syntax(self.lang).is_some()
+    }
+
+    /// Highlighted spans, one entry per `\n`-separated source line (a
+    /// trailing newline yields a trailing empty entry, like `str::split`).
+    /// Returns `None` when the language is unknown.
+    pub fn highlighted_lines(&self) -> Option<Vec<Vec<Span<'static>>>> {
+        let syntax = find_syntax(self.lang)?;
+        let theme = active_theme()?;
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut lines = Vec::new();
+        for line in self.code.split('\n') {
+            // The newline-aware syntax set expects each line to carry its
+            // terminator so multi-line constructs keep their parse state.
+            let source = format!("{line}\n");
+            let spans = match highlighter.highlight_line(&source, syntax_set()) {
+                Ok(ranges) => ranges
+                    .into_iter()
+                    .filter_map(|(style, text)| {
+                        let text = text.trim_end_matches(['\n', '\r']);
+                        (!text.is_empty()).then(|| {
+                            Span::styled(
+                                text.to_string(),
+                                convert_style(style, self.response_theme),
+                            )
+                        })
+                    })
+                    .collect(),
+                Err(_) => vec![Span::styled(
+                    line.to_string(),
+                    theme::markdown::code_block(self.response_theme),
+                )],
+            };
+            lines.push(spans);
+        }
+        Some(lines)
+    }
+
+    /// Render-ready lines; falls back to the plain code-block style when the
+    /// language is unknown.
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        match self.highlighted_lines() {
+            Some(lines) => lines.into_iter().map(Line::from).collect(),
+            None => self
+                .code
+                .split('\n')
+                .map(|line| {
+                    Line::styled(
+                        line.to_string(),
+                        theme::markdown::code_block(self.response_theme),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Widget for CodeBlockWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(self.lines()).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal::tui::theme::Theme as ResponseTheme;
+
+    #[test]
+    fn known_language_produces_distinct_colours() {
+        let widget = CodeBlockWidget::new("rust", "fn main() {}\n", &ResponseTheme::default());
+        let lines = widget
+            .highlighted_lines()
+            .expect("rust is a bundled syntax");
+        assert_eq!(lines.len(), 2, "trailing newline keeps a trailing entry");
+        let text: String = lines[0].iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "fn main() {}");
+        let colours: std::collections::HashSet<_> =
+            lines[0].iter().filter_map(|span| span.style.fg).collect();
+        assert!(
+            colours.len() > 1,
+            "keywords and identifiers should be coloured differently"
+        );
+    }
+
+    #[test]
+    fn fence_info_string_uses_first_token() {
+        assert!(
+            CodeBlockWidget::new("rust,ignore", "let x = 1;", &ResponseTheme::default())
+                .is_supported()
+        );
+        assert!(
+            CodeBlockWidget::new("py title=\"x\"", "x = 1", &ResponseTheme::default())
+                .is_supported()
+        );
+    }
+
+    #[test]
+    fn unknown_language_falls_back_to_plain_lines() {
+        let widget = CodeBlockWidget::new(
+            "definitely-not-a-language",
+            "a\nb",
+            &ResponseTheme::default(),
+        );
+        assert!(widget.highlighted_lines().is_none());
+        let lines = widget.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].spans[0].content, "b");
+    }
+
+    #[test]
+    fn response_theme_sets_the_block_background() {
+        let nord = ResponseTheme::builtin("nord").unwrap();
+        let highlighted = CodeBlockWidget::new("rust", "let x = 1;", &nord)
+            .highlighted_lines()
+            .expect("rust is a bundled syntax");
+        assert!(
+            highlighted[0]
+                .iter()
+                .all(|span| span.style.bg == Some(nord.code_block_bg))
+        );
+        let plain = CodeBlockWidget::new("definitely-not-a-language", "a", &nord).lines();
+        assert_eq!(plain[0].style.bg, Some(nord.code_block_bg));
+    }
+
+    #[test]
+    fn unknown_theme_name_is_rejected() {
+        assert!(!set_highlight_theme("no-such-theme"));
+    }
+}
@@ -28,7 +28,7 @@ use ratatui::{
     text::{Line, Span},
 };
 
-use super::theme;
+use super::theme::{self, Theme};
 
 /// File change type for diff display.
 ///
@@ -88,6 +88,7 @@ enum DiffLineType {
 ///   subtracts indents and gutters internally before splitting long lines.
 /// - The output is `Vec<Line<'static>>` because callers cache it inside
 ///   history cells that outlive the originating diff buffers.
+/// - Added/removed colours come from `theme`.
 ///
 /// See: [`tests::test_create_diff_summary_single_file`],
 /// [`tests::test_create_diff_summary_update`], [`tests::test_multiple_files`].
@@ -95,9 +96,46 @@ pub fn create_diff_summary(
     changes: &HashMap<PathBuf, FileChange>,
     cwd: &Path,
     wrap_cols: usize,
+    theme: &Theme,
 ) -> Vec<Line<'static>> {
     let rows = collect_rows(changes);
-    render_changes_block(rows, wrap_cols, cwd)
+    render_changes_block(rows, wrap_cols, cwd, theme)
+}
+
+/// Plain-text unified diff for every change, ordered by path — what the TUI
+/// copies to the clipboard for a diff cell.
+///
+/// Each file gets `--- a/<path>` / `+++ b/<path>` headers (`/dev/null` for the
+/// missing side of an add or delete), replacing any headers already present
+/// in the stored diff, followed by its hunks verbatim.
+pub fn unified_diff_text(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> String {
+    let mut out = String::new();
+    for row in collect_rows(changes) {
+        let old_path = format!("a/{}", display_path_for(&row.path, cwd));
+        let new_path = format!(
+            "b/{}",
+            display_path_for(row.move_path.as_ref().unwrap_or(&row.path), cwd)
+        );
+        let (old_header, new_header, unified_diff) = match &row.change {
+            FileChange::Add { unified_diff } => ("/dev/null".to_string(), new_path, unified_diff),
+            FileChange::Delete { unified_diff } => {
+                (old_path, "/dev/null".to_string(), unified_diff)
+            }
+            FileChange::Update { unified_diff, .. } => (old_path, new_path, unified_diff),
+        };
+        out.push_str(&format!("--- {old_header}\n+++ {new_header}\n"));
+        let mut body = unified_diff.as_str();
+        for prefix in ["--- ", "+++ "] {
+            if body.starts_with(prefix) {
+                body = body.split_once('\n').map_or("", |(_, rest)| rest);
+            }
+        }
+        out.push_str(body);
+        if !body.is_empty() && !body.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
 }
 
 // Shared row for per-file presentation. `Row` aggregates the path, optional
@@ -175,7 +213,12 @@ fn render_line_count_summary_text(added: usize, removed: usize) -> String {
 ///
 /// Boundary conditions: diff bodies are wrapped to `wrap_cols - indent` so
 /// continuation lines align with the gutter rather than overflowing.
-fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<Line<'static>> {
+fn render_changes_block(
+    rows: Vec<Row>,
+    wrap_cols: usize,
+    cwd: &Path,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
     let mut out: Vec<Line<'static>> = Vec::new();
     let indent = if rows.len() > 1 { "  " } else { "" };
     let content_indent = format!("{indent}  ");
@@ -187,8 +230,8 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<Lin
 
         // File header: ● Update(path) / ● Added(path) / ● Deleted(path)
         let (verb, bullet_color) = match &r.change {
-            FileChange::Add { .. } => ("Added", theme::diff::added_header_color()),
-            FileChange::Delete { .. } => ("Deleted", theme::diff::removed_header_color()),
+            FileChange::Add { .. } => ("Added", theme::diff::added_header_color(theme)),
+            FileChange::Delete { .. } => ("Deleted", theme::diff::removed_header_color(theme)),
             FileChange::Update { .. } => ("Update", theme::diff::updated_header_color()),
         };
         let path_display = display_path_for(&r.path, cwd);
@@ -221,6 +264,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<Lin
             &r.change,
             &mut lines,
             wrap_cols.saturating_sub(content_indent.len()),
+            theme,
         );
         out.extend(prefix_lines(lines, &content_indent, &content_indent));
     }
@@ -241,7 +285,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<Lin
 ///   row appeared without details.
 /// - `width` is the available content width *after* outer indent; the
 ///   per-line renderer subtracts the gutter and sign column further.
-fn render_change(change: &FileChange, out: &mut Vec<Line<'static>>, width: usize) {
+fn render_change(change: &FileChange, out: &mut Vec<Line<'static>>, width: usize, theme: &Theme) {
     let unified_diff = match change {
         FileChange::Add { unified_diff }
         | FileChange::Delete { unified_diff }
@@ -296,6 +340,7 @@ fn render_change(change: &FileChange, out: &mut Vec<Line<'static>>, width: usize
                         s,
                         width,
                         line_number_width,
+                        theme,
                     ));
                     new_ln += 1;
                 }
@@ -307,6 +352,7 @@ fn render_change(change: &FileChange, out: &mut Vec<Line<'static>>, width: usize
                         s,
                         width,
                         line_number_width,
+                        theme,
                     ));
                     old_ln += 1;
                 }
@@ -318,6 +364,7 @@ fn render_change(change: &FileChange, out: &mut Vec<Line<'static>>, width: usize
                         s,
                         width,
                         line_number_width,
+                        theme,
                     ));
                     old_ln += 1;
                     new_ln += 1;
@@ -411,6 +458,7 @@ fn push_wrapped_diff_line(
     text: &str,
     width: usize,
     line_number_width: usize,
+    theme: &Theme,
 ) -> Vec<Line<'static>> {
     let ln_str = line_number.to_string();
     let mut remaining_text: &str = text;
@@ -422,8 +470,8 @@ fn push_wrapped_diff_line(
 
     let mut first = true;
     let (sign_char, line_style) = match kind {
-        DiffLineType::Insert => ('+', style_add()),
-        DiffLineType::Delete => ('-', style_del()),
+        DiffLineType::Insert => ('+', style_add(theme)),
+        DiffLineType::Delete => ('-', style_del(theme)),
         DiffLineType::Context => (' ', style_context()),
     };
     let mut lines: Vec<Line<'static>> = Vec::new();
@@ -487,13 +535,13 @@ fn style_context() -> Style {
 }
 
 /// Style for `+` insertion lines.
-fn style_add() -> Style {
-    theme::diff::added_line()
+fn style_add(theme: &Theme) -> Style {
+    theme::diff::added_line(theme)
 }
 
 /// Style for `-` deletion lines.
-fn style_del() -> Style {
-    theme::diff::removed_line()
+fn style_del(theme: &Theme) -> Style {
+    theme::diff::removed_line(theme)
 }
 
 /// Add a prefix to each line in the vector.
@@ -536,6 +584,33 @@ mod tests {
         assert_eq!(removed, 1);
     }
 
+    /// Scenario: the clipboard text re-heads each file with `a/`/`b/` paths
+    /// (and `/dev/null` for an add) instead of diffy's `original`/`modified`.
+    #[test]
+    fn test_unified_diff_text_rewrites_headers() {
+        let cwd = PathBuf::from("/workspace/project");
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("/workspace/project/b.txt"),
+            FileChange::Update {
+                unified_diff: diffy::create_patch("one\n", "two\n").to_string(),
+                move_path: None,
+            },
+        );
+        changes.insert(
+            PathBuf::from("/workspace/project/a.txt"),
+            FileChange::Add {
+                unified_diff: diffy::create_patch("", "new\n").to_string(),
+            },
+        );
+
+        let text = unified_diff_text(&changes, &cwd);
+        assert!(text.starts_with("--- /dev/null\n+++ b/a.txt\n@@"), "{text}");
+        assert!(text.contains("--- a/b.txt\n+++ b/b.txt\n@@"), "{text}");
+        assert!(text.contains("-one\n+two\n"), "{text}");
+        assert!(!text.contains("original"), "{text}");
+    }
+
     /// Scenario: an absolute path inside cwd is rendered relative for clarity.
     #[test]
     fn test_display_path_relative() {
@@ -571,7 +646,7 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80);
+        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80, &Theme::default());
 
         // Should have header + summary + content lines
         assert!(!lines.is_empty());
@@ -594,7 +669,7 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80);
+        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80, &Theme::default());
 
         // Should have header + context lines + changed lines
         assert!(!lines.is_empty());
@@ -604,7 +679,14 @@ mod tests {
     /// so the wrapper must not produce continuation rows for it.
     #[test]
     fn test_push_wrapped_diff_line_short() {
-        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, "short line", 80, 1);
+        let lines = push_wrapped_diff_line(
+            1,
+            DiffLineType::Insert,
+            "short line",
+            80,
+            1,
+            &Theme::default(),
+        );
 
         // Short line should not wrap
         assert_eq!(lines.len(), 1);
@@ -616,7 +698,8 @@ mod tests {
     #[test]
     fn test_push_wrapped_diff_line_long() {
         let long_line = "this is a very long line that should wrap across multiple terminal columns and continue";
-        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, 40, 1);
+        let lines =
+            push_wrapped_diff_line(1, DiffLineType::Insert, long_line, 40, 1, &Theme::default());
 
         // Long line should wrap into multiple lines
         assert!(lines.len() > 1);
@@ -631,16 +714,18 @@ mod tests {
     }
 
     /// Scenario: every diff row must use the shared theme so palette tweaks
-    /// propagate. Pin gutter + add + delete + context to `theme::diff::*`.
+    /// propagate. Pin gutter + add + delete + context to `theme::diff::*`, with
+    /// the added/removed colours taken from the theme passed in.
     #[test]
     fn diff_line_styles_follow_theme() {
-        let insert = push_wrapped_diff_line(1, DiffLineType::Insert, "added", 40, 1);
-        let delete = push_wrapped_diff_line(1, DiffLineType::Delete, "removed", 40, 1);
-        let context = push_wrapped_diff_line(1, DiffLineType::Context, "same", 40, 1);
+        let nord = Theme::builtin("nord").unwrap();
+        let insert = push_wrapped_diff_line(1, DiffLineType::Insert, "added", 40, 1, &nord);
+        let delete = push_wrapped_diff_line(1, DiffLineType::Delete, "removed", 40, 1, &nord);
+        let context = push_wrapped_diff_line(1, DiffLineType::Context, "same", 40, 1, &nord);
 
         assert_eq!(insert[0].spans[0].style, theme::diff::gutter());
-        assert_eq!(insert[0].spans[1].style, theme::diff::added_line());
-        assert_eq!(delete[0].spans[1].style, theme::diff::removed_line());
+        assert_eq!(insert[0].spans[1].style.fg, Some(nord.diff_added));
+        assert_eq!(delete[0].spans[1].style, theme::diff::removed_line(&nord));
         assert_eq!(context[0].spans[1].style, theme::diff::context());
     }
 
@@ -667,7 +752,7 @@ mod tests {
             },
         );
 
-        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80);
+        let lines = create_diff_summary(&changes, &PathBuf::from("/"), 80, &Theme::default());
 
         // Should have separate per-file headers
         let all_text: String = lines
@@ -0,0 +1,477 @@
+//! Collapsible working-tree explorer shown as a left-side panel (`Ctrl+B`).
+//!
+//! The tree is built lazily: a directory is read from disk the first time it is
+//! expanded and its listing is cached until [`FileTree::refresh`]. The flattened
+//! row list is rebuilt only when the expansion set changes, and rendering only
+//! materialises the rows inside the visible window, so very large directories
+//! cost no more per frame than a small one.
+//!
+//! Rows are coloured by working-tree status (see [`FileStatus`]). Activating a
+//! file (`Enter` or a left click) hands its workdir-relative path back to the
+//! caller, which turns it into a `read_file <path>` prompt.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use super::theme;
+use crate::command::status::Changes;
+
+/// Default panel width in columns; clamped to a third of the screen.
+pub const FILE_TREE_WIDTH: u16 = 32;
+
+/// Entries never shown in the explorer.
+const HIDDEN_NAMES: [&str; 2] = [".libra", ".git"];
+
+/// Working-tree status used to colour a file row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileStatus {
+    #[default]
+    Clean,
+    /// New file staged in the index.
+    Added,
+    /// Modified in the index or the working tree.
+    Modified,
+    /// Deleted from the index or the working tree.
+    Deleted,
+    /// Present on disk but not tracked.
+    Untracked,
+}
+
+impl FileStatus {
+    fn style(self) -> Style {
+        match self {
+            FileStatus::Clean => theme::file_tree::clean(),
+            FileStatus::Added => theme::file_tree::added(),
+            FileStatus::Modified => theme::file_tree::modified(),
+            FileStatus::Deleted => theme::file_tree::deleted(),
+            FileStatus::Untracked => theme::file_tree::untracked(),
+        }
+    }
+}
+
+/// Merge staged (`index` vs `HEAD`) and unstaged (worktree vs `index`) changes
+/// into a per-path status map. A staged addition wins over later worktree
+/// edits so newly added files stay green.
+pub fn statuses_from_changes(staged: &Changes, unstaged: &Changes) -> HashMap<PathBuf, FileStatus> {
+    let mut statuses = HashMap::new();
+    for path in &staged.new {
+        statuses.insert(path.clone(), FileStatus::Added);
+    }
+    for (_, target) in &staged.renamed {
+        statuses.insert(target.clone(), FileStatus::Added);
+    }
+    for path in staged.modified.iter().chain(&unstaged.modified) {
+        statuses.entry(path.clone()).or_insert(FileStatus::Modified);
+    }
+    for path in staged.deleted.iter().chain(&unstaged.deleted) {
+        statuses.insert(path.clone(), FileStatus::Deleted);
+    }
+    for (source, _) in &staged.renamed {
+        statuses.insert(source.clone(), FileStatus::Deleted);
+    }
+    for path in &unstaged.new {
+        statuses
+            .entry(path.clone())
+            .or_insert(FileStatus::Untracked);
+    }
+    statuses
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Path relative to the tree root.
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    entry: Entry,
+    depth: usize,
+}
+
+/// Collapsible file tree rooted at the repository working directory.
+#[derive(Debug)]
+pub struct FileTree {
+    root: PathBuf,
+    /// Cached directory listings keyed by root-relative directory path.
+    listings: HashMap<PathBuf, Vec<Entry>>,
+    expanded: HashSet<PathBuf>,
+    statuses: HashMap<PathBuf, FileStatus>,
+    /// Flattened visible rows; rebuilt when `dirty` is set.
+    rows: Vec<Row>,
+    dirty: bool,
+    selected: usize,
+    offset: usize,
+    visible: bool,
+    focused: bool,
+    /// Panel area and its border-less row area from the last render, for
+    /// mouse hit-testing.
+    last_area: Option<(Rect, Rect)>,
+}
+
+impl FileTree {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            listings: HashMap::new(),
+            expanded: HashSet::new(),
+            statuses: HashMap::new(),
+            rows: Vec::new(),
+            dirty: true,
+            selected: 0,
+            offset: 0,
+            visible: false,
+            focused: false,
+            last_area: None,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.visible && self.focused
+    }
+
+    /// Show the panel and give it focus, or hide it if it is already shown.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.focused = self.visible;
+        if !self.visible {
+            self.last_area = None;
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused && self.visible;
+    }
+
+    /// Replace the status map (root-relative paths) and drop cached listings
+    /// so newly created or deleted files show up.
+    pub fn refresh(&mut self, statuses: HashMap<PathBuf, FileStatus>) {
+        self.statuses = statuses;
+        self.listings.clear();
+        self.dirty = true;
+    }
+
+    /// Width the panel should occupy inside a screen `total_width` wide.
+    pub fn panel_width(total_width: u16) -> u16 {
+        FILE_TREE_WIDTH.min(total_width / 3)
+    }
+
+    pub fn select_next(&mut self) {
+        self.ensure_rows();
+        if self.selected + 1 < self.rows.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Expand the selected directory (`Right`).
+    pub fn expand_selected(&mut self) {
+        self.ensure_rows();
+        if let Some(row) = self.rows.get(self.selected)
+            && row.entry.is_dir
+            && self.expanded.insert(row.entry.path.clone())
+        {
+            self.dirty = true;
+        }
+    }
+
+    /// Collapse the selected directory, or jump to the parent row (`Left`).
+    pub fn collapse_selected(&mut self) {
+        self.ensure_rows();
+        let Some(row) = self.rows.get(self.selected) else {
+            return;
+        };
+        if row.entry.is_dir && self.expanded.remove(&row.entry.path) {
+            self.dirty = true;
+            return;
+        }
+        let Some(parent) = row.entry.path.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        if let Some(index) = self.rows.iter().position(|r| r.entry.path == parent) {
+            self.selected = index;
+        }
+    }
+
+    /// Activate the selected row: directories toggle open/closed, files return
+    /// their root-relative path.
+    pub fn activate_selected(&mut self) -> Option<PathBuf> {
+        self.ensure_rows();
+        let row = self.rows.get(self.selected)?;
+        if row.entry.is_dir {
+            let path = row.entry.path.clone();
+            if !self.expanded.remove(&path) {
+                self.expanded.insert(path);
+            }
+            self.dirty = true;
+            None
+        } else {
+            Some(row.entry.path.clone())
+        }
+    }
+
+    /// Whether `(x, y)` falls inside the panel drawn by the last render.
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.last_area
+            .is_some_and(|(panel, _)| rect_contains(panel, x, y))
+    }
+
+    /// Select and activate the row under a left click.
+    pub fn click(&mut self, x: u16, y: u16) -> Option<PathBuf> {
+        let (_, rows) = self.last_area?;
+        if !rect_contains(rows, x, y) {
+            return None;
+        }
+        let index = self.offset + usize::from(y - rows.y);
+        self.ensure_rows();
+        if index >= self.rows.len() {
+            return None;
+        }
+        self.focused = true;
+        self.selected = index;
+        self.activate_selected()
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::RIGHT | Borders::TOP | Borders::BOTTOM)
+            .border_style(if self.focused {
+                theme::border::focused()
+            } else {
+                theme::border::idle()
+            })
+            .title(Line::styled(" Files ", theme::interactive::title()));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        self.last_area = Some((area, inner));
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        self.ensure_rows();
+        let height = usize::from(inner.height);
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let lines: Vec<Line<'static>> = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+            .map(|(index, row)| self.row_line(row, index == self.selected))
+            .collect();
+        Paragraph::new(lines).render(inner, buf);
+    }
+
+    fn row_line(&self, row: &Row, selected: bool) -> Line<'static> {
+        let indent = "  ".repeat(row.depth);
+        let (marker, style) = if row.entry.is_dir {
+            let marker = if self.expanded.contains(&row.entry.path) {
+                "▾ "
+            } else {
+                "▸ "
+            };
+            (marker, theme::file_tree::directory())
+        } else {
+            ("  ", self.status_of(&row.entry.path).style())
+        };
+        let style = if selected && self.focused {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        };
+        Line::from(vec![
+            Span::raw(indent),
+            Span::styled(marker, theme::text::subtle()),
+            Span::styled(row.entry.name.clone(), style),
+        ])
+    }
+
+    fn status_of(&self, path: &Path) -> FileStatus {
+        self.statuses.get(path).copied().unwrap_or_default()
+    }
+
+    fn ensure_rows(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let mut rows = Vec::new();
+        self.flatten(PathBuf::new(), 0, &mut rows);
+        self.rows = rows;
+        self.dirty = false;
+    }
+
+    fn flatten(&mut self, dir: PathBuf, depth: usize, rows: &mut Vec<Row>) {
+        let entries = self.listing(&dir).to_vec();
+        for entry in entries {
+            let expand = entry.is_dir && self.expanded.contains(&entry.path);
+            let path = entry.path.clone();
+            rows.push(Row { entry, depth });
+            if expand {
+                self.flatten(path, depth + 1, rows);
+            }
+        }
+    }
+
+    /// Directory listing for `dir`, read on first use: directories first, then
+    /// files, each sorted by name. Deleted files are merged back in from the
+    /// status map so they remain visible.
+    fn listing(&mut self, dir: &Path) -> &[Entry] {
+        if !self.listings.contains_key(dir) {
+            let entries = self.read_dir(dir);
+            self.listings.insert(dir.to_path_buf(), entries);
+        }
+        &self.listings[dir]
+    }
+
+    fn read_dir(&self, dir: &Path) -> Vec<Entry> {
+        let mut entries: Vec<Entry> = std::fs::read_dir(self.root.join(dir))
+            .map(|iter| {
+                iter.filter_map(Result::ok)
+                    .filter_map(|dirent| {
+                        let name = dirent.file_name().to_string_lossy().into_owned();
+                        if HIDDEN_NAMES.contains(&name.as_str()) {
+                            return None;
+                        }
+                        let is_dir = dirent.file_type().ok()?.is_dir();
+                        Some(Entry {
+                            path: dir.join(&name),
+                            name,
+                            is_dir,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for (path, status) in &self.statuses {
+            if *status == FileStatus::Deleted
+                && path.parent() == Some(dir)
+                && !entries.iter().any(|entry| &entry.path == path)
+                && let Some(name) = path.file_name()
+            {
+                entries.push(Entry {
+                    path: path.clone(),
+                    name: name.to_string_lossy().into_owned(),
+                    is_dir: false,
+                });
+            }
+        }
+
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        entries
+    }
+}
+
+fn rect_contains(rect: Rect, x: u16, y: u16) -> bool {
+    x >= rect.x
+        && x < rect.x.saturating_add(rect.width)
+        && y >= rect.y
+        && y < rect.y.saturating_add(rect.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn rendered_text(tree: &mut FileTree, width: u16, height: u16) -> Vec<String> {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        tree.render(area, &mut buf);
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| buf[(x, y)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn directories_expand_lazily_and_files_activate() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        fs::write(dir.path().join("README.md"), "").unwrap();
+        fs::create_dir(dir.path().join(".libra")).unwrap();
+
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+        tree.toggle();
+        let text = rendered_text(&mut tree, 30, 6).join("\n");
+        assert!(text.contains("src") && text.contains("README.md"));
+        assert!(
+            !text.contains("lib.rs"),
+            "collapsed directory hides children"
+        );
+        assert!(!text.contains(".libra"));
+
+        // `src` sorts first; Enter on a directory expands it.
+        assert_eq!(tree.activate_selected(), None);
+        tree.select_next();
+        assert_eq!(tree.activate_selected(), Some(PathBuf::from("src/lib.rs")));
+    }
+
+    #[test]
+    fn rows_are_coloured_by_status_and_deleted_files_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("new.rs"), "").unwrap();
+        fs::write(dir.path().join("clean.rs"), "").unwrap();
+
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+        tree.refresh(HashMap::from([
+            (PathBuf::from("new.rs"), FileStatus::Added),
+            (PathBuf::from("gone.rs"), FileStatus::Deleted),
+        ]));
+        tree.ensure_rows();
+        let names: Vec<_> = tree.rows.iter().map(|r| r.entry.name.as_str()).collect();
+        assert_eq!(names, ["clean.rs", "gone.rs", "new.rs"]);
+
+        let line = tree.row_line(&tree.rows[2].clone(), false);
+        assert_eq!(line.spans[2].style, theme::file_tree::added());
+        let line = tree.row_line(&tree.rows[1].clone(), false);
+        assert_eq!(line.spans[2].style, theme::file_tree::deleted());
+    }
+
+    #[test]
+    fn render_only_materialises_the_visible_window() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..200 {
+            fs::write(dir.path().join(format!("f{i:03}.txt")), "").unwrap();
+        }
+        let mut tree = FileTree::new(dir.path().to_path_buf());
+        tree.toggle();
+        for _ in 0..150 {
+            tree.select_next();
+        }
+        // 2 border rows leave 8 visible rows; the selection stays in view.
+        let text = rendered_text(&mut tree, 30, 10).join("\n");
+        assert!(text.contains("f150.txt"));
+        assert!(!text.contains("f000.txt"));
+    }
+}
@@ -16,9 +16,9 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use super::{
-    diff::{DiffSummary, FileChange, create_diff_summary},
+    diff::{DiffSummary, FileChange, create_diff_summary, unified_diff_text},
     markdown_render::render_markdown_lines,
-    theme,
+    theme::{self, Theme},
 };
 use crate::internal::ai::{
     intentspec::types::IntentSpec,
@@ -124,15 +124,34 @@ fn wrap_text(text: &str, prefix: &str, width: u16, style: Style) -> Vec<Line<'st
 
 /// Trait for cells displayed in the chat history.
 pub trait HistoryCell: Debug + Send + Sync {
-    /// Render the cell as lines for display.
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>>;
+    /// Render the cell as lines for display, taking the configurable
+    /// response colours from `theme`.
+    fn display_lines(&self, width: u16, theme: &Theme) -> Vec<Line<'static>>;
 
     /// Calculate the desired height for the cell.
-    fn desired_height(&self, width: u16) -> u16 {
-        let lines = self.display_lines(width);
+    fn desired_height(&self, width: u16, theme: &Theme) -> u16 {
+        let lines = self.display_lines(width, theme);
         lines.len() as u16
     }
 
+    /// Plain-text content of the cell, as copied to the clipboard (`y` on a
+    /// focused cell). Defaults to the unwrapped display text; cells backed by
+    /// raw text (messages, tool output, diffs) override it to return that.
+    fn text_content(&self) -> String {
+        self.display_lines(u16::MAX, &Theme::default())
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+
     /// Downcast to concrete type for mutation.
     fn as_any(&self) -> &dyn Any;
 
@@ -155,17 +174,26 @@ impl UserHistoryCell {
 }
 
 impl HistoryCell for UserHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
 
         for line in self.message.lines() {
-            lines.extend(wrap_text(line, "│ ", width, theme::interactive::accent()));
+            lines.extend(wrap_text(
+                line,
+                "│ ",
+                width,
+                theme::history::user_message(theme),
+            ));
         }
 
         lines.push(Line::raw("")); // Empty line for spacing
         lines
     }
 
+    fn text_content(&self) -> String {
+        self.message.clone()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -208,17 +236,20 @@ impl AssistantHistoryCell {
 }
 
 impl HistoryCell for AssistantHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
 
         let content = self.content.trim();
         if !content.is_empty() {
-            let rendered = render_markdown_lines(content, width.saturating_sub(2));
+            let rendered = render_markdown_lines(content, width.saturating_sub(2), theme);
             for (idx, line) in rendered.into_iter().enumerate() {
                 let prefix = if idx == 0 { "● " } else { "  " };
                 let mut spans = vec![Span::raw(prefix.to_string())];
                 spans.extend(line.spans);
-                lines.push(Line::from(spans).style(line.style));
+                lines.push(
+                    Line::from(spans)
+                        .style(theme::history::assistant_message(theme).patch(line.style)),
+                );
             }
         }
 
@@ -231,6 +262,10 @@ impl HistoryCell for AssistantHistoryCell {
         lines
     }
 
+    fn text_content(&self) -> String {
+        self.content.trim().to_string()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -270,7 +305,7 @@ impl ThinkingHistoryCell {
 }
 
 impl HistoryCell for ThinkingHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, _theme: &Theme) -> Vec<Line<'static>> {
         let content = self.content.trim();
         if content.is_empty() {
             return Vec::new();
@@ -290,6 +325,10 @@ impl HistoryCell for ThinkingHistoryCell {
         lines
     }
 
+    fn text_content(&self) -> String {
+        self.content.trim().to_string()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -582,7 +621,7 @@ impl ToolCallHistoryCell {
 }
 
 impl HistoryCell for ToolCallHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
         let phase = animation_phase(120);
         let (preview_label, running_label, done_label, failed_label) = self.group.labels();
@@ -606,14 +645,14 @@ impl HistoryCell for ToolCallHistoryCell {
             ));
         } else {
             let status_color = if self.is_success() {
-                theme::status::success_color()
+                theme::tool::success_color(theme)
             } else {
-                theme::status::danger_color()
+                theme::tool::error_color(theme)
             };
-            lines.push(Line::styled(
-                format!("● {summary}"),
-                Style::default().fg(status_color).bold(),
-            ));
+            lines.push(Line::from(vec![
+                Span::styled("● ", Style::default().fg(status_color).bold()),
+                Span::styled(summary.to_string(), theme::tool::header(theme).bold()),
+            ]));
         }
 
         let grouped_entries = group_tool_entries(&self.entries);
@@ -640,7 +679,9 @@ impl HistoryCell for ToolCallHistoryCell {
                     &truncate_utf8(error.trim(), 180),
                     "    ",
                     width,
-                    theme::status::danger().add_modifier(Modifier::DIM),
+                    Style::default()
+                        .fg(theme::tool::error_color(theme))
+                        .add_modifier(Modifier::DIM),
                 ));
             }
             for output in &entry.outputs {
@@ -649,6 +690,7 @@ impl HistoryCell for ToolCallHistoryCell {
                     output.keep_full_text || self.group == ToolCallGroup::Input,
                     output.is_markdown,
                     width,
+                    theme,
                 ));
             }
         }
@@ -657,6 +699,27 @@ impl HistoryCell for ToolCallHistoryCell {
         lines
     }
 
+    /// One `<tool>: <call summary>` line per entry, followed by its full
+    /// (untruncated) output or error.
+    fn text_content(&self) -> String {
+        let mut parts = Vec::new();
+        for entry in &self.entries {
+            parts.push(format!("{}: {}", entry.tool_name, entry.summary));
+            match &entry.status {
+                ToolCallEntryStatus::Success(Some(output)) => {
+                    parts.push(output.text.trim().to_string());
+                }
+                ToolCallEntryStatus::Failed(error) => {
+                    parts.push(format!("error: {}", error.trim()));
+                }
+                ToolCallEntryStatus::Preview
+                | ToolCallEntryStatus::Running
+                | ToolCallEntryStatus::Success(None) => {}
+            }
+        }
+        parts.join("\n")
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -671,11 +734,12 @@ fn render_tool_output(
     keep_full_text: bool,
     is_markdown: bool,
     width: u16,
+    theme: &Theme,
 ) -> Vec<Line<'static>> {
     let style = theme::text::muted().add_modifier(Modifier::DIM);
     let trimmed = text.trim();
     if is_markdown || trimmed.starts_with("```") {
-        return render_markdown_lines(trimmed, width.saturating_sub(4))
+        return render_markdown_lines(trimmed, width.saturating_sub(4), theme)
             .into_iter()
             .map(|line| {
                 let mut spans = vec![Span::styled("    ", style)];
@@ -1304,16 +1368,21 @@ impl DiffHistoryCell {
 }
 
 impl HistoryCell for DiffHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, theme: &Theme) -> Vec<Line<'static>> {
         let mut lines = vec![Line::styled("● Diff", theme::text::primary().bold())];
         lines.extend(create_diff_summary(
             &self.summary.changes,
             &self.summary.cwd,
             width as usize,
+            theme,
         ));
         lines
     }
 
+    fn text_content(&self) -> String {
+        unified_diff_text(&self.summary.changes, &self.summary.cwd)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -1324,15 +1393,21 @@ impl HistoryCell for DiffHistoryCell {
 }
 
 /// A plan update displayed as a checkbox list.
+///
+/// Follow-up `update_plan` calls for the same plan are folded into the
+/// existing cell ([`Self::continue_plan`]) rather than appending a new one, so
+/// the checklist fills in as steps complete.
 #[derive(Debug, Clone)]
 pub struct PlanUpdateHistoryCell {
     /// Optional explanation from the model.
     pub explanation: Option<String>,
     /// The plan steps with their statuses.
     pub steps: Vec<PlanStep>,
+    /// Per step: whether the latest update added it or changed its status.
+    pub changed: Vec<bool>,
     /// Whether the tool call is still running.
     pub is_running: bool,
-    /// Unique id for this tool call.
+    /// Id of the latest tool call that updated this plan.
     pub call_id: String,
 }
 
@@ -1341,6 +1416,7 @@ impl PlanUpdateHistoryCell {
     pub fn new(call_id: String, explanation: Option<String>, steps: Vec<PlanStep>) -> Self {
         Self {
             explanation,
+            changed: vec![false; steps.len()],
             steps,
             is_running: true,
             call_id,
@@ -1352,15 +1428,104 @@ impl PlanUpdateHistoryCell {
         self.is_running = false;
     }
 
-    /// Refresh previewed plan arguments when the real tool call starts.
+    /// Replace the plan with `steps`, diffing against the current steps by
+    /// description: steps that are new or whose status moved are marked as
+    /// changed. Re-applying identical steps (preview, then the real call)
+    /// keeps the existing marks.
     pub fn update(&mut self, explanation: Option<String>, steps: Vec<PlanStep>) {
+        let changed: Vec<bool> = steps
+            .iter()
+            .map(|step| {
+                !self
+                    .steps
+                    .iter()
+                    .any(|old| old.step == step.step && old.status == step.status)
+            })
+            .collect();
+        if changed.contains(&true) || steps.len() != self.steps.len() {
+            self.changed = changed;
+        }
         self.explanation = explanation;
         self.steps = steps;
     }
+
+    /// Whether `steps` continue this plan rather than start a new one, i.e.
+    /// at least one step description carries over.
+    pub fn is_same_plan(&self, steps: &[PlanStep]) -> bool {
+        steps
+            .iter()
+            .any(|step| self.steps.iter().any(|old| old.step == step.step))
+    }
+
+    /// Fold a follow-up `update_plan` call into this cell.
+    pub fn continue_plan(
+        &mut self,
+        call_id: String,
+        explanation: Option<String>,
+        steps: Vec<PlanStep>,
+    ) {
+        self.call_id = call_id;
+        self.is_running = true;
+        self.update(explanation, steps);
+    }
+}
+
+/// Checklist icon and style for a plan step status.
+fn plan_step_marker(status: &StepStatus) -> (&'static str, Style) {
+    match status {
+        StepStatus::Completed => (
+            "✔",
+            Style::default()
+                .add_modifier(Modifier::DIM)
+                .add_modifier(Modifier::CROSSED_OUT),
+        ),
+        StepStatus::InProgress => ("◐", theme::interactive::in_progress()),
+        StepStatus::Pending => ("□", Style::default().add_modifier(Modifier::DIM)),
+        StepStatus::Skipped => (
+            "⊘",
+            Style::default()
+                .add_modifier(Modifier::DIM)
+                .add_modifier(Modifier::ITALIC),
+        ),
+    }
+}
+
+/// Explanation plus one checkbox row per step, shared by the transcript cell
+/// and the `/plan` sidebar. Rows flagged in `changed` get a `›` gutter marker.
+pub(crate) fn plan_checklist_lines(
+    explanation: Option<&str>,
+    steps: &[PlanStep],
+    changed: &[bool],
+    width: u16,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if let Some(explanation) = explanation {
+        lines.extend(wrap_text(
+            explanation,
+            "  ",
+            width,
+            Style::default().add_modifier(Modifier::DIM).italic(),
+        ));
+    }
+    for (index, step) in steps.iter().enumerate() {
+        let (icon, style) = plan_step_marker(&step.status);
+        let prefix = if changed.get(index).copied().unwrap_or(false) {
+            "› "
+        } else {
+            "  "
+        };
+        lines.extend(wrap_text(
+            &format!("{} {}", icon, step.step),
+            prefix,
+            width,
+            style,
+        ));
+    }
+    lines
 }
 
 impl HistoryCell for PlanUpdateHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, _theme: &Theme) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
 
         // Header
@@ -1378,36 +1543,13 @@ impl HistoryCell for PlanUpdateHistoryCell {
             ),
         ]));
 
-        // Optional explanation
-        if let Some(ref explanation) = self.explanation {
-            lines.extend(wrap_text(
-                explanation,
-                "  ",
-                width,
-                Style::default().add_modifier(Modifier::DIM).italic(),
-            ));
-        }
-
-        // Steps with checkboxes
-        for step in &self.steps {
-            let (icon, style) = match step.status {
-                StepStatus::Completed => (
-                    "✔",
-                    Style::default()
-                        .add_modifier(Modifier::DIM)
-                        .add_modifier(Modifier::CROSSED_OUT),
-                ),
-                StepStatus::InProgress => ("◐", theme::interactive::in_progress()),
-                StepStatus::Pending => ("□", Style::default().add_modifier(Modifier::DIM)),
-            };
-
-            lines.extend(wrap_text(
-                &format!("{} {}", icon, step.step),
-                "  ",
-                width,
-                style,
-            ));
-        }
+        // Optional explanation, then the steps with checkboxes
+        lines.extend(plan_checklist_lines(
+            self.explanation.as_deref(),
+            &self.steps,
+            &self.changed,
+            width,
+        ));
 
         lines.push(Line::raw("")); // Spacing
         lines
@@ -1677,7 +1819,7 @@ impl PlanSummaryHistoryCell {
 }
 
 impl HistoryCell for PlanSummaryHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, _theme: &Theme) -> Vec<Line<'static>> {
         let mut lines = self.render_header();
         lines.push(Line::raw(""));
         lines.extend(self.render_overview(width));
@@ -2134,7 +2276,7 @@ impl OrchestratorResultHistoryCell {
 }
 
 impl HistoryCell for OrchestratorResultHistoryCell {
-    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+    fn display_lines(&self, width: u16, _theme: &Theme) -> Vec<Line<'static>> {
         let mut lines = self.render_header();
         lines.push(Line::raw(""));
         lines.extend(self.render_overview(width));
@@ -2332,7 +2474,7 @@ mod tests {
 
     use super::{
         AssistantHistoryCell, HistoryCell, OrchestratorResultHistoryCell, PlanSummaryHistoryCell,
-        PlanUpdateHistoryCell, ThinkingHistoryCell, ToolCallHistoryCell, UserHistoryCell,
+        PlanUpdateHistoryCell, Theme, ThinkingHistoryCell, ToolCallHistoryCell, UserHistoryCell,
         human_json_output,
     };
     use crate::internal::ai::{
@@ -2487,7 +2629,7 @@ mod tests {
     #[test]
     fn user_cell_uses_vertical_bar_and_no_user_label() {
         let cell = UserHistoryCell::new("hello".to_string());
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         assert!(rendered.iter().any(|line| line.starts_with("│ ")));
         assert!(!rendered.iter().any(|line| line.contains("User:")));
     }
@@ -2495,7 +2637,7 @@ mod tests {
     #[test]
     fn assistant_cell_uses_bullet_and_no_assistant_label() {
         let cell = AssistantHistoryCell::new("response".to_string());
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         assert!(rendered.iter().any(|line| line.starts_with("● ")));
         assert!(!rendered.iter().any(|line| line.contains("Assistant:")));
     }
@@ -2504,7 +2646,7 @@ mod tests {
     fn thinking_cell_renders_provider_thoughts_for_developers() {
         let mut cell = ThinkingHistoryCell::streaming();
         cell.append("Inspecting failure evidence");
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Think"));
@@ -2521,7 +2663,7 @@ mod tests {
             Some("019ce52e-18d5-7910-b999-a6782e91666e".to_string()),
             vec!["execution plan not persisted".to_string()],
         );
-        let rendered = to_strings(cell.display_lines(100));
+        let rendered = to_strings(cell.display_lines(100, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Plan Ready"));
@@ -2546,7 +2688,7 @@ mod tests {
             Some("019ce52e-18d5-7910-b999-a6782e91666e".to_string()),
             Vec::new(),
         );
-        let rendered = to_strings(cell.display_lines(180));
+        let rendered = to_strings(cell.display_lines(180, &Theme::default()));
         let header = rendered
             .iter()
             .find(|line| line.contains("Task") && line.contains("Type"))
@@ -2561,7 +2703,7 @@ mod tests {
     #[test]
     fn orchestrator_result_cell_renders_structured_sections() {
         let cell = OrchestratorResultHistoryCell::new(orchestrator_result_fixture());
-        let rendered = to_strings(cell.display_lines(100));
+        let rendered = to_strings(cell.display_lines(100, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Execution Summary"));
@@ -2580,7 +2722,7 @@ mod tests {
     #[test]
     fn streaming_placeholder_does_not_render_standalone_cursor_line() {
         let cell = AssistantHistoryCell::streaming();
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         assert!(!rendered.iter().any(|line| line.contains("Thinking")));
         assert!(rendered.is_empty());
         assert!(!rendered.iter().any(|line| line.trim() == "▌"));
@@ -2589,10 +2731,25 @@ mod tests {
     #[test]
     fn tool_cell_header_uses_bullet() {
         let cell = ToolCallHistoryCell::new("1".to_string(), "read_file".to_string(), json!({}));
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         assert!(rendered.iter().any(|line| line.starts_with("● ")));
     }
 
+    #[test]
+    fn tool_cell_takes_status_and_header_colours_from_the_theme() {
+        let mut cell = ToolCallHistoryCell::new(
+            "1".to_string(),
+            "read_file".to_string(),
+            json!({"file_path":"src/main.rs"}),
+        );
+        cell.complete_call("1", Ok(ToolOutput::success("L1: fn main() {}")));
+        let nord = Theme::builtin("nord").unwrap();
+
+        let lines = cell.display_lines(80, &nord);
+        assert_eq!(lines[0].spans[0].style.fg, Some(nord.tool_success));
+        assert_eq!(lines[0].spans[1].style.fg, Some(nord.tool_call_header));
+    }
+
     #[test]
     fn tool_cell_hides_raw_args_and_results() {
         let mut cell = ToolCallHistoryCell::new(
@@ -2602,7 +2759,7 @@ mod tests {
         );
         cell.complete_call("1", Ok(ToolOutput::success("L1: fn main() {}")));
 
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Explored"));
@@ -2624,7 +2781,7 @@ mod tests {
                 "limit": 41250.0
             }),
         );
-        let rendered = to_strings(cell.display_lines(120));
+        let rendered = to_strings(cell.display_lines(120, &Theme::default()));
         let joined = rendered.join("\n");
         assert!(
             joined.contains("\"symbol\":\"BTC-USD\""),
@@ -2646,7 +2803,7 @@ mod tests {
                 "subtitle": "中文标题"
             }),
         );
-        let rendered = to_strings(cell.display_lines(160));
+        let rendered = to_strings(cell.display_lines(160, &Theme::default()));
         let joined = rendered.join("\n");
         assert!(
             joined.contains("\"He said \\\"hello\\\"\""),
@@ -2658,6 +2815,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn text_content_copies_raw_message_and_tool_call_with_result() {
+        let assistant = AssistantHistoryCell::new("```rust\nfn main() {}\n```\n".to_string());
+        assert_eq!(assistant.text_content(), "```rust\nfn main() {}\n```");
+
+        let mut cell = ToolCallHistoryCell::new(
+            "1".to_string(),
+            "shell".to_string(),
+            json!({"command": "echo hi"}),
+        );
+        cell.complete_call("1", Ok(ToolOutput::success("hi")));
+        let text = cell.text_content();
+        assert!(text.starts_with("shell: "), "{text}");
+        assert!(text.contains("echo hi"), "{text}");
+        assert!(text.ends_with("\nhi"), "{text}");
+    }
+
     #[test]
     fn json_tool_output_renders_readable_full_text() {
         // Use a non-`shell` tool so the only way the assertion below can pass
@@ -2676,7 +2850,7 @@ mod tests {
   "visible_after_truncation_limit": true
 }"#;
         cell.complete_call("1", Ok(ToolOutput::success(json_blob)));
-        let rendered = to_strings(cell.display_lines(160));
+        let rendered = to_strings(cell.display_lines(160, &Theme::default()));
         let joined = rendered.join("\n");
         assert!(
             joined.contains("items:"),
@@ -2764,7 +2938,7 @@ mod tests {
                 r#"{"status":"ok","items":[{"id":1,"name":"alpha"}]}"#,
             )),
         );
-        let rendered = to_strings(cell.display_lines(120));
+        let rendered = to_strings(cell.display_lines(120, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(
@@ -2799,7 +2973,7 @@ mod tests {
             )),
         );
 
-        let rendered = to_strings(cell.display_lines(120));
+        let rendered = to_strings(cell.display_lines(120, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Input received"));
@@ -2829,7 +3003,7 @@ mod tests {
             )),
         );
 
-        let rendered = to_strings(cell.display_lines(100));
+        let rendered = to_strings(cell.display_lines(100, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Ran command"));
@@ -2853,7 +3027,7 @@ mod tests {
             ))),
         );
 
-        let rendered = to_strings(cell.display_lines(140));
+        let rendered = to_strings(cell.display_lines(140, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("cd <task workspace> && cargo test"));
@@ -2876,7 +3050,7 @@ mod tests {
         cell.complete_call("1", Ok(ToolOutput::success("src/internal/tui/app.rs")));
         cell.complete_call("2", Ok(ToolOutput::success("Absolute path: /tmp/src")));
 
-        let rendered = to_strings(cell.display_lines(100));
+        let rendered = to_strings(cell.display_lines(100, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Explored"));
@@ -2905,7 +3079,7 @@ mod tests {
         cell.complete_call("2", Ok(ToolOutput::success("ok")));
         cell.complete_call("3", Ok(ToolOutput::success("ok")));
 
-        let rendered = to_strings(cell.display_lines(100));
+        let rendered = to_strings(cell.display_lines(100, &Theme::default()));
         let read_lines = rendered
             .iter()
             .filter(|line| line.contains("Read "))
@@ -2930,7 +3104,7 @@ mod tests {
             Err("Tool 'read_file' failed: outside working dir".to_string()),
         );
 
-        let rendered = to_strings(cell.display_lines(100));
+        let rendered = to_strings(cell.display_lines(100, &Theme::default()));
         let joined = rendered.join("\n");
 
         assert!(joined.contains("Explore failed"));
@@ -2948,7 +3122,48 @@ mod tests {
                 status: StepStatus::InProgress,
             }],
         );
-        let rendered = to_strings(cell.display_lines(80));
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
         assert!(rendered.iter().any(|line| line.starts_with("● ")));
     }
+
+    #[test]
+    fn plan_cell_continue_plan_updates_statuses_in_place_and_marks_changes() {
+        let step = |text: &str, status: StepStatus| PlanStep {
+            step: text.to_string(),
+            status,
+        };
+        let mut cell = PlanUpdateHistoryCell::new(
+            "call-1".to_string(),
+            None,
+            vec![
+                step("parse args", StepStatus::InProgress),
+                step("write tests", StepStatus::Pending),
+                step("update docs", StepStatus::Pending),
+            ],
+        );
+        cell.complete();
+
+        let next = vec![
+            step("parse args", StepStatus::Completed),
+            step("write tests", StepStatus::InProgress),
+            step("update docs", StepStatus::Skipped),
+            step("release notes", StepStatus::Pending),
+        ];
+        assert!(cell.is_same_plan(&next));
+        assert!(!cell.is_same_plan(&[step("unrelated", StepStatus::Pending)]));
+
+        cell.continue_plan("call-2".to_string(), None, next.clone());
+        assert_eq!(cell.call_id, "call-2");
+        assert!(cell.is_running);
+        assert_eq!(cell.changed, vec![true, true, true, true]);
+
+        // The real call re-applies the previewed steps; marks survive.
+        cell.update(None, next);
+        assert_eq!(cell.changed, vec![true, true, true, true]);
+
+        let rendered = to_strings(cell.display_lines(80, &Theme::default()));
+        assert!(rendered.iter().any(|line| line == "› ✔ parse args"));
+        assert!(rendered.iter().any(|line| line == "› ⊘ update docs"));
+        assert!(rendered.iter().any(|line| line == "› □ release notes"));
+    }
 }
@@ -7,7 +7,10 @@ use ratatui::{
 };
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use super::theme;
+use super::{
+    code_block::CodeBlockWidget,
+    theme::{self, Theme},
+};
 
 #[derive(Clone, Copy)]
 struct MarkdownStyles {
@@ -25,15 +28,15 @@ struct MarkdownStyles {
     table_header: Style,
 }
 
-impl Default for MarkdownStyles {
-    fn default() -> Self {
+impl MarkdownStyles {
+    fn new(theme: &Theme) -> Self {
         Self {
             heading: theme::text::primary().add_modifier(Modifier::BOLD),
             heading_marker: theme::markdown::heading_marker(),
             emphasis: Style::default().add_modifier(Modifier::ITALIC),
             strong: Style::default().add_modifier(Modifier::BOLD),
             code_inline: theme::markdown::code_inline(),
-            code_block: theme::markdown::code_block(),
+            code_block: theme::markdown::code_block(theme),
             link: theme::markdown::link(),
             blockquote: theme::markdown::blockquote(),
             bullet: theme::markdown::bullet(),
@@ -101,18 +104,19 @@ impl TableState {
     }
 }
 
-pub fn render_markdown_lines(input: &str, width: u16) -> Vec<Line<'static>> {
+pub fn render_markdown_lines(input: &str, width: u16, theme: &Theme) -> Vec<Line<'static>> {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TABLES);
     let parser = Parser::new_ext(input, options);
-    let mut renderer = Renderer::new(width as usize);
+    let mut renderer = Renderer::new(width as usize, theme);
     renderer.render(parser);
     renderer.into_lines()
 }
 
-struct Renderer {
+struct Renderer<'a> {
     width: usize,
+    theme: &'a Theme,
     styles: MarkdownStyles,
     lines: Vec<Line<'static>>,
     current_segments: Vec<InlineSpan>,
@@ -123,15 +127,20 @@ struct Renderer {
     pending_heading: Option<HeadingLevel>,
     active_link_dest: Option<String>,
     in_code_block: bool,
+    /// Fence language of the open code block; its text is buffered so the
+    /// whole block can be highlighted at once on close.
+    code_block_lang: Option<String>,
+    code_block_buffer: String,
     needs_block_spacing: bool,
     table_state: Option<TableState>,
 }
 
-impl Renderer {
-    fn new(width: usize) -> Self {
+impl<'a> Renderer<'a> {
+    fn new(width: usize, theme: &'a Theme) -> Self {
         Self {
             width: width.max(8),
-            styles: MarkdownStyles::default(),
+            theme,
+            styles: MarkdownStyles::new(theme),
             lines: Vec::new(),
             current_segments: Vec::new(),
             inline_style_stack: Vec::new(),
@@ -141,6 +150,8 @@ impl Renderer {
             pending_heading: None,
             active_link_dest: None,
             in_code_block: false,
+            code_block_lang: None,
+            code_block_buffer: String::new(),
             needs_block_spacing: false,
             table_state: None,
         }
@@ -216,6 +227,7 @@ impl Renderer {
                         format!("  [{lang}]"),
                         self.styles.code_block.add_modifier(Modifier::BOLD),
                     )]));
+                    self.code_block_lang = Some(lang.to_string());
                 }
                 self.prefix_stack.push(PrefixSegment {
                     text: "  ".to_string(),
@@ -273,6 +285,7 @@ impl Renderer {
                 self.needs_block_spacing = false;
             }
             TagEnd::CodeBlock => {
+                self.finish_highlighted_code_block();
                 self.flush_line(false);
                 self.in_code_block = false;
                 self.pop_prefix(PrefixKind::Plain);
@@ -326,6 +339,10 @@ impl Renderer {
             style = self.styles.heading.patch(style);
         }
         if self.in_code_block {
+            if self.code_block_lang.is_some() {
+                self.code_block_buffer.push_str(text);
+                return;
+            }
             self.push_code_block_text(text, self.styles.code_block.patch(style));
             return;
         }
@@ -516,6 +533,28 @@ impl Renderer {
         self.needs_block_spacing = true;
     }
 
+    /// Emit the buffered text of a fenced block through [`CodeBlockWidget`],
+    /// falling back to the plain code-block style for unknown languages.
+    fn finish_highlighted_code_block(&mut self) {
+        let Some(lang) = self.code_block_lang.take() else {
+            return;
+        };
+        let code = std::mem::take(&mut self.code_block_buffer);
+        match CodeBlockWidget::new(&lang, &code, self.theme).highlighted_lines() {
+            Some(lines) => {
+                for (idx, spans) in lines.into_iter().enumerate() {
+                    if idx > 0 {
+                        self.flush_line(false);
+                    }
+                    for span in spans {
+                        self.push_inline(span.content.as_ref(), span.style);
+                    }
+                }
+            }
+            None => self.push_code_block_text(&code, self.styles.code_block),
+        }
+    }
+
     fn push_code_block_text(&mut self, text: &str, style: Style) {
         for (idx, line) in text.split('\n').enumerate() {
             if idx > 0 {
@@ -941,6 +980,7 @@ fn table_border_line(
 #[cfg(test)]
 mod tests {
     use super::render_markdown_lines;
+    use crate::internal::tui::theme::{self, Theme};
 
     fn lines_to_strings(lines: &[ratatui::text::Line<'static>]) -> Vec<String> {
         lines.iter().map(ToString::to_string).collect()
@@ -951,6 +991,7 @@ mod tests {
         let lines = render_markdown_lines(
             "# Title\n\n- first item\n- second item\n\n```rust\nfn main() {}\n```",
             60,
+            &Theme::default(),
         );
         let rendered = lines_to_strings(&lines);
         assert!(rendered.iter().any(|line| line.contains("# Title")));
@@ -958,10 +999,42 @@ mod tests {
         assert!(rendered.iter().any(|line| line.contains("fn main() {}")));
     }
 
+    #[test]
+    fn highlights_fenced_code_block_with_known_language() {
+        let plain_fg = theme::markdown::code_block(&Theme::default()).fg;
+
+        let lines = render_markdown_lines("```rust\nlet x = 1;\n```", 60, &Theme::default());
+        let code_line = lines
+            .iter()
+            .find(|line| line.to_string().contains("let x = 1;"))
+            .expect("code line should render");
+        assert!(
+            code_line.spans.iter().any(|span| span.style.fg != plain_fg),
+            "known languages should be syntax highlighted"
+        );
+
+        let plain =
+            render_markdown_lines("```not-a-language\nlet x = 1;\n```", 60, &Theme::default());
+        let plain_line = plain
+            .iter()
+            .find(|line| line.to_string().contains("let x = 1;"))
+            .expect("code line should render");
+        assert!(
+            plain_line
+                .spans
+                .iter()
+                .all(|span| span.style.fg == plain_fg),
+            "unknown languages fall back to the plain code-block style"
+        );
+    }
+
     #[test]
     fn preserves_code_block_line_breaks() {
-        let lines =
-            render_markdown_lines("```rust\nfn main() {\n    println!(\"hi\");\n}\n```", 60);
+        let lines = render_markdown_lines(
+            "```rust\nfn main() {\n    println!(\"hi\");\n}\n```",
+            60,
+            &Theme::default(),
+        );
         let rendered = lines_to_strings(&lines);
         assert!(rendered.iter().any(|line| line.contains("[rust]")));
         assert!(rendered.iter().any(|line| line.contains("fn main() {")));
@@ -978,6 +1051,7 @@ mod tests {
         let lines = render_markdown_lines(
             "- this is a long list item that should wrap onto another line",
             24,
+            &Theme::default(),
         );
         let rendered = lines_to_strings(&lines);
         assert!(rendered.first().is_some_and(|line| line.starts_with("• ")));
@@ -989,6 +1063,7 @@ mod tests {
         let lines = render_markdown_lines(
             "| Name | Value |\n| ---- | ----: |\n| alpha | 12 |\n| beta | 345 |",
             60,
+            &Theme::default(),
         );
         let rendered = lines_to_strings(&lines);
         assert!(rendered.iter().any(|line| line.contains("┌")));
@@ -1002,6 +1077,7 @@ mod tests {
         let lines = render_markdown_lines(
             "| Column | Details |\n| --- | --- |\n| alpha | this cell should wrap in a narrow terminal |",
             28,
+            &Theme::default(),
         );
         let rendered = lines_to_strings(&lines);
         assert!(rendered.iter().any(|line| line.contains("this cell")));
@@ -22,13 +22,17 @@
 //! - [`app_event`]: typed event bus shared between the agent and the UI.
 //! - [`bottom_pane`]: composer, slash-command palette, modal popups, focus.
 //! - [`chatwidget`]: scrollback transcript and per-turn history rendering.
+//! - [`clipboard`]: OSC 52 clipboard writes for `y` on a focused cell.
 //! - [`diff`]: shared diff-rendering primitives used by transcript cells.
+//! - [`file_tree`]: `Ctrl+B` working-tree explorer panel on the left.
 //! - [`history_cell`]: pluggable cell types (assistant text, diffs, plans, ...).
 //! - [`markdown_render`]: Markdown-to-ratatui converter used inside cells.
+//! - [`plan_sidebar`]: `/plan` checklist panel mirroring the agent's plan.
 //! - [`slash_command`]: built-in `/help`, `/clear`, ... command parser.
 //! - [`status_indicator`]: spinner/elapsed-time widget shown while busy.
 //! - [`terminal`]: crossterm setup/teardown, event streaming, alt-screen.
 //! - [`theme`]: shared semantic colours/styles consumed by every widget.
+//! - [`transfer_progress`]: byte-count line fed by a `ChannelProgress` sink.
 //! - [`welcome_shader`]: animated "L I B R A   C O D E" splash on startup.
 //!
 //! Only a handful of items are re-exported; everything else is module-private
@@ -48,12 +52,20 @@ pub mod control;
 mod bottom_pane;
 // Scrollback transcript widget.
 mod chatwidget;
+// OSC 52 clipboard writes.
+mod clipboard;
+// Syntax-highlighted fenced code blocks.
+pub mod code_block;
 // Diff rendering primitives.
 mod diff;
+// Collapsible working-tree explorer panel.
+mod file_tree;
 // Pluggable transcript cell types.
 mod history_cell;
 // Markdown-to-ratatui converter.
 mod markdown_render;
+// Session-wide plan checklist panel on the right.
+mod plan_sidebar;
 // Built-in slash command parser.
 mod slash_command;
 // Typed parser for the `/goal` subcommand family.
@@ -68,6 +80,8 @@ mod status_indicator;
 mod terminal;
 // Shared theme palette and semantic styles.
 mod theme;
+// Byte-count line for clone/fetch/push transfers.
+mod transfer_progress;
 // Animated welcome screen.
 mod welcome_shader;
 
@@ -83,3 +97,5 @@ pub use history_cell::{AssistantHistoryCell, DiffHistoryCell, HistoryCell, PlanU
 pub use slash_command::{BuiltinCommand, parse_builtin};
 pub use status_indicator::StatusIndicator;
 pub use terminal::{Tui, TuiEvent, init as tui_init, restore as tui_restore};
+pub use theme::{BUILTIN_THEMES, THEME_FILE_NAME, Theme, ThemeLoadError};
+pub use transfer_progress::TransferProgress;
@@ -0,0 +1,191 @@
+//! Plan checklist shown as a right-side panel (`/plan` with no arguments).
+//!
+//! The sidebar mirrors the latest `update_plan` call for the whole session: it
+//! lives on the [`super::app::App`] rather than in the transcript, so it
+//! survives `/clear` and stays on screen while the history scrolls. Rows are
+//! produced by the same checklist renderer as the transcript's plan cell; the
+//! mouse wheel scrolls the panel when the pointer is over it.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use super::{history_cell::plan_checklist_lines, theme};
+use crate::internal::ai::tools::context::{PlanStep, StepStatus};
+
+/// Default panel width in columns; clamped to a third of the screen.
+pub const PLAN_SIDEBAR_WIDTH: u16 = 36;
+
+/// Session-wide view of the agent's current plan.
+#[derive(Debug, Default)]
+pub struct PlanSidebar {
+    explanation: Option<String>,
+    steps: Vec<PlanStep>,
+    visible: bool,
+    /// First rendered line (after wrapping).
+    offset: usize,
+    /// Panel area from the last render, for mouse hit-testing.
+    last_area: Option<Rect>,
+}
+
+impl PlanSidebar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        if !self.visible {
+            self.last_area = None;
+        }
+    }
+
+    /// Replace the mirrored plan with the arguments of an `update_plan` call.
+    pub fn set_plan(&mut self, explanation: Option<String>, steps: Vec<PlanStep>) {
+        self.explanation = explanation;
+        self.steps = steps;
+    }
+
+    /// Width the panel should occupy inside a screen `total_width` wide.
+    pub fn panel_width(total_width: u16) -> u16 {
+        PLAN_SIDEBAR_WIDTH.min(total_width / 3)
+    }
+
+    /// Whether `(x, y)` falls inside the panel drawn by the last render.
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        self.last_area.is_some_and(|area| {
+            x >= area.x
+                && x < area.x.saturating_add(area.width)
+                && y >= area.y
+                && y < area.y.saturating_add(area.height)
+        })
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.offset = self.offset.saturating_sub(lines);
+    }
+
+    /// Scroll down; clamped against the content height at render time.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.offset = self.offset.saturating_add(lines);
+    }
+
+    fn title(&self) -> String {
+        if self.steps.is_empty() {
+            return " Plan ".to_string();
+        }
+        let done = self
+            .steps
+            .iter()
+            .filter(|step| matches!(step.status, StepStatus::Completed | StepStatus::Skipped))
+            .count();
+        format!(" Plan {done}/{} ", self.steps.len())
+    }
+
+    fn lines(&self, width: u16) -> Vec<Line<'static>> {
+        if self.steps.is_empty() {
+            return vec![Line::styled(
+                "  No plan yet. The agent's update_plan steps appear here.",
+                theme::text::muted(),
+            )];
+        }
+        plan_checklist_lines(self.explanation.as_deref(), &self.steps, &[], width)
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::LEFT | Borders::TOP | Borders::BOTTOM)
+            .border_style(theme::border::idle())
+            .title(Line::styled(self.title(), theme::interactive::title()));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        self.last_area = Some(area);
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let lines = self.lines(inner.width);
+        let height = usize::from(inner.height);
+        self.offset = self.offset.min(lines.len().saturating_sub(height));
+        let visible: Vec<Line<'static>> =
+            lines.into_iter().skip(self.offset).take(height).collect();
+        Paragraph::new(visible).render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(text: &str, status: StepStatus) -> PlanStep {
+        PlanStep {
+            step: text.to_string(),
+            status,
+        }
+    }
+
+    fn rendered_rows(sidebar: &mut PlanSidebar, area: Rect) -> Vec<String> {
+        let mut buf = Buffer::empty(area);
+        sidebar.render(area, &mut buf);
+        (area.y..area.y + area.height)
+            .map(|y| {
+                (area.x..area.x + area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sidebar_shows_progress_title_and_checklist() {
+        let mut sidebar = PlanSidebar::new();
+        sidebar.set_plan(
+            Some("Halfway there".to_string()),
+            vec![
+                step("parse args", StepStatus::Completed),
+                step("write tests", StepStatus::InProgress),
+                step("update docs", StepStatus::Skipped),
+                step("ship", StepStatus::Pending),
+            ],
+        );
+        let rows = rendered_rows(&mut sidebar, Rect::new(0, 0, 30, 8));
+        assert!(rows[0].contains("Plan 2/4"), "title: {}", rows[0]);
+        let body = rows.join("\n");
+        for expected in [
+            "Halfway there",
+            "✔ parse args",
+            "◐ write tests",
+            "⊘ update docs",
+            "□ ship",
+        ] {
+            assert!(body.contains(expected), "missing {expected:?} in:\n{body}");
+        }
+    }
+
+    #[test]
+    fn sidebar_scroll_is_clamped_to_content() {
+        let mut sidebar = PlanSidebar::new();
+        sidebar.set_plan(
+            None,
+            (0..10)
+                .map(|index| step(&format!("step {index}"), StepStatus::Pending))
+                .collect(),
+        );
+        sidebar.scroll_down(100);
+        // 4 visible rows inside the top/bottom border leaves 6 lines above.
+        let rows = rendered_rows(&mut sidebar, Rect::new(0, 0, 30, 6));
+        assert!(rows[1].contains("step 6"), "first row: {}", rows[1]);
+        assert!(rows[4].contains("step 9"), "last row: {}", rows[4]);
+
+        sidebar.scroll_up(100);
+        let rows = rendered_rows(&mut sidebar, Rect::new(0, 0, 30, 6));
+        assert!(rows[1].contains("step 0"), "first row: {}", rows[1]);
+    }
+}
@@ -117,7 +117,7 @@ impl BuiltinCommand {
             Self::Model => "Show current model info",
             Self::Status => "Show current status",
             Self::Usage => "Show current session usage",
-            Self::Plan => "Generate validated IntentSpec from a request",
+            Self::Plan => "Generate IntentSpec from a request; bare /plan toggles sidebar",
             Self::Skill => "Activate a markdown skill",
             Self::Intent => "IntentSpec utilities (show latest or execute it)",
             Self::Mux => "Control task mux view during parallel execution",
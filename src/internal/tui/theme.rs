@@ -14,8 +14,19 @@
 //!    ratio of at least 3.0 against both pure white and pure black (see
 //!    [`tests::semantic_rgb_colors_contrast_with_light_and_dark_backgrounds`]),
 //!    so the TUI is legible regardless of the user's terminal theme.
+//!
+//! On top of the fixed palette, a [`Theme`] carries the user-configurable
+//! colours for AI response rendering (message, tool-call, diff, code-block and
+//! status-bar colours). It is loaded from `.libra/theme.toml`, starting from one
+//! of the built-in themes (`default`, `solarized-dark`, `nord`). The app owns it
+//! as an `Arc<Theme>` and hands it to the renderers; the role accessors for
+//! those colours take it as an argument.
+
+use std::{path::Path, str::FromStr};
 
 use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use thiserror::Error;
 
 // ---------------------------------------------------------------------------
 // Palette: raw colour constants. Don't reference these from outside the
@@ -82,6 +93,200 @@ const WELCOME_GRADIENT: [Color; 6] = [
     ACCENT_PRIMARY,
 ];
 
+/// File name of the per-repository theme under `.libra/`.
+pub const THEME_FILE_NAME: &str = "theme.toml";
+
+/// Names accepted by [`Theme::builtin`] and the `base` key of `theme.toml`.
+pub const BUILTIN_THEMES: [&str; 3] = ["default", "solarized-dark", "nord"];
+
+/// User-configurable colours for AI response rendering.
+///
+/// [`Color::Reset`] means "leave the terminal default", which is how the
+/// `default` theme keeps body text and backgrounds following the terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Built-in theme this one started from.
+    pub name: String,
+    /// Background behind user messages.
+    pub user_message_bg: Color,
+    /// Foreground of assistant message text.
+    pub assistant_fg: Color,
+    /// Summary text of a finished tool-call cell (`● Ran 2 commands`).
+    pub tool_call_header: Color,
+    /// Status bullet of a tool call that succeeded.
+    pub tool_success: Color,
+    /// Status bullet and error text of a tool call that failed.
+    pub tool_error: Color,
+    /// `+` lines and "Added" file headers in diffs.
+    pub diff_added: Color,
+    /// `-` lines and "Deleted" file headers in diffs.
+    pub diff_removed: Color,
+    /// Background of fenced code blocks.
+    pub code_block_bg: Color,
+    /// Background of the status bar under the composer.
+    pub status_bar_bg: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            user_message_bg: Color::Reset,
+            assistant_fg: TEXT_PRIMARY,
+            tool_call_header: TEXT_PRIMARY,
+            tool_success: STATUS_SUCCESS,
+            tool_error: STATUS_DANGER,
+            diff_added: STATUS_SUCCESS,
+            diff_removed: STATUS_DANGER,
+            code_block_bg: Color::Reset,
+            status_bar_bg: Color::Reset,
+        }
+    }
+}
+
+/// Error loading `.libra/theme.toml`.
+#[derive(Debug, Error)]
+pub enum ThemeLoadError {
+    #[error("failed to read theme at '{path}': {source}")]
+    Read {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to parse theme at '{path}': {source}")]
+    Parse {
+        path: String,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("unknown base theme '{0}' (expected one of: {})", BUILTIN_THEMES.join(", "))]
+    UnknownBase(String),
+    #[error("invalid colour '{value}' for '{key}' (use a name like 'blue' or '#rrggbb')")]
+    InvalidColor { key: &'static str, value: String },
+}
+
+/// On-disk shape of `theme.toml`: an optional built-in `base` plus
+/// per-field colour overrides (`"#rrggbb"`, a colour name, or an ANSI index).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    user_message_bg: Option<String>,
+    assistant_fg: Option<String>,
+    tool_call_header: Option<String>,
+    tool_success: Option<String>,
+    tool_error: Option<String>,
+    diff_added: Option<String>,
+    diff_removed: Option<String>,
+    code_block_bg: Option<String>,
+    status_bar_bg: Option<String>,
+}
+
+impl Theme {
+    /// Look up a built-in theme by name (see [`BUILTIN_THEMES`]).
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "solarized-dark" => Some(Self {
+                name: name.to_string(),
+                user_message_bg: Color::Rgb(7, 54, 66),
+                assistant_fg: Color::Rgb(131, 148, 150),
+                tool_call_header: Color::Rgb(38, 139, 210),
+                tool_success: Color::Rgb(133, 153, 0),
+                tool_error: Color::Rgb(220, 50, 47),
+                diff_added: Color::Rgb(133, 153, 0),
+                diff_removed: Color::Rgb(220, 50, 47),
+                code_block_bg: Color::Rgb(7, 54, 66),
+                status_bar_bg: Color::Rgb(7, 54, 66),
+            }),
+            "nord" => Some(Self {
+                name: name.to_string(),
+                user_message_bg: Color::Rgb(59, 66, 82),
+                assistant_fg: Color::Rgb(216, 222, 233),
+                tool_call_header: Color::Rgb(136, 192, 208),
+                tool_success: Color::Rgb(163, 190, 140),
+                tool_error: Color::Rgb(191, 97, 106),
+                diff_added: Color::Rgb(163, 190, 140),
+                diff_removed: Color::Rgb(191, 97, 106),
+                code_block_bg: Color::Rgb(59, 66, 82),
+                status_bar_bg: Color::Rgb(59, 66, 82),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Parse the contents of a `theme.toml`.
+    pub fn from_toml_str(contents: &str, path: &Path) -> Result<Self, ThemeLoadError> {
+        let file: ThemeFile = toml::from_str(contents).map_err(|source| ThemeLoadError::Parse {
+            path: path.display().to_string(),
+            source,
+        })?;
+        let base = file.base.as_deref().unwrap_or("default");
+        let mut theme =
+            Self::builtin(base).ok_or_else(|| ThemeLoadError::UnknownBase(base.to_string()))?;
+
+        let overrides = [
+            (
+                "user_message_bg",
+                &file.user_message_bg,
+                &mut theme.user_message_bg,
+            ),
+            ("assistant_fg", &file.assistant_fg, &mut theme.assistant_fg),
+            (
+                "tool_call_header",
+                &file.tool_call_header,
+                &mut theme.tool_call_header,
+            ),
+            ("tool_success", &file.tool_success, &mut theme.tool_success),
+            ("tool_error", &file.tool_error, &mut theme.tool_error),
+            ("diff_added", &file.diff_added, &mut theme.diff_added),
+            ("diff_removed", &file.diff_removed, &mut theme.diff_removed),
+            (
+                "code_block_bg",
+                &file.code_block_bg,
+                &mut theme.code_block_bg,
+            ),
+            (
+                "status_bar_bg",
+                &file.status_bar_bg,
+                &mut theme.status_bar_bg,
+            ),
+        ];
+        for (key, value, slot) in overrides {
+            if let Some(value) = value {
+                *slot =
+                    Color::from_str(value.trim()).map_err(|_| ThemeLoadError::InvalidColor {
+                        key,
+                        value: value.clone(),
+                    })?;
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Load `path` if it exists, otherwise return the `default` theme.
+    pub fn load_or_default(path: &Path) -> Result<Self, ThemeLoadError> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(path).map_err(|source| ThemeLoadError::Read {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::from_toml_str(&contents, path)
+    }
+}
+
+/// `Style::bg`, skipped for [`Color::Reset`] so the default theme leaves
+/// existing styles untouched.
+fn with_bg(style: Style, color: Color) -> Style {
+    if color == Color::Reset {
+        style
+    } else {
+        style.bg(color)
+    }
+}
+
 /// Text colour roles for body copy and annotations.
 pub(crate) mod text {
     use super::*;
@@ -199,6 +404,21 @@ pub(crate) mod tool {
     pub(crate) fn draft() -> Style {
         Style::default().fg(ACCENT_DRAFT)
     }
+
+    /// Summary text of a finished tool-call cell.
+    pub(crate) fn header(theme: &Theme) -> Style {
+        Style::default().fg(theme.tool_call_header)
+    }
+
+    /// Status bullet of a successful tool call.
+    pub(crate) fn success_color(theme: &Theme) -> Color {
+        theme.tool_success
+    }
+
+    /// Status bullet and error text of a failed tool call.
+    pub(crate) fn error_color(theme: &Theme) -> Color {
+        theme.tool_error
+    }
 }
 
 /// Styles for status pills (ready, awaiting input, warning, ...).
@@ -210,11 +430,6 @@ pub(crate) mod status {
         STATUS_SUCCESS
     }
 
-    /// Raw danger colour for callers that need to compose their own style.
-    pub(crate) fn danger_color() -> Color {
-        STATUS_DANGER
-    }
-
     /// Plain success style.
     pub(crate) fn success() -> Style {
         Style::default().fg(STATUS_SUCCESS)
@@ -251,6 +466,11 @@ pub(crate) mod status {
             .add_modifier(Modifier::BOLD)
     }
 
+    /// Background of the status bar row.
+    pub(crate) fn bar(theme: &Theme) -> Style {
+        with_bg(Style::default(), theme.status_bar_bg)
+    }
+
     /// Warning text colour (orange-amber).
     pub(crate) fn warning() -> Style {
         Style::default().fg(ACCENT_EDIT)
@@ -262,6 +482,73 @@ pub(crate) mod status {
     }
 }
 
+/// History search (`/`) styles consumed by [`super::chatwidget`].
+pub(crate) mod search {
+    use super::*;
+
+    /// Background highlight for query matches inside history cells.
+    pub(crate) fn highlight() -> Style {
+        Style::default()
+            .bg(ACCENT_EDIT)
+            .add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Chat history styles consumed by [`super::chatwidget`].
+pub(crate) mod history {
+    use super::*;
+
+    /// Background for the cell selected with `Up`/`Down` (the `y` copy target).
+    pub(crate) fn focused_cell() -> Style {
+        Style::default().bg(ACCENT_PRIMARY)
+    }
+
+    /// User message text and background.
+    pub(crate) fn user_message(theme: &Theme) -> Style {
+        with_bg(Style::default().fg(ACCENT_PRIMARY), theme.user_message_bg)
+    }
+
+    /// Base style for assistant message text; Markdown spans patch over it.
+    pub(crate) fn assistant_message(theme: &Theme) -> Style {
+        Style::default().fg(theme.assistant_fg)
+    }
+}
+
+/// Row styles for the file tree panel ([`super::file_tree`]).
+pub(crate) mod file_tree {
+    use super::*;
+
+    /// Directory rows.
+    pub(crate) fn directory() -> Style {
+        Style::default().fg(ACCENT_EXPLORE)
+    }
+
+    /// Tracked file with no changes.
+    pub(crate) fn clean() -> Style {
+        Style::default().fg(TEXT_PRIMARY)
+    }
+
+    /// New file staged in the index.
+    pub(crate) fn added() -> Style {
+        Style::default().fg(STATUS_SUCCESS)
+    }
+
+    /// Modified file (staged or not).
+    pub(crate) fn modified() -> Style {
+        Style::default().fg(ACCENT_EDIT)
+    }
+
+    /// Deleted file (staged or not).
+    pub(crate) fn deleted() -> Style {
+        Style::default().fg(STATUS_DANGER)
+    }
+
+    /// File on disk that is not tracked.
+    pub(crate) fn untracked() -> Style {
+        Style::default().fg(TEXT_MUTED)
+    }
+}
+
 /// Markdown rendering styles consumed by [`super::markdown_render`].
 pub(crate) mod markdown {
     use super::*;
@@ -277,8 +564,13 @@ pub(crate) mod markdown {
     }
 
     /// Fenced `\`\`\`` code block bodies.
-    pub(crate) fn code_block() -> Style {
-        Style::default().fg(ACCENT_EDIT)
+    pub(crate) fn code_block(theme: &Theme) -> Style {
+        code_block_background(theme).fg(ACCENT_EDIT)
+    }
+
+    /// Background applied to every fenced code block line, highlighted or not.
+    pub(crate) fn code_block_background(theme: &Theme) -> Style {
+        with_bg(Style::default(), theme.code_block_bg)
     }
 
     /// Hyperlink text. Underlined for affordance even though terminals
@@ -320,13 +612,13 @@ pub(crate) mod diff {
     use super::*;
 
     /// File header bullet for an "Added" change.
-    pub(crate) fn added_header_color() -> Color {
-        STATUS_SUCCESS
+    pub(crate) fn added_header_color(theme: &Theme) -> Color {
+        theme.diff_added
     }
 
     /// File header bullet for a "Deleted" change.
-    pub(crate) fn removed_header_color() -> Color {
-        STATUS_DANGER
+    pub(crate) fn removed_header_color(theme: &Theme) -> Color {
+        theme.diff_removed
     }
 
     /// File header bullet for an "Update" change.
@@ -345,13 +637,13 @@ pub(crate) mod diff {
     }
 
     /// `+` insertion lines.
-    pub(crate) fn added_line() -> Style {
-        status::success()
+    pub(crate) fn added_line(theme: &Theme) -> Style {
+        Style::default().fg(theme.diff_added)
     }
 
     /// `-` deletion lines.
-    pub(crate) fn removed_line() -> Style {
-        status::danger()
+    pub(crate) fn removed_line(theme: &Theme) -> Style {
+        Style::default().fg(theme.diff_removed)
     }
 }
 
@@ -438,6 +730,70 @@ mod tests {
         }
     }
 
+    /// Scenario: every advertised built-in resolves, and `default` keeps the
+    /// fixed palette so users without a `theme.toml` see no change.
+    #[test]
+    fn builtin_themes_resolve_and_default_matches_palette() {
+        for name in BUILTIN_THEMES {
+            assert_eq!(
+                Theme::builtin(name).map(|theme| theme.name),
+                Some(name.to_string())
+            );
+        }
+        assert_eq!(Theme::builtin("solarized-light"), None);
+
+        let theme = Theme::default();
+        assert_eq!(theme.assistant_fg, TEXT_PRIMARY);
+        assert_eq!(theme.diff_added, STATUS_SUCCESS);
+        assert_eq!(theme.diff_removed, STATUS_DANGER);
+        assert_eq!(
+            with_bg(Style::default(), theme.code_block_bg),
+            Style::default()
+        );
+    }
+
+    /// Scenario: `theme.toml` starts from its `base` and overrides individual
+    /// colours given as hex, names or ANSI indices.
+    #[test]
+    fn theme_toml_overrides_colours_on_top_of_base() {
+        let path = Path::new(".libra/theme.toml");
+        let theme = Theme::from_toml_str(
+            "base = \"nord\"\ntool_error = \"#ff0000\"\nassistant_fg = \"white\"\ncode_block_bg = \"236\"\n",
+            path,
+        )
+        .unwrap();
+        let nord = Theme::builtin("nord").unwrap();
+        assert_eq!(theme.name, "nord");
+        assert_eq!(theme.tool_error, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.assistant_fg, Color::White);
+        assert_eq!(theme.code_block_bg, Color::Indexed(236));
+        assert_eq!(theme.diff_added, nord.diff_added);
+
+        assert_eq!(Theme::from_toml_str("", path).unwrap(), Theme::default());
+    }
+
+    /// Scenario: a bad base, colour or key is reported instead of silently
+    /// ignored, so `libra code` can warn about it.
+    #[test]
+    fn theme_toml_rejects_unknown_base_colour_and_key() {
+        let path = Path::new(".libra/theme.toml");
+        assert!(matches!(
+            Theme::from_toml_str("base = \"dracula\"", path),
+            Err(ThemeLoadError::UnknownBase(name)) if name == "dracula"
+        ));
+        assert!(matches!(
+            Theme::from_toml_str("diff_added = \"not-a-colour\"", path),
+            Err(ThemeLoadError::InvalidColor {
+                key: "diff_added",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Theme::from_toml_str("user_bg = \"red\"", path),
+            Err(ThemeLoadError::Parse { .. })
+        ));
+    }
+
     fn assert_min_contrast(foreground: Color, background: Color, minimum: f64) {
         let ratio = contrast_ratio(foreground, background);
         assert!(
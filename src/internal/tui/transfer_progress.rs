@@ -0,0 +1,122 @@
+//! Transfer progress line for network operations started from the TUI.
+//!
+//! A transport reports into a [`ChannelProgress`] handed to it by the caller;
+//! the TUI keeps the matching receiver and calls [`TransferProgress::drain`]
+//! once per frame, so the transfer never blocks on the terminal. The widget
+//! renders a single dim line of the form
+//! `<label> <done> / <total> (<pct>%)`, or `<label> <done>` while the total is
+//! unknown.
+//!
+//! [`ChannelProgress`]: crate::utils::progress::ChannelProgress
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Paragraph, Widget},
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::utils::{progress::ProgressEvent, util};
+
+/// Accumulated state of one transfer, rebuilt from [`ProgressEvent`]s.
+#[derive(Debug, Default)]
+pub struct TransferProgress {
+    /// Current phase, e.g. `Receiving objects`.
+    label: String,
+    /// Expected byte count, once the transport knows it.
+    total: Option<u64>,
+    /// Bytes completed so far.
+    done: u64,
+    /// Set by [`ProgressEvent::Finish`]; hides the widget.
+    finished: bool,
+}
+
+impl TransferProgress {
+    /// Fold one event into the state. Events after `Finish` are ignored.
+    pub fn apply(&mut self, event: ProgressEvent) {
+        if self.finished {
+            return;
+        }
+        match event {
+            ProgressEvent::Total(n) => self.total = Some(n),
+            ProgressEvent::Inc(delta) => self.done = self.done.saturating_add(delta),
+            ProgressEvent::Message(msg) => self.label = msg,
+            ProgressEvent::Finish => self.finished = true,
+        }
+    }
+
+    /// Apply every event currently queued on `rx` without waiting.
+    pub fn drain(&mut self, rx: &mut UnboundedReceiver<ProgressEvent>) {
+        while let Ok(event) = rx.try_recv() {
+            self.apply(event);
+        }
+    }
+
+    /// Whether the widget would render anything: a transfer has started and
+    /// not yet finished.
+    pub fn is_visible(&self) -> bool {
+        !self.finished && (!self.label.is_empty() || self.done > 0 || self.total.is_some())
+    }
+
+    /// The text of the rendered line.
+    fn line_text(&self) -> String {
+        let done = util::auto_unit_bytes(self.done);
+        match self.total {
+            Some(total) if total > 0 => {
+                let pct = (self.done.min(total) * 100) / total;
+                let total = util::auto_unit_bytes(total);
+                format!("{} {done:.2} / {total:.2} ({pct}%)", self.label)
+            }
+            _ => format!("{} {done:.2}", self.label),
+        }
+    }
+
+    /// Render the progress line into `buf`; a no-op when invisible.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if !self.is_visible() {
+            return;
+        }
+        let style = Style::default().add_modifier(Modifier::DIM);
+        Paragraph::new(Line::styled(self.line_text(), style)).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::progress::{ChannelProgress, Progress};
+
+    #[test]
+    fn drain_accumulates_channel_events() {
+        let (sink, mut rx) = ChannelProgress::channel();
+        sink.set_message("Writing objects");
+        sink.set_total(200);
+        sink.inc(50);
+        sink.inc(50);
+
+        let mut progress = TransferProgress::default();
+        progress.drain(&mut rx);
+        assert!(progress.is_visible());
+        assert_eq!(progress.done, 100);
+        assert!(progress.line_text().ends_with("(50%)"));
+
+        sink.finish();
+        sink.inc(100);
+        progress.drain(&mut rx);
+        assert!(!progress.is_visible());
+        assert_eq!(progress.done, 100);
+    }
+
+    #[test]
+    fn unknown_total_omits_percentage() {
+        let mut progress = TransferProgress::default();
+        assert!(!progress.is_visible());
+        progress.apply(ProgressEvent::Message("Receiving objects".to_string()));
+        progress.apply(ProgressEvent::Inc(10));
+        let text = progress.line_text();
+        assert!(text.starts_with("Receiving objects "));
+        assert!(!text.contains('%'));
+    }
+}
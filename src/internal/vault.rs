@@ -41,7 +41,10 @@ use serde_json::Value;
 use crate::utils::util::try_get_storage_path;
 
 const VAULT_DB_NAME: &str = "vault.db";
-const PGP_KEY_NAME: &str = "libra-signing";
+/// Vault PGP key generated by `libra init`, used when `user.signingkey` is unset.
+pub const DEFAULT_PGP_KEY_NAME: &str = "libra-signing";
+/// Config key naming the vault PGP key that signs and verifies, as in Git.
+pub const SIGNING_KEY_CONFIG: &str = "user.signingkey";
 const SSH_ROLE_NAME: &str = "libra-ssh";
 const PKI_MOUNT_PATH: &str = "pki";
 
@@ -158,11 +161,12 @@ pub async fn init_vault(root_dir: &Path) -> Result<(Vec<u8>, Vec<u8>)> {
     Ok((unseal_key, enc_token))
 }
 
-/// Generate a PGP key pair in the vault for commit signing.
+/// Generate the PGP key pair `key_name` in the vault for commit signing.
 #[allow(dead_code)]
 pub async fn generate_pgp_key(
     root_dir: &Path,
     unseal_key: &[u8],
+    key_name: &str,
     user_name: &str,
     user_email: &str,
 ) -> Result<String> {
@@ -177,7 +181,7 @@ pub async fn generate_pgp_key(
     vault.set_token(&root_token);
 
     let data = serde_json::json!({
-        "key_name": PGP_KEY_NAME,
+        "key_name": key_name,
         "key_type": "pgp",
         "name": user_name,
         "email": user_email,
@@ -202,7 +206,7 @@ pub async fn generate_pgp_key(
 
     // Store in config so it can be exported without requiring backend-specific
     // read-path support.
-    upsert_config_value("vault.gpg.pubkey", &public_key).await;
+    upsert_config_value(&pgp_public_key_config(key_name), &public_key).await;
 
     vault
         .seal()
@@ -212,10 +216,15 @@ pub async fn generate_pgp_key(
     Ok(public_key)
 }
 
-/// Sign data using the vault's PGP key.
+/// Sign data using the vault PGP key `key_name`.
 ///
 /// `data` is the raw bytes to sign. Returns the hex-encoded detached signature.
-pub async fn pgp_sign(root_dir: &Path, unseal_key: &[u8], data: &[u8]) -> Result<String> {
+pub async fn pgp_sign(
+    root_dir: &Path,
+    unseal_key: &[u8],
+    key_name: &str,
+    data: &[u8],
+) -> Result<String> {
     let vault = create_vault(root_dir).await?;
 
     vault
@@ -228,7 +237,7 @@ pub async fn pgp_sign(root_dir: &Path, unseal_key: &[u8], data: &[u8]) -> Result
 
     let data_hex = hex::encode(data);
     let req_data = serde_json::json!({
-        "key_name": PGP_KEY_NAME,
+        "key_name": key_name,
         "data": data_hex,
     });
 
@@ -255,12 +264,13 @@ pub async fn pgp_sign(root_dir: &Path, unseal_key: &[u8], data: &[u8]) -> Result
     Ok(signature_hex)
 }
 
-/// Verify a hex-encoded PGP `signature` over `data` using the vault PGP key.
-/// Mirrors [`pgp_sign`] but calls the `keys/verify` endpoint and returns whether
-/// the signature is valid.
+/// Verify a hex-encoded PGP `signature` over `data` using the vault PGP key
+/// `key_name`. Mirrors [`pgp_sign`] but calls the `keys/verify` endpoint and
+/// returns whether the signature is valid.
 pub async fn pgp_verify(
     root_dir: &Path,
     unseal_key: &[u8],
+    key_name: &str,
     data: &[u8],
     signature_hex: &str,
 ) -> Result<bool> {
@@ -275,7 +285,7 @@ pub async fn pgp_verify(
     vault.set_token(&root_token);
 
     let req_data = serde_json::json!({
-        "key_name": PGP_KEY_NAME,
+        "key_name": key_name,
         "data": hex::encode(data),
         "signature": signature_hex,
     });
@@ -308,6 +318,101 @@ pub async fn pgp_verify(
     Ok(valid)
 }
 
+/// The key a signature was verified against: its primary user id and its
+/// fingerprint (upper-case hex). Reported as the signer instead of the
+/// unverified author, committer or tagger header.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PgpSigner {
+    pub user_id: String,
+    pub fingerprint: String,
+}
+
+impl PgpSigner {
+    /// Read the identity of an ASCII-armored public key.
+    #[cfg(feature = "signing")]
+    pub fn from_armored_public_key(armored: &str) -> Result<Self> {
+        use pgp::{
+            composed::{Deserializable, SignedPublicKey},
+            types::KeyDetails,
+        };
+
+        let (key, _) = SignedPublicKey::from_armor_single(armored.as_bytes())
+            .context("failed to parse PGP public key")?;
+        let user_id = key
+            .details
+            .users
+            .first()
+            .map(|user| String::from_utf8_lossy(user.id.id()).into_owned())
+            .unwrap_or_default();
+        Ok(Self {
+            user_id,
+            fingerprint: format!("{:X}", key.fingerprint()),
+        })
+    }
+
+    #[cfg(not(feature = "signing"))]
+    pub fn from_armored_public_key(_armored: &str) -> Result<Self> {
+        Err(anyhow!(
+            "this build of libra does not include signature support (the `signing` feature)"
+        ))
+    }
+}
+
+impl std::fmt::Display for PgpSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\" (key {})", self.user_id, self.fingerprint)
+    }
+}
+
+/// Name of the vault PGP key that signs and verifies: `user.signingkey` from
+/// the repository config when set, otherwise [`DEFAULT_PGP_KEY_NAME`].
+pub async fn signing_key_name() -> String {
+    use crate::internal::config::ConfigKv;
+
+    ConfigKv::get(SIGNING_KEY_CONFIG)
+        .await
+        .ok()
+        .flatten()
+        .map(|entry| entry.value.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_PGP_KEY_NAME.to_string())
+}
+
+/// Config key caching the armored public key of vault PGP key `key_name`.
+pub fn pgp_public_key_config(key_name: &str) -> String {
+    if key_name == DEFAULT_PGP_KEY_NAME {
+        "vault.gpg.pubkey".to_string()
+    } else {
+        format!("vault.gpg.{key_name}.pubkey")
+    }
+}
+
+/// Verify like [`pgp_verify`] and, for a good signature, return the identity
+/// of the key `key_name` that verified it (`None` for a bad signature).
+pub async fn pgp_verify_signer(
+    root_dir: &Path,
+    unseal_key: &[u8],
+    key_name: &str,
+    data: &[u8],
+    signature_hex: &str,
+) -> Result<Option<PgpSigner>> {
+    use crate::internal::config::ConfigKv;
+
+    if !pgp_verify(root_dir, unseal_key, key_name, data, signature_hex).await? {
+        return Ok(None);
+    }
+    let mut public_key = ConfigKv::get(&pgp_public_key_config(key_name))
+        .await
+        .ok()
+        .flatten();
+    if public_key.is_none() && key_name == DEFAULT_PGP_KEY_NAME {
+        public_key = ConfigKv::get("vault.gpg_pubkey").await.ok().flatten();
+    }
+    let public_key =
+        public_key.ok_or_else(|| anyhow!("no public key recorded for vault key '{key_name}'"))?;
+    PgpSigner::from_armored_public_key(&public_key.value).map(Some)
+}
+
 /// Decode an ASCII-armored PGP signature block back into the hex-encoded
 /// signature bytes (the inverse of [`signature_to_armored`]). Used to verify a
 /// signature that was embedded in an annotated tag.
@@ -552,7 +657,7 @@ pub async fn get_gpg_public_key(root_dir: &Path, unseal_key: &[u8]) -> Result<St
     vault.set_token(&root_token);
 
     let read_result = async {
-        let pgp_key_path = format!("{PKI_MOUNT_PATH}/keys/{PGP_KEY_NAME}");
+        let pgp_key_path = format!("{PKI_MOUNT_PATH}/keys/{DEFAULT_PGP_KEY_NAME}");
         let resp = vault
             .read(Some(root_token), &pgp_key_path)
             .await
@@ -301,6 +301,7 @@ pub struct LockRequest {
 #[derive(Serialize, Deserialize)]
 pub struct LockResponse {
     pub lock: Lock,
+    #[serde(default)]
     pub message: String,
 }
 
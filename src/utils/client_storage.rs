@@ -56,7 +56,13 @@ use crate::{
         model::object_index,
     },
     utils::{
-        storage::{Storage, local::LocalStorage, remote::RemoteStorage, tiered::TieredStorage},
+        storage::{
+            Storage,
+            local::{DEFAULT_MAX_CACHED_BYTES, LocalStorage, ObjectCacheConfig},
+            promisor::PromisorStorage,
+            remote::RemoteStorage,
+            tiered::{DedupReport, TieredStorage, VerifyDedupReport},
+        },
         util::{DATABASE, try_get_storage_path},
     },
 };
@@ -157,6 +163,29 @@ pub const DEFAULT_STORAGE_THRESHOLD_BYTES: usize = 1024 * 1024;
 /// Default local LRU disk budget for large cached objects (200 MiB).
 pub const DEFAULT_CACHE_SIZE_BYTES: usize = 200 * 1024 * 1024;
 
+/// Byte budget of the in-memory decompressed-object cache in [`LocalStorage`]
+/// (`LIBRA_OBJECT_CACHE_SIZE`), with the same lenient parse as the other
+/// tunables: an unset, unresolvable, or unparseable value keeps the default.
+/// `0` disables the cache.
+fn object_cache_bytes() -> usize {
+    resolve_env_sync("LIBRA_OBJECT_CACHE_SIZE")
+        .ok()
+        .flatten()
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or(DEFAULT_MAX_CACHED_BYTES)
+}
+
+/// A [`LocalStorage`] whose object cache honours `LIBRA_OBJECT_CACHE_SIZE`.
+fn local_storage(base_path: PathBuf) -> LocalStorage {
+    LocalStorage::with_cache_config(
+        base_path,
+        ObjectCacheConfig {
+            max_cached_bytes: object_cache_bytes(),
+            ..ObjectCacheConfig::default()
+        },
+    )
+}
+
 /// The resolved tiered-storage / LRU-cache tunables (lore.md §0.10). Exposes the
 /// existing `LIBRA_STORAGE_*` knobs for inspection via `libra cache info`.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -176,6 +205,9 @@ pub struct CacheConfig {
     pub threshold_bytes: usize,
     /// Local LRU disk budget in bytes (`LIBRA_STORAGE_CACHE_SIZE`).
     pub cache_size_bytes: usize,
+    /// In-memory decompressed-object cache budget in bytes
+    /// (`LIBRA_OBJECT_CACHE_SIZE`). Applies to every backend, tiered or not.
+    pub object_cache_bytes: usize,
 }
 
 /// Resolve the cache/storage tunables the way [`ClientStorage::create_storage_backend`]
@@ -248,6 +280,7 @@ pub fn resolve_cache_config() -> Result<CacheConfig, String> {
         tiered,
         threshold_bytes,
         cache_size_bytes,
+        object_cache_bytes: object_cache_bytes(),
     })
 }
 
@@ -262,6 +295,8 @@ impl ClientStorage {
     /// - Picks the storage backend based on env / vault config (see
     ///   [`Self::create_storage_backend`]). Local-only when `LIBRA_STORAGE_TYPE` is
     ///   absent.
+    /// - Wraps the backend in [`PromisorStorage`] so a partial clone fetches the
+    ///   objects its `--filter` omitted on first read.
     ///
     /// Boundary conditions:
     /// - Never panics on misconfiguration: any unrecoverable env error degrades to
@@ -269,7 +304,10 @@ impl ClientStorage {
     ///   `LIBRA_STORAGE_*` setting silently disables remote backup instead of stopping
     ///   the CLI.
     pub fn init(base_path: PathBuf) -> ClientStorage {
-        let storage = Self::create_storage_backend(base_path.clone());
+        let storage = Arc::new(PromisorStorage::new(
+            Self::create_storage_backend(base_path.clone()),
+            base_path.clone(),
+        ));
         ClientStorage { storage, base_path }
     }
 
@@ -281,7 +319,7 @@ impl ClientStorage {
     /// fall back to the configured remote on a miss and could write fetched
     /// objects back into that foreign directory using cloud credentials.
     pub fn init_local(base_path: PathBuf) -> ClientStorage {
-        let storage = Arc::new(LocalStorage::new(base_path.clone()));
+        let storage = Arc::new(local_storage(base_path.clone()));
         ClientStorage { storage, base_path }
     }
 
@@ -308,6 +346,8 @@ impl ClientStorage {
     /// - `LIBRA_STORAGE_THRESHOLD` and `LIBRA_STORAGE_CACHE_SIZE` accept any
     ///   parseable usize and silently fall back to defaults (1 MiB, 200 MiB) when the
     ///   value is not a valid number.
+    /// - `LIBRA_OBJECT_CACHE_SIZE` sizes the in-memory object cache of every
+    ///   `LocalStorage` built here, the local-only fallbacks included.
     /// - The `expect("Failed to build S3 storage")` is the one panicking path: it
     ///   only fires if the partial AWS builder is missing a required field, which
     ///   should be impossible given the explicit checks above.
@@ -317,7 +357,7 @@ impl ClientStorage {
         let storage_type = match resolve_env_sync("LIBRA_STORAGE_TYPE") {
             Ok(Some(storage_type)) => storage_type,
             Ok(None) => {
-                return Arc::new(LocalStorage::new(base_path));
+                return Arc::new(local_storage(base_path));
             }
             Err(err) => {
                 return Self::storage_config_resolution_fallback(
@@ -343,7 +383,7 @@ impl ClientStorage {
             eprintln!(
                 "Warning: LIBRA_STORAGE_BUCKET cannot be empty. Falling back to local storage."
             );
-            return Arc::new(LocalStorage::new(base_path));
+            return Arc::new(local_storage(base_path));
         }
 
         // Build ObjectStore
@@ -383,7 +423,7 @@ impl ClientStorage {
                             "Warning: Invalid LIBRA_STORAGE_ENDPOINT URL: {}. Falling back to local storage.",
                             endpoint
                         );
-                        return Arc::new(LocalStorage::new(base_path));
+                        return Arc::new(local_storage(base_path));
                     }
                     builder = builder.with_endpoint(endpoint);
                 }
@@ -415,7 +455,7 @@ impl ClientStorage {
                         eprintln!(
                             "Warning: LIBRA_STORAGE_ACCESS_KEY cannot be empty. Falling back to local storage."
                         );
-                        return Arc::new(LocalStorage::new(base_path));
+                        return Arc::new(local_storage(base_path));
                     }
                     builder = builder.with_access_key_id(key);
                 }
@@ -434,7 +474,7 @@ impl ClientStorage {
                         eprintln!(
                             "Warning: LIBRA_STORAGE_SECRET_KEY cannot be empty. Falling back to local storage."
                         );
-                        return Arc::new(LocalStorage::new(base_path));
+                        return Arc::new(local_storage(base_path));
                     }
                     builder = builder.with_secret_access_key(secret);
                 }
@@ -465,7 +505,7 @@ impl ClientStorage {
                     "Warning: Unsupported storage type: {}. Falling back to local storage.",
                     storage_type
                 );
-                return Arc::new(LocalStorage::new(base_path));
+                return Arc::new(local_storage(base_path));
             }
         };
 
@@ -473,7 +513,7 @@ impl ClientStorage {
             Some(repo_id) => RemoteStorage::new_with_prefix(object_store, repo_id),
             None => RemoteStorage::new(object_store),
         };
-        let local = LocalStorage::new(base_path.clone());
+        let local = local_storage(base_path.clone());
 
         let threshold = match resolve_env_sync("LIBRA_STORAGE_THRESHOLD") {
             Ok(Some(raw_threshold)) => raw_threshold
@@ -528,7 +568,7 @@ impl ClientStorage {
             "Warning: failed to resolve {}: {}. Falling back to local storage.",
             name, error
         );
-        Arc::new(LocalStorage::new(base_path.to_path_buf()))
+        Arc::new(local_storage(base_path.to_path_buf()))
     }
 
     /// Helper to execute async task on dedicated runtime and block waiting for result.
@@ -608,6 +648,14 @@ impl ClientStorage {
         self.block_on_storage(async move { storage.get(&hash).await.map(|(data, _)| data) })
     }
 
+    /// Uncompressed size of an object's payload, read from its header where
+    /// the backend can (see [`crate::utils::storage::Storage::object_size`]).
+    pub fn object_size(&self, object_id: &ObjectHash) -> Result<usize, GitError> {
+        let storage = self.storage.clone();
+        let hash = *object_id;
+        self.block_on_storage(async move { storage.object_size(&hash).await })
+    }
+
     /// Attempt to repair a missing or corrupted object from the durable tier
     /// (`libra fsck --heal`, lore.md §0.4).
     ///
@@ -622,6 +670,21 @@ impl ClientStorage {
         self.block_on_storage(async move { storage.heal(&hash).await })
     }
 
+    /// Replace large local objects the durable tier already holds with redirect
+    /// markers (`libra cache dedup`). `Ok(None)` when the configured backend is
+    /// local-only. See [`crate::utils::storage::Storage::dedup`].
+    pub fn dedup(&self) -> Result<Option<DedupReport>, GitError> {
+        let storage = self.storage.clone();
+        self.block_on_storage(async move { storage.dedup().await })
+    }
+
+    /// Check that every dedup redirect still resolves (`libra cache
+    /// verify-dedup`). `None` when the configured backend is local-only.
+    pub fn verify_dedup(&self) -> Option<VerifyDedupReport> {
+        let storage = self.storage.clone();
+        self.block_on_storage(async move { storage.verify_dedup().await })
+    }
+
     /// Persist a Git object and queue a background index update.
     ///
     /// Functional scope:
@@ -1053,6 +1116,10 @@ impl Storage for ClientStorage {
     async fn search(&self, prefix: &str) -> Vec<ObjectHash> {
         ClientStorage::search(self, prefix).await
     }
+
+    async fn object_size(&self, hash: &ObjectHash) -> Result<usize, GitError> {
+        ClientStorage::object_size(self, hash)
+    }
 }
 
 /// Resolve an environment variable, checking both system env and vault config.
@@ -0,0 +1,260 @@
+//! Classic Git hooks (`pre-commit`, `commit-msg`, ...), run the way Git runs
+//! them.
+//!
+//! A hook is an executable file named after its event in the hooks directory:
+//! `core.hooksPath` when set (a relative path resolves against the working
+//! tree, a leading `~/` against `$HOME`), otherwise `.libra/hooks`. A hook file
+//! without the executable bit is ignored, as in Git. Hooks run from the
+//! working-tree root.
+//!
+//! The `pre-commit.sh` / `pre-commit.ps1` scripts installed by `libra init` are
+//! Libra's own convention and keep running alongside these.
+//!
+//! Commands only decide *when* a hook runs; [`run_hook`] maps the outcome to an
+//! error message, and the `post-*` helpers build the arguments Git passes to
+//! its notification hooks.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+};
+
+use git_internal::hash::{ObjectHash, get_hash_kind};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::{
+    internal::config::{LocalIdentityTarget, read_cascaded_config_value},
+    utils::{output::OutputConfig, path, util},
+};
+
+/// Config key overriding the hooks directory, as in Git.
+pub const HOOKS_PATH_CONFIG: &str = "core.hooksPath";
+
+/// The directory hooks are looked up in.
+pub async fn hooks_dir() -> PathBuf {
+    match read_cascaded_config_value(LocalIdentityTarget::CurrentRepo, HOOKS_PATH_CONFIG)
+        .await
+        .ok()
+        .flatten()
+    {
+        Some(value) => resolve_hooks_path(&value, &util::working_dir()),
+        None => path::hooks(),
+    }
+}
+
+fn resolve_hooks_path(value: &str, work_dir: &Path) -> PathBuf {
+    let expanded = match (value.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(value),
+    };
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        work_dir.join(expanded)
+    }
+}
+
+/// The installed hook `name`, if it exists and is executable.
+pub async fn find_hook(name: &str) -> Option<PathBuf> {
+    let hook = hooks_dir().await.join(name);
+    is_executable(&hook).then_some(hook)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Run hook `name` with `args`, writing `stdin` to it when given.
+///
+/// Returns `Ok(None)` when no such hook is installed, otherwise its exit
+/// status; callers decide whether a failure aborts the operation. Hook output
+/// is shown in human mode and captured in JSON mode so it never mixes with
+/// structured output.
+///
+/// # Errors
+///
+/// Returns an I/O error when the hook cannot be spawned.
+pub async fn run_git_hook(
+    name: &str,
+    args: &[&str],
+    stdin: Option<&[u8]>,
+    output: &OutputConfig,
+) -> io::Result<Option<ExitStatus>> {
+    let Some(hook) = find_hook(name).await else {
+        return Ok(None);
+    };
+
+    // Git for Windows runs hooks through its bundled `sh`; elsewhere the
+    // shebang line picks the interpreter.
+    #[cfg(unix)]
+    let mut command = Command::new(&hook);
+    #[cfg(not(unix))]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.arg(&hook);
+        command
+    };
+
+    let (stdout_cfg, stderr_cfg) = if output.is_json() {
+        (Stdio::piped(), Stdio::piped())
+    } else {
+        (Stdio::inherit(), Stdio::inherit())
+    };
+    let mut child = command
+        .args(args)
+        .current_dir(util::working_dir())
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(stdout_cfg)
+        .stderr(stderr_cfg)
+        .spawn()?;
+
+    if let (Some(data), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        // A hook that does not read its input may exit first and close the
+        // pipe; its exit status still decides the outcome.
+        match pipe.write_all(data).await {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(Some(child.wait_with_output().await?.status))
+}
+
+/// Run hook `name` (see [`run_git_hook`]); `Err` carries the failure detail
+/// when the hook is installed and does not succeed.
+pub async fn run_hook(
+    name: &str,
+    args: &[&str],
+    stdin: Option<&[u8]>,
+    output: &OutputConfig,
+) -> Result<(), String> {
+    match run_git_hook(name, args, stdin, output).await {
+        Ok(None) => Ok(()),
+        Ok(Some(status)) if status.success() => Ok(()),
+        Ok(Some(status)) => Err(format!("exit code {}", status.code().unwrap_or(-1))),
+        Err(e) => Err(format!("failed to execute hook '{name}': {e}")),
+    }
+}
+
+/// Run a hook that only notifies: like Git, its exit status cannot undo the
+/// operation that triggered it, so a failure is logged and otherwise ignored.
+pub async fn run_notify_hook(
+    name: &str,
+    args: &[&str],
+    stdin: Option<&[u8]>,
+    output: &OutputConfig,
+) {
+    if let Err(detail) = run_hook(name, args, stdin, output).await {
+        tracing::warn!("{name} hook failed: {detail}");
+    }
+}
+
+/// `post-checkout <old HEAD> <new HEAD> <flag>`, where the flag is `1` for a
+/// branch checkout and `0` for a file checkout. A missing side (unborn or
+/// orphan HEAD) is passed as the all-zero object id.
+pub async fn run_post_checkout_hook(
+    old_head: Option<&str>,
+    new_head: Option<&str>,
+    branch_checkout: bool,
+    output: &OutputConfig,
+) {
+    let zero_oid = ObjectHash::zero_str(get_hash_kind());
+    let old_head = old_head.unwrap_or(&zero_oid);
+    let new_head = new_head.unwrap_or(&zero_oid);
+    let flag = if branch_checkout { "1" } else { "0" };
+    run_notify_hook("post-checkout", &[old_head, new_head, flag], None, output).await;
+}
+
+/// `post-merge <squash>`, where the flag is `1` for a squash merge.
+pub async fn run_post_merge_hook(squash: bool, output: &OutputConfig) {
+    let flag = if squash { "1" } else { "0" };
+    run_notify_hook("post-merge", &[flag], None, output).await;
+}
+
+/// The command that rewrote commits, passed as `post-rewrite`'s argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewriteKind {
+    Amend,
+    Rebase,
+}
+
+impl RewriteKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            RewriteKind::Amend => "amend",
+            RewriteKind::Rebase => "rebase",
+        }
+    }
+}
+
+/// `post-rewrite <amend|rebase>`, with one `<old sha> <new sha>` line per
+/// rewritten commit on stdin. Nothing runs when no commit was rewritten.
+pub async fn run_post_rewrite_hook(
+    kind: RewriteKind,
+    rewritten: &[(String, String)],
+    output: &OutputConfig,
+) {
+    if rewritten.is_empty() {
+        return;
+    }
+    let stdin = rewrite_list(rewritten);
+    run_notify_hook(
+        "post-rewrite",
+        &[kind.as_str()],
+        Some(stdin.as_bytes()),
+        output,
+    )
+    .await;
+}
+
+fn rewrite_list(rewritten: &[(String, String)]) -> String {
+    rewritten
+        .iter()
+        .map(|(old, new)| format!("{old} {new}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn resolve_hooks_path_anchors_relative_paths_at_the_work_tree() {
+        let work_dir = Path::new("/repo");
+        assert_eq!(
+            resolve_hooks_path(".githooks", work_dir),
+            PathBuf::from("/repo/.githooks")
+        );
+        assert_eq!(
+            resolve_hooks_path("/srv/hooks", work_dir),
+            PathBuf::from("/srv/hooks")
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(resolve_hooks_path("~/hooks", work_dir), home.join("hooks"));
+        }
+    }
+
+    #[test]
+    fn rewrite_list_has_one_line_per_rewritten_commit() {
+        let rewritten = vec![
+            ("aaa".to_string(), "bbb".to_string()),
+            ("ccc".to_string(), "ddd".to_string()),
+        ];
+        assert_eq!(rewrite_list(&rewritten), "aaa bbb\nccc ddd\n");
+    }
+}
@@ -0,0 +1,370 @@
+//! Git attribute lookup over `.gitattributes` and `.libra_attributes`, with the
+//! `text`/`eol` normalization applied on staging, its `eol=crlf` counterpart
+//! applied when writing the worktree, `filter=lfs` routing, and the binary
+//! checks used by `diff` and the conflict writers.
+//!
+//! Sources, lowest precedence first (a later matching line wins, like Git):
+//! the global file (`core.attributesFile`, else `$XDG_CONFIG_HOME/git/attributes`
+//! or `~/.config/git/attributes`), the worktree root `.gitattributes`, the root
+//! `.libra_attributes`, then `.gitattributes` files in subdirectories from the
+//! root down to the path's own directory.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use super::util;
+use crate::internal::config::ConfigKv;
+
+const GITATTRIBUTES_FILE: &str = ".gitattributes";
+
+/// The state of one attribute on a path. An attribute that no line mentions
+/// (or that a `!attr` line reset) is "unspecified" and simply absent from a
+/// lookup result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrValue {
+    /// `attr`
+    Set,
+    /// `-attr`
+    Unset,
+    /// `attr=value`
+    Value(String),
+}
+
+#[derive(Debug)]
+struct AttrRule {
+    matcher: Gitignore,
+    /// `None` resets the attribute to unspecified (`!attr`).
+    attrs: Vec<(String, Option<AttrValue>)>,
+}
+
+/// Attribute rules for one worktree. Subdirectory `.gitattributes` files are
+/// read lazily, once per directory, the first time a path beneath them is
+/// looked up.
+#[derive(Debug)]
+pub struct Attributes {
+    root: PathBuf,
+    /// Global, root `.gitattributes`, and root `.libra_attributes` rules.
+    base: Vec<AttrRule>,
+    /// Subdirectory rules keyed by worktree-relative directory.
+    nested: Mutex<HashMap<PathBuf, Vec<AttrRule>>>,
+}
+
+impl Attributes {
+    /// Load the rules for the current worktree, resolving the global file from
+    /// `core.attributesFile`.
+    pub async fn for_worktree() -> Self {
+        let configured = ConfigKv::get("core.attributesFile")
+            .await
+            .ok()
+            .flatten()
+            .map(|entry| entry.value)
+            .filter(|value| !value.trim().is_empty());
+        Self::load(&util::working_dir(), global_attributes_file(configured))
+    }
+
+    /// Load the rules rooted at `root` with Git's default global file, for
+    /// synchronous callers that cannot consult `core.attributesFile`.
+    pub fn load_default(root: &Path) -> Self {
+        Self::load(root, global_attributes_file(None))
+    }
+
+    /// Load the rules rooted at `root` plus an optional global file.
+    pub fn load(root: &Path, global: Option<PathBuf>) -> Self {
+        let mut base = Vec::new();
+        if let Some(global) = global {
+            base.extend(read_rules(&global, root));
+        }
+        base.extend(read_rules(&root.join(GITATTRIBUTES_FILE), root));
+        base.extend(read_rules(&root.join(util::ATTRIBUTES), root));
+        Self {
+            root: root.to_path_buf(),
+            base,
+            nested: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Every specified attribute on `path` (worktree-relative).
+    pub fn lookup(&self, path: &Path) -> HashMap<String, AttrValue> {
+        let mut result = HashMap::new();
+        apply_rules(&self.base, path, &mut result);
+
+        let mut nested = self
+            .nested
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut dir = PathBuf::new();
+        let parents: Vec<_> = path
+            .parent()
+            .into_iter()
+            .flat_map(|parent| parent.components())
+            .collect();
+        for component in parents {
+            dir.push(component);
+            let rules = nested.entry(dir.clone()).or_insert_with(|| {
+                let base = self.root.join(&dir);
+                read_rules(&base.join(GITATTRIBUTES_FILE), &base)
+            });
+            if let Ok(relative) = path.strip_prefix(&dir) {
+                apply_rules(rules, relative, &mut result);
+            }
+        }
+        result
+    }
+
+    /// A single attribute on `path`, or `None` when unspecified.
+    pub fn get(&self, path: &Path, name: &str) -> Option<AttrValue> {
+        self.lookup(path).remove(name)
+    }
+}
+
+/// Whether `data` looks binary the way Git guesses it: a NUL byte in the first
+/// 8000 bytes.
+pub fn is_binary_content(data: &[u8]) -> bool {
+    data.iter().take(8000).any(|&byte| byte == 0)
+}
+
+/// Whether a conflicted path must not receive conflict markers: `-merge` (which
+/// the `binary` macro implies) or binary content on any side.
+pub fn is_binary_merge(attrs: &HashMap<String, AttrValue>, sides: &[&[u8]]) -> bool {
+    attrs.get("merge") == Some(&AttrValue::Unset)
+        || sides.iter().any(|side| is_binary_content(side))
+}
+
+/// Normalize worktree content for the index: CRLF becomes LF when `text` is set,
+/// when `eol` is set and `text` is not unset, or when `text=auto` and the
+/// content is not binary. Everything else is stored verbatim.
+pub fn convert_to_index(attrs: &HashMap<String, AttrValue>, data: Vec<u8>) -> Vec<u8> {
+    let normalize = match attrs.get("text") {
+        Some(AttrValue::Set) => true,
+        Some(AttrValue::Unset) => false,
+        Some(AttrValue::Value(value)) if value == "auto" => !is_binary_content(&data),
+        Some(AttrValue::Value(_)) => false,
+        None => matches!(attrs.get("eol"), Some(AttrValue::Value(_))),
+    };
+    if !normalize || !data.windows(2).any(|pair| pair == b"\r\n") {
+        return data;
+    }
+    let mut out = Vec::with_capacity(data.len());
+    let mut bytes = data.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'\r' && bytes.peek() == Some(&&b'\n') {
+            continue;
+        }
+        out.push(byte);
+    }
+    out
+}
+
+/// Convert index content for the worktree, the smudge side of
+/// [`convert_to_index`]: a bare LF becomes CRLF when `eol=crlf` is set and
+/// `text` is not unset (for `text=auto`, only when the content is not binary).
+/// Everything else, including `eol=lf`, is written verbatim.
+pub fn convert_to_worktree(attrs: &HashMap<String, AttrValue>, data: Vec<u8>) -> Vec<u8> {
+    if !matches!(attrs.get("eol"), Some(AttrValue::Value(eol)) if eol == "crlf") {
+        return data;
+    }
+    let convert = match attrs.get("text") {
+        Some(AttrValue::Unset) => false,
+        Some(AttrValue::Value(value)) if value == "auto" => !is_binary_content(&data),
+        _ => true,
+    };
+    if !convert || !data.contains(&b'\n') {
+        return data;
+    }
+    let mut out = Vec::with_capacity(data.len() + data.len() / 16);
+    let mut previous = None;
+    for &byte in &data {
+        if byte == b'\n' && previous != Some(b'\r') {
+            out.push(b'\r');
+        }
+        out.push(byte);
+        previous = Some(byte);
+    }
+    out
+}
+
+/// Whether `filter=lfs` routes the path through Git LFS.
+pub fn is_lfs_filter(attrs: &HashMap<String, AttrValue>) -> bool {
+    matches!(attrs.get("filter"), Some(AttrValue::Value(filter)) if filter == "lfs")
+}
+
+/// Parse attribute file `content` whose patterns are relative to `base`.
+/// Negative patterns are rejected by Git and skipped here; `binary` expands to
+/// `binary -diff -merge -text`.
+fn parse_rules(content: &str, base: &Path) -> Vec<AttrRule> {
+    let mut rules = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        let Some(pattern) = tokens.next() else {
+            continue;
+        };
+        if pattern.starts_with('!') {
+            tracing::warn!(pattern, "negative patterns are ignored in attribute files");
+            continue;
+        }
+        let mut builder = GitignoreBuilder::new(base);
+        if builder.add_line(None, pattern).is_err() {
+            continue;
+        }
+        let Ok(matcher) = builder.build() else {
+            continue;
+        };
+
+        let mut attrs = Vec::new();
+        for token in tokens {
+            if let Some(name) = token.strip_prefix('-') {
+                attrs.push((name.to_string(), Some(AttrValue::Unset)));
+            } else if let Some(name) = token.strip_prefix('!') {
+                attrs.push((name.to_string(), None));
+            } else if let Some((name, value)) = token.split_once('=') {
+                attrs.push((name.to_string(), Some(AttrValue::Value(value.to_string()))));
+            } else if token == "binary" {
+                attrs.push(("binary".to_string(), Some(AttrValue::Set)));
+                for name in ["diff", "merge", "text"] {
+                    attrs.push((name.to_string(), Some(AttrValue::Unset)));
+                }
+            } else {
+                attrs.push((token.to_string(), Some(AttrValue::Set)));
+            }
+        }
+        rules.push(AttrRule { matcher, attrs });
+    }
+    rules
+}
+
+fn read_rules(file: &Path, base: &Path) -> Vec<AttrRule> {
+    fs::read_to_string(file)
+        .map(|content| parse_rules(&content, base))
+        .unwrap_or_default()
+}
+
+fn apply_rules(rules: &[AttrRule], path: &Path, result: &mut HashMap<String, AttrValue>) {
+    for rule in rules {
+        if !rule.matcher.matched(path, false).is_ignore() {
+            continue;
+        }
+        for (name, value) in &rule.attrs {
+            match value {
+                Some(value) => {
+                    result.insert(name.clone(), value.clone());
+                }
+                None => {
+                    result.remove(name);
+                }
+            }
+        }
+    }
+}
+
+/// `core.attributesFile` (with `~/` expanded), else Git's XDG default.
+fn global_attributes_file(configured: Option<String>) -> Option<PathBuf> {
+    if let Some(configured) = configured {
+        return match configured.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None => Some(PathBuf::from(configured)),
+        };
+    }
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(config_home.join("git").join("attributes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs_for(content: &str, path: &str) -> HashMap<String, AttrValue> {
+        let root = Path::new("/repo");
+        let mut result = HashMap::new();
+        apply_rules(&parse_rules(content, root), Path::new(path), &mut result);
+        result
+    }
+
+    #[test]
+    fn later_lines_override_and_bang_resets() {
+        let attrs = attrs_for("*.txt text eol=lf\ndocs/*.txt -text\n", "docs/a.txt");
+        assert_eq!(attrs.get("text"), Some(&AttrValue::Unset));
+        assert_eq!(attrs.get("eol"), Some(&AttrValue::Value("lf".to_string())));
+
+        let attrs = attrs_for("*.txt text\n*.txt !text\n", "a.txt");
+        assert!(!attrs.contains_key("text"));
+    }
+
+    #[test]
+    fn binary_macro_unsets_diff_merge_and_text() {
+        let attrs = attrs_for("*.png binary\n", "img/logo.png");
+        assert_eq!(attrs.get("binary"), Some(&AttrValue::Set));
+        for name in ["diff", "merge", "text"] {
+            assert_eq!(attrs.get(name), Some(&AttrValue::Unset), "{name}");
+        }
+    }
+
+    #[test]
+    fn convert_to_index_follows_text_and_eol() {
+        let crlf = b"a\r\nb\r\n".to_vec();
+        let text = attrs_for("*.txt text\n", "a.txt");
+        assert_eq!(convert_to_index(&text, crlf.clone()), b"a\nb\n");
+        let eol = attrs_for("*.bat eol=crlf\n", "run.bat");
+        assert_eq!(convert_to_index(&eol, crlf.clone()), b"a\nb\n");
+        let unset = attrs_for("*.txt -text\n", "a.txt");
+        assert_eq!(convert_to_index(&unset, crlf.clone()), crlf);
+        let none = attrs_for("", "a.txt");
+        assert_eq!(convert_to_index(&none, crlf.clone()), crlf);
+
+        let auto = attrs_for("* text=auto\n", "a.bin");
+        let binary = b"\0\r\n".to_vec();
+        assert_eq!(convert_to_index(&auto, binary.clone()), binary);
+        assert_eq!(convert_to_index(&auto, crlf), b"a\nb\n");
+    }
+
+    #[test]
+    fn convert_to_worktree_only_adds_cr_for_eol_crlf() {
+        let lf = b"a\nb\r\nc\n".to_vec();
+        let crlf = attrs_for("*.bat eol=crlf\n", "run.bat");
+        assert_eq!(convert_to_worktree(&crlf, lf.clone()), b"a\r\nb\r\nc\r\n");
+        let text_crlf = attrs_for("*.txt text eol=crlf\n", "a.txt");
+        assert_eq!(
+            convert_to_worktree(&text_crlf, lf.clone()),
+            b"a\r\nb\r\nc\r\n"
+        );
+        let lf_attrs = attrs_for("*.txt text eol=lf\n", "a.txt");
+        assert_eq!(convert_to_worktree(&lf_attrs, lf.clone()), lf);
+        let unset = attrs_for("*.bin -text eol=crlf\n", "x.bin");
+        assert_eq!(convert_to_worktree(&unset, lf.clone()), lf);
+
+        let auto = attrs_for("* text=auto eol=crlf\n", "x.dat");
+        let binary = b"\0\n".to_vec();
+        assert_eq!(convert_to_worktree(&auto, binary.clone()), binary);
+    }
+
+    #[test]
+    fn lfs_filter_is_read_from_attributes() {
+        assert!(is_lfs_filter(&attrs_for(
+            "*.psd filter=lfs diff=lfs\n",
+            "art/a.psd"
+        )));
+        assert!(!is_lfs_filter(&attrs_for("*.psd filter=other\n", "a.psd")));
+        assert!(!is_lfs_filter(&attrs_for("", "a.psd")));
+    }
+
+    #[test]
+    fn binary_merge_detection_uses_attribute_or_nul_bytes() {
+        let none = HashMap::new();
+        assert!(!is_binary_merge(&none, &[b"text", b"more"]));
+        assert!(is_binary_merge(&none, &[b"text", b"a\0b"]));
+        let attrs = attrs_for("*.dat -merge\n", "x.dat");
+        assert!(is_binary_merge(&attrs, &[b"text"]));
+    }
+}
@@ -0,0 +1,181 @@
+//! External GnuPG verification for `verify-commit` / `verify-tag`.
+//!
+//! The vault backend can only check signatures made by this repository's own
+//! key. Objects signed elsewhere (e.g. imported from Git) carry ordinary
+//! OpenPGP signatures, which this module hands to the user's `gpg` binary
+//! together with the signed payload, exactly like `git verify-commit`:
+//!
+//! ```text
+//! gpg --status-fd=1 --verify <detached-signature-file> -   # payload on stdin
+//! ```
+//!
+//! The program is `gpg.program` from config when set, otherwise `gpg`, falling
+//! back to `gpg2` when `gpg` is not installed. The verdict is read from the
+//! machine-readable `[GNUPG:]` status lines; the human-readable stderr is kept
+//! verbatim for `--verbose`.
+
+use std::{io, process::Stdio};
+
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::internal::{config::ConfigKv, vault::PgpSigner};
+
+/// Config key overriding the GnuPG binary, as in Git.
+pub const GPG_PROGRAM_CONFIG: &str = "gpg.program";
+
+/// Outcome of one `gpg --verify` run, derived from its status lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpgVerdict {
+    /// `GOODSIG`: valid signature from a key in the keyring, identified by its
+    /// user id and the `VALIDSIG` fingerprint (the `GOODSIG` key id without one).
+    Good { signer: PgpSigner },
+    /// `BADSIG`, or a good signature from an expired or revoked key.
+    Bad { signer: String },
+    /// `ERRSIG` / `NO_PUBKEY`: the signature could not be checked, usually
+    /// because the signing key is not in the keyring.
+    Unchecked,
+}
+
+/// Result of [`verify_detached`].
+#[derive(Debug, Clone)]
+pub struct GpgVerification {
+    pub verdict: GpgVerdict,
+    /// GnuPG's human-readable output (its stderr), shown by `--verbose`.
+    pub output: String,
+}
+
+/// Verify the armored detached `signature` over `payload` with the configured
+/// GnuPG program.
+///
+/// # Errors
+///
+/// Returns [`io::ErrorKind::NotFound`] when no GnuPG binary is installed, and
+/// other I/O errors when the temporary signature file or the child process
+/// cannot be set up.
+pub async fn verify_detached(signature: &str, payload: &[u8]) -> io::Result<GpgVerification> {
+    let mut sig_file = tempfile::NamedTempFile::new()?;
+    io::Write::write_all(&mut sig_file, signature.as_bytes())?;
+    if !signature.ends_with('\n') {
+        io::Write::write_all(&mut sig_file, b"\n")?;
+    }
+
+    let configured = ConfigKv::get(GPG_PROGRAM_CONFIG)
+        .await
+        .ok()
+        .flatten()
+        .map(|entry| entry.value)
+        .filter(|value| !value.trim().is_empty());
+    let candidates = match configured {
+        Some(program) => vec![program],
+        None => vec!["gpg".to_string(), "gpg2".to_string()],
+    };
+
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "gpg not found");
+    for program in candidates {
+        let spawned = Command::new(&program)
+            .arg("--status-fd=1")
+            .arg("--verify")
+            .arg(sig_file.path())
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                last_error = error;
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // gpg may exit early (e.g. unparsable signature) and close its end;
+            // the status lines still carry the verdict, so a broken pipe here
+            // is not an error.
+            match stdin.write_all(payload).await {
+                Ok(()) => {}
+                Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {}
+                Err(error) => return Err(error),
+            }
+        }
+        let output = child.wait_with_output().await?;
+        return Ok(GpgVerification {
+            verdict: parse_status(&String::from_utf8_lossy(&output.stdout)),
+            output: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    Err(last_error)
+}
+
+/// Derive the verdict from `--status-fd` output. Each line is
+/// `[GNUPG:] <KEYWORD> <args…>`; for signature keywords the args are the key
+/// id followed by the user id, and `VALIDSIG` starts with the fingerprint.
+fn parse_status(status: &str) -> GpgVerdict {
+    let mut verdict = GpgVerdict::Unchecked;
+    let mut fingerprint = None;
+    for line in status.lines() {
+        let Some(rest) = line.strip_prefix("[GNUPG:] ") else {
+            continue;
+        };
+        let (keyword, args) = rest.split_once(' ').unwrap_or((rest, ""));
+        let (key_id, signer) = args.split_once(' ').unwrap_or((args, ""));
+        let signer = signer.to_string();
+        match keyword {
+            "GOODSIG" => {
+                verdict = GpgVerdict::Good {
+                    signer: PgpSigner {
+                        user_id: signer,
+                        fingerprint: key_id.to_string(),
+                    },
+                }
+            }
+            "VALIDSIG" => fingerprint = Some(key_id.to_string()),
+            // These follow GOODSIG for expired/revoked keys and win over it.
+            "BADSIG" | "EXPSIG" | "EXPKEYSIG" | "REVKEYSIG" => {
+                return GpgVerdict::Bad { signer };
+            }
+            _ => {}
+        }
+    }
+    if let (GpgVerdict::Good { signer }, Some(fingerprint)) = (&mut verdict, fingerprint) {
+        signer.fingerprint = fingerprint;
+    }
+    verdict
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_status_reads_signature_keywords() {
+        let good = "[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 0123456789ABCDEF Jane Doe <jane@example.com>\n[GNUPG:] VALIDSIG ABC 2024-01-01\n";
+        assert_eq!(
+            parse_status(good),
+            GpgVerdict::Good {
+                signer: PgpSigner {
+                    user_id: "Jane Doe <jane@example.com>".to_string(),
+                    fingerprint: "ABC".to_string(),
+                }
+            }
+        );
+
+        let no_validsig = "[GNUPG:] GOODSIG 0123456789ABCDEF Jane Doe <jane@example.com>\n";
+        assert!(matches!(
+            parse_status(no_validsig),
+            GpgVerdict::Good { signer } if signer.fingerprint == "0123456789ABCDEF"
+        ));
+
+        let bad = "[GNUPG:] BADSIG 0123456789ABCDEF Jane Doe <jane@example.com>\n";
+        assert!(matches!(parse_status(bad), GpgVerdict::Bad { .. }));
+
+        let expired = "[GNUPG:] GOODSIG 0123 Jane\n[GNUPG:] EXPKEYSIG 0123 Jane\n";
+        assert!(matches!(parse_status(expired), GpgVerdict::Bad { .. }));
+
+        let missing = "[GNUPG:] ERRSIG 0123 1 10 00 1700000000 9\n[GNUPG:] NO_PUBKEY 0123\n";
+        assert_eq!(parse_status(missing), GpgVerdict::Unchecked);
+        assert_eq!(parse_status(""), GpgVerdict::Unchecked);
+    }
+}
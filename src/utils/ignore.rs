@@ -430,6 +430,8 @@ mod tests {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
 
@@ -508,6 +510,8 @@ mod tests {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
 
@@ -548,6 +552,8 @@ mod tests {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
 
@@ -635,6 +641,8 @@ mod tests {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
 
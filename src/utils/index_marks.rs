@@ -0,0 +1,143 @@
+//! Per-entry index marks the index reader/writer does not carry.
+//!
+//! Git keeps intent-to-add, skip-worktree and assume-unchanged as bits on the
+//! index entry. The git-internal index drops the extended flags (intent-to-add,
+//! skip-worktree) and sets the assume-valid bit on every new entry, so Libra
+//! lists the marked index keys in a sidecar file per mark instead:
+//! `.libra/intent-to-add`, `.libra/skip-worktree` and `.libra/assume-unchanged`,
+//! one key per line. A listed key only counts while its entry backs the mark —
+//! tracked at stage 0, and for intent-to-add still the empty placeholder blob —
+//! so removing or restaging the entry retires the mark without extra
+//! bookkeeping.
+//!
+//! `libra add -N` records intent-to-add, `libra update-index` sets the other two
+//! by hand, and sparse checkout maintains skip-worktree for the entries it
+//! leaves out of the working tree. Status and diff treat a skip-worktree or
+//! assume-unchanged entry as matching the working tree.
+
+use std::{collections::BTreeSet, fs, io, path::PathBuf};
+
+use git_internal::internal::{index::Index, object::blob::Blob};
+
+use crate::utils::util;
+
+/// A per-entry index mark kept in a sidecar file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexMark {
+    IntentToAdd,
+    SkipWorktree,
+    AssumeUnchanged,
+}
+
+impl IndexMark {
+    fn file_name(self) -> &'static str {
+        match self {
+            IndexMark::IntentToAdd => "intent-to-add",
+            IndexMark::SkipWorktree => "skip-worktree",
+            IndexMark::AssumeUnchanged => "assume-unchanged",
+        }
+    }
+}
+
+fn marker_path(mark: IndexMark) -> io::Result<PathBuf> {
+    Ok(util::try_get_storage_path(None)?.join(mark.file_name()))
+}
+
+/// Keys listed for `mark`, whether or not they are still active.
+fn load_listed(mark: IndexMark) -> BTreeSet<String> {
+    marker_path(mark)
+        .and_then(fs::read_to_string)
+        .map(|data| data.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Rewrite the sidecar file, dropping it entirely when no key is listed.
+fn save_listed(mark: IndexMark, paths: &BTreeSet<String>) -> io::Result<()> {
+    let path = marker_path(mark)?;
+    if paths.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    let mut data = String::new();
+    for entry in paths {
+        data.push_str(entry);
+        data.push('\n');
+    }
+    fs::write(path, data)
+}
+
+/// The empty blob recorded for every intent-to-add entry.
+pub fn placeholder_blob() -> Blob {
+    Blob::from_content("")
+}
+
+/// Index keys currently carrying `mark`: listed and tracked at stage 0, and
+/// for intent-to-add still pointing at the placeholder blob.
+pub fn active(index: &Index, mark: IndexMark) -> BTreeSet<String> {
+    let listed = load_listed(mark).into_iter();
+    match mark {
+        IndexMark::IntentToAdd => {
+            let placeholder = placeholder_blob().id;
+            listed
+                .filter(|path| index.verify_hash(path, 0, &placeholder))
+                .collect()
+        }
+        IndexMark::SkipWorktree | IndexMark::AssumeUnchanged => {
+            listed.filter(|path| index.tracked(path, 0)).collect()
+        }
+    }
+}
+
+/// Set (`on`) or clear `mark` on `paths`. Marks that are no longer active are
+/// pruned at the same time.
+pub fn mark(index: &Index, mark: IndexMark, paths: &[String], on: bool) -> io::Result<()> {
+    let mut listed = active(index, mark);
+    for path in paths {
+        if on {
+            listed.insert(path.clone());
+        } else {
+            listed.remove(path);
+        }
+    }
+    save_listed(mark, &listed)
+}
+
+/// Make `paths` the complete set of keys carrying `mark`.
+pub fn replace(mark: IndexMark, paths: &BTreeSet<String>) -> io::Result<()> {
+    save_listed(mark, paths)
+}
+
+/// Drop `mark` from `paths` without consulting the index. `add` uses it after
+/// staging, so an empty file (whose content equals the placeholder) stops
+/// being intent-to-add too.
+pub fn retire(mark: IndexMark, paths: &[String]) -> io::Result<()> {
+    let mut listed = load_listed(mark);
+    let before = listed.len();
+    for path in paths {
+        listed.remove(path);
+    }
+    if listed.len() == before {
+        return Ok(());
+    }
+    save_listed(mark, &listed)
+}
+
+/// Remove intent-to-add entries from an in-memory index, as Git does when
+/// committing. `commit` strips before its own tree builder and emptiness
+/// checks; every other index→tree writer goes through
+/// [`crate::internal::tree_plumbing::write_tree_from_index`], which skips them.
+pub fn strip_intent_to_add(index: &mut Index) {
+    for path in active(index, IndexMark::IntentToAdd) {
+        index.remove(&path, 0);
+    }
+}
+
+/// Index keys whose working-tree state is ignored: skip-worktree or
+/// assume-unchanged.
+pub fn unchanged_paths(index: &Index) -> BTreeSet<String> {
+    let mut paths = active(index, IndexMark::SkipWorktree);
+    paths.extend(active(index, IndexMark::AssumeUnchanged));
+    paths
+}
@@ -1,6 +1,7 @@
 //! LFS helpers to detect tracked files from attributes, compute SHA256 OIDs, build request payloads/headers, and stream uploads or downloads.
 
 use std::{
+    collections::HashSet,
     fs,
     fs::File,
     io,
@@ -13,11 +14,21 @@ use ignore::{Match, gitignore::GitignoreBuilder};
 use lazy_static::lazy_static;
 use regex::Regex;
 use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue};
-use ring::digest::{Context, SHA256};
+use ring::digest::{Context, SHA256, digest};
+use serde::{Deserialize, Serialize};
 use url::Url;
 use wax::Program;
 
-use crate::utils::{path, path_ext::PathExt, util};
+use crate::{
+    lfs_structs::Lock,
+    utils::{
+        atomic_write::{sync_data_enabled, write_atomic},
+        error::emit_warning,
+        path,
+        path_ext::PathExt,
+        util,
+    },
+};
 
 lazy_static! {
     static ref LFS_PATTERNS: Vec<String> = { // cache
@@ -58,8 +69,21 @@ where
     if LFS_PATTERNS.is_empty() {
         return false;
     }
+    matches_lfs_patterns(&LFS_PATTERNS, path)
+}
 
-    let patterns = LFS_PATTERNS.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+/// Whether the absolute `path` matches any of `patterns`, with the same
+/// gitignore-plus-glob rules as [`is_lfs_tracked`]. Used directly by
+/// `lfs migrate`, whose patterns come from `--include` / `--exclude` rather
+/// than `.libra_attributes`.
+pub fn matches_lfs_patterns<P>(patterns: &[String], path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    if patterns.is_empty() {
+        return false;
+    }
+    let patterns = patterns.iter().map(|s| s.as_str()).collect::<Vec<_>>();
 
     let mut gitignore = GitignoreBuilder::new(util::working_dir());
     patterns.iter().for_each(|&s| {
@@ -102,7 +126,8 @@ const LFS_VERSION: &str = "https://git-lfs.github.com/spec/v1";
 pub const LFS_TRANSFER_API: &str = "basic";
 pub const LFS_HASH_ALGO: &str = "sha256";
 const LFS_OID_LEN: usize = 64;
-const LFS_POINTER_MAX_SIZE: usize = 300; // bytes
+/// Upper bound on the size of a pointer file; anything larger is content.
+pub(crate) const LFS_POINTER_MAX_SIZE: usize = 300; // bytes
 
 /// Generate lfs pointer file string
 /// - return (pointer content, lfs oid)
@@ -136,6 +161,14 @@ pub fn generate_pointer_file(path: impl AsRef<Path>) -> (String, String) {
     (pointer, oid)
 }
 
+/// Generate lfs pointer file string for in-memory content (e.g. `hash-object --stdin`)
+/// - return (pointer content, lfs oid)
+pub fn generate_pointer_data(data: &[u8]) -> (String, String) {
+    let oid = hex::encode(digest(&SHA256, data).as_ref());
+    let pointer = format_pointer_string(&oid, data.len() as u64);
+    (pointer, oid)
+}
+
 pub fn format_pointer_string(oid: &str, size: u64) -> String {
     format!("version {LFS_VERSION}\noid {LFS_HASH_ALGO}:{oid}\nsize {size}\n")
 }
@@ -294,6 +327,21 @@ where
     Ok(())
 }
 
+/// Store in-memory content in the LFS object store, like [`backup_lfs_file`].
+pub fn backup_lfs_data(data: &[u8], oid: &str) -> io::Result<()> {
+    let backup_path = lfs_object_path(oid);
+    if !backup_path.exists() {
+        // INVARIANT: lfs_object_path() always returns `.libra/lfs/objects/AB/CD/<oid>`
+        // which has a parent.
+        let parent = backup_path
+            .parent()
+            .expect("lfs_object_path always produces a path with a parent");
+        fs::create_dir_all(parent)?;
+        fs::write(backup_path, data)?;
+    }
+    Ok(())
+}
+
 /// SHA256 without type
 // `ring` crate is much faster than `sha2` crate ( > 10 times)
 pub fn calc_lfs_file_hash<P>(path: P) -> io::Result<String>
@@ -398,6 +446,168 @@ pub fn extract_lfs_patterns(file_path: &str) -> io::Result<Vec<String>> {
     Ok(patterns)
 }
 
+/// Local LFS lock cache, in `.libra/lfs/locks.json`.
+pub fn lfs_lock_cache_path() -> PathBuf {
+    util::storage_path().join("lfs/locks.json")
+}
+
+/// One lock remembered from the LFS lock API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CachedLock {
+    pub id: String,
+    /// Repository-relative path with `/` separators, as the server reports it.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Held by the current user: reported under `ours` by the server's
+    /// `locks/verify`, or acquired by `libra lfs lock` in this repository.
+    #[serde(default)]
+    pub ours: bool,
+}
+
+/// Locks seen by `lfs lock` / `lfs locks`, so `lfs unlock <path>` can reuse
+/// the acquired id and commit/checkout can warn about other users' locks
+/// without a server round-trip. Like Git LFS's lock cache it is only as fresh
+/// as the last `libra lfs locks`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct LockCache {
+    #[serde(default)]
+    pub locks: Vec<CachedLock>,
+}
+
+impl LockCache {
+    /// Load the repository's cache. A missing file is an empty cache; an
+    /// unreadable one is logged and treated as empty so it can never block a
+    /// commit or checkout.
+    pub fn load() -> Self {
+        Self::load_from(&lfs_lock_cache_path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), error = %err, "failed to read LFS lock cache");
+                return Self::default();
+            }
+        };
+        serde_json::from_slice(&data).unwrap_or_else(|err| {
+            tracing::warn!(path = %path.display(), error = %err, "ignoring corrupt LFS lock cache");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(&lfs_lock_cache_path())
+    }
+
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        let data = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        write_atomic(path, &data, sync_data_enabled())
+    }
+
+    /// Remember a lock this client just acquired.
+    pub fn record_ours(&mut self, lock: &Lock) {
+        self.upsert(lock, true);
+    }
+
+    /// Replace the cache with a complete server listing. `server_ours` holds
+    /// the ids the server's `locks/verify` endpoint attributes to the current
+    /// user; without it (a server lacking the endpoint) only ids this client
+    /// acquired count as ours.
+    pub fn refresh(&mut self, locks: &[Lock], server_ours: Option<&HashSet<String>>) {
+        let previous = std::mem::take(&mut self.locks);
+        for lock in locks {
+            let is_ours = Self::is_ours(&previous, lock, server_ours);
+            self.upsert(lock, is_ours);
+        }
+    }
+
+    /// Add the locks of a filtered listing without dropping unrelated ones.
+    /// `server_ours` is as for [`LockCache::refresh`].
+    pub fn merge(&mut self, locks: &[Lock], server_ours: Option<&HashSet<String>>) {
+        for lock in locks {
+            let is_ours = Self::is_ours(&self.locks, lock, server_ours);
+            self.upsert(lock, is_ours);
+        }
+    }
+
+    fn is_ours(cached: &[CachedLock], lock: &Lock, server_ours: Option<&HashSet<String>>) -> bool {
+        match server_ours {
+            Some(ids) => ids.contains(&lock.id),
+            None => cached.iter().any(|c| c.id == lock.id && c.ours),
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.locks.retain(|lock| lock.id != id);
+    }
+
+    /// Id of the lock this client holds on `path`, if any.
+    pub fn ours_for_path(&self, path: &str) -> Option<&CachedLock> {
+        let path = normalize_lock_path(path);
+        self.locks
+            .iter()
+            .find(|lock| lock.ours && lock.path == path)
+    }
+
+    /// A lock on `path` held by someone else.
+    pub fn foreign_lock(&self, path: &str) -> Option<&CachedLock> {
+        let path = normalize_lock_path(path);
+        self.locks
+            .iter()
+            .find(|lock| !lock.ours && lock.path == path)
+    }
+
+    pub fn has_foreign_locks(&self) -> bool {
+        self.locks.iter().any(|lock| !lock.ours)
+    }
+
+    fn upsert(&mut self, lock: &Lock, ours: bool) {
+        let entry = CachedLock {
+            id: lock.id.clone(),
+            path: normalize_lock_path(&lock.path),
+            owner: lock.owner.as_ref().map(|owner| owner.name.clone()),
+            ours,
+        };
+        match self.locks.iter_mut().find(|cached| cached.id == lock.id) {
+            Some(cached) => *cached = entry,
+            None => self.locks.push(entry),
+        }
+    }
+}
+
+fn normalize_lock_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Warn about each of `paths` (workdir-relative) that the lock cache records
+/// as locked by another user. Used by commit and checkout.
+pub fn warn_foreign_locks<I, P>(paths: I)
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    if util::try_get_storage_path(None).is_err() {
+        return;
+    }
+    let cache = LockCache::load();
+    if !cache.has_foreign_locks() {
+        return;
+    }
+    for path in paths {
+        let path = path.as_ref().to_string_lossy();
+        if let Some(lock) = cache.foreign_lock(&path) {
+            let owner = lock.owner.as_deref().unwrap_or("another user");
+            emit_warning(format!(
+                "'{}' is locked by {owner} (LFS lock {})",
+                lock.path, lock.id
+            ));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
@@ -554,4 +764,81 @@ size 10
         assert_eq!(oid.len(), LFS_OID_LEN);
         assert_eq!(size, 10);
     }
+
+    fn server_lock(id: &str, path: &str, owner: &str) -> Lock {
+        Lock {
+            id: id.to_string(),
+            path: path.to_string(),
+            locked_at: "2026-01-01T00:00:00Z".to_string(),
+            owner: Some(crate::lfs_structs::User {
+                name: owner.to_string(),
+            }),
+        }
+    }
+
+    /// With the server's `locks/verify` answer, ownership follows the server:
+    /// a lock the user took from another clone is ours, and an id this client
+    /// once acquired but the server now attributes to someone else is not.
+    #[test]
+    fn lock_cache_refresh_trusts_verified_ownership() {
+        let mut cache = LockCache::default();
+        cache.record_ours(&server_lock("1", "assets/a.bin", "me"));
+
+        let server_ours: HashSet<String> = ["2".to_string()].into_iter().collect();
+        cache.refresh(
+            &[
+                server_lock("1", "assets/a.bin", "alice"),
+                server_lock("2", "assets/b.bin", "me"),
+            ],
+            Some(&server_ours),
+        );
+        assert!(cache.foreign_lock("assets/a.bin").is_some());
+        assert!(cache.foreign_lock("assets/b.bin").is_none());
+        assert_eq!(
+            cache.ours_for_path("assets/b.bin").map(|l| l.id.as_str()),
+            Some("2")
+        );
+
+        cache.merge(&[server_lock("3", "c.bin", "me")], Some(&server_ours));
+        assert!(cache.foreign_lock("c.bin").is_some());
+    }
+
+    /// A full `lfs locks` refresh replaces stale entries but keeps the
+    /// ownership of ids acquired through `lfs lock`, and the cache survives
+    /// a save/load round trip.
+    #[test]
+    fn lock_cache_refresh_keeps_own_ids() {
+        let mut cache = LockCache::default();
+        cache.record_ours(&server_lock("1", "assets/a.bin", "me"));
+        cache.merge(&[server_lock("9", "gone.bin", "bob")], None);
+
+        cache.refresh(
+            &[
+                server_lock("1", "assets/a.bin", "me"),
+                server_lock("2", "assets/b.bin", "alice"),
+            ],
+            None,
+        );
+        assert_eq!(cache.locks.len(), 2);
+        assert_eq!(
+            cache.ours_for_path("assets/a.bin").map(|l| l.id.as_str()),
+            Some("1")
+        );
+        assert!(cache.foreign_lock("assets/a.bin").is_none());
+        let theirs = cache.foreign_lock("./assets/b.bin").expect("foreign lock");
+        assert_eq!(theirs.owner.as_deref(), Some("alice"));
+        assert!(cache.foreign_lock("gone.bin").is_none());
+
+        cache.remove("1");
+        assert!(cache.ours_for_path("assets/a.bin").is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("locks.json");
+        cache.save_to(&path).unwrap();
+        assert_eq!(LockCache::load_from(&path), cache);
+        assert_eq!(
+            LockCache::load_from(&dir.path().join("missing.json")),
+            LockCache::default()
+        );
+    }
 }
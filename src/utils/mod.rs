@@ -9,8 +9,13 @@ pub mod atomic_write;
 pub mod backoff;
 pub mod client_storage;
 pub mod convert;
+pub mod credential_helper;
 pub mod d1_client;
+pub mod git_hooks;
+pub mod gitattributes;
+pub mod gpg;
 pub mod ignore;
+pub mod index_marks;
 pub mod lfs;
 pub mod log_config;
 pub mod object;
@@ -18,9 +23,11 @@ pub mod object_ext;
 pub mod pager;
 pub mod path;
 pub mod path_ext;
+pub mod progress;
 pub mod read_policy;
 pub mod redact;
 pub mod resource_limits;
+pub mod sparse_checkout;
 pub mod storage;
 pub mod storage_ext;
 pub mod test;
@@ -1,5 +1,7 @@
 //! Helpers to read or write compressed git objects on disk, returning raw payloads and computing their object hashes.
 
+pub mod pack_index;
+
 use std::{
     fs,
     io::{Read, Write},
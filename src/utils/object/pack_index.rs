@@ -0,0 +1,394 @@
+//! In-memory reader and writer for Git pack index (`.idx`) files.
+//!
+//! A pack index maps every object in its `.pack` to the object's byte offset.
+//! The 256-entry fan-out table records, for each leading hash byte, how many
+//! objects sort at or below it, so a lookup narrows to one bucket in O(1) and
+//! binary-searches the sorted names inside it. Both on-disk versions are read:
+//!
+//! ```text
+//! v1: fanout[256] | (offset u32, name)*N                              | pack sum | idx sum
+//! v2: magic | 2 | fanout[256] | name*N | crc32*N | offset u32*N | u64* | pack sum | idx sum
+//! ```
+//!
+//! Parsing and validation are shared with `verify-pack`
+//! (`command::verify_pack_index::parse_index`). [`PackIndex::write`] always
+//! emits v2, taking each object's CRC32 and the pack checksum from the `.pack`
+//! it indexes; the trailing index checksum is computed with the repository's
+//! hash algorithm.
+
+use std::{io, path::Path};
+
+use git_internal::hash::{HashKind, ObjectHash, get_hash_kind};
+
+use crate::{
+    command::verify_pack_index::parse_index,
+    utils::atomic_write::{sync_data_enabled, write_atomic},
+};
+
+const IDX_MAGIC: [u8; 4] = [0xFF, 0x74, 0x4F, 0x63];
+/// `PACK`, version and object count.
+const PACK_HEADER_LEN: usize = 12;
+/// Offsets at or above this bit are stored in the v2 64-bit offset table.
+const LARGE_OFFSET_FLAG: u32 = 0x8000_0000;
+
+/// A fully loaded pack index: object names in sorted order with their offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackIndex {
+    fanout: Vec<u32>,
+    hashes: Vec<ObjectHash>,
+    offsets: Vec<u64>,
+}
+
+impl PackIndex {
+    /// Load a v1 or v2 `.idx` file, using the current hash kind for name width.
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error from reading the file, or
+    /// [`io::ErrorKind::InvalidData`] when the file is truncated, declares an
+    /// unsupported version, is not sorted to match its fan-out table, or fails
+    /// its checksum.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let data = std::fs::read(path)?;
+        let parsed = parse_index(&data).map_err(invalid_data)?;
+        let mut fanout = vec![0u32; 256];
+        for entry in &parsed.entries {
+            fanout[entry.hash.as_ref()[0] as usize] += 1;
+        }
+        for slot in 1..fanout.len() {
+            fanout[slot] += fanout[slot - 1];
+        }
+        let (hashes, offsets) = parsed
+            .entries
+            .into_iter()
+            .map(|entry| (entry.hash, entry.offset))
+            .unzip();
+        Ok(Self {
+            fanout,
+            hashes,
+            offsets,
+        })
+    }
+
+    /// Write a v2 index for `entries` (object name, pack offset) of the pack
+    /// at `pack` to `path`, atomically replacing any existing file. Entries may
+    /// be in any order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidInput`] when an object name appears
+    /// twice or does not match the current hash kind, or an offset lies
+    /// outside the pack's object data; [`io::ErrorKind::InvalidData`] when the
+    /// pack is too short to carry its checksum; and any I/O error from reading
+    /// the pack or writing the index.
+    pub fn write(pack: &Path, entries: &[(ObjectHash, u64)], path: &Path) -> io::Result<()> {
+        let pack_data = std::fs::read(pack)?;
+        let hash_size = get_hash_kind().size();
+        let Some(data_end) = pack_data
+            .len()
+            .checked_sub(hash_size)
+            .filter(|end| *end >= PACK_HEADER_LEN)
+        else {
+            return Err(invalid_data(format!(
+                "pack {} is too short to carry a checksum",
+                pack.display()
+            )));
+        };
+        let crcs = object_crcs(&pack_data[..data_end], entries)?;
+        let entries: Vec<_> = entries
+            .iter()
+            .zip(crcs)
+            .map(|((hash, offset), crc)| (*hash, *offset, crc))
+            .collect();
+        let bytes = Self::encode(&entries, &pack_data[data_end..])?;
+        write_atomic(path, &bytes, sync_data_enabled())
+    }
+
+    /// Number of objects in the index.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Object names in ascending order.
+    pub fn hashes(&self) -> &[ObjectHash] {
+        &self.hashes
+    }
+
+    /// Pack offset of `hash`, if the index contains it.
+    pub fn find(&self, hash: &ObjectHash) -> Option<u64> {
+        let first = *hash.as_ref().first()?;
+        let range = self.bucket(first);
+        self.hashes[range.clone()]
+            .binary_search(hash)
+            .ok()
+            .map(|pos| self.offsets[range.start + pos])
+    }
+
+    /// All object names whose hex form starts with `prefix`.
+    ///
+    /// A prefix of two or more hex digits is first narrowed to its fan-out
+    /// bucket; the first match is then found by binary search, so the cost is
+    /// O(log n) plus the number of matches.
+    pub fn find_prefix(&self, prefix: &str) -> Vec<ObjectHash> {
+        let range = match prefix.get(..2).map(|head| u8::from_str_radix(head, 16)) {
+            Some(Ok(first)) => self.bucket(first),
+            Some(Err(_)) => return Vec::new(),
+            None => 0..self.hashes.len(),
+        };
+        let bucket = &self.hashes[range];
+        let start = bucket.partition_point(|hash| hash.to_string().as_str() < prefix);
+        bucket[start..]
+            .iter()
+            .take_while(|hash| hash.to_string().starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    /// Index range of names whose first byte is `first`.
+    fn bucket(&self, first: u8) -> std::ops::Range<usize> {
+        let start = match first {
+            0 => 0,
+            _ => self.fanout[first as usize - 1] as usize,
+        };
+        start..self.fanout[first as usize] as usize
+    }
+
+    /// Serialize `entries` (object name, pack offset, CRC32) as a v2 index of
+    /// the pack whose trailing checksum is `pack_checksum`.
+    fn encode(entries: &[(ObjectHash, u64, u32)], pack_checksum: &[u8]) -> io::Result<Vec<u8>> {
+        let kind = get_hash_kind();
+        let mut sorted: Vec<&(ObjectHash, u64, u32)> = entries.iter().collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        if let Some(pair) = sorted.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("duplicate object {} in pack index entries", pair[0].0),
+            ));
+        }
+        if let Some((hash, ..)) = sorted
+            .iter()
+            .find(|(hash, ..)| hash.as_ref().len() != kind.size())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("object {hash} does not match the repository hash kind"),
+            ));
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&IDX_MAGIC);
+        out.extend_from_slice(&2u32.to_be_bytes());
+        let mut fanout = [0u32; 256];
+        for (hash, ..) in &sorted {
+            fanout[hash.as_ref()[0] as usize] += 1;
+        }
+        let mut running = 0u32;
+        for slot in fanout {
+            running += slot;
+            out.extend_from_slice(&running.to_be_bytes());
+        }
+        for (hash, ..) in &sorted {
+            out.extend_from_slice(hash.as_ref());
+        }
+        for (.., crc) in &sorted {
+            out.extend_from_slice(&crc.to_be_bytes());
+        }
+
+        let mut large = Vec::new();
+        for (_, offset, _) in &sorted {
+            let small = match u32::try_from(*offset) {
+                Ok(small) if small & LARGE_OFFSET_FLAG == 0 => small,
+                _ => {
+                    large.push(*offset);
+                    LARGE_OFFSET_FLAG | (large.len() as u32 - 1)
+                }
+            };
+            out.extend_from_slice(&small.to_be_bytes());
+        }
+        for offset in large {
+            out.extend_from_slice(&offset.to_be_bytes());
+        }
+
+        out.extend_from_slice(pack_checksum);
+        let checksum = digest(kind, &out);
+        out.extend_from_slice(&checksum);
+        Ok(out)
+    }
+}
+
+/// CRC32 of each object's raw bytes in `objects` (the pack without its
+/// checksum), in the order of `entries`. An object runs from its offset to the
+/// next object's offset, and the last one to the end of `objects`.
+fn object_crcs(objects: &[u8], entries: &[(ObjectHash, u64)]) -> io::Result<Vec<u32>> {
+    let mut bounds: Vec<u64> = entries.iter().map(|(_, offset)| *offset).collect();
+    bounds.sort_unstable();
+    bounds.push(objects.len() as u64);
+    entries
+        .iter()
+        .map(|(hash, offset)| {
+            if *offset < PACK_HEADER_LEN as u64 || *offset >= objects.len() as u64 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("offset {offset} of object {hash} is outside the pack"),
+                ));
+            }
+            let next = bounds[bounds.partition_point(|bound| bound <= offset)];
+            Ok(crc32fast::hash(&objects[*offset as usize..next as usize]))
+        })
+        .collect()
+}
+
+fn digest(kind: HashKind, bytes: &[u8]) -> Vec<u8> {
+    use sha1::Digest as _;
+    match kind {
+        HashKind::Sha1 => sha1::Sha1::digest(bytes).to_vec(),
+        HashKind::Sha256 => {
+            use sha2::Digest as _;
+            sha2::Sha256::digest(bytes).to_vec()
+        }
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::Write as _;
+
+    use flate2::{Compression, write::ZlibEncoder};
+    use git_internal::{hash::set_hash_kind_for_test, internal::object::types::ObjectType};
+    use sha1::Digest as _;
+
+    use super::*;
+
+    fn hash(byte0: u8, byte1: u8) -> ObjectHash {
+        let mut bytes = [0u8; 20];
+        bytes[0] = byte0;
+        bytes[1] = byte1;
+        ObjectHash::from_bytes(&bytes).unwrap()
+    }
+
+    /// A SHA-1 pack of undeltified `blobs`, with each blob's name and offset.
+    pub(crate) fn blob_pack(blobs: &[&[u8]]) -> (Vec<u8>, Vec<(ObjectHash, u64)>) {
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&(blobs.len() as u32).to_be_bytes());
+        let mut entries = Vec::new();
+        for blob in blobs {
+            entries.push((
+                ObjectHash::from_type_and_data(ObjectType::Blob, blob),
+                pack.len() as u64,
+            ));
+            let mut size = blob.len();
+            let mut byte = (3 << 4) | (size & 0x0f) as u8;
+            size >>= 4;
+            while size > 0 {
+                pack.push(byte | 0x80);
+                byte = (size & 0x7f) as u8;
+                size >>= 7;
+            }
+            pack.push(byte);
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(blob).unwrap();
+            pack.extend(encoder.finish().unwrap());
+        }
+        let checksum = sha1::Sha1::digest(&pack).to_vec();
+        pack.extend(checksum);
+        (pack, entries)
+    }
+
+    #[test]
+    fn write_then_read_round_trips_offsets() {
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pack-test.idx");
+        let entries = vec![
+            (hash(0xab, 0x02), 12, 1),
+            (hash(0x00, 0x01), 400, 2),
+            (hash(0xab, 0x01), 5_000_000_000, 3),
+            (hash(0xff, 0xff), 77, 4),
+        ];
+        std::fs::write(&path, PackIndex::encode(&entries, &[0x11; 20]).unwrap()).unwrap();
+
+        let index = PackIndex::read(&path).unwrap();
+        assert_eq!(index.len(), 4);
+        assert!(index.hashes().windows(2).all(|pair| pair[0] < pair[1]));
+        for (hash, offset, _) in &entries {
+            assert_eq!(index.find(hash), Some(*offset));
+        }
+        assert_eq!(index.find(&hash(0xab, 0x03)), None);
+    }
+
+    #[test]
+    fn write_records_object_crcs_and_pack_checksum() {
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let (pack, entries) = blob_pack(&[b"first blob".as_slice(), b"second blob"]);
+        let pack_path = dir.path().join("pack-test.pack");
+        let idx_path = dir.path().join("pack-test.idx");
+        std::fs::write(&pack_path, &pack).unwrap();
+        PackIndex::write(&pack_path, &entries, &idx_path).unwrap();
+
+        let parsed = parse_index(&std::fs::read(&idx_path).unwrap()).unwrap();
+        assert_eq!(parsed.pack_hash.as_ref(), &pack[pack.len() - 20..]);
+        let trailer = pack.len() - 20;
+        for entry in &parsed.entries {
+            let start = entry.offset as usize;
+            let end = entries
+                .iter()
+                .map(|(_, offset)| *offset as usize)
+                .filter(|offset| *offset > start)
+                .min()
+                .unwrap_or(trailer);
+            assert_eq!(entry.crc32, Some(crc32fast::hash(&pack[start..end])));
+        }
+    }
+
+    #[test]
+    fn find_prefix_uses_fanout_bucket() {
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pack-test.idx");
+        let entries = vec![
+            (hash(0xab, 0x01), 12, 0),
+            (hash(0xab, 0x02), 13, 0),
+            (hash(0xac, 0x01), 14, 0),
+        ];
+        std::fs::write(&path, PackIndex::encode(&entries, &[0; 20]).unwrap()).unwrap();
+        let index = PackIndex::read(&path).unwrap();
+
+        assert_eq!(index.find_prefix("ab").len(), 2);
+        assert_eq!(index.find_prefix("ab02"), vec![hash(0xab, 0x02)]);
+        assert_eq!(index.find_prefix("a").len(), 3);
+        assert!(index.find_prefix("ad").is_empty());
+        assert!(index.find_prefix("zz").is_empty());
+    }
+
+    #[test]
+    fn write_rejects_duplicate_entries_and_read_rejects_truncation() {
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let (pack, entries) = blob_pack(&[b"only blob".as_slice()]);
+        let pack_path = dir.path().join("pack-test.pack");
+        let path = dir.path().join("pack-test.idx");
+        std::fs::write(&pack_path, &pack).unwrap();
+        let duplicate = [entries[0], entries[0]];
+        let err = PackIndex::write(&pack_path, &duplicate, &path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let outside = [(entries[0].0, pack.len() as u64)];
+        let err = PackIndex::write(&pack_path, &outside, &path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        PackIndex::write(&pack_path, &entries, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        let err = PackIndex::read(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -6,7 +6,6 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use colored::Colorize;
 use git_internal::{
     hash::ObjectHash,
     internal::object::{
@@ -73,17 +72,10 @@ impl TreeExt for Tree {
             if item.mode != TreeItemMode::Tree {
                 // `160000` gitlink entries (submodules) reference commits that are
                 // not guaranteed to exist in this repository's object database.
-                // Treat them as unsupported for plain-file expansion to avoid
-                // panicking later when callers assume Blob objects.
+                // Skip them for plain-file expansion to avoid panicking later when
+                // callers assume Blob objects; `libra submodule update` checks
+                // them out.
                 if item.mode == TreeItemMode::Commit {
-                    eprintln!(
-                        "{}",
-                        format!(
-                            "Warning: Submodule '{}' is not supported yet; skipping checkout entry",
-                            item.name
-                        )
-                        .red()
-                    );
                     continue;
                 }
                 // Not Tree, maybe Blob, link, etc.
@@ -96,22 +96,50 @@ pub struct OutputConfig {
     pub progress_preference: ProgressPreference,
 }
 
+fn command_envelope(command: &str, data: serde_json::Value, ok: bool) -> serde_json::Value {
+    serde_json::json!({
+        "ok": ok,
+        "command": command,
+        "data": data,
+    })
+}
+
+fn serialize_envelope(envelope: &serde_json::Value, format: JsonFormat) -> io::Result<String> {
+    Ok(match format {
+        JsonFormat::Pretty => serde_json::to_string_pretty(envelope)?,
+        JsonFormat::Compact | JsonFormat::Ndjson => serde_json::to_string(envelope)?,
+    })
+}
+
 fn write_json_command_envelope<W: Write, T: Serialize>(
     writer: &mut W,
     command: &str,
     data: &T,
     format: JsonFormat,
 ) -> io::Result<()> {
-    let envelope = serde_json::json!({
-        "ok": true,
-        "command": command,
-        "data": data,
-    });
-    match format {
-        JsonFormat::Pretty => serde_json::to_writer_pretty(&mut *writer, &envelope)?,
-        JsonFormat::Compact | JsonFormat::Ndjson => serde_json::to_writer(&mut *writer, &envelope)?,
-    }
-    writeln!(writer)
+    let envelope = command_envelope(command, serde_json::to_value(data)?, true);
+    writeln!(writer, "{}", serialize_envelope(&envelope, format)?)
+}
+
+/// The text around `data` in a command envelope, for commands that stream
+/// their `data` object instead of building it in memory. The layout is the one
+/// [`write_json_command_envelope`] produces; `"ok"` follows `data` there, so
+/// the status is written after the streamed work and an error part-way through
+/// still closes the document with `"ok": false`. The tail includes the final
+/// newline.
+pub(crate) fn json_stream_envelope(
+    command: &str,
+    ok: bool,
+    format: JsonFormat,
+) -> io::Result<(String, String)> {
+    const PLACEHOLDER: &str = "\u{0}data\u{0}";
+    let envelope = command_envelope(command, PLACEHOLDER.into(), ok);
+    let text = serialize_envelope(&envelope, format)?;
+    let marker = serde_json::to_string(PLACEHOLDER)?;
+    let (head, tail) = text
+        .split_once(&marker)
+        .ok_or_else(|| io::Error::other("JSON envelope lost its data placeholder"))?;
+    Ok((head.to_string(), format!("{tail}\n")))
 }
 
 impl Default for OutputConfig {
@@ -610,6 +638,25 @@ mod tests {
         assert_eq!(config.progress_preference, ProgressPreference::Auto);
     }
 
+    #[test]
+    fn streamed_envelope_matches_the_buffered_one() {
+        let data = serde_json::json!({"commits": [1, 2], "total": null});
+        for format in [JsonFormat::Pretty, JsonFormat::Compact] {
+            let mut buffered = Vec::new();
+            write_json_command_envelope(&mut buffered, "log", &data, format).unwrap();
+            let (head, tail) = json_stream_envelope("log", true, format).unwrap();
+            let body = match format {
+                JsonFormat::Pretty => serde_json::to_string_pretty(&data)
+                    .unwrap()
+                    .replace('\n', "\n  "),
+                _ => serde_json::to_string(&data).unwrap(),
+            };
+            assert_eq!(String::from_utf8(buffered).unwrap(), head + &body + &tail);
+        }
+        let (_, tail) = json_stream_envelope("log", false, JsonFormat::Compact).unwrap();
+        assert_eq!(tail, ",\"ok\":false}\n");
+    }
+
     #[test]
     fn resolve_machine_mode() {
         let config = OutputConfig::resolve(
@@ -0,0 +1,185 @@
+//! Progress sinks for long-running transfers.
+//!
+//! Transports report into an `Arc<dyn Progress>` without knowing who is
+//! watching: [`ConsoleProgress`] draws an `indicatif` bar on stderr,
+//! [`NoopProgress`] discards everything (tests, and callers that render their
+//! own progress), and [`ChannelProgress`] forwards [`ProgressEvent`]s over an
+//! `mpsc` channel so the TUI can draw them without touching the terminal.
+//!
+//! Units are bytes unless a caller documents otherwise.
+
+use std::sync::Arc;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use super::{
+    output::{OutputConfig, ProgressMode},
+    util,
+};
+
+/// Receives updates from a long-running operation. Implementations must be
+/// cheap to call from a hot loop.
+pub trait Progress: Send + Sync {
+    /// Set (or replace) the expected total; until called the work is
+    /// indeterminate.
+    fn set_total(&self, n: u64);
+    /// Record `delta` more units of completed work.
+    fn inc(&self, delta: u64);
+    /// Describe the current phase, e.g. `Receiving objects`.
+    fn set_message(&self, msg: &str);
+    /// Mark the operation finished; later calls are ignored.
+    fn finish(&self);
+}
+
+/// A shared sink that ignores every update.
+pub fn noop() -> Arc<dyn Progress> {
+    Arc::new(NoopProgress)
+}
+
+/// The sink a command should hand its transport for `output.progress`: a
+/// console bar in text mode, otherwise nothing (JSON progress is emitted by
+/// the commands' own `ProgressReporter`s).
+pub fn for_output(output: &OutputConfig) -> Arc<dyn Progress> {
+    match output.progress {
+        ProgressMode::Text => Arc::new(ConsoleProgress::new()),
+        ProgressMode::Json | ProgressMode::None => noop(),
+    }
+}
+
+/// Discards all updates.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopProgress;
+
+impl Progress for NoopProgress {
+    fn set_total(&self, _n: u64) {}
+    fn inc(&self, _delta: u64) {}
+    fn set_message(&self, _msg: &str) {}
+    fn finish(&self) {}
+}
+
+/// An `indicatif` progress bar on stderr: a spinner until [`Progress::set_total`]
+/// is called, then the same byte bar as [`util::default_progress_bar`].
+pub struct ConsoleProgress {
+    bar: ProgressBar,
+}
+
+impl ConsoleProgress {
+    pub fn new() -> Self {
+        let bar = ProgressBar::new_spinner();
+        if let Ok(style) = ProgressStyle::default_spinner()
+            .template("{spinner:.magenta} {msg} {bytes} ({bytes_per_sec})")
+        {
+            bar.set_style(style);
+        }
+        Self { bar }
+    }
+}
+
+impl Default for ConsoleProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Progress for ConsoleProgress {
+    fn set_total(&self, n: u64) {
+        self.bar.set_style(util::default_progress_style());
+        self.bar.set_length(n);
+    }
+
+    fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+    }
+
+    fn set_message(&self, msg: &str) {
+        self.bar.set_message(msg.to_string());
+    }
+
+    fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+/// One update forwarded by [`ChannelProgress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    Total(u64),
+    Inc(u64),
+    Message(String),
+    Finish,
+}
+
+/// Forwards updates over an unbounded channel. Sends after the receiver is
+/// dropped are ignored, so an abandoned view never fails the transfer.
+#[derive(Debug, Clone)]
+pub struct ChannelProgress {
+    tx: UnboundedSender<ProgressEvent>,
+}
+
+impl ChannelProgress {
+    pub fn new(tx: UnboundedSender<ProgressEvent>) -> Self {
+        Self { tx }
+    }
+
+    /// A sink and the receiver that observes it.
+    pub fn channel() -> (Self, UnboundedReceiver<ProgressEvent>) {
+        let (tx, rx) = unbounded_channel();
+        (Self::new(tx), rx)
+    }
+}
+
+impl Progress for ChannelProgress {
+    fn set_total(&self, n: u64) {
+        let _ = self.tx.send(ProgressEvent::Total(n));
+    }
+
+    fn inc(&self, delta: u64) {
+        let _ = self.tx.send(ProgressEvent::Inc(delta));
+    }
+
+    fn set_message(&self, msg: &str) {
+        let _ = self.tx.send(ProgressEvent::Message(msg.to_string()));
+    }
+
+    fn finish(&self) {
+        let _ = self.tx.send(ProgressEvent::Finish);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_progress_forwards_updates_in_order() {
+        let (progress, mut rx) = ChannelProgress::channel();
+        let progress: Arc<dyn Progress> = Arc::new(progress);
+        progress.set_message("Receiving objects");
+        progress.set_total(10);
+        progress.inc(4);
+        progress.finish();
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            vec![
+                ProgressEvent::Message("Receiving objects".to_string()),
+                ProgressEvent::Total(10),
+                ProgressEvent::Inc(4),
+                ProgressEvent::Finish,
+            ]
+        );
+    }
+
+    #[test]
+    fn channel_progress_ignores_a_dropped_receiver() {
+        let (progress, rx) = ChannelProgress::channel();
+        drop(rx);
+        progress.inc(1);
+        progress.finish();
+    }
+}
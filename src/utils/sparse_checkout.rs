@@ -0,0 +1,243 @@
+//! Sparse-checkout rules written by `libra sparse-checkout`.
+//!
+//! The patterns live in `.libra/info/sparse-checkout`, one per line in
+//! `.gitignore` syntax, exactly as Git writes them (cone mode stores Git's
+//! expanded `/*`, `!/*/`, `/<dir>/` form). Sparse checkout is enabled while that
+//! file exists. An index path is *included* when the patterns match it or one of
+//! its parent directories.
+//!
+//! Like Git, Libra marks the excluded entries skip-worktree, using the same
+//! mark as `update-index --skip-worktree` ([`crate::utils::index_marks`]):
+//! whenever the index or working tree is brought in line with the patterns, an
+//! entry is marked while it is excluded and absent from the working tree.
+//! Status and diff treat a marked entry as unchanged rather than deleted; an
+//! excluded file that is still on disk (because it had local modifications)
+//! is tracked as usual.
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use git_internal::internal::index::Index;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::utils::{
+    index_marks::{self, IndexMark},
+    path, util,
+};
+
+const PATTERN_FILE: &str = "sparse-checkout";
+
+fn pattern_path() -> io::Result<PathBuf> {
+    Ok(util::try_get_storage_path(None)?
+        .join("info")
+        .join(PATTERN_FILE))
+}
+
+/// The stored patterns, or `None` when sparse checkout is disabled.
+pub fn read_patterns() -> io::Result<Option<Vec<String>>> {
+    match fs::read_to_string(pattern_path()?) {
+        Ok(data) => Ok(Some(
+            data.lines()
+                .map(str::trim_end)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replace the stored patterns, enabling sparse checkout.
+pub fn write_patterns(patterns: &[String]) -> io::Result<()> {
+    let path = pattern_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut data = String::new();
+    for pattern in patterns {
+        data.push_str(pattern);
+        data.push('\n');
+    }
+    fs::write(path, data)
+}
+
+/// Remove the stored patterns, disabling sparse checkout.
+pub fn remove_patterns() -> io::Result<()> {
+    match fs::remove_file(pattern_path()?) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Compiled sparse-checkout patterns.
+pub struct SparseCheckout {
+    matcher: Gitignore,
+}
+
+impl SparseCheckout {
+    /// The active rules, or `None` when sparse checkout is disabled (or the
+    /// pattern file cannot be read).
+    pub fn load() -> Option<Self> {
+        read_patterns()
+            .ok()
+            .flatten()
+            .map(|patterns| Self::from_patterns(&patterns))
+    }
+
+    pub fn from_patterns(patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in patterns {
+            // An invalid glob matches nothing, as a bad `.gitignore` line does.
+            let _ = builder.add_line(None, pattern);
+        }
+        let matcher = builder.build().unwrap_or_else(|_| Gitignore::empty());
+        SparseCheckout { matcher }
+    }
+
+    /// Whether the working-tree-relative `path` belongs in the working tree.
+    pub fn includes(&self, path: &Path) -> bool {
+        self.matcher
+            .matched_path_or_any_parents(path, false)
+            .is_ignore()
+    }
+
+    /// Whether the index entry at `path` is left out of the working tree:
+    /// excluded by the patterns and absent from it.
+    pub fn skips(&self, path: &Path, workdir: &Path) -> bool {
+        !self.includes(path) && fs::symlink_metadata(workdir.join(path)).is_err()
+    }
+
+    /// The stage-0 index keys to mark skip-worktree.
+    pub fn skipped_entries(&self, index: &Index, workdir: &Path) -> BTreeSet<String> {
+        index
+            .tracked_entries(0)
+            .into_iter()
+            .map(|entry| entry.name)
+            .filter(|name| self.skips(Path::new(name), workdir))
+            .collect()
+    }
+}
+
+/// Recompute the skip-worktree marks from the active rules after a command
+/// rewrote the index or working tree. Marks are left alone while sparse
+/// checkout is disabled, so `update-index --skip-worktree` keeps working.
+pub fn refresh_skip_worktree() -> io::Result<()> {
+    let Some(sparse) = SparseCheckout::load() else {
+        return Ok(());
+    };
+    let index =
+        Index::load(path::try_index()?).map_err(|error| io::Error::other(error.to_string()))?;
+    index_marks::replace(
+        IndexMark::SkipWorktree,
+        &sparse.skipped_entries(&index, &util::working_dir()),
+    )
+}
+
+/// Normalize a cone-mode directory argument (`/src/app/` → `src/app`).
+/// Returns `None` for an argument that cannot name a directory.
+pub fn normalize_cone_directory(arg: &str) -> Option<String> {
+    let dir = arg.trim_matches('/');
+    let valid = !dir.is_empty()
+        && dir
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..");
+    valid.then(|| dir.to_string())
+}
+
+/// Expand cone-mode directories into Git's pattern form: every file at the
+/// top level, every file directly inside a parent of a listed directory, and
+/// everything below a listed directory. A directory nested in another listed
+/// one is redundant and dropped.
+pub fn cone_patterns(directories: &[String]) -> Vec<String> {
+    let unique: BTreeSet<&str> = directories.iter().map(String::as_str).collect();
+    let recursive: Vec<&str> = unique
+        .iter()
+        .copied()
+        .filter(|dir| {
+            !unique.iter().any(|other| {
+                dir.strip_prefix(other)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+        .collect();
+    let parents: BTreeSet<&str> = recursive
+        .iter()
+        .flat_map(|dir| dir.match_indices('/').map(|(at, _)| &dir[..at]))
+        .collect();
+
+    let mut patterns = vec!["/*".to_string(), "!/*/".to_string()];
+    for parent in parents {
+        patterns.push(format!("/{parent}/"));
+        patterns.push(format!("!/{parent}/*/"));
+    }
+    patterns.extend(recursive.iter().map(|dir| format!("/{dir}/")));
+    patterns
+}
+
+/// The directories a cone-mode pattern list was expanded from (the inverse of
+/// [`cone_patterns`]).
+pub fn cone_directories(patterns: &[String]) -> Vec<String> {
+    let parents: BTreeSet<&str> = patterns
+        .iter()
+        .filter_map(|pattern| pattern.strip_prefix("!/")?.strip_suffix("/*/"))
+        .collect();
+    patterns
+        .iter()
+        .filter_map(|pattern| pattern.strip_prefix('/')?.strip_suffix('/'))
+        .filter(|dir| !dir.is_empty() && *dir != "*" && !parents.contains(dir))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cone(dirs: &[&str]) -> SparseCheckout {
+        let dirs: Vec<String> = dirs.iter().map(|dir| dir.to_string()).collect();
+        SparseCheckout::from_patterns(&cone_patterns(&dirs))
+    }
+
+    #[test]
+    fn cone_patterns_match_git_layout() {
+        let dirs = vec!["a/b".to_string(), "c".to_string(), "a/b/deep".to_string()];
+        assert_eq!(
+            cone_patterns(&dirs),
+            vec!["/*", "!/*/", "/a/", "!/a/*/", "/a/b/", "/c/"]
+        );
+        assert_eq!(cone_directories(&cone_patterns(&dirs)), vec!["a/b", "c"]);
+    }
+
+    #[test]
+    fn cone_mode_includes_top_level_parent_files_and_listed_trees() {
+        let sparse = cone(&["a/b"]);
+        assert!(sparse.includes(Path::new("README.md")));
+        assert!(sparse.includes(Path::new("a/top.txt")));
+        assert!(sparse.includes(Path::new("a/b/x/y.txt")));
+        assert!(!sparse.includes(Path::new("a/other/z.txt")));
+        assert!(!sparse.includes(Path::new("c/z.txt")));
+    }
+
+    #[test]
+    fn non_cone_patterns_use_gitignore_syntax() {
+        let sparse =
+            SparseCheckout::from_patterns(&["*.md".to_string(), "!/docs/draft.md".to_string()]);
+        assert!(sparse.includes(Path::new("docs/guide.md")));
+        assert!(!sparse.includes(Path::new("docs/draft.md")));
+        assert!(!sparse.includes(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn normalize_cone_directory_rejects_non_directories() {
+        assert_eq!(
+            normalize_cone_directory("/src/app/").as_deref(),
+            Some("src/app")
+        );
+        assert_eq!(normalize_cone_directory("/"), None);
+        assert_eq!(normalize_cone_directory("a/../b"), None);
+    }
+}
@@ -2,12 +2,18 @@
 //! This module implements the `Storage` trait for a local filesystem backend. It supports both loose objects and packed objects, allowing for efficient storage and retrieval of Git objects on disk.
 //! The `LocalStorage` struct provides methods to read and write Git objects, as well as to search for objects by prefix. It handles the Git object storage format, including zlib compression for loose objects
 //! and the pack file format for packed objects. The implementation also includes caching mechanisms for pack objects to improve performance when accessing packed data.
+//! Decompressed objects returned by `get` are kept in a per-storage, byte-bounded LRU cache ([`ObjectCacheConfig`]), so walks that load the same commits and trees repeatedly decompress them once.
 use std::{
+    collections::HashMap,
     fs, io,
     io::{Read, Seek, Write},
     path::{Path, PathBuf},
     str::FromStr,
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::SystemTime,
 };
 
 use async_trait::async_trait;
@@ -22,10 +28,14 @@ use git_internal::{
     },
     utils::read_sha,
 };
+use lru::LruCache as ObjectLru;
 use lru_mem::LruCache;
 use once_cell::sync::Lazy;
 
-use crate::{command, utils::storage::Storage};
+use crate::{
+    command,
+    utils::{object::pack_index::PackIndex, storage::Storage},
+};
 
 /// Cache for pack objects, keyed by "pack_file_name-offset"
 static PACK_OBJ_CACHE: Lazy<Mutex<LruCache<String, CacheObject>>> =
@@ -33,6 +43,9 @@ static PACK_OBJ_CACHE: Lazy<Mutex<LruCache<String, CacheObject>>> =
 
 const IDX_MAGIC: [u8; 4] = [0xFF, 0x74, 0x4F, 0x63];
 const FANOUT: u64 = 256 * 4;
+/// Directory (under the objects dir) holding redirect markers for loose
+/// objects deduplicated to the remote tier. See `TieredStorage::dedup`.
+const REDIRECT_DIR: &str = "redirect";
 
 /// Index version for pack files
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -41,15 +54,160 @@ enum IdxVersion {
     V2,
 }
 
+/// Byte budget of the decompressed-object cache unless configured otherwise.
+pub const DEFAULT_MAX_CACHED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Objects larger than this many bytes bypass the object cache by default.
+pub const DEFAULT_LARGE_OBJECT_THRESHOLD: usize = 1024 * 1024;
+
+/// Limits of the decompressed-object cache in [`LocalStorage`]. Repository
+/// storage built by `ClientStorage` takes `max_cached_bytes` from
+/// `LIBRA_OBJECT_CACHE_SIZE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectCacheConfig {
+    /// Total size of cached object contents; least recently used objects are
+    /// evicted beyond it. `0` disables the cache.
+    pub max_cached_bytes: usize,
+    /// Objects with more content bytes than this (in practice large blobs) are
+    /// never cached, so one big file cannot evict many small commits and trees.
+    pub large_object_threshold: usize,
+}
+
+impl Default for ObjectCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_cached_bytes: DEFAULT_MAX_CACHED_BYTES,
+            large_object_threshold: DEFAULT_LARGE_OBJECT_THRESHOLD,
+        }
+    }
+}
+
+/// Snapshot of the object cache counters, see [`LocalStorage::cache_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectCacheStats {
+    /// `get` calls answered from the cache.
+    pub hits: u64,
+    /// `get` calls that had to read and decompress the object.
+    pub misses: u64,
+    /// Objects currently cached.
+    pub entries: usize,
+    /// Content bytes currently cached.
+    pub cached_bytes: usize,
+}
+
+struct CachedObjects {
+    lru: ObjectLru<ObjectHash, (Vec<u8>, ObjectType)>,
+    bytes: usize,
+}
+
+/// Byte-bounded LRU of decompressed objects, shared by every clone of a
+/// [`LocalStorage`]. Lookups only take the read lock; the recency bump that
+/// follows a hit is skipped when another thread holds the lock.
+struct ObjectCache {
+    config: ObjectCacheConfig,
+    objects: RwLock<CachedObjects>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ObjectCache {
+    fn new(config: ObjectCacheConfig) -> Self {
+        Self {
+            config,
+            objects: RwLock::new(CachedObjects {
+                lru: ObjectLru::unbounded(),
+                bytes: 0,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn get(&self, hash: &ObjectHash) -> Option<(Vec<u8>, ObjectType)> {
+        // A poisoned lock only means a panic elsewhere mid-update; the byte
+        // count may be off but every cached entry is still a valid object.
+        let cached = self
+            .objects
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .lru
+            .peek(hash)
+            .cloned();
+        match cached {
+            Some(object) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                if let Ok(mut objects) = self.objects.try_write() {
+                    objects.lru.promote(hash);
+                }
+                Some(object)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    fn insert(&self, hash: ObjectHash, data: &[u8], obj_type: ObjectType) {
+        let size = data.len();
+        if size > self.config.large_object_threshold || size > self.config.max_cached_bytes {
+            return;
+        }
+        let mut objects = self.objects.write().unwrap_or_else(|err| err.into_inner());
+        if let Some((old, _)) = objects.lru.put(hash, (data.to_vec(), obj_type)) {
+            objects.bytes -= old.len();
+        }
+        objects.bytes += size;
+        while objects.bytes > self.config.max_cached_bytes {
+            let Some((_, (evicted, _))) = objects.lru.pop_lru() else {
+                break;
+            };
+            objects.bytes -= evicted.len();
+        }
+    }
+
+    fn stats(&self) -> ObjectCacheStats {
+        let objects = self.objects.read().unwrap_or_else(|err| err.into_inner());
+        ObjectCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: objects.lru.len(),
+            cached_bytes: objects.bytes,
+        }
+    }
+}
+
+impl Default for ObjectCache {
+    fn default() -> Self {
+        Self::new(ObjectCacheConfig::default())
+    }
+}
+
+/// A parsed `.idx` file, valid while the file keeps the same size and mtime.
+struct CachedPackIndex {
+    len: u64,
+    modified: Option<SystemTime>,
+    index: Arc<PackIndex>,
+}
+
 /// Local filesystem storage backend
 #[derive(Default, Clone)]
 pub struct LocalStorage {
     base_path: PathBuf,
     hash_kind: Option<HashKind>, // Capture hash kind from creation thread
+    object_cache: Arc<ObjectCache>,
+    /// Parsed pack indexes keyed by `.idx` path, so prefix searches parse
+    /// each index once rather than on every call.
+    pack_indexes: Arc<Mutex<HashMap<PathBuf, CachedPackIndex>>>,
 }
 
 impl LocalStorage {
     pub fn new(base_path: PathBuf) -> Self {
+        Self::with_cache_config(base_path, ObjectCacheConfig::default())
+    }
+
+    /// Like [`Self::new`], with explicit limits for the decompressed-object cache.
+    pub fn with_cache_config(base_path: PathBuf, cache_config: ObjectCacheConfig) -> Self {
         fs::create_dir_all(&base_path).unwrap_or_else(|err| {
             panic!(
                 "LocalStorage::new({}): create_dir_all failed: {err}",
@@ -59,9 +217,16 @@ impl LocalStorage {
         Self {
             base_path,
             hash_kind: Some(get_hash_kind()),
+            object_cache: Arc::new(ObjectCache::new(cache_config)),
+            pack_indexes: Arc::default(),
         }
     }
 
+    /// Hit/miss counters and current size of the decompressed-object cache.
+    pub fn cache_stats(&self) -> ObjectCacheStats {
+        self.object_cache.stats()
+    }
+
     /// Transforms an object hash into a path like "ab/cdef...". This is used for loose objects.
     fn transform_path(&self, hash: &ObjectHash) -> String {
         let hash = hash.to_string();
@@ -81,6 +246,86 @@ impl LocalStorage {
         Path::new(&self.base_path).join(self.transform_path(obj_id))
     }
 
+    /// Path of the redirect marker recorded when a loose object was deduplicated
+    /// to the remote tier, e.g. "base_path/redirect/ab/cdef...".
+    pub(crate) fn get_redirect_path(&self, obj_id: &ObjectHash) -> PathBuf {
+        self.base_path
+            .join(REDIRECT_DIR)
+            .join(self.transform_path(obj_id))
+    }
+
+    /// Ids of all loose objects. Packed objects are not included.
+    pub(crate) fn loose_objects(&self) -> Vec<ObjectHash> {
+        Self::fanout_ids(&self.base_path)
+    }
+
+    /// Uncompressed payload size of a loose object, read from its `type size\0`
+    /// header without inflating the rest of the object.
+    pub(crate) fn loose_object_size(&self, obj_id: &ObjectHash) -> io::Result<usize> {
+        let file = fs::File::open(self.get_obj_path(obj_id))?;
+        let mut header = Vec::new();
+        // "commit 18446744073709551615" is the longest well-formed header.
+        for byte in ZlibDecoder::new(file).bytes().take(32) {
+            let byte = byte?;
+            if byte == b'\0' {
+                return std::str::from_utf8(&header)
+                    .ok()
+                    .and_then(|header| header.split_once(' '))
+                    .and_then(|(_, size)| size.parse().ok())
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("malformed header in loose object {obj_id}"),
+                        )
+                    });
+            }
+            header.push(byte);
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("missing header terminator in loose object {obj_id}"),
+        ))
+    }
+
+    /// Ids of all objects with a redirect marker.
+    pub(crate) fn redirected_objects(&self) -> Vec<ObjectHash> {
+        Self::fanout_ids(&self.base_path.join(REDIRECT_DIR))
+    }
+
+    /// Object ids stored as `dir/ab/cdef...`. Entries that are not a two-char
+    /// directory holding hex-named files (packs, temp files) are ignored.
+    fn fanout_ids(dir: &Path) -> Vec<ObjectHash> {
+        let mut ids = Vec::new();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return ids;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(prefix) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .filter(|n| n.len() == 2)
+            else {
+                continue;
+            };
+            let Ok(files) = fs::read_dir(&path) else {
+                continue;
+            };
+            for file in files.flatten() {
+                if !file.path().is_file() {
+                    continue;
+                }
+                if let Some(rest) = file.file_name().to_str()
+                    && let Ok(hash) = ObjectHash::from_str(&format!("{prefix}{rest}"))
+                {
+                    ids.push(hash);
+                }
+            }
+        }
+        ids.sort();
+        ids
+    }
+
     /// Checks if a loose object exists by looking for its file. This is a quick check before looking into packs.
     fn exist_loosely(&self, obj_id: &ObjectHash) -> bool {
         let path = self.get_obj_path(obj_id);
@@ -235,6 +480,33 @@ impl LocalStorage {
         idxs
     }
 
+    /// The parsed index at `idx`, reusing the cached parse while the file is
+    /// unchanged.
+    fn pack_index(&self, idx: &Path) -> io::Result<Arc<PackIndex>> {
+        let metadata = fs::metadata(idx)?;
+        let (len, modified) = (metadata.len(), metadata.modified().ok());
+        let mut cache = self
+            .pack_indexes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(cached) = cache.get(idx)
+            && cached.len == len
+            && cached.modified == modified
+        {
+            return Ok(Arc::clone(&cached.index));
+        }
+        let index = Arc::new(PackIndex::read(idx)?);
+        cache.insert(
+            idx.to_path_buf(),
+            CachedPackIndex {
+                len,
+                modified,
+                index: Arc::clone(&index),
+            },
+        );
+        Ok(index)
+    }
+
     fn read_idx_version(file: &mut fs::File) -> Result<IdxVersion, io::Error> {
         let mut header = [0u8; 4];
         file.read_exact(&mut header)?;
@@ -421,6 +693,75 @@ impl LocalStorage {
         Ok(full_obj)
     }
 
+    /// Uncompressed size of a packed object, read from its entry header. For a
+    /// delta this is the size of the rebuilt object, taken from the delta's own
+    /// header, so the base never has to be resolved.
+    fn pack_object_size(pack_file: &Path, offset: u64) -> io::Result<usize> {
+        let mut reader = io::BufReader::new(fs::File::open(pack_file)?);
+        reader.seek(io::SeekFrom::Start(offset))?;
+        let first = reader.read_u8()?;
+        let kind = (first >> 4) & 0x7;
+        let mut size = usize::from(first & 0x0f);
+        if first & 0x80 != 0 {
+            let rest = Self::read_size_varint(&mut reader.by_ref().bytes())?;
+            size |= rest.checked_shl(4).ok_or_else(Self::oversized_varint)?;
+        }
+        match kind {
+            // OFS_DELTA: skip the variable-length base offset.
+            6 => while reader.read_u8()? & 0x80 != 0 {},
+            // REF_DELTA: skip the base object id.
+            7 => {
+                let mut base = vec![0; get_hash_kind().size()];
+                reader.read_exact(&mut base)?;
+            }
+            _ => return Ok(size),
+        }
+        let mut delta = ZlibDecoder::new(reader).bytes();
+        let _base_size = Self::read_size_varint(&mut delta)?;
+        Self::read_size_varint(&mut delta)
+    }
+
+    /// Little-endian base-128 size as used by pack entry and delta headers.
+    fn read_size_varint(bytes: &mut impl Iterator<Item = io::Result<u8>>) -> io::Result<usize> {
+        let mut size = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = bytes
+                .next()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))??;
+            let part = usize::from(byte & 0x7f)
+                .checked_shl(shift)
+                .ok_or_else(Self::oversized_varint)?;
+            size |= part;
+            if byte & 0x80 == 0 {
+                return Ok(size);
+            }
+            shift += 7;
+        }
+    }
+
+    fn oversized_varint() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "object size does not fit in usize",
+        )
+    }
+
+    /// Uncompressed payload size of an object from its loose-object or pack
+    /// entry header, without inflating the object. `None` when it is absent.
+    fn header_object_size(&self, obj_id: &ObjectHash) -> Result<Option<usize>, GitError> {
+        if self.exist_loosely(obj_id) {
+            return Ok(Some(self.loose_object_size(obj_id)?));
+        }
+        for idx in self.list_all_idx() {
+            if let Some(offset) = Self::read_idx(&idx, obj_id)? {
+                let pack_file = idx.with_extension("pack");
+                return Ok(Some(Self::pack_object_size(&pack_file, offset)?));
+            }
+        }
+        Ok(None)
+    }
+
     fn get_from_pack(
         &self,
         obj_id: &ObjectHash,
@@ -462,18 +803,22 @@ impl Storage for LocalStorage {
             if let Some(kind) = self_clone.hash_kind {
                 set_hash_kind(kind);
             }
-            if self_clone.exist_loosely(&hash) {
+            if let Some(cached) = self_clone.object_cache.get(&hash) {
+                return Ok(cached);
+            }
+            let (data, obj_type) = if self_clone.exist_loosely(&hash) {
                 let raw_data = self_clone.read_raw_data(&hash)?;
                 let data = Self::decompress_zlib(&raw_data)?;
                 let (type_str, _, end_of_header) = Self::parse_header(&data)?;
                 let obj_type = ObjectType::from_string(&type_str)?;
-                Ok((data[end_of_header + 1..].to_vec(), obj_type))
+                (data[end_of_header + 1..].to_vec(), obj_type)
             } else {
                 self_clone
                     .get_from_pack(&hash)?
-                    .map(|x| (x.0, x.1))
-                    .ok_or(GitError::ObjectNotFound(hash.to_string()))
-            }
+                    .ok_or(GitError::ObjectNotFound(hash.to_string()))?
+            };
+            self_clone.object_cache.insert(hash, &data, obj_type);
+            Ok((data, obj_type))
         })
         .await
         .map_err(|e| GitError::IOError(io::Error::other(e)))?
@@ -550,6 +895,22 @@ impl Storage for LocalStorage {
         .unwrap_or(false)
     }
 
+    async fn object_size(&self, hash: &ObjectHash) -> Result<usize, GitError> {
+        let self_clone = self.clone();
+        let hash = *hash;
+
+        tokio::task::spawn_blocking(move || {
+            if let Some(kind) = self_clone.hash_kind {
+                set_hash_kind(kind);
+            }
+            self_clone
+                .header_object_size(&hash)?
+                .ok_or(GitError::ObjectNotFound(hash.to_string()))
+        })
+        .await
+        .map_err(|e| GitError::IOError(io::Error::other(e)))?
+    }
+
     async fn search(&self, prefix: &str) -> Vec<ObjectHash> {
         let self_clone = self.clone();
         let prefix = prefix.to_string();
@@ -559,6 +920,18 @@ impl Storage for LocalStorage {
                 set_hash_kind(kind);
             }
             let mut objects = Vec::new();
+            // Pack objects first: each `.idx` narrows the prefix through its
+            // fan-out table and binary search instead of listing every name.
+            for idx in self_clone.list_all_idx() {
+                match self_clone.pack_index(&idx) {
+                    Ok(index) => objects.extend(index.find_prefix(&prefix)),
+                    Err(err) => tracing::warn!(
+                        idx = %idx.display(),
+                        error = %err,
+                        "skipping unreadable pack index during search"
+                    ),
+                }
+            }
             // Loose objects: walk objects/AB/CDEF... directories. Skip-and-warn on any
             // filesystem hiccup so a single bad entry doesn't kill the whole search.
             if let Ok(paths) = fs::read_dir(&self_clone.base_path) {
@@ -626,17 +999,8 @@ impl Storage for LocalStorage {
                 }
             }
 
-            // Pack objects
-            let idxes = self_clone.list_all_idx();
-            for idx in idxes {
-                if let Ok(objs) = Self::list_idx_objects(&idx) {
-                    for obj in objs {
-                        if obj.to_string().starts_with(&prefix) {
-                            objects.push(obj);
-                        }
-                    }
-                }
-            }
+            objects.sort();
+            objects.dedup();
             objects
         })
         .await
@@ -644,46 +1008,6 @@ impl Storage for LocalStorage {
     }
 }
 
-impl LocalStorage {
-    /// Lists all object hashes contained in a pack index file. This is used for searching objects by prefix in packs.
-    fn list_idx_objects(idx_file: &Path) -> Result<Vec<ObjectHash>, io::Error> {
-        let (version, fanout) = Self::read_idx_fanout(idx_file)?;
-        let mut idx_file = fs::File::open(idx_file)?;
-        let object_count = fanout[255] as u64;
-        let hash_size = get_hash_kind().size() as u64;
-
-        let names_offset = match version {
-            IdxVersion::V1 => FANOUT,
-            IdxVersion::V2 => FANOUT + 8,
-        };
-        idx_file.seek(io::SeekFrom::Start(names_offset))?;
-
-        let mut objs = Vec::new();
-        match version {
-            IdxVersion::V1 => {
-                if hash_size != 20 {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "pack index v1 only supports sha1",
-                    ));
-                }
-                for _ in 0..object_count {
-                    let _offset = idx_file.read_u32::<BigEndian>()?;
-                    let hash = read_sha(&mut idx_file)?;
-                    objs.push(hash);
-                }
-            }
-            IdxVersion::V2 => {
-                for _ in 0..object_count {
-                    let hash = read_sha(&mut idx_file)?;
-                    objs.push(hash);
-                }
-            }
-        }
-        Ok(objs)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     //! Unit-test the loose-object header parser. Validates the v0.17.226
@@ -804,4 +1128,205 @@ mod tests {
             "shard should hold only the final object (no stray temp), got: {entries:?}"
         );
     }
+
+    /// `loose_object_size` reports the payload size from the header alone.
+    #[tokio::test]
+    async fn loose_object_size_reads_the_header() {
+        use git_internal::{
+            hash::{HashKind, ObjectHash, set_hash_kind_for_test},
+            internal::object::types::ObjectType,
+        };
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path().to_path_buf());
+        let data = vec![5u8; 4096];
+        let hash = ObjectHash::from_type_and_data(ObjectType::Blob, &data);
+        storage
+            .put(&hash, &data, ObjectType::Blob)
+            .await
+            .expect("put");
+
+        assert_eq!(storage.loose_object_size(&hash).unwrap(), 4096);
+
+        std::fs::write(storage.get_obj_path(&hash), b"not zlib").unwrap();
+        assert!(storage.loose_object_size(&hash).is_err());
+    }
+
+    /// A second `get` is answered from the object cache; an object above the
+    /// large-object threshold is read from disk every time.
+    #[tokio::test]
+    async fn get_caches_small_objects_and_skips_large_ones() {
+        use git_internal::hash::{HashKind, set_hash_kind_for_test};
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::with_cache_config(
+            dir.path().to_path_buf(),
+            ObjectCacheConfig {
+                max_cached_bytes: 1024,
+                large_object_threshold: 16,
+            },
+        );
+        let small = b"small".to_vec();
+        let large = vec![b'x'; 64];
+        let small_id = ObjectHash::from_type_and_data(ObjectType::Blob, &small);
+        let large_id = ObjectHash::from_type_and_data(ObjectType::Blob, &large);
+        storage
+            .put(&small_id, &small, ObjectType::Blob)
+            .await
+            .unwrap();
+        storage
+            .put(&large_id, &large, ObjectType::Blob)
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(storage.get(&small_id).await.unwrap().0, small);
+            assert_eq!(storage.get(&large_id).await.unwrap().0, large);
+        }
+        assert_eq!(
+            storage.cache_stats(),
+            ObjectCacheStats {
+                hits: 1,
+                misses: 3,
+                entries: 1,
+                cached_bytes: small.len(),
+            }
+        );
+    }
+
+    /// Inserting past `max_cached_bytes` evicts the least recently used
+    /// objects first.
+    #[test]
+    fn object_cache_evicts_least_recently_used_beyond_byte_limit() {
+        let _kind = git_internal::hash::set_hash_kind_for_test(HashKind::Sha1);
+        let cache = ObjectCache::new(ObjectCacheConfig {
+            max_cached_bytes: 10,
+            large_object_threshold: 10,
+        });
+        let ids: Vec<ObjectHash> = ["a", "b", "c"]
+            .iter()
+            .map(|name| ObjectHash::from_type_and_data(ObjectType::Blob, name.as_bytes()))
+            .collect();
+
+        cache.insert(ids[0], b"aaaa", ObjectType::Blob);
+        cache.insert(ids[1], b"bbbb", ObjectType::Blob);
+        // Touch `a` so `b` becomes the eviction candidate.
+        assert!(cache.get(&ids[0]).is_some());
+        cache.insert(ids[2], b"cccc", ObjectType::Blob);
+
+        assert!(cache.get(&ids[0]).is_some());
+        assert!(cache.get(&ids[1]).is_none());
+        assert!(cache.get(&ids[2]).is_some());
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.cached_bytes), (2, 8));
+    }
+
+    #[tokio::test]
+    async fn search_finds_packed_and_loose_objects_by_prefix() {
+        use git_internal::{
+            hash::{HashKind, ObjectHash, set_hash_kind_for_test},
+            internal::object::types::ObjectType,
+        };
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path().to_path_buf());
+        let data = b"loose object".to_vec();
+        let loose = ObjectHash::from_type_and_data(ObjectType::Blob, &data);
+        storage
+            .put(&loose, &data, ObjectType::Blob)
+            .await
+            .expect("put");
+
+        // The index is only consulted next to its pack; the pack body is
+        // never read by a prefix search.
+        let pack_dir = dir.path().join("pack");
+        std::fs::create_dir_all(&pack_dir).unwrap();
+        let (pack, entries) = crate::utils::object::pack_index::tests::blob_pack(&[
+            b"packed object".as_slice(),
+            data.as_slice(),
+        ]);
+        let pack_path = pack_dir.join("pack-test.pack");
+        let idx_path = pack_dir.join("pack-test.idx");
+        std::fs::write(&pack_path, &pack).unwrap();
+        PackIndex::write(&pack_path, &entries, &idx_path).unwrap();
+        let packed = entries[0].0;
+        assert_eq!(entries[1].0, loose);
+
+        let packed_hex = packed.to_string();
+        assert_eq!(storage.search(&packed_hex[..6]).await, vec![packed]);
+        // Present both loose and packed: reported once.
+        assert_eq!(storage.search(&loose.to_string()).await, vec![loose]);
+
+        // The parsed index is cached until the file changes.
+        let cached = storage.pack_index(&idx_path).unwrap();
+        assert!(Arc::ptr_eq(
+            &cached,
+            &storage.pack_index(&idx_path).unwrap()
+        ));
+        let (pack, entries) =
+            crate::utils::object::pack_index::tests::blob_pack(&[data.as_slice()]);
+        std::fs::write(&pack_path, &pack).unwrap();
+        PackIndex::write(&pack_path, &entries, &idx_path).unwrap();
+        assert!(storage.search(&packed_hex[..6]).await.is_empty());
+    }
+
+    /// `object_size` answers from the pack entry header, and for a delta from
+    /// the delta's result size, without rebuilding the object.
+    #[tokio::test]
+    async fn object_size_reads_pack_entry_and_delta_headers() {
+        use flate2::{Compression, write::ZlibEncoder};
+        use git_internal::{
+            hash::{HashKind, ObjectHash, set_hash_kind_for_test},
+            internal::object::types::ObjectType,
+        };
+        use sha1::Digest;
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::new(dir.path().to_path_buf());
+        let base = vec![b'a'; 5000];
+        let (pack, mut entries) =
+            crate::utils::object::pack_index::tests::blob_pack(&[base.as_slice()]);
+
+        // Append an OFS_DELTA that copies the base and inserts one byte.
+        let mut pack = pack[..pack.len() - 20].to_vec();
+        pack[8..12].copy_from_slice(&2u32.to_be_bytes());
+        let mut result = base.clone();
+        result.push(b'!');
+        let delta = [0x88, 0x27, 0x89, 0x27, 0xb0, 0x88, 0x13, 0x01, b'!'];
+        let delta_offset = pack.len() as u64;
+        let distance = delta_offset - entries[0].1;
+        assert!(distance < 0x80, "single-byte base offset");
+        pack.push((6 << 4) | delta.len() as u8);
+        pack.push(distance as u8);
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&delta).unwrap();
+        pack.extend(encoder.finish().unwrap());
+        let checksum = sha1::Sha1::digest(&pack).to_vec();
+        pack.extend(checksum);
+        entries.push((
+            ObjectHash::from_type_and_data(ObjectType::Blob, &result),
+            delta_offset,
+        ));
+
+        let pack_dir = dir.path().join("pack");
+        std::fs::create_dir_all(&pack_dir).unwrap();
+        let pack_path = pack_dir.join("pack-test.pack");
+        std::fs::write(&pack_path, &pack).unwrap();
+        PackIndex::write(&pack_path, &entries, &pack_dir.join("pack-test.idx")).unwrap();
+
+        assert_eq!(storage.object_size(&entries[0].0).await.unwrap(), 5000);
+        assert_eq!(storage.object_size(&entries[1].0).await.unwrap(), 5001);
+        let (data, _) = storage.get(&entries[1].0).await.expect("rebuild delta");
+        assert_eq!(data, result);
+
+        let absent = ObjectHash::from_type_and_data(ObjectType::Blob, b"absent");
+        assert!(matches!(
+            storage.object_size(&absent).await,
+            Err(GitError::ObjectNotFound(_))
+        ));
+    }
 }
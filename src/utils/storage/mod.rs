@@ -6,6 +6,7 @@
 //! accidentally route publish JSON / bytes through Git zlib/header
 //! packing.
 pub mod local;
+pub mod promisor;
 pub mod publish_storage;
 pub mod remote;
 pub mod tiered;
@@ -41,6 +42,13 @@ pub trait Storage: Send + Sync {
     /// Note: Performance may vary significantly between backends (fast locally, potentially slow remotely).
     async fn search(&self, prefix: &str) -> Vec<ObjectHash>;
 
+    /// Uncompressed size of an object's payload. The default reads the whole
+    /// object; [`local::LocalStorage`] answers from the loose-object or pack
+    /// entry header, so a caller can skip a large blob without inflating it.
+    async fn object_size(&self, hash: &ObjectHash) -> Result<usize, GitError> {
+        self.get(hash).await.map(|(data, _)| data.len())
+    }
+
     /// Batch existence check — returns one `bool` per input hash, in the same
     /// order (`lore.md` §0.6). Used as a dedup pre-check (e.g. "which of these
     /// objects does the remote already have before I upload?").
@@ -75,4 +83,21 @@ pub trait Storage: Send + Sync {
     async fn heal(&self, _hash: &ObjectHash) -> Result<bool, GitError> {
         Ok(false)
     }
+
+    /// Replace large local objects that the durable tier already holds with
+    /// redirect markers (`libra cache dedup`). See
+    /// [`tiered::TieredStorage::dedup`].
+    ///
+    /// Returns `Ok(None)` when this backend has no durable tier to dedup
+    /// against; only [`tiered::TieredStorage`] overrides this.
+    async fn dedup(&self) -> Result<Option<tiered::DedupReport>, GitError> {
+        Ok(None)
+    }
+
+    /// Check that every redirect left by [`Self::dedup`] still resolves
+    /// (`libra cache verify-dedup`). `None` when this backend has no durable
+    /// tier.
+    async fn verify_dedup(&self) -> Option<tiered::VerifyDedupReport> {
+        None
+    }
 }
@@ -0,0 +1,214 @@
+//! Partial-clone storage wrapper. A repository cloned with `--filter` is missing
+//! the objects its promisor remote omitted; `PromisorStorage` answers a `get`
+//! miss by fetching the object from that remote (`extensions.partialclone`) and
+//! reading it again, so callers never see the gap.
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, mpsc},
+};
+
+use async_trait::async_trait;
+use git_internal::{errors::GitError, hash::ObjectHash, internal::object::types::ObjectType};
+
+use super::{
+    Storage,
+    tiered::{DedupReport, VerifyDedupReport},
+};
+use crate::{command::fetch, utils::path};
+
+pub struct PromisorStorage {
+    inner: Arc<dyn Storage>,
+    base_path: PathBuf,
+}
+
+impl PromisorStorage {
+    pub fn new(inner: Arc<dyn Storage>, base_path: PathBuf) -> Self {
+        PromisorStorage { inner, base_path }
+    }
+
+    /// Lazy fetches write into the current repository's pack directory, so
+    /// only a store rooted there may trigger one.
+    fn is_current_repository(&self) -> bool {
+        path::try_objects().is_ok_and(|objects| objects == self.base_path)
+    }
+}
+
+/// A missing object queued for the lazy fetcher, with the channel its
+/// caller waits on.
+struct LazyFetch {
+    objects: PathBuf,
+    hash: ObjectHash,
+    reply: mpsc::Sender<Result<bool, String>>,
+}
+
+static LAZY_FETCHER: OnceLock<mpsc::Sender<LazyFetch>> = OnceLock::new();
+
+thread_local! {
+    /// Set on the lazy fetcher's thread, so reading objects during a fetch
+    /// never queues another one behind itself.
+    static IN_LAZY_FETCHER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Start the lazy fetcher on first use: one thread with one runtime, as
+/// `resolve_env_sync` does for config reads, so a fetch never nests inside the
+/// storage runtime that is waiting on this `get`. The promisor client is
+/// built once per repository, and misses queued while a fetch is running are
+/// fetched together in the next upload-pack request.
+fn lazy_fetcher() -> &'static mpsc::Sender<LazyFetch> {
+    LAZY_FETCHER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<LazyFetch>();
+        std::thread::spawn(move || {
+            IN_LAZY_FETCHER.with(|flag| flag.set(true));
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(error) => {
+                    let message = format!("failed to create runtime for lazy fetch: {error}");
+                    for request in receiver {
+                        let _ = request.reply.send(Err(message.clone()));
+                    }
+                    return;
+                }
+            };
+            let mut remotes: HashMap<PathBuf, Option<fetch::PromisorRemote>> = HashMap::new();
+            while let Ok(first) = receiver.recv() {
+                let mut batch = vec![first];
+                batch.extend(receiver.try_iter());
+                runtime.block_on(serve_lazy_fetches(batch, &mut remotes));
+            }
+        });
+        sender
+    })
+}
+
+/// Fetch one batch of misses, a single request per repository, and answer
+/// every waiting caller with the outcome.
+async fn serve_lazy_fetches(
+    batch: Vec<LazyFetch>,
+    remotes: &mut HashMap<PathBuf, Option<fetch::PromisorRemote>>,
+) {
+    let mut by_repository: HashMap<PathBuf, Vec<LazyFetch>> = HashMap::new();
+    for request in batch {
+        by_repository
+            .entry(request.objects.clone())
+            .or_default()
+            .push(request);
+    }
+    for (objects, requests) in by_repository {
+        if !remotes.contains_key(&objects) {
+            match fetch::PromisorRemote::configured().await {
+                Ok(remote) => {
+                    remotes.insert(objects.clone(), remote);
+                }
+                Err(error) => {
+                    let error = error.to_string();
+                    for request in requests {
+                        let _ = request.reply.send(Err(error.clone()));
+                    }
+                    continue;
+                }
+            }
+        }
+        let outcome = match &remotes[&objects] {
+            Some(remote) => {
+                let mut hashes: Vec<ObjectHash> =
+                    requests.iter().map(|request| request.hash).collect();
+                hashes.sort();
+                hashes.dedup();
+                remote
+                    .fetch(&hashes)
+                    .await
+                    .map(|()| true)
+                    .map_err(|error| error.to_string())
+            }
+            None => Ok(false),
+        };
+        for request in requests {
+            let _ = request.reply.send(outcome.clone());
+        }
+    }
+}
+
+/// Queue `hash` for the lazy fetcher and wait for its batch to finish.
+/// Returns `Ok(false)` when the repository is not a partial clone, or when
+/// called from the fetcher itself.
+fn fetch_promised_object_blocking(objects: &Path, hash: ObjectHash) -> Result<bool, String> {
+    if IN_LAZY_FETCHER.with(Cell::get) {
+        return Ok(false);
+    }
+    let (reply, outcome) = mpsc::channel();
+    lazy_fetcher()
+        .send(LazyFetch {
+            objects: objects.to_path_buf(),
+            hash,
+            reply,
+        })
+        .map_err(|_| "lazy fetcher is not running".to_string())?;
+    outcome
+        .recv()
+        .map_err(|_| "lazy fetcher stopped before answering".to_string())?
+}
+
+#[async_trait]
+impl Storage for PromisorStorage {
+    async fn get(&self, hash: &ObjectHash) -> Result<(Vec<u8>, ObjectType), GitError> {
+        match self.inner.get(hash).await {
+            Err(GitError::ObjectNotFound(missing)) if self.is_current_repository() => {
+                match fetch_promised_object_blocking(&self.base_path, *hash) {
+                    Ok(true) => self.inner.get(hash).await,
+                    Ok(false) => Err(GitError::ObjectNotFound(missing)),
+                    Err(error) => Err(GitError::CustomError(format!(
+                        "failed to fetch missing object {hash} from promisor remote: {error}"
+                    ))),
+                }
+            }
+            result => result,
+        }
+    }
+
+    async fn put(
+        &self,
+        hash: &ObjectHash,
+        data: &[u8],
+        obj_type: ObjectType,
+    ) -> Result<String, GitError> {
+        self.inner.put(hash, data, obj_type).await
+    }
+
+    async fn exist(&self, hash: &ObjectHash) -> bool {
+        self.inner.exist(hash).await
+    }
+
+    async fn search(&self, prefix: &str) -> Vec<ObjectHash> {
+        self.inner.search(prefix).await
+    }
+
+    async fn object_size(&self, hash: &ObjectHash) -> Result<usize, GitError> {
+        match self.inner.object_size(hash).await {
+            Err(GitError::ObjectNotFound(_)) if self.is_current_repository() => {
+                self.get(hash).await.map(|(data, _)| data.len())
+            }
+            result => result,
+        }
+    }
+
+    async fn exist_batch(&self, hashes: &[ObjectHash]) -> Vec<bool> {
+        self.inner.exist_batch(hashes).await
+    }
+
+    async fn heal(&self, hash: &ObjectHash) -> Result<bool, GitError> {
+        self.inner.heal(hash).await
+    }
+
+    async fn dedup(&self) -> Result<Option<DedupReport>, GitError> {
+        self.inner.dedup().await
+    }
+
+    async fn verify_dedup(&self) -> Option<VerifyDedupReport> {
+        self.inner.verify_dedup().await
+    }
+}
@@ -174,6 +174,112 @@ impl TieredStorage {
         }
         Ok(())
     }
+
+    /// Whether `hash` was deduplicated to the remote tier by [`Self::dedup`].
+    fn is_redirected(&self, hash: &ObjectHash) -> bool {
+        self.local.get_redirect_path(hash).is_file()
+    }
+
+    /// Reclaim local disk by dropping large loose objects the remote tier
+    /// already holds. Only objects at or above the small/large threshold are
+    /// considered: small objects are meant to stay local permanently, so
+    /// redirecting them would turn every commit/tree read into a remote round
+    /// trip. Each deduplicated object is replaced by a thin redirect marker
+    /// (`objects/redirect/ab/cdef...`, holding the object id) so later reads
+    /// know to go to the remote without re-caching the object. The remote tier
+    /// is an object store, so there is no file to hardlink to. Packed objects
+    /// are left alone.
+    ///
+    /// Idempotent and safe to interrupt: the marker is written atomically
+    /// before the loose file is removed, so a run cut short leaves at worst an
+    /// object with both, which the next run finishes.
+    pub async fn dedup(&self) -> Result<DedupReport, GitError> {
+        let loose = self.local.loose_objects();
+        let mut report = DedupReport {
+            scanned: loose.len(),
+            ..DedupReport::default()
+        };
+        let mut large = Vec::new();
+        for hash in loose {
+            if self.local.loose_object_size(&hash)? >= self.threshold {
+                large.push(hash);
+            } else {
+                report.below_threshold += 1;
+            }
+        }
+        let in_remote = self.remote.exist_batch(&large).await;
+        for (hash, remote_has) in large.iter().zip(in_remote) {
+            if !remote_has {
+                report.local_only += 1;
+                continue;
+            }
+            crate::utils::atomic_write::write_atomic(
+                &self.local.get_redirect_path(hash),
+                format!("{hash}\n").as_bytes(),
+                crate::utils::atomic_write::sync_data_enabled(),
+            )?;
+
+            let path = self.local.get_obj_path(hash);
+            let size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+            // A large object's `CachedFile` deletes the file when dropped;
+            // untrack it so the LRU no longer counts it against the budget.
+            drop(
+                self.lru
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .remove(hash),
+            );
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+            report.deduplicated += 1;
+            report.bytes_reclaimed += size;
+        }
+        Ok(report)
+    }
+
+    /// Check that every redirect left by [`Self::dedup`] still resolves: the
+    /// remote tier has the object, or a copy is back in the local store.
+    pub async fn verify_dedup(&self) -> VerifyDedupReport {
+        let redirects = self.local.redirected_objects();
+        let resolves = self.remote.exist_batch(&redirects).await;
+        let mut dangling = Vec::new();
+        for (hash, in_remote) in redirects.iter().zip(resolves) {
+            if !in_remote && !self.local.exist(hash).await {
+                dangling.push(*hash);
+            }
+        }
+        VerifyDedupReport {
+            checked: redirects.len(),
+            dangling,
+        }
+    }
+}
+
+/// Outcome of [`TieredStorage::dedup`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DedupReport {
+    /// Loose objects examined.
+    pub scanned: usize,
+    /// Loose objects replaced by a redirect in this run.
+    pub deduplicated: usize,
+    /// Loose objects kept because they are smaller than the threshold.
+    pub below_threshold: usize,
+    /// Large loose objects kept because the remote tier does not have them.
+    pub local_only: usize,
+    /// Size of the loose-object files removed.
+    pub bytes_reclaimed: u64,
+}
+
+/// Outcome of [`TieredStorage::verify_dedup`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VerifyDedupReport {
+    /// Redirects examined.
+    pub checked: usize,
+    /// Redirected objects found neither in the remote tier nor locally.
+    pub dangling: Vec<ObjectHash>,
 }
 
 #[async_trait]
@@ -187,7 +293,9 @@ impl Storage for TieredStorage {
             match self.remote.get(hash).await {
                 Ok((data, obj_type)) => {
                     verify_fetched_object(hash, obj_type, &data)?;
-                    self.cache_fetched_object(hash, &data, obj_type).await?;
+                    if !self.is_redirected(hash) {
+                        self.cache_fetched_object(hash, &data, obj_type).await?;
+                    }
                     return Ok((data, obj_type));
                 }
                 Err(GitError::ObjectNotFound(_)) => { /* fall back to local below */ }
@@ -213,6 +321,12 @@ impl Storage for TieredStorage {
         // Not local. Under `--offline`/`--local`, never reach for the durable
         // tier — surface a clear, actionable error instead (lore.md §0.8).
         if policy == ReadPolicy::LocalOnly {
+            if self.is_redirected(hash) {
+                return Err(GitError::ObjectNotFound(format!(
+                    "object {hash} was deduplicated to the durable tier; the offline/local \
+                     read policy forbids fetching it (drop --offline/--local to fetch)"
+                )));
+            }
             return Err(GitError::ObjectNotFound(format!(
                 "object {hash} is not in the local store; the offline/local read \
                  policy forbids fetching it from the durable tier (drop --offline/--local \
@@ -225,7 +339,10 @@ impl Storage for TieredStorage {
         // Verify-on-cache: reject a payload that does not hash to the requested
         // OID before it can poison the local cache (lore.md §0.3).
         verify_fetched_object(hash, obj_type, &data)?;
-        self.cache_fetched_object(hash, &data, obj_type).await?;
+        // A deduplicated object stays remote-only instead of being re-cached.
+        if !self.is_redirected(hash) {
+            self.cache_fetched_object(hash, &data, obj_type).await?;
+        }
         Ok((data, obj_type))
     }
 
@@ -317,6 +434,21 @@ impl Storage for TieredStorage {
         self.cache_fetched_object(hash, &data, obj_type).await?;
         Ok(true)
     }
+
+    async fn object_size(&self, hash: &ObjectHash) -> Result<usize, GitError> {
+        match self.local.object_size(hash).await {
+            Err(GitError::ObjectNotFound(_)) => self.get(hash).await.map(|(data, _)| data.len()),
+            result => result,
+        }
+    }
+
+    async fn dedup(&self) -> Result<Option<DedupReport>, GitError> {
+        TieredStorage::dedup(self).await.map(Some)
+    }
+
+    async fn verify_dedup(&self) -> Option<VerifyDedupReport> {
+        Some(TieredStorage::verify_dedup(self).await)
+    }
 }
 
 #[cfg(test)]
@@ -858,6 +990,137 @@ mod tests {
         );
     }
 
+    /// `dedup` replaces large loose objects the remote already holds with
+    /// redirect markers, keeps local-only and below-threshold ones, and serves
+    /// deduplicated objects from the remote without re-caching them. A second
+    /// run has nothing left to do.
+    #[tokio::test]
+    #[serial]
+    async fn dedup_redirects_remote_backed_objects_and_is_idempotent() {
+        use std::sync::Arc;
+
+        use git_internal::hash::{HashKind, set_hash_kind_for_test};
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let obj_type = ObjectType::Blob;
+        let shared = b"in both tiers".to_vec();
+        let shared_hash = ObjectHash::from_type_and_data(obj_type, &shared);
+        let private = b"local only".to_vec();
+        let private_hash = ObjectHash::from_type_and_data(obj_type, &private);
+        let small = b"tiny".to_vec();
+        let small_hash = ObjectHash::from_type_and_data(obj_type, &small);
+
+        let remote = RemoteStorage::new(Arc::new(object_store::memory::InMemory::new()));
+        remote.put(&shared_hash, &shared, obj_type).await.unwrap();
+        remote.put(&small_hash, &small, obj_type).await.unwrap();
+        let dir = tempdir().unwrap();
+        let local = LocalStorage::new(dir.path().to_path_buf());
+        local.put(&shared_hash, &shared, obj_type).await.unwrap();
+        local.put(&private_hash, &private, obj_type).await.unwrap();
+        local.put(&small_hash, &small, obj_type).await.unwrap();
+        // threshold=8: the 4-byte object is small and must stay local even
+        // though the remote has it.
+        let tiered = TieredStorage::new(local, remote, 8, 1 << 20);
+
+        let report = tiered.dedup().await.expect("dedup");
+        assert_eq!(report.scanned, 3);
+        assert_eq!(report.deduplicated, 1);
+        assert_eq!(report.below_threshold, 1);
+        assert_eq!(report.local_only, 1);
+        assert!(report.bytes_reclaimed > 0);
+        assert!(!tiered.local.get_obj_path(&shared_hash).exists());
+        assert!(tiered.local.get_redirect_path(&shared_hash).is_file());
+        assert!(tiered.local.get_obj_path(&private_hash).exists());
+        assert!(tiered.local.get_obj_path(&small_hash).exists());
+        assert!(!tiered.local.get_redirect_path(&small_hash).exists());
+
+        let (got, _) = tiered.get(&shared_hash).await.expect("get via redirect");
+        assert_eq!(got, shared);
+        assert!(
+            !tiered.local.get_obj_path(&shared_hash).exists(),
+            "a deduplicated object must not be re-cached"
+        );
+
+        let again = tiered.dedup().await.expect("second dedup");
+        assert_eq!(again.scanned, 2);
+        assert_eq!(again.deduplicated, 0);
+        assert_eq!(tiered.verify_dedup().await.checked, 1);
+        assert!(tiered.verify_dedup().await.dangling.is_empty());
+    }
+
+    /// A run interrupted after writing the marker but before removing the
+    /// loose file is finished by the next run.
+    #[tokio::test]
+    async fn dedup_completes_an_interrupted_run() {
+        use std::sync::Arc;
+
+        use git_internal::hash::{HashKind, set_hash_kind_for_test};
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let obj_type = ObjectType::Blob;
+        let data = vec![3u8; 128];
+        let hash = ObjectHash::from_type_and_data(obj_type, &data);
+        let remote = RemoteStorage::new(Arc::new(object_store::memory::InMemory::new()));
+        let dir = tempdir().unwrap();
+        // threshold=16: the put tracks the object in the LRU.
+        let tiered = TieredStorage::new(
+            LocalStorage::new(dir.path().to_path_buf()),
+            remote,
+            16,
+            1 << 20,
+        );
+        tiered.put(&hash, &data, obj_type).await.unwrap();
+
+        let marker = tiered.local.get_redirect_path(&hash);
+        fs::create_dir_all(marker.parent().unwrap()).unwrap();
+        fs::write(&marker, format!("{hash}\n")).unwrap();
+
+        let report = tiered.dedup().await.expect("dedup");
+        assert_eq!(report.deduplicated, 1);
+        assert!(!tiered.local.get_obj_path(&hash).exists());
+        assert!(marker.is_file());
+        assert!(
+            tiered.lru.lock().unwrap().get(&hash).is_none(),
+            "deduplicated object must no longer count against the cache budget"
+        );
+    }
+
+    /// `verify_dedup` reports a redirect whose object has gone from the remote
+    /// tier, unless a copy is back in the local store.
+    #[tokio::test]
+    async fn verify_dedup_reports_dangling_redirects() {
+        use std::sync::Arc;
+
+        use git_internal::hash::{HashKind, set_hash_kind_for_test};
+
+        let _kind = set_hash_kind_for_test(HashKind::Sha1);
+        let obj_type = ObjectType::Blob;
+        let data = b"deduplicated".to_vec();
+        let hash = ObjectHash::from_type_and_data(obj_type, &data);
+        let remote = RemoteStorage::new(Arc::new(object_store::memory::InMemory::new()));
+        remote.put(&hash, &data, obj_type).await.unwrap();
+        let dir = tempdir().unwrap();
+        let local = LocalStorage::new(dir.path().to_path_buf());
+        local.put(&hash, &data, obj_type).await.unwrap();
+        let tiered = TieredStorage::new(local, remote, 8, 1 << 20);
+        tiered.dedup().await.expect("dedup");
+
+        // Same local store, but a remote tier that lost the object.
+        let empty = RemoteStorage::new(Arc::new(object_store::memory::InMemory::new()));
+        let detached = TieredStorage::new(
+            LocalStorage::new(dir.path().to_path_buf()),
+            empty,
+            8,
+            1 << 20,
+        );
+        let report = detached.verify_dedup().await;
+        assert_eq!(report.checked, 1);
+        assert_eq!(report.dangling, vec![hash]);
+
+        detached.local.put(&hash, &data, obj_type).await.unwrap();
+        assert!(detached.verify_dedup().await.dangling.is_empty());
+    }
+
     /// `HeapSize::heap_size` MUST report the `disk_size` accounting
     /// field (the uncompressed object length — see the field doc) so
     /// the `LruCache`'s budget bounds cached-object resource cost. If
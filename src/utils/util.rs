@@ -7,6 +7,7 @@ use std::{
     fs, io,
     io::Write,
     path::{Path, PathBuf},
+    str::FromStr,
     sync::{Arc, Mutex},
     time::SystemTime,
 };
@@ -28,7 +29,9 @@ use crate::{
     internal::{
         branch::{Branch, BranchStoreError},
         config::ConfigKv,
+        db::get_db_conn_instance,
         head::Head,
+        reflog::Reflog,
         tag,
     },
     utils::{client_storage::ClientStorage, path, path_ext::PathExt},
@@ -530,6 +533,9 @@ pub fn list_files(path: &Path) -> io::Result<Vec<PathBuf>> {
             let entry = entry?;
             let path = entry.path();
             if path.is_dir() {
+                if is_nested_repository(&path) {
+                    continue;
+                }
                 files.extend(list_files(&path)?);
             } else {
                 files.push(to_workdir_path(&path));
@@ -539,6 +545,14 @@ pub fn list_files(path: &Path) -> io::Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// Whether `dir` is the root of another repository inside the worktree (for
+/// example a submodule checkout): it has its own `.libra` directory. Worktree
+/// walks list nothing below such a directory, as Git does for nested
+/// repositories.
+pub fn is_nested_repository(dir: &Path) -> bool {
+    dir.join(ROOT_DIR).is_dir()
+}
+
 /// list all non-ignored files in the working dir(include sub_dir)
 /// - output: to workdir path
 pub fn list_workdir_files() -> io::Result<Vec<PathBuf>> {
@@ -567,10 +581,13 @@ fn list_files_respecting_libraignore(path: &Path) -> io::Result<Vec<PathBuf>> {
         .git_global(false)
         .git_exclude(false)
         .add_custom_ignore_filename(LIBRAIGNORE_FILE)
-        // Always skip `.libra` (Libra metadata) and `.git` (like Git).
+        // Always skip `.libra` (Libra metadata) and `.git` (like Git), and
+        // never descend into a nested repository such as a submodule.
         .filter_entry(|entry| {
             let name = entry.file_name();
-            name != OsStr::new(ROOT_DIR) && name != OsStr::new(GIT_DIR)
+            name != OsStr::new(ROOT_DIR)
+                && name != OsStr::new(GIT_DIR)
+                && !(entry.depth() > 0 && is_nested_repository(entry.path()))
         });
 
     for entry in builder.build() {
@@ -766,8 +783,11 @@ fn nth_parent_commit_typed(
     })?;
 
     if n == 0 || n > commit.parent_commit_ids.len() {
+        let short = commit_id.to_string();
         return Err(CommitBaseError::InvalidReference(format!(
-            "invalid reference: {display_name}"
+            "invalid reference: {display_name} (commit {} has {} parent(s), no parent {n})",
+            &short[..7.min(short.len())],
+            commit.parent_commit_ids.len()
         )));
     }
 
@@ -833,7 +853,141 @@ fn navigate_commit_path_typed(
     Ok(current)
 }
 
+/// Split a trailing `@{<selector>}` off a revision, e.g. `HEAD@{2}` ->
+/// (`HEAD`, `2`), `@{-1}` -> (``, `-1`), `main@{u}` -> (`main`, `u`).
+fn split_at_selector(name: &str) -> Option<(&str, &str)> {
+    let body = name.strip_suffix('}')?;
+    let at = body.rfind("@{")?;
+    Some((&body[..at], &body[at + 2..]))
+}
+
+/// Name of the branch (or short commit id, when HEAD was detached) checked out
+/// `n` switches ago, read from the `moving from <from> to <to>` HEAD reflog
+/// entries written by `switch`/`checkout`. `n = 1` is the branch before the
+/// current one; `None` when the reflog does not reach back that far.
+pub async fn previous_branch_name(n: usize) -> Result<Option<String>, CommitBaseError> {
+    let db = get_db_conn_instance().await;
+    let entries = Reflog::find_all(&db, "HEAD").await.map_err(|error| {
+        CommitBaseError::classify_storage_failure(format!(
+            "failed to read the HEAD reflog: {error}"
+        ))
+    })?;
+    let mut switches = entries.iter().filter_map(|entry| {
+        if entry.action != "switch" && entry.action != "checkout" {
+            return None;
+        }
+        let (from, _to) = entry
+            .message
+            .strip_prefix("moving from ")?
+            .split_once(" to ")?;
+        Some(from.to_string())
+    });
+    Ok(n.checked_sub(1).and_then(|skip| switches.nth(skip)))
+}
+
+/// Resolve `<base>@{<selector>}`:
+/// - `@{-N}`: the N-th branch checked out before the current one,
+/// - `[<branch>]@{upstream}` / `@{u}`: the branch's configured upstream,
+/// - `[<ref>]@{N}`: the N-th prior value of `<ref>` in its reflog (`@{0}` is
+///   the current value).
+///
+/// An empty base means the current branch (or HEAD, when detached).
+async fn resolve_at_selector_typed(
+    name: &str,
+    base: &str,
+    selector: &str,
+) -> Result<ObjectHash, CommitBaseError> {
+    let invalid = || CommitBaseError::InvalidReference(format!("invalid reference: {name}"));
+
+    if let Some(n) = selector.strip_prefix('-') {
+        let n: usize = n.parse().map_err(|_| invalid())?;
+        if !base.is_empty() || n == 0 {
+            return Err(invalid());
+        }
+        let previous = previous_branch_name(n).await?.ok_or_else(|| {
+            CommitBaseError::InvalidReference(format!(
+                "{name}: only {} previous branch switch(es) recorded in the reflog",
+                n - 1
+            ))
+        })?;
+        return Box::pin(resolve_commit_base_atom_typed(&previous)).await;
+    }
+
+    let current_branch = || async {
+        match Head::current_result().await {
+            Ok(Head::Branch(branch)) => Ok(Some(branch)),
+            Ok(Head::Detached(_)) => Ok(None),
+            Err(error) => Err(CommitBaseError::from_branch_store_error(
+                "failed to resolve HEAD".to_string(),
+                error,
+            )),
+        }
+    };
+
+    if selector.eq_ignore_ascii_case("u") || selector.eq_ignore_ascii_case("upstream") {
+        let branch = match base {
+            "" | "HEAD" => current_branch().await?.ok_or_else(|| {
+                CommitBaseError::InvalidReference(format!(
+                    "{name}: HEAD does not point to a branch"
+                ))
+            })?,
+            branch => branch.to_string(),
+        };
+        let upstream = ConfigKv::branch_config(&branch)
+            .await
+            .map_err(|error| {
+                CommitBaseError::ReadFailure(format!(
+                    "failed to read branch configuration for '{branch}': {error}"
+                ))
+            })?
+            .ok_or_else(|| {
+                CommitBaseError::InvalidReference(format!(
+                    "no upstream configured for branch '{branch}'"
+                ))
+            })?;
+        // `branch.<b>.remote = .` tracks a local branch.
+        let target = if upstream.remote == "." {
+            upstream.merge
+        } else {
+            format!("{}/{}", upstream.remote, upstream.merge)
+        };
+        return Box::pin(resolve_commit_base_atom_typed(&target)).await;
+    }
+
+    let n: usize = selector.parse().map_err(|_| invalid())?;
+    let ref_name = match base {
+        "" => match current_branch().await? {
+            Some(branch) => format!("refs/heads/{branch}"),
+            None => "HEAD".to_string(),
+        },
+        "HEAD" => "HEAD".to_string(),
+        other if other.starts_with("refs/") => other.to_string(),
+        branch => format!("refs/heads/{branch}"),
+    };
+    let db = get_db_conn_instance().await;
+    let entries = Reflog::find_all(&db, &ref_name).await.map_err(|error| {
+        CommitBaseError::classify_storage_failure(format!(
+            "failed to read the reflog for '{ref_name}': {error}"
+        ))
+    })?;
+    let entry = entries.get(n).ok_or_else(|| {
+        CommitBaseError::InvalidReference(format!(
+            "log for '{ref_name}' only has {} entries",
+            entries.len()
+        ))
+    })?;
+    ObjectHash::from_str(&entry.new_oid).map_err(|error| {
+        CommitBaseError::CorruptReference(format!(
+            "invalid object id in the reflog for '{ref_name}': {error}"
+        ))
+    })
+}
+
 async fn resolve_commit_base_atom_typed(name: &str) -> Result<ObjectHash, CommitBaseError> {
+    if let Some((base, selector)) = split_at_selector(name) {
+        return resolve_at_selector_typed(name, base, selector).await;
+    }
+
     // 1. Check for HEAD
     if name == "HEAD" {
         return match Head::current_commit_result().await {
@@ -846,6 +1000,21 @@ async fn resolve_commit_base_atom_typed(name: &str) -> Result<ObjectHash, Commit
         };
     }
 
+    // `ORIG_HEAD`, when a reset/merge/rebase/pull has recorded one; otherwise
+    // the name resolves like any other ref (and usually fails as unknown).
+    if name == "ORIG_HEAD" {
+        match Head::orig_head() {
+            Ok(Some(commit_id)) => return Ok(commit_id),
+            Ok(None) => {}
+            Err(error) => {
+                return Err(CommitBaseError::from_branch_store_error(
+                    "failed to resolve ORIG_HEAD".to_string(),
+                    error,
+                ));
+            }
+        }
+    }
+
     // 2. Check for a local branch
     if let Some(commit) = resolve_branch_commit_typed(name, None, name).await? {
         return Ok(commit);
@@ -993,8 +1162,10 @@ pub async fn get_commit_base_typed(name: &str) -> Result<ObjectHash, CommitBaseE
 /// Resolve a string to a commit [`ObjectHash`].
 /// The string can be a local branch name, a remote-tracking branch name
 /// (such as `origin/main`), a tag name, or a commit hash prefix.
+/// A trailing `@{N}`, `@{-N}` or `@{upstream}`/`@{u}` selector is resolved
+/// through the reflog or the branch's upstream config first.
 /// Order of resolution:
-/// 1. HEAD
+/// 1. HEAD (and `ORIG_HEAD`, when recorded)
 /// 2. Local branch
 /// 3. Remote-tracking branch (e.g. `origin/main`)
 /// 4. Tag
@@ -1034,18 +1205,21 @@ pub fn auto_unit_bytes(bytes: u64) -> byte_unit::AdjustedByte {
 /// Create a default style progress bar
 pub fn default_progress_bar(len: u64) -> ProgressBar {
     let progress_bar = ProgressBar::new(len);
-    progress_bar.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.magenta} [{elapsed_precise}] [{bar:40.green/white}] {bytes}/{total_bytes} ({eta}) {bytes_per_sec}")
-            // INVARIANT: the template string is a compile-time literal whose
-            // placeholders are validated by indicatif at parse time; this is
-            // covered by every command that uses default_progress_bar().
-            .expect("default progress bar template is a valid indicatif format string")
-            .progress_chars("=> "),
-    );
+    progress_bar.set_style(default_progress_style());
     progress_bar
 }
 
+/// The byte-count bar style used by [`default_progress_bar`].
+pub fn default_progress_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{spinner:.magenta} [{elapsed_precise}] [{bar:40.green/white}] {bytes}/{total_bytes} ({eta}) {bytes_per_sec}")
+        // INVARIANT: the template string is a compile-time literal whose
+        // placeholders are validated by indicatif at parse time; this is
+        // covered by every command that uses default_progress_bar().
+        .expect("default progress bar template is a valid indicatif format string")
+        .progress_chars("=> ")
+}
+
 /// Returns `true` when any component of `target_file`, taken relative to
 /// `work_dir`, is a literal `.git` entry (see [`GIT_DIR`]). Only the portion
 /// below `work_dir` is inspected, so a repository that merely lives under an
@@ -1733,6 +1907,8 @@ mod test {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
         commit::execute(CommitArgs {
@@ -1807,6 +1983,8 @@ mod test {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
         commit::execute(CommitArgs {
@@ -1852,6 +2030,8 @@ mod test {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
         commit::execute(CommitArgs {
@@ -1900,6 +2080,8 @@ mod test {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
         commit::execute(CommitArgs {
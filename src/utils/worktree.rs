@@ -1,8 +1,11 @@
 //! Worktree helpers shared across commands.
 
-use std::path::{Path, PathBuf};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
 
-use git_internal::internal::index::Index;
+use git_internal::internal::{index::Index, object::tree::TreeItemMode};
 
 use crate::utils::{
     ignore::{self, IgnorePolicy},
@@ -101,3 +104,98 @@ pub fn untracked_overwrite_path(untracked: &[PathBuf], new_index: &Index) -> Opt
 pub fn paths_conflict(left: &Path, right: &Path) -> bool {
     left == right || left.starts_with(right) || right.starts_with(left)
 }
+
+/// The tree mode an index entry's mode bits record.
+pub fn tree_mode_from_index_mode(mode: u32) -> TreeItemMode {
+    match mode & 0o170000 {
+        0o120000 => TreeItemMode::Link,
+        0o160000 => TreeItemMode::Commit,
+        _ if mode & 0o111 != 0 => TreeItemMode::BlobExecutable,
+        _ => TreeItemMode::Blob,
+    }
+}
+
+/// The index mode bits for a blob's tree mode.
+pub fn index_mode_from_tree_mode(mode: TreeItemMode) -> u32 {
+    match mode {
+        TreeItemMode::BlobExecutable => 0o100755,
+        TreeItemMode::Link => 0o120000,
+        TreeItemMode::Commit => 0o160000,
+        _ => 0o100644,
+    }
+}
+
+/// Write `content` to `path` as the file type `mode` records: a regular file
+/// with `0o644` or `0o755` permissions, or a symlink whose target is the blob
+/// content (`120000`). An existing symlink at `path` is replaced rather than
+/// written through. Windows has no executable bit or unprivileged symlinks, so
+/// there both blob modes land as plain files and a link as a file holding its
+/// target, like Git with `core.symlinks=false`.
+pub fn write_blob_with_mode(path: &Path, mode: TreeItemMode, content: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if let Ok(metadata) = fs::symlink_metadata(path)
+        && (metadata.file_type().is_symlink() || (mode == TreeItemMode::Link && metadata.is_file()))
+    {
+        fs::remove_file(path)?;
+    }
+    if mode == TreeItemMode::Link {
+        return write_symlink(path, content);
+    }
+    fs::write(path, content)?;
+    set_blob_permissions(path, mode)
+}
+
+/// Whether the file at `path` already has the file type and executable bit
+/// `mode` records. Always true for blobs on platforms without either.
+pub fn workdir_mode_matches(path: &Path, mode: TreeItemMode) -> bool {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let is_link = mode == TreeItemMode::Link;
+        if metadata.file_type().is_symlink() != is_link {
+            return false;
+        }
+        is_link
+            || (metadata.permissions().mode() & 0o111 != 0)
+                == (mode == TreeItemMode::BlobExecutable)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (metadata, mode);
+        true
+    }
+}
+
+#[cfg(unix)]
+fn set_blob_permissions(path: &Path, mode: TreeItemMode) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bits = match mode {
+        TreeItemMode::BlobExecutable => 0o755,
+        _ => 0o644,
+    };
+    fs::set_permissions(path, fs::Permissions::from_mode(bits))
+}
+
+#[cfg(not(unix))]
+fn set_blob_permissions(_path: &Path, _mode: TreeItemMode) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_symlink(path: &Path, target: &[u8]) -> io::Result<()> {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    std::os::unix::fs::symlink(OsStr::from_bytes(target), path)
+}
+
+#[cfg(not(unix))]
+fn write_symlink(path: &Path, target: &[u8]) -> io::Result<()> {
+    fs::write(path, target)
+}
@@ -26,8 +26,8 @@ use libra::internal::ai::{
         handlers::{
             ApplyPatchHandler, GrepFilesHandler, ListDirHandler, McpBridgeHandler, PlanHandler,
             ReadFileHandler, SearchFilesHandler, ShellHandler, SubmitIntentDraftHandler,
-            SubmitPlanDraftHandler, SubmitTaskCompleteHandler, WebSearchHandler,
-            register_semantic_handlers,
+            SubmitPlanDraftHandler, SubmitTaskCompleteHandler, TreeSitterQueryHandler,
+            WatchFileHandler, WebSearchHandler, register_semantic_handlers,
         },
     },
 };
@@ -59,6 +59,8 @@ fn build_tui_like_registry() -> Arc<ToolRegistry> {
         .register("list_dir", Arc::new(ListDirHandler))
         .register("grep_files", Arc::new(GrepFilesHandler))
         .register("search_files", Arc::new(SearchFilesHandler))
+        .register("watch_file", Arc::new(WatchFileHandler))
+        .register("tree_sitter_query", Arc::new(TreeSitterQueryHandler))
         .register("web_search", Arc::new(WebSearchHandler))
         .register("apply_patch", Arc::new(ApplyPatchHandler))
         .register("shell", Arc::new(ShellHandler))
@@ -134,6 +136,8 @@ fn review_context_filter_drops_mutating_tools() {
         "list_dir",
         "grep_files",
         "search_files",
+        "watch_file",
+        "tree_sitter_query",
         "web_search",
     ] {
         assert!(
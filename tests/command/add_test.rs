@@ -46,6 +46,8 @@ async fn test_add_single_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -88,6 +90,8 @@ async fn test_add_dispatches_vcs_automation_history() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &libra::utils::output::OutputConfig::default(),
     )
@@ -135,6 +139,8 @@ async fn test_add_dry_run_does_not_dispatch_vcs_automation_history() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &libra::utils::output::OutputConfig::default(),
     )
@@ -183,6 +189,8 @@ async fn test_add_multiple_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -241,6 +249,8 @@ async fn test_add_all_flag() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -303,6 +313,8 @@ async fn test_add_update_flag() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -336,6 +348,8 @@ async fn test_add_update_flag() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -407,6 +421,8 @@ async fn test_add_with_ignore_patterns() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -493,6 +509,8 @@ async fn test_add_force_tracks_ignored_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -519,6 +537,8 @@ async fn test_add_force_tracks_ignored_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -555,6 +575,8 @@ async fn test_add_force_tracks_ignored_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -605,6 +627,8 @@ async fn test_add_force_dot_includes_ignored_directory() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -639,6 +663,8 @@ async fn test_add_force_dot_includes_ignored_directory() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -683,6 +709,8 @@ async fn test_add_dry_run() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -722,6 +750,8 @@ async fn test_add_without_path_should_error() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -760,6 +790,8 @@ async fn test_add_nonexistent_file_should_error() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -802,6 +834,8 @@ async fn test_add_duplicate_file_should_not_duplicate_index() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
 
@@ -849,6 +883,8 @@ async fn test_add_empty_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -891,6 +927,8 @@ async fn test_add_sub_directory_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -934,6 +972,8 @@ async fn test_add_pathspec_from_file_newline_stages_listed_paths() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -977,6 +1017,8 @@ async fn test_add_pathspec_from_file_nul_stages_listed_paths() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1296,3 +1338,192 @@ async fn test_add_ignore_missing_triggers_warning_exit() {
     );
     assert!(clean.status.success(), "no warning -> success exit");
 }
+
+/// Scenario: `.gitattributes` `text`/`eol` normalize CRLF to LF in the staged
+/// blob, `-text` keeps the bytes verbatim, and a subdirectory
+/// `.gitattributes` overrides the root one.
+#[test]
+fn test_add_applies_gitattributes_line_ending_normalization() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(
+        p.join(".gitattributes"),
+        "*.txt text\n*.bat eol=crlf\n*.raw -text\n",
+    )
+    .unwrap();
+    fs::create_dir_all(p.join("vendor")).unwrap();
+    fs::write(p.join("vendor/.gitattributes"), "*.txt -text\n").unwrap();
+    for file in ["notes.txt", "run.bat", "keep.raw", "vendor/lib.txt"] {
+        fs::write(p.join(file), "one\r\ntwo\r\n").unwrap();
+    }
+    let add = run_libra_command(
+        &["add", "notes.txt", "run.bat", "keep.raw", "vendor/lib.txt"],
+        p,
+    );
+    assert_cli_success(&add, "add files with attributes");
+
+    let staged = |file: &str| -> String {
+        let ls = run_libra_command(&["ls-files", "-s", file], p);
+        assert_cli_success(&ls, "ls-files -s");
+        let stdout = String::from_utf8_lossy(&ls.stdout).to_string();
+        let hash = stdout
+            .split_whitespace()
+            .nth(1)
+            .expect("hash column")
+            .to_string();
+        let cat = run_libra_command(&["cat-file", "-p", &hash], p);
+        assert_cli_success(&cat, "cat-file -p");
+        String::from_utf8_lossy(&cat.stdout).to_string()
+    };
+    assert_eq!(staged("notes.txt"), "one\ntwo\n");
+    assert_eq!(staged("run.bat"), "one\ntwo\n");
+    assert_eq!(staged("keep.raw"), "one\r\ntwo\r\n");
+    assert_eq!(staged("vendor/lib.txt"), "one\r\ntwo\r\n");
+}
+
+/// Read the staged blob for `file` via `ls-files -s` + `cat-file -p`.
+fn staged_blob(repo: &Path, file: &str) -> Vec<u8> {
+    let ls = run_libra_command(&["ls-files", "-s", file], repo);
+    assert_cli_success(&ls, "ls-files -s");
+    let stdout = String::from_utf8_lossy(&ls.stdout).to_string();
+    let hash = stdout.split_whitespace().nth(1).expect("hash column");
+    let cat = run_libra_command(&["cat-file", "-p", hash], repo);
+    assert_cli_success(&cat, "cat-file -p");
+    cat.stdout
+}
+
+/// Scenario: `*.txt text eol=lf` stores LF for a CRLF file and status stays
+/// clean afterwards, while `*.bin -text` keeps CR bytes and NULs byte-exact.
+#[test]
+fn test_add_eol_lf_normalizes_crlf_and_keeps_binary_exact() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join(".gitattributes"), "*.txt text eol=lf\n*.bin -text\n").unwrap();
+    fs::write(p.join("notes.txt"), "one\r\ntwo\r\n").unwrap();
+    let binary = b"\x00\r\n\x01\r\r\n\xff".to_vec();
+    fs::write(p.join("data.bin"), &binary).unwrap();
+
+    let add = run_libra_command(&["add", ".gitattributes", "notes.txt", "data.bin"], p);
+    assert_cli_success(&add, "add files with attributes");
+    assert_eq!(staged_blob(p, "notes.txt"), b"one\ntwo\n");
+    assert_eq!(staged_blob(p, "data.bin"), binary);
+
+    let status = run_libra_command(&["status", "--porcelain"], p);
+    assert_cli_success(&status, "status after normalizing add");
+    let porcelain = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        !porcelain.lines().any(|line| line.starts_with(" M")),
+        "a CRLF file normalized on add must not show as modified: {porcelain}"
+    );
+}
+
+/// Scenario: `filter=lfs` in `.gitattributes` (not only `.libra_attributes`)
+/// stages an LFS pointer instead of the file content.
+#[test]
+fn test_add_gitattributes_lfs_filter_stages_pointer() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(
+        p.join(".gitattributes"),
+        "*.psd filter=lfs diff=lfs merge=lfs -text\n",
+    )
+    .unwrap();
+    fs::write(p.join("art.psd"), "large binary payload").unwrap();
+
+    let add = run_libra_command(&["add", "art.psd"], p);
+    assert_cli_success(&add, "add LFS-filtered file");
+    let staged = String::from_utf8(staged_blob(p, "art.psd")).expect("pointer is text");
+    assert!(
+        staged.starts_with("version https://git-lfs.github.com/spec/v1"),
+        "expected an LFS pointer, got: {staged}"
+    );
+}
+
+/// Scenario: `add -p` on a file with two distant changes offers two hunks;
+/// accepting the first and declining the second stages only the first change,
+/// leaving the second one unstaged in the working tree.
+#[test]
+fn test_add_patch_stages_only_accepted_hunk() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let base: Vec<String> = (1..=20).map(|n| format!("line {n}\n")).collect();
+    fs::write(p.join("f.txt"), base.concat()).unwrap();
+    assert_cli_success(&run_libra_command(&["add", "f.txt"], p), "add base");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "base", "--no-verify"], p),
+        "commit base",
+    );
+
+    let mut edited = base.clone();
+    edited[1] = "line two\n".to_string();
+    edited[17] = "line eighteen\n".to_string();
+    fs::write(p.join("f.txt"), edited.concat()).unwrap();
+
+    let out = run_libra_command_with_stdin(&["add", "-p", "f.txt"], p, "y\nn\n");
+    assert_cli_success(&out, "add -p");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("(1/2) Stage this hunk"), "{stdout}");
+    assert!(stdout.contains("(2/2) Stage this hunk"), "{stdout}");
+
+    let mut expected = base.clone();
+    expected[1] = "line two\n".to_string();
+    assert_eq!(staged_blob(p, "f.txt"), expected.concat().into_bytes());
+    assert_eq!(
+        fs::read_to_string(p.join("f.txt")).unwrap(),
+        edited.concat()
+    );
+
+    let status = run_libra_command(&["status", "--porcelain"], p);
+    assert_cli_success(&status, "status after add -p");
+    let porcelain = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        porcelain.lines().any(|line| line == "MM f.txt"),
+        "the declined hunk must remain unstaged: {porcelain}"
+    );
+}
+
+/// Scenario: `add -N` records an intent-to-add entry (the empty blob) for a new
+/// file without staging its content; status reports it as a new file with
+/// nothing staged (` A`), and a later plain `add` stages the real content.
+#[test]
+fn test_add_intent_to_add_records_placeholder_entry() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join("new.txt"), "fresh content\n").unwrap();
+
+    let out = run_libra_command(&["add", "-N", "new.txt"], p);
+    assert_cli_success(&out, "add -N");
+
+    let ls = run_libra_command(&["ls-files", "-s", "new.txt"], p);
+    assert_cli_success(&ls, "ls-files -s");
+    let entry = String::from_utf8_lossy(&ls.stdout).to_string();
+    assert!(
+        entry.contains("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        "intent-to-add entry must be the empty blob: {entry}"
+    );
+
+    let status = run_libra_command(&["status", "--porcelain"], p);
+    assert_cli_success(&status, "status --porcelain");
+    let porcelain = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        porcelain.lines().any(|line| line == " A new.txt"),
+        "intent-to-add must be added with nothing staged: {porcelain}"
+    );
+    let long = run_libra_command(&["status"], p);
+    let long = String::from_utf8_lossy(&long.stdout);
+    let unstaged = long
+        .split("Changes not staged for commit:")
+        .nth(1)
+        .expect("unstaged section");
+    assert!(unstaged.contains("new file:"), "{long}");
+    assert!(!long.contains("Changes to be committed:"), "{long}");
+
+    assert_cli_success(&run_libra_command(&["add", "new.txt"], p), "add");
+    assert_eq!(staged_blob(p, "new.txt"), b"fresh content\n");
+    let status = run_libra_command(&["status", "--porcelain"], p);
+    let porcelain = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        porcelain.lines().any(|line| line == "A  new.txt"),
+        "a real add replaces the placeholder: {porcelain}"
+    );
+}
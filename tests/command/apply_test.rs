@@ -1,4 +1,4 @@
-//! Integration tests for `libra apply --check`.
+//! Integration tests for `libra apply`.
 //!
 //! Layer: L1 (deterministic; tempdir + isolated HOME, no network).
 
@@ -6,7 +6,10 @@ use std::fs;
 
 use tempfile::{TempDir, tempdir};
 
-use super::{parse_json_stdout, run_libra_command, run_libra_command_with_stdin};
+use super::{
+    assert_cli_success, create_committed_repo_via_cli, parse_json_stdout, run_libra_command,
+    run_libra_command_with_stdin,
+};
 
 fn init_repo() -> TempDir {
     let repo = tempdir().unwrap();
@@ -169,18 +172,6 @@ fn apply_check_clean_deletion_exits_0() {
     );
 }
 
-#[test]
-fn apply_requires_check_flag() {
-    let repo = init_repo();
-    fs::write(repo.path().join("p.diff"), MODIFY_PATCH).unwrap();
-    let out = run_libra_command(&["apply", "p.diff"], repo.path());
-    assert_eq!(
-        out.status.code(),
-        Some(128),
-        "without --check this version errors out"
-    );
-}
-
 #[test]
 fn apply_check_malformed_patch_is_an_error() {
     let repo = init_repo();
@@ -208,3 +199,144 @@ fn apply_outside_repository_is_an_error() {
     let out = run_libra_command(&["apply", "--check", "p.diff"], dir.path());
     assert_eq!(out.status.code(), Some(128));
 }
+
+/// `libra diff` output for a one-line change to `tracked.txt`, with the file
+/// then restored so the patch can be applied to the committed content.
+fn generated_patch(repo: &TempDir) -> String {
+    fs::write(repo.path().join("tracked.txt"), "tracked\nmore\n").unwrap();
+    let out = run_libra_command(&["diff"], repo.path());
+    assert_cli_success(&out, "diff");
+    let patch = String::from_utf8(out.stdout).unwrap();
+    assert!(patch.contains("+more"), "unexpected diff: {patch}");
+    assert_cli_success(
+        &run_libra_command(&["restore", "tracked.txt"], repo.path()),
+        "restore",
+    );
+    patch
+}
+
+#[test]
+fn apply_generated_diff_updates_worktree_only() {
+    let repo = create_committed_repo_via_cli();
+    let patch = generated_patch(&repo);
+    fs::write(repo.path().join("p.diff"), patch).unwrap();
+
+    let out = run_libra_command(&["apply", "p.diff"], repo.path());
+    assert_cli_success(&out, "apply");
+    assert_eq!(
+        fs::read_to_string(repo.path().join("tracked.txt")).unwrap(),
+        "tracked\nmore\n"
+    );
+    let out = run_libra_command(&["status", "--porcelain"], repo.path());
+    assert_cli_success(&out, "status");
+    let status = String::from_utf8_lossy(&out.stdout);
+    assert!(status.contains(" M tracked.txt"), "status: {status}");
+}
+
+#[test]
+fn apply_index_stages_the_result() {
+    let repo = create_committed_repo_via_cli();
+    let patch = generated_patch(&repo);
+    fs::write(repo.path().join("p.diff"), patch).unwrap();
+
+    let out = run_libra_command(&["apply", "--index", "p.diff"], repo.path());
+    assert_cli_success(&out, "apply --index");
+    let out = run_libra_command(&["status", "--porcelain"], repo.path());
+    assert_cli_success(&out, "status");
+    let status = String::from_utf8_lossy(&out.stdout);
+    assert!(status.contains("M  tracked.txt"), "status: {status}");
+}
+
+#[test]
+fn apply_index_rejects_file_that_differs_from_index() {
+    let repo = create_committed_repo_via_cli();
+    let patch = "--- a/tracked.txt\n+++ b/tracked.txt\n@@ -1 +1,2 @@\n tracked\n+more\n";
+    fs::write(repo.path().join("p.diff"), patch).unwrap();
+    // Same first line, so the patch itself applies, but the file is dirty.
+    fs::write(repo.path().join("tracked.txt"), "tracked\n\n").unwrap();
+
+    let out = run_libra_command(&["apply", "--index", "p.diff"], repo.path());
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("tracked.txt: does not match index"),
+        "stderr: {stderr}"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("tracked.txt")).unwrap(),
+        "tracked\n\n"
+    );
+}
+
+#[test]
+fn apply_reverse_undoes_a_patch() {
+    let repo = init_repo();
+    fs::write(repo.path().join("f.txt"), "a\nB\nc\n").unwrap();
+    fs::write(repo.path().join("p.diff"), MODIFY_PATCH).unwrap();
+
+    let out = run_libra_command(&["apply", "-R", "p.diff"], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "reverse apply: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("f.txt")).unwrap(),
+        "a\nb\nc\n"
+    );
+
+    // Reversing a new-file patch deletes the file.
+    let patch = "--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1,2 @@\n+hello\n+world\n";
+    fs::write(repo.path().join("new.diff"), patch).unwrap();
+    let out = run_libra_command(&["apply", "new.diff"], repo.path());
+    assert_eq!(out.status.code(), Some(0));
+    assert!(repo.path().join("new.txt").is_file());
+    let out = run_libra_command(&["apply", "--reverse", "new.diff"], repo.path());
+    assert_eq!(out.status.code(), Some(0));
+    assert!(!repo.path().join("new.txt").exists());
+}
+
+#[test]
+fn apply_check_reports_why_a_patch_would_fail() {
+    let repo = init_repo();
+    fs::write(repo.path().join("f.txt"), "a\nB\nc\n").unwrap();
+    fs::write(repo.path().join("p.diff"), MODIFY_PATCH).unwrap();
+
+    // Already applied: the forward patch fails, its reverse applies.
+    let out = run_libra_command(&["apply", "--check", "p.diff"], repo.path());
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("patch failed: f.txt"), "stderr: {stderr}");
+    let out = run_libra_command(&["apply", "--check", "-R", "p.diff"], repo.path());
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(repo.path().join("f.txt")).unwrap(),
+        "a\nB\nc\n"
+    );
+}
+
+#[test]
+fn apply_failure_in_one_file_writes_nothing() {
+    let repo = init_repo();
+    fs::write(repo.path().join("f.txt"), "a\nb\nc\n").unwrap();
+    fs::write(repo.path().join("g.txt"), "x\ny\nz\n").unwrap();
+    let patch =
+        format!("{MODIFY_PATCH}--- a/g.txt\n+++ b/g.txt\n@@ -1,3 +1,3 @@\n 1\n-2\n+TWO\n 3\n");
+    fs::write(repo.path().join("p.diff"), patch).unwrap();
+
+    let out = run_libra_command(&["--json", "apply", "p.diff"], repo.path());
+    assert_eq!(out.status.code(), Some(1));
+    let json = parse_json_stdout(&out);
+    assert_eq!(json["data"]["applies"].as_bool(), Some(false));
+    assert_eq!(json["data"]["applied"].as_bool(), Some(false));
+    assert_eq!(
+        json["data"]["errors"][0].as_str(),
+        Some("patch failed: g.txt")
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("f.txt")).unwrap(),
+        "a\nb\nc\n",
+        "f.txt applies on its own but must not be written"
+    );
+}
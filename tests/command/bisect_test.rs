@@ -103,6 +103,8 @@ async fn create_linear_commits(count: usize) -> Vec<String> {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         })
         .await;
         commit::execute(commit::CommitArgs {
@@ -607,6 +607,8 @@ async fn prepare_history() -> (ObjectHash, ObjectHash) {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -637,6 +639,8 @@ async fn prepare_history() -> (ObjectHash, ObjectHash) {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -667,6 +671,8 @@ async fn commit_foo(message: &str) -> ObjectHash {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -757,6 +763,8 @@ async fn test_blame_empty_file_returns_empty_result() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -811,6 +819,9 @@ async fn blame_runs_with_sha1() {
         root: false,
         show_name: false,
         ignore_whitespace: false,
+        copies: 0,
+        copy_threshold: 40,
+        skip_copy_detection: false,
     })
     .await;
 }
@@ -840,6 +851,9 @@ async fn blame_runs_with_sha256() {
         root: false,
         show_name: false,
         ignore_whitespace: false,
+        copies: 0,
+        copy_threshold: 40,
+        skip_copy_detection: false,
     })
     .await;
 }
@@ -951,6 +965,9 @@ async fn blame_root_flag_is_accepted_noop() {
         root: true,
         show_name: false,
         ignore_whitespace: false,
+        copies: 0,
+        copy_threshold: 40,
+        skip_copy_detection: false,
     })
     .await;
 }
@@ -1021,3 +1038,126 @@ fn test_blame_show_name_flag_prints_filename() {
         "-f does not change porcelain output"
     );
 }
+
+fn commit_all(repo: &std::path::Path, message: &str) -> String {
+    assert_cli_success(&run_libra_command(&["add", "."], repo), "add .");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", message, "--no-verify"], repo),
+        "commit",
+    );
+    let out = run_libra_command(&["rev-parse", "HEAD"], repo);
+    assert_cli_success(&out, "rev-parse HEAD");
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `source.txt` holds two lines long enough to clear the default copy
+/// threshold; a later commit pastes them into a new `copy.txt` and, when
+/// `touch_source` is set, also edits `source.txt`. Returns the repo and the
+/// hashes of the commit that wrote the lines and the one that copied them.
+fn copied_lines_repo(touch_source: bool) -> (tempfile::TempDir, String, String) {
+    const SHARED: &str = "let greeting = compose_greeting(first_name, last_name);\n\
+                          send_message(recipient_address, greeting);\n";
+    let repo = create_committed_repo_via_cli();
+    fs::write(repo.path().join("source.txt"), SHARED).unwrap();
+    let original = commit_all(repo.path(), "write source");
+
+    fs::write(repo.path().join("copy.txt"), format!("{SHARED}done();\n")).unwrap();
+    if touch_source {
+        fs::write(repo.path().join("source.txt"), format!("{SHARED}log();\n")).unwrap();
+    }
+    let copying = commit_all(repo.path(), "copy lines");
+    (repo, original, copying)
+}
+
+fn blame_hashes(repo: &std::path::Path, args: &[&str]) -> Vec<(String, Option<String>)> {
+    let mut full = vec!["--json", "blame"];
+    full.extend_from_slice(args);
+    let out = run_libra_command(&full, repo);
+    assert_cli_success(&out, "--json blame");
+    parse_json_stdout(&out)["data"]["lines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| {
+            (
+                line["hash"].as_str().unwrap().to_string(),
+                line["copied_from"].as_str().map(str::to_string),
+            )
+        })
+        .collect()
+}
+
+/// Scenario: `-C` traces lines pasted from a file changed in the same commit
+/// back to the commit that wrote them, marking them `C` with the source file;
+/// `--skip-copy-detection` restores the plain attribution.
+#[test]
+fn test_blame_copy_detection_follows_lines_from_modified_file() {
+    let (repo, original, copying) = copied_lines_repo(true);
+
+    let copied = Some("source.txt".to_string());
+    assert_eq!(
+        blame_hashes(repo.path(), &["-C", "copy.txt"]),
+        [
+            (original.clone(), copied.clone()),
+            (original.clone(), copied),
+            (copying.clone(), None),
+        ]
+    );
+    assert!(
+        blame_hashes(repo.path(), &["copy.txt"])
+            .iter()
+            .all(|(hash, from)| *hash == copying && from.is_none())
+    );
+    assert!(
+        blame_hashes(repo.path(), &["-C", "--skip-copy-detection", "copy.txt"])
+            .iter()
+            .all(|(hash, from)| *hash == copying && from.is_none())
+    );
+
+    let out = run_libra_command(&["blame", "-C", "copy.txt"], repo.path());
+    assert_cli_success(&out, "blame -C");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(
+        lines[0].starts_with(&format!("{} C source.txt (", &original[..8])),
+        "copied line is marked: {stdout}"
+    );
+    assert!(
+        lines[2].starts_with(&format!("{}   copy.txt   (", &copying[..8])),
+        "own line keeps the blamed file: {stdout}"
+    );
+
+    let out = run_libra_command(&["blame", "--porcelain", "-C", "copy.txt"], repo.path());
+    assert_cli_success(&out, "blame --porcelain -C");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("filename source.txt\n"), "{stdout}");
+    assert!(stdout.contains("filename copy.txt\n"), "{stdout}");
+}
+
+/// Scenario: an unchanged source file is only searched from `-CC` (the commit
+/// creates the blamed file), and a block scoring below `--copy-threshold` is
+/// not treated as a copy.
+#[test]
+fn test_blame_copy_detection_levels_and_threshold() {
+    let (repo, original, copying) = copied_lines_repo(false);
+
+    assert!(
+        blame_hashes(repo.path(), &["-C", "copy.txt"])
+            .iter()
+            .all(|(hash, _)| *hash == copying),
+        "-C only searches files modified by the copying commit"
+    );
+    for flags in [&["-CC"][..], &["-C", "-C", "-C"][..]] {
+        let mut args = flags.to_vec();
+        args.push("copy.txt");
+        let lines = blame_hashes(repo.path(), &args);
+        assert_eq!(lines[0].0, original, "{flags:?}");
+        assert_eq!(lines[1].1.as_deref(), Some("source.txt"), "{flags:?}");
+    }
+    assert!(
+        blame_hashes(repo.path(), &["-CC", "--copy-threshold", "200", "copy.txt"])
+            .iter()
+            .all(|(hash, _)| *hash == copying),
+        "a block below the threshold stays with the copying commit"
+    );
+}
@@ -316,6 +316,174 @@ fn test_branch_unset_upstream_clears_current_branch_tracking() {
     );
 }
 
+/// Scenario: `branch -m <new>` on the checked-out branch must move HEAD,
+/// the `refs/heads/<name>` reflog, and the upstream config to the new name.
+#[test]
+fn test_branch_move_current_branch_carries_reflog_and_upstream() {
+    let repo = create_committed_repo_via_cli();
+    let remote_add = run_libra_command(
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://example.invalid/repo.git",
+        ],
+        repo.path(),
+    );
+    assert_cli_success(&remote_add, "remote add origin");
+    let set = run_libra_command(&["branch", "--set-upstream-to", "origin/main"], repo.path());
+    assert_cli_success(&set, "branch --set-upstream-to origin/main");
+
+    let rename = run_libra_command(&["branch", "-m", "trunk"], repo.path());
+    assert_cli_success(&rename, "branch -m trunk");
+
+    let current = run_libra_command(&["branch", "--show-current"], repo.path());
+    assert_cli_success(&current, "branch --show-current");
+    assert_eq!(String::from_utf8_lossy(&current.stdout).trim(), "trunk");
+
+    let reflog = run_libra_command(&["reflog", "show", "trunk"], repo.path());
+    assert_cli_success(&reflog, "reflog show trunk");
+    let stdout = String::from_utf8_lossy(&reflog.stdout);
+    assert!(
+        stdout.contains("base"),
+        "renamed branch should keep its reflog: {stdout}"
+    );
+
+    let remote = run_libra_command(&["config", "get", "branch.trunk.remote"], repo.path());
+    assert_cli_success(&remote, "config get branch.trunk.remote");
+    assert_eq!(String::from_utf8_lossy(&remote.stdout).trim(), "origin");
+    let old_remote = run_libra_command(&["config", "get", "branch.main.remote"], repo.path());
+    assert!(
+        !old_remote.status.success(),
+        "branch.main.remote should move with the rename"
+    );
+}
+
+/// Scenario: `branch -m` onto an existing branch fails without `-M` and
+/// succeeds with it, leaving only the destination name behind.
+#[test]
+fn test_branch_move_force_overwrites_existing_destination() {
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["branch", "topic"], repo.path()),
+        "branch topic",
+    );
+    assert_cli_success(
+        &run_libra_command(&["branch", "other"], repo.path()),
+        "branch other",
+    );
+
+    let refused = run_libra_command(&["branch", "-m", "topic", "other"], repo.path());
+    assert!(
+        !refused.status.success(),
+        "rename onto an existing branch must fail without -M"
+    );
+
+    let forced = run_libra_command(&["branch", "-M", "topic", "other"], repo.path());
+    assert_cli_success(&forced, "branch -M topic other");
+
+    let list = run_libra_command(&["branch", "--list"], repo.path());
+    assert_cli_success(&list, "branch --list");
+    let stdout = String::from_utf8_lossy(&list.stdout);
+    assert!(
+        stdout.contains("other"),
+        "destination should exist: {stdout}"
+    );
+    assert!(
+        !stdout.contains("topic"),
+        "source should be gone after -M: {stdout}"
+    );
+}
+
+/// Scenario: `branch -c <old> <new>` keeps the source, creates the copy at the
+/// same tip, and duplicates the source's reflog.
+#[test]
+fn test_branch_copy_preserves_tip_and_reflog() {
+    let repo = create_committed_repo_via_cli();
+    let copy = run_libra_command(&["branch", "-c", "main", "backup"], repo.path());
+    assert_cli_success(&copy, "branch -c main backup");
+
+    let main_tip = run_libra_command(&["rev-parse", "main"], repo.path());
+    assert_cli_success(&main_tip, "rev-parse main");
+    let backup_tip = run_libra_command(&["rev-parse", "backup"], repo.path());
+    assert_cli_success(&backup_tip, "rev-parse backup");
+    assert_eq!(
+        String::from_utf8_lossy(&main_tip.stdout).trim(),
+        String::from_utf8_lossy(&backup_tip.stdout).trim()
+    );
+
+    let current = run_libra_command(&["branch", "--show-current"], repo.path());
+    assert_eq!(String::from_utf8_lossy(&current.stdout).trim(), "main");
+
+    let reflog = run_libra_command(&["reflog", "show", "backup"], repo.path());
+    assert_cli_success(&reflog, "reflog show backup");
+    let stdout = String::from_utf8_lossy(&reflog.stdout);
+    assert!(
+        stdout.contains("base"),
+        "copied branch should carry the source reflog: {stdout}"
+    );
+}
+
+/// Scenario: `branch -C <old> <new>` over an existing destination replaces the
+/// destination's `branch.<new>.*` config instead of leaving a stale upstream
+/// behind when the source has none.
+#[test]
+fn test_branch_force_copy_clears_destination_upstream() {
+    let repo = create_committed_repo_via_cli();
+    let create = run_libra_command(&["branch", "topic"], repo.path());
+    assert_cli_success(&create, "branch topic");
+    for (key, value) in [
+        ("branch.topic.remote", "origin"),
+        ("branch.topic.merge", "refs/heads/topic"),
+    ] {
+        let set = run_libra_command(&["config", key, value], repo.path());
+        assert_cli_success(&set, "config branch.topic.*");
+    }
+
+    let copy = run_libra_command(&["branch", "-C", "main", "topic"], repo.path());
+    assert_cli_success(&copy, "branch -C main topic");
+
+    for key in ["branch.topic.remote", "branch.topic.merge"] {
+        let get = run_libra_command(&["config", "get", key], repo.path());
+        assert!(
+            !get.status.success(),
+            "{key} should be cleared by -C: {}",
+            String::from_utf8_lossy(&get.stdout)
+        );
+    }
+}
+
+/// Scenario: `branch --set-upstream-to=<remote>/<branch>` persists the
+/// tracking pair as `branch.<name>.remote` and a fully-qualified
+/// `branch.<name>.merge`, which `pull`/`push` read as their default target.
+#[test]
+fn test_branch_set_upstream_writes_remote_and_merge_config() {
+    let repo = create_committed_repo_via_cli();
+    let remote_add = run_libra_command(
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://example.invalid/repo.git",
+        ],
+        repo.path(),
+    );
+    assert_cli_success(&remote_add, "remote add origin");
+
+    let set = run_libra_command(&["branch", "--set-upstream-to=origin/trunk"], repo.path());
+    assert_cli_success(&set, "branch --set-upstream-to=origin/trunk");
+
+    let remote = run_libra_command(&["config", "get", "branch.main.remote"], repo.path());
+    assert_cli_success(&remote, "config get branch.main.remote");
+    assert_eq!(String::from_utf8_lossy(&remote.stdout).trim(), "origin");
+    let merge = run_libra_command(&["config", "get", "branch.main.merge"], repo.path());
+    assert_cli_success(&merge, "config get branch.main.merge");
+    assert_eq!(
+        String::from_utf8_lossy(&merge.stdout).trim(),
+        "refs/heads/trunk"
+    );
+}
+
 #[test]
 fn test_branch_points_at_filters_exact_tip() {
     let repo = create_committed_repo_via_cli();
@@ -548,6 +716,7 @@ async fn test_branch() {
             edit_description: None,
             show_current: false,
             rename: vec![],
+            rename_force: vec![],
             copy: vec![],
             copy_force: vec![],
             remotes: false,
@@ -596,6 +765,7 @@ async fn test_branch() {
             edit_description: None,
             show_current: false,
             rename: vec![],
+            rename_force: vec![],
             copy: vec![],
             copy_force: vec![],
             remotes: false,
@@ -634,6 +804,7 @@ async fn test_branch() {
         edit_description: None,
         show_current: true,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -700,6 +871,7 @@ async fn test_create_branch_from_remote() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -767,6 +939,7 @@ async fn test_create_branch_from_remote_tracking_ref() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -990,6 +1163,7 @@ async fn test_branch_rename() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1027,6 +1201,7 @@ async fn test_branch_rename() {
         edit_description: None,
         show_current: false,
         rename: vec!["old_name".to_string(), "new_name".to_string()],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1133,6 +1308,7 @@ async fn test_rename_current_branch() {
         edit_description: None,
         show_current: false,
         rename: vec![feature_new.clone()],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1214,6 +1390,7 @@ async fn test_rename_to_existing_branch() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1243,6 +1420,7 @@ async fn test_rename_to_existing_branch() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1273,6 +1451,7 @@ async fn test_rename_to_existing_branch() {
         edit_description: None,
         show_current: false,
         rename: vec!["branch1".to_string(), "branch2".to_string()],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1347,6 +1526,7 @@ async fn test_list_all_branches() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1387,6 +1567,7 @@ async fn test_list_all_branches() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1466,6 +1647,7 @@ async fn test_branch_delete_safe() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1542,6 +1724,7 @@ async fn test_branch_delete_safe() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1619,6 +1802,7 @@ async fn test_branch_delete_safe() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -1717,6 +1901,7 @@ async fn test_branch_contains_commit_filter() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -2065,6 +2250,65 @@ fn branch_merged_and_no_merged_filters() {
     assert!(!n.contains("old"), "old IS merged into main: {n:?}");
 }
 
+/// `--merged main` lists exactly the topic branches whose work is already in
+/// `main`, and `-d` agrees with it: merged topics delete, the rest refuse.
+#[test]
+fn branch_merged_topics_match_safe_delete() {
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let commit_on = |branch: &str, file: &str| {
+        assert_cli_success(&run_libra_command(&["switch", branch], p), "switch");
+        std::fs::write(p.join(file), format!("{file}\n")).unwrap();
+        assert_cli_success(&run_libra_command(&["add", file], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", file, "--no-verify"], p),
+            "commit",
+        );
+    };
+    let listed = |filter: &str| -> Vec<String> {
+        let out = run_libra_command(&["branch", filter, "main"], p);
+        assert_cli_success(&out, filter);
+        let mut names: Vec<String> = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|line| line.trim_start_matches('*').trim().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    for topic in ["topic-a", "topic-b"] {
+        assert_cli_success(&run_libra_command(&["branch", topic], p), topic);
+    }
+    commit_on("topic-a", "a.txt");
+    commit_on("topic-b", "b.txt");
+    commit_on("main", "m.txt");
+    assert_cli_success(
+        &run_libra_command(&["merge", "topic-a", "--no-ff", "-m", "merge topic-a"], p),
+        "merge topic-a",
+    );
+
+    assert_eq!(listed("--merged"), ["main", "topic-a"]);
+    assert_eq!(listed("--no-merged"), ["topic-b"]);
+
+    let refused = run_libra_command(&["branch", "-d", "topic-b"], p);
+    assert!(
+        !refused.status.success(),
+        "unmerged topic-b must not delete"
+    );
+    assert!(
+        String::from_utf8_lossy(&refused.stderr).contains("not fully merged"),
+        "stderr: {}",
+        String::from_utf8_lossy(&refused.stderr)
+    );
+    assert_cli_success(
+        &run_libra_command(&["branch", "-d", "topic-a"], p),
+        "branch -d topic-a",
+    );
+    assert_eq!(listed("--merged"), ["main"]);
+}
+
 #[test]
 fn branch_sort_orders_list_by_key() {
     use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
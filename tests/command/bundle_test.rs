@@ -113,3 +113,108 @@ fn bundle_outside_repository_is_an_error() {
     );
     assert_eq!(result.status.code(), Some(128));
 }
+
+#[test]
+fn bundle_create_all_carries_branches_and_annotated_tags() {
+    let repo = create_committed_repo_via_cli();
+    for args in [
+        &["branch", "topic"][..],
+        &["tag", "light"][..],
+        &["tag", "-m", "release", "v1"][..],
+    ] {
+        let result = run_libra_command(args, repo.path());
+        assert_eq!(
+            result.status.code(),
+            Some(0),
+            "{args:?}: {}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+    }
+    let path = repo.path().join("all.bundle");
+    let result = run_libra_command(
+        &["bundle", "create", path.to_str().unwrap(), "--all"],
+        repo.path(),
+    );
+    assert_eq!(
+        result.status.code(),
+        Some(0),
+        "bundle create --all failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let heads = run_libra_command(
+        &["bundle", "list-heads", path.to_str().unwrap()],
+        repo.path(),
+    );
+    let refs: Vec<String> = String::from_utf8_lossy(&heads.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' ').map(|(_, name)| name.to_string()))
+        .collect();
+    assert_eq!(
+        refs,
+        [
+            "refs/heads/main",
+            "refs/heads/topic",
+            "refs/tags/light",
+            "refs/tags/v1"
+        ]
+    );
+}
+
+#[test]
+fn bundle_unbundle_stores_objects_in_another_repository() {
+    let repo = create_committed_repo_via_cli();
+    let path = repo.path().join("out.bundle");
+    assert_eq!(
+        run_libra_command(
+            &["bundle", "create", path.to_str().unwrap(), "main"],
+            repo.path()
+        )
+        .status
+        .code(),
+        Some(0)
+    );
+
+    let target = tempdir().unwrap();
+    assert_eq!(
+        run_libra_command(&["init"], target.path()).status.code(),
+        Some(0)
+    );
+    let result = run_libra_command(
+        &["bundle", "unbundle", path.to_str().unwrap()],
+        target.path(),
+    );
+    assert_eq!(
+        result.status.code(),
+        Some(0),
+        "unbundle failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let (oid, name) = stdout.trim().split_once(' ').expect("head line");
+    assert_eq!(name, "refs/heads/main");
+
+    let kind = run_libra_command(&["cat-file", "-t", oid], target.path());
+    assert_eq!(String::from_utf8_lossy(&kind.stdout).trim(), "commit");
+    let result = run_libra_command(&["update-ref", "refs/heads/main", oid], target.path());
+    assert_eq!(result.status.code(), Some(0));
+    let commit = run_libra_command(&["cat-file", "-p", "main"], target.path());
+    assert!(String::from_utf8_lossy(&commit.stdout).contains("\nbase"));
+}
+
+#[test]
+fn bundle_unbundle_rejects_a_corrupt_pack() {
+    let repo = create_committed_repo_via_cli();
+    let path = repo.path().join("out.bundle");
+    run_libra_command(
+        &["bundle", "create", path.to_str().unwrap(), "HEAD"],
+        repo.path(),
+    );
+    let mut bytes = fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    fs::write(&path, bytes).unwrap();
+
+    let result = run_libra_command(&["bundle", "unbundle", path.to_str().unwrap()], repo.path());
+    assert_eq!(result.status.code(), Some(1));
+}
@@ -1,10 +1,13 @@
-//! Integration tests for `libra cache info`.
+//! Integration tests for `libra cache info`, `cache dedup` and `cache verify-dedup`.
 //!
-//! Layer: L1 (deterministic; tempdir + isolated HOME, no network, no repo).
+//! Layer: L1 (deterministic; tempdir + isolated HOME, no network).
 
 use tempfile::tempdir;
 
-use super::{parse_json_stdout, run_libra_command_with_stdin_and_env};
+use super::{
+    init_repo_via_cli, parse_cli_error_stderr, parse_json_stdout,
+    run_libra_command_with_stdin_and_env,
+};
 
 /// With no `LIBRA_STORAGE_*` configured, `cache info` reports the local-only
 /// defaults (1 MiB threshold, 200 MiB LRU budget) and needs no repository.
@@ -22,6 +25,26 @@ fn cache_info_reports_local_defaults() {
         json["data"]["cache_size_bytes"].as_u64(),
         Some(200 * 1024 * 1024)
     );
+    assert_eq!(
+        json["data"]["object_cache_bytes"].as_u64(),
+        Some(64 * 1024 * 1024)
+    );
+}
+
+/// `LIBRA_OBJECT_CACHE_SIZE` sizes the in-memory object cache; `0` (disabled)
+/// is a valid value.
+#[test]
+fn cache_info_reflects_object_cache_size() {
+    let dir = tempdir().unwrap();
+    let result = run_libra_command_with_stdin_and_env(
+        &["--json", "cache", "info"],
+        dir.path(),
+        "",
+        &[("LIBRA_OBJECT_CACHE_SIZE", "0")],
+    );
+    assert_eq!(result.status.code(), Some(0));
+    let json = parse_json_stdout(&result);
+    assert_eq!(json["data"]["object_cache_bytes"].as_u64(), Some(0));
 }
 
 /// A durable tier (`r2`) plus custom threshold/cache-size env vars are reflected
@@ -142,3 +165,35 @@ fn cache_info_human_output_labels_fields() {
     assert!(stdout.contains("threshold:"), "should label threshold");
     assert!(stdout.contains("cache:"), "should label cache budget");
 }
+
+/// `cache dedup` and `cache verify-dedup` operate on a repository's objects, so
+/// unlike `cache info` they fail outside one.
+#[test]
+fn cache_dedup_requires_a_repository() {
+    let dir = tempdir().unwrap();
+    for subcommand in ["dedup", "verify-dedup"] {
+        let result =
+            run_libra_command_with_stdin_and_env(&["cache", subcommand], dir.path(), "", &[]);
+        assert!(!result.status.success(), "{subcommand} outside a repo");
+        let (_, report) = parse_cli_error_stderr(&result.stderr);
+        assert_eq!(report.error_code, "LBR-REPO-001", "{subcommand}");
+    }
+}
+
+/// A local-only repository has no durable tier to deduplicate against; both
+/// subcommands say so instead of reporting an empty run.
+#[test]
+fn cache_dedup_rejects_a_local_only_backend() {
+    let dir = tempdir().unwrap();
+    init_repo_via_cli(dir.path());
+    for subcommand in ["dedup", "verify-dedup"] {
+        let result =
+            run_libra_command_with_stdin_and_env(&["cache", subcommand], dir.path(), "", &[]);
+        assert!(
+            !result.status.success(),
+            "{subcommand} without a durable tier"
+        );
+        let (_, report) = parse_cli_error_stderr(&result.stderr);
+        assert_eq!(report.error_code, "LBR-UNSUPPORTED-001", "{subcommand}");
+    }
+}
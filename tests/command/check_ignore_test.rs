@@ -246,3 +246,42 @@ fn verbose_reports_the_last_matching_pattern_line() {
         "verbose reports the deciding (last) matching line"
     );
 }
+
+/// Nested `.libraignore` files: the nearest file that matches decides, and `-v`
+/// attributes the match to that file (with its own line numbers), while paths
+/// outside the nested directory still resolve to the root rules.
+#[test]
+fn verbose_attributes_nested_libraignore_rules() {
+    let repo = setup_repo();
+    fs::create_dir_all(repo.path().join("sub/deep")).expect("create sub/deep");
+    fs::write(
+        repo.path().join("sub/.libraignore"),
+        "# sub rules\n*.tmp\n!notes.log\n",
+    )
+    .expect("write sub/.libraignore");
+
+    let out = run_libra_command(
+        &["check-ignore", "-v", "sub/deep/a.tmp", "sub/b.log", "c.log"],
+        repo.path(),
+    );
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        stdout_of(&out),
+        "sub/.libraignore:2:*.tmp\tsub/deep/a.tmp\n\
+         .libraignore:1:*.log\tsub/b.log\n\
+         .libraignore:1:*.log\tc.log\n",
+        "each match is attributed to the .libraignore that decided it"
+    );
+
+    // A whitelist in the nested file overrides the root `*.log` rule.
+    let out = run_libra_command(&["check-ignore", "-v", "-n", "sub/notes.log"], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "whitelisted path is not ignored"
+    );
+    assert_eq!(
+        stdout_of(&out),
+        "sub/.libraignore:3:!notes.log\tsub/notes.log\n"
+    );
+}
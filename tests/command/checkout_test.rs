@@ -222,6 +222,8 @@ async fn checkout_restore_rejects_sha1_hash_in_sha256_repo() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &OutputConfig::default(),
     )
@@ -320,6 +322,8 @@ async fn test_checkout_new_branch_with_dirty_worktree_returns_error() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &OutputConfig::default(),
     )
@@ -362,6 +366,8 @@ async fn test_checkout_new_branch_with_dirty_worktree_returns_error() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &OutputConfig::default(),
     )
@@ -428,6 +434,8 @@ async fn test_checkout_current_branch_with_dirty_worktree_succeeds() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &OutputConfig::default(),
     )
@@ -517,6 +525,8 @@ async fn test_checkout_existing_branch_with_unstaged_dirty_worktree_returns_erro
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &OutputConfig::default(),
     )
@@ -704,6 +714,60 @@ fn test_checkout_force_still_refuses_untracked_overwrite() {
     );
 }
 
+#[cfg(unix)]
+#[test]
+fn test_checkout_preserves_executable_bit_across_branches() {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "tools"], repo.path()),
+        "switch -c tools",
+    );
+    let script = repo.path().join("bin/build.sh");
+    std::fs::create_dir_all(script.parent().unwrap()).unwrap();
+    std::fs::write(&script, "#!/bin/sh\necho build\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "bin/build.sh"], repo.path()),
+        "add bin/build.sh",
+    );
+    assert_cli_success(
+        &run_libra_command(
+            &["commit", "-m", "add build script", "--no-verify"],
+            repo.path(),
+        ),
+        "commit build script",
+    );
+
+    assert_cli_success(
+        &run_libra_command(&["checkout", "main"], repo.path()),
+        "checkout main",
+    );
+    assert!(!script.exists(), "main does not track the script");
+
+    assert_cli_success(
+        &run_libra_command(&["checkout", "tools"], repo.path()),
+        "checkout tools",
+    );
+    let mode = std::fs::metadata(&script).unwrap().permissions().mode();
+    assert_eq!(
+        mode & 0o777,
+        0o755,
+        "checkout should restore the executable bit"
+    );
+
+    let staged = run_libra_command(&["ls-files", "-s", "bin/build.sh"], repo.path());
+    assert_cli_success(&staged, "ls-files -s bin/build.sh");
+    assert!(
+        String::from_utf8_lossy(&staged.stdout).starts_with("100755 "),
+        "the index should record 100755 after checkout: {}",
+        String::from_utf8_lossy(&staged.stdout)
+    );
+}
+
 #[test]
 fn test_checkout_json_show_current_branch() {
     use super::{
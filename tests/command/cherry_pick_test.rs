@@ -59,6 +59,8 @@ async fn test_basic_cherry_pick() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -112,6 +114,8 @@ async fn test_basic_cherry_pick() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -153,6 +157,8 @@ async fn test_basic_cherry_pick() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -304,6 +310,8 @@ async fn test_cherry_pick_with_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -353,6 +361,8 @@ async fn test_cherry_pick_with_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -459,6 +469,8 @@ async fn test_cherry_pick_multiple_commits() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -509,6 +521,8 @@ async fn test_cherry_pick_multiple_commits() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -545,6 +559,8 @@ async fn test_cherry_pick_multiple_commits() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -811,6 +827,8 @@ async fn test_cherry_pick_sha256_hash_handling() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(commit::CommitArgs {
@@ -859,6 +877,8 @@ async fn test_cherry_pick_sha256_hash_handling() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(commit::CommitArgs {
@@ -1412,6 +1432,84 @@ fn cherry_pick_edit_no_tty_falls_back() {
     );
 }
 
+/// Configure `core.editor` as a script that runs `body` (a shell snippet with
+/// the message file in `$1`).
+fn set_script_editor(repo: &std::path::Path, body: &str) {
+    let editor = repo.join("fake-editor.sh");
+    fs::write(&editor, format!("#!/bin/sh\n{body}\n")).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    assert_cli_success(
+        &run_libra_command(
+            &["config", "set", "core.editor", editor.to_str().unwrap()],
+            repo,
+        ),
+        "config core.editor",
+    );
+}
+
+/// `-e -x` opens the configured editor (no TTY needed) on the message with the
+/// `(cherry picked from commit …)` line already appended; the saved buffer is
+/// committed with `#` lines stripped.
+#[test]
+fn cherry_pick_edit_opens_configured_editor_with_source_line() {
+    let (repo, oid) = repo_with_feature_commit("f.txt", "feat\n", "feature work");
+    let p = repo.path();
+    // Record what the editor was shown, then rewrite the subject.
+    set_script_editor(
+        p,
+        "cp \"$1\" seen.txt\nsed -i.bak 's/feature work/edited subject/' \"$1\"\necho '# dropped' >> \"$1\"",
+    );
+
+    let out = run_libra_command(&["cherry-pick", "-e", "-x", &oid], p);
+    assert_cli_success(&out, "cherry-pick -e -x");
+
+    let seen = fs::read_to_string(p.join("seen.txt")).unwrap();
+    assert!(seen.starts_with("feature work"), "{seen}");
+    assert!(
+        seen.contains(&format!("(cherry picked from commit {oid})")),
+        "-x line is in the editor buffer: {seen}"
+    );
+    let message = cp_head_message(p);
+    assert!(message.contains("\nedited subject"), "{message}");
+    assert!(!message.contains("feature work"), "{message}");
+    assert!(message.contains(&format!("(cherry picked from commit {oid})")));
+    assert!(!message.contains("# dropped"), "{message}");
+}
+
+/// An editor that exits abnormally aborts the pick before the index or
+/// worktree is touched.
+#[test]
+fn cherry_pick_edit_failing_editor_aborts_cleanly() {
+    let (repo, oid) = repo_with_feature_commit("f.txt", "feat\n", "feature work");
+    let p = repo.path();
+    let head = cp_rev_parse(p, "HEAD");
+    set_script_editor(p, "exit 1");
+
+    let out = run_libra_command(&["cherry-pick", "--edit", &oid], p);
+    assert_eq!(
+        out.status.code(),
+        Some(128),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("Error-Code: LBR-IO-001"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("failed to edit the cherry-pick message"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(cp_rev_parse(p, "HEAD"), head, "HEAD must not move");
+    assert!(!p.join("f.txt").exists(), "worktree left untouched");
+}
+
 /// `--machine` emits machine JSON (NDJSON) rather than suppressing stdout.
 #[test]
 fn cherry_pick_machine_emits_ndjson() {
@@ -113,6 +113,8 @@ async fn test_clean_force_keeps_tracked_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -180,6 +182,8 @@ async fn test_clean_force_respects_ignore_rules() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -225,6 +229,8 @@ async fn test_clean_force_multiple_untracked_with_tracked() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -539,6 +545,8 @@ async fn test_clean_d_flag_keeps_dirs_with_tracked_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -586,6 +594,8 @@ async fn test_clean_x_flag_removes_ignored_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -633,6 +643,8 @@ async fn test_clean_x_flag_removes_only_ignored_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -781,6 +793,8 @@ async fn test_clean_dx_removes_ignored_directories() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -974,3 +988,39 @@ fn test_clean_short_exclude_alias() {
         "keep.log excluded by -e: {names:?}"
     );
 }
+
+#[test]
+fn test_clean_dry_run_ignore_modes_partition_candidates() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join(".libraignore"), "*.log\n").unwrap();
+    let add = run_libra_command(&["add", ".libraignore"], p);
+    assert_cli_success(&add, "add .libraignore");
+    fs::write(p.join("debug.log"), "log").unwrap();
+    fs::write(p.join("scratch.txt"), "scratch").unwrap();
+
+    let candidates = |flags: &[&str]| -> Vec<String> {
+        let mut argv = vec!["clean", "-n", "--json"];
+        argv.extend_from_slice(flags);
+        let out = run_libra_command(&argv, p);
+        assert_cli_success(&out, "clean -n --json");
+        let json = parse_json_stdout(&out);
+        let mut names: Vec<String> = json["data"]["removed"]
+            .as_array()
+            .expect("removed array")
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    // Default respects ignore rules, -x adds ignored files, -X keeps only them.
+    assert_eq!(candidates(&[]), vec!["scratch.txt"]);
+    assert_eq!(candidates(&["-x"]), vec!["debug.log", "scratch.txt"]);
+    assert_eq!(candidates(&["-X"]), vec!["debug.log"]);
+
+    // Dry-run never touches the worktree.
+    assert!(p.join("debug.log").exists());
+    assert!(p.join("scratch.txt").exists());
+}
@@ -1395,6 +1395,165 @@ fn bare_clone_does_not_create_libraignore() {
     );
 }
 
+/// Add a `dev` branch (with `dev.txt`) and a `v1` tag on `main` to the remote
+/// built by [`create_remote_with_main`].
+fn add_dev_branch_and_tag(base: &Path, remote: &Path) {
+    let work = base.join("work");
+    assert!(run_git(&["tag", "v1"], &work).status.success());
+    assert!(run_git(&["checkout", "-b", "dev"], &work).status.success());
+    fs::write(work.join("dev.txt"), "dev\n").unwrap();
+    assert!(run_git(&["add", "dev.txt"], &work).status.success());
+    assert!(run_git(&["commit", "-m", "dev"], &work).status.success());
+    assert!(
+        run_git(&["push", "origin", "dev", "v1"], &work)
+            .status
+            .success()
+    );
+    assert!(
+        run_git(&["symbolic-ref", "HEAD", "refs/heads/main"], remote)
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn clone_branch_checks_out_non_default_branch() {
+    let temp = tempdir().unwrap();
+    let remote = create_remote_with_main(temp.path());
+    add_dev_branch_and_tag(temp.path(), &remote);
+    let dest = temp.path().join("dev-clone");
+
+    let output = run_libra(
+        &[
+            "clone",
+            "--branch",
+            "dev",
+            remote.to_str().unwrap(),
+            dest.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "clone --branch dev failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dest.join("dev.txt").exists());
+
+    let output = run_libra(&["rev-parse", "--abbrev-ref", "HEAD"], &dest);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "dev");
+}
+
+/// Scenario: `clone --single-branch` auto-follows only the tags that point
+/// into the branch it fetched; a tag on another branch is left behind.
+#[test]
+fn clone_single_branch_follows_only_tags_on_that_branch() {
+    let temp = tempdir().unwrap();
+    let remote = create_remote_with_main(temp.path());
+    add_dev_branch_and_tag(temp.path(), &remote);
+    let work = temp.path().join("work");
+    assert!(run_git(&["tag", "dev-tag", "dev"], &work).status.success());
+    assert!(
+        run_git(&["push", "origin", "dev-tag"], &work)
+            .status
+            .success()
+    );
+    let dest = temp.path().join("single-clone");
+
+    let output = run_libra(
+        &[
+            "clone",
+            "--single-branch",
+            remote.to_str().unwrap(),
+            dest.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "clone --single-branch failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run_libra(&["tag", "-l"], &dest);
+    let tags = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        tags.lines().any(|line| line.trim() == "v1"),
+        "tag on main should be followed: {tags}"
+    );
+    assert!(
+        !tags.contains("dev-tag"),
+        "tag on the unfetched branch should be skipped: {tags}"
+    );
+}
+
+#[test]
+fn clone_branch_accepts_tag_and_detaches_head() {
+    let temp = tempdir().unwrap();
+    let remote = create_remote_with_main(temp.path());
+    add_dev_branch_and_tag(temp.path(), &remote);
+    let dest = temp.path().join("tag-clone");
+
+    let output = run_libra(
+        &[
+            "clone",
+            "--branch",
+            "v1",
+            remote.to_str().unwrap(),
+            dest.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "clone --branch v1 failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dest.join("README.md").exists());
+    assert!(
+        !dest.join("dev.txt").exists(),
+        "the tag predates the dev commit"
+    );
+
+    let tagged = run_git(&["rev-parse", "v1^{commit}"], &remote);
+    let output = run_libra(&["rev-parse", "HEAD"], &dest);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        String::from_utf8_lossy(&tagged.stdout).trim()
+    );
+    let output = run_libra(&["rev-parse", "--abbrev-ref", "HEAD"], &dest);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "HEAD");
+}
+
+#[test]
+fn bare_clone_has_no_working_files() {
+    let temp = tempdir().unwrap();
+    let remote = create_remote_with_main(temp.path());
+    let dest = temp.path().join("bare.git");
+
+    let output = run_libra(
+        &[
+            "clone",
+            "--bare",
+            remote.to_str().unwrap(),
+            dest.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "bare clone failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dest.join("objects").is_dir());
+    assert!(!dest.join("README.md").exists());
+    assert!(!dest.join(".libra").exists());
+}
+
 #[test]
 fn machine_clone_suppresses_decorative_stderr() {
     let temp = tempdir().unwrap();
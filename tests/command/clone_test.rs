@@ -194,8 +194,10 @@ async fn test_clone_branch() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(temp_path.path().to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -242,8 +244,10 @@ async fn test_clone_bare_repository() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(repo_dir.to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -305,8 +309,10 @@ async fn test_clone_branch_single_branch() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(temp_path.path().to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -352,8 +358,10 @@ async fn test_clone_default_branch() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(temp_path.path().to_str().unwrap().to_string()),
         branch: None,
@@ -399,8 +407,10 @@ async fn test_clone_default_branch_single_branch() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(temp_path.path().to_str().unwrap().to_string()),
         branch: None,
@@ -448,8 +458,10 @@ async fn test_clone_to_existing_empty_dir() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(repo_path.to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -500,8 +512,10 @@ async fn test_clone_to_existing_dir() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(repo_path.to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -548,8 +562,10 @@ async fn test_clone_to_dir_with_existing_file_name() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(conflict_path.to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -595,8 +611,10 @@ async fn test_clone_with_depth() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(temp_path.path().to_str().unwrap().to_string()),
         branch: None,
@@ -642,8 +660,10 @@ async fn test_clone_with_depth_and_branch() {
         filter: None,
         shallow_since: None,
         shallow_exclude: vec![],
+        recurse_submodules: false,
         no_checkout: false,
         no_progress: false,
+        jobs: None,
         remote_repo: repo.https_url.clone(),
         local_path: Some(temp_path.path().to_str().unwrap().to_string()),
         branch: Some("dev".to_string()),
@@ -1168,9 +1188,9 @@ fn test_clone_mirror_maps_all_refs_and_sets_config() {
     );
 }
 
-/// `--filter`/`--shallow-since`/`--shallow-exclude` are accepted but ignored
-/// (Libra has no partial-clone/promisor support and its fetch only does `--depth`
-/// shallow), so a COMPLETE clone is performed and each given flag emits a warning.
+/// `--shallow-since`/`--shallow-exclude` are accepted but ignored (Libra's fetch
+/// only does `--depth` shallow), so a COMPLETE clone is performed and each given
+/// flag emits a warning.
 #[test]
 #[serial]
 fn test_clone_unsupported_fetch_optimizations_warn_and_full_clone() {
@@ -1198,32 +1218,6 @@ fn test_clone_unsupported_fetch_optimizations_warn_and_full_clone() {
     let source_str = sp.to_str().unwrap();
     let dest_root = tempdir().expect("dest root");
 
-    // --filter: full clone (the blob is present) + a warning.
-    let d1 = dest_root.path().join("d1");
-    let out = run_libra_command(
-        &[
-            "clone",
-            "--filter",
-            "blob:none",
-            source_str,
-            d1.to_str().unwrap(),
-        ],
-        dest_root.path(),
-    );
-    assert!(
-        out.status.success(),
-        "clone with --filter succeeds (full clone): {}",
-        String::from_utf8_lossy(&out.stderr)
-    );
-    assert!(
-        d1.join("f.txt").exists(),
-        "a full clone is performed (the filtered-out blob is still present)"
-    );
-    assert!(
-        String::from_utf8_lossy(&out.stderr).contains("--filter is ignored"),
-        "--filter warns it is ignored"
-    );
-
     // --shallow-since + --shallow-exclude (multi): full clone + warnings.
     let d2 = dest_root.path().join("d2");
     let out = run_libra_command(
@@ -1252,6 +1246,92 @@ fn test_clone_unsupported_fetch_optimizations_warn_and_full_clone() {
     );
 }
 
+/// `--filter=blob:none` performs a blobless clone: the pack carries only the
+/// commit and tree, the source is recorded as the promisor remote, and checkout
+/// fetches the missing blob from it on demand.
+#[test]
+#[serial]
+fn test_clone_filter_blob_none_fetches_blobs_on_checkout() {
+    use super::run_libra_command;
+
+    let source = tempdir().expect("source dir");
+    let sp = source.path();
+    assert!(
+        run_libra_command(&["init"], sp).status.success(),
+        "init source"
+    );
+    run_libra_command(&["config", "set", "user.name", "t"], sp);
+    run_libra_command(&["config", "set", "user.email", "t@t"], sp);
+    fs::write(sp.join("f.txt"), "lazy\n").expect("write f");
+    assert!(
+        run_libra_command(&["add", "f.txt"], sp).status.success(),
+        "add"
+    );
+    assert!(
+        run_libra_command(&["commit", "-m", "c1", "--no-verify"], sp)
+            .status
+            .success(),
+        "commit"
+    );
+    let source_str = sp.to_str().unwrap();
+
+    let dest_root = tempdir().expect("dest root");
+    let dest = dest_root.path().join("partial");
+    let out = run_libra_command(
+        &[
+            "clone",
+            "--json",
+            "--filter=blob:none",
+            source_str,
+            dest.to_str().unwrap(),
+        ],
+        dest_root.path(),
+    );
+    assert!(
+        out.status.success(),
+        "blobless clone succeeds: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&out.stdout).expect("clone --json emits valid JSON");
+    assert_eq!(
+        json["data"]["objects_fetched"].as_u64(),
+        Some(2),
+        "the filtered pack holds only the commit and its tree: {json}"
+    );
+    assert_eq!(
+        fs::read_to_string(dest.join("f.txt")).expect("checked-out file"),
+        "lazy\n",
+        "checkout fetched the omitted blob from the promisor remote"
+    );
+
+    for (key, expected) in [
+        ("remote.origin.promisor", "true"),
+        ("remote.origin.partialclonefilter", "blob:none"),
+        ("extensions.partialclone", "origin"),
+    ] {
+        let out = run_libra_command(&["config", "get", key], &dest);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout).trim(),
+            expected,
+            "{key} is recorded"
+        );
+    }
+
+    // An unsupported filter spec is a usage error.
+    let out = run_libra_command(
+        &[
+            "clone",
+            "--filter=tree:0",
+            source_str,
+            dest_root.path().join("bad").to_str().unwrap(),
+        ],
+        dest_root.path(),
+    );
+    assert_eq!(out.status.code(), Some(129), "tree filters are rejected");
+    assert!(!dest_root.path().join("bad").exists());
+}
+
 /// A Git clone reports the fetch transfer counts `objects_fetched` and
 /// `bytes_received` in its `--json` output (both > 0 for a non-empty source).
 #[test]
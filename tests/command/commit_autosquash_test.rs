@@ -136,6 +136,47 @@ fn squash_generates_squash_subject() {
     );
 }
 
+#[test]
+fn squash_with_message_keeps_it_as_body() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path().join("repo");
+    init_repo(&repo);
+    write_and_add(&repo, "a.txt", "x\n");
+    commit(&repo, "base subject");
+
+    write_and_add(&repo, "a.txt", "y\n");
+    let out = run_libra(
+        &["commit", "--squash", "HEAD", "-m", "extra squash notes"],
+        &repo,
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "squash commit with -m failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let message = last_commit_message(&repo);
+    let subject = message.find("squash! base subject");
+    let body = message.find("extra squash notes");
+    assert!(
+        subject.is_some() && body.is_some() && subject < body,
+        "expected squash! subject followed by the -m body, got: {message}"
+    );
+}
+
+#[test]
+fn fixup_with_message_is_rejected() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path().join("repo");
+    init_repo(&repo);
+    write_and_add(&repo, "a.txt", "x\n");
+    commit(&repo, "base subject");
+
+    write_and_add(&repo, "a.txt", "y\n");
+    let out = run_libra(&["commit", "--fixup", "HEAD", "-m", "nope"], &repo);
+    assert_ne!(out.status.code(), Some(0), "--fixup must conflict with -m");
+}
+
 #[test]
 fn fixup_unknown_target_returns_repo_error() {
     let temp = tempdir().unwrap();
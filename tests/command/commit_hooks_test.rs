@@ -0,0 +1,180 @@
+//! Classic Git hooks (`pre-commit`, `commit-msg`, ...) run by `libra commit`.
+//!
+//! **Layer:** L1 — deterministic, no external dependencies.
+
+#![cfg(unix)]
+
+use std::{fs, os::unix::fs::PermissionsExt, path::Path, process::Command};
+
+use tempfile::tempdir;
+
+fn run_libra(args: &[&str], cwd: &Path) -> std::process::Output {
+    let home = cwd.join(".libra-test-home");
+    let config_home = home.join(".config");
+    fs::create_dir_all(&config_home).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_libra"))
+        .args(args)
+        .current_dir(cwd)
+        .env("HOME", &home)
+        .env("USERPROFILE", &home)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env_remove("RUST_LOG")
+        .env_remove("LIBRA_LOG")
+        .env_remove("EDITOR")
+        .env_remove("VISUAL")
+        .env_remove("GIT_EDITOR")
+        .output()
+        .unwrap()
+}
+
+fn init_repo(repo: &Path) {
+    fs::create_dir_all(repo).unwrap();
+    assert!(run_libra(&["init"], repo).status.success(), "init failed");
+    assert!(
+        run_libra(&["config", "user.name", "Test User"], repo)
+            .status
+            .success()
+    );
+    assert!(
+        run_libra(&["config", "user.email", "test@example.com"], repo)
+            .status
+            .success()
+    );
+    fs::write(repo.join("a.txt"), "a\n").unwrap();
+    assert!(run_libra(&["add", "a.txt"], repo).status.success(), "add");
+}
+
+fn write_hook(dir: &Path, name: &str, body: &str, executable: bool) {
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    let mode = if executable { 0o755 } else { 0o644 };
+    fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+}
+
+fn has_head(repo: &Path) -> bool {
+    run_libra(&["rev-parse", "HEAD"], repo).status.success()
+}
+
+fn last_subject(repo: &Path) -> String {
+    let out = run_libra(&["log", "-1", "--format=%s"], repo);
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+#[test]
+fn failing_pre_commit_hook_blocks_the_commit() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    init_repo(repo);
+    write_hook(
+        &repo.join(".libra/hooks"),
+        "pre-commit",
+        "echo rejected >&2; exit 3",
+        true,
+    );
+
+    let out = run_libra(&["commit", "-m", "blocked"], repo);
+    assert!(!out.status.success(), "a failing pre-commit must abort");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("pre-commit hook failed"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("rejected"),
+        "hook stderr is shown: {stderr}"
+    );
+    assert!(!has_head(repo), "no commit may be created");
+
+    let out = run_libra(&["commit", "--no-verify", "-m", "bypassed"], repo);
+    assert!(
+        out.status.success(),
+        "--no-verify skips pre-commit: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(last_subject(repo), "bypassed");
+}
+
+#[test]
+fn commit_msg_hook_can_rewrite_or_reject_the_message() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    init_repo(repo);
+    let hooks = repo.join(".libra/hooks");
+    write_hook(
+        &hooks,
+        "commit-msg",
+        r#"printf 'rewritten: %s\n' "$(head -n 1 "$1")" > "$1""#,
+        true,
+    );
+
+    let out = run_libra(&["commit", "-m", "original"], repo);
+    assert!(
+        out.status.success(),
+        "commit failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(last_subject(repo), "rewritten: original");
+
+    write_hook(&hooks, "commit-msg", "exit 1", true);
+    fs::write(repo.join("a.txt"), "b\n").unwrap();
+    assert!(run_libra(&["add", "a.txt"], repo).status.success(), "add");
+    let out = run_libra(&["commit", "-m", "second"], repo);
+    assert!(!out.status.success(), "a failing commit-msg must abort");
+    assert!(String::from_utf8_lossy(&out.stderr).contains("commit-msg hook failed"));
+    assert_eq!(last_subject(repo), "rewritten: original");
+}
+
+#[test]
+fn hooks_require_the_executable_bit_and_honor_core_hooks_path() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    init_repo(repo);
+    write_hook(&repo.join(".libra/hooks"), "pre-commit", "exit 1", false);
+    write_hook(
+        &repo.join(".githooks"),
+        "commit-msg",
+        r#"echo "from hooksPath" > "$1""#,
+        true,
+    );
+    assert!(
+        run_libra(&["config", "core.hooksPath", ".githooks"], repo)
+            .status
+            .success()
+    );
+
+    let out = run_libra(&["commit", "-m", "original"], repo);
+    assert!(
+        out.status.success(),
+        "non-executable hooks are ignored: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(last_subject(repo), "from hooksPath");
+}
+
+#[test]
+fn pre_commit_hook_staged_changes_are_committed() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    init_repo(repo);
+    let libra = env!("CARGO_BIN_EXE_libra");
+    write_hook(
+        &repo.join(".libra/hooks"),
+        "pre-commit",
+        &format!("echo generated > gen.txt && '{libra}' add gen.txt"),
+        true,
+    );
+
+    let out = run_libra(&["commit", "-m", "with hook"], repo);
+    assert!(
+        out.status.success(),
+        "commit failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let out = run_libra(&["ls-tree", "-r", "--name-only", "HEAD"], repo);
+    let files = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        files.lines().any(|line| line == "gen.txt"),
+        "the file the hook staged is in the commit: {files}"
+    );
+}
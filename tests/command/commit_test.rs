@@ -3,7 +3,7 @@
 //! **Layer:** L1 — deterministic, no external dependencies.
 
 use libra::{
-    command::commit::CleanupMode,
+    common_utils::CleanupMode,
     utils::{object_ext::TreeExt, output::OutputConfig},
 };
 use serial_test::serial;
@@ -79,6 +79,8 @@ async fn test_commit_requires_configured_identity_in_strict_mode() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -267,6 +269,8 @@ async fn test_execute_commit() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         };
         add::execute(args).await;
     }
@@ -364,6 +368,8 @@ async fn test_commit_with_all_flag_stages_tracked_changes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -443,6 +449,8 @@ async fn test_commit_with_all_flag_records_deletions() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -547,6 +555,8 @@ async fn test_commit_sha256() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -640,6 +650,8 @@ async fn test_commit_with_custom_author() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -902,6 +914,8 @@ async fn test_commit_with_actual_changes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     };
     add::execute(add_args).await;
 
@@ -967,6 +981,82 @@ async fn test_commit_amend_without_changes() {
     commit::execute(amend_args).await;
 }
 
+/// `commit -a --amend --no-edit` with nothing staged but a modified tracked
+/// file: the amended commit keeps the original author line (name, email and
+/// date) and message, takes the current committer, replaces HEAD rather than
+/// stacking on it, and is logged as a `commit (amend)` reflog entry.
+#[test]
+fn test_commit_amend_no_edit_preserves_author_and_records_amend_reflog() {
+    let repo = create_committed_repo_via_cli();
+    let repo_path = repo.path();
+
+    let output = run_libra_command(&["rev-parse", "HEAD"], repo_path);
+    assert_cli_success(&output, "rev-parse original HEAD");
+    let original_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let output = run_libra_command(&["cat-file", "-p", "HEAD"], repo_path);
+    assert_cli_success(&output, "cat-file original HEAD");
+    let original = String::from_utf8_lossy(&output.stdout).to_string();
+    let header = |object: &str, key: &str| {
+        object
+            .lines()
+            .find(|line| line.starts_with(key))
+            .unwrap_or_else(|| panic!("missing {key} header in:\n{object}"))
+            .to_string()
+    };
+
+    // Timestamps have one-second resolution; make the new committer date differ.
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let output = run_libra_command(&["config", "user.name", "Amending User"], repo_path);
+    assert_cli_success(&output, "configure new user.name");
+    let output = run_libra_command(&["config", "user.email", "amend@example.com"], repo_path);
+    assert_cli_success(&output, "configure new user.email");
+
+    fs::write(repo_path.join("tracked.txt"), "tracked\namended\n").unwrap();
+    let output = run_libra_command(
+        &["commit", "-a", "--amend", "--no-edit", "--no-verify"],
+        repo_path,
+    );
+    assert_cli_success(&output, "commit -a --amend --no-edit");
+
+    let output = run_libra_command(&["rev-parse", "HEAD"], repo_path);
+    assert_cli_success(&output, "rev-parse amended HEAD");
+    let amended_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert_ne!(amended_id, original_id);
+    let output = run_libra_command(&["cat-file", "-p", "HEAD"], repo_path);
+    assert_cli_success(&output, "cat-file amended HEAD");
+    let amended = String::from_utf8_lossy(&output.stdout).to_string();
+
+    assert_eq!(
+        header(&amended, "author "),
+        header(&original, "author "),
+        "author name, email and date must survive the amend"
+    );
+    let committer = header(&amended, "committer ");
+    assert!(
+        committer.starts_with("committer Amending User <amend@example.com> "),
+        "committer should be the current identity, got: {committer}"
+    );
+    assert_ne!(committer, header(&original, "committer "));
+    assert!(
+        !amended.lines().any(|line| line.starts_with("parent ")),
+        "amending the root commit must not stack on it:\n{amended}"
+    );
+    assert_eq!(amended.trim_end().lines().last(), Some("base"));
+
+    let output = run_libra_command(&["show", "HEAD:tracked.txt"], repo_path);
+    assert_cli_success(&output, "show amended tracked.txt");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("amended"));
+
+    let output = run_libra_command(&["--json", "reflog", "show", "-n", "1"], repo_path);
+    assert_cli_success(&output, "reflog after amend");
+    let json = parse_json_stdout(&output);
+    let entry = &json["data"]["entries"][0];
+    assert_eq!(entry["action"], "commit (amend)");
+    assert_eq!(entry["summary"], "commit (amend): base");
+    assert_eq!(entry["old_oid"], original_id.as_str());
+    assert_eq!(entry["new_oid"], amended_id.as_str());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_commit_signoff_persists_trailer() {
@@ -989,6 +1079,8 @@ async fn test_commit_signoff_persists_trailer() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1158,6 +1250,8 @@ async fn test_commit_without_identity_fails_by_default() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1251,6 +1345,8 @@ async fn test_commit_cleanup_strips_comments() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1316,6 +1412,72 @@ fn test_commit_honors_cleanup_and_verbose_config() {
     );
 }
 
+#[test]
+fn test_commit_empty_message_requires_allow_empty_message() {
+    // A message that cleans up to nothing aborts like Git, unless
+    // `--allow-empty-message` is given.
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    std::fs::write(p.join("e.txt"), "one\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "e.txt"], p), "add e");
+
+    let rejected = run_libra_command(&["commit", "-m", "# only a comment\n\n", "--no-verify"], p);
+    assert!(!rejected.status.success(), "empty message must be rejected");
+    let stderr = String::from_utf8_lossy(&rejected.stderr);
+    assert!(
+        stderr.contains("aborting commit due to empty commit message")
+            && stderr.contains("--allow-empty-message"),
+        "stderr should explain the abort and the override:\n{stderr}"
+    );
+
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "commit",
+                "-m",
+                "# only a comment\n\n",
+                "--allow-empty-message",
+                "--no-verify",
+            ],
+            p,
+        ),
+        "commit --allow-empty-message",
+    );
+    let output = run_libra_command(&["--json", "log", "-n", "1"], p);
+    assert_cli_success(&output, "log after empty-message commit");
+    let json = parse_json_stdout(&output);
+    let commit = &json["data"]["commits"][0];
+    assert_eq!(commit["subject"], "", "unexpected commit: {commit}");
+    assert_eq!(commit["body"], "", "unexpected commit: {commit}");
+}
+
+#[test]
+fn test_commit_cleanup_whitespace_collapses_blank_lines_and_keeps_comments() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    std::fs::write(p.join("w.txt"), "one\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "w.txt"], p), "add w");
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "commit",
+                "--cleanup=whitespace",
+                "-m",
+                "subject   \n\n\n\n# kept\nbody\n\n",
+                "--no-verify",
+            ],
+            p,
+        ),
+        "commit --cleanup=whitespace",
+    );
+    let raw = String::from_utf8_lossy(&run_libra_command(&["cat-file", "-p", "HEAD"], p).stdout)
+        .into_owned();
+    assert!(
+        raw.trim_end().ends_with("\n\nsubject\n\n# kept\nbody"),
+        "whitespace cleanup trims, collapses blank runs, and keeps comments:\n{raw:?}"
+    );
+}
+
 #[test]
 fn test_commit_cleanup_default_on_non_editor_keeps_comments() {
     // Git's `default` cleanup means strip when the message is edited, otherwise
@@ -1442,6 +1604,8 @@ async fn test_commit_trailer_appended() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1480,6 +1644,8 @@ async fn test_commit_dry_run_does_not_create_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1519,6 +1685,8 @@ async fn test_commit_reuse_message() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1545,6 +1713,8 @@ async fn test_commit_reuse_message() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1585,6 +1755,8 @@ async fn test_commit_fixup_sets_subject() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1609,6 +1781,8 @@ async fn test_commit_fixup_sets_subject() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1793,6 +1967,152 @@ fn commit_no_gpg_sign_produces_unsigned_commit() {
     );
 }
 
+/// `-S` signs with the vault key even when `vault.signing` is off, and
+/// `commit.gpgsign=true` makes that the default; `log`/`show
+/// --show-signature` then report the verdict under the commit line, naming
+/// the vault key rather than the committer.
+#[test]
+#[cfg(feature = "signing")]
+fn test_commit_gpg_sign_flag_and_config_sign_with_vault_key() {
+    let repo = tempdir().unwrap();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["init", "--vault", "false"], p),
+        "init --vault false",
+    );
+    configure_identity_via_cli(p);
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "config",
+                "generate-gpg-key",
+                "--name",
+                "Vault Key",
+                "--email",
+                "vault-key@example.com",
+            ],
+            p,
+        ),
+        "generate-gpg-key",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "vault.signing", "false"], p),
+        "disable vault.signing",
+    );
+
+    let commit_file = |name: &str, extra: &[&str]| {
+        std::fs::write(p.join(name), format!("{name}\n")).unwrap();
+        assert_cli_success(&run_libra_command(&["add", name], p), "add file");
+        let mut args = vec!["--json", "commit", "-m", name, "--no-verify"];
+        args.extend_from_slice(extra);
+        let output = run_libra_command(&args, p);
+        assert_cli_success(&output, "commit");
+        parse_json_stdout(&output)["data"]["signed"].as_bool()
+    };
+
+    assert_eq!(commit_file("plain.txt", &[]), Some(false));
+    assert_eq!(commit_file("signed.txt", &["-S"]), Some(true));
+    assert_eq!(
+        commit_file("overridden.txt", &["-S", "--no-gpg-sign"]),
+        Some(false),
+        "--no-gpg-sign after -S wins"
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "commit.gpgsign", "true"], p),
+        "set commit.gpgsign",
+    );
+    assert_eq!(commit_file("config.txt", &[]), Some(true));
+
+    let log = run_libra_command(&["log", "--show-signature", "-n", "4"], p);
+    assert_cli_success(&log, "log --show-signature");
+    let stdout = String::from_utf8_lossy(&log.stdout);
+    assert_eq!(
+        stdout.matches("Good signature from").count(),
+        2,
+        "only the two signed commits carry a verdict: {stdout}"
+    );
+    let first_lines: Vec<&str> = stdout.lines().take(2).collect();
+    assert!(first_lines[0].starts_with("commit "), "{stdout}");
+    assert!(
+        first_lines[1]
+            .starts_with("Good signature from \"Vault Key <vault-key@example.com>\" (key "),
+        "the verdict names the verifying key, not the committer: {stdout}"
+    );
+
+    let show = run_libra_command(&["show", "--show-signature", "--no-patch", "HEAD"], p);
+    assert_cli_success(&show, "show --show-signature");
+    assert!(
+        String::from_utf8_lossy(&show.stdout).contains("Good signature from"),
+        "show reports the signature check"
+    );
+    let quiet = run_libra_command(&["log", "-n", "1"], p);
+    assert!(
+        !String::from_utf8_lossy(&quiet.stdout).contains("signature from"),
+        "signature checks are opt-in"
+    );
+}
+
+/// `user.signingkey` names the vault key that signs and verifies: a commit
+/// signed with the named key reports that key's identity, and switching the
+/// setting to another key turns the verdict BAD.
+#[test]
+#[cfg(feature = "signing")]
+fn test_commit_signing_key_config_selects_the_vault_key() {
+    let repo = tempdir().unwrap();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["init", "--vault", "false"], p),
+        "init --vault false",
+    );
+    configure_identity_via_cli(p);
+    assert_cli_success(
+        &run_libra_command(&["config", "generate-gpg-key", "--name", "Default Key"], p),
+        "generate default key",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "user.signingkey", "release"], p),
+        "set user.signingkey",
+    );
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "config",
+                "generate-gpg-key",
+                "--name",
+                "Release Key",
+                "--email",
+                "release@example.com",
+            ],
+            p,
+        ),
+        "generate release key",
+    );
+
+    std::fs::write(p.join("release.txt"), "release\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "release.txt"], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-S", "-m", "release", "--no-verify"], p),
+        "commit -S",
+    );
+
+    let show = run_libra_command(&["show", "--show-signature", "--no-patch", "HEAD"], p);
+    assert_cli_success(&show, "show --show-signature");
+    let stdout = String::from_utf8_lossy(&show.stdout);
+    assert!(
+        stdout.contains("Good signature from \"Release Key <release@example.com>\""),
+        "{stdout}"
+    );
+
+    assert_cli_success(
+        &run_libra_command(&["config", "user.signingkey", "libra-signing"], p),
+        "switch user.signingkey",
+    );
+    let show = run_libra_command(&["show", "--show-signature", "--no-patch", "HEAD"], p);
+    let stdout = String::from_utf8_lossy(&show.stdout);
+    assert!(stdout.contains("BAD signature"), "{stdout}");
+    assert!(!stdout.contains("Good signature"), "{stdout}");
+}
+
 /// Regression: `--amend --no-edit` on a *signed* parent must reuse the parent's
 /// real log message, NOT leak the parent's embedded `gpgsig` signature block.
 ///
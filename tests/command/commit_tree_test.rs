@@ -0,0 +1,106 @@
+//! Integration tests for `libra commit-tree` (with `write-tree` and `update-ref`).
+//!
+//! Layer: L1 (deterministic; tempdir + isolated HOME, no network).
+
+use std::fs;
+
+use super::{
+    assert_cli_success, create_committed_repo_via_cli, parse_cli_error_stderr, parse_json_stdout,
+    run_libra_command, run_libra_command_with_stdin,
+};
+
+fn stdout_trimmed(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn commit_tree_builds_a_child_commit_from_the_index() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let head = stdout_trimmed(&run_libra_command(&["rev-parse", "HEAD"], p));
+
+    fs::write(p.join("next.txt"), "next\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "next.txt"], p), "add");
+    let tree = stdout_trimmed(&run_libra_command(&["write-tree"], p));
+
+    let out = run_libra_command(
+        &[
+            "commit-tree",
+            &tree,
+            "-p",
+            "HEAD",
+            "-m",
+            "plumbed",
+            "-m",
+            "body",
+        ],
+        p,
+    );
+    assert_cli_success(&out, "commit-tree");
+    let commit = stdout_trimmed(&out);
+    assert_eq!(commit.len(), 40, "SHA-1 commit id: {commit}");
+
+    let shown = run_libra_command(&["cat-file", "-p", &commit], p);
+    assert_cli_success(&shown, "cat-file -p");
+    let shown = String::from_utf8_lossy(&shown.stdout);
+    assert!(
+        shown.starts_with(&format!("tree {tree}\nparent {head}\n")),
+        "{shown}"
+    );
+    assert!(
+        shown.contains("author Test User <test@example.com>"),
+        "{shown}"
+    );
+    assert!(shown.contains("\nplumbed\n\nbody"), "{shown}");
+
+    // commit-tree leaves refs alone; update-ref publishes the commit.
+    assert_eq!(
+        stdout_trimmed(&run_libra_command(&["rev-parse", "HEAD"], p)),
+        head
+    );
+    assert_cli_success(
+        &run_libra_command(&["update-ref", "refs/heads/main", &commit], p),
+        "update-ref",
+    );
+    let log = run_libra_command(&["log", "--oneline", "-n", "1"], p);
+    assert!(
+        stdout_trimmed(&log).contains("plumbed"),
+        "{}",
+        stdout_trimmed(&log)
+    );
+}
+
+#[test]
+fn commit_tree_root_commit_reads_message_from_stdin_and_reports_json() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let tree = stdout_trimmed(&run_libra_command(&["write-tree"], p));
+
+    let out = run_libra_command_with_stdin(&["--json", "commit-tree", &tree], p, "from stdin\n");
+    assert_cli_success(&out, "commit-tree --json");
+    let json = parse_json_stdout(&out);
+    assert_eq!(json["command"], "commit-tree");
+    assert_eq!(json["data"]["tree"], tree.as_str());
+    assert_eq!(json["data"]["parents"], serde_json::json!([]));
+
+    let commit = json["data"]["commit"].as_str().unwrap().to_string();
+    let shown = run_libra_command(&["cat-file", "-p", &commit], p);
+    let shown = String::from_utf8_lossy(&shown.stdout);
+    assert!(!shown.contains("parent "), "root commit: {shown}");
+    assert!(shown.contains("from stdin"), "{shown}");
+}
+
+#[test]
+fn commit_tree_rejects_unknown_parent() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let tree = stdout_trimmed(&run_libra_command(&["write-tree"], p));
+
+    let out = run_libra_command(
+        &["commit-tree", &tree, "-p", "no-such-branch", "-m", "x"],
+        p,
+    );
+    assert!(!out.status.success());
+    let (_, report) = parse_cli_error_stderr(&out.stderr);
+    assert_eq!(report.error_code, "LBR-CLI-003");
+}
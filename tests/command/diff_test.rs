@@ -482,6 +482,8 @@ async fn test_basic_diff() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -552,6 +554,8 @@ async fn test_diff_staged() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -589,6 +593,8 @@ async fn test_diff_staged() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -652,6 +658,8 @@ async fn test_diff_between_commits() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -691,6 +699,8 @@ async fn test_diff_between_commits() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -771,6 +781,8 @@ async fn test_diff_with_pathspec() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -849,6 +861,8 @@ async fn test_diff_output_to_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -922,6 +936,8 @@ async fn test_diff_algorithms() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1163,6 +1179,48 @@ fn test_diff_check_reports_whitespace_errors() {
     );
 }
 
+/// Each comparison mode shows only its own side of the change: plain `diff`
+/// is worktree vs index, `--cached`/`--staged` is index vs HEAD, and
+/// `--cached <commit>` is index vs that commit.
+#[test]
+fn test_diff_cached_and_staged_compare_index_against_head_or_commit() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    std::fs::write(p.join("m.txt"), "one\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "m.txt"], p), "add m.txt");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "add m", "--no-verify"], p),
+        "commit m.txt",
+    );
+    std::fs::write(p.join("m.txt"), "one\nstaged\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "m.txt"], p), "stage change");
+    std::fs::write(p.join("m.txt"), "one\nstaged\nunstaged\n").unwrap();
+
+    let diff_stdout = |args: &[&str]| {
+        let out = run_libra_command(args, p);
+        assert_cli_success(&out, &args.join(" "));
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+
+    let worktree = diff_stdout(&["diff"]);
+    assert!(
+        worktree.contains("+unstaged"),
+        "worktree diff: {worktree:?}"
+    );
+    assert!(!worktree.contains("+staged"), "worktree diff: {worktree:?}");
+
+    let cached = diff_stdout(&["diff", "--cached"]);
+    assert!(cached.contains("+staged"), "cached diff: {cached:?}");
+    assert!(!cached.contains("+unstaged"), "cached diff: {cached:?}");
+    assert_eq!(diff_stdout(&["diff", "--staged"]), cached);
+    assert_eq!(diff_stdout(&["diff", "--cached", "HEAD"]), cached);
+
+    // Against the base commit, m.txt did not exist yet: the whole staged file
+    // is new.
+    let against_base = diff_stdout(&["diff", "--cached", "HEAD~1", "--name-status"]);
+    assert_eq!(against_base.trim(), "A\tm.txt");
+}
+
 #[test]
 fn test_diff_reverse_swaps_sides() {
     let repo = create_committed_repo_via_cli();
@@ -3278,3 +3336,151 @@ fn test_diff_three_dot_positional_and_no_merge_base() {
         "A...B equals --old <merge-base> --new B"
     );
 }
+
+/// On a diverged history `A..B` compares the two tips directly, so it also
+/// shows A's own changes (reversed); `A...B` starts from the merge base and
+/// shows only what B introduced.
+#[test]
+fn test_diff_two_dot_and_three_dot_on_diverged_branches() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let commit_file = |file: &str, msg: &str| {
+        fs::write(p.join(file), format!("{msg}\n")).unwrap();
+        assert_cli_success(&run_libra_command(&["add", file], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", msg, "--no-verify"], p),
+            "commit",
+        );
+    };
+    assert_cli_success(&run_libra_command(&["branch", "feature"], p), "branch");
+    commit_file("main.txt", "main side");
+    assert_cli_success(&run_libra_command(&["checkout", "feature"], p), "co feat");
+    commit_file("feature.txt", "feature side");
+
+    let two = run_libra_command(&["diff", "--name-status", "main..feature"], p);
+    assert_cli_success(&two, "two-dot");
+    let two = String::from_utf8_lossy(&two.stdout);
+    assert!(two.contains("A\tfeature.txt"), "{two}");
+    assert!(
+        two.contains("D\tmain.txt"),
+        "two-dot includes main's side: {two}"
+    );
+
+    let three = run_libra_command(&["diff", "--name-status", "main...feature"], p);
+    assert_cli_success(&three, "three-dot");
+    let three = String::from_utf8_lossy(&three.stdout);
+    assert!(three.contains("A\tfeature.txt"), "{three}");
+    assert!(
+        !three.contains("main.txt"),
+        "three-dot excludes changes already on main: {three}"
+    );
+
+    // Swapping the sides of a three-dot diff shows the other branch's work.
+    let reversed = run_libra_command(&["diff", "--name-status", "feature...main"], p);
+    assert_cli_success(&reversed, "reversed three-dot");
+    let reversed = String::from_utf8_lossy(&reversed.stdout);
+    assert!(reversed.contains("A\tmain.txt"), "{reversed}");
+    assert!(!reversed.contains("feature.txt"), "{reversed}");
+}
+
+#[test]
+fn diff_unset_diff_attribute_reports_text_file_as_binary() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join(".gitattributes"), "*.lock -diff\n").unwrap();
+    fs::write(p.join("deps.lock"), "a = 1\n").unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "deps.lock"], p),
+        "stage deps.lock",
+    );
+
+    let out = run_libra_command(&["diff", "--cached"], p);
+    assert_cli_success(&out, "diff --cached");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("Binary files /dev/null and b/deps.lock differ")
+            && !stdout.contains("+a = 1"),
+        "-diff must suppress the content diff: {stdout:?}"
+    );
+}
+
+#[test]
+fn diff_no_index_compares_two_files_outside_a_repository() {
+    let temp = tempdir().unwrap();
+    let p = temp.path();
+    fs::write(p.join("old.txt"), "one\ntwo\nthree\n").unwrap();
+    fs::write(p.join("new.txt"), "one\n2\nthree\n").unwrap();
+
+    let out = run_libra_command(&["diff", "--no-index", "old.txt", "new.txt"], p);
+    assert_eq!(out.status.code(), Some(1), "differences exit 1");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "diff --git a/old.txt b/new.txt\n\
+         index 4cb29ea..f04eb26 100644\n\
+         --- a/old.txt\n\
+         +++ b/new.txt\n\
+         @@ -1,3 +1,3 @@\n \
+         one\n\
+         -two\n\
+         +2\n \
+         three\n"
+    );
+
+    fs::write(p.join("same.txt"), "one\ntwo\nthree\n").unwrap();
+    let out = run_libra_command(&["diff", "--no-index", "old.txt", "same.txt"], p);
+    assert_cli_success(&out, "identical files");
+    assert!(out.stdout.is_empty());
+
+    let out = run_libra_command(&["diff", "--no-index", "old.txt"], p);
+    assert_eq!(out.status.code(), Some(129));
+    let (stderr, report) = parse_cli_error_stderr(&out.stderr);
+    assert_eq!(report.error_code, "LBR-CLI-002");
+    assert!(stderr.contains("exactly two paths"), "stderr: {stderr}");
+}
+
+#[test]
+fn diff_no_index_recurses_into_directories() {
+    let temp = tempdir().unwrap();
+    let p = temp.path();
+    for (path, content) in [
+        ("left/same.txt", "same\n"),
+        ("left/changed.txt", "before\n"),
+        ("left/gone/removed.txt", "removed\n"),
+        ("right/same.txt", "same\n"),
+        ("right/changed.txt", "after\n"),
+        ("right/nested/added.txt", "added\n"),
+    ] {
+        let path = p.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    let out = run_libra_command(&["diff", "--no-index", "--name-status", "left", "right"], p);
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "M\tright/changed.txt\nD\tleft/gone/removed.txt\nA\tright/nested/added.txt\n"
+    );
+
+    let out = run_libra_command(&["diff", "--no-index", "left", "right"], p);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains(
+            "diff --git a/right/nested/added.txt b/right/nested/added.txt\n\
+             new file mode 100644\n"
+        ) && stdout
+            .contains("--- /dev/null\n+++ b/right/nested/added.txt\n@@ -0,0 +1,1 @@\n+added"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("deleted file mode 100644\n") && stdout.contains("+++ /dev/null\n"),
+        "{stdout}"
+    );
+
+    let out = run_libra_command(&["--json", "diff", "--no-index", "left", "right"], p);
+    assert_eq!(out.status.code(), Some(1));
+    let json = parse_json_stdout(&out);
+    assert_eq!(json["data"]["files_changed"], 3);
+    assert_eq!(json["data"]["total_insertions"], 2);
+    assert_eq!(json["data"]["total_deletions"], 2);
+}
@@ -0,0 +1,52 @@
+//! Integration tests for `libra export-snapshot`: the single JSON document
+//! of HEAD, status, recent commits, and the staged diff used as AI context.
+//!
+//! **Layer:** L1 — deterministic, no external dependencies.
+
+use super::*;
+
+#[test]
+fn export_snapshot_json_reports_head_status_commits_and_staged_diff() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join("tracked.txt"), "tracked\nmore\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "tracked.txt"], p), "add");
+
+    let head = run_libra_command(&["rev-parse", "HEAD"], p);
+    assert_cli_success(&head, "rev-parse HEAD");
+    let head = String::from_utf8(head.stdout).unwrap().trim().to_string();
+
+    let output = run_libra_command(&["--json", "export-snapshot"], p);
+    assert_cli_success(&output, "export-snapshot");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["command"], "export-snapshot");
+    let data = json["data"].as_object().expect("data object");
+    for key in [
+        "head",
+        "branch",
+        "session_id",
+        "status",
+        "recent_commits",
+        "staged_diff",
+    ] {
+        assert!(data.contains_key(key), "missing `{key}` in {data:?}");
+    }
+
+    assert_eq!(data["head"], head.as_str());
+    assert!(data["branch"].is_string());
+    assert!(data["session_id"].is_null());
+    assert_eq!(data["status"]["staged"]["modified"][0], "tracked.txt");
+    let commits = data["recent_commits"].as_array().expect("commits array");
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0]["hash"], head.as_str());
+    assert_eq!(commits[0]["subject"], "base");
+    let diff = data["staged_diff"].as_str().expect("staged diff text");
+    assert!(diff.contains("tracked.txt"), "diff: {diff}");
+    assert!(diff.contains("+more"), "diff: {diff}");
+
+    let output = run_libra_command(&["--json", "export-snapshot", "--no-diff", "-n", "0"], p);
+    assert_cli_success(&output, "export-snapshot --no-diff -n 0");
+    let json = parse_json_stdout(&output);
+    assert!(json["data"]["staged_diff"].is_null());
+    assert_eq!(json["data"]["recent_commits"], serde_json::json!([]));
+}
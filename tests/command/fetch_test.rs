@@ -1822,6 +1822,7 @@ fn local_fetch_args(repository: &str, prune: bool, dry_run: bool) -> fetch::Fetc
         refspec: None,
         all: false,
         depth: None,
+        jobs: None,
         dry_run,
         append: false,
         verbose: false,
@@ -1959,3 +1960,214 @@ fn test_fetch_prune_flag_and_toggle_parse() {
         "--prune and --no-prune should form a last-wins toggle, not a hard conflict: {stderr}"
     );
 }
+
+/// A wildcard refspec maps every matching remote branch into the chosen
+/// `refs/remotes/origin/*` namespace, and leaves non-matching branches alone.
+#[tokio::test]
+#[serial]
+async fn test_fetch_wildcard_refspec_creates_mapped_tracking_refs() {
+    let (_temp_root, _repo_dir, default_branch, _guard) =
+        setup_multi_branch_remote_and_fetch().await;
+
+    let mut args = local_fetch_args("origin", false, false);
+    args.refspec = Some("+refs/heads/feature*:refs/remotes/origin/f/*".to_string());
+    fetch::execute_safe(args, &OutputConfig::default())
+        .await
+        .expect("fetch with a wildcard refspec should succeed");
+
+    for suffix in ["1", "2", "3"] {
+        assert!(
+            origin_tracking_ref_exists(&format!("f/{suffix}")).await,
+            "feature{suffix} should be mapped to refs/remotes/origin/f/{suffix}"
+        );
+    }
+    assert!(
+        !origin_tracking_ref_exists(&format!("f/{default_branch}")).await,
+        "the default branch does not match the refspec source"
+    );
+}
+
+/// With a refspec, `--prune` removes the tracking ref of a deleted branch only
+/// inside the refspec's destination; tracking refs elsewhere under
+/// `refs/remotes/origin/` are left untouched.
+#[tokio::test]
+#[serial]
+async fn test_fetch_prune_with_refspec_only_touches_its_destination() {
+    let (temp_root, _repo_dir, _default_branch, _guard) =
+        setup_multi_branch_remote_and_fetch().await;
+    let remote_dir = temp_root.path().join("remote.git");
+    let refspec = "+refs/heads/feature*:refs/remotes/origin/f/*";
+
+    let mut args = local_fetch_args("origin", false, false);
+    args.refspec = Some(refspec.to_string());
+    fetch::execute_safe(args, &OutputConfig::default())
+        .await
+        .expect("fetch with a wildcard refspec should succeed");
+
+    assert!(
+        Command::new("git")
+            .current_dir(&remote_dir)
+            .args(["update-ref", "-d", "refs/heads/feature1"])
+            .status()
+            .expect("git update-ref -d failed")
+            .success()
+    );
+
+    let mut args = local_fetch_args("origin", true, false);
+    args.refspec = Some(refspec.to_string());
+    fetch::execute_safe(args, &OutputConfig::default())
+        .await
+        .expect("fetch --prune with a refspec should succeed");
+
+    assert!(
+        !origin_tracking_ref_exists("f/1").await,
+        "the deleted branch's mapped tracking ref should be pruned"
+    );
+    assert!(
+        origin_tracking_ref_exists("f/2").await,
+        "live mapped tracking refs must be kept"
+    );
+    assert!(
+        origin_tracking_ref_exists("feature1").await,
+        "tracking refs outside the refspec destination are not pruned"
+    );
+}
+
+/// A configured `remote.<name>.fetch` refspec narrows a plain `fetch <remote>`.
+#[tokio::test]
+#[serial]
+async fn test_fetch_honors_configured_fetch_refspec() {
+    let (_temp_root, _repo_dir, _default_branch, _guard) =
+        setup_multi_branch_remote_and_fetch().await;
+    ConfigKv::add(
+        "remote.origin.fetch",
+        "+refs/heads/feature2:refs/remotes/origin/only-two",
+        false,
+    )
+    .await
+    .unwrap();
+
+    fetch::execute_safe(
+        local_fetch_args("origin", false, false),
+        &OutputConfig::default(),
+    )
+    .await
+    .expect("fetch with a configured refspec should succeed");
+
+    assert!(origin_tracking_ref_exists("only-two").await);
+}
+
+/// A configured refspec Libra cannot honour is skipped with a warning; the
+/// remaining refspecs still apply.
+#[tokio::test]
+#[serial]
+async fn test_fetch_skips_unsupported_configured_refspec() {
+    let (_temp_root, _repo_dir, _default_branch, _guard) =
+        setup_multi_branch_remote_and_fetch().await;
+    for spec in [
+        "+refs/heads/feature1:refs/heads/feature1",
+        "+refs/heads/feature2:refs/remotes/origin/only-two",
+    ] {
+        ConfigKv::add("remote.origin.fetch", spec, false)
+            .await
+            .unwrap();
+    }
+
+    fetch::execute_safe(
+        local_fetch_args("origin", false, false),
+        &OutputConfig::default(),
+    )
+    .await
+    .expect("an unsupported configured refspec should not fail the fetch");
+
+    assert!(origin_tracking_ref_exists("only-two").await);
+}
+
+/// A refspec without `+` refuses a non-fast-forward update of its tracking
+/// ref; `+` (or `--force`) lets it through.
+#[tokio::test]
+#[serial]
+async fn test_fetch_refspec_rejects_non_fast_forward_without_plus() {
+    let (temp_root, _repo_dir, _default_branch, _guard) =
+        setup_multi_branch_remote_and_fetch().await;
+    let work_dir = temp_root.path().join("workdir");
+    let tracking_oid = || async {
+        Branch::find_branch_result("refs/remotes/origin/feature1", Some("origin"))
+            .await
+            .expect("failed to query remote-tracking branch")
+            .expect("feature1 should be tracked")
+            .commit
+            .to_string()
+    };
+    let before = tracking_oid().await;
+
+    // Rewrite feature1 on the remote with an unrelated commit.
+    for args in [
+        vec!["checkout", "--orphan", "rewritten"],
+        vec!["commit", "-m", "rewritten history"],
+        vec!["push", "-f", "origin", "rewritten:feature1"],
+    ] {
+        assert!(
+            Command::new("git")
+                .current_dir(&work_dir)
+                .args(&args)
+                .status()
+                .expect("git command failed")
+                .success(),
+            "git {args:?} failed"
+        );
+    }
+
+    let refspec = "refs/heads/feature1:refs/remotes/origin/feature1";
+    let mut args = local_fetch_args("origin", false, false);
+    args.refspec = Some(refspec.to_string());
+    let error = fetch::execute_safe(args, &OutputConfig::default())
+        .await
+        .expect_err("a non-fast-forward update without '+' must be rejected");
+    assert!(
+        error.message().contains("non-fast-forward"),
+        "unexpected error: {}",
+        error.message()
+    );
+    assert_eq!(tracking_oid().await, before, "the tracking ref is kept");
+
+    let mut args = local_fetch_args("origin", false, false);
+    args.refspec = Some(format!("+{refspec}"));
+    fetch::execute_safe(args, &OutputConfig::default())
+        .await
+        .expect("a '+' refspec allows the forced update");
+    assert_ne!(
+        tracking_oid().await,
+        before,
+        "the tracking ref is rewritten"
+    );
+}
+
+/// Benchmark `clone --jobs 4` against a single upload-pack request. Needs a
+/// smart-HTTP remote with many objects (the reference run uses a 10k-object
+/// repository); run with
+/// `LIBRA_BENCH_FETCH_URL=<url> cargo test --test command_test bench_clone_jobs -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark; set LIBRA_BENCH_FETCH_URL"]
+fn bench_clone_jobs_against_single_request() {
+    let url = std::env::var("LIBRA_BENCH_FETCH_URL")
+        .expect("LIBRA_BENCH_FETCH_URL must name a smart-HTTP remote");
+    let root = tempdir().unwrap();
+    let mut heads = Vec::new();
+    for jobs in ["1", "4"] {
+        let target = root.path().join(format!("jobs-{jobs}"));
+        let target_arg = target.to_str().unwrap();
+        let started = std::time::Instant::now();
+        let output = run_libra_command(
+            &["clone", "--no-checkout", "--jobs", jobs, &url, target_arg],
+            root.path(),
+        );
+        let elapsed = started.elapsed();
+        assert_cli_success(&output, "clone --jobs");
+        println!("--jobs {jobs}: {elapsed:?}");
+        let head = run_libra_command(&["rev-parse", "HEAD"], &target);
+        assert_cli_success(&head, "rev-parse HEAD");
+        heads.push(head.stdout);
+    }
+    assert_eq!(heads[0], heads[1], "both clones must reach the same tip");
+}
@@ -32,6 +32,8 @@ async fn add_and_commit(message: &str, pathspec: Vec<String>) {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &OutputConfig::default(),
     )
@@ -343,6 +345,7 @@ async fn test_grep_tree_accepts_branch_revisions() {
             edit_description: None,
             show_current: false,
             rename: Vec::new(),
+            rename_force: Vec::new(),
             copy: Vec::new(),
             copy_force: Vec::new(),
             remotes: false,
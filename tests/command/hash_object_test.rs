@@ -428,3 +428,87 @@ async fn hash_object_stdin_paths_hashes_each_path_in_order() {
         String::from_utf8_lossy(&bad.stdout)
     );
 }
+
+#[tokio::test]
+async fn hash_object_applies_lfs_filter_like_add() {
+    let repo = tempfile::tempdir().expect("create temp repo");
+    init_repo_via_cli(repo.path());
+    let p = repo.path();
+    fs::write(
+        p.join(".libra_attributes"),
+        "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+    )
+    .expect("write attributes");
+    fs::write(p.join("big.bin"), b"hello").expect("write fixture");
+
+    // An LFS-tracked path hashes to the pointer blob `add` would stage.
+    let filtered = run_libra_command(&["hash-object", "-w", "big.bin"], p);
+    assert_cli_success(&filtered, "hash-object on an LFS path should succeed");
+    let pointer_oid = String::from_utf8_lossy(&filtered.stdout).trim().to_string();
+    let pointer = run_libra_command(&["cat-file", "-p", &pointer_oid], p);
+    assert_cli_success(&pointer, "cat-file should find the pointer blob");
+    assert_eq!(
+        String::from_utf8_lossy(&pointer.stdout),
+        "version https://git-lfs.github.com/spec/v1\n\
+         oid sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824\n\
+         size 5\n"
+    );
+
+    let add = run_libra_command(&["add", "big.bin"], p);
+    assert_cli_success(&add, "add big.bin");
+    let staged = run_libra_command(&["ls-files", "-s", "big.bin"], p);
+    assert_cli_success(&staged, "ls-files -s");
+    assert!(
+        String::from_utf8_lossy(&staged.stdout).contains(&pointer_oid),
+        "add and hash-object must agree: {}",
+        String::from_utf8_lossy(&staged.stdout)
+    );
+
+    // --no-filters hashes the raw bytes; --path applies another path's filter.
+    let raw = run_libra_command(&["hash-object", "--no-filters", "big.bin"], p);
+    assert_cli_success(&raw, "hash-object --no-filters");
+    assert_eq!(
+        String::from_utf8_lossy(&raw.stdout).trim(),
+        "b6fc4c620b67d95f953a5c1c1230aaab5db5a1b0"
+    );
+    let stdin =
+        run_libra_command_with_stdin(&["hash-object", "--stdin", "--path=other.bin"], p, "hello");
+    assert_cli_success(&stdin, "hash-object --stdin --path");
+    assert_eq!(String::from_utf8_lossy(&stdin.stdout).trim(), pointer_oid);
+}
+
+#[tokio::test]
+async fn hash_object_applies_gitattributes_like_add() {
+    let repo = tempfile::tempdir().expect("create temp repo");
+    init_repo_via_cli(repo.path());
+    let p = repo.path();
+    fs::write(
+        p.join(".gitattributes"),
+        "*.txt text eol=crlf\n*.dat filter=lfs diff=lfs merge=lfs -text\n",
+    )
+    .expect("write attributes");
+    fs::write(p.join("crlf.txt"), b"one\r\ntwo\r\n").expect("write crlf fixture");
+    fs::write(p.join("big.dat"), b"hello").expect("write lfs fixture");
+
+    let add = run_libra_command(&["add", "crlf.txt", "big.dat"], p);
+    assert_cli_success(&add, "add fixtures");
+    let staged = run_libra_command(&["ls-files", "-s"], p);
+    assert_cli_success(&staged, "ls-files -s");
+    let staged = String::from_utf8_lossy(&staged.stdout).to_string();
+
+    for path in ["crlf.txt", "big.dat"] {
+        let hashed = run_libra_command(&["hash-object", path], p);
+        assert_cli_success(&hashed, "hash-object on an attributed path");
+        let oid = String::from_utf8_lossy(&hashed.stdout).trim().to_string();
+        assert!(
+            staged.contains(&format!("{oid} 0\t{path}")),
+            "add and hash-object must agree for {path}: {oid} vs {staged}"
+        );
+    }
+
+    // The CRLF file hashes to its LF-normalized blob.
+    let lf = run_libra_command_with_stdin(&["hash-object", "--stdin"], p, "one\ntwo\n");
+    assert_cli_success(&lf, "hash-object --stdin");
+    let crlf = run_libra_command(&["hash-object", "crlf.txt"], p);
+    assert_eq!(lf.stdout, crlf.stdout);
+}
@@ -94,11 +94,8 @@ fn index_pack_keep_without_message_writes_empty_keep_file() {
     );
 }
 
-/// `--fix-thin` is accepted but a no-op: indexing a complete pack with it
-/// produces the exact same index as without it. Libra never produces thin packs
-/// and its pack decoder requires self-contained packs, so a pack that indexes
-/// successfully had no external delta bases to complete — matching Git, where
-/// `--fix-thin` on a complete pack does nothing.
+/// `--fix-thin` on a complete pack leaves it alone: the index is identical
+/// to one built without the flag, matching Git.
 #[test]
 #[serial]
 fn index_pack_fix_thin_is_noop_on_complete_pack() {
@@ -641,3 +641,213 @@ fn build_index_v1_rejects_sha256_hash_kind() {
         other => panic!("unexpected error: {other:?}"),
     }
 }
+
+/// Pack entry header: 3-bit type, then the size as a varint (four bits in the
+/// first byte, seven in each following one).
+fn pack_entry_header(type_bits: u8, size: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut byte = (type_bits << 4) | (size & 0x0f) as u8;
+    let mut rest = size >> 4;
+    while rest != 0 {
+        out.push(byte | 0x80);
+        byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+    }
+    out.push(byte);
+    out
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// A delta turning a `base_len`-byte base into the base followed by `tail`:
+/// one copy of the whole base, then one insert. Small sizes only.
+fn append_delta(base_len: usize, tail: &[u8]) -> Vec<u8> {
+    assert!(base_len < 128 && base_len + tail.len() < 128 && !tail.is_empty());
+    let mut delta = vec![base_len as u8, (base_len + tail.len()) as u8];
+    delta.extend_from_slice(&[0x90, base_len as u8, tail.len() as u8]);
+    delta.extend_from_slice(tail);
+    delta
+}
+
+fn blob_entry(content: &[u8]) -> Vec<u8> {
+    let mut entry = pack_entry_header(3, content.len());
+    entry.extend(zlib(content));
+    entry
+}
+
+/// `OFS_DELTA` entry whose base starts `distance` (< 128) bytes before it.
+fn ofs_delta_entry(distance: usize, delta: &[u8]) -> Vec<u8> {
+    assert!(distance < 128);
+    let mut entry = pack_entry_header(6, delta.len());
+    entry.push(distance as u8);
+    entry.extend(zlib(delta));
+    entry
+}
+
+fn ref_delta_entry(base: &ObjectHash, delta: &[u8]) -> Vec<u8> {
+    let mut entry = pack_entry_header(7, delta.len());
+    entry.extend_from_slice(base.as_ref());
+    entry.extend(zlib(delta));
+    entry
+}
+
+/// A SHA-1 version 2 pack of `entries` with its trailing checksum.
+fn sha1_pack(entries: &[Vec<u8>]) -> Vec<u8> {
+    let mut pack = b"PACK".to_vec();
+    pack.extend_from_slice(&2u32.to_be_bytes());
+    pack.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+    for entry in entries {
+        pack.extend_from_slice(entry);
+    }
+    let trailer: [u8; 20] = Sha1::digest(&pack).into();
+    pack.extend_from_slice(&trailer);
+    pack
+}
+
+fn blob_id(content: &[u8]) -> ObjectHash {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content);
+    let digest: [u8; 20] = hasher.finalize().into();
+    ObjectHash::from_bytes(&digest).unwrap()
+}
+
+fn idx_v1_hashes(index_path: &Path) -> Vec<ObjectHash> {
+    let bytes = fs::read(index_path).expect("index should be written");
+    parse_idx_v1(&bytes)
+        .entries
+        .into_iter()
+        .map(|entry| entry.hash)
+        .collect()
+}
+
+#[test]
+#[serial]
+fn test_index_pack_resolves_offset_delta_chain() {
+    let repo = tempdir().unwrap();
+    init_repo_via_cli(repo.path());
+
+    let base = b"hello base\n";
+    let blob = blob_entry(base);
+    let first = ofs_delta_entry(blob.len(), &append_delta(base.len(), b"one\n"));
+    let second = ofs_delta_entry(first.len(), &append_delta(base.len() + 4, b"two\n"));
+    let pack = sha1_pack(&[blob, first, second]);
+
+    let dir = tempdir().unwrap();
+    let pack_path = dir.path().join("ofs-chain.pack");
+    fs::write(&pack_path, &pack).unwrap();
+    let output = run_libra_command(&["index-pack", pack_path.to_str().unwrap()], repo.path());
+    assert_cli_success(&output, "index-pack of an offset-delta chain");
+
+    let hashes = idx_v1_hashes(&pack_path.with_extension("idx"));
+    assert_eq!(hashes.len(), 3);
+    for content in [
+        &b"hello base\n"[..],
+        b"hello base\none\n",
+        b"hello base\none\ntwo\n",
+    ] {
+        assert!(hashes.contains(&blob_id(content)), "{content:?} indexed");
+    }
+}
+
+#[test]
+#[serial]
+fn test_index_pack_resolves_ref_delta_before_its_base() {
+    let repo = tempdir().unwrap();
+    init_repo_via_cli(repo.path());
+
+    // The delta comes first; it resolves once its base is decoded.
+    let base = b"ref base\n";
+    let pack = sha1_pack(&[
+        ref_delta_entry(&blob_id(base), &append_delta(base.len(), b"tail\n")),
+        blob_entry(base),
+    ]);
+
+    let dir = tempdir().unwrap();
+    let pack_path = dir.path().join("ref-order.pack");
+    fs::write(&pack_path, &pack).unwrap();
+    let output = run_libra_command(&["index-pack", pack_path.to_str().unwrap()], repo.path());
+    assert_cli_success(&output, "index-pack with a forward REF_DELTA");
+
+    let hashes = idx_v1_hashes(&pack_path.with_extension("idx"));
+    assert!(hashes.contains(&blob_id(b"ref base\ntail\n")));
+    assert_eq!(hashes.len(), 2);
+}
+
+#[test]
+#[serial]
+fn test_index_pack_fix_thin_appends_missing_bases() {
+    let repo = tempdir().unwrap();
+    init_repo_via_cli(repo.path());
+
+    // The base blob lives only in the repository, as after a thin fetch.
+    let base = b"thin base\n";
+    fs::write(repo.path().join("base.txt"), base).unwrap();
+    let stored = run_libra_command(&["hash-object", "-w", "base.txt"], repo.path());
+    assert_cli_success(&stored, "hash-object -w base.txt");
+    let base_id = blob_id(base);
+    assert_eq!(
+        String::from_utf8_lossy(&stored.stdout).trim(),
+        base_id.to_string()
+    );
+
+    let dir = tempdir().unwrap();
+    let pack_path = dir.path().join("thin.pack");
+    let pack = sha1_pack(&[ref_delta_entry(
+        &base_id,
+        &append_delta(base.len(), b"extra\n"),
+    )]);
+    fs::write(&pack_path, &pack).unwrap();
+
+    let output = run_libra_command(&["index-pack", pack_path.to_str().unwrap()], repo.path());
+    assert_eq!(output.status.code(), Some(128));
+    let (stderr, report) = parse_cli_error_stderr(&output.stderr);
+    assert_eq!(report.error_code, "LBR-REPO-002");
+    assert!(stderr.contains("rerun with --fix-thin"), "stderr: {stderr}");
+    assert_eq!(fs::read(&pack_path).unwrap(), pack, "pack left untouched");
+
+    let output = run_libra_command(
+        &[
+            "index-pack",
+            "--fix-thin",
+            pack_path.to_str().unwrap(),
+            "--json",
+        ],
+        repo.path(),
+    );
+    assert_cli_success(&output, "index-pack --fix-thin");
+    let json = parse_json_stdout(&output);
+    let data = &json["data"];
+    assert_eq!(data["appended_objects"], 1);
+    assert_eq!(
+        fs::read(&pack_path).unwrap(),
+        pack,
+        "input pack not rewritten"
+    );
+    let completed = std::path::PathBuf::from(data["pack_file"].as_str().unwrap());
+    assert_eq!(completed.parent(), pack_path.parent());
+    assert!(
+        completed
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("pack-") && name.ends_with(".pack")),
+        "completed pack is named after its checksum: {}",
+        completed.display()
+    );
+    assert_eq!(
+        data["index_file"].as_str().unwrap(),
+        completed.with_extension("idx").to_str().unwrap()
+    );
+
+    let mut hashes = idx_v1_hashes(&completed.with_extension("idx"));
+    hashes.sort();
+    let mut expected = vec![base_id, blob_id(b"thin base\nextra\n")];
+    expected.sort();
+    assert_eq!(hashes, expected, "the completed pack is self-contained");
+}
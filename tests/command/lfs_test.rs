@@ -2,14 +2,17 @@
 //!
 //! **Layer:** L1 — deterministic, no external dependencies.
 
-use std::{fs, path::Path, process::Command};
+use std::{collections::HashMap, fs, path::Path, process::Command, sync::Arc};
 
 use axum::{
     Json, Router,
-    http::StatusCode,
+    extract::Query,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
     routing::{get, post},
 };
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use tempfile::TempDir;
 
 /// Build a `Command` for the Libra binary with an isolated HOME.
@@ -282,6 +285,185 @@ async fn test_lfs_ls_files_json_output() {
     assert!(full_oid.starts_with(display_oid));
 }
 
+#[tokio::test]
+/// `lfs status` reports a newly staged pointer under "to be committed" and a
+/// later working-tree edit of the same path under "not staged".
+async fn test_lfs_status_reports_staged_and_unstaged_objects() {
+    let temp_repo = init_temp_repo();
+    let temp_path = temp_repo.path();
+
+    libra_command(temp_path)
+        .args(["lfs", "track", "*.bin"])
+        .output()
+        .expect("Failed to track pattern");
+    fs::write(temp_path.join("data.bin"), "first version").expect("Failed to write file");
+    libra_command(temp_path)
+        .args(["add", "data.bin"])
+        .output()
+        .expect("Failed to add file");
+    fs::write(temp_path.join("data.bin"), "second version").expect("Failed to rewrite file");
+
+    let output = libra_command(temp_path)
+        .args(["--json", "lfs", "status"])
+        .output()
+        .expect("Failed to run lfs status");
+    assert!(
+        output.status.success(),
+        "lfs status failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("lfs status stdout should be JSON");
+    assert_eq!(json["data"]["action"], "status");
+    let status = &json["data"]["status"];
+    assert_eq!(status["branch"], "main");
+
+    let staged = status["staged"].as_array().expect("staged array");
+    assert_eq!(staged.len(), 1, "unexpected staged entries: {staged:?}");
+    assert_eq!(staged[0]["path"], "data.bin");
+    assert!(staged[0].get("from").is_none());
+    assert_eq!(staged[0]["to"]["kind"], "lfs");
+
+    let unstaged = status["unstaged"].as_array().expect("unstaged array");
+    assert_eq!(
+        unstaged.len(),
+        1,
+        "unexpected unstaged entries: {unstaged:?}"
+    );
+    assert_eq!(unstaged[0]["path"], "data.bin");
+    assert_eq!(unstaged[0]["from"]["oid"], staged[0]["to"]["oid"]);
+    assert_eq!(unstaged[0]["to"]["kind"], "file");
+
+    let human = libra_command(temp_path)
+        .args(["lfs", "status"])
+        .output()
+        .expect("Failed to run lfs status");
+    let stdout = String::from_utf8_lossy(&human.stdout);
+    assert!(stdout.contains("Objects to be committed:"), "{stdout}");
+    assert!(
+        stdout.contains("Objects not staged for commit:"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("data.bin (LFS: "), "{stdout}");
+}
+
+/// Mock LFS server that serves `objects` (oid -> content) through the batch
+/// API, pointing each download action back at its own `/download` route.
+fn mock_lfs_download_server(objects: HashMap<String, Vec<u8>>) -> Router {
+    let objects = Arc::new(objects);
+    Router::new()
+        .route(
+            "/objects/batch",
+            post(
+                |headers: HeaderMap, Json(body): Json<serde_json::Value>| async move {
+                    let host = headers["host"].to_str().unwrap().to_string();
+                    let objects: Vec<_> = body["objects"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|object| {
+                            let oid = object["oid"].as_str().unwrap();
+                            json!({
+                                "oid": oid,
+                                "size": object["size"],
+                                "actions": { "download": {
+                                    "href": format!("http://{host}/download?oid={oid}"),
+                                    "expires_at": "2099-01-01T00:00:00Z"
+                                }}
+                            })
+                        })
+                        .collect();
+                    Json(json!({ "transfer": "basic", "objects": objects }))
+                },
+            ),
+        )
+        .route(
+            "/download",
+            get(move |Query(query): Query<HashMap<String, String>>| {
+                let objects = objects.clone();
+                async move { objects[&query["oid"]].clone() }
+            }),
+        )
+}
+
+fn lfs_oid(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+/// `lfs fetch` fills the local LFS cache from the batch API: by default only
+/// for HEAD, with `--all` for every commit, and skips objects already cached.
+async fn test_lfs_fetch_populates_cache_for_head_and_all() {
+    let old = b"old design".to_vec();
+    let new = b"new design".to_vec();
+    let (old_oid, new_oid) = (lfs_oid(&old), lfs_oid(&new));
+    let app = mock_lfs_download_server(HashMap::from([
+        (old_oid.clone(), old.clone()),
+        (new_oid.clone(), new.clone()),
+    ]));
+    let addr = spawn_mock_lfs_server(app).await;
+    let repo = init_repo_with_mock_remote(&format!("http://{addr}"));
+    let repo_path = repo.path().to_path_buf();
+
+    let output = tokio::task::spawn_blocking(move || {
+        let run = |args: &[&str]| {
+            let output = libra_command(&repo_path).args(args).output().unwrap();
+            assert!(
+                output.status.success(),
+                "{args:?}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            output
+        };
+        let fetch = |args: &[&str]| -> serde_json::Value {
+            let output = run(&[&["--json", "lfs", "fetch"][..], args].concat());
+            serde_json::from_slice(&output.stdout).expect("lfs fetch stdout should be JSON")
+        };
+        run(&["config", "user.name", "tester"]);
+        run(&["config", "user.email", "tester@example.com"]);
+        run(&["lfs", "track", "*.psd"]);
+        fs::write(repo_path.join("old.psd"), &old).unwrap();
+        run(&["add", ".libra_attributes", "old.psd"]);
+        run(&["commit", "-m", "old design"]);
+        run(&["rm", "old.psd"]);
+        fs::write(repo_path.join("new.psd"), &new).unwrap();
+        run(&["add", "new.psd"]);
+        run(&["commit", "-m", "new design"]);
+        fs::remove_dir_all(repo_path.join(".libra/lfs/objects")).unwrap();
+
+        let cached = |oid: &str| {
+            repo_path
+                .join(".libra/lfs/objects")
+                .join(&oid[..2])
+                .join(&oid[2..4])
+                .join(oid)
+        };
+        let head = fetch(&[]);
+        let all = fetch(&["--all"]);
+        let again = fetch(&["--recent"]);
+        (
+            head,
+            all,
+            again,
+            fs::read(cached(&old_oid)),
+            fs::read(cached(&new_oid)),
+        )
+    })
+    .await
+    .expect("spawn_blocking join failed");
+    let (head, all, again, old_cached, new_cached) = output;
+
+    assert_eq!(head["data"]["action"], "fetch");
+    assert_eq!(head["data"]["fetch"]["fetched"], json!([new_oid]));
+    assert_eq!(head["data"]["fetch"]["already_cached"], 0);
+    assert_eq!(all["data"]["fetch"]["fetched"], json!([old_oid]));
+    assert_eq!(all["data"]["fetch"]["already_cached"], 1);
+    assert_eq!(again["data"]["fetch"]["fetched"], json!([]));
+    assert_eq!(again["data"]["fetch"]["already_cached"], 2);
+    assert_eq!(old_cached.unwrap(), old);
+    assert_eq!(new_cached.unwrap(), new);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 /// `lfs locks --json` against a mock server that returns one lock; verifies the JSON
 /// envelope surfaces the locks list and matches the `LfsOutput` schema.
@@ -671,6 +853,221 @@ async fn test_lfs_unlock_by_path_resolves_lock_id_via_get_locks() {
     assert_eq!(stdout["data"]["id"], "lock-by-path");
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+/// `lfs lock` persists the granted lock id, so a later `lfs unlock <path>`
+/// unlocks by that id without asking `GET /locks` (which fails here) and then
+/// forgets it.
+async fn test_lfs_lock_persists_id_for_unlock_by_path() {
+    let app = Router::new()
+        .route(
+            "/locks",
+            post(|| async {
+                (
+                    StatusCode::CREATED,
+                    Json(json!({
+                        "lock": {
+                            "id": "lock-7",
+                            "path": "tracked.bin",
+                            "locked_at": "2026-01-01T00:00:00Z",
+                            "owner": { "name": "tester" }
+                        }
+                    })),
+                )
+            })
+            .get(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+        )
+        .route(
+            "/locks/{id}/unlock",
+            post(
+                |axum::extract::Path(id): axum::extract::Path<String>| async move {
+                    if id != "lock-7" {
+                        return StatusCode::NOT_FOUND.into_response();
+                    }
+                    Json(json!({
+                        "lock": {
+                            "id": id,
+                            "path": "tracked.bin",
+                            "locked_at": "2026-01-01T00:00:00Z",
+                            "owner": { "name": "tester" }
+                        }
+                    }))
+                    .into_response()
+                },
+            ),
+        );
+    let addr = spawn_mock_lfs_server(app).await;
+    let repo = init_repo_with_mock_remote(&format!("http://{addr}"));
+    let repo_path = repo.path().to_path_buf();
+    fs::write(repo_path.join("tracked.bin"), "content").expect("failed to create tracked file");
+    let cache_path = repo_path.join(".libra/lfs/locks.json");
+
+    let (lock, unlock) = tokio::task::spawn_blocking(move || {
+        let lock = libra_command(&repo_path)
+            .args(["--json", "lfs", "lock", "tracked.bin"])
+            .output()
+            .expect("failed to run lfs lock");
+        let unlock = libra_command(&repo_path)
+            .args(["--json", "lfs", "unlock", "tracked.bin", "--force"])
+            .output()
+            .expect("failed to run lfs unlock");
+        (lock, unlock)
+    })
+    .await
+    .expect("spawn_blocking join failed");
+
+    assert!(
+        lock.status.success(),
+        "lfs lock should succeed; stderr: {}",
+        String::from_utf8_lossy(&lock.stderr)
+    );
+    let stdout: serde_json::Value =
+        serde_json::from_slice(&lock.stdout).expect("lock stdout should be JSON");
+    assert_eq!(stdout["data"]["id"], "lock-7");
+
+    assert!(
+        unlock.status.success(),
+        "unlock should use the cached id; stderr: {}",
+        String::from_utf8_lossy(&unlock.stderr)
+    );
+    let stdout: serde_json::Value =
+        serde_json::from_slice(&unlock.stdout).expect("unlock stdout should be JSON");
+    assert_eq!(stdout["data"]["id"], "lock-7");
+    let cache = fs::read_to_string(&cache_path).expect("lock cache should exist");
+    assert!(
+        !cache.contains("lock-7"),
+        "released lock should leave the cache: {cache}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+/// After `lfs locks` caches another user's lock, committing that path warns
+/// (without blocking the commit) while unrelated paths stay quiet.
+async fn test_lfs_locks_cache_warns_on_commit_of_foreign_locked_path() {
+    let app = Router::new().route(
+        "/locks",
+        get(|| async {
+            Json(json!({
+                "locks": [{
+                    "id": "lock-9",
+                    "path": "shared.bin",
+                    "locked_at": "2026-01-01T00:00:00Z",
+                    "owner": { "name": "alice" }
+                }],
+                "next_cursor": ""
+            }))
+        }),
+    );
+    let addr = spawn_mock_lfs_server(app).await;
+    let repo = init_repo_with_mock_remote(&format!("http://{addr}"));
+    let repo_path = repo.path().to_path_buf();
+
+    let (seed, locked) = tokio::task::spawn_blocking(move || {
+        let run = |args: &[&str]| {
+            let output = libra_command(&repo_path).args(args).output().unwrap();
+            assert!(
+                output.status.success(),
+                "{args:?}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            output
+        };
+        run(&["config", "user.name", "tester"]);
+        run(&["config", "user.email", "tester@example.com"]);
+        run(&["lfs", "locks"]);
+
+        fs::write(repo_path.join("seed.txt"), b"seed").unwrap();
+        run(&["add", "seed.txt"]);
+        let seed = run(&["commit", "-m", "seed"]);
+
+        fs::write(repo_path.join("shared.bin"), b"edited").unwrap();
+        run(&["add", "shared.bin"]);
+        let locked = run(&["commit", "-m", "edit shared"]);
+        (seed, locked)
+    })
+    .await
+    .expect("spawn_blocking join failed");
+
+    let seed_stderr = String::from_utf8_lossy(&seed.stderr);
+    assert!(
+        !seed_stderr.contains("locked by"),
+        "unlocked paths must not warn: {seed_stderr}"
+    );
+    let stderr = String::from_utf8_lossy(&locked.stderr);
+    assert!(
+        stderr.contains("warning: 'shared.bin' is locked by alice (LFS lock lock-9)"),
+        "commit of a foreign-locked path should warn: {stderr}"
+    );
+}
+
+#[tokio::test]
+/// `lfs migrate import` rewrites a committed large file into an LFS pointer
+/// (keeping the original under `.libra/lfs/objects`), `checkout` smudges it
+/// back, and `lfs migrate export` restores the plain blob.
+async fn test_lfs_migrate_import_rewrites_history_to_pointers() {
+    let repo = init_temp_repo();
+    let repo_path = repo.path();
+    let run = |args: &[&str]| {
+        let output = libra_command(repo_path).args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{args:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        output
+    };
+    run(&["config", "user.name", "tester"]);
+    run(&["config", "user.email", "tester@example.com"]);
+
+    let content = vec![b'p'; 256 * 1024];
+    fs::write(repo_path.join("design.psd"), &content).unwrap();
+    fs::write(repo_path.join("notes.txt"), b"notes").unwrap();
+    run(&["add", "design.psd", "notes.txt"]);
+    run(&["commit", "-m", "add design"]);
+
+    let import = run(&["lfs", "migrate", "import", "--include=*.psd"]);
+    let stdout = String::from_utf8_lossy(&import.stdout);
+    assert!(stdout.contains("rewrote 1 commit(s)"), "{stdout}");
+
+    let blob = run(&["show", "HEAD:design.psd"]);
+    let pointer = String::from_utf8_lossy(&blob.stdout);
+    assert!(
+        pointer.starts_with("version https://git-lfs.github.com/spec/v1"),
+        "HEAD should store a pointer: {pointer}"
+    );
+    assert!(pointer.contains("oid sha256:"), "{pointer}");
+    assert!(
+        pointer.contains(&format!("size {}", content.len())),
+        "{pointer}"
+    );
+    let notes = run(&["show", "HEAD:notes.txt"]);
+    assert_eq!(notes.stdout, b"notes");
+    let reflog = run(&["reflog", "show", "HEAD"]);
+    assert!(
+        String::from_utf8_lossy(&reflog.stdout).contains("lfs migrate"),
+        "the branch rewrite should be logged: {}",
+        String::from_utf8_lossy(&reflog.stdout)
+    );
+    let attributes = run(&["show", "HEAD:.libra_attributes"]);
+    assert!(
+        String::from_utf8_lossy(&attributes.stdout)
+            .contains("*.psd filter=lfs diff=lfs merge=lfs -text")
+    );
+
+    fs::remove_file(repo_path.join("design.psd")).unwrap();
+    run(&["checkout", "--", "design.psd"]);
+    assert_eq!(fs::read(repo_path.join("design.psd")).unwrap(), content);
+
+    run(&["lfs", "migrate", "export", "--include=*.psd"]);
+    let blob = run(&["show", "HEAD:design.psd"]);
+    assert_eq!(blob.stdout, content, "export should restore the plain blob");
+    let status = run(&["status", "--porcelain"]);
+    assert!(
+        status.stdout.is_empty(),
+        "{:?}",
+        String::from_utf8_lossy(&status.stdout)
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 /// Pre-v0.17.1071 `current_refspec` printed
 /// `"fatal: HEAD is detached"` via `emit_legacy_stderr` then returned
@@ -891,6 +1288,7 @@ fn test_lfs_help_lists_examples_banner() {
         "libra lfs untrack",
         "libra lfs ls-files",
         "libra lfs locks",
+        "libra lfs migrate import",
         "libra lfs lock build/output.bin",
         "libra lfs unlock build/output.bin",
         "libra lfs unlock --force",
@@ -60,47 +60,51 @@ fn test_log_cli_empty_repository_returns_fatal_128() {
     );
 }
 
-#[test]
-fn test_log_first_parent_skips_merged_branch_commits() {
-    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
-
-    let repo = create_committed_repo_via_cli();
+/// Build `MAIN_ONE` → (`MAIN_TWO` | feature `SIDE_BRANCH`) → merge on `main`,
+/// so HEAD is a two-parent merge commit.
+fn commit_merged_side_branch(repo: &std::path::Path) {
+    use super::{assert_cli_success, run_libra_command};
 
     // main: a unique commit.
-    std::fs::write(repo.path().join("a.txt"), "a\n").unwrap();
-    assert_cli_success(&run_libra_command(&["add", "a.txt"], repo.path()), "add a");
+    std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "a.txt"], repo), "add a");
     assert_cli_success(
-        &run_libra_command(&["commit", "-m", "MAIN_ONE", "--no-verify"], repo.path()),
+        &run_libra_command(&["commit", "-m", "MAIN_ONE", "--no-verify"], repo),
         "commit MAIN_ONE",
     );
 
     // feature: a side-branch commit with a distinct subject + file.
     assert_cli_success(
-        &run_libra_command(&["switch", "-c", "feature"], repo.path()),
+        &run_libra_command(&["switch", "-c", "feature"], repo),
         "switch -c feature",
     );
-    std::fs::write(repo.path().join("b.txt"), "b\n").unwrap();
-    assert_cli_success(&run_libra_command(&["add", "b.txt"], repo.path()), "add b");
+    std::fs::write(repo.join("b.txt"), "b\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "b.txt"], repo), "add b");
     assert_cli_success(
-        &run_libra_command(&["commit", "-m", "SIDE_BRANCH", "--no-verify"], repo.path()),
+        &run_libra_command(&["commit", "-m", "SIDE_BRANCH", "--no-verify"], repo),
         "commit SIDE_BRANCH",
     );
 
     // main diverges, then merges feature -> a two-parent merge commit.
+    assert_cli_success(&run_libra_command(&["switch", "main"], repo), "switch main");
+    std::fs::write(repo.join("a.txt"), "a2\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "a.txt"], repo), "add a2");
     assert_cli_success(
-        &run_libra_command(&["switch", "main"], repo.path()),
-        "switch main",
-    );
-    std::fs::write(repo.path().join("a.txt"), "a2\n").unwrap();
-    assert_cli_success(&run_libra_command(&["add", "a.txt"], repo.path()), "add a2");
-    assert_cli_success(
-        &run_libra_command(&["commit", "-m", "MAIN_TWO", "--no-verify"], repo.path()),
+        &run_libra_command(&["commit", "-m", "MAIN_TWO", "--no-verify"], repo),
         "commit MAIN_TWO",
     );
     assert_cli_success(
-        &run_libra_command(&["merge", "feature"], repo.path()),
+        &run_libra_command(&["merge", "feature"], repo),
         "merge feature",
     );
+}
+
+#[test]
+fn test_log_first_parent_skips_merged_branch_commits() {
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    commit_merged_side_branch(repo.path());
 
     // Plain log includes the merged side-branch commit; --first-parent omits it.
     let plain = run_libra_command(&["log", "--oneline"], repo.path());
@@ -124,6 +128,123 @@ fn test_log_first_parent_skips_merged_branch_commits() {
     );
 }
 
+#[test]
+fn test_log_merges_and_no_merges_filter_by_parent_count() {
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    commit_merged_side_branch(repo.path());
+
+    let plain = run_libra_command(&["log", "--oneline"], repo.path());
+    assert_cli_success(&plain, "log --oneline");
+    let plain_lines = String::from_utf8_lossy(&plain.stdout).lines().count();
+
+    // --merges keeps only the merge commit.
+    let merges = run_libra_command(&["log", "--merges", "--oneline"], repo.path());
+    assert_cli_success(&merges, "log --merges --oneline");
+    let merges_out = String::from_utf8_lossy(&merges.stdout);
+    assert_eq!(
+        merges_out.lines().count(),
+        1,
+        "--merges should list only the merge commit:\n{merges_out}"
+    );
+    assert!(!merges_out.contains("MAIN_TWO") && !merges_out.contains("SIDE_BRANCH"));
+
+    // --no-merges drops only the merge commit.
+    let no_merges = run_libra_command(&["log", "--no-merges", "--oneline"], repo.path());
+    assert_cli_success(&no_merges, "log --no-merges --oneline");
+    let no_merges_out = String::from_utf8_lossy(&no_merges.stdout);
+    assert_eq!(
+        no_merges_out.lines().count(),
+        plain_lines - 1,
+        "--no-merges should drop just the merge commit:\n{no_merges_out}"
+    );
+    assert!(no_merges_out.contains("MAIN_TWO") && no_merges_out.contains("SIDE_BRANCH"));
+
+    // --first-parent --no-merges: the mainline without the merge itself.
+    let mainline = run_libra_command(
+        &["log", "--first-parent", "--no-merges", "--oneline"],
+        repo.path(),
+    );
+    assert_cli_success(&mainline, "log --first-parent --no-merges --oneline");
+    let mainline_out = String::from_utf8_lossy(&mainline.stdout);
+    assert!(mainline_out.contains("MAIN_TWO") && mainline_out.contains("MAIN_ONE"));
+    assert!(!mainline_out.contains("SIDE_BRANCH"));
+}
+
+#[test]
+fn test_log_author_and_grep_regex_filters() {
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    for (file, author, message) in [
+        (
+            "a.txt",
+            "Alice Smith <alice@example.com>",
+            "fix: parser crash",
+        ),
+        ("b.txt", "Bob Jones <bob@corp.test>", "feat: ui theme"),
+        (
+            "c.txt",
+            "Carol Alison <carol@example.com>",
+            "fix: ui glitch",
+        ),
+    ] {
+        std::fs::write(repo.path().join(file), file).unwrap();
+        assert_cli_success(&run_libra_command(&["add", file], repo.path()), "add");
+        let commit = run_libra_command(
+            &["commit", "--no-verify", "--author", author, "-m", message],
+            repo.path(),
+        );
+        assert_cli_success(&commit, "commit with --author");
+    }
+
+    let subjects = |args: &[&str]| -> Vec<String> {
+        let mut argv = vec!["log", "--format=%s"];
+        argv.extend_from_slice(args);
+        let output = run_libra_command(&argv, repo.path());
+        assert_cli_success(&output, &format!("log {args:?}"));
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    };
+
+    // --author is a case-insensitive regex over the name and the email.
+    assert_eq!(
+        subjects(&["--author", "^ali"]),
+        vec!["fix: parser crash"],
+        "an anchored name pattern must not match 'Carol Alison'"
+    );
+    assert_eq!(
+        subjects(&["--author", "@example\\.com$"]),
+        vec!["fix: ui glitch", "fix: parser crash", "base"]
+    );
+    assert_eq!(subjects(&["--author", "BOB"]), vec!["feat: ui theme"]);
+    assert_eq!(
+        subjects(&["--author", "bob|carol"]),
+        vec!["fix: ui glitch", "feat: ui theme"]
+    );
+
+    // Multiple --grep patterns match any; --all-match requires every one.
+    assert_eq!(
+        subjects(&["--grep", "^feat", "--grep", "crash"]),
+        vec!["feat: ui theme", "fix: parser crash"]
+    );
+    assert_eq!(
+        subjects(&["--grep", "^fix", "--grep", "ui", "--all-match"]),
+        vec!["fix: ui glitch"]
+    );
+    assert_eq!(
+        subjects(&["--grep", "ui", "--invert-grep", "--author", "example"]),
+        vec!["fix: parser crash", "base"]
+    );
+
+    let invalid = run_libra_command(&["log", "--author", "("], repo.path());
+    assert_eq!(invalid.status.code(), Some(129));
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("invalid --author regex '('"));
+}
+
 #[test]
 fn test_log_pickaxe_s_finds_commit_that_changes_string_count() {
     use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
@@ -248,6 +369,72 @@ fn test_log_skip_omits_leading_commits() {
     );
 }
 
+#[test]
+fn test_log_name_status_reports_added_deleted_and_renamed_files() {
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    std::fs::write(p.join("moved.txt"), "line one\nline two\nline three\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "moved.txt"], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "add moved", "--no-verify"], p),
+        "commit",
+    );
+
+    // Add one file and delete an unrelated one: no rename pairing.
+    std::fs::write(p.join("added.txt"), "brand new\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "added.txt"], p), "add");
+    assert_cli_success(&run_libra_command(&["rm", "tracked.txt"], p), "rm");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "swap files", "--no-verify"], p),
+        "commit",
+    );
+
+    let out = run_libra_command(&["log", "-n", "1", "--name-status", "--oneline"], p);
+    assert_cli_success(&out, "log --name-status");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("A\tadded.txt\n"), "{stdout}");
+    assert!(stdout.contains("D\ttracked.txt\n"), "{stdout}");
+
+    // A rename with a small edit keeps enough content to pair up.
+    std::fs::remove_file(p.join("moved.txt")).unwrap();
+    std::fs::write(
+        p.join("renamed.txt"),
+        "line one\nline two\nline three\nline four\n",
+    )
+    .unwrap();
+    assert_cli_success(&run_libra_command(&["add", "-A"], p), "add -A");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "rename", "--no-verify"], p),
+        "commit",
+    );
+
+    let out = run_libra_command(&["log", "-n", "1", "--name-status", "--oneline"], p);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let rename_line = stdout
+        .lines()
+        .find(|line| line.starts_with('R'))
+        .unwrap_or_else(|| panic!("missing rename entry:\n{stdout}"));
+    assert!(
+        rename_line.ends_with("\tmoved.txt\trenamed.txt"),
+        "{rename_line}"
+    );
+    assert!(!stdout.contains("D\tmoved.txt"), "{stdout}");
+
+    let out = run_libra_command(&["log", "-n", "1", "--name-only", "--oneline"], p);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("renamed.txt\n"), "{stdout}");
+    assert!(!stdout.contains("moved.txt"), "{stdout}");
+
+    let out = run_libra_command(&["log", "-n", "1", "--json"], p);
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let files = &json["data"]["commits"][0]["files"];
+    assert_eq!(files[0]["status"], "renamed");
+    assert_eq!(files[0]["old_path"], "moved.txt");
+    assert_eq!(files[0]["path"], "renamed.txt");
+}
+
 #[tokio::test]
 #[serial]
 async fn test_log_corrupt_head_reference_returns_repo_corrupt() {
@@ -299,6 +486,75 @@ fn test_log_json_output_includes_commit_list() {
     assert!(json["data"]["commits"][0]["files"].as_array().is_some());
 }
 
+#[test]
+fn test_log_json_streams_two_commit_history_as_one_document() {
+    let repo = create_committed_repo_via_cli();
+    fs::write(repo.path().join("tracked.txt"), "tracked\nmore\n").unwrap();
+    let output = run_libra_command(&["add", "tracked.txt"], repo.path());
+    assert_cli_success(&output, "failed to stage second change");
+    let output = run_libra_command(
+        &[
+            "commit",
+            "-m",
+            "second\n\nexplain the change",
+            "--no-verify",
+        ],
+        repo.path(),
+    );
+    assert_cli_success(&output, "failed to create second commit");
+
+    let output = run_libra_command(&["log", "--json"], repo.path());
+    assert_cli_success(&output, "log --json failed");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["ok"], true);
+    assert_eq!(json["command"], "log");
+    assert_eq!(json["data"]["total"], 2);
+    let commits = json["data"]["commits"].as_array().expect("commits array");
+    assert_eq!(commits.len(), 2);
+
+    let (newest, root) = (&commits[0], &commits[1]);
+    assert_eq!(newest["subject"], "second");
+    assert_eq!(
+        newest["body"].as_str().map(str::trim),
+        Some("explain the change")
+    );
+    assert_eq!(root["subject"], "base");
+    assert_eq!(newest["parents"], serde_json::json!([root["hash"].clone()]));
+    assert_eq!(root["parents"], serde_json::json!([]));
+    for commit in commits {
+        assert_eq!(commit["author_name"], "Test User");
+        assert_eq!(commit["author_email"], "test@example.com");
+        assert_eq!(commit["committer_name"], "Test User");
+        assert_eq!(commit["committer_email"], "test@example.com");
+        assert!(
+            commit["author_date"]
+                .as_str()
+                .is_some_and(|d| !d.is_empty())
+        );
+        assert!(
+            commit["committer_date"]
+                .as_str()
+                .is_some_and(|d| !d.is_empty())
+        );
+    }
+    assert_eq!(newest["files"][0]["path"], "tracked.txt");
+    assert_eq!(newest["files"][0]["status"], "modified");
+
+    // The compact layout is the same document on a single line.
+    let output = run_libra_command(&["--json=compact", "log"], repo.path());
+    assert_cli_success(&output, "log --json=compact failed");
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    assert_eq!(stdout.trim_end().lines().count(), 1, "stdout: {stdout}");
+    assert_eq!(parse_json_stdout(&output), json);
+
+    // A walk that matches nothing still closes the envelope.
+    let output = run_libra_command(&["log", "--json", "--author", "nobody"], repo.path());
+    assert_cli_success(&output, "log --json --author failed");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["data"]["commits"], serde_json::json!([]));
+    assert_eq!(json["data"]["total"], 0);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_log_quiet_does_not_initialize_pager() {
@@ -825,6 +1081,8 @@ async fn test_log_patch_no_pathspec() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -859,6 +1117,8 @@ async fn test_log_patch_no_pathspec() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -954,6 +1214,8 @@ async fn test_log_patch_with_pathspec() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1088,6 +1350,8 @@ async fn test_log_stat() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1121,6 +1385,8 @@ async fn test_log_stat() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1182,6 +1448,8 @@ async fn test_log_patch_with_stat_shows_diffstat_before_patch() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1215,6 +1483,8 @@ async fn test_log_patch_with_stat_shows_diffstat_before_patch() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1294,6 +1564,8 @@ async fn test_log_stat_with_modifications() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1327,6 +1599,8 @@ async fn test_log_stat_with_modifications() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1539,6 +1813,8 @@ async fn test_log_graph_simple_chain() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1572,6 +1848,8 @@ async fn test_log_graph_simple_chain() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1623,6 +1901,8 @@ async fn test_log_stat_and_graph_combined() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1759,6 +2039,8 @@ async fn test_log_double_dash_disables_short_number_rewrite() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1793,6 +2075,8 @@ async fn test_log_double_dash_disables_short_number_rewrite() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1982,17 +2266,17 @@ fn test_log_json_oneline_flag_does_not_alter_schema() {
 #[test]
 fn test_log_args_grep() {
     let args = LogArgs::parse_from(["libra", "--grep", "fix"]);
-    assert_eq!(args.grep, Some("fix".to_string()));
+    assert_eq!(args.grep, vec!["fix".to_string()]);
 
     let args = LogArgs::parse_from(["libra"]);
-    assert_eq!(args.grep, None);
+    assert!(args.grep.is_empty());
 }
 
 // Test grep combined with other arguments
 #[test]
 fn test_grep_with_other_args() {
     let args = LogArgs::parse_from(["libra", "--grep", "feature", "--oneline", "-n", "5"]);
-    assert_eq!(args.grep, Some("feature".to_string()));
+    assert_eq!(args.grep, vec!["feature".to_string()]);
     assert!(args.oneline);
     assert_eq!(args.number, Some(5));
 }
@@ -2001,14 +2285,14 @@ fn test_grep_with_other_args() {
 #[test]
 fn test_grep_case_sensitive() {
     let args = LogArgs::parse_from(["libra", "--grep", "FIX"]);
-    assert_eq!(args.grep, Some("FIX".to_string()));
+    assert_eq!(args.grep, vec!["FIX".to_string()]);
 }
 
 // Test empty string grep
 #[test]
 fn test_grep_empty_string() {
     let args = LogArgs::parse_from(["libra", "--grep", ""]);
-    assert_eq!(args.grep, Some("".to_string()));
+    assert_eq!(args.grep, vec![String::new()]);
 }
 
 // Test graph with grep combination
@@ -2016,7 +2300,7 @@ fn test_grep_empty_string() {
 fn test_graph_with_grep() {
     let args = LogArgs::parse_from(["libra", "--graph", "--grep", "fix"]);
     assert!(args.graph);
-    assert_eq!(args.grep, Some("fix".to_string()));
+    assert_eq!(args.grep, vec!["fix".to_string()]);
 }
 
 // Integration test: verify actual filtering behavior
@@ -2043,6 +2327,8 @@ async fn test_log_grep_filtering() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2077,6 +2363,8 @@ async fn test_log_grep_filtering() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2111,6 +2399,8 @@ async fn test_log_grep_filtering() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2200,6 +2490,8 @@ async fn test_log_reverse_outputs_oldest_first() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2224,6 +2516,8 @@ async fn test_log_reverse_outputs_oldest_first() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2264,6 +2558,8 @@ async fn test_log_range_excludes_start_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2289,6 +2585,8 @@ async fn test_log_range_excludes_start_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2328,6 +2626,8 @@ async fn test_log_all_includes_branches() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2350,6 +2650,7 @@ async fn test_log_all_includes_branches() {
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -2381,6 +2682,8 @@ async fn test_log_all_includes_branches() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2423,6 +2726,8 @@ async fn test_log_follow_detects_rename() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2448,6 +2753,8 @@ async fn test_log_follow_detects_rename() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2466,6 +2773,80 @@ async fn test_log_follow_detects_rename() {
     );
 }
 
+#[test]
+fn test_log_follow_tracks_similar_content_across_renames() {
+    use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+    let repo = create_committed_repo_via_cli();
+    let body: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+    let commit_all = |message: &str| {
+        assert_cli_success(&run_libra_command(&["add", "-A"], repo.path()), "add -A");
+        let commit = run_libra_command(&["commit", "--no-verify", "-m", message], repo.path());
+        assert_cli_success(&commit, message);
+    };
+
+    std::fs::write(repo.path().join("first.txt"), &body).unwrap();
+    commit_all("create first");
+    std::fs::write(repo.path().join("first.txt"), format!("{body}line 21\n")).unwrap();
+    commit_all("edit first");
+    std::fs::write(repo.path().join("other.txt"), "unrelated\n").unwrap();
+    commit_all("unrelated change");
+
+    // An exact rename, then a rename that also edits the content.
+    std::fs::rename(
+        repo.path().join("first.txt"),
+        repo.path().join("second.txt"),
+    )
+    .unwrap();
+    commit_all("exact rename");
+    std::fs::remove_file(repo.path().join("second.txt")).unwrap();
+    std::fs::write(
+        repo.path().join("third.txt"),
+        format!(
+            "{}line 21 edited\n",
+            body.replace("line 3\n", "line three\n")
+        ),
+    )
+    .unwrap();
+    commit_all("rename with edits");
+
+    let subjects = |args: &[&str]| -> Vec<String> {
+        let mut argv = vec!["log", "--format=%s"];
+        argv.extend_from_slice(args);
+        let output = run_libra_command(&argv, repo.path());
+        assert_cli_success(&output, &format!("log {args:?}"));
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    };
+
+    assert_eq!(
+        subjects(&["--follow", "third.txt"]),
+        vec![
+            "rename with edits",
+            "exact rename",
+            "edit first",
+            "create first"
+        ]
+    );
+    assert_eq!(
+        subjects(&["--follow", "third.txt", "--reverse"]),
+        vec![
+            "create first",
+            "edit first",
+            "exact rename",
+            "rename with edits"
+        ]
+    );
+
+    // Dissimilar content is not a rename: history stops at the new file.
+    std::fs::remove_file(repo.path().join("third.txt")).unwrap();
+    std::fs::write(repo.path().join("fourth.txt"), "something else entirely\n").unwrap();
+    commit_all("replace third");
+    assert_eq!(subjects(&["--follow", "fourth.txt"]), vec!["replace third"]);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_log_line_range_flag_accepted() {
@@ -2488,6 +2869,8 @@ async fn test_log_line_range_flag_accepted() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2976,6 +3359,8 @@ async fn commit_file(path: &str, content: &str, message: &str) -> String {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -245,3 +245,48 @@ fn diff_three_dot_uses_merge_base() {
         "A...B should NOT include the default branch's change (it is on the A side): {diff}"
     );
 }
+
+/// `--octopus` folds the bases pairwise across every commit, so the result is
+/// the ancestor shared by all of them rather than by the first two.
+///
+/// ```text
+///        c0
+///       /  \
+///      x    c
+///     / \
+///    a   b
+/// ```
+#[test]
+fn octopus_finds_the_base_shared_by_all_commits() {
+    let repo = tempdir().unwrap();
+    assert_cli_success(&run_libra_command(&["init"], repo.path()), "init");
+    let tree = out_trim(&run_libra_command(&["write-tree"], repo.path()));
+
+    let c0 = craft_commit(&repo, &tree, &[], "c0");
+    let x = craft_commit(&repo, &tree, &[&c0], "x");
+    let a = craft_commit(&repo, &tree, &[&x], "a");
+    let b = craft_commit(&repo, &tree, &[&x], "b");
+    let c = craft_commit(&repo, &tree, &[&c0], "c");
+
+    let pair = run_libra_command(&["merge-base", &a, &b], repo.path());
+    assert_eq!(out_trim(&pair), x, "pairwise base of a and b is x");
+
+    let out = run_libra_command(&["merge-base", "--octopus", &a, &b, &c], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "octopus merge-base failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(out_trim(&out), c0, "only c0 is shared by a, b, and c");
+
+    // A single commit is its own octopus base.
+    let single = run_libra_command(&["merge-base", "--octopus", &a], repo.path());
+    assert_eq!(out_trim(&single), a);
+
+    let conflicting = run_libra_command(
+        &["merge-base", "--octopus", "--is-ancestor", &a, &b],
+        repo.path(),
+    );
+    assert_eq!(conflicting.status.code(), Some(128));
+}
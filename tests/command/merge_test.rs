@@ -13,8 +13,8 @@ use libra::{
 use serial_test::serial;
 
 use super::{
-    assert_cli_success, create_committed_repo_via_cli, parse_cli_error_stderr, parse_json_stdout,
-    run_libra_command,
+    assert_cli_success, create_committed_repo_via_cli, merge_state_row, parse_cli_error_stderr,
+    parse_json_stdout, run_libra_command,
 };
 
 fn commit_file(repo: &Path, file: &str, content: &str, message: &str) {
@@ -522,6 +522,202 @@ fn test_merge_no_commit_then_continue() {
     );
 }
 
+/// Branch `feat` (commits "feat one", "feat two") off `main`, then add a
+/// `main` commit so the merge is a real three-way merge.
+fn diverged_feat_repo() -> tempfile::TempDir {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["checkout", "-b", "feat"], p),
+        "checkout -b feat",
+    );
+    commit_file(p, "feat.txt", "feat content", "feat one");
+    commit_file(p, "feat2.txt", "feat content 2", "feat two");
+    assert_cli_success(
+        &run_libra_command(&["checkout", "main"], p),
+        "checkout main",
+    );
+    commit_file(p, "main.txt", "main content", "main commit");
+    repo
+}
+
+#[test]
+fn test_merge_no_commit_stages_merge_and_continue_uses_dash_m() {
+    let repo = diverged_feat_repo();
+    let p = repo.path();
+    let before = run_libra_command(&["rev-parse", "HEAD"], p);
+    let before_head = String::from_utf8_lossy(&before.stdout).trim().to_string();
+
+    let merge = run_libra_command(&["merge", "--no-commit", "-m", "Integrate feat", "feat"], p);
+    assert_cli_success(&merge, "merge --no-commit -m");
+
+    // No new commit: HEAD is unchanged and the merged paths are staged.
+    let mid = run_libra_command(&["rev-parse", "HEAD"], p);
+    assert_eq!(String::from_utf8_lossy(&mid.stdout).trim(), before_head);
+    let staged = run_libra_command(&["diff", "--cached", "--name-only"], p);
+    assert_cli_success(&staged, "diff --cached --name-only");
+    let staged = String::from_utf8_lossy(&staged.stdout);
+    assert!(
+        staged.contains("feat.txt") && staged.contains("feat2.txt"),
+        "merged paths should be staged: {staged}"
+    );
+
+    // `-m` survives the stop and becomes the finished commit's message.
+    assert_cli_success(
+        &run_libra_command(&["merge", "--continue"], p),
+        "merge --continue",
+    );
+    let log = run_libra_command(&["log", "-n", "1", "--pretty=%s"], p);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Integrate feat"
+    );
+}
+
+/// `main` plus topics `a` and `b`, each branched from the `base` commit;
+/// `a` writes `a_file`, `b` writes `b_file` (contents as given).
+fn octopus_repo(a_file: &str, b_file: &str) -> tempfile::TempDir {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    for (branch, file) in [("a", a_file), ("b", b_file)] {
+        assert_cli_success(&run_libra_command(&["branch", branch], p), "branch");
+        assert_cli_success(
+            &run_libra_command(&["checkout", branch], p),
+            "checkout topic",
+        );
+        commit_file(
+            p,
+            file,
+            &format!("{branch} content\n"),
+            &format!("{branch} work"),
+        );
+        assert_cli_success(
+            &run_libra_command(&["checkout", "main"], p),
+            "checkout main",
+        );
+    }
+    repo
+}
+
+#[test]
+fn test_merge_octopus_creates_commit_with_parent_per_head() {
+    let repo = octopus_repo("a.txt", "b.txt");
+    let p = repo.path();
+    let head_before = run_libra_command(&["rev-parse", "HEAD"], p);
+    let head_before = String::from_utf8_lossy(&head_before.stdout)
+        .trim()
+        .to_string();
+
+    let out = run_libra_command(&["--json", "merge", "a", "b"], p);
+    assert_cli_success(&out, "merge a b");
+    let json = parse_json_stdout(&out);
+    assert_eq!(json["data"]["strategy"], "octopus");
+    let parents = json["data"]["parents"].as_array().unwrap();
+    assert_eq!(parents.len(), 3, "HEAD plus one parent per branch");
+    assert_eq!(parents[0], head_before.as_str());
+
+    assert_eq!(
+        std::fs::read_to_string(p.join("a.txt")).unwrap(),
+        "a content\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(p.join("b.txt")).unwrap(),
+        "b content\n"
+    );
+    let log = run_libra_command(&["log", "-n", "1", "--pretty=%s"], p);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Merge branches 'a' and 'b' into main"
+    );
+}
+
+#[test]
+fn test_merge_octopus_conflict_writes_nothing() {
+    let repo = octopus_repo("same.txt", "same.txt");
+    let p = repo.path();
+    let head_before = run_libra_command(&["rev-parse", "HEAD"], p);
+
+    let out = run_libra_command(&["merge", "a", "b"], p);
+    let (stderr, report) = parse_cli_error_stderr(&out.stderr);
+    assert_eq!(out.status.code(), Some(128));
+    assert_eq!(report.error_code, "LBR-CONFLICT-002");
+    assert!(
+        stderr.contains("merging 'b' conflicts in same.txt"),
+        "stderr: {stderr}"
+    );
+
+    let head_after = run_libra_command(&["rev-parse", "HEAD"], p);
+    assert_eq!(head_after.stdout, head_before.stdout, "HEAD must not move");
+    assert!(!p.join("same.txt").exists(), "worktree must be untouched");
+    assert!(merge_state_row(p).is_none(), "no merge state is recorded");
+}
+
+#[test]
+fn test_merge_octopus_rejects_no_commit() {
+    let repo = octopus_repo("a.txt", "b.txt");
+    let out = run_libra_command(&["merge", "--no-commit", "a", "b"], repo.path());
+    assert_eq!(out.status.code(), Some(129));
+    let (stderr, _) = parse_cli_error_stderr(&out.stderr);
+    assert!(
+        stderr.contains("--no-commit cannot be used when merging more than one branch"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn test_merge_default_message_names_branch() {
+    let repo = diverged_feat_repo();
+    let p = repo.path();
+
+    assert_cli_success(&run_libra_command(&["merge", "feat"], p), "merge feat");
+    let log = run_libra_command(&["log", "-n", "1", "--pretty=%s"], p);
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).trim(),
+        "Merge branch 'feat' into main"
+    );
+}
+
+#[test]
+fn test_merge_log_appends_merged_commit_subjects() {
+    let repo = diverged_feat_repo();
+    let p = repo.path();
+
+    assert_cli_success(
+        &run_libra_command(&["merge", "--log", "feat"], p),
+        "merge --log",
+    );
+    let show = run_libra_command(&["cat-file", "-p", "HEAD"], p);
+    assert_cli_success(&show, "cat-file -p HEAD");
+    let body = String::from_utf8_lossy(&show.stdout);
+    assert!(
+        body.contains("Merge branch 'feat' into main\n\n* feat:\n"),
+        "--log should add the branch header: {body}"
+    );
+    assert!(
+        body.contains("  feat one") && body.contains("  feat two"),
+        "--log should list the merged subjects: {body}"
+    );
+    assert!(
+        !body.contains("main commit"),
+        "HEAD's own commits are not merged: {body}"
+    );
+
+    // `--log=1` keeps one subject and marks the truncation.
+    let repo = diverged_feat_repo();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["merge", "--log=1", "feat"], p),
+        "merge --log=1",
+    );
+    let show = run_libra_command(&["cat-file", "-p", "HEAD"], p);
+    let body = String::from_utf8_lossy(&show.stdout);
+    assert!(
+        body.contains("* feat:\n  feat "),
+        "one subject listed: {body}"
+    );
+    assert!(body.contains("\n  ..."), "truncation marker: {body}");
+}
+
 #[tokio::test]
 #[serial]
 async fn test_merge_same_file_non_overlapping_edits_merges_without_conflict() {
@@ -571,7 +767,7 @@ async fn test_merge_same_file_non_overlapping_edits_merges_without_conflict() {
         "clean same-file merge must not leave conflict markers: {merged}"
     );
     assert!(
-        !temp_path.join(".libra").join("merge-state.json").exists(),
+        merge_state_row(temp_path).is_none(),
         "clean same-file merge must not leave merge state"
     );
 
@@ -587,6 +783,63 @@ async fn test_merge_same_file_non_overlapping_edits_merges_without_conflict() {
     );
 }
 
+/// A criss-cross history has two merge bases. Each single base (and the root)
+/// makes both sides look like they changed the same line, so only the virtual
+/// base built from merging the two LCAs lets this merge succeed cleanly.
+#[test]
+#[serial]
+fn test_merge_criss_cross_uses_virtual_merge_base() {
+    let temp_repo = create_committed_repo_via_cli();
+    let p = temp_repo.path();
+    let lines = |first: &str, last: &str| format!("{first}\nl2\nl3\nl4\n{last}\n");
+
+    commit_file(p, "f.txt", &lines("l1", "l5"), "base");
+    assert_cli_success(&run_libra_command(&["branch", "side"], p), "branch side");
+
+    commit_file(p, "f.txt", &lines("M", "l5"), "main edits first line");
+    assert_cli_success(
+        &run_libra_command(&["branch", "main-1"], p),
+        "branch main-1",
+    );
+    assert_cli_success(
+        &run_libra_command(&["checkout", "side"], p),
+        "checkout side",
+    );
+    commit_file(p, "f.txt", &lines("l1", "S"), "side edits last line");
+    assert_cli_success(
+        &run_libra_command(&["branch", "side-1"], p),
+        "branch side-1",
+    );
+
+    // Criss-cross: each side merges the other's first commit.
+    assert_cli_success(&run_libra_command(&["merge", "main-1"], p), "merge main-1");
+    assert_cli_success(
+        &run_libra_command(&["checkout", "main"], p),
+        "checkout main",
+    );
+    assert_cli_success(&run_libra_command(&["merge", "side-1"], p), "merge side-1");
+
+    commit_file(p, "f.txt", &lines("M", "S2"), "main edits last line");
+    assert_cli_success(
+        &run_libra_command(&["checkout", "side"], p),
+        "checkout side",
+    );
+    commit_file(p, "f.txt", &lines("M2", "S"), "side edits first line");
+    assert_cli_success(
+        &run_libra_command(&["checkout", "main"], p),
+        "checkout main",
+    );
+
+    let bases = run_libra_command(&["merge-base", "--all", "main", "side"], p);
+    assert_cli_success(&bases, "merge-base --all");
+    assert_eq!(String::from_utf8_lossy(&bases.stdout).lines().count(), 2);
+
+    let output = run_libra_command(&["merge", "side"], p);
+    assert_cli_success(&output, "criss-cross merge");
+    let merged = std::fs::read_to_string(p.join("f.txt")).expect("read merged file");
+    assert_eq!(merged, lines("M2", "S2"));
+}
+
 #[test]
 #[serial]
 fn test_merge_diverged_nested_directory_file_survives_three_way() {
@@ -622,6 +875,54 @@ fn test_merge_diverged_nested_directory_file_survives_three_way() {
     );
 }
 
+#[cfg(unix)]
+#[test]
+fn test_merge_three_way_keeps_executable_bit_and_symlinks() {
+    use std::os::unix::fs::{PermissionsExt, symlink};
+
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["checkout", "-b", "feature"], p),
+        "checkout -b feature",
+    );
+    std::fs::write(p.join("run.sh"), "#!/bin/sh\n").unwrap();
+    std::fs::set_permissions(p.join("run.sh"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    symlink("run.sh", p.join("link")).unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "run.sh", "link"], p),
+        "add script and link",
+    );
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "script", "--no-verify"], p),
+        "commit script",
+    );
+    assert_cli_success(
+        &run_libra_command(&["checkout", "main"], p),
+        "checkout main",
+    );
+    commit_file(p, "main.txt", "main\n", "main change");
+
+    let output = run_libra_command(&["merge", "feature"], p);
+    assert_cli_success(&output, "three-way merge");
+    let mode = std::fs::metadata(p.join("run.sh"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_ne!(mode & 0o111, 0, "run.sh lost its executable bit: {mode:o}");
+    assert!(
+        std::fs::symlink_metadata(p.join("link"))
+            .unwrap()
+            .file_type()
+            .is_symlink(),
+        "link was written as a regular file"
+    );
+    assert_eq!(
+        std::fs::read_link(p.join("link")).unwrap(),
+        Path::new("run.sh")
+    );
+}
+
 #[test]
 #[serial]
 /// Test JSON envelope for a clean three-way merge.
@@ -703,6 +1004,13 @@ fn test_merge_conflict_writes_markers_status_hints_and_abort_restores() {
         "checkout main",
     );
     commit_file(temp_path, "tracked.txt", "main change\n", "main change");
+    let rev_parse = |rev: &str| {
+        let output = run_libra_command(&["rev-parse", rev], temp_path);
+        assert_cli_success(&output, "rev-parse");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    let main_head = rev_parse("HEAD");
+    let feature_head = rev_parse("feature");
 
     let output = run_libra_command(&["merge", "feature"], temp_path);
     let (stderr, report) = parse_cli_error_stderr(&output.stderr);
@@ -730,6 +1038,16 @@ fn test_merge_conflict_writes_markers_status_hints_and_abort_restores() {
         status_stdout.contains("You are in the middle of a merge with 'feature'."),
         "status should mention merge state, stdout: {status_stdout}"
     );
+    assert!(
+        status_stdout.contains("You have unmerged paths."),
+        "stdout: {status_stdout}"
+    );
+    assert_eq!(
+        merge_state_row(temp_path),
+        Some((main_head.clone(), feature_head)),
+        "merge state should record the pre-merge HEAD and MERGE_HEAD"
+    );
+    assert_eq!(rev_parse("ORIG_HEAD"), main_head);
     assert!(status_stdout.contains("libra merge --continue"));
     assert!(status_stdout.contains("libra merge --abort"));
 
@@ -739,8 +1057,9 @@ fn test_merge_conflict_writes_markers_status_hints_and_abort_restores() {
         std::fs::read_to_string(temp_path.join("tracked.txt")).expect("read restored file"),
         "main change\n"
     );
+    assert_eq!(rev_parse("HEAD"), main_head, "abort should restore HEAD");
     assert!(
-        !temp_path.join(".libra").join("merge-state.json").exists(),
+        merge_state_row(temp_path).is_none(),
         "abort should remove merge state"
     );
 }
@@ -786,6 +1105,10 @@ async fn test_merge_continue_after_resolving_conflict_creates_two_parent_commit(
         status_stdout.contains("all conflicts fixed"),
         "status should acknowledge staged conflict resolution, stdout: {status_stdout}"
     );
+    assert!(
+        status_stdout.contains("All conflicts fixed but you are still merging."),
+        "stdout: {status_stdout}"
+    );
     let continued = run_libra_command(&["merge", "--continue"], temp_path);
     assert_cli_success(&continued, "merge continue");
     let stdout = String::from_utf8_lossy(&continued.stdout);
@@ -805,7 +1128,7 @@ async fn test_merge_continue_after_resolving_conflict_creates_two_parent_commit(
         std::fs::read_to_string(temp_path.join("tracked.txt")).expect("read resolved file"),
         "resolved change\n"
     );
-    assert!(!temp_path.join(".libra").join("merge-state.json").exists());
+    assert!(merge_state_row(temp_path).is_none());
 }
 
 #[test]
@@ -884,7 +1207,7 @@ fn test_merge_dirty_worktree_refuses_before_state() {
     assert_eq!(report.error_code, "LBR-CONFLICT-002");
     assert!(report.message.contains("uncommitted changes"));
     assert!(
-        !temp_path.join(".libra").join("merge-state.json").exists(),
+        merge_state_row(temp_path).is_none(),
         "dirty refusal should not create merge state"
     );
 }
@@ -931,7 +1254,7 @@ fn test_merge_untracked_overwrite_refuses_before_head_update() {
         std::fs::read_to_string(temp_path.join("clobber.txt")).expect("read untracked file"),
         "untracked local\n"
     );
-    assert!(!temp_path.join(".libra").join("merge-state.json").exists());
+    assert!(merge_state_row(temp_path).is_none());
 }
 
 /// `libra merge --help` surfaces the EXAMPLES banner so users see the
@@ -994,7 +1317,7 @@ fn test_merge_no_edit_accepts_default_message() {
     assert_cli_success(&output, "merge feature --no-edit");
     let log = run_libra_command(&["log", "--oneline", "-n", "1"], temp_path);
     assert!(
-        String::from_utf8_lossy(&log.stdout).contains("Merge feature into main"),
+        String::from_utf8_lossy(&log.stdout).contains("Merge branch 'feature' into main"),
         "merge commit landed with the default message: {:?}",
         String::from_utf8_lossy(&log.stdout)
     );
@@ -1542,6 +1865,36 @@ fn test_merge_conflict_is_line_level() {
     );
 }
 
+/// A conflicted path marked `binary` in `.gitattributes` keeps our content
+/// byte-for-byte: conflict markers would corrupt it.
+#[test]
+#[serial]
+fn test_merge_binary_attribute_conflict_keeps_ours_without_markers() {
+    let temp_repo = create_committed_repo_via_cli();
+    let p = temp_repo.path();
+
+    commit_file(p, ".gitattributes", "*.dat binary\n", "attributes");
+    commit_file(p, "asset.dat", "base\n", "base asset");
+    assert_cli_success(&run_libra_command(&["branch", "feature"], p), "branch");
+    assert_cli_success(
+        &run_libra_command(&["checkout", "feature"], p),
+        "co feature",
+    );
+    commit_file(p, "asset.dat", "feature\n", "feature asset");
+    assert_cli_success(&run_libra_command(&["checkout", "main"], p), "co main");
+    commit_file(p, "asset.dat", "main\n", "main asset");
+
+    let out = run_libra_command(&["merge", "feature"], p);
+    assert_eq!(out.status.code(), Some(128), "merge conflict exits 128");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("warning: Cannot merge binary files: asset.dat"),
+        "binary conflict warns: {stderr}"
+    );
+    let body = std::fs::read_to_string(p.join("asset.dat")).expect("read asset");
+    assert_eq!(body, "main\n", "ours is kept verbatim: {body:?}");
+}
+
 /// Build a one-line both-modified conflict repo (`shared.txt`) with `feature`
 /// diverging from `main`, without running the merge yet.
 fn create_diverged_repo_for_conflict() -> tempfile::TempDir {
@@ -1612,7 +1965,7 @@ fn test_merge_conflict_style_invalid_rejected() {
         "actionable error names the bad value: {stderr}"
     );
     assert!(
-        !p.join(".libra").join("merge-state.json").exists(),
+        merge_state_row(p).is_none(),
         "no merge state is left behind when the style is rejected"
     );
     let body = std::fs::read_to_string(p.join("shared.txt")).expect("read file");
@@ -1678,7 +2031,7 @@ fn test_merge_dry_run_fast_forward_writes_nothing() {
         !p.join("file.txt").exists(),
         "worktree must not receive the feature file"
     );
-    assert!(!p.join(".libra").join("merge-state.json").exists());
+    assert!(merge_state_row(p).is_none());
 }
 
 #[test]
@@ -1730,7 +2083,7 @@ fn test_merge_dry_run_clean_three_way_writes_no_objects() {
         objects_before,
         "a dry-run must not write objects (auto-merged blobs stay in memory)"
     );
-    assert!(!p.join(".libra").join("merge-state.json").exists());
+    assert!(merge_state_row(p).is_none());
     assert_eq!(
         std::fs::read_to_string(p.join("shared.txt")).unwrap(),
         "top\nl1\nl2\nMAIN\nbottom\n",
@@ -1762,7 +2115,7 @@ fn test_merge_dry_run_conflict_exits_1_and_writes_nothing() {
     );
 
     assert_eq!(head_commit(p), head_before, "HEAD must not move");
-    assert!(!p.join(".libra").join("merge-state.json").exists());
+    assert!(merge_state_row(p).is_none());
     let body = std::fs::read_to_string(p.join("shared.txt")).unwrap();
     assert!(
         !body.contains("<<<<<<<"),
@@ -1838,7 +2191,7 @@ fn test_merge_restart_regenerates_fresh_conflict() {
         "fresh markers regenerated, user edits discarded: {body:?}"
     );
     assert!(
-        p.join(".libra").join("merge-state.json").exists(),
+        merge_state_row(p).is_some(),
         "a fresh merge state exists after restart"
     );
     assert_eq!(head_commit(p), head_before, "HEAD is back at orig_head");
@@ -1884,7 +2237,7 @@ fn test_merge_restart_refuses_staged_no_commit_merge() {
         &run_libra_command(&["merge", "--no-commit", "feature"], p),
         "clean --no-commit merge",
     );
-    assert!(p.join(".libra").join("merge-state.json").exists());
+    assert!(merge_state_row(p).is_some());
     let head_before = head_commit(p);
 
     let out = run_libra_command(&["merge", "--restart"], p);
@@ -1896,10 +2249,7 @@ fn test_merge_restart_refuses_staged_no_commit_merge() {
     );
     // The staged no-commit merge is untouched and still finishable.
     assert_eq!(head_commit(p), head_before, "HEAD untouched");
-    assert!(
-        p.join(".libra").join("merge-state.json").exists(),
-        "staged merge state preserved"
-    );
+    assert!(merge_state_row(p).is_some(), "staged merge state preserved");
     assert_cli_success(
         &run_libra_command(&["merge", "--continue"], p),
         "staged merge still finishable",
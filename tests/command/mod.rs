@@ -215,6 +215,39 @@ fn loose_object_path(repo: &Path, hash: &str) -> std::path::PathBuf {
         .join(&hash[2..])
 }
 
+/// The in-progress merge recorded in the repository database, as
+/// `(orig_head, merge_head)`. The query runs on its own thread and runtime so
+/// both plain and `#[tokio::test]` tests can call it.
+fn merge_state_row(repo: &Path) -> Option<(String, String)> {
+    use sea_orm::{ConnectionTrait, Database, DatabaseBackend, Statement};
+
+    let db_url = format!(
+        "sqlite://{}?mode=rwc",
+        repo.join(".libra/libra.db").display()
+    );
+    std::thread::spawn(move || {
+        tokio::runtime::Runtime::new()
+            .expect("failed to create tokio runtime")
+            .block_on(async {
+                let conn = Database::connect(db_url).await.expect("connect repo db");
+                let row = conn
+                    .query_one(Statement::from_string(
+                        DatabaseBackend::Sqlite,
+                        "SELECT orig_head, merge_head FROM merge_state LIMIT 1".to_string(),
+                    ))
+                    .await
+                    .ok()
+                    .flatten()?;
+                Some((
+                    row.try_get_by_index(0).expect("orig_head"),
+                    row.try_get_by_index(1).expect("merge_head"),
+                ))
+            })
+    })
+    .join()
+    .expect("merge_state query thread panicked")
+}
+
 /// Initialize a repository through the CLI to exercise the real process entrypoint.
 fn init_repo_via_cli(repo: &Path) {
     fs::create_dir_all(repo).expect("failed to create repository directory");
@@ -297,16 +330,20 @@ mod code_thread_id_test;
 mod commit_autosquash_test;
 mod commit_editor_test;
 mod commit_error_test;
+mod commit_hooks_test;
 mod commit_json_test;
 mod commit_test;
+mod commit_tree_test;
 mod completions_test;
 mod config_test;
+mod credential_helper_test;
 mod credential_test;
 mod describe_long_test;
 mod describe_test;
 mod diff_plumbing_test;
 mod diff_test;
 mod dirty_test;
+mod export_snapshot_test;
 mod fast_export_test;
 mod fast_import_test;
 mod fetch_test;
@@ -365,16 +402,19 @@ mod sandbox_status_test;
 mod schema_upgrade_test;
 mod service_test;
 mod shortlog_test;
+mod show_branch_test;
 mod show_ref_abbrev_test;
 mod show_ref_alias_test;
 mod show_ref_deref_pattern_test;
 mod show_ref_exclude_existing_test;
 mod show_ref_test;
 mod show_test;
+mod sparse_checkout_test;
 mod stash_test;
 mod status_error_test;
 mod status_json_test;
 mod status_test;
+mod submodule_test;
 mod switch_error_test;
 mod switch_json_test;
 mod switch_test;
@@ -383,8 +423,10 @@ mod tag_test;
 mod update_index_test;
 mod update_ref_test;
 mod usage_help_test;
+mod verify_commit_test;
 mod verify_pack_stat_test;
 mod verify_pack_test;
+mod verify_tag_test;
 #[cfg(all(unix, feature = "worktree-fuse"))]
 mod worktree_fuse_test;
 mod worktree_test;
@@ -37,6 +37,8 @@ async fn stage_file(path: &str, content: &str) {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 }
@@ -45,6 +45,7 @@ async fn test_open_remote_origin() {
     // Test explicit remote
     open::execute_safe(
         open::OpenArgs {
+            target: None,
             remote: Some("origin".to_string()),
         },
         &output,
@@ -53,12 +54,19 @@ async fn test_open_remote_origin() {
     .expect("opening explicit origin remote should succeed");
 
     // Test default remote should find origin
-    open::execute_safe(open::OpenArgs { remote: None }, &output)
-        .await
-        .expect("opening default remote should succeed");
+    open::execute_safe(
+        open::OpenArgs {
+            target: None,
+            remote: None,
+        },
+        &output,
+    )
+    .await
+    .expect("opening default remote should succeed");
 
     let error = open::execute_safe(
         open::OpenArgs {
+            target: None,
             remote: Some("nonexistent".to_string()),
         },
         &output,
@@ -85,9 +93,15 @@ async fn test_open_no_remote() {
         ..OutputConfig::default()
     };
 
-    let error = open::execute_safe(open::OpenArgs { remote: None }, &output)
-        .await
-        .expect_err("opening without a configured remote should fail");
+    let error = open::execute_safe(
+        open::OpenArgs {
+            target: None,
+            remote: None,
+        },
+        &output,
+    )
+    .await
+    .expect_err("opening without a configured remote should fail");
     assert_eq!(error.stable_code(), StableErrorCode::RepoStateInvalid);
     assert_eq!(error.exit_code(), 128);
     assert!(
@@ -291,3 +305,134 @@ fn test_open_json_output_keeps_explicit_https_url() {
     );
     assert_eq!(json["data"]["launched"], false);
 }
+
+#[test]
+fn test_open_subcommands_build_forge_pages() {
+    let repo = create_committed_repo_via_cli();
+    let add_remote = run_libra_command(
+        &[
+            "remote",
+            "add",
+            "origin",
+            "git@gitlab.com:group/project.git",
+        ],
+        repo.path(),
+    );
+    assert_cli_success(&add_remote, "failed to add gitlab origin");
+    let output = run_libra_command(&["rev-parse", "HEAD"], repo.path());
+    assert_cli_success(&output, "rev-parse HEAD");
+    let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let output = run_libra_command(&["--json", "open", "commit", "HEAD"], repo.path());
+    assert_cli_success(&output, "open commit HEAD");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["data"]["target"], "commit");
+    assert_eq!(json["data"]["forge"], "gitlab");
+    assert_eq!(
+        json["data"]["web_url"],
+        format!("https://gitlab.com/group/project/-/commit/{head}")
+    );
+
+    let output = run_libra_command(&["--json", "open", "pr"], repo.path());
+    assert_cli_success(&output, "open pr");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["data"]["target"], "pr");
+    assert_eq!(
+        json["data"]["web_url"],
+        "https://gitlab.com/group/project/-/merge_requests/new?merge_request%5Bsource_branch%5D=main"
+    );
+
+    let output = run_libra_command(
+        &["--json", "open", "file", "tracked.txt", "--line", "1"],
+        repo.path(),
+    );
+    assert_cli_success(&output, "open file --line");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["data"]["target"], "file");
+    assert_eq!(
+        json["data"]["web_url"],
+        "https://gitlab.com/group/project/-/blob/main/tracked.txt#L1"
+    );
+    assert_eq!(json["data"]["launched"], false);
+
+    let output = run_libra_command(&["--json", "open", "commit", "no-such-rev"], repo.path());
+    assert!(!output.status.success(), "unknown revision must fail");
+    let output = run_libra_command(&["--json", "open", "file", "../outside.txt"], repo.path());
+    assert!(!output.status.success(), "paths outside the repo must fail");
+}
+
+#[test]
+fn test_open_pr_requires_a_branch() {
+    let repo = create_committed_repo_via_cli();
+    let add_remote = run_libra_command(
+        &["remote", "add", "origin", "https://github.com/o/r.git"],
+        repo.path(),
+    );
+    assert_cli_success(&add_remote, "failed to add origin");
+    let output = run_libra_command(&["rev-parse", "HEAD"], repo.path());
+    let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert_cli_success(
+        &run_libra_command(&["switch", "--detach", &head], repo.path()),
+        "detach HEAD",
+    );
+
+    let output = run_libra_command(&["open", "pr"], repo.path());
+    assert!(
+        !output.status.success(),
+        "open pr on a detached HEAD must fail"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("HEAD is detached"), "stderr: {stderr}");
+
+    // A file on a detached HEAD is shown at the commit instead.
+    let output = run_libra_command(&["--json", "open", "file", "tracked.txt"], repo.path());
+    assert_cli_success(&output, "open file on detached HEAD");
+    let json = parse_json_stdout(&output);
+    assert_eq!(
+        json["data"]["web_url"],
+        format!("https://github.com/o/r/blob/{head}/tracked.txt")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_open_uses_libra_browser_override() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = create_committed_repo_via_cli();
+    let script = repo.path().join("browser.sh");
+    let log = repo.path().join("browser.log");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\necho \"$@\" > '{}'\n", log.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut command = base_libra_command(&["open", "https://codeberg.org/o/r.git"], repo.path());
+    let output = command
+        .env(
+            open::LIBRA_BROWSER_ENV,
+            format!("{} --new-window", script.display()),
+        )
+        .output()
+        .expect("failed to run open with LIBRA_BROWSER");
+    assert_cli_success(&output, "open with LIBRA_BROWSER");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Opening https://codeberg.org/o/r"));
+
+    // The browser is spawned in the background; wait for it to record its args.
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let recorded = loop {
+        if let Ok(contents) = fs::read_to_string(&log)
+            && !contents.is_empty()
+        {
+            break contents;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "browser was not launched"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    assert_eq!(recorded.trim(), "--new-window https://codeberg.org/o/r");
+}
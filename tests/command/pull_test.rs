@@ -15,7 +15,8 @@ use tempfile::{TempDir, tempdir};
 
 use super::{
     assert_cli_success, configure_identity_via_cli, create_committed_repo_via_cli,
-    init_repo_via_cli, parse_cli_error_stderr, parse_json_stdout, run_libra_command,
+    init_repo_via_cli, merge_state_row, parse_cli_error_stderr, parse_json_stdout,
+    run_libra_command,
 };
 
 fn git(args: &[&str], cwd: &Path) {
@@ -209,6 +210,36 @@ async fn test_pull_fast_forward_updates_head_from_tracking_remote() {
     );
 }
 
+/// Scenario: an upstream recorded through `branch --set-upstream-to` (rather
+/// than raw config writes) is what a bare `libra pull` fetches and merges.
+#[tokio::test]
+#[serial]
+async fn test_pull_without_arguments_uses_branch_set_upstream_target() {
+    let (_temp_root, remote_dir, work_dir, branch) = create_remote_fixture();
+    let remote_head = git_stdout(&["rev-parse", "HEAD"], &work_dir);
+
+    let local_repo = tempdir().expect("failed to create local repo");
+    init_repo_via_cli(local_repo.path());
+    configure_identity_via_cli(local_repo.path());
+    let remote_output = run_libra_command(
+        &["remote", "add", "origin", remote_dir.to_str().unwrap()],
+        local_repo.path(),
+    );
+    assert_cli_success(&remote_output, "remote add");
+    let upstream = format!("--set-upstream-to=origin/{branch}");
+    let set = run_libra_command(&["branch", &upstream], local_repo.path());
+    assert_cli_success(&set, "branch --set-upstream-to");
+
+    let output = run_libra_command(&["pull"], local_repo.path());
+    assert_cli_success(&output, "pull with configured upstream");
+
+    let _guard = ChangeDirGuard::new(local_repo.path());
+    let head = Head::current_commit()
+        .await
+        .expect("pull should update HEAD to the upstream commit");
+    assert_eq!(head.to_string(), remote_head);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_pull_ff_only_fast_forward_updates_head_from_tracking_remote() {
@@ -353,11 +384,7 @@ async fn test_pull_squash_stages_merge_without_committing() {
         "squash should apply the merged worktree"
     );
     assert!(
-        !local_repo
-            .path()
-            .join(".libra")
-            .join("merge-state.json")
-            .exists(),
+        merge_state_row(local_repo.path()).is_none(),
         "pull --squash must not record merge state"
     );
 
@@ -442,11 +469,7 @@ async fn test_pull_no_commit_stops_before_commit_and_records_merge_state() {
         "no-commit should apply the merged worktree"
     );
     assert!(
-        local_repo
-            .path()
-            .join(".libra")
-            .join("merge-state.json")
-            .exists(),
+        merge_state_row(local_repo.path()).is_some(),
         "pull --no-commit must record merge state for `merge --continue`"
     );
 
@@ -542,11 +565,7 @@ async fn test_pull_ff_only_diverged_remote_rejects_without_changing_head_or_work
         "ff-only rejection must not apply remote worktree changes"
     );
     assert!(
-        !local_repo
-            .path()
-            .join(".libra")
-            .join("merge-state.json")
-            .exists(),
+        merge_state_row(local_repo.path()).is_none(),
         "ff-only rejection must not create merge state"
     );
 }
@@ -991,3 +1010,144 @@ fn pull_no_progress_flag_is_accepted() {
         "--no-progress is accepted by the parser: {err}"
     );
 }
+
+/// Pull once from the fixture remote, then commit `remote_file` upstream and
+/// `local_file` locally so the two histories diverge.
+fn create_diverged_pull_fixture(remote_file: &str, local_file: &str) -> (TempDir, TempDir, String) {
+    let (temp_root, remote_dir, work_dir, branch) = create_remote_fixture();
+
+    let local_repo = tempdir().expect("failed to create local repo");
+    init_repo_via_cli(local_repo.path());
+    configure_identity_via_cli(local_repo.path());
+    configure_pull_tracking(local_repo.path(), &remote_dir, &branch);
+    assert_cli_success(
+        &run_libra_command(&["pull"], local_repo.path()),
+        "initial pull",
+    );
+
+    let remote_head = push_remote_commit(
+        &work_dir,
+        &branch,
+        remote_file,
+        "remote change\n",
+        "remote update",
+    );
+
+    fs::write(local_repo.path().join(local_file), "local change\n").expect("write local change");
+    assert_cli_success(
+        &run_libra_command(&["add", local_file], local_repo.path()),
+        "stage local change",
+    );
+    assert_cli_success(
+        &run_libra_command(
+            &["commit", "-m", "local update", "--no-verify"],
+            local_repo.path(),
+        ),
+        "commit local change",
+    );
+
+    (temp_root, local_repo, remote_head)
+}
+
+#[test]
+#[serial]
+fn test_pull_rebase_config_replays_local_commit() {
+    let (_temp_root, local_repo, remote_head) =
+        create_diverged_pull_fixture("remote.txt", "local.txt");
+    assert_cli_success(
+        &run_libra_command(&["config", "pull.rebase", "true"], local_repo.path()),
+        "set pull.rebase",
+    );
+
+    let output = run_libra_command(&["--json", "pull"], local_repo.path());
+    assert_cli_success(&output, "pull with pull.rebase=true");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON on stdout");
+    let data = &parsed["data"];
+    assert!(data["merge"].is_null(), "pull.rebase=true must not merge");
+    assert_eq!(data["rebase"]["status"], "completed");
+    assert_eq!(data["rebase"]["replay_count"], 1);
+    assert_ne!(data["rebase"]["commit"], remote_head.as_str());
+
+    // `--no-rebase` on the command line overrides the config.
+    let (_temp_root, local_repo, _) = create_diverged_pull_fixture("remote.txt", "local.txt");
+    assert_cli_success(
+        &run_libra_command(&["config", "pull.rebase", "true"], local_repo.path()),
+        "set pull.rebase",
+    );
+    let output = run_libra_command(&["--json", "pull", "--no-rebase"], local_repo.path());
+    assert_cli_success(&output, "pull --no-rebase with pull.rebase=true");
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).expect("JSON on stdout");
+    assert!(parsed["data"]["rebase"].is_null());
+    assert_eq!(parsed["data"]["merge"]["strategy"], "three-way");
+}
+
+#[test]
+#[serial]
+fn test_pull_ff_only_config_rejects_diverged_history() {
+    let (_temp_root, local_repo, _) = create_diverged_pull_fixture("remote.txt", "local.txt");
+    assert_cli_success(
+        &run_libra_command(&["config", "pull.ff", "only"], local_repo.path()),
+        "set pull.ff",
+    );
+
+    let output = run_libra_command(&["--json", "pull"], local_repo.path());
+    let report = parse_json_stderr(&output.stderr);
+    assert_eq!(output.status.code(), Some(128));
+    assert_eq!(report["error_code"], "LBR-CONFLICT-002");
+    assert_eq!(report["details"]["phase"], "merge");
+    assert!(
+        !local_repo.path().join("remote.txt").exists(),
+        "pull.ff=only rejection must not apply remote changes"
+    );
+
+    // An explicit `--rebase` wins over `pull.ff=only`.
+    let output = run_libra_command(&["pull", "--rebase"], local_repo.path());
+    assert_cli_success(&output, "pull --rebase with pull.ff=only");
+    assert!(local_repo.path().join("remote.txt").exists());
+}
+
+#[test]
+#[serial]
+fn test_pull_invalid_ff_config_is_rejected_before_fetch() {
+    let (_temp_root, local_repo, _) = create_diverged_pull_fixture("remote.txt", "local.txt");
+    assert_cli_success(
+        &run_libra_command(&["config", "pull.ff", "sometimes"], local_repo.path()),
+        "set pull.ff",
+    );
+
+    let output = run_libra_command(&["--json", "pull"], local_repo.path());
+    let report = parse_json_stderr(&output.stderr);
+    assert_eq!(output.status.code(), Some(129));
+    assert_eq!(report["error_code"], "LBR-CLI-002");
+    assert!(
+        report["message"]
+            .as_str()
+            .is_some_and(|text| text.contains("pull.ff")),
+        "error should name the bad key: {report}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_pull_rebase_conflict_leaves_resumable_rebase() {
+    let (_temp_root, local_repo, _) = create_diverged_pull_fixture("shared.txt", "shared.txt");
+    let local_head = {
+        let _guard = ChangeDirGuard::new(local_repo.path());
+        Head::current_commit().await.expect("local HEAD")
+    };
+
+    let output = run_libra_command(&["--json", "pull", "--rebase"], local_repo.path());
+    let report = parse_json_stderr(&output.stderr);
+    assert_ne!(output.status.code(), Some(0));
+    assert_eq!(report["error_code"], "LBR-CONFLICT-001");
+    assert_eq!(report["details"]["phase"], "rebase");
+
+    let status = run_libra_command(&["rebase", "--abort"], local_repo.path());
+    assert_cli_success(&status, "rebase --abort after pull --rebase conflict");
+    let _guard = ChangeDirGuard::new(local_repo.path());
+    assert_eq!(
+        Head::current_commit().await.expect("HEAD after abort"),
+        local_head,
+        "aborting the stopped rebase restores the local branch"
+    );
+}
@@ -3,9 +3,12 @@
 //! **Layer:** L1 (most tests). `test_push_invalid_remote` and `test_push_force_with_local_changes`
 //! are L2 — require `LIBRA_TEST_GITHUB_TOKEN` or are `#[cfg(unix)]`.
 
-#[cfg(unix)]
-use std::path::{Path, PathBuf};
-use std::{env, fs, process::Command, time::Duration};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
 
 use clap::Parser;
 use libra::{
@@ -19,7 +22,9 @@ use serial_test::serial;
 use tempfile::TempDir;
 use tokio::{process::Command as TokioCommand, time::timeout};
 
-use super::{create_committed_repo_via_cli, parse_cli_error_stderr, run_libra_command};
+use super::{
+    assert_cli_success, create_committed_repo_via_cli, parse_cli_error_stderr, run_libra_command,
+};
 
 fn libra_command(cwd: &std::path::Path) -> Command {
     let home = cwd.join(".libra-test-home");
@@ -303,7 +308,7 @@ async fn test_push_force_flag_parsing() {
 #[tokio::test]
 #[serial]
 async fn test_push_file_remote_fails_without_reflog() {
-    // local file remotes are not supported; ensure we fail loudly and avoid reflog writes
+    // local paths that hold no Libra repository cannot be pushed to; ensure we fail loudly and avoid reflog writes
     let remote_dir = tempfile::tempdir().unwrap();
     let remote_path = remote_dir.path();
 
@@ -1931,9 +1936,9 @@ fn test_push_help_lists_examples_banner() {
 #[test]
 fn push_no_verify_flag_is_accepted() {
     let repo = create_committed_repo_via_cli();
-    // `--no-verify` (bypass the pre-push hook) parses and reaches the runtime.
-    // Libra runs no client-side pre-push hook, so it is an accepted no-op; with
-    // no configured remote it fails at the push-destination check, NOT at clap.
+    // `--no-verify` (bypass the pre-push hook) parses and reaches the runtime;
+    // with no configured remote it fails at the push-destination check, NOT at
+    // clap.
     let output = run_libra_command(&["push", "--no-verify"], repo.path());
     assert!(!output.status.success(), "push without a remote fails");
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1961,3 +1966,276 @@ fn push_no_progress_flag_is_accepted() {
         "--no-progress reaches the push-destination runtime check: {stderr}"
     );
 }
+
+/// Create a bare Libra repository at `<parent>/remote.git` and add it to
+/// `repo` as `origin`.
+fn add_bare_libra_remote(repo: &Path, parent: &Path) -> PathBuf {
+    let bare = parent.join("remote.git");
+    let output = run_libra_command(
+        &["init", "--bare", "--vault", "false", bare.to_str().unwrap()],
+        parent,
+    );
+    assert_cli_success(&output, "bare init");
+    let output = run_libra_command(&["remote", "add", "origin", bare.to_str().unwrap()], repo);
+    assert_cli_success(&output, "remote add");
+    bare
+}
+
+fn rev_parse(repo: &Path, rev: &str) -> String {
+    let output = run_libra_command(&["rev-parse", rev], repo);
+    assert_cli_success(&output, "rev-parse");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_push_to_local_bare_libra_repository_updates_branches_and_tags() {
+    let repo = create_committed_repo_via_cli();
+    let remote_parent = tempfile::tempdir().unwrap();
+    let bare = add_bare_libra_remote(repo.path(), remote_parent.path());
+
+    assert_cli_success(&run_libra_command(&["tag", "v1"], repo.path()), "tag v1");
+    let output = run_libra_command(&["push", "origin", "main", "v1"], repo.path());
+    assert_cli_success(&output, "push to bare repository");
+    let head = rev_parse(repo.path(), "HEAD");
+    assert_eq!(rev_parse(&bare, "main"), head);
+    assert_eq!(rev_parse(&bare, "v1"), head);
+    assert_eq!(rev_parse(repo.path(), "origin/main"), head);
+
+    // A clone of the bare repository sees the pushed history.
+    let clone_parent = tempfile::tempdir().unwrap();
+    let output = run_libra_command(
+        &["clone", bare.to_str().unwrap(), "copy"],
+        clone_parent.path(),
+    );
+    assert_cli_success(&output, "clone bare repository");
+    let copy = clone_parent.path().join("copy");
+    assert_eq!(
+        fs::read_to_string(copy.join("tracked.txt")).unwrap(),
+        "tracked\n"
+    );
+
+    // A new commit fast-forwards; deleting the tag removes it server-side.
+    fs::write(repo.path().join("tracked.txt"), "second\n").unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "tracked.txt"], repo.path()),
+        "add",
+    );
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "second", "--no-verify"], repo.path()),
+        "commit",
+    );
+    let output = run_libra_command(&["push", "origin", "main", ":refs/tags/v1"], repo.path());
+    assert_cli_success(&output, "fast-forward push");
+    assert_eq!(rev_parse(&bare, "main"), rev_parse(repo.path(), "HEAD"));
+    assert!(
+        !run_libra_command(&["rev-parse", "v1"], &bare)
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_push_tags_follow_tags_and_delete_against_libra_remote() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let remote_parent = tempfile::tempdir().unwrap();
+    let bare = add_bare_libra_remote(p, remote_parent.path());
+    let head = rev_parse(p, "HEAD");
+    let tag_exists = |name: &str| {
+        run_libra_command(&["rev-parse", name], &bare)
+            .status
+            .success()
+    };
+
+    assert_cli_success(&run_libra_command(&["tag", "light"], p), "tag light");
+    assert_cli_success(
+        &run_libra_command(&["tag", "-a", "v1", "-m", "release"], p),
+        "tag -a v1",
+    );
+
+    // `--follow-tags` adds only annotated tags reachable from the pushed refs.
+    assert_cli_success(
+        &run_libra_command(&["push", "--follow-tags", "origin", "main"], p),
+        "push --follow-tags",
+    );
+    assert_eq!(rev_parse(&bare, "v1"), head);
+    assert!(!tag_exists("light"), "lightweight tags are not followed");
+
+    // `--tags` pushes every local tag.
+    assert_cli_success(
+        &run_libra_command(&["push", "--tags", "origin"], p),
+        "push --tags",
+    );
+    assert_eq!(rev_parse(&bare, "light"), head);
+
+    // `--delete <tag>` removes the tag on the remote.
+    assert_cli_success(
+        &run_libra_command(&["push", "--delete", "origin", "light"], p),
+        "push --delete",
+    );
+    assert!(!tag_exists("light"), "deleted tag must be gone remotely");
+    assert_eq!(rev_parse(&bare, "v1"), head, "other tags are untouched");
+}
+
+#[test]
+fn test_push_without_refspec_uses_configured_upstream() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let remote_parent = tempfile::tempdir().unwrap();
+    let bare = add_bare_libra_remote(p, remote_parent.path());
+    assert_cli_success(
+        &run_libra_command(&["push", "origin", "main"], p),
+        "initial push",
+    );
+
+    // `switch -c --track` records origin/main as the new branch's upstream.
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "topic", "--track", "origin/main"], p),
+        "switch -c --track",
+    );
+    let merge = run_libra_command(&["config", "--get", "branch.topic.merge"], p);
+    assert_eq!(
+        String::from_utf8_lossy(&merge.stdout).trim(),
+        "refs/heads/main"
+    );
+    let remote = run_libra_command(&["config", "--get", "branch.topic.remote"], p);
+    assert_eq!(String::from_utf8_lossy(&remote.stdout).trim(), "origin");
+
+    fs::write(p.join("tracked.txt"), "topic\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "tracked.txt"], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "topic", "--no-verify"], p),
+        "commit",
+    );
+    let verbose = run_libra_command(&["branch", "-vv"], p);
+    assert_cli_success(&verbose, "branch -vv");
+    let verbose = String::from_utf8_lossy(&verbose.stdout);
+    assert!(
+        verbose
+            .lines()
+            .any(|line| line.contains("topic") && line.contains("[origin/main: ahead 1]")),
+        "branch -vv should show the upstream: {verbose}"
+    );
+
+    // A bare `push` sends topic to the upstream's branch, not refs/heads/topic.
+    assert_cli_success(&run_libra_command(&["push"], p), "push to upstream");
+    assert_eq!(rev_parse(&bare, "main"), rev_parse(p, "HEAD"));
+    assert!(
+        !run_libra_command(&["rev-parse", "topic"], &bare)
+            .status
+            .success(),
+        "push must not create refs/heads/topic on the remote"
+    );
+
+    // Without an upstream there is no remote to default to.
+    assert_cli_success(
+        &run_libra_command(&["branch", "--unset-upstream"], p),
+        "unset upstream",
+    );
+    let output = run_libra_command(&["push"], p);
+    assert_eq!(output.status.code(), Some(128));
+
+    assert_cli_success(
+        &run_libra_command(&["branch", "--set-upstream-to", "origin/main"], p),
+        "set upstream",
+    );
+    assert_cli_success(&run_libra_command(&["push"], p), "push after -u");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_push_hook_can_reject_pushes_to_main() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = create_committed_repo_via_cli();
+    let remote_parent = tempfile::tempdir().unwrap();
+    let bare = add_bare_libra_remote(repo.path(), remote_parent.path());
+
+    // The hook records its arguments and stdin, then refuses updates to main.
+    let hook = repo.path().join(".libra/hooks/pre-push");
+    fs::create_dir_all(hook.parent().unwrap()).unwrap();
+    fs::write(
+        &hook,
+        r#"#!/bin/sh
+echo "$1 $2" > pre-push.log
+while read local_ref local_oid remote_ref remote_oid; do
+    echo "$local_ref $local_oid $remote_ref $remote_oid" >> pre-push.log
+    if [ "$remote_ref" = "refs/heads/main" ]; then
+        echo "pushing to main is not allowed" >&2
+        exit 1
+    fi
+done
+"#,
+    )
+    .unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = run_libra_command(&["push", "origin", "main"], repo.path());
+    assert!(!output.status.success(), "the hook must block the push");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pre-push hook failed"), "stderr: {stderr}");
+    assert!(
+        stderr.contains("pushing to main is not allowed"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        !run_libra_command(&["rev-parse", "main"], &bare)
+            .status
+            .success(),
+        "nothing may reach the remote"
+    );
+
+    let head = rev_parse(repo.path(), "HEAD");
+    let log = fs::read_to_string(repo.path().join("pre-push.log")).unwrap();
+    let zero = "0".repeat(head.len());
+    let mut lines = log.lines();
+    let args_line = lines.next().unwrap_or_default();
+    assert!(
+        args_line.starts_with("origin ") && args_line.ends_with("remote.git"),
+        "hook arguments are the remote name and URL: {args_line}"
+    );
+    assert_eq!(
+        lines.collect::<Vec<_>>(),
+        vec![format!("refs/heads/main {head} refs/heads/main {zero}")]
+    );
+
+    // Other branches pass the policy; --no-verify skips the hook entirely.
+    let output = run_libra_command(&["push", "origin", "main:release"], repo.path());
+    assert_cli_success(&output, "push to release");
+    assert_eq!(rev_parse(&bare, "release"), head);
+    let output = run_libra_command(&["push", "--no-verify", "origin", "main"], repo.path());
+    assert_cli_success(&output, "push --no-verify");
+    assert_eq!(rev_parse(&bare, "main"), head);
+}
+
+#[test]
+fn test_push_to_local_non_bare_repository_refuses_checked_out_branch() {
+    let repo = create_committed_repo_via_cli();
+    let target = create_committed_repo_via_cli();
+    let output = run_libra_command(
+        &["remote", "add", "origin", target.path().to_str().unwrap()],
+        repo.path(),
+    );
+    assert_cli_success(&output, "remote add");
+    let target_head = rev_parse(target.path(), "HEAD");
+
+    let output = run_libra_command(&["push", "--force", "origin", "main"], repo.path());
+    assert!(
+        !output.status.success(),
+        "checked-out branch must be refused"
+    );
+    let (stderr, _) = parse_cli_error_stderr(&output.stderr);
+    assert!(
+        stderr.contains("branch is currently checked out"),
+        "stderr: {stderr}"
+    );
+    assert_eq!(rev_parse(target.path(), "HEAD"), target_head);
+
+    // Other branches are accepted.
+    let output = run_libra_command(&["push", "origin", "main:incoming"], repo.path());
+    assert_cli_success(&output, "push to another branch");
+    assert_eq!(
+        rev_parse(target.path(), "incoming"),
+        rev_parse(repo.path(), "HEAD")
+    );
+}
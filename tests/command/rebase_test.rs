@@ -388,6 +388,76 @@ fn test_rebase_preserves_executable_mode_in_rewritten_commit() {
     );
 }
 
+/// A submodule committed on the rebased branch survives the replay, both for
+/// a clean pick and through a conflict resolved with `--continue`.
+#[test]
+fn test_rebase_keeps_submodule_gitlink_through_conflict_and_continue() {
+    let lib = create_committed_repo_via_cli();
+    let pinned = {
+        let output = run_libra_command(&["rev-parse", "HEAD"], lib.path());
+        assert_cli_success(&output, "rev-parse submodule HEAD");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    let repo = create_cli_rebase_conflict_ready_repo();
+    let repo_path = repo.path();
+    // `feature` is checked out: record the submodule before the commit that
+    // conflicts with `main`, so the conflicted index carries the gitlink.
+    let output = run_libra_command(&["reset", "--hard", "HEAD~1"], repo_path);
+    assert_cli_success(&output, "drop the conflicting commit");
+    let output = run_libra_command(
+        &[
+            "submodule",
+            "add",
+            lib.path().to_str().unwrap(),
+            "vendor/lib",
+        ],
+        repo_path,
+    );
+    assert_cli_success(&output, "submodule add");
+    let output = run_libra_command(&["commit", "-m", "Add lib", "--no-verify"], repo_path);
+    assert_cli_success(&output, "commit submodule");
+    commit_file_via_cli(
+        repo_path,
+        "conflict.txt",
+        "feature\n",
+        "Feature modifies conflict.txt",
+    );
+
+    let output = run_libra_command(&["rebase", "main"], repo_path);
+    assert_eq!(
+        output.status.code(),
+        Some(128),
+        "expected a conflict, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let output = run_libra_command(&["ls-files", "--stage"], repo_path);
+    assert_cli_success(&output, "ls-files --stage during conflict");
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains(&format!("160000 {pinned} 0\tvendor/lib")),
+        "conflicted index keeps the gitlink: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    fs::write(repo_path.join("conflict.txt"), "merged\n").expect("resolve conflict");
+    let output = run_libra_command(&["add", "conflict.txt"], repo_path);
+    assert_cli_success(&output, "stage resolution");
+    let output = run_libra_command(&["rebase", "--continue"], repo_path);
+    assert_cli_success(&output, "rebase --continue with a gitlink in the index");
+
+    for rev in ["HEAD", "HEAD~1"] {
+        let output = run_libra_command(&["ls-tree", rev], repo_path);
+        assert_cli_success(&output, "ls-tree rebased commit");
+        assert!(
+            String::from_utf8_lossy(&output.stdout)
+                .contains(&format!("160000 commit {pinned}\tvendor/lib")),
+            "{rev} keeps the submodule: {}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+    assert!(repo_path.join("vendor/lib").is_dir());
+}
+
 fn create_cli_rebase_fast_forward_repo() -> tempfile::TempDir {
     let repo = tempdir().expect("failed to create temp repo");
     let repo_path = repo.path();
@@ -471,6 +541,259 @@ fn test_rebase_autosquash_folds_fixup_commit() {
     );
 }
 
+/// `--no-autosquash` after `--autosquash` wins: the fixup commit is replayed
+/// as an ordinary pick instead of being folded.
+#[test]
+fn test_rebase_no_autosquash_overrides_autosquash() {
+    let repo = tempdir().expect("failed to create temp repo");
+    let repo_path = repo.path();
+    init_repo_via_cli(repo_path);
+    configure_identity_via_cli(repo_path);
+
+    commit_file_via_cli(repo_path, "base.txt", "base\n", "Base");
+
+    let output = run_libra_command(&["switch", "-c", "feature"], repo_path);
+    assert_cli_success(&output, "failed to create feature branch");
+    commit_file_via_cli(repo_path, "feature.txt", "feature\n", "Feature adds file");
+    commit_file_via_cli(
+        repo_path,
+        "feature.txt",
+        "feature\nfixup\n",
+        "fixup! Feature adds file",
+    );
+
+    let output = run_libra_command(&["switch", "main"], repo_path);
+    assert_cli_success(&output, "failed to switch to main");
+    commit_file_via_cli(repo_path, "main.txt", "main\n", "Main adds file");
+
+    let output = run_libra_command(&["switch", "feature"], repo_path);
+    assert_cli_success(&output, "failed to switch to feature");
+
+    let output = run_libra_command(
+        &["rebase", "--autosquash", "--no-autosquash", "main"],
+        repo_path,
+    );
+    assert_cli_success(&output, "rebase with --no-autosquash should succeed");
+
+    let output = run_libra_command(&["log", "--oneline", "-n", "4"], repo_path);
+    assert_cli_success(&output, "log after rebase");
+    let log = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        log.contains("fixup! Feature adds file"),
+        "--no-autosquash should keep the fixup commit, got: {log}"
+    );
+}
+
+/// `rebase -i --autosquash` hands the editor a todo list whose fixup commit is
+/// already moved under its target and marked `fixup`; saving it unchanged
+/// folds the fixup like the non-interactive form.
+#[cfg(unix)]
+#[test]
+fn test_interactive_rebase_autosquash_marks_fixup_in_todo() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = tempdir().expect("failed to create temp repo");
+    let repo_path = repo.path();
+    init_repo_via_cli(repo_path);
+    configure_identity_via_cli(repo_path);
+
+    commit_file_via_cli(repo_path, "base.txt", "base\n", "Base");
+
+    let output = run_libra_command(&["switch", "-c", "feature"], repo_path);
+    assert_cli_success(&output, "failed to create feature branch");
+    commit_file_via_cli(repo_path, "feature.txt", "feature\n", "Feature adds file");
+    commit_file_via_cli(repo_path, "other.txt", "other\n", "Unrelated change");
+    commit_file_via_cli(
+        repo_path,
+        "feature.txt",
+        "feature\nfixup\n",
+        "fixup! Feature adds file",
+    );
+
+    let output = run_libra_command(&["switch", "main"], repo_path);
+    assert_cli_success(&output, "failed to switch to main");
+    commit_file_via_cli(repo_path, "main.txt", "main\n", "Main adds file");
+
+    let output = run_libra_command(&["switch", "feature"], repo_path);
+    assert_cli_success(&output, "failed to switch to feature");
+
+    // The scripted editor keeps the todo list as generated and saves a copy.
+    let scratch = tempdir().expect("failed to create scratch dir");
+    let seen = scratch.path().join("seen-todo");
+    let editor = scratch.path().join("editor.sh");
+    fs::write(
+        &editor,
+        format!("#!/bin/sh\ncp \"$1\" '{}'\n", seen.display()),
+    )
+    .unwrap();
+    let mut perms = fs::metadata(&editor).unwrap().permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&editor, perms).unwrap();
+
+    let output = run_libra_command_with_stdin_and_env(
+        &["rebase", "-i", "--autosquash", "main"],
+        repo_path,
+        "",
+        &[("GIT_EDITOR", editor.to_str().unwrap())],
+    );
+    assert_cli_success(&output, "interactive autosquash rebase should succeed");
+
+    let todo = fs::read_to_string(&seen).expect("editor should have seen the todo list");
+    let commands = todo
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.splitn(3, ' ');
+            let action = fields.next().unwrap_or_default().to_string();
+            let subject = fields.nth(1).unwrap_or_default().to_string();
+            (action, subject)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        commands,
+        vec![
+            ("pick".to_string(), "Feature adds file".to_string()),
+            ("fixup".to_string(), "fixup! Feature adds file".to_string()),
+            ("pick".to_string(), "Unrelated change".to_string()),
+        ],
+        "todo list: {todo}"
+    );
+
+    let output = run_libra_command(&["log", "--oneline", "-n", "4"], repo_path);
+    assert_cli_success(&output, "log after interactive rebase");
+    let log = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !log.contains("fixup! Feature adds file"),
+        "the fixup commit should be folded, got: {log}"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("feature.txt")).unwrap(),
+        "feature\nfixup\n"
+    );
+}
+
+/// `commit --fixup` of an earlier commit lands after an unrelated commit;
+/// `rebase --autosquash` must move it back next to its target (matched by
+/// subject) and fold it in, leaving the unrelated commit on top.
+#[test]
+fn test_commit_fixup_then_autosquash_reorders_and_folds_into_target() {
+    let repo = tempdir().expect("failed to create temp repo");
+    let repo_path = repo.path();
+    init_repo_via_cli(repo_path);
+    configure_identity_via_cli(repo_path);
+
+    commit_file_via_cli(repo_path, "base.txt", "base\n", "Base");
+
+    let output = run_libra_command(&["switch", "-c", "feature"], repo_path);
+    assert_cli_success(&output, "failed to create feature branch");
+    commit_file_via_cli(repo_path, "first.txt", "first\n", "First feature commit");
+    commit_file_via_cli(repo_path, "second.txt", "second\n", "Second feature commit");
+
+    fs::write(repo_path.join("first.txt"), "first\nfixed\n").unwrap();
+    let output = run_libra_command(&["add", "first.txt"], repo_path);
+    assert_cli_success(&output, "failed to stage fixup change");
+    let output = run_libra_command(&["commit", "--fixup", "HEAD~1", "--no-verify"], repo_path);
+    assert_cli_success(&output, "commit --fixup should succeed");
+
+    let output = run_libra_command(&["switch", "main"], repo_path);
+    assert_cli_success(&output, "failed to switch to main");
+    commit_file_via_cli(repo_path, "main.txt", "main\n", "Main adds file");
+    let output = run_libra_command(&["switch", "feature"], repo_path);
+    assert_cli_success(&output, "failed to switch to feature");
+
+    let output = run_libra_command(&["rebase", "--autosquash", "main"], repo_path);
+    assert_cli_success(&output, "autosquash rebase should succeed");
+
+    let output = run_libra_command(&["log", "--oneline", "-n", "4"], repo_path);
+    assert_cli_success(&output, "log after autosquash rebase");
+    let subjects: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.split_once(' ').map_or(line, |(_, s)| s).to_string())
+        .collect();
+    assert_eq!(
+        subjects,
+        [
+            "Second feature commit",
+            "First feature commit",
+            "Main adds file",
+            "Base"
+        ],
+        "fixup should be folded into its target below the second commit"
+    );
+
+    // The folded change belongs to the first commit, not the second.
+    let output = run_libra_command(&["show", "HEAD~1:first.txt"], repo_path);
+    assert_cli_success(&output, "show first.txt at the rewritten target");
+    let shown = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        shown.contains("fixed"),
+        "target commit should carry the fixup change, got: {shown}"
+    );
+}
+
+/// `commit --squash -m` of an earlier commit is moved next to its target by
+/// `rebase --autosquash`; unlike a fixup, its message is kept in the folded
+/// commit.
+#[test]
+fn test_commit_squash_then_autosquash_folds_message_into_target() {
+    let repo = tempdir().expect("failed to create temp repo");
+    let repo_path = repo.path();
+    init_repo_via_cli(repo_path);
+    configure_identity_via_cli(repo_path);
+
+    commit_file_via_cli(repo_path, "base.txt", "base\n", "Base");
+
+    let output = run_libra_command(&["switch", "-c", "feature"], repo_path);
+    assert_cli_success(&output, "failed to create feature branch");
+    commit_file_via_cli(repo_path, "first.txt", "first\n", "First feature commit");
+    commit_file_via_cli(repo_path, "second.txt", "second\n", "Second feature commit");
+
+    fs::write(repo_path.join("first.txt"), "first\nsquashed\n").unwrap();
+    let output = run_libra_command(&["add", "first.txt"], repo_path);
+    assert_cli_success(&output, "failed to stage squash change");
+    let output = run_libra_command(
+        &[
+            "commit",
+            "--squash",
+            "HEAD~1",
+            "-m",
+            "Explain the squashed change",
+            "--no-verify",
+        ],
+        repo_path,
+    );
+    assert_cli_success(&output, "commit --squash should succeed");
+
+    let output = run_libra_command(&["rebase", "--autosquash", "main"], repo_path);
+    assert_cli_success(&output, "autosquash rebase should succeed");
+
+    let output = run_libra_command(&["log", "--oneline", "-n", "3"], repo_path);
+    assert_cli_success(&output, "log after autosquash rebase");
+    let subjects: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.split_once(' ').map_or(line, |(_, s)| s).to_string())
+        .collect();
+    assert_eq!(
+        subjects,
+        ["Second feature commit", "First feature commit", "Base"],
+        "squash commit should be folded into its target"
+    );
+
+    // The squash commit itself is gone (see subjects above), so its body can
+    // only survive inside the folded target.
+    let output = run_libra_command(&["log", "-n", "3"], repo_path);
+    assert_cli_success(&output, "log the folded history");
+    let message = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        message.contains("Explain the squashed change"),
+        "squash message should be kept in the folded commit, got: {message}"
+    );
+
+    let output = run_libra_command(&["show", "HEAD~1:first.txt"], repo_path);
+    assert_cli_success(&output, "show first.txt at the rewritten target");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("squashed"));
+}
+
 #[test]
 fn test_rebase_autosquash_keeps_unmatched_fixup_as_pick() {
     let repo = tempdir().expect("failed to create temp repo");
@@ -1226,6 +1549,8 @@ async fn test_basic_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1260,6 +1585,8 @@ async fn test_basic_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1310,6 +1637,8 @@ async fn test_basic_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1344,6 +1673,8 @@ async fn test_basic_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1393,6 +1724,8 @@ async fn test_basic_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1439,7 +1772,10 @@ async fn test_basic_rebase() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -1508,6 +1844,8 @@ async fn test_rebase_preserves_untracked_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1557,6 +1895,8 @@ async fn test_rebase_preserves_untracked_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1606,6 +1946,8 @@ async fn test_rebase_preserves_untracked_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1655,7 +1997,10 @@ async fn test_rebase_preserves_untracked_files() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -1696,6 +2041,8 @@ async fn test_rebase_already_up_to_date() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1730,6 +2077,8 @@ async fn test_rebase_already_up_to_date() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1777,7 +2126,10 @@ async fn test_rebase_already_up_to_date() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -1810,6 +2162,8 @@ async fn test_rebase_abort_when_no_rebase_in_progress() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1859,6 +2213,8 @@ async fn test_rebase_abort_when_no_rebase_in_progress() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1908,6 +2264,8 @@ async fn test_rebase_abort_when_no_rebase_in_progress() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1955,7 +2313,10 @@ async fn test_rebase_abort_when_no_rebase_in_progress() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -1981,7 +2342,10 @@ async fn test_rebase_abort_when_no_rebase_in_progress() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2032,6 +2396,8 @@ async fn test_rebase_abort_restores_branch_after_finalize_failure() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2080,6 +2446,8 @@ async fn test_rebase_abort_restores_branch_after_finalize_failure() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2129,6 +2497,8 @@ async fn test_rebase_abort_restores_branch_after_finalize_failure() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2175,7 +2545,10 @@ async fn test_rebase_abort_restores_branch_after_finalize_failure() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2229,7 +2602,10 @@ async fn test_rebase_abort_restores_branch_after_finalize_failure() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2278,6 +2654,8 @@ async fn test_rebase_continue_no_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2310,7 +2688,10 @@ async fn test_rebase_continue_no_rebase() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2341,6 +2722,8 @@ async fn test_rebase_skip_no_rebase() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2373,7 +2756,10 @@ async fn test_rebase_skip_no_rebase() {
         abort: false,
         skip: true,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2406,6 +2792,8 @@ async fn test_rebase_with_conflict_and_abort() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2459,6 +2847,8 @@ async fn test_rebase_with_conflict_and_abort() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2508,6 +2898,8 @@ async fn test_rebase_with_conflict_and_abort() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2554,7 +2946,10 @@ async fn test_rebase_with_conflict_and_abort() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2587,7 +2982,10 @@ async fn test_rebase_with_conflict_and_abort() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2651,6 +3049,8 @@ async fn test_rebase_binary_conflict_writes_markers() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2699,6 +3099,8 @@ async fn test_rebase_binary_conflict_writes_markers() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2747,6 +3149,8 @@ async fn test_rebase_binary_conflict_writes_markers() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2792,7 +3196,10 @@ async fn test_rebase_binary_conflict_writes_markers() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -2828,7 +3235,10 @@ async fn test_rebase_binary_conflict_writes_markers() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
     assert!(
@@ -2866,6 +3276,8 @@ async fn test_rebase_with_conflict_and_skip() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2920,6 +3332,8 @@ async fn test_rebase_with_conflict_and_skip() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -2959,6 +3373,8 @@ async fn test_rebase_with_conflict_and_skip() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3008,6 +3424,8 @@ async fn test_rebase_with_conflict_and_skip() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3054,7 +3472,10 @@ async fn test_rebase_with_conflict_and_skip() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3079,7 +3500,10 @@ async fn test_rebase_with_conflict_and_skip() {
         abort: false,
         skip: true,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3122,6 +3546,8 @@ async fn test_rebase_with_conflict_and_continue() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3175,6 +3601,8 @@ async fn test_rebase_with_conflict_and_continue() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3224,6 +3652,8 @@ async fn test_rebase_with_conflict_and_continue() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3270,7 +3700,10 @@ async fn test_rebase_with_conflict_and_continue() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3305,6 +3738,8 @@ async fn test_rebase_with_conflict_and_continue() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -3322,7 +3757,10 @@ async fn test_rebase_with_conflict_and_continue() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3380,6 +3818,8 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3430,6 +3870,8 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3469,6 +3911,8 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3504,6 +3948,8 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3553,6 +3999,8 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3599,7 +4047,10 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3625,7 +4076,10 @@ async fn test_rebase_multiple_commits_partial_conflict() {
         abort: false,
         skip: true,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3684,6 +4138,8 @@ async fn test_rebase_state_persistence() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3733,6 +4189,8 @@ async fn test_rebase_state_persistence() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3782,6 +4240,8 @@ async fn test_rebase_state_persistence() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3828,7 +4288,10 @@ async fn test_rebase_state_persistence() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3871,7 +4334,10 @@ async fn test_rebase_state_persistence() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -3914,6 +4380,8 @@ async fn test_rebase_fast_forward_branch_behind() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -3978,6 +4446,8 @@ async fn test_rebase_fast_forward_branch_behind() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4026,7 +4496,10 @@ async fn test_rebase_fast_forward_branch_behind() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -4071,6 +4544,8 @@ async fn test_rebase_fast_forward_blocks_dirty_workdir() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4135,6 +4610,8 @@ async fn test_rebase_fast_forward_blocks_dirty_workdir() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4184,7 +4661,10 @@ async fn test_rebase_fast_forward_blocks_dirty_workdir() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -4229,6 +4709,8 @@ async fn test_rebase_fast_forward_blocks_untracked_overwrite() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4293,6 +4775,8 @@ async fn test_rebase_fast_forward_blocks_untracked_overwrite() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4342,7 +4826,10 @@ async fn test_rebase_fast_forward_blocks_untracked_overwrite() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -4387,6 +4874,8 @@ async fn test_rebase_blocks_dirty_workdir_non_fast_forward() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4436,6 +4925,8 @@ async fn test_rebase_blocks_dirty_workdir_non_fast_forward() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4485,6 +4976,8 @@ async fn test_rebase_blocks_dirty_workdir_non_fast_forward() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4534,7 +5027,10 @@ async fn test_rebase_blocks_dirty_workdir_non_fast_forward() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -4586,6 +5082,8 @@ async fn test_rebase_conflict_preserves_non_conflicting_workdir() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4636,6 +5134,8 @@ async fn test_rebase_conflict_preserves_non_conflicting_workdir() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4685,6 +5185,8 @@ async fn test_rebase_conflict_preserves_non_conflicting_workdir() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4731,7 +5233,10 @@ async fn test_rebase_conflict_preserves_non_conflicting_workdir() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -4762,7 +5267,10 @@ async fn test_rebase_conflict_preserves_non_conflicting_workdir() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 }
@@ -4793,6 +5301,8 @@ async fn test_rebase_conflict_does_not_overwrite_untracked_paths() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4843,6 +5353,8 @@ async fn test_rebase_conflict_does_not_overwrite_untracked_paths() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4910,6 +5422,8 @@ async fn test_rebase_conflict_does_not_overwrite_untracked_paths() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -4958,7 +5472,10 @@ async fn test_rebase_conflict_does_not_overwrite_untracked_paths() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -4986,7 +5503,10 @@ async fn test_rebase_conflict_does_not_overwrite_untracked_paths() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 }
@@ -5017,6 +5537,8 @@ async fn test_rebase_continue_requires_resolution() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -5066,6 +5588,8 @@ async fn test_rebase_continue_requires_resolution() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -5115,6 +5639,8 @@ async fn test_rebase_continue_requires_resolution() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -5161,7 +5687,10 @@ async fn test_rebase_continue_requires_resolution() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -5188,7 +5717,10 @@ async fn test_rebase_continue_requires_resolution() {
         abort: false,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 
@@ -5215,7 +5747,10 @@ async fn test_rebase_continue_requires_resolution() {
         abort: true,
         skip: false,
         autosquash: false,
+        no_autosquash: false,
+        interactive: false,
         reapply_cherry_picks: false,
+        no_verify: false,
     })
     .await;
 }
@@ -5917,3 +6452,81 @@ fn test_rebase_empty_drop_survives_conflict_resume() {
         String::from_utf8_lossy(&after.stderr)
     );
 }
+
+/// A `pre-rebase` hook sees `<upstream> [<branch>]` and can refuse the rebase
+/// before the branch is checked out; `--no-verify` bypasses it.
+#[cfg(unix)]
+#[test]
+#[serial]
+fn test_pre_rebase_hook_blocks_rebasing_a_published_branch() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "topic"], p),
+        "branch topic",
+    );
+    std::fs::write(p.join("topic.txt"), "topic\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "topic.txt"], p), "add topic");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "topic work", "--no-verify"], p),
+        "commit topic",
+    );
+    assert_cli_success(&run_libra_command(&["switch", "main"], p), "switch main");
+    std::fs::write(p.join("main.txt"), "main\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "main.txt"], p), "add main");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "main work", "--no-verify"], p),
+        "commit main",
+    );
+
+    std::fs::write(p.join(".libra/published-branches"), "topic\n").unwrap();
+    let hook = p.join(".libra/hooks/pre-rebase");
+    std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook,
+        r#"#!/bin/sh
+echo "$@" > .libra/pre-rebase.log
+if [ -n "$2" ] && grep -qx "$2" .libra/published-branches; then
+    echo "refusing to rebase published branch $2" >&2
+    exit 1
+fi
+"#,
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let topic_before =
+        String::from_utf8_lossy(&run_libra_command(&["rev-parse", "topic"], p).stdout).into_owned();
+    let out = run_libra_command(&["rebase", "main", "topic"], p);
+    assert!(!out.status.success(), "the hook must refuse the rebase");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("pre-rebase hook refused to rebase")
+            && stderr.contains("refusing to rebase published branch topic"),
+        "stderr: {stderr}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(p.join(".libra/pre-rebase.log")).unwrap(),
+        "main topic\n"
+    );
+    let topic_after =
+        String::from_utf8_lossy(&run_libra_command(&["rev-parse", "topic"], p).stdout).into_owned();
+    assert_eq!(topic_before, topic_after, "topic must not be rewritten");
+    let branch = run_libra_command(&["branch", "--show-current"], p);
+    assert_eq!(
+        String::from_utf8_lossy(&branch.stdout).trim(),
+        "main",
+        "the branch is not checked out when the hook refuses"
+    );
+
+    let out = run_libra_command(&["rebase", "--no-verify", "main", "topic"], p);
+    assert_cli_success(&out, "rebase --no-verify");
+    let log =
+        String::from_utf8_lossy(&run_libra_command(&["log", "--pretty=%s"], p).stdout).into_owned();
+    assert_eq!(
+        log.lines().take(2).collect::<Vec<_>>(),
+        vec!["topic work", "main work"]
+    );
+}
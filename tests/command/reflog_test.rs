@@ -692,3 +692,101 @@ fn test_reflog_no_abbrev_shows_full_hash() {
     );
     assert!(full_hash.starts_with(ab_hash), "abbrev is a prefix of full");
 }
+
+/// Two commits on top of `base`, then `reset --hard HEAD~1`, so HEAD and
+/// `main` both end with a reset entry above the commit entries.
+fn repo_with_commits_and_reset() -> tempfile::TempDir {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    for (content, message) in [("second\n", "second"), ("third\n", "third")] {
+        fs::write(p.join("tracked.txt"), content).unwrap();
+        assert_cli_success(&run_libra_command(&["add", "tracked.txt"], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", message, "--no-verify"], p),
+            "commit",
+        );
+    }
+    assert_cli_success(
+        &run_libra_command(&["reset", "--hard", "HEAD~1"], p),
+        "reset --hard",
+    );
+    repo
+}
+
+/// `<selector>: <action>: <details>` for each line, dropping the hash.
+fn reflog_lines(output: &std::process::Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' ').map(|(_, rest)| rest.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_reflog_show_head_and_branch_after_commits_and_reset() {
+    let repo = repo_with_commits_and_reset();
+    let p = repo.path();
+
+    let head = run_libra_command(&["reflog", "show"], p);
+    assert_cli_success(&head, "reflog show");
+    let lines = reflog_lines(&head);
+    assert_eq!(lines[0], "HEAD@{0}: reset: moving to HEAD~1", "{lines:?}");
+    assert_eq!(lines[1], "HEAD@{1}: commit: third", "{lines:?}");
+    assert_eq!(lines[2], "HEAD@{2}: commit: second", "{lines:?}");
+
+    // HEAD@{0} names the commit HEAD was reset to.
+    let short_head = run_libra_command(&["rev-parse", "--short", "HEAD"], p);
+    let stdout = String::from_utf8_lossy(&head.stdout);
+    assert!(
+        stdout.starts_with(String::from_utf8_lossy(&short_head.stdout).trim()),
+        "{stdout}"
+    );
+
+    let branch = run_libra_command(&["reflog", "show", "main"], p);
+    assert_cli_success(&branch, "reflog show main");
+    let lines = reflog_lines(&branch);
+    assert_eq!(lines[0], "main@{0}: reset: moving to HEAD~1", "{lines:?}");
+    assert_eq!(lines[1], "main@{1}: commit: third", "{lines:?}");
+    assert_eq!(lines[2], "main@{2}: commit: second", "{lines:?}");
+}
+
+#[test]
+fn test_reflog_show_all_lists_every_ref() {
+    let repo = repo_with_commits_and_reset();
+    let p = repo.path();
+
+    let all = run_libra_command(&["reflog", "show", "--all"], p);
+    assert_cli_success(&all, "reflog show --all");
+    let lines = reflog_lines(&all);
+    assert_eq!(lines[0], "HEAD@{0}: reset: moving to HEAD~1", "{lines:?}");
+    assert!(
+        lines.contains(&"refs/heads/main@{0}: reset: moving to HEAD~1".to_string()),
+        "{lines:?}"
+    );
+    let first_branch = lines
+        .iter()
+        .position(|line| line.starts_with("refs/heads/main@{"))
+        .unwrap();
+    assert!(
+        lines[..first_branch]
+            .iter()
+            .all(|line| line.starts_with("HEAD@{")),
+        "HEAD entries come first: {lines:?}"
+    );
+
+    let json = run_libra_command(&["--json", "reflog", "show", "--all"], p);
+    assert_cli_success(&json, "json reflog show --all");
+    let json = parse_json_stdout(&json);
+    assert_eq!(json["data"]["all"], true);
+    assert!(json["data"]["ref_name"].is_null());
+    let selectors: Vec<&str> = json["data"]["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["selector"].as_str())
+        .collect();
+    assert!(selectors.contains(&"HEAD@{0}"), "{selectors:?}");
+    assert!(selectors.contains(&"refs/heads/main@{0}"), "{selectors:?}");
+
+    let conflict = run_libra_command(&["reflog", "show", "--all", "main"], p);
+    assert!(!conflict.status.success(), "a ref and --all conflict");
+}
@@ -392,6 +392,123 @@ async fn test_remote_rename_updates_branch_tracking() {
     );
 }
 
+/// Seed `origin` with a tracking ref, a cached remote HEAD, the default fetch
+/// refspec and a `main` upstream, as a fetch + `branch -u` would leave them.
+async fn seed_tracked_origin() {
+    remote::execute(RemoteCmds::Add {
+        name: "origin".into(),
+        url: "https://example.com/repo.git".into(),
+        fetch: false,
+        track: vec![],
+        master: None,
+        tags: false,
+        no_tags: false,
+        mirror: false,
+    })
+    .await;
+    ConfigKv::add(
+        "remote.origin.fetch",
+        "+refs/heads/*:refs/remotes/origin/*",
+        false,
+    )
+    .await
+    .unwrap();
+    ConfigKv::set("branch.main.remote", "origin", false)
+        .await
+        .unwrap();
+    ConfigKv::set("branch.main.merge", "refs/heads/main", false)
+        .await
+        .unwrap();
+    let oid = "1".repeat(40);
+    Branch::update_branch("refs/remotes/origin/main", &oid, Some("origin"))
+        .await
+        .unwrap();
+    Head::update(Head::Branch("main".into()), Some("origin")).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_remote_rename_moves_tracking_refs_and_refspec() {
+    let repo_dir = tempdir().unwrap();
+    test::setup_with_new_libra_in(repo_dir.path()).await;
+    let _guard = test::ChangeDirGuard::new(repo_dir.path());
+    seed_tracked_origin().await;
+
+    remote::execute_safe(
+        RemoteCmds::Rename {
+            old: "origin".into(),
+            new: "upstream".into(),
+        },
+        &OutputConfig::default(),
+    )
+    .await
+    .expect("remote rename should succeed");
+
+    assert!(
+        Branch::list_branches_result(Some("origin"))
+            .await
+            .unwrap()
+            .is_empty(),
+        "no tracking ref may stay under the old remote"
+    );
+    let moved: Vec<String> = Branch::list_branches_result(Some("upstream"))
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|branch| branch.name)
+        .collect();
+    assert_eq!(moved, vec!["refs/remotes/upstream/main".to_string()]);
+    assert!(
+        matches!(
+            Head::remote_current("upstream").await,
+            Some(Head::Branch(ref branch)) if branch == "main"
+        ),
+        "the cached remote HEAD follows the rename"
+    );
+    assert!(Head::remote_current("origin").await.is_none());
+
+    let refspecs: Vec<String> = ConfigKv::get_all("remote.upstream.fetch")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.value)
+        .collect();
+    assert_eq!(refspecs, vec!["+refs/heads/*:refs/remotes/upstream/*"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_remote_remove_deletes_tracking_refs_and_upstreams() {
+    let repo_dir = tempdir().unwrap();
+    test::setup_with_new_libra_in(repo_dir.path()).await;
+    let _guard = test::ChangeDirGuard::new(repo_dir.path());
+    seed_tracked_origin().await;
+
+    remote::execute_safe(
+        RemoteCmds::Remove {
+            name: "origin".into(),
+        },
+        &OutputConfig::default(),
+    )
+    .await
+    .expect("remote remove should succeed");
+
+    assert!(
+        Branch::list_branches_result(Some("origin"))
+            .await
+            .unwrap()
+            .is_empty(),
+        "remote remove must delete the remote-tracking refs"
+    );
+    assert!(Head::remote_current("origin").await.is_none());
+    for key in ["branch.main.remote", "branch.main.merge"] {
+        assert!(
+            ConfigKv::get(key).await.unwrap().is_none(),
+            "remote remove must drop {key}"
+        );
+    }
+}
+
 #[tokio::test]
 #[serial]
 async fn test_remote_rename_cascades_vault_ssh_keys() {
@@ -140,6 +140,8 @@ async fn test_remove_single_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -221,6 +223,8 @@ async fn test_remove_cached() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -283,6 +287,8 @@ async fn test_remove_directory_recursive() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -377,6 +383,8 @@ async fn test_remove_directory_without_recursive() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -470,6 +478,8 @@ async fn test_remove_modified_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -551,6 +561,8 @@ async fn test_remove_multiple_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -612,6 +624,8 @@ async fn test_remove_dry_run() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -682,6 +696,8 @@ async fn test_remove_dry_run_cached() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -741,6 +757,8 @@ async fn test_remove_dry_run_recursive() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -808,6 +826,8 @@ async fn test_remove_ignore_unmatch() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -865,6 +885,8 @@ async fn test_remove_pathspec_from_file_newline() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -915,6 +937,8 @@ async fn test_remove_pathspec_from_file_nul() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -965,6 +989,8 @@ async fn test_remove_pathspec_from_file_ignore_unmatch() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -998,6 +1024,43 @@ async fn test_remove_pathspec_from_file_ignore_unmatch() {
 /// and JSON forms before they hit one of `rm`'s strict
 /// conflicting-state safety errors. Cross-cutting `--help` EXAMPLES
 /// rollout per `docs/development/commands/_general.md` item B.
+#[test]
+fn test_remove_outside_sparse_checkout_requires_sparse_flag() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    for (path, content) in [("a/one.txt", "one\n"), ("b/two.txt", "two\n")] {
+        fs::create_dir_all(p.join(path).parent().unwrap()).unwrap();
+        fs::write(p.join(path), content).unwrap();
+    }
+    assert_cli_success(&run_libra_command(&["add", "."], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "dirs", "--no-verify"], p),
+        "commit",
+    );
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "set", "a"], p),
+        "sparse-checkout set",
+    );
+    assert!(!p.join("b/two.txt").exists());
+
+    let refused = run_libra_command(&["rm", "b/two.txt"], p);
+    assert_eq!(refused.status.code(), Some(128));
+    let stderr = String::from_utf8_lossy(&refused.stderr);
+    assert!(
+        stderr.contains("outside of your sparse-checkout definition"),
+        "stderr: {stderr}"
+    );
+    let ls = run_libra_command(&["ls-files", "b/two.txt"], p);
+    assert!(String::from_utf8_lossy(&ls.stdout).contains("b/two.txt"));
+
+    assert_cli_success(
+        &run_libra_command(&["rm", "--sparse", "b/two.txt"], p),
+        "rm --sparse",
+    );
+    let ls = run_libra_command(&["ls-files", "b/two.txt"], p);
+    assert_eq!(String::from_utf8_lossy(&ls.stdout).trim(), "");
+}
+
 #[test]
 fn test_rm_help_lists_examples_banner() {
     let repo = tempdir().expect("tempdir for rm --help");
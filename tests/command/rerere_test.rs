@@ -275,3 +275,56 @@ fn rerere_auto_replays_a_recurring_merge_conflict() {
         "rerere should have replayed the recorded merge resolution, got: {replayed:?}"
     );
 }
+
+#[test]
+fn rerere_auto_replays_a_recurring_rebase_conflict() {
+    // A conflict resolved during one rebase is resolved automatically when a
+    // *different* commit with the same change is rebased later: the marker
+    // labels name another commit, so only the normalised conflict id matches.
+    let repo = repo_with_cherry_pick_conflict();
+    let path = repo.path();
+    let base = rev_parse(path, "HEAD~1");
+    assert_cli_success(
+        &run_libra_command(&["config", "rerere.enabled", "true"], path),
+        "enable rerere",
+    );
+
+    assert_cli_success(
+        &run_libra_command(&["switch", "feature"], path),
+        "switch feature",
+    );
+    let first = run_libra_command(&["rebase", "main"], path);
+    assert!(!first.status.success(), "first rebase should conflict");
+    fs::write(path.join("f.txt"), "rebased-by-hand\n").unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "f.txt"], path),
+        "stage resolution",
+    );
+    assert_cli_success(
+        &run_libra_command(&["rebase", "--continue"], path),
+        "rebase --continue",
+    );
+
+    // Recreate the feature change as a new commit on another branch.
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "feature2", &base], path),
+        "branch feature2",
+    );
+    fs::write(path.join("f.txt"), "feature\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "f.txt"], path), "add feature2");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "feature f again", "--no-verify"], path),
+        "commit feature2",
+    );
+
+    let second = run_libra_command(&["rebase", "main"], path);
+    assert!(
+        !second.status.success(),
+        "second rebase still stops on conflict"
+    );
+    let replayed = fs::read_to_string(path.join("f.txt")).unwrap();
+    assert_eq!(
+        replayed, "rebased-by-hand\n",
+        "rerere should have replayed the recorded rebase resolution, got: {replayed:?}"
+    );
+}
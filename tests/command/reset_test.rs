@@ -1,4 +1,4 @@
-//! Tests reset command modes (soft/mixed/hard) and resulting state changes.
+//! Tests reset command modes (soft/mixed/hard/merge/keep) and resulting state changes.
 //!
 //! **Layer:** L1 — deterministic, no external dependencies.
 
@@ -125,6 +125,48 @@ fn test_reset_json_output_reports_target_commit() {
     assert_eq!(json["data"]["files_restored"], 1);
 }
 
+#[test]
+fn test_reset_hard_orig_head_recovers_previous_tip() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let rev_parse = |rev: &str| {
+        let output = run_libra_command(&["rev-parse", rev], p);
+        assert_cli_success(&output, "rev-parse");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    assert!(
+        !run_libra_command(&["rev-parse", "ORIG_HEAD"], p)
+            .status
+            .success(),
+        "ORIG_HEAD is unset until HEAD is moved"
+    );
+
+    fs::write(p.join("tracked.txt"), "tracked\nsecond\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "tracked.txt"], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "second", "--no-verify"], p),
+        "commit",
+    );
+    let tip = rev_parse("HEAD");
+
+    assert_cli_success(
+        &run_libra_command(&["reset", "--hard", "HEAD~1"], p),
+        "reset --hard HEAD~1",
+    );
+    assert_ne!(rev_parse("HEAD"), tip);
+    assert_eq!(rev_parse("ORIG_HEAD"), tip);
+
+    assert_cli_success(
+        &run_libra_command(&["reset", "--hard", "ORIG_HEAD"], p),
+        "reset --hard ORIG_HEAD",
+    );
+    assert_eq!(rev_parse("HEAD"), tip);
+    assert_eq!(
+        fs::read_to_string(p.join("tracked.txt")).unwrap(),
+        "tracked\nsecond\n"
+    );
+}
+
 #[test]
 fn test_reset_json_hard_head_clean_repo_reports_zero_restores() {
     let repo = create_committed_repo_via_cli();
@@ -162,6 +204,51 @@ fn test_reset_json_hard_head_reports_actual_restored_files() {
     );
 }
 
+#[cfg(unix)]
+#[test]
+fn test_reset_hard_restores_executable_bit() {
+    let repo = create_committed_repo_via_cli();
+    let script = repo.path().join("run.sh");
+    fs::write(&script, "#!/bin/sh\nexit 0\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "run.sh"], repo.path()),
+        "add run.sh",
+    );
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "add script", "--no-verify"], repo.path()),
+        "commit run.sh",
+    );
+
+    // Only the bit differs from HEAD; the content alone would not trigger a rewrite.
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o644)).unwrap();
+    let output = run_libra_command(&["--json", "reset", "--hard", "HEAD"], repo.path());
+    assert_cli_success(&output, "reset --hard restores mode");
+    assert_eq!(parse_json_stdout(&output)["data"]["files_restored"], 1);
+    assert_eq!(
+        fs::metadata(&script).unwrap().permissions().mode() & 0o777,
+        0o755
+    );
+
+    fs::remove_file(&script).unwrap();
+    assert_cli_success(
+        &run_libra_command(&["reset", "--hard", "HEAD"], repo.path()),
+        "reset --hard recreates script",
+    );
+    assert_eq!(
+        fs::metadata(&script).unwrap().permissions().mode() & 0o777,
+        0o755
+    );
+
+    let staged = run_libra_command(&["ls-files", "-s", "run.sh"], repo.path());
+    assert_cli_success(&staged, "ls-files -s run.sh");
+    assert!(
+        String::from_utf8_lossy(&staged.stdout).starts_with("100755 "),
+        "index should keep the executable mode: {}",
+        String::from_utf8_lossy(&staged.stdout)
+    );
+}
+
 #[test]
 fn test_reset_hard_with_pathspec_returns_usage_error() {
     let repo = create_committed_repo_via_cli();
@@ -515,6 +602,8 @@ async fn test_reset_hard_io_failure_rolls_back_index_and_keeps_head() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -549,6 +638,8 @@ async fn test_reset_hard_io_failure_rolls_back_index_and_keeps_head() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -577,6 +668,8 @@ async fn test_reset_hard_io_failure_rolls_back_index_and_keeps_head() {
             soft: false,
             mixed: false,
             hard: true,
+            merge: false,
+            keep: false,
             pathspecs: vec![],
             pathspec_from_file: None,
             pathspec_file_nul: false,
@@ -630,6 +723,8 @@ async fn setup_standard_repo(
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -661,6 +756,7 @@ async fn setup_standard_repo(
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -693,6 +789,8 @@ async fn setup_standard_repo(
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -724,6 +822,7 @@ async fn setup_standard_repo(
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -756,6 +855,8 @@ async fn setup_standard_repo(
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -787,6 +888,7 @@ async fn setup_standard_repo(
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -819,6 +921,8 @@ async fn setup_standard_repo(
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -850,6 +954,7 @@ async fn setup_standard_repo(
         edit_description: None,
         show_current: false,
         rename: vec![],
+        rename_force: vec![],
         copy: vec![],
         copy_force: vec![],
         remotes: false,
@@ -891,6 +996,8 @@ async fn setup_test_state() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 }
@@ -911,6 +1018,8 @@ async fn test_reset_soft() {
         soft: true,
         mixed: false,
         hard: false,
+        merge: false,
+        keep: false,
         pathspecs: vec![],
         pathspec_from_file: None,
         pathspec_file_nul: false,
@@ -958,6 +1067,8 @@ async fn test_reset_mixed() {
         soft: false,
         mixed: false, // false means default (mixed)
         hard: false,
+        merge: false,
+        keep: false,
         pathspecs: vec![],
         pathspec_from_file: None,
         pathspec_file_nul: false,
@@ -1009,6 +1120,8 @@ async fn test_reset_hard() {
         soft: false,
         mixed: false,
         hard: true,
+        merge: false,
+        keep: false,
         pathspecs: vec![],
         pathspec_from_file: None,
         pathspec_file_nul: false,
@@ -1086,6 +1199,8 @@ async fn test_reset_mixed_same_target_resets_index_without_moving_head() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1121,6 +1236,8 @@ async fn test_reset_mixed_same_target_resets_index_without_moving_head() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1130,6 +1247,8 @@ async fn test_reset_mixed_same_target_resets_index_without_moving_head() {
             soft: false,
             mixed: true,
             hard: false,
+            merge: false,
+            keep: false,
             pathspecs: vec![],
             pathspec_from_file: None,
             pathspec_file_nul: false,
@@ -1179,6 +1298,8 @@ async fn test_reset_hard_same_target_restores_worktree_and_removes_staged_additi
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1214,6 +1335,8 @@ async fn test_reset_hard_same_target_restores_worktree_and_removes_staged_additi
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1223,6 +1346,8 @@ async fn test_reset_hard_same_target_restores_worktree_and_removes_staged_additi
             soft: false,
             mixed: false,
             hard: true,
+            merge: false,
+            keep: false,
             pathspecs: vec![],
             pathspec_from_file: None,
             pathspec_file_nul: false,
@@ -1277,6 +1402,8 @@ async fn test_reset_hard_removes_paths_tracked_only_by_head_tree() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1311,6 +1438,8 @@ async fn test_reset_hard_removes_paths_tracked_only_by_head_tree() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1349,6 +1478,8 @@ async fn test_reset_hard_removes_paths_tracked_only_by_head_tree() {
             soft: false,
             mixed: false,
             hard: true,
+            merge: false,
+            keep: false,
             pathspecs: vec![],
             pathspec_from_file: None,
             pathspec_file_nul: false,
@@ -1390,6 +1521,8 @@ async fn test_reset_with_head_reference() {
         soft: false,
         mixed: true,
         hard: false,
+        merge: false,
+        keep: false,
         pathspecs: vec![],
         pathspec_from_file: None,
         pathspec_file_nul: false,
@@ -1436,6 +1569,8 @@ async fn test_reset_on_branch() {
                 soft: true,
                 mixed: false,
                 hard: false,
+                merge: false,
+                keep: false,
                 pathspecs: vec![],
                 pathspec_from_file: None,
                 pathspec_file_nul: false,
@@ -1489,6 +1624,8 @@ async fn test_reset_hard_skips_ignored_directories() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1525,6 +1662,8 @@ async fn test_reset_hard_skips_ignored_directories() {
         soft: false,
         mixed: false,
         hard: true,
+        merge: false,
+        keep: false,
         pathspecs: vec![],
         pathspec_from_file: None,
         pathspec_file_nul: false,
@@ -1845,3 +1984,166 @@ fn reset_pathspec_from_file_rejects_escape() {
         report["message"]
     );
 }
+
+/// `base` commit plus `kept.txt`/`changed.txt` at HEAD~1; HEAD changes
+/// `changed.txt` and adds `added.txt`.
+fn create_repo_for_worktree_modes() -> tempfile::TempDir {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join("kept.txt"), "kept\n").unwrap();
+    fs::write(p.join("changed.txt"), "v1\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "."], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "first", "--no-verify"], p),
+        "commit first",
+    );
+    fs::write(p.join("changed.txt"), "v2\n").unwrap();
+    fs::write(p.join("added.txt"), "added\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "."], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "second", "--no-verify"], p),
+        "commit second",
+    );
+    repo
+}
+
+fn rev_parse(repo: &std::path::Path, rev: &str) -> String {
+    let output = run_libra_command(&["rev-parse", rev], repo);
+    assert_cli_success(&output, "rev-parse");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_reset_merge_keeps_unstaged_edits_to_untouched_files() {
+    let repo = create_repo_for_worktree_modes();
+    let p = repo.path();
+    let target = rev_parse(p, "HEAD~1");
+    fs::write(p.join("kept.txt"), "kept\nlocal edit\n").unwrap();
+
+    let output = run_libra_command(&["reset", "--merge", "HEAD~1"], p);
+    assert_cli_success(&output, "reset --merge");
+
+    assert_eq!(rev_parse(p, "HEAD"), target);
+    assert_eq!(fs::read_to_string(p.join("changed.txt")).unwrap(), "v1\n");
+    assert!(!p.join("added.txt").exists(), "added.txt leaves with HEAD");
+    assert_eq!(
+        fs::read_to_string(p.join("kept.txt")).unwrap(),
+        "kept\nlocal edit\n",
+        "the unstaged edit survives"
+    );
+    let cached = run_libra_command(&["diff", "--cached", "--name-only"], p);
+    assert_cli_success(&cached, "diff --cached");
+    assert_eq!(String::from_utf8_lossy(&cached.stdout).trim(), "");
+}
+
+#[test]
+fn test_reset_merge_refuses_to_overwrite_local_edit() {
+    let repo = create_repo_for_worktree_modes();
+    let p = repo.path();
+    let head = rev_parse(p, "HEAD");
+    fs::write(p.join("changed.txt"), "v2\nlocal edit\n").unwrap();
+
+    let output = run_libra_command(&["reset", "--merge", "HEAD~1"], p);
+    let (stderr, report) = parse_cli_error_stderr(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(128));
+    assert_eq!(report.error_code, "LBR-CONFLICT-002");
+    assert!(
+        stderr.contains("changed.txt"),
+        "unexpected stderr: {stderr}"
+    );
+    assert_eq!(rev_parse(p, "HEAD"), head, "HEAD must not move");
+    assert_eq!(
+        fs::read_to_string(p.join("changed.txt")).unwrap(),
+        "v2\nlocal edit\n"
+    );
+    assert!(p.join("added.txt").exists());
+}
+
+#[test]
+fn test_reset_merge_discards_staged_changes_matching_worktree() {
+    let repo = create_repo_for_worktree_modes();
+    let p = repo.path();
+    fs::write(p.join("kept.txt"), "kept\nstaged\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "kept.txt"], p), "add");
+
+    let output = run_libra_command(&["reset", "--merge", "HEAD"], p);
+    assert_cli_success(&output, "reset --merge HEAD");
+
+    assert_eq!(fs::read_to_string(p.join("kept.txt")).unwrap(), "kept\n");
+    let status = run_libra_command(&["status", "--short"], p);
+    assert_cli_success(&status, "status");
+    assert_eq!(String::from_utf8_lossy(&status.stdout).trim(), "");
+}
+
+#[test]
+fn test_reset_keep_preserves_edits_to_files_the_reset_does_not_touch() {
+    let repo = create_repo_for_worktree_modes();
+    let p = repo.path();
+    let target = rev_parse(p, "HEAD~1");
+    fs::write(p.join("kept.txt"), "kept\nlocal edit\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "kept.txt"], p), "add");
+
+    let output = run_libra_command(&["--json", "reset", "--keep", "HEAD~1"], p);
+    assert_cli_success(&output, "reset --keep");
+    let json = parse_json_stdout(&output);
+    assert_eq!(json["data"]["mode"], "keep");
+    assert_eq!(json["data"]["files_restored"], 2);
+
+    assert_eq!(rev_parse(p, "HEAD"), target);
+    assert_eq!(fs::read_to_string(p.join("changed.txt")).unwrap(), "v1\n");
+    assert!(!p.join("added.txt").exists());
+    assert_eq!(
+        fs::read_to_string(p.join("kept.txt")).unwrap(),
+        "kept\nlocal edit\n"
+    );
+    // The staged edit is now an unstaged one: the index matches the target.
+    let cached = run_libra_command(&["diff", "--cached", "--name-only"], p);
+    assert_eq!(String::from_utf8_lossy(&cached.stdout).trim(), "");
+    let unstaged = run_libra_command(&["diff", "--name-only"], p);
+    assert_eq!(String::from_utf8_lossy(&unstaged.stdout).trim(), "kept.txt");
+}
+
+#[test]
+fn test_reset_keep_refuses_when_changed_file_has_local_edits() {
+    let repo = create_repo_for_worktree_modes();
+    let p = repo.path();
+    let head = rev_parse(p, "HEAD");
+    fs::write(p.join("changed.txt"), "v2\nstaged\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "changed.txt"], p), "add");
+
+    let output = run_libra_command(&["reset", "--keep", "HEAD~1"], p);
+    let (stderr, report) = parse_cli_error_stderr(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(128));
+    assert_eq!(report.error_code, "LBR-CONFLICT-002");
+    assert!(
+        stderr.contains("would be overwritten by reset --keep: changed.txt"),
+        "unexpected stderr: {stderr}"
+    );
+    assert_eq!(rev_parse(p, "HEAD"), head);
+    assert_eq!(
+        fs::read_to_string(p.join("changed.txt")).unwrap(),
+        "v2\nstaged\n"
+    );
+}
+
+#[test]
+fn test_reset_merge_and_keep_with_pathspec_return_usage_error() {
+    let repo = create_repo_for_worktree_modes();
+    for mode in ["merge", "keep"] {
+        let flag = format!("--{mode}");
+        let output = run_libra_command(
+            &["reset", &flag, "HEAD~1", "--", "changed.txt"],
+            repo.path(),
+        );
+        let (stderr, report) = parse_cli_error_stderr(&output.stderr);
+
+        assert_eq!(output.status.code(), Some(129));
+        assert_eq!(report.error_code, "LBR-CLI-002");
+        assert!(
+            stderr.contains(&format!("Cannot do {mode} reset with paths.")),
+            "unexpected stderr: {stderr}"
+        );
+    }
+}
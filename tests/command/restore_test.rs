@@ -98,6 +98,42 @@ fn test_restore_pathspec_from_file_restores_listed_paths() {
     );
 }
 
+/// `eol=crlf` writes CRLF to the worktree on restore while the blob keeps LF,
+/// and status treats the converted file as unchanged.
+#[test]
+#[serial]
+fn test_restore_applies_eol_crlf_on_checkout() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+
+    std::fs::write(p.join(".gitattributes"), "*.bat text eol=crlf\n").unwrap();
+    std::fs::write(p.join("run.bat"), "echo one\r\necho two\r\n").unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", ".gitattributes", "run.bat"], p),
+        "add run.bat",
+    );
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "add run.bat", "--no-verify"], p),
+        "commit run.bat",
+    );
+
+    std::fs::remove_file(p.join("run.bat")).unwrap();
+    assert_cli_success(&run_libra_command(&["restore", "run.bat"], p), "restore");
+    assert_eq!(
+        std::fs::read(p.join("run.bat")).unwrap(),
+        b"echo one\r\necho two\r\n",
+        "eol=crlf converts LF blobs to CRLF in the worktree"
+    );
+
+    let status = run_libra_command(&["status", "--porcelain"], p);
+    assert_cli_success(&status, "status after restore");
+    assert!(
+        String::from_utf8_lossy(&status.stdout).trim().is_empty(),
+        "a CRLF checkout of an LF blob is not a modification: {}",
+        String::from_utf8_lossy(&status.stdout)
+    );
+}
+
 #[tokio::test]
 #[serial]
 async fn test_restore_source_does_not_fall_back_from_unborn_branch_to_hash_prefix() {
@@ -1053,3 +1089,69 @@ fn test_restore_merge_rewrites_conflict_markers() {
         "stderr should name the unsupported style: {human}"
     );
 }
+
+#[test]
+#[serial]
+fn test_restore_source_accepts_revisions_tags_and_trees() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let commit_content = |content: &str| {
+        std::fs::write(p.join("tracked.txt"), content).unwrap();
+        assert_cli_success(&run_libra_command(&["add", "tracked.txt"], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", content.trim(), "--no-verify"], p),
+            "commit",
+        );
+    };
+    let read = || std::fs::read_to_string(p.join("tracked.txt")).unwrap();
+    let names = |args: &[&str]| {
+        let out = run_libra_command(args, p);
+        assert_cli_success(&out, &args.join(" "));
+        String::from_utf8_lossy(&out.stdout).trim().to_string()
+    };
+
+    commit_content("one\n");
+    assert_cli_success(
+        &run_libra_command(&["tag", "-m", "release", "v1"], p),
+        "annotated tag v1",
+    );
+    commit_content("two\n");
+    let cat = names(&["cat-file", "-p", "HEAD"]);
+    let two_tree = cat
+        .lines()
+        .find_map(|line| line.strip_prefix("tree "))
+        .expect("commit has a tree line")
+        .to_string();
+    commit_content("three\n");
+
+    // `--worktree` is the default and leaves the index alone.
+    let out = run_libra_command(&["restore", "--source=HEAD~2", "tracked.txt"], p);
+    assert_cli_success(&out, "restore --source=HEAD~2");
+    assert_eq!(read(), "one\n");
+    assert_eq!(names(&["diff", "--cached", "--name-only"]), "");
+    assert_cli_success(&run_libra_command(&["restore", "tracked.txt"], p), "reset");
+    assert_eq!(read(), "three\n");
+
+    // `--staged` with an annotated tag only touches the index.
+    let out = run_libra_command(&["restore", "--source", "v1", "--staged", "tracked.txt"], p);
+    assert_cli_success(&out, "restore --source v1 --staged");
+    assert_eq!(read(), "three\n");
+    assert_eq!(names(&["diff", "--cached", "--name-only"]), "tracked.txt");
+
+    // A bare tree id, written to both the index and the worktree.
+    let out = run_libra_command(
+        &[
+            "restore",
+            "--source",
+            &two_tree,
+            "--staged",
+            "--worktree",
+            "tracked.txt",
+        ],
+        p,
+    );
+    assert_cli_success(&out, "restore --source <tree> --staged --worktree");
+    assert_eq!(read(), "two\n");
+    assert_eq!(names(&["diff", "--name-only"]), "");
+    assert_eq!(names(&["diff", "--cached", "--name-only"]), "tracked.txt");
+}
@@ -1273,3 +1273,114 @@ fn test_rev_parse_default_with_dashdash() {
         vec![sha.clone(), "--".to_string()]
     );
 }
+
+fn rev_parse_value(repo: &std::path::Path, spec: &str) -> String {
+    let output = run_libra_command(&["rev-parse", spec], repo);
+    assert_cli_success(&output, &format!("rev-parse {spec}"));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn commit_change(repo: &std::path::Path, content: &str) {
+    fs::write(repo.join("tracked.txt"), content).unwrap();
+    assert_cli_success(&run_libra_command(&["add", "tracked.txt"], repo), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", content, "--no-verify"], repo),
+        "commit",
+    );
+}
+
+#[test]
+fn test_rev_parse_resolves_reflog_selector() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let first = rev_parse_value(p, "HEAD");
+    commit_change(p, "second\n");
+    let second = rev_parse_value(p, "HEAD");
+
+    assert_eq!(rev_parse_value(p, "HEAD@{0}"), second);
+    assert_eq!(rev_parse_value(p, "HEAD@{1}"), first);
+    assert_eq!(rev_parse_value(p, "main@{1}"), first);
+    assert_eq!(rev_parse_value(p, "@{1}"), first);
+    assert_eq!(rev_parse_value(p, "HEAD@{0}~1"), first);
+
+    let out_of_range = run_libra_command(&["rev-parse", "HEAD@{99}"], p);
+    assert_eq!(out_of_range.status.code(), Some(129));
+    let stderr = String::from_utf8_lossy(&out_of_range.stderr);
+    assert!(stderr.contains("log for 'HEAD' only has"), "{stderr}");
+}
+
+#[test]
+fn test_rev_parse_resolves_previous_branch_and_upstream() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let main_tip = rev_parse_value(p, "HEAD");
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "feature"], p),
+        "switch -c feature",
+    );
+    commit_change(p, "feature\n");
+    let feature_tip = rev_parse_value(p, "HEAD");
+    assert_cli_success(&run_libra_command(&["switch", "main"], p), "switch main");
+
+    assert_eq!(rev_parse_value(p, "@{-1}"), feature_tip);
+
+    let no_upstream = run_libra_command(&["rev-parse", "@{u}"], p);
+    assert_eq!(no_upstream.status.code(), Some(129));
+    assert!(
+        String::from_utf8_lossy(&no_upstream.stderr).contains("no upstream configured"),
+        "{}",
+        String::from_utf8_lossy(&no_upstream.stderr)
+    );
+
+    // `branch.<b>.remote = .` makes another local branch the upstream.
+    assert_cli_success(
+        &run_libra_command(&["config", "branch.main.remote", "."], p),
+        "config branch.main.remote",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "branch.main.merge", "refs/heads/feature"], p),
+        "config branch.main.merge",
+    );
+    assert_eq!(rev_parse_value(p, "@{u}"), feature_tip);
+    assert_eq!(rev_parse_value(p, "main@{upstream}"), feature_tip);
+
+    // `switch -` is `switch @{-1}`.
+    assert_cli_success(&run_libra_command(&["switch", "-"], p), "switch -");
+    assert_eq!(rev_parse_value(p, "HEAD"), feature_tip);
+    assert_eq!(rev_parse_value(p, "@{-1}"), main_tip);
+}
+
+#[test]
+fn test_rev_parse_ancestry_operators_walk_merge_parents() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let base = rev_parse_value(p, "HEAD");
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "side"], p),
+        "switch -c side",
+    );
+    fs::write(p.join("side.txt"), "side\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "side.txt"], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "side", "--no-verify"], p),
+        "commit side",
+    );
+    let side = rev_parse_value(p, "HEAD");
+    assert_cli_success(&run_libra_command(&["switch", "main"], p), "switch main");
+    commit_change(p, "main\n");
+    let main = rev_parse_value(p, "HEAD");
+    assert_cli_success(&run_libra_command(&["merge", "side"], p), "merge side");
+
+    assert_eq!(rev_parse_value(p, "HEAD^"), main);
+    assert_eq!(rev_parse_value(p, "HEAD^1"), main);
+    assert_eq!(rev_parse_value(p, "HEAD^2"), side);
+    assert_eq!(rev_parse_value(p, "HEAD~2"), base);
+    assert_eq!(rev_parse_value(p, "HEAD^^"), base);
+    assert_eq!(rev_parse_value(p, "HEAD^2~1"), base);
+    assert_eq!(rev_parse_value(p, "HEAD~0^2"), side);
+
+    let output = run_libra_command(&["rev-parse", "HEAD^3"], p);
+    assert_eq!(output.status.code(), Some(129));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("has 2 parent(s), no parent 3"), "{stderr}");
+}
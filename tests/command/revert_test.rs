@@ -56,6 +56,8 @@ async fn test_basic_revert() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -91,6 +93,8 @@ async fn test_basic_revert() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -127,6 +131,8 @@ async fn test_basic_revert() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -245,6 +251,8 @@ async fn test_revert_no_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -278,6 +286,8 @@ async fn test_revert_no_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -362,6 +372,8 @@ async fn test_revert_root_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -455,6 +467,8 @@ async fn test_revert_root_commit_creates_empty_tree_commit() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -655,19 +669,140 @@ fn test_revert_multiple_commits_in_one_invocation() {
     );
 }
 
+/// Commit `f.txt` three times (`v1`, `v2`, `v3`), returning the `v1` commit.
+fn commit_three_versions(p: &std::path::Path) -> String {
+    let mut first = String::new();
+    for version in ["v1", "v2", "v3"] {
+        fs::write(p.join("f.txt"), format!("{version}\n")).unwrap();
+        assert_cli_success(&run_libra_command(&["add", "f.txt"], p), "add f");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", version, "--no-verify"], p),
+            "commit",
+        );
+        if first.is_empty() {
+            first = String::from_utf8_lossy(&run_libra_command(&["rev-parse", "HEAD"], p).stdout)
+                .trim()
+                .to_string();
+        }
+    }
+    first
+}
+
 #[test]
 #[serial]
-fn test_revert_multiple_commits_rejects_no_commit() {
+fn test_revert_no_commit_accumulates_multiple_commits() {
     let repo = create_committed_repo_via_cli();
     let p = repo.path();
-    // --no-commit with multiple commits needs the sequencer; it is rejected.
+    for (file, msg) in [("a.txt", "c1 add a"), ("b.txt", "c2 add b")] {
+        fs::write(p.join(file), "x\n").unwrap();
+        assert_cli_success(&run_libra_command(&["add", file], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", msg, "--no-verify"], p),
+            "commit",
+        );
+    }
+    let head = String::from_utf8_lossy(&run_libra_command(&["rev-parse", "HEAD"], p).stdout)
+        .trim()
+        .to_string();
+
     let out = run_libra_command(&["revert", "--no-commit", "HEAD", "HEAD~1"], p);
+    assert_cli_success(&out, "revert --no-commit HEAD HEAD~1");
+    assert!(!p.join("a.txt").exists() && !p.join("b.txt").exists());
+    let now = String::from_utf8_lossy(&run_libra_command(&["rev-parse", "HEAD"], p).stdout)
+        .trim()
+        .to_string();
+    assert_eq!(now, head, "--no-commit must not move HEAD");
+    let staged = run_libra_command(&["diff", "--cached", "--name-only"], p);
+    assert_eq!(
+        String::from_utf8_lossy(&staged.stdout).trim(),
+        "a.txt\nb.txt",
+        "both reverts are staged together"
+    );
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "revert both", "--no-verify"], p),
+        "commit the staged reverts",
+    );
+}
+
+#[test]
+#[serial]
+fn test_revert_range_reverts_newest_first() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    commit_three_versions(p);
+
+    // Oldest-first would conflict (v2 is no longer in the file); newest-first
+    // undoes v3, then v2.
+    let out = run_libra_command(&["revert", "HEAD~2..HEAD"], p);
+    assert_cli_success(&out, "revert HEAD~2..HEAD");
+    assert_eq!(fs::read_to_string(p.join("f.txt")).unwrap(), "v1\n");
+
+    let log = run_libra_command(&["log", "--oneline", "-n", "2"], p);
+    let log = String::from_utf8_lossy(&log.stdout);
+    let subjects: Vec<&str> = log.lines().collect();
+    assert!(subjects[0].contains("Revert \"v2\""), "log: {log}");
+    assert!(subjects[1].contains("Revert \"v3\""), "log: {log}");
+}
+
+#[test]
+#[serial]
+fn test_revert_no_commit_range_stages_without_committing() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let v1 = commit_three_versions(p);
+
+    let out = run_libra_command(&["revert", "-n", &format!("{v1}..HEAD")], p);
+    assert_cli_success(&out, "revert -n v1..HEAD");
+    assert_eq!(fs::read_to_string(p.join("f.txt")).unwrap(), "v1\n");
+    let log = run_libra_command(&["log", "--oneline", "-n", "1"], p);
     assert!(
-        !out.status.success(),
-        "revert --no-commit with multiple commits should be rejected"
+        String::from_utf8_lossy(&log.stdout).contains("v3"),
+        "no revert commit is created"
+    );
+    let staged = run_libra_command(&["diff", "--cached", "--name-only"], p);
+    assert_eq!(String::from_utf8_lossy(&staged.stdout).trim(), "f.txt");
+}
+
+#[test]
+#[serial]
+fn test_revert_empty_range_errors() {
+    let repo = create_committed_repo_via_cli();
+    let out = run_libra_command(&["revert", "HEAD..HEAD"], repo.path());
+    assert_eq!(out.status.code(), Some(128));
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("empty commit set passed"),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
     );
 }
 
+/// The in-progress `revert_sequence` row as `(current_oid, todo)`, if any.
+fn revert_sequence_row(repo: &std::path::Path) -> Option<(String, String)> {
+    use sea_orm::{ConnectionTrait, Database, DatabaseBackend, Statement};
+
+    let db_url = format!(
+        "sqlite://{}?mode=rwc",
+        repo.join(".libra/libra.db").display()
+    );
+    tokio::runtime::Runtime::new()
+        .expect("failed to create tokio runtime")
+        .block_on(async {
+            let conn = Database::connect(db_url).await.expect("connect repo db");
+            let row = conn
+                .query_one(Statement::from_string(
+                    DatabaseBackend::Sqlite,
+                    "SELECT current_oid, todo FROM revert_sequence LIMIT 1".to_string(),
+                ))
+                .await
+                .ok()
+                .flatten()?;
+            Some((
+                row.try_get_by_index(0).expect("current_oid"),
+                row.try_get_by_index(1).expect("todo"),
+            ))
+        })
+}
+
 /// Build a repo where reverting `c2` conflicts with a later change in `c3`,
 /// returning (repo, c2_hash).
 fn setup_revert_conflict() -> (tempfile::TempDir, String) {
@@ -710,7 +845,7 @@ fn test_revert_conflict_then_continue() {
         "conflicting revert should fail and pause"
     );
     assert!(
-        p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_some(),
         "revert state should be recorded"
     );
     assert!(
@@ -726,7 +861,7 @@ fn test_revert_conflict_then_continue() {
     let cont = run_libra_command(&["revert", "--continue"], p);
     assert_cli_success(&cont, "revert --continue");
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "state should be cleared after --continue"
     );
     assert_eq!(
@@ -743,12 +878,12 @@ fn test_revert_conflict_then_abort() {
 
     let out = run_libra_command(&["revert", c2.as_str()], p);
     assert!(!out.status.success(), "conflicting revert should pause");
-    assert!(p.join(".libra/revert-state.json").exists());
+    assert!(revert_sequence_row(p).is_some());
 
     let ab = run_libra_command(&["revert", "--abort"], p);
     assert_cli_success(&ab, "revert --abort");
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "state should be cleared after --abort"
     );
     assert_eq!(
@@ -1159,7 +1294,7 @@ fn test_revert_edit_failure_leaves_worktree_clean() {
         "worktree unchanged"
     );
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "no stray revert state on a clean-path editor failure"
     );
     let subject = run_libra_command(&["log", "-1", "--pretty=%s"], p);
@@ -1170,7 +1305,7 @@ fn test_revert_edit_failure_leaves_worktree_clean() {
     );
 }
 
-/// A failing editor during `revert --continue` must leave `revert-state.json`
+/// A failing editor during `revert --continue` must leave the revert sequence
 /// in place so the revert stays recoverable (`--abort`/retry).
 #[test]
 #[serial]
@@ -1212,10 +1347,7 @@ fn test_revert_edit_failure_during_continue_keeps_state() {
     // Conflicting revert with --edit (editor not reached yet at conflict time).
     let conflicted = run_libra_command(&["revert", "HEAD~1", "--edit"], p);
     assert!(!conflicted.status.success(), "revert should conflict");
-    assert!(
-        p.join(".libra/revert-state.json").exists(),
-        "conflict records state"
-    );
+    assert!(revert_sequence_row(p).is_some(), "conflict records state");
 
     // Resolve, then --continue: the editor runs and FAILS.
     fs::write(p.join("f.txt"), "a\nRESOLVED\nc\n").expect("resolve");
@@ -1227,7 +1359,7 @@ fn test_revert_edit_failure_during_continue_keeps_state() {
     assert!(!cont.status.success(), "a failing editor aborts --continue");
     // State persists so the user can retry or --abort.
     assert!(
-        p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_some(),
         "revert state remains after a failed --continue editor"
     );
 }
@@ -1299,7 +1431,7 @@ fn test_revert_continue_drains_remaining_commits() {
         "remaining commit c3 reverted on --continue"
     );
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "state cleared after the whole sequence completes"
     );
 }
@@ -1335,10 +1467,7 @@ fn test_revert_skip_continues_with_remaining() {
         !p.join("g.txt").exists(),
         "remaining commit c3 reverted after --skip"
     );
-    assert!(
-        !p.join(".libra/revert-state.json").exists(),
-        "state cleared"
-    );
+    assert!(revert_sequence_row(p).is_none(), "state cleared");
 }
 
 /// `revert --skip` when nothing remains after the skipped commit clears the
@@ -1370,10 +1499,7 @@ fn test_revert_skip_with_nothing_remaining() {
             .contains("<<<<<<<"),
         "conflict markers discarded"
     );
-    assert!(
-        !p.join(".libra/revert-state.json").exists(),
-        "state cleared"
-    );
+    assert!(revert_sequence_row(p).is_none(), "state cleared");
 }
 
 /// Build a repo like `setup_conflict_then_clean` but also create a 2-parent
@@ -1442,11 +1568,45 @@ fn test_revert_rejects_bad_ref_up_front() {
         "no commit reverted when a later ref is invalid"
     );
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "no in-progress state from an up-front validation failure"
     );
 }
 
+/// A conflict partway through `revert --no-commit` is recorded in the
+/// database; `--skip` drops only the conflicted commit and keeps the revert
+/// staged before it.
+#[test]
+#[serial]
+fn test_revert_no_commit_skip_keeps_earlier_staged_reverts() {
+    let repo = tempdir().expect("repo");
+    let p = repo.path();
+    let (c2, c3) = setup_conflict_then_clean(p);
+    let head = String::from_utf8_lossy(&run_libra_command(&["rev-parse", "HEAD"], p).stdout)
+        .trim()
+        .to_string();
+
+    let out = run_libra_command(&["revert", "-n", &c3, &c2], p);
+    assert!(!out.status.success(), "c2 conflicts");
+    assert!(!p.join("g.txt").exists(), "c3 revert is staged first");
+    let (current, todo) = revert_sequence_row(p).expect("conflict records state");
+    assert_eq!(current, c2);
+    assert_eq!(todo, "");
+
+    let skip = run_libra_command(&["revert", "--skip"], p);
+    assert_cli_success(&skip, "revert --skip");
+    assert!(revert_sequence_row(p).is_none(), "state cleared");
+    assert!(!p.join("g.txt").exists(), "the staged c3 revert survives");
+    assert_eq!(
+        fs::read_to_string(p.join("f.txt")).unwrap(),
+        "L1\nL2again\nL3\n"
+    );
+    let now = String::from_utf8_lossy(&run_libra_command(&["rev-parse", "HEAD"], p).stdout)
+        .trim()
+        .to_string();
+    assert_eq!(now, head, "--no-commit never moves HEAD");
+}
+
 /// The pending queue persists resolved commit IDs, not the raw refs, so a ref
 /// that moves during the conflict pause cannot change what gets reverted.
 #[test]
@@ -1461,7 +1621,7 @@ fn test_revert_remaining_persists_resolved_ids() {
 
     let out = run_libra_command(&["revert", &c2, "target"], p);
     assert!(!out.status.success(), "c2 conflicts");
-    let state = fs::read_to_string(p.join(".libra/revert-state.json")).expect("state");
+    let (_, state) = revert_sequence_row(p).expect("state");
     // The stored remaining entry is c3's resolved hash, not the branch name.
     assert!(
         state.contains(&c3),
@@ -1486,10 +1646,7 @@ fn test_revert_continue_clears_state_on_drain_error() {
 
     let out = run_libra_command(&["revert", &c2, &merge], p);
     assert!(!out.status.success(), "c2 revert conflicts");
-    assert!(
-        p.join(".libra/revert-state.json").exists(),
-        "conflict records state"
-    );
+    assert!(revert_sequence_row(p).is_some(), "conflict records state");
 
     fs::write(p.join("f.txt"), "L1\nRESOLVED\nL3\n").expect("resolve");
     run_libra_command(&["add", "f.txt"], p);
@@ -1500,7 +1657,7 @@ fn test_revert_continue_clears_state_on_drain_error() {
         "the merge commit in the queue fails the drain"
     );
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "stale state must not point at the already-committed conflict"
     );
     let retry = run_libra_command(&["revert", "--continue"], p);
@@ -1529,7 +1686,7 @@ fn test_revert_skip_clears_state_on_drain_error() {
         "the merge commit in the queue fails the drain"
     );
     assert!(
-        !p.join(".libra/revert-state.json").exists(),
+        revert_sequence_row(p).is_none(),
         "skip must not leave stale state after a drain error"
     );
     let retry = run_libra_command(&["revert", "--skip"], p);
@@ -993,7 +993,7 @@ fn test_shortlog_merges_and_no_merges() {
     // --merges keeps only the merge commit.
     let m = body(&["shortlog", "--merges"]);
     assert!(
-        m.contains("Merge feat into main"),
+        m.contains("Merge branch 'feat' into main"),
         "--merges shows the merge: {m:?}"
     );
     assert!(
@@ -1004,7 +1004,7 @@ fn test_shortlog_merges_and_no_merges() {
     // --no-merges drops the merge commit.
     let nm = body(&["shortlog", "--no-merges"]);
     assert!(
-        !nm.contains("Merge feat into main"),
+        !nm.contains("Merge branch 'feat' into main"),
         "--no-merges drops the merge: {nm:?}"
     );
     assert!(
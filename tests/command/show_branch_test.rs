@@ -0,0 +1,158 @@
+//! Integration tests for `libra show-branch`.
+//!
+//! Layer: L1 (deterministic; tempdir + isolated HOME, no network).
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use super::{
+    assert_cli_success, create_committed_repo_via_cli, parse_json_stdout, run_libra_command,
+};
+
+/// Commit with a committer timestamp strictly after the previous commit, so
+/// the newest-first row order is deterministic (timestamps have one-second
+/// resolution).
+fn commit_file(repo: &TempDir, file: &str, message: &str) {
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    fs::write(repo.path().join(file), format!("{message}\n")).unwrap();
+    assert_cli_success(&run_libra_command(&["add", file], repo.path()), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", message, "--no-verify"], repo.path()),
+        "commit",
+    );
+}
+
+/// `main` and `topic` share the `base` commit; each then gets one commit of
+/// its own, `topic`'s being the newer. HEAD is left on `main`.
+fn two_branch_repo() -> TempDir {
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["branch", "topic"], repo.path()),
+        "branch topic",
+    );
+    commit_file(&repo, "main.txt", "main work");
+    assert_cli_success(
+        &run_libra_command(&["switch", "topic"], repo.path()),
+        "switch topic",
+    );
+    commit_file(&repo, "topic.txt", "topic work");
+    assert_cli_success(
+        &run_libra_command(&["switch", "main"], repo.path()),
+        "switch main",
+    );
+    repo
+}
+
+fn stdout_lines(output: &std::process::Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_show_branch_marks_divergent_commits_and_merge_base() {
+    let repo = two_branch_repo();
+
+    let out = run_libra_command(&["show-branch", "main", "topic"], repo.path());
+    assert_cli_success(&out, "show-branch main topic");
+    assert_eq!(
+        stdout_lines(&out),
+        [
+            "* [main] main work",
+            " ! [topic] topic work",
+            "--",
+            " + [topic] topic work",
+            "*  [main] main work",
+            "*+ [topic^] base",
+        ]
+    );
+}
+
+#[test]
+fn test_show_branch_defaults_to_all_local_branches() {
+    let repo = two_branch_repo();
+
+    let out = run_libra_command(&["show-branch"], repo.path());
+    assert_cli_success(&out, "show-branch");
+    let lines = stdout_lines(&out);
+    assert_eq!(
+        lines[..3],
+        ["* [main] main work", " ! [topic] topic work", "--"]
+    );
+    assert_eq!(lines.last().unwrap(), "*+ [topic^] base");
+}
+
+#[test]
+fn test_show_branch_merge_commit_uses_dash_marker() {
+    let repo = two_branch_repo();
+    assert_cli_success(
+        &run_libra_command(
+            &["merge", "topic", "--no-ff", "-m", "merge topic"],
+            repo.path(),
+        ),
+        "merge topic",
+    );
+
+    let out = run_libra_command(&["show-branch", "main", "topic"], repo.path());
+    assert_cli_success(&out, "show-branch after merge");
+    assert_eq!(
+        stdout_lines(&out),
+        [
+            "* [main] merge topic",
+            " ! [topic] topic work",
+            "--",
+            "-  [main] merge topic",
+            "*+ [topic] topic work",
+            "*  [main^] main work",
+        ]
+    );
+}
+
+#[test]
+fn test_show_branch_json_reports_markers() {
+    let repo = two_branch_repo();
+
+    let out = run_libra_command(&["--json", "show-branch", "topic", "main"], repo.path());
+    assert_cli_success(&out, "--json show-branch");
+    let json = parse_json_stdout(&out);
+    let data = &json["data"];
+    assert_eq!(data["branches"][0]["name"], "topic");
+    assert_eq!(data["branches"][0]["current"], false);
+    assert_eq!(data["branches"][1]["name"], "main");
+    assert_eq!(data["branches"][1]["current"], true);
+
+    let rows: Vec<(String, String)> = data["commits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            (
+                row["markers"].as_str().unwrap().to_string(),
+                row["name"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("+ ".to_string(), "topic".to_string()),
+            (" *".to_string(), "main".to_string()),
+            ("+*".to_string(), "topic^".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_show_branch_invalid_rev_fails() {
+    let repo = create_committed_repo_via_cli();
+
+    let out = run_libra_command(&["show-branch", "main", "nope"], repo.path());
+    assert_eq!(out.status.code(), Some(128));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("not a valid commit 'nope'"),
+        "stderr: {stderr}"
+    );
+}
@@ -309,6 +309,7 @@ async fn test_show_non_quiet_uses_forced_pager() {
 
     let args = ShowArgs {
         no_abbrev_commit: false,
+        show_signature: false,
         no_show_signature: false,
         no_expand_tabs: false,
         no_notes: false,
@@ -368,6 +369,7 @@ async fn test_show_quiet_still_validates_patch_generation() {
     let _guard = ChangeDirGuard::new(repo.path());
     let args = ShowArgs {
         no_abbrev_commit: false,
+        show_signature: false,
         no_show_signature: false,
         no_expand_tabs: false,
         no_notes: false,
@@ -428,6 +430,7 @@ async fn test_show_quiet_stat_succeeds_with_missing_blob_like_human_path() {
     let _guard = ChangeDirGuard::new(repo.path());
     let args = ShowArgs {
         no_abbrev_commit: false,
+        show_signature: false,
         no_show_signature: false,
         no_expand_tabs: false,
         no_notes: false,
@@ -703,6 +706,45 @@ async fn test_show_tree_output_uses_git_modes_and_types() {
     assert_eq!(tracked_entry["object_type"], "blob");
 }
 
+/// `<rev>:<path>` resolves directories (and the empty path, the root tree) to
+/// a tree listing, not only files.
+#[test]
+fn test_show_revision_path_lists_directories() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    std::fs::create_dir_all(p.join("src")).unwrap();
+    create_commit(p, "src/lib.rs", "pub fn lib() {}\n", "add src");
+
+    let root = run_libra_command(&["show", "HEAD:"], p);
+    assert_cli_success(&root, "show HEAD:");
+    let root = String::from_utf8_lossy(&root.stdout);
+    assert!(root.starts_with("tree "), "{root}");
+    assert!(root.contains("040000 tree "), "{root}");
+    assert!(root.contains("\tsrc\n"), "{root}");
+    assert!(root.contains("\ttracked.txt\n"), "{root}");
+
+    let dir = run_libra_command(&["show", "HEAD:src"], p);
+    assert_cli_success(&dir, "show HEAD:src");
+    let dir = String::from_utf8_lossy(&dir.stdout);
+    assert!(dir.contains("100644 blob "), "{dir}");
+    assert!(dir.contains("\tlib.rs\n"), "{dir}");
+
+    let file = run_libra_command(&["show", "HEAD:src/lib.rs"], p);
+    assert_cli_success(&file, "show HEAD:src/lib.rs");
+    assert_eq!(String::from_utf8_lossy(&file.stdout), "pub fn lib() {}\n");
+
+    let json = run_libra_command(&["--json", "show", "HEAD:src/"], p);
+    assert_cli_success(&json, "show --json HEAD:src/");
+    let json = parse_json_stdout(&json);
+    assert_eq!(json["data"]["type"], "tree");
+    assert_eq!(json["data"]["entries"][0]["name"], "lib.rs");
+
+    let missing = run_libra_command(&["show", "HEAD:src/missing.rs"], p);
+    assert!(!missing.status.success());
+    let through_file = run_libra_command(&["show", "HEAD:tracked.txt/x"], p);
+    assert!(!through_file.status.success());
+}
+
 /// Test that show can display a lightweight tag.
 #[tokio::test]
 async fn test_show_lightweight_tag() {
@@ -897,6 +939,7 @@ async fn test_show_execute_safe_bad_ref_returns_cli_error() {
 
     let args = ShowArgs {
         no_abbrev_commit: false,
+        show_signature: false,
         no_show_signature: false,
         no_expand_tabs: false,
         no_notes: false,
@@ -948,6 +991,7 @@ async fn test_show_execute_safe_bad_rev_path_returns_cli_error() {
 
     let args = ShowArgs {
         no_abbrev_commit: false,
+        show_signature: false,
         no_show_signature: false,
         no_expand_tabs: false,
         no_notes: false,
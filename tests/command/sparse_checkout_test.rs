@@ -0,0 +1,168 @@
+//! Integration tests for `libra sparse-checkout`.
+//!
+//! Layer: L1 (deterministic; tempdir + isolated HOME, no network).
+
+use std::{fs, path::Path, process::Output};
+
+use tempfile::TempDir;
+
+use super::{assert_cli_success, create_committed_repo_via_cli, run_libra_command};
+
+fn out(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn commit_files(repo: &Path, files: &[(&str, &str)], message: &str) {
+    for (path, content) in files {
+        let path = repo.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    assert_cli_success(&run_libra_command(&["add", "."], repo), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", message, "--no-verify"], repo),
+        "commit",
+    );
+}
+
+/// `main` has `a/one.txt` and `b/two.txt`; `feature` changes both and adds
+/// `b/new.txt`. HEAD is left on `main`.
+fn repo_with_two_directories() -> TempDir {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    commit_files(p, &[("a/one.txt", "one\n"), ("b/two.txt", "two\n")], "dirs");
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "feature"], p),
+        "switch -c",
+    );
+    commit_files(
+        p,
+        &[
+            ("a/one.txt", "one on feature\n"),
+            ("b/two.txt", "two on feature\n"),
+            ("b/new.txt", "new\n"),
+        ],
+        "feature",
+    );
+    assert_cli_success(&run_libra_command(&["switch", "main"], p), "switch main");
+    repo
+}
+
+fn assert_clean(repo: &Path) {
+    let status = run_libra_command(&["status", "--short"], repo);
+    assert_cli_success(&status, "status");
+    assert_eq!(out(&status).trim(), "", "worktree should be clean");
+    let diff = run_libra_command(&["diff"], repo);
+    assert_cli_success(&diff, "diff");
+    assert_eq!(out(&diff).trim(), "", "diff should be empty");
+}
+
+#[test]
+fn switch_materializes_only_paths_in_the_sparse_checkout() {
+    let repo = repo_with_two_directories();
+    let p = repo.path();
+
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "set", "a"], p),
+        "sparse-checkout set",
+    );
+    assert!(p.join("a/one.txt").exists());
+    assert!(p.join("tracked.txt").exists(), "top-level files stay");
+    assert!(!p.join("b").exists(), "excluded directory is removed");
+    assert_clean(p);
+
+    assert_cli_success(&run_libra_command(&["switch", "feature"], p), "switch");
+    assert_eq!(
+        fs::read_to_string(p.join("a/one.txt")).unwrap(),
+        "one on feature\n"
+    );
+    assert!(!p.join("b/two.txt").exists());
+    assert!(!p.join("b/new.txt").exists());
+    assert_clean(p);
+
+    // The index still holds the excluded entries.
+    let ls_files = run_libra_command(&["ls-files"], p);
+    assert!(out(&ls_files).contains("b/new.txt"), "{}", out(&ls_files));
+
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "disable"], p),
+        "sparse-checkout disable",
+    );
+    assert_eq!(
+        fs::read_to_string(p.join("b/two.txt")).unwrap(),
+        "two on feature\n"
+    );
+    assert!(p.join("b/new.txt").exists());
+    assert_clean(p);
+}
+
+#[test]
+fn sparse_checkout_list_add_and_pattern_mode() {
+    let repo = repo_with_two_directories();
+    let p = repo.path();
+
+    let list = run_libra_command(&["sparse-checkout", "list"], p);
+    assert_eq!(
+        list.status.code(),
+        Some(128),
+        "list requires sparse checkout"
+    );
+
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "set", "a"], p),
+        "set",
+    );
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "add", "b"], p),
+        "add",
+    );
+    let list = run_libra_command(&["sparse-checkout", "list"], p);
+    assert_cli_success(&list, "list");
+    assert_eq!(out(&list), "a\nb\n");
+    assert!(p.join("b/two.txt").exists(), "added directory is restored");
+
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "set", "--no-cone", "/b/"], p),
+        "set --no-cone",
+    );
+    assert!(!p.join("a/one.txt").exists());
+    assert!(!p.join("tracked.txt").exists());
+    assert!(p.join("b/two.txt").exists());
+    let list = run_libra_command(&["sparse-checkout", "list"], p);
+    assert_eq!(out(&list), "/b/\n");
+    assert_clean(p);
+}
+
+#[test]
+fn sparse_checkout_records_the_skip_worktree_mark() {
+    let repo = repo_with_two_directories();
+    let p = repo.path();
+    let marks = || fs::read_to_string(p.join(".libra/skip-worktree")).unwrap_or_default();
+
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "set", "a"], p),
+        "set",
+    );
+    assert_eq!(marks(), "b/two.txt\n");
+
+    // Switching brings in `b/new.txt`, which stays out of the working tree.
+    assert_cli_success(&run_libra_command(&["switch", "feature"], p), "switch");
+    assert_eq!(marks(), "b/new.txt\nb/two.txt\n");
+
+    // It is the same mark `update-index` manages: clearing it by hand makes
+    // the absent file a deletion.
+    assert_cli_success(
+        &run_libra_command(&["update-index", "--no-skip-worktree", "b/two.txt"], p),
+        "update-index --no-skip-worktree",
+    );
+    let status = run_libra_command(&["status", "--short"], p);
+    assert_cli_success(&status, "status");
+    assert_eq!(out(&status).trim(), "D b/two.txt");
+
+    assert_cli_success(
+        &run_libra_command(&["sparse-checkout", "disable"], p),
+        "disable",
+    );
+    assert_eq!(marks(), "", "disable clears every skip-worktree mark");
+    assert_clean(p);
+}
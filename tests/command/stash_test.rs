@@ -63,6 +63,8 @@ async fn test_stash_push_no_changes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -114,6 +116,8 @@ async fn test_stash_push_no_changes_json_output() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -166,6 +170,8 @@ async fn test_stash_push_and_pop() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -249,6 +255,8 @@ async fn test_stash_push_and_pop_preserves_dotfiles() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -329,6 +337,8 @@ async fn test_stash_list() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -456,6 +466,8 @@ async fn test_stash_drop() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -523,6 +535,8 @@ async fn test_stash_drop_missing_reflog_returns_no_stash_found() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -586,6 +600,8 @@ async fn test_stash_json_output() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -697,6 +713,40 @@ fn test_stash_push_default_excludes_untracked() {
     );
 }
 
+#[test]
+fn test_stash_push_index_commit_leaves_out_intent_to_add() {
+    let repo = create_committed_repo_via_cli();
+
+    fs::write(repo.path().join("tracked.txt"), "modified tracked\n")
+        .expect("failed to modify tracked file");
+    fs::write(repo.path().join("new.txt"), "new\n").expect("failed to write new file");
+    let output = run_libra_command(&["add", "-N", "new.txt"], repo.path());
+    assert_cli_success(&output, "add -N new.txt");
+
+    let output = run_libra_command(&["stash", "push"], repo.path());
+    assert_cli_success(&output, "stash push with an intent-to-add path");
+
+    let stash_commit = latest_stash_commit(repo.path());
+    let _guard = ChangeDirGuard::new(repo.path());
+    let index_commit = load_object::<Commit>(&stash_commit.parent_commit_ids[1])
+        .expect("failed to load stash index commit");
+    let index_tree =
+        load_object::<Tree>(&index_commit.tree_id).expect("failed to load stash index tree");
+    let names: Vec<&str> = index_tree
+        .tree_items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect();
+    assert!(
+        names.contains(&"tracked.txt"),
+        "index tree should keep tracked files: {names:?}"
+    );
+    assert!(
+        !names.contains(&"new.txt"),
+        "index tree must not record the intent-to-add placeholder: {names:?}"
+    );
+}
+
 #[test]
 fn test_stash_push_untracked_only_not_noop() {
     let repo = create_committed_repo_via_cli();
@@ -875,6 +925,54 @@ fn test_stash_apply_restores_included_untracked() {
     );
 }
 
+#[test]
+fn test_stash_pop_restores_untracked_and_ignored_files() {
+    let repo = create_committed_repo_via_cli();
+
+    fs::write(repo.path().join(".libraignore"), "*.log\n").expect("failed to update libraignore");
+    fs::create_dir_all(repo.path().join("notes/drafts")).expect("failed to create nested dir");
+    fs::write(repo.path().join("notes/drafts/idea.txt"), "idea\n")
+        .expect("failed to write nested untracked file");
+    fs::write(repo.path().join("build.log"), "log\n").expect("failed to write ignored file");
+    fs::write(repo.path().join("tracked.txt"), "modified tracked\n")
+        .expect("failed to modify tracked file");
+
+    let output = run_libra_command(&["stash", "push", "--all"], repo.path());
+    assert_cli_success(&output, "stash push --all");
+    assert!(
+        !repo.path().join("notes").exists(),
+        "stash push --all should remove the emptied untracked directory"
+    );
+    assert!(!repo.path().join("build.log").exists());
+    assert_eq!(
+        fs::read_to_string(repo.path().join("tracked.txt")).expect("tracked file after stash"),
+        "tracked\n"
+    );
+
+    let output = run_libra_command(&["stash", "pop"], repo.path());
+    assert_cli_success(&output, "stash pop");
+    assert_eq!(
+        fs::read_to_string(repo.path().join("notes/drafts/idea.txt"))
+            .expect("nested untracked file should be restored"),
+        "idea\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("build.log")).expect("ignored file should be restored"),
+        "log\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path().join("tracked.txt")).expect("tracked file after pop"),
+        "modified tracked\n"
+    );
+
+    let output = run_libra_command(&["stash", "list"], repo.path());
+    assert_cli_success(&output, "stash list after pop");
+    assert!(
+        String::from_utf8_lossy(&output.stdout).trim().is_empty(),
+        "stash pop should drop the entry"
+    );
+}
+
 #[test]
 fn test_stash_apply_untracked_collision_errors() {
     let repo = create_committed_repo_via_cli();
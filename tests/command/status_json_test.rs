@@ -187,6 +187,70 @@ fn json_status_with_staged_changes() {
     assert_eq!(data["is_clean"], false);
 }
 
+// ---------------------------------------------------------------------------
+// Per-path records
+// ---------------------------------------------------------------------------
+
+#[test]
+fn json_status_files_lists_per_path_records() {
+    let repo = create_committed_repo();
+
+    fs::write(repo.path().join("staged.txt"), "staged\n").unwrap();
+    let out = run_libra_command(&["add", "staged.txt"], repo.path());
+    assert_cli_success(&out, "add staged.txt");
+    fs::write(repo.path().join("tracked.txt"), "modified\n").unwrap();
+    fs::write(repo.path().join("untracked.txt"), "new\n").unwrap();
+
+    let output = run_libra_command(&["status", "--json"], repo.path());
+    assert_cli_success(&output, "status --json");
+
+    let parsed = parse_json_stdout(&output);
+    let files = parsed["data"]["files"]
+        .as_array()
+        .expect("files should be an array");
+    let record = |path: &str| {
+        files
+            .iter()
+            .find(|entry| entry["path"] == path)
+            .unwrap_or_else(|| panic!("no record for {path}: {files:?}"))
+    };
+
+    let staged = record("staged.txt");
+    assert_eq!(staged["index"], "A");
+    assert_eq!(staged["worktree"], ".");
+    assert!(staged.get("orig_path").is_none());
+
+    let modified = record("tracked.txt");
+    assert_eq!(modified["index"], ".");
+    assert_eq!(modified["worktree"], "M");
+
+    let untracked = record("untracked.txt");
+    assert_eq!(untracked["index"], "?");
+    assert_eq!(untracked["worktree"], "?");
+
+    assert_eq!(files.len(), 3, "unexpected records: {files:?}");
+}
+
+#[test]
+fn json_status_files_reports_rename_once_with_orig_path() {
+    let repo = create_committed_repo();
+
+    let out = run_libra_command(&["mv", "tracked.txt", "renamed.txt"], repo.path());
+    assert_cli_success(&out, "mv tracked.txt");
+
+    let output = run_libra_command(&["status", "--renames", "--json"], repo.path());
+    assert_cli_success(&output, "status --renames --json after mv");
+
+    let parsed = parse_json_stdout(&output);
+    let files = parsed["data"]["files"]
+        .as_array()
+        .expect("files should be an array");
+    assert_eq!(files.len(), 1, "unexpected records: {files:?}");
+    assert_eq!(files[0]["path"], "renamed.txt");
+    assert_eq!(files[0]["orig_path"], "tracked.txt");
+    assert_eq!(files[0]["index"], "R");
+}
+
 // ---------------------------------------------------------------------------
 // --show-stash --json
 // ---------------------------------------------------------------------------
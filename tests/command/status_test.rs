@@ -229,6 +229,8 @@ async fn test_changes_to_be_staged() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -309,6 +311,7 @@ fn test_output_porcelain_format() {
         modified: vec![PathBuf::from("modified_file.txt")],
         deleted: vec![PathBuf::from("deleted_file.txt")],
         renamed: vec![],
+        intent_to_add: vec![],
     };
 
     let unstaged = Changes {
@@ -316,6 +319,7 @@ fn test_output_porcelain_format() {
         modified: vec![PathBuf::from("unstaged_modified.txt")],
         deleted: vec![PathBuf::from("unstaged_deleted.txt")],
         renamed: vec![],
+        intent_to_add: vec![],
     };
 
     // Create a buffer to capture the output
@@ -370,6 +374,8 @@ async fn test_status_porcelain() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -389,6 +395,8 @@ async fn test_status_porcelain() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     file2.write_all(b"modified content").unwrap();
@@ -454,6 +462,7 @@ fn test_output_short_format() {
         modified: vec![PathBuf::from("modified_file.txt")],
         deleted: vec![PathBuf::from("deleted_file.txt")],
         renamed: vec![],
+        intent_to_add: vec![],
     };
 
     let unstaged = Changes {
@@ -461,6 +470,7 @@ fn test_output_short_format() {
         modified: vec![PathBuf::from("unstaged_modified.txt")],
         deleted: vec![PathBuf::from("unstaged_deleted.txt")],
         renamed: vec![],
+        intent_to_add: vec![],
     };
 
     // Create a buffer to capture the output
@@ -532,6 +542,8 @@ async fn test_status_short_format() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -551,6 +563,8 @@ async fn test_status_short_format() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -651,6 +665,8 @@ async fn test_status_porcelain_v2_basic() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     file1.write_all(b" more").unwrap(); // unstaged modification
@@ -734,6 +750,8 @@ async fn test_status_porcelain_v2_branch_metadata_includes_upstream_counts() {
             chmod: None,
             renormalize: false,
             ignore_missing: false,
+            patch: false,
+            intent_to_add: false,
         },
         &libra::utils::output::OutputConfig::default(),
     )
@@ -810,6 +828,8 @@ async fn test_status_porcelain_v2_untracked_files_no() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -873,6 +893,8 @@ async fn test_status_porcelain_v2_untracked_files_all() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -928,6 +950,8 @@ async fn test_status_untracked_files_no() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -991,6 +1015,8 @@ async fn test_status_untracked_files_all() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1080,6 +1106,8 @@ async fn test_status_mixed_changes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1149,6 +1177,8 @@ async fn test_status_deleted_files() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1223,6 +1253,8 @@ async fn test_status_with_subdirectories() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1314,6 +1346,8 @@ async fn test_status_verbose_output() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1373,6 +1407,8 @@ async fn test_status_short_format_with_branch() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1442,6 +1478,8 @@ async fn test_status_porcelain_format_with_branch() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1511,6 +1549,8 @@ async fn test_status_show_stash_with_existing_stash() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1538,6 +1578,8 @@ async fn test_status_show_stash_with_existing_stash() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1663,6 +1705,8 @@ async fn test_status_show_stash_without_stash() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1723,6 +1767,8 @@ async fn test_status_branch_detached_head() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1750,6 +1796,8 @@ async fn test_status_branch_detached_head() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1825,6 +1873,8 @@ async fn test_status_porcelain_v2_file_modes_and_hashes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(create_commit_args("Initial commit")).await;
@@ -1846,6 +1896,8 @@ async fn test_status_porcelain_v2_file_modes_and_hashes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1866,6 +1918,8 @@ async fn test_status_porcelain_v2_file_modes_and_hashes() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1994,6 +2048,8 @@ async fn test_status_porcelain_v2_executable_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -2063,6 +2119,8 @@ async fn test_status_porcelain_v2_deleted_file() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(create_commit_args("Initial commit")).await;
@@ -2153,6 +2211,8 @@ async fn test_status_after_add() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -2709,3 +2769,124 @@ fn untracked_files_short_flag_parses_like_git() {
         UntrackedFiles::No
     );
 }
+
+/// Commit a one-line change to `tracked.txt` through the CLI and return the
+/// new HEAD hash.
+fn commit_change_via_cli(repo: &std::path::Path, content: &str, message: &str) -> String {
+    fs::write(repo.join("tracked.txt"), content).unwrap();
+    assert_cli_success(
+        &run_libra_command(&["add", "tracked.txt"], repo),
+        "add tracked.txt",
+    );
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", message, "--no-verify"], repo),
+        "commit change",
+    );
+    let output = run_libra_command(&["rev-parse", "HEAD"], repo);
+    assert_cli_success(&output, "rev-parse HEAD");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Point `origin/main` at `commit` and make it the upstream of `main`.
+async fn track_origin_main_at(repo: &std::path::Path, commit: &str) {
+    assert_cli_success(
+        &run_libra_command(&["config", "branch.main.remote", "origin"], repo),
+        "configure branch.main.remote",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "branch.main.merge", "refs/heads/main"], repo),
+        "configure branch.main.merge",
+    );
+    let _guard = ChangeDirGuard::new(repo);
+    Branch::update_branch("main", commit, Some("origin"))
+        .await
+        .expect("remote-tracking branch should be created");
+}
+
+fn status_and_branch_vv(repo: &std::path::Path) -> (String, String) {
+    let status = run_libra_command(&["status"], repo);
+    assert_cli_success(&status, "status");
+    let branch = run_libra_command(&["branch", "-vv"], repo);
+    assert_cli_success(&branch, "branch -vv");
+    (
+        String::from_utf8_lossy(&status.stdout).into_owned(),
+        String::from_utf8_lossy(&branch.stdout).into_owned(),
+    )
+}
+
+#[tokio::test]
+#[serial]
+/// Upstream at an older commit with shared history below it: only the local
+/// commits above the upstream tip count as ahead.
+async fn test_status_and_branch_vv_report_ahead_only() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let upstream = commit_change_via_cli(p, "one\n", "one");
+    commit_change_via_cli(p, "two\n", "two");
+    commit_change_via_cli(p, "three\n", "three");
+    track_origin_main_at(p, &upstream).await;
+
+    let (status, branch) = status_and_branch_vv(p);
+    assert!(
+        status.contains("Your branch is ahead of 'origin/main' by 2 commits."),
+        "status should report ahead-only: {status}"
+    );
+    assert!(
+        branch.contains("[origin/main: ahead 2]"),
+        "branch -vv should report ahead-only: {branch}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+/// Upstream two commits past the local tip reports behind-only.
+async fn test_status_and_branch_vv_report_behind_only() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let local = commit_change_via_cli(p, "one\n", "one");
+    commit_change_via_cli(p, "two\n", "two");
+    let upstream = commit_change_via_cli(p, "three\n", "three");
+    assert_cli_success(
+        &run_libra_command(&["reset", "--hard", &local], p),
+        "reset to local tip",
+    );
+    track_origin_main_at(p, &upstream).await;
+
+    let (status, branch) = status_and_branch_vv(p);
+    assert!(
+        status.contains("Your branch is behind 'origin/main' by 2 commits."),
+        "status should report behind-only: {status}"
+    );
+    assert!(
+        branch.contains("[origin/main: behind 2]"),
+        "branch -vv should report behind-only: {branch}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+/// Local and upstream each add commits on top of a shared base.
+async fn test_status_and_branch_vv_report_diverged() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let base = commit_change_via_cli(p, "one\n", "one");
+    let upstream = commit_change_via_cli(p, "upstream\n", "upstream");
+    assert_cli_success(
+        &run_libra_command(&["reset", "--hard", &base], p),
+        "reset to shared base",
+    );
+    commit_change_via_cli(p, "local a\n", "local a");
+    commit_change_via_cli(p, "local b\n", "local b");
+    track_origin_main_at(p, &upstream).await;
+
+    let (status, branch) = status_and_branch_vv(p);
+    assert!(
+        status.contains("Your branch and 'origin/main' have diverged,")
+            && status.contains("and have 2 and 1 different commits each, respectively."),
+        "status should report divergence: {status}"
+    );
+    assert!(
+        branch.contains("[origin/main: ahead 2, behind 1]"),
+        "branch -vv should report divergence: {branch}"
+    );
+}
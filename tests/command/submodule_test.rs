@@ -0,0 +1,219 @@
+//! Integration tests for `libra submodule` and `clone --recurse-submodules`.
+//!
+//! Layer: L1 (deterministic; tempdir + isolated HOME, local-path remotes, no
+//! network).
+
+use std::{fs, path::Path};
+
+use tempfile::TempDir;
+
+use super::{
+    assert_cli_success, configure_identity_via_cli, create_committed_repo_via_cli,
+    parse_cli_error_stderr, parse_json_stdout, run_libra_command,
+};
+
+fn head_of(repo: &Path) -> String {
+    let out = run_libra_command(&["rev-parse", "HEAD"], repo);
+    assert_cli_success(&out, "rev-parse HEAD");
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A superproject with `lib` added as the `vendor/lib` submodule (staged, not
+/// committed). Returns `(superproject, lib)`.
+fn superproject_with_submodule() -> (TempDir, TempDir) {
+    let lib = create_committed_repo_via_cli();
+    let superproject = create_committed_repo_via_cli();
+    let out = run_libra_command(
+        &[
+            "submodule",
+            "add",
+            lib.path().to_str().unwrap(),
+            "vendor/lib",
+        ],
+        superproject.path(),
+    );
+    assert_cli_success(&out, "submodule add");
+    (superproject, lib)
+}
+
+#[test]
+fn test_submodule_add_records_gitlink_and_gitmodules() {
+    let (superproject, lib) = superproject_with_submodule();
+    let pinned = head_of(lib.path());
+
+    assert_eq!(
+        fs::read_to_string(superproject.path().join(".gitmodules")).unwrap(),
+        format!(
+            "[submodule \"vendor/lib\"]\n\tpath = vendor/lib\n\turl = {}\n",
+            lib.path().display()
+        )
+    );
+    assert!(
+        superproject.path().join("vendor/lib/tracked.txt").is_file(),
+        "the submodule is checked out"
+    );
+
+    assert_cli_success(
+        &run_libra_command(
+            &["commit", "-m", "add lib", "--no-verify"],
+            superproject.path(),
+        ),
+        "commit submodule",
+    );
+    let out = run_libra_command(&["ls-tree", "HEAD"], superproject.path());
+    assert_cli_success(&out, "ls-tree HEAD");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains(&format!("160000 commit {pinned}\tvendor/lib")),
+        "gitlink entry missing: {stdout}"
+    );
+    assert!(
+        stdout.contains("100644 blob ") && stdout.contains("\t.gitmodules"),
+        ".gitmodules not committed: {stdout}"
+    );
+
+    // The nested repository's files are never reported as untracked.
+    let out = run_libra_command(&["status", "--porcelain"], superproject.path());
+    assert_cli_success(&out, "status --porcelain");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!stdout.contains("vendor/"), "status: {stdout}");
+}
+
+#[test]
+fn test_reset_hard_keeps_committed_gitlink() {
+    let (superproject, lib) = superproject_with_submodule();
+    let pinned = head_of(lib.path());
+    assert_cli_success(
+        &run_libra_command(
+            &["commit", "-m", "add lib", "--no-verify"],
+            superproject.path(),
+        ),
+        "commit submodule",
+    );
+
+    // The gitlink's commit is not in the superproject's object database, so
+    // rebuilding the index must not try to load it as a blob.
+    let out = run_libra_command(&["reset", "--hard", "HEAD"], superproject.path());
+    assert_cli_success(&out, "reset --hard HEAD");
+
+    let out = run_libra_command(&["ls-files", "--stage"], superproject.path());
+    assert_cli_success(&out, "ls-files --stage");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains(&format!("160000 {pinned} 0\tvendor/lib")),
+        "gitlink index entry missing: {stdout}"
+    );
+    assert!(
+        superproject.path().join("vendor/lib/tracked.txt").is_file(),
+        "the submodule checkout is left in place"
+    );
+    let out = run_libra_command(&["status", "--porcelain"], superproject.path());
+    assert_cli_success(&out, "status --porcelain");
+    assert!(
+        String::from_utf8_lossy(&out.stdout).trim().is_empty(),
+        "worktree is clean after the reset"
+    );
+}
+
+#[test]
+fn test_submodule_status_reports_pinned_and_checked_out_commit() {
+    let (superproject, lib) = superproject_with_submodule();
+    let pinned = head_of(lib.path());
+
+    let out = run_libra_command(&["submodule", "status"], superproject.path());
+    assert_cli_success(&out, "submodule status");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        format!(" {pinned} vendor/lib\n")
+    );
+
+    // A new commit inside the submodule makes it differ from the pin.
+    let nested = superproject.path().join("vendor/lib");
+    fs::write(nested.join("more.txt"), "more\n").unwrap();
+    configure_identity_via_cli(&nested);
+    assert_cli_success(&run_libra_command(&["add", "more.txt"], &nested), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "more", "--no-verify"], &nested),
+        "commit in submodule",
+    );
+    let moved = head_of(&nested);
+
+    let out = run_libra_command(&["--json", "submodule", "status"], superproject.path());
+    assert_cli_success(&out, "--json submodule status");
+    let json = parse_json_stdout(&out);
+    let entry = &json["data"]["submodules"][0];
+    assert_eq!(entry["path"], "vendor/lib");
+    assert_eq!(entry["pinned"], pinned.as_str());
+    assert_eq!(entry["checked_out"], moved.as_str());
+    assert_eq!(entry["state"], "modified");
+
+    // `update` puts the submodule back on the pinned commit.
+    let out = run_libra_command(&["submodule", "update"], superproject.path());
+    assert_cli_success(&out, "submodule update");
+    assert_eq!(head_of(&nested), pinned);
+}
+
+#[test]
+fn test_clone_recurse_submodules_checks_out_pinned_commit() {
+    let (superproject, lib) = superproject_with_submodule();
+    assert_cli_success(
+        &run_libra_command(
+            &["commit", "-m", "add lib", "--no-verify"],
+            superproject.path(),
+        ),
+        "commit submodule",
+    );
+
+    let dest_root = tempfile::tempdir().unwrap();
+    let dest = dest_root.path().join("copy");
+    let out = run_libra_command(
+        &[
+            "clone",
+            "--recurse-submodules",
+            superproject.path().to_str().unwrap(),
+            dest.to_str().unwrap(),
+        ],
+        dest_root.path(),
+    );
+    assert_cli_success(&out, "clone --recurse-submodules");
+    assert!(dest.join("vendor/lib/tracked.txt").is_file());
+    assert_eq!(head_of(&dest.join("vendor/lib")), head_of(lib.path()));
+
+    // Without the flag the submodule is recorded but not cloned.
+    let plain = dest_root.path().join("plain");
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "clone",
+                superproject.path().to_str().unwrap(),
+                plain.to_str().unwrap(),
+            ],
+            dest_root.path(),
+        ),
+        "clone",
+    );
+    let out = run_libra_command(&["submodule", "status"], &plain);
+    assert_cli_success(&out, "submodule status in plain clone");
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with('-'));
+}
+
+#[test]
+fn test_submodule_add_rejects_occupied_path() {
+    let lib = create_committed_repo_via_cli();
+    let superproject = create_committed_repo_via_cli();
+    fs::create_dir_all(superproject.path().join("vendor")).unwrap();
+    fs::write(superproject.path().join("vendor/file.txt"), "x\n").unwrap();
+
+    let out = run_libra_command(
+        &["submodule", "add", lib.path().to_str().unwrap(), "vendor"],
+        superproject.path(),
+    );
+    assert_eq!(out.status.code(), Some(128));
+    let (stderr, report) = parse_cli_error_stderr(&out.stderr);
+    assert_eq!(report.error_code, "LBR-CLI-003");
+    assert!(
+        stderr.contains("already exists and is not an empty directory"),
+        "stderr: {stderr}"
+    );
+    assert!(!superproject.path().join(".gitmodules").exists());
+}
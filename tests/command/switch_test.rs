@@ -153,22 +153,69 @@ fn test_switch_force_create_refuses_current_branch() {
     );
 }
 
+/// `--orphan` leaves HEAD on an unborn branch: the index and tracked files are
+/// cleared, untracked files survive, the branch is not listed until its first
+/// commit, and that commit has no parents.
 #[test]
 fn test_switch_orphan_creates_branch_with_no_history() {
     let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    std::fs::write(p.join("scratch.txt"), "untracked\n").unwrap();
 
-    let output = run_libra_command(&["switch", "--orphan", "fresh"], repo.path());
+    let output = run_libra_command(&["switch", "--orphan", "fresh"], p);
     assert_cli_success(&output, "create orphan branch should succeed");
+    assert!(!p.join("tracked.txt").exists(), "tracked files are removed");
+    assert!(p.join("scratch.txt").exists(), "untracked files are kept");
 
-    let output = run_libra_command(&["log", "--oneline"], repo.path());
-    assert_cli_success(&output, "log should succeed");
-    let stdout = String::from_utf8_lossy(&output.stdout);
+    let ls = run_libra_command(&["ls-files"], p);
+    assert_cli_success(&ls, "ls-files on orphan branch");
+    assert!(ls.stdout.is_empty(), "index must be empty: {ls:?}");
+
+    let branches = run_libra_command(&["branch"], p);
+    assert_cli_success(&branches, "branch list on orphan branch");
+    let listed = String::from_utf8_lossy(&branches.stdout);
+    assert!(
+        !listed.contains("fresh"),
+        "unborn branch must not be listed before its first commit: {listed}"
+    );
+
+    std::fs::write(p.join("page.html"), "<h1>docs</h1>\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "page.html"], p), "add");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "first page", "--no-verify"], p),
+        "first commit on orphan branch",
+    );
+
+    let head = run_libra_command(&["cat-file", "-p", "HEAD"], p);
+    assert_cli_success(&head, "cat-file HEAD");
+    let commit = String::from_utf8_lossy(&head.stdout);
     assert!(
-        stdout.contains("orphan branch root commit"),
-        "expected orphan root commit in log, got: {stdout}"
+        !commit.lines().any(|line| line.starts_with("parent ")),
+        "first orphan commit must have no parents: {commit}"
+    );
+    let log = run_libra_command(&["log", "--oneline"], p);
+    let stdout = String::from_utf8_lossy(&log.stdout);
+    assert_eq!(stdout.lines().count(), 1, "orphan history: {stdout}");
+
+    let branches = run_libra_command(&["branch"], p);
+    let listed = String::from_utf8_lossy(&branches.stdout);
+    assert!(
+        listed.contains("fresh"),
+        "branch exists after commit: {listed}"
     );
 }
 
+/// `--orphan` refuses a name that already exists instead of replacing it.
+#[test]
+fn test_switch_orphan_rejects_existing_branch() {
+    let repo = create_committed_repo_via_cli();
+    let output = run_libra_command(&["switch", "--orphan", "main"], repo.path());
+    assert!(!output.status.success(), "existing branch must be rejected");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("already exists"), "got: {stderr}");
+    assert!(repo.path().join("tracked.txt").exists());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_switch_json_track_output_stays_clean() {
@@ -1082,3 +1129,50 @@ fn switch_no_progress_flag_is_accepted_noop() {
         "switched to feature"
     );
 }
+
+/// `post-checkout` receives `<old HEAD> <new HEAD> 1` after a branch switch.
+#[cfg(unix)]
+#[test]
+fn test_switch_runs_post_checkout_hook_with_old_and_new_head() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "topic"], p),
+        "branch topic",
+    );
+    std::fs::write(p.join("topic.txt"), "topic\n").unwrap();
+    assert_cli_success(&run_libra_command(&["add", "topic.txt"], p), "add topic");
+    assert_cli_success(
+        &run_libra_command(&["commit", "-m", "topic work", "--no-verify"], p),
+        "commit topic",
+    );
+
+    let hook = p.join(".libra/hooks/post-checkout");
+    std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook,
+        "#!/bin/sh\necho \"$1 $2 $3\" >> .libra/post-checkout.log\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let rev_parse = |rev: &str| {
+        String::from_utf8_lossy(&run_libra_command(&["rev-parse", rev], p).stdout)
+            .trim()
+            .to_string()
+    };
+    let topic = rev_parse("topic");
+    let main = rev_parse("main");
+    assert_ne!(topic, main);
+
+    assert_cli_success(&run_libra_command(&["switch", "main"], p), "switch main");
+    let log = std::fs::read_to_string(p.join(".libra/post-checkout.log")).unwrap();
+    assert_eq!(log, format!("{topic} {main} 1\n"));
+
+    // A failing notification hook cannot undo the switch.
+    std::fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+    assert_cli_success(&run_libra_command(&["switch", "topic"], p), "switch topic");
+    assert_eq!(rev_parse("HEAD"), topic);
+}
@@ -63,6 +63,35 @@ fn symbolic_ref_set_head_to_existing_branch() {
     assert_eq!(String::from_utf8_lossy(&current.stdout).trim(), "feature");
 }
 
+#[test]
+fn symbolic_ref_set_head_writes_a_head_reflog_entry() {
+    let repo = create_committed_repo_via_cli();
+
+    let branch_output = run_libra_command(&["branch", "feature"], repo.path());
+    assert_cli_success(&branch_output, "branch feature");
+
+    let output = run_libra_command(
+        &[
+            "symbolic-ref",
+            "-m",
+            "point at feature",
+            "HEAD",
+            "refs/heads/feature",
+        ],
+        repo.path(),
+    );
+    assert_cli_success(&output, "symbolic-ref -m HEAD refs/heads/feature");
+
+    let reflog = run_libra_command(&["reflog", "show", "HEAD"], repo.path());
+    assert_cli_success(&reflog, "reflog show HEAD");
+    let stdout = String::from_utf8_lossy(&reflog.stdout);
+    let newest = stdout.lines().next().unwrap_or_default();
+    assert!(
+        newest.contains("symbolic-ref: point at feature"),
+        "newest HEAD reflog entry should record the symbolic-ref update: {stdout}"
+    );
+}
+
 #[test]
 fn symbolic_ref_detached_head_returns_invalid_target() {
     let repo = create_committed_repo_via_cli();
@@ -171,6 +171,136 @@ fn test_tag_contains_filter() {
     );
 }
 
+/// Tag names from `--json tag <filter args>`, sorted.
+fn listed_tag_names(repo: &std::path::Path, filter: &[&str]) -> Vec<String> {
+    let mut argv = vec!["--json", "tag"];
+    argv.extend_from_slice(filter);
+    let out = run_libra_command(&argv, repo);
+    assert_cli_success(&out, &format!("tag {filter:?}"));
+    let mut names: Vec<String> = parse_json_stdout(&out)["data"]["tags"]
+        .as_array()
+        .expect("expected tags array")
+        .iter()
+        .map(|entry| entry["name"].as_str().unwrap_or("").to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// `v1` tags the fork point; `v2` is on `main` and `side` on a diverged
+/// branch. HEAD stays on `main`.
+#[test]
+fn test_tag_commit_filters_on_diverged_history() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let commit = |file: &str| {
+        std::fs::write(p.join(file), "x\n").unwrap();
+        assert_cli_success(&run_libra_command(&["add", file], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", file, "--no-verify"], p),
+            "commit",
+        );
+    };
+    assert_cli_success(&run_libra_command(&["tag", "v1"], p), "tag v1");
+    assert_cli_success(
+        &run_libra_command(&["switch", "-c", "topic"], p),
+        "switch -c topic",
+    );
+    commit("side.txt");
+    assert_cli_success(&run_libra_command(&["tag", "side"], p), "tag side");
+    assert_cli_success(&run_libra_command(&["switch", "main"], p), "switch main");
+    commit("main.txt");
+    assert_cli_success(&run_libra_command(&["tag", "v2"], p), "tag v2");
+
+    assert_eq!(
+        listed_tag_names(p, &["--contains", "v1"]),
+        ["side", "v1", "v2"]
+    );
+    assert_eq!(
+        listed_tag_names(p, &["--no-contains", "v2"]),
+        ["side", "v1"]
+    );
+    // Repeated `--contains` keeps a tag containing any of the commits.
+    assert_eq!(
+        listed_tag_names(p, &["--contains", "v2", "--contains", "side"]),
+        ["side", "v2"]
+    );
+    assert_eq!(
+        listed_tag_names(p, &["--contains", "v1", "--no-contains", "side"]),
+        ["v1", "v2"]
+    );
+    assert_eq!(listed_tag_names(p, &["--merged", "main"]), ["v1", "v2"]);
+    assert_eq!(listed_tag_names(p, &["--no-merged", "main"]), ["side"]);
+    assert_eq!(
+        listed_tag_names(p, &["--points-at", "v1", "--points-at", "side"]),
+        ["side", "v1"]
+    );
+    // Without a value each filter defaults to HEAD.
+    assert_eq!(listed_tag_names(p, &["--contains"]), ["v2"]);
+    assert_eq!(listed_tag_names(p, &["--points-at"]), ["v2"]);
+    assert_eq!(listed_tag_names(p, &["--no-merged"]), ["side"]);
+}
+
+/// Tags across a linear history `base <- c1 <- c2`: annotated `z-old` on
+/// `base`, lightweight `m-mid` on `c1`, annotated `a-new` on `c2`. The names
+/// sort opposite to their dates so `creatordate` cannot pass by accident.
+#[test]
+fn test_tag_contains_and_creatordate_across_history() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    let commit = |file: &str| {
+        // Commit and tagger timestamps have one-second resolution.
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        std::fs::write(p.join(file), "x\n").unwrap();
+        assert_cli_success(&run_libra_command(&["add", file], p), "add");
+        assert_cli_success(
+            &run_libra_command(&["commit", "-m", file, "--no-verify"], p),
+            "commit",
+        );
+    };
+    assert_cli_success(
+        &run_libra_command(&["tag", "-m", "old", "z-old"], p),
+        "tag z-old",
+    );
+    commit("c1.txt");
+    let c1 = String::from_utf8_lossy(&run_libra_command(&["rev-parse", "HEAD"], p).stdout)
+        .trim()
+        .to_string();
+    assert_cli_success(&run_libra_command(&["tag", "m-mid"], p), "tag m-mid");
+    commit("c2.txt");
+    assert_cli_success(
+        &run_libra_command(&["tag", "-m", "new", "a-new"], p),
+        "tag a-new",
+    );
+
+    // Annotated tags are peeled to their commit before the ancestor walk.
+    assert_eq!(
+        listed_tag_names(p, &["--contains", &c1]),
+        ["a-new", "m-mid"]
+    );
+    assert_eq!(listed_tag_names(p, &["--contains", "z-old"]).len(), 3);
+    assert_eq!(listed_tag_names(p, &["--no-contains", &c1]), ["z-old"]);
+    assert_eq!(listed_tag_names(p, &["--merged", &c1]), ["m-mid", "z-old"]);
+    assert_eq!(listed_tag_names(p, &["--no-merged", &c1]), ["a-new"]);
+
+    let list = |args: &[&str]| -> Vec<String> {
+        let out = run_libra_command(args, p);
+        assert_cli_success(&out, "tag list");
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    };
+    assert_eq!(
+        list(&["tag", "--sort=creatordate"]),
+        ["z-old", "m-mid", "a-new"]
+    );
+    assert_eq!(
+        list(&["tag", "--merged", &c1, "--sort=-creatordate"]),
+        ["m-mid", "z-old"]
+    );
+}
+
 #[test]
 fn test_tag_sign_embeds_pgp_signature() {
     let repo = create_committed_repo_via_cli();
@@ -240,6 +370,61 @@ fn test_tag_verify_rejects_unsigned_tag() {
     );
 }
 
+#[tokio::test]
+#[serial]
+async fn test_tag_verify_rejects_tampered_tag() {
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["tag", "-s", "-m", "signed release", "v1.0"], repo.path()),
+        "tag -s -m signed v1.0",
+    );
+
+    // Forge a copy of the signed tag object with an edited message but the
+    // original signature block, and point a second tag ref at it.
+    {
+        let _guard = ChangeDirGuard::new(repo.path());
+        let signed = match get_tag_by_name(&ref_name("v1.0"))
+            .await
+            .expect("signed tag should exist")
+            .object
+        {
+            internal_tag::TagObject::Tag(tag) => tag,
+            _ => panic!("tag -s should create an annotated tag object"),
+        };
+        let tampered = GitTag::new(
+            signed.object_hash,
+            signed.object_type,
+            signed.tag_name.clone(),
+            signed.tagger.clone(),
+            signed
+                .message
+                .replacen("signed release", "tampered release", 1),
+        );
+        save_object(&tampered, &tampered.id).expect("store tampered tag object");
+        insert_broken_tag_ref("v1.0-tampered", Some(&tampered.id.to_string())).await;
+    }
+
+    let out = run_libra_command(&["tag", "-v", "v1.0-tampered"], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "a tampered tag should fail verification, stdout: {} stderr: {}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("has a bad signature"),
+        "tag -v should report the bad signature: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    // The untouched original still verifies.
+    assert_cli_success(
+        &run_libra_command(&["tag", "-v", "v1.0"], repo.path()),
+        "tag -v v1.0",
+    );
+}
+
 #[test]
 fn test_tag_create_outputs_concise_confirmation() {
     let repo = create_committed_repo_via_cli();
@@ -622,6 +807,8 @@ async fn setup_repo_with_commit_with(
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -818,6 +1005,8 @@ async fn test_force_tag() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -846,12 +1035,12 @@ async fn test_force_tag() {
         message: Some("Updated".into()),
         force: true,
         n_lines: None,
-        points_at: None,
-        contains: None,
-        no_contains: None,
+        points_at: vec![],
+        contains: vec![],
+        no_contains: vec![],
         merged: None,
         no_merged: None,
-        sort: None,
+        sort: vec![],
         column: None,
         sign: false,
         no_sign: false,
@@ -977,12 +1166,12 @@ async fn test_delete_tag() {
         message: None,
         force: false,
         n_lines: None,
-        points_at: None,
-        contains: None,
-        no_contains: None,
+        points_at: vec![],
+        contains: vec![],
+        no_contains: vec![],
         merged: None,
         no_merged: None,
-        sort: None,
+        sort: vec![],
         column: None,
         sign: false,
         no_sign: false,
@@ -1020,6 +1209,8 @@ async fn test_annotation_lines_tag() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1048,12 +1239,12 @@ async fn test_annotation_lines_tag() {
         message: Some("Single line annotation message".into()),
         force: false,
         n_lines: None,
-        points_at: None,
-        contains: None,
-        no_contains: None,
+        points_at: vec![],
+        contains: vec![],
+        no_contains: vec![],
         merged: None,
         no_merged: None,
-        sort: None,
+        sort: vec![],
         column: None,
         sign: false,
         no_sign: false,
@@ -1078,6 +1269,8 @@ async fn test_annotation_lines_tag() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     commit::execute(CommitArgs {
@@ -1106,12 +1299,12 @@ async fn test_annotation_lines_tag() {
         message: Some("multi\nline\nannotation\ntag".into()),
         force: false,
         n_lines: None,
-        points_at: None,
-        contains: None,
-        no_contains: None,
+        points_at: vec![],
+        contains: vec![],
+        no_contains: vec![],
         merged: None,
         no_merged: None,
-        sort: None,
+        sort: vec![],
         column: None,
         sign: false,
         no_sign: false,
@@ -1282,6 +1475,43 @@ fn test_tag_sort_by_refname() {
     assert_eq!(names, vec!["v3.0", "v2.0", "v1.0"]);
 }
 
+#[test]
+fn test_tag_sort_version_refname_pattern_and_stacked_keys() {
+    let repo = create_committed_repo_via_cli();
+    for name in ["v1.2.10", "v1.2.3", "v1.10.0", "v1.2.9", "v2.0"] {
+        assert_cli_success(&run_libra_command(&["tag", name], repo.path()), name);
+    }
+    let list = |args: &[&str]| -> Vec<String> {
+        let out = run_libra_command(args, repo.path());
+        assert_cli_success(&out, "tag list");
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect()
+    };
+
+    assert_eq!(
+        list(&["tag", "-l", "v1.2.*", "--sort=version:refname"]),
+        vec!["v1.2.3", "v1.2.9", "v1.2.10"]
+    );
+    assert_eq!(
+        list(&["tag", "--sort=-v:refname"]),
+        vec!["v2.0", "v1.10.0", "v1.2.10", "v1.2.9", "v1.2.3"]
+    );
+    // All tags share one commit, so the primary `creatordate` key ties and the
+    // earlier `-refname` key decides.
+    assert_eq!(
+        list(&[
+            "tag",
+            "-l",
+            "v1.2.*",
+            "--sort=-refname",
+            "--sort=creatordate"
+        ]),
+        vec!["v1.2.9", "v1.2.3", "v1.2.10"]
+    );
+}
+
 #[test]
 fn test_tag_sort_invalid_key_errors() {
     let repo = create_committed_repo_via_cli();
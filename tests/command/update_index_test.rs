@@ -6,7 +6,7 @@ use std::fs;
 
 use tempfile::tempdir;
 
-use super::{parse_json_stdout, run_libra_command};
+use super::{create_committed_repo_via_cli, parse_json_stdout, run_libra_command};
 
 fn init_repo() -> tempfile::TempDir {
     let repo = tempdir().expect("tempdir");
@@ -222,3 +222,79 @@ fn add_directory_is_rejected_not_panicked() {
         String::from_utf8_lossy(&upd.stderr)
     );
 }
+
+/// A skip-worktree entry's local edits (and deletion) are not reported by
+/// `status` or `diff`; clearing the mark reports them again.
+#[test]
+fn skip_worktree_hides_local_edits_from_status_and_diff() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join("tracked.txt"), "local override\n").unwrap();
+
+    let mark = run_libra_command(&["update-index", "--skip-worktree", "tracked.txt"], p);
+    assert_eq!(mark.status.code(), Some(0));
+    let status = run_libra_command(&["status", "--short"], p);
+    assert_eq!(stdout_trimmed(&status), "", "skip-worktree edit is hidden");
+    assert_eq!(stdout_trimmed(&run_libra_command(&["diff"], p)), "");
+
+    fs::remove_file(p.join("tracked.txt")).unwrap();
+    let status = run_libra_command(&["status", "--short"], p);
+    assert_eq!(
+        stdout_trimmed(&status),
+        "",
+        "skip-worktree deletion is hidden"
+    );
+
+    let unmark = run_libra_command(&["update-index", "--no-skip-worktree", "tracked.txt"], p);
+    assert_eq!(unmark.status.code(), Some(0));
+    let status = run_libra_command(&["status", "--short"], p);
+    assert!(
+        stdout_trimmed(&status).contains("tracked.txt"),
+        "cleared mark reports the deletion again: {}",
+        stdout_trimmed(&status)
+    );
+}
+
+#[test]
+fn assume_unchanged_hides_local_edits_and_requires_a_tracked_path() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    fs::write(p.join("tracked.txt"), "edited\n").unwrap();
+
+    let mark = run_libra_command(&["update-index", "--assume-unchanged", "tracked.txt"], p);
+    assert_eq!(mark.status.code(), Some(0));
+    assert_eq!(
+        stdout_trimmed(&run_libra_command(&["status", "--short"], p)),
+        ""
+    );
+
+    let untracked = run_libra_command(&["update-index", "--assume-unchanged", "nope.txt"], p);
+    assert_eq!(untracked.status.code(), Some(128));
+}
+
+#[test]
+fn chmod_sets_and_clears_the_executable_bit() {
+    let repo = init_repo();
+    fs::write(repo.path().join("run.sh"), "#!/bin/sh\n").unwrap();
+
+    let upd = run_libra_command(
+        &["update-index", "--add", "--chmod=+x", "run.sh"],
+        repo.path(),
+    );
+    assert_eq!(upd.status.code(), Some(0));
+    let ls = run_libra_command(&["ls-files", "-s", "run.sh"], repo.path());
+    assert!(
+        stdout_trimmed(&ls).starts_with("100755 "),
+        "{}",
+        stdout_trimmed(&ls)
+    );
+
+    let upd = run_libra_command(&["update-index", "--chmod=-x", "run.sh"], repo.path());
+    assert_eq!(upd.status.code(), Some(0));
+    let ls = run_libra_command(&["ls-files", "-s", "run.sh"], repo.path());
+    assert!(
+        stdout_trimmed(&ls).starts_with("100644 "),
+        "{}",
+        stdout_trimmed(&ls)
+    );
+}
@@ -84,6 +84,35 @@ fn compare_and_swap_fails_when_old_mismatches() {
     assert_eq!(stdout_trimmed(&rev_parse(&repo, "feature")), c1);
 }
 
+#[test]
+fn stale_old_value_rolls_back_without_a_reflog_entry() {
+    let (repo, c1, c2) = repo_with_two_commits();
+    let create = run_libra_command(&["update-ref", "refs/heads/feature", &c1], repo.path());
+    assert!(create.status.success());
+    let advance = run_libra_command(&["update-ref", "refs/heads/feature", &c2, &c1], repo.path());
+    assert!(advance.status.success());
+    let reflog_before = stdout_trimmed(&run_libra_command(
+        &["reflog", "show", "feature"],
+        repo.path(),
+    ));
+
+    // A second writer still believes the ref is at c1.
+    let stale = run_libra_command(&["update-ref", "refs/heads/feature", &c1, &c1], repo.path());
+    assert_eq!(stale.status.code(), Some(128), "stale CAS must fail");
+    assert!(
+        String::from_utf8_lossy(&stale.stderr).contains("expected"),
+        "error should name the expected value: {}",
+        String::from_utf8_lossy(&stale.stderr)
+    );
+
+    assert_eq!(stdout_trimmed(&rev_parse(&repo, "feature")), c2);
+    let reflog_after = stdout_trimmed(&run_libra_command(
+        &["reflog", "show", "feature"],
+        repo.path(),
+    ));
+    assert_eq!(reflog_before, reflog_after, "a rejected CAS must not log");
+}
+
 #[test]
 fn zero_old_value_creates_only_when_absent() {
     let (repo, c1, _c2) = repo_with_two_commits();
@@ -0,0 +1,223 @@
+//! Tests for `verify-commit`, covering good, unsigned, and tampered signatures,
+//! multi-argument reporting, signer attribution, and the GnuPG keyring
+//! fallback. Signature tests need the `signing` feature.
+//!
+//! **Layer:** L1 — deterministic, no external dependencies.
+
+use super::*;
+
+/// Add `name` and commit it, forwarding `extra` commit flags (e.g. `-S`).
+fn commit_file(repo: &Path, name: &str, extra: &[&str]) {
+    fs::write(repo.join(name), format!("{name}\n")).unwrap();
+    assert_cli_success(&run_libra_command(&["add", name], repo), "add file");
+    let mut args = vec!["commit", "-m", name, "--no-verify"];
+    args.extend_from_slice(extra);
+    assert_cli_success(&run_libra_command(&args, repo), "commit");
+}
+
+/// Forge a copy of the signed HEAD commit with an edited message but the
+/// original `gpgsig` header, returning its id.
+async fn forge_tampered_commit(repo: &Path) -> String {
+    let _guard = ChangeDirGuard::new(repo);
+    let head = get_target_commit("HEAD").await.expect("HEAD resolves");
+    let signed = load_object::<Commit>(&head).expect("load signed commit");
+    let tampered = Commit::new(
+        signed.author.clone(),
+        signed.committer.clone(),
+        signed.tree_id,
+        signed.parent_commit_ids.clone(),
+        &signed.message.replacen("signed.txt", "tampered.txt", 1),
+    );
+    save_object(&tampered, &tampered.id).expect("store tampered commit");
+    tampered.id.to_string()
+}
+
+#[test]
+#[cfg(feature = "signing")]
+fn test_verify_commit_accepts_signed_commit() {
+    let repo = create_committed_repo_via_cli();
+    commit_file(repo.path(), "signed.txt", &["-S"]);
+
+    let out = run_libra_command(&["verify-commit", "HEAD"], repo.path());
+    assert_cli_success(&out, "verify-commit HEAD");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("HEAD: Good signature from"),
+        "verify-commit should report the good signature on stderr: {stderr}"
+    );
+    assert!(out.stdout.is_empty(), "status goes to stderr only");
+}
+
+#[test]
+#[cfg(feature = "signing")]
+fn test_verify_commit_reports_every_argument_and_fails_on_unsigned() {
+    let repo = create_committed_repo_via_cli();
+    commit_file(repo.path(), "signed.txt", &["-S"]);
+    commit_file(repo.path(), "plain.txt", &["--no-gpg-sign"]);
+
+    let out = run_libra_command(&["verify-commit", "HEAD", "HEAD~1"], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "an unsigned commit fails verification: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("HEAD: no signature found"), "{stderr}");
+    assert!(
+        stderr.contains("HEAD~1: Good signature from"),
+        "later arguments are still checked: {stderr}"
+    );
+
+    let json = run_libra_command(&["--json", "verify-commit", "HEAD~1", "HEAD"], repo.path());
+    assert_eq!(json.status.code(), Some(1));
+    let value = parse_json_stdout(&json);
+    let statuses: Vec<&str> = value["data"]["results"]
+        .as_array()
+        .expect("results array")
+        .iter()
+        .map(|result| result["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["good", "unsigned"]);
+}
+
+#[tokio::test]
+#[serial]
+#[cfg(feature = "signing")]
+async fn test_verify_commit_rejects_tampered_commit() {
+    let repo = create_committed_repo_via_cli();
+    commit_file(repo.path(), "signed.txt", &["-S"]);
+
+    let tampered_id = forge_tampered_commit(repo.path()).await;
+
+    let out = run_libra_command(&["verify-commit", &tampered_id], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "a tampered commit fails verification: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("BAD signature"),
+        "verify-commit should report the bad signature: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[cfg(all(unix, feature = "signing"))]
+#[tokio::test]
+#[serial]
+async fn test_verify_commit_falls_back_to_gpg_keyring() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = create_committed_repo_via_cli();
+    commit_file(repo.path(), "signed.txt", &["-S"]);
+    let tampered_id = forge_tampered_commit(repo.path()).await;
+
+    // Stand-in for `gpg`: vouches for any payload that reaches it on stdin
+    // with the forged message, so a good verdict proves the payload was passed.
+    let fake_gpg = repo.path().join("fake-gpg.sh");
+    fs::write(
+        &fake_gpg,
+        "#!/bin/sh
+         grep -q tampered.txt || exit 1
+         echo 'gpg: Signature made by fake keyring' >&2
+         echo '[GNUPG:] GOODSIG 0123456789ABCDEF Fake Signer <fake@example.com>'
+         echo '[GNUPG:] VALIDSIG 89ABCDEF0123456789ABCDEF0123456789ABCDEF 2024-01-01'
+",
+    )
+    .unwrap();
+    fs::set_permissions(&fake_gpg, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_cli_success(
+        &run_libra_command(
+            &["config", "gpg.program", fake_gpg.to_str().unwrap()],
+            repo.path(),
+        ),
+        "config gpg.program",
+    );
+
+    let out = run_libra_command(&["verify-commit", "-v", &tampered_id], repo.path());
+    assert_cli_success(&out, "verify-commit -v via gpg.program");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains(
+            "Good signature from \"Fake Signer <fake@example.com>\" \
+             (key 89ABCDEF0123456789ABCDEF0123456789ABCDEF)"
+        ),
+        "the keyring verdict replaces the vault one: {stderr}"
+    );
+    assert!(
+        stderr.contains("gpg: Signature made by fake keyring"),
+        "--verbose shows GnuPG's own output: {stderr}"
+    );
+
+    let quiet = run_libra_command(&["verify-commit", &tampered_id], repo.path());
+    assert!(
+        !String::from_utf8_lossy(&quiet.stderr).contains("fake keyring"),
+        "GnuPG output is hidden without --verbose"
+    );
+}
+
+/// The reported signer is the vault key that verified the signature, not the
+/// committer header, which anyone can set.
+#[test]
+#[cfg(feature = "signing")]
+fn test_verify_commit_names_the_signing_key_not_the_committer() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "config",
+                "generate-gpg-key",
+                "--name",
+                "Release Key",
+                "--email",
+                "release@example.com",
+            ],
+            p,
+        ),
+        "generate-gpg-key",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "user.name", "Mallory Forger"], p),
+        "config user.name",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "user.email", "mallory@example.com"], p),
+        "config user.email",
+    );
+    commit_file(p, "signed.txt", &["-S"]);
+
+    let out = run_libra_command(&["verify-commit", "HEAD"], p);
+    assert_cli_success(&out, "verify-commit HEAD");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("HEAD: Good signature from \"Release Key <release@example.com>\" (key "),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Mallory"), "{stderr}");
+
+    let json = run_libra_command(&["--json", "verify-commit", "HEAD"], p);
+    assert_cli_success(&json, "verify-commit --json");
+    let value = parse_json_stdout(&json);
+    let signer = &value["data"]["results"][0]["signer"];
+    assert_eq!(signer["user_id"], "Release Key <release@example.com>");
+    assert_eq!(
+        signer["fingerprint"].as_str().map(str::len),
+        Some(40),
+        "{signer}"
+    );
+}
+
+#[test]
+fn test_verify_commit_unknown_revision_is_fatal() {
+    let repo = create_committed_repo_via_cli();
+    let out = run_libra_command(&["verify-commit", "no-such-rev"], repo.path());
+    assert_eq!(out.status.code(), Some(128));
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("no-such-rev: not a valid commit name"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
@@ -0,0 +1,141 @@
+//! Tests for `verify-tag`, covering good and tampered signatures, signer
+//! attribution, unsigned and lightweight tags, and multi-argument reporting.
+//! Signature tests need the `signing` feature.
+//!
+//! **Layer:** L1 — deterministic, no external dependencies.
+
+use libra::internal::tag as internal_tag;
+
+use super::*;
+
+#[test]
+#[cfg(feature = "signing")]
+fn test_verify_tag_accepts_signed_tag() {
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["tag", "-s", "-m", "signed release", "v1.0"], repo.path()),
+        "tag -s v1.0",
+    );
+
+    let out = run_libra_command(&["verify-tag", "v1.0", "refs/tags/v1.0"], repo.path());
+    assert_cli_success(&out, "verify-tag v1.0");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("v1.0: Good signature from"), "{stderr}");
+    assert!(
+        stderr.contains("refs/tags/v1.0: Good signature from"),
+        "{stderr}"
+    );
+}
+
+/// The reported signer is the vault key that verified the tag, not the tagger
+/// header, which anyone can set.
+#[test]
+#[cfg(feature = "signing")]
+fn test_verify_tag_names_the_signing_key_not_the_tagger() {
+    let repo = create_committed_repo_via_cli();
+    let p = repo.path();
+    assert_cli_success(
+        &run_libra_command(
+            &[
+                "config",
+                "generate-gpg-key",
+                "--name",
+                "Release Key",
+                "--email",
+                "release@example.com",
+            ],
+            p,
+        ),
+        "generate-gpg-key",
+    );
+    assert_cli_success(
+        &run_libra_command(&["config", "user.name", "Mallory Forger"], p),
+        "config user.name",
+    );
+    assert_cli_success(
+        &run_libra_command(&["tag", "-s", "-m", "signed release", "v1.0"], p),
+        "tag -s v1.0",
+    );
+
+    let out = run_libra_command(&["verify-tag", "v1.0"], p);
+    assert_cli_success(&out, "verify-tag v1.0");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("v1.0: Good signature from \"Release Key <release@example.com>\" (key "),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Mallory"), "{stderr}");
+}
+
+#[tokio::test]
+#[serial]
+#[cfg(feature = "signing")]
+async fn test_verify_tag_rejects_tampered_and_unsigned_tags() {
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["tag", "-s", "-m", "signed release", "v1.0"], repo.path()),
+        "tag -s v1.0",
+    );
+    assert_cli_success(
+        &run_libra_command(&["tag", "-m", "plain annotated", "v1.1"], repo.path()),
+        "tag -m v1.1",
+    );
+
+    // Forge a copy of the signed tag object with an edited message but the
+    // original signature block; it is addressed by object id only.
+    let tampered_id = {
+        let _guard = ChangeDirGuard::new(repo.path());
+        let signed = match internal_tag::find_tag_and_commit("v1.0")
+            .await
+            .expect("load v1.0")
+            .expect("v1.0 exists")
+            .0
+        {
+            internal_tag::TagObject::Tag(tag) => tag,
+            _ => panic!("tag -s should create an annotated tag object"),
+        };
+        let tampered = GitTag::new(
+            signed.object_hash,
+            signed.object_type,
+            signed.tag_name.clone(),
+            signed.tagger.clone(),
+            signed
+                .message
+                .replacen("signed release", "tampered release", 1),
+        );
+        save_object(&tampered, &tampered.id).expect("store tampered tag object");
+        tampered.id.to_string()
+    };
+
+    let out = run_libra_command(&["verify-tag", &tampered_id, "v1.1", "v1.0"], repo.path());
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "tampered and unsigned tags fail verification: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains(&format!("{tampered_id}: BAD signature")),
+        "{stderr}"
+    );
+    assert!(stderr.contains("v1.1: no signature found"), "{stderr}");
+    assert!(stderr.contains("v1.0: Good signature from"), "{stderr}");
+}
+
+#[test]
+fn test_verify_tag_rejects_lightweight_tag() {
+    let repo = create_committed_repo_via_cli();
+    assert_cli_success(
+        &run_libra_command(&["tag", "light"], repo.path()),
+        "lightweight tag",
+    );
+
+    let out = run_libra_command(&["verify-tag", "light"], repo.path());
+    assert_eq!(out.status.code(), Some(128));
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("cannot verify a non-tag object"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
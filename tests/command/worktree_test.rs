@@ -866,6 +866,8 @@ async fn test_worktree_add_rejects_existing_non_empty_directory() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     exec_async(vec!["commit", "-m", "initial"])
@@ -962,6 +964,8 @@ async fn test_worktree_add_rolls_back_link_on_restore_failure() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     exec_async(vec!["commit", "-m", "initial"])
@@ -1029,6 +1033,8 @@ async fn test_worktree_add_rolls_back_populated_files_when_state_save_fails() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     exec_async(vec!["commit", "-m", "initial"])
@@ -1232,6 +1238,8 @@ async fn test_worktree_add_does_not_reset_index() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1254,6 +1262,8 @@ async fn test_worktree_add_does_not_reset_index() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -1303,6 +1313,8 @@ async fn test_worktree_add_populates_from_head_not_staged_index() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
     exec_async(vec!["commit", "-m", "initial"])
@@ -1324,6 +1336,8 @@ async fn test_worktree_add_populates_from_head_not_staged_index() {
         chmod: None,
         renormalize: false,
         ignore_missing: false,
+        patch: false,
+        intent_to_add: false,
     })
     .await;
 
@@ -82,6 +82,36 @@ fn write_tree_builds_nested_directories() {
     );
 }
 
+#[test]
+fn write_tree_leaves_out_intent_to_add_entries() {
+    let repo = init_repo();
+    fs::write(repo.path().join("kept.txt"), "kept").unwrap();
+    fs::write(repo.path().join("new.txt"), "new").unwrap();
+    run_libra_command(&["add", "kept.txt"], repo.path());
+    let add = run_libra_command(&["add", "-N", "new.txt"], repo.path());
+    assert!(
+        add.status.success(),
+        "add -N failed: {}",
+        String::from_utf8_lossy(&add.stderr)
+    );
+
+    let tree = run_libra_command(&["write-tree"], repo.path());
+    assert_eq!(tree.status.code(), Some(0));
+    let root = stdout_trimmed(&tree);
+    let listing = run_libra_command(&["ls-tree", "-r", "--name-only", &root], repo.path());
+    assert_eq!(
+        listing.status.code(),
+        Some(0),
+        "ls-tree failed: {}",
+        String::from_utf8_lossy(&listing.stderr)
+    );
+    assert_eq!(
+        stdout_trimmed(&listing),
+        "kept.txt",
+        "the intent-to-add placeholder must not be written into the tree"
+    );
+}
+
 #[test]
 fn write_tree_json_reports_the_tree_id() {
     let repo = init_repo();
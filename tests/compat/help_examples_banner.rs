@@ -105,6 +105,7 @@ const VISIBLE_COMMANDS: &[&str] = &[
     "usage",
     "graph",
     "sandbox",
+    "export-snapshot",
     "agent",
     "maintenance",
     "completions",
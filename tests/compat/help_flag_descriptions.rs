@@ -87,6 +87,7 @@ const COMMANDS: &[&str] = &[
     "open",
     "graph",
     "sandbox",
+    "export-snapshot",
     "usage",
 ];
 
@@ -101,6 +101,7 @@ const VISIBLE_COMMANDS: &[&str] = &[
     "usage",
     "graph",
     "sandbox",
+    "export-snapshot",
     "agent",
 ];
 
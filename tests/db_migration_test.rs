@@ -49,7 +49,7 @@ fn builtin_migrations_register_current_schema_migrations() {
         vec![
             2026050301, 2026050302, 2026050303, 2026050501, 2026050601, 2026050801, 2026052301,
             2026053101, 2026060201, 2026060401, 2026060801, 2026061401, 2026062301, 2026070201,
-            2026070202
+            2026070202, 2026071001
         ]
     );
     assert_eq!(
@@ -70,13 +70,14 @@ fn builtin_migrations_register_current_schema_migrations() {
             "rename_agent_traces_branch",
             "metadata_kv",
             "working_dirty",
+            "merge_state",
         ]
     );
 
     let runner = builtin_runner().expect("builtin registry must build clean");
     assert!(!runner.is_empty());
-    assert_eq!(runner.len(), 15);
-    assert_eq!(runner.max_registered_version(), Some(2026070202));
+    assert_eq!(runner.len(), 16);
+    assert_eq!(runner.max_registered_version(), Some(2026071001));
 }
 
 // ---------------------------------------------------------------------------
@@ -1053,7 +1054,7 @@ async fn run_builtin_migrations_applies_current_builtin_registry() {
         vec![
             2026050301, 2026050302, 2026050303, 2026050501, 2026050601, 2026050801, 2026052301,
             2026053101, 2026060201, 2026060401, 2026060801, 2026061401, 2026062301, 2026070201,
-            2026070202
+            2026070202, 2026071001
         ]
     );
     assert!(table_exists(&conn, "schema_versions").await);
@@ -1074,6 +1075,8 @@ async fn run_builtin_migrations_applies_current_builtin_registry() {
     assert!(index_exists(&conn, "idx_source_call_log_agent_run_id").await);
     assert!(table_exists(&conn, "cherry_pick_state").await);
     assert!(table_exists(&conn, "revert_sequence").await);
+    assert!(table_exists(&conn, "merge_state").await);
+    assert!(column_exists(&conn, "merge_state", "message").await);
     assert!(table_exists(&conn, "notes").await);
     assert!(index_exists(&conn, "idx_notes_ref").await);
 }
@@ -1105,8 +1108,8 @@ async fn approved_permission_up_down_up_round_trip() {
     assert_eq!(
         rolled,
         vec![
-            2026070202, 2026070201, 2026062301, 2026061401, 2026060801, 2026060401, 2026060201,
-            2026053101, 2026052301, 2026050801, 2026050601
+            2026071001, 2026070202, 2026070201, 2026062301, 2026061401, 2026060801, 2026060401,
+            2026060201, 2026053101, 2026052301, 2026050801, 2026050601
         ]
     );
     assert!(
@@ -1131,7 +1134,7 @@ async fn approved_permission_up_down_up_round_trip() {
         reapplied,
         vec![
             2026050601, 2026050801, 2026052301, 2026053101, 2026060201, 2026060401, 2026060801,
-            2026061401, 2026062301, 2026070201, 2026070202
+            2026061401, 2026062301, 2026070201, 2026070202, 2026071001
         ]
     );
     assert!(table_exists(&conn, "approved_permission").await);
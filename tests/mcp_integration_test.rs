@@ -20,6 +20,7 @@
 
 use std::sync::Arc;
 
+use clap::Parser;
 use git_internal::internal::object::{
     context::{ContextSnapshot, SelectionStrategy},
     decision::{Decision, DecisionType},
@@ -34,6 +35,7 @@ use git_internal::internal::object::{
     types::ActorRef,
 };
 use libra::{
+    command::add::{self, AddArgs},
     internal::{
         ai::{
             history::HistoryManager,
@@ -49,10 +51,11 @@ use libra::{
         },
         model::reference,
     },
-    utils::{storage::local::LocalStorage, storage_ext::StorageExt},
+    utils::{output::OutputConfig, storage::local::LocalStorage, storage_ext::StorageExt, test},
 };
 use rmcp::{ServerHandler, handler::server::wrapper::Parameters};
 use sea_orm::{ActiveModelTrait, ConnectionTrait, Database, Schema, Set};
+use serial_test::serial;
 use tempfile::tempdir;
 use uuid::Uuid;
 
@@ -975,6 +978,7 @@ async fn test_history_latest_returns_real_hash() {
 /// JSON with `active = false`. Pins the inactive-state contract for the active
 /// context resource.
 #[tokio::test]
+#[serial]
 async fn test_context_active_no_active() {
     let (server, _, _, _temp_dir) = setup_server().await;
 
@@ -988,6 +992,42 @@ async fn test_context_active_no_active() {
     assert_eq!(json["active"], false);
 }
 
+/// Scenario: read `libra://context/active` from inside a repository. Besides the
+/// AI-object fields it carries `repository`, the `export-snapshot` document, so
+/// an agent gets HEAD, status and the staged diff from the same resource.
+#[tokio::test]
+#[serial]
+async fn test_context_active_includes_repository_snapshot() {
+    let (server, _, _, _temp_dir) = setup_server().await;
+    let repo_dir = tempdir().unwrap();
+    test::setup_with_new_libra_in(repo_dir.path()).await;
+    let _guard = test::ChangeDirGuard::new(repo_dir.path());
+    std::fs::write(repo_dir.path().join("staged.txt"), "staged\n").unwrap();
+    add::execute_safe(
+        AddArgs::parse_from(["add", "staged.txt"]),
+        &OutputConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    let contents = server
+        .read_resource_impl("libra://context/active")
+        .await
+        .unwrap();
+    let val = serde_json::to_value(&contents[0]).unwrap();
+    let text = val.get("text").unwrap().as_str().unwrap();
+    let json: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(json["active"], false);
+    let repository = &json["repository"];
+    assert!(repository["head"].is_null(), "no commits yet: {repository}");
+    assert!(repository["branch"].is_string(), "{repository}");
+    assert_eq!(repository["recent_commits"], serde_json::json!([]));
+    let diff = repository["staged_diff"]
+        .as_str()
+        .expect("staged diff text");
+    assert!(diff.contains("staged.txt"), "diff: {diff}");
+}
+
 /// Scenario: store a `ContextSnapshot` with a custom summary, then call
 /// `list_context_snapshots` and confirm the rendered text shows "Strategy:",
 /// "Items:", and the summary string. Pins the listing endpoint's text format.